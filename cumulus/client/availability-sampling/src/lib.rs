@@ -0,0 +1,490 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parachain availability sampling.
+//!
+//! Collators and full nodes of a parachain want to know early if the relay chain validators fail
+//! to make the candidates of the parachain available, since unavailable candidates can't be
+//! approved and end up being disputed or timed out. This is especially relevant with elastic
+//! scaling, where several candidates of the parachain are pending availability at the same time.
+//!
+//! For every candidate of the parachain which becomes pending availability on the relay chain, this
+//! service waits for [`SamplingConfig::delay`] and then requests the erasure chunks held by
+//! [`SamplingConfig::samples`] randomly chosen validators. Every received chunk is verified against
+//! the erasure root of the candidate. The share of validators serving a valid chunk is the
+//! availability confidence of the candidate, which is reported to Prometheus. If it is below the
+//! share of chunks needed to recover the candidate, a warning is logged.
+
+use cumulus_relay_chain_interface::{
+	call_runtime_api, CommittedCandidateReceipt, OverseerHandle, ParaId, RelayChainInterface,
+	RelayChainResult,
+};
+use futures::{
+	future::{self, Either},
+	stream::FuturesUnordered,
+	Future, FutureExt, StreamExt,
+};
+use polkadot_node_network_protocol::request_response::{
+	outgoing::{Recipient, RequestError},
+	v1, v2, OutgoingRequest, Requests,
+};
+use polkadot_node_primitives::ErasureChunk;
+use polkadot_node_subsystem::messages::{IfDisconnected, NetworkBridgeTxMessage};
+use polkadot_primitives::{CandidateHash, Hash, SessionIndex, SessionInfo, ValidatorIndex};
+use prometheus_endpoint::{
+	register, Counter, CounterVec, Gauge, Histogram, HistogramOpts, Opts, PrometheusError,
+	Registry, F64, U64,
+};
+use sp_runtime::traits::{BlakeTwo256, Hash as HashT, Header as HeaderT};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	sync::Arc,
+	time::Duration,
+};
+
+const LOG_TARGET: &str = "cumulus-availability-sampling";
+
+/// The number of sampled candidates remembered, to sample every candidate only once.
+const SAMPLED_CANDIDATES_CACHE_SIZE: usize = 256;
+
+/// Timeout of the chunk requests of one candidate.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// The configuration of the availability sampling.
+#[derive(Debug, Clone)]
+pub struct SamplingConfig {
+	/// The number of validators asked for their chunk of every candidate.
+	pub samples: usize,
+	/// The time waited after a candidate became pending availability, before it is sampled.
+	///
+	/// Validators fetch their chunks after the candidate was backed, so this should be around one
+	/// relay chain slot.
+	pub delay: Duration,
+}
+
+impl Default for SamplingConfig {
+	fn default() -> Self {
+		Self { samples: 10, delay: Duration::from_secs(6) }
+	}
+}
+
+/// The outcome of requesting a chunk from a validator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkOutcome {
+	/// The validator served a valid chunk.
+	Available,
+	/// The validator doesn't have its chunk.
+	Missing,
+	/// The validator served a chunk not matching the erasure root.
+	Invalid,
+	/// The request failed.
+	Unreachable,
+}
+
+impl ChunkOutcome {
+	fn label(self) -> &'static str {
+		match self {
+			Self::Available => "available",
+			Self::Missing => "missing",
+			Self::Invalid => "invalid",
+			Self::Unreachable => "unreachable",
+		}
+	}
+}
+
+/// Availability sampling metrics.
+#[derive(Clone)]
+struct Metrics {
+	chunks: CounterVec<U64>,
+	confidence: Gauge<F64>,
+	confidence_distribution: Histogram,
+	at_risk: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			chunks: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_availability_sampling_chunks_total",
+						"Number of sampled erasure chunks, by outcome",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
+			confidence: register(
+				Gauge::new(
+					"cumulus_availability_sampling_confidence",
+					"Share of sampled validators serving a valid chunk of the last sampled candidate",
+				)?,
+				registry,
+			)?,
+			confidence_distribution: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"cumulus_availability_sampling_confidence_distribution",
+						"Share of sampled validators serving a valid chunk, per candidate",
+					)
+					.buckets(vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]),
+				)?,
+				registry,
+			)?,
+			at_risk: register(
+				Counter::new(
+					"cumulus_availability_sampling_candidates_at_risk_total",
+					"Number of candidates with fewer sampled chunks than needed for recovery",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// A candidate of the parachain to sample.
+struct Candidate {
+	hash: CandidateHash,
+	erasure_root: Hash,
+	relay_parent: Hash,
+	session_index: SessionIndex,
+}
+
+/// Samples the availability of the candidates of a parachain.
+pub struct AvailabilitySampling<RCInterface> {
+	relay_chain_interface: RCInterface,
+	overseer_handle: OverseerHandle,
+	para_id: ParaId,
+	config: SamplingConfig,
+	metrics: Option<Metrics>,
+	sessions: HashMap<SessionIndex, Arc<SessionInfo>>,
+}
+
+impl<RCInterface> AvailabilitySampling<RCInterface>
+where
+	RCInterface: RelayChainInterface + Clone + 'static,
+{
+	/// Create the availability sampling of the parachain `para_id`.
+	///
+	/// Metrics are registered in `registry`, if given.
+	pub fn new(
+		relay_chain_interface: RCInterface,
+		para_id: ParaId,
+		config: SamplingConfig,
+		registry: Option<&Registry>,
+	) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+		let overseer_handle = relay_chain_interface.overseer_handle()?;
+		let metrics = registry.map(Metrics::register).transpose()?;
+		Ok(Self {
+			relay_chain_interface,
+			overseer_handle,
+			para_id,
+			config,
+			metrics,
+			sessions: HashMap::new(),
+		})
+	}
+
+	/// Returns a never-ending `Future` that samples every candidate of the parachain.
+	pub async fn run(mut self) {
+		let mut imports = match self.relay_chain_interface.import_notification_stream().await {
+			Ok(imports) => imports.fuse(),
+			Err(e) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Failed to follow relay chain imports, availability sampling is disabled.",
+				);
+				return
+			},
+		};
+		let mut pending = FuturesUnordered::new();
+		// Chunk requests are awaited here, so new imports are handled while candidates are
+		// sampled.
+		let mut sampling = FuturesUnordered::new();
+		let mut sampled = HashSet::new();
+		let mut sampled_order = VecDeque::new();
+
+		loop {
+			futures::select! {
+				header = imports.next() => {
+					let Some(header) = header else { return };
+					let candidates = match self.pending_candidates(header.hash()).await {
+						Ok(candidates) => candidates,
+						Err(e) => {
+							tracing::debug!(
+								target: LOG_TARGET,
+								error = ?e,
+								"Failed to fetch candidates pending availability.",
+							);
+							continue
+						},
+					};
+					for candidate in candidates {
+						if !sampled.insert(candidate.hash) {
+							continue
+						}
+						sampled_order.push_back(candidate.hash);
+						if sampled_order.len() > SAMPLED_CANDIDATES_CACHE_SIZE {
+							if let Some(hash) = sampled_order.pop_front() {
+								sampled.remove(&hash);
+							}
+						}
+						let delay = futures_timer::Delay::new(self.config.delay);
+						pending.push(delay.map(move |_| candidate));
+					}
+				},
+				candidate = pending.select_next_some() => {
+					let session = self
+						.session_info(candidate.relay_parent, candidate.session_index)
+						.await;
+					match session {
+						Ok(Some(session)) => sampling.push(self.sample(candidate, session)),
+						Ok(None) => {},
+						Err(e) => tracing::debug!(
+							target: LOG_TARGET,
+							error = ?e,
+							"Failed to fetch session info.",
+						),
+					}
+				},
+				() = sampling.select_next_some() => {},
+			}
+		}
+	}
+
+	/// The candidates of the parachain pending availability at `relay_hash`.
+	async fn pending_candidates(&self, relay_hash: Hash) -> RelayChainResult<Vec<Candidate>> {
+		let receipts = self
+			.relay_chain_interface
+			.candidates_pending_availability(relay_hash, self.para_id)
+			.await?;
+		let session_index = self.relay_chain_interface.session_index_for_child(relay_hash).await?;
+		Ok(receipts
+			.into_iter()
+			.map(|receipt: CommittedCandidateReceipt| Candidate {
+				hash: receipt.hash(),
+				erasure_root: receipt.descriptor.erasure_root(),
+				relay_parent: relay_hash,
+				session_index,
+			})
+			.collect())
+	}
+
+	/// The session info of `session_index`, as known at `relay_hash`.
+	async fn session_info(
+		&mut self,
+		relay_hash: Hash,
+		session_index: SessionIndex,
+	) -> RelayChainResult<Option<Arc<SessionInfo>>> {
+		if let Some(info) = self.sessions.get(&session_index) {
+			return Ok(Some(info.clone()))
+		}
+
+		let info: Option<SessionInfo> = call_runtime_api(
+			&self.relay_chain_interface,
+			"ParachainHost_session_info",
+			relay_hash,
+			session_index,
+		)
+		.await?;
+		let Some(info) = info.map(Arc::new) else { return Ok(None) };
+		// Only the current and the previous session are of interest.
+		self.sessions.retain(|index, _| index + 1 >= session_index);
+		self.sessions.insert(session_index, info.clone());
+		Ok(Some(info))
+	}
+
+	/// Sample the chunks of `candidate` and report its availability confidence.
+	///
+	/// The returned future completes once all chunk requests were answered or timed out.
+	fn sample(&self, candidate: Candidate, session: Arc<SessionInfo>) -> impl Future<Output = ()> {
+		let mut overseer_handle = self.overseer_handle.clone();
+		let metrics = self.metrics.clone();
+		let samples = self.config.samples;
+		async move {
+			sample_chunks(&mut overseer_handle, metrics.as_ref(), samples, candidate, &session)
+				.await
+		}
+	}
+}
+
+/// Sample the chunks of `candidate` from `samples` validators of `session`.
+async fn sample_chunks(
+	overseer_handle: &mut OverseerHandle,
+	metrics: Option<&Metrics>,
+	samples: usize,
+	candidate: Candidate,
+	session: &SessionInfo,
+) {
+	let n_validators = session.validators.len();
+	let samples = samples.min(n_validators).min(session.discovery_keys.len());
+	if samples == 0 {
+		return
+	}
+
+	let indices = rand::seq::index::sample(&mut rand::thread_rng(), n_validators, samples);
+	let mut requests = Vec::with_capacity(samples);
+	let mut responses = Vec::with_capacity(samples);
+	for index in indices.iter() {
+		let Some(authority) = session.discovery_keys.get(index) else { continue };
+		let (request, response) =
+			OutgoingRequest::<v2::ChunkFetchingRequest, v1::ChunkFetchingRequest>::new(
+				Recipient::Authority(authority.clone()),
+				v2::ChunkFetchingRequest {
+					candidate_hash: candidate.hash,
+					index: ValidatorIndex(index as u32),
+				},
+			);
+		requests.push(Requests::ChunkFetching(request));
+		responses.push(response);
+	}
+	overseer_handle
+		.send_msg(
+			NetworkBridgeTxMessage::SendRequests(requests, IfDisconnected::TryConnect),
+			"AvailabilitySampling",
+		)
+		.await;
+
+	let outcomes = future::join_all(responses.into_iter().map(|response| {
+		let timeout = futures_timer::Delay::new(REQUEST_TIMEOUT);
+		future::select(Box::pin(response), timeout).map(|result| match result {
+			Either::Left((response, _)) => chunk_outcome(&candidate.erasure_root, response),
+			Either::Right(_) => ChunkOutcome::Unreachable,
+		})
+	}))
+	.await;
+
+	let available = outcomes.iter().filter(|o| **o == ChunkOutcome::Available).count();
+	let confidence = available as f64 / outcomes.len() as f64;
+	let threshold = polkadot_erasure_coding::recovery_threshold(n_validators)
+		.map(|threshold| threshold as f64 / n_validators as f64)
+		.unwrap_or(1.0);
+	let at_risk = confidence < threshold;
+
+	if let Some(metrics) = metrics {
+		for outcome in &outcomes {
+			metrics.chunks.with_label_values(&[outcome.label()]).inc();
+		}
+		metrics.confidence.set(confidence);
+		metrics.confidence_distribution.observe(confidence);
+		if at_risk {
+			metrics.at_risk.inc();
+		}
+	}
+	if at_risk {
+		tracing::warn!(
+			target: LOG_TARGET,
+			candidate_hash = ?candidate.hash,
+			available,
+			sampled = outcomes.len(),
+			"Candidate availability at risk, fewer chunks sampled than needed for recovery.",
+		);
+	} else {
+		tracing::debug!(
+			target: LOG_TARGET,
+			candidate_hash = ?candidate.hash,
+			available,
+			sampled = outcomes.len(),
+			"Sampled candidate availability.",
+		);
+	}
+}
+
+/// The outcome of a chunk request, verifying the chunk against `erasure_root`.
+fn chunk_outcome(
+	erasure_root: &Hash,
+	response: Result<v2::ChunkFetchingResponse, RequestError>,
+) -> ChunkOutcome {
+	match response {
+		Ok(v2::ChunkFetchingResponse::Chunk(chunk)) if verify_chunk(erasure_root, &chunk) =>
+			ChunkOutcome::Available,
+		Ok(v2::ChunkFetchingResponse::Chunk(_)) => ChunkOutcome::Invalid,
+		Ok(v2::ChunkFetchingResponse::NoSuchChunk) => ChunkOutcome::Missing,
+		Err(RequestError::InvalidResponse(_)) => ChunkOutcome::Invalid,
+		Err(_) => ChunkOutcome::Unreachable,
+	}
+}
+
+/// Whether `chunk` is part of the erasure coding with the root `erasure_root`.
+fn verify_chunk(erasure_root: &Hash, chunk: &ErasureChunk) -> bool {
+	polkadot_erasure_coding::branch_hash(erasure_root, &chunk.proof, chunk.index.0 as usize)
+		.is_ok_and(|hash| hash == BlakeTwo256::hash(&chunk.chunk))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_primitives::ChunkIndex;
+
+	fn chunks() -> (Hash, Vec<ErasureChunk>) {
+		let chunks = polkadot_erasure_coding::obtain_chunks(10, &vec![7u8; 1000]).unwrap();
+		let branches = polkadot_erasure_coding::branches(&chunks);
+		let root = branches.root();
+		let chunks = branches
+			.enumerate()
+			.map(|(index, (proof, chunk))| ErasureChunk {
+				chunk: chunk.to_vec(),
+				index: ChunkIndex(index as u32),
+				proof,
+			})
+			.collect();
+		(root, chunks)
+	}
+
+	#[test]
+	fn valid_chunks_are_available() {
+		let (root, chunks) = chunks();
+		for chunk in chunks {
+			assert_eq!(
+				chunk_outcome(&root, Ok(v2::ChunkFetchingResponse::Chunk(chunk))),
+				ChunkOutcome::Available,
+			);
+		}
+	}
+
+	#[test]
+	fn invalid_chunks_are_detected() {
+		let (root, chunks) = chunks();
+
+		let mut tampered = chunks[0].clone();
+		tampered.chunk[0] ^= 1;
+		assert_eq!(
+			chunk_outcome(&root, Ok(v2::ChunkFetchingResponse::Chunk(tampered))),
+			ChunkOutcome::Invalid,
+		);
+
+		let mut wrong_index = chunks[0].clone();
+		wrong_index.index = ChunkIndex(1);
+		assert_eq!(
+			chunk_outcome(&root, Ok(v2::ChunkFetchingResponse::Chunk(wrong_index))),
+			ChunkOutcome::Invalid,
+		);
+
+		assert_eq!(
+			chunk_outcome(
+				&Hash::repeat_byte(1),
+				Ok(v2::ChunkFetchingResponse::Chunk(chunks[1].clone()))
+			),
+			ChunkOutcome::Invalid,
+		);
+		assert_eq!(
+			chunk_outcome(&root, Ok(v2::ChunkFetchingResponse::NoSuchChunk)),
+			ChunkOutcome::Missing,
+		);
+	}
+}
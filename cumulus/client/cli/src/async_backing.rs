@@ -0,0 +1,520 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `advise-async-backing` command.
+//!
+//! Inspects the recent history of the parachain stored in the local database and recommends an
+//! asynchronous backing configuration. The node side configuration passed on the command line is
+//! validated against the relay chain host configuration known to the parachain.
+
+use std::{fmt, sync::Arc};
+
+use codec::{Compact, Decode};
+use cumulus_primitives_core::{
+	relay_chain::AsyncBackingParams, AbridgedHostConfiguration, PersistedValidationData,
+};
+use sc_client_api::{Backend, HeaderBackend, StorageProvider};
+use sp_core::storage::StorageKey;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+
+/// Default relay chain slot duration in milliseconds.
+const DEFAULT_RELAY_SLOT_DURATION_MS: u64 = 6000;
+
+/// Number of relay chain ancestors a collator should be allowed to build on.
+///
+/// Two ancestors allow a collator to keep building while a relay chain block is still being
+/// propagated.
+const RECOMMENDED_LOOKAHEAD: u32 = 2;
+
+/// Share of the maximum PoV size above which blocks are considered close to the limit.
+const POV_USAGE_WARNING_PERCENT: u64 = 80;
+
+/// Command for recommending an asynchronous backing configuration.
+#[derive(Debug, clap::Parser)]
+pub struct AsyncBackingAdvisorCmd {
+	/// Number of recent blocks to analyze, starting from the best block.
+	#[arg(long, default_value_t = 256)]
+	pub blocks: u32,
+
+	/// Relay chain slot duration in milliseconds.
+	#[arg(long, default_value_t = DEFAULT_RELAY_SLOT_DURATION_MS)]
+	pub relay_slot_duration: u64,
+
+	/// Block processing velocity configured in the runtime, to validate against the analysis.
+	#[arg(long)]
+	pub velocity: Option<u32>,
+
+	/// Unincluded segment capacity configured in the runtime, to validate against the analysis.
+	#[arg(long)]
+	pub unincluded_segment_capacity: Option<u32>,
+
+	/// Number of relay chain ancestors the collator builds on, to validate against the
+	/// `allowed_ancestry_len` of the relay chain.
+	#[arg(long)]
+	pub lookahead: Option<u32>,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub pruning_params: sc_cli::PruningParams,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+/// The node side asynchronous backing configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeAsyncBackingConfig {
+	/// Block processing velocity.
+	pub velocity: Option<u32>,
+	/// Unincluded segment capacity.
+	pub unincluded_segment_capacity: Option<u32>,
+	/// Number of relay chain ancestors the collator builds on.
+	pub lookahead: Option<u32>,
+}
+
+/// Data extracted from the state of a single parachain block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockSample {
+	/// The timestamp of the block in milliseconds.
+	pub timestamp: Option<u64>,
+	/// The relay parent number the block was built on.
+	pub relay_parent_number: Option<u32>,
+	/// The maximum PoV size allowed for the block.
+	pub max_pov_size: Option<u32>,
+	/// The proof size consumed by the block, as accounted by the runtime.
+	pub proof_size: Option<u64>,
+	/// The length of the unincluded segment after the block.
+	pub unincluded_segment_len: Option<u32>,
+}
+
+/// The recommended asynchronous backing configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsyncBackingRecommendation {
+	/// Block processing velocity of the runtime.
+	pub velocity: u32,
+	/// Unincluded segment capacity of the runtime.
+	pub unincluded_segment_capacity: u32,
+	/// Number of relay chain ancestors the collator builds on.
+	pub lookahead: u32,
+	/// `max_candidate_depth` required on the relay chain.
+	pub max_candidate_depth: u32,
+}
+
+/// Result of the analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsyncBackingReport {
+	/// Number of blocks that were analyzed.
+	pub blocks: usize,
+	/// Average time between blocks in milliseconds.
+	pub average_block_time: Option<u64>,
+	/// Maximum number of consecutive blocks built on the same relay parent.
+	pub max_blocks_per_relay_parent: u32,
+	/// Highest share of the maximum PoV size consumed by a block, in percent.
+	pub max_pov_usage_percent: Option<u64>,
+	/// Maximum observed length of the unincluded segment.
+	pub max_unincluded_segment_len: Option<u32>,
+	/// The asynchronous backing parameters of the relay chain.
+	pub host_params: Option<AsyncBackingParams>,
+	/// The recommended configuration.
+	pub recommendation: AsyncBackingRecommendation,
+	/// Problems found in the current configuration.
+	pub issues: Vec<String>,
+}
+
+/// Analyze the given samples, ordered from the oldest to the newest block.
+pub fn analyze(
+	samples: &[BlockSample],
+	host_params: Option<AsyncBackingParams>,
+	node_config: NodeAsyncBackingConfig,
+	relay_slot_duration: u64,
+) -> AsyncBackingReport {
+	let timestamps = samples.iter().filter_map(|s| s.timestamp).collect::<Vec<_>>();
+	let average_block_time = match (timestamps.first(), timestamps.last()) {
+		(Some(first), Some(last)) if timestamps.len() > 1 && last > first =>
+			Some((last - first) / (timestamps.len() as u64 - 1)),
+		_ => None,
+	};
+
+	let mut max_blocks_per_relay_parent = 0;
+	let mut current = (None, 0);
+	for relay_parent in samples.iter().filter_map(|s| s.relay_parent_number) {
+		current = if current.0 == Some(relay_parent) {
+			(current.0, current.1 + 1)
+		} else {
+			(Some(relay_parent), 1)
+		};
+		max_blocks_per_relay_parent = max_blocks_per_relay_parent.max(current.1);
+	}
+
+	let max_pov_usage_percent = samples
+		.iter()
+		.filter_map(|s| match (s.proof_size, s.max_pov_size) {
+			(Some(used), Some(max)) if max > 0 => Some(used.saturating_mul(100) / max as u64),
+			_ => None,
+		})
+		.max();
+
+	let max_unincluded_segment_len = samples.iter().filter_map(|s| s.unincluded_segment_len).max();
+
+	let velocity_from_block_time = average_block_time
+		.filter(|t| *t > 0)
+		.map(|t| relay_slot_duration.div_ceil(t) as u32)
+		.unwrap_or(1);
+	let velocity = velocity_from_block_time.max(max_blocks_per_relay_parent).max(1);
+	let unincluded_segment_capacity = 2 * velocity + 1;
+	let recommendation = AsyncBackingRecommendation {
+		velocity,
+		unincluded_segment_capacity,
+		lookahead: RECOMMENDED_LOOKAHEAD,
+		max_candidate_depth: unincluded_segment_capacity,
+	};
+
+	let mut issues = Vec::new();
+
+	if let Some(host) = host_params {
+		if host.max_candidate_depth == 0 && host.allowed_ancestry_len == 0 {
+			issues.push("Asynchronous backing is disabled on the relay chain.".into());
+		} else if host.max_candidate_depth < recommendation.max_candidate_depth {
+			issues.push(format!(
+				"The relay chain `max_candidate_depth` of {} is lower than the recommended {}.",
+				host.max_candidate_depth, recommendation.max_candidate_depth,
+			));
+		}
+
+		if let Some(capacity) = node_config.unincluded_segment_capacity {
+			if capacity > host.max_candidate_depth + 1 {
+				issues.push(format!(
+					"The unincluded segment capacity of {capacity} exceeds `max_candidate_depth + 1` \
+					 ({}) of the relay chain, candidates beyond that depth will be rejected.",
+					host.max_candidate_depth + 1,
+				));
+			}
+		}
+
+		if let Some(lookahead) = node_config.lookahead {
+			if lookahead > host.allowed_ancestry_len {
+				issues.push(format!(
+					"The lookahead of {lookahead} exceeds the relay chain `allowed_ancestry_len` of \
+					 {}, collations built on older relay parents will be rejected.",
+					host.allowed_ancestry_len,
+				));
+			}
+		}
+	}
+
+	if let Some(velocity) = node_config.velocity {
+		if velocity < recommendation.velocity {
+			issues.push(format!(
+				"The velocity of {velocity} is lower than the {} required by the observed block \
+				 production rate.",
+				recommendation.velocity,
+			));
+		}
+
+		if let Some(capacity) = node_config.unincluded_segment_capacity {
+			if velocity > capacity {
+				issues.push(format!(
+					"The velocity of {velocity} exceeds the unincluded segment capacity of \
+					 {capacity}."
+				));
+			}
+		}
+	}
+
+	if let Some(usage) = max_pov_usage_percent.filter(|u| *u >= POV_USAGE_WARNING_PERCENT) {
+		issues.push(format!(
+			"Blocks used up to {usage}% of the maximum PoV size, consider reducing the block \
+			 weight limits before increasing the velocity."
+		));
+	}
+
+	let capacity = node_config.unincluded_segment_capacity.unwrap_or(unincluded_segment_capacity);
+	if let Some(len) = max_unincluded_segment_len.filter(|len| *len >= capacity) {
+		issues.push(format!(
+			"The unincluded segment reached a length of {len}, block production was limited by \
+			 the segment capacity of {capacity}."
+		));
+	}
+
+	AsyncBackingReport {
+		blocks: samples.len(),
+		average_block_time,
+		max_blocks_per_relay_parent,
+		max_pov_usage_percent,
+		max_unincluded_segment_len,
+		host_params,
+		recommendation,
+		issues,
+	}
+}
+
+impl fmt::Display for AsyncBackingReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fn or_unknown<T: fmt::Display>(value: Option<T>) -> String {
+			value.map_or_else(|| "unknown".into(), |v| v.to_string())
+		}
+
+		writeln!(f, "Analyzed blocks: {}", self.blocks)?;
+		writeln!(f, "Average block time: {} ms", or_unknown(self.average_block_time))?;
+		writeln!(f, "Max blocks per relay parent: {}", self.max_blocks_per_relay_parent)?;
+		writeln!(f, "Max PoV usage: {}%", or_unknown(self.max_pov_usage_percent))?;
+		writeln!(
+			f,
+			"Max unincluded segment length: {}",
+			or_unknown(self.max_unincluded_segment_len)
+		)?;
+		if let Some(host) = &self.host_params {
+			writeln!(
+				f,
+				"Relay chain: max_candidate_depth = {}, allowed_ancestry_len = {}",
+				host.max_candidate_depth, host.allowed_ancestry_len,
+			)?;
+		}
+
+		writeln!(f)?;
+		writeln!(f, "Recommended configuration:")?;
+		writeln!(f, "  velocity = {}", self.recommendation.velocity)?;
+		writeln!(
+			f,
+			"  unincluded segment capacity = {}",
+			self.recommendation.unincluded_segment_capacity
+		)?;
+		writeln!(f, "  lookahead = {}", self.recommendation.lookahead)?;
+		writeln!(
+			f,
+			"  relay chain max_candidate_depth >= {}",
+			self.recommendation.max_candidate_depth
+		)?;
+
+		if !self.issues.is_empty() {
+			writeln!(f)?;
+			writeln!(f, "Issues:")?;
+			for issue in &self.issues {
+				writeln!(f, "  - {issue}")?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// `Weight` as stored by `frame-system`.
+#[derive(Decode)]
+struct Weight {
+	#[codec(compact)]
+	_ref_time: u64,
+	#[codec(compact)]
+	proof_size: u64,
+}
+
+/// `PerDispatchClass<Weight>` as stored by `frame-system`.
+#[derive(Decode)]
+struct BlockWeight {
+	normal: Weight,
+	operational: Weight,
+	mandatory: Weight,
+}
+
+fn storage_key(pallet: &[u8], item: &[u8]) -> StorageKey {
+	StorageKey([sp_crypto_hashing::twox_128(pallet), sp_crypto_hashing::twox_128(item)].concat())
+}
+
+fn read_storage<B, BE, C, T>(client: &C, at: B::Hash, key: &StorageKey) -> sc_cli::Result<Option<T>>
+where
+	B: BlockT,
+	BE: Backend<B>,
+	C: StorageProvider<B, BE>,
+	T: Decode,
+{
+	Ok(client.storage(at, key)?.map(|data| T::decode(&mut &data.0[..])).transpose()?)
+}
+
+impl AsyncBackingAdvisorCmd {
+	/// Run the advise-async-backing command
+	pub fn run<B, BE, C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		B: BlockT,
+		BE: Backend<B>,
+		C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	{
+		let timestamp_key = storage_key(b"Timestamp", b"Now");
+		let validation_data_key = storage_key(b"ParachainSystem", b"ValidationData");
+		let unincluded_segment_key = storage_key(b"ParachainSystem", b"UnincludedSegment");
+		let block_weight_key = storage_key(b"System", b"BlockWeight");
+		let host_config_key = storage_key(b"ParachainSystem", b"HostConfiguration");
+
+		let best_hash = client.info().best_hash;
+		let host_params = read_storage::<B, BE, _, AbridgedHostConfiguration>(
+			&*client,
+			best_hash,
+			&host_config_key,
+		)?
+		.map(|config| config.async_backing_params);
+
+		let mut samples = Vec::with_capacity(self.blocks as usize);
+		let mut hash = best_hash;
+		for _ in 0..self.blocks {
+			let header = client
+				.header(hash)?
+				.ok_or_else(|| format!("Header of block {hash:?} not found"))?;
+			if header.number().is_zero() {
+				break
+			}
+
+			let validation_data = read_storage::<B, BE, _, PersistedValidationData>(
+				&*client,
+				hash,
+				&validation_data_key,
+			)?;
+			let block_weight =
+				read_storage::<B, BE, _, BlockWeight>(&*client, hash, &block_weight_key)?;
+
+			samples.push(BlockSample {
+				timestamp: read_storage::<B, BE, _, u64>(&*client, hash, &timestamp_key)?,
+				relay_parent_number: validation_data.as_ref().map(|v| v.relay_parent_number),
+				max_pov_size: validation_data.as_ref().map(|v| v.max_pov_size),
+				proof_size: block_weight.map(|w| {
+					w.normal.proof_size + w.operational.proof_size + w.mandatory.proof_size
+				}),
+				// Only the length prefix of the segment is of interest.
+				unincluded_segment_len: read_storage::<B, BE, _, Compact<u32>>(
+					&*client,
+					hash,
+					&unincluded_segment_key,
+				)?
+				.map(|len| len.0),
+			});
+
+			hash = *header.parent_hash();
+		}
+		samples.reverse();
+
+		let report = analyze(
+			&samples,
+			host_params,
+			NodeAsyncBackingConfig {
+				velocity: self.velocity,
+				unincluded_segment_capacity: self.unincluded_segment_capacity,
+				lookahead: self.lookahead,
+			},
+			self.relay_slot_duration,
+		);
+		print!("{report}");
+
+		Ok(())
+	}
+}
+
+impl sc_cli::CliConfiguration for AsyncBackingAdvisorCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&sc_cli::PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn samples(block_time: u64, relay_parents: &[u32]) -> Vec<BlockSample> {
+		relay_parents
+			.iter()
+			.enumerate()
+			.map(|(i, relay_parent)| BlockSample {
+				timestamp: Some(i as u64 * block_time),
+				relay_parent_number: Some(*relay_parent),
+				max_pov_size: Some(5 * 1024 * 1024),
+				proof_size: Some(1024 * 1024),
+				unincluded_segment_len: Some(1),
+			})
+			.collect()
+	}
+
+	#[test]
+	fn recommends_configuration_from_block_time() {
+		let report = analyze(
+			&samples(2000, &[1, 1, 1, 2, 2, 2]),
+			Some(AsyncBackingParams { max_candidate_depth: 7, allowed_ancestry_len: 2 }),
+			Default::default(),
+			6000,
+		);
+
+		assert_eq!(report.average_block_time, Some(2000));
+		assert_eq!(report.max_blocks_per_relay_parent, 3);
+		assert_eq!(report.max_pov_usage_percent, Some(20));
+		assert_eq!(
+			report.recommendation,
+			AsyncBackingRecommendation {
+				velocity: 3,
+				unincluded_segment_capacity: 7,
+				lookahead: 2,
+				max_candidate_depth: 7,
+			}
+		);
+		assert!(report.issues.is_empty(), "{:?}", report.issues);
+	}
+
+	#[test]
+	fn validates_node_config_against_host_config() {
+		let report = analyze(
+			&samples(6000, &[1, 2, 3]),
+			Some(AsyncBackingParams { max_candidate_depth: 1, allowed_ancestry_len: 1 }),
+			NodeAsyncBackingConfig {
+				velocity: Some(4),
+				unincluded_segment_capacity: Some(3),
+				lookahead: Some(2),
+			},
+			6000,
+		);
+
+		assert_eq!(report.recommendation.velocity, 1);
+		assert_eq!(report.recommendation.max_candidate_depth, 3);
+		// Depth too low, segment too long, lookahead too long and velocity above capacity.
+		assert_eq!(report.issues.len(), 4, "{:?}", report.issues);
+		assert!(
+			report.issues[0].contains("`max_candidate_depth` of 1 is lower than the recommended 3")
+		);
+		assert!(report.issues[1].contains("capacity of 3 exceeds `max_candidate_depth + 1` (2)"));
+		assert!(report.issues[2].contains("lookahead of 2 exceeds the relay chain"));
+		assert!(report.issues[3].contains("velocity of 4 exceeds the unincluded segment capacity"));
+	}
+
+	#[test]
+	fn reports_disabled_async_backing() {
+		let report = analyze(
+			&samples(12000, &[1, 3]),
+			Some(AsyncBackingParams { max_candidate_depth: 0, allowed_ancestry_len: 0 }),
+			Default::default(),
+			6000,
+		);
+
+		assert_eq!(report.issues.len(), 1);
+		assert!(report.issues[0].contains("disabled"));
+	}
+}
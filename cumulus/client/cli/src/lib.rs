@@ -24,6 +24,7 @@ use std::{
 	io::{self, Write},
 	path::PathBuf,
 	sync::Arc,
+	time::Duration,
 };
 
 use codec::Encode;
@@ -38,6 +39,13 @@ use sp_core::hexdisplay::HexDisplay;
 use sp_runtime::traits::{Block as BlockT, Zero};
 use url::Url;
 
+mod async_backing;
+
+pub use async_backing::{
+	analyze as analyze_async_backing, AsyncBackingAdvisorCmd, AsyncBackingRecommendation,
+	AsyncBackingReport, BlockSample, NodeAsyncBackingConfig,
+};
+
 /// The `purge-chain` command used to remove the whole chain: the parachain and the relay chain.
 #[derive(Debug, clap::Parser)]
 #[group(skip)]
@@ -292,7 +300,12 @@ pub struct RunCmd {
 	///
 	/// The provided URLs should point to RPC endpoints of the relay chain.
 	/// This node connects to the remote nodes following the order they were specified in. If the
-	/// connection fails, it attempts to connect to the next endpoint in the list.
+	/// connection fails, it attempts to connect to the next endpoint in the list. Endpoints that
+	/// fail repeatedly are quarantined for an increasing amount of time.
+	///
+	/// An endpoint can be given a weight with a URL fragment, e.g. `wss://host#weight=3`. When
+	/// weights differ, endpoints are picked randomly in proportion to their weight and health,
+	/// spreading the load of a collator fleet over the available relay chain nodes.
 	///
 	/// Note: This option doesn't stop the node from connecting to the relay chain network but
 	/// reduces bandwidth use.
@@ -381,6 +394,18 @@ impl sc_cli::CliConfiguration for NormalizedRunCmd {
 		self.base.offchain_worker_params()
 	}
 
+	fn tokio_params(&self) -> Option<&sc_cli::TokioParams> {
+		self.base.tokio_params()
+	}
+
+	fn resource_limits_params(&self) -> Option<&sc_cli::ResourceLimitsParams> {
+		self.base.resource_limits_params()
+	}
+
+	fn informant_params(&self) -> Option<&sc_cli::InformantParams> {
+		self.base.informant_params()
+	}
+
 	fn node_name(&self) -> sc_cli::Result<String> {
 		self.base.node_name()
 	}
@@ -452,6 +477,18 @@ impl sc_cli::CliConfiguration for NormalizedRunCmd {
 		self.base.rpc_batch_config()
 	}
 
+	fn rpc_serve_finalized_only(&self) -> sc_cli::Result<bool> {
+		self.base.rpc_serve_finalized_only()
+	}
+
+	fn rpc_transaction_confirmations(&self) -> sc_cli::Result<u32> {
+		self.base.rpc_transaction_confirmations()
+	}
+
+	fn rpc_chain_head_resume_grace_period(&self) -> sc_cli::Result<Option<Duration>> {
+		self.base.rpc_chain_head_resume_grace_period()
+	}
+
 	fn transaction_pool(&self, is_dev: bool) -> sc_cli::Result<TransactionPoolOptions> {
 		self.base.transaction_pool(is_dev)
 	}
@@ -0,0 +1,327 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Health tracking and weighted selection of relay chain RPC endpoints.
+
+use prometheus::{Error as PrometheusError, Registry};
+use prometheus_endpoint::{CounterVec, GaugeVec, Opts, F64, U64};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+const LOG_TARGET: &str = "relay-chain-rpc-endpoints";
+
+/// Weight of the latest observation in the health score.
+const HEALTH_SMOOTHING: f64 = 0.3;
+/// Number of consecutive failures after which an endpoint is quarantined.
+const QUARANTINE_AFTER_FAILURES: u32 = 3;
+/// Quarantine duration after reaching [`QUARANTINE_AFTER_FAILURES`], doubled on every further
+/// failure.
+const BASE_QUARANTINE: Duration = Duration::from_secs(30);
+/// Upper bound of the quarantine duration.
+const MAX_QUARANTINE: Duration = Duration::from_secs(600);
+
+/// A single relay chain RPC endpoint.
+#[derive(Debug)]
+pub(crate) struct Endpoint {
+	/// Websocket URL, including the port.
+	pub url: String,
+	/// Relative share of the connections this endpoint should receive.
+	weight: u32,
+	/// Health score between `0.0` (always failing) and `1.0` (never failing).
+	health: f64,
+	consecutive_failures: u32,
+	quarantined_until: Option<Instant>,
+}
+
+impl Endpoint {
+	fn is_quarantined(&self, now: Instant) -> bool {
+		self.quarantined_until.map_or(false, |until| until > now)
+	}
+
+	/// Selection score; endpoints with a higher score are tried first on average.
+	fn score(&self) -> f64 {
+		// Keep a small floor so that failing endpoints can recover.
+		self.weight as f64 * self.health.max(0.01)
+	}
+}
+
+/// Extract the endpoint weight from the URL fragment (`wss://host:443#weight=3`).
+///
+/// Defaults to `1` if no valid weight is given.
+pub(crate) fn weight_from_fragment(fragment: Option<&str>) -> u32 {
+	fragment
+		.and_then(|fragment| fragment.strip_prefix("weight="))
+		.and_then(|weight| weight.parse().ok())
+		.filter(|weight| *weight > 0)
+		.unwrap_or(1)
+}
+
+/// Set of relay chain RPC endpoints with health scoring, quarantine of misbehaving endpoints and
+/// weighted selection.
+pub(crate) struct EndpointPool {
+	endpoints: Vec<Endpoint>,
+	metrics: Option<EndpointMetrics>,
+}
+
+impl EndpointPool {
+	/// Create a new pool from `(url, weight)` pairs.
+	pub fn new(endpoints: Vec<(String, u32)>, registry: Option<&Registry>) -> Self {
+		let metrics = registry.and_then(|registry| {
+			EndpointMetrics::register(registry)
+				.map_err(|err| {
+					tracing::warn!(target: LOG_TARGET, error = %err, "Unable to register endpoint metrics.");
+				})
+				.ok()
+		});
+
+		let endpoints = endpoints
+			.into_iter()
+			.map(|(url, weight)| Endpoint {
+				url,
+				weight,
+				health: 1.0,
+				consecutive_failures: 0,
+				quarantined_until: None,
+			})
+			.collect::<Vec<_>>();
+
+		let pool = Self { endpoints, metrics };
+		for index in 0..pool.endpoints.len() {
+			pool.update_metrics(index);
+		}
+		pool
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.endpoints.is_empty()
+	}
+
+	pub fn len(&self) -> usize {
+		self.endpoints.len()
+	}
+
+	pub fn get(&self, index: usize) -> &Endpoint {
+		&self.endpoints[index]
+	}
+
+	/// The order in which endpoints should be tried when (re)connecting.
+	///
+	/// Endpoints that are not quarantined come first. If all endpoints have the same weight they
+	/// are tried in the configured order, starting after `previous`, favoring healthier endpoints.
+	/// Otherwise they are shuffled with probabilities proportional to weight and health, spreading
+	/// the load of a collator fleet. Quarantined endpoints are only tried as a last resort.
+	pub fn connection_order(&self, previous: Option<usize>) -> Vec<usize> {
+		let now = Instant::now();
+		let len = self.endpoints.len();
+		let start = previous.map_or(0, |p| p + 1);
+		let uniform = self.endpoints.windows(2).all(|w| w[0].weight == w[1].weight);
+		let mut rng = rand::thread_rng();
+
+		let mut keyed = (0..len)
+			.map(|offset| (start + offset) % len)
+			.enumerate()
+			.map(|(position, index)| {
+				let endpoint = &self.endpoints[index];
+				let key = if uniform {
+					endpoint.health
+				} else {
+					// Weighted random sampling without replacement (Efraimidis-Spirakis).
+					rng.gen::<f64>().powf(1.0 / endpoint.score())
+				};
+				(endpoint.is_quarantined(now), key, position, index)
+			})
+			.collect::<Vec<_>>();
+
+		keyed.sort_by(|a, b| {
+			a.0.cmp(&b.0)
+				.then_with(|| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal))
+				.then_with(|| a.2.cmp(&b.2))
+		});
+
+		keyed.into_iter().map(|(_, _, _, index)| index).collect()
+	}
+
+	/// Record a successful interaction with the endpoint at `index`.
+	pub fn report_success(&mut self, index: usize) {
+		let endpoint = &mut self.endpoints[index];
+		endpoint.health = endpoint.health * (1.0 - HEALTH_SMOOTHING) + HEALTH_SMOOTHING;
+		endpoint.consecutive_failures = 0;
+		endpoint.quarantined_until = None;
+		self.update_metrics(index);
+	}
+
+	/// Record a failed interaction (connection failure or dropped connection) with the endpoint
+	/// at `index`, quarantining it after repeated failures.
+	pub fn report_failure(&mut self, index: usize) {
+		let endpoint = &mut self.endpoints[index];
+		endpoint.health *= 1.0 - HEALTH_SMOOTHING;
+		endpoint.consecutive_failures += 1;
+
+		if endpoint.consecutive_failures >= QUARANTINE_AFTER_FAILURES {
+			let exponent = (endpoint.consecutive_failures - QUARANTINE_AFTER_FAILURES).min(16);
+			let duration = BASE_QUARANTINE.saturating_mul(1 << exponent).min(MAX_QUARANTINE);
+			endpoint.quarantined_until = Some(Instant::now() + duration);
+			tracing::warn!(
+				target: LOG_TARGET,
+				url = endpoint.url,
+				failures = endpoint.consecutive_failures,
+				?duration,
+				"Quarantining misbehaving relay chain RPC endpoint."
+			);
+		}
+
+		if let Some(metrics) = &self.metrics {
+			metrics.failures.with_label_values(&[&self.endpoints[index].url]).inc();
+		}
+		self.update_metrics(index);
+	}
+
+	/// Record that the endpoint at `index` is now the active one.
+	pub fn report_active(&self, index: usize) {
+		if let Some(metrics) = &self.metrics {
+			for (i, endpoint) in self.endpoints.iter().enumerate() {
+				metrics.active.with_label_values(&[&endpoint.url]).set((i == index) as u64);
+			}
+		}
+	}
+
+	fn update_metrics(&self, index: usize) {
+		let Some(metrics) = &self.metrics else { return };
+		let endpoint = &self.endpoints[index];
+		metrics.health.with_label_values(&[&endpoint.url]).set(endpoint.health);
+		metrics
+			.quarantined
+			.with_label_values(&[&endpoint.url])
+			.set(endpoint.is_quarantined(Instant::now()) as u64);
+	}
+}
+
+/// Per endpoint metrics.
+#[derive(Clone)]
+struct EndpointMetrics {
+	health: GaugeVec<F64>,
+	quarantined: GaugeVec<U64>,
+	active: GaugeVec<U64>,
+	failures: CounterVec<U64>,
+}
+
+impl EndpointMetrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			health: prometheus_endpoint::register(
+				GaugeVec::new(
+					Opts::new(
+						"relay_chain_rpc_endpoint_health",
+						"Health score of a relay chain RPC endpoint, between 0 and 1",
+					),
+					&["endpoint"],
+				)?,
+				registry,
+			)?,
+			quarantined: prometheus_endpoint::register(
+				GaugeVec::new(
+					Opts::new(
+						"relay_chain_rpc_endpoint_quarantined",
+						"Whether a relay chain RPC endpoint is currently quarantined",
+					),
+					&["endpoint"],
+				)?,
+				registry,
+			)?,
+			active: prometheus_endpoint::register(
+				GaugeVec::new(
+					Opts::new(
+						"relay_chain_rpc_endpoint_active",
+						"Whether a relay chain RPC endpoint is the one currently in use",
+					),
+					&["endpoint"],
+				)?,
+				registry,
+			)?,
+			failures: prometheus_endpoint::register(
+				CounterVec::new(
+					Opts::new(
+						"relay_chain_rpc_endpoint_failures_total",
+						"Number of connection failures of a relay chain RPC endpoint",
+					),
+					&["endpoint"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pool(weights: &[u32]) -> EndpointPool {
+		EndpointPool::new(
+			weights.iter().enumerate().map(|(i, w)| (format!("ws://node-{i}:9944"), *w)).collect(),
+			None,
+		)
+	}
+
+	#[test]
+	fn weight_is_parsed_from_fragment() {
+		assert_eq!(weight_from_fragment(None), 1);
+		assert_eq!(weight_from_fragment(Some("weight=5")), 5);
+		assert_eq!(weight_from_fragment(Some("weight=0")), 1);
+		assert_eq!(weight_from_fragment(Some("other")), 1);
+	}
+
+	#[test]
+	fn uniform_weights_keep_configured_order() {
+		let pool = pool(&[1, 1, 1]);
+		assert_eq!(pool.connection_order(None), vec![0, 1, 2]);
+		assert_eq!(pool.connection_order(Some(0)), vec![1, 2, 0]);
+		assert_eq!(pool.connection_order(Some(2)), vec![0, 1, 2]);
+	}
+
+	#[test]
+	fn unhealthy_endpoints_are_tried_later() {
+		let mut pool = pool(&[1, 1, 1]);
+		pool.report_failure(0);
+		assert_eq!(pool.connection_order(None), vec![1, 2, 0]);
+
+		pool.report_failure(1);
+		pool.report_failure(1);
+		assert_eq!(pool.connection_order(None), vec![2, 0, 1]);
+	}
+
+	#[test]
+	fn repeatedly_failing_endpoints_are_quarantined() {
+		let mut pool = pool(&[10, 1]);
+		for _ in 0..QUARANTINE_AFTER_FAILURES {
+			pool.report_failure(0);
+		}
+		assert!(pool.get(0).is_quarantined(Instant::now()));
+		// Even with the higher weight, the quarantined endpoint comes last.
+		assert_eq!(pool.connection_order(None), vec![1, 0]);
+
+		pool.report_success(0);
+		assert!(!pool.get(0).is_quarantined(Instant::now()));
+	}
+
+	#[test]
+	fn weights_bias_the_selection() {
+		let pool = pool(&[9, 1]);
+		let first_choices = (0..1000).filter(|_| pool.connection_order(None)[0] == 0).count();
+		assert!(first_choices > 700, "{first_choices}");
+	}
+}
@@ -40,6 +40,7 @@ use std::{collections::btree_map::BTreeMap, pin::Pin};
 use cumulus_primitives_core::relay_chain::BlockId;
 pub use url::Url;
 
+mod endpoint_pool;
 mod light_client_worker;
 mod metrics;
 mod reconnecting_ws_client;
@@ -41,7 +41,11 @@ use tokio::sync::mpsc::{
 };
 use url::Url;
 
-use crate::rpc_client::{distribute_header, RpcDispatcherMessage};
+use crate::{
+	endpoint_pool::{weight_from_fragment, EndpointPool},
+	rpc_client::{distribute_header, RpcDispatcherMessage},
+};
+use prometheus::Registry;
 
 const LOG_TARGET: &str = "reconnecting-websocket-client";
 const DEFAULT_EXTERNAL_RPC_CONN_RETRIES: usize = 5;
@@ -52,7 +56,7 @@ const DEFAULT_SLEEP_EXP_BACKOFF_BETWEEN_RETRIES: i32 = 2;
 ///
 /// Must be polled to distribute header notifications to listeners.
 pub struct ReconnectingWebsocketWorker {
-	ws_urls: Vec<String>,
+	endpoints: Option<EndpointPool>,
 	/// Communication channel with the RPC client
 	client_receiver: TokioReceiver<RpcDispatcherMessage>,
 
@@ -84,9 +88,8 @@ fn url_to_string_with_port(url: Url) -> Option<String> {
 /// Manages the active websocket client.
 /// Responsible for creating request futures, subscription streams
 /// and reconnections.
-#[derive(Debug)]
 struct ClientManager {
-	urls: Vec<String>,
+	endpoints: EndpointPool,
 	active_client: Arc<JsonRpcClient>,
 	active_index: usize,
 }
@@ -97,53 +100,50 @@ struct RelayChainSubscriptions {
 	best_subscription: Subscription<RelayHeader>,
 }
 
-/// Try to find a new RPC server to connect to. Uses a naive retry
-/// logic that does an exponential backoff in between iterations
-/// through all URLs from the list. It uses a constant to tell how
-/// many iterations of connection attempts to all URLs we allow. We
-/// return early when a connection is made.
+/// Try to find a new RPC server to connect to. Endpoints are tried in the order given by
+/// [`EndpointPool::connection_order`], which takes weights, health and quarantine into account.
+/// Uses a naive retry logic that does an exponential backoff in between iterations through all
+/// endpoints. It uses a constant to tell how many iterations of connection attempts to all
+/// endpoints we allow. We return early when a connection is made.
 async fn connect_next_available_rpc_server(
-	urls: &Vec<String>,
-	starting_position: usize,
+	endpoints: &mut EndpointPool,
+	previous: Option<usize>,
 ) -> Result<(usize, Arc<JsonRpcClient>), ()> {
-	tracing::debug!(target: LOG_TARGET, starting_position, "Connecting to RPC server.");
-
-	let mut prev_iteration: u32 = 0;
-	for (counter, url) in urls
-		.iter()
-		.cycle()
-		.skip(starting_position)
-		.take(urls.len() * DEFAULT_EXTERNAL_RPC_CONN_RETRIES)
-		.enumerate()
-	{
-		// If we reached the end of the urls list, backoff before retrying
+	tracing::debug!(target: LOG_TARGET, ?previous, "Connecting to RPC server.");
+
+	for iteration in 0..DEFAULT_EXTERNAL_RPC_CONN_RETRIES {
+		// If we reached the end of the endpoint list, backoff before retrying
 		// connections to the entire list once more.
-		let Ok(current_iteration) = (counter / urls.len()).try_into() else {
-			tracing::error!(target: LOG_TARGET, "Too many connection attempts to the RPC servers, aborting...");
-			break;
-		};
-		if current_iteration > prev_iteration {
+		if iteration > 0 {
 			// Safe conversion given we convert positive i32s which are lower than u64::MAX.
 			tokio::time::sleep(Duration::from_millis(
 				DEFAULT_SLEEP_TIME_MS_BETWEEN_RETRIES *
-					DEFAULT_SLEEP_EXP_BACKOFF_BETWEEN_RETRIES.pow(prev_iteration) as u64,
+					DEFAULT_SLEEP_EXP_BACKOFF_BETWEEN_RETRIES.pow(iteration as u32 - 1) as u64,
 			))
 			.await;
-			prev_iteration = current_iteration;
 		}
 
-		let index = (starting_position + counter) % urls.len();
-		tracing::info!(
-			target: LOG_TARGET,
-			attempt = current_iteration,
-			index,
-			url,
-			"Trying to connect to next external relaychain node.",
-		);
-		match WsClientBuilder::default().build(&url).await {
-			Ok(ws_client) => return Ok((index, Arc::new(ws_client))),
-			Err(err) => tracing::debug!(target: LOG_TARGET, url, ?err, "Unable to connect."),
-		};
+		for index in endpoints.connection_order(previous) {
+			let url = endpoints.get(index).url.clone();
+			tracing::info!(
+				target: LOG_TARGET,
+				attempt = iteration,
+				index,
+				url,
+				"Trying to connect to next external relaychain node.",
+			);
+			match WsClientBuilder::default().build(&url).await {
+				Ok(ws_client) => {
+					endpoints.report_success(index);
+					endpoints.report_active(index);
+					return Ok((index, Arc::new(ws_client)))
+				},
+				Err(err) => {
+					tracing::debug!(target: LOG_TARGET, url, ?err, "Unable to connect.");
+					endpoints.report_failure(index);
+				},
+			};
+		}
 	}
 
 	tracing::error!(target: LOG_TARGET, "Retrying to connect to any external relaychain node failed.");
@@ -151,17 +151,21 @@ async fn connect_next_available_rpc_server(
 }
 
 impl ClientManager {
-	pub async fn new(urls: Vec<String>) -> Result<Self, ()> {
-		if urls.is_empty() {
+	pub async fn new(mut endpoints: EndpointPool) -> Result<Self, ()> {
+		if endpoints.is_empty() {
 			return Err(())
 		}
-		let active_client = connect_next_available_rpc_server(&urls, 0).await?;
-		Ok(Self { urls, active_client: active_client.1, active_index: active_client.0 })
+		let active_client = connect_next_available_rpc_server(&mut endpoints, None).await?;
+		Ok(Self { endpoints, active_client: active_client.1, active_index: active_client.0 })
 	}
 
+	/// Connect to a new RPC server, penalizing the currently active one which is assumed to have
+	/// failed.
 	pub async fn connect_to_new_rpc_server(&mut self) -> Result<(), ()> {
+		self.endpoints.report_failure(self.active_index);
 		let new_active =
-			connect_next_available_rpc_server(&self.urls, self.active_index + 1).await?;
+			connect_next_available_rpc_server(&mut self.endpoints, Some(self.active_index))
+				.await?;
 		self.active_client = new_active.1;
 		self.active_index = new_active.0;
 		Ok(())
@@ -263,14 +267,24 @@ enum ConnectionStatus {
 
 impl ReconnectingWebsocketWorker {
 	/// Create new worker. Returns the worker and a channel to register new listeners.
+	///
+	/// An endpoint can be given a weight via the URL fragment, e.g. `wss://host#weight=3`, to
+	/// receive a larger share of the connections of a collator fleet.
 	pub async fn new(
 		urls: Vec<Url>,
+		prometheus_registry: Option<&Registry>,
 	) -> (ReconnectingWebsocketWorker, TokioSender<RpcDispatcherMessage>) {
-		let urls = urls.into_iter().filter_map(url_to_string_with_port).collect();
+		let endpoints = urls
+			.into_iter()
+			.filter_map(|url| {
+				let weight = weight_from_fragment(url.fragment());
+				url_to_string_with_port(url).map(|url| (url, weight))
+			})
+			.collect();
 
 		let (tx, rx) = tokio_channel(100);
 		let worker = ReconnectingWebsocketWorker {
-			ws_urls: urls,
+			endpoints: Some(EndpointPool::new(endpoints, prometheus_registry)),
 			client_receiver: rx,
 			imported_header_listeners: Vec::new(),
 			finalized_header_listeners: Vec::new(),
@@ -333,8 +347,8 @@ impl ReconnectingWebsocketWorker {
 	pub async fn run(mut self) {
 		let mut pending_requests = FuturesUnordered::new();
 
-		let urls = std::mem::take(&mut self.ws_urls);
-		let Ok(mut client_manager) = ClientManager::new(urls).await else {
+		let Some(endpoints) = self.endpoints.take() else { return };
+		let Ok(mut client_manager) = ClientManager::new(endpoints).await else {
 			tracing::error!(target: LOG_TARGET, "No valid RPC url found. Stopping RPC worker.");
 			return
 		};
@@ -468,7 +482,7 @@ impl ReconnectingWebsocketWorker {
 mod test {
 	use std::time::Duration;
 
-	use super::{url_to_string_with_port, ClientManager};
+	use super::{url_to_string_with_port, ClientManager, EndpointPool};
 	use jsonrpsee::Methods;
 	use url::Url;
 
@@ -520,7 +534,11 @@ mod test {
 		// Start the client. Not exitting right away with an error means it
 		// is handling gracefully received connections refused while the server
 		// is starting.
-		let res = ClientManager::new(vec![format!("ws://127.0.0.1:{}", port)]).await;
+		let res = ClientManager::new(EndpointPool::new(
+			vec![(format!("ws://127.0.0.1:{}", port), 1)],
+			None,
+		))
+		.await;
 		assert!(res.is_ok());
 
 		server.await.unwrap();
@@ -97,7 +97,7 @@ pub async fn create_client_and_start_worker(
 	task_manager: &mut TaskManager,
 	prometheus_registry: Option<&Registry>,
 ) -> RelayChainResult<RelayChainRpcClient> {
-	let (worker, sender) = ReconnectingWebsocketWorker::new(urls).await;
+	let (worker, sender) = ReconnectingWebsocketWorker::new(urls, prometheus_registry).await;
 
 	task_manager
 		.spawn_essential_handle()
@@ -0,0 +1,418 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tracking of the latency between producing a parachain block and its inclusion on the relay
+//! chain.
+//!
+//! For every block authored by this node the tracker records the moment of its import and then
+//! follows the relay chain for `CandidateBacked`, `CandidateIncluded` and `CandidateTimedOut`
+//! events of the parachain. The time spent in each stage is logged and, when a Prometheus
+//! registry is available, reported as histograms.
+
+use cumulus_primitives_core::ParaId;
+use cumulus_relay_chain_interface::{call_runtime_api, PHash, RelayChainInterface};
+use futures::{stream, StreamExt};
+use polkadot_primitives::vstaging::CandidateEvent;
+use prometheus_endpoint::{
+	exponential_buckets, register, Counter, Histogram, HistogramOpts, PrometheusError, Registry,
+	U64,
+};
+use sc_client_api::BlockchainEvents;
+use sc_telemetry::log;
+use sp_consensus::BlockOrigin;
+use sp_core::Decode;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+const LOG_TARGET: &str = "cumulus-candidate-inclusion";
+
+/// Maximum number of authored blocks that are tracked at the same time.
+///
+/// Blocks that never make it into the relay chain (e.g. because they were superseded by another
+/// fork) are dropped in the order they were produced once this limit is reached.
+const MAX_TRACKED_CANDIDATES: usize = 256;
+
+/// Prometheus metrics for candidate inclusion latency.
+#[derive(Clone)]
+pub struct CandidateInclusionMetrics {
+	produced_to_backed: Histogram,
+	backed_to_included: Histogram,
+	produced_to_included: Histogram,
+	timed_out: Counter<U64>,
+}
+
+impl CandidateInclusionMetrics {
+	/// Create and register the metrics.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		let histogram = |name: &str, help: &str| -> Result<Histogram, PrometheusError> {
+			register(
+				Histogram::with_opts(
+					HistogramOpts::new(name, help).buckets(
+						exponential_buckets(0.5, 2.0, 10)
+							.expect("function parameters are constant and always valid; qed"),
+					),
+				)?,
+				registry,
+			)
+		};
+
+		Ok(Self {
+			produced_to_backed: histogram(
+				"parachain_candidate_produced_to_backed_seconds",
+				"Time between authoring a parachain block and its candidate being backed on the \
+				 relay chain",
+			)?,
+			backed_to_included: histogram(
+				"parachain_candidate_backed_to_included_seconds",
+				"Time between a candidate being backed and included on the relay chain",
+			)?,
+			produced_to_included: histogram(
+				"parachain_candidate_produced_to_included_seconds",
+				"Time between authoring a parachain block and its candidate being included on \
+				 the relay chain",
+			)?,
+			timed_out: register(
+				Counter::new(
+					"parachain_candidate_timed_out_total",
+					"Number of authored candidates that timed out waiting for availability",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Timestamps recorded for a single authored block.
+#[derive(Debug, Clone, Copy)]
+struct CandidateTimestamps {
+	produced: Instant,
+	backed: Option<Instant>,
+}
+
+/// The latency of a candidate that got included on the relay chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InclusionLatency {
+	/// Time between authoring the block and the candidate being backed, if the backing was
+	/// observed.
+	pub produced_to_backed: Option<Duration>,
+	/// Time between the candidate being backed and included, if the backing was observed.
+	pub backed_to_included: Option<Duration>,
+	/// Time between authoring the block and the candidate being included.
+	pub produced_to_included: Duration,
+}
+
+/// Keeps the per-candidate timestamps of blocks authored by this node.
+pub struct CandidateInclusionTracker<H> {
+	candidates: HashMap<H, CandidateTimestamps>,
+	order: VecDeque<H>,
+	metrics: Option<CandidateInclusionMetrics>,
+}
+
+impl<H: Clone + Eq + std::hash::Hash + std::fmt::Debug> CandidateInclusionTracker<H> {
+	/// Create a new tracker.
+	pub fn new(metrics: Option<CandidateInclusionMetrics>) -> Self {
+		Self { candidates: HashMap::new(), order: VecDeque::new(), metrics }
+	}
+
+	/// Number of candidates currently tracked.
+	pub fn len(&self) -> usize {
+		self.candidates.len()
+	}
+
+	/// Returns `true` if no candidates are tracked.
+	pub fn is_empty(&self) -> bool {
+		self.candidates.is_empty()
+	}
+
+	/// Note that the block with the given hash was authored by this node at `now`.
+	pub fn note_produced(&mut self, hash: H, now: Instant) {
+		if self.candidates.contains_key(&hash) {
+			return
+		}
+
+		while self.order.len() >= MAX_TRACKED_CANDIDATES {
+			if let Some(oldest) = self.order.pop_front() {
+				self.candidates.remove(&oldest);
+			}
+		}
+
+		self.candidates
+			.insert(hash.clone(), CandidateTimestamps { produced: now, backed: None });
+		self.order.push_back(hash);
+	}
+
+	/// Note that the candidate of the given block was backed at `now`.
+	///
+	/// Returns the time since the block was produced, or `None` if the block is not tracked or
+	/// the backing was already observed (e.g. on another relay chain fork).
+	pub fn note_backed(&mut self, hash: &H, now: Instant) -> Option<Duration> {
+		let entry = self.candidates.get_mut(hash)?;
+		if entry.backed.is_some() {
+			return None
+		}
+
+		entry.backed = Some(now);
+		let elapsed = now.saturating_duration_since(entry.produced);
+
+		log::debug!(target: LOG_TARGET, "Candidate for block {hash:?} backed after {elapsed:?}");
+		if let Some(metrics) = &self.metrics {
+			metrics.produced_to_backed.observe(elapsed.as_secs_f64());
+		}
+
+		Some(elapsed)
+	}
+
+	/// Note that the candidate of the given block was included at `now`.
+	///
+	/// The block stops being tracked. Returns `None` if the block was not tracked.
+	pub fn note_included(&mut self, hash: &H, now: Instant) -> Option<InclusionLatency> {
+		let entry = self.candidates.remove(hash)?;
+		self.order.retain(|h| h != hash);
+
+		let latency = InclusionLatency {
+			produced_to_backed: entry.backed.map(|b| b.saturating_duration_since(entry.produced)),
+			backed_to_included: entry.backed.map(|b| now.saturating_duration_since(b)),
+			produced_to_included: now.saturating_duration_since(entry.produced),
+		};
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Candidate for block {hash:?} included after {:?} (backed to included: {:?})",
+			latency.produced_to_included,
+			latency.backed_to_included,
+		);
+		if let Some(metrics) = &self.metrics {
+			metrics.produced_to_included.observe(latency.produced_to_included.as_secs_f64());
+			if let Some(backed_to_included) = latency.backed_to_included {
+				metrics.backed_to_included.observe(backed_to_included.as_secs_f64());
+			}
+		}
+
+		Some(latency)
+	}
+
+	/// Note that the candidate of the given block timed out.
+	///
+	/// The block keeps being tracked, as it may still be backed again. Returns `false` if the
+	/// block was not tracked.
+	pub fn note_timed_out(&mut self, hash: &H) -> bool {
+		let Some(entry) = self.candidates.get_mut(hash) else { return false };
+		entry.backed = None;
+
+		log::debug!(target: LOG_TARGET, "Candidate for block {hash:?} timed out");
+		if let Some(metrics) = &self.metrics {
+			metrics.timed_out.inc();
+		}
+
+		true
+	}
+}
+
+enum Notification<H> {
+	Produced(H),
+	RelayBlock(PHash),
+}
+
+enum EventKind {
+	Backed,
+	Included,
+	TimedOut,
+}
+
+/// Run the candidate inclusion tracker.
+///
+/// Follows the blocks authored by this node and the relay chain's candidate events for `para_id`.
+/// The returned future only finishes when one of the notification streams terminates.
+pub async fn run_candidate_inclusion_tracker<Block, Client, RCInterface>(
+	para_id: ParaId,
+	client: Arc<Client>,
+	relay_chain_interface: RCInterface,
+	prometheus_registry: Option<Registry>,
+) where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + Send + Sync + 'static,
+	RCInterface: RelayChainInterface + Clone + 'static,
+{
+	let metrics = prometheus_registry.as_ref().and_then(|registry| {
+		CandidateInclusionMetrics::register(registry)
+			.map_err(|err| {
+				log::warn!(target: LOG_TARGET, "Failed to register metrics: {err}");
+			})
+			.ok()
+	});
+	let mut tracker = CandidateInclusionTracker::<Block::Hash>::new(metrics);
+
+	let relay_imports = match relay_chain_interface.import_notification_stream().await {
+		Ok(stream) => stream,
+		Err(err) => {
+			log::error!(
+				target: LOG_TARGET,
+				"Failed to subscribe to relay chain imports: {err:?}",
+			);
+			return
+		},
+	};
+
+	let produced = client
+		.import_notification_stream()
+		.filter(|n| futures::future::ready(n.origin == BlockOrigin::Own))
+		.map(|n| Notification::Produced(n.hash));
+	let relay_blocks = relay_imports.map(|header| Notification::RelayBlock(header.hash()));
+	let mut notifications = stream::select(produced, relay_blocks);
+
+	while let Some(notification) = notifications.next().await {
+		match notification {
+			Notification::Produced(hash) => tracker.note_produced(hash, Instant::now()),
+			Notification::RelayBlock(relay_hash) => {
+				if tracker.is_empty() {
+					continue
+				}
+
+				let events = match call_runtime_api::<Vec<CandidateEvent>>(
+					&relay_chain_interface,
+					"ParachainHost_candidate_events",
+					relay_hash,
+					(),
+				)
+				.await
+				{
+					Ok(events) => events,
+					Err(err) => {
+						log::debug!(
+							target: LOG_TARGET,
+							"Failed to fetch candidate events at {relay_hash:?}: {err:?}",
+						);
+						continue
+					},
+				};
+
+				let now = Instant::now();
+				for event in events {
+					let (receipt, head_data, kind) = match event {
+						CandidateEvent::CandidateBacked(receipt, head_data, ..) =>
+							(receipt, head_data, EventKind::Backed),
+						CandidateEvent::CandidateIncluded(receipt, head_data, ..) =>
+							(receipt, head_data, EventKind::Included),
+						CandidateEvent::CandidateTimedOut(receipt, head_data, ..) =>
+							(receipt, head_data, EventKind::TimedOut),
+					};
+
+					if receipt.descriptor.para_id() != para_id {
+						continue
+					}
+
+					let Ok(header) = Block::Header::decode(&mut &head_data.0[..]) else {
+						log::debug!(
+							target: LOG_TARGET,
+							"Could not decode head data of candidate at {relay_hash:?}",
+						);
+						continue
+					};
+					let hash = header.hash();
+
+					match kind {
+						EventKind::Backed => {
+							tracker.note_backed(&hash, now);
+						},
+						EventKind::Included => {
+							tracker.note_included(&hash, now);
+						},
+						EventKind::TimedOut => {
+							tracker.note_timed_out(&hash);
+						},
+					}
+				}
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tracker() -> CandidateInclusionTracker<u64> {
+		CandidateInclusionTracker::new(None)
+	}
+
+	#[test]
+	fn tracks_produced_backed_included() {
+		let mut tracker = tracker();
+		let start = Instant::now();
+
+		tracker.note_produced(1, start);
+		assert_eq!(
+			tracker.note_backed(&1, start + Duration::from_secs(6)),
+			Some(Duration::from_secs(6))
+		);
+		// Backing on another fork does not overwrite the first observation.
+		assert_eq!(tracker.note_backed(&1, start + Duration::from_secs(12)), None);
+
+		let latency = tracker.note_included(&1, start + Duration::from_secs(18)).unwrap();
+		assert_eq!(
+			latency,
+			InclusionLatency {
+				produced_to_backed: Some(Duration::from_secs(6)),
+				backed_to_included: Some(Duration::from_secs(12)),
+				produced_to_included: Duration::from_secs(18),
+			}
+		);
+		assert!(tracker.is_empty());
+	}
+
+	#[test]
+	fn unknown_candidates_are_ignored() {
+		let mut tracker = tracker();
+		let now = Instant::now();
+
+		assert_eq!(tracker.note_backed(&1, now), None);
+		assert_eq!(tracker.note_included(&1, now), None);
+		assert!(!tracker.note_timed_out(&1));
+	}
+
+	#[test]
+	fn timed_out_candidate_can_be_backed_again() {
+		let mut tracker = tracker();
+		let start = Instant::now();
+
+		tracker.note_produced(1, start);
+		tracker.note_backed(&1, start + Duration::from_secs(6));
+		assert!(tracker.note_timed_out(&1));
+		assert_eq!(
+			tracker.note_backed(&1, start + Duration::from_secs(30)),
+			Some(Duration::from_secs(30))
+		);
+	}
+
+	#[test]
+	fn oldest_candidates_are_pruned() {
+		let mut tracker = tracker();
+		let now = Instant::now();
+
+		for hash in 0..(MAX_TRACKED_CANDIDATES as u64 + 10) {
+			tracker.note_produced(hash, now);
+		}
+
+		assert_eq!(tracker.len(), MAX_TRACKED_CANDIDATES);
+		assert_eq!(tracker.note_backed(&0, now), None);
+		assert!(tracker.note_backed(&(MAX_TRACKED_CANDIDATES as u64 + 9), now).is_some());
+	}
+}
@@ -0,0 +1,223 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reminders about upcoming renewals of Bulk Coretime.
+//!
+//! Meant to be run by nodes of a coretime chain. After every new best block the [`BrokerApi`] is
+//! queried for the upcoming renewals of the watched tasks. Opened renewals and renewals which are
+//! about to lapse are logged, so that their owners can renew in time.
+
+use codec::Codec;
+use futures::StreamExt;
+use pallet_broker::{
+	runtime_api::BrokerApi, CompletionStatus, CoreAssignment, CoreIndex, TaskId, Timeslice,
+	UpcomingRenewal,
+};
+use prometheus_endpoint::{register, GaugeVec, Opts, PrometheusError, Registry, U64};
+use sc_client_api::BlockchainEvents;
+use sc_telemetry::log;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
+
+const LOG_TARGET: &str = "cumulus-coretime-renewals";
+
+/// Configuration of the renewal reminders.
+#[derive(Clone, Debug)]
+pub struct RenewalReminderConfig {
+	/// The tasks whose renewals are watched. All tasks are watched if empty.
+	pub tasks: Vec<TaskId>,
+	/// The number of relay chain blocks before the deadline of an open renewal at which a
+	/// reminder is logged.
+	pub remind_before: u32,
+}
+
+/// Prometheus metrics for the renewal reminders.
+#[derive(Clone)]
+pub struct RenewalMetrics {
+	blocks_left: GaugeVec<U64>,
+}
+
+impl RenewalMetrics {
+	/// Create and register the metrics.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			blocks_left: register(
+				GaugeVec::new(
+					Opts::new(
+						"coretime_renewal_blocks_left",
+						"Relay chain blocks left until an upcoming renewal of a task lapses",
+					),
+					&["task", "core"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Export the given renewals.
+	pub fn update<Balance>(&self, renewals: &[(TaskId, UpcomingRenewal<Balance>)]) {
+		self.blocks_left.reset();
+		for (task, renewal) in renewals {
+			self.blocks_left
+				.with_label_values(&[&task.to_string(), &renewal.core.to_string()])
+				.set(renewal.blocks_left.into());
+		}
+	}
+}
+
+/// Whether a reminder was already logged for a renewal.
+#[derive(Clone, Copy, Default)]
+struct Reminded {
+	opened: bool,
+	deadline: bool,
+}
+
+/// Keeps track of the reminders which were logged.
+struct Reminders {
+	config: RenewalReminderConfig,
+	open: BTreeMap<(CoreIndex, Timeslice), (TaskId, Reminded)>,
+}
+
+impl Reminders {
+	fn new(config: RenewalReminderConfig) -> Self {
+		Self { config, open: Default::default() }
+	}
+
+	/// The renewals of the watched tasks.
+	///
+	/// Incomplete workloads can't be renewed and are skipped.
+	fn watched<Balance>(
+		&self,
+		renewals: Vec<UpcomingRenewal<Balance>>,
+	) -> Vec<(TaskId, UpcomingRenewal<Balance>)> {
+		renewals
+			.into_iter()
+			.filter_map(|renewal| {
+				let CompletionStatus::Complete(workload) = &renewal.completion else { return None };
+				let task = workload.iter().find_map(|item| match item.assignment {
+					CoreAssignment::Task(task)
+						if self.config.tasks.is_empty() || self.config.tasks.contains(&task) =>
+						Some(task),
+					_ => None,
+				})?;
+				Some((task, renewal))
+			})
+			.collect()
+	}
+
+	/// Log the reminders which are due for the given renewals.
+	fn process<Balance: Debug>(&mut self, renewals: &[(TaskId, UpcomingRenewal<Balance>)]) {
+		let mut open = BTreeMap::new();
+		for (task, renewal) in renewals.iter().filter(|(_, r)| r.open && !r.auto_renew) {
+			let key = (renewal.core, renewal.when);
+			let mut reminded = self.open.remove(&key).map(|(_, r)| r).unwrap_or_default();
+			if !reminded.opened {
+				log::info!(
+					target: LOG_TARGET,
+					"Renewal of task {task} on core {} is open at a price of {:?}, {} relay chain \
+					 blocks left",
+					renewal.core,
+					renewal.price,
+					renewal.blocks_left,
+				);
+				reminded.opened = true;
+			}
+			if !reminded.deadline && renewal.blocks_left <= self.config.remind_before {
+				log::warn!(
+					target: LOG_TARGET,
+					"Renewal of task {task} on core {} lapses in {} relay chain blocks",
+					renewal.core,
+					renewal.blocks_left,
+				);
+				reminded.deadline = true;
+			}
+			open.insert(key, (*task, reminded));
+		}
+
+		// Open renewals which are gone were either renewed or have lapsed.
+		for ((core, when), (task, _)) in std::mem::replace(&mut self.open, open) {
+			if renewals.iter().any(|(_, r)| r.core == core && r.when == when) {
+				// Still there, but auto-renewal got enabled.
+				continue
+			}
+			if renewals.iter().any(|(t, r)| *t == task && r.when > when) {
+				log::info!(target: LOG_TARGET, "Task {task} on core {core} got renewed");
+			} else {
+				log::warn!(target: LOG_TARGET, "Renewal of task {task} on core {core} lapsed");
+			}
+		}
+	}
+}
+
+/// Run the renewal reminders.
+///
+/// Blocks of runtimes that do not provide version 2 of the [`BrokerApi`] are skipped.
+pub async fn run_renewal_reminders<Block, Client, Balance>(
+	client: Arc<Client>,
+	config: RenewalReminderConfig,
+	prometheus_registry: Option<Registry>,
+) where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client::Api: BrokerApi<Block, Balance>,
+	Balance: Codec + Debug,
+{
+	let metrics = prometheus_registry.as_ref().and_then(|registry| {
+		RenewalMetrics::register(registry)
+			.map_err(|err| {
+				log::warn!(target: LOG_TARGET, "Failed to register metrics: {err}");
+			})
+			.ok()
+	});
+
+	let mut reminders = Reminders::new(config);
+	let mut imports = client.import_notification_stream();
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best {
+			continue
+		}
+
+		let api = client.runtime_api();
+		match api.api_version::<dyn BrokerApi<Block, Balance>>(notification.hash) {
+			Ok(Some(version)) if version >= 2 => {},
+			Ok(_) => continue,
+			Err(err) => {
+				log::debug!(target: LOG_TARGET, "Failed to check for broker API: {err}");
+				continue
+			},
+		}
+
+		let renewals = match api.upcoming_renewals(notification.hash) {
+			Ok(Ok(renewals)) => renewals,
+			Ok(Err(err)) => {
+				log::debug!(target: LOG_TARGET, "No upcoming renewals: {err:?}");
+				continue
+			},
+			Err(err) => {
+				log::debug!(target: LOG_TARGET, "Failed to fetch upcoming renewals: {err}");
+				continue
+			},
+		};
+
+		let renewals = reminders.watched(renewals);
+		reminders.process(&renewals);
+		if let Some(metrics) = &metrics {
+			metrics.update(&renewals);
+		}
+	}
+}
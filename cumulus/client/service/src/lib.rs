@@ -52,6 +52,11 @@ use std::{sync::Arc, time::Duration};
 
 pub use cumulus_primitives_proof_size_hostfunction::storage_proof_size;
 
+pub mod candidate_inclusion;
+pub mod coretime_renewals;
+pub mod relay_chain_informant;
+pub mod xcmp_queue_metrics;
+
 /// Host functions that should be used in parachain nodes.
 ///
 /// Contains the standard substrate host functions, as well as a
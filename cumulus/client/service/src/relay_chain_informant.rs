@@ -0,0 +1,98 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Status of the relay chain displayed by the informant of a parachain node.
+//!
+//! Besides the heads of the relay chain, the status contains the collation backlog: the number of
+//! parachain blocks on top of the parachain head included in the best relay chain block.
+
+use cumulus_primitives_core::{relay_chain::BlockId, ParaId};
+use cumulus_relay_chain_interface::RelayChainInterface;
+use futures::{future::BoxFuture, FutureExt};
+use polkadot_primitives::OccupiedCoreAssumption;
+use sc_informant::{RelayChainStatus, RelayChainStatusProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::Decode;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	SaturatedConversion,
+};
+use std::{marker::PhantomData, sync::Arc};
+
+/// [`RelayChainStatusProvider`] following the relay chain through a [`RelayChainInterface`].
+pub struct RelayChainInformant<Block, Client, RCInterface> {
+	para_id: ParaId,
+	client: Arc<Client>,
+	relay_chain_interface: RCInterface,
+	_phantom: PhantomData<fn() -> Block>,
+}
+
+impl<Block, Client, RCInterface> RelayChainInformant<Block, Client, RCInterface> {
+	/// Create the provider of the status of the relay chain of the parachain `para_id`.
+	pub fn new(para_id: ParaId, client: Arc<Client>, relay_chain_interface: RCInterface) -> Self {
+		Self { para_id, client, relay_chain_interface, _phantom: PhantomData }
+	}
+}
+
+impl<Block, Client, RCInterface> RelayChainInformant<Block, Client, RCInterface>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block>,
+	RCInterface: RelayChainInterface,
+{
+	async fn relay_chain_status(&self) -> Option<RelayChainStatus> {
+		let relay_chain = &self.relay_chain_interface;
+		let best_hash = relay_chain.best_block_hash().await.ok()?;
+		let best = relay_chain.header(BlockId::Hash(best_hash)).await.ok()??;
+		let finalized_hash = relay_chain.finalized_block_hash().await.ok()?;
+		let finalized = relay_chain.header(BlockId::Hash(finalized_hash)).await.ok()??;
+		let is_major_syncing = relay_chain.is_major_syncing().await.ok()?;
+
+		let included = relay_chain
+			.persisted_validation_data(best_hash, self.para_id, OccupiedCoreAssumption::TimedOut)
+			.await
+			.ok()
+			.flatten()
+			.and_then(|data| Block::Header::decode(&mut &data.parent_head.0[..]).ok());
+		let collation_backlog = included.map(|included| {
+			let best_number = self.client.info().best_number.saturated_into::<u64>();
+			best_number.saturating_sub((*included.number()).saturated_into())
+		});
+
+		Some(RelayChainStatus {
+			best_number: best.number.into(),
+			best_hash: best_hash.to_string(),
+			finalized_number: finalized.number.into(),
+			finalized_hash: finalized_hash.to_string(),
+			peers: None,
+			is_major_syncing,
+			collation_backlog,
+		})
+	}
+}
+
+impl<Block, Client, RCInterface> RelayChainStatusProvider
+	for RelayChainInformant<Block, Client, RCInterface>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + Send + Sync,
+	RCInterface: RelayChainInterface,
+{
+	fn status(&self) -> BoxFuture<'_, Option<RelayChainStatus>> {
+		self.relay_chain_status().boxed()
+	}
+}
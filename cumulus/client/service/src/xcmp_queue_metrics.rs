@@ -0,0 +1,217 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics and logs about the XCMP queue of the parachain.
+//!
+//! After every new best block the [`XcmpQueueStatusApi`] is queried and the suspension state and
+//! queue depth of every channel is exported, so that suspended channels become visible before
+//! transfers silently stop.
+
+use cumulus_primitives_core::{ParaId, XcmpQueueStatus, XcmpQueueStatusApi};
+use futures::StreamExt;
+use prometheus_endpoint::{register, Gauge, GaugeVec, Opts, PrometheusError, Registry, U64};
+use sc_client_api::BlockchainEvents;
+use sc_telemetry::log;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::BTreeSet, sync::Arc};
+
+const LOG_TARGET: &str = "cumulus-xcmp-queue";
+
+/// Prometheus metrics for the XCMP queue.
+#[derive(Clone)]
+pub struct XcmpQueueMetrics {
+	execution_suspended: Gauge<U64>,
+	thresholds: GaugeVec<U64>,
+	inbound_suspended: GaugeVec<U64>,
+	inbound_pages: GaugeVec<U64>,
+	outbound_suspended: GaugeVec<U64>,
+	outbound_pages: GaugeVec<U64>,
+}
+
+impl XcmpQueueMetrics {
+	/// Create and register the metrics.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		let channel_gauge = |name: &str, help: &str| -> Result<GaugeVec<U64>, PrometheusError> {
+			register(GaugeVec::new(Opts::new(name, help), &["sibling"])?, registry)
+		};
+
+		Ok(Self {
+			execution_suspended: register(
+				Gauge::new(
+					"parachain_xcmp_queue_execution_suspended",
+					"Whether the execution of incoming XCMs is suspended",
+				)?,
+				registry,
+			)?,
+			thresholds: register(
+				GaugeVec::new(
+					Opts::new(
+						"parachain_xcmp_queue_threshold_pages",
+						"Configured XCMP queue thresholds in pages",
+					),
+					&["threshold"],
+				)?,
+				registry,
+			)?,
+			inbound_suspended: channel_gauge(
+				"parachain_xcmp_channel_inbound_suspended",
+				"Whether the inbound channel from a sibling is suspended by this parachain",
+			)?,
+			inbound_pages: channel_gauge(
+				"parachain_xcmp_channel_inbound_pages",
+				"Number of ready pages in the inbound queue of a sibling",
+			)?,
+			outbound_suspended: channel_gauge(
+				"parachain_xcmp_channel_outbound_suspended",
+				"Whether the outbound channel to a sibling is suspended by the sibling",
+			)?,
+			outbound_pages: channel_gauge(
+				"parachain_xcmp_channel_outbound_pages",
+				"Number of pages waiting to be sent to a sibling",
+			)?,
+		})
+	}
+
+	/// Export the given status.
+	///
+	/// Channels that are not part of the status anymore are removed.
+	pub fn update(&self, status: &XcmpQueueStatus) {
+		self.execution_suspended.set(status.execution_suspended as u64);
+		self.thresholds
+			.with_label_values(&["suspend"])
+			.set(status.suspend_threshold.into());
+		self.thresholds.with_label_values(&["drop"]).set(status.drop_threshold.into());
+		self.thresholds
+			.with_label_values(&["resume"])
+			.set(status.resume_threshold.into());
+
+		self.inbound_suspended.reset();
+		self.inbound_pages.reset();
+		self.outbound_suspended.reset();
+		self.outbound_pages.reset();
+		for channel in &status.channels {
+			let sibling = u32::from(channel.sibling).to_string();
+			let labels = [sibling.as_str()];
+			self.inbound_suspended
+				.with_label_values(&labels)
+				.set(channel.inbound_suspended as u64);
+			self.inbound_pages
+				.with_label_values(&labels)
+				.set(channel.inbound_ready_pages.into());
+			self.outbound_suspended
+				.with_label_values(&labels)
+				.set(channel.outbound_suspended as u64);
+			self.outbound_pages
+				.with_label_values(&labels)
+				.set(channel.outbound_pages.into());
+		}
+	}
+}
+
+/// Suspended inbound and outbound channels of a status.
+fn suspended_channels(status: &XcmpQueueStatus) -> (BTreeSet<ParaId>, BTreeSet<ParaId>) {
+	let inbound = status.channels.iter().filter(|c| c.inbound_suspended).map(|c| c.sibling);
+	let outbound = status.channels.iter().filter(|c| c.outbound_suspended).map(|c| c.sibling);
+	(inbound.collect(), outbound.collect())
+}
+
+/// Log changes of the suspension state between two statuses.
+fn log_changes(previous: &XcmpQueueStatus, current: &XcmpQueueStatus) {
+	if previous.execution_suspended != current.execution_suspended {
+		if current.execution_suspended {
+			log::warn!(target: LOG_TARGET, "Execution of incoming XCMs got suspended");
+		} else {
+			log::info!(target: LOG_TARGET, "Execution of incoming XCMs got resumed");
+		}
+	}
+
+	let (prev_inbound, prev_outbound) = suspended_channels(previous);
+	let (inbound, outbound) = suspended_channels(current);
+
+	for sibling in inbound.difference(&prev_inbound) {
+		log::warn!(
+			target: LOG_TARGET,
+			"Inbound XCMP channel from sibling {sibling} suspended, its queue is full",
+		);
+	}
+	for sibling in prev_inbound.difference(&inbound) {
+		log::info!(target: LOG_TARGET, "Inbound XCMP channel from sibling {sibling} resumed");
+	}
+	for sibling in outbound.difference(&prev_outbound) {
+		log::warn!(
+			target: LOG_TARGET,
+			"Outbound XCMP channel to sibling {sibling} suspended by the sibling, messages are \
+			 queued until it resumes",
+		);
+	}
+	for sibling in prev_outbound.difference(&outbound) {
+		log::info!(target: LOG_TARGET, "Outbound XCMP channel to sibling {sibling} resumed");
+	}
+}
+
+/// Run the XCMP queue monitor.
+///
+/// Blocks of runtimes that do not provide the [`XcmpQueueStatusApi`] are skipped.
+pub async fn run_xcmp_queue_monitor<Block, Client>(
+	client: Arc<Client>,
+	prometheus_registry: Option<Registry>,
+) where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client::Api: XcmpQueueStatusApi<Block>,
+{
+	let metrics = prometheus_registry.as_ref().and_then(|registry| {
+		XcmpQueueMetrics::register(registry)
+			.map_err(|err| {
+				log::warn!(target: LOG_TARGET, "Failed to register metrics: {err}");
+			})
+			.ok()
+	});
+
+	let mut previous = XcmpQueueStatus::default();
+	let mut imports = client.import_notification_stream();
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best {
+			continue
+		}
+
+		let api = client.runtime_api();
+		match api.has_api::<dyn XcmpQueueStatusApi<Block>>(notification.hash) {
+			Ok(true) => {},
+			Ok(false) => continue,
+			Err(err) => {
+				log::debug!(target: LOG_TARGET, "Failed to check for XCMP queue API: {err}");
+				continue
+			},
+		}
+
+		let status = match api.xcmp_queue_status(notification.hash) {
+			Ok(status) => status,
+			Err(err) => {
+				log::debug!(target: LOG_TARGET, "Failed to fetch XCMP queue status: {err}");
+				continue
+			},
+		};
+
+		log_changes(&previous, &status);
+		if let Some(metrics) = &metrics {
+			metrics.update(&status);
+		}
+		previous = status;
+	}
+}
@@ -40,6 +40,16 @@ mod benchmarks {
 		Pallet::<T>::update_resume_threshold(RawOrigin::Root, 1);
 	}
 
+	#[benchmark]
+	fn set_auto_resume_policy() {
+		let policy = AutoResumePolicy { inbound: true, outbound_after: Some(10u32.into()) };
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, policy);
+
+		assert_eq!(AutoResume::<T>::get(), policy);
+	}
+
 	#[benchmark]
 	fn enqueue_xcmp_message() {
 		assert!(QueueConfig::<T>::get().drop_threshold * MaxXcmpMessageLenOf::<T>::get() > 1000);
@@ -54,10 +54,11 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 use bounded_collections::BoundedBTreeSet;
-use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
+use codec::{Decode, DecodeLimit, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use cumulus_primitives_core::{
 	relay_chain::BlockNumber as RelayBlockNumber, ChannelStatus, GetChannelInfo, MessageSendError,
-	ParaId, XcmpMessageFormat, XcmpMessageHandler, XcmpMessageSource,
+	ParaId, XcmpChannelStatus, XcmpMessageFormat, XcmpMessageHandler, XcmpMessageSource,
+	XcmpQueueStatus,
 };
 
 use frame_support::{
@@ -71,7 +72,9 @@ use polkadot_runtime_common::xcm_sender::PriceForMessageDelivery;
 use polkadot_runtime_parachains::FeeTracker;
 use scale_info::TypeInfo;
 use sp_core::MAX_POSSIBLE_ALLOCATION;
-use sp_runtime::{FixedU128, RuntimeDebug, Saturating, WeakBoundedVec};
+use sp_runtime::{
+	traits::BlockNumberProvider, FixedU128, RuntimeDebug, Saturating, WeakBoundedVec,
+};
 use xcm::{latest::prelude::*, VersionedLocation, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH};
 use xcm_builder::InspectMessageQueues;
 use xcm_executor::traits::ConvertOrigin;
@@ -254,6 +257,23 @@ pub mod pallet {
 				data.validate::<T>()
 			})
 		}
+
+		/// Overwrites the policy for automatically resuming suspended channels.
+		///
+		/// - `origin`: Must pass `Root`.
+		/// - `new`: Desired value for `AutoResume`
+		#[pallet::call_index(6)]
+		#[pallet::weight((T::WeightInfo::set_auto_resume_policy(), DispatchClass::Operational,))]
+		pub fn set_auto_resume_policy(
+			origin: OriginFor<T>,
+			new: AutoResumePolicy<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			AutoResume::<T>::put(new);
+			Self::deposit_event(Event::AutoResumePolicySet { policy: new });
+			Ok(())
+		}
 	}
 
 	#[pallet::hooks]
@@ -278,6 +298,8 @@ pub mod pallet {
 
 			migration::v3::lazy_migrate_inbound_queue::<T>();
 
+			Self::auto_resume(&mut meter);
+
 			meter.consumed()
 		}
 	}
@@ -287,6 +309,19 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// An HRMP message was sent to a sibling parachain.
 		XcmpMessageSent { message_hash: XcmHash },
+		/// The inbound channel from a sibling was suspended since our queue for it is too full.
+		InboundChannelSuspended { sibling: ParaId },
+		/// The inbound channel from a sibling was resumed.
+		InboundChannelResumed { sibling: ParaId },
+		/// The outbound channel to a sibling was suspended by the sibling.
+		OutboundChannelSuspended { sibling: ParaId },
+		/// The outbound channel to a sibling was resumed.
+		///
+		/// `automatically` is set if the channel was resumed by the [`AutoResumePolicy`] instead
+		/// of a signal from the sibling.
+		OutboundChannelResumed { sibling: ParaId, automatically: bool },
+		/// The policy for automatically resuming suspended channels was changed.
+		AutoResumePolicySet { policy: AutoResumePolicy<BlockNumberFor<T>> },
 	}
 
 	#[pallet::error]
@@ -353,6 +388,16 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type QueueSuspended<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+	/// The policy for automatically resuming suspended channels.
+	#[pallet::storage]
+	pub(super) type AutoResume<T: Config> =
+		StorageValue<_, AutoResumePolicy<BlockNumberFor<T>>, ValueQuery>;
+
+	/// The block number at which outbound channels were suspended by their recipient.
+	#[pallet::storage]
+	pub(super) type OutboundSuspendedSince<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, BlockNumberFor<T>, OptionQuery>;
+
 	/// Initialization value for the DeliveryFee factor.
 	#[pallet::type_value]
 	pub fn InitialFactor() -> FixedU128 {
@@ -450,6 +495,33 @@ impl QueueConfigData {
 	}
 }
 
+/// Policy for automatically resuming suspended XCMP channels in `on_idle`.
+///
+/// The default policy does not resume any channel automatically.
+#[derive(
+	Copy,
+	Clone,
+	Default,
+	Eq,
+	PartialEq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct AutoResumePolicy<BlockNumber> {
+	/// Re-send the resume signal to siblings whose inbound channel is still suspended although
+	/// their queue already drained to the `resume_threshold`.
+	///
+	/// This recovers channels for which sending the resume signal failed.
+	pub inbound: bool,
+	/// Resume outbound channels that were suspended by their recipient for at least this many
+	/// blocks. `None` keeps them suspended until the recipient resumes them.
+	pub outbound_after: Option<BlockNumber>,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, TypeInfo)]
 pub enum ChannelSignal {
 	Suspend,
@@ -606,6 +678,12 @@ impl<T: Config> Pallet<T> {
 	}
 
 	fn suspend_channel(target: ParaId) {
+		<OutboundSuspendedSince<T>>::insert(
+			target,
+			frame_system::Pallet::<T>::current_block_number(),
+		);
+		Self::deposit_event(Event::OutboundChannelSuspended { sibling: target });
+
 		<OutboundXcmpStatus<T>>::mutate(|s| {
 			if let Some(details) = s.iter_mut().find(|item| item.recipient == target) {
 				let ok = details.state == OutboundState::Ok;
@@ -620,6 +698,12 @@ impl<T: Config> Pallet<T> {
 	}
 
 	fn resume_channel(target: ParaId) {
+		<OutboundSuspendedSince<T>>::remove(target);
+		Self::deposit_event(Event::OutboundChannelResumed {
+			sibling: target,
+			automatically: false,
+		});
+
 		<OutboundXcmpStatus<T>>::mutate(|s| {
 			if let Some(index) = s.iter().position(|item| item.recipient == target) {
 				let suspended = s[index].state == OutboundState::Suspended;
@@ -704,6 +788,121 @@ impl<T: Config> Pallet<T> {
 			(c.state, queued_pages)
 		})
 	}
+
+	/// Resume suspended channels according to the [`AutoResumePolicy`].
+	///
+	/// Stops as soon as the `meter` runs out of weight; remaining channels are handled in a later
+	/// block.
+	fn auto_resume(meter: &mut WeightMeter) {
+		let db = T::DbWeight::get();
+		if meter.try_consume(db.reads(1)).is_err() {
+			return
+		}
+		let policy = <AutoResume<T>>::get();
+
+		if policy.inbound && meter.try_consume(db.reads(2)).is_ok() {
+			let QueueConfigData { resume_threshold, .. } = <QueueConfig<T>>::get();
+			for para in <InboundXcmpSuspended<T>>::get() {
+				if meter.try_consume(db.reads_writes(3, 3)).is_err() {
+					return
+				}
+
+				let fp = T::XcmpQueue::footprint(para);
+				if fp.ready_pages <= resume_threshold {
+					log::debug!(
+						target: LOG_TARGET,
+						"Re-sending resume signal to drained inbound channel of sibling {:?}.",
+						para
+					);
+					Self::on_queue_changed(para, fp);
+				}
+			}
+		}
+
+		let Some(outbound_after) = policy.outbound_after else { return };
+		let now = frame_system::Pallet::<T>::current_block_number();
+		// Collect first, since resuming mutates the map.
+		let mut expired = Vec::new();
+		for (para, since) in <OutboundSuspendedSince<T>>::iter() {
+			if meter.try_consume(db.reads(1)).is_err() {
+				break
+			}
+			if now.saturating_sub(since) >= outbound_after {
+				expired.push(para);
+			}
+		}
+
+		for para in expired {
+			if meter
+				.try_consume(T::WeightInfo::resume_channel().saturating_add(db.writes(1)))
+				.is_err()
+			{
+				return
+			}
+
+			log::info!(
+				target: LOG_TARGET,
+				"Automatically resuming outbound channel to sibling {:?} after {:?} blocks.",
+				para,
+				outbound_after
+			);
+			<OutboundSuspendedSince<T>>::remove(para);
+			<OutboundXcmpStatus<T>>::mutate(|s| {
+				if let Some(index) = s.iter().position(|item| item.recipient == para) {
+					if s[index].first_index == s[index].last_index && !s[index].signals_exist {
+						s.remove(index);
+					} else {
+						s[index].state = OutboundState::Ok;
+					}
+				}
+			});
+			Self::deposit_event(Event::OutboundChannelResumed {
+				sibling: para,
+				automatically: true,
+			});
+		}
+	}
+
+	/// The status of the XCMP queue and of all channels that are suspended or have outbound
+	/// messages queued.
+	///
+	/// Meant to be used by the [`XcmpQueueStatusApi`](cumulus_primitives_core::XcmpQueueStatusApi)
+	/// runtime API.
+	pub fn queue_status() -> XcmpQueueStatus {
+		let QueueConfigData { suspend_threshold, drop_threshold, resume_threshold } =
+			<QueueConfig<T>>::get();
+		let inbound_suspended = <InboundXcmpSuspended<T>>::get();
+		let outbound = <OutboundXcmpStatus<T>>::get();
+
+		let mut siblings = inbound_suspended.iter().copied().collect::<Vec<_>>();
+		siblings.extend(outbound.iter().map(|c| c.recipient));
+		siblings.sort();
+		siblings.dedup();
+
+		let channels = siblings
+			.into_iter()
+			.map(|sibling| {
+				let outbound = outbound.iter().find(|c| c.recipient == sibling);
+				XcmpChannelStatus {
+					sibling,
+					inbound_suspended: inbound_suspended.contains(&sibling),
+					inbound_ready_pages: T::XcmpQueue::footprint(sibling).ready_pages,
+					outbound_suspended: outbound
+						.is_some_and(|c| c.state == OutboundState::Suspended),
+					outbound_pages: outbound
+						.map_or(0, |c| c.last_index.saturating_sub(c.first_index).into()),
+				}
+			})
+			.collect();
+
+		XcmpQueueStatus {
+			execution_suspended: <QueueSuspended<T>>::get(),
+			suspend_threshold,
+			drop_threshold,
+			resume_threshold,
+			channels,
+		}
+	}
 }
 
 impl<T: Config> OnQueueChanged<ParaId> for Pallet<T> {
@@ -720,6 +919,7 @@ impl<T: Config> OnQueueChanged<ParaId> for Pallet<T> {
 			} else {
 				suspended_channels.remove(&para);
 				<InboundXcmpSuspended<T>>::put(suspended_channels);
+				Self::deposit_event(Event::InboundChannelResumed { sibling: para });
 			}
 		} else if !suspended && fp.ready_pages >= suspend_threshold {
 			log::warn!("XCMP queue for sibling {:?} is full; suspending channel.", para);
@@ -733,6 +933,7 @@ impl<T: Config> OnQueueChanged<ParaId> for Pallet<T> {
 				log::error!("Too many channels suspended; cannot suspend sibling {:?}: {:?}; further messages may be dropped.", para, err);
 			} else {
 				<InboundXcmpSuspended<T>>::put(suspended_channels);
+				Self::deposit_event(Event::InboundChannelSuspended { sibling: para });
 			}
 		}
 	}
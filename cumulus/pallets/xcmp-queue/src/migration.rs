@@ -17,6 +17,7 @@
 //! A module that is responsible for migration of storage.
 
 pub mod v5;
+pub mod v6;
 
 use crate::{Config, OverweightIndex, Pallet, QueueConfig, QueueConfigData, DEFAULT_POV_SIZE};
 use alloc::vec::Vec;
@@ -28,7 +29,7 @@ use frame_support::{
 };
 
 /// The in-code storage version.
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
 pub const LOG: &str = "runtime::xcmp-queue-migration";
 
@@ -422,4 +423,41 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn test_migration_to_v6() {
+		use crate::{OutboundChannelDetails, OutboundSuspendedSince, OutboundXcmpStatus};
+		use cumulus_primitives_core::ParaId;
+
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(5).put::<Pallet<Test>>();
+			frame_system::Pallet::<Test>::set_block_number(7);
+
+			// Suspended before `OutboundSuspendedSince` existed.
+			let legacy = ParaId::from(1000);
+			// Suspended after the pallet was upgraded, but before the migration ran.
+			let recent = ParaId::from(1001);
+			let ok = ParaId::from(1002);
+			OutboundXcmpStatus::<Test>::set(
+				vec![
+					OutboundChannelDetails::new(legacy).with_suspended_state(),
+					OutboundChannelDetails::new(recent).with_suspended_state(),
+					OutboundChannelDetails::new(ok),
+				]
+				.try_into()
+				.unwrap(),
+			);
+			OutboundSuspendedSince::<Test>::insert(recent, 3);
+
+			let bytes = v6::MigrateV5ToV6::<Test>::pre_upgrade();
+			assert!(bytes.is_ok());
+			v6::MigrateV5ToV6::<Test>::on_runtime_upgrade();
+			assert!(v6::MigrateV5ToV6::<Test>::post_upgrade(bytes.unwrap()).is_ok());
+
+			assert_eq!(OutboundSuspendedSince::<Test>::get(legacy), Some(7));
+			assert_eq!(OutboundSuspendedSince::<Test>::get(recent), Some(3));
+			assert_eq!(OutboundSuspendedSince::<Test>::get(ok), None);
+			assert_eq!(StorageVersion::get::<Pallet<Test>>(), 6);
+		});
+	}
 }
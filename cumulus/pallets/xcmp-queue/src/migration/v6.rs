@@ -0,0 +1,79 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrates the storage to version 6.
+
+use crate::*;
+use alloc::vec::Vec;
+use frame_support::{pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade};
+
+/// Backfills `OutboundSuspendedSince` for the outbound channels that were already suspended
+/// before it was introduced.
+///
+/// Their suspension is recorded as starting at the block of the migration, so that the
+/// `outbound_after` delay of the [`AutoResumePolicy`] applies to them like to any other channel.
+pub type MigrateV5ToV6<T> = frame_support::migrations::VersionedMigration<
+	5,
+	6,
+	unversioned::UncheckedMigrateV5ToV6<T>,
+	Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
+
+// Private module to hide the migration.
+mod unversioned {
+	/// Please use [`MigrateV5ToV6`] instead.
+	pub struct UncheckedMigrateV5ToV6<T: crate::Config>(core::marker::PhantomData<T>);
+}
+
+impl<T: Config> UncheckedOnRuntimeUpgrade for unversioned::UncheckedMigrateV5ToV6<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let now = frame_system::Pallet::<T>::current_block_number();
+		let suspended = OutboundXcmpStatus::<T>::get()
+			.into_iter()
+			.filter(|channel| channel.state == OutboundState::Suspended)
+			.map(|channel| channel.recipient)
+			.collect::<Vec<_>>();
+
+		let mut backfilled = 0u64;
+		for para in &suspended {
+			if !OutboundSuspendedSince::<T>::contains_key(para) {
+				OutboundSuspendedSince::<T>::insert(para, now);
+				backfilled += 1;
+			}
+		}
+
+		log::info!(
+			target: super::LOG,
+			"Backfilled the suspension block of {} outbound channels",
+			backfilled
+		);
+		T::DbWeight::get().reads_writes(1 + suspended.len() as u64, backfilled)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		for channel in OutboundXcmpStatus::<T>::get() {
+			ensure!(
+				channel.state != OutboundState::Suspended ||
+					OutboundSuspendedSince::<T>::contains_key(channel.recipient),
+				"Suspended outbound channel without a suspension block"
+			);
+		}
+
+		Ok(())
+	}
+}
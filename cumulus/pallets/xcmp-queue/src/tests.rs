@@ -920,3 +920,109 @@ fn page_not_modified_when_fragment_does_not_fit() {
 		}
 	});
 }
+
+#[test]
+fn set_auto_resume_policy_works() {
+	new_test_ext().execute_with(|| {
+		let policy = AutoResumePolicy { inbound: true, outbound_after: Some(10) };
+		assert_eq!(<AutoResume<Test>>::get(), AutoResumePolicy::default());
+		assert_noop!(XcmpQueue::set_auto_resume_policy(Origin::signed(2), policy), BadOrigin);
+
+		assert_ok!(XcmpQueue::set_auto_resume_policy(Origin::root(), policy));
+		assert_eq!(<AutoResume<Test>>::get(), policy);
+	});
+}
+
+#[test]
+fn outbound_channel_is_resumed_after_timeout() {
+	let sibling = ParaId::from(2000);
+	let suspend = (Signals, ChannelSignal::Suspend).encode();
+
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1);
+		XcmpQueue::handle_xcmp_messages(once((sibling, 1, suspend.as_slice())), Weight::MAX);
+
+		assert_eq!(<OutboundSuspendedSince<Test>>::get(sibling), Some(1));
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::<Test>::OutboundChannelSuspended { sibling }.into(),
+		);
+		let status = XcmpQueue::queue_status();
+		assert_eq!(status.channels.len(), 1);
+		assert!(status.channels[0].outbound_suspended);
+
+		// The default policy never resumes.
+		frame_system::Pallet::<Test>::set_block_number(100);
+		XcmpQueue::on_idle(100, Weight::MAX);
+		assert!(XcmpQueue::queue_status().channels[0].outbound_suspended);
+
+		assert_ok!(XcmpQueue::set_auto_resume_policy(
+			Origin::root(),
+			AutoResumePolicy { inbound: false, outbound_after: Some(200) },
+		));
+		XcmpQueue::on_idle(100, Weight::MAX);
+		assert!(XcmpQueue::queue_status().channels[0].outbound_suspended);
+
+		frame_system::Pallet::<Test>::set_block_number(201);
+		XcmpQueue::on_idle(201, Weight::MAX);
+		assert!(XcmpQueue::queue_status().channels.is_empty());
+		assert_eq!(<OutboundSuspendedSince<Test>>::get(sibling), None);
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::<Test>::OutboundChannelResumed { sibling, automatically: true }.into(),
+		);
+	});
+}
+
+#[test]
+fn resume_signal_clears_outbound_suspension() {
+	let sibling = ParaId::from(2000);
+	let suspend = (Signals, ChannelSignal::Suspend).encode();
+	let resume = (Signals, ChannelSignal::Resume).encode();
+
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1);
+		XcmpQueue::handle_xcmp_messages(once((sibling, 1, suspend.as_slice())), Weight::MAX);
+		XcmpQueue::handle_xcmp_messages(once((sibling, 1, resume.as_slice())), Weight::MAX);
+
+		assert_eq!(<OutboundSuspendedSince<Test>>::get(sibling), None);
+		assert!(XcmpQueue::queue_status().channels.is_empty());
+		frame_system::Pallet::<Test>::assert_last_event(
+			Event::<Test>::OutboundChannelResumed { sibling, automatically: false }.into(),
+		);
+	});
+}
+
+#[test]
+fn drained_inbound_channel_is_resumed() {
+	let sibling = ParaId::from(2000);
+
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1);
+		// Simulate a channel that stayed suspended although its queue is empty, e.g. because the
+		// resume signal could not be sent.
+		<InboundXcmpSuspended<Test>>::put(
+			BoundedBTreeSet::try_from(
+				[sibling].into_iter().collect::<std::collections::BTreeSet<_>>(),
+			)
+			.unwrap(),
+		);
+
+		let status = XcmpQueue::queue_status();
+		assert_eq!(status.channels.len(), 1);
+		assert!(status.channels[0].inbound_suspended);
+		assert_eq!(status.channels[0].inbound_ready_pages, 0);
+		assert_eq!(status.resume_threshold, 8);
+
+		XcmpQueue::on_idle(1, Weight::MAX);
+		assert!(!<InboundXcmpSuspended<Test>>::get().is_empty());
+
+		assert_ok!(XcmpQueue::set_auto_resume_policy(
+			Origin::root(),
+			AutoResumePolicy { inbound: true, outbound_after: None },
+		));
+		XcmpQueue::on_idle(1, Weight::MAX);
+		assert!(<InboundXcmpSuspended<Test>>::get().is_empty());
+		frame_system::Pallet::<Test>::assert_has_event(
+			Event::<Test>::InboundChannelResumed { sibling }.into(),
+		);
+	});
+}
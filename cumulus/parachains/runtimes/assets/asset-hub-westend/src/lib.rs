@@ -440,6 +440,7 @@ impl pallet_asset_conversion::Config for Runtime {
 	type LPFee = ConstU32<3>;
 	type PalletId = AssetConversionPalletId;
 	type MaxSwapPathLength = ConstU32<3>;
+	type MaxPriceObservations = ConstU32<24>;
 	type MintMinLiquidity = ConstU128<100>;
 	type WeightInfo = weights::pallet_asset_conversion::WeightInfo<Runtime>;
 	#[cfg(feature = "runtime-benchmarks")]
@@ -1253,6 +1254,7 @@ pub type Migrations = (
 	// unreleased
 	cumulus_pallet_xcmp_queue::migration::v4::MigrationToV4<Runtime>,
 	cumulus_pallet_xcmp_queue::migration::v5::MigrateV4ToV5<Runtime>,
+	cumulus_pallet_xcmp_queue::migration::v6::MigrateV5ToV6<Runtime>,
 	// unreleased
 	pallet_assets::migration::next_asset_id::SetNextAssetId<
 		ConstU32<50_000_000>,
@@ -1634,6 +1636,7 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(2)]
 	impl pallet_asset_conversion::AssetConversionApi<
 		Block,
 		Balance,
@@ -1651,6 +1654,18 @@ impl_runtime_apis! {
 		fn get_reserves(asset1: xcm::v5::Location, asset2: xcm::v5::Location) -> Option<(Balance, Balance)> {
 			AssetConversion::get_reserves(asset1, asset2).ok()
 		}
+
+		fn quote_exact_tokens_for_tokens_with_slippage(path: Vec<xcm::v5::Location>, amount_in: Balance, slippage: sp_runtime::Permill) -> Option<(Balance, Balance)> {
+			AssetConversion::quote_exact_tokens_for_tokens_with_slippage(path, amount_in, slippage)
+		}
+
+		fn quote_tokens_for_exact_tokens_with_slippage(path: Vec<xcm::v5::Location>, amount_out: Balance, slippage: sp_runtime::Permill) -> Option<(Balance, Balance)> {
+			AssetConversion::quote_tokens_for_exact_tokens_with_slippage(path, amount_out, slippage)
+		}
+
+		fn quote_time_weighted_price(path: Vec<xcm::v5::Location>, amount: Balance, window: u32) -> Option<Balance> {
+			AssetConversion::quote_time_weighted_price(path, amount, window.into())
+		}
 	}
 
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
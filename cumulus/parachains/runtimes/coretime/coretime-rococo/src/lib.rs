@@ -124,6 +124,7 @@ pub type Migrations = (
 	pallet_collator_selection::migration::v2::MigrationToV2<Runtime>,
 	cumulus_pallet_xcmp_queue::migration::v4::MigrationToV4<Runtime>,
 	cumulus_pallet_xcmp_queue::migration::v5::MigrateV4ToV5<Runtime>,
+	cumulus_pallet_xcmp_queue::migration::v6::MigrateV5ToV6<Runtime>,
 	pallet_broker::migration::MigrateV0ToV1<Runtime>,
 	pallet_broker::migration::MigrateV1ToV2<Runtime>,
 	pallet_broker::migration::MigrateV2ToV3<Runtime>,
@@ -794,10 +795,24 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(2)]
 	impl pallet_broker::runtime_api::BrokerApi<Block, Balance> for Runtime {
 		fn sale_price() -> Result<Balance, DispatchError> {
 			Broker::current_price()
 		}
+
+		fn region_utilization() -> Result<Vec<pallet_broker::RegionUtilization>, DispatchError> {
+			Broker::region_utilization()
+		}
+
+		fn upcoming_renewals(
+		) -> Result<Vec<pallet_broker::UpcomingRenewal<Balance>>, DispatchError> {
+			Broker::upcoming_renewals()
+		}
+
+		fn price_history() -> Vec<pallet_broker::PriceHistoryRecord<Balance>> {
+			Broker::price_history()
+		}
 	}
 
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
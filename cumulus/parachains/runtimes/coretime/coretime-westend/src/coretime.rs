@@ -334,4 +334,5 @@ impl pallet_broker::Config for Runtime {
 	type MaxAutoRenewals = ConstU32<20>;
 	type PriceAdapter = pallet_broker::CenterTargetPrice<Balance>;
 	type MinimumCreditPurchase = MinimumCreditPurchase;
+	type MaxPriceHistory = ConstU32<32>;
 }
@@ -25,6 +25,8 @@ use crate::{
 };
 use chain_spec_builder::ChainSpecBuilder;
 use clap::{Command, CommandFactory, FromArgMatches};
+use cumulus_client_availability_sampling::SamplingConfig;
+use cumulus_client_service::coretime_renewals::RenewalReminderConfig;
 use sc_chain_spec::ChainSpec;
 use sc_cli::{
 	CliConfiguration, DefaultConfigurationValues, ImportParams, KeystoreParams, NetworkParams,
@@ -124,6 +126,9 @@ pub enum Subcommand {
 	/// Export the genesis wasm of the parachain.
 	ExportGenesisWasm(cumulus_client_cli::ExportGenesisWasmCommand),
 
+	/// Analyze recent blocks and recommend an asynchronous backing configuration.
+	AdviseAsyncBacking(cumulus_client_cli::AsyncBackingAdvisorCmd),
+
 	/// Sub-commands concerned with benchmarking.
 	/// The pallet benchmarking moved to the `pallet` sub-command.
 	#[command(subcommand)]
@@ -185,6 +190,36 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long)]
 	pub export_pov_to_path: Option<PathBuf>,
 
+	/// Log reminders about upcoming Bulk Coretime renewals of the given tasks.
+	///
+	/// Only has an effect when running a coretime chain. The renewals of all tasks are watched if
+	/// no task is given.
+	#[arg(long, value_name = "TASK_ID", num_args = 0.., value_delimiter = ',')]
+	pub coretime_renewal_reminders: Option<Vec<u32>>,
+
+	/// Number of relay chain blocks before the deadline of an open renewal at which a reminder
+	/// is logged.
+	#[arg(long, default_value_t = 14_400, requires = "coretime_renewal_reminders")]
+	pub coretime_renewal_remind_before: u32,
+
+	/// Sample the availability of the candidates of the parachain by requesting the erasure
+	/// chunks of the given number of randomly chosen validators.
+	///
+	/// The availability confidence is reported to Prometheus, and a warning is logged for
+	/// candidates with fewer chunks than needed for recovery.
+	#[arg(long, value_name = "VALIDATORS")]
+	pub availability_sampling: Option<usize>,
+
+	/// Run the parachain of the given chain spec in this process as well.
+	///
+	/// Can be given multiple times, for chains of the same relay chain. All chains follow the
+	/// relay chain through one relay chain node, share the Prometheus endpoint and use the same
+	/// options. The data of every chain is stored in its own directory of the base path. The p2p
+	/// and RPC ports of the n-th additional chain are the configured ports increased by n.
+	/// Not available to collators.
+	#[arg(long, value_name = "CHAIN_SPEC")]
+	pub additional_chain: Vec<String>,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
@@ -198,6 +233,12 @@ impl<Config: CliConfig> Cli<Config> {
 		NodeExtraArgs {
 			use_slot_based_consensus: self.experimental_use_slot_based,
 			export_pov: self.export_pov_to_path.clone(),
+			coretime_renewal_reminders: self.coretime_renewal_reminders.clone().map(|tasks| {
+				RenewalReminderConfig { tasks, remind_before: self.coretime_renewal_remind_before }
+			}),
+			availability_sampling: self
+				.availability_sampling
+				.map(|samples| SamplingConfig { samples, ..Default::default() }),
 		}
 	}
 }
@@ -22,6 +22,7 @@ use crate::{
 			AuraConsensusId, Consensus, Runtime, RuntimeResolver as RuntimeResolverT,
 			RuntimeResolver,
 		},
+		spec::RelayChain,
 		types::Block,
 		NodeBlock, NodeExtraArgs,
 	},
@@ -35,6 +36,8 @@ use cumulus_primitives_core::ParaId;
 use frame_benchmarking_cli::{BenchmarkCmd, SUBSTRATE_REFERENCE_HARDWARE};
 use log::info;
 use sc_cli::{CliConfiguration, Result, SubstrateCli};
+use sc_network::multiaddr::Protocol;
+use sc_service::Configuration;
 use sp_runtime::traits::AccountIdConversion;
 #[cfg(feature = "runtime-benchmarks")]
 use sp_runtime::traits::HashingFor;
@@ -98,6 +101,35 @@ fn new_node_spec(
 	})
 }
 
+/// Create the configuration of the additional chain `chain`, the `offset`-th one.
+fn additional_chain_config<CliConfig: crate::cli::CliConfig>(
+	cli: &Cli<CliConfig>,
+	chain: &str,
+	offset: u16,
+	tokio_handle: tokio::runtime::Handle,
+) -> Result<Configuration> {
+	let mut run = cli.run.normalize();
+	run.base.shared_params.chain = Some(chain.to_string());
+	let mut config = run.create_configuration(cli, tokio_handle)?;
+
+	// Random ports are kept, they can't clash.
+	let offset_port = |port: u16| if port == 0 { port } else { port.saturating_add(offset) };
+	for address in &mut config.network.listen_addresses {
+		*address = address
+			.iter()
+			.map(|protocol| match protocol {
+				Protocol::Tcp(port) => Protocol::Tcp(offset_port(port)),
+				Protocol::Udp(port) => Protocol::Udp(offset_port(port)),
+				protocol => protocol,
+			})
+			.collect();
+	}
+	for endpoint in config.rpc.addr.iter_mut().flatten() {
+		endpoint.listen_addr.set_port(offset_port(endpoint.listen_addr.port()));
+	}
+	Ok(config)
+}
+
 /// Parse command line arguments into service configuration.
 pub fn run<CliConfig: crate::cli::CliConfig>(cmd_config: RunConfig) -> Result<()> {
 	let mut cli = Cli::<CliConfig>::from_args();
@@ -176,6 +208,14 @@ pub fn run<CliConfig: crate::cli::CliConfig>(cmd_config: RunConfig) -> Result<()
 				node.run_export_genesis_head_cmd(config, cmd)
 			})
 		},
+		Some(Subcommand::AdviseAsyncBacking(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let node =
+					new_node_spec(&config, &cmd_config.runtime_resolver, &cli.node_extra_args())?;
+				node.run_async_backing_advisor_cmd(config, cmd)
+			})
+		},
 		Some(Subcommand::ExportGenesisWasm(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|_config| {
@@ -236,10 +276,21 @@ pub fn run<CliConfig: crate::cli::CliConfig>(cmd_config: RunConfig) -> Result<()
 						.ok_or("Could not find parachain extension in chain-spec.")?,
 				);
 
+				if (cli.run.base.is_dev()? || cli.dev_block_time.is_some()) &&
+					!cli.additional_chain.is_empty()
+				{
+					return Err("Additional chains are not supported by dev nodes".into());
+				}
+
 				if cli.run.base.is_dev()? {
-					// Set default dev block time to 3000ms if not set.
-					// TODO: take block time from AURA config if set.
-					let dev_block_time = cli.dev_block_time.unwrap_or(DEFAULT_DEV_BLOCK_TIME_MS);
+					// Use the block time of the runtime if not set, falling back to 3000ms.
+					let dev_block_time = match cli.dev_block_time {
+						Some(dev_block_time) => dev_block_time,
+						None => cmd_config
+							.runtime_resolver
+							.block_time(&*config.chain_spec)?
+							.unwrap_or(DEFAULT_DEV_BLOCK_TIME_MS),
+					};
 					return node_spec
 						.start_manual_seal_node(config, para_id, dev_block_time)
 						.map_err(Into::into);
@@ -308,25 +359,69 @@ pub fn run<CliConfig: crate::cli::CliConfig>(cmd_config: RunConfig) -> Result<()
 					);
 
 				let tokio_handle = config.tokio_handle.clone();
-				let polkadot_config =
-					SubstrateCli::create_configuration(&polkadot_cli, &polkadot_cli, tokio_handle)
-						.map_err(|err| format!("Relay chain argument error: {}", err))?;
+				let polkadot_config = SubstrateCli::create_configuration(
+					&polkadot_cli,
+					&polkadot_cli,
+					tokio_handle.clone(),
+				)
+				.map_err(|err| format!("Relay chain argument error: {}", err))?;
+				let relay_chain =
+					Extensions::try_get(&*config.chain_spec).map(|e| e.relay_chain.clone());
+				// The chains share the overseer of the relay chain node, which only generates the
+				// collations of one parachain.
+				if !cli.additional_chain.is_empty() && config.role.is_authority() {
+					return Err("Additional chains are not supported by collators".into());
+				}
 
 				info!("🪪 Parachain id: {:?}", para_id);
 				info!("🧾 Parachain Account: {}", parachain_account);
 				info!("✍️ Is collating: {}", if config.role.is_authority() { "yes" } else { "no" });
 
-				node_spec
+				let (mut task_manager, shared_relay_chain) = node_spec
 					.start_node(
 						config,
-						polkadot_config,
-						collator_options,
+						RelayChain::New { polkadot_config, collator_options },
 						para_id,
 						hwbench,
 						cli.node_extra_args(),
 					)
-					.await
-					.map_err(Into::into)
+					.await?;
+
+				for (chain, offset) in cli.additional_chain.iter().zip(1..) {
+					let config =
+						additional_chain_config(&cli, chain, offset, tokio_handle.clone())?;
+					let extension = Extensions::try_get(&*config.chain_spec)
+						.ok_or("Could not find parachain extension in chain-spec.")?;
+					if Some(&extension.relay_chain) != relay_chain.as_ref() {
+						return Err(format!(
+							"Chain {} is a parachain of {}, not of {}.",
+							config.chain_spec.id(),
+							extension.relay_chain,
+							relay_chain.as_deref().unwrap_or_default(),
+						)
+						.into());
+					}
+					let para_id = ParaId::from(extension.para_id);
+					let node_spec = new_node_spec(
+						&config,
+						&cmd_config.runtime_resolver,
+						&cli.node_extra_args(),
+					)?;
+
+					info!("🪪 Additional parachain id: {:?}", para_id);
+					let (child, _) = node_spec
+						.start_node(
+							config,
+							RelayChain::Shared(shared_relay_chain.clone()),
+							para_id,
+							None,
+							cli.node_extra_args(),
+						)
+						.await?;
+					task_manager.add_child(child);
+				}
+
+				Ok(task_manager)
 			})
 		},
 	}
@@ -15,7 +15,7 @@
 // limitations under the License.
 
 use crate::common::spec::BaseNodeSpec;
-use cumulus_client_cli::ExportGenesisHeadCommand;
+use cumulus_client_cli::{AsyncBackingAdvisorCmd, ExportGenesisHeadCommand};
 use frame_benchmarking_cli::BlockCmd;
 #[cfg(any(feature = "runtime-benchmarks"))]
 use frame_benchmarking_cli::StorageCmd;
@@ -65,6 +65,12 @@ pub trait NodeCommandRunner {
 		cmd: &ExportGenesisHeadCommand,
 	) -> SyncCmdResult;
 
+	fn run_async_backing_advisor_cmd(
+		self: Box<Self>,
+		config: Configuration,
+		cmd: &AsyncBackingAdvisorCmd,
+	) -> SyncCmdResult;
+
 	fn run_benchmark_block_cmd(
 		self: Box<Self>,
 		config: Configuration,
@@ -137,6 +143,15 @@ where
 		cmd.run(partial.client)
 	}
 
+	fn run_async_backing_advisor_cmd(
+		self: Box<Self>,
+		config: Configuration,
+		cmd: &AsyncBackingAdvisorCmd,
+	) -> SyncCmdResult {
+		let partial = T::new_partial(&config).map_err(sc_cli::Error::Service)?;
+		cmd.run(partial.client)
+	}
+
 	fn run_benchmark_block_cmd(
 		self: Box<Self>,
 		config: Configuration,
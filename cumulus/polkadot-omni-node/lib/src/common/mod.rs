@@ -26,7 +26,11 @@ pub mod runtime;
 pub mod spec;
 pub mod types;
 
-use cumulus_primitives_core::{CollectCollationInfo, GetCoreSelectorApi};
+use cumulus_client_availability_sampling::SamplingConfig;
+use cumulus_client_service::coretime_renewals::RenewalReminderConfig;
+use cumulus_primitives_core::{CollectCollationInfo, GetCoreSelectorApi, XcmpQueueStatusApi};
+use pallet_broker::runtime_api::BrokerApi;
+use parachains_common::Balance;
 use sc_client_db::DbHash;
 use sc_offchain::OffchainWorkerApi;
 use serde::de::DeserializeOwned;
@@ -69,6 +73,8 @@ pub trait NodeRuntimeApi<Block: BlockT>:
 	+ OffchainWorkerApi<Block>
 	+ CollectCollationInfo<Block>
 	+ GetCoreSelectorApi<Block>
+	+ XcmpQueueStatusApi<Block>
+	+ BrokerApi<Block, Balance>
 	+ Sized
 {
 }
@@ -82,6 +88,8 @@ impl<T, Block: BlockT> NodeRuntimeApi<Block> for T where
 		+ OffchainWorkerApi<Block>
 		+ GetCoreSelectorApi<Block>
 		+ CollectCollationInfo<Block>
+		+ XcmpQueueStatusApi<Block>
+		+ BrokerApi<Block, Balance>
 {
 }
 
@@ -108,4 +116,10 @@ pub struct NodeExtraArgs {
 
 	/// If set, each `PoV` build by the node will be exported to this folder.
 	pub export_pov: Option<PathBuf>,
+
+	/// If set, reminders about upcoming Bulk Coretime renewals are logged.
+	pub coretime_renewal_reminders: Option<RenewalReminderConfig>,
+
+	/// If set, the availability of the candidates of the parachain is sampled.
+	pub availability_sampling: Option<SamplingConfig>,
 }
@@ -29,6 +29,10 @@ use subxt_metadata::{Metadata, StorageEntryType};
 pub const DEFAULT_PARACHAIN_SYSTEM_PALLET_NAME: &str = "ParachainSystem";
 /// Expected frame system pallet runtime type name.
 pub const DEFAULT_FRAME_SYSTEM_PALLET_NAME: &str = "System";
+/// Expected aura pallet runtime type name.
+pub const DEFAULT_AURA_PALLET_NAME: &str = "Aura";
+/// Expected babe pallet runtime type name.
+pub const DEFAULT_BABE_PALLET_NAME: &str = "Babe";
 
 /// The Aura ID used by the Aura consensus
 #[derive(PartialEq)]
@@ -95,10 +99,21 @@ pub enum Runtime {
 pub trait RuntimeResolver {
 	/// Extract the Runtime variant from the chain spec ID.
 	fn runtime(&self, chain_spec: &dyn ChainSpec) -> sc_cli::Result<Runtime>;
+
+	/// The block time of the runtime in milliseconds, if known.
+	///
+	/// Used by dev nodes if no block time is given.
+	fn block_time(&self, _chain_spec: &dyn ChainSpec) -> sc_cli::Result<Option<u64>> {
+		Ok(None)
+	}
 }
 
-/// Default implementation for `RuntimeResolver` that just returns
-/// `Runtime::Omni(BlockNumber::U32, Consensus::Aura(AuraConsensusId::Sr25519))`.
+/// Default implementation for `RuntimeResolver` that inspects the metadata of the runtime.
+///
+/// The consensus is derived from the consensus pallets of the runtime, and the block time from
+/// the slot duration of the aura pallet. If the metadata isn't available, or the runtime has no
+/// consensus pallet, `Runtime::Omni(BlockNumber::U32, Consensus::Aura(AuraConsensusId::Sr25519))`
+/// is assumed.
 pub struct DefaultRuntimeResolver;
 
 impl RuntimeResolver for DefaultRuntimeResolver {
@@ -129,7 +144,26 @@ impl RuntimeResolver for DefaultRuntimeResolver {
 			);
 		}
 
-		Ok(Runtime::Omni(block_number, Consensus::Aura(AuraConsensusId::Sr25519)))
+		let consensus = match metadata_inspector.consensus()? {
+			Some(consensus) => consensus,
+			None => {
+				log::warn!(
+					r#"⚠️  There isn't a consensus pallet named `Aura` in the runtime. Please check Omni Node
+                docs for runtime conventions:
+                https://paritytech.github.io/polkadot-sdk/master/polkadot_sdk_docs/reference_docs/omni_node/index.html#runtime-conventions.
+                Note: We'll assume Aura consensus with `sr25519` authority keys."#
+				);
+				Consensus::Aura(AuraConsensusId::Sr25519)
+			},
+		};
+
+		Ok(Runtime::Omni(block_number, consensus))
+	}
+
+	fn block_time(&self, chain_spec: &dyn ChainSpec) -> sc_cli::Result<Option<u64>> {
+		Ok(MetadataInspector::new(chain_spec)
+			.ok()
+			.and_then(|inspector| inspector.slot_duration()))
 	}
 }
 
@@ -144,6 +178,77 @@ impl MetadataInspector {
 		self.0.pallet_by_name(name).is_some()
 	}
 
+	/// The consensus of the runtime, derived from its consensus pallets.
+	///
+	/// Returns `None` if the runtime has no consensus pallet, and an error if the consensus can't
+	/// be run by the omni-node or can't be determined unambiguously.
+	fn consensus(&self) -> Result<Option<Consensus>, sc_cli::Error> {
+		let has_babe = self.pallet_exists(DEFAULT_BABE_PALLET_NAME);
+		match (self.pallet_exists(DEFAULT_AURA_PALLET_NAME), has_babe) {
+			(true, true) => Err(format!(
+				"The runtime contains both the `{DEFAULT_AURA_PALLET_NAME}` and the \
+				`{DEFAULT_BABE_PALLET_NAME}` pallet, so its consensus is ambiguous. Parachains \
+				run by the omni-node must use Aura consensus only."
+			)
+			.into()),
+			(false, true) => Err(format!(
+				"The runtime uses BABE consensus through the `{DEFAULT_BABE_PALLET_NAME}` pallet, \
+				which isn't supported by the omni-node. Parachains run by the omni-node must use \
+				Aura consensus."
+			)
+			.into()),
+			(false, false) => Ok(None),
+			(true, false) => match self.aura_consensus_id() {
+				Some(aura_id) => Ok(Some(Consensus::Aura(aura_id))),
+				None => Err(format!(
+					"Unable to determine the key type of the `{DEFAULT_AURA_PALLET_NAME}` \
+					authorities. The `Authorities` storage of the pallet must contain `sr25519` or \
+					`ed25519` keys, e.g. `sp_consensus_aura::sr25519::AuthorityId`."
+				)
+				.into()),
+			},
+		}
+	}
+
+	/// The key type of the aura authorities, derived from the type path of the authority ids.
+	fn aura_consensus_id(&self) -> Option<AuraConsensusId> {
+		let ty_id = match self
+			.0
+			.pallet_by_name(DEFAULT_AURA_PALLET_NAME)?
+			.storage()?
+			.entry_by_name("Authorities")?
+			.entry_type()
+		{
+			StorageEntryType::Plain(ty_id) => *ty_id,
+			_ => return None,
+		};
+		let types = self.0.types();
+
+		// The authorities are stored in a `BoundedVec`, a newtype around a sequence.
+		let TypeDef::Composite(bounded_vec) = &types.resolve(ty_id)?.type_def else { return None };
+		let [field] = &bounded_vec.fields[..] else { return None };
+		let TypeDef::Sequence(vec) = &types.resolve(field.ty.id)?.type_def else { return None };
+		let authority_id = types.resolve(vec.type_param.id)?;
+
+		let segments = &authority_id.path.segments;
+		let is_sr25519 = segments.iter().any(|segment| segment.contains("sr25519"));
+		let is_ed25519 = segments.iter().any(|segment| segment.contains("ed25519"));
+		match (is_sr25519, is_ed25519) {
+			(true, false) => Some(AuraConsensusId::Sr25519),
+			(false, true) => Some(AuraConsensusId::Ed25519),
+			_ => None,
+		}
+	}
+
+	/// The slot duration of the aura pallet in milliseconds.
+	fn slot_duration(&self) -> Option<u64> {
+		let constant = self
+			.0
+			.pallet_by_name(DEFAULT_AURA_PALLET_NAME)?
+			.constant_by_name("SlotDuration")?;
+		u64::decode(&mut constant.value()).ok()
+	}
+
 	fn block_number(&self) -> Option<BlockNumber> {
 		let pallet_metadata = self.0.pallet_by_name(DEFAULT_FRAME_SYSTEM_PALLET_NAME);
 		pallet_metadata
@@ -178,8 +283,8 @@ impl MetadataInspector {
 #[cfg(test)]
 mod tests {
 	use crate::runtime::{
-		BlockNumber, MetadataInspector, DEFAULT_FRAME_SYSTEM_PALLET_NAME,
-		DEFAULT_PARACHAIN_SYSTEM_PALLET_NAME,
+		AuraConsensusId, BlockNumber, Consensus, MetadataInspector,
+		DEFAULT_FRAME_SYSTEM_PALLET_NAME, DEFAULT_PARACHAIN_SYSTEM_PALLET_NAME,
 	};
 	use codec::Decode;
 	use cumulus_client_service::ParachainHostFunctions;
@@ -210,4 +315,13 @@ mod tests {
 		let metadata_inspector = MetadataInspector(cumulus_test_runtime_metadata());
 		assert_eq!(metadata_inspector.block_number().unwrap(), BlockNumber::U32);
 	}
+
+	#[test]
+	fn test_runtime_consensus() {
+		let metadata_inspector = MetadataInspector(cumulus_test_runtime_metadata());
+		assert!(
+			metadata_inspector.consensus().unwrap() ==
+				Some(Consensus::Aura(AuraConsensusId::Sr25519))
+		);
+	}
 }
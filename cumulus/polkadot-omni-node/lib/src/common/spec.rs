@@ -23,10 +23,14 @@ use crate::common::{
 	},
 	ConstructNodeRuntimeApi, NodeBlock, NodeExtraArgs,
 };
+use cumulus_client_availability_sampling::AvailabilitySampling;
 use cumulus_client_cli::CollatorOptions;
 use cumulus_client_service::{
-	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
-	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
+	build_network, build_relay_chain_interface,
+	candidate_inclusion::run_candidate_inclusion_tracker, coretime_renewals::run_renewal_reminders,
+	prepare_node_config, relay_chain_informant::RelayChainInformant, start_relay_chain_tasks,
+	xcmp_queue_metrics::run_xcmp_queue_monitor, BuildNetworkParams, CollatorSybilResistance,
+	DARecoveryProfile, StartRelayChainTasksParams,
 };
 use cumulus_primitives_core::{BlockT, ParaId};
 use cumulus_relay_chain_interface::{OverseerHandle, RelayChainInterface};
@@ -86,6 +90,24 @@ where
 	) -> Result<(), sc_service::Error>;
 }
 
+/// The relay chain followed by a parachain node.
+pub(crate) enum RelayChain {
+	/// Start a relay chain node, or connect to the relay chain RPC nodes, as configured.
+	New { polkadot_config: Configuration, collator_options: CollatorOptions },
+	/// Follow the relay chain of another parachain node running in this process.
+	Shared(SharedRelayChain),
+}
+
+/// The relay chain interface of a parachain node, which can be shared with other parachain nodes
+/// running in the same process.
+#[derive(Clone)]
+pub(crate) struct SharedRelayChain {
+	/// The interface to the relay chain.
+	pub interface: Arc<dyn RelayChainInterface>,
+	/// The collator key, if the node is a collator.
+	pub collator_key: Option<CollatorPair>,
+}
+
 /// Checks that the hardware meets the requirements and print a warning otherwise.
 fn warn_if_slow_hardware(hwbench: &sc_sysinfo::HwBench) {
 	// Polkadot para-chains should generally use these requirements to ensure that the relay-chain
@@ -252,17 +274,23 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 	/// Start a node with the given parachain spec.
 	///
 	/// This is the actual implementation that is abstract over the executor and the runtime api.
+	/// Returns the relay chain the node follows, for other nodes to share it.
 	fn start_node<Net>(
 		parachain_config: Configuration,
-		polkadot_config: Configuration,
-		collator_options: CollatorOptions,
+		relay_chain: RelayChain,
 		para_id: ParaId,
 		hwbench: Option<sc_sysinfo::HwBench>,
 		node_extra_args: NodeExtraArgs,
-	) -> Pin<Box<dyn Future<Output = sc_service::error::Result<TaskManager>>>>
+	) -> Pin<Box<dyn Future<Output = sc_service::error::Result<(TaskManager, SharedRelayChain)>>>>
 	where
 		Net: NetworkBackend<Self::Block, Hash>,
 	{
+		// The logs of a node sharing the relay chain are prefixed with its chain id, to tell them
+		// apart from the logs of the other parachain nodes.
+		let log_prefix = match relay_chain {
+			RelayChain::New { .. } => "Parachain".to_string(),
+			RelayChain::Shared(_) => parachain_config.chain_spec.id().to_string(),
+		};
 		let fut = async move {
 			let parachain_config = prepare_node_config(parachain_config);
 
@@ -272,16 +300,25 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 			let client = params.client.clone();
 			let backend = params.backend.clone();
 			let mut task_manager = params.task_manager;
-			let (relay_chain_interface, collator_key) = build_relay_chain_interface(
-				polkadot_config,
-				&parachain_config,
-				telemetry_worker_handle,
-				&mut task_manager,
-				collator_options.clone(),
-				hwbench.clone(),
-			)
-			.await
-			.map_err(|e| sc_service::Error::Application(Box::new(e) as Box<_>))?;
+			let (relay_chain_interface, collator_key) = match relay_chain {
+				RelayChain::New { polkadot_config, collator_options } =>
+					build_relay_chain_interface(
+						polkadot_config,
+						&parachain_config,
+						telemetry_worker_handle,
+						&mut task_manager,
+						collator_options,
+						hwbench.clone(),
+					)
+					.await
+					.map_err(|e| sc_service::Error::Application(Box::new(e) as Box<_>))?,
+				RelayChain::Shared(SharedRelayChain { interface, collator_key }) =>
+					(interface, collator_key),
+			};
+			let shared_relay_chain = SharedRelayChain {
+				interface: relay_chain_interface.clone(),
+				collator_key: collator_key.clone(),
+			};
 
 			let validator = parachain_config.role.is_authority();
 			let prometheus_registry = parachain_config.prometheus_registry().cloned();
@@ -354,6 +391,13 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				system_rpc_tx,
 				tx_handler_controller,
 				telemetry: telemetry.as_mut(),
+				informant_data_providers: Vec::new(),
+				informant_relay_chain: Some(Arc::new(RelayChainInformant::new(
+					para_id,
+					client.clone(),
+					relay_chain_interface.clone(),
+				))),
+				informant_session_provider: None,
 			})?;
 
 			if let Some(hwbench) = hwbench {
@@ -400,7 +444,47 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				sync_service,
 			})?;
 
+			task_manager.spawn_handle().spawn(
+				"cumulus-xcmp-queue-monitor",
+				None,
+				run_xcmp_queue_monitor(client.clone(), prometheus_registry.clone()),
+			);
+
+			if let Some(config) = node_extra_args.coretime_renewal_reminders.clone() {
+				task_manager.spawn_handle().spawn(
+					"coretime-renewal-reminders",
+					None,
+					run_renewal_reminders(client.clone(), config, prometheus_registry.clone()),
+				);
+			}
+
+			if let Some(config) = node_extra_args.availability_sampling.clone() {
+				let sampling = AvailabilitySampling::new(
+					relay_chain_interface.clone(),
+					para_id,
+					config,
+					prometheus_registry.as_ref(),
+				)
+				.map_err(sc_service::Error::Application)?;
+				task_manager.spawn_handle().spawn(
+					"cumulus-availability-sampling",
+					None,
+					sampling.run(),
+				);
+			}
+
 			if validator {
+				task_manager.spawn_handle().spawn(
+					"cumulus-candidate-inclusion-tracker",
+					None,
+					run_candidate_inclusion_tracker(
+						para_id,
+						client.clone(),
+						relay_chain_interface.clone(),
+						prometheus_registry.clone(),
+					),
+				);
+
 				Self::StartConsensus::start_consensus(
 					client.clone(),
 					block_import,
@@ -421,14 +505,14 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				)?;
 			}
 
-			Ok(task_manager)
+			Ok((task_manager, shared_relay_chain))
 		};
 
 		Box::pin(Instrument::instrument(
 			fut,
 			sc_tracing::tracing::info_span!(
 				sc_tracing::logging::PREFIX_LOG_SPAN,
-				name = "Parachain"
+				name = log_prefix.as_str()
 			),
 		))
 	}
@@ -438,12 +522,11 @@ pub(crate) trait DynNodeSpec: NodeCommandRunner {
 	fn start_node(
 		self: Box<Self>,
 		parachain_config: Configuration,
-		polkadot_config: Configuration,
-		collator_options: CollatorOptions,
+		relay_chain: RelayChain,
 		para_id: ParaId,
 		hwbench: Option<HwBench>,
 		node_extra_args: NodeExtraArgs,
-	) -> Pin<Box<dyn Future<Output = sc_service::error::Result<TaskManager>>>>;
+	) -> Pin<Box<dyn Future<Output = sc_service::error::Result<(TaskManager, SharedRelayChain)>>>>;
 }
 
 impl<T> DynNodeSpec for T
@@ -453,18 +536,17 @@ where
 	fn start_node(
 		self: Box<Self>,
 		parachain_config: Configuration,
-		polkadot_config: Configuration,
-		collator_options: CollatorOptions,
+		relay_chain: RelayChain,
 		para_id: ParaId,
 		hwbench: Option<HwBench>,
 		node_extra_args: NodeExtraArgs,
-	) -> Pin<Box<dyn Future<Output = sc_service::error::Result<TaskManager>>>> {
+	) -> Pin<Box<dyn Future<Output = sc_service::error::Result<(TaskManager, SharedRelayChain)>>>>
+	{
 		match parachain_config.network.network_backend {
 			sc_network::config::NetworkBackendType::Libp2p =>
 				<Self as NodeSpec>::start_node::<sc_network::NetworkWorker<_, _>>(
 					parachain_config,
-					polkadot_config,
-					collator_options,
+					relay_chain,
 					para_id,
 					hwbench,
 					node_extra_args,
@@ -472,8 +554,7 @@ where
 			sc_network::config::NetworkBackendType::Litep2p =>
 				<Self as NodeSpec>::start_node::<sc_network::Litep2pNetworkBackend>(
 					parachain_config,
-					polkadot_config,
-					collator_options,
+					relay_chain,
 					para_id,
 					hwbench,
 					node_extra_args,
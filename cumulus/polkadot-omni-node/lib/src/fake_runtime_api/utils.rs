@@ -169,6 +169,35 @@ macro_rules! impl_node_runtime_apis {
 				}
 			}
 
+			impl cumulus_primitives_core::XcmpQueueStatusApi<$block> for $runtime {
+				fn xcmp_queue_status() -> cumulus_primitives_core::XcmpQueueStatus {
+					unimplemented!()
+				}
+			}
+
+			#[api_version(2)]
+			impl pallet_broker::runtime_api::BrokerApi<$block, Balance> for $runtime {
+				fn sale_price() -> Result<Balance, sp_runtime::DispatchError> {
+					unimplemented!()
+				}
+
+				fn region_utilization(
+				) -> Result<Vec<pallet_broker::RegionUtilization>, sp_runtime::DispatchError> {
+					unimplemented!()
+				}
+
+				fn upcoming_renewals() -> Result<
+					Vec<pallet_broker::UpcomingRenewal<Balance>>,
+					sp_runtime::DispatchError,
+				> {
+					unimplemented!()
+				}
+
+				fn price_history() -> Vec<pallet_broker::PriceHistoryRecord<Balance>> {
+					unimplemented!()
+				}
+			}
+
 			#[cfg(feature = "try-runtime")]
 			impl frame_try_runtime::TryRuntime<$block> for $runtime {
 				fn on_runtime_upgrade(
@@ -280,6 +280,9 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 			sync_service,
 			config,
 			telemetry: telemetry.as_mut(),
+			informant_data_providers: Vec::new(),
+			informant_relay_chain: None,
+			informant_session_provider: None,
 		})?;
 
 		Ok(task_manager)
@@ -18,7 +18,8 @@ use cumulus_primitives_core::ParaId;
 use polkadot_omni_node_lib::{
 	chain_spec::{GenericChainSpec, LoadSpec},
 	runtime::{
-		AuraConsensusId, BlockNumber, Consensus, Runtime, RuntimeResolver as RuntimeResolverT,
+		AuraConsensusId, BlockNumber, Consensus, DefaultRuntimeResolver, Runtime,
+		RuntimeResolver as RuntimeResolverT,
 	},
 };
 use sc_chain_spec::{ChainSpec, ChainType};
@@ -242,7 +243,7 @@ impl LegacyRuntime {
 		} else {
 			log::warn!(
 				"No specific runtime was recognized for ChainSpec's id: '{}', \
-				so Runtime::Omni(Consensus::Aura) will be used",
+				so the runtime will be inspected to determine its consensus
 				id
 			);
 			LegacyRuntime::Omni
@@ -265,11 +266,15 @@ impl RuntimeResolverT for RuntimeResolver {
 			LegacyRuntime::Coretime(_) |
 			LegacyRuntime::People(_) |
 			LegacyRuntime::Glutton |
-			LegacyRuntime::Penpal |
-			LegacyRuntime::Omni =>
+			LegacyRuntime::Penpal =>
 				Runtime::Omni(BlockNumber::U32, Consensus::Aura(AuraConsensusId::Sr25519)),
+			LegacyRuntime::Omni => return DefaultRuntimeResolver.runtime(chain_spec),
 		})
 	}
+
+	fn block_time(&self, chain_spec: &dyn ChainSpec) -> sc_cli::Result<Option<u64>> {
+		DefaultRuntimeResolver.block_time(chain_spec)
+	}
 }
 
 #[cfg(test)]
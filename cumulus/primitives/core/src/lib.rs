@@ -385,6 +385,36 @@ pub struct CollationInfo {
 	pub head_data: HeadData,
 }
 
+/// Status of an XCMP channel as seen by the XCMP queue.
+#[derive(Clone, Debug, codec::Decode, codec::Encode, PartialEq, Eq, TypeInfo)]
+pub struct XcmpChannelStatus {
+	/// The sibling parachain on the other side of the channel.
+	pub sibling: ParaId,
+	/// Whether the inbound channel was suspended by us because our queue is too full.
+	pub inbound_suspended: bool,
+	/// Number of ready pages in the inbound queue of the channel.
+	pub inbound_ready_pages: u32,
+	/// Whether the outbound channel was suspended by the sibling.
+	pub outbound_suspended: bool,
+	/// Number of pages waiting to be sent over the outbound channel.
+	pub outbound_pages: u32,
+}
+
+/// Status of the XCMP queue.
+#[derive(Clone, Debug, Default, codec::Decode, codec::Encode, PartialEq, Eq, TypeInfo)]
+pub struct XcmpQueueStatus {
+	/// Whether the execution of incoming XCMs is suspended.
+	pub execution_suspended: bool,
+	/// The number of inbound pages at which a channel gets suspended.
+	pub suspend_threshold: u32,
+	/// The number of inbound pages at which messages of a channel get dropped.
+	pub drop_threshold: u32,
+	/// The number of inbound pages at which a suspended channel gets resumed.
+	pub resume_threshold: u32,
+	/// All channels that are suspended or have messages queued in either direction.
+	pub channels: Vec<XcmpChannelStatus>,
+}
+
 sp_api::decl_runtime_apis! {
 	/// Runtime api to collect information about a collation.
 	#[api_version(2)]
@@ -404,4 +434,10 @@ sp_api::decl_runtime_apis! {
 		/// Retrieve core selector and claim queue offset for the next block.
 		fn core_selector() -> (CoreSelector, ClaimQueueOffset);
 	}
+
+	/// Runtime api to inspect the XCMP queue.
+	pub trait XcmpQueueStatusApi {
+		/// Returns the status of the XCMP queue and its channels.
+		fn xcmp_queue_status() -> XcmpQueueStatus;
+	}
 }
@@ -410,6 +410,9 @@ where
 		system_rpc_tx,
 		tx_handler_controller,
 		telemetry: None,
+		informant_data_providers: Vec::new(),
+		informant_relay_chain: None,
+		informant_session_provider: None,
 	})?;
 
 	let announce_block = {
@@ -859,6 +862,7 @@ pub fn node_config(
 		impl_version: "0.1".to_string(),
 		role,
 		tokio_handle,
+		dedicated_runtimes: Default::default(),
 		transaction_pool: Default::default(),
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
@@ -866,6 +870,7 @@ pub fn node_config(
 		trie_cache_maximum_size: Some(64 * 1024 * 1024),
 		state_pruning: Some(PruningMode::ArchiveAll),
 		blocks_pruning: BlocksPruning::KeepAll,
+		database_write_rate_limit: None,
 		chain_spec: spec,
 		executor: ExecutorConfiguration {
 			wasm_method: WasmExecutionMethod::Compiled {
@@ -889,6 +894,9 @@ pub fn node_config(
 			rate_limit: None,
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			serve_finalized_only: false,
+			transaction_confirmations: 0,
+			chain_head_resume_grace_period: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -899,9 +907,11 @@ pub fn node_config(
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
 		announce_block: true,
+		informant: Default::default(),
 		data_path: root,
 		base_path,
 		wasm_runtime_overrides: None,
+		import_queue: Default::default(),
 	})
 }
 
@@ -190,6 +190,10 @@
 //! * runtimes must define a type for [`frame-system`] pallet, which is recommended to be named as
 //!   `System`. The configured [`block number`] here will be used by Omni Node to configure AURA
 //!   accordingly.
+//! * runtimes should define a type for [`pallet-aura`], which is recommended to be named as
+//!   `Aura`. The key type of its authorities selects the `sr25519` or `ed25519` flavour of AURA,
+//!   and its slot duration is used as the default [`--dev-block-time`]. Runtimes containing a
+//!   `Babe` pallet are rejected, as the Omni Node only supports AURA.
 //!
 //! [`templates`]: crate::polkadot_sdk::templates
 //! [`parachain-template`]: https://github.com/paritytech/polkadot-sdk-parachain-template
@@ -198,4 +202,5 @@
 //! [`chain-spec-builder`]: https://crates.io/crates/staging-chain-spec-builder
 //! [`cumulus-pallet-parachain-system`]: https://docs.rs/cumulus-pallet-parachain-system/latest/cumulus_pallet_parachain_system/
 //! [`frame-system`]: https://docs.rs/frame-system/latest/frame_system/
+//! [`pallet-aura`]: https://docs.rs/pallet-aura/latest/pallet_aura/
 //! [`block number`]: https://docs.rs/frame-system/latest/frame_system/pallet/storage_types/struct.Number.html
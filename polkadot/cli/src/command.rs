@@ -394,6 +394,12 @@ pub fn run() -> Result<()> {
 
 					cmd.run(client.clone()).map_err(Error::SubstrateCli)
 				}),
+				BenchmarkCmd::Calibrate(cmd) => runner.sync_run(|mut config| {
+					let (client, _, _, _) = polkadot_service::new_chain_ops(&mut config)?;
+
+					cmd.run::<polkadot_service::Block, _, u128>(client.clone())
+						.map_err(Error::SubstrateCli)
+				}),
 				BenchmarkCmd::Overhead(cmd) => runner.sync_run(|config| {
 					if cmd.params.runtime.is_some() {
 						return Err(sc_cli::Error::Input(
@@ -226,6 +226,13 @@ where
 		let force_authoring = config.force_authoring;
 		let disable_grandpa = config.disable_grandpa;
 		let name = config.network.node_name.clone();
+		let slashing_protection = if role.is_authority() {
+			let path =
+				config.base_path.config_dir(config.chain_spec.id()).join("slashing-protection");
+			Some(sc_consensus_slashing_protection::SlashingProtection::open(&path)?)
+		} else {
+			None
+		};
 		let backoff_authoring_blocks = if !force_authoring_backoff &&
 			(config.chain_spec.is_polkadot() || config.chain_spec.is_kusama())
 		{
@@ -502,6 +509,9 @@ where
 			system_rpc_tx,
 			tx_handler_controller,
 			telemetry: telemetry.as_mut(),
+			informant_data_providers: Vec::new(),
+			informant_relay_chain: None,
+			informant_session_provider: None,
 		})?;
 
 		if let Some(hwbench) = hwbench {
@@ -713,6 +723,9 @@ where
 				block_proposal_slot_portion: sc_consensus_babe::SlotProportion::new(2f32 / 3f32),
 				max_block_proposal_slot_portion: None,
 				telemetry: telemetry.as_ref().map(|x| x.handle()),
+				slashing_protection: slashing_protection.clone(),
+				clock_health: None,
+				slot_leakage_analyzer: None,
 			};
 
 			let babe = sc_consensus_babe::start_babe(babe_config)?;
@@ -749,6 +762,7 @@ where
 				links: beefy_links,
 				on_demand_justifications_handler: beefy_on_demand_justifications_handler,
 				is_authority: role.is_authority(),
+				slashing_protection: slashing_protection.clone(),
 			};
 
 			let gadget = sc_consensus_beefy::start_beefy_gadget::<
@@ -786,13 +800,16 @@ where
 			// Grandpa performance can be improved a bit by tuning this parameter, see:
 			// https://github.com/paritytech/polkadot/issues/5464
 			gossip_duration: Duration::from_millis(1000),
-			justification_generation_period: GRANDPA_JUSTIFICATION_PERIOD,
+			justification_policy: sc_consensus_grandpa::JustificationPolicy::Interval(
+				GRANDPA_JUSTIFICATION_PERIOD,
+			),
 			name: Some(name),
 			observer_enabled: false,
 			keystore: keystore_opt,
 			local_role: role,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			protocol_name: grandpa_protocol_name,
+			slashing_protection,
 		};
 
 		let enable_grandpa = !disable_grandpa;
@@ -210,6 +210,7 @@ where
 			registry: config.prometheus_registry(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
+			import_queue_config: config.import_queue,
 		})?;
 
 	let justification_stream = grandpa_link.justification_stream();
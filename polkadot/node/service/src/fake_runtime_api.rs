@@ -239,6 +239,20 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl runtime_api::HrmpChannelsApi<Block> for Runtime {
+		fn hrmp_channels(_: ParaId) -> polkadot_primitives::vstaging::hrmp::HrmpParaChannels {
+			unimplemented!()
+		}
+
+		fn hrmp_init_open_channel_call(_: ParaId, _: Option<u32>, _: Option<u32>) -> Vec<u8> {
+			unimplemented!()
+		}
+
+		fn hrmp_accept_open_channel_call(_: ParaId) -> Vec<u8> {
+			unimplemented!()
+		}
+	}
+
 	impl sp_consensus_beefy::BeefyApi<Block, BeefyId> for Runtime {
 		fn beefy_genesis() -> Option<BlockNumber> {
 			unimplemented!()
@@ -457,4 +471,32 @@ sp_api::impl_runtime_apis! {
 			unimplemented!()
 		}
 	}
+
+	impl xcm_runtime_apis::transfers::XcmTransferApi<Block, AccountId> for Runtime {
+		fn sovereign_account(_: VersionedLocation, _: Option<u16>) -> Result<AccountId, xcm_runtime_apis::transfers::Error> {
+			unimplemented!()
+		}
+
+		fn estimate_transfer_fees(_: VersionedLocation, _: VersionedXcm<()>, _: VersionedAssetId) -> Result<xcm_runtime_apis::transfers::TransferFees, xcm_runtime_apis::transfers::Error> {
+			unimplemented!()
+		}
+	}
+
+	impl pallet_preimage::runtime_api::PreimageApi<Block, AccountId, Hash, BlockNumber> for Runtime {
+		fn preimage_usage(
+			_: Hash,
+		) -> Option<pallet_preimage::PreimageUsage<AccountId, BlockNumber>> {
+			unimplemented!()
+		}
+
+		fn expired_preimages() -> Vec<Hash> {
+			unimplemented!()
+		}
+	}
+
+	impl pallet_referenda::runtime_api::ReferendaApi<Block, u16, BlockNumber, Hash> for Runtime {
+		fn ongoing_referenda() -> Vec<pallet_referenda::ReferendumSummary<u16, BlockNumber, Hash>> {
+			unimplemented!()
+		}
+	}
 }
@@ -195,6 +195,7 @@ pub fn node_config(
 		impl_version: "0.1".to_string(),
 		role,
 		tokio_handle,
+		dedicated_runtimes: Default::default(),
 		transaction_pool: Default::default(),
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
@@ -202,6 +203,7 @@ pub fn node_config(
 		trie_cache_maximum_size: Some(64 * 1024 * 1024),
 		state_pruning: Default::default(),
 		blocks_pruning: BlocksPruning::KeepFinalized,
+		database_write_rate_limit: None,
 		chain_spec: Box::new(spec),
 		executor: ExecutorConfiguration {
 			wasm_method: WasmExecutionMethod::Compiled {
@@ -210,6 +212,7 @@ pub fn node_config(
 			..ExecutorConfiguration::default()
 		},
 		wasm_runtime_overrides: Default::default(),
+		import_queue: Default::default(),
 		rpc: RpcConfiguration {
 			addr: Default::default(),
 			max_request_size: Default::default(),
@@ -225,6 +228,9 @@ pub fn node_config(
 			rate_limit: None,
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			serve_finalized_only: false,
+			transaction_confirmations: 0,
+			chain_head_resume_grace_period: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -235,6 +241,7 @@ pub fn node_config(
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
 		announce_block: true,
+		informant: Default::default(),
 		data_path: root,
 		base_path,
 	}
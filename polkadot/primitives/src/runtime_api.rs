@@ -314,4 +314,26 @@ sp_api::decl_runtime_apis! {
 		#[api_version(12)]
 		fn validation_code_bomb_limit() -> u32;
 	}
+
+	/// The API for managing the HRMP channels of a parachain.
+	pub trait HrmpChannelsApi {
+		/// Get the open channels and pending open requests the given para participates in.
+		fn hrmp_channels(para_id: ppp::Id) -> vstaging::hrmp::HrmpParaChannels;
+
+		/// Get the encoded call for requesting a channel from `sender` to `recipient`.
+		///
+		/// The channel limits default to the maximum allowed by the configuration. The call has
+		/// to be dispatched with the origin of `sender`, usually through an XCM `Transact`.
+		fn hrmp_init_open_channel_call(
+			recipient: ppp::Id,
+			max_capacity: Option<u32>,
+			max_message_size: Option<u32>,
+		) -> Vec<u8>;
+
+		/// Get the encoded call for accepting a channel requested by `sender`.
+		///
+		/// The call has to be dispatched with the origin of the recipient, usually through an XCM
+		/// `Transact`.
+		fn hrmp_accept_open_channel_call(sender: ppp::Id) -> Vec<u8>;
+	}
 }
@@ -0,0 +1,115 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HRMP channel management primitives.
+
+use super::Id;
+use crate::Balance;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::RuntimeDebug;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// An open HRMP channel.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct HrmpChannelInfo {
+	/// The para that acts as the sender in this channel.
+	pub sender: Id,
+	/// The para that acts as the recipient in this channel.
+	pub recipient: Id,
+	/// The maximum number of messages that can be pending in the channel at once.
+	pub max_capacity: u32,
+	/// The maximum total size of the messages that can be pending in the channel at once.
+	pub max_total_size: u32,
+	/// The maximum message size that could be put into the channel.
+	pub max_message_size: u32,
+	/// The current number of messages pending in the channel.
+	pub msg_count: u32,
+	/// The total size in bytes of all message payloads in the channel.
+	pub total_size: u32,
+	/// The deposit reserved from the sender.
+	pub sender_deposit: Balance,
+	/// The deposit reserved from the recipient.
+	pub recipient_deposit: Balance,
+	/// Whether a request to close this channel is pending.
+	pub closing: bool,
+}
+
+/// A pending request to open an HRMP channel.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct HrmpOpenRequestInfo {
+	/// The para that requested the channel.
+	pub sender: Id,
+	/// The para that needs to accept the channel.
+	pub recipient: Id,
+	/// Whether the request was accepted by the recipient. Accepted requests are turned into
+	/// channels at the next session change.
+	pub confirmed: bool,
+	/// The deposit reserved from the sender.
+	pub sender_deposit: Balance,
+	/// The maximum number of messages that can be pending in the channel at once.
+	pub max_capacity: u32,
+	/// The maximum message size that could be put into the channel.
+	pub max_message_size: u32,
+	/// The maximum total size of the messages that can be pending in the channel at once.
+	pub max_total_size: u32,
+}
+
+/// Limits and deposits that apply to new HRMP channels.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct HrmpChannelLimits {
+	/// The deposit reserved from the sender when requesting a channel.
+	pub sender_deposit: Balance,
+	/// The deposit reserved from the recipient when accepting a channel.
+	pub recipient_deposit: Balance,
+	/// The maximum capacity that can be requested for a channel.
+	pub max_capacity: u32,
+	/// The maximum total size that can be requested for a channel.
+	pub max_total_size: u32,
+	/// The maximum message size that can be requested for a channel.
+	pub max_message_size: u32,
+	/// The maximum number of outbound channels of a parachain.
+	pub max_outbound_channels: u32,
+	/// The maximum number of inbound channels of a parachain.
+	pub max_inbound_channels: u32,
+}
+
+/// All HRMP channels and requests a para participates in.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct HrmpParaChannels {
+	/// Open channels the para receives messages from.
+	pub ingress: Vec<HrmpChannelInfo>,
+	/// Open channels the para sends messages to.
+	pub egress: Vec<HrmpChannelInfo>,
+	/// Open requests the para made, waiting for the recipient or the next session.
+	pub pending_outgoing: Vec<HrmpOpenRequestInfo>,
+	/// Open requests other paras made to the para.
+	pub pending_incoming: Vec<HrmpOpenRequestInfo>,
+	/// The limits and deposits for new channels.
+	pub limits: HrmpChannelLimits,
+}
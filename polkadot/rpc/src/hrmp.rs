@@ -0,0 +1,121 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC methods for managing the HRMP channels of a parachain.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use polkadot_primitives::{
+	runtime_api::HrmpChannelsApi as HrmpChannelsRuntimeApi, vstaging::hrmp::HrmpParaChannels,
+	Id as ParaId,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+/// Error code returned when the runtime API call failed.
+const RUNTIME_ERROR: i32 = 1;
+
+/// HRMP channel management RPC methods.
+#[rpc(client, server)]
+pub trait HrmpApi<BlockHash> {
+	/// Returns the open channels and pending open requests of the given para, together with the
+	/// deposits and limits for new channels.
+	#[method(name = "hrmp_channels")]
+	fn channels(&self, para_id: ParaId, at: Option<BlockHash>) -> RpcResult<HrmpParaChannels>;
+
+	/// Returns the encoded relay chain call for requesting a channel to `recipient`.
+	///
+	/// Limits that are not given default to the maximum allowed by the relay chain.
+	#[method(name = "hrmp_initOpenChannelCall")]
+	fn init_open_channel_call(
+		&self,
+		recipient: ParaId,
+		max_capacity: Option<u32>,
+		max_message_size: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Bytes>;
+
+	/// Returns the encoded relay chain call for accepting the channel requested by `sender`.
+	#[method(name = "hrmp_acceptOpenChannelCall")]
+	fn accept_open_channel_call(&self, sender: ParaId, at: Option<BlockHash>) -> RpcResult<Bytes>;
+}
+
+/// Implementation of [`HrmpApiServer`].
+pub struct Hrmp<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Hrmp<C, Block> {
+	/// Create a new instance of the HRMP RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(error: impl ToString, desc: &'static str) -> ErrorObjectOwned {
+	ErrorObject::owned(RUNTIME_ERROR, desc, Some(error.to_string()))
+}
+
+impl<C, Block> HrmpApiServer<Block::Hash> for Hrmp<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: HrmpChannelsRuntimeApi<Block>,
+{
+	fn channels(&self, para_id: ParaId, at: Option<Block::Hash>) -> RpcResult<HrmpParaChannels> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.hrmp_channels(at, para_id)
+			.map_err(|e| runtime_error(e, "Unable to query HRMP channels."))
+	}
+
+	fn init_open_channel_call(
+		&self,
+		recipient: ParaId,
+		max_capacity: Option<u32>,
+		max_message_size: Option<u32>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Bytes> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.hrmp_init_open_channel_call(at, recipient, max_capacity, max_message_size)
+			.map(Into::into)
+			.map_err(|e| runtime_error(e, "Unable to build the HRMP open channel call."))
+	}
+
+	fn accept_open_channel_call(
+		&self,
+		sender: ParaId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Bytes> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.hrmp_accept_open_channel_call(at, sender)
+			.map(Into::into)
+			.map_err(|e| runtime_error(e, "Unable to build the HRMP accept channel call."))
+	}
+}
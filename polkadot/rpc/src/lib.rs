@@ -18,6 +18,10 @@
 
 #![warn(missing_docs)]
 
+pub mod hrmp;
+pub mod referenda;
+pub mod xcm_transfer;
+
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
@@ -115,6 +119,10 @@ where
 	C::Api: mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash, BlockNumber>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
+	C::Api: polkadot_primitives::runtime_api::HrmpChannelsApi<Block>,
+	C::Api: xcm_runtime_apis::transfers::XcmTransferApi<Block, AccountId>,
+	C::Api: pallet_referenda::runtime_api::ReferendaApi<Block, u16, BlockNumber, Hash>,
+	C::Api: sp_api::Metadata<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
@@ -123,14 +131,17 @@ where
 	AuthorityId: AuthorityIdBound,
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
 {
+	use hrmp::{Hrmp, HrmpApiServer};
 	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use referenda::{Referenda, ReferendaApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
 	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
 	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
+	use xcm_transfer::{XcmTransfer, XcmTransferApiServer};
 
 	let mut io = RpcModule::new(());
 	let BabeDeps { babe_worker_handle, keystore } = babe;
@@ -145,6 +156,9 @@ where
 	io.merge(StateMigration::new(client.clone(), backend.clone()).into_rpc())?;
 	io.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	io.merge(Hrmp::new(client.clone()).into_rpc())?;
+	io.merge(XcmTransfer::new(client.clone()).into_rpc())?;
+	io.merge(Referenda::new(client.clone()).into_rpc())?;
 	io.merge(
 		Mmr::new(
 			client.clone(),
@@ -0,0 +1,251 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC methods for governance UIs tracking the ongoing referenda.
+//!
+//! Proposals are previewed by resolving their pallet and call and decoding their arguments
+//! through the runtime metadata, so that clients don't need to decode them on their own.
+
+use std::sync::Arc;
+
+use frame_metadata::v15::RuntimeMetadataV15;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use pallet_referenda::{runtime_api::ReferendaApi as ReferendaRuntimeApi, ReferendumSummary};
+use polkadot_primitives::{BlockNumber, Hash};
+use sc_runtime_metadata::{decode::decode_value, MetadataCache};
+use scale_info::TypeDef;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_arithmetic::Perbill;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+/// Error code returned when the runtime API call failed.
+const RUNTIME_ERROR: i32 = 1;
+
+/// An argument of a call.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallField {
+	/// The name of the argument.
+	pub name: Option<String>,
+	/// The name of the type of the argument, as written in the source code.
+	pub type_name: Option<String>,
+	/// The decoded value of the argument, or `None` if it could not be decoded.
+	pub value: Option<Value>,
+}
+
+/// The pallet and call of a proposal, resolved through the runtime metadata.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallPreview {
+	/// The name of the pallet.
+	pub pallet: String,
+	/// The name of the call.
+	pub call: String,
+	/// The arguments of the call.
+	pub fields: Vec<CallField>,
+	/// The first line of the documentation of the call.
+	pub docs: String,
+}
+
+/// An ongoing referendum, as returned by `referenda_ongoing`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcReferendum {
+	/// The index of the referendum.
+	pub index: u32,
+	/// The track of the referendum.
+	pub track: u16,
+	/// The name of the track.
+	pub track_name: String,
+	/// The hash of the proposal.
+	pub proposal_hash: Hash,
+	/// The SCALE encoded proposal, or `None` if its preimage is not available.
+	pub proposal: Option<Bytes>,
+	/// A preview of the proposal, or `None` if it could not be resolved.
+	pub call: Option<CallPreview>,
+	/// The block of submission.
+	pub submitted: BlockNumber,
+	/// Whether the Decision Deposit has been placed.
+	pub decision_deposit_placed: bool,
+	/// The block at which the deciding period started.
+	pub deciding_since: Option<BlockNumber>,
+	/// The block at which the confirmation period ends.
+	pub confirming_until: Option<BlockNumber>,
+	/// Whether the referendum is queued for being decided.
+	pub in_queue: bool,
+	/// The number of referenda on the track which are being decided.
+	pub track_deciding: u32,
+	/// The number of referenda on the track which may be decided at once.
+	pub track_max_deciding: u32,
+	/// The current approval.
+	pub approval: Perbill,
+	/// The current support.
+	pub support: Perbill,
+	/// The approval which is currently needed to pass.
+	pub min_approval: Perbill,
+	/// The support which is currently needed to pass.
+	pub min_support: Perbill,
+	/// The block at which the deciding period ends.
+	pub decision_ends: Option<BlockNumber>,
+	/// The block at which the referendum times out if it does not enter the deciding period.
+	pub undeciding_timeout: Option<BlockNumber>,
+	/// The block at which the referendum will be approved if the tally does not change.
+	pub projected_approval: Option<BlockNumber>,
+}
+
+impl RpcReferendum {
+	fn new(
+		summary: ReferendumSummary<u16, BlockNumber, Hash>,
+		metadata: Option<&RuntimeMetadataV15>,
+	) -> Self {
+		let call = summary
+			.proposal
+			.as_ref()
+			.zip(metadata)
+			.and_then(|(proposal, metadata)| preview(metadata, proposal));
+		Self {
+			index: summary.index,
+			track: summary.track,
+			track_name: String::from_utf8_lossy(&summary.track_name).into_owned(),
+			proposal_hash: summary.proposal_hash,
+			proposal: summary.proposal.map(Into::into),
+			call,
+			submitted: summary.submitted,
+			decision_deposit_placed: summary.decision_deposit_placed,
+			deciding_since: summary.deciding.as_ref().map(|deciding| deciding.since),
+			confirming_until: summary.deciding.and_then(|deciding| deciding.confirming),
+			in_queue: summary.in_queue,
+			track_deciding: summary.track_deciding,
+			track_max_deciding: summary.track_max_deciding,
+			approval: summary.approval,
+			support: summary.support,
+			min_approval: summary.min_approval,
+			min_support: summary.min_support,
+			decision_ends: summary.decision_ends,
+			undeciding_timeout: summary.undeciding_timeout,
+			projected_approval: summary.projected_approval,
+		}
+	}
+}
+
+/// Resolve the pallet and call of the encoded `call` and decode its arguments.
+///
+/// The arguments are decoded in order, so once an argument fails to decode the values of all
+/// following arguments are `None` as well.
+fn preview(metadata: &RuntimeMetadataV15, call: &[u8]) -> Option<CallPreview> {
+	let (&pallet_index, rest) = call.split_first()?;
+	let (&call_index, mut args) = rest.split_first()?;
+	let pallet = metadata.pallets.iter().find(|pallet| pallet.index == pallet_index)?;
+	let calls = pallet.calls.as_ref()?;
+	let TypeDef::Variant(calls) = &metadata.types.resolve(calls.ty.id)?.type_def else {
+		return None
+	};
+	let variant = calls.variants.iter().find(|variant| variant.index == call_index)?;
+
+	let mut decodable = true;
+	let fields = variant
+		.fields
+		.iter()
+		.map(|field| {
+			let value = decodable
+				.then(|| decode_value(&metadata.types, field.ty.id, &mut args).ok())
+				.flatten();
+			decodable = value.is_some();
+			CallField { name: field.name.clone(), type_name: field.type_name.clone(), value }
+		})
+		.collect();
+
+	Some(CallPreview {
+		pallet: pallet.name.clone(),
+		call: variant.name.clone(),
+		fields,
+		docs: variant.docs.first().map(|docs| docs.trim().to_owned()).unwrap_or_default(),
+	})
+}
+
+/// Referenda tracking RPC methods.
+#[rpc(client, server)]
+pub trait ReferendaApi<BlockHash> {
+	/// Returns the ongoing referenda together with previews of their proposals, the state of
+	/// their tracks and their projected approval.
+	#[method(name = "referenda_ongoing")]
+	fn ongoing(&self, at: Option<BlockHash>) -> RpcResult<Vec<RpcReferendum>>;
+}
+
+/// Implementation of [`ReferendaApiServer`].
+pub struct Referenda<C, Block> {
+	client: Arc<C>,
+	metadata: MetadataCache,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Referenda<C, Block> {
+	/// Create a new instance of the referenda RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, metadata: MetadataCache::default(), _marker: Default::default() }
+	}
+}
+
+impl<C, Block> Referenda<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: Core<Block> + Metadata<Block>,
+{
+	/// The metadata of the runtime at `at`, or `None` if it is not available.
+	fn metadata(&self, at: Block::Hash) -> Option<Arc<RuntimeMetadataV15>> {
+		let spec_version = self.client.runtime_api().version(at).ok()?.spec_version;
+		self.metadata.get::<Block, _>(&*self.client, at, spec_version).ok()
+	}
+}
+
+fn runtime_error(error: impl ToString, desc: &'static str) -> ErrorObjectOwned {
+	ErrorObject::owned(RUNTIME_ERROR, desc, Some(error.to_string()))
+}
+
+impl<C, Block> ReferendaApiServer<Block::Hash> for Referenda<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ReferendaRuntimeApi<Block, u16, BlockNumber, Hash> + Core<Block> + Metadata<Block>,
+{
+	fn ongoing(&self, at: Option<Block::Hash>) -> RpcResult<Vec<RpcReferendum>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let referenda = self
+			.client
+			.runtime_api()
+			.ongoing_referenda(at)
+			.map_err(|e| runtime_error(e, "Unable to query ongoing referenda."))?;
+
+		let metadata = referenda
+			.iter()
+			.any(|summary| summary.proposal.is_some())
+			.then(|| self.metadata(at))
+			.flatten();
+		Ok(referenda
+			.into_iter()
+			.map(|summary| RpcReferendum::new(summary, metadata.as_deref()))
+			.collect())
+	}
+}
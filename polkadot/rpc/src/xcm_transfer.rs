@@ -0,0 +1,190 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC methods for wallets preparing XCM transfers.
+//!
+//! XCM types are passed and returned SCALE encoded.
+
+use std::sync::Arc;
+
+use codec::{Codec, Decode, Encode};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::traits::Block as BlockT;
+use sp_weights::Weight;
+use xcm::{VersionedAssetId, VersionedLocation, VersionedXcm};
+use xcm_runtime_apis::transfers::{TransferFees, XcmTransferApi as XcmTransferRuntimeApi};
+
+/// Error code returned when the runtime API call failed.
+const RUNTIME_ERROR: i32 = 1;
+/// Error code returned when a parameter could not be decoded.
+const DECODE_ERROR: i32 = 2;
+/// Error code returned when the runtime rejected the request.
+const TRANSFER_ERROR: i32 = 3;
+
+/// Fees of a transfer, as returned by `xcm_estimateTransferFees`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransferFees {
+	/// The weight of executing the transfer program locally.
+	pub local_execution_weight: Weight,
+	/// The fee for executing the transfer program locally, in the requested asset.
+	pub local_execution_fee: NumberOrHex,
+	/// The messages sent by the transfer program.
+	pub forwarded: Vec<RpcForwardedMessage>,
+}
+
+/// A message sent by a transfer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcForwardedMessage {
+	/// The SCALE encoded `VersionedLocation` the message is sent to.
+	pub destination: Bytes,
+	/// The SCALE encoded `VersionedXcm<()>`.
+	pub message: Bytes,
+	/// The SCALE encoded `VersionedAssets` charged for delivering the message.
+	pub delivery_fees: Bytes,
+}
+
+impl From<TransferFees> for RpcTransferFees {
+	fn from(fees: TransferFees) -> Self {
+		let forwarded = fees
+			.forwarded
+			.into_iter()
+			.flat_map(|forwarded| {
+				let destination: Bytes = forwarded.destination.encode().into();
+				forwarded.messages.into_iter().map(move |(message, delivery_fees)| {
+					RpcForwardedMessage {
+						destination: destination.clone(),
+						message: message.encode().into(),
+						delivery_fees: delivery_fees.encode().into(),
+					}
+				})
+			})
+			.collect();
+		Self {
+			local_execution_weight: fees.local_execution_weight,
+			local_execution_fee: fees.local_execution_fee.into(),
+			forwarded,
+		}
+	}
+}
+
+/// XCM transfer helper RPC methods.
+#[rpc(client, server)]
+pub trait XcmTransferApi<BlockHash, AccountId> {
+	/// Returns the sovereign account of the SCALE encoded `VersionedLocation`.
+	///
+	/// If `derivative_index` is given, the derivative account with that index of the sovereign
+	/// account is returned instead.
+	#[method(name = "xcm_sovereignAccount")]
+	fn sovereign_account(
+		&self,
+		location: Bytes,
+		derivative_index: Option<u16>,
+		at: Option<BlockHash>,
+	) -> RpcResult<AccountId>;
+
+	/// Estimates the local execution and delivery fees of the SCALE encoded transfer program
+	/// `xcm` executed by `origin`, paying for the local execution in `asset`.
+	///
+	/// The forwarded messages can be passed to the `XcmPaymentApi` of their destinations to
+	/// estimate the remote execution fees.
+	#[method(name = "xcm_estimateTransferFees")]
+	fn estimate_transfer_fees(
+		&self,
+		origin: Bytes,
+		xcm: Bytes,
+		asset: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<RpcTransferFees>;
+}
+
+/// Implementation of [`XcmTransferApiServer`].
+pub struct XcmTransfer<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> XcmTransfer<C, Block> {
+	/// Create a new instance of the XCM transfer RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn decode<T: Decode>(encoded: Bytes, what: &'static str) -> Result<T, ErrorObjectOwned> {
+	T::decode(&mut &*encoded)
+		.map_err(|e| ErrorObject::owned(DECODE_ERROR, what, Some(e.to_string())))
+}
+
+fn runtime_error(error: impl ToString, desc: &'static str) -> ErrorObjectOwned {
+	ErrorObject::owned(RUNTIME_ERROR, desc, Some(error.to_string()))
+}
+
+fn transfer_error(error: impl std::fmt::Debug) -> ErrorObjectOwned {
+	ErrorObject::owned(TRANSFER_ERROR, "Transfer request rejected.", Some(format!("{error:?}")))
+}
+
+impl<C, Block, AccountId> XcmTransferApiServer<Block::Hash, AccountId> for XcmTransfer<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: XcmTransferRuntimeApi<Block, AccountId>,
+	AccountId: Codec + Serialize + Send + Sync + 'static,
+{
+	fn sovereign_account(
+		&self,
+		location: Bytes,
+		derivative_index: Option<u16>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<AccountId> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let location: VersionedLocation = decode(location, "Unable to decode location.")?;
+		self.client
+			.runtime_api()
+			.sovereign_account(at, location, derivative_index)
+			.map_err(|e| runtime_error(e, "Unable to query sovereign account."))?
+			.map_err(transfer_error)
+	}
+
+	fn estimate_transfer_fees(
+		&self,
+		origin: Bytes,
+		xcm: Bytes,
+		asset: Bytes,
+		at: Option<Block::Hash>,
+	) -> RpcResult<RpcTransferFees> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let origin: VersionedLocation = decode(origin, "Unable to decode origin.")?;
+		let xcm: VersionedXcm<()> = decode(xcm, "Unable to decode XCM program.")?;
+		let asset: VersionedAssetId = decode(asset, "Unable to decode asset id.")?;
+		self.client
+			.runtime_api()
+			.estimate_transfer_fees(at, origin, xcm, asset)
+			.map_err(|e| runtime_error(e, "Unable to estimate transfer fees."))?
+			.map(Into::into)
+			.map_err(transfer_error)
+	}
+}
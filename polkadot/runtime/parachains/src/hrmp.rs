@@ -29,6 +29,7 @@ use frame_support::{pallet_prelude::*, traits::ReservableCurrency, DefaultNoBoun
 use frame_system::pallet_prelude::*;
 use polkadot_parachain_primitives::primitives::{HorizontalMessages, IsSystem};
 use polkadot_primitives::{
+	vstaging::hrmp::{HrmpChannelInfo, HrmpChannelLimits, HrmpOpenRequestInfo, HrmpParaChannels},
 	Balance, Hash, HrmpChannelId, Id as ParaId, InboundHrmpMessage, OutboundHrmpMessage,
 	SessionIndex,
 };
@@ -1305,6 +1306,71 @@ impl<T: Config> Pallet<T> {
 		remaining
 	}
 
+	/// Returns the open channels and pending open requests `para` participates in.
+	pub(crate) fn para_channels(para: ParaId) -> HrmpParaChannels {
+		let channel_info = |id: HrmpChannelId| {
+			let channel = HrmpChannels::<T>::get(&id)?;
+			Some(HrmpChannelInfo {
+				sender: id.sender,
+				recipient: id.recipient,
+				max_capacity: channel.max_capacity,
+				max_total_size: channel.max_total_size,
+				max_message_size: channel.max_message_size,
+				msg_count: channel.msg_count,
+				total_size: channel.total_size,
+				sender_deposit: channel.sender_deposit,
+				recipient_deposit: channel.recipient_deposit,
+				closing: HrmpCloseChannelRequests::<T>::contains_key(&id),
+			})
+		};
+
+		let ingress = HrmpIngressChannelsIndex::<T>::get(&para)
+			.into_iter()
+			.filter_map(|sender| channel_info(HrmpChannelId { sender, recipient: para }))
+			.collect();
+		let egress = HrmpEgressChannelsIndex::<T>::get(&para)
+			.into_iter()
+			.filter_map(|recipient| channel_info(HrmpChannelId { sender: para, recipient }))
+			.collect();
+
+		let mut pending_outgoing = Vec::new();
+		let mut pending_incoming = Vec::new();
+		for id in HrmpOpenChannelRequestsList::<T>::get() {
+			if !id.is_participant(para) {
+				continue
+			}
+			let Some(request) = HrmpOpenChannelRequests::<T>::get(&id) else { continue };
+
+			let info = HrmpOpenRequestInfo {
+				sender: id.sender,
+				recipient: id.recipient,
+				confirmed: request.confirmed,
+				sender_deposit: request.sender_deposit,
+				max_capacity: request.max_capacity,
+				max_message_size: request.max_message_size,
+				max_total_size: request.max_total_size,
+			};
+			if id.sender == para {
+				pending_outgoing.push(info);
+			} else {
+				pending_incoming.push(info);
+			}
+		}
+
+		let config = configuration::ActiveConfig::<T>::get();
+		let limits = HrmpChannelLimits {
+			sender_deposit: config.hrmp_sender_deposit,
+			recipient_deposit: config.hrmp_recipient_deposit,
+			max_capacity: config.hrmp_channel_max_capacity,
+			max_total_size: config.hrmp_channel_max_total_size,
+			max_message_size: config.hrmp_channel_max_message_size,
+			max_outbound_channels: config.hrmp_max_parachain_outbound_channels,
+			max_inbound_channels: config.hrmp_max_parachain_inbound_channels,
+		};
+
+		HrmpParaChannels { ingress, egress, pending_outgoing, pending_incoming, limits }
+	}
+
 	pub(crate) fn prune_hrmp(recipient: ParaId, new_hrmp_watermark: BlockNumberFor<T>) {
 		// sift through the incoming messages digest to collect the paras that sent at least one
 		// message to this parachain between the old and new watermarks.
@@ -179,6 +179,51 @@ fn open_channel_works() {
 	});
 }
 
+#[test]
+fn para_channels_reports_requests_and_channels() {
+	let para_a = 2001.into();
+	let para_a_origin: crate::Origin = 2001.into();
+	let para_b = 2003.into();
+	let para_b_origin: crate::Origin = 2003.into();
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+
+		run_to_block(5, Some(vec![4, 5]));
+		let channels = Hrmp::para_channels(para_a);
+		assert!(channels.egress.is_empty() && channels.pending_outgoing.is_empty());
+		assert_eq!(channels.limits.sender_deposit, 100);
+		assert_eq!(channels.limits.max_capacity, 2);
+
+		Hrmp::hrmp_init_open_channel(para_a_origin.into(), para_b, 2, 8).unwrap();
+		let request = HrmpOpenRequestInfo {
+			sender: para_a,
+			recipient: para_b,
+			confirmed: false,
+			sender_deposit: 100,
+			max_capacity: 2,
+			max_message_size: 8,
+			max_total_size: 16,
+		};
+		assert_eq!(Hrmp::para_channels(para_a).pending_outgoing, vec![request.clone()]);
+		assert_eq!(Hrmp::para_channels(para_b).pending_incoming, vec![request]);
+
+		Hrmp::hrmp_accept_open_channel(para_b_origin.into(), para_a).unwrap();
+		assert!(Hrmp::para_channels(para_a).pending_outgoing[0].confirmed);
+
+		run_to_block(8, Some(vec![8]));
+		let channels = Hrmp::para_channels(para_a);
+		assert!(channels.pending_outgoing.is_empty());
+		assert_eq!(channels.egress.len(), 1);
+		assert_eq!(channels.egress[0].recipient, para_b);
+		assert_eq!(channels.egress[0].sender_deposit, 100);
+		assert_eq!(channels.egress[0].recipient_deposit, 100);
+		assert!(!channels.egress[0].closing);
+		assert_eq!(Hrmp::para_channels(para_b).ingress, channels.egress);
+	});
+}
+
 #[test]
 fn force_open_channel_works() {
 	let para_a = 1.into();
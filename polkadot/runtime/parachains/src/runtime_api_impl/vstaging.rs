@@ -16,9 +16,12 @@
 
 //! Put implementations of functions from staging APIs here.
 
-use crate::{configuration, initializer};
+use crate::{configuration, hrmp, initializer};
 use frame_system::pallet_prelude::*;
-use polkadot_primitives::{vstaging::async_backing::Constraints, Id as ParaId};
+use polkadot_primitives::{
+	vstaging::{async_backing::Constraints, hrmp::HrmpParaChannels},
+	Id as ParaId,
+};
 
 /// Implementation for `constraints` function from the runtime API
 pub fn backing_constraints<T: initializer::Config>(
@@ -56,3 +59,21 @@ pub fn validation_code_bomb_limit<T: initializer::Config>() -> u32 {
 	configuration::ActiveConfig::<T>::get().max_code_size *
 		configuration::MAX_VALIDATION_CODE_COMPRESSION_RATIO
 }
+
+/// Implementation for `hrmp_channels` function from the runtime API
+pub fn hrmp_channels<T: hrmp::Config>(para_id: ParaId) -> HrmpParaChannels {
+	hrmp::Pallet::<T>::para_channels(para_id)
+}
+
+/// Returns the `proposed_max_capacity` and `proposed_max_message_size` for a new HRMP channel,
+/// falling back to the maximum allowed by the configuration.
+pub fn hrmp_open_channel_parameters<T: configuration::Config>(
+	max_capacity: Option<u32>,
+	max_message_size: Option<u32>,
+) -> (u32, u32) {
+	let config = configuration::ActiveConfig::<T>::get();
+	(
+		max_capacity.unwrap_or(config.hrmp_channel_max_capacity),
+		max_message_size.unwrap_or(config.hrmp_channel_max_message_size),
+	)
+}
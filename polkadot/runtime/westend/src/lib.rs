@@ -344,6 +344,7 @@ impl pallet_preimage::Config for Runtime {
 		PreimageHoldReason,
 		LinearStoragePrice<PreimageBaseDeposit, PreimageByteDeposit, Balance>,
 	>;
+	type ExpiryPeriod = ConstU32<{ 28 * DAYS }>;
 }
 
 parameter_types! {
@@ -2219,6 +2220,34 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl polkadot_primitives::runtime_api::HrmpChannelsApi<Block> for Runtime {
+		fn hrmp_channels(para_id: ParaId) -> polkadot_primitives::vstaging::hrmp::HrmpParaChannels {
+			parachains_staging_runtime_api_impl::hrmp_channels::<Runtime>(para_id)
+		}
+
+		fn hrmp_init_open_channel_call(
+			recipient: ParaId,
+			max_capacity: Option<u32>,
+			max_message_size: Option<u32>,
+		) -> Vec<u8> {
+			let (proposed_max_capacity, proposed_max_message_size) =
+				parachains_staging_runtime_api_impl::hrmp_open_channel_parameters::<Runtime>(
+					max_capacity,
+					max_message_size,
+				);
+			RuntimeCall::Hrmp(parachains_hrmp::Call::hrmp_init_open_channel {
+				recipient,
+				proposed_max_capacity,
+				proposed_max_message_size,
+			})
+			.encode()
+		}
+
+		fn hrmp_accept_open_channel_call(sender: ParaId) -> Vec<u8> {
+			RuntimeCall::Hrmp(parachains_hrmp::Call::hrmp_accept_open_channel { sender }).encode()
+		}
+	}
+
 	#[api_version(5)]
 	impl sp_consensus_beefy::BeefyApi<Block, BeefyId> for Runtime {
 		fn beefy_genesis() -> Option<BlockNumber> {
@@ -2548,6 +2577,49 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl xcm_runtime_apis::transfers::XcmTransferApi<Block, AccountId> for Runtime {
+		fn sovereign_account(location: VersionedLocation, derivative_index: Option<u16>) -> Result<
+			AccountId,
+			xcm_runtime_apis::transfers::Error
+		> {
+			xcm_runtime_apis::transfers::SovereignAccountHelper::<
+				AccountId,
+				xcm_config::LocationConverter,
+			>::sovereign_account(location, derivative_index)
+		}
+
+		fn estimate_transfer_fees(origin: VersionedLocation, xcm: VersionedXcm<()>, asset: VersionedAssetId) -> Result<
+			xcm_runtime_apis::transfers::TransferFees,
+			xcm_runtime_apis::transfers::Error
+		> {
+			use xcm_runtime_apis::fees::runtime_decl_for_xcm_payment_api::XcmPaymentApiV1;
+			XcmPallet::estimate_transfer_fees::<Runtime, xcm_config::XcmRouter, RuntimeCall, xcm_config::XcmConfig>(
+				origin,
+				xcm,
+				asset,
+				<Runtime as XcmPaymentApiV1<Block>>::query_weight_to_asset_fee,
+			)
+		}
+	}
+
+	impl pallet_preimage::runtime_api::PreimageApi<Block, AccountId, Hash, BlockNumber> for Runtime {
+		fn preimage_usage(
+			hash: Hash,
+		) -> Option<pallet_preimage::PreimageUsage<AccountId, BlockNumber>> {
+			Preimage::preimage_usage(&hash)
+		}
+
+		fn expired_preimages() -> Vec<Hash> {
+			Preimage::expired_preimages()
+		}
+	}
+
+	impl pallet_referenda::runtime_api::ReferendaApi<Block, u16, BlockNumber, Hash> for Runtime {
+		fn ongoing_referenda() -> Vec<pallet_referenda::ReferendumSummary<u16, BlockNumber, Hash>> {
+			Referenda::ongoing_referenda()
+		}
+	}
+
 	impl pallet_nomination_pools_runtime_api::NominationPoolsApi<
 		Block,
 		AccountId,
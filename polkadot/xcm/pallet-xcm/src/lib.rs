@@ -70,6 +70,7 @@ use xcm_executor::{
 use xcm_runtime_apis::{
 	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
 	fees::Error as XcmPaymentApiError,
+	transfers::{Error as XcmTransferApiError, ForwardedMessages, TransferFees},
 	trusted_query::Error as TrustedQueryApiError,
 };
 
@@ -2628,6 +2629,73 @@ impl<T: Config> Pallet<T> {
 		Ok(XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result })
 	}
 
+	/// Estimates the fees of the transfer program `xcm` executed by `origin_location`.
+	///
+	/// The program is dry-run with [`Self::dry_run_xcm`] and the delivery fees of every forwarded
+	/// message are queried. The local execution weight is converted to a fee in `asset` using
+	/// `weight_to_fee`, which is usually the runtime's `XcmPaymentApi::query_weight_to_asset_fee`.
+	/// Meant to be used in the `xcm_runtime_apis::transfers::XcmTransferApi` runtime API.
+	pub fn estimate_transfer_fees<
+		Runtime,
+		Router,
+		RuntimeCall: Decode + GetDispatchInfo,
+		XcmConfig,
+	>(
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<()>,
+		asset: VersionedAssetId,
+		weight_to_fee: impl FnOnce(Weight, VersionedAssetId) -> Result<u128, XcmPaymentApiError>,
+	) -> Result<TransferFees, XcmTransferApiError>
+	where
+		Runtime: frame_system::Config,
+		Router: InspectMessageQueues,
+		XcmConfig: xcm_executor::Config<RuntimeCall = RuntimeCall>,
+	{
+		let local_execution_weight = Self::query_xcm_weight(xcm.clone())?;
+		let local_execution_fee = weight_to_fee(local_execution_weight, asset)?;
+
+		let xcm_version = xcm.identify_version();
+		let xcm: Xcm<()> = xcm.try_into().map_err(|error| {
+			tracing::error!(
+				target: "xcm::XcmTransferApi::estimate_transfer_fees",
+				?error, "Xcm version conversion failed with error"
+			);
+			XcmTransferApiError::VersionedConversionFailed
+		})?;
+		let xcm = VersionedXcm::<RuntimeCall>::from(xcm.into())
+			.into_version(xcm_version)
+			.map_err(|()| XcmTransferApiError::VersionedConversionFailed)?;
+
+		let effects =
+			Self::dry_run_xcm::<Runtime, Router, RuntimeCall, XcmConfig>(origin_location, xcm)?;
+		if let Outcome::Incomplete { error, .. } | Outcome::Error { error } =
+			effects.execution_result
+		{
+			tracing::debug!(
+				target: "xcm::XcmTransferApi::estimate_transfer_fees",
+				?error, "Transfer program failed to execute"
+			);
+			return Err(XcmTransferApiError::ExecutionFailed)
+		}
+
+		let forwarded = effects
+			.forwarded_xcms
+			.into_iter()
+			.map(|(destination, messages)| {
+				let messages = messages
+					.into_iter()
+					.map(|message| {
+						Self::query_delivery_fees(destination.clone(), message.clone())
+							.map(|fees| (message, fees))
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok(ForwardedMessages { destination, messages })
+			})
+			.collect::<Result<Vec<_>, XcmPaymentApiError>>()?;
+
+		Ok(TransferFees { local_execution_weight, local_execution_fee, forwarded })
+	}
+
 	fn convert_xcms(
 		xcm_version: XcmVersion,
 		xcms: Vec<VersionedXcm<()>>,
@@ -34,3 +34,7 @@ pub mod fees;
 // Exposes runtime API for querying whether a Location is trusted as a reserve or teleporter for a
 // given Asset.
 pub mod trusted_query;
+
+/// Transfer API.
+/// Given a location or a transfer program, it returns the accounts and fees involved.
+pub mod transfers;
@@ -0,0 +1,169 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains runtime APIs for wallets preparing cross-chain transfers.
+
+use crate::conversions::{Error as LocationToAccountApiError, LocationToAccountHelper};
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::{pallet_prelude::TypeInfo, sp_runtime::traits::TrailingZeroInput, Hashable};
+use sp_weights::Weight;
+use xcm::{VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm};
+use xcm_executor::traits::ConvertLocation;
+
+sp_api::decl_runtime_apis! {
+	/// API for the accounts and fees involved in cross-chain transfers.
+	///
+	/// Combines [`LocationToAccountApi`](crate::conversions::LocationToAccountApi),
+	/// [`DryRunApi`](crate::dry_run::DryRunApi) and [`XcmPaymentApi`](crate::fees::XcmPaymentApi),
+	/// so that wallets can prepare a transfer with a single call.
+	pub trait XcmTransferApi<AccountId> where AccountId: Decode {
+		/// Returns the sovereign account of `location` on this chain.
+		///
+		/// If `derivative_index` is given, the derivative account with that index of the
+		/// sovereign account is returned instead, as used by `pallet_utility::as_derivative`.
+		///
+		/// # Arguments
+		///
+		/// * `location`: The location to compute the account for.
+		/// * `derivative_index`: Optional derivative index.
+		fn sovereign_account(location: VersionedLocation, derivative_index: Option<u16>) -> Result<AccountId, Error>;
+
+		/// Estimates the fees of a transfer executed as the XCM program `xcm` by `origin`.
+		///
+		/// The program is dry-run to find the messages it sends. The returned estimate covers
+		/// the local execution paid in `asset` and the delivery of every forwarded message. The
+		/// execution fees at each destination can be obtained by passing the forwarded messages
+		/// to the `XcmPaymentApi` of the destination chain.
+		///
+		/// # Arguments
+		///
+		/// * `origin`: The location executing the program.
+		/// * `xcm`: The transfer program.
+		/// * `asset`: The asset the local execution is paid in.
+		fn estimate_transfer_fees(origin: VersionedLocation, xcm: VersionedXcm<()>, asset: VersionedAssetId) -> Result<TransferFees, Error>;
+	}
+}
+
+/// Fee estimate returned by [`XcmTransferApi::estimate_transfer_fees`].
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct TransferFees {
+	/// The weight of executing the transfer program locally.
+	pub local_execution_weight: Weight,
+	/// The fee for executing the transfer program locally, in the requested asset.
+	pub local_execution_fee: u128,
+	/// The messages sent by the transfer program.
+	pub forwarded: Vec<ForwardedMessages>,
+}
+
+/// Messages sent to one destination, each with its delivery fees.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct ForwardedMessages {
+	/// The destination of the messages.
+	pub destination: VersionedLocation,
+	/// The messages with the fees charged for delivering each of them.
+	pub messages: Vec<(VersionedXcm<()>, VersionedAssets)>,
+}
+
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum Error {
+	/// An API part is unsupported.
+	#[codec(index = 0)]
+	Unimplemented,
+
+	/// Converting a versioned data structure from one version to another failed.
+	#[codec(index = 1)]
+	VersionedConversionFailed,
+
+	/// Requested `Location` is not supported by the local conversion.
+	#[codec(index = 2)]
+	UnsupportedLocation,
+
+	/// XCM message weight calculation failed.
+	#[codec(index = 3)]
+	WeightNotComputable,
+
+	/// The given asset is not handled as a fee asset.
+	#[codec(index = 4)]
+	AssetNotFound,
+
+	/// A destination of the transfer is known to be unroutable.
+	#[codec(index = 5)]
+	Unroutable,
+
+	/// Dry-running the transfer program failed.
+	#[codec(index = 6)]
+	ExecutionFailed,
+}
+
+impl From<LocationToAccountApiError> for Error {
+	fn from(error: LocationToAccountApiError) -> Self {
+		match error {
+			LocationToAccountApiError::Unsupported => Self::UnsupportedLocation,
+			LocationToAccountApiError::VersionedConversionFailed => Self::VersionedConversionFailed,
+		}
+	}
+}
+
+impl From<crate::fees::Error> for Error {
+	fn from(error: crate::fees::Error) -> Self {
+		use crate::fees::Error as XcmPaymentApiError;
+		match error {
+			XcmPaymentApiError::Unimplemented => Self::Unimplemented,
+			XcmPaymentApiError::VersionedConversionFailed |
+			XcmPaymentApiError::UnhandledXcmVersion => Self::VersionedConversionFailed,
+			XcmPaymentApiError::WeightNotComputable => Self::WeightNotComputable,
+			XcmPaymentApiError::AssetNotFound => Self::AssetNotFound,
+			XcmPaymentApiError::Unroutable => Self::Unroutable,
+		}
+	}
+}
+
+impl From<crate::dry_run::Error> for Error {
+	fn from(error: crate::dry_run::Error) -> Self {
+		match error {
+			crate::dry_run::Error::Unimplemented => Self::Unimplemented,
+			crate::dry_run::Error::VersionedConversionFailed => Self::VersionedConversionFailed,
+		}
+	}
+}
+
+/// Computes sovereign and derivative accounts for [`XcmTransferApi::sovereign_account`].
+pub struct SovereignAccountHelper<AccountId, Conversion>(
+	core::marker::PhantomData<(AccountId, Conversion)>,
+);
+impl<AccountId: Encode + Decode, Conversion: ConvertLocation<AccountId>>
+	SovereignAccountHelper<AccountId, Conversion>
+{
+	pub fn sovereign_account(
+		location: VersionedLocation,
+		derivative_index: Option<u16>,
+	) -> Result<AccountId, Error> {
+		let sovereign =
+			LocationToAccountHelper::<AccountId, Conversion>::convert_location(location)?;
+		Ok(match derivative_index {
+			Some(index) => derivative_account(sovereign, index),
+			None => sovereign,
+		})
+	}
+}
+
+/// Same derivation as `pallet_utility::Pallet::derivative_account_id`.
+fn derivative_account<AccountId: Encode + Decode>(who: AccountId, index: u16) -> AccountId {
+	let entropy = (b"modlpy/utilisuba", who, index).blake2_256();
+	Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
@@ -48,6 +48,9 @@ use xcm_runtime_apis::{
 	conversions::{Error as LocationToAccountApiError, LocationToAccountApi},
 	dry_run::{CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, XcmDryRunEffects},
 	fees::{Error as XcmPaymentApiError, XcmPaymentApi},
+	transfers::{
+		Error as XcmTransferApiError, SovereignAccountHelper, TransferFees, XcmTransferApi,
+	},
 	trusted_query::{Error as TrustedQueryApiError, TrustedQueryApi},
 };
 
@@ -424,6 +427,31 @@ pub fn new_test_ext_with_balances_and_assets(
 	ext
 }
 
+fn weight_to_asset_fee(
+	weight: Weight,
+	asset: VersionedAssetId,
+) -> Result<u128, XcmPaymentApiError> {
+	let latest_asset_id: Result<AssetId, ()> = asset.clone().try_into();
+	match latest_asset_id {
+		Ok(asset_id) if asset_id.0 == HereLocation::get() =>
+			Ok(WeightToFee::weight_to_fee(&weight)),
+		Ok(asset_id) => {
+			log::trace!(
+				target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
+				"query_weight_to_asset_fee - unhandled asset_id: {asset_id:?}!"
+			);
+			Err(XcmPaymentApiError::AssetNotFound)
+		},
+		Err(_) => {
+			log::trace!(
+				target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
+				"query_weight_to_asset_fee - failed to convert asset: {asset:?}!"
+			);
+			Err(XcmPaymentApiError::VersionedConversionFailed)
+		},
+	}
+}
+
 #[derive(Clone)]
 pub(crate) struct TestClient;
 
@@ -471,26 +499,7 @@ sp_api::mock_impl_runtime_apis! {
 		}
 
 		fn query_weight_to_asset_fee(weight: Weight, asset: VersionedAssetId) -> Result<u128, XcmPaymentApiError> {
-			let latest_asset_id: Result<AssetId, ()> = asset.clone().try_into();
-			match latest_asset_id {
-				Ok(asset_id) if asset_id.0 == HereLocation::get() => {
-					Ok(WeightToFee::weight_to_fee(&weight))
-				},
-				Ok(asset_id) => {
-					log::trace!(
-						target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
-						"query_weight_to_asset_fee - unhandled asset_id: {asset_id:?}!"
-					);
-					Err(XcmPaymentApiError::AssetNotFound)
-				},
-				Err(_) => {
-					log::trace!(
-						target: "xcm::XcmPaymentApi::query_weight_to_asset_fee",
-						"query_weight_to_asset_fee - failed to convert asset: {asset:?}!"
-					);
-					Err(XcmPaymentApiError::VersionedConversionFailed)
-				}
-			}
+			weight_to_asset_fee(weight, asset)
 		}
 
 		fn query_delivery_fees(destination: VersionedLocation, message: VersionedXcm<()>) -> Result<VersionedAssets, XcmPaymentApiError> {
@@ -518,4 +527,14 @@ sp_api::mock_impl_runtime_apis! {
 			pallet_xcm::Pallet::<TestRuntime>::dry_run_xcm::<TestRuntime, XcmRouter, RuntimeCall, XcmConfig>(origin_location, xcm)
 		}
 	}
+
+	impl XcmTransferApi<Block, AccountId> for RuntimeApi {
+		fn sovereign_account(location: VersionedLocation, derivative_index: Option<u16>) -> Result<AccountId, XcmTransferApiError> {
+			SovereignAccountHelper::<AccountId, LocationToAccountId>::sovereign_account(location, derivative_index)
+		}
+
+		fn estimate_transfer_fees(origin: VersionedLocation, xcm: VersionedXcm<()>, asset: VersionedAssetId) -> Result<TransferFees, XcmTransferApiError> {
+			pallet_xcm::Pallet::<TestRuntime>::estimate_transfer_fees::<TestRuntime, XcmRouter, RuntimeCall, XcmConfig>(origin, xcm, asset, weight_to_asset_fee)
+		}
+	}
 }
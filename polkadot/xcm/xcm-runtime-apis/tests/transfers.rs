@@ -0,0 +1,153 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the transfer helpers API.
+
+mod mock;
+
+use codec::{Decode, Encode};
+use frame_support::sp_runtime::{testing::H256, traits::TrailingZeroInput};
+use mock::*;
+use sp_api::ProvideRuntimeApi;
+use xcm::prelude::*;
+use xcm_runtime_apis::{
+	fees::XcmPaymentApi,
+	transfers::{Error as XcmTransferApiError, ForwardedMessages, XcmTransferApi},
+};
+
+#[test]
+fn sovereign_account_works() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		let client = TestClient {};
+		let runtime_api = client.runtime_api();
+		let sibling = VersionedLocation::from((Parent, Parachain(1000)));
+
+		assert_eq!(
+			runtime_api.sovereign_account(H256::zero(), sibling.clone(), None).unwrap(),
+			Ok(1000_u64)
+		);
+
+		// Derivative accounts follow `pallet_utility::derivative_account_id`.
+		let entropy =
+			(b"modlpy/utilisuba", 1000_u64, 7_u16).using_encoded(sp_io::hashing::blake2_256);
+		let derivative = u64::decode(&mut TrailingZeroInput::new(entropy.as_ref())).unwrap();
+		assert_eq!(
+			runtime_api.sovereign_account(H256::zero(), sibling.clone(), Some(7)).unwrap(),
+			Ok(derivative)
+		);
+		assert_ne!(
+			runtime_api.sovereign_account(H256::zero(), sibling, Some(8)).unwrap(),
+			Ok(derivative)
+		);
+
+		assert_eq!(
+			runtime_api
+				.sovereign_account(H256::zero(), VersionedLocation::from(Location::here()), None)
+				.unwrap(),
+			Err(XcmTransferApiError::UnsupportedLocation)
+		);
+	})
+}
+
+#[test]
+fn estimate_transfer_fees_works() {
+	let who = 1; // AccountId = u64.
+	let transfer_amount = 100u128;
+	let inner_xcm = Xcm::<()>::builder_unsafe()
+		.buy_execution((Here, 1u128), Unlimited)
+		.deposit_asset(AllCounted(1), [0u8; 32])
+		.build();
+	let xcm = Xcm::<()>::builder_unsafe()
+		.withdraw_asset((Here, transfer_amount))
+		.clear_origin()
+		.buy_execution((Here, transfer_amount), Unlimited)
+		.deposit_reserve_asset(AllCounted(1), (Parent, Parachain(2100)), inner_xcm)
+		.build();
+	let origin = VersionedLocation::from([AccountIndex64 { index: who, network: None }]);
+	let client = TestClient;
+	let runtime_api = client.runtime_api();
+	let balances = vec![(who, transfer_amount + DeliveryFees::get() + ExistentialDeposit::get())];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let expected_weight = runtime_api
+			.query_xcm_weight(H256::zero(), VersionedXcm::from(xcm.clone()))
+			.unwrap()
+			.unwrap();
+		let expected_fee = runtime_api
+			.query_weight_to_asset_fee(
+				H256::zero(),
+				expected_weight,
+				VersionedAssetId::from(AssetId(Here.into())),
+			)
+			.unwrap()
+			.unwrap();
+
+		let fees = runtime_api
+			.estimate_transfer_fees(
+				H256::zero(),
+				origin.clone(),
+				VersionedXcm::from(xcm.clone()),
+				VersionedAssetId::from(AssetId(Here.into())),
+			)
+			.unwrap()
+			.unwrap();
+		assert_eq!(fees.local_execution_weight, expected_weight);
+		assert_eq!(fees.local_execution_fee, expected_fee);
+		assert_eq!(fees.forwarded.len(), 1);
+		let ForwardedMessages { destination, messages } = &fees.forwarded[0];
+		assert_eq!(destination, &VersionedLocation::from((Parent, Parachain(2100))));
+		assert_eq!(messages.len(), 1);
+		assert_eq!(messages[0].1, VersionedAssets::from((Here, DeliveryFees::get())));
+
+		// Fees can only be estimated in accepted assets.
+		assert_eq!(
+			runtime_api
+				.estimate_transfer_fees(
+					H256::zero(),
+					origin,
+					VersionedXcm::from(xcm),
+					VersionedAssetId::from(AssetId(Parent.into())),
+				)
+				.unwrap(),
+			Err(XcmTransferApiError::AssetNotFound)
+		);
+	});
+}
+
+#[test]
+fn estimate_transfer_fees_fails_for_failing_program() {
+	let xcm = Xcm::<()>::builder_unsafe()
+		.withdraw_asset((Here, 100u128))
+		.clear_origin()
+		.buy_execution((Here, 100u128), Unlimited)
+		.deposit_asset(AllCounted(1), [0u8; 32])
+		.build();
+	let client = TestClient;
+	let runtime_api = client.runtime_api();
+	// The origin has no funds to withdraw.
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		assert_eq!(
+			runtime_api
+				.estimate_transfer_fees(
+					H256::zero(),
+					VersionedLocation::from([AccountIndex64 { index: 1, network: None }]),
+					VersionedXcm::from(xcm),
+					VersionedAssetId::from(AssetId(Here.into())),
+				)
+				.unwrap(),
+			Err(XcmTransferApiError::ExecutionFailed)
+		);
+	});
+}
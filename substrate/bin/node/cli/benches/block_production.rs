@@ -67,6 +67,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		// in the background which would mess with our benchmark.
 		role: Role::Full,
 		tokio_handle,
+		dedicated_runtimes: Default::default(),
 		transaction_pool: Default::default(),
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
@@ -74,6 +75,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		trie_cache_maximum_size: Some(64 * 1024 * 1024),
 		state_pruning: Some(PruningMode::ArchiveAll),
 		blocks_pruning: BlocksPruning::KeepAll,
+		database_write_rate_limit: None,
 		chain_spec: spec,
 		executor: ExecutorConfiguration {
 			wasm_method: WasmExecutionMethod::Compiled {
@@ -96,6 +98,9 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			rate_limit: None,
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			serve_finalized_only: false,
+			transaction_confirmations: 0,
+			chain_head_resume_grace_period: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -106,15 +111,27 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
 		announce_block: true,
+		informant: Default::default(),
 		data_path: base_path.path().into(),
 		base_path,
 		wasm_runtime_overrides: None,
+		import_queue: Default::default(),
 	};
 
 	node_cli::service::new_full_base::<sc_network::NetworkWorker<_, _>>(
 		config,
 		None,
 		false,
+		None,
+		None,
+		None,
+		None,
+		None,
+		None,
+		None,
+		None,
+		None,
+		Default::default(),
 		|_, _| (),
 	)
 	.expect("creating a full node doesn't fail")
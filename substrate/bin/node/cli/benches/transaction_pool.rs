@@ -58,6 +58,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		impl_version: "1.0".into(),
 		role: Role::Authority,
 		tokio_handle: tokio_handle.clone(),
+		dedicated_runtimes: Default::default(),
 		transaction_pool: TransactionPoolOptions::new_for_benchmarks(),
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
@@ -65,6 +66,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		trie_cache_maximum_size: Some(64 * 1024 * 1024),
 		state_pruning: Some(PruningMode::ArchiveAll),
 		blocks_pruning: BlocksPruning::KeepAll,
+		database_write_rate_limit: None,
 		chain_spec: spec,
 		executor: ExecutorConfiguration::default(),
 		rpc: RpcConfiguration {
@@ -82,6 +84,9 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			rate_limit: None,
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			serve_finalized_only: false,
+			transaction_confirmations: 0,
+			chain_head_resume_grace_period: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -92,9 +97,11 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
 		announce_block: true,
+		informant: Default::default(),
 		data_path: base_path.path().into(),
 		base_path,
 		wasm_runtime_overrides: None,
+		import_queue: Default::default(),
 	};
 
 	tokio_handle.block_on(async move {
@@ -102,6 +109,16 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			config,
 			None,
 			false,
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			Default::default(),
 			|_, _| (),
 		)
 		.expect("Creates node")
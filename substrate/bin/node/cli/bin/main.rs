@@ -23,6 +23,14 @@
 use polkadot_sdk::*;
 use staging_node_cli as node_cli;
 
+/// Global allocator attributing the heap usage to the subsystems of the node.
+#[cfg(feature = "memory-breakdown")]
+#[global_allocator]
+static ALLOC: sc_memory_breakdown::TrackingAllocator<std::alloc::System> =
+	sc_memory_breakdown::TrackingAllocator::new(std::alloc::System);
+
 fn main() -> sc_cli::Result<()> {
-	node_cli::run()
+	let result = node_cli::run();
+	sc_self_update::restart_if_updated()?;
+	result
 }
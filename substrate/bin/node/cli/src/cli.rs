@@ -46,6 +46,82 @@ pub struct Cli {
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub storage_monitor: sc_storage_monitor::StorageMonitorParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub state_scrubber: sc_state_scrubber::StateScrubberParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub state_regeneration: sc_state_regeneration::StateRegenerationParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub self_update: sc_self_update::SelfUpdateParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub crash_report: sc_crash_report::CrashReportParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub signing_lease: sc_consensus_slashing_protection::SigningLeaseParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub propagation_latency: sc_network_propagation::PropagationLatencyParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub grandpa_justifications: sc_consensus_grandpa::JustificationPolicyParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub backup: sc_cli::BackupParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub replica: sc_replica::ReplicaParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub content_store: sc_content_store::ContentStoreParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub clock_health: sc_consensus_slots::ClockHealthParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub statement_store: sc_statement_store::StatementStoreParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub grpc: sc_rpc_grpc::GrpcParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub graphql: sc_graphql::GraphqlParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub webhooks: sc_webhooks::WebhookParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub exporter: sc_chain_exporter::ExporterParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub remote_db: sc_remote_db::RemoteDbParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub randomness_beacon: sc_randomness_beacon::RandomnessBeaconParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub state_migration: crate::StateMigrationParams,
 }
 
 /// Possible subcommands of the main binary.
@@ -89,6 +165,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export decoded extrinsics, events and storage entries to CSV or Parquet files.
+	ExportData(sc_data_export::ExportDataCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -98,6 +177,15 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Export the BABE, GRANDPA and BEEFY aux data, for disaster recovery.
+	ExportConsensusState(sc_cli::ExportConsensusStateCmd),
+
+	/// Import the BABE, GRANDPA and BEEFY aux data exported by `export-consensus-state`.
+	ImportConsensusState(sc_cli::ImportConsensusStateCmd),
+
+	/// Report and verify the migration of the state to the `V1` state version.
+	CheckStateMigration(sc_cli::CheckStateMigrationCmd),
+
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
 }
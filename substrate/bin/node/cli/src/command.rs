@@ -26,7 +26,7 @@ use crate::{
 };
 use frame_benchmarking_cli::*;
 use kitchensink_runtime::{ExistentialDeposit, RuntimeApi};
-use node_primitives::Block;
+use node_primitives::{Balance, Block};
 use sc_cli::{Result, SubstrateCli};
 use sc_service::PartialComponents;
 use sp_keyring::Sr25519Keyring;
@@ -34,6 +34,13 @@ use sp_runtime::traits::HashingFor;
 
 use std::sync::Arc;
 
+/// The aux data of the consensus engines, exported and imported by the consensus state commands.
+const CONSENSUS_AUX_KEYS: &[sc_cli::ConsensusAuxKeys] = &[
+	("babe", sc_consensus_babe::aux_schema::AUX_KEYS),
+	("grandpa", sc_consensus_grandpa::AUX_KEYS),
+	("beefy", sc_consensus_beefy::AUX_KEYS),
+];
+
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
 		"Substrate Node".into()
@@ -83,7 +90,10 @@ pub fn run() -> Result<()> {
 
 	match &cli.subcommand {
 		None => {
-			let runner = cli.create_runner(&cli.run)?;
+			let crash_reports = !cli.crash_report.no_crash_reports;
+			let runner = cli.create_runner_with_logger_hook(&cli.run, |logger, _| {
+				logger.with_recent_logs(crash_reports);
+			})?;
 			runner.run_node_until_exit(|config| async move {
 				service::new_full(config, cli).map_err(sc_cli::Error::Service)
 			})
@@ -113,9 +123,14 @@ pub fn run() -> Result<()> {
 					},
 					BenchmarkCmd::Block(cmd) => {
 						// ensure that we keep the task manager alive
-						let partial = new_partial(&config, None)?;
+						let partial = new_partial(&config, None, None, None, None, Default::default())?;
 						cmd.run(partial.client)
 					},
+					BenchmarkCmd::Calibrate(cmd) => {
+						// ensure that we keep the task manager alive
+						let partial = new_partial(&config, None, None, None, None, Default::default())?;
+						cmd.run::<Block, _, Balance>(partial.client)
+					},
 					#[cfg(not(feature = "runtime-benchmarks"))]
 					BenchmarkCmd::Storage(_) => Err(
 						"Storage benchmarking can be enabled with `--features runtime-benchmarks`."
@@ -124,7 +139,7 @@ pub fn run() -> Result<()> {
 					#[cfg(feature = "runtime-benchmarks")]
 					BenchmarkCmd::Storage(cmd) => {
 						// ensure that we keep the task manager alive
-						let partial = new_partial(&config, None)?;
+						let partial = new_partial(&config, None, None, None, None, Default::default())?;
 						let db = partial.backend.expose_db();
 						let storage = partial.backend.expose_storage();
 
@@ -132,7 +147,7 @@ pub fn run() -> Result<()> {
 					},
 					BenchmarkCmd::Overhead(cmd) => {
 						// ensure that we keep the task manager alive
-						let partial = new_partial(&config, None)?;
+						let partial = new_partial(&config, None, None, None, None, Default::default())?;
 						let ext_builder = RemarkBuilder::new(partial.client.clone());
 
 						cmd.run(
@@ -146,7 +161,7 @@ pub fn run() -> Result<()> {
 					},
 					BenchmarkCmd::Extrinsic(cmd) => {
 						// ensure that we keep the task manager alive
-						let partial = service::new_partial(&config, None)?;
+						let partial = service::new_partial(&config, None, None, None, None, Default::default())?;
 						// Register the *Remark* and *TKA* builders.
 						let ext_factory = ExtrinsicFactory(vec![
 							Box::new(RemarkBuilder::new(partial.client.clone())),
@@ -181,29 +196,39 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, import_queue, .. } =
-					new_partial(&config, None)?;
+					new_partial(&config, None, None, None, None, Default::default())?;
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
 		Some(Subcommand::ExportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
-				let PartialComponents { client, task_manager, .. } = new_partial(&config, None)?;
+				let PartialComponents { client, task_manager, .. } =
+					new_partial(&config, None, None, None, None, Default::default())?;
 				Ok((cmd.run(client, config.database), task_manager))
 			})
 		},
 		Some(Subcommand::ExportState(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
-				let PartialComponents { client, task_manager, .. } = new_partial(&config, None)?;
+				let PartialComponents { client, task_manager, .. } =
+					new_partial(&config, None, None, None, None, Default::default())?;
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportData(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, .. } =
+					new_partial(&config, None, None, None, None, Default::default())?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, import_queue, .. } =
-					new_partial(&config, None)?;
+					new_partial(&config, None, None, None, None, Default::default())?;
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
@@ -215,7 +240,7 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, backend, .. } =
-					new_partial(&config, None)?;
+					new_partial(&config, None, None, None, None, Default::default())?;
 				let aux_revert = Box::new(|client: Arc<FullClient>, backend, blocks| {
 					sc_consensus_babe::revert(client.clone(), backend, blocks)?;
 					sc_consensus_grandpa::revert(client, blocks)?;
@@ -224,6 +249,22 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, backend, Some(aux_revert)), task_manager))
 			})
 		},
+		Some(Subcommand::ExportConsensusState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(&config, CONSENSUS_AUX_KEYS))
+		},
+		Some(Subcommand::ImportConsensusState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(&config, CONSENSUS_AUX_KEYS))
+		},
+		Some(Subcommand::CheckStateMigration(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, backend, .. } =
+					new_partial(&config, None, None, None, None, Default::default())?;
+				Ok((cmd.run(client, backend), task_manager))
+			})
+		},
 		Some(Subcommand::ChainInfo(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run::<Block>(&config))
@@ -38,8 +38,12 @@ mod cli;
 #[cfg(feature = "cli")]
 mod command;
 pub mod service;
+#[cfg(feature = "cli")]
+mod state_migration;
 
 #[cfg(feature = "cli")]
 pub use cli::*;
 #[cfg(feature = "cli")]
 pub use command::*;
+#[cfg(feature = "cli")]
+pub use state_migration::StateMigrationParams;
@@ -32,7 +32,7 @@ use frame_system_rpc_runtime_api::AccountNonceApi;
 use futures::prelude::*;
 use kitchensink_runtime::RuntimeApi;
 use node_primitives::Block;
-use sc_client_api::{Backend, BlockBackend};
+use sc_client_api::{Backend, BlockBackend, HeaderBackend};
 use sc_consensus_babe::{self, SlotProportion};
 use sc_network::{
 	event::Event, service::traits::NetworkService, NetworkBackend, NetworkEventStream,
@@ -50,14 +50,22 @@ use std::{path::Path, sync::Arc};
 
 /// Host functions required for kitchensink runtime and Substrate node.
 #[cfg(not(feature = "runtime-benchmarks"))]
-pub type HostFunctions =
-	(sp_io::SubstrateHostFunctions, sp_statement_store::runtime_api::HostFunctions);
+pub type HostFunctions = (
+	sp_io::SubstrateHostFunctions,
+	sp_statement_store::runtime_api::HostFunctions,
+	sp_crypto_zk::bn254::host_calls::HostFunctions,
+	sp_crypto_zk::bls12_381::host_calls::HostFunctions,
+	sp_content_store::HostFunctions,
+);
 
 /// Host functions required for kitchensink runtime and Substrate node.
 #[cfg(feature = "runtime-benchmarks")]
 pub type HostFunctions = (
 	sp_io::SubstrateHostFunctions,
 	sp_statement_store::runtime_api::HostFunctions,
+	sp_crypto_zk::bn254::host_calls::HostFunctions,
+	sp_crypto_zk::bls12_381::host_calls::HostFunctions,
+	sp_content_store::HostFunctions,
 	frame_benchmarking::benchmarking::HostFunctions,
 );
 
@@ -101,8 +109,6 @@ pub fn fetch_nonce(client: &FullClient, account: sp_core::sr25519::Pair) -> u32
 ///
 /// The transaction will be signed by `sender`. If `nonce` is `None` it will be fetched from the
 /// state of the best block.
-///
-/// Note: Should only be used for tests.
 pub fn create_extrinsic(
 	client: &FullClient,
 	sender: sp_core::sr25519::Pair,
@@ -120,31 +126,36 @@ pub fn create_extrinsic(
 		.map(|c| c / 2)
 		.unwrap_or(2) as u64;
 	let tip = 0;
-	let tx_ext: kitchensink_runtime::TxExtension =
-		(
-			frame_system::CheckNonZeroSender::<kitchensink_runtime::Runtime>::new(),
-			frame_system::CheckSpecVersion::<kitchensink_runtime::Runtime>::new(),
-			frame_system::CheckTxVersion::<kitchensink_runtime::Runtime>::new(),
-			frame_system::CheckGenesis::<kitchensink_runtime::Runtime>::new(),
-			frame_system::CheckEra::<kitchensink_runtime::Runtime>::from(generic::Era::mortal(
-				period,
-				best_block.saturated_into(),
-			)),
-			frame_system::CheckNonce::<kitchensink_runtime::Runtime>::from(nonce),
-			frame_system::CheckWeight::<kitchensink_runtime::Runtime>::new(),
+	let tx_ext: kitchensink_runtime::TxExtension = (
+		pallet_account_abstraction::AuthorizeAccount::new_disabled(),
+		frame_system::CheckNonZeroSender::<kitchensink_runtime::Runtime>::new(),
+		frame_system::CheckSpecVersion::<kitchensink_runtime::Runtime>::new(),
+		frame_system::CheckTxVersion::<kitchensink_runtime::Runtime>::new(),
+		frame_system::CheckGenesis::<kitchensink_runtime::Runtime>::new(),
+		frame_system::CheckEra::<kitchensink_runtime::Runtime>::from(generic::Era::mortal(
+			period,
+			best_block.saturated_into(),
+		)),
+		pallet_nonceless::CheckNonceOrTag::from(frame_system::CheckNonce::<
+			kitchensink_runtime::Runtime,
+		>::from(nonce)),
+		frame_system::CheckWeight::<kitchensink_runtime::Runtime>::new(),
+		pallet_sponsored_payment::Sponsored::from(
 			pallet_skip_feeless_payment::SkipCheckIfFeeless::from(
 				pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<
 					kitchensink_runtime::Runtime,
 				>::from(tip, None),
 			),
-			frame_metadata_hash_extension::CheckMetadataHash::new(false),
-			frame_system::WeightReclaim::<kitchensink_runtime::Runtime>::new(),
-		);
+		),
+		frame_metadata_hash_extension::CheckMetadataHash::new(false),
+		frame_system::WeightReclaim::<kitchensink_runtime::Runtime>::new(),
+	);
 
 	let raw_payload = kitchensink_runtime::SignedPayload::from_raw(
 		function.clone(),
 		tx_ext.clone(),
 		(
+			(),
 			(),
 			kitchensink_runtime::VERSION.spec_version,
 			kitchensink_runtime::VERSION.transaction_version,
@@ -168,10 +179,37 @@ pub fn create_extrinsic(
 	.into()
 }
 
+/// The BABE pre-runtime digest of blocks built by the `dev_dryBuildBlock` RPC.
+///
+/// The runtime doesn't check the slot claim, so a secondary claim of the first authority is used.
+fn dry_build_pre_digest(
+	inherent_data: &sp_inherents::InherentData,
+) -> Result<sp_runtime::Digest, String> {
+	use sp_consensus_babe::{
+		digests::{CompatibleDigestItem, PreDigest, SecondaryPlainPreDigest},
+		inherents::BabeInherentData,
+	};
+
+	let slot = inherent_data
+		.babe_inherent_data()
+		.map_err(|e| e.to_string())?
+		.ok_or("Missing BABE inherent data")?;
+	let pre_digest =
+		PreDigest::SecondaryPlain(SecondaryPlainPreDigest { authority_index: 0, slot });
+	Ok(sp_runtime::Digest { logs: vec![sp_runtime::DigestItem::babe_pre_digest(pre_digest)] })
+}
+
 /// Creates a new partial node.
+///
+/// The `block_import_layers` are stacked on top of the BABE block import, see
+/// [`sc_service::BlockImportLayers`].
 pub fn new_partial(
 	config: &Configuration,
 	mixnet_config: Option<&sc_mixnet::Config>,
+	grandpa_justifications: Option<&grandpa::JustificationPolicyParams>,
+	backup: Option<&sc_cli::BackupParams>,
+	statement_store: Option<&sc_statement_store::StatementStoreParams>,
+	block_import_layers: sc_service::BlockImportLayers<Block>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -247,6 +285,11 @@ pub fn new_partial(
 		select_chain.clone(),
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
+	let grandpa_block_import = match grandpa_justifications {
+		Some(params) => grandpa_block_import
+			.with_justification_request_policy(params.grandpa_justification_requests),
+		None => grandpa_block_import,
+	};
 	let justification_import = grandpa_block_import.clone();
 
 	let (beefy_block_import, beefy_voter_links, beefy_rpc_links) =
@@ -267,7 +310,7 @@ pub fn new_partial(
 	let (import_queue, babe_worker_handle) =
 		sc_consensus_babe::import_queue(sc_consensus_babe::ImportQueueParams {
 			link: babe_link.clone(),
-			block_import: block_import.clone(),
+			block_import: block_import_layers.apply(block_import.clone()),
 			justification_import: Some(Box::new(justification_import)),
 			client: client.clone(),
 			select_chain: select_chain.clone(),
@@ -286,13 +329,14 @@ pub fn new_partial(
 			registry: config.prometheus_registry(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
+			import_queue_config: config.import_queue,
 		})?;
 
 	let import_setup = (block_import, grandpa_link, babe_link, beefy_voter_links);
 
 	let statement_store = sc_statement_store::Store::new_shared(
 		&config.data_path,
-		Default::default(),
+		statement_store.map(|params| params.options()).unwrap_or_default(),
 		client.clone(),
 		keystore_container.local_keystore(),
 		config.prometheus_registry(),
@@ -301,6 +345,8 @@ pub fn new_partial(
 	.map_err(|e| ServiceError::Other(format!("Statement store error: {:?}", e)))?;
 
 	let (mixnet_api, mixnet_api_backend) = mixnet_config.map(sc_mixnet::Api::new).unzip();
+	let route_author_submissions =
+		mixnet_config.is_some_and(|config| config.substrate.route_author_submissions);
 
 	let (rpc_extensions_builder, rpc_setup) = {
 		let (_, grandpa_link, _, _) = &import_setup;
@@ -322,7 +368,9 @@ pub fn new_partial(
 		let chain_spec = config.chain_spec.cloned_box();
 
 		let rpc_backend = backend.clone();
+		let backup_settings = backup.map(|params| params.settings()).unwrap_or_default();
 		let rpc_statement_store = statement_store.clone();
+		let spawn_handle = task_manager.spawn_handle();
 		let rpc_extensions_builder =
 			move |subscription_executor: node_rpc::SubscriptionTaskExecutor| {
 				let deps = node_rpc::FullDeps {
@@ -348,14 +396,44 @@ pub fn new_partial(
 						beefy_best_block_stream: beefy_rpc_links
 							.from_voter_best_beefy_stream
 							.clone(),
-						subscription_executor,
+						subscription_executor: subscription_executor.clone(),
 					},
 					statement_store: rpc_statement_store.clone(),
 					backend: rpc_backend.clone(),
-					mixnet_api: mixnet_api.as_ref().cloned(),
+					backup: backup_settings.clone(),
+					mixnet: mixnet_api.as_ref().map(|api| node_rpc::MixnetDeps {
+						api: api.clone(),
+						route_author_submissions,
+						subscription_executor,
+					}),
 				};
 
-				node_rpc::create_full(deps).map_err(Into::into)
+				let mut io = node_rpc::create_full(deps)?;
+
+				let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+					spawn_handle.clone(),
+					client.clone(),
+					pool.clone(),
+					None,
+					None,
+				);
+				let dry_build = sc_rpc::dev::DryBuild::<Block, _, _, _>::new(
+					client.clone(),
+					proposer_factory,
+					move |_, ()| async move {
+						let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+						let slot =
+							sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+								*timestamp,
+								slot_duration,
+							);
+						Ok((slot, timestamp))
+					},
+				)
+				.with_pre_digest(Box::new(dry_build_pre_digest));
+				io.merge(dry_build.into_rpc()).map_err(|e| ServiceError::Application(e.into()))?;
+
+				Ok(io)
 			};
 
 		(rpc_extensions_builder, shared_voter_state2)
@@ -386,6 +464,8 @@ pub struct NewFullBase {
 	pub task_manager: TaskManager,
 	/// The client instance of the node.
 	pub client: Arc<FullClient>,
+	/// The database backend of the node.
+	pub backend: Arc<FullBackend>,
 	/// The networking service of the node.
 	pub network: Arc<dyn NetworkService>,
 	/// The syncing service of the node.
@@ -398,9 +478,19 @@ pub struct NewFullBase {
 
 /// Creates a full service from the configuration.
 pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
-	config: Configuration,
+	mut config: Configuration,
 	mixnet_config: Option<sc_mixnet::Config>,
 	disable_hardware_benchmarks: bool,
+	signing_lease: Option<&sc_consensus_slashing_protection::SigningLeaseParams>,
+	propagation_latency: Option<&sc_network_propagation::PropagationLatencyParams>,
+	grandpa_justifications: Option<&grandpa::JustificationPolicyParams>,
+	backup: Option<&sc_cli::BackupParams>,
+	replica: Option<&sc_replica::ReplicaParams>,
+	content_store: Option<&sc_content_store::ContentStoreParams>,
+	clock_health: Option<&sc_consensus_slots::ClockHealthParams>,
+	statement_store: Option<&sc_statement_store::StatementStoreParams>,
+	randomness_beacon: Option<&sc_randomness_beacon::RandomnessBeaconParams>,
+	block_import_layers: sc_service::BlockImportLayers<Block>,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<
 			Block,
@@ -410,6 +500,19 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		&sc_consensus_babe::BabeLink<Block>,
 	),
 ) -> Result<NewFullBase, ServiceError> {
+	if replica.is_some_and(|replica| replica.replica_upstream.is_some()) {
+		if config.role.is_authority() {
+			return Err(ServiceError::Other("Read replicas can't run as authorities".to_string()))
+		}
+		// The blocks streamed to a replica bypass BABE and GRANDPA, whose data would be missing to
+		// import blocks from the network or to follow the finality, keep the replica out of both.
+		config.network.boot_nodes.clear();
+		config.network.default_peers_set.reserved_nodes.clear();
+		config.network.default_peers_set.non_reserved_mode =
+			sc_network::config::NonReservedPeerMode::Deny;
+		config.disable_grandpa = true;
+	}
+
 	let is_offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
 	let role = config.role;
 	let force_authoring = config.force_authoring;
@@ -419,6 +522,14 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let enable_offchain_worker = config.offchain_worker.enabled;
+	let slashing_protection = if role.is_authority() {
+		let path = config.base_path.config_dir(config.chain_spec.id()).join("slashing-protection");
+		let store = sc_consensus_slashing_protection::SlashingProtection::open(&path)
+			.map_err(|e| ServiceError::Other(format!("Slashing protection error: {e}")))?;
+		Some(store)
+	} else {
+		None
+	};
 
 	let hwbench = (!disable_hardware_benchmarks)
 		.then(|| {
@@ -439,7 +550,27 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		transaction_pool,
 		other:
 			(rpc_builder, import_setup, rpc_setup, mut telemetry, statement_store, mixnet_api_backend),
-	} = new_partial(&config, mixnet_config.as_ref())?;
+	} = new_partial(
+		&config,
+		mixnet_config.as_ref(),
+		grandpa_justifications,
+		backup,
+		statement_store,
+		block_import_layers,
+	)?;
+
+	let slashing_protection = match (slashing_protection, signing_lease) {
+		(Some(store), Some(signing_lease)) => {
+			let lease = signing_lease
+				.start(&name, &task_manager.spawn_essential_handle())
+				.map_err(|e| ServiceError::Other(format!("Failed to start signing lease: {e}")))?;
+			Some(match lease {
+				Some(lease) => store.with_lease(lease),
+				None => store,
+			})
+		},
+		(store, _) => store,
+	};
 
 	let metrics = N::register_notification_metrics(
 		config.prometheus_config.as_ref().map(|cfg| &cfg.registry),
@@ -496,6 +627,20 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		);
 	net_config.add_notification_protocol(statement_config);
 
+	let propagation_handler_proto = propagation_latency
+		.filter(|params| params.block_propagation_latency)
+		.map(|_| {
+			let (proto, protocol_config) =
+				sc_network_propagation::PropagationHandlerPrototype::new::<_, _, N>(
+					genesis_hash,
+					config.chain_spec.fork_id(),
+					metrics.clone(),
+					Arc::clone(&peer_store_handle),
+				);
+			net_config.add_notification_protocol(protocol_config);
+			proto
+		});
+
 	let mixnet_protocol_name =
 		sc_mixnet::protocol_name(genesis_hash.as_ref(), config.chain_spec.fork_id());
 	let mixnet_notification_service = mixnet_config.as_ref().map(|mixnet_config| {
@@ -545,6 +690,23 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		task_manager.spawn_handle().spawn("mixnet", None, mixnet);
 	}
 
+	// The sessions of the kitchensink runtime last one BABE epoch.
+	let session_provider = {
+		let client = client.clone();
+		let epoch_length = import_setup.2.config().epoch_length;
+		move |number: u64| {
+			let slot = |number: u64| {
+				let hash = client.block_hash(number.saturated_into()).ok()??;
+				let header = client.header(hash).ok()??;
+				sc_consensus_babe::find_pre_digest::<Block>(&header)
+					.ok()
+					.map(|digest| *digest.slot())
+			};
+			// The first epoch starts at the slot of the first block.
+			Some(slot(number)?.saturating_sub(slot(1)?) / epoch_length)
+		}
+	};
+
 	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -558,6 +720,9 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		tx_handler_controller,
 		sync_service: sync_service.clone(),
 		telemetry: telemetry.as_mut(),
+		informant_data_providers: Vec::new(),
+		informant_relay_chain: None,
+		informant_session_provider: Some(Arc::new(session_provider)),
 	})?;
 
 	if let Some(hwbench) = hwbench {
@@ -597,6 +762,65 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 
 		let client_clone = client.clone();
 		let slot_duration = babe_link.config().slot_duration();
+		let clock_health = clock_health.and_then(|params| params.config()).map(|config| {
+			let health = sc_consensus_slots::ClockHealth::new(config, slot_duration.as_duration());
+			task_manager.spawn_handle().spawn(
+				"clock-health-monitor",
+				None,
+				sc_consensus_slots::ClockHealthMonitor::new(health.clone()).run(),
+			);
+			health
+		});
+		let slot_leakage_analyzer = sc_consensus_slots::SlotLeakageAnalyzer::new();
+		task_manager.spawn_handle().spawn(
+			"slot-leakage-report",
+			None,
+			slot_leakage_analyzer.clone().run::<Block, _>(
+				client.clone(),
+				|header| sc_consensus_babe::find_pre_digest::<Block>(header).ok().map(|d| d.slot()),
+				babe_link.config().epoch_length,
+			),
+		);
+		let pulses = match randomness_beacon {
+			Some(params) => sc_randomness_beacon::RandomnessBeacon::spawn(
+				params.clone(),
+				prometheus_registry.as_ref(),
+				&task_manager.spawn_handle(),
+			)
+			.map_err(ServiceError::Other)?,
+			None => None,
+		};
+		// Runtime upgrades adding or removing inherents register their providers for the spec
+		// versions of the runtimes using them.
+		let inherent_data_providers = sc_consensus::InherentDataProviderRegistry::new(
+			client.clone(),
+			move |_, ()| async move {
+				let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+				let slot =
+					sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+						*timestamp,
+						slot_duration,
+					);
+
+				Ok((slot, timestamp))
+			},
+		);
+		inherent_data_providers.register(.., move |parent, ()| {
+			let client_clone = client_clone.clone();
+			async move {
+				let storage_proof = sp_transaction_storage_proof::registration::new_data_provider(
+					&*client_clone,
+					&parent,
+				)?;
+				Ok(storage_proof)
+			}
+		});
+		inherent_data_providers.register(.., move |_, ()| {
+			let pulse = pulses.as_ref().and_then(|pulses| pulses.latest());
+			async move { Ok(sp_randomness_beacon::InherentDataProvider::new(pulse)) }
+		});
+
 		let babe_config = sc_consensus_babe::BabeParams {
 			keystore: keystore_container.keystore(),
 			client: client.clone(),
@@ -605,32 +829,16 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 			block_import,
 			sync_oracle: sync_service.clone(),
 			justification_sync_link: sync_service.clone(),
-			create_inherent_data_providers: move |parent, ()| {
-				let client_clone = client_clone.clone();
-				async move {
-					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
-
-					let slot =
-						sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-							*timestamp,
-							slot_duration,
-						);
-
-					let storage_proof =
-						sp_transaction_storage_proof::registration::new_data_provider(
-							&*client_clone,
-							&parent,
-						)?;
-
-					Ok((slot, timestamp, storage_proof))
-				}
-			},
+			create_inherent_data_providers: inherent_data_providers,
 			force_authoring,
 			backoff_authoring_blocks,
 			babe_link,
 			block_proposal_slot_portion: SlotProportion::new(0.5),
 			max_block_proposal_slot_portion: None,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
+			slashing_protection: slashing_protection.clone(),
+			clock_health,
+			slot_leakage_analyzer: Some(slot_leakage_analyzer),
 		};
 
 		let babe = sc_consensus_babe::start_babe(babe_config)?;
@@ -698,6 +906,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		links: beefy_links,
 		on_demand_justifications_handler: beefy_on_demand_justifications_handler,
 		is_authority: role.is_authority(),
+		slashing_protection: slashing_protection.clone(),
 	};
 
 	let beefy_gadget = beefy::start_beefy_gadget::<_, _, _, _, _, _, _, _>(beefy_params);
@@ -722,13 +931,16 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	let grandpa_config = grandpa::Config {
 		// FIXME #1578 make this available through chainspec
 		gossip_duration: std::time::Duration::from_millis(333),
-		justification_generation_period: GRANDPA_JUSTIFICATION_PERIOD,
+		justification_policy: grandpa_justifications
+			.and_then(|params| params.grandpa_justification_serving)
+			.unwrap_or(grandpa::JustificationPolicy::Interval(GRANDPA_JUSTIFICATION_PERIOD)),
 		name: Some(name),
 		observer_enabled: false,
 		keystore,
 		local_role: role,
 		telemetry: telemetry.as_ref().map(|x| x.handle()),
 		protocol_name: grandpa_protocol_name,
+		slashing_protection,
 	};
 
 	if enable_grandpa {
@@ -780,7 +992,62 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		statement_handler.run(),
 	);
 
+	// Spawn block propagation latency protocol worker
+	if let Some(propagation_handler_proto) = propagation_handler_proto {
+		let propagation_handler = propagation_handler_proto.build(
+			network.clone(),
+			sync_service.clone(),
+			&*client,
+			prometheus_registry.as_ref(),
+		)?;
+		task_manager.spawn_handle().spawn(
+			"network-block-propagation-handler",
+			Some("networking"),
+			propagation_handler.run(),
+		);
+	}
+
+	// Spawn the streaming of blocks to or from read replicas
+	if let Some(replica) = replica {
+		if let Some(listen_addr) = replica.replica_listen_addr {
+			let upstream = sc_replica::ReplicaUpstream::new(
+				client.clone(),
+				listen_addr,
+				replica.secret()?,
+				task_manager.spawn_handle(),
+				prometheus_registry.as_ref(),
+			)?;
+			task_manager
+				.spawn_handle()
+				.spawn("replica-upstream", Some("replica"), upstream.run());
+		}
+		if let Some(upstream) = &replica.replica_upstream {
+			let downstream = sc_replica::ReplicaDownstream::<_, FullBackend, _>::new(
+				client.clone(),
+				upstream.clone(),
+				replica.secret()?,
+				prometheus_registry.as_ref(),
+			)?;
+			task_manager.spawn_handle().spawn(
+				"replica-downstream",
+				Some("replica"),
+				downstream.run(),
+			);
+		}
+	}
+
 	if enable_offchain_worker {
+		// The content store is only accessed by the offchain workers.
+		let content_store = match content_store.map(|params| params.backends()).transpose()? {
+			Some(backends) if !backends.is_empty() => {
+				let (service, store) = sc_content_store::ContentStoreService::new(backends);
+				task_manager
+					.spawn_handle()
+					.spawn("content-store", "offchain-work", service.run());
+				Some(store)
+			},
+			_ => None,
+		};
 		let offchain_workers =
 			sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
 				runtime_api_provider: client.clone(),
@@ -793,7 +1060,13 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 				is_validator: role.is_authority(),
 				enable_http_requests: true,
 				custom_extensions: move |_| {
-					vec![Box::new(statement_store.clone().as_statement_store_ext()) as Box<_>]
+					let mut extensions =
+						vec![Box::new(statement_store.clone().as_statement_store_ext()) as Box<_>];
+					if let Some(store) = &content_store {
+						let store = sc_content_store::ContentStoreExt::new(store.clone());
+						extensions.push(Box::new(store) as _);
+					}
+					extensions
 				},
 			})?;
 		task_manager.spawn_handle().spawn(
@@ -806,6 +1079,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	Ok(NewFullBase {
 		task_manager,
 		client,
+		backend,
 		network,
 		sync: sync_service,
 		transaction_pool,
@@ -814,33 +1088,170 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
+pub fn new_full(mut config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let mixnet_config = cli.mixnet_params.config(config.role.is_authority());
-	let database_path = config.database.path().map(Path::to_path_buf);
-
-	let task_manager = match config.network.network_backend {
-		sc_network::config::NetworkBackendType::Libp2p => {
-			let task_manager = new_full_base::<sc_network::NetworkWorker<_, _>>(
-				config,
-				mixnet_config,
-				cli.no_hardware_benchmarks,
-				|_, _| (),
-			)
-			.map(|NewFullBase { task_manager, .. }| task_manager)?;
-			task_manager
-		},
-		sc_network::config::NetworkBackendType::Litep2p => {
-			let task_manager = new_full_base::<sc_network::Litep2pNetworkBackend>(
-				config,
-				mixnet_config,
-				cli.no_hardware_benchmarks,
-				|_, _| (),
-			)
-			.map(|NewFullBase { task_manager, .. }| task_manager)?;
-			task_manager
+	let remote_db_replica = match cli.remote_db.replica_config() {
+		Some(replica_config) => {
+			if config.role.is_authority() {
+				return Err(ServiceError::Other(
+					"Read replicas can't run as authorities".to_string(),
+				))
+			}
+			let database = sc_remote_db::RemoteDatabase::connect(replica_config)
+				.map_err(|e| ServiceError::Application(e.into()))?;
+			config.database = database.source();
+			// The replica doesn't import blocks, it serves the finalized state of the primary.
+			config.network.boot_nodes.clear();
+			config.network.default_peers_set.reserved_nodes.clear();
+			config.network.default_peers_set.non_reserved_mode =
+				sc_network::config::NonReservedPeerMode::Deny;
+			config.disable_grandpa = true;
+			Some(database)
 		},
+		None => None,
+	};
+	let database_path = config.database.path().map(Path::to_path_buf);
+	let prometheus_registry = config.prometheus_registry().cloned();
+	let impl_version = config.impl_version.clone();
+	let is_authority = config.role.is_authority();
+	let crash_reporter = sc_crash_report::CrashReporter::new(cli.crash_report, &config)?;
+	let block_time =
+		std::time::Duration::from_millis(kitchensink_runtime::constants::time::MILLISECS_PER_BLOCK);
+	if let Some(state_pruning) = cli.state_regeneration.state_pruning(block_time) {
+		config.state_pruning = Some(state_pruning);
+	}
+	let executor: RuntimeExecutor = sc_service::new_wasm_executor(&config.executor);
+	let remote_db_primary = match cli.remote_db.remote_db_listen {
+		Some(addr) =>
+			Some((sc_remote_db::PrimaryDatabase::open::<Block>(&mut config.database)?, addr)),
+		None => None,
 	};
 
+	let NewFullBase { task_manager, client, backend, transaction_pool, .. } =
+		match config.network.network_backend {
+			sc_network::config::NetworkBackendType::Libp2p =>
+				new_full_base::<sc_network::NetworkWorker<_, _>>(
+					config,
+					mixnet_config,
+					cli.no_hardware_benchmarks,
+					Some(&cli.signing_lease),
+					Some(&cli.propagation_latency),
+					Some(&cli.grandpa_justifications),
+					Some(&cli.backup),
+					Some(&cli.replica),
+					Some(&cli.content_store),
+					Some(&cli.clock_health),
+					Some(&cli.statement_store),
+					Some(&cli.randomness_beacon),
+					Default::default(),
+					|_, _| (),
+				)?,
+			sc_network::config::NetworkBackendType::Litep2p =>
+				new_full_base::<sc_network::Litep2pNetworkBackend>(
+					config,
+					mixnet_config,
+					cli.no_hardware_benchmarks,
+					Some(&cli.signing_lease),
+					Some(&cli.propagation_latency),
+					Some(&cli.grandpa_justifications),
+					Some(&cli.backup),
+					Some(&cli.replica),
+					Some(&cli.content_store),
+					Some(&cli.clock_health),
+					Some(&cli.statement_store),
+					Some(&cli.randomness_beacon),
+					Default::default(),
+					|_, _| (),
+				)?,
+		};
+
+	if let Some((database, addr)) = remote_db_primary {
+		task_manager.spawn_handle().spawn("remote-db-server", None, async move {
+			match sc_remote_db::RemoteDbServer::bind(database, addr).await {
+				Ok(server) => server.run().await,
+				Err(e) => log::error!("Failed to serve the database on {addr}: {e}"),
+			}
+		});
+	}
+
+	if let Some(database) = remote_db_replica {
+		task_manager.spawn_handle().spawn(
+			"remote-db-follow",
+			None,
+			sc_remote_db::follow_primary(backend.clone(), database.commits()),
+		);
+	}
+
+	crate::state_migration::spawn(
+		&cli.state_migration,
+		client.clone(),
+		backend.clone(),
+		transaction_pool.clone(),
+		&task_manager.spawn_handle(),
+	)?;
+
+	if let Some(addr) = cli.grpc.listen_addr() {
+		let server = sc_rpc_grpc::start_server(addr, client.clone(), transaction_pool);
+		task_manager.spawn_handle().spawn("grpc-server", None, async move {
+			if let Err(e) = server.await {
+				log::error!("gRPC server failed: {e}");
+			}
+		});
+	}
+
+	if let Some(addr) = cli.graphql.listen_addr() {
+		let server = sc_graphql::GraphqlServer::new(client.clone());
+		task_manager.spawn_handle().spawn("graphql-server", None, async move {
+			if let Err(e) = server.run(addr).await {
+				log::error!("GraphQL server failed: {e}");
+			}
+		});
+	}
+
+	if let Some(path) = &cli.webhooks.webhooks_config {
+		let config = sc_webhooks::WebhooksConfig::load(path).map_err(ServiceError::Other)?;
+		let webhooks =
+			sc_webhooks::WebhookService::new(client.clone(), config, &task_manager.spawn_handle())
+				.map_err(ServiceError::Other)?;
+		task_manager.spawn_handle().spawn("webhooks", None, webhooks.run());
+	}
+
+	if let Some(url) = &cli.exporter.exporter {
+		let broker = sc_chain_exporter::BrokerUrl::parse(url).map_err(ServiceError::Other)?;
+		let client = client.clone();
+		let params = cli.exporter.clone();
+		task_manager.spawn_handle().spawn("chain-exporter", None, async move {
+			match broker.connect().await {
+				Ok(publisher) =>
+					sc_chain_exporter::ChainExporter::new(
+						client,
+						publisher,
+						&params.exporter_topic_prefix,
+						params.exporter_encoding,
+					)
+					.run()
+					.await,
+				Err(e) => log::error!("Failed to connect the chain exporter: {e}"),
+			}
+		});
+	}
+
+	if let Some(crash_reporter) = crash_reporter {
+		crash_reporter.install(client, &task_manager.spawn_handle());
+	}
+
+	if let Some(database_path) = &database_path {
+		sc_state_regeneration::StateRegeneration::spawn(
+			cli.state_regeneration,
+			&backend,
+			executor,
+			database_path.with_file_name("state-checkpoints"),
+			prometheus_registry.as_ref(),
+			&task_manager.spawn_handle(),
+		)
+		.map_err(|e| ServiceError::Application(e.into()))?;
+	}
+
 	if let Some(database_path) = database_path {
 		sc_storage_monitor::StorageMonitorService::try_spawn(
 			cli.storage_monitor,
@@ -850,6 +1261,21 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 		.map_err(|e| ServiceError::Application(e.into()))?;
 	}
 
+	sc_state_scrubber::StateScrubber::spawn(
+		cli.state_scrubber,
+		&backend,
+		prometheus_registry.as_ref(),
+		&task_manager.spawn_handle(),
+	)?;
+
+	sc_self_update::SelfUpdater::try_spawn(
+		cli.self_update,
+		&impl_version,
+		is_authority,
+		&task_manager.spawn_essential_handle(),
+	)
+	.map_err(|e| ServiceError::Application(e.into()))?;
+
 	Ok(task_manager)
 }
 
@@ -921,6 +1347,16 @@ mod tests {
 						config,
 						None,
 						false,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						Default::default(),
 						|block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 						 babe_link: &sc_consensus_babe::BabeLink<Block>| {
 							setup_handles = Some((block_import.clone(), babe_link.clone()));
@@ -1052,19 +1488,25 @@ mod tests {
 					value: amount,
 				});
 
+				let authorize_account =
+					pallet_account_abstraction::AuthorizeAccount::new_disabled();
 				let check_non_zero_sender = frame_system::CheckNonZeroSender::new();
 				let check_spec_version = frame_system::CheckSpecVersion::new();
 				let check_tx_version = frame_system::CheckTxVersion::new();
 				let check_genesis = frame_system::CheckGenesis::new();
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
-				let check_nonce = frame_system::CheckNonce::from(index);
+				let check_nonce =
+					pallet_nonceless::CheckNonceOrTag::from(frame_system::CheckNonce::from(index));
 				let check_weight = frame_system::CheckWeight::new();
-				let tx_payment = pallet_skip_feeless_payment::SkipCheckIfFeeless::from(
-					pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::from(0, None),
+				let tx_payment = pallet_sponsored_payment::Sponsored::from(
+					pallet_skip_feeless_payment::SkipCheckIfFeeless::from(
+						pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::from(0, None),
+					),
 				);
 				let weight_reclaim = frame_system::WeightReclaim::new();
 				let metadata_hash = frame_metadata_hash_extension::CheckMetadataHash::new(false);
 				let tx_ext: TxExtension = (
+					authorize_account,
 					check_non_zero_sender,
 					check_spec_version,
 					check_tx_version,
@@ -1080,6 +1522,7 @@ mod tests {
 					function,
 					tx_ext,
 					(
+						(),
 						(),
 						spec_version,
 						transaction_version,
@@ -1122,6 +1565,16 @@ mod tests {
 						config,
 						None,
 						false,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						None,
+						Default::default(),
 						|_, _| (),
 					)?;
 				Ok(sc_service_test::TestNetComponents::new(
@@ -0,0 +1,202 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Migration of the state to the `V1` state version, driven by the node.
+//!
+//! Migrating a value changes the state root, so the migration is done on chain by the state trie
+//! migration pallet. The node finds the values still stored as in the `V0` state version and
+//! migrates them with the signed `migrate_custom_top` and `migrate_custom_child` calls of the
+//! pallet, one batch per block. Once the whole state is migrated, it's verified to be intact.
+
+use crate::service::{create_extrinsic, FullClient, TransactionPool};
+use frame_system_rpc_runtime_api::AccountNonceApi;
+use futures::prelude::*;
+use kitchensink_runtime::Runtime;
+use node_primitives::Block;
+use polkadot_sdk::*;
+use sc_client_api::BlockchainEvents;
+use sc_service::{
+	chain_ops::{check_state_migration, next_state_migration_batch, StateMigrationBatch},
+	error::Error as ServiceError,
+	SpawnTaskHandle, TFullBackend,
+};
+use sc_transaction_pool_api::{TransactionPool as _, TransactionSource};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{crypto::Pair as _, sr25519};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	OpaqueExtrinsic, SaturatedConversion,
+};
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "state-migration";
+
+/// Number of blocks after which a migration transaction which isn't included is submitted again.
+const RESUBMIT_AFTER: u32 = 64;
+
+/// Parameters of the migration of the state to the `V1` state version.
+#[derive(Debug, Clone, clap::Args)]
+pub struct StateMigrationParams {
+	/// Migrate the state to the `V1` state version, signing the migration transactions with the
+	/// account of the given secret URI.
+	///
+	/// The values still stored as in the `V0` state version are migrated with the
+	/// `migrate_custom_top` and `migrate_custom_child` calls of the state trie migration pallet,
+	/// one batch per block. The account pays the fees of the calls and must be able to hold their
+	/// deposit.
+	#[arg(long, value_name = "SECRET_URI")]
+	pub state_migration_signer: Option<String>,
+
+	/// Maximum number of values migrated by a transaction of the state migration.
+	#[arg(long, value_name = "COUNT", default_value_t = 256)]
+	pub state_migration_batch_size: usize,
+}
+
+/// A migration transaction waiting to be included.
+struct Submitted {
+	batch: StateMigrationBatch,
+	nonce: u32,
+	at: u32,
+}
+
+/// Spawn the migration of the state, if enabled by `params`.
+pub fn spawn(
+	params: &StateMigrationParams,
+	client: Arc<FullClient>,
+	backend: Arc<TFullBackend<Block>>,
+	transaction_pool: Arc<TransactionPool>,
+	spawn_handle: &SpawnTaskHandle,
+) -> Result<(), ServiceError> {
+	let Some(suri) = &params.state_migration_signer else { return Ok(()) };
+	let signer = sr25519::Pair::from_string(suri, None)
+		.map_err(|e| ServiceError::Other(format!("Invalid state migration signer: {e:?}")))?;
+	let batch_size = params.state_migration_batch_size;
+	spawn_handle.spawn(
+		"state-migration",
+		None,
+		run(client, backend, transaction_pool, signer, batch_size),
+	);
+	Ok(())
+}
+
+async fn run(
+	client: Arc<FullClient>,
+	backend: Arc<TFullBackend<Block>>,
+	transaction_pool: Arc<TransactionPool>,
+	signer: sr25519::Pair,
+	batch_size: usize,
+) {
+	let mut best_blocks = client
+		.import_notification_stream()
+		.filter(|block| future::ready(block.is_new_best));
+
+	let best_hash = client.chain_info().best_hash;
+	let remaining = match check_state_migration(&*backend, best_hash, false, |_| ()) {
+		Ok(status) => status.top.remaining + status.child.remaining,
+		Err(e) => {
+			log::error!(target: LOG_TARGET, "Failed to scan the state: {e}");
+			return
+		},
+	};
+	log::info!(target: LOG_TARGET, "{remaining} values to migrate to the V1 state version");
+
+	let mut migrated = 0;
+	let mut submitted: Option<Submitted> = None;
+	while let Some(block) = best_blocks.next().await {
+		let number = (*block.header.number()).saturated_into::<u32>();
+		let nonce = match client.runtime_api().account_nonce(block.hash, signer.public().into()) {
+			Ok(nonce) => nonce,
+			Err(e) => {
+				log::error!(target: LOG_TARGET, "Failed to read the nonce of the signer: {e}");
+				return
+			},
+		};
+
+		// Wait for the previous batch to be migrated, or to expire.
+		let from = match submitted.take() {
+			Some(previous) if nonce <= previous.nonce && number < previous.at + RESUBMIT_AFTER => {
+				submitted = Some(previous);
+				continue
+			},
+			Some(previous) => {
+				if nonce > previous.nonce {
+					migrated += previous.batch.keys.len() as u64;
+					log::info!(
+						target: LOG_TARGET,
+						"Migrated {migrated} of {remaining} values to the V1 state version",
+					);
+				}
+				Some(previous.batch)
+			},
+			None => None,
+		};
+
+		let batch =
+			match next_state_migration_batch(&*backend, block.hash, from.as_ref(), batch_size) {
+				Ok(Some(batch)) => batch,
+				Ok(None) => {
+					verify(&backend, block.hash);
+					return
+				},
+				Err(e) => {
+					log::error!(target: LOG_TARGET, "Failed to scan the state: {e}");
+					return
+				},
+			};
+
+		let size = batch.size.saturated_into();
+		let call = match &batch.child_info {
+			None => pallet_state_trie_migration::Call::<Runtime>::migrate_custom_top {
+				keys: batch.keys.clone(),
+				witness_size: size,
+			},
+			Some(child_info) =>
+				pallet_state_trie_migration::Call::<Runtime>::migrate_custom_child {
+					root: child_info.prefixed_storage_key().into_inner(),
+					child_keys: batch.keys.clone(),
+					total_size: size,
+				},
+		};
+		let extrinsic = create_extrinsic(&client, signer.clone(), call, Some(nonce));
+		let submission = transaction_pool
+			.submit_one(block.hash, TransactionSource::Local, OpaqueExtrinsic::from(extrinsic))
+			.await;
+		match submission {
+			Ok(_) => submitted = Some(Submitted { batch, nonce, at: number }),
+			Err(e) =>
+				log::warn!(target: LOG_TARGET, "Failed to submit a migration transaction: {e}"),
+		}
+	}
+}
+
+/// Verify that the state at `hash` is fully migrated and intact.
+fn verify(backend: &TFullBackend<Block>, hash: <Block as BlockT>::Hash) {
+	log::info!(target: LOG_TARGET, "Verifying the migrated state of block {hash:?}...");
+	match check_state_migration(backend, hash, true, |_| ()) {
+		Ok(status) if status.is_complete() => log::info!(
+			target: LOG_TARGET,
+			"State of block {hash:?} is fully migrated to the V1 state version and intact",
+		),
+		Ok(status) => log::error!(
+			target: LOG_TARGET,
+			"State of block {hash:?} is not fully migrated, {} values remain",
+			status.top.remaining + status.child.remaining,
+		),
+		Err(e) => log::error!(target: LOG_TARGET, "State of block {hash:?} is corrupted: {e}"),
+	}
+}
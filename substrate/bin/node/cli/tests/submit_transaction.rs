@@ -131,9 +131,9 @@ fn should_submit_signed_twice_from_the_same_account() {
 
 		// now check that the transaction nonces are not equal
 		let s = state.read();
-		fn nonce(tx: UncheckedExtrinsic) -> frame_system::CheckNonce<Runtime> {
+		fn nonce(tx: UncheckedExtrinsic) -> pallet_nonceless::CheckNonceOrTag<Runtime> {
 			let extra = tx.0.preamble.to_signed().unwrap().2;
-			extra.5
+			extra.6
 		}
 		let nonce1 = nonce(UncheckedExtrinsic::decode(&mut &*s.transactions[0]).unwrap());
 		let nonce2 = nonce(UncheckedExtrinsic::decode(&mut &*s.transactions[1]).unwrap());
@@ -180,9 +180,9 @@ fn should_submit_signed_twice_from_all_accounts() {
 
 		// now check that the transaction nonces are not equal
 		let s = state.read();
-		fn nonce(tx: UncheckedExtrinsic) -> frame_system::CheckNonce<Runtime> {
+		fn nonce(tx: UncheckedExtrinsic) -> pallet_nonceless::CheckNonceOrTag<Runtime> {
 			let extra = tx.0.preamble.to_signed().unwrap().2;
-			extra.5
+			extra.6
 		}
 		let nonce1 = nonce(UncheckedExtrinsic::decode(&mut &*s.transactions[0]).unwrap());
 		let nonce2 = nonce(UncheckedExtrinsic::decode(&mut &*s.transactions[1]).unwrap());
@@ -86,6 +86,16 @@ pub struct BeefyDeps<AuthorityId: AuthorityIdBound> {
 	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
+/// Dependencies for the mixnet
+pub struct MixnetDeps {
+	/// Mixnet API.
+	pub api: sc_mixnet::Api,
+	/// Route extrinsics submitted via `author_submitExtrinsic` through the mixnet.
+	pub route_author_submissions: bool,
+	/// Executor to drive the submissions routed through the mixnet.
+	pub subscription_executor: SubscriptionTaskExecutor,
+}
+
 /// Full client dependencies.
 pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	/// The client instance to use.
@@ -106,8 +116,10 @@ pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	pub statement_store: Arc<dyn sp_statement_store::StatementStore>,
 	/// The backend used by the node.
 	pub backend: Arc<B>,
-	/// Mixnet API.
-	pub mixnet_api: Option<sc_mixnet::Api>,
+	/// Settings of the backups taken through the `dev_backup` RPC.
+	pub backup: sc_client_api::BackupSettings,
+	/// Mixnet dependencies, if the mixnet is enabled.
+	pub mixnet: Option<MixnetDeps>,
 }
 
 /// Instantiate all Full RPC extensions.
@@ -122,7 +134,8 @@ pub fn create_full<C, P, SC, B, AuthorityId>(
 		beefy,
 		statement_store,
 		backend,
-		mixnet_api,
+		backup,
+		mixnet,
 	}: FullDeps<C, P, SC, B, AuthorityId>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
@@ -130,6 +143,7 @@ where
 		+ sc_client_api::BlockBackend<Block>
 		+ HeaderBackend<Block>
 		+ AuxStore
+		+ sc_client_api::BlockBlacklist<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
 		+ Sync
 		+ Send
@@ -139,9 +153,10 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: sp_api::Metadata<Block>,
 	P: TransactionPool + 'static,
 	SC: SelectChain<Block> + 'static,
-	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+	B: sc_client_api::BackupBackend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashingFor<Block>>,
 	AuthorityId: AuthorityIdBound,
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
@@ -152,8 +167,10 @@ where
 	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
 	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use sc_rpc::{
-		dev::{Dev, DevApiServer},
-		mixnet::MixnetApiServer,
+		construction::{Construction, ConstructionApiServer},
+		dev::{Backup, BackupApiServer, Blacklist, BlacklistApiServer, Dev, DevApiServer},
+		mixnet::{MixnetApiServer, MixnetAuthor, MixnetAuthorApiServer},
+		scale::{Scale, ScaleApiServer},
 		statement::StatementApiServer,
 	};
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
@@ -171,7 +188,7 @@ where
 		finality_provider,
 	} = grandpa;
 
-	io.merge(System::new(client.clone(), pool).into_rpc())?;
+	io.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
 	// Making synchronous calls in light client freezes the browser currently,
 	// more context: https://github.com/paritytech/substrate/pull/3480
 	// These RPCs should use an asynchronous caller instead.
@@ -204,13 +221,21 @@ where
 			.into_rpc(),
 	)?;
 
+	io.merge(Backup::new(backend.clone(), backup).into_rpc())?;
 	io.merge(StateMigration::new(client.clone(), backend).into_rpc())?;
+	io.merge(Construction::new(client.clone()).into_rpc())?;
+	io.merge(Scale::new(client.clone()).into_rpc())?;
+	io.merge(Blacklist::new(client.clone()).into_rpc())?;
 	io.merge(Dev::new(client).into_rpc())?;
 	let statement_store = sc_rpc::statement::StatementStore::new(statement_store).into_rpc();
 	io.merge(statement_store)?;
 
-	if let Some(mixnet_api) = mixnet_api {
-		let mixnet = sc_rpc::mixnet::Mixnet::new(mixnet_api).into_rpc();
+	if let Some(MixnetDeps { api, route_author_submissions, subscription_executor }) = mixnet {
+		if route_author_submissions {
+			let author = MixnetAuthor::new(pool.clone(), api.clone(), subscription_executor);
+			io.merge(author.into_rpc())?;
+		}
+		let mixnet = sc_rpc::mixnet::Mixnet::new(api).into_rpc();
 		io.merge(mixnet)?;
 	}
 
@@ -182,10 +182,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 268,
+	spec_version: 269,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 	system_version: 1,
 };
 
@@ -538,6 +538,7 @@ impl pallet_preimage::Config for Runtime {
 			Balance,
 		>,
 	>;
+	type ExpiryPeriod = ConstU32<{ 28 * DAYS }>;
 }
 
 parameter_types! {
@@ -1778,16 +1779,21 @@ where
 			.saturating_sub(1);
 		let era = Era::mortal(period, current_block);
 		let tx_ext: TxExtension = (
+			pallet_account_abstraction::AuthorizeAccount::<Runtime>::new_disabled(),
 			frame_system::CheckNonZeroSender::<Runtime>::new(),
 			frame_system::CheckSpecVersion::<Runtime>::new(),
 			frame_system::CheckTxVersion::<Runtime>::new(),
 			frame_system::CheckGenesis::<Runtime>::new(),
 			frame_system::CheckEra::<Runtime>::from(era),
-			frame_system::CheckNonce::<Runtime>::from(nonce),
+			pallet_nonceless::CheckNonceOrTag::from(frame_system::CheckNonce::<Runtime>::from(
+				nonce,
+			)),
 			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_skip_feeless_payment::SkipCheckIfFeeless::from(
-				pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(
-					tip, None,
+			pallet_sponsored_payment::Sponsored::from(
+				pallet_skip_feeless_payment::SkipCheckIfFeeless::from(
+					pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(
+						tip, None,
+					),
 				),
 			),
 			frame_metadata_hash_extension::CheckMetadataHash::new(false),
@@ -2114,6 +2120,7 @@ impl pallet_asset_conversion::Config for Runtime {
 	type LiquidityWithdrawalFee = LiquidityWithdrawalFee;
 	type WeightInfo = pallet_asset_conversion::weights::SubstrateWeight<Runtime>;
 	type MaxSwapPathLength = ConstU32<4>;
+	type MaxPriceObservations = ConstU32<64>;
 	type MintMinLiquidity = MintMinLiquidity;
 	#[cfg(feature = "runtime-benchmarks")]
 	type BenchmarkHelper = ();
@@ -2401,6 +2408,110 @@ impl pallet_verify_signature::Config for Runtime {
 	type BenchmarkHelper = VerifySignatureBenchmarkHelper;
 }
 
+parameter_types! {
+	pub const AccountAbstractionHoldReason: RuntimeHoldReason =
+		RuntimeHoldReason::AccountAbstraction(pallet_account_abstraction::HoldReason::Deposit);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct AccountAbstractionBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl
+	pallet_account_abstraction::BenchmarkHelper<
+		pallet_account_abstraction::CredentialOf<Runtime>,
+		pallet_account_abstraction::ProofOf<Runtime>,
+	> for AccountAbstractionBenchmarkHelper
+{
+	fn credential(index: u32) -> pallet_account_abstraction::CredentialOf<Runtime> {
+		use sp_io::crypto::sr25519_generate;
+		use sp_runtime::traits::IdentifyAccount;
+		let seed = alloc::format!("//{index}").into_bytes();
+		let public = sr25519_generate(0.into(), Some(seed));
+		pallet_account_abstraction::Either::Left(MultiSigner::Sr25519(public).into_account())
+	}
+
+	fn proof(index: u32, msg: &[u8; 32]) -> pallet_account_abstraction::ProofOf<Runtime> {
+		use sp_io::crypto::{sr25519_generate, sr25519_sign};
+		let seed = alloc::format!("//{index}").into_bytes();
+		let public = sr25519_generate(0.into(), Some(seed));
+		let signature = MultiSignature::Sr25519(sr25519_sign(0.into(), &public, msg).unwrap());
+		pallet_account_abstraction::Either::Left(signature)
+	}
+}
+
+impl pallet_account_abstraction::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Authenticator =
+		(pallet_account_abstraction::SignedBy<MultiSignature>, pallet_account_abstraction::Passkey);
+	type MaxCredentials = ConstU32<8>;
+	type MaxGuardians = ConstU32<10>;
+	type RecoveryDelay = ConstU32<{ 2 * DAYS }>;
+	type Consideration = HoldConsideration<
+		AccountId,
+		Balances,
+		AccountAbstractionHoldReason,
+		LinearStoragePrice<
+			dynamic_params::storage::BaseDeposit,
+			dynamic_params::storage::ByteDeposit,
+			Balance,
+		>,
+	>;
+	type WeightInfo = pallet_account_abstraction::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = AccountAbstractionBenchmarkHelper;
+}
+
+parameter_types! {
+	pub const SponsoredPaymentHoldReason: RuntimeHoldReason =
+		RuntimeHoldReason::SponsoredPayment(pallet_sponsored_payment::HoldReason::Policy);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct SponsoredPaymentBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_sponsored_payment::BenchmarkHelper<MultiSignature, AccountId>
+	for SponsoredPaymentBenchmarkHelper
+{
+	fn sponsor() -> AccountId {
+		use sp_io::crypto::sr25519_generate;
+		use sp_runtime::traits::IdentifyAccount;
+		let public = sr25519_generate(0.into(), Some(b"//Sponsor".to_vec()));
+		MultiSigner::Sr25519(public).into_account()
+	}
+
+	fn sign(msg: &[u8]) -> MultiSignature {
+		use sp_io::crypto::{sr25519_generate, sr25519_sign};
+		let public = sr25519_generate(0.into(), Some(b"//Sponsor".to_vec()));
+		MultiSignature::Sr25519(sr25519_sign(0.into(), &public, msg).unwrap())
+	}
+}
+
+impl pallet_sponsored_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Signature = MultiSignature;
+	type AccountIdentifier = MultiSigner;
+	type UsagePeriod = ConstU32<DAYS>;
+	type MaxEnvelopeLifetime = ConstU32<HOURS>;
+	type Consideration = HoldConsideration<
+		AccountId,
+		Balances,
+		SponsoredPaymentHoldReason,
+		LinearStoragePrice<
+			dynamic_params::storage::BaseDeposit,
+			dynamic_params::storage::ByteDeposit,
+			Balance,
+		>,
+	>;
+	type WeightInfo = pallet_sponsored_payment::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = SponsoredPaymentBenchmarkHelper;
+}
+
+impl pallet_nonceless::Config for Runtime {
+	type MaxLifetime = ConstU32<HOURS>;
+	type WeightInfo = pallet_nonceless::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_whitelist::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
@@ -2599,6 +2710,7 @@ impl pallet_broker::Config for Runtime {
 	type MaxAutoRenewals = ConstU32<10>;
 	type PriceAdapter = pallet_broker::CenterTargetPrice<Balance>;
 	type MinimumCreditPurchase = MinimumCreditPurchase;
+	type MaxPriceHistory = ConstU32<16>;
 }
 
 parameter_types! {
@@ -3010,6 +3122,15 @@ mod runtime {
 	pub type MultiBlockUnsigned = pallet_election_provider_multi_block::unsigned::Pallet<Runtime>;
 	#[runtime::pallet_index(88)]
 	pub type MultiBlockSigned = pallet_election_provider_multi_block::signed::Pallet<Runtime>;
+
+	#[runtime::pallet_index(89)]
+	pub type AccountAbstraction = pallet_account_abstraction::Pallet<Runtime>;
+
+	#[runtime::pallet_index(90)]
+	pub type SponsoredPayment = pallet_sponsored_payment::Pallet<Runtime>;
+
+	#[runtime::pallet_index(91)]
+	pub type Nonceless = pallet_nonceless::Pallet<Runtime>;
 }
 
 impl TryFrom<RuntimeCall> for pallet_revive::Call<Runtime> {
@@ -3038,16 +3159,20 @@ pub type BlockId = generic::BlockId<Block>;
 ///
 /// [`sign`]: <../../testing/src/keyring.rs.html>
 pub type TxExtension = (
+	pallet_account_abstraction::AuthorizeAccount<Runtime>,
 	frame_system::CheckNonZeroSender<Runtime>,
 	frame_system::CheckSpecVersion<Runtime>,
 	frame_system::CheckTxVersion<Runtime>,
 	frame_system::CheckGenesis<Runtime>,
 	frame_system::CheckEra<Runtime>,
-	frame_system::CheckNonce<Runtime>,
+	pallet_nonceless::CheckNonceOrTag<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_skip_feeless_payment::SkipCheckIfFeeless<
+	pallet_sponsored_payment::Sponsored<
 		Runtime,
-		pallet_asset_conversion_tx_payment::ChargeAssetTxPayment<Runtime>,
+		pallet_skip_feeless_payment::SkipCheckIfFeeless<
+			Runtime,
+			pallet_asset_conversion_tx_payment::ChargeAssetTxPayment<Runtime>,
+		>,
 	>,
 	frame_metadata_hash_extension::CheckMetadataHash<Runtime>,
 	frame_system::WeightReclaim<Runtime>,
@@ -3062,15 +3187,22 @@ impl EthExtra for EthExtraImpl {
 
 	fn get_eth_extension(nonce: u32, tip: Balance) -> Self::Extension {
 		(
+			pallet_account_abstraction::AuthorizeAccount::<Runtime>::new_disabled(),
 			frame_system::CheckNonZeroSender::<Runtime>::new(),
 			frame_system::CheckSpecVersion::<Runtime>::new(),
 			frame_system::CheckTxVersion::<Runtime>::new(),
 			frame_system::CheckGenesis::<Runtime>::new(),
 			frame_system::CheckEra::from(crate::generic::Era::Immortal),
-			frame_system::CheckNonce::<Runtime>::from(nonce),
+			pallet_nonceless::CheckNonceOrTag::from(frame_system::CheckNonce::<Runtime>::from(
+				nonce,
+			)),
 			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(tip, None)
+			pallet_sponsored_payment::Sponsored::from(
+				pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(
+					tip, None,
+				)
 				.into(),
+			),
 			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(false),
 			frame_system::WeightReclaim::<Runtime>::new(),
 		)
@@ -3277,6 +3409,9 @@ mod benches {
 		[pallet_example_mbm, PalletExampleMbms]
 		[pallet_asset_conversion_ops, AssetConversionMigration]
 		[pallet_verify_signature, VerifySignature]
+		[pallet_account_abstraction, AccountAbstraction]
+		[pallet_sponsored_payment, SponsoredPayment]
+		[pallet_nonceless, Nonceless]
 	);
 }
 
@@ -3339,7 +3474,7 @@ impl_runtime_apis! {
 			tx: <Block as BlockT>::Extrinsic,
 			block_hash: <Block as BlockT>::Hash,
 		) -> TransactionValidity {
-			Executive::validate_transaction(source, tx, block_hash)
+			Executive::validate_transaction_with_call_filter(source, tx, block_hash)
 		}
 	}
 
@@ -3780,6 +3915,7 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(2)]
 	impl pallet_asset_conversion::AssetConversionApi<
 		Block,
 		Balance,
@@ -3797,6 +3933,18 @@ impl_runtime_apis! {
 		fn get_reserves(asset1: NativeOrWithId<u32>, asset2: NativeOrWithId<u32>) -> Option<(Balance, Balance)> {
 			AssetConversion::get_reserves(asset1, asset2).ok()
 		}
+
+		fn quote_exact_tokens_for_tokens_with_slippage(path: Vec<NativeOrWithId<u32>>, amount_in: Balance, slippage: sp_runtime::Permill) -> Option<(Balance, Balance)> {
+			AssetConversion::quote_exact_tokens_for_tokens_with_slippage(path, amount_in, slippage)
+		}
+
+		fn quote_tokens_for_exact_tokens_with_slippage(path: Vec<NativeOrWithId<u32>>, amount_out: Balance, slippage: sp_runtime::Permill) -> Option<(Balance, Balance)> {
+			AssetConversion::quote_tokens_for_exact_tokens_with_slippage(path, amount_out, slippage)
+		}
+
+		fn quote_time_weighted_price(path: Vec<NativeOrWithId<u32>>, amount: Balance, window: u32) -> Option<Balance> {
+			AssetConversion::quote_time_weighted_price(path, amount, window.into())
+		}
 	}
 
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
@@ -0,0 +1,216 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Namespaced view of the auxiliary data, with atomic transactions.
+//!
+//! The auxiliary data is a flat key-value store shared by all the users of the client. An
+//! [`AuxNamespace`] confines its user to the keys of its namespace, keeps track of these keys to
+//! iterate them, and applies changes in atomic transactions, only if the values of the keys the
+//! transaction expects are unchanged.
+
+use crate::backend::AuxStore;
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Prefix of the keys of all namespaces.
+const KEY_PREFIX: &[u8] = b"aux_namespace:";
+
+/// Prefix of the keys holding the set of keys of every namespace.
+const INDEX_PREFIX: &[u8] = b"aux_namespace_index:";
+
+/// Serializes the transactions of all namespaces, for the checks of their expected values to hold
+/// until their changes are written.
+///
+/// Writes to the keys of a namespace that don't go through the namespace bypass the lock.
+static TRANSACTION_LOCK: Mutex<()> = Mutex::new(());
+
+/// Changes to the keys of a namespace, applied atomically by [`AuxNamespace::commit`].
+#[derive(Debug, Clone, Default)]
+pub struct AuxTransaction {
+	expected: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+	changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl AuxTransaction {
+	/// Create an empty transaction.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Only apply the transaction if `key` has `value`, `None` meaning that `key` is not set.
+	pub fn expect(mut self, key: &[u8], value: Option<&[u8]>) -> Self {
+		self.expected.insert(key.to_vec(), value.map(<[u8]>::to_vec));
+		self
+	}
+
+	/// Set `key` to `value`.
+	pub fn insert(mut self, key: &[u8], value: &[u8]) -> Self {
+		self.changes.insert(key.to_vec(), Some(value.to_vec()));
+		self
+	}
+
+	/// Delete `key`.
+	pub fn delete(mut self, key: &[u8]) -> Self {
+		self.changes.insert(key.to_vec(), None);
+		self
+	}
+}
+
+/// The auxiliary data under a namespace.
+///
+/// The keys of the namespace are kept track of under a single key of the auxiliary data, which
+/// makes namespaces best suited to a moderate number of keys.
+pub struct AuxNamespace<'a, S> {
+	store: &'a S,
+	namespace: Vec<u8>,
+}
+
+impl<'a, S: AuxStore> AuxNamespace<'a, S> {
+	/// Create the view of the auxiliary data of `store` under `namespace`.
+	pub fn new(store: &'a S, namespace: &[u8]) -> Self {
+		Self { store, namespace: namespace.to_vec() }
+	}
+
+	/// Get the value of `key`.
+	pub fn get(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		self.store.get_aux(&self.full_key(key))
+	}
+
+	/// Get the keys of the namespace, in order.
+	pub fn keys(&self) -> sp_blockchain::Result<BTreeSet<Vec<u8>>> {
+		match self.store.get_aux(&self.index_key())? {
+			Some(index) => BTreeSet::decode(&mut &index[..]).map_err(|e| {
+				sp_blockchain::Error::Backend(format!("Invalid aux namespace index: {e}"))
+			}),
+			None => Ok(BTreeSet::new()),
+		}
+	}
+
+	/// Get the keys of the namespace with their values, in the order of the keys.
+	pub fn pairs(&self) -> sp_blockchain::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+		let mut pairs = Vec::new();
+		for key in self.keys()? {
+			if let Some(value) = self.get(&key)? {
+				pairs.push((key, value));
+			}
+		}
+		Ok(pairs)
+	}
+
+	/// Apply the changes of `transaction` atomically.
+	///
+	/// Returns `false`, without applying any change, if a key expected by the transaction doesn't
+	/// have the expected value.
+	pub fn commit(&self, transaction: AuxTransaction) -> sp_blockchain::Result<bool> {
+		let _lock = TRANSACTION_LOCK.lock();
+		for (key, value) in &transaction.expected {
+			if self.get(key)? != *value {
+				return Ok(false)
+			}
+		}
+
+		let mut keys = self.keys()?;
+		let mut insert = Vec::new();
+		let mut delete = Vec::new();
+		for (key, value) in transaction.changes {
+			let full_key = self.full_key(&key);
+			match value {
+				Some(value) => {
+					insert.push((full_key, value));
+					keys.insert(key);
+				},
+				None => {
+					delete.push(full_key);
+					keys.remove(&key);
+				},
+			}
+		}
+		insert.push((self.index_key(), keys.encode()));
+
+		let insert = insert.iter().map(|(key, value)| (&key[..], &value[..])).collect::<Vec<_>>();
+		let delete = delete.iter().map(|key| &key[..]).collect::<Vec<_>>();
+		self.store.insert_aux(&insert, &delete)?;
+		Ok(true)
+	}
+
+	/// The key of the auxiliary data of `key`.
+	///
+	/// The namespace is length prefixed, for no namespace to be a prefix of another.
+	fn full_key(&self, key: &[u8]) -> Vec<u8> {
+		let mut full_key = KEY_PREFIX.to_vec();
+		self.namespace.encode_to(&mut full_key);
+		full_key.extend_from_slice(key);
+		full_key
+	}
+
+	/// The key of the auxiliary data holding the keys of the namespace.
+	fn index_key(&self) -> Vec<u8> {
+		let mut index_key = INDEX_PREFIX.to_vec();
+		self.namespace.encode_to(&mut index_key);
+		index_key
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::in_mem::Blockchain;
+	use substrate_test_runtime::Block;
+
+	#[test]
+	fn namespaces_are_separate() {
+		let store = Blockchain::<Block>::new();
+		let first = store.aux_namespace(b"first");
+		let second = store.aux_namespace(b"firstsecond");
+
+		assert!(first.commit(AuxTransaction::new().insert(b"secondkey", b"1")).unwrap());
+		assert!(second.commit(AuxTransaction::new().insert(b"key", b"2")).unwrap());
+
+		assert_eq!(first.get(b"secondkey").unwrap(), Some(b"1".to_vec()));
+		assert_eq!(second.get(b"key").unwrap(), Some(b"2".to_vec()));
+		assert_eq!(first.pairs().unwrap(), vec![(b"secondkey".to_vec(), b"1".to_vec())]);
+		assert_eq!(second.pairs().unwrap(), vec![(b"key".to_vec(), b"2".to_vec())]);
+	}
+
+	#[test]
+	fn commit_checks_expected_values() {
+		let store = Blockchain::<Block>::new();
+		let namespace = store.aux_namespace(b"test");
+		let transaction = AuxTransaction::new().insert(b"a", b"1").insert(b"b", b"2");
+		assert!(namespace.commit(transaction).unwrap());
+
+		// `a` doesn't have the expected value, nothing is changed.
+		let transaction =
+			AuxTransaction::new().expect(b"a", Some(b"2")).insert(b"a", b"3").delete(b"b");
+		assert!(!namespace.commit(transaction).unwrap());
+		assert_eq!(
+			namespace.pairs().unwrap(),
+			vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())],
+		);
+
+		let transaction = AuxTransaction::new()
+			.expect(b"a", Some(b"1"))
+			.expect(b"c", None)
+			.insert(b"a", b"3")
+			.delete(b"b");
+		assert!(namespace.commit(transaction).unwrap());
+		assert_eq!(namespace.pairs().unwrap(), vec![(b"a".to_vec(), b"3".to_vec())]);
+		assert_eq!(namespace.get(b"b").unwrap(), None);
+	}
+}
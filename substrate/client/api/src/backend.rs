@@ -18,8 +18,15 @@
 
 //! Substrate Client data backend
 
-use std::collections::HashSet;
+use std::{
+	cell::Cell,
+	collections::HashSet,
+	marker::PhantomData,
+	path::PathBuf,
+	time::{Duration, Instant},
+};
 
+use codec::{Decode, Encode};
 use parking_lot::RwLock;
 
 use sp_consensus::BlockOrigin;
@@ -35,13 +42,39 @@ use sp_state_machine::{
 use sp_storage::{ChildInfo, StorageData, StorageKey};
 pub use sp_trie::MerkleValue;
 
-use crate::{blockchain::Backend as BlockchainBackend, UsageInfo};
+use crate::{aux_namespace::AuxNamespace, blockchain::Backend as BlockchainBackend, UsageInfo};
 
 pub use sp_state_machine::{Backend as StateBackend, BackendTransaction, KeyValueStates};
 
 /// Extracts the state backend type for the given backend.
 pub type StateBackendFor<B, Block> = <B as Backend<Block>>::State;
 
+thread_local! {
+	static STATE_REGENERATION: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f`, letting the backend regenerate the pruned states it requests, if it supports it.
+///
+/// Regenerating a pruned state may take minutes, so it is only done for the callers opting in,
+/// like the state RPC, and never for the requests of the network peers.
+pub fn with_state_regeneration<R>(f: impl FnOnce() -> R) -> R {
+	struct Reset(bool);
+	impl Drop for Reset {
+		fn drop(&mut self) {
+			STATE_REGENERATION.set(self.0);
+		}
+	}
+
+	let _reset = Reset(STATE_REGENERATION.replace(true));
+	f()
+}
+
+/// Whether the pruned states may be regenerated for the current caller, see
+/// [`with_state_regeneration`].
+pub fn state_regeneration_allowed() -> bool {
+	STATE_REGENERATION.get()
+}
+
 /// Describes which block import notification stream should be notified.
 #[derive(Debug, Clone, Copy)]
 pub enum ImportNotificationAction {
@@ -76,6 +109,8 @@ pub struct ImportSummary<Block: BlockT> {
 	pub tree_route: Option<sp_blockchain::TreeRoute<Block>>,
 	/// What notify action to take for this import.
 	pub import_notification_action: ImportNotificationAction,
+	/// When the import of the block started, if measured by the importer.
+	pub import_started: Option<Instant>,
 }
 
 /// Finalization operation summary.
@@ -309,6 +344,15 @@ pub trait AuxStore {
 
 	/// Query auxiliary data from key-value store.
 	fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>>;
+
+	/// Get the view of the auxiliary data under `namespace`, with iteration of its keys and atomic
+	/// transactions.
+	fn aux_namespace(&self, namespace: &[u8]) -> AuxNamespace<'_, Self>
+	where
+		Self: Sized,
+	{
+		AuxNamespace::new(self, namespace)
+	}
 }
 
 /// An `Iterator` that iterates keys in a given block under a prefix.
@@ -414,6 +458,141 @@ where
 	}
 }
 
+/// The state of a block, for mutually consistent reads of its storage.
+///
+/// All reads are done on the same state, which isn't pruned while the snapshot is alive.
+pub struct StorageSnapshot<State, Block>
+where
+	State: StateBackend<HashingFor<Block>>,
+	Block: BlockT,
+{
+	state: State,
+	_phantom: PhantomData<Block>,
+}
+
+impl<State, Block> StorageSnapshot<State, Block>
+where
+	State: StateBackend<HashingFor<Block>>,
+	Block: BlockT,
+{
+	/// Create a snapshot of `state`.
+	pub fn new(state: State) -> Self {
+		Self { state, _phantom: PhantomData }
+	}
+
+	/// Return the value under `key`.
+	pub fn storage(&self, key: &StorageKey) -> sp_blockchain::Result<Option<StorageData>> {
+		Ok(self
+			.state
+			.storage(&key.0)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))?
+			.map(StorageData))
+	}
+
+	/// Return the values under `keys`, in the order of the keys.
+	pub fn storage_batch(
+		&self,
+		keys: &[StorageKey],
+	) -> sp_blockchain::Result<Vec<Option<StorageData>>> {
+		keys.iter().map(|key| self.storage(key)).collect()
+	}
+
+	/// Return the hash of the value under `key`.
+	pub fn storage_hash(&self, key: &StorageKey) -> sp_blockchain::Result<Option<Block::Hash>> {
+		self.state
+			.storage_hash(&key.0)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
+	}
+
+	/// Return the value under `key` in the child storage `child_info`.
+	pub fn child_storage(
+		&self,
+		child_info: &ChildInfo,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<StorageData>> {
+		Ok(self
+			.state
+			.child_storage(child_info, &key.0)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))?
+			.map(StorageData))
+	}
+
+	/// Return the hash of the value under `key` in the child storage `child_info`.
+	pub fn child_storage_hash(
+		&self,
+		child_info: &ChildInfo,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<Block::Hash>> {
+		self.state
+			.child_storage_hash(child_info, &key.0)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
+	}
+
+	/// Return the closest merkle value of `key`.
+	pub fn closest_merkle_value(
+		&self,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>> {
+		self.state
+			.closest_merkle_value(&key.0)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
+	}
+
+	/// Return the closest merkle value of `key` in the child storage `child_info`.
+	pub fn child_closest_merkle_value(
+		&self,
+		child_info: &ChildInfo,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>> {
+		self.state
+			.child_closest_merkle_value(child_info, &key.0)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
+	}
+
+	/// Return an iterator over the keys under `prefix`, after `start_at`, of the child storage
+	/// `child_info` or of the main storage if `None`.
+	pub fn keys(
+		&self,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&StorageKey>,
+		start_at: Option<&StorageKey>,
+	) -> sp_blockchain::Result<SnapshotKeysIter<'_, State, Block>> {
+		let mut args = IterArgs::default();
+		args.prefix = prefix.as_ref().map(|prefix| prefix.0.as_slice());
+		args.start_at = start_at.as_ref().map(|start_at| start_at.0.as_slice());
+		args.child_info = child_info.cloned();
+		args.start_at_exclusive = true;
+
+		let inner = self
+			.state
+			.raw_iter(args)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))?;
+		Ok(SnapshotKeysIter { inner, state: &self.state })
+	}
+}
+
+/// An `Iterator` that iterates keys of a [`StorageSnapshot`] under a prefix.
+pub struct SnapshotKeysIter<'a, State, Block>
+where
+	State: StateBackend<HashingFor<Block>>,
+	Block: BlockT,
+{
+	inner: <State as StateBackend<HashingFor<Block>>>::RawIter,
+	state: &'a State,
+}
+
+impl<State, Block> Iterator for SnapshotKeysIter<'_, State, Block>
+where
+	Block: BlockT,
+	State: StateBackend<HashingFor<Block>>,
+{
+	type Item = StorageKey;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.inner.next_key(self.state)?.ok().map(StorageKey)
+	}
+}
+
 /// Provides access to storage primitives
 pub trait StorageProvider<Block: BlockT, B: Backend<Block>> {
 	/// Given a block's `Hash` and a key, return the value under the key in that block.
@@ -490,6 +669,15 @@ pub trait StorageProvider<Block: BlockT, B: Backend<Block>> {
 		child_info: &ChildInfo,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<Option<MerkleValue<Block::Hash>>>;
+
+	/// Given a block's `Hash`, return a snapshot of the state of that block.
+	///
+	/// Unlike separate calls of the other methods, the reads of a snapshot are mutually
+	/// consistent, as the state isn't pruned while the snapshot is alive.
+	fn storage_snapshot(
+		&self,
+		hash: Block::Hash,
+	) -> sp_blockchain::Result<StorageSnapshot<B::State, Block>>;
 }
 
 /// Client backend.
@@ -635,7 +823,75 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 
 	/// Tells whether the backend requires full-sync mode.
 	fn requires_full_sync(&self) -> bool;
+
+	/// The time to wait before importing a block whose import can be deferred, like the blocks of
+	/// the initial sync, to keep the rate of writes below the limit of the backend.
+	fn write_delay(&self) -> Duration {
+		Duration::ZERO
+	}
 }
 
 /// Mark for all Backend implementations, that are making use of state data, stored locally.
 pub trait LocalBackend<Block: BlockT>: Backend<Block> {}
+
+/// Settings of the backups of the database taken while the node runs.
+#[derive(Debug, Clone, Default)]
+pub struct BackupSettings {
+	/// Command run for every backup.
+	///
+	/// With a snapshot directory, the command runs once the writes resumed, to copy the snapshot
+	/// elsewhere. Without, it runs while the writes are quiesced, to take an instant snapshot of
+	/// the file system holding the database.
+	///
+	/// The paths of the database and of the snapshot, and the blocks of the consistency marker
+	/// are passed in the `BACKUP_*` environment variables. The backup fails if the command
+	/// exits with a non-zero status.
+	pub hook: Option<PathBuf>,
+	/// Directory in which a checkpoint of the database is created for every backup.
+	///
+	/// Only supported by RocksDB databases.
+	pub snapshot_dir: Option<PathBuf>,
+}
+
+/// Consistency marker committed to the database when a backup is taken.
+///
+/// A database restored from the backup contains the marker of the backup, and the blocks it
+/// refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct BackupMarker<Block: BlockT> {
+	/// Best block hash at the time of the backup.
+	pub best_hash: Block::Hash,
+	/// Best block number at the time of the backup.
+	pub best_number: NumberFor<Block>,
+	/// Finalized block hash at the time of the backup.
+	pub finalized_hash: Block::Hash,
+	/// Finalized block number at the time of the backup.
+	pub finalized_number: NumberFor<Block>,
+	/// Unix timestamp of the backup, in seconds.
+	pub timestamp: u64,
+}
+
+/// A backup of the database.
+#[derive(Debug, Clone)]
+pub struct BackupReport<Block: BlockT> {
+	/// The consistency marker of the backup.
+	pub marker: BackupMarker<Block>,
+	/// The snapshot of the database, if any.
+	pub snapshot: Option<PathBuf>,
+	/// Time during which the database was quiesced.
+	pub duration: Duration,
+}
+
+/// Backend able to take consistent backups of its database while the node runs.
+pub trait BackupBackend<Block: BlockT>: Backend<Block> {
+	/// Commit a consistency marker to the database, then create the snapshot and run the hook of
+	/// `settings`.
+	///
+	/// The writes are quiesced while the marker is committed and the snapshot is created: the
+	/// [import lock](Backend::get_import_lock) is held and the writes to the offchain storage
+	/// are paused. The caller must not hold the import lock.
+	fn backup(&self, settings: &BackupSettings) -> sp_blockchain::Result<BackupReport<Block>>;
+
+	/// The marker of the last backup, if any.
+	fn last_backup(&self) -> sp_blockchain::Result<Option<BackupMarker<Block>>>;
+}
@@ -29,6 +29,7 @@ use std::{
 	collections::HashSet,
 	fmt::{self, Debug},
 	sync::Arc,
+	time::Duration,
 };
 
 use crate::{blockchain::Info, notifications::StorageEventStream, FinalizeSummary, ImportSummary};
@@ -36,6 +37,7 @@ use crate::{blockchain::Info, notifications::StorageEventStream, FinalizeSummary
 use sc_transaction_pool_api::ChainEvent;
 use sc_utils::mpsc::{TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_blockchain;
+use sp_state_machine::{ChildStorageCollection, StorageCollection};
 
 /// Type that implements `futures::Stream` of block import events.
 pub type ImportNotifications<Block> = TracingUnboundedReceiver<BlockImportNotification<Block>>;
@@ -43,6 +45,10 @@ pub type ImportNotifications<Block> = TracingUnboundedReceiver<BlockImportNotifi
 /// A stream of block finality notifications.
 pub type FinalityNotifications<Block> = TracingUnboundedReceiver<FinalityNotification<Block>>;
 
+/// A stream of the storage changes of imported blocks.
+pub type BlockStorageChangesNotifications<Block> =
+	TracingUnboundedReceiver<BlockStorageChanges<<Block as BlockT>::Hash>>;
+
 /// Expected hashes of blocks at given heights.
 ///
 /// This may be used as chain spec extension to set trusted checkpoints, i.e.
@@ -94,6 +100,51 @@ pub trait BlockchainEvents<Block: BlockT> {
 	) -> sp_blockchain::Result<StorageEventStream<Block::Hash>>;
 }
 
+/// Replay of the most recent blockchain events, for services started after the node.
+pub trait BlockchainEventsReplay<Block: BlockT>: BlockchainEvents<Block> {
+	/// Get block import event stream, starting with the replay of up to `max_replay` of the most
+	/// recent notifications of [`BlockchainEvents::import_notification_stream`].
+	///
+	/// The live notifications directly follow the replayed ones, none is missed or sent twice.
+	/// Only a bounded number of notifications is retained, fewer may be replayed.
+	fn import_notification_stream_with_replay(
+		&self,
+		max_replay: usize,
+	) -> ImportNotifications<Block>;
+
+	/// Get a stream of finality notifications, starting with the replay of up to `max_replay` of
+	/// the most recent notifications of [`BlockchainEvents::finality_notification_stream`].
+	///
+	/// The live notifications directly follow the replayed ones, none is missed or sent twice.
+	/// Only a bounded number of notifications is retained, fewer may be replayed.
+	fn finality_notification_stream_with_replay(
+		&self,
+		max_replay: usize,
+	) -> FinalityNotifications<Block>;
+}
+
+/// Storage changes of the imported blocks, for services following the state of the node.
+pub trait BlockStorageChangesEvents<Block: BlockT>: BlockchainEvents<Block> {
+	/// Get a stream of the storage changes of the imported blocks.
+	///
+	/// Fired for the same blocks as [`BlockchainEvents::import_notification_stream`], right
+	/// before their import notification. Unlike
+	/// [`BlockchainEvents::storage_changes_notification_stream`], the changes are never filtered
+	/// and include all child tries.
+	fn block_storage_changes_stream(&self) -> BlockStorageChangesNotifications<Block>;
+}
+
+/// The changes made to the storage by an imported block.
+#[derive(Debug, Clone)]
+pub struct BlockStorageChanges<Hash> {
+	/// Hash of the block.
+	pub hash: Hash,
+	/// Changes to the main storage. A value of `None` means that it was deleted.
+	pub main_changes: Arc<StorageCollection>,
+	/// Changes to the child storages, by storage key of the child trie.
+	pub child_changes: Arc<ChildStorageCollection>,
+}
+
 /// List of operations to be performed on storage aux data.
 /// First tuple element is the encoded data key.
 /// Second tuple element is the encoded optional data to write.
@@ -164,6 +215,24 @@ pub trait BlockBackend<Block: BlockT> {
 	fn requires_full_sync(&self) -> bool;
 }
 
+/// Blacklist of blocks, for emergency interventions of the node operators, e.g. during a
+/// coordinated response to a consensus bug.
+///
+/// The blacklisted blocks and their descendants are removed from the database, rejected at import
+/// and reported as known bad to the sync. A blacklisted block of the best chain is retracted from
+/// it, with an import notification of its parent as the new best block. The blacklist is persisted
+/// across restarts.
+pub trait BlockBlacklist<Block: BlockT> {
+	/// Blacklist the block `hash`. Finalized blocks can't be blacklisted.
+	fn blacklist_block(&self, hash: Block::Hash) -> sp_blockchain::Result<()>;
+
+	/// Remove the block `hash` from the blacklist, returns whether it was blacklisted.
+	fn unblacklist_block(&self, hash: Block::Hash) -> sp_blockchain::Result<bool>;
+
+	/// The blacklisted blocks.
+	fn blacklisted_blocks(&self) -> Vec<Block::Hash>;
+}
+
 /// Provide a list of potential uncle headers for a given block.
 pub trait ProvideUncles<Block: BlockT> {
 	/// Gets the uncles of the block with `target_hash` going back `max_generation` ancestors.
@@ -260,6 +329,8 @@ pub struct UsageInfo {
 	pub memory: MemoryInfo,
 	/// I/O statistics.
 	pub io: IoInfo,
+	/// Size of the database on disk in bytes, if the backend stores it on disk.
+	pub database_size: Option<u64>,
 }
 
 impl fmt::Display for UsageInfo {
@@ -361,6 +432,9 @@ pub struct BlockImportNotification<Block: BlockT> {
 	///
 	/// If `None`, there was no re-org while importing.
 	pub tree_route: Option<Arc<sp_blockchain::TreeRoute<Block>>>,
+	/// Time taken to execute the block and commit it to the database, if measured by the
+	/// importer.
+	pub import_duration: Option<Duration>,
 	/// Handle to unpin the block this notification is for
 	unpin_handle: UnpinHandle<Block>,
 }
@@ -381,6 +455,7 @@ impl<Block: BlockT> BlockImportNotification<Block> {
 			header,
 			is_new_best,
 			tree_route,
+			import_duration: None,
 			unpin_handle: UnpinHandle::new(hash, unpin_worker_sender),
 		}
 	}
@@ -465,6 +540,7 @@ impl<Block: BlockT> BlockImportNotification<Block> {
 			header: summary.header,
 			is_new_best: summary.is_new_best,
 			tree_route: summary.tree_route.map(Arc::new),
+			import_duration: None,
 			unpin_handle: UnpinHandle::new(hash, unpin_worker_sender),
 		}
 	}
@@ -19,6 +19,7 @@
 //! Substrate client interfaces.
 #![warn(missing_docs)]
 
+pub mod aux_namespace;
 pub mod backend;
 pub mod call_executor;
 pub mod client;
@@ -28,6 +29,7 @@ pub mod leaves;
 pub mod notifications;
 pub mod proof_provider;
 
+pub use aux_namespace::{AuxNamespace, AuxTransaction};
 pub use backend::*;
 pub use call_executor::*;
 pub use client::*;
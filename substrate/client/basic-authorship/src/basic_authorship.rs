@@ -41,6 +41,10 @@ use sp_runtime::{
 };
 use std::{marker::PhantomData, pin::Pin, sync::Arc, time};
 
+use crate::{
+	injection::LocalExtrinsics,
+	policy::{PriorityOrder, TransactionPolicy},
+};
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_proposer_metrics::{EndProposingReason, MetricsLink as PrometheusMetrics};
 
@@ -82,6 +86,10 @@ pub struct ProposerFactory<A, C, PR> {
 	telemetry: Option<TelemetryHandle>,
 	/// When estimating the block size, should the proof be included?
 	include_proof_in_block_size_estimation: bool,
+	/// The policy applied to the ready transactions.
+	transaction_policy: Arc<dyn TransactionPolicy<A>>,
+	/// The provider of the extrinsics pushed after the inherents.
+	local_extrinsics: Option<Arc<dyn LocalExtrinsics<A>>>,
 	/// phantom member to pin the `ProofRecording` type.
 	_phantom: PhantomData<PR>,
 }
@@ -97,6 +105,8 @@ impl<A, C, PR> Clone for ProposerFactory<A, C, PR> {
 			soft_deadline_percent: self.soft_deadline_percent,
 			telemetry: self.telemetry.clone(),
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			transaction_policy: self.transaction_policy.clone(),
+			local_extrinsics: self.local_extrinsics.clone(),
 			_phantom: self._phantom,
 		}
 	}
@@ -123,6 +133,8 @@ impl<A, C> ProposerFactory<A, C, DisableProofRecording> {
 			telemetry,
 			client,
 			include_proof_in_block_size_estimation: false,
+			transaction_policy: Arc::new(PriorityOrder),
+			local_extrinsics: None,
 			_phantom: PhantomData,
 		}
 	}
@@ -151,6 +163,8 @@ impl<A, C> ProposerFactory<A, C, EnableProofRecording> {
 			soft_deadline_percent: DEFAULT_SOFT_DEADLINE_PERCENT,
 			telemetry,
 			include_proof_in_block_size_estimation: true,
+			transaction_policy: Arc::new(PriorityOrder),
+			local_extrinsics: None,
 			_phantom: PhantomData,
 		}
 	}
@@ -188,6 +202,21 @@ impl<A, C, PR> ProposerFactory<A, C, PR> {
 	pub fn set_soft_deadline(&mut self, percent: Percent) {
 		self.soft_deadline_percent = percent;
 	}
+
+	/// Set the policy applied to the ready transactions when building a block.
+	///
+	/// The default [`PriorityOrder`] offers all ready transactions in the priority order of the
+	/// transaction pool.
+	pub fn set_transaction_policy(&mut self, policy: impl TransactionPolicy<A> + 'static) {
+		self.transaction_policy = Arc::new(policy);
+	}
+
+	/// Set the provider of the local extrinsics pushed right after the inherents of every block.
+	///
+	/// See the [`injection`](crate::injection) module for details.
+	pub fn set_local_extrinsics(&mut self, local_extrinsics: impl LocalExtrinsics<A> + 'static) {
+		self.local_extrinsics = Some(Arc::new(local_extrinsics));
+	}
 }
 
 impl<Block, C, A, PR> ProposerFactory<A, C, PR>
@@ -223,6 +252,8 @@ where
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			transaction_policy: self.transaction_policy.clone(),
+			local_extrinsics: self.local_extrinsics.clone(),
 		};
 
 		proposer
@@ -259,6 +290,8 @@ pub struct Proposer<Block: BlockT, C, A: TransactionPool, PR> {
 	include_proof_in_block_size_estimation: bool,
 	soft_deadline_percent: Percent,
 	telemetry: Option<TelemetryHandle>,
+	transaction_policy: Arc<dyn TransactionPolicy<A>>,
+	local_extrinsics: Option<Arc<dyn LocalExtrinsics<A>>>,
 	_phantom: PhantomData<PR>,
 }
 
@@ -339,9 +372,12 @@ where
 		self.apply_inherents(&mut block_builder, inherent_data)?;
 
 		let mode = block_builder.extrinsic_inclusion_mode();
+		let block_size_limit = block_size_limit.unwrap_or(self.default_block_size_limit);
 		let end_reason = match mode {
-			ExtrinsicInclusionMode::AllExtrinsics =>
-				self.apply_extrinsics(&mut block_builder, deadline, block_size_limit).await?,
+			ExtrinsicInclusionMode::AllExtrinsics => {
+				self.apply_local_extrinsics(&mut block_builder, block_size_limit);
+				self.apply_extrinsics(&mut block_builder, deadline, block_size_limit).await?
+			},
 			ExtrinsicInclusionMode::OnlyInherents => EndProposingReason::TransactionForbidden,
 		};
 		let (block, storage_changes, proof) = block_builder.build()?.into_inner();
@@ -398,12 +434,35 @@ where
 		Ok(())
 	}
 
+	/// Apply the local extrinsics to the block, dropping the ones which can't be applied.
+	fn apply_local_extrinsics(
+		&self,
+		block_builder: &mut sc_block_builder::BlockBuilder<'_, Block, C>,
+		block_size_limit: usize,
+	) {
+		let Some(local_extrinsics) = &self.local_extrinsics else { return };
+		for extrinsic in local_extrinsics.extrinsics(self.parent_hash) {
+			let block_size =
+				block_builder.estimate_block_size(self.include_proof_in_block_size_estimation);
+			if block_size + extrinsic.encoded_size() > block_size_limit {
+				warn!(
+					target: LOG_TARGET,
+					"❗️ Dropping local extrinsic overflowing the block size limit."
+				);
+				continue
+			}
+			if let Err(e) = block_builder.push(extrinsic) {
+				warn!(target: LOG_TARGET, "❗️ Dropping local extrinsic: {}", e);
+			}
+		}
+	}
+
 	/// Apply as many extrinsics as possible to the block.
 	async fn apply_extrinsics(
 		&self,
 		block_builder: &mut sc_block_builder::BlockBuilder<'_, Block, C>,
 		deadline: time::Instant,
-		block_size_limit: Option<usize>,
+		block_size_limit: usize,
 	) -> Result<EndProposingReason, sp_blockchain::Error> {
 		// proceed with transactions
 		// We calculate soft deadline used only in case we start skipping transactions.
@@ -416,10 +475,9 @@ where
 		let mut unqueue_invalid = TxInvalidityReportMap::new();
 
 		let delay = deadline.saturating_duration_since((self.now)()) / 8;
-		let mut pending_iterator =
-			self.transaction_pool.ready_at_with_timeout(self.parent_hash, delay).await;
-
-		let block_size_limit = block_size_limit.unwrap_or(self.default_block_size_limit);
+		let mut pending_iterator = self
+			.transaction_policy
+			.apply(self.transaction_pool.ready_at_with_timeout(self.parent_hash, delay).await);
 
 		debug!(target: LOG_TARGET, "Attempting to push transactions from the pool at {:?}.", self.parent_hash);
 		let mut transaction_pushed = false;
@@ -607,7 +665,7 @@ mod tests {
 	use futures::executor::block_on;
 	use parking_lot::Mutex;
 	use sc_client_api::Backend;
-	use sc_transaction_pool::BasicPool;
+	use sc_transaction_pool::{BasicPool, FullChainApi};
 	use sc_transaction_pool_api::{ChainEvent, MaintainedTransactionPool, TransactionSource};
 	use sp_api::Core;
 	use sp_blockchain::HeaderBackend;
@@ -616,11 +674,13 @@ mod tests {
 	use substrate_test_runtime_client::{
 		prelude::*,
 		runtime::{Block as TestBlock, Extrinsic, ExtrinsicBuilder, Transfer},
-		TestClientBuilder, TestClientBuilderExt,
+		Backend as TestBackend, Client as TestClient, TestClientBuilder, TestClientBuilderExt,
 	};
 
 	const SOURCE: TransactionSource = TransactionSource::External;
 
+	type TestPool = BasicPool<FullChainApi<TestClient<TestBackend>, TestBlock>, TestBlock>;
+
 	// Note:
 	// Maximum normal extrinsic size for `substrate_test_runtime` is ~65% of max_block (refer to
 	// `substrate_test_runtime::RuntimeBlockWeights` for details).
@@ -1158,4 +1218,94 @@ mod tests {
 			"Not enough calls to current time, which indicates the test might have ended because of deadline, not soft deadline"
 		);
 	}
+	#[test]
+	fn transaction_policy_filters_ready_transactions() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = Arc::from(BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		));
+		let genesis_hash = client.info().genesis_hash;
+
+		let transfer = |nonce| {
+			Transfer {
+				from: Sr25519Keyring::Alice.into(),
+				to: Sr25519Keyring::Bob.into(),
+				amount: 1,
+				nonce,
+			}
+			.into_unchecked_extrinsic()
+		};
+		let blocked = transfer(1);
+		let xts = vec![transfer(0), blocked.clone(), transfer(2)];
+		block_on(txpool.submit_at(genesis_hash, SOURCE, xts)).unwrap();
+		block_on(txpool.maintain(chain_event(client.expect_header(genesis_hash).unwrap())));
+
+		let mut proposer_factory =
+			ProposerFactory::new(spawner.clone(), client.clone(), txpool.clone(), None, None);
+		proposer_factory.set_transaction_policy(crate::policy::Filter(
+			move |tx: &<TestPool as TransactionPool>::InPoolTransaction| **tx.data() != blocked,
+		));
+
+		let proposer =
+			block_on(proposer_factory.init(&client.expect_header(genesis_hash).unwrap())).unwrap();
+		let deadline = time::Duration::from_secs(9);
+		let block =
+			block_on(proposer.propose(Default::default(), Default::default(), deadline, None))
+				.map(|r| r.block)
+				.unwrap();
+
+		// The transaction depending on the filtered one is skipped too.
+		assert_eq!(block.extrinsics(), &[transfer(0)]);
+		// Filtered transactions stay in the pool.
+		assert_eq!(txpool.ready().count(), 3);
+	}
+	#[test]
+	fn local_extrinsics_are_pushed_before_pool_transactions() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = Arc::from(BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		));
+		let genesis_hash = client.info().genesis_hash;
+
+		let transfer = |from: Sr25519Keyring, nonce| {
+			Transfer { from: from.into(), to: Sr25519Keyring::Bob.into(), amount: 1, nonce }
+				.into_unchecked_extrinsic()
+		};
+		block_on(txpool.submit_at(genesis_hash, SOURCE, vec![transfer(Sr25519Keyring::Alice, 0)]))
+			.unwrap();
+		block_on(txpool.maintain(chain_event(client.expect_header(genesis_hash).unwrap())));
+
+		let queue = crate::LocalExtrinsicQueue::new();
+		queue.push(transfer(Sr25519Keyring::Charlie, 0));
+		// Invalid, as the nonce is too high.
+		queue.push(transfer(Sr25519Keyring::Charlie, 5));
+
+		let mut proposer_factory =
+			ProposerFactory::new(spawner.clone(), client.clone(), txpool.clone(), None, None);
+		proposer_factory.set_local_extrinsics(queue.clone());
+
+		let proposer =
+			block_on(proposer_factory.init(&client.expect_header(genesis_hash).unwrap())).unwrap();
+		let deadline = time::Duration::from_secs(9);
+		let block =
+			block_on(proposer.propose(Default::default(), Default::default(), deadline, None))
+				.map(|r| r.block)
+				.unwrap();
+
+		assert_eq!(
+			block.extrinsics(),
+			&[transfer(Sr25519Keyring::Charlie, 0), transfer(Sr25519Keyring::Alice, 0)],
+		);
+		assert!(queue.is_empty());
+	}
 }
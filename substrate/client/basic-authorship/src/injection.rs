@@ -0,0 +1,90 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Injection of locally crafted extrinsics into authored blocks.
+//!
+//! Some chains need their block authors to include operational extrinsics, like oracle updates,
+//! in every block. A [`LocalExtrinsics`] provider set with
+//! [`ProposerFactory::set_local_extrinsics`] supplies such extrinsics, signed or unsigned. They
+//! are pushed right after the inherents and before any transaction of the pool, in the order
+//! given by the provider.
+//!
+//! Local extrinsics are applied like any other extrinsic: the runtime checks their validity and
+//! accounts their weight. An extrinsic which is invalid, exhausts the resources of the block or
+//! doesn't fit into the block size limit is dropped, without failing the block.
+//!
+//! [`ProposerFactory::set_local_extrinsics`]: crate::ProposerFactory::set_local_extrinsics
+
+use parking_lot::Mutex;
+use sc_transaction_pool_api::{BlockHash, TransactionFor, TransactionPool};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Provider of the local extrinsics of the blocks authored on top of the pool `A`.
+pub trait LocalExtrinsics<A>: Send + Sync {
+	/// The extrinsics to include in the block built on top of `parent_hash`, in order.
+	fn extrinsics(&self, parent_hash: BlockHash<A>) -> Vec<TransactionFor<A>>
+	where
+		A: TransactionPool;
+}
+
+/// A queue of local extrinsics, each included in the next authored block.
+///
+/// Queued extrinsics are taken by the next block built, whether or not the block is imported
+/// afterwards. Services requiring the inclusion of an extrinsic must queue it again if it doesn't
+/// show up in the chain.
+pub struct LocalExtrinsicQueue<E>(Arc<Mutex<VecDeque<E>>>);
+
+impl<E> LocalExtrinsicQueue<E> {
+	/// Create an empty queue.
+	pub fn new() -> Self {
+		Self(Default::default())
+	}
+
+	/// Queue `extrinsic` for inclusion in the next authored block.
+	pub fn push(&self, extrinsic: E) {
+		self.0.lock().push_back(extrinsic);
+	}
+
+	/// The number of queued extrinsics.
+	pub fn len(&self) -> usize {
+		self.0.lock().len()
+	}
+
+	/// Whether no extrinsic is queued.
+	pub fn is_empty(&self) -> bool {
+		self.0.lock().is_empty()
+	}
+}
+
+impl<E> Default for LocalExtrinsicQueue<E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<E> Clone for LocalExtrinsicQueue<E> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<A: TransactionPool> LocalExtrinsics<A> for LocalExtrinsicQueue<TransactionFor<A>> {
+	fn extrinsics(&self, _parent_hash: BlockHash<A>) -> Vec<TransactionFor<A>> {
+		self.0.lock().drain(..).collect()
+	}
+}
@@ -71,5 +71,9 @@
 //! ```
 
 mod basic_authorship;
+pub mod injection;
+pub mod policy;
 
 pub use crate::basic_authorship::{Proposer, ProposerFactory, DEFAULT_BLOCK_SIZE_LIMIT};
+pub use injection::{LocalExtrinsicQueue, LocalExtrinsics};
+pub use policy::TransactionPolicy;
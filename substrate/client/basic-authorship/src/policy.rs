@@ -0,0 +1,198 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Policies deciding which ready transactions are offered to the block builder, and in which
+//! order.
+//!
+//! By default, transactions are offered in the priority order of the transaction pool. A
+//! [`TransactionPolicy`] set with [`ProposerFactory::set_transaction_policy`] can filter or
+//! reorder them, e.g. to enforce a blacklist or to cap the number of transactions per account in a
+//! block.
+//!
+//! [`ProposerFactory::set_transaction_policy`]: crate::ProposerFactory::set_transaction_policy
+
+use sc_transaction_pool_api::{ReadyTransactions, TransactionPool};
+use std::{collections::HashMap, sync::Arc};
+
+/// The ready transactions of the transaction pool `A`.
+pub type ReadyTransactionsOf<A> =
+	Box<dyn ReadyTransactions<Item = Arc<<A as TransactionPool>::InPoolTransaction>> + Send>;
+
+/// Policy applied to the ready transactions of the pool `A` when authoring a block.
+pub trait TransactionPolicy<A>: Send + Sync {
+	/// Wrap the ready transactions of the pool, in priority order, for a new block.
+	///
+	/// A transaction may only be offered after the transactions it depends on, so reordering must
+	/// preserve the relative order of the transactions of an account. Transactions which are
+	/// skipped must be reported as invalid to `ready`, so that their dependents are skipped as
+	/// well. Reports of invalid transactions must be forwarded to `ready`.
+	fn apply(&self, ready: ReadyTransactionsOf<A>) -> ReadyTransactionsOf<A>
+	where
+		A: TransactionPool;
+}
+
+/// The default policy, offering all ready transactions in the priority order of the pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriorityOrder;
+
+impl<A> TransactionPolicy<A> for PriorityOrder {
+	fn apply(&self, ready: ReadyTransactionsOf<A>) -> ReadyTransactionsOf<A>
+	where
+		A: TransactionPool,
+	{
+		ready
+	}
+}
+
+/// A policy only offering the transactions matching a predicate, e.g. to enforce a blacklist.
+///
+/// Transactions depending on a skipped transaction are skipped too.
+pub struct Filter<F>(pub F);
+
+impl<A, F> TransactionPolicy<A> for Filter<F>
+where
+	A: TransactionPool + 'static,
+	F: Fn(&A::InPoolTransaction) -> bool + Clone + Send + Sync + 'static,
+{
+	fn apply(&self, ready: ReadyTransactionsOf<A>) -> ReadyTransactionsOf<A> {
+		Box::new(Filtered { inner: ready, predicate: self.0.clone() })
+	}
+}
+
+/// A policy offering at most `limit` transactions per key in a block, e.g. per account.
+///
+/// The key of a transaction is computed by the given function, transactions without key are not
+/// limited. Transactions depending on a skipped transaction are skipped too.
+pub struct LimitPerKey<F> {
+	key: F,
+	limit: usize,
+}
+
+impl<F> LimitPerKey<F> {
+	/// Create a policy offering at most `limit` transactions with the same `key`.
+	pub fn new(key: F, limit: usize) -> Self {
+		Self { key, limit }
+	}
+}
+
+impl<A, F> TransactionPolicy<A> for LimitPerKey<F>
+where
+	A: TransactionPool + 'static,
+	F: Fn(&A::InPoolTransaction) -> Option<Vec<u8>> + Clone + Send + Sync + 'static,
+{
+	fn apply(&self, ready: ReadyTransactionsOf<A>) -> ReadyTransactionsOf<A> {
+		let key = self.key.clone();
+		let limit = self.limit;
+		let mut counts = HashMap::new();
+		let predicate = move |tx: &A::InPoolTransaction| match key(tx) {
+			Some(key) => {
+				let count = counts.entry(key).or_insert(0);
+				*count += 1;
+				*count <= limit
+			},
+			None => true,
+		};
+		Box::new(Filtered { inner: ready, predicate })
+	}
+}
+
+/// Ready transactions skipping the ones not matching a predicate.
+struct Filtered<I, F> {
+	inner: I,
+	predicate: F,
+}
+
+impl<T, I, F> Iterator for Filtered<I, F>
+where
+	I: ReadyTransactions<Item = Arc<T>>,
+	F: FnMut(&T) -> bool,
+{
+	type Item = Arc<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let tx = self.inner.next()?;
+			if (self.predicate)(&tx) {
+				return Some(tx)
+			}
+			self.inner.report_invalid(&tx);
+		}
+	}
+}
+
+impl<T, I, F> ReadyTransactions for Filtered<I, F>
+where
+	I: ReadyTransactions<Item = Arc<T>>,
+	F: FnMut(&T) -> bool,
+{
+	fn report_invalid(&mut self, tx: &Self::Item) {
+		self.inner.report_invalid(tx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Ready transactions where every transaction depends on the previous one with the same tens.
+	struct Ready {
+		txs: std::vec::IntoIter<Arc<u32>>,
+		invalid: Vec<u32>,
+	}
+
+	impl Iterator for Ready {
+		type Item = Arc<u32>;
+
+		fn next(&mut self) -> Option<Self::Item> {
+			let invalid = &self.invalid;
+			self.txs.find(|tx| !invalid.contains(&(**tx / 10)))
+		}
+	}
+
+	impl ReadyTransactions for Ready {
+		fn report_invalid(&mut self, tx: &Self::Item) {
+			self.invalid.push(**tx / 10)
+		}
+	}
+
+	fn ready(txs: &[u32]) -> Ready {
+		Ready {
+			txs: txs.iter().copied().map(Arc::new).collect::<Vec<_>>().into_iter(),
+			invalid: vec![],
+		}
+	}
+
+	fn collect(ready: impl Iterator<Item = Arc<u32>>) -> Vec<u32> {
+		ready.map(|tx| *tx).collect()
+	}
+
+	#[test]
+	fn filter_skips_dependents() {
+		let filtered =
+			Filtered { inner: ready(&[10, 20, 11, 21, 12]), predicate: |tx: &u32| *tx != 11 };
+		assert_eq!(collect(filtered), vec![10, 20, 21]);
+	}
+
+	#[test]
+	fn reports_are_forwarded() {
+		let mut filtered = Filtered { inner: ready(&[10, 20, 11, 21]), predicate: |_: &u32| true };
+		let first = filtered.next().unwrap();
+		filtered.report_invalid(&first);
+		assert_eq!(collect(filtered), vec![20, 21]);
+	}
+}
@@ -0,0 +1,137 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Encoding of the exported messages.
+
+use clap::ValueEnum;
+use codec::Encode;
+use scale_info::PortableRegistry;
+use serde_json::{json, Value};
+
+/// The encoding of the exported messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Encoding {
+	/// The SCALE encoding used by the node.
+	///
+	/// Headers are SCALE encoded, extrinsics are the SCALE encoded `Vec` of the extrinsics of the
+	/// block and events are the raw value of the `System::Events` storage.
+	#[default]
+	Scale,
+	/// JSON objects.
+	///
+	/// Events are decoded with the types of the runtime metadata, extrinsics are hex encoded.
+	Json,
+}
+
+/// The types needed to decode the events of a runtime into JSON.
+pub(crate) struct EventTypes {
+	pub(crate) registry: PortableRegistry,
+	pub(crate) events_ty: u32,
+}
+
+/// The messages exported for one block.
+pub(crate) struct Messages {
+	pub(crate) header: Vec<u8>,
+	pub(crate) extrinsics: Vec<u8>,
+	pub(crate) events: Vec<u8>,
+}
+
+impl Encoding {
+	/// Encode the messages of a block.
+	///
+	/// `events` is the raw value of the `System::Events` storage, `event_types` are only used for
+	/// JSON encoding.
+	pub(crate) fn encode<Header: Encode + serde::Serialize, Extrinsic: Encode>(
+		self,
+		number: u64,
+		hash: &[u8],
+		header: &Header,
+		extrinsics: &[Extrinsic],
+		events: Option<Vec<u8>>,
+		event_types: Option<&EventTypes>,
+	) -> Result<Messages, String> {
+		match self {
+			Self::Scale => Ok(Messages {
+				header: header.encode(),
+				extrinsics: extrinsics.encode(),
+				events: events.unwrap_or_else(|| Vec::<()>::new().encode()),
+			}),
+			Self::Json => {
+				let hash = array_bytes::bytes2hex("0x", hash);
+				let extrinsics: Vec<_> =
+					extrinsics.iter().map(|xt| array_bytes::bytes2hex("0x", xt.encode())).collect();
+				let events = match (events, event_types) {
+					(Some(events), Some(types)) => sc_runtime_metadata::decode::decode_value(
+						&types.registry,
+						types.events_ty,
+						&mut &events[..],
+					)
+					.map_err(|e| format!("Failed to decode events: {e}"))?,
+					(Some(events), None) => Value::String(array_bytes::bytes2hex("0x", events)),
+					(None, _) => Value::Array(Vec::new()),
+				};
+
+				let to_vec = |value: Value| serde_json::to_vec(&value).map_err(|e| e.to_string());
+				Ok(Messages {
+					header: to_vec(json!({ "number": number, "hash": hash, "header": header }))?,
+					extrinsics: to_vec(
+						json!({ "number": number, "hash": hash, "extrinsics": extrinsics }),
+					)?,
+					events: to_vec(json!({ "number": number, "hash": hash, "events": events }))?,
+				})
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Encode, serde::Serialize)]
+	struct Header {
+		number: u32,
+	}
+
+	#[test]
+	fn scale_encoding_works() {
+		let messages = Encoding::Scale
+			.encode(7, &[1; 32], &Header { number: 7 }, &[vec![1u8, 2]], None, None)
+			.unwrap();
+		assert_eq!(messages.header, 7u32.encode());
+		assert_eq!(messages.extrinsics, vec![vec![1u8, 2]].encode());
+		assert_eq!(messages.events, vec![0]);
+	}
+
+	#[test]
+	fn json_encoding_works() {
+		let messages = Encoding::Json
+			.encode(7, &[1; 2], &Header { number: 7 }, &[vec![1u8, 2]], Some(vec![0]), None)
+			.unwrap();
+		let header: Value = serde_json::from_slice(&messages.header).unwrap();
+		let extrinsics: Value = serde_json::from_slice(&messages.extrinsics).unwrap();
+		let events: Value = serde_json::from_slice(&messages.events).unwrap();
+
+		assert_eq!(header, json!({ "number": 7, "hash": "0x0101", "header": { "number": 7 } }));
+		assert_eq!(
+			extrinsics,
+			json!({ "number": 7, "hash": "0x0101", "extrinsics": ["0x080102"] })
+		);
+		assert_eq!(events, json!({ "number": 7, "hash": "0x0101", "events": "0x00" }));
+	}
+}
@@ -0,0 +1,307 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Streaming exporter of finalized chain data.
+//!
+//! Publishes the header, the extrinsics and the events of every finalized block to the
+//! `<prefix>.headers`, `<prefix>.extrinsics` and `<prefix>.events` topics of a Kafka cluster or
+//! NATS JetStream server. The messages are keyed by the block hash and encoded as configured by
+//! [`Encoding`].
+//!
+//! Delivery is at-least-once: the last block whose messages were all acknowledged by the broker is
+//! stored in the aux database, and the exporter resumes after it when the node restarts. Blocks
+//! are exported in order, a failed block is retried until the broker accepts it.
+//!
+//! The Kafka and NATS publishers are behind the `kafka` and `nats` features.
+
+mod encoding;
+mod publisher;
+
+pub use encoding::Encoding;
+#[cfg(feature = "kafka")]
+pub use publisher::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use publisher::NatsPublisher;
+pub use publisher::Publisher;
+
+use clap::Args;
+use codec::{Decode, Encode};
+use encoding::EventTypes;
+use frame_metadata::v15::StorageEntryType;
+use futures::StreamExt;
+use sc_client_api::{AuxStore, Backend, BlockBackend, BlockchainEvents, StorageProvider};
+use sc_runtime_metadata::RuntimeCache;
+use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor, One},
+	SaturatedConversion,
+};
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+const LOG_TARGET: &str = "chain-exporter";
+
+/// Aux store key of the last exported block.
+const LAST_EXPORTED_KEY: &[u8] = b"chain_exporter_last_exported";
+
+/// The delay before a failed block is exported again, doubled after every attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum delay between two attempts to export a block.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Parameters used to configure the chain exporter.
+#[derive(Debug, Clone, Args)]
+pub struct ExporterParams {
+	/// Publish finalized headers, extrinsics and events to a message broker.
+	///
+	/// Either `kafka://<broker>[,<broker>...]` or `nats://<host>:<port>`. The exporter is disabled
+	/// if no URL is given.
+	#[arg(long, value_name = "URL")]
+	pub exporter: Option<String>,
+
+	/// Prefix of the topics the chain data is published to.
+	#[arg(long, value_name = "PREFIX", default_value = "substrate")]
+	pub exporter_topic_prefix: String,
+
+	/// Encoding of the published messages.
+	#[arg(long, value_name = "ENCODING", value_enum, default_value_t = Encoding::Scale)]
+	pub exporter_encoding: Encoding,
+}
+
+/// A message broker URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrokerUrl {
+	/// Comma separated Kafka bootstrap brokers.
+	Kafka(String),
+	/// The URL of a NATS server.
+	Nats(String),
+}
+
+impl BrokerUrl {
+	/// Parse an URL given to `--exporter`.
+	pub fn parse(url: &str) -> Result<Self, String> {
+		if let Some(brokers) = url.strip_prefix("kafka://") {
+			Ok(Self::Kafka(brokers.to_string()))
+		} else if url.starts_with("nats://") {
+			Ok(Self::Nats(url.to_string()))
+		} else {
+			Err(format!("Unsupported exporter URL `{url}`, expected `kafka://` or `nats://`"))
+		}
+	}
+
+	/// Connect to the broker.
+	pub async fn connect(self) -> Result<Arc<dyn Publisher>, String> {
+		match self {
+			#[cfg(feature = "kafka")]
+			Self::Kafka(brokers) => Ok(Arc::new(KafkaPublisher::new(&brokers)?)),
+			#[cfg(feature = "nats")]
+			Self::Nats(url) => Ok(Arc::new(NatsPublisher::new(&url).await?)),
+			#[allow(unreachable_patterns)]
+			url => Err(format!("Support for {url:?} is not compiled in")),
+		}
+	}
+}
+
+/// Exports finalized blocks to a message broker.
+pub struct ChainExporter<Block, BE, Client> {
+	client: Arc<Client>,
+	publisher: Arc<dyn Publisher>,
+	topics: [String; 3],
+	encoding: Encoding,
+	/// The event types of the last seen runtime, `None` if its events can't be decoded.
+	event_types: RuntimeCache<Option<Arc<EventTypes>>>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+impl<Block, BE, Client> ChainExporter<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ AuxStore
+		+ ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>,
+	Client::Api: Metadata<Block>,
+{
+	/// Create an exporter publishing to `publisher`.
+	pub fn new(
+		client: Arc<Client>,
+		publisher: Arc<dyn Publisher>,
+		topic_prefix: &str,
+		encoding: Encoding,
+	) -> Self {
+		Self {
+			client,
+			publisher,
+			topics: ["headers", "extrinsics", "events"]
+				.map(|topic| format!("{topic_prefix}.{topic}")),
+			encoding,
+			event_types: RuntimeCache::default(),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Returns a never-ending `Future` that exports every finalized block.
+	///
+	/// Without a previously exported block, the export starts at the block finalized next.
+	pub async fn run(mut self) {
+		let mut finality = self.client.finality_notification_stream();
+		let mut next = match self.last_exported() {
+			Ok(Some(number)) => number + One::one(),
+			Ok(None) => self.client.info().finalized_number + One::one(),
+			Err(e) => {
+				log::error!(target: LOG_TARGET, "Failed to read the last exported block: {e}");
+				return
+			},
+		};
+
+		loop {
+			let finalized = self.client.info().finalized_number;
+			while next <= finalized {
+				self.export_with_retry(next).await;
+				next += One::one();
+			}
+			if finality.next().await.is_none() {
+				return
+			}
+		}
+	}
+
+	/// The number of the last block which was completely exported.
+	fn last_exported(&self) -> Result<Option<NumberFor<Block>>, String> {
+		self.client
+			.get_aux(LAST_EXPORTED_KEY)
+			.map_err(|e| e.to_string())?
+			.map(|value| NumberFor::<Block>::decode(&mut &value[..]).map_err(|e| e.to_string()))
+			.transpose()
+	}
+
+	/// Export the finalized block `number`, retrying until it succeeds.
+	async fn export_with_retry(&mut self, number: NumberFor<Block>) {
+		let mut delay = INITIAL_RETRY_DELAY;
+		loop {
+			match self.export(number).await {
+				Ok(()) => return,
+				Err(e) => log::warn!(
+					target: LOG_TARGET,
+					"Failed to export block #{number}, retrying in {delay:?}: {e}",
+				),
+			}
+			futures_timer::Delay::new(delay).await;
+			delay = (delay * 2).min(MAX_RETRY_DELAY);
+		}
+	}
+
+	/// Publish the messages of the finalized block `number` and record it as exported.
+	async fn export(&mut self, number: NumberFor<Block>) -> Result<(), String> {
+		let hash = self
+			.client
+			.hash(number)
+			.map_err(|e| e.to_string())?
+			.ok_or("Finalized block not found")?;
+		let header = self.client.header(hash).map_err(|e| e.to_string())?.ok_or("Unknown block")?;
+		let extrinsics =
+			self.client.block_body(hash).map_err(|e| e.to_string())?.unwrap_or_default();
+
+		let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+		key.extend(sp_crypto_hashing::twox_128(b"Events"));
+		let events = self
+			.client
+			.storage(hash, &StorageKey(key))
+			.map_err(|e| e.to_string())?
+			.map(|data| data.0);
+		let event_types = match self.encoding {
+			Encoding::Json => self.event_types(hash)?,
+			Encoding::Scale => None,
+		};
+
+		let messages = self.encoding.encode(
+			number.saturated_into(),
+			hash.as_ref(),
+			&header,
+			&extrinsics,
+			events,
+			event_types.as_deref(),
+		)?;
+		let [headers, extrinsics, events] = &self.topics;
+		for (topic, payload) in [
+			(headers, messages.header),
+			(extrinsics, messages.extrinsics),
+			(events, messages.events),
+		] {
+			self.publisher.publish(topic, hash.as_ref(), payload).await?;
+		}
+
+		self.client
+			.insert_aux(&[(LAST_EXPORTED_KEY, number.encode().as_slice())], &[])
+			.map_err(|e| e.to_string())
+	}
+
+	/// The event types of the runtime at `hash`, or `None` if its events can't be decoded.
+	fn event_types(&self, hash: Block::Hash) -> Result<Option<Arc<EventTypes>>, String> {
+		let spec_version =
+			self.client.runtime_version_at(hash).map_err(|e| e.to_string())?.spec_version;
+		self.event_types.get_or_try_insert_with(spec_version, || {
+			let metadata = sc_runtime_metadata::metadata_at::<Block, _>(&*self.client, hash)?;
+			let event_types = metadata.and_then(|metadata| {
+				metadata
+					.pallets
+					.iter()
+					.find(|pallet| pallet.name == "System")
+					.and_then(|pallet| pallet.storage.as_ref())
+					.and_then(|storage| storage.entries.iter().find(|entry| entry.name == "Events"))
+					.and_then(|entry| match &entry.ty {
+						StorageEntryType::Plain(ty) => Some(ty.id),
+						StorageEntryType::Map { .. } => None,
+					})
+					.map(|events_ty| Arc::new(EventTypes { registry: metadata.types, events_ty }))
+			});
+			if event_types.is_none() {
+				log::warn!(
+					target: LOG_TARGET,
+					"Runtime version {spec_version} has no decodable events, events are \
+					exported as hex",
+				);
+			}
+			Ok(event_types)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn broker_url_parsing_works() {
+		assert_eq!(
+			BrokerUrl::parse("kafka://a:9092,b:9092"),
+			Ok(BrokerUrl::Kafka("a:9092,b:9092".into())),
+		);
+		assert_eq!(
+			BrokerUrl::parse("nats://localhost:4222"),
+			Ok(BrokerUrl::Nats("nats://localhost:4222".into())),
+		);
+		assert!(BrokerUrl::parse("http://localhost").is_err());
+	}
+}
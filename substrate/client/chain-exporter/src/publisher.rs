@@ -0,0 +1,99 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Message brokers the exporter publishes to.
+
+use async_trait::async_trait;
+
+/// A message broker.
+#[async_trait]
+pub trait Publisher: Send + Sync + 'static {
+	/// Publish `payload` to `topic` and wait until the broker acknowledged it.
+	///
+	/// The exporter only records a block as exported once all its messages were acknowledged.
+	async fn publish(&self, topic: &str, key: &[u8], payload: Vec<u8>) -> Result<(), String>;
+}
+
+/// Publishes to Kafka topics.
+#[cfg(feature = "kafka")]
+pub struct KafkaPublisher {
+	producer: rdkafka::producer::FutureProducer,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaPublisher {
+	/// Connect to the Kafka cluster with the given comma separated bootstrap brokers.
+	pub fn new(brokers: &str) -> Result<Self, String> {
+		let producer = rdkafka::ClientConfig::new()
+			.set("bootstrap.servers", brokers)
+			// Ordering within a topic must survive retries of the producer.
+			.set("enable.idempotence", "true")
+			.set("acks", "all")
+			.create()
+			.map_err(|e| e.to_string())?;
+		Ok(Self { producer })
+	}
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl Publisher for KafkaPublisher {
+	async fn publish(&self, topic: &str, key: &[u8], payload: Vec<u8>) -> Result<(), String> {
+		let record = rdkafka::producer::FutureRecord::to(topic).key(key).payload(&payload);
+		self.producer
+			.send(record, rdkafka::util::Timeout::Never)
+			.await
+			.map(drop)
+			.map_err(|(e, _)| e.to_string())
+	}
+}
+
+/// Publishes to NATS JetStream subjects.
+#[cfg(feature = "nats")]
+pub struct NatsPublisher {
+	jetstream: async_nats::jetstream::Context,
+}
+
+#[cfg(feature = "nats")]
+impl NatsPublisher {
+	/// Connect to the NATS server at `url`.
+	///
+	/// The subjects must be bound to a JetStream stream, so that publishing is acknowledged.
+	pub async fn new(url: &str) -> Result<Self, String> {
+		let client = async_nats::connect(url).await.map_err(|e| e.to_string())?;
+		Ok(Self { jetstream: async_nats::jetstream::new(client) })
+	}
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl Publisher for NatsPublisher {
+	async fn publish(&self, topic: &str, key: &[u8], payload: Vec<u8>) -> Result<(), String> {
+		let mut headers = async_nats::HeaderMap::new();
+		// Lets JetStream drop duplicates caused by re-publishing after a restart.
+		headers
+			.insert("Nats-Msg-Id", format!("{topic}-{}", array_bytes::bytes2hex("", key)).as_str());
+		self.jetstream
+			.publish_with_headers(topic.to_string(), headers, payload.into())
+			.await
+			.map_err(|e| e.to_string())?
+			.await
+			.map(drop)
+			.map_err(|e| e.to_string())
+	}
+}
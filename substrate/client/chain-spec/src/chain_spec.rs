@@ -20,7 +20,7 @@
 #![warn(missing_docs)]
 use crate::{
 	extension::GetExtension, genesis_config_builder::HostFunctions, ChainType,
-	GenesisConfigBuilderRuntimeCaller as RuntimeCaller, Properties,
+	GenesisConfigBuilderRuntimeCaller as RuntimeCaller, HostedCode, Properties,
 };
 use sc_network::config::MultiaddrWithPeerId;
 use sc_telemetry::TelemetryEndpoints;
@@ -36,7 +36,7 @@ use std::{
 	collections::{BTreeMap, VecDeque},
 	fs::File,
 	marker::PhantomData,
-	path::PathBuf,
+	path::{Path, PathBuf},
 };
 
 #[derive(Serialize, Deserialize)]
@@ -114,17 +114,20 @@ impl<EHF: HostFunctions> GenesisSource<EHF> {
 				Ok(Genesis::RuntimeGenesis(RuntimeGenesisInner {
 					json_blob: RuntimeGenesisConfigJson::Config(config.clone()),
 					code: code.clone(),
+					hosted_code: None,
 				})),
 			Self::GenesisBuilderApi(GenesisBuildAction::Patch(patch), code) =>
 				Ok(Genesis::RuntimeGenesis(RuntimeGenesisInner {
 					json_blob: RuntimeGenesisConfigJson::Patch(patch.clone()),
 					code: code.clone(),
+					hosted_code: None,
 				})),
 			Self::GenesisBuilderApi(GenesisBuildAction::NamedPreset(name, _), code) => {
 				let patch = RuntimeCaller::<EHF>::new(&code[..]).get_named_preset(Some(name))?;
 				Ok(Genesis::RuntimeGenesis(RuntimeGenesisInner {
 					json_blob: RuntimeGenesisConfigJson::Patch(patch),
 					code: code.clone(),
+					hosted_code: None,
 				}))
 			},
 		}
@@ -136,7 +139,7 @@ where
 	EHF: HostFunctions,
 {
 	fn assimilate_storage(&self, storage: &mut Storage) -> Result<(), String> {
-		match self.genesis.resolve()? {
+		match self.genesis.resolve()?.with_code(self.code_cache_dir.as_deref())? {
 			Genesis::Raw(RawGenesis { top: map, children_default: children_map }) => {
 				storage.top.extend(map.into_iter().map(|(k, v)| (k.0, v.0)));
 				children_map.into_iter().for_each(|(k, v)| {
@@ -157,6 +160,7 @@ where
 			Genesis::RuntimeGenesis(RuntimeGenesisInner {
 				json_blob: RuntimeGenesisConfigJson::Config(config),
 				code,
+				..
 			}) => {
 				RuntimeCaller::<EHF>::new(&code[..])
 					.get_storage_for_config(config)?
@@ -168,6 +172,7 @@ where
 			Genesis::RuntimeGenesis(RuntimeGenesisInner {
 				json_blob: RuntimeGenesisConfigJson::Patch(patch),
 				code,
+				..
 			}) => {
 				RuntimeCaller::<EHF>::new(&code[..])
 					.get_storage_for_patch(patch)?
@@ -222,6 +227,9 @@ struct RuntimeGenesisInner {
 	/// The code shall be capable of parsing `json_blob`.
 	#[serde(default, with = "sp_core::bytes")]
 	code: Vec<u8>,
+	/// Reference to the runtime wasm code, used if `code` is empty.
+	#[serde(default, rename = "hostedCode", skip_serializing_if = "Option::is_none")]
+	hosted_code: Option<HostedCode>,
 	/// The patch or full representation of runtime's `RuntimeGenesisConfig` struct.
 	#[serde(flatten)]
 	json_blob: RuntimeGenesisConfigJson,
@@ -257,6 +265,24 @@ enum Genesis {
 	RuntimeGenesis(RuntimeGenesisInner),
 }
 
+impl Genesis {
+	/// Fetch the runtime code of the [`Genesis::RuntimeGenesis`] format, if it references hosted
+	/// code instead of containing it.
+	///
+	/// The code is cached in `cache_dir`, if given.
+	fn with_code(self, cache_dir: Option<&Path>) -> Result<Self, String> {
+		match self {
+			Genesis::RuntimeGenesis(mut inner) if inner.code.is_empty() => {
+				if let Some(hosted_code) = &inner.hosted_code {
+					inner.code = hosted_code.fetch(cache_dir)?;
+				}
+				Ok(Genesis::RuntimeGenesis(inner))
+			},
+			genesis => Ok(genesis),
+		}
+	}
+}
+
 /// A configuration of a client. Does not include runtime storage initialization.
 /// Note: `genesis` field is ignored due to way how the chain specification is serialized into
 /// JSON file. Refer to [`ChainSpecJsonContainer`], which flattens [`ClientSpec`] and denies unknown
@@ -433,6 +459,7 @@ impl<E, EHF> ChainSpecBuilder<E, EHF> {
 		ChainSpec {
 			client_spec,
 			genesis: GenesisSource::GenesisBuilderApi(self.genesis_build_action, self.code.into()),
+			code_cache_dir: None,
 			_host_functions: Default::default(),
 		}
 	}
@@ -446,6 +473,8 @@ impl<E, EHF> ChainSpecBuilder<E, EHF> {
 pub struct ChainSpec<E = NoExtension, EHF = ()> {
 	client_spec: ClientSpec<E>,
 	genesis: GenesisSource<EHF>,
+	/// The directory hosted runtime code is cached in.
+	code_cache_dir: Option<PathBuf>,
 	_host_functions: PhantomData<EHF>,
 }
 
@@ -454,6 +483,7 @@ impl<E: Clone, EHF> Clone for ChainSpec<E, EHF> {
 		ChainSpec {
 			client_spec: self.client_spec.clone(),
 			genesis: self.genesis.clone(),
+			code_cache_dir: self.code_cache_dir.clone(),
 			_host_functions: self._host_functions,
 		}
 	}
@@ -533,6 +563,7 @@ impl<E: serde::de::DeserializeOwned, EHF> ChainSpec<E, EHF> {
 		Ok(ChainSpec {
 			client_spec,
 			genesis: GenesisSource::Binary(json),
+			code_cache_dir: None,
 			_host_functions: Default::default(),
 		})
 	}
@@ -556,6 +587,7 @@ impl<E: serde::de::DeserializeOwned, EHF> ChainSpec<E, EHF> {
 		Ok(ChainSpec {
 			client_spec,
 			genesis: GenesisSource::File(path),
+			code_cache_dir: None,
 			_host_functions: Default::default(),
 		})
 	}
@@ -577,12 +609,16 @@ where
 	EHF: HostFunctions,
 {
 	fn json_container(&self, raw: bool) -> Result<ChainSpecJsonContainer<E>, String> {
-		let raw_genesis = match (raw, self.genesis.resolve()?) {
+		let genesis = self.genesis.resolve()?;
+		let genesis =
+			if raw { genesis.with_code(self.code_cache_dir.as_deref())? } else { genesis };
+		let raw_genesis = match (raw, genesis) {
 			(
 				true,
 				Genesis::RuntimeGenesis(RuntimeGenesisInner {
 					json_blob: RuntimeGenesisConfigJson::Config(config),
 					code,
+					..
 				}),
 			) => {
 				let mut storage =
@@ -595,6 +631,7 @@ where
 				Genesis::RuntimeGenesis(RuntimeGenesisInner {
 					json_blob: RuntimeGenesisConfigJson::Patch(patch),
 					code,
+					..
 				}),
 			) => {
 				let mut storage =
@@ -692,6 +729,10 @@ where
 			.map(|(h, c)| (h.clone(), c.0.clone()))
 			.collect()
 	}
+
+	fn set_code_cache_dir(&mut self, dir: PathBuf) {
+		self.code_cache_dir = Some(dir);
+	}
 }
 
 /// The `fun` will be called with the value at `path`.
@@ -766,6 +807,30 @@ pub fn update_code_in_json_chain_spec(chain_spec: &mut json::Value, code: &[u8])
 	}
 }
 
+/// This function replaces the runtime code in given chain spec by a reference to hosted code.
+///
+/// Only the `Genesis::RuntimeGenesis` format is supported. The code is downloaded and verified
+/// when the genesis storage is built.
+///
+/// If update was successful `true` is returned, otherwise `false`. Chain spec JSON is modified in
+/// place.
+pub fn set_hosted_code_in_json_chain_spec(
+	chain_spec: &mut json::Value,
+	hosted_code: &HostedCode,
+) -> bool {
+	let mut path = json_path!["genesis", "runtimeGenesis"];
+
+	if json_contains_path(&chain_spec, &mut path) {
+		let code_patch = json::json!({
+			"genesis": {"runtimeGenesis": {"code": "0x", "hostedCode": hosted_code}}
+		});
+		crate::json_patch::merge(chain_spec, code_patch);
+		true
+	} else {
+		false
+	}
+}
+
 /// This function sets a codeSubstitute in the chain spec.
 pub fn set_code_substitute_in_json_chain_spec(
 	chain_spec: &mut json::Value,
@@ -781,7 +846,7 @@ mod tests {
 	use super::*;
 	use serde_json::{from_str, json, Value};
 	use sp_application_crypto::Ss58Codec;
-	use sp_core::storage::well_known_keys;
+	use sp_core::{storage::well_known_keys, Pair};
 	use sp_keyring::Sr25519Keyring;
 
 	type TestSpec = ChainSpec;
@@ -1192,4 +1257,35 @@ mod tests {
 			&|v| { *v == "0x000102040506" }
 		));
 	}
+
+	#[test]
+	fn hosted_code_is_fetched_when_building_storage() {
+		let code = substrate_test_runtime::wasm_binary_unwrap();
+		let chain_spec = ChainSpec::<()>::builder(code, Default::default())
+			.with_name("TestName")
+			.with_id("test_id")
+			.with_chain_type(ChainType::Local)
+			.with_genesis_config_patch(json!({}))
+			.build();
+		let expected_raw = chain_spec.as_json(true).unwrap();
+
+		let publisher = sp_core::sr25519::Pair::from_seed(&[1; 32]);
+		let hosted_code =
+			HostedCode::new("https://example.com/runtime.wasm".into(), code, &publisher);
+		let cache_dir = tempfile::tempdir().unwrap();
+		std::fs::write(hosted_code.cache_path(cache_dir.path()), code).unwrap();
+
+		let mut chain_spec_json = from_str::<Value>(&chain_spec.as_json(false).unwrap()).unwrap();
+		assert!(set_hosted_code_in_json_chain_spec(&mut chain_spec_json, &hosted_code));
+		assert!(json_eval_value_at_key(
+			&chain_spec_json,
+			&mut json_path!["genesis", "runtimeGenesis", "code"],
+			&|v| { *v == "0x" }
+		));
+
+		let mut hosted_spec =
+			ChainSpec::<()>::from_json_bytes(chain_spec_json.to_string().into_bytes()).unwrap();
+		crate::ChainSpec::set_code_cache_dir(&mut hosted_spec, cache_dir.path().into());
+		assert_eq!(hosted_spec.as_json(true).unwrap(), expected_raw);
+	}
 }
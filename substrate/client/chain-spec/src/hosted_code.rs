@@ -0,0 +1,156 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime code hosted outside of the chain spec.
+
+use serde::{Deserialize, Serialize};
+use sp_core::{sr25519, Pair, H256};
+use std::{
+	io::Read,
+	path::{Path, PathBuf},
+};
+
+/// The maximum size of downloaded runtime code, in bytes.
+pub const MAX_CODE_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Reference to the runtime wasm code of a `runtimeGenesis` chain spec, which is downloaded
+/// instead of being contained in the chain spec.
+///
+/// The downloaded code is only accepted if its BLAKE2-256 hash is `hash` and `signature` is the
+/// signature of the hash by `publisher`, and it must not be larger than [`MAX_CODE_SIZE`]. Nodes
+/// cache it in their base path, so it's only downloaded on the first start of the node.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct HostedCode {
+	/// The URL the code is downloaded from.
+	pub url: String,
+	/// The BLAKE2-256 hash of the code.
+	pub hash: H256,
+	/// The publisher of the code.
+	pub publisher: sr25519::Public,
+	/// The signature of `hash` by `publisher`.
+	pub signature: sr25519::Signature,
+}
+
+impl HostedCode {
+	/// Reference `code` hosted at `url`, signed by `publisher`.
+	pub fn new(url: String, code: &[u8], publisher: &sr25519::Pair) -> Self {
+		let hash = H256(sp_crypto_hashing::blake2_256(code));
+		let signature = publisher.sign(hash.as_bytes());
+		Self { url, hash, publisher: publisher.public(), signature }
+	}
+
+	/// Check that `code` is the referenced code, signed by the publisher.
+	pub fn verify(&self, code: &[u8]) -> Result<(), String> {
+		let hash = H256(sp_crypto_hashing::blake2_256(code));
+		if hash != self.hash {
+			return Err(format!(
+				"Runtime code from {} has hash {hash:?}, expected {:?}",
+				self.url, self.hash
+			))
+		}
+		if !sr25519::Pair::verify(&self.signature, self.hash.as_bytes(), &self.publisher) {
+			return Err(format!(
+				"Invalid signature of the runtime code from {} by {}",
+				self.url, self.publisher
+			))
+		}
+		Ok(())
+	}
+
+	/// The referenced code, read from the cache in `cache_dir` or downloaded.
+	///
+	/// Without a `cache_dir`, the code is always downloaded.
+	pub fn fetch(&self, cache_dir: Option<&Path>) -> Result<Vec<u8>, String> {
+		let cache_path = cache_dir.map(|dir| self.cache_path(dir));
+		if let Some(code) = cache_path.as_ref().and_then(|path| std::fs::read(path).ok()) {
+			if self.verify(&code).is_ok() {
+				return Ok(code)
+			}
+		}
+
+		log::info!("Downloading runtime code from {}", self.url);
+		let code = download(&self.url, MAX_CODE_SIZE)?;
+		self.verify(&code)?;
+
+		if let Some(cache_path) = cache_path {
+			let cached = cache_path
+				.parent()
+				.map_or(Ok(()), std::fs::create_dir_all)
+				.and_then(|_| std::fs::write(&cache_path, &code));
+			if let Err(e) = cached {
+				log::warn!("Failed to cache runtime code at {}: {e}", cache_path.display());
+			}
+		}
+		Ok(code)
+	}
+
+	pub(crate) fn cache_path(&self, cache_dir: &Path) -> PathBuf {
+		cache_dir.join(format!("{:?}.wasm", self.hash))
+	}
+}
+
+/// Download the content at `url`, failing if it's larger than `max_size` bytes.
+///
+/// The download runs on its own thread, as the blocking HTTP client can't be used in async
+/// contexts, which chain specs are built in by some nodes.
+fn download(url: &str, max_size: u64) -> Result<Vec<u8>, String> {
+	let url = url.to_string();
+	std::thread::spawn(move || {
+		let error =
+			|e: &dyn std::fmt::Display| format!("Failed to download runtime code from {url}: {e}");
+		let response = reqwest::blocking::get(&url)
+			.and_then(|response| response.error_for_status())
+			.map_err(|e| error(&e))?;
+		if response.content_length().is_some_and(|len| len > max_size) {
+			return Err(error(&format!("code is larger than {max_size} bytes")))
+		}
+
+		let mut code = Vec::new();
+		response.take(max_size + 1).read_to_end(&mut code).map_err(|e| error(&e))?;
+		if code.len() as u64 > max_size {
+			return Err(error(&format!("code is larger than {max_size} bytes")))
+		}
+		Ok(code)
+	})
+	.join()
+	.map_err(|_| "Runtime code download panicked".to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hosted_code_is_verified() {
+		let publisher = sr25519::Pair::from_seed(&[1; 32]);
+		let code = b"runtime code".to_vec();
+		let hosted = HostedCode::new("https://example.com/runtime.wasm".into(), &code, &publisher);
+		assert_eq!(hosted.verify(&code), Ok(()));
+		assert!(hosted.verify(b"other code").is_err());
+
+		let other = sr25519::Pair::from_seed(&[2; 32]);
+		let forged = HostedCode { publisher: other.public(), ..hosted.clone() };
+		assert!(forged.verify(&code).is_err());
+
+		// Cached code is used without downloading it.
+		let cache_dir = tempfile::tempdir().unwrap();
+		std::fs::write(hosted.cache_path(cache_dir.path()), &code).unwrap();
+		assert_eq!(hosted.fetch(Some(cache_dir.path())), Ok(code));
+	}
+}
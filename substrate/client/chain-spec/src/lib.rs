@@ -225,6 +225,29 @@
 //! Use this functionality only when there is no other way around it, and only patch the problematic
 //! bug; the rest should be done with an on-chain runtime upgrade.
 //!
+//! # Hosted Runtime Code
+//!
+//! Instead of the multi-megabyte runtime code itself, the `runtimeGenesis` field of a chain spec
+//! can contain a `hostedCode` reference to the code, with an empty `code`:
+//!
+//! ```json
+//! "runtimeGenesis": {
+//!   "code": "0x",
+//!   "hostedCode": {
+//!     "url": "https://example.com/runtime.compact.compressed.wasm",
+//!     "hash": "0x…",
+//!     "publisher": "5…",
+//!     "signature": "0x…"
+//!   },
+//!   "patch": { … }
+//! }
+//! ```
+//!
+//! The code is downloaded when the genesis storage is built, and only accepted if it has the given
+//! BLAKE2-256 hash, signed by the sr25519 key of the publisher. Nodes cache the code in their
+//! base path, see [`ChainSpec::set_code_cache_dir`]. See [`HostedCode`] and
+//! [`set_hosted_code_in_json_chain_spec`].
+//!
 //! # Building a Chain Specification
 //!
 //! The [`ChainSpecBuilder`] should be used to create an instance of a chain specification. Its API
@@ -335,12 +358,14 @@ mod chain_spec;
 mod extension;
 mod genesis_block;
 mod genesis_config_builder;
+mod hosted_code;
 pub mod json_patch;
 
 pub use self::{
 	chain_spec::{
-		set_code_substitute_in_json_chain_spec, update_code_in_json_chain_spec,
-		ChainSpec as GenericChainSpec, ChainSpecBuilder, NoExtension,
+		set_code_substitute_in_json_chain_spec, set_hosted_code_in_json_chain_spec,
+		update_code_in_json_chain_spec, ChainSpec as GenericChainSpec, ChainSpecBuilder,
+		NoExtension,
 	},
 	extension::{get_extension, get_extension_mut, Extension, Fork, Forks, GetExtension, Group},
 	genesis_block::{
@@ -350,6 +375,7 @@ pub use self::{
 	genesis_config_builder::{
 		GenesisConfigBuilderRuntimeCaller, DEV_RUNTIME_PRESET, LOCAL_TESTNET_RUNTIME_PRESET,
 	},
+	hosted_code::HostedCode,
 	json_patch::merge as json_merge,
 };
 pub use sc_chain_spec_derive::{ChainSpecExtension, ChainSpecGroup};
@@ -424,6 +450,10 @@ pub trait ChainSpec: BuildStorage + Send + Sync {
 	fn set_storage(&mut self, storage: Storage);
 	/// Returns code substitutes that should be used for the on chain wasm.
 	fn code_substitutes(&self) -> std::collections::BTreeMap<String, Vec<u8>>;
+	/// Set the directory the runtime code referenced by [`HostedCode`] is cached in.
+	///
+	/// Without a cache directory, the code is downloaded whenever it is needed.
+	fn set_code_cache_dir(&mut self, dir: std::path::PathBuf);
 }
 
 impl std::fmt::Debug for dyn ChainSpec {
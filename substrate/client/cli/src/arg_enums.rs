@@ -154,6 +154,18 @@ pub enum ExecutionStrategy {
 	NativeElseWasm,
 }
 
+/// IO scheduling class of the node process.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum IoClass {
+	/// Served before the other classes, with the IO priority within the class.
+	Realtime,
+	/// Served according to the IO priority within the class. The default of processes.
+	BestEffort,
+	/// Only served when no process of another class uses the disk.
+	Idle,
+}
+
 /// Available RPC methods.
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
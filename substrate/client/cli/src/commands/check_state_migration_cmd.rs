@@ -0,0 +1,129 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use log::info;
+use sc_client_api::{HeaderBackend, UsageProvider};
+use sc_service::chain_ops::{check_state_migration, StateMigrationStatus};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fmt::Debug, str::FromStr, sync::Arc};
+
+/// The `check-state-migration` command used to follow the migration of the state to the `V1`
+/// state version.
+///
+/// The migration is done on chain, as it changes the state root. This command reports how much of
+/// the state of a block remains to be migrated and can verify the state once migrated.
+#[derive(Debug, Clone, Parser)]
+pub struct CheckStateMigrationCmd {
+	/// Block hash or number.
+	///
+	/// Default is the best block.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub input: Option<BlockNumberOrHash>,
+
+	/// Verify that the whole state is migrated and that all its trie nodes are intact.
+	#[arg(long)]
+	pub verify: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CheckStateMigrationCmd {
+	/// Run the check-state-migration command
+	pub async fn run<B, BA, C>(&self, client: Arc<C>, backend: Arc<BA>) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: sc_client_api::backend::Backend<B>,
+		C: UsageProvider<B> + HeaderBackend<B>,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block_id = self.input.as_ref().map(|b| b.parse()).transpose()?;
+		let hash = match block_id {
+			Some(id) => client.expect_block_hash_from_id(&id)?,
+			None => client.usage_info().chain.best_hash,
+		};
+
+		info!("Scanning the state of block {hash:?}...");
+		let status = check_state_migration(&*backend, hash, self.verify, |status| {
+			info!("Scanned {}", Summary(status))
+		})?;
+		info!("Scanned {}", Summary(&status));
+
+		if self.verify {
+			if !status.is_complete() {
+				return Err(error::Error::Input(format!(
+					"State of block {hash:?} is not fully migrated, {} values remain",
+					status.top.remaining + status.child.remaining,
+				)))
+			}
+			info!("State of block {hash:?} is fully migrated and intact");
+		}
+		Ok(())
+	}
+}
+
+/// Displays the progress of a [`StateMigrationStatus`].
+struct Summary<'a>(&'a StateMigrationStatus);
+
+impl std::fmt::Display for Summary<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let Summary(status) = self;
+		write!(
+			f,
+			"{} top values, {} to migrate ({} bytes), and {} values of {} child tries, {} to \
+			 migrate ({} bytes)",
+			status.top.total,
+			status.top.remaining,
+			status.top.remaining_size,
+			status.child.total,
+			status.child_tries,
+			status.child.remaining,
+			status.child.remaining_size,
+		)
+	}
+}
+
+impl CliConfiguration for CheckStateMigrationCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,331 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export and import of the consensus aux data, to repair databases restored from partial
+//! backups without a full resync.
+
+use crate::{
+	error,
+	params::{DatabaseParams, PruningParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use log::info;
+use sc_client_api::{backend::Backend as BackendT, blockchain::HeaderBackend, AuxStore};
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+/// Version of the format of the exported consensus state.
+const FORMAT_VERSION: u32 = 1;
+
+/// The aux storage keys of a consensus engine: its name in the exported file, and its keys.
+pub type ConsensusAuxKeys = (&'static str, &'static [&'static [u8]]);
+
+/// An aux storage entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct AuxEntry {
+	key: Bytes,
+	value: Bytes,
+}
+
+/// The exported consensus state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "")]
+struct ConsensusState<B: BlockT> {
+	/// Version of the format.
+	version: u32,
+	/// Genesis hash of the chain.
+	genesis_hash: B::Hash,
+	/// The last finalized block at the time of the export.
+	finalized_hash: B::Hash,
+	/// The number of the last finalized block.
+	finalized_number: NumberFor<B>,
+	/// The aux storage entries of each consensus engine.
+	engines: BTreeMap<String, Vec<AuxEntry>>,
+}
+
+/// The `export-consensus-state` command used to export the consensus aux data.
+#[derive(Debug, Clone, Parser)]
+pub struct ExportConsensusStateCmd {
+	/// Output file name or stdout if unspecified.
+	#[arg()]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportConsensusStateCmd {
+	/// Run the export-consensus-state command, exporting the aux data of the given `engines`.
+	pub fn run<B: BlockT>(
+		&self,
+		config: &sc_service::Configuration,
+		engines: &[ConsensusAuxKeys],
+	) -> error::Result<()> {
+		let state = export_state(&*open_backend::<B>(config)?, engines)?;
+		let writer: Box<dyn io::Write> = match &self.output {
+			Some(path) => Box::new(fs::File::create(path)?),
+			None => Box::new(io::stdout()),
+		};
+		serde_json::to_writer_pretty(writer, &state)
+			.map_err(|e| format!("Error writing JSON: {}", e))?;
+
+		info!(
+			"Exported the consensus state at finalized block #{} ({})",
+			state.finalized_number, state.finalized_hash,
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportConsensusStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The `import-consensus-state` command used to import the consensus aux data.
+#[derive(Debug, Clone, Parser)]
+pub struct ImportConsensusStateCmd {
+	/// Input file or stdin if unspecified.
+	#[arg()]
+	pub input: Option<PathBuf>,
+
+	/// Import the state even if its finalized block is not in the database.
+	///
+	/// The consensus engines may then fail to import the blocks following the finalized block
+	/// of the database.
+	#[arg(long)]
+	pub force: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ImportConsensusStateCmd {
+	/// Run the import-consensus-state command, replacing the aux data of the `engines` present
+	/// in the input.
+	pub fn run<B: BlockT>(
+		&self,
+		config: &sc_service::Configuration,
+		engines: &[ConsensusAuxKeys],
+	) -> error::Result<()> {
+		let json = match &self.input {
+			Some(path) => fs::read(path)?,
+			None => io::read_to_string(io::stdin())?.into_bytes(),
+		};
+		let state = parse_state::<B>(&json)?;
+		import_state(&*open_backend::<B>(config)?, &state, engines, self.force)?;
+
+		info!(
+			"Imported the consensus state of {} at finalized block #{} ({})",
+			state.engines.keys().cloned().collect::<Vec<_>>().join(", "),
+			state.finalized_number,
+			state.finalized_hash,
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ImportConsensusStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// Open the database directly, as the consensus components may fail to initialize from damaged
+/// aux data.
+fn open_backend<B: BlockT>(
+	config: &sc_service::Configuration,
+) -> error::Result<std::sync::Arc<sc_client_db::Backend<B>>> {
+	Ok(sc_service::new_db_backend::<B>(config.db_config())?)
+}
+
+fn export_state<B, BA>(
+	backend: &BA,
+	engines: &[ConsensusAuxKeys],
+) -> error::Result<ConsensusState<B>>
+where
+	B: BlockT,
+	BA: BackendT<B>,
+{
+	let info = backend.blockchain().info();
+	let mut state = ConsensusState {
+		version: FORMAT_VERSION,
+		genesis_hash: info.genesis_hash,
+		finalized_hash: info.finalized_hash,
+		finalized_number: info.finalized_number,
+		engines: BTreeMap::new(),
+	};
+	for (engine, keys) in engines {
+		let mut entries = Vec::new();
+		for key in keys.iter() {
+			if let Some(value) = backend.get_aux(key)? {
+				entries.push(AuxEntry { key: key.to_vec().into(), value: value.into() });
+			}
+		}
+		state.engines.insert(engine.to_string(), entries);
+	}
+	Ok(state)
+}
+
+fn parse_state<B: BlockT>(json: &[u8]) -> error::Result<ConsensusState<B>> {
+	let json: serde_json::Value =
+		serde_json::from_slice(json).map_err(|e| format!("Invalid consensus state: {e}"))?;
+	match json.get("version").and_then(|version| version.as_u64()) {
+		Some(version) if version == u64::from(FORMAT_VERSION) => {},
+		Some(version) =>
+			return Err(format!("Unsupported consensus state version {version}").into()),
+		None => return Err("Consensus state without version".into()),
+	}
+	serde_json::from_value(json).map_err(|e| format!("Invalid consensus state: {e}").into())
+}
+
+fn import_state<B, BA>(
+	backend: &BA,
+	state: &ConsensusState<B>,
+	engines: &[ConsensusAuxKeys],
+	force: bool,
+) -> error::Result<()>
+where
+	B: BlockT,
+	BA: BackendT<B>,
+{
+	let info = backend.blockchain().info();
+	if state.genesis_hash != info.genesis_hash {
+		return Err(format!(
+			"Consensus state of another chain, with genesis {}, expected {}",
+			state.genesis_hash, info.genesis_hash
+		)
+		.into())
+	}
+	if !force && backend.blockchain().hash(state.finalized_number)? != Some(state.finalized_hash) {
+		return Err(format!(
+			"Finalized block #{} ({}) of the consensus state is not in the database, use \
+			 `--force` to import it anyway",
+			state.finalized_number, state.finalized_hash,
+		)
+		.into())
+	}
+
+	let mut insert = Vec::new();
+	let mut delete = Vec::new();
+	for (engine, entries) in &state.engines {
+		let (_, keys) = engines
+			.iter()
+			.find(|(name, _)| name == engine)
+			.ok_or_else(|| format!("Unknown consensus engine `{engine}`"))?;
+		for entry in entries {
+			if !keys.iter().any(|key| *key == &entry.key[..]) {
+				let key = array_bytes::bytes2hex("0x", &entry.key[..]);
+				return Err(format!("Unknown aux key {key} of `{engine}`").into())
+			}
+			insert.push((&entry.key[..], &entry.value[..]));
+		}
+		// Keys missing from the export are removed, to replace the state as a whole.
+		delete.extend(
+			keys.iter()
+				.copied()
+				.filter(|key| entries.iter().all(|entry| *key != &entry.key[..])),
+		);
+	}
+	backend.insert_aux(&insert, &delete)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime_client::{
+		runtime::Block, DefaultTestClientBuilderExt, TestClientBuilderExt,
+	};
+
+	const BABE_KEYS: &[&[u8]] = &[b"babe_version", b"babe_epochs"];
+	const GRANDPA_KEYS: &[&[u8]] = &[b"grandpa_voters"];
+	const ENGINES: &[ConsensusAuxKeys] = &[("babe", BABE_KEYS), ("grandpa", GRANDPA_KEYS)];
+
+	#[test]
+	fn consensus_state_roundtrip() {
+		let builder = substrate_test_runtime_client::TestClientBuilder::new();
+		let backend = builder.backend();
+		let _client = builder.build();
+		backend.insert_aux(&[(&b"babe_epochs"[..], &b"epochs"[..])], &[]).unwrap();
+		backend.insert_aux(&[(&b"grandpa_voters"[..], &b"voters"[..])], &[]).unwrap();
+
+		let state = export_state(&*backend, ENGINES).unwrap();
+		let json = serde_json::to_vec(&state).unwrap();
+		assert_eq!(parse_state(&json).unwrap(), state);
+
+		// Keys missing from the export are removed, other engines are kept.
+		backend.insert_aux(&[(&b"babe_version"[..], &b"3"[..])], &[]).unwrap();
+		backend.insert_aux(&[(&b"grandpa_voters"[..], &b"new"[..])], &[]).unwrap();
+		let babe_only = ConsensusState {
+			engines: state.engines.clone().into_iter().filter(|(e, _)| *e == "babe").collect(),
+			..state.clone()
+		};
+		import_state(&*backend, &babe_only, ENGINES, false).unwrap();
+		assert_eq!(backend.get_aux(b"babe_version").unwrap(), None);
+		assert_eq!(backend.get_aux(b"babe_epochs").unwrap(), Some(b"epochs".to_vec()));
+		assert_eq!(backend.get_aux(b"grandpa_voters").unwrap(), Some(b"new".to_vec()));
+
+		let other_chain = ConsensusState { genesis_hash: Default::default(), ..state.clone() };
+		assert!(import_state(&*backend, &other_chain, ENGINES, true).is_err());
+		let unknown_block = ConsensusState { finalized_number: 1, ..state.clone() };
+		assert!(import_state(&*backend, &unknown_block, ENGINES, false).is_err());
+		assert!(import_state(&*backend, &unknown_block, ENGINES, true).is_ok());
+
+		let json = String::from_utf8(json).unwrap().replace("\"version\":1", "\"version\":2");
+		assert!(parse_state::<Block>(json.as_bytes()).is_err());
+	}
+}
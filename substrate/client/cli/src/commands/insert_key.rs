@@ -67,9 +67,12 @@ impl InsertKeyCmd {
 		let config_dir = base_path.config_dir(chain_spec.id());
 
 		let (keystore, public) = match self.keystore_params.keystore_config(&config_dir)? {
-			KeystoreConfig::Path { path, password } => {
+			KeystoreConfig::Path { path, password, encryption, .. } => {
 				let public = with_crypto_scheme!(self.scheme, to_vec(&suri, password.clone()))?;
-				let keystore: KeystorePtr = LocalKeystore::open(path, password)?.into();
+				let keystore: KeystorePtr = match encryption {
+					Some(unlock) => LocalKeystore::open_encrypted(path, password, &unlock)?.into(),
+					None => LocalKeystore::open(path, password)?.into(),
+				};
 				(keystore, public)
 			},
 			_ => unreachable!("keystore_config always returns path and password; qed"),
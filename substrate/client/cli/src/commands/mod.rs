@@ -21,6 +21,8 @@
 mod build_spec_cmd;
 mod chain_info_cmd;
 mod check_block_cmd;
+mod check_state_migration_cmd;
+mod consensus_state_cmd;
 mod export_blocks_cmd;
 mod export_state_cmd;
 mod generate;
@@ -40,10 +42,24 @@ mod vanity;
 mod verify;
 
 pub use self::{
-	build_spec_cmd::BuildSpecCmd, chain_info_cmd::ChainInfoCmd, check_block_cmd::CheckBlockCmd,
-	export_blocks_cmd::ExportBlocksCmd, export_state_cmd::ExportStateCmd, generate::GenerateCmd,
-	generate_node_key::GenerateKeyCmdCommon, import_blocks_cmd::ImportBlocksCmd,
-	insert_key::InsertKeyCmd, inspect_key::InspectKeyCmd, inspect_node_key::InspectNodeKeyCmd,
-	key::KeySubcommand, purge_chain_cmd::PurgeChainCmd, revert_cmd::RevertCmd, run_cmd::RunCmd,
-	sign::SignCmd, vanity::VanityCmd, verify::VerifyCmd,
+	build_spec_cmd::BuildSpecCmd,
+	chain_info_cmd::ChainInfoCmd,
+	check_block_cmd::CheckBlockCmd,
+	check_state_migration_cmd::CheckStateMigrationCmd,
+	consensus_state_cmd::{ConsensusAuxKeys, ExportConsensusStateCmd, ImportConsensusStateCmd},
+	export_blocks_cmd::ExportBlocksCmd,
+	export_state_cmd::ExportStateCmd,
+	generate::GenerateCmd,
+	generate_node_key::GenerateKeyCmdCommon,
+	import_blocks_cmd::ImportBlocksCmd,
+	insert_key::InsertKeyCmd,
+	inspect_key::InspectKeyCmd,
+	inspect_node_key::InspectNodeKeyCmd,
+	key::KeySubcommand,
+	purge_chain_cmd::PurgeChainCmd,
+	revert_cmd::RevertCmd,
+	run_cmd::RunCmd,
+	sign::SignCmd,
+	vanity::VanityCmd,
+	verify::VerifyCmd,
 };
@@ -19,8 +19,8 @@
 use crate::{
 	error::{Error, Result},
 	params::{
-		ImportParams, KeystoreParams, NetworkParams, OffchainWorkerParams, RpcEndpoint,
-		SharedParams, TransactionPoolParams,
+		ImportParams, InformantParams, KeystoreParams, NetworkParams, OffchainWorkerParams,
+		ResourceLimitsParams, RpcEndpoint, SharedParams, TokioParams, TransactionPoolParams,
 	},
 	CliConfiguration, PrometheusParams, RpcParams, RuntimeParams, TelemetryParams,
 };
@@ -33,7 +33,7 @@ use sc_service::{
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, time::Duration};
 
 /// The `run` command used to run a node.
 #[derive(Debug, Clone, Parser)]
@@ -99,6 +99,18 @@ pub struct RunCmd {
 	#[clap(flatten)]
 	pub keystore_params: KeystoreParams,
 
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub tokio_params: TokioParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub resource_limits_params: ResourceLimitsParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub informant_params: InformantParams,
+
 	/// Shortcut for `--name Alice --validator`.
 	///
 	/// Session keys for `Alice` are added to keystore.
@@ -208,6 +220,18 @@ impl CliConfiguration for RunCmd {
 		Some(&self.keystore_params)
 	}
 
+	fn tokio_params(&self) -> Option<&TokioParams> {
+		Some(&self.tokio_params)
+	}
+
+	fn resource_limits_params(&self) -> Option<&ResourceLimitsParams> {
+		Some(&self.resource_limits_params)
+	}
+
+	fn informant_params(&self) -> Option<&InformantParams> {
+		Some(&self.informant_params)
+	}
+
 	fn offchain_worker_params(&self) -> Option<&OffchainWorkerParams> {
 		Some(&self.offchain_worker_params)
 	}
@@ -330,6 +354,18 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_params.rpc_rate_limit_trust_proxy_headers)
 	}
 
+	fn rpc_serve_finalized_only(&self) -> Result<bool> {
+		Ok(self.rpc_params.serve_finalized_only)
+	}
+
+	fn rpc_transaction_confirmations(&self) -> Result<u32> {
+		Ok(self.rpc_params.rpc_transaction_confirmations)
+	}
+
+	fn rpc_chain_head_resume_grace_period(&self) -> Result<Option<Duration>> {
+		Ok(self.rpc_params.rpc_chain_head_resume_grace_period.map(Duration::from_secs))
+	}
+
 	fn transaction_pool(&self, is_dev: bool) -> Result<TransactionPoolOptions> {
 		Ok(self.pool_config.transaction_pool(is_dev))
 	}
@@ -19,23 +19,24 @@
 //! Configuration trait for a CLI based on substrate
 
 use crate::{
-	arg_enums::Database, error::Result, DatabaseParams, ImportParams, KeystoreParams,
-	NetworkParams, NodeKeyParams, OffchainWorkerParams, PruningParams, RpcEndpoint, SharedParams,
-	SubstrateCli,
+	arg_enums::Database, error::Result, DatabaseParams, ImportParams, InformantParams,
+	KeystoreParams, NetworkParams, NodeKeyParams, OffchainWorkerParams, PruningParams,
+	ResourceLimitsParams, RpcEndpoint, SharedParams, SubstrateCli, TokioParams,
 };
 use log::warn;
 use names::{Generator, Name};
 use sc_service::{
 	config::{
-		BasePath, Configuration, DatabaseSource, ExecutorConfiguration, IpNetwork, KeystoreConfig,
-		NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode,
-		Role, RpcBatchRequestConfig, RpcConfiguration, RpcMethods, TelemetryEndpoints,
-		TransactionPoolOptions, WasmExecutionMethod,
+		BasePath, Configuration, DatabaseSource, ExecutorConfiguration, ImportQueueConfig,
+		InformantConfig, IpNetwork, KeystoreConfig, NetworkConfiguration, NodeKeyConfig,
+		OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcBatchRequestConfig,
+		RpcConfiguration, RpcMethods, TelemetryEndpoints, TransactionPoolOptions,
+		WasmExecutionMethod,
 	},
 	BlocksPruning, ChainSpec, TracingReceiver,
 };
 use sc_tracing::logging::LoggerBuilder;
-use std::{num::NonZeroU32, path::PathBuf};
+use std::{num::NonZeroU32, path::PathBuf, time::Duration};
 
 /// The maximum number of characters for a node name.
 pub(crate) const NODE_NAME_MAX_LENGTH: usize = 64;
@@ -109,6 +110,21 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		None
 	}
 
+	/// Get the TokioParams for this object
+	fn tokio_params(&self) -> Option<&TokioParams> {
+		None
+	}
+
+	/// Get the ResourceLimitsParams for this object
+	fn resource_limits_params(&self) -> Option<&ResourceLimitsParams> {
+		None
+	}
+
+	/// Get the InformantParams for this object
+	fn informant_params(&self) -> Option<&InformantParams> {
+		None
+	}
+
 	/// Get the NetworkParams for this object
 	fn network_params(&self) -> Option<&NetworkParams> {
 		None
@@ -212,6 +228,14 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.database_params().map(|x| x.database_cache_size()).unwrap_or_default())
 	}
 
+	/// Get the maximum rate at which the database is written to, in MiB per second.
+	///
+	/// By default this is retrieved from `ResourceLimitsParams` if it is available. Otherwise its
+	/// `None`.
+	fn database_write_rate_limit(&self) -> Result<Option<NonZeroU32>> {
+		Ok(self.resource_limits_params().and_then(|x| x.db_write_rate_limit))
+	}
+
 	/// Get the database backend variant.
 	///
 	/// By default this is retrieved from `DatabaseParams` if it is available. Otherwise its `None`.
@@ -301,6 +325,14 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		self.import_params().map(|x| x.wasm_runtime_overrides()).unwrap_or_default()
 	}
 
+	/// Get the configuration of the block verification by the import queue.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise blocks are
+	/// verified right before their import.
+	fn import_queue(&self) -> Result<ImportQueueConfig> {
+		Ok(self.import_params().map(|x| x.import_queue()).unwrap_or_default())
+	}
+
 	/// Get the RPC address.
 	fn rpc_addr(&self, _default_listen_port: u16) -> Result<Option<Vec<RpcEndpoint>>> {
 		Ok(None)
@@ -366,6 +398,29 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(false)
 	}
 
+	/// Whether the RPC serves the last finalized block instead of the best block.
+	///
+	/// By default this is `false`.
+	fn rpc_serve_finalized_only(&self) -> Result<bool> {
+		Ok(false)
+	}
+
+	/// The number of blocks on top of a watched transaction before it is reported in a block or
+	/// finalized.
+	///
+	/// By default this is `0`.
+	fn rpc_transaction_confirmations(&self) -> Result<u32> {
+		Ok(0)
+	}
+
+	/// The duration a `chainHead_follow` subscription is kept after its stream ended, such
+	/// that it can be resumed.
+	///
+	/// By default this is `None`.
+	fn rpc_chain_head_resume_grace_period(&self) -> Result<Option<Duration>> {
+		Ok(None)
+	}
+
 	/// Get the prometheus configuration (`None` if disabled)
 	///
 	/// By default this is `None`.
@@ -473,6 +528,14 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(true)
 	}
 
+	/// Get the informant configuration.
+	///
+	/// By default this is retrieved from `InformantParams` if it is available. Otherwise the
+	/// default configuration is used.
+	fn informant(&self) -> Result<InformantConfig> {
+		Ok(self.informant_params().map(|x| x.informant_config()).unwrap_or_default())
+	}
+
 	/// Create a Configuration object from the current object
 	fn create_configuration<C: SubstrateCli>(
 		&self,
@@ -481,9 +544,10 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 	) -> Result<Configuration> {
 		let is_dev = self.is_dev()?;
 		let chain_id = self.chain_id(is_dev)?;
-		let chain_spec = cli.load_spec(&chain_id)?;
+		let mut chain_spec = cli.load_spec(&chain_id)?;
 		let base_path = base_path_or_default(self.base_path()?, &C::executable_name());
 		let config_dir = build_config_dir(&base_path, chain_spec.id());
+		chain_spec.set_code_cache_dir(config_dir.join("runtime-code"));
 		let net_config_dir = build_net_config_dir(&config_dir);
 		let client_id = C::client_id();
 		let database_cache_size = self.database_cache_size()?.unwrap_or(1024);
@@ -513,6 +577,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			impl_name: C::impl_name(),
 			impl_version: C::impl_version(),
 			tokio_handle,
+			dedicated_runtimes: Default::default(),
 			transaction_pool: self.transaction_pool(is_dev)?,
 			network: self.network_config(
 				&chain_spec,
@@ -530,6 +595,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			trie_cache_maximum_size: self.trie_cache_maximum_size()?,
 			state_pruning: self.state_pruning()?,
 			blocks_pruning: self.blocks_pruning()?,
+			database_write_rate_limit: self.database_write_rate_limit()?,
 			executor: ExecutorConfiguration {
 				wasm_method: self.wasm_method()?,
 				default_heap_pages: self.default_heap_pages()?,
@@ -537,6 +603,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				runtime_cache_size,
 			},
 			wasm_runtime_overrides: self.wasm_runtime_overrides(),
+			import_queue: self.import_queue()?,
 			rpc: RpcConfiguration {
 				addr: rpc_addrs,
 				methods: self.rpc_methods()?,
@@ -552,6 +619,9 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				rate_limit: self.rpc_rate_limit()?,
 				rate_limit_whitelisted_ips: self.rpc_rate_limit_whitelisted_ips()?,
 				rate_limit_trust_proxy_headers: self.rpc_rate_limit_trust_proxy_headers()?,
+				serve_finalized_only: self.rpc_serve_finalized_only()?,
+				transaction_confirmations: self.rpc_transaction_confirmations()?,
+				chain_head_resume_grace_period: self.rpc_chain_head_resume_grace_period()?,
 			},
 			prometheus_config: self
 				.prometheus_config(DCV::prometheus_listen_port(), &chain_spec)?,
@@ -564,6 +634,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			tracing_receiver: self.tracing_receiver()?,
 			chain_spec,
 			announce_block: self.announce_block()?,
+			informant: self.informant()?,
 			role,
 			base_path,
 		})
@@ -642,24 +713,41 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			logger.with_colors(false);
 		}
 
+		let shared_params = self.shared_params();
+		if let Some(runtime_log) = shared_params.runtime_log() {
+			logger.with_runtime_logs(
+				runtime_log,
+				shared_params.runtime_log_file().cloned(),
+				shared_params.runtime_log_rate_limit(),
+			);
+		}
+
 		// Call hook for custom profiling setup.
 		logger_hook(&mut logger);
 
 		logger.init()?;
 
+		let resource_limits = self.resource_limits_params();
 		match fdlimit::raise_fd_limit() {
-			Ok(fdlimit::Outcome::LimitRaised { to, .. }) =>
+			Ok(fdlimit::Outcome::LimitRaised { to, .. }) => {
+				if let Some(resource_limits) = resource_limits {
+					resource_limits.check_open_files(to)?;
+				}
 				if to < RECOMMENDED_OPEN_FILE_DESCRIPTOR_LIMIT {
 					warn!(
 						"Low open file descriptor limit configured for the process. \
 						Current value: {:?}, recommended value: {:?}.",
 						to, RECOMMENDED_OPEN_FILE_DESCRIPTOR_LIMIT,
 					);
-				},
+				}
+			},
 			Ok(fdlimit::Outcome::Unsupported) => {
 				// Unsupported platform (non-Linux)
 			},
 			Err(error) => {
+				if resource_limits.is_some_and(|x| x.min_open_files.is_some()) {
+					return Err(error.into())
+				}
 				warn!(
 					"Failed to configure file descriptor limit for the process: \
 					{}, recommended value: {:?}.",
@@ -668,6 +756,17 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			},
 		}
 
+		if let (Some(limit), Some(cache_size)) =
+			(crate::params::cgroup_memory_limit(), self.trie_cache_maximum_size()?)
+		{
+			if cache_size as u64 > limit {
+				warn!(
+					"The trie cache size of {cache_size} bytes exceeds the memory limit of the \
+					cgroup of the process, {limit} bytes. Consider lowering `--trie-cache-size`.",
+				);
+			}
+		}
+
 		Ok(())
 	}
 }
@@ -235,18 +235,32 @@ pub trait SubstrateCli: Sized {
 	where
 		F: FnOnce(&mut LoggerBuilder, &Configuration),
 	{
-		let tokio_runtime = build_runtime()?;
+		// The priorities are inherited by the threads of the runtimes, so set them before.
+		if let Some(resource_limits) = command.resource_limits_params() {
+			resource_limits.apply_priorities()?;
+		}
+
+		let tokio_params = command.tokio_params();
+		let tokio_runtime = match tokio_params {
+			Some(params) => params.build_runtime()?,
+			None => build_runtime()?,
+		};
+		let (dedicated_handles, dedicated_runtimes) = match tokio_params {
+			Some(params) => params.build_dedicated_runtimes()?,
+			None => Default::default(),
+		};
 
 		// `capture` needs to be called in a tokio context.
 		// Also capture them as early as possible.
 		let signals = tokio_runtime.block_on(async { Signals::capture() })?;
 
-		let config = command.create_configuration(self, tokio_runtime.handle().clone())?;
+		let mut config = command.create_configuration(self, tokio_runtime.handle().clone())?;
+		config.dedicated_runtimes = dedicated_handles;
 
 		command.init(&Self::support_url(), &Self::impl_version(), |logger_builder| {
 			logger_hook(logger_builder, &config)
 		})?;
 
-		Runner::new(config, tokio_runtime, signals)
+		Ok(Runner::new(config, tokio_runtime, signals)?.with_dedicated_runtimes(dedicated_runtimes))
 	}
 }
@@ -0,0 +1,56 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use clap::Args;
+use sc_client_api::BackupSettings;
+use std::path::PathBuf;
+
+/// Parameters of the backups of the database taken with the `dev_backup` RPC.
+#[derive(Debug, Clone, Args)]
+pub struct BackupParams {
+	/// Command run by `dev_backup`.
+	///
+	/// With `--backup-snapshot-dir`, the command runs once the database writes resumed, to copy
+	/// the snapshot elsewhere. Without, it runs while the writes are quiesced, to take an instant
+	/// snapshot of the file system holding the database.
+	///
+	/// The database path, the snapshot path and the best and finalized blocks of the backup are
+	/// passed in the `BACKUP_DATABASE_PATH`, `BACKUP_SNAPSHOT_PATH`, `BACKUP_BEST_NUMBER`,
+	/// `BACKUP_BEST_HASH`, `BACKUP_FINALIZED_NUMBER` and `BACKUP_FINALIZED_HASH` environment
+	/// variables. The backup fails if the command exits with a non-zero status.
+	#[arg(long, value_name = "COMMAND")]
+	pub backup_hook: Option<PathBuf>,
+
+	/// Directory in which `dev_backup` creates a snapshot of the database.
+	///
+	/// The snapshot is a subdirectory named after the best block and the time of the backup.
+	/// RocksDB table files are hard-linked, the other files are copied. Only supported by RocksDB
+	/// databases.
+	#[arg(long, value_name = "PATH")]
+	pub backup_snapshot_dir: Option<PathBuf>,
+}
+
+impl BackupParams {
+	/// Returns the backup settings.
+	pub fn settings(&self) -> BackupSettings {
+		BackupSettings {
+			hook: self.backup_hook.clone(),
+			snapshot_dir: self.backup_snapshot_dir.clone(),
+		}
+	}
+}
@@ -24,6 +24,7 @@ use crate::{
 	params::{DatabaseParams, PruningParams},
 };
 use clap::Args;
+use sc_service::config::ImportQueueConfig;
 use std::path::PathBuf;
 
 /// Parameters for block import.
@@ -80,6 +81,20 @@ pub struct ImportParams {
 	/// Providing `0` will disable the cache.
 	#[arg(long, value_name = "Bytes", default_value_t = 1024 * 1024 * 1024)]
 	pub trie_cache_size: usize,
+
+	/// The number of workers verifying blocks ahead of their import.
+	///
+	/// Only useful with verifiers not requiring the parent block to be imported, BABE and Aura
+	/// reject the blocks verified ahead of their parent and verify them again on import.
+	/// By default, every block is verified right before its import.
+	#[arg(long, value_name = "COUNT", default_value_t = 0)]
+	pub verification_workers: usize,
+
+	/// The maximum number of blocks verified ahead of their import.
+	///
+	/// Defaults to four blocks per verification worker.
+	#[arg(long, value_name = "COUNT")]
+	pub max_blocks_in_verification: Option<usize>,
 }
 
 impl ImportParams {
@@ -104,6 +119,15 @@ impl ImportParams {
 	pub fn wasm_runtime_overrides(&self) -> Option<PathBuf> {
 		self.wasm_runtime_overrides.clone()
 	}
+
+	/// Get the configuration of the block verification by the import queue.
+	pub fn import_queue(&self) -> ImportQueueConfig {
+		let mut config = ImportQueueConfig::with_workers(self.verification_workers);
+		if let Some(max_blocks) = self.max_blocks_in_verification {
+			config.max_blocks_in_flight = max_blocks;
+		}
+		config
+	}
 }
 
 /// Execution strategies parameters.
@@ -0,0 +1,78 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use clap::Args;
+use sc_service::config::{FinalityLagThresholds, InformantConfig};
+use std::{path::PathBuf, time::Duration};
+
+/// Parameters of the informant printing the status of the node.
+#[derive(Debug, Clone, Args)]
+pub struct InformantParams {
+	/// Interval at which the status line is printed, in seconds.
+	#[arg(
+		long,
+		value_name = "SECONDS",
+		default_value_t = 5,
+		value_parser = clap::value_parser!(u64).range(1..),
+	)]
+	pub informant_interval: u64,
+
+	/// Finality lag, in blocks, from which the lag is printed in yellow in the status line.
+	#[arg(long, value_name = "BLOCKS", default_value_t = 10)]
+	pub finality_lag_warning: u32,
+
+	/// Finality lag, in blocks, from which the lag is printed in red in the status line.
+	#[arg(long, value_name = "BLOCKS", default_value_t = 50)]
+	pub finality_lag_critical: u32,
+
+	/// Write the status of the node as JSON to the given file at every tick of the informant.
+	///
+	/// The file contains the best and finalized blocks, the number of peers, the sync state and
+	/// the bandwidth, for external watchdogs.
+	#[arg(long, value_name = "PATH")]
+	pub status_file: Option<PathBuf>,
+
+	/// Import duration, in milliseconds, from which a warning is printed for the imported block.
+	///
+	/// `0` disables the warning.
+	#[arg(long, value_name = "MILLISECONDS", default_value_t = 2000)]
+	pub slow_import_threshold: u64,
+
+	/// Replace the status line by a progress bar during a major sync.
+	///
+	/// The bar is only drawn if stderr is a terminal.
+	#[arg(long)]
+	pub progress_bar: bool,
+}
+
+impl InformantParams {
+	/// The informant configuration.
+	pub fn informant_config(&self) -> InformantConfig {
+		InformantConfig {
+			interval: Duration::from_secs(self.informant_interval),
+			finality_lag_thresholds: FinalityLagThresholds {
+				warning: self.finality_lag_warning,
+				critical: self.finality_lag_critical,
+			},
+			status_file: self.status_file.clone(),
+			slow_import_threshold: Some(Duration::from_millis(self.slow_import_threshold))
+				.filter(|threshold| !threshold.is_zero()),
+			progress_bar: self.progress_bar,
+		}
+	}
+}
@@ -18,11 +18,17 @@
 
 use crate::{error, error::Result};
 use clap::Args;
+use sc_keystore::{plugin::enclave::EnclaveConfig, CommandKms, Unlock};
 use sc_service::config::KeystoreConfig;
-use sp_core::crypto::SecretString;
+use sp_core::{
+	crypto::{KeyTypeId, SecretString, Ss58Codec},
+	ed25519,
+};
 use std::{
 	fs,
+	net::SocketAddr,
 	path::{Path, PathBuf},
+	sync::Arc,
 };
 
 /// default sub directory for the key store
@@ -56,6 +62,80 @@ pub struct KeystoreParams {
 		conflicts_with_all = &["password_interactive", "password"]
 	)]
 	pub password_filename: Option<PathBuf>,
+
+	/// Encrypt the key files at rest with a passphrase entered in an interactive shell.
+	///
+	/// An existing keystore with plain text key files is encrypted when first opened this way.
+	#[arg(long, conflicts_with_all = &["keystore_passphrase_env", "keystore_kms_command"])]
+	pub keystore_passphrase_interactive: bool,
+
+	/// Encrypt the key files at rest with a passphrase read from the given environment variable.
+	///
+	/// An existing keystore with plain text key files is encrypted when first opened this way.
+	#[arg(
+		long,
+		value_name = "VAR",
+		conflicts_with_all = &["keystore_passphrase_interactive", "keystore_kms_command"]
+	)]
+	pub keystore_passphrase_env: Option<String>,
+
+	/// Encrypt the key files at rest with a master key protected by a key management service.
+	///
+	/// The given plugin command is invoked as `<COMMAND> wrap` or `<COMMAND> unwrap`, receives
+	/// the hex encoded master key on stdin and must write the hex encoded result to stdout.
+	#[arg(
+		long,
+		value_name = "COMMAND",
+		conflicts_with_all = &["keystore_passphrase_interactive", "keystore_passphrase_env"]
+	)]
+	pub keystore_kms_command: Option<PathBuf>,
+
+	/// Delegate the keys of the `--enclave-key-type` key types to the secure enclave hosted at
+	/// the given address.
+	///
+	/// The secret keys of these types never leave the enclave, which must be attested by one of
+	/// the `--enclave-attestation-key` keys to run one of the `--enclave-measurement` builds.
+	#[arg(
+		long,
+		value_name = "ADDRESS",
+		requires_all = &["enclave_measurement", "enclave_attestation_key"]
+	)]
+	pub signing_enclave: Option<SocketAddr>,
+
+	/// Hex encoded measurement of a trusted build of the `--signing-enclave`.
+	#[arg(long, value_name = "HEX", value_parser = parse_measurement, requires = "signing_enclave")]
+	pub enclave_measurement: Vec<[u8; 32]>,
+
+	/// SS58 address of an ed25519 key trusted to sign the attestations of the
+	/// `--signing-enclave`.
+	#[arg(
+		long,
+		value_name = "ADDRESS",
+		value_parser = parse_attestation_key,
+		requires = "signing_enclave"
+	)]
+	pub enclave_attestation_key: Vec<ed25519::Public>,
+
+	/// Key type whose keys are delegated to the `--signing-enclave`.
+	#[arg(
+		long,
+		value_name = "KEY_TYPE",
+		value_parser = parse_key_type,
+		default_values = ["babe", "gran"]
+	)]
+	pub enclave_key_type: Vec<KeyTypeId>,
+}
+
+fn parse_measurement(s: &str) -> std::result::Result<[u8; 32], String> {
+	array_bytes::hex2array(s).map_err(|e| format!("Invalid measurement: {e:?}"))
+}
+
+fn parse_attestation_key(s: &str) -> std::result::Result<ed25519::Public, String> {
+	ed25519::Public::from_ss58check(s).map_err(|e| format!("Invalid SS58 address: {e}"))
+}
+
+fn parse_key_type(s: &str) -> std::result::Result<KeyTypeId, String> {
+	KeyTypeId::try_from(s).map_err(|_| format!("Invalid key type `{s}`"))
 }
 
 /// Parse a secret string, returning a displayable error.
@@ -80,7 +160,40 @@ impl KeystoreParams {
 			.clone()
 			.unwrap_or_else(|| config_dir.join(DEFAULT_KEYSTORE_CONFIG_PATH));
 
-		Ok(KeystoreConfig::Path { path, password })
+		Ok(KeystoreConfig::Path {
+			path,
+			password,
+			encryption: self.keystore_encryption()?,
+			enclave: self.enclave_config(),
+		})
+	}
+
+	/// The configuration of the enclave the keys of some key types are delegated to, if any.
+	pub fn enclave_config(&self) -> Option<EnclaveConfig> {
+		self.signing_enclave.map(|address| EnclaveConfig {
+			address,
+			measurements: self.enclave_measurement.clone(),
+			attestation_keys: self.enclave_attestation_key.clone(),
+			key_types: self.enclave_key_type.clone(),
+		})
+	}
+
+	/// How to unlock the encryption of the key files, if enabled.
+	pub fn keystore_encryption(&self) -> Result<Option<Unlock>> {
+		let unlock = if self.keystore_passphrase_interactive {
+			let passphrase = rpassword::prompt_password("Keystore passphrase: ")?;
+			Some(Unlock::Passphrase(SecretString::new(passphrase)))
+		} else if let Some(ref var) = self.keystore_passphrase_env {
+			let passphrase = std::env::var(var)
+				.map_err(|e| format!("Failed to read keystore passphrase from `{var}`: {e}"))?;
+			Some(Unlock::Passphrase(SecretString::new(passphrase)))
+		} else {
+			self.keystore_kms_command
+				.as_ref()
+				.map(|command| Unlock::Kms(Arc::new(CommandKms::new(command))))
+		};
+
+		Ok(unlock)
 	}
 
 	/// helper method to fetch password from `KeyParams` or read from stdin
@@ -18,7 +18,7 @@
 
 use clap::Args;
 use sp_core::H256;
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 fn parse_kx_secret(s: &str) -> Result<sc_mixnet::KxSecret, String> {
 	H256::from_str(s).map(H256::to_fixed_bytes).map_err(|err| err.to_string())
@@ -42,6 +42,20 @@ pub struct MixnetParams {
 	/// should be limited to development and testing.
 	#[arg(long, value_name = "SECRET", value_parser = parse_kx_secret)]
 	pub mixnet_session_0_kx_secret: Option<sc_mixnet::KxSecret>,
+
+	/// Mean delay in milliseconds a packet is held at each mixnode before being forwarded.
+	///
+	/// Longer delays make it harder to correlate incoming and outgoing packets, at the cost of
+	/// latency. This should really be the same for all nodes!
+	#[arg(long, value_name = "MILLISECONDS", requires = "mixnet")]
+	pub mixnet_mean_forwarding_delay: Option<u64>,
+
+	/// Route extrinsics submitted via `author_submitExtrinsic` through the mixnet.
+	///
+	/// The RPC returns the hash of the extrinsic as soon as it has been sent. Whether the
+	/// extrinsic was delivered can be queried with `mixnet_submissionStatus`.
+	#[arg(long, requires = "mixnet")]
+	pub rpc_submit_via_mixnet: bool,
 }
 
 impl MixnetParams {
@@ -55,6 +69,10 @@ impl MixnetParams {
 				},
 				..Default::default()
 			};
+			if let Some(delay) = self.mixnet_mean_forwarding_delay {
+				config.core.mean_forwarding_delay = Duration::from_millis(delay);
+			}
+			config.substrate.route_author_submissions = self.rpc_submit_via_mixnet;
 			if !is_authority {
 				// Only authorities can be mixnodes; don't attempt to register
 				config.substrate.register = false;
@@ -15,8 +15,10 @@
 
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
+mod backup_params;
 mod database_params;
 mod import_params;
+mod informant_params;
 mod keystore_params;
 mod message_params;
 mod mixnet_params;
@@ -25,10 +27,12 @@ mod node_key_params;
 mod offchain_worker_params;
 mod prometheus_params;
 mod pruning_params;
+mod resource_limits_params;
 mod rpc_params;
 mod runtime_params;
 mod shared_params;
 mod telemetry_params;
+mod tokio_params;
 mod transaction_pool_params;
 
 use crate::arg_enums::{CryptoScheme, OutputType};
@@ -42,9 +46,10 @@ use sp_runtime::{
 use std::{fmt::Debug, str::FromStr};
 
 pub use crate::params::{
-	database_params::*, import_params::*, keystore_params::*, message_params::*, mixnet_params::*,
-	network_params::*, node_key_params::*, offchain_worker_params::*, prometheus_params::*,
-	pruning_params::*, rpc_params::*, runtime_params::*, shared_params::*, telemetry_params::*,
+	backup_params::*, database_params::*, import_params::*, informant_params::*,
+	keystore_params::*, message_params::*, mixnet_params::*, network_params::*, node_key_params::*,
+	offchain_worker_params::*, prometheus_params::*, pruning_params::*, resource_limits_params::*,
+	rpc_params::*, runtime_params::*, shared_params::*, telemetry_params::*, tokio_params::*,
 	transaction_pool_params::*,
 };
 
@@ -23,7 +23,8 @@ use crate::{
 use clap::Args;
 use sc_network::{
 	config::{
-		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig,
+		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, ProxyConfig, SetConfig,
+		TransactionPropagation, TransportConfig, WarpCheckpointConfig,
 	},
 	multiaddr::Protocol,
 };
@@ -31,7 +32,18 @@ use sc_service::{
 	config::{Multiaddr, MultiaddrWithPeerId},
 	ChainSpec, ChainType,
 };
-use std::{borrow::Cow, num::NonZeroUsize, path::PathBuf};
+use sp_core::{crypto::Ss58Codec, sr25519};
+use std::{borrow::Cow, net::SocketAddr, num::NonZeroUsize, path::PathBuf, time::Duration};
+
+fn parse_proxy_credentials(s: &str) -> Result<(String, String), String> {
+	s.split_once(':')
+		.map(|(username, password)| (username.into(), password.into()))
+		.ok_or_else(|| "Expected `USERNAME:PASSWORD`".into())
+}
+
+fn parse_checkpoint_publisher(s: &str) -> Result<sr25519::Public, String> {
+	sr25519::Public::from_ss58check(s).map_err(|e| format!("Invalid SS58 address: {e}"))
+}
 
 /// Parameters used to create the network configuration.
 #[derive(Debug, Clone, Args)]
@@ -163,6 +175,30 @@ pub struct NetworkParams {
 	)]
 	pub sync: SyncMode,
 
+	/// Checkpoint provider attesting the target of the warp sync.
+	///
+	/// Either the `http(s)://` URL of the RPC endpoint of a trusted node, or the path of a signed
+	/// checkpoint file. Can be given multiple times. The warp sync of an empty database then
+	/// downloads the state of a finalized block attested by `--warp-checkpoint-threshold` of the
+	/// providers, instead of relying on the warp proofs of the peers.
+	///
+	/// RPC endpoints require a node built with the `rpc-checkpoints` feature of
+	/// `sc-network-sync`. Signed checkpoints must be bound to the genesis hash of the chain.
+	#[arg(long, value_name = "URL_OR_PATH")]
+	pub warp_checkpoint: Vec<String>,
+
+	/// Number of `--warp-checkpoint` providers that must attest the target of the warp sync.
+	///
+	/// Defaults to all of them.
+	#[arg(long, value_name = "COUNT", requires = "warp_checkpoint")]
+	pub warp_checkpoint_threshold: Option<usize>,
+
+	/// SS58 address of a publisher whose signed checkpoint files are accepted.
+	///
+	/// Can be given multiple times.
+	#[arg(long, value_name = "ADDRESS", value_parser = parse_checkpoint_publisher)]
+	pub warp_checkpoint_publisher: Vec<sr25519::Public>,
+
 	/// Maximum number of blocks per request.
 	///
 	/// Try reducing this number from the default value if you have a slow network connection
@@ -183,6 +219,39 @@ pub struct NetworkParams {
 		verbatim_doc_comment
 	)]
 	pub network_backend: NetworkBackendType,
+
+	/// Dial outbound P2P connections through the SOCKS5 proxy at the given address.
+	///
+	/// Host names and `/onion3/...` addresses are resolved by the proxy. Use e.g.
+	/// `127.0.0.1:9050` to connect to peers over a local Tor daemon without revealing the IP
+	/// address of the node. Listening addresses are unaffected. Implies `--no-mdns`. Only
+	/// supported by the libp2p network backend.
+	#[arg(long, value_name = "ADDRESS")]
+	pub proxy: Option<SocketAddr>,
+
+	/// Credentials to authenticate with at the SOCKS5 proxy, as `USERNAME:PASSWORD`.
+	#[arg(
+		long,
+		value_name = "USERNAME:PASSWORD",
+		requires = "proxy",
+		value_parser = parse_proxy_credentials
+	)]
+	pub proxy_credentials: Option<(String, String)>,
+
+	/// Relay locally submitted transactions to a single peer before gossiping them.
+	///
+	/// Dandelion-style stem/fluff propagation: transactions submitted to this node, e.g. over
+	/// RPC, are first sent to one periodically rotated peer only, which gossips them further. So
+	/// observers can't trivially link the transactions to the IP address of this node.
+	#[arg(long)]
+	pub tx_stem_propagation: bool,
+
+	/// Seconds to wait for a stemmed transaction to be gossiped back by other peers, before this
+	/// node gossips it itself.
+	///
+	/// Only used with `--tx-stem-propagation`.
+	#[arg(long, value_name = "SECONDS", default_value_t = 30)]
+	pub tx_stem_embargo: u64,
 }
 
 impl NetworkParams {
@@ -266,7 +335,7 @@ impl NetworkParams {
 			node_name: node_name.to_string(),
 			client_version: client_id.to_string(),
 			transport: TransportConfig::Normal {
-				enable_mdns: !is_dev && !self.no_mdns,
+				enable_mdns: !is_dev && !self.no_mdns && self.proxy.is_none(),
 				allow_private_ip,
 			},
 			max_parallel_downloads: self.max_parallel_downloads,
@@ -277,7 +346,21 @@ impl NetworkParams {
 			kademlia_replication_factor: self.kademlia_replication_factor,
 			ipfs_server: self.ipfs_server,
 			sync_mode: self.sync.into(),
+			warp_checkpoints: WarpCheckpointConfig {
+				providers: self.warp_checkpoint.clone(),
+				threshold: self.warp_checkpoint_threshold,
+				trusted_publishers: self.warp_checkpoint_publisher.clone(),
+			},
 			network_backend: self.network_backend.into(),
+			proxy: self.proxy.map(|address| ProxyConfig {
+				address,
+				credentials: self.proxy_credentials.clone(),
+			}),
+			transaction_propagation: if self.tx_stem_propagation {
+				TransactionPropagation::Stem { embargo: Duration::from_secs(self.tx_stem_embargo) }
+			} else {
+				TransactionPropagation::Flood
+			},
 		}
 	}
 }
@@ -326,6 +409,15 @@ mod tests {
 		assert_eq!(expected, params.network_params.reserved_nodes);
 	}
 
+	#[test]
+	fn tx_stem_embargo_defaults_to_30_seconds() {
+		let params =
+			Cli::try_parse_from(["", "--tx-stem-propagation"]).expect("Parses network params");
+
+		assert!(params.network_params.tx_stem_propagation);
+		assert_eq!(30, params.network_params.tx_stem_embargo);
+	}
+
 	#[test]
 	fn sync_ignores_case() {
 		let params = Cli::try_parse_from(["", "--sync", "wArP"]).expect("Parses network params");
@@ -0,0 +1,234 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{arg_enums::IoClass, error::Result, Error};
+use clap::Args;
+use std::{
+	num::NonZeroU32,
+	path::{Path, PathBuf},
+};
+
+/// Parameters of the resource limits the node imposes on itself, to constrain it on shared hosts.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ResourceLimitsParams {
+	/// Minimum limit of open file descriptors of the process.
+	///
+	/// The node doesn't start if the limit can't be raised to this value.
+	#[arg(long, value_name = "COUNT")]
+	pub min_open_files: Option<u64>,
+
+	/// Limit the average rate at which the database is written to, in MiB per second.
+	///
+	/// The imports of the blocks of the initial sync are delayed, by at most ten seconds, once more
+	/// than one second worth of writes is pending. The other writes are never delayed.
+	#[arg(long, value_name = "MIB/S")]
+	pub db_write_rate_limit: Option<NonZeroU32>,
+
+	/// Niceness of the node process, from -20 (highest priority) to 19 (lowest priority).
+	///
+	/// Only supported on Linux.
+	#[arg(
+		long,
+		value_name = "NICENESS",
+		allow_hyphen_values = true,
+		value_parser = clap::value_parser!(i32).range(-20..=19),
+	)]
+	pub nice: Option<i32>,
+
+	/// IO scheduling class of the node process.
+	///
+	/// Only supported on Linux.
+	#[arg(long, value_enum, value_name = "CLASS")]
+	pub io_class: Option<IoClass>,
+
+	/// IO priority of the node process within its IO scheduling class, from 0 (highest priority)
+	/// to 7 (lowest priority).
+	///
+	/// Ignored by the `idle` class.
+	#[arg(
+		long,
+		value_name = "PRIORITY",
+		requires = "io_class",
+		value_parser = clap::value_parser!(u8).range(0..=7),
+	)]
+	pub io_priority: Option<u8>,
+}
+
+impl ResourceLimitsParams {
+	/// Apply the niceness and the IO priority to the current thread.
+	///
+	/// The threads spawned afterwards inherit them, so this needs to be called before spawning
+	/// the threads of the node.
+	pub fn apply_priorities(&self) -> Result<()> {
+		if let Some(niceness) = self.nice {
+			priority::set_niceness(niceness)?;
+		}
+		if let Some(class) = self.io_class {
+			priority::set_io_priority(class, self.io_priority.unwrap_or(4))?;
+		}
+		Ok(())
+	}
+
+	/// Check that the limit of open file descriptors of the process is at least the minimum.
+	pub fn check_open_files(&self, limit: u64) -> Result<()> {
+		match self.min_open_files {
+			Some(min) if limit < min => Err(Error::Input(format!(
+				"The open file descriptor limit of the process is {limit}, below the minimum of \
+				{min} set with `--min-open-files`"
+			))),
+			_ => Ok(()),
+		}
+	}
+}
+
+#[cfg(target_os = "linux")]
+mod priority {
+	use crate::{arg_enums::IoClass, error::Result};
+	use std::io;
+
+	/// `IOPRIO_WHO_PROCESS` of `ioprio_set`, which applies to a thread on Linux.
+	const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+	/// The shift of the class in an IO priority.
+	const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+	pub(super) fn set_niceness(niceness: i32) -> Result<()> {
+		// SAFETY: `setpriority` only changes the priority of the calling thread.
+		if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, niceness) } != 0 {
+			return Err(io::Error::last_os_error().into())
+		}
+		Ok(())
+	}
+
+	pub(super) fn set_io_priority(class: IoClass, priority: u8) -> Result<()> {
+		let (class, priority) = match class {
+			IoClass::Realtime => (1, priority),
+			IoClass::BestEffort => (2, priority),
+			IoClass::Idle => (3, 0),
+		};
+		let ioprio = class << IOPRIO_CLASS_SHIFT | libc::c_int::from(priority);
+		// SAFETY: `ioprio_set` only changes the IO priority of the calling thread.
+		if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) } != 0 {
+			return Err(io::Error::last_os_error().into())
+		}
+		Ok(())
+	}
+}
+
+#[cfg(not(target_os = "linux"))]
+mod priority {
+	use crate::{arg_enums::IoClass, error::Result, Error};
+
+	pub(super) fn set_niceness(_: i32) -> Result<()> {
+		Err(Error::Input("`--nice` is only supported on Linux".into()))
+	}
+
+	pub(super) fn set_io_priority(_: IoClass, _: u8) -> Result<()> {
+		Err(Error::Input("`--io-class` is only supported on Linux".into()))
+	}
+}
+
+/// The memory limit of the cgroup of the process, in bytes, if any.
+///
+/// The limits of the ancestors of the cgroup apply as well, the lowest limit is returned.
+pub(crate) fn cgroup_memory_limit() -> Option<u64> {
+	let cgroups = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+	cgroup_memory_limit_files(&cgroups)
+		.into_iter()
+		.filter_map(|path| std::fs::read_to_string(path).ok()?.trim().parse::<u64>().ok())
+		// cgroup v2 exposes the limit as `max` when there is none, cgroup v1 as a huge number.
+		.filter(|limit| *limit < u64::MAX / 2)
+		.min()
+}
+
+/// The files holding the memory limits of the cgroups listed in `/proc/self/cgroup`, from the
+/// cgroup of the process up to the root of the hierarchy.
+fn cgroup_memory_limit_files(cgroups: &str) -> Vec<PathBuf> {
+	let mut files = Vec::new();
+	for line in cgroups.lines() {
+		// `hierarchy-ID:controllers:path`, the controllers of the cgroup v2 hierarchy are empty.
+		let mut fields = line.splitn(3, ':').skip(1);
+		let (Some(controllers), Some(path)) = (fields.next(), fields.next()) else { continue };
+		let (root, file) = if controllers.is_empty() {
+			(Path::new("/sys/fs/cgroup"), "memory.max")
+		} else if controllers.split(',').any(|controller| controller == "memory") {
+			(Path::new("/sys/fs/cgroup/memory"), "memory.limit_in_bytes")
+		} else {
+			continue
+		};
+		let mut path = Some(Path::new(path.trim_start_matches('/')));
+		while let Some(cgroup) = path {
+			files.push(root.join(cgroup).join(file));
+			path = cgroup.parent();
+		}
+	}
+	files
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[clap(flatten)]
+		limits: ResourceLimitsParams,
+	}
+
+	#[test]
+	fn resource_limits_params_parse_works() {
+		let Cli { limits } =
+			Cli::parse_from(["", "--nice=-5", "--io-class=best-effort", "--io-priority=7"]);
+		assert_eq!(limits.nice, Some(-5));
+		assert_eq!(limits.io_class, Some(IoClass::BestEffort));
+		assert_eq!(limits.io_priority, Some(7));
+
+		assert!(Cli::try_parse_from(["", "--nice=20"]).is_err());
+		assert!(Cli::try_parse_from(["", "--io-priority=1"]).is_err());
+
+		let Cli { limits } = Cli::parse_from(["", "--min-open-files=1000"]);
+		assert!(limits.check_open_files(1000).is_ok());
+		assert!(limits.check_open_files(999).is_err());
+	}
+
+	#[test]
+	fn cgroup_memory_limit_files_are_resolved() {
+		let v2 = cgroup_memory_limit_files("0::/system.slice/node.service\n");
+		assert_eq!(
+			v2,
+			[
+				"/sys/fs/cgroup/system.slice/node.service/memory.max",
+				"/sys/fs/cgroup/system.slice/memory.max",
+				"/sys/fs/cgroup/memory.max",
+			]
+			.map(PathBuf::from),
+		);
+
+		let v1 = cgroup_memory_limit_files("5:cpu,cpuacct:/docker/abc\n4:memory:/docker/abc\n");
+		assert_eq!(
+			v1,
+			[
+				"/sys/fs/cgroup/memory/docker/abc/memory.limit_in_bytes",
+				"/sys/fs/cgroup/memory/docker/memory.limit_in_bytes",
+				"/sys/fs/cgroup/memory/memory.limit_in_bytes",
+			]
+			.map(PathBuf::from),
+		);
+	}
+}
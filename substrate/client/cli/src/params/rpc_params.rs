@@ -100,6 +100,34 @@ pub struct RpcParams {
 	#[arg(long)]
 	pub rpc_rate_limit_trust_proxy_headers: bool,
 
+	/// Serve the last finalized block instead of the best block by default.
+	///
+	/// The RPC requests without block hash are answered at the last finalized block, and the
+	/// subscriptions to new heads, storage changes and runtime versions only notify finalized
+	/// blocks. This relieves the RPC clients from handling reorgs. Only the legacy RPC API is
+	/// affected.
+	#[arg(long)]
+	pub serve_finalized_only: bool,
+
+	/// Number of blocks built on top of a watched transaction before it is reported.
+	///
+	/// The `InBlock` and `Finalized` events of the transactions watched through
+	/// `author_submitAndWatchExtrinsic` and `transactionWatch_v1_submitAndWatch` are delayed
+	/// until this number of blocks are respectively built and finalized on top of the including
+	/// block. The number of blocks on top is reported in the `confirmations` field of the
+	/// delayed events. Useful when bridging to systems with probabilistic finality.
+	#[arg(long, value_name = "BLOCKS", default_value_t = 0)]
+	pub rpc_transaction_confirmations: u32,
+
+	/// Keep the `chainHead_v1_follow` subscriptions for this number of seconds after their
+	/// stream ended.
+	///
+	/// A subscription interrupted by a transient disconnect keeps its blocks pinned and can be
+	/// resumed by passing its ID as resume token to `chainHead_unstable_resumeFollow`. The client
+	/// then receives the events it missed instead of starting from scratch. Disabled by default.
+	#[arg(long, value_name = "SECONDS")]
+	pub rpc_chain_head_resume_grace_period: Option<u64>,
+
 	/// Set the maximum RPC request payload size for both HTTP and WS in megabytes.
 	#[arg(long, default_value_t = RPC_DEFAULT_MAX_REQUEST_SIZE_MB)]
 	pub rpc_max_request_size: u32,
@@ -89,6 +89,24 @@ pub struct SharedParams {
 	/// Receiver to process tracing messages.
 	#[arg(long, value_name = "RECEIVER", value_enum, ignore_case = true, default_value_t = TracingReceiver::Log)]
 	pub tracing_receiver: TracingReceiver,
+
+	/// Route the log messages of the runtime to a dedicated sink, with the given log filter.
+	///
+	/// Syntax is the same as for logging (`--log`), e.g. `info,runtime::staking=debug`. The
+	/// messages of the runtime are then no longer subject to `--log`, and their filter can be
+	/// changed with the `system_setRuntimeLogLevels` RPC.
+	#[arg(long, value_name = "LOG_PATTERN")]
+	pub runtime_log: Option<String>,
+
+	/// Write the log messages of the runtime to the given file instead of stderr.
+	#[arg(long, value_name = "PATH", requires = "runtime_log")]
+	pub runtime_log_file: Option<PathBuf>,
+
+	/// Maximum number of log messages of the runtime written per second.
+	///
+	/// Further messages are dropped and counted. `0` disables the limit.
+	#[arg(long, value_name = "COUNT", default_value_t = 100, requires = "runtime_log")]
+	pub runtime_log_rate_limit: u32,
 }
 
 impl SharedParams {
@@ -145,4 +163,19 @@ impl SharedParams {
 	pub fn tracing_targets(&self) -> Option<String> {
 		self.tracing_targets.clone()
 	}
+
+	/// Log filter of the runtime log sink, if enabled.
+	pub fn runtime_log(&self) -> Option<&str> {
+		self.runtime_log.as_deref()
+	}
+
+	/// File the log messages of the runtime are written to.
+	pub fn runtime_log_file(&self) -> Option<&PathBuf> {
+		self.runtime_log_file.as_ref()
+	}
+
+	/// Maximum number of log messages of the runtime written per second.
+	pub fn runtime_log_rate_limit(&self) -> u32 {
+		self.runtime_log_rate_limit
+	}
 }
@@ -0,0 +1,118 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::runner::runtime_builder;
+use clap::Args;
+use sc_service::config::DedicatedRuntimes;
+use tokio::runtime::Runtime;
+
+/// Parameters of the tokio runtimes of the node.
+#[derive(Debug, Clone, Default, Args)]
+pub struct TokioParams {
+	/// Number of worker threads of the main tokio runtime.
+	///
+	/// Defaults to the number of CPU cores.
+	#[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u16).range(1..))]
+	pub worker_threads: Option<u16>,
+
+	/// Maximum number of threads of the blocking pool of each tokio runtime.
+	///
+	/// Defaults to 512.
+	#[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u16).range(1..))]
+	pub max_blocking_threads: Option<u16>,
+
+	/// Run the block import queue on a dedicated tokio runtime with the given number of worker
+	/// threads.
+	///
+	/// Block verification and import then don't compete with block authoring for the threads of
+	/// the main runtime.
+	#[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u16).range(1..))]
+	pub import_runtime_threads: Option<u16>,
+
+	/// Run the RPC servers and subscriptions on a dedicated tokio runtime with the given number of
+	/// worker threads.
+	///
+	/// A high RPC load then doesn't delay block authoring on validators.
+	#[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u16).range(1..))]
+	pub rpc_runtime_threads: Option<u16>,
+}
+
+impl TokioParams {
+	/// Build the main tokio runtime.
+	pub fn build_runtime(&self) -> std::io::Result<Runtime> {
+		self.builder("tokio-runtime-worker", self.worker_threads).build()
+	}
+
+	/// Build the dedicated tokio runtimes, returning them together with their handles.
+	///
+	/// The runtimes need to be kept alive as long as the node is running.
+	pub fn build_dedicated_runtimes(&self) -> std::io::Result<(DedicatedRuntimes, Vec<Runtime>)> {
+		let mut handles = DedicatedRuntimes::default();
+		let mut runtimes = Vec::new();
+		if let Some(threads) = self.import_runtime_threads {
+			let runtime = self.builder("tokio-import-worker", Some(threads)).build()?;
+			handles.block_import = Some(runtime.handle().clone());
+			runtimes.push(runtime);
+		}
+		if let Some(threads) = self.rpc_runtime_threads {
+			let runtime = self.builder("tokio-rpc-worker", Some(threads)).build()?;
+			handles.rpc = Some(runtime.handle().clone());
+			runtimes.push(runtime);
+		}
+		Ok((handles, runtimes))
+	}
+
+	fn builder(
+		&self,
+		thread_name: &'static str,
+		worker_threads: Option<u16>,
+	) -> tokio::runtime::Builder {
+		let mut builder = runtime_builder();
+		builder.thread_name(thread_name);
+		if let Some(threads) = worker_threads {
+			builder.worker_threads(threads.into());
+		}
+		if let Some(threads) = self.max_blocking_threads {
+			builder.max_blocking_threads(threads.into());
+		}
+		builder
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[clap(flatten)]
+		tokio: TokioParams,
+	}
+
+	#[test]
+	fn only_requested_runtimes_are_dedicated() {
+		let Cli { tokio } = Cli::parse_from(["", "--rpc-runtime-threads=2"]);
+		let (handles, runtimes) = tokio.build_dedicated_runtimes().unwrap();
+		assert_eq!(runtimes.len(), 1);
+		assert!(handles.rpc.is_some());
+		assert!(handles.for_group("block-import").is_none());
+
+		assert!(Cli::try_parse_from(["", "--worker-threads=0"]).is_err());
+	}
+}
@@ -50,6 +50,28 @@ pub struct TransactionPoolParams {
 	#[arg(long, value_name = "COUNT", default_value_t = 20480)]
 	pub pool_kbytes: usize,
 
+	/// Maximum number of privileged transactions stored in addition to `--pool-limit`.
+	///
+	/// Privileged transactions are the locally submitted ones, e.g. offence reports of offchain
+	/// workers, and the ones with at least `--pool-reserved-priority`. Other transactions can't
+	/// use this capacity, so a pool full of them can't drop the privileged ones. No capacity is
+	/// reserved by default.
+	#[arg(long, value_name = "COUNT", default_value_t = 0)]
+	pub pool_reserved_limit: usize,
+
+	/// Maximum number of kilobytes of privileged transactions stored in addition to
+	/// `--pool-kbytes`.
+	#[arg(long, value_name = "COUNT", default_value_t = 0)]
+	pub pool_reserved_kbytes: usize,
+
+	/// Minimum priority of externally submitted transactions to be privileged.
+	///
+	/// Operational-class transactions get high priorities in FRAME based runtimes, so a threshold
+	/// below their priorities lets them use the reserved capacity. By default, only transactions
+	/// with the maximal priority are privileged.
+	#[arg(long, value_name = "PRIORITY", default_value_t = u64::MAX)]
+	pub pool_reserved_priority: u64,
+
 	/// How long a transaction is banned for.
 	///
 	/// If it is considered invalid. Defaults to 1800s.
@@ -71,5 +93,30 @@ impl TransactionPoolParams {
 			self.pool_type.into(),
 			is_dev,
 		)
+		.with_reserved_limit(
+			self.pool_reserved_limit,
+			self.pool_reserved_kbytes * 1024,
+			self.pool_reserved_priority,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[clap(flatten)]
+		pool_params: TransactionPoolParams,
+	}
+
+	#[test]
+	fn no_capacity_is_reserved_by_default() {
+		let params = Cli::try_parse_from([""]).expect("Parses pool params").pool_params;
+
+		assert_eq!((params.pool_limit, params.pool_kbytes), (8192, 20480));
+		assert_eq!((params.pool_reserved_limit, params.pool_reserved_kbytes), (0, 0));
 	}
 }
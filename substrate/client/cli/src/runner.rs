@@ -26,7 +26,14 @@ use std::{marker::PhantomData, time::Duration};
 
 /// Build a tokio runtime with all features.
 pub fn build_runtime() -> std::result::Result<tokio::runtime::Runtime, std::io::Error> {
-	tokio::runtime::Builder::new_multi_thread()
+	runtime_builder().build()
+}
+
+/// The builder of a multi-threaded tokio runtime with all features, whose threads are counted in
+/// the metrics.
+pub(crate) fn runtime_builder() -> tokio::runtime::Builder {
+	let mut builder = tokio::runtime::Builder::new_multi_thread();
+	builder
 		.on_thread_start(|| {
 			TOKIO_THREADS_ALIVE.inc();
 			TOKIO_THREADS_TOTAL.inc();
@@ -34,14 +41,15 @@ pub fn build_runtime() -> std::result::Result<tokio::runtime::Runtime, std::io::
 		.on_thread_stop(|| {
 			TOKIO_THREADS_ALIVE.dec();
 		})
-		.enable_all()
-		.build()
+		.enable_all();
+	builder
 }
 
 /// A Substrate CLI runtime that can be used to run a node or a command
 pub struct Runner<C: SubstrateCli> {
 	config: Configuration,
 	tokio_runtime: tokio::runtime::Runtime,
+	dedicated_runtimes: Vec<tokio::runtime::Runtime>,
 	signals: Signals,
 	phantom: PhantomData<C>,
 }
@@ -53,7 +61,21 @@ impl<C: SubstrateCli> Runner<C> {
 		tokio_runtime: tokio::runtime::Runtime,
 		signals: Signals,
 	) -> Result<Runner<C>> {
-		Ok(Runner { config, tokio_runtime, signals, phantom: PhantomData })
+		Ok(Runner {
+			config,
+			tokio_runtime,
+			dedicated_runtimes: Vec::new(),
+			signals,
+			phantom: PhantomData,
+		})
+	}
+
+	/// Keep the tokio runtimes dedicated to some subsystems alive until the node is stopped.
+	///
+	/// Their handles are expected to be in [`Configuration::dedicated_runtimes`].
+	pub fn with_dedicated_runtimes(mut self, runtimes: Vec<tokio::runtime::Runtime>) -> Self {
+		self.dedicated_runtimes = runtimes;
+		self
 	}
 
 	/// Log information about the node itself.
@@ -100,6 +122,9 @@ impl<C: SubstrateCli> Runner<C> {
 		// Give all futures 60 seconds to shutdown, before tokio "leaks" them.
 		let shutdown_timeout = Duration::from_secs(60);
 		self.tokio_runtime.shutdown_timeout(shutdown_timeout);
+		for runtime in self.dedicated_runtimes {
+			runtime.shutdown_timeout(shutdown_timeout);
+		}
 
 		let running_tasks = task_registry.running_tasks();
 
@@ -247,6 +272,7 @@ mod tests {
 				impl_version: "3".into(),
 				role: sc_service::Role::Authority,
 				tokio_handle: runtime.handle().clone(),
+				dedicated_runtimes: Default::default(),
 				transaction_pool: Default::default(),
 				network: NetworkConfiguration::new_memory(),
 				keystore: sc_service::config::KeystoreConfig::InMemory,
@@ -254,6 +280,7 @@ mod tests {
 				trie_cache_maximum_size: None,
 				state_pruning: None,
 				blocks_pruning: sc_client_db::BlocksPruning::KeepAll,
+				database_write_rate_limit: None,
 				chain_spec: Box::new(
 					GenericChainSpec::<NoExtension, ()>::builder(
 						Default::default(),
@@ -267,6 +294,7 @@ mod tests {
 				),
 				executor: ExecutorConfiguration::default(),
 				wasm_runtime_overrides: None,
+				import_queue: Default::default(),
 				rpc: RpcConfiguration {
 					addr: None,
 					max_connections: Default::default(),
@@ -282,6 +310,9 @@ mod tests {
 					rate_limit: None,
 					rate_limit_whitelisted_ips: Default::default(),
 					rate_limit_trust_proxy_headers: Default::default(),
+					serve_finalized_only: false,
+					transaction_confirmations: 0,
+					chain_head_resume_grace_period: None,
 				},
 				prometheus_config: None,
 				telemetry_endpoints: None,
@@ -292,6 +323,7 @@ mod tests {
 				tracing_targets: None,
 				tracing_receiver: Default::default(),
 				announce_block: true,
+				informant: Default::default(),
 				base_path: sc_service::BasePath::new(root.clone()),
 				data_path: root,
 			},
@@ -246,6 +246,7 @@ mod tests {
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
 				RejectAllTxPool::default(),
 			),
+			import_queue_config: Default::default(),
 		})
 		.unwrap();
 
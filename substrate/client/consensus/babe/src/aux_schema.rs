@@ -35,6 +35,13 @@ const BABE_EPOCH_CHANGES_VERSION: &[u8] = b"babe_epoch_changes_version";
 const BABE_EPOCH_CHANGES_KEY: &[u8] = b"babe_epoch_changes";
 const BABE_EPOCH_CHANGES_CURRENT_VERSION: u32 = 3;
 
+/// The aux storage keys of the epoch changes, exported and imported as a whole for disaster
+/// recovery.
+///
+/// The block weights are not included, as they only matter for the fork choice of the blocks
+/// imported since the export.
+pub const AUX_KEYS: &[&[u8]] = &[BABE_EPOCH_CHANGES_VERSION, BABE_EPOCH_CHANGES_KEY];
+
 /// The aux storage key used to store the block weight of the given block hash.
 pub fn block_weight_key<H: Encode>(block_hash: H) -> Vec<u8> {
 	(b"block_weight", block_hash).encode()
@@ -97,19 +97,22 @@ use sc_consensus::{
 		BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult,
 		StateAction,
 	},
-	import_queue::{BasicQueue, BoxJustificationImport, DefaultImportQueue, Verifier},
+	import_queue::{
+		BasicQueue, BoxJustificationImport, DefaultImportQueue, ImportQueueConfig, Verifier,
+	},
 };
 use sc_consensus_epochs::{
 	descendent_query, Epoch as EpochT, EpochChangesFor, SharedEpochChanges, ViableEpochDescriptor,
 };
+use sc_consensus_slashing_protection::SlashingProtection;
 use sc_consensus_slots::{
-	check_equivocation, BackoffAuthoringBlocksStrategy, CheckedHeader, InherentDataProviderExt,
-	SlotInfo, StorageChanges,
+	check_equivocation, BackoffAuthoringBlocksStrategy, CheckedHeader, ClockHealth,
+	InherentDataProviderExt, SlotInfo, SlotLeakageAnalyzer, StorageChanges,
 };
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_TRACE};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_api::{ApiExt, ProvideRuntimeApi};
-use sp_application_crypto::AppCrypto;
+use sp_application_crypto::{AppCrypto, ByteArray};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{
 	Backend as _, BlockStatus, Error as ClientError, ForkBackend, HeaderBackend, HeaderMetadata,
@@ -457,6 +460,15 @@ pub struct BabeParams<B: BlockT, C, SC, E, I, SO, L, CIDP, BS> {
 
 	/// Handle use to report telemetries.
 	pub telemetry: Option<TelemetryHandle>,
+
+	/// Slashing protection checked before sealing a block.
+	pub slashing_protection: Option<SlashingProtection>,
+
+	/// Health of the local clock, consulted before authoring.
+	pub clock_health: Option<ClockHealth>,
+
+	/// Analyzer informed about claimed slots and the outcome of every authoring attempt.
+	pub slot_leakage_analyzer: Option<SlotLeakageAnalyzer>,
 }
 
 /// Start the babe worker.
@@ -476,6 +488,9 @@ pub fn start_babe<B, C, SC, E, I, SO, CIDP, BS, L, Error>(
 		block_proposal_slot_portion,
 		max_block_proposal_slot_portion,
 		telemetry,
+		slashing_protection,
+		clock_health,
+		slot_leakage_analyzer,
 	}: BabeParams<B, C, SC, E, I, SO, L, CIDP, BS>,
 ) -> Result<BabeWorker<B>, ConsensusError>
 where
@@ -515,6 +530,9 @@ where
 		block_proposal_slot_portion,
 		max_block_proposal_slot_portion,
 		telemetry,
+		slashing_protection,
+		clock_health,
+		slot_leakage_analyzer,
 	};
 
 	info!(target: LOG_TARGET, "👶 Starting BABE Authorship worker");
@@ -712,6 +730,9 @@ struct BabeSlotWorker<B: BlockT, C, E, I, SO, L, BS> {
 	block_proposal_slot_portion: SlotProportion,
 	max_block_proposal_slot_portion: Option<SlotProportion>,
 	telemetry: Option<TelemetryHandle>,
+	slashing_protection: Option<SlashingProtection>,
+	clock_health: Option<ClockHealth>,
+	slot_leakage_analyzer: Option<SlotLeakageAnalyzer>,
 }
 
 #[async_trait::async_trait]
@@ -772,6 +793,11 @@ where
 		slot: Slot,
 		epoch_descriptor: &ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>,
 	) -> Option<Self::Claim> {
+		if self.slashing_protection.as_ref().is_some_and(|store| !store.may_sign()) {
+			debug!(target: LOG_TARGET, "Not claiming slot {}, signing is not allowed", slot);
+			return None
+		}
+
 		debug!(target: LOG_TARGET, "Attempting to claim slot {}", slot);
 		let s = authorship::claim_slot(
 			slot,
@@ -818,9 +844,15 @@ where
 		header_hash: &B::Hash,
 		body: Vec<B::Extrinsic>,
 		storage_changes: StorageChanges<B>,
-		(_, public): Self::Claim,
+		(pre_digest, public): Self::Claim,
 		epoch_descriptor: Self::AuxData,
 	) -> Result<BlockImportParams<B>, ConsensusError> {
+		if let Some(slashing_protection) = &self.slashing_protection {
+			slashing_protection
+				.check_babe_block(public.as_slice(), *pre_digest.slot(), header_hash.as_ref())
+				.map_err(|e| ConsensusError::CannotSign(format!("{}. Key: {:?}", e, public)))?;
+		}
+
 		let signature = self
 			.keystore
 			.sr25519_sign(<AuthorityId as AppCrypto>::ID, public.as_ref(), header_hash.as_ref())
@@ -882,6 +914,14 @@ where
 		self.telemetry.clone()
 	}
 
+	fn clock_health(&self) -> Option<ClockHealth> {
+		self.clock_health.clone()
+	}
+
+	fn slot_leakage_analyzer(&self) -> Option<SlotLeakageAnalyzer> {
+		self.slot_leakage_analyzer.clone()
+	}
+
 	fn proposing_remaining_duration(&self, slot_info: &SlotInfo<B>) -> Duration {
 		let parent_slot = find_pre_digest::<B>(&slot_info.chain_head).ok().map(|d| d.slot());
 
@@ -1796,6 +1836,8 @@ pub struct ImportQueueParams<'a, Block: BlockT, BI, Client, CIDP, SelectChain, S
 	///
 	/// Will be used when sending equivocation reports.
 	pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+	/// Configuration of the block verification by the import queue.
+	pub import_queue_config: ImportQueueConfig,
 }
 
 /// Start an import queue for the BABE consensus algorithm.
@@ -1819,6 +1861,7 @@ pub fn import_queue<Block: BlockT, Client, SelectChain, BI, CIDP, Spawn>(
 		registry,
 		telemetry,
 		offchain_tx_pool_factory,
+		import_queue_config,
 	}: ImportQueueParams<'_, Block, BI, Client, CIDP, SelectChain, Spawn>,
 ) -> ClientResult<(DefaultImportQueue<Block>, BabeWorkerHandle<Block>)>
 where
@@ -1856,7 +1899,14 @@ where
 	spawner.spawn_essential("babe-worker", Some("babe"), answer_requests.boxed());
 
 	Ok((
-		BasicQueue::new(verifier, Box::new(block_import), justification_import, spawner, registry),
+		BasicQueue::with_config(
+			verifier,
+			Box::new(block_import),
+			justification_import,
+			spawner,
+			registry,
+			import_queue_config,
+		),
 		BabeWorkerHandle(worker_tx),
 	))
 }
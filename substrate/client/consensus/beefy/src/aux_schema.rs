@@ -31,6 +31,10 @@ const WORKER_STATE_KEY: &[u8] = b"beefy_voter_state";
 
 const CURRENT_VERSION: u32 = 4;
 
+/// The aux storage keys of the voter state, exported and imported as a whole for disaster
+/// recovery.
+pub const AUX_KEYS: &[&[u8]] = &[VERSION_KEY, WORKER_STATE_KEY];
+
 pub(crate) fn write_current_version<BE: AuxStore>(backend: &BE) -> Result<(), Error> {
 	debug!(target: LOG_TARGET, "🥩 write aux schema version {:?}", CURRENT_VERSION);
 	AuxStore::insert_aux(backend, &[(VERSION_KEY, CURRENT_VERSION.encode().as_slice())], &[])
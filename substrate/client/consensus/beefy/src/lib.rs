@@ -37,6 +37,7 @@ use parking_lot::Mutex;
 use prometheus_endpoint::Registry;
 use sc_client_api::{Backend, BlockBackend, BlockchainEvents, FinalityNotification, Finalizer};
 use sc_consensus::BlockImport;
+use sc_consensus_slashing_protection::SlashingProtection;
 use sc_network::{NetworkRequest, NotificationService, ProtocolName};
 use sc_network_gossip::{GossipEngine, Network as GossipNetwork, Syncing as GossipSyncing};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver};
@@ -77,6 +78,7 @@ use crate::{
 	round::Rounds,
 	worker::{BeefyWorker, PersistedState},
 };
+pub use aux_schema::AUX_KEYS;
 pub use communication::beefy_protocol_name::{
 	gossip_protocol_name, justifications_protocol_name as justifs_protocol_name,
 };
@@ -233,6 +235,8 @@ pub struct BeefyParams<B: Block, BE, C, N, P, R, S, AuthorityId: AuthorityIdBoun
 	pub on_demand_justifications_handler: BeefyJustifsRequestHandler<B, C>,
 	/// Whether running under "Authority" role.
 	pub is_authority: bool,
+	/// Slashing protection checked before signing a vote.
+	pub slashing_protection: Option<SlashingProtection>,
 }
 /// Helper object holding BEEFY worker communication/gossip components.
 ///
@@ -315,6 +319,7 @@ where
 		links: BeefyVoterLinks<B, AuthorityId>,
 		pending_justifications: BTreeMap<NumberFor<B>, BeefyVersionedFinalityProof<B, AuthorityId>>,
 		is_authority: bool,
+		slashing_protection: Option<SlashingProtection>,
 	) -> BeefyWorker<B, BE, P, R, S, N, AuthorityId> {
 		let key_store = Arc::new(self.key_store);
 		BeefyWorker {
@@ -330,6 +335,7 @@ where
 			links,
 			pending_justifications,
 			is_authority,
+			slashing_protection,
 		}
 	}
 
@@ -542,6 +548,7 @@ pub async fn start_beefy_gadget<B, BE, C, N, P, R, S, AuthorityId>(
 		links,
 		mut on_demand_justifications_handler,
 		is_authority,
+		slashing_protection,
 	} = beefy_params;
 
 	let BeefyNetworkParams {
@@ -629,6 +636,7 @@ pub async fn start_beefy_gadget<B, BE, C, N, P, R, S, AuthorityId>(
 			links.clone(),
 			BTreeMap::new(),
 			is_authority,
+			slashing_protection.clone(),
 		);
 
 		futures::select! {
@@ -37,6 +37,7 @@ use codec::{Codec, Decode, DecodeAll, Encode};
 use futures::{stream::Fuse, FutureExt, StreamExt};
 use log::{debug, error, info, trace, warn};
 use sc_client_api::{Backend, HeaderBackend};
+use sc_consensus_slashing_protection::SlashingProtection;
 use sc_utils::notification::NotificationReceiver;
 use sp_api::ProvideRuntimeApi;
 use sp_arithmetic::traits::{AtLeast32Bit, Saturating};
@@ -405,6 +406,8 @@ pub(crate) struct BeefyWorker<B: Block, BE, P, RuntimeApi, S, N, AuthorityId: Au
 	pub metrics: Option<VoterMetrics>,
 	/// Node runs under "Authority" role.
 	pub is_authority: bool,
+	/// Slashing protection checked before signing a vote.
+	pub slashing_protection: Option<SlashingProtection>,
 }
 
 impl<B, BE, P, R, S, N, AuthorityId> BeefyWorker<B, BE, P, R, S, N, AuthorityId>
@@ -781,6 +784,18 @@ where
 		let commitment = Commitment { payload, block_number: target_number, validator_set_id };
 		let encoded_commitment = commitment.encode();
 
+		if let Some(slashing_protection) = &self.slashing_protection {
+			if let Err(err) = slashing_protection.check_beefy_vote(
+				&authority_id.encode(),
+				validator_set_id,
+				target_number.saturated_into(),
+				&encoded_commitment,
+			) {
+				warn!(target: LOG_TARGET, "🥩 Refusing to sign commitment: {}", err);
+				return Ok(());
+			}
+		}
+
 		let signature = match self.key_store.sign(&authority_id, &encoded_commitment) {
 			Ok(sig) => sig,
 			Err(err) => {
@@ -1161,6 +1176,7 @@ pub(crate) mod tests {
 			pending_justifications: BTreeMap::new(),
 			persisted_state,
 			is_authority: true,
+			slashing_protection: None,
 		}
 	}
 
@@ -18,12 +18,19 @@
 
 //! Block import helpers.
 
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use sp_runtime::{
 	traits::{Block as BlockT, HashingFor, Header as HeaderT, NumberFor},
 	DigestItem, Justification, Justifications,
 };
-use std::{any::Any, borrow::Cow, collections::HashMap, sync::Arc};
+use std::{
+	any::Any,
+	borrow::Cow,
+	collections::{HashMap, HashSet, VecDeque},
+	hash::Hash,
+	sync::Arc,
+};
 
 use sp_consensus::{BlockOrigin, Error};
 
@@ -394,3 +401,123 @@ impl<B: BlockT, L: JustificationSyncLink<B>> JustificationSyncLink<B> for Arc<L>
 		L::clear_justification_requests(self);
 	}
 }
+
+/// A policy vetoing the import of blocks, e.g. checking an external attestation of the blocks.
+///
+/// Closures taking the header and the body of the block and returning the reason of the veto, if
+/// any, implement this trait.
+#[async_trait::async_trait]
+pub trait ImportVeto<B: BlockT>: Send + Sync {
+	/// Check the block before its import, returns the reason of the veto if it must not be
+	/// imported.
+	///
+	/// The `body` is `None` if the block is imported without its body, e.g. by the light sync.
+	async fn check(&self, header: &B::Header, body: Option<&[B::Extrinsic]>) -> Result<(), String>;
+}
+
+#[async_trait::async_trait]
+impl<B, F> ImportVeto<B> for F
+where
+	B: BlockT,
+	F: Fn(&B::Header, Option<&[B::Extrinsic]>) -> Result<(), String> + Send + Sync,
+{
+	async fn check(&self, header: &B::Header, body: Option<&[B::Extrinsic]>) -> Result<(), String> {
+		self(header, body)
+	}
+}
+
+/// Maximum number of vetoed blocks remembered by a [`VetoBlockImport`].
+const MAX_VETOED_BLOCKS: usize = 1024;
+
+/// Block import wrapper consulting an [`ImportVeto`] before passing the blocks to the inner
+/// block import.
+///
+/// Vetoed blocks fail with [`Error::ImportVetoed`], which the sync doesn't hold against the peers
+/// having sent them, unlike a [`ImportResult::KnownBad`] block. The hashes of the vetoed blocks are
+/// remembered: the blocks received again, and their descendants, are rejected with the same error
+/// without consulting the veto.
+pub struct VetoBlockImport<B: BlockT, I> {
+	inner: I,
+	name: Cow<'static, str>,
+	veto: Arc<dyn ImportVeto<B>>,
+	vetoed: Mutex<VetoedBlocks<B::Hash>>,
+}
+
+/// The hashes of the last vetoed blocks, the oldest ones are forgotten first.
+struct VetoedBlocks<H> {
+	hashes: HashSet<H>,
+	order: VecDeque<H>,
+}
+
+impl<H: Hash + Eq + Clone> VetoedBlocks<H> {
+	fn contains(&self, hash: &H) -> bool {
+		self.hashes.contains(hash)
+	}
+
+	fn insert(&mut self, hash: H) {
+		if !self.hashes.insert(hash.clone()) {
+			return
+		}
+		self.order.push_back(hash);
+		if self.order.len() > MAX_VETOED_BLOCKS {
+			if let Some(oldest) = self.order.pop_front() {
+				self.hashes.remove(&oldest);
+			}
+		}
+	}
+}
+
+impl<B: BlockT, I> VetoBlockImport<B, I> {
+	/// Wrap `inner`, vetoing blocks with `veto`. The `name` of the veto is logged with the
+	/// rejected blocks.
+	pub fn new(inner: I, name: impl Into<Cow<'static, str>>, veto: Arc<dyn ImportVeto<B>>) -> Self {
+		let vetoed = VetoedBlocks { hashes: HashSet::new(), order: VecDeque::new() };
+		Self { inner, name: name.into(), veto, vetoed: Mutex::new(vetoed) }
+	}
+
+	/// Reject the block `hash` if it, or its parent, was vetoed before.
+	fn check_vetoed(&self, hash: B::Hash, parent_hash: &B::Hash) -> Result<(), Error> {
+		let mut vetoed = self.vetoed.lock();
+		if vetoed.contains(&hash) {
+			return Err(Error::ImportVetoed(format!("block {hash} vetoed by {}", self.name)))
+		}
+		if vetoed.contains(parent_hash) {
+			vetoed.insert(hash);
+			return Err(Error::ImportVetoed(format!(
+				"parent {parent_hash} of block {hash} vetoed by {}",
+				self.name
+			)))
+		}
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, I> BlockImport<B> for VetoBlockImport<B, I>
+where
+	B: BlockT,
+	I: BlockImport<B, Error = Error> + Send + Sync,
+{
+	type Error = Error;
+
+	async fn check_block(&self, block: BlockCheckParams<B>) -> Result<ImportResult, Self::Error> {
+		self.check_vetoed(block.hash, &block.parent_hash)?;
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(&self, block: BlockImportParams<B>) -> Result<ImportResult, Self::Error> {
+		let hash = block.post_hash();
+		self.check_vetoed(hash, block.header.parent_hash())?;
+		if let Err(reason) = self.veto.check(&block.header, block.body.as_deref()).await {
+			log::warn!(
+				target: "sync",
+				"⛔ Block #{} ({hash}) vetoed by {}: {reason}",
+				block.header.number(),
+				self.name,
+			);
+			self.vetoed.lock().insert(hash);
+			return Err(Error::ImportVetoed(reason))
+		}
+		self.inner.import_block(block).await
+	}
+}
@@ -47,7 +47,7 @@ use crate::{
 	metrics::Metrics,
 };
 
-pub use basic_queue::BasicQueue;
+pub use basic_queue::{BasicQueue, ImportQueueConfig};
 
 const LOG_TARGET: &str = "sync::import-queue";
 
@@ -209,6 +209,10 @@ pub enum BlockImportError {
 	#[error("block has an unknown parent")]
 	UnknownParent,
 
+	/// A local policy vetoed the import of the block, which isn't necessarily bad.
+	#[error("block import vetoed: {0}")]
+	Vetoed(String),
+
 	/// Block import has been cancelled. This can happen if the parent block fails to be imported.
 	#[error("import has been cancelled")]
 	Cancelled,
@@ -269,6 +273,10 @@ where
 			debug!(target: LOG_TARGET, "Peer gave us a bad block {}: {:?}", number, hash);
 			Err(BlockImportError::BadBlock(block_origin))
 		},
+		Err(ConsensusError::ImportVetoed(reason)) => {
+			debug!(target: LOG_TARGET, "Import of block {}: {:?} vetoed: {}", number, hash, reason);
+			Err(BlockImportError::Vetoed(reason))
+		},
 		Err(e) => {
 			debug!(target: LOG_TARGET, "Error importing block {}: {:?}: {}", number, hash, e);
 			Err(BlockImportError::Other(e))
@@ -294,14 +302,13 @@ pub(crate) struct SingleBlockImportParameters<Block: BlockT> {
 pub(crate) async fn verify_single_block_metered<B: BlockT, V: Verifier<B>>(
 	import_handle: &impl BlockImport<B, Error = ConsensusError>,
 	block_origin: BlockOrigin,
-	block: IncomingBlock<B>,
+	mut block: IncomingBlock<B>,
 	verifier: &V,
 	metrics: Option<&Metrics>,
 ) -> Result<SingleBlockVerificationOutcome<B>, BlockImportError> {
 	let peer = block.origin;
-	let justifications = block.justifications;
 
-	let Some(header) = block.header else {
+	let Some(header) = block.header.take() else {
 		if let Some(ref peer) = peer {
 			debug!(target: LOG_TARGET, "Header {} was not provided by {peer} ", block.hash);
 		} else {
@@ -341,22 +348,7 @@ pub(crate) async fn verify_single_block_metered<B: BlockT, V: Verifier<B>>(
 
 	let started = Instant::now();
 
-	let mut import_block = BlockImportParams::new(block_origin, header);
-	import_block.body = block.body;
-	import_block.justifications = justifications;
-	import_block.post_hash = Some(hash);
-	import_block.import_existing = block.import_existing;
-	import_block.indexed_body = block.indexed_body;
-
-	if let Some(state) = block.state {
-		let changes = crate::block_import::StorageChanges::Import(state);
-		import_block.state_action = StateAction::ApplyChanges(changes);
-	} else if block.skip_execution {
-		import_block.state_action = StateAction::Skip;
-	} else if block.allow_missing_state {
-		import_block.state_action = StateAction::ExecuteIfPossible;
-	}
-
+	let import_block = block_import_params(block_origin, header, block);
 	let import_block = verifier.verify(import_block).await.map_err(|msg| {
 		if let Some(ref peer) = peer {
 			trace!(
@@ -389,6 +381,31 @@ pub(crate) async fn verify_single_block_metered<B: BlockT, V: Verifier<B>>(
 	}))
 }
 
+/// The parameters handed to the verifier to import `block`, whose header is `header`.
+pub(crate) fn block_import_params<B: BlockT>(
+	block_origin: BlockOrigin,
+	header: B::Header,
+	block: IncomingBlock<B>,
+) -> BlockImportParams<B> {
+	let mut import_block = BlockImportParams::new(block_origin, header);
+	import_block.body = block.body;
+	import_block.justifications = block.justifications;
+	import_block.post_hash = Some(block.hash);
+	import_block.import_existing = block.import_existing;
+	import_block.indexed_body = block.indexed_body;
+
+	if let Some(state) = block.state {
+		let changes = crate::block_import::StorageChanges::Import(state);
+		import_block.state_action = StateAction::ApplyChanges(changes);
+	} else if block.skip_execution {
+		import_block.state_action = StateAction::Skip;
+	} else if block.allow_missing_state {
+		import_block.state_action = StateAction::ExecuteIfPossible;
+	}
+
+	import_block
+}
+
 pub(crate) async fn import_single_block_metered<Block: BlockT>(
 	import_handle: &mut impl BlockImport<Block, Error = ConsensusError>,
 	import_parameters: SingleBlockImportParameters<Block>,
@@ -16,21 +16,26 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 use futures::{
+	channel::oneshot,
 	prelude::*,
 	task::{Context, Poll},
 };
 use log::{debug, trace};
+use parking_lot::Mutex;
 use prometheus_endpoint::Registry;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_consensus::BlockOrigin;
+use sp_core::traits::SpawnEssentialNamed;
 use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT, NumberFor},
 	Justification, Justifications,
 };
-use std::pin::Pin;
+use std::{collections::VecDeque, pin::Pin, sync::Arc};
 
 use crate::{
+	block_import::BlockImportParams,
 	import_queue::{
+		block_import_params,
 		buffered_link::{self, BufferedLinkReceiver, BufferedLinkSender},
 		import_single_block_metered, verify_single_block_metered, BlockImportError,
 		BlockImportStatus, BoxBlockImport, BoxJustificationImport, ImportQueue, ImportQueueService,
@@ -39,6 +44,35 @@ use crate::{
 	metrics::Metrics,
 };
 
+/// The number of blocks verified ahead of their import per verification worker, by default.
+const BLOCKS_IN_FLIGHT_PER_WORKER: usize = 4;
+
+/// Configuration of the verification of blocks by the [`BasicQueue`].
+///
+/// With verification workers, the queue verifies the next blocks of a batch in parallel while
+/// importing them one after the other. Verifiers needing the parent block to be imported reject
+/// blocks verified ahead of their parent; these blocks are verified again before their import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ImportQueueConfig {
+	/// The number of workers verifying blocks ahead of their import.
+	///
+	/// With `0` workers, every block is verified right before its import, by the import task.
+	pub verification_workers: usize,
+	/// The maximum number of blocks verified ahead of their import.
+	pub max_blocks_in_flight: usize,
+}
+
+impl ImportQueueConfig {
+	/// Verification with the given number of workers, each verifying up to four blocks ahead of
+	/// their import.
+	pub fn with_workers(verification_workers: usize) -> Self {
+		Self {
+			verification_workers,
+			max_blocks_in_flight: verification_workers * BLOCKS_IN_FLIGHT_PER_WORKER,
+		}
+	}
+}
+
 /// Interface to a basic block import queue that is importing blocks sequentially in a separate
 /// task, with plugable verification.
 pub struct BasicQueue<B: BlockT> {
@@ -64,8 +98,33 @@ impl<B: BlockT> BasicQueue<B> {
 		verifier: V,
 		block_import: BoxBlockImport<B>,
 		justification_import: Option<BoxJustificationImport<B>>,
-		spawner: &impl sp_core::traits::SpawnEssentialNamed,
+		spawner: &impl SpawnEssentialNamed,
+		prometheus_registry: Option<&Registry>,
+	) -> Self
+	where
+		V: Verifier<B> + 'static,
+	{
+		Self::with_config(
+			verifier,
+			block_import,
+			justification_import,
+			spawner,
+			prometheus_registry,
+			ImportQueueConfig::default(),
+		)
+	}
+
+	/// Instantiate a new basic queue, with given verifier and verification configuration.
+	///
+	/// This creates a background task, and calls `on_start` on the justification importer. The
+	/// verification workers of the configuration are spawned as tasks of their own.
+	pub fn with_config<V>(
+		verifier: V,
+		block_import: BoxBlockImport<B>,
+		justification_import: Option<BoxJustificationImport<B>>,
+		spawner: &impl SpawnEssentialNamed,
 		prometheus_registry: Option<&Registry>,
+		config: ImportQueueConfig,
 	) -> Self
 	where
 		V: Verifier<B> + 'static,
@@ -80,9 +139,14 @@ impl<B: BlockT> BasicQueue<B> {
 				.ok()
 		});
 
+		let verifier = Arc::new(verifier);
+		let verification_pool = (config.verification_workers > 0)
+			.then(|| VerificationPool::spawn(verifier.clone(), config, spawner));
+
 		let (future, justification_sender, block_import_sender) = BlockImportWorker::new(
 			result_sender,
 			verifier,
+			verification_pool,
 			block_import,
 			justification_import,
 			metrics,
@@ -213,6 +277,89 @@ mod worker_messages {
 	);
 }
 
+/// The result of verifying a block.
+type VerificationResult<B> = Result<BlockImportParams<B>, String>;
+
+/// Workers verifying blocks ahead of their import.
+struct VerificationPool<B: BlockT> {
+	/// Channel to send the blocks to verify to the workers.
+	jobs: TracingUnboundedSender<(BlockImportParams<B>, oneshot::Sender<VerificationResult<B>>)>,
+	/// The maximum number of blocks verified ahead of their import.
+	max_blocks_in_flight: usize,
+}
+
+impl<B: BlockT> VerificationPool<B> {
+	/// Spawn the verification workers of `config`.
+	///
+	/// The workers stop once the pool is dropped.
+	fn spawn<V: Verifier<B> + 'static>(
+		verifier: Arc<V>,
+		config: ImportQueueConfig,
+		spawner: &impl SpawnEssentialNamed,
+	) -> Self {
+		let (jobs, receiver) = tracing_unbounded("mpsc_import_queue_verification", 100_000);
+		let receiver = Arc::new(futures::lock::Mutex::new(receiver));
+
+		for _ in 0..config.verification_workers {
+			let verifier = verifier.clone();
+			let receiver = receiver.clone();
+			let worker = async move {
+				loop {
+					let Some((block, result)) = receiver.lock().await.next().await else {
+						return
+					};
+					let _ = result.send(verifier.verify(block).await);
+				}
+			};
+			spawner.spawn_essential_blocking(
+				"block-verification-worker",
+				Some("block-import"),
+				worker.boxed(),
+			);
+		}
+
+		Self { jobs, max_blocks_in_flight: config.max_blocks_in_flight.max(1) }
+	}
+
+	/// Start verifying `block`, returning the receiver of the result.
+	///
+	/// Returns `None` if the block can't be verified ahead of its import.
+	fn verify(
+		&self,
+		origin: BlockOrigin,
+		block: &IncomingBlock<B>,
+	) -> Option<oneshot::Receiver<VerificationResult<B>>> {
+		let header = block.header.clone()?;
+		let (sender, receiver) = oneshot::channel();
+		self.jobs
+			.unbounded_send((block_import_params(origin, header, block.clone()), sender))
+			.ok()?;
+		Some(receiver)
+	}
+}
+
+/// Verifier returning the result of the verification by the [`VerificationPool`], if any.
+///
+/// Blocks the pool failed to verify are verified again by the inner verifier, as their parent
+/// may not have been imported when the pool verified them.
+struct AheadOfImport<'a, B: BlockT, V> {
+	verifier: &'a V,
+	verified: Mutex<Option<oneshot::Receiver<VerificationResult<B>>>>,
+}
+
+#[async_trait::async_trait]
+impl<B: BlockT, V: Verifier<B>> Verifier<B> for AheadOfImport<'_, B, V> {
+	async fn verify(&self, block: BlockImportParams<B>) -> VerificationResult<B> {
+		let verified = self.verified.lock().take();
+		if let Some(verified) = verified {
+			if let Ok(Ok(verified)) = verified.await {
+				return Ok(verified)
+			}
+		}
+		self.verifier.verify(block).await
+	}
+}
+
 /// The process of importing blocks.
 ///
 /// This polls the `block_import_receiver` for new blocks to import and than awaits on
@@ -222,7 +369,8 @@ mod worker_messages {
 /// Returns when `block_import` ended.
 async fn block_import_process<B: BlockT>(
 	mut block_import: BoxBlockImport<B>,
-	verifier: impl Verifier<B>,
+	verifier: Arc<impl Verifier<B>>,
+	verification_pool: Option<VerificationPool<B>>,
 	result_sender: BufferedLinkSender<B>,
 	mut block_import_receiver: TracingUnboundedReceiver<worker_messages::ImportBlocks<B>>,
 	metrics: Option<Metrics>,
@@ -240,8 +388,15 @@ async fn block_import_process<B: BlockT>(
 			},
 		};
 
-		let res =
-			import_many_blocks(&mut block_import, origin, blocks, &verifier, metrics.clone()).await;
+		let res = import_many_blocks(
+			&mut block_import,
+			origin,
+			blocks,
+			&*verifier,
+			verification_pool.as_ref(),
+			metrics.clone(),
+		)
+		.await;
 
 		result_sender.blocks_processed(res.imported, res.block_count, res.results);
 	}
@@ -256,7 +411,8 @@ struct BlockImportWorker<B: BlockT> {
 impl<B: BlockT> BlockImportWorker<B> {
 	fn new<V>(
 		result_sender: BufferedLinkSender<B>,
-		verifier: V,
+		verifier: Arc<V>,
+		verification_pool: Option<VerificationPool<B>>,
 		block_import: BoxBlockImport<B>,
 		justification_import: Option<BoxJustificationImport<B>>,
 		metrics: Option<Metrics>,
@@ -289,6 +445,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 			let block_import_process = block_import_process(
 				block_import,
 				verifier,
+				verification_pool,
 				worker.result_sender.clone(),
 				block_import_receiver,
 				worker.metrics.clone(),
@@ -381,6 +538,9 @@ struct ImportManyBlocksResult<B: BlockT> {
 
 /// Import several blocks at once, returning import result for each block.
 ///
+/// With a `verification_pool`, the next blocks are verified by the pool while importing the
+/// current one.
+///
 /// This will yield after each imported block once, to ensure that other futures can
 /// be called as well.
 async fn import_many_blocks<B: BlockT, V: Verifier<B>>(
@@ -388,6 +548,7 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>>(
 	blocks_origin: BlockOrigin,
 	blocks: Vec<IncomingBlock<B>>,
 	verifier: &V,
+	verification_pool: Option<&VerificationPool<B>>,
 	metrics: Option<Metrics>,
 ) -> ImportManyBlocksResult<B> {
 	let count = blocks.len();
@@ -407,11 +568,21 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>>(
 	let mut results = vec![];
 	let mut has_error = false;
 	let mut blocks = blocks.into_iter();
+	// The blocks being verified by the pool, in order.
+	let mut in_flight = VecDeque::new();
 
 	// Blocks in the response/drain should be in ascending order.
 	loop {
+		if let Some(pool) = verification_pool.filter(|_| !has_error) {
+			while in_flight.len() < pool.max_blocks_in_flight {
+				let Some(block) = blocks.next() else { break };
+				in_flight.push_back((pool.verify(blocks_origin, &block), block));
+			}
+		}
+
 		// Is there any block left to import?
-		let block = match blocks.next() {
+		let next = in_flight.pop_front().or_else(|| blocks.next().map(|block| (None, block)));
+		let (verified, block) = match next {
 			Some(b) => b,
 			None => {
 				// No block left to import, success!
@@ -424,11 +595,12 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>>(
 		let import_result = if has_error {
 			Err(BlockImportError::Cancelled)
 		} else {
+			let verifier = AheadOfImport { verifier, verified: Mutex::new(verified) };
 			let verification_fut = verify_single_block_metered(
 				import_handle,
 				blocks_origin,
 				block,
-				verifier,
+				&verifier,
 				metrics.as_ref(),
 			);
 			match verification_fut.await {
@@ -503,6 +675,7 @@ mod tests {
 	use futures::{executor::block_on, Future};
 	use parking_lot::Mutex;
 	use sp_test_primitives::{Block, BlockNumber, Hash, Header};
+	use std::collections::HashSet;
 
 	#[async_trait::async_trait]
 	impl Verifier<Block> for () {
@@ -590,7 +763,14 @@ mod tests {
 		let (result_sender, mut result_port) = buffered_link::buffered_link(100_000);
 
 		let (worker, finality_sender, block_import_sender) =
-			BlockImportWorker::new(result_sender, (), Box::new(()), Some(Box::new(())), None);
+			BlockImportWorker::new(
+				result_sender,
+				Arc::new(()),
+				None,
+				Box::new(()),
+				Some(Box::new(())),
+				None,
+			);
 		futures::pin_mut!(worker);
 
 		let import_block = |n| {
@@ -682,4 +862,97 @@ mod tests {
 			]
 		);
 	}
+
+	/// Block import of a chain, whose verifier rejects the blocks whose parent isn't imported.
+	#[derive(Clone, Default)]
+	struct ChainImport {
+		imported: Arc<Mutex<HashSet<Hash>>>,
+	}
+
+	#[async_trait::async_trait]
+	impl Verifier<Block> for ChainImport {
+		async fn verify(
+			&self,
+			block: BlockImportParams<Block>,
+		) -> Result<BlockImportParams<Block>, String> {
+			if block.header.number > 1 &&
+				!self.imported.lock().contains(&block.header.parent_hash)
+			{
+				return Err("Parent not imported".into())
+			}
+			Ok(BlockImportParams::new(block.origin, block.header))
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for ChainImport {
+		type Error = sp_consensus::Error;
+
+		async fn check_block(
+			&self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		async fn import_block(
+			&self,
+			block: BlockImportParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			self.imported.lock().insert(block.header.hash());
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	#[test]
+	fn verifies_blocks_ahead_of_import() {
+		let chain = ChainImport::default();
+		let pool = VerificationPool::spawn(
+			Arc::new(chain.clone()),
+			ImportQueueConfig::with_workers(2),
+			&sp_core::testing::TaskExecutor::new(),
+		);
+
+		let mut parent_hash = Hash::random();
+		let blocks = (1..=6)
+			.map(|number| {
+				let header = Header {
+					parent_hash,
+					number,
+					extrinsics_root: Hash::random(),
+					state_root: Default::default(),
+					digest: Default::default(),
+				};
+				parent_hash = header.hash();
+				IncomingBlock {
+					hash: header.hash(),
+					header: Some(header),
+					body: None,
+					indexed_body: None,
+					justifications: None,
+					origin: None,
+					allow_missing_state: false,
+					import_existing: false,
+					state: None,
+					skip_execution: false,
+				}
+			})
+			.collect::<Vec<_>>();
+		let hashes = blocks.iter().map(|block| block.hash).collect::<Vec<_>>();
+
+		let mut block_import: BoxBlockImport<Block> = Box::new(chain.clone());
+		let result = block_on(import_many_blocks(
+			&mut block_import,
+			BlockOrigin::NetworkInitialSync,
+			blocks,
+			&chain,
+			Some(&pool),
+			None,
+		));
+
+		// Blocks verified before their parent was imported are verified again.
+		assert_eq!(result.imported, 6);
+		assert!(result.results.iter().all(|(result, _)| result.is_ok()));
+		assert_eq!(result.results.iter().map(|(_, hash)| *hash).collect::<Vec<_>>(), hashes);
+	}
 }
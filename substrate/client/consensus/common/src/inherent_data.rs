@@ -0,0 +1,275 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A registry of inherent data providers, each registered for a range of runtime spec versions.
+//!
+//! A runtime upgrade may add or remove inherents. With an [`InherentDataProviderRegistry`], a node
+//! registers the providers needed by every runtime it supports, and only the providers matching
+//! the spec version of the runtime at the parent block are used when building or checking a block.
+//! Providers can be registered while the node is running, so the node can follow an upgrade
+//! without a lockstep release.
+//!
+//! The providers every runtime needs, like the slot and timestamp providers, are the base of the
+//! registry. They are available through [`RegisteredProviders::base`], so the consensus engines
+//! can use the registry.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use sp_api::CallApiAt;
+use sp_inherents::{
+	CreateInherentDataProviders, Error, InherentData, InherentDataProvider, InherentIdentifier,
+};
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	ops::{Bound, RangeBounds},
+	sync::Arc,
+};
+
+/// Creates the inherent data providers of a registration.
+#[async_trait]
+trait CreateBoxed<Block: BlockT, ExtraArgs>: Send + Sync {
+	async fn create(
+		&self,
+		parent: Block::Hash,
+		extra_args: ExtraArgs,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<Block, ExtraArgs, C> CreateBoxed<Block, ExtraArgs> for C
+where
+	Block: BlockT,
+	ExtraArgs: Send + 'static,
+	C: CreateInherentDataProviders<Block, ExtraArgs>,
+	C::InherentDataProviders: 'static,
+{
+	async fn create(
+		&self,
+		parent: Block::Hash,
+		extra_args: ExtraArgs,
+	) -> Result<Box<dyn InherentDataProvider>, Box<dyn std::error::Error + Send + Sync>> {
+		let providers = self.create_inherent_data_providers(parent, extra_args).await?;
+		Ok(Box::new(providers))
+	}
+}
+
+/// Inherent data providers registered for a range of spec versions.
+struct Registration<Block, ExtraArgs> {
+	spec_versions: (Bound<u32>, Bound<u32>),
+	create: Arc<dyn CreateBoxed<Block, ExtraArgs>>,
+}
+
+/// A registry of inherent data providers, keyed by ranges of runtime spec versions.
+///
+/// The registry is a [`CreateInherentDataProviders`] creating the `Base` providers and the
+/// providers registered for the spec version of the runtime at the parent block. Clones share the
+/// same registrations.
+pub struct InherentDataProviderRegistry<Block, Client, Base, ExtraArgs> {
+	client: Arc<Client>,
+	base: Arc<Base>,
+	registrations: Arc<RwLock<Vec<Registration<Block, ExtraArgs>>>>,
+}
+
+impl<Block, Client, Base, ExtraArgs> Clone
+	for InherentDataProviderRegistry<Block, Client, Base, ExtraArgs>
+{
+	fn clone(&self) -> Self {
+		Self {
+			client: self.client.clone(),
+			base: self.base.clone(),
+			registrations: self.registrations.clone(),
+		}
+	}
+}
+
+impl<Block, Client, Base, ExtraArgs> InherentDataProviderRegistry<Block, Client, Base, ExtraArgs>
+where
+	Block: BlockT,
+	ExtraArgs: Send + 'static,
+{
+	/// Create a registry using the `base` providers for every runtime, looking up the runtime
+	/// versions with `client`.
+	pub fn new(client: Arc<Client>, base: Base) -> Self {
+		Self { client, base: Arc::new(base), registrations: Default::default() }
+	}
+
+	/// Register inherent data providers for the runtimes with a spec version in `spec_versions`.
+	///
+	/// The providers of all registrations matching a runtime are used together, so ranges may
+	/// overlap if the providers don't.
+	pub fn register<C>(&self, spec_versions: impl RangeBounds<u32>, create: C)
+	where
+		C: CreateInherentDataProviders<Block, ExtraArgs> + 'static,
+		C::InherentDataProviders: 'static,
+	{
+		let spec_versions =
+			(spec_versions.start_bound().cloned(), spec_versions.end_bound().cloned());
+		self.registrations
+			.write()
+			.push(Registration { spec_versions, create: Arc::new(create) });
+	}
+
+	/// The providers registered for `spec_version`.
+	fn registered(&self, spec_version: u32) -> Vec<Arc<dyn CreateBoxed<Block, ExtraArgs>>> {
+		self.registrations
+			.read()
+			.iter()
+			.filter(|registration| registration.spec_versions.contains(&spec_version))
+			.map(|registration| registration.create.clone())
+			.collect()
+	}
+}
+
+#[async_trait]
+impl<Block, Client, Base, ExtraArgs> CreateInherentDataProviders<Block, ExtraArgs>
+	for InherentDataProviderRegistry<Block, Client, Base, ExtraArgs>
+where
+	Block: BlockT,
+	Client: CallApiAt<Block> + Send + Sync,
+	Base: CreateInherentDataProviders<Block, ExtraArgs>,
+	ExtraArgs: Clone + Send + 'static,
+{
+	type InherentDataProviders = RegisteredProviders<Base::InherentDataProviders>;
+
+	async fn create_inherent_data_providers(
+		&self,
+		parent: Block::Hash,
+		extra_args: ExtraArgs,
+	) -> Result<Self::InherentDataProviders, Box<dyn std::error::Error + Send + Sync>> {
+		let spec_version = self.client.runtime_version_at(parent)?.spec_version;
+		let base = self.base.create_inherent_data_providers(parent, extra_args.clone()).await?;
+		let mut registered = Vec::new();
+		for create in self.registered(spec_version) {
+			registered.push(create.create(parent, extra_args.clone()).await?);
+		}
+		Ok(RegisteredProviders { base, registered })
+	}
+}
+
+/// The base inherent data providers and the providers registered for a runtime.
+pub struct RegisteredProviders<Base> {
+	base: Base,
+	registered: Vec<Box<dyn InherentDataProvider>>,
+}
+
+impl<Base> RegisteredProviders<Base> {
+	/// The base providers, used for every runtime.
+	pub fn base(&self) -> &Base {
+		&self.base
+	}
+}
+
+#[async_trait]
+impl<Base: InherentDataProvider> InherentDataProvider for RegisteredProviders<Base> {
+	async fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
+		self.base.provide_inherent_data(inherent_data).await?;
+		for provider in &self.registered {
+			provider.provide_inherent_data(inherent_data).await?;
+		}
+		Ok(())
+	}
+
+	async fn try_handle_error(
+		&self,
+		identifier: &InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), Error>> {
+		if let Some(result) = self.base.try_handle_error(identifier, error).await {
+			return Some(result)
+		}
+		for provider in &self.registered {
+			if let Some(result) = provider.try_handle_error(identifier, error).await {
+				return Some(result)
+			}
+		}
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_test_primitives::Block;
+
+	struct Provider(InherentIdentifier);
+
+	#[async_trait]
+	impl InherentDataProvider for Provider {
+		async fn provide_inherent_data(
+			&self,
+			inherent_data: &mut InherentData,
+		) -> Result<(), Error> {
+			inherent_data.put_data(self.0, &())
+		}
+
+		async fn try_handle_error(
+			&self,
+			_: &InherentIdentifier,
+			_: &[u8],
+		) -> Option<Result<(), Error>> {
+			None
+		}
+	}
+
+	fn provider(
+		identifier: InherentIdentifier,
+	) -> impl CreateInherentDataProviders<Block, (), InherentDataProviders = Provider> {
+		move |_: <Block as BlockT>::Hash, ()| async move {
+			Ok::<_, Box<dyn std::error::Error + Send + Sync>>(Provider(identifier))
+		}
+	}
+
+	fn base() -> impl CreateInherentDataProviders<Block, (), InherentDataProviders = ()> {
+		|_: <Block as BlockT>::Hash, ()| async {
+			Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+		}
+	}
+
+	const BASE: InherentIdentifier = *b"base____";
+	const REMOVED: InherentIdentifier = *b"removed_";
+	const ALWAYS: InherentIdentifier = *b"always__";
+	const ADDED: InherentIdentifier = *b"added___";
+
+	#[test]
+	fn providers_are_selected_by_spec_version() {
+		let registry = InherentDataProviderRegistry::<Block, (), _, ()>::new(Arc::new(()), base());
+		registry.register(..2, provider(REMOVED));
+		registry.register(1.., provider(ALWAYS));
+		registry.register(2..=3, provider(ADDED));
+
+		let identifiers = |spec_version| {
+			let providers = registry
+				.registered(spec_version)
+				.into_iter()
+				.map(|create| futures::executor::block_on(create.create(Default::default(), ())))
+				.collect::<Result<Vec<_>, _>>()
+				.unwrap();
+			let providers = RegisteredProviders { base: Provider(BASE), registered: providers };
+			let data = futures::executor::block_on(providers.create_inherent_data()).unwrap();
+			[BASE, REMOVED, ALWAYS, ADDED]
+				.into_iter()
+				.filter(|identifier| data.get_data::<()>(identifier).unwrap().is_some())
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(identifiers(0), vec![BASE, REMOVED]);
+		assert_eq!(identifiers(1), vec![BASE, REMOVED, ALWAYS]);
+		assert_eq!(identifiers(3), vec![BASE, ALWAYS, ADDED]);
+		assert_eq!(identifiers(4), vec![BASE, ALWAYS]);
+	}
+}
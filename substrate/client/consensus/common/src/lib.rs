@@ -20,6 +20,7 @@
 
 pub mod block_import;
 pub mod import_queue;
+pub mod inherent_data;
 pub mod metrics;
 
 pub use block_import::{
@@ -29,8 +30,10 @@ pub use block_import::{
 };
 pub use import_queue::{
 	import_single_block, BasicQueue, BlockImportError, BlockImportStatus, BoxBlockImport,
-	BoxJustificationImport, DefaultImportQueue, ImportQueue, IncomingBlock, Link, Verifier,
+	BoxJustificationImport, DefaultImportQueue, ImportQueue, ImportQueueConfig, IncomingBlock, Link,
+	Verifier,
 };
+pub use inherent_data::{InherentDataProviderRegistry, RegisteredProviders};
 
 mod longest_chain;
 
@@ -87,6 +87,7 @@ impl Metrics {
 			Err(BlockImportError::BadBlock(_)) => "bad_block",
 			Err(BlockImportError::MissingState) => "missing_state",
 			Err(BlockImportError::UnknownParent) => "unknown_parent",
+			Err(BlockImportError::Vetoed(_)) => "vetoed",
 			Err(BlockImportError::Cancelled) => "cancelled",
 			Err(BlockImportError::Other(_)) => "failed",
 		};
@@ -33,6 +33,9 @@ pub enum Error {
 	/// GRANDPA prove finality failed.
 	#[error("GRANDPA prove finality rpc failed: {0}")]
 	ProveFinalityFailed(#[from] sc_consensus_grandpa::FinalityProofError),
+	/// GRANDPA justification retrieval or verification failed.
+	#[error("GRANDPA justification rpc failed: {0}")]
+	JustificationFailed(sc_consensus_grandpa::FinalityProofError),
 }
 
 /// The error codes returned by jsonrpc.
@@ -45,6 +48,8 @@ pub enum ErrorCode {
 	VoterStateTooLarge,
 	/// Failed to prove finality.
 	ProveFinality,
+	/// Failed to retrieve or verify a justification.
+	Justification,
 }
 
 impl From<Error> for ErrorCode {
@@ -54,6 +59,7 @@ impl From<Error> for ErrorCode {
 			Error::AuthoritySetIdReportedAsUnreasonablyLarge => ErrorCode::AuthoritySetTooLarge,
 			Error::VoterStateReportsUnreasonablyLargeNumbers => ErrorCode::VoterStateTooLarge,
 			Error::ProveFinalityFailed(_) => ErrorCode::ProveFinality,
+			Error::JustificationFailed(_) => ErrorCode::Justification,
 		}
 	}
 }
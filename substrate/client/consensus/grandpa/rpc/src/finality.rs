@@ -32,6 +32,20 @@ pub trait RpcFinalityProofProvider<Block: BlockT> {
 		&self,
 		block: NumberFor<Block>,
 	) -> Result<Option<EncodedFinalityProof>, sc_consensus_grandpa::FinalityProofError>;
+
+	/// The encoded GRANDPA justification stored for the given block, if any.
+	fn rpc_justification(
+		&self,
+		block: Block::Hash,
+	) -> Result<Option<sp_core::Bytes>, sc_consensus_grandpa::FinalityProofError>;
+
+	/// Verify that the encoded GRANDPA justification finalizes the given block, returning the id
+	/// of the authority set that signed it.
+	fn rpc_verify_justification(
+		&self,
+		block: Block::Hash,
+		justification: sp_core::Bytes,
+	) -> Result<u64, sc_consensus_grandpa::FinalityProofError>;
 }
 
 impl<B, Block> RpcFinalityProofProvider<Block> for FinalityProofProvider<B, Block>
@@ -46,4 +60,19 @@ where
 	) -> Result<Option<EncodedFinalityProof>, sc_consensus_grandpa::FinalityProofError> {
 		self.prove_finality(block).map(|x| x.map(|y| EncodedFinalityProof(y.into())))
 	}
+
+	fn rpc_justification(
+		&self,
+		block: Block::Hash,
+	) -> Result<Option<sp_core::Bytes>, sc_consensus_grandpa::FinalityProofError> {
+		self.justification(block).map(|x| x.map(Into::into))
+	}
+
+	fn rpc_verify_justification(
+		&self,
+		block: Block::Hash,
+		justification: sp_core::Bytes,
+	) -> Result<u64, sc_consensus_grandpa::FinalityProofError> {
+		self.verify_justification(block, &justification)
+	}
 }
@@ -42,6 +42,7 @@ use sc_rpc::{
 	utils::{BoundedVecDeque, PendingSubscription},
 	SubscriptionTaskExecutor,
 };
+use sp_core::Bytes;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
 /// Provides RPC methods for interacting with GRANDPA.
@@ -65,6 +66,15 @@ pub trait GrandpaApi<Notification, Hash, Number> {
 	/// in the set and all the intermediary headers to link them together.
 	#[method(name = "grandpa_proveFinality")]
 	async fn prove_finality(&self, block: Number) -> Result<Option<EncodedFinalityProof>, Error>;
+
+	/// Returns the encoded GRANDPA justification stored for the given block, if any.
+	#[method(name = "grandpa_justification")]
+	async fn justification(&self, block: Hash) -> Result<Option<Bytes>, Error>;
+
+	/// Verify that the given encoded justification finalizes the given block. Returns the id of
+	/// the authority set that signed it. Only blocks of the current authority set are supported.
+	#[method(name = "grandpa_verifyJustification")]
+	async fn verify_justification(&self, block: Hash, justification: Bytes) -> Result<u64, Error>;
 }
 
 /// Provides RPC methods for interacting with GRANDPA.
@@ -126,6 +136,22 @@ where
 			error::Error::ProveFinalityFailed(e)
 		})
 	}
+
+	async fn justification(&self, block: Block::Hash) -> Result<Option<Bytes>, Error> {
+		self.finality_proof_provider
+			.rpc_justification(block)
+			.map_err(error::Error::JustificationFailed)
+	}
+
+	async fn verify_justification(
+		&self,
+		block: Block::Hash,
+		justification: Bytes,
+	) -> Result<u64, Error> {
+		self.finality_proof_provider
+			.rpc_verify_justification(block, justification)
+			.map_err(error::Error::JustificationFailed)
+	}
 }
 
 #[cfg(test)]
@@ -203,6 +229,21 @@ mod tests {
 					.into(),
 			)))
 		}
+
+		fn rpc_justification(
+			&self,
+			_block: Block::Hash,
+		) -> Result<Option<Bytes>, sc_consensus_grandpa::FinalityProofError> {
+			Ok(self.finality_proof.as_ref().map(|proof| proof.justification.clone().into()))
+		}
+
+		fn rpc_verify_justification(
+			&self,
+			_block: Block::Hash,
+			_justification: Bytes,
+		) -> Result<u64, sc_consensus_grandpa::FinalityProofError> {
+			Err(sc_consensus_grandpa::FinalityProofError::BlockNotInAuthoritySetChanges)
+		}
 	}
 
 	impl ReportVoterState for TestVoterState {
@@ -412,4 +453,27 @@ mod tests {
 		let finality_proof_rpc: FinalityProof<Header> = Decode::decode(&mut &bytes[..]).unwrap();
 		assert_eq!(finality_proof_rpc, finality_proof);
 	}
+
+	#[tokio::test]
+	async fn justification_with_test_finality_proof_provider() {
+		let justification = create_justification().encode();
+		let finality_proof = FinalityProof {
+			block: header(42).hash(),
+			justification: justification.clone(),
+			unknown_headers: Vec::new(),
+		};
+		let (rpc, _) = setup_io_handler_with_finality_proofs(TestVoterState, Some(finality_proof));
+
+		let bytes: Option<Bytes> =
+			rpc.call("grandpa_justification", [header(42).hash()]).await.unwrap();
+		assert_eq!(bytes, Some(justification.clone().into()));
+
+		let verified = rpc
+			.call::<_, u64>(
+				"grandpa_verifyJustification",
+				(header(42).hash(), Bytes::from(justification)),
+			)
+			.await;
+		assert!(verified.is_err());
+	}
 }
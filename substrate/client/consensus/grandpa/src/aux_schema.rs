@@ -49,6 +49,11 @@ const BEST_JUSTIFICATION: &[u8] = b"grandpa_best_justification";
 
 const CURRENT_VERSION: u32 = 3;
 
+/// The aux storage keys of the authority set and voter set state, exported and imported as a
+/// whole for disaster recovery.
+pub const AUX_KEYS: &[&[u8]] =
+	&[VERSION_KEY, SET_STATE_KEY, CONCLUDED_ROUNDS, AUTHORITY_SET_KEY, BEST_JUSTIFICATION];
+
 /// The voter set state.
 #[derive(Debug, Clone, Encode, Decode)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -1674,13 +1674,14 @@ mod tests {
 	fn config() -> crate::Config {
 		crate::Config {
 			gossip_duration: Duration::from_millis(10),
-			justification_generation_period: 256,
+			justification_policy: crate::JustificationPolicy::Interval(256),
 			keystore: None,
 			name: None,
 			local_role: Role::Authority,
 			observer_enabled: true,
 			telemetry: None,
 			protocol_name: communication::grandpa_protocol_name::NAME.into(),
+			slashing_protection: None,
 		}
 	}
 
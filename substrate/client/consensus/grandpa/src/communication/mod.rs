@@ -46,9 +46,11 @@ use finality_grandpa::{
 	voter_set::VoterSet,
 	Message::{Precommit, Prevote, PrimaryPropose},
 };
+use sc_consensus_slashing_protection::{GrandpaVote, SlashingProtection};
 use sc_network::{NetworkBlock, NetworkSyncForkRequest, NotificationService, ReputationChange};
 use sc_network_gossip::{GossipEngine, Network as GossipNetwork};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO};
+use sp_core::crypto::ByteArray;
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, Hash as HashT, Header as HeaderT, NumberFor};
 
@@ -334,6 +336,7 @@ impl<B: BlockT, N: Network<B>, S: Syncing<B>> NetworkBridge<B, N, S> {
 	pub(crate) fn round_communication(
 		&self,
 		keystore: Option<LocalIdKeystore>,
+		slashing_protection: Option<SlashingProtection>,
 		round: Round,
 		set_id: SetId,
 		voters: Arc<VoterSet<AuthorityId>>,
@@ -421,6 +424,7 @@ impl<B: BlockT, N: Network<B>, S: Syncing<B>> NetworkBridge<B, N, S> {
 		let (tx, out_rx) = mpsc::channel(0);
 		let outgoing = OutgoingMessages::<B> {
 			keystore,
+			slashing_protection,
 			round: round.0,
 			set_id: set_id.0,
 			network: self.gossip_engine.clone(),
@@ -712,6 +716,7 @@ pub(crate) struct OutgoingMessages<Block: BlockT> {
 	round: RoundNumber,
 	set_id: SetIdNumber,
 	keystore: Option<LocalIdKeystore>,
+	slashing_protection: Option<SlashingProtection>,
 	sender: mpsc::Sender<SignedMessage<Block::Header>>,
 	network: Arc<Mutex<GossipEngine<Block>>>,
 	has_voted: HasVoted<Block::Header>,
@@ -757,6 +762,23 @@ impl<Block: BlockT> Sink<Message<Block::Header>> for OutgoingMessages<Block> {
 		// when locals exist, sign messages on import
 		if let Some(ref keystore) = self.keystore {
 			let target_hash = *(msg.target().0);
+			if let Some(ref slashing_protection) = self.slashing_protection {
+				let vote = match msg {
+					PrimaryPropose(_) => GrandpaVote::PrimaryPropose,
+					Prevote(_) => GrandpaVote::Prevote,
+					Precommit(_) => GrandpaVote::Precommit,
+				};
+				slashing_protection
+					.check_grandpa_vote(
+						keystore.local_id().as_slice(),
+						vote,
+						self.set_id,
+						self.round,
+						&msg.encode(),
+					)
+					.map_err(|e| Error::Signing(e.to_string()))?;
+			}
+
 			let signed = sp_consensus_grandpa::sign_message(
 				keystore.keystore(),
 				msg,
@@ -297,13 +297,14 @@ impl Tester {
 fn config() -> crate::Config {
 	crate::Config {
 		gossip_duration: std::time::Duration::from_millis(10),
-		justification_generation_period: 256,
+		justification_policy: crate::JustificationPolicy::Interval(256),
 		keystore: None,
 		name: None,
 		local_role: Role::Authority,
 		observer_enabled: true,
 		telemetry: None,
 		protocol_name: grandpa_protocol_name::NAME.into(),
+		slashing_protection: None,
 	}
 }
 
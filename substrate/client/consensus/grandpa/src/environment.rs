@@ -53,6 +53,7 @@ use crate::{
 	authorities::{AuthoritySet, SharedAuthoritySet},
 	communication::{Network as NetworkT, Syncing as SyncingT},
 	justification::GrandpaJustification,
+	justification_policy::JustificationPolicy,
 	local_authority_id,
 	notification::GrandpaJustificationSender,
 	until_imported::UntilVoteTargetImported,
@@ -759,6 +760,7 @@ where
 
 		let (incoming, outgoing) = self.network.round_communication(
 			keystore,
+			self.config.slashing_protection.clone(),
 			crate::communication::Round(round),
 			crate::communication::SetId(self.set_id),
 			self.voters.clone(),
@@ -1099,7 +1101,7 @@ where
 		finalize_block(
 			self.client.clone(),
 			&self.authority_set,
-			Some(self.config.justification_generation_period),
+			Some(self.config.justification_policy),
 			hash,
 			number,
 			(round, commit).into(),
@@ -1324,10 +1326,10 @@ where
 /// importing a block), or whether to generate a justification from a
 /// commit (when validating). Justifications for blocks that change the
 /// authority set will always be processed, otherwise we'll only process
-/// justifications if the last one was `justification_period` blocks ago.
+/// justifications of the blocks selected by the `policy`.
 pub(crate) fn should_process_justification<BE, Block, Client>(
 	client: &Client,
-	justification_period: u32,
+	policy: JustificationPolicy,
 	number: NumberFor<Block>,
 	enacts_change: bool,
 ) -> bool
@@ -1340,14 +1342,7 @@ where
 		return true
 	}
 
-	let last_finalized_number = client.info().finalized_number;
-
-	// keep the first justification before reaching the justification period
-	if last_finalized_number.is_zero() {
-		return true
-	}
-
-	last_finalized_number / justification_period.into() != number / justification_period.into()
+	policy.selects_finalized::<Block>(client.info().finalized_number, number)
 }
 
 /// Finalize the given block and apply any authority set changes. If an
@@ -1357,7 +1352,7 @@ where
 pub(crate) fn finalize_block<BE, Block, Client>(
 	client: Arc<Client>,
 	authority_set: &SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
-	justification_generation_period: Option<u32>,
+	justification_policy: Option<JustificationPolicy>,
 	hash: Block::Hash,
 	number: NumberFor<Block>,
 	justification_or_commit: JustificationOrCommit<Block>,
@@ -1430,9 +1425,9 @@ where
 			JustificationOrCommit::Commit((round_number, commit)) => {
 				let enacts_change = status.new_set_block.is_some();
 
-				let justification_required = justification_generation_period
-					.map(|period| {
-						should_process_justification(&*client, period, number, enacts_change)
+				let justification_required = justification_policy
+					.map(|policy| {
+						should_process_justification(&*client, policy, number, enacts_change)
 					})
 					.unwrap_or(enacts_change);
 
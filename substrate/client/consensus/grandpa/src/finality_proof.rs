@@ -52,7 +52,7 @@ use crate::{
 	authorities::{AuthoritySetChangeId, AuthoritySetChanges},
 	best_justification,
 	justification::GrandpaJustification,
-	SharedAuthoritySet, LOG_TARGET,
+	BlockNumberOps, SetId, SharedAuthoritySet, LOG_TARGET,
 };
 
 const MAX_UNKNOWN_HEADERS: usize = 100_000;
@@ -130,6 +130,57 @@ where
 
 		prove_finality(&*self.backend, authority_set_changes, block, collect_unknown_headers)
 	}
+
+	/// The encoded GRANDPA justification stored for the given block, if any.
+	pub fn justification(&self, block: Block::Hash) -> Result<Option<Vec<u8>>, FinalityProofError> {
+		Ok(self
+			.backend
+			.blockchain()
+			.justifications(block)?
+			.and_then(|justifications| justifications.into_justification(GRANDPA_ENGINE_ID)))
+	}
+
+	/// Verify that the encoded GRANDPA justification finalizes the given block, returning the id
+	/// of the authority set that signed it.
+	///
+	/// Only justifications of blocks in the current authority set can be verified, since the
+	/// authorities of previous sets are not kept.
+	pub fn verify_justification(
+		&self,
+		block: Block::Hash,
+		encoded: &[u8],
+	) -> Result<SetId, FinalityProofError>
+	where
+		NumberFor<Block>: BlockNumberOps,
+	{
+		let shared_authority_set = self
+			.shared_authority_set
+			.as_ref()
+			.ok_or(FinalityProofError::BlockNotInAuthoritySetChanges)?;
+		let number =
+			self.backend.blockchain().expect_block_number_from_id(&BlockId::Hash(block))?;
+
+		let current_set_id = shared_authority_set.set_id();
+		let set_id = match shared_authority_set.authority_set_changes().get_set_id(number) {
+			AuthoritySetChangeId::Latest => current_set_id,
+			// Without any change, all blocks are in the genesis set.
+			AuthoritySetChangeId::Unknown if current_set_id == 0 => current_set_id,
+			AuthoritySetChangeId::Set(set_id, _) =>
+				return Err(FinalityProofError::UnknownAuthorities(set_id)),
+			AuthoritySetChangeId::Unknown =>
+				return Err(FinalityProofError::BlockNotInAuthoritySetChanges),
+		};
+
+		GrandpaJustification::<Block>::decode_and_verify_finalizes(
+			encoded,
+			(block, number),
+			set_id,
+			&shared_authority_set.current_authorities(),
+		)
+		.map_err(|e| FinalityProofError::InvalidJustification(e.to_string()))?;
+
+		Ok(set_id)
+	}
 }
 
 /// Finality for block B is proved by providing:
@@ -155,6 +206,12 @@ pub enum FinalityProofError {
 	/// in the latest authority set, and the subscription API is more appropriate.
 	#[error("Block not covered by authority set changes")]
 	BlockNotInAuthoritySetChanges,
+	/// The authorities of the set that finalized the requested block are not known.
+	#[error("Authorities of set {0} are not known")]
+	UnknownAuthorities(SetId),
+	/// The justification does not finalize the requested block.
+	#[error("Invalid justification: {0}")]
+	InvalidJustification(String),
 	/// Errors originating from the client.
 	#[error(transparent)]
 	Client(#[from] sp_blockchain::Error),
@@ -555,6 +612,33 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn verify_justification_works_in_current_set() {
+		let (client, backend, blocks) = test_blockchain(8, &[4, 5]);
+		let alice = Ed25519Keyring::Alice;
+		let block8 = &blocks[7];
+
+		let commit = create_commit(block8.clone(), 8, 0, &[alice]);
+		let grandpa_just8 = GrandpaJustification::from_commit(&client, 8, commit).unwrap();
+		client
+			.finalize_block(block8.hash(), Some((ID, grandpa_just8.encode())))
+			.unwrap();
+
+		let authority_set = crate::AuthoritySet::genesis(vec![(alice.public().into(), 1)]).unwrap();
+		let provider = FinalityProofProvider::new(backend, Some(authority_set.into()));
+
+		let justification = provider.justification(block8.hash()).unwrap().unwrap();
+		assert_eq!(justification, grandpa_just8.encode());
+		assert_eq!(provider.verify_justification(block8.hash(), &justification).unwrap(), 0);
+
+		// The justification doesn't finalize block 7.
+		assert!(matches!(
+			provider.verify_justification(blocks[6].hash(), &justification),
+			Err(FinalityProofError::InvalidJustification(_)),
+		));
+		assert_eq!(provider.justification(blocks[6].hash()).unwrap(), None);
+	}
+
 	#[test]
 	fn finality_proof_in_last_set_fails_without_latest() {
 		let (_, backend, _) = test_blockchain(8, &[4, 5, 8]);
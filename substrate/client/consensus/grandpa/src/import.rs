@@ -44,8 +44,8 @@ use crate::{
 	environment,
 	justification::GrandpaJustification,
 	notification::GrandpaJustificationSender,
-	AuthoritySetChanges, ClientForGrandpa, CommandOrError, Error, NewAuthoritySet, VoterCommand,
-	LOG_TARGET,
+	AuthoritySetChanges, ClientForGrandpa, CommandOrError, Error, JustificationPolicy,
+	NewAuthoritySet, VoterCommand, LOG_TARGET,
 };
 
 /// A block-import handler for GRANDPA.
@@ -60,6 +60,7 @@ use crate::{
 pub struct GrandpaBlockImport<Backend, Block: BlockT, Client, SC> {
 	inner: Arc<Client>,
 	justification_import_period: u32,
+	justification_request_policy: JustificationPolicy,
 	select_chain: SC,
 	authority_set: SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
 	send_voter_commands: TracingUnboundedSender<VoterCommand<Block::Hash, NumberFor<Block>>>,
@@ -77,6 +78,7 @@ impl<Backend, Block: BlockT, Client, SC: Clone> Clone
 		GrandpaBlockImport {
 			inner: self.inner.clone(),
 			justification_import_period: self.justification_import_period,
+			justification_request_policy: self.justification_request_policy,
 			select_chain: self.select_chain.clone(),
 			authority_set: self.authority_set.clone(),
 			send_voter_commands: self.send_voter_commands.clone(),
@@ -613,6 +615,8 @@ where
 		}
 
 		let needs_justification = applied_changes.needs_justification();
+		let requests_justification = needs_justification ||
+			self.justification_request_policy.selects_imported::<Block>(number);
 
 		match applied_changes {
 			AppliedChanges::Forced(new) => {
@@ -649,12 +653,13 @@ where
 
 		match grandpa_justification {
 			Some(justification) => {
-				if environment::should_process_justification(
-					&*self.inner,
-					self.justification_import_period,
-					number,
-					needs_justification,
-				) {
+				if requests_justification ||
+					environment::should_process_justification(
+						&*self.inner,
+						JustificationPolicy::Interval(self.justification_import_period),
+						number,
+						needs_justification,
+					) {
 					let import_res = self.import_justification(
 						hash,
 						number,
@@ -673,6 +678,14 @@ where
 							);
 							imported_aux.bad_justification = true;
 							imported_aux.needs_justification = true;
+						} else if requests_justification {
+							debug!(
+								target: LOG_TARGET,
+								"Requesting justification from peers due to imported block #{} with invalid justification: {}",
+								number,
+								err
+							);
+							imported_aux.needs_justification = true;
 						}
 					});
 				} else {
@@ -691,6 +704,14 @@ where
 						number,
 					);
 
+					imported_aux.needs_justification = true;
+				} else if requests_justification {
+					debug!(
+						target: LOG_TARGET,
+						"Imported unjustified block #{}, requesting its justification from peers.",
+						number,
+					);
+
 					imported_aux.needs_justification = true;
 				},
 		}
@@ -749,6 +770,7 @@ impl<Backend, Block: BlockT, Client, SC> GrandpaBlockImport<Backend, Block, Clie
 		GrandpaBlockImport {
 			inner,
 			justification_import_period,
+			justification_request_policy: JustificationPolicy::OnDemand,
 			select_chain,
 			authority_set,
 			send_voter_commands,
@@ -758,6 +780,15 @@ impl<Backend, Block: BlockT, Client, SC> GrandpaBlockImport<Backend, Block, Clie
 			_phantom: PhantomData,
 		}
 	}
+
+	/// Request the justifications of the blocks selected by `policy` from peers when they are
+	/// imported without, in addition to the ones of blocks enacting an authority set change.
+	///
+	/// Defaults to [`JustificationPolicy::OnDemand`], only requesting the latter.
+	pub fn with_justification_request_policy(mut self, policy: JustificationPolicy) -> Self {
+		self.justification_request_policy = policy;
+		self
+	}
 }
 
 impl<BE, Block: BlockT, Client, SC> GrandpaBlockImport<BE, Block, Client, SC>
@@ -0,0 +1,149 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Policies selecting the blocks GRANDPA justifications are stored and requested for.
+
+use clap::Args;
+use sp_runtime::traits::{Block as BlockT, NumberFor, Zero};
+use std::{fmt, str::FromStr};
+
+/// Policy selecting the blocks a GRANDPA justification is stored or requested for.
+///
+/// Justifications of blocks enacting an authority set change are always stored and requested,
+/// whatever the policy, since they are needed to follow the authority set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JustificationPolicy {
+	/// Every block finalized by a justification.
+	EveryBlock,
+	/// At least one block every given number of blocks.
+	Interval(u32),
+	/// Only the blocks enacting an authority set change. Finality of the other blocks can still be
+	/// proven on demand, with the justification of the last block of their authority set.
+	OnDemand,
+}
+
+impl JustificationPolicy {
+	/// Whether the policy selects the block `number` being finalized, given the last finalized
+	/// block.
+	pub(crate) fn selects_finalized<Block: BlockT>(
+		&self,
+		last_finalized: NumberFor<Block>,
+		number: NumberFor<Block>,
+	) -> bool {
+		match *self {
+			Self::EveryBlock => true,
+			Self::Interval(period) => {
+				// keep the first justification before reaching the interval
+				if last_finalized.is_zero() {
+					return true
+				}
+				let period = NumberFor::<Block>::from(period.max(1));
+				last_finalized / period != number / period
+			},
+			Self::OnDemand => false,
+		}
+	}
+
+	/// Whether the policy selects the block `number` being imported.
+	///
+	/// Unlike for finalized blocks, the blocks selected by an interval don't depend on finality:
+	/// they are the blocks whose number is a multiple of the interval.
+	pub(crate) fn selects_imported<Block: BlockT>(&self, number: NumberFor<Block>) -> bool {
+		match *self {
+			Self::EveryBlock => true,
+			Self::Interval(period) => (number % NumberFor::<Block>::from(period.max(1))).is_zero(),
+			Self::OnDemand => false,
+		}
+	}
+}
+
+impl fmt::Display for JustificationPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::EveryBlock => write!(f, "every-block"),
+			Self::Interval(period) => write!(f, "{period}"),
+			Self::OnDemand => write!(f, "on-demand"),
+		}
+	}
+}
+
+impl FromStr for JustificationPolicy {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"every-block" => Ok(Self::EveryBlock),
+			"on-demand" => Ok(Self::OnDemand),
+			interval => match interval.parse() {
+				Ok(0) | Err(_) => Err(format!(
+					"Invalid justification policy `{s}`, expected `every-block`, `on-demand` or a \
+					 positive number of blocks",
+				)),
+				Ok(period) => Ok(Self::Interval(period)),
+			},
+		}
+	}
+}
+
+/// Parameters of the GRANDPA justification policies.
+#[derive(Debug, Clone, Args)]
+pub struct JustificationPolicyParams {
+	/// Blocks whose GRANDPA justification is stored, and thereby served to peers and over RPC.
+	///
+	/// Either `every-block`, `on-demand` to only store the justifications of blocks enacting an
+	/// authority set change, or a number of blocks `N` to store at least one justification every
+	/// `N` blocks. Defaults to the interval of the node.
+	#[arg(long, value_name = "POLICY")]
+	pub grandpa_justification_serving: Option<JustificationPolicy>,
+
+	/// Blocks whose GRANDPA justification is requested from peers when importing them without.
+	///
+	/// Takes the same values as `--grandpa-justification-serving`, an interval `N` selecting the
+	/// blocks whose number is a multiple of `N`. Justifications of blocks enacting an authority set
+	/// change are always requested.
+	#[arg(long, value_name = "POLICY", default_value_t = JustificationPolicy::OnDemand)]
+	pub grandpa_justification_requests: JustificationPolicy,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime_client::runtime::Block;
+
+	#[test]
+	fn parses_policies() {
+		assert_eq!("every-block".parse(), Ok(JustificationPolicy::EveryBlock));
+		assert_eq!("on-demand".parse(), Ok(JustificationPolicy::OnDemand));
+		assert_eq!("512".parse(), Ok(JustificationPolicy::Interval(512)));
+		assert!("0".parse::<JustificationPolicy>().is_err());
+		assert!("sometimes".parse::<JustificationPolicy>().is_err());
+	}
+
+	#[test]
+	fn interval_selects_one_block_per_interval() {
+		let policy = JustificationPolicy::Interval(32);
+		assert!(policy.selects_finalized::<Block>(0, 5));
+		assert!(!policy.selects_finalized::<Block>(5, 31));
+		assert!(policy.selects_finalized::<Block>(31, 32));
+		assert!(policy.selects_finalized::<Block>(20, 70));
+		assert!(!policy.selects_finalized::<Block>(32, 63));
+
+		assert!(policy.selects_imported::<Block>(64));
+		assert!(!policy.selects_imported::<Block>(65));
+	}
+}
@@ -67,6 +67,7 @@ use sc_client_api::{
 	BlockchainEvents, CallExecutor, ExecutorProvider, Finalizer, LockImportRun, StorageProvider,
 };
 use sc_consensus::BlockImport;
+use sc_consensus_slashing_protection::SlashingProtection;
 use sc_network::{types::ProtocolName, NetworkBackend, NotificationService};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
@@ -120,6 +121,7 @@ mod environment;
 mod finality_proof;
 mod import;
 mod justification;
+mod justification_policy;
 mod notification;
 mod observer;
 mod until_imported;
@@ -127,12 +129,13 @@ mod voting_rule;
 pub mod warp_proof;
 
 pub use authorities::{AuthoritySet, AuthoritySetChanges, SharedAuthoritySet};
-pub use aux_schema::best_justification;
+pub use aux_schema::{best_justification, AUX_KEYS};
 pub use communication::grandpa_protocol_name::standard_name as protocol_standard_name;
 pub use finality_grandpa::voter::report;
 pub use finality_proof::{FinalityProof, FinalityProofError, FinalityProofProvider};
 pub use import::{find_forced_change, find_scheduled_change, GrandpaBlockImport};
 pub use justification::GrandpaJustification;
+pub use justification_policy::{JustificationPolicy, JustificationPolicyParams};
 pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
 pub use observer::run_grandpa_observer;
 pub use voting_rule::{
@@ -214,10 +217,9 @@ impl Clone for SharedVoterState {
 pub struct Config {
 	/// The expected duration for a message to be gossiped across the network.
 	pub gossip_duration: Duration,
-	/// Justification generation period (in blocks). GRANDPA will try to generate
-	/// justifications at least every justification_generation_period blocks. There
-	/// are some other events which might cause justification generation.
-	pub justification_generation_period: u32,
+	/// The blocks whose justification is generated and stored, and thereby served to peers. The
+	/// justifications of blocks enacting an authority set change are always stored.
+	pub justification_policy: JustificationPolicy,
 	/// Whether the GRANDPA observer protocol is live on the network and thereby
 	/// a full-node not running as a validator is running the GRANDPA observer
 	/// protocol (we will only issue catch-up requests to authorities when the
@@ -233,6 +235,8 @@ pub struct Config {
 	pub telemetry: Option<TelemetryHandle>,
 	/// Chain specific GRANDPA protocol name. See [`crate::protocol_standard_name`].
 	pub protocol_name: ProtocolName,
+	/// Slashing protection checked before signing a vote.
+	pub slashing_protection: Option<SlashingProtection>,
 }
 
 impl Config {
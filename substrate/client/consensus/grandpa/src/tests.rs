@@ -324,13 +324,14 @@ fn initialize_grandpa(
 		let grandpa_params = GrandpaParams {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
-				justification_generation_period: 32,
+				justification_policy: JustificationPolicy::Interval(32),
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -483,13 +484,14 @@ async fn finalize_3_voters_1_full_observer() {
 		let grandpa_params = GrandpaParams {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
-				justification_generation_period: 32,
+				justification_policy: JustificationPolicy::Interval(32),
 				keystore: None,
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -582,13 +584,14 @@ async fn transition_3_voters_twice_1_full_observer() {
 		let grandpa_params = GrandpaParams {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
-				justification_generation_period: 32,
+				justification_policy: JustificationPolicy::Interval(32),
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -713,7 +716,7 @@ async fn justification_is_generated_periodically() {
 	let net = Arc::new(Mutex::new(net));
 	run_to_completion(32, net.clone(), peers).await;
 
-	// when block#32 (justification_generation_period) is finalized,
+	// when block#32 (the justification interval) is finalized,
 	// justification is required => generated
 	for i in 0..3 {
 		assert!(net.lock().peer(i).client().justifications(hashof32).unwrap().is_some());
@@ -1017,13 +1020,14 @@ async fn voter_persists_its_votes() {
 	let bob_network = {
 		let config = Config {
 			gossip_duration: TEST_GOSSIP_DURATION,
-			justification_generation_period: 32,
+			justification_policy: JustificationPolicy::Interval(32),
 			keystore: Some(bob_keystore.clone()),
 			name: Some(format!("peer#{}", 1)),
 			local_role: Role::Authority,
 			observer_enabled: true,
 			telemetry: None,
 			protocol_name: grandpa_protocol_name::NAME.into(),
+			slashing_protection: None,
 		};
 
 		let set_state = {
@@ -1065,13 +1069,14 @@ async fn voter_persists_its_votes() {
 		let grandpa_params = GrandpaParams {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
-				justification_generation_period: 32,
+				justification_policy: JustificationPolicy::Interval(32),
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", 0)),
 				local_role: Role::Authority,
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -1115,13 +1120,14 @@ async fn voter_persists_its_votes() {
 		let grandpa_params = GrandpaParams {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
-				justification_generation_period: 32,
+				justification_policy: JustificationPolicy::Interval(32),
 				keystore: Some(keystore),
 				name: Some(format!("peer#{}", 0)),
 				local_role: Role::Authority,
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net_service,
@@ -1161,6 +1167,7 @@ async fn voter_persists_its_votes() {
 	{
 		let (round_rx, round_tx) = bob_network.round_communication(
 			Some((peers[1].public().into(), bob_keystore).into()),
+			None,
 			communication::Round(1),
 			communication::SetId(0),
 			Arc::new(VoterSet::new(voters).unwrap()),
@@ -1284,13 +1291,14 @@ async fn finalize_3_voters_1_light_observer() {
 	let observer = observer::run_grandpa_observer(
 		Config {
 			gossip_duration: TEST_GOSSIP_DURATION,
-			justification_generation_period: 32,
+			justification_policy: JustificationPolicy::Interval(32),
 			keystore: None,
 			name: Some("observer".to_string()),
 			local_role: Role::Full,
 			observer_enabled: true,
 			telemetry: None,
 			protocol_name: grandpa_protocol_name::NAME.into(),
+			slashing_protection: None,
 		},
 		net.peers[3].data.lock().take().expect("link initialized at startup; qed"),
 		net.peers[3].network_service().clone(),
@@ -1333,13 +1341,14 @@ async fn voter_catches_up_to_latest_round_when_behind() {
 		let grandpa_params = GrandpaParams {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
-				justification_generation_period: 32,
+				justification_policy: JustificationPolicy::Interval(32),
 				keystore,
 				name: Some(format!("peer#{}", peer_id)),
 				local_role: Role::Authority,
 				observer_enabled: true,
 				telemetry: None,
 				protocol_name: grandpa_protocol_name::NAME.into(),
+				slashing_protection: None,
 			},
 			link,
 			network: net.peer(peer_id).network_service().clone(),
@@ -1453,13 +1462,14 @@ where
 
 	let config = Config {
 		gossip_duration: TEST_GOSSIP_DURATION,
-		justification_generation_period: 32,
+		justification_policy: JustificationPolicy::Interval(32),
 		keystore,
 		name: None,
 		local_role: Role::Authority,
 		observer_enabled: true,
 		telemetry: None,
 		protocol_name: grandpa_protocol_name::NAME.into(),
+		slashing_protection: None,
 	};
 
 	let network = NetworkBridge::new(
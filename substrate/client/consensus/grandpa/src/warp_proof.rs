@@ -314,11 +314,7 @@ where
 		if proof.is_finished {
 			Ok(VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header))
 		} else {
-			Ok(VerificationResult::<Block>::Partial(
-				next_set_id,
-				next_authorities,
-				last_header.hash(),
-			))
+			Ok(VerificationResult::<Block>::Partial(next_set_id, next_authorities, last_header))
 		}
 	}
 
@@ -0,0 +1,208 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Standby mode for validator failover.
+//!
+//! A standby node runs with the same session keys as the active node. It fully syncs and follows
+//! consensus, but doesn't sign anything until an external health check or leader election
+//! endpoint promotes it. Encrypted key files are only decrypted for signing, so the keys of a
+//! standby node stay encrypted until it's promoted.
+//!
+//! To avoid equivocation during the hand-off, a promoted node only starts signing once the
+//! endpoint has reported the promotion for the whole hand-off delay. A node stops signing as soon
+//! as the endpoint reports it isn't promoted, or once it failed to reach the endpoint for half of
+//! the hand-off delay. The previous active node has thus stopped signing before its replacement
+//! starts, as long as both nodes are checking the same endpoint.
+
+use crate::{SigningLease, LOG_TARGET};
+use http_body_util::Empty;
+use hyper::{body::Bytes, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::{
+	client::legacy::{connect::HttpConnector, Client},
+	rt::TokioExecutor,
+};
+use parking_lot::Mutex;
+use std::{
+	io,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The number of times the endpoint is checked per hand-off delay.
+const CHECKS_PER_HANDOFF_DELAY: u32 = 10;
+
+type HttpClient = Client<HttpsConnector<HttpConnector>, Empty<Bytes>>;
+
+/// The promotion of a standby node.
+#[derive(Debug, Default)]
+struct Promotion {
+	/// Since when the endpoint reports the promotion without interruption.
+	since: Option<Instant>,
+	/// When the endpoint last confirmed the promotion.
+	confirmed_at: Option<Instant>,
+}
+
+impl Promotion {
+	/// Update the promotion with the result of a check, `None` if the endpoint couldn't be reached.
+	fn update(&mut self, now: Instant, promoted: Option<bool>, handoff_delay: Duration) {
+		match promoted {
+			Some(true) => {
+				self.since.get_or_insert(now);
+				self.confirmed_at = Some(now);
+			},
+			Some(false) => *self = Self::default(),
+			None => {
+				if !self.is_confirmed(now, handoff_delay) {
+					*self = Self::default()
+				}
+			},
+		}
+	}
+
+	fn is_confirmed(&self, now: Instant, handoff_delay: Duration) -> bool {
+		self.confirmed_at
+			.is_some_and(|confirmed_at| now.duration_since(confirmed_at) < handoff_delay / 2)
+	}
+
+	/// Whether the node may sign.
+	fn is_active(&self, now: Instant, handoff_delay: Duration) -> bool {
+		self.since.is_some_and(|since| now.duration_since(since) >= handoff_delay)
+			&& self.is_confirmed(now, handoff_delay)
+	}
+}
+
+/// A lease held while an external endpoint promotes this node from standby.
+///
+/// The endpoint is queried with `GET` requests, any successful status promotes the node.
+pub struct StandbyLease {
+	client: HttpClient,
+	url: Uri,
+	handoff_delay: Duration,
+	promotion: Mutex<Promotion>,
+}
+
+impl StandbyLease {
+	/// Create a lease held once `url` reported the promotion for `handoff_delay`.
+	pub fn new(url: Uri, handoff_delay: Duration) -> io::Result<Self> {
+		let connector = HttpsConnectorBuilder::new()
+			.with_provider_and_native_roots(rustls::crypto::ring::default_provider())?
+			.https_or_http()
+			.enable_http1()
+			.build();
+		let client = Client::builder(TokioExecutor::new()).build(connector);
+		Ok(Self { client, url, handoff_delay, promotion: Default::default() })
+	}
+
+	/// Keep checking the endpoint for the promotion.
+	pub async fn run(self: Arc<Self>) {
+		let interval = self.handoff_delay / CHECKS_PER_HANDOFF_DELAY;
+		let mut ticks = tokio::time::interval(interval);
+		loop {
+			ticks.tick().await;
+			let promoted =
+				match tokio::time::timeout(interval, self.client.get(self.url.clone())).await {
+					Ok(Ok(response)) => Some(response.status().is_success()),
+					Ok(Err(e)) => {
+						log::debug!(target: LOG_TARGET, "Failed to check for promotion: {e}");
+						None
+					},
+					Err(_) => {
+						log::debug!(target: LOG_TARGET, "Check for promotion timed out");
+						None
+					},
+				};
+
+			let was_active = self.is_held();
+			let mut promotion = self.promotion.lock();
+			let was_promoted = promotion.since.is_some();
+			promotion.update(Instant::now(), promoted, self.handoff_delay);
+			if promotion.since.is_some() && !was_promoted {
+				log::info!(
+					target: LOG_TARGET,
+					"Promoted from standby, signing in {:?}",
+					self.handoff_delay,
+				);
+			}
+			if was_active && !promotion.is_active(Instant::now(), self.handoff_delay) {
+				log::warn!(target: LOG_TARGET, "Demoted to standby, no longer signing");
+			}
+		}
+	}
+}
+
+impl SigningLease for StandbyLease {
+	fn is_held(&self) -> bool {
+		self.promotion.lock().is_active(Instant::now(), self.handoff_delay)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DELAY: Duration = Duration::from_secs(60);
+
+	#[test]
+	fn promotion_takes_effect_after_handoff_delay() {
+		let start = Instant::now();
+		let mut promotion = Promotion::default();
+		assert!(!promotion.is_active(start, DELAY));
+
+		promotion.update(start, Some(true), DELAY);
+		assert!(!promotion.is_active(start, DELAY));
+
+		promotion.update(start + DELAY / 2, Some(false), DELAY);
+		promotion.update(start + DELAY, Some(true), DELAY);
+		assert!(!promotion.is_active(start + DELAY, DELAY));
+
+		promotion.update(start + DELAY * 2, Some(true), DELAY);
+		assert!(promotion.is_active(start + DELAY * 2, DELAY));
+	}
+
+	#[test]
+	fn demotion_takes_effect_immediately() {
+		let start = Instant::now();
+		let mut promotion = Promotion::default();
+		promotion.update(start, Some(true), DELAY);
+		promotion.update(start + DELAY, Some(true), DELAY);
+		assert!(promotion.is_active(start + DELAY, DELAY));
+
+		promotion.update(start + DELAY, Some(false), DELAY);
+		assert!(!promotion.is_active(start + DELAY, DELAY));
+	}
+
+	#[test]
+	fn unreachable_endpoint_demotes_before_replacement_is_promoted() {
+		let start = Instant::now();
+		let mut promotion = Promotion::default();
+		promotion.update(start, Some(true), DELAY);
+		promotion.update(start + DELAY, Some(true), DELAY);
+
+		// Short outages are tolerated.
+		let now = start + DELAY + DELAY / 4;
+		promotion.update(now, None, DELAY);
+		assert!(promotion.is_active(now, DELAY));
+
+		let now = start + DELAY + DELAY / 2;
+		assert!(!promotion.is_active(now, DELAY));
+		promotion.update(now, None, DELAY);
+		promotion.update(now, Some(true), DELAY);
+		assert!(!promotion.is_active(now, DELAY));
+	}
+}
@@ -0,0 +1,408 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Signing leases for active-passive validator failover.
+//!
+//! Two modes are supported:
+//!
+//! - [`CommandLease`]: the lease is granted by an external lock service, like etcd or Consul, via
+//!   a command run periodically to acquire or renew it.
+//! - [`HeartbeatLease`]: the nodes exchange heartbeats over UDP. A node only takes the lease if no
+//!   other node holds it and no node with a higher priority is alive. This mode can't tell a
+//!   crashed peer from a network partition, so it must only be used over a reliable, dedicated
+//!   link between the nodes.
+
+use crate::{StandbyLease, LOG_TARGET};
+use clap::Args;
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sp_core::traits::SpawnEssentialNamed;
+use std::{
+	collections::HashMap,
+	io,
+	net::{SocketAddr, UdpSocket},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Prefix of heartbeat datagrams.
+const HEARTBEAT_MAGIC: [u8; 4] = *b"SPHB";
+
+/// Something that tells whether this node may sign.
+pub trait SigningLease: Send + Sync {
+	/// Whether this node currently holds the lease.
+	fn is_held(&self) -> bool;
+}
+
+/// Parameters of the signing lease.
+#[derive(Debug, Clone, Args)]
+pub struct SigningLeaseParams {
+	/// Only sign while holding a lease granted by an external lock service.
+	///
+	/// The command is run as `<COMMAND> <NODE_NAME> <SECONDS>` three times per lease duration. It
+	/// must exit successfully only if the lease is acquired or renewed for this node for the given
+	/// number of seconds.
+	#[arg(
+		long,
+		value_name = "COMMAND",
+		conflicts_with_all = &["signing_lease_peer", "failover_standby"]
+	)]
+	pub signing_lease_command: Option<String>,
+
+	/// Only sign while no peer holds the lease, exchanging heartbeats with the given peer.
+	///
+	/// Can be given multiple times.
+	#[arg(
+		long,
+		value_name = "ADDRESS",
+		requires = "signing_lease_listen_addr",
+		conflicts_with = "failover_standby"
+	)]
+	pub signing_lease_peer: Vec<SocketAddr>,
+
+	/// Address heartbeats from the `--signing-lease-peer` nodes are received on.
+	#[arg(long, value_name = "ADDRESS")]
+	pub signing_lease_listen_addr: Option<SocketAddr>,
+
+	/// Priority of this node for taking the lease, lower values take precedence.
+	///
+	/// Every node must have a different priority.
+	#[arg(long, value_name = "PRIORITY", default_value_t = 0)]
+	pub signing_lease_priority: u32,
+
+	/// Duration of the lease in seconds.
+	///
+	/// A failed node is replaced after this duration at the latest.
+	#[arg(
+		long,
+		value_name = "SECONDS",
+		default_value_t = 30,
+		value_parser = clap::value_parser!(u64).range(3..)
+	)]
+	pub signing_lease_duration: u64,
+
+	/// Run as standby node, only signing once promoted by the `--failover-promotion-url` endpoint.
+	#[arg(long, requires = "failover_promotion_url")]
+	pub failover_standby: bool,
+
+	/// Endpoint promoting a standby node, by answering `GET` requests with a successful status.
+	#[arg(long, value_name = "URL", requires = "failover_standby")]
+	pub failover_promotion_url: Option<hyper::Uri>,
+
+	/// Time in seconds the endpoint must promote a standby node before it starts signing.
+	///
+	/// The previous active node stops signing within half of this delay once it isn't promoted
+	/// anymore.
+	#[arg(
+		long,
+		value_name = "SECONDS",
+		default_value_t = 60,
+		value_parser = clap::value_parser!(u64).range(10..)
+	)]
+	pub failover_handoff_delay: u64,
+}
+
+impl SigningLeaseParams {
+	/// Start renewing the configured lease, returning `None` if no lease is configured.
+	pub fn start(
+		&self,
+		node_name: &str,
+		spawner: &impl SpawnEssentialNamed,
+	) -> io::Result<Option<Arc<dyn SigningLease>>> {
+		if let Some(url) = self.failover_promotion_url.clone().filter(|_| self.failover_standby) {
+			let handoff_delay = Duration::from_secs(self.failover_handoff_delay);
+			let lease = Arc::new(StandbyLease::new(url, handoff_delay)?);
+			spawner.spawn_essential("failover-standby", None, Box::pin(lease.clone().run()));
+			return Ok(Some(lease))
+		}
+		let duration = Duration::from_secs(self.signing_lease_duration);
+		if let Some(command) = &self.signing_lease_command {
+			let lease = Arc::new(CommandLease::new(command.clone(), node_name.into(), duration));
+			spawner.spawn_essential("signing-lease", None, Box::pin(lease.clone().run()));
+			return Ok(Some(lease))
+		}
+		if let Some(listen_addr) = self.signing_lease_listen_addr {
+			let socket = UdpSocket::bind(listen_addr)?;
+			let lease = Arc::new(HeartbeatLease::new(
+				self.signing_lease_peer.clone(),
+				self.signing_lease_priority,
+				duration,
+			));
+			spawner.spawn_essential("signing-lease", None, Box::pin(lease.clone().run(socket)));
+			return Ok(Some(lease))
+		}
+		Ok(None)
+	}
+}
+
+/// A lease granted by an external lock service, acquired and renewed by running a command.
+pub struct CommandLease {
+	command: String,
+	node_name: String,
+	duration: Duration,
+	held_until: Mutex<Option<Instant>>,
+}
+
+impl CommandLease {
+	/// Create a lease of `duration`, acquired by running `command` for `node_name`.
+	pub fn new(command: String, node_name: String, duration: Duration) -> Self {
+		Self { command, node_name, duration, held_until: Mutex::new(None) }
+	}
+
+	/// Keep acquiring and renewing the lease.
+	pub async fn run(self: Arc<Self>) {
+		loop {
+			// The lease is counted from before the request, so it expires locally first.
+			let requested_at = Instant::now();
+			let status = tokio::process::Command::new(&self.command)
+				.arg(&self.node_name)
+				.arg(self.duration.as_secs().to_string())
+				.kill_on_drop(true)
+				.status();
+			match tokio::time::timeout(self.duration / 3, status).await {
+				Ok(Ok(status)) if status.success() => {
+					let was_held = self.is_held();
+					*self.held_until.lock() = Some(requested_at + self.duration);
+					if !was_held {
+						log::info!(target: LOG_TARGET, "Acquired the signing lease");
+					}
+				},
+				result => {
+					if self.is_held() {
+						log::warn!(target: LOG_TARGET, "Failed to renew signing lease: {result:?}");
+					} else {
+						log::debug!(target: LOG_TARGET, "Signing lease not acquired: {result:?}");
+					}
+				},
+			}
+			tokio::time::sleep_until((requested_at + self.duration / 3).into()).await;
+		}
+	}
+}
+
+impl SigningLease for CommandLease {
+	fn is_held(&self) -> bool {
+		self.held_until.lock().is_some_and(|held_until| Instant::now() < held_until)
+	}
+}
+
+/// A heartbeat sent to the peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+struct Heartbeat {
+	priority: u32,
+	holding: bool,
+}
+
+/// The last heartbeat of a peer.
+struct Peer {
+	heartbeat: Heartbeat,
+	seen_at: Instant,
+}
+
+struct HeartbeatState {
+	started_at: Instant,
+	/// Whether this node holds the lease, until the given instant.
+	held_until: Option<Instant>,
+	peers: HashMap<SocketAddr, Peer>,
+}
+
+impl HeartbeatState {
+	/// Decide whether this node holds the lease.
+	fn update(&mut self, now: Instant, priority: u32, duration: Duration) -> bool {
+		self.peers.retain(|_, peer| now.duration_since(peer.seen_at) < duration);
+		let holding = self.held_until.is_some_and(|held_until| now < held_until);
+
+		let holding = if holding {
+			// If several nodes hold the lease, only the one with the highest priority keeps it.
+			!self
+				.peers
+				.values()
+				.any(|peer| peer.heartbeat.holding && peer.heartbeat.priority < priority)
+		} else {
+			// Wait for the heartbeats of the alive peers before taking the lease.
+			now.duration_since(self.started_at) >= duration
+				&& !self
+					.peers
+					.values()
+					.any(|peer| peer.heartbeat.holding || peer.heartbeat.priority < priority)
+		};
+		// Expire the lease if no update happens, e.g. because the task stalls.
+		self.held_until = holding.then(|| now + duration / 3);
+		holding
+	}
+}
+
+/// A lease held by at most one of several nodes exchanging heartbeats.
+pub struct HeartbeatLease {
+	peers: Vec<SocketAddr>,
+	priority: u32,
+	duration: Duration,
+	state: Mutex<HeartbeatState>,
+}
+
+impl HeartbeatLease {
+	/// Create a lease of `duration`, shared with `peers`.
+	pub fn new(peers: Vec<SocketAddr>, priority: u32, duration: Duration) -> Self {
+		let state =
+			HeartbeatState { started_at: Instant::now(), held_until: None, peers: HashMap::new() };
+		Self { peers, priority, duration, state: Mutex::new(state) }
+	}
+
+	/// Exchange heartbeats with the peers over `socket`, updating the lease.
+	pub async fn run(self: Arc<Self>, socket: UdpSocket) {
+		let socket = match socket.set_nonblocking(true).and_then(tokio::net::UdpSocket::from_std) {
+			Ok(socket) => socket,
+			Err(e) => {
+				log::error!(target: LOG_TARGET, "Failed to set up the heartbeat socket: {e}");
+				return
+			},
+		};
+		let mut interval = tokio::time::interval(self.duration / 6);
+		let mut buffer = [0; 64];
+		loop {
+			tokio::select! {
+				_ = interval.tick() => {
+					let was_held = self.is_held();
+					let holding =
+						self.state.lock().update(Instant::now(), self.priority, self.duration);
+					if holding != was_held {
+						log::info!(
+							target: LOG_TARGET,
+							"{} the signing lease",
+							if holding { "Acquired" } else { "Released" },
+						);
+					}
+
+					let mut heartbeat = HEARTBEAT_MAGIC.to_vec();
+					Heartbeat { priority: self.priority, holding }.encode_to(&mut heartbeat);
+					for peer in &self.peers {
+						if let Err(e) = socket.send_to(&heartbeat, peer).await {
+							log::debug!(target: LOG_TARGET, "Heartbeat to {peer} failed: {e}");
+						}
+					}
+				},
+				received = socket.recv_from(&mut buffer) => {
+					let Ok((len, from)) = received else { continue };
+					if !self.peers.contains(&from) {
+						log::debug!(target: LOG_TARGET, "Ignoring heartbeat from {from}");
+						continue
+					}
+					let heartbeat = buffer[..len]
+						.strip_prefix(&HEARTBEAT_MAGIC)
+						.and_then(|mut data| Heartbeat::decode(&mut data).ok());
+					if let Some(heartbeat) = heartbeat {
+						let peer = Peer { heartbeat, seen_at: Instant::now() };
+						self.state.lock().peers.insert(from, peer);
+					}
+				},
+			}
+		}
+	}
+}
+
+impl SigningLease for HeartbeatLease {
+	fn is_held(&self) -> bool {
+		self.state
+			.lock()
+			.held_until
+			.is_some_and(|held_until| Instant::now() < held_until)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Error, SlashingProtection};
+
+	const DURATION: Duration = Duration::from_secs(30);
+
+	fn state(started_at: Instant) -> HeartbeatState {
+		HeartbeatState { started_at, held_until: None, peers: HashMap::new() }
+	}
+
+	fn peer(state: &mut HeartbeatState, port: u16, priority: u32, holding: bool, seen_at: Instant) {
+		let heartbeat = Heartbeat { priority, holding };
+		state.peers.insert(([127, 0, 0, 1], port).into(), Peer { heartbeat, seen_at });
+	}
+
+	#[test]
+	fn lease_is_taken_after_waiting_for_peers() {
+		let start = Instant::now();
+		let mut state = state(start);
+		assert!(!state.update(start, 1, DURATION));
+
+		peer(&mut state, 1, 2, false, start);
+		assert!(state.update(start + DURATION, 1, DURATION));
+	}
+
+	#[test]
+	fn lease_is_not_taken_while_held_or_preferred_elsewhere() {
+		let start = Instant::now();
+		let now = start + DURATION;
+
+		let mut state = state(start);
+		peer(&mut state, 1, 2, true, now);
+		assert!(!state.update(now, 1, DURATION));
+
+		let mut state = self::state(start);
+		peer(&mut state, 1, 0, false, now);
+		assert!(!state.update(now, 1, DURATION));
+
+		// The peers expire.
+		assert!(state.update(now + DURATION, 1, DURATION));
+	}
+
+	#[test]
+	fn only_preferred_node_keeps_lease() {
+		let start = Instant::now();
+		let now = start + DURATION;
+
+		let mut state = state(start);
+		assert!(state.update(now, 1, DURATION));
+
+		// A passive preferred node doesn't take over the lease.
+		peer(&mut state, 1, 0, false, now);
+		assert!(state.update(now, 1, DURATION));
+
+		peer(&mut state, 2, 2, true, now);
+		assert!(state.update(now, 1, DURATION));
+
+		peer(&mut state, 1, 0, true, now);
+		assert!(!state.update(now, 1, DURATION));
+	}
+
+	struct Fixed(bool);
+
+	impl SigningLease for Fixed {
+		fn is_held(&self) -> bool {
+			self.0
+		}
+	}
+
+	#[test]
+	fn signing_requires_lease() {
+		let store = SlashingProtection::in_memory().with_lease(Arc::new(Fixed(false)));
+		assert!(matches!(
+			store.check_babe_block(b"alice", 1, b"block"),
+			Err(Error::LeaseNotHeld { .. })
+		));
+
+		let store = SlashingProtection::in_memory().with_lease(Arc::new(Fixed(true)));
+		store.check_babe_block(b"alice", 1, b"block").unwrap();
+	}
+}
@@ -0,0 +1,427 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Slashing protection for validator keys.
+//!
+//! The [`SlashingProtection`] store records every block and vote signed by the local
+//! authorities, and refuses to sign a conflicting one:
+//!
+//! - a second BABE block for the same slot,
+//! - a second GRANDPA vote of the same kind in the same round,
+//! - a second BEEFY vote for the same block.
+//!
+//! This protects validators from being slashed for equivocation even if the consensus code has a
+//! bug, or the node is misconfigured, e.g. by running the same keys on a node restored from a
+//! backup. Signing the very same message again is allowed.
+//!
+//! Every record is written to disk before the message may be signed. Only the most recent
+//! [`KEPT_RECORDS`] records of every duty and authority are kept, signing older messages is
+//! refused.
+//!
+//! Operators running the same keys on an active and a passive node for failover can additionally
+//! require a [`SigningLease`], so that only the node holding the lease signs at all. A
+//! [`StandbyLease`] lets an orchestrator promote a standby node once the active node failed.
+
+mod failover;
+mod lease;
+
+pub use failover::StandbyLease;
+pub use lease::{CommandLease, HeartbeatLease, SigningLease, SigningLeaseParams};
+
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs::{self, File, OpenOptions},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+const LOG_TARGET: &str = "slashing-protection";
+
+/// The number of records kept for every duty and authority.
+pub const KEPT_RECORDS: usize = 1000;
+
+/// The kind of a GRANDPA vote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode)]
+pub enum GrandpaVote {
+	/// A primary proposal.
+	PrimaryPropose,
+	/// A prevote.
+	Prevote,
+	/// A precommit.
+	Precommit,
+}
+
+/// A kind of message signed by an authority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode)]
+pub enum Duty {
+	/// A BABE block, at `(0, slot)`.
+	BabeBlock,
+	/// A GRANDPA vote, at `(set_id, round)`.
+	Grandpa(GrandpaVote),
+	/// A BEEFY vote, at `(set_id, block_number)`.
+	BeefyVote,
+}
+
+/// The position of a signed message, ordered from old to new.
+pub type Position = (u64, u64);
+
+/// Slashing protection error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// IO error.
+	#[error(transparent)]
+	Io(#[from] io::Error),
+	/// The store couldn't be decoded.
+	#[error("Slashing protection database is corrupted: {0}")]
+	Corrupted(#[from] codec::Error),
+	/// A conflicting message was already signed.
+	#[error("Refusing to sign {duty:?} at {position:?}: conflicts with an already signed message")]
+	Conflict {
+		/// The duty of the message.
+		duty: Duty,
+		/// The position of the message.
+		position: Position,
+	},
+	/// The message is older than the kept records.
+	#[error(
+		"Refusing to sign {duty:?} at {position:?}: older than the slashing protection history"
+	)]
+	TooOld {
+		/// The duty of the message.
+		duty: Duty,
+		/// The position of the message.
+		position: Position,
+	},
+	/// The node doesn't hold the signing lease.
+	#[error("Refusing to sign {duty:?} at {position:?}: the signing lease is held by another node")]
+	LeaseNotHeld {
+		/// The duty of the message.
+		duty: Duty,
+		/// The position of the message.
+		position: Position,
+	},
+}
+
+/// A record of the store file.
+#[derive(Encode, Decode)]
+enum Record {
+	/// A message was signed.
+	Signed { duty: Duty, authority: Vec<u8>, position: Position, payload: Vec<u8> },
+	/// Messages older than `position` must not be signed.
+	LowWatermark { duty: Duty, authority: Vec<u8>, position: Position },
+}
+
+/// The messages signed for a duty by an authority.
+#[derive(Default)]
+struct History {
+	low_watermark: Option<Position>,
+	signed: BTreeMap<Position, Vec<u8>>,
+}
+
+impl History {
+	/// Forget all but the most recent [`KEPT_RECORDS`] records.
+	fn prune(&mut self) {
+		while self.signed.len() > KEPT_RECORDS {
+			self.signed.pop_first();
+		}
+		if self.signed.len() == KEPT_RECORDS {
+			self.low_watermark = self.signed.first_key_value().map(|(position, _)| *position);
+		}
+	}
+}
+
+struct Inner {
+	/// The store file records are appended to, `None` if the store is in memory.
+	file: Option<File>,
+	histories: HashMap<(Duty, Vec<u8>), History>,
+	/// The lease required for signing, if any.
+	lease: Option<Arc<dyn SigningLease>>,
+}
+
+/// A store of the messages signed by the local authorities.
+///
+/// Cheap to clone, all clones share the same store.
+#[derive(Clone)]
+pub struct SlashingProtection(Arc<Mutex<Inner>>);
+
+impl SlashingProtection {
+	/// Open the store at `path`, creating it if it doesn't exist.
+	pub fn open(path: &Path) -> Result<Self, Error> {
+		let mut histories = HashMap::<_, History>::new();
+		let data = match fs::read(path) {
+			Ok(data) => data,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+			Err(e) => return Err(e.into()),
+		};
+
+		let mut input = &data[..];
+		while !input.is_empty() {
+			let Some(record) = read_record(&mut input)? else {
+				log::warn!(
+					target: LOG_TARGET,
+					"Dropping incomplete last record of {}, probably written during a crash",
+					path.display(),
+				);
+				break
+			};
+			match record {
+				Record::Signed { duty, authority, position, payload } => {
+					histories
+						.entry((duty, authority))
+						.or_default()
+						.signed
+						.insert(position, payload);
+				},
+				Record::LowWatermark { duty, authority, position } => {
+					let history = histories.entry((duty, authority)).or_default();
+					history.low_watermark = history.low_watermark.max(Some(position));
+				},
+			}
+		}
+
+		// Rewrite the store without the pruned records.
+		let mut compacted = Vec::new();
+		for ((duty, authority), history) in &mut histories {
+			history.prune();
+			if let Some(position) = history.low_watermark {
+				write_record(
+					&mut compacted,
+					&Record::LowWatermark { duty: *duty, authority: authority.clone(), position },
+				)?;
+			}
+			for (position, payload) in &history.signed {
+				let record = Record::Signed {
+					duty: *duty,
+					authority: authority.clone(),
+					position: *position,
+					payload: payload.clone(),
+				};
+				write_record(&mut compacted, &record)?;
+			}
+		}
+		let file = replace_file(path, &compacted)?;
+
+		Ok(Self(Arc::new(Mutex::new(Inner { file: Some(file), histories, lease: None }))))
+	}
+
+	/// Create a store which is only kept in memory.
+	pub fn in_memory() -> Self {
+		Self(Arc::new(Mutex::new(Inner { file: None, histories: HashMap::new(), lease: None })))
+	}
+
+	/// Only allow signing while `lease` is held.
+	pub fn with_lease(self, lease: Arc<dyn SigningLease>) -> Self {
+		self.0.lock().lease = Some(lease);
+		self
+	}
+
+	/// Whether signing is currently allowed, i.e. the lease is held if one is required.
+	pub fn may_sign(&self) -> bool {
+		self.0.lock().lease.as_ref().map_or(true, |lease| lease.is_held())
+	}
+
+	/// Check that `authority` may sign the BABE block with `header_hash` at `slot`, and record it.
+	pub fn check_babe_block(
+		&self,
+		authority: &[u8],
+		slot: u64,
+		header_hash: &[u8],
+	) -> Result<(), Error> {
+		self.check_and_record(Duty::BabeBlock, authority, (0, slot), header_hash)
+	}
+
+	/// Check that `authority` may sign the encoded GRANDPA `message` in `round` of the authority
+	/// set `set_id`, and record it.
+	pub fn check_grandpa_vote(
+		&self,
+		authority: &[u8],
+		vote: GrandpaVote,
+		set_id: u64,
+		round: u64,
+		message: &[u8],
+	) -> Result<(), Error> {
+		self.check_and_record(Duty::Grandpa(vote), authority, (set_id, round), message)
+	}
+
+	/// Check that `authority` may sign the encoded BEEFY `commitment` for `block_number` of the
+	/// validator set `set_id`, and record it.
+	pub fn check_beefy_vote(
+		&self,
+		authority: &[u8],
+		set_id: u64,
+		block_number: u64,
+		commitment: &[u8],
+	) -> Result<(), Error> {
+		self.check_and_record(Duty::BeefyVote, authority, (set_id, block_number), commitment)
+	}
+
+	/// Check that `authority` may sign `payload` for `duty` at `position`, and record it.
+	///
+	/// Fails if a lease is required, but not held. The record is persisted before returning.
+	pub fn check_and_record(
+		&self,
+		duty: Duty,
+		authority: &[u8],
+		position: Position,
+		payload: &[u8],
+	) -> Result<(), Error> {
+		let mut inner = self.0.lock();
+		let Inner { file, histories, lease } = &mut *inner;
+		if lease.as_ref().is_some_and(|lease| !lease.is_held()) {
+			return Err(Error::LeaseNotHeld { duty, position })
+		}
+		let history = histories.entry((duty, authority.to_vec())).or_default();
+
+		if history.low_watermark.is_some_and(|low_watermark| position < low_watermark) {
+			return Err(Error::TooOld { duty, position })
+		}
+		match history.signed.get(&position) {
+			Some(signed) if signed == payload => return Ok(()),
+			Some(_) => return Err(Error::Conflict { duty, position }),
+			None => {},
+		}
+
+		if let Some(file) = file {
+			let mut data = Vec::new();
+			let record = Record::Signed {
+				duty,
+				authority: authority.to_vec(),
+				position,
+				payload: payload.to_vec(),
+			};
+			write_record(&mut data, &record)?;
+			file.write_all(&data)?;
+			file.sync_data()?;
+		}
+
+		history.signed.insert(position, payload.to_vec());
+		if history.signed.len() > 2 * KEPT_RECORDS {
+			history.prune();
+		}
+		Ok(())
+	}
+}
+
+/// Write a length prefixed record.
+fn write_record(output: &mut Vec<u8>, record: &Record) -> io::Result<()> {
+	let record = record.encode();
+	let len = u32::try_from(record.len())
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Record too large"))?;
+	output.extend(len.to_le_bytes());
+	output.extend(record);
+	Ok(())
+}
+
+/// Read a length prefixed record, `None` if the input ends within the record.
+fn read_record(input: &mut &[u8]) -> Result<Option<Record>, Error> {
+	let Some((len, rest)) = input.split_first_chunk::<4>() else { return Ok(None) };
+	let len = u32::from_le_bytes(*len) as usize;
+	if rest.len() < len {
+		return Ok(None)
+	}
+	let (mut record, rest) = rest.split_at(len);
+	*input = rest;
+	Ok(Some(Record::decode(&mut record)?))
+}
+
+/// Atomically replace the file at `path` with `data`, and open it for appending.
+fn replace_file(path: &Path, data: &[u8]) -> io::Result<File> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	let mut tmp = PathBuf::from(path);
+	tmp.as_mut_os_string().push(".tmp");
+
+	let mut file = File::create(&tmp)?;
+	file.write_all(data)?;
+	file.sync_all()?;
+	fs::rename(&tmp, path)?;
+
+	OpenOptions::new().append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALICE: &[u8] = b"alice";
+	const BOB: &[u8] = b"bob";
+
+	#[test]
+	fn conflicting_messages_are_refused() {
+		let store = SlashingProtection::in_memory();
+
+		store.check_babe_block(ALICE, 10, b"block").unwrap();
+		store.check_babe_block(ALICE, 10, b"block").unwrap();
+		assert!(matches!(
+			store.check_babe_block(ALICE, 10, b"other"),
+			Err(Error::Conflict { duty: Duty::BabeBlock, position: (0, 10) })
+		));
+		store.check_babe_block(ALICE, 11, b"other").unwrap();
+		store.check_babe_block(BOB, 10, b"other").unwrap();
+
+		store.check_grandpa_vote(ALICE, GrandpaVote::Prevote, 1, 5, b"vote").unwrap();
+		store.check_grandpa_vote(ALICE, GrandpaVote::Precommit, 1, 5, b"other").unwrap();
+		assert!(store.check_grandpa_vote(ALICE, GrandpaVote::Prevote, 1, 5, b"other").is_err());
+
+		store.check_beefy_vote(ALICE, 1, 100, b"commitment").unwrap();
+		assert!(store.check_beefy_vote(ALICE, 1, 100, b"other").is_err());
+	}
+
+	#[test]
+	fn records_are_persisted() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("slashing-protection");
+
+		let store = SlashingProtection::open(&path).unwrap();
+		store.check_grandpa_vote(ALICE, GrandpaVote::Prevote, 1, 5, b"vote").unwrap();
+		drop(store);
+
+		// An incomplete record, as written during a crash.
+		let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+		file.write_all(&[100, 0, 0, 0, 1, 2]).unwrap();
+
+		let store = SlashingProtection::open(&path).unwrap();
+		store.check_grandpa_vote(ALICE, GrandpaVote::Prevote, 1, 5, b"vote").unwrap();
+		assert!(store.check_grandpa_vote(ALICE, GrandpaVote::Prevote, 1, 5, b"other").is_err());
+	}
+
+	#[test]
+	fn old_messages_are_refused_after_pruning() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("slashing-protection");
+
+		let store = SlashingProtection::open(&path).unwrap();
+		for slot in 0..KEPT_RECORDS as u64 + 10 {
+			store.check_babe_block(ALICE, slot, b"block").unwrap();
+		}
+		drop(store);
+
+		let store = SlashingProtection::open(&path).unwrap();
+		assert!(matches!(
+			store.check_babe_block(ALICE, 9, b"block"),
+			Err(Error::TooOld { duty: Duty::BabeBlock, position: (0, 9) })
+		));
+		store.check_babe_block(ALICE, 10, b"block").unwrap();
+		assert!(store.check_babe_block(ALICE, 10, b"other").is_err());
+		store.check_babe_block(ALICE, KEPT_RECORDS as u64 + 10, b"block").unwrap();
+	}
+}
@@ -0,0 +1,336 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Clock health checking.
+//!
+//! Slot based consensus relies on the local clock to determine the current slot. A skewed clock
+//! makes the node author in the wrong slot, which at best leaks slots and at worst risks
+//! equivocation. [`ClockHealthMonitor`] periodically measures the offset of the local clock
+//! against NTP servers and exposes it through a shareable [`ClockHealth`] handle that the slot
+//! worker consults before authoring.
+
+use crate::LOG_TARGET;
+use log::{debug, warn};
+use parking_lot::RwLock;
+use std::{
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::net::{lookup_host, UdpSocket};
+
+/// Seconds between the NTP epoch (1900) and the unix epoch (1970).
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// Size of an (S)NTP packet without extensions.
+const NTP_PACKET_SIZE: usize = 48;
+
+/// Timeout of a single NTP query.
+const NTP_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configuration of the clock health checks.
+#[derive(Debug, Clone)]
+pub struct ClockHealthConfig {
+	/// NTP servers to query, as `host:port`.
+	pub ntp_servers: Vec<String>,
+	/// How often the clock offset is measured.
+	pub check_interval: Duration,
+	/// Fraction of the slot duration above which the offset is reported as a warning.
+	pub warn_fraction: f32,
+	/// Fraction of the slot duration above which the clock is considered unsafe for authoring.
+	pub unsafe_fraction: f32,
+	/// Whether authoring should be refused while the clock is unsafe.
+	pub refuse_authoring_when_unsafe: bool,
+}
+
+impl Default for ClockHealthConfig {
+	fn default() -> Self {
+		Self {
+			ntp_servers: vec!["pool.ntp.org:123".into()],
+			check_interval: Duration::from_secs(600),
+			warn_fraction: 0.1,
+			unsafe_fraction: 0.3,
+			refuse_authoring_when_unsafe: false,
+		}
+	}
+}
+
+/// Parameters of the clock health checks.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct ClockHealthParams {
+	/// NTP server the offset of the local clock is measured against, as `host:port`.
+	///
+	/// Can be given multiple times. Enables the clock health checks of the block authoring,
+	/// which warn when the local clock drifts by a significant fraction of the slot duration.
+	#[arg(long, value_name = "HOST:PORT")]
+	pub ntp_server: Vec<String>,
+
+	/// Refuse to author blocks while the local clock is off by more than 30% of the slot
+	/// duration.
+	#[arg(long, requires = "ntp_server")]
+	pub refuse_authoring_on_unsafe_clock: bool,
+}
+
+impl ClockHealthParams {
+	/// The configuration of the clock health checks, `None` if they are disabled.
+	pub fn config(&self) -> Option<ClockHealthConfig> {
+		(!self.ntp_server.is_empty()).then(|| ClockHealthConfig {
+			ntp_servers: self.ntp_server.clone(),
+			refuse_authoring_when_unsafe: self.refuse_authoring_on_unsafe_clock,
+			..Default::default()
+		})
+	}
+}
+
+/// The state of the local clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockStatus {
+	/// No measurement available yet.
+	Unknown,
+	/// The clock offset is within the configured bounds.
+	Healthy,
+	/// The clock offset is above the warning threshold.
+	Drifting,
+	/// The clock offset is above the unsafe threshold.
+	Unsafe,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Measurement {
+	/// Offset of the local clock in milliseconds, positive if the local clock is ahead.
+	offset_millis: i64,
+}
+
+/// Shareable handle to the latest clock measurement.
+#[derive(Clone)]
+pub struct ClockHealth {
+	config: Arc<ClockHealthConfig>,
+	slot_duration: Duration,
+	latest: Arc<RwLock<Option<Measurement>>>,
+}
+
+impl ClockHealth {
+	/// Create a new handle for a chain with the given `slot_duration`.
+	pub fn new(config: ClockHealthConfig, slot_duration: Duration) -> Self {
+		Self { config: Arc::new(config), slot_duration, latest: Arc::new(RwLock::new(None)) }
+	}
+
+	/// The latest measured offset of the local clock in milliseconds, positive if the local clock
+	/// is ahead.
+	pub fn offset_millis(&self) -> Option<i64> {
+		self.latest.read().map(|m| m.offset_millis)
+	}
+
+	/// The current status of the local clock.
+	pub fn status(&self) -> ClockStatus {
+		let Some(offset) = self.offset_millis() else { return ClockStatus::Unknown };
+		let offset = offset.unsigned_abs() as f32;
+		let slot_millis = self.slot_duration.as_millis() as f32;
+
+		if offset > slot_millis * self.config.unsafe_fraction {
+			ClockStatus::Unsafe
+		} else if offset > slot_millis * self.config.warn_fraction {
+			ClockStatus::Drifting
+		} else {
+			ClockStatus::Healthy
+		}
+	}
+
+	/// Whether the node may author a block according to the clock health.
+	pub fn authoring_allowed(&self) -> bool {
+		!(self.config.refuse_authoring_when_unsafe && self.status() == ClockStatus::Unsafe)
+	}
+
+	fn record(&self, offset_millis: i64) {
+		*self.latest.write() = Some(Measurement { offset_millis });
+
+		match self.status() {
+			ClockStatus::Unsafe => warn!(
+				target: LOG_TARGET,
+				"⏰ Local clock is off by {offset_millis}ms, which is unsafe for a slot duration of {}ms.{}",
+				self.slot_duration.as_millis(),
+				if self.config.refuse_authoring_when_unsafe {
+					" Authoring is suspended until the clock is fixed."
+				} else {
+					""
+				},
+			),
+			ClockStatus::Drifting => warn!(
+				target: LOG_TARGET,
+				"⏰ Local clock is off by {offset_millis}ms, check your NTP setup.",
+			),
+			ClockStatus::Healthy | ClockStatus::Unknown =>
+				debug!(target: LOG_TARGET, "Local clock offset: {offset_millis}ms"),
+		}
+	}
+}
+
+/// Background task measuring the clock offset.
+pub struct ClockHealthMonitor {
+	health: ClockHealth,
+}
+
+impl ClockHealthMonitor {
+	/// Create a new monitor feeding the given handle.
+	pub fn new(health: ClockHealth) -> Self {
+		Self { health }
+	}
+
+	/// Run the monitor.
+	pub async fn run(self) {
+		loop {
+			let mut offsets = Vec::new();
+			for server in &self.health.config.ntp_servers {
+				match query_ntp_offset(server).await {
+					Ok(offset) => offsets.push(offset),
+					Err(err) => debug!(target: LOG_TARGET, "NTP query to {server} failed: {err}"),
+				}
+			}
+
+			match median(offsets) {
+				Some(offset) => self.health.record(offset),
+				None => debug!(target: LOG_TARGET, "No NTP server could be reached"),
+			}
+
+			futures_timer::Delay::new(self.health.config.check_interval).await;
+		}
+	}
+}
+
+/// The median of the given offsets.
+fn median(mut offsets: Vec<i64>) -> Option<i64> {
+	if offsets.is_empty() {
+		return None
+	}
+	offsets.sort_unstable();
+	Some(offsets[offsets.len() / 2])
+}
+
+/// Milliseconds since the unix epoch.
+fn unix_millis(time: SystemTime) -> i64 {
+	match time.duration_since(UNIX_EPOCH) {
+		Ok(d) => d.as_millis() as i64,
+		Err(e) => -(e.duration().as_millis() as i64),
+	}
+}
+
+/// Convert an NTP timestamp (seconds and fraction since 1900) to milliseconds since the unix
+/// epoch.
+fn ntp_to_unix_millis(seconds: u32, fraction: u32) -> i64 {
+	let secs = seconds as i64 - NTP_UNIX_OFFSET_SECS as i64;
+	let millis = ((fraction as u64 * 1000) >> 32) as i64;
+	secs * 1000 + millis
+}
+
+/// Query a single NTP server using SNTP and return the local clock offset in milliseconds,
+/// positive if the local clock is ahead.
+async fn query_ntp_offset(server: &str) -> std::io::Result<i64> {
+	tokio::time::timeout(NTP_QUERY_TIMEOUT, query_ntp_offset_inner(server))
+		.await
+		.map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "NTP query timed out"))?
+}
+
+async fn query_ntp_offset_inner(server: &str) -> std::io::Result<i64> {
+	let addr = lookup_host(server).await?.next().ok_or_else(|| {
+		std::io::Error::new(std::io::ErrorKind::NotFound, "NTP server address not resolved")
+	})?;
+	let socket = UdpSocket::bind(if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }).await?;
+
+	let mut request = [0u8; NTP_PACKET_SIZE];
+	// LI = 0, VN = 3, Mode = 3 (client).
+	request[0] = 0x1B;
+
+	let sent_at = unix_millis(SystemTime::now());
+	socket.send_to(&request, addr).await?;
+
+	let mut response = [0u8; NTP_PACKET_SIZE];
+	let (len, _) = socket.recv_from(&mut response).await?;
+	let received_at = unix_millis(SystemTime::now());
+
+	if len < NTP_PACKET_SIZE {
+		return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "short NTP response"))
+	}
+
+	let read_u32 = |at: usize| {
+		u32::from_be_bytes([response[at], response[at + 1], response[at + 2], response[at + 3]])
+	};
+	let server_time = ntp_to_unix_millis(read_u32(40), read_u32(44));
+
+	Ok(offset_from(sent_at, received_at, server_time))
+}
+
+/// Local clock offset, assuming the server timestamp was taken halfway through the round trip.
+fn offset_from(sent_at: i64, received_at: i64, server_time: i64) -> i64 {
+	(sent_at + received_at) / 2 - server_time
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn health(refuse: bool) -> ClockHealth {
+		ClockHealth::new(
+			ClockHealthConfig { refuse_authoring_when_unsafe: refuse, ..Default::default() },
+			Duration::from_secs(6),
+		)
+	}
+
+	#[test]
+	fn ntp_timestamps_are_converted() {
+		assert_eq!(ntp_to_unix_millis(NTP_UNIX_OFFSET_SECS as u32, 0), 0);
+		assert_eq!(ntp_to_unix_millis(NTP_UNIX_OFFSET_SECS as u32 + 1, u32::MAX / 2 + 1), 1500);
+	}
+
+	#[test]
+	fn offset_is_computed_from_round_trip() {
+		assert_eq!(offset_from(1000, 1200, 1100), 0);
+		assert_eq!(offset_from(1000, 1200, 600), 500);
+		assert_eq!(offset_from(1000, 1200, 1600), -500);
+	}
+
+	#[test]
+	fn status_follows_thresholds() {
+		let health = health(true);
+		assert_eq!(health.status(), ClockStatus::Unknown);
+		assert!(health.authoring_allowed());
+
+		health.record(100);
+		assert_eq!(health.status(), ClockStatus::Healthy);
+
+		health.record(-1000);
+		assert_eq!(health.status(), ClockStatus::Drifting);
+		assert!(health.authoring_allowed());
+
+		health.record(2000);
+		assert_eq!(health.status(), ClockStatus::Unsafe);
+		assert!(!health.authoring_allowed());
+	}
+
+	#[test]
+	fn unsafe_clock_only_blocks_authoring_when_configured() {
+		let health = health(false);
+		health.record(2000);
+		assert_eq!(health.status(), ClockStatus::Unsafe);
+		assert!(health.authoring_allowed());
+	}
+
+	#[test]
+	fn median_works() {
+		assert_eq!(median(vec![]), None);
+		assert_eq!(median(vec![3, -1, 2]), Some(2));
+	}
+}
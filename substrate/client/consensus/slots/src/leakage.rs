@@ -0,0 +1,333 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Slot leakage analyzer.
+//!
+//! Correlates the slots this node was expected to author (successful slot claims) with the
+//! outcome of the local proposing attempt and with the blocks that ended up in the canonical
+//! chain. Every missed slot gets a [`MissCause`], so operators can tell whether they lose slots
+//! because of their clock, because the node is busy importing, or because their blocks don't
+//! make it through the network.
+//!
+//! The slot worker feeds the analyzer with claimed slots and authoring outcomes, while
+//! [`SlotLeakageAnalyzer::run`] follows finality and logs a report every given number of slots.
+
+use crate::LOG_TARGET;
+use futures::StreamExt;
+use log::{info, warn};
+use parking_lot::Mutex;
+use sc_client_api::BlockchainEvents;
+use sp_consensus_slots::Slot;
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+/// Maximum number of slots for which attempts are remembered.
+const MAX_TRACKED_SLOTS: usize = 4096;
+
+/// The outcome of a local authoring attempt for a claimed slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptOutcome {
+	/// A block was built and imported locally.
+	Authored,
+	/// Authoring was skipped because of the backoff strategy.
+	Backoff,
+	/// The proposer could not be created, usually because the parent is not imported yet.
+	ProposerUnavailable,
+	/// Proposing took longer than the time available in the slot.
+	ProposalTimeout,
+	/// Proposing failed.
+	ProposalFailed,
+	/// The proposed block failed to be imported locally.
+	ImportFailed,
+}
+
+/// Why a claimed slot did not result in a canonical block authored by this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MissCause {
+	/// The slot worker woke up too late into the slot, the local clock is likely drifting.
+	ClockDrift,
+	/// The node was busy importing blocks and could not build on top of the right parent in time.
+	ImportBacklog,
+	/// Building the block took longer than the slot allowed.
+	SlowProposal,
+	/// Authoring was deliberately skipped by the backoff strategy.
+	Backoff,
+	/// The block was authored, but did not become canonical. Usually a propagation problem.
+	Networking,
+}
+
+impl MissCause {
+	fn as_str(&self) -> &'static str {
+		match self {
+			Self::ClockDrift => "clock-drift",
+			Self::ImportBacklog => "import-backlog",
+			Self::SlowProposal => "slow-proposal",
+			Self::Backoff => "backoff",
+			Self::Networking => "networking",
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct Attempt {
+	/// How late into the slot the slot worker started.
+	lateness: Duration,
+	/// The slot duration.
+	slot_duration: Duration,
+	/// The outcome of the attempt, `None` while proposing.
+	outcome: Option<AttemptOutcome>,
+	/// The hash of the block authored in the slot, if any.
+	block: Option<Vec<u8>>,
+	/// Whether the slot ended up with our block in the canonical chain.
+	canonical: bool,
+}
+
+impl Attempt {
+	fn miss_cause(&self) -> Option<MissCause> {
+		if self.canonical {
+			return None
+		}
+
+		// Waking up in the second half of the slot is never caused by the proposer itself.
+		if self.lateness > self.slot_duration / 2 {
+			return Some(MissCause::ClockDrift)
+		}
+
+		match self.outcome? {
+			AttemptOutcome::Authored => Some(MissCause::Networking),
+			AttemptOutcome::Backoff => Some(MissCause::Backoff),
+			AttemptOutcome::ProposerUnavailable | AttemptOutcome::ImportFailed =>
+				Some(MissCause::ImportBacklog),
+			AttemptOutcome::ProposalTimeout | AttemptOutcome::ProposalFailed =>
+				Some(MissCause::SlowProposal),
+		}
+	}
+}
+
+/// Summary of the slots claimed within a reporting window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotLeakageReport {
+	/// Number of slots this node was expected to author.
+	pub claimed: u32,
+	/// Number of claimed slots that resulted in a canonical block authored by this node.
+	pub canonical: u32,
+	/// Missed slots, with their cause.
+	pub missed: BTreeMap<Slot, MissCause>,
+}
+
+impl SlotLeakageReport {
+	/// Number of missed slots per cause.
+	pub fn missed_by_cause(&self) -> BTreeMap<MissCause, u32> {
+		let mut by_cause = BTreeMap::new();
+		for cause in self.missed.values() {
+			*by_cause.entry(*cause).or_default() += 1;
+		}
+		by_cause
+	}
+}
+
+/// Shareable slot leakage analyzer.
+///
+/// The slot worker notes every claimed slot and the outcome of the authoring attempt, while
+/// finality tells which of those slots ended up with a canonical block authored by this node.
+/// [`SlotLeakageAnalyzer::take_report`] closes the current window, see
+/// [`SlotLeakageAnalyzer::run`].
+#[derive(Clone, Default)]
+pub struct SlotLeakageAnalyzer {
+	attempts: Arc<Mutex<BTreeMap<Slot, Attempt>>>,
+}
+
+impl SlotLeakageAnalyzer {
+	/// Create a new analyzer.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Note that `slot` was claimed by this node and that the slot worker started `lateness` after
+	/// the slot began.
+	pub fn note_claimed(&self, slot: Slot, lateness: Duration, slot_duration: Duration) {
+		let mut attempts = self.attempts.lock();
+		attempts.insert(
+			slot,
+			Attempt { lateness, slot_duration, outcome: None, block: None, canonical: false },
+		);
+
+		while attempts.len() > MAX_TRACKED_SLOTS {
+			attempts.pop_first();
+		}
+	}
+
+	/// Note the outcome of the local authoring attempt for a claimed `slot`.
+	pub fn note_outcome(&self, slot: Slot, outcome: AttemptOutcome) {
+		if let Some(attempt) = self.attempts.lock().get_mut(&slot) {
+			attempt.outcome = Some(outcome);
+		}
+	}
+
+	/// Note that the block with the given hash was authored and imported locally in `slot`.
+	pub fn note_authored(&self, slot: Slot, block: &[u8]) {
+		if let Some(attempt) = self.attempts.lock().get_mut(&slot) {
+			attempt.outcome = Some(AttemptOutcome::Authored);
+			attempt.block = Some(block.to_vec());
+		}
+	}
+
+	/// Note that the block with the given hash was finalized in `slot`.
+	///
+	/// The slot is only marked as canonical if the block is the one authored by this node.
+	pub fn note_finalized(&self, slot: Slot, block: &[u8]) {
+		if let Some(attempt) = self.attempts.lock().get_mut(&slot) {
+			if attempt.block.as_deref() == Some(block) {
+				attempt.canonical = true;
+			}
+		}
+	}
+
+	/// Follow the finalized blocks of `client` and log a report every `report_every` slots.
+	///
+	/// `slot_of` extracts the slot from a block header, usually from the consensus pre-runtime
+	/// digest. Only finalized slots are reported, as the fate of the other ones is not known yet.
+	pub async fn run<B, C>(
+		self,
+		client: Arc<C>,
+		slot_of: impl Fn(&B::Header) -> Option<Slot>,
+		report_every: u64,
+	) where
+		B: BlockT,
+		C: BlockchainEvents<B> + sp_blockchain::HeaderBackend<B>,
+	{
+		let mut finality_notifications = client.finality_notification_stream();
+		let mut last_report = None;
+
+		while let Some(notification) = finality_notifications.next().await {
+			let mut finalized_slot = None;
+			for hash in notification.tree_route.iter().chain(std::iter::once(&notification.hash)) {
+				let Ok(Some(header)) = client.header(*hash) else { continue };
+				if let Some(slot) = slot_of(&header) {
+					self.note_finalized(slot, hash.as_ref());
+					finalized_slot = Some(slot);
+				}
+			}
+
+			let Some(finalized_slot) = finalized_slot else { continue };
+			let last_report = last_report.get_or_insert(finalized_slot);
+			if *finalized_slot >= **last_report + report_every {
+				self.take_report(finalized_slot);
+				*last_report = finalized_slot;
+			}
+		}
+	}
+
+	/// Build the report for all claimed slots up to and including `until`, and forget about them.
+	///
+	/// The report is also logged.
+	pub fn take_report(&self, until: Slot) -> SlotLeakageReport {
+		let mut attempts = self.attempts.lock();
+		let remaining = attempts.split_off(&(until + 1));
+		let window = std::mem::replace(&mut *attempts, remaining);
+		drop(attempts);
+
+		let mut report = SlotLeakageReport::default();
+		for (slot, attempt) in window {
+			report.claimed += 1;
+			match attempt.miss_cause() {
+				None => report.canonical += 1,
+				Some(cause) => {
+					report.missed.insert(slot, cause);
+				},
+			}
+		}
+
+		if report.missed.is_empty() {
+			info!(
+				target: LOG_TARGET,
+				"🎰 Slot report: authored {}/{} claimed slots",
+				report.canonical,
+				report.claimed,
+			);
+		} else {
+			let causes = report
+				.missed_by_cause()
+				.into_iter()
+				.map(|(cause, count)| format!("{}: {count}", cause.as_str()))
+				.collect::<Vec<_>>()
+				.join(", ");
+			warn!(
+				target: LOG_TARGET,
+				"🎰 Slot report: authored {}/{} claimed slots, missed {} ({causes})",
+				report.canonical,
+				report.claimed,
+				report.missed.len(),
+			);
+		}
+
+		report
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SLOT_DURATION: Duration = Duration::from_secs(6);
+
+	#[test]
+	fn missed_slots_are_classified() {
+		let analyzer = SlotLeakageAnalyzer::new();
+
+		for slot in 1..=6u64 {
+			analyzer.note_claimed(slot.into(), Duration::from_millis(10), SLOT_DURATION);
+		}
+		analyzer.note_authored(1.into(), &[1]);
+		analyzer.note_finalized(1.into(), &[1]);
+		analyzer.note_authored(2.into(), &[2]);
+		// another author's block was finalized in slot 2.
+		analyzer.note_finalized(2.into(), &[0]);
+		analyzer.note_outcome(3.into(), AttemptOutcome::ProposalTimeout);
+		analyzer.note_outcome(4.into(), AttemptOutcome::ProposerUnavailable);
+		analyzer.note_outcome(5.into(), AttemptOutcome::Backoff);
+		// slot 6 is still in progress and not part of the report.
+
+		let report = analyzer.take_report(5.into());
+
+		assert_eq!(report.claimed, 5);
+		assert_eq!(report.canonical, 1);
+		assert_eq!(
+			report.missed.into_iter().collect::<Vec<_>>(),
+			vec![
+				(2.into(), MissCause::Networking),
+				(3.into(), MissCause::SlowProposal),
+				(4.into(), MissCause::ImportBacklog),
+				(5.into(), MissCause::Backoff),
+			],
+		);
+
+		assert_eq!(analyzer.take_report(6.into()).claimed, 1);
+	}
+
+	#[test]
+	fn late_wake_up_is_clock_drift() {
+		let analyzer = SlotLeakageAnalyzer::new();
+
+		analyzer.note_claimed(1.into(), Duration::from_secs(4), SLOT_DURATION);
+		analyzer.note_outcome(1.into(), AttemptOutcome::ProposalTimeout);
+
+		let report = analyzer.take_report(1.into());
+		assert_eq!(report.missed.get(&1.into()), Some(&MissCause::ClockDrift));
+	}
+}
@@ -26,9 +26,15 @@
 #![warn(missing_docs)]
 
 mod aux_schema;
+mod clock;
+mod leakage;
 mod slots;
 
 pub use aux_schema::{check_equivocation, MAX_SLOT_CAPACITY, PRUNING_BOUND};
+pub use clock::{
+	ClockHealth, ClockHealthConfig, ClockHealthMonitor, ClockHealthParams, ClockStatus,
+};
+pub use leakage::{AttemptOutcome, MissCause, SlotLeakageAnalyzer, SlotLeakageReport};
 use slots::Slots;
 pub use slots::{time_until_next_slot, SlotInfo};
 
@@ -178,6 +184,18 @@ pub trait SimpleSlotWorker<B: BlockT> {
 	/// Remaining duration for proposing.
 	fn proposing_remaining_duration(&self, slot_info: &SlotInfo<B>) -> Duration;
 
+	/// Returns the slot leakage analyzer that should be informed about claimed slots and the
+	/// outcome of every authoring attempt, if slot leakage analysis is enabled.
+	fn slot_leakage_analyzer(&self) -> Option<SlotLeakageAnalyzer> {
+		None
+	}
+
+	/// Returns the clock health handle consulted before authoring, if clock health checking is
+	/// enabled.
+	fn clock_health(&self) -> Option<ClockHealth> {
+		None
+	}
+
 	/// Propose a block by `Proposer`.
 	async fn propose(
 		&mut self,
@@ -348,9 +366,43 @@ pub trait SimpleSlotWorker<B: BlockT> {
 			return None
 		}
 
+		if let Some(clock_health) = self.clock_health() {
+			if !clock_health.authoring_allowed() {
+				warn!(
+					target: logging_target,
+					"⏰ Skipping slot {slot}: local clock is off by {}ms",
+					clock_health.offset_millis().unwrap_or_default(),
+				);
+				telemetry!(
+					telemetry;
+					CONSENSUS_WARN;
+					"slots.skipping_proposal_slot_unsafe_clock";
+					"slot" => *slot,
+					"offset_ms" => clock_health.offset_millis(),
+				);
+
+				return None
+			}
+		}
+
 		let claim = self.claim_slot(&slot_info.chain_head, slot, &aux_data).await?;
 
+		let leakage_analyzer = self.slot_leakage_analyzer();
+		let note_outcome = |outcome| {
+			if let Some(analyzer) = &leakage_analyzer {
+				analyzer.note_outcome(slot, outcome);
+			}
+		};
+		if let Some(analyzer) = &leakage_analyzer {
+			let slot_start = Duration::from_millis(
+				(slot_info.duration.as_millis() as u64).saturating_mul(*slot),
+			);
+			let lateness = slots::duration_now().saturating_sub(slot_start);
+			analyzer.note_claimed(slot, lateness, slot_info.duration);
+		}
+
 		if self.should_backoff(slot, &slot_info.chain_head) {
+			note_outcome(AttemptOutcome::Backoff);
 			return None
 		}
 
@@ -362,6 +414,7 @@ pub trait SimpleSlotWorker<B: BlockT> {
 			Ok(p) => p,
 			Err(err) => {
 				warn!(target: logging_target, "Unable to author block in slot {slot:?}: {err}");
+				note_outcome(AttemptOutcome::ProposerUnavailable);
 
 				telemetry!(
 					telemetry;
@@ -375,7 +428,15 @@ pub trait SimpleSlotWorker<B: BlockT> {
 			},
 		};
 
-		let proposal = self.propose(proposer, &claim, slot_info, end_proposing_at).await?;
+		let Some(proposal) = self.propose(proposer, &claim, slot_info, end_proposing_at).await
+		else {
+			note_outcome(if Instant::now() >= end_proposing_at {
+				AttemptOutcome::ProposalTimeout
+			} else {
+				AttemptOutcome::ProposalFailed
+			});
+			return None
+		};
 
 		let (block, storage_proof) = (proposal.block, proposal.proof);
 		let (header, body) = block.deconstruct();
@@ -397,6 +458,7 @@ pub trait SimpleSlotWorker<B: BlockT> {
 			Ok(bi) => bi,
 			Err(err) => {
 				warn!(target: logging_target, "Failed to create block import params: {}", err);
+				note_outcome(AttemptOutcome::ProposalFailed);
 
 				return None
 			},
@@ -427,12 +489,16 @@ pub trait SimpleSlotWorker<B: BlockT> {
 					*header.number(),
 					self.justification_sync_link(),
 				);
+				if let Some(analyzer) = &leakage_analyzer {
+					analyzer.note_authored(slot, header.hash().as_ref());
+				}
 			},
 			Err(err) => {
 				warn!(
 					target: logging_target,
 					"Error with block built on {:?}: {}", parent_hash, err,
 				);
+				note_outcome(AttemptOutcome::ImportFailed);
 
 				telemetry!(
 					telemetry;
@@ -499,6 +565,12 @@ impl_inherent_data_provider_ext_tuple!(S, A, B, C, D, E, F, G, H);
 impl_inherent_data_provider_ext_tuple!(S, A, B, C, D, E, F, G, H, I);
 impl_inherent_data_provider_ext_tuple!(S, A, B, C, D, E, F, G, H, I, J);
 
+impl<P: InherentDataProviderExt> InherentDataProviderExt for sc_consensus::RegisteredProviders<P> {
+	fn slot(&self) -> Slot {
+		self.base().slot()
+	}
+}
+
 /// Start a new slot worker.
 ///
 /// Every time a new slot is triggered, `worker.on_slot` is called and the future it returns is
@@ -810,7 +882,10 @@ impl<N> BackoffAuthoringBlocksStrategy<N> for () {
 mod test {
 	use super::*;
 	use sp_runtime::traits::NumberFor;
-	use std::time::{Duration, Instant};
+	use std::{
+		sync::Arc,
+		time::{Duration, Instant},
+	};
 	use substrate_test_runtime_client::runtime::{Block, Header};
 
 	const SLOT_DURATION: Duration = Duration::from_millis(6000);
@@ -905,6 +980,78 @@ mod test {
 		);
 	}
 
+	struct TestProvider {
+		identifier: sp_inherents::InherentIdentifier,
+		slot: Slot,
+	}
+
+	impl Deref for TestProvider {
+		type Target = Slot;
+
+		fn deref(&self) -> &Slot {
+			&self.slot
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl sp_inherents::InherentDataProvider for TestProvider {
+		async fn provide_inherent_data(
+			&self,
+			inherent_data: &mut sp_inherents::InherentData,
+		) -> Result<(), sp_inherents::Error> {
+			inherent_data.put_data(self.identifier, &self.slot)
+		}
+
+		async fn try_handle_error(
+			&self,
+			_: &sp_inherents::InherentIdentifier,
+			_: &[u8],
+		) -> Option<Result<(), sp_inherents::Error>> {
+			None
+		}
+	}
+
+	fn test_provider(
+		identifier: sp_inherents::InherentIdentifier,
+		slot: u64,
+	) -> impl CreateInherentDataProviders<Block, (), InherentDataProviders = TestProvider> {
+		move |_: <Block as BlockT>::Hash, ()| async move {
+			Ok::<_, Box<dyn std::error::Error + Send + Sync>>(TestProvider {
+				identifier,
+				slot: slot.into(),
+			})
+		}
+	}
+
+	#[test]
+	fn registered_providers_provide_the_slot_of_the_base() {
+		const SLOT: sp_inherents::InherentIdentifier = *b"slot____";
+		const REMOVED: sp_inherents::InherentIdentifier = *b"removed_";
+		const ADDED: sp_inherents::InherentIdentifier = *b"added___";
+
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let genesis_hash = client.chain_info().genesis_hash;
+		let spec_version = substrate_test_runtime_client::runtime::VERSION.spec_version;
+
+		let registry =
+			sc_consensus::InherentDataProviderRegistry::new(client, test_provider(SLOT, 42));
+		registry.register(..spec_version, test_provider(REMOVED, 1));
+		registry.register(spec_version.., test_provider(ADDED, 2));
+
+		let providers =
+			futures::executor::block_on(registry.create_inherent_data_providers(genesis_hash, ()))
+				.unwrap();
+		assert_eq!(providers.slot(), Slot::from(42));
+
+		let data = futures::executor::block_on(
+			sp_inherents::InherentDataProvider::create_inherent_data(&providers),
+		)
+		.unwrap();
+		assert_eq!(data.get_data::<Slot>(&SLOT).unwrap(), Some(Slot::from(42)));
+		assert_eq!(data.get_data::<Slot>(&REMOVED).unwrap(), None);
+		assert_eq!(data.get_data::<Slot>(&ADDED).unwrap(), Some(Slot::from(2)));
+	}
+
 	#[derive(PartialEq, Debug)]
 	struct HeadState {
 		head_number: NumberFor<Block>,
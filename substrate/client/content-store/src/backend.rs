@@ -0,0 +1,230 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Pinning backends storing and serving the blobs.
+
+use async_trait::async_trait;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::{
+	body::Bytes,
+	header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+	Method, Request,
+};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::{
+	client::legacy::{connect::HttpConnector, Client},
+	rt::TokioExecutor,
+};
+use sp_content_store::{Cid, MAX_BLOB_SIZE};
+use std::time::Duration;
+
+/// Timeout of a single request to a backend.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The boundary of the multipart bodies sent to the IPFS RPC API.
+const MULTIPART_BOUNDARY: &str = "content-store-blob";
+
+pub(crate) type HttpClient = Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+
+pub(crate) fn http_client() -> std::io::Result<HttpClient> {
+	let connector = HttpsConnectorBuilder::new()
+		.with_provider_and_native_roots(rustls::crypto::ring::default_provider())?
+		.https_or_http()
+		.enable_http1()
+		.build();
+	Ok(Client::builder(TokioExecutor::new()).build(connector))
+}
+
+/// A service storing, pinning or serving blobs.
+#[async_trait]
+pub trait PinningBackend: Send + Sync + 'static {
+	/// Human readable name of the backend, used in logs.
+	fn name(&self) -> &str;
+
+	/// Pin the blob `cid`.
+	///
+	/// `data` is the blob, if it was published by this node. Backends which can't store blobs
+	/// pin the CID only, relying on other nodes of the IPFS network providing the blob.
+	async fn pin(&self, cid: Cid, data: Option<&[u8]>) -> Result<(), String>;
+
+	/// Fetch the blob `cid`, if the backend can serve blobs.
+	///
+	/// The blob isn't verified yet.
+	async fn fetch(&self, cid: Cid) -> Result<Option<Vec<u8>>, String>;
+}
+
+/// Send a request and return the body of a successful response.
+async fn send(
+	client: &HttpClient,
+	request: hyper::http::request::Builder,
+	body: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+	let request = request.body(Full::new(Bytes::from(body))).map_err(|e| e.to_string())?;
+	let response = tokio::time::timeout(REQUEST_TIMEOUT, client.request(request))
+		.await
+		.map_err(|_| "Request timed out".to_string())?
+		.map_err(|e| e.to_string())?;
+
+	let status = response.status();
+	// Leave room for the JSON wrapping of responses.
+	let body = Limited::new(response.into_body(), 2 * MAX_BLOB_SIZE)
+		.collect()
+		.await
+		.map_err(|e| e.to_string())?
+		.to_bytes();
+	if status.is_success() {
+		Ok(body.to_vec())
+	} else {
+		Err(format!("Unexpected status {status}: {}", String::from_utf8_lossy(&body)))
+	}
+}
+
+/// The RPC API of an IPFS node, like Kubo.
+///
+/// Published blobs are added as raw blocks and pinned, other blobs are pinned by CID. Blobs are
+/// fetched from the IPFS network through the node.
+pub struct IpfsApiBackend {
+	client: HttpClient,
+	url: String,
+}
+
+impl IpfsApiBackend {
+	/// Create a backend using the RPC API at `url`, e.g. `http://127.0.0.1:5001`.
+	pub fn new(client: HttpClient, url: &str) -> Self {
+		Self { client, url: url.trim_end_matches('/').to_string() }
+	}
+
+	fn request(&self, path: &str) -> hyper::http::request::Builder {
+		Request::builder()
+			.method(Method::POST)
+			.uri(format!("{}/api/v0/{path}", self.url))
+	}
+}
+
+#[async_trait]
+impl PinningBackend for IpfsApiBackend {
+	fn name(&self) -> &str {
+		&self.url
+	}
+
+	async fn pin(&self, cid: Cid, data: Option<&[u8]>) -> Result<(), String> {
+		let Some(data) = data else {
+			send(&self.client, self.request(&format!("pin/add?arg={cid}")), Vec::new()).await?;
+			return Ok(());
+		};
+
+		let mut body = format!(
+			"--{MULTIPART_BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; \
+			 filename=\"blob\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+		)
+		.into_bytes();
+		body.extend_from_slice(data);
+		body.extend_from_slice(format!("\r\n--{MULTIPART_BOUNDARY}--\r\n").as_bytes());
+
+		let request = self
+			.request("block/put?cid-codec=raw&mhtype=sha2-256&pin=true")
+			.header(CONTENT_TYPE, format!("multipart/form-data; boundary={MULTIPART_BOUNDARY}"));
+		let response = send(&self.client, request, body).await?;
+
+		#[derive(serde::Deserialize)]
+		struct BlockPut {
+			#[serde(rename = "Key")]
+			key: String,
+		}
+		let response: BlockPut = serde_json::from_slice(&response).map_err(|e| e.to_string())?;
+		if response.key == cid.to_string() {
+			Ok(())
+		} else {
+			Err(format!("Blob was stored as {}", response.key))
+		}
+	}
+
+	async fn fetch(&self, cid: Cid) -> Result<Option<Vec<u8>>, String> {
+		send(&self.client, self.request(&format!("block/get?arg={cid}")), Vec::new())
+			.await
+			.map(Some)
+	}
+}
+
+/// A remote pinning service implementing the IPFS Pinning Service API.
+///
+/// Blobs are pinned by CID, the service fetches them from the IPFS network.
+pub struct PinningServiceBackend {
+	client: HttpClient,
+	endpoint: String,
+	token: String,
+}
+
+impl PinningServiceBackend {
+	/// Create a backend using the pinning service at `endpoint`, authenticated by `token`.
+	pub fn new(client: HttpClient, endpoint: &str, token: String) -> Self {
+		Self { client, endpoint: endpoint.trim_end_matches('/').to_string(), token }
+	}
+}
+
+#[async_trait]
+impl PinningBackend for PinningServiceBackend {
+	fn name(&self) -> &str {
+		&self.endpoint
+	}
+
+	async fn pin(&self, cid: Cid, _data: Option<&[u8]>) -> Result<(), String> {
+		let request = Request::builder()
+			.method(Method::POST)
+			.uri(format!("{}/pins", self.endpoint))
+			.header(AUTHORIZATION, format!("Bearer {}", self.token))
+			.header(CONTENT_TYPE, "application/json");
+		let body = serde_json::json!({ "cid": cid.to_string() }).to_string().into_bytes();
+		send(&self.client, request, body).await.map(drop)
+	}
+
+	async fn fetch(&self, _cid: Cid) -> Result<Option<Vec<u8>>, String> {
+		Ok(None)
+	}
+}
+
+/// A trustless IPFS HTTP gateway, only used to fetch blobs.
+pub struct GatewayBackend {
+	client: HttpClient,
+	url: String,
+}
+
+impl GatewayBackend {
+	/// Create a backend fetching from the gateway at `url`, e.g. `https://ipfs.io`.
+	pub fn new(client: HttpClient, url: &str) -> Self {
+		Self { client, url: url.trim_end_matches('/').to_string() }
+	}
+}
+
+#[async_trait]
+impl PinningBackend for GatewayBackend {
+	fn name(&self) -> &str {
+		&self.url
+	}
+
+	async fn pin(&self, _cid: Cid, _data: Option<&[u8]>) -> Result<(), String> {
+		Ok(())
+	}
+
+	async fn fetch(&self, cid: Cid) -> Result<Option<Vec<u8>>, String> {
+		let request = Request::builder()
+			.uri(format!("{}/ipfs/{cid}", self.url))
+			.header(ACCEPT, "application/vnd.ipld.raw");
+		send(&self.client, request, Vec::new()).await.map(Some)
+	}
+}
@@ -0,0 +1,309 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Content-addressed off-chain data availability.
+//!
+//! Publishes and fetches the blobs referenced on chain by their [`Cid`], using the configured
+//! [`PinningBackend`]s: the RPC API of an IPFS node, remote pinning services and IPFS gateways.
+//! Offchain workers access the store through the host functions of [`sp_content_store`], which
+//! requires registering a [`ContentStoreExt`] with the offchain workers:
+//!
+//! ```ignore
+//! let (service, store) = ContentStoreService::new(params.backends()?);
+//! task_manager.spawn_handle().spawn("content-store", None, service.run());
+//!
+//! OffchainWorkerOptions {
+//!     // ...
+//!     custom_extensions: move |_| {
+//!         vec![Box::new(ContentStoreExt::new(store.clone())) as Box<_>]
+//!     },
+//! }
+//! ```
+//!
+//! The executor of the node must include [`sp_content_store::HostFunctions`].
+//!
+//! Published blobs are pinned on all backends, retrying failed attempts with an exponential
+//! backoff. Blobs are fetched from the backends in the order they were configured, the first blob
+//! matching the CID is returned.
+
+mod backend;
+
+pub use backend::{GatewayBackend, IpfsApiBackend, PinningBackend, PinningServiceBackend};
+pub use sp_content_store::{Cid, ContentStoreExt, PublishResult};
+
+use clap::Args;
+use futures::{channel::oneshot, FutureExt, StreamExt};
+use sp_content_store::MAX_BLOB_SIZE;
+use std::{sync::Arc, time::Duration};
+
+const LOG_TARGET: &str = "content-store";
+
+/// The number of requests queued before new ones are rejected.
+const QUEUE_SIZE: usize = 1024;
+
+/// The number of requests processed concurrently.
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// The number of attempts to pin a blob on a backend.
+const PIN_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry of pinning a blob, doubled for every following retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Timeout of fetches without deadline.
+const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Parameters used to configure the content store.
+#[derive(Default, Debug, Clone, Args)]
+pub struct ContentStoreParams {
+	/// URL of the RPC API of an IPFS node storing, pinning and fetching blobs.
+	///
+	/// For example `http://127.0.0.1:5001`.
+	#[arg(long, value_name = "URL")]
+	pub content_store_ipfs_api: Option<String>,
+
+	/// Endpoint of a remote pinning service implementing the IPFS Pinning Service API.
+	#[arg(long, value_name = "URL", requires = "content_store_pinning_token")]
+	pub content_store_pinning_service: Option<String>,
+
+	/// Access token of the remote pinning service.
+	#[arg(long, value_name = "TOKEN")]
+	pub content_store_pinning_token: Option<String>,
+
+	/// URL of a trustless IPFS gateway blobs are fetched from, e.g. `https://ipfs.io`.
+	///
+	/// Can be given multiple times.
+	#[arg(long, value_name = "URL")]
+	pub content_store_gateway: Vec<String>,
+}
+
+impl ContentStoreParams {
+	/// The configured backends. The content store is disabled if there are none.
+	pub fn backends(&self) -> std::io::Result<Vec<Arc<dyn PinningBackend>>> {
+		let client = backend::http_client()?;
+		let mut backends: Vec<Arc<dyn PinningBackend>> = Vec::new();
+		if let Some(url) = &self.content_store_ipfs_api {
+			backends.push(Arc::new(IpfsApiBackend::new(client.clone(), url)));
+		}
+		if let (Some(endpoint), Some(token)) =
+			(&self.content_store_pinning_service, &self.content_store_pinning_token)
+		{
+			backends.push(Arc::new(PinningServiceBackend::new(
+				client.clone(),
+				endpoint,
+				token.clone(),
+			)));
+		}
+		for url in &self.content_store_gateway {
+			backends.push(Arc::new(GatewayBackend::new(client.clone(), url)));
+		}
+		Ok(backends)
+	}
+}
+
+/// A request to the content store service.
+enum Request {
+	Pin { cid: Cid, data: Option<Vec<u8>> },
+	Fetch { cid: Cid, response: oneshot::Sender<Option<Vec<u8>>> },
+}
+
+/// Handle used by offchain workers to access the content store.
+pub struct ContentStoreHandle {
+	requests: async_channel::Sender<Request>,
+}
+
+impl ContentStoreHandle {
+	fn queue(&self, request: Request) -> PublishResult {
+		match self.requests.try_send(request) {
+			Ok(()) => PublishResult::Queued,
+			Err(async_channel::TrySendError::Full(_)) => PublishResult::Full,
+			Err(async_channel::TrySendError::Closed(_)) => PublishResult::NotAvailable,
+		}
+	}
+}
+
+impl sp_content_store::ContentStore for ContentStoreHandle {
+	fn publish(&self, data: Vec<u8>) -> PublishResult {
+		if data.len() > MAX_BLOB_SIZE {
+			return PublishResult::TooLarge
+		}
+		self.queue(Request::Pin { cid: Cid::for_data(&data), data: Some(data) })
+	}
+
+	fn pin(&self, cid: Cid) -> PublishResult {
+		self.queue(Request::Pin { cid, data: None })
+	}
+
+	fn fetch(&self, cid: Cid, timeout: Option<Duration>) -> Option<Vec<u8>> {
+		let (response, receiver) = oneshot::channel();
+		self.requests.try_send(Request::Fetch { cid, response }).ok()?;
+
+		let timeout = futures_timer::Delay::new(timeout.unwrap_or(DEFAULT_FETCH_TIMEOUT));
+		futures::executor::block_on(async {
+			futures::select! {
+				data = receiver.fuse() => data.ok().flatten(),
+				_ = timeout.fuse() => None,
+			}
+		})
+	}
+}
+
+/// Processes the requests of the offchain workers.
+pub struct ContentStoreService {
+	backends: Vec<Arc<dyn PinningBackend>>,
+	requests: async_channel::Receiver<Request>,
+}
+
+impl ContentStoreService {
+	/// Create the service, together with the handle given to the offchain workers.
+	pub fn new(backends: Vec<Arc<dyn PinningBackend>>) -> (Self, Arc<ContentStoreHandle>) {
+		let (sender, requests) = async_channel::bounded(QUEUE_SIZE);
+		(Self { backends, requests }, Arc::new(ContentStoreHandle { requests: sender }))
+	}
+
+	/// Returns a never-ending `Future` that processes the requests.
+	pub async fn run(self) {
+		let this = &self;
+		self.requests
+			.clone()
+			.for_each_concurrent(MAX_CONCURRENT_REQUESTS, |request| async move {
+				match request {
+					Request::Pin { cid, data } => this.pin(cid, data.as_deref()).await,
+					Request::Fetch { cid, response } => {
+						// The offchain worker may have given up waiting already.
+						let _ = response.send(this.fetch(cid).await);
+					},
+				}
+			})
+			.await
+	}
+
+	/// Pin a blob on all backends.
+	async fn pin(&self, cid: Cid, data: Option<&[u8]>) {
+		futures::future::join_all(self.backends.iter().map(|backend| async move {
+			let mut delay = INITIAL_RETRY_DELAY;
+			for attempt in 1..=PIN_ATTEMPTS {
+				match backend.pin(cid, data).await {
+					Ok(()) => {
+						log::debug!(target: LOG_TARGET, "Pinned {cid} on {}", backend.name());
+						return
+					},
+					Err(e) if attempt < PIN_ATTEMPTS => {
+						log::debug!(
+							target: LOG_TARGET,
+							"Pinning {cid} on {} failed, retrying in {delay:?}: {e}",
+							backend.name(),
+						);
+						futures_timer::Delay::new(delay).await;
+						delay *= 2;
+					},
+					Err(e) => log::warn!(
+						target: LOG_TARGET,
+						"Failed to pin {cid} on {} after {attempt} attempts: {e}",
+						backend.name(),
+					),
+				}
+			}
+		}))
+		.await;
+	}
+
+	/// Fetch a blob from the first backend providing it.
+	async fn fetch(&self, cid: Cid) -> Option<Vec<u8>> {
+		for backend in &self.backends {
+			match backend.fetch(cid).await {
+				Ok(Some(data)) if cid.matches(&data) => return Some(data),
+				Ok(Some(_)) => log::warn!(
+					target: LOG_TARGET,
+					"{} returned a blob not matching {cid}",
+					backend.name(),
+				),
+				Ok(None) => {},
+				Err(e) => log::debug!(
+					target: LOG_TARGET,
+					"Failed to fetch {cid} from {}: {e}",
+					backend.name(),
+				),
+			}
+		}
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use async_trait::async_trait;
+	use sp_content_store::ContentStore;
+	use std::sync::Mutex;
+
+	/// Backend keeping blobs in memory.
+	#[derive(Default)]
+	struct MemoryBackend {
+		blobs: Mutex<Vec<(Cid, Vec<u8>)>>,
+	}
+
+	#[async_trait]
+	impl PinningBackend for MemoryBackend {
+		fn name(&self) -> &str {
+			"memory"
+		}
+
+		async fn pin(&self, cid: Cid, data: Option<&[u8]>) -> Result<(), String> {
+			let data = data.ok_or("Can only store published blobs")?;
+			self.blobs.lock().unwrap().push((cid, data.to_vec()));
+			Ok(())
+		}
+
+		async fn fetch(&self, cid: Cid) -> Result<Option<Vec<u8>>, String> {
+			let blobs = self.blobs.lock().unwrap();
+			Ok(blobs.iter().find(|(c, _)| *c == cid).map(|(_, data)| data.clone()))
+		}
+	}
+
+	#[test]
+	fn published_blobs_can_be_fetched() {
+		let backend = Arc::new(MemoryBackend::default());
+		let (service, store) = ContentStoreService::new(vec![backend.clone()]);
+		let runtime = tokio::runtime::Runtime::new().unwrap();
+		runtime.spawn(service.run());
+
+		assert_eq!(store.publish(b"blob".to_vec()), PublishResult::Queued);
+		assert_eq!(store.publish(vec![0; MAX_BLOB_SIZE + 1]), PublishResult::TooLarge);
+		let cid = Cid::for_data(b"blob");
+		// Wait until the blob was pinned.
+		while backend.blobs.lock().unwrap().is_empty() {
+			std::thread::sleep(Duration::from_millis(10));
+		}
+
+		assert_eq!(store.fetch(cid, None), Some(b"blob".to_vec()));
+		assert_eq!(store.fetch(Cid::for_data(b"unknown"), None), None);
+	}
+
+	#[test]
+	fn blobs_not_matching_the_cid_are_rejected() {
+		let backend = Arc::new(MemoryBackend::default());
+		let cid = Cid::for_data(b"blob");
+		backend.blobs.lock().unwrap().push((cid, b"forged".to_vec()));
+		let (service, store) = ContentStoreService::new(vec![backend]);
+		let runtime = tokio::runtime::Runtime::new().unwrap();
+		runtime.spawn(service.run());
+
+		assert_eq!(store.fetch(cid, Some(Duration::from_secs(5))), None);
+	}
+}
@@ -0,0 +1,299 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Crash reports.
+//!
+//! When a panic aborts the node, or an essential task panics, the [`CrashReporter`] writes a
+//! [`CrashReport`] as JSON file to the crash report directory, and optionally posts it to an
+//! endpoint, so operators can file actionable bug reports. The report contains the panic with its
+//! backtrace, the most recent log messages, the configuration of the node without secrets and
+//! paths, and the best and finalized block of the node.
+
+use clap::Args;
+use parking_lot::Mutex;
+use sc_service::Configuration;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::SpawnNamed;
+use sp_panic_handler::PanicReport;
+use sp_runtime::{traits::Block as BlockT, SaturatedConversion};
+use std::{
+	io,
+	path::PathBuf,
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const LOG_TARGET: &str = "crash-report";
+
+/// Timeout of posting a crash report.
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the chain position of the node is updated.
+const CHAIN_POSITION_PERIOD: Duration = Duration::from_secs(6);
+
+/// Parameters used to configure the crash reports.
+#[derive(Default, Debug, Clone, Args)]
+pub struct CrashReportParams {
+	/// Disable crash reports.
+	#[arg(long)]
+	pub no_crash_reports: bool,
+
+	/// Directory crash reports are written to.
+	///
+	/// Defaults to `crash-reports` in the base path.
+	#[arg(long, value_name = "PATH", conflicts_with = "no_crash_reports")]
+	pub crash_report_dir: Option<PathBuf>,
+
+	/// URL crash reports are posted to, in addition to being written to the crash report
+	/// directory.
+	#[arg(long, value_name = "URL", conflicts_with = "no_crash_reports")]
+	pub crash_report_url: Option<String>,
+}
+
+/// Report of a crash of the node.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+	/// Unix time of the crash, in milliseconds.
+	pub timestamp: u64,
+	/// The panic which crashed the node.
+	pub panic: Panic,
+	/// The chain position of the node shortly before the crash.
+	pub chain: Option<ChainPosition>,
+	/// The configuration of the node, without secrets and paths.
+	pub config: serde_json::Value,
+	/// The most recent log messages, oldest first.
+	///
+	/// Only collected if enabled with `LoggerBuilder::with_recent_logs`.
+	pub recent_logs: Vec<String>,
+}
+
+/// A panic, see [`PanicReport`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Panic {
+	/// The panic message.
+	pub message: String,
+	/// The source location of the panic, as `file:line`.
+	pub location: String,
+	/// The name of the panicking thread.
+	pub thread: String,
+	/// The backtrace of the panic.
+	pub backtrace: String,
+}
+
+impl From<&PanicReport> for Panic {
+	fn from(report: &PanicReport) -> Self {
+		Self {
+			message: report.message.clone(),
+			location: report.location.clone(),
+			thread: report.thread.clone(),
+			backtrace: report.backtrace.clone(),
+		}
+	}
+}
+
+/// The best and finalized block of the node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainPosition {
+	/// Number of the best block.
+	pub best_number: u64,
+	/// Hash of the best block.
+	pub best_hash: String,
+	/// Number of the last finalized block.
+	pub finalized_number: u64,
+	/// Hash of the last finalized block.
+	pub finalized_hash: String,
+}
+
+/// Writes and posts the crash reports of the node.
+pub struct CrashReporter {
+	dir: PathBuf,
+	url: Option<String>,
+	config: serde_json::Value,
+	chain: Arc<Mutex<Option<ChainPosition>>>,
+}
+
+impl CrashReporter {
+	/// Creates the crash reporter of the node with `config`, or `None` if disabled.
+	pub fn new(parameters: CrashReportParams, config: &Configuration) -> io::Result<Option<Self>> {
+		if parameters.no_crash_reports {
+			return Ok(None)
+		}
+		let dir = parameters
+			.crash_report_dir
+			.unwrap_or_else(|| config.base_path.path().join("crash-reports"));
+		std::fs::create_dir_all(&dir)?;
+
+		Ok(Some(CrashReporter {
+			dir,
+			url: parameters.crash_report_url,
+			config: sanitized_config(config),
+			chain: Arc::new(Mutex::new(None)),
+		}))
+	}
+
+	/// Installs the crash reporter as crash handler of the process.
+	///
+	/// Spawns a task tracking the chain position of `client`.
+	pub fn install<Block, Client>(self, client: Arc<Client>, spawner: &impl SpawnNamed)
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block> + 'static,
+	{
+		spawner.spawn(
+			"crash-report-chain-position",
+			Some("crash-report"),
+			Box::pin(track_chain_position(client, self.chain.clone())),
+		);
+		if !sp_panic_handler::set_crash_handler(move |panic| self.report(panic)) {
+			log::warn!(target: LOG_TARGET, "Crash handler already set, crash reports are disabled");
+		}
+	}
+
+	/// Writes and posts the report of the crash caused by `panic`.
+	fn report(&self, panic: &PanicReport) {
+		let report = CrashReport {
+			timestamp: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.unwrap_or_default()
+				.as_millis()
+				.saturated_into(),
+			panic: panic.into(),
+			chain: self.chain.try_lock_for(Duration::from_secs(1)).and_then(|chain| chain.clone()),
+			config: self.config.clone(),
+			recent_logs: sc_tracing::logging::recent_logs(),
+		};
+
+		match self.write(&report) {
+			Ok(path) =>
+				log::error!(target: LOG_TARGET, "Crash report written to {}", path.display()),
+			Err(e) => log::error!(target: LOG_TARGET, "Failed to write crash report: {e}"),
+		}
+		if let Some(url) = &self.url {
+			if let Err(e) = post(url, &report) {
+				log::error!(target: LOG_TARGET, "Failed to post crash report to {url}: {e}");
+			}
+		}
+	}
+
+	/// Writes `report` to the crash report directory, returning the path of the file.
+	fn write(&self, report: &CrashReport) -> io::Result<PathBuf> {
+		let path = self.dir.join(format!("crash-{}.json", report.timestamp));
+		std::fs::write(&path, serde_json::to_vec_pretty(report)?)?;
+		Ok(path)
+	}
+}
+
+/// The configuration of the node relevant for bug reports, without secrets and paths.
+fn sanitized_config(config: &Configuration) -> serde_json::Value {
+	serde_json::json!({
+		"implName": config.impl_name,
+		"implVersion": config.impl_version,
+		"chain": config.chain_spec.id(),
+		"role": format!("{:?}", config.role),
+		"database": config.database.to_string(),
+		"statePruning": config.state_pruning.as_ref().map(|pruning| format!("{pruning:?}")),
+		"blocksPruning": format!("{:?}", config.blocks_pruning),
+		"trieCacheMaximumSize": config.trie_cache_maximum_size,
+		"wasmMethod": format!("{:?}", config.executor.wasm_method),
+		"networkBackend": format!("{:?}", config.network.network_backend),
+		"rpcMethods": format!("{:?}", config.rpc.methods),
+		"offchainWorker": config.offchain_worker.enabled,
+	})
+}
+
+/// Posts `report` as JSON to `url`.
+///
+/// The post runs on its own thread, as the blocking HTTP client can't be used on the threads of
+/// the async runtime, where the node usually panics.
+fn post(url: &str, report: &CrashReport) -> Result<(), String> {
+	let url = url.to_owned();
+	let body = serde_json::to_vec(report).map_err(|e| e.to_string())?;
+	std::thread::spawn(move || {
+		reqwest::blocking::Client::builder()
+			.timeout(POST_TIMEOUT)
+			.build()
+			.and_then(|client| {
+				client
+					.post(&url)
+					.header(reqwest::header::CONTENT_TYPE, "application/json")
+					.body(body)
+					.send()
+			})
+			.and_then(|response| response.error_for_status())
+			.map(|_| ())
+			.map_err(|e| e.to_string())
+	})
+	.join()
+	.map_err(|_| "Crash report upload panicked".to_string())?
+}
+
+/// Keeps `chain` up to date with the chain position of `client`.
+async fn track_chain_position<Block: BlockT, Client: HeaderBackend<Block>>(
+	client: Arc<Client>,
+	chain: Arc<Mutex<Option<ChainPosition>>>,
+) {
+	loop {
+		let info = client.info();
+		*chain.lock() = Some(ChainPosition {
+			best_number: info.best_number.saturated_into(),
+			best_hash: array_bytes::bytes2hex("0x", info.best_hash.as_ref()),
+			finalized_number: info.finalized_number.saturated_into(),
+			finalized_hash: array_bytes::bytes2hex("0x", info.finalized_hash.as_ref()),
+		});
+		tokio::time::sleep(CHAIN_POSITION_PERIOD).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn crash_report_is_written() {
+		let dir = tempfile::tempdir().unwrap();
+		let reporter = CrashReporter {
+			dir: dir.path().to_path_buf(),
+			url: None,
+			config: serde_json::json!({ "implName": "test-node" }),
+			chain: Arc::new(Mutex::new(Some(ChainPosition {
+				best_number: 10,
+				best_hash: "0x01".into(),
+				finalized_number: 8,
+				finalized_hash: "0x02".into(),
+			}))),
+		};
+		reporter.report(&PanicReport {
+			message: "essential task failed".into(),
+			location: "src/lib.rs:1".into(),
+			thread: "tokio-runtime-worker".into(),
+			backtrace: String::new(),
+		});
+
+		let files = std::fs::read_dir(dir.path()).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(files.len(), 1);
+		let report: serde_json::Value =
+			serde_json::from_slice(&std::fs::read(files[0].path()).unwrap()).unwrap();
+		assert_eq!(report["panic"]["message"], "essential task failed");
+		assert_eq!(report["chain"]["bestNumber"], 10);
+		assert_eq!(report["config"]["implName"], "test-node");
+	}
+}
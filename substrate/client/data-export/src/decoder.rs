@@ -0,0 +1,314 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of events, extrinsics and storage entries into table rows.
+
+use crate::table::{field_values, Cell, Column, ColumnType};
+use codec::{Compact, Decode, Error};
+use frame_metadata::v15::{RuntimeMetadataV15, StorageEntryType};
+use sc_runtime_metadata::decode::decode_value;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef};
+use serde_json::Value;
+
+/// The extrinsic format version supported by the decoder.
+const EXTRINSIC_VERSION: u8 = 4;
+
+/// The kind of data stored in a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Kind {
+	Events,
+	Calls,
+	Storage,
+}
+
+impl Kind {
+	/// The directory the tables of this kind are stored in.
+	pub(crate) fn directory(self) -> &'static str {
+		match self {
+			Self::Events => "events",
+			Self::Calls => "calls",
+			Self::Storage => "storage",
+		}
+	}
+
+	/// The columns preceding the columns of the decoded fields.
+	fn base_columns(self) -> Vec<Column> {
+		let mut columns = vec![
+			Column::new("block_number", ColumnType::UInt),
+			Column::new("block_hash", ColumnType::Text),
+		];
+		columns.extend(match self {
+			Self::Events => vec![
+				Column::new("event_index", ColumnType::UInt),
+				Column::new("extrinsic_index", ColumnType::UInt),
+			],
+			Self::Calls => vec![
+				Column::new("extrinsic_index", ColumnType::UInt),
+				Column::new("signer", ColumnType::Text),
+			],
+			Self::Storage => vec![Column::new("key", ColumnType::Text)],
+		});
+		columns
+	}
+}
+
+/// A decoded row of the table of an event, call or storage entry.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Row {
+	pub(crate) kind: Kind,
+	pub(crate) pallet: String,
+	pub(crate) item: String,
+	pub(crate) columns: Vec<Column>,
+	pub(crate) cells: Vec<Cell>,
+}
+
+/// A storage entry selected for export.
+pub(crate) struct StorageEntry {
+	/// The prefix of all keys of the entry.
+	pub(crate) prefix: Vec<u8>,
+	pallet: String,
+	name: String,
+	value_ty: u32,
+}
+
+/// Decodes the data of blocks of one runtime.
+pub(crate) struct Decoder {
+	metadata: RuntimeMetadataV15,
+	events_ty: u32,
+}
+
+/// Splits a decoded enum value into the name of its variant and its fields.
+fn variant(value: Value) -> Option<(String, Value)> {
+	match value {
+		Value::String(name) => Some((name, Value::Null)),
+		Value::Object(object) if object.len() == 1 => object.into_iter().next(),
+		_ => None,
+	}
+}
+
+impl Decoder {
+	/// Create a decoder for the runtime described by `metadata`.
+	///
+	/// Returns `None` if the runtime has no `System::Events` storage.
+	pub(crate) fn new(metadata: RuntimeMetadataV15) -> Option<Self> {
+		let events_ty = metadata
+			.pallets
+			.iter()
+			.find(|pallet| pallet.name == "System")?
+			.storage
+			.as_ref()?
+			.entries
+			.iter()
+			.find(|entry| entry.name == "Events")
+			.and_then(|entry| match &entry.ty {
+				StorageEntryType::Plain(ty) => Some(ty.id),
+				StorageEntryType::Map { .. } => None,
+			})?;
+		Some(Self { metadata, events_ty })
+	}
+
+	fn registry(&self) -> &PortableRegistry {
+		&self.metadata.types
+	}
+
+	/// The fields of the variant `name` of the enum `ty`.
+	fn variant_fields(&self, ty: u32, name: &str) -> Option<&[Field<PortableForm>]> {
+		match &self.registry().resolve(ty)?.type_def {
+			TypeDef::Variant(variant) => variant
+				.variants
+				.iter()
+				.find(|variant| variant.name == name)
+				.map(|variant| &variant.fields[..]),
+			_ => None,
+		}
+	}
+
+	/// Build the row of an event or call of `pallet`.
+	///
+	/// `ty` is the enum type of the events or calls of the pallet and `value` the decoded value of
+	/// its variant.
+	fn row(
+		&self,
+		kind: Kind,
+		mut base: Vec<Cell>,
+		pallet: String,
+		ty: Option<u32>,
+		value: Value,
+	) -> Result<Row, Error> {
+		let (item, fields) = variant(value).ok_or("Invalid variant")?;
+		let variant_fields =
+			ty.and_then(|ty| self.variant_fields(ty, &item)).ok_or("Unknown variant")?;
+
+		let mut columns = kind.base_columns();
+		let field_columns = Column::of_fields(self.registry(), variant_fields);
+		base.extend(
+			field_columns
+				.iter()
+				.zip(field_values(variant_fields, fields))
+				.map(|(column, value)| Cell::from_value(column.ty, value)),
+		);
+		columns.extend(field_columns);
+		Ok(Row { kind, pallet, item, columns, cells: base })
+	}
+
+	/// Decode the value of the `System::Events` storage into one row per event.
+	pub(crate) fn events(&self, block: &[Cell], mut data: &[u8]) -> Result<Vec<Row>, Error> {
+		let Value::Array(records) = decode_value(self.registry(), self.events_ty, &mut data)?
+		else {
+			return Err("Events are not a sequence".into());
+		};
+
+		records
+			.into_iter()
+			.enumerate()
+			.map(|(index, mut record)| {
+				let extrinsic_index = record
+					.get("phase")
+					.and_then(|phase| phase.get("ApplyExtrinsic"))
+					.and_then(Value::as_u64)
+					.map_or(Cell::Null, Cell::UInt);
+				let event =
+					record.get_mut("event").map(Value::take).ok_or("Invalid event record")?;
+				let (pallet, event) = variant(event).ok_or("Invalid event")?;
+				let ty = self
+					.metadata
+					.pallets
+					.iter()
+					.find(|p| p.name == pallet)
+					.and_then(|p| p.event.as_ref())
+					.map(|event| event.ty.id);
+
+				let mut base = block.to_vec();
+				base.extend([Cell::UInt(index as u64), extrinsic_index]);
+				self.row(Kind::Events, base, pallet, ty, event)
+			})
+			.collect()
+	}
+
+	/// Decode an extrinsic with a length prefix into the row of its call.
+	pub(crate) fn extrinsic(
+		&self,
+		block: &[Cell],
+		index: u32,
+		mut data: &[u8],
+	) -> Result<Row, Error> {
+		let input = &mut data;
+		let _length = Compact::<u32>::decode(input)?;
+		let version = u8::decode(input)?;
+		if version & 0b0111_1111 != EXTRINSIC_VERSION {
+			return Err("Unsupported extrinsic version".into());
+		}
+
+		let extrinsic = &self.metadata.extrinsic;
+		let signer = if version & 0b1000_0000 != 0 {
+			let address = decode_value(self.registry(), extrinsic.address_ty.id, input)?;
+			decode_value(self.registry(), extrinsic.signature_ty.id, input)?;
+			decode_value(self.registry(), extrinsic.extra_ty.id, input)?;
+			Cell::from_value(ColumnType::Text, address)
+		} else {
+			Cell::Null
+		};
+		let call = decode_value(self.registry(), extrinsic.call_ty.id, input)?;
+		let (pallet, call) = variant(call).ok_or("Invalid call")?;
+		let ty = self
+			.metadata
+			.pallets
+			.iter()
+			.find(|p| p.name == pallet)
+			.and_then(|p| p.calls.as_ref())
+			.map(|calls| calls.ty.id);
+
+		let mut base = block.to_vec();
+		base.extend([Cell::UInt(index.into()), signer]);
+		self.row(Kind::Calls, base, pallet, ty, call)
+	}
+
+	/// Look up the storage entry `Pallet.Entry`.
+	pub(crate) fn storage_entry(&self, name: &str) -> Option<StorageEntry> {
+		let (pallet_name, entry_name) = name.split_once('.')?;
+		let storage = self
+			.metadata
+			.pallets
+			.iter()
+			.find(|pallet| pallet.name == pallet_name)?
+			.storage
+			.as_ref()?;
+		let entry = storage.entries.iter().find(|entry| entry.name == entry_name)?;
+
+		let mut prefix = sp_crypto_hashing::twox_128(storage.prefix.as_bytes()).to_vec();
+		prefix.extend(sp_crypto_hashing::twox_128(entry.name.as_bytes()));
+		let value_ty = match &entry.ty {
+			StorageEntryType::Plain(ty) => ty.id,
+			StorageEntryType::Map { value, .. } => value.id,
+		};
+		Some(StorageEntry { prefix, pallet: pallet_name.into(), name: entry_name.into(), value_ty })
+	}
+
+	/// Decode the value of `entry` stored under `key` into a row.
+	///
+	/// Values which are structs with named fields get one column per field, all other values are
+	/// stored in a single `value` column.
+	pub(crate) fn storage(
+		&self,
+		block: &[Cell],
+		entry: &StorageEntry,
+		key: &[u8],
+		mut data: &[u8],
+	) -> Result<Row, Error> {
+		let value = decode_value(self.registry(), entry.value_ty, &mut data)?;
+
+		let mut columns = Kind::Storage.base_columns();
+		let mut cells = block.to_vec();
+		cells.push(Cell::Text(array_bytes::bytes2hex("0x", &key[entry.prefix.len()..])));
+
+		let fields = match self.registry().resolve(entry.value_ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Composite(composite))
+				if !composite.fields.is_empty()
+					&& composite.fields.iter().all(|field| field.name.is_some()) =>
+			{
+				Some(&composite.fields[..])
+			},
+			_ => None,
+		};
+		match fields {
+			Some(fields) => {
+				let field_columns = Column::of_fields(self.registry(), fields);
+				cells.extend(
+					field_columns
+						.iter()
+						.zip(field_values(fields, value))
+						.map(|(column, value)| Cell::from_value(column.ty, value)),
+				);
+				columns.extend(field_columns);
+			},
+			None => {
+				let ty = ColumnType::of(self.registry(), entry.value_ty);
+				cells.push(Cell::from_value(ty, value));
+				columns.push(Column::new("value", ty));
+			},
+		}
+
+		Ok(Row {
+			kind: Kind::Storage,
+			pallet: entry.pallet.clone(),
+			item: entry.name.clone(),
+			columns,
+			cells,
+		})
+	}
+}
@@ -0,0 +1,296 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export of decoded chain data for offline analytics.
+//!
+//! The [`ExportDataCmd`] scans a range of blocks of an archive node and writes the decoded
+//! extrinsics, events and selected storage entries to CSV or Parquet files:
+//!
+//! - `calls/<Pallet>.<call>` has one row per extrinsic calling the call,
+//! - `events/<Pallet>.<Event>` has one row per emitted event,
+//! - `storage/<Pallet>.<Entry>` has one row per key of the storage entry, for every block at which
+//!   the storage is exported.
+//!
+//! Besides the block number, block hash and indices, every table has one column per field of the
+//! call, event or storage value, derived from the runtime metadata. Integers of up to 64 bits and
+//! booleans are stored as such, all other values as strings: hex for byte arrays, JSON for
+//! composites. If an upgrade changes the fields, the rows of the new runtime are written to a
+//! separate file suffixed with its spec version.
+
+mod decoder;
+mod table;
+mod writer;
+
+pub use writer::Format;
+
+use codec::Encode;
+use decoder::{Decoder, Kind, Row};
+use log::info;
+use sc_cli::{CliConfiguration, DatabaseParams, GenericNumber, PruningParams, SharedParams};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sc_runtime_metadata::RuntimeCache;
+use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor, One},
+	SaturatedConversion,
+};
+use std::{
+	collections::HashMap, fmt::Debug, marker::PhantomData, path::PathBuf, str::FromStr, sync::Arc,
+};
+use table::{Cell, Column};
+use writer::TableWriter;
+
+/// The `export-data` command used to export decoded chain data.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportDataCmd {
+	/// Directory the exported files are written to.
+	#[arg()]
+	pub output: PathBuf,
+
+	/// Specify starting block number.
+	/// Default is 1.
+	#[arg(long, value_name = "BLOCK")]
+	pub from: Option<GenericNumber>,
+
+	/// Specify last block number.
+	/// Default is best block.
+	#[arg(long, value_name = "BLOCK")]
+	pub to: Option<GenericNumber>,
+
+	/// Format of the exported files.
+	#[arg(long, value_enum, default_value_t = Format::Parquet)]
+	pub format: Format,
+
+	/// Storage entry to export, as `Pallet.Entry`, e.g. `System.Account`.
+	///
+	/// Can be given multiple times.
+	#[arg(long, value_name = "PALLET.ENTRY")]
+	pub storage: Vec<String>,
+
+	/// Export the selected storage entries every `N` blocks, counted from `--from`.
+	///
+	/// By default they are only exported at the last block of the range.
+	#[arg(long, value_name = "N")]
+	pub storage_interval: Option<u32>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportDataCmd {
+	/// Run the export-data command.
+	pub async fn run<B, BE, C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		B: BlockT,
+		BE: Backend<B>,
+		C: HeaderBackend<B>
+			+ BlockBackend<B>
+			+ StorageProvider<B, BE>
+			+ ProvideRuntimeApi<B>
+			+ CallApiAt<B>,
+		C::Api: Metadata<B>,
+		<NumberFor<B> as FromStr>::Err: Debug,
+	{
+		let from = self.from.as_ref().map(|f| f.parse()).transpose()?.unwrap_or_else(One::one);
+		let to = match &self.to {
+			Some(to) => to.parse()?,
+			None => client.info().best_number,
+		};
+		if from > to {
+			return Err(format!("Empty block range #{from}..=#{to}").into())
+		}
+
+		let mut exporter = Exporter::<B, BE, C> {
+			client: client.clone(),
+			output: self.output.clone(),
+			format: self.format,
+			decoder: RuntimeCache::default(),
+			tables: HashMap::new(),
+			_phantom: PhantomData,
+		};
+		let mut number = from;
+		loop {
+			let hash = client.hash(number)?.ok_or_else(|| format!("Block #{number} not found"))?;
+			let offset = (number - from).saturated_into::<u64>();
+			let export_storage = number == to
+				|| self
+					.storage_interval
+					.is_some_and(|interval| interval > 0 && offset % u64::from(interval) == 0);
+			exporter.export(number, hash, if export_storage { &self.storage } else { &[] })?;
+
+			if offset % 1000 == 999 {
+				info!("Exported block #{number}");
+			}
+			if number == to {
+				break
+			}
+			number += One::one();
+		}
+		exporter.finish()?;
+
+		info!("Exported blocks #{from}..=#{to} to {}", self.output.display());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportDataCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// A table being written.
+struct Table {
+	columns: Vec<Column>,
+	writer: Box<dyn TableWriter>,
+}
+
+/// Writes the decoded data of blocks to tables.
+struct Exporter<B, BE, C> {
+	client: Arc<C>,
+	output: PathBuf,
+	format: Format,
+	decoder: RuntimeCache<Arc<Decoder>>,
+	tables: HashMap<(Kind, String, String), Table>,
+	_phantom: PhantomData<fn() -> (B, BE)>,
+}
+
+impl<B, BE, C> Exporter<B, BE, C>
+where
+	B: BlockT,
+	BE: Backend<B>,
+	C: HeaderBackend<B>
+		+ BlockBackend<B>
+		+ StorageProvider<B, BE>
+		+ ProvideRuntimeApi<B>
+		+ CallApiAt<B>,
+	C::Api: Metadata<B>,
+{
+	/// Export the extrinsics, events and the given storage entries of the block `hash`.
+	fn export(
+		&mut self,
+		number: NumberFor<B>,
+		hash: B::Hash,
+		storage: &[String],
+	) -> sc_cli::Result<()> {
+		let (spec_version, decoder) = self.decoder(hash)?;
+		let block = [
+			Cell::UInt(number.saturated_into()),
+			Cell::Text(array_bytes::bytes2hex("0x", hash.as_ref())),
+		];
+
+		let extrinsics = self.client.block_body(hash)?.unwrap_or_default();
+		for (index, xt) in extrinsics.iter().enumerate() {
+			let row = decoder.extrinsic(&block, index as u32, &xt.encode()).map_err(|e| {
+				format!("Failed to decode extrinsic {index} of block #{number}: {e}")
+			})?;
+			self.write(spec_version, row)?;
+		}
+
+		let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+		key.extend(sp_crypto_hashing::twox_128(b"Events"));
+		if let Some(events) = self.client.storage(hash, &StorageKey(key))? {
+			let rows = decoder
+				.events(&block, &events.0)
+				.map_err(|e| format!("Failed to decode events of block #{number}: {e}"))?;
+			for row in rows {
+				self.write(spec_version, row)?;
+			}
+		}
+
+		for name in storage {
+			let entry = decoder
+				.storage_entry(name)
+				.ok_or_else(|| format!("Unknown storage entry `{name}` at block #{number}"))?;
+			let prefix = StorageKey(entry.prefix.clone());
+			for (key, value) in self.client.storage_pairs(hash, Some(&prefix), None)? {
+				let row = decoder
+					.storage(&block, &entry, &key.0, &value.0)
+					.map_err(|e| format!("Failed to decode `{name}` at block #{number}: {e}"))?;
+				self.write(spec_version, row)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Write a row to its table, creating the table if needed.
+	fn write(&mut self, spec_version: u32, row: Row) -> sc_cli::Result<()> {
+		let id = (row.kind, row.pallet.clone(), row.item.clone());
+		let table = match self.tables.remove(&id) {
+			Some(table) if table.columns == row.columns => table,
+			previous => {
+				let mut file_name = format!("{}.{}", row.pallet, row.item);
+				if let Some(previous) = previous {
+					previous.writer.finish()?;
+					file_name = format!("{file_name}.{spec_version}");
+				}
+				let directory = self.output.join(row.kind.directory());
+				std::fs::create_dir_all(&directory)?;
+				let path = directory.join(format!("{file_name}.{}", self.format.extension()));
+				Table {
+					columns: row.columns.clone(),
+					writer: self.format.create(&path, row.columns)?,
+				}
+			},
+		};
+		let table = self.tables.entry(id).or_insert(table);
+		table.writer.write(row.cells)?;
+		Ok(())
+	}
+
+	/// Flush and close all tables.
+	fn finish(self) -> sc_cli::Result<()> {
+		for table in self.tables.into_values() {
+			table.writer.finish()?;
+		}
+		Ok(())
+	}
+
+	/// The decoder of the runtime at `hash`.
+	fn decoder(&self, hash: B::Hash) -> sc_cli::Result<(u32, Arc<Decoder>)> {
+		let spec_version =
+			self.client.runtime_version_at(hash).map_err(|e| e.to_string())?.spec_version;
+		let decoder = self.decoder.get_or_try_insert_with(spec_version, || {
+			let metadata = sc_runtime_metadata::metadata_at::<B, _>(&*self.client, hash)?
+				.ok_or("Runtime doesn't provide metadata V15")?;
+			Decoder::new(metadata)
+				.map(Arc::new)
+				.ok_or_else(|| format!("Runtime version {spec_version} has no events storage"))
+		})?;
+		Ok((spec_version, decoder))
+	}
+}
@@ -0,0 +1,232 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tables of exported data, with columns derived from the runtime metadata.
+
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::Value;
+
+/// The type of the values of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColumnType {
+	/// Booleans.
+	Bool,
+	/// Signed integers of up to 64 bits.
+	Int,
+	/// Unsigned integers of up to 64 bits.
+	UInt,
+	/// Everything else, as strings. Composite values are JSON encoded.
+	Text,
+}
+
+impl ColumnType {
+	/// The column type of the type `ty` of the registry.
+	pub(crate) fn of(registry: &PortableRegistry, ty: u32) -> Self {
+		let Some(type_info) = registry.resolve(ty) else { return Self::Text };
+		match &type_info.type_def {
+			TypeDef::Primitive(primitive) => Self::of_primitive(primitive),
+			TypeDef::Compact(compact) => match registry.resolve(compact.type_param.id) {
+				Some(inner) => match &inner.type_def {
+					TypeDef::Primitive(primitive) => Self::of_primitive(primitive),
+					_ => Self::Text,
+				},
+				None => Self::Text,
+			},
+			// Newtypes are decoded as their inner value.
+			TypeDef::Composite(composite) => match &composite.fields[..] {
+				[field] if field.name.is_none() => Self::of(registry, field.ty.id),
+				_ => Self::Text,
+			},
+			_ => Self::Text,
+		}
+	}
+
+	fn of_primitive(primitive: &TypeDefPrimitive) -> Self {
+		match primitive {
+			TypeDefPrimitive::Bool => Self::Bool,
+			TypeDefPrimitive::U8
+			| TypeDefPrimitive::U16
+			| TypeDefPrimitive::U32
+			| TypeDefPrimitive::U64 => Self::UInt,
+			TypeDefPrimitive::I8
+			| TypeDefPrimitive::I16
+			| TypeDefPrimitive::I32
+			| TypeDefPrimitive::I64 => Self::Int,
+			_ => Self::Text,
+		}
+	}
+}
+
+/// A column of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Column {
+	pub(crate) name: String,
+	pub(crate) ty: ColumnType,
+}
+
+impl Column {
+	pub(crate) fn new(name: impl Into<String>, ty: ColumnType) -> Self {
+		Self { name: name.into(), ty }
+	}
+
+	/// The columns of the fields of a struct or enum variant.
+	///
+	/// Unnamed fields are called `field_<index>`.
+	pub(crate) fn of_fields(
+		registry: &PortableRegistry,
+		fields: &[Field<PortableForm>],
+	) -> Vec<Self> {
+		fields
+			.iter()
+			.enumerate()
+			.map(|(index, field)| {
+				let name = field.name.clone().unwrap_or_else(|| format!("field_{index}"));
+				Self::new(name, ColumnType::of(registry, field.ty.id))
+			})
+			.collect()
+	}
+}
+
+/// A value of a row.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Cell {
+	Null,
+	Bool(bool),
+	Int(i64),
+	UInt(u64),
+	Text(String),
+}
+
+impl Cell {
+	/// Convert a decoded value into a cell of a column of type `ty`.
+	///
+	/// Values which don't fit the column become [`Cell::Null`].
+	pub(crate) fn from_value(ty: ColumnType, value: Value) -> Self {
+		match (ty, value) {
+			(_, Value::Null) => Self::Null,
+			(ColumnType::Bool, Value::Bool(value)) => Self::Bool(value),
+			(ColumnType::Int, Value::Number(value)) => value.as_i64().map_or(Self::Null, Self::Int),
+			(ColumnType::UInt, Value::Number(value)) =>
+				value.as_u64().map_or(Self::Null, Self::UInt),
+			(ColumnType::Text, Value::String(value)) => Self::Text(value),
+			(ColumnType::Text, value) => Self::Text(value.to_string()),
+			_ => Self::Null,
+		}
+	}
+
+	/// The cell formatted for CSV files, where `NULL` is an empty string.
+	pub(crate) fn to_csv(&self) -> String {
+		match self {
+			Self::Null => String::new(),
+			Self::Bool(value) => value.to_string(),
+			Self::Int(value) => value.to_string(),
+			Self::UInt(value) => value.to_string(),
+			Self::Text(value) => value.clone(),
+		}
+	}
+}
+
+/// Split the decoded value of the fields `fields` into one value per field.
+///
+/// This is the inverse of how [`sc_runtime_metadata::decode`] represents fields: named fields are
+/// objects, a single unnamed field is the value itself and other unnamed fields are arrays.
+pub(crate) fn field_values(fields: &[Field<PortableForm>], value: Value) -> Vec<Value> {
+	match (fields, value) {
+		([], _) => Vec::new(),
+		([field], value) if field.name.is_none() => vec![value],
+		(_, Value::Object(mut object)) => fields
+			.iter()
+			.map(|field| {
+				field.name.as_ref().and_then(|name| object.remove(name)).unwrap_or(Value::Null)
+			})
+			.collect(),
+		(_, Value::Array(values)) => {
+			let mut values = values.into_iter();
+			fields.iter().map(|_| values.next().unwrap_or(Value::Null)).collect()
+		},
+		_ => fields.iter().map(|_| Value::Null).collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, Registry, TypeInfo};
+	use serde_json::json;
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct Balance(u128);
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct Transfer {
+		from: [u8; 32],
+		#[codec(compact)]
+		amount: u64,
+		keep_alive: bool,
+		fee: Balance,
+	}
+
+	fn transfer_fields() -> (PortableRegistry, Vec<Field<PortableForm>>) {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Transfer>()).id;
+		let registry: PortableRegistry = registry.into();
+		let TypeDef::Composite(composite) = &registry.resolve(ty).unwrap().type_def else {
+			panic!("`Transfer` is a composite")
+		};
+		let fields = composite.fields.clone();
+		(registry, fields)
+	}
+
+	#[test]
+	fn columns_are_derived_from_fields() {
+		let (registry, fields) = transfer_fields();
+		assert_eq!(
+			Column::of_fields(&registry, &fields),
+			vec![
+				Column::new("from", ColumnType::Text),
+				Column::new("amount", ColumnType::UInt),
+				Column::new("keep_alive", ColumnType::Bool),
+				Column::new("fee", ColumnType::Text),
+			],
+		);
+	}
+
+	#[test]
+	fn field_values_are_split() {
+		let (_, fields) = transfer_fields();
+		let values = field_values(
+			&fields,
+			json!({ "from": "0x00", "amount": 5, "keep_alive": true, "fee": "7" }),
+		);
+		assert_eq!(values, vec![json!("0x00"), json!(5), json!(true), json!("7")]);
+	}
+
+	#[test]
+	fn cells_match_column_types() {
+		assert_eq!(Cell::from_value(ColumnType::UInt, json!(5)), Cell::UInt(5));
+		assert_eq!(Cell::from_value(ColumnType::Int, json!(-5)), Cell::Int(-5));
+		assert_eq!(Cell::from_value(ColumnType::UInt, json!("5")), Cell::Null);
+		assert_eq!(
+			Cell::from_value(ColumnType::Text, json!({ "a": 1 })),
+			Cell::Text("{\"a\":1}".into())
+		);
+		assert_eq!(Cell::Null.to_csv(), "");
+	}
+}
@@ -0,0 +1,262 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Writers of the exported tables.
+
+use crate::table::{Cell, Column, ColumnType};
+use clap::ValueEnum;
+use parquet::{
+	basic::{LogicalType, Repetition, Type as PhysicalType},
+	data_type::{BoolType, ByteArray, ByteArrayType, Int64Type},
+	file::{properties::WriterProperties, writer::SerializedFileWriter},
+	schema::types::Type,
+};
+use std::{fs::File, path::Path, sync::Arc};
+
+/// The number of rows of a Parquet row group.
+const ROW_GROUP_SIZE: usize = 8192;
+
+/// The format of the exported files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+	/// Comma separated values, with a header row.
+	Csv,
+	/// Apache Parquet.
+	Parquet,
+}
+
+impl Format {
+	/// The file extension of the format.
+	pub(crate) fn extension(self) -> &'static str {
+		match self {
+			Self::Csv => "csv",
+			Self::Parquet => "parquet",
+		}
+	}
+
+	/// Create a file at `path` storing rows of the given `columns`.
+	pub(crate) fn create(
+		self,
+		path: &Path,
+		columns: Vec<Column>,
+	) -> Result<Box<dyn TableWriter>, String> {
+		match self {
+			Self::Csv => Ok(Box::new(CsvWriter::new(path, &columns)?)),
+			Self::Parquet => Ok(Box::new(ParquetWriter::new(path, columns)?)),
+		}
+	}
+}
+
+/// A writer of the rows of a table.
+pub(crate) trait TableWriter {
+	/// Write a row, with one cell per column.
+	fn write(&mut self, row: Vec<Cell>) -> Result<(), String>;
+
+	/// Flush the remaining rows and close the file.
+	fn finish(self: Box<Self>) -> Result<(), String>;
+}
+
+struct CsvWriter {
+	writer: csv::Writer<File>,
+}
+
+impl CsvWriter {
+	fn new(path: &Path, columns: &[Column]) -> Result<Self, String> {
+		let mut writer = csv::Writer::from_path(path).map_err(|e| e.to_string())?;
+		writer
+			.write_record(columns.iter().map(|column| &column.name))
+			.map_err(|e| e.to_string())?;
+		Ok(Self { writer })
+	}
+}
+
+impl TableWriter for CsvWriter {
+	fn write(&mut self, row: Vec<Cell>) -> Result<(), String> {
+		self.writer
+			.write_record(row.iter().map(Cell::to_csv))
+			.map_err(|e| e.to_string())
+	}
+
+	fn finish(mut self: Box<Self>) -> Result<(), String> {
+		self.writer.flush().map_err(|e| e.to_string())
+	}
+}
+
+struct ParquetWriter {
+	writer: SerializedFileWriter<File>,
+	columns: Vec<Column>,
+	rows: Vec<Vec<Cell>>,
+}
+
+impl ParquetWriter {
+	fn new(path: &Path, columns: Vec<Column>) -> Result<Self, String> {
+		let fields = columns
+			.iter()
+			.map(|column| {
+				let (physical, logical) = match column.ty {
+					ColumnType::Bool => (PhysicalType::BOOLEAN, None),
+					ColumnType::Int => (
+						PhysicalType::INT64,
+						Some(LogicalType::Integer { bit_width: 64, is_signed: true }),
+					),
+					ColumnType::UInt => (
+						PhysicalType::INT64,
+						Some(LogicalType::Integer { bit_width: 64, is_signed: false }),
+					),
+					ColumnType::Text => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+				};
+				Type::primitive_type_builder(&column.name, physical)
+					.with_repetition(Repetition::OPTIONAL)
+					.with_logical_type(logical)
+					.build()
+					.map(Arc::new)
+			})
+			.collect::<Result<_, _>>()
+			.map_err(|e| e.to_string())?;
+		let schema = Type::group_type_builder("schema")
+			.with_fields(fields)
+			.build()
+			.map_err(|e| e.to_string())?;
+
+		let file = File::create(path).map_err(|e| e.to_string())?;
+		let properties = WriterProperties::builder()
+			.set_compression(parquet::basic::Compression::SNAPPY)
+			.build();
+		let writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))
+			.map_err(|e| e.to_string())?;
+		Ok(Self { writer, columns, rows: Vec::with_capacity(ROW_GROUP_SIZE) })
+	}
+
+	/// Write the buffered rows as a row group.
+	fn flush(&mut self) -> Result<(), parquet::errors::ParquetError> {
+		if self.rows.is_empty() {
+			return Ok(())
+		}
+
+		let mut row_group = self.writer.next_row_group()?;
+		let mut index = 0;
+		while let Some(mut column) = row_group.next_column()? {
+			let cells = self.rows.iter().map(|row| &row[index]);
+			let levels: Vec<i16> = cells.clone().map(|cell| (*cell != Cell::Null) as i16).collect();
+			match self.columns[index].ty {
+				ColumnType::Bool => {
+					let values: Vec<_> = cells
+						.filter_map(|cell| match cell {
+							Cell::Bool(value) => Some(*value),
+							_ => None,
+						})
+						.collect();
+					column.typed::<BoolType>().write_batch(&values, Some(&levels), None)?;
+				},
+				ColumnType::Int | ColumnType::UInt => {
+					let values: Vec<_> = cells
+						.filter_map(|cell| match cell {
+							Cell::Int(value) => Some(*value),
+							// Unsigned integers are stored with the same bits, as mandated by
+							// the `UINT_64` logical type.
+							Cell::UInt(value) => Some(*value as i64),
+							_ => None,
+						})
+						.collect();
+					column.typed::<Int64Type>().write_batch(&values, Some(&levels), None)?;
+				},
+				ColumnType::Text => {
+					let values: Vec<_> = cells
+						.filter_map(|cell| match cell {
+							Cell::Text(value) => Some(ByteArray::from(value.as_str())),
+							_ => None,
+						})
+						.collect();
+					column.typed::<ByteArrayType>().write_batch(&values, Some(&levels), None)?;
+				},
+			}
+			column.close()?;
+			index += 1;
+		}
+		row_group.close()?;
+		self.rows.clear();
+		Ok(())
+	}
+}
+
+impl TableWriter for ParquetWriter {
+	fn write(&mut self, row: Vec<Cell>) -> Result<(), String> {
+		self.rows.push(row);
+		if self.rows.len() >= ROW_GROUP_SIZE {
+			self.flush().map_err(|e| e.to_string())?;
+		}
+		Ok(())
+	}
+
+	fn finish(mut self: Box<Self>) -> Result<(), String> {
+		self.flush().map_err(|e| e.to_string())?;
+		self.writer.close().map(drop).map_err(|e| e.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn csv_writer_works() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("table.csv");
+		let mut writer = Format::Csv
+			.create(
+				&path,
+				vec![
+					Column::new("number", ColumnType::UInt),
+					Column::new("name", ColumnType::Text),
+				],
+			)
+			.unwrap();
+		writer.write(vec![Cell::UInt(1), Cell::Text("a,b".into())]).unwrap();
+		writer.write(vec![Cell::UInt(2), Cell::Null]).unwrap();
+		writer.finish().unwrap();
+
+		assert_eq!(std::fs::read_to_string(path).unwrap(), "number,name\n1,\"a,b\"\n2,\n");
+	}
+
+	#[test]
+	fn parquet_writer_works() {
+		use parquet::file::reader::{FileReader, SerializedFileReader};
+
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("table.parquet");
+		let mut writer = Format::Parquet
+			.create(
+				&path,
+				vec![
+					Column::new("number", ColumnType::UInt),
+					Column::new("success", ColumnType::Bool),
+					Column::new("name", ColumnType::Text),
+				],
+			)
+			.unwrap();
+		writer
+			.write(vec![Cell::UInt(u64::MAX), Cell::Bool(true), Cell::Text("a".into())])
+			.unwrap();
+		writer.write(vec![Cell::UInt(2), Cell::Null, Cell::Null]).unwrap();
+		writer.finish().unwrap();
+
+		let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+		assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+		assert_eq!(reader.metadata().file_metadata().schema_descr().num_columns(), 3);
+	}
+}
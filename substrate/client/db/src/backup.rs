@@ -0,0 +1,238 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Consistent backups of the database taken while the node runs.
+//!
+//! The backup quiesces the writes by holding the import lock and pausing the writes to the
+//! offchain storage. It then commits a consistency marker and creates a checkpoint of the
+//! database, as RocksDB checkpoints do: the table files, which RocksDB never modifies once
+//! written, are hard-linked and the few other files, like the manifest and the write-ahead log,
+//! are copied. The writes resume before the backup hook runs, so it can copy the checkpoint
+//! elsewhere without blocking the import of blocks.
+//!
+//! ParityDB updates its tables in place and applies the commits in the background, so it can't be
+//! checkpointed by linking or copying its files. Without a snapshot directory, the hook runs while
+//! the writes are quiesced instead, to take an instant snapshot of the file system holding the
+//! database.
+
+use crate::{columns, utils::meta_keys, Backend};
+use codec::{Decode, Encode};
+use log::info;
+use sc_client_api::{BackupBackend, BackupMarker, BackupReport, BackupSettings};
+use sp_blockchain::{Error as ClientError, HeaderBackend, Result as ClientResult};
+use sp_database::Transaction;
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	process::Command,
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Extensions of the database files that are never modified once written.
+const IMMUTABLE_EXTENSIONS: &[&str] = &["sst", "blob"];
+
+impl<Block: BlockT> BackupBackend<Block> for Backend<Block> {
+	fn backup(&self, settings: &BackupSettings) -> ClientResult<BackupReport<Block>> {
+		let database_paths =
+			self.database_paths.iter().filter(|path| path.exists()).collect::<Vec<_>>();
+		if settings.snapshot_dir.is_some() {
+			if let Some(path) = database_paths.iter().find(|path| !is_rocksdb(path)) {
+				return Err(ClientError::Backend(format!(
+					"Snapshots are only supported for RocksDB, {} is not a RocksDB database",
+					path.display()
+				)))
+			}
+		}
+
+		// Quiesce the writes: no block is imported or finalized and no offchain value is written.
+		let import_lock = self.import_lock.write();
+		let offchain_writes = self.offchain_storage.pause_writes();
+		let quiesced = Instant::now();
+
+		let info = self.blockchain.info();
+		let marker = BackupMarker {
+			best_hash: info.best_hash,
+			best_number: info.best_number,
+			finalized_hash: info.finalized_hash,
+			finalized_number: info.finalized_number,
+			timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+		};
+		let mut transaction = Transaction::new();
+		transaction.set_from_vec(columns::META, meta_keys::BACKUP_MARKER, marker.encode());
+		self.storage.db.commit(transaction)?;
+
+		let snapshot = match &settings.snapshot_dir {
+			Some(dir) => {
+				let snapshot = dir.join(format!("{}-{}", marker.best_number, marker.timestamp));
+				for path in &database_paths {
+					snapshot_directory(path, &snapshot.join(snapshot_name(path))).map_err(|e| {
+						ClientError::Backend(format!(
+							"Failed to snapshot {} to {}: {e}",
+							path.display(),
+							snapshot.display()
+						))
+					})?;
+				}
+				Some(snapshot)
+			},
+			None => None,
+		};
+		let database_path = database_paths.first().copied();
+		if let (Some(hook), None) = (&settings.hook, &snapshot) {
+			run_hook(hook, &marker, database_path, None)?;
+		}
+
+		drop(offchain_writes);
+		drop(import_lock);
+		let duration = quiesced.elapsed();
+
+		if let (Some(hook), Some(snapshot)) = (&settings.hook, &snapshot) {
+			run_hook(hook, &marker, database_path, Some(snapshot))?;
+		}
+
+		info!(
+			target: "db",
+			"💾 Backup at best #{} ({}), finalized #{} ({}), writes quiesced for {duration:.2?}",
+			marker.best_number,
+			marker.best_hash,
+			marker.finalized_number,
+			marker.finalized_hash,
+		);
+		Ok(BackupReport { marker, snapshot, duration })
+	}
+
+	fn last_backup(&self) -> ClientResult<Option<BackupMarker<Block>>> {
+		self.storage
+			.db
+			.get(columns::META, meta_keys::BACKUP_MARKER)
+			.map(|marker| BackupMarker::decode(&mut &marker[..]))
+			.transpose()
+			.map_err(|e| ClientError::Backend(format!("Invalid backup marker: {e}")))
+	}
+}
+
+/// Run the backup `hook`, passing it the backup in its environment.
+fn run_hook<Block: BlockT>(
+	hook: &Path,
+	marker: &BackupMarker<Block>,
+	database_path: Option<&PathBuf>,
+	snapshot: Option<&PathBuf>,
+) -> ClientResult<()> {
+	let mut command = Command::new(hook);
+	command
+		.env("BACKUP_BEST_HASH", format!("{:?}", marker.best_hash))
+		.env("BACKUP_BEST_NUMBER", marker.best_number.to_string())
+		.env("BACKUP_FINALIZED_HASH", format!("{:?}", marker.finalized_hash))
+		.env("BACKUP_FINALIZED_NUMBER", marker.finalized_number.to_string())
+		.env("BACKUP_TIMESTAMP", marker.timestamp.to_string());
+	if let Some(path) = database_path {
+		command.env("BACKUP_DATABASE_PATH", path);
+	}
+	if let Some(snapshot) = snapshot {
+		command.env("BACKUP_SNAPSHOT_PATH", snapshot);
+	}
+	let status = command
+		.status()
+		.map_err(|e| ClientError::Backend(format!("Failed to run the backup hook: {e}")))?;
+	if !status.success() {
+		return Err(ClientError::Backend(format!("Backup hook failed: {status}")))
+	}
+	Ok(())
+}
+
+/// Whether the database at `path` is a RocksDB database, which has a `CURRENT` file naming its
+/// manifest.
+fn is_rocksdb(path: &Path) -> bool {
+	path.join("CURRENT").is_file()
+}
+
+/// The name of the snapshot of the database at `path`: its last two components, as in
+/// `paritydb/full`, to tell the databases of [`crate::DatabaseSource::Auto`] apart.
+fn snapshot_name(path: &Path) -> PathBuf {
+	path.components().rev().take(2).collect::<Vec<_>>().into_iter().rev().collect()
+}
+
+/// Create the snapshot of the directory `source` at `target`.
+fn snapshot_directory(source: &Path, target: &Path) -> io::Result<()> {
+	fs::create_dir_all(target)?;
+	for entry in fs::read_dir(source)? {
+		let entry = entry?;
+		let path = entry.path();
+		let target = target.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			snapshot_directory(&path, &target)?;
+			continue
+		}
+		let immutable = path
+			.extension()
+			.and_then(|extension| extension.to_str())
+			.is_some_and(|extension| IMMUTABLE_EXTENSIONS.contains(&extension));
+		// Hard links can't cross file systems, copy the file then.
+		if !immutable || fs::hard_link(&path, &target).is_err() {
+			fs::copy(&path, &target)?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::Block;
+
+	#[test]
+	fn backup_commits_marker() {
+		let backend = Backend::<Block>::new_test(0, 0);
+		assert_eq!(backend.last_backup().unwrap(), None);
+
+		let report = backend.backup(&BackupSettings::default()).unwrap();
+		assert_eq!(report.snapshot, None);
+		assert_eq!(backend.last_backup().unwrap(), Some(report.marker));
+	}
+
+	#[test]
+	fn snapshot_links_immutable_files() {
+		let dir = tempfile::TempDir::new().unwrap();
+		let database = dir.path().join("db/full");
+		fs::create_dir_all(database.join("sub")).unwrap();
+		fs::write(database.join("000001.sst"), b"table").unwrap();
+		fs::write(database.join("sub/MANIFEST"), b"manifest").unwrap();
+
+		assert!(!is_rocksdb(&database));
+		fs::write(database.join("CURRENT"), b"MANIFEST-000001").unwrap();
+		assert!(is_rocksdb(&database));
+
+		let snapshot = dir.path().join("snapshot");
+		snapshot_directory(&database, &snapshot.join(snapshot_name(&database))).unwrap();
+		let table = snapshot.join("db/full/000001.sst");
+		let manifest = snapshot.join("db/full/sub/MANIFEST");
+		assert_eq!(fs::read(&table).unwrap(), b"table");
+		assert_eq!(fs::read(&manifest).unwrap(), b"manifest");
+
+		// The table is shared with the database, the other files are independent copies.
+		fs::write(database.join("sub/MANIFEST"), b"changed").unwrap();
+		assert_eq!(fs::read(&manifest).unwrap(), b"manifest");
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::MetadataExt;
+			let ino = |path: &Path| fs::metadata(path).unwrap().ino();
+			assert_eq!(ino(&table), ino(&database.join("000001.sst")));
+		}
+	}
+}
@@ -32,6 +32,7 @@ pub mod offchain;
 
 pub mod bench;
 
+mod backup;
 mod children;
 mod parity_db;
 mod pinned_blocks_cache;
@@ -40,6 +41,7 @@ mod stats;
 #[cfg(any(feature = "rocksdb", test))]
 mod upgrade;
 mod utils;
+mod write_rate_limit;
 
 use linked_hash_map::LinkedHashMap;
 use log::{debug, trace, warn};
@@ -47,6 +49,7 @@ use parking_lot::{Mutex, RwLock};
 use std::{
 	collections::{HashMap, HashSet},
 	io,
+	num::NonZeroU32,
 	path::{Path, PathBuf},
 	sync::Arc,
 };
@@ -60,12 +63,13 @@ use crate::{
 use codec::{Decode, Encode};
 use hash_db::Prefix;
 use sc_client_api::{
-	backend::NewBlockState,
+	backend::{state_regeneration_allowed, NewBlockState},
 	blockchain::{BlockGap, BlockGapType},
 	leaves::{FinalizationOutcome, LeafSet},
 	utils::is_descendent_of,
 	IoInfo, MemoryInfo, MemorySize, UsageInfo,
 };
+use sc_memory_breakdown::Subsystem;
 use sc_state_db::{IsPruned, LastCanonicalized, StateDb};
 use sp_arithmetic::traits::Saturating;
 use sp_blockchain::{
@@ -102,6 +106,9 @@ pub use bench::BenchmarkingState;
 
 const CACHE_HEADERS: usize = 8;
 
+/// How long the size of the database on disk is cached, as computing it walks the directory.
+const DATABASE_SIZE_CACHE_DURATION: std::time::Duration = std::time::Duration::from_secs(60);
+
 /// DB-backed patricia trie state, transaction type is an overlay of changes to commit.
 pub type DbState<H> = sp_state_machine::TrieBackend<Arc<dyn sp_state_machine::Storage<H>>, H>;
 
@@ -109,6 +116,21 @@ pub type DbState<H> = sp_state_machine::TrieBackend<Arc<dyn sp_state_machine::St
 pub type DbStateBuilder<Hasher> =
 	sp_state_machine::TrieBackendBuilder<Arc<dyn sp_state_machine::Storage<Hasher>>, Hasher>;
 
+/// Regenerates states which were discarded by the pruning.
+///
+/// Registered with [`Backend::set_state_regenerator`], it is asked for the trie nodes of the
+/// states requested after being pruned, by the callers allowing it with
+/// [`sc_client_api::backend::with_state_regeneration`].
+pub trait StateRegenerator<Block: BlockT>: Send + Sync {
+	/// Regenerate the state of the block `hash`.
+	///
+	/// Returns a storage containing at least the trie nodes of the state.
+	fn regenerate(
+		&self,
+		hash: Block::Hash,
+	) -> Result<Arc<dyn sp_state_machine::Storage<HashingFor<Block>>>, String>;
+}
+
 /// Length of a [`DbHash`].
 const DB_HASH_LEN: usize = 32;
 
@@ -312,6 +334,10 @@ pub struct DatabaseSettings {
 	///
 	/// NOTE: only finalized blocks are subject for removal!
 	pub blocks_pruning: BlocksPruning,
+	/// Maximum average rate at which the database is written to, in MiB per second.
+	///
+	/// Commits exceeding the rate are delayed, after one second worth of writes.
+	pub write_rate_limit: Option<NonZeroU32>,
 }
 
 /// Block pruning settings.
@@ -479,6 +505,21 @@ fn cache_header<Hash: std::cmp::Eq + std::hash::Hash, Header>(
 	}
 }
 
+/// Open the full node database configured in `source`, creating it if it doesn't exist.
+///
+/// Returns the database, together with whether it has just been created.
+pub fn open_full_database<Block: BlockT>(
+	source: &DatabaseSource,
+) -> ClientResult<(Arc<dyn Database<DbHash>>, bool)> {
+	match utils::open_database::<Block>(source, DatabaseType::Full, false) {
+		Ok(db) => Ok((db, false)),
+		Err(utils::OpenDbError::DoesNotExist) => {
+			Ok((utils::open_database::<Block>(source, DatabaseType::Full, true)?, true))
+		},
+		Err(as_is) => Err(as_is.into()),
+	}
+}
+
 /// Block database
 pub struct BlockchainDb<Block: BlockT> {
 	db: Arc<dyn Database<DbHash>>,
@@ -524,6 +565,15 @@ impl<Block: BlockT> BlockchainDb<Block> {
 		}
 	}
 
+	/// Re-read the metadata and the leaves from the database.
+	fn reload_meta(&self) -> ClientResult<()> {
+		let meta = read_meta::<Block>(&*self.db, columns::HEADER)?;
+		let leaves = LeafSet::read_from_db(&*self.db, columns::META, meta_keys::LEAF_PREFIX)?;
+		*self.meta.write() = meta;
+		*self.leaves.write() = leaves;
+		Ok(())
+	}
+
 	fn update_block_gap(&self, gap: Option<BlockGap<NumberFor<Block>>>) {
 		let mut meta = self.meta.write();
 		meta.block_gap = gap;
@@ -1113,8 +1163,16 @@ pub struct Backend<Block: BlockT> {
 	blocks_pruning: BlocksPruning,
 	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo)>,
 	state_usage: Arc<StateUsageStats>,
+	/// Directories the database may be stored in, empty if it is not stored on disk.
+	database_paths: Vec<PathBuf>,
+	database_size: FrozenForDuration<Option<u64>>,
 	genesis_state: RwLock<Option<Arc<DbGenesisStorage<Block>>>>,
+	state_regenerator: RwLock<Option<Arc<dyn StateRegenerator<Block>>>>,
 	shared_trie_cache: Option<sp_trie::cache::SharedTrieCache<HashingFor<Block>>>,
+	/// Reports the size of the shared trie cache to the memory breakdown.
+	_state_cache_report: Option<sc_memory_breakdown::ReportHandle>,
+	/// Accounting of the writes, if their rate is limited.
+	write_rate_limiter: Option<Arc<write_rate_limit::WriteRateLimiter>>,
 }
 
 impl<Block: BlockT> Backend<Block> {
@@ -1122,22 +1180,43 @@ impl<Block: BlockT> Backend<Block> {
 	///
 	/// The pruning window is how old a block must be before the state is pruned.
 	pub fn new(db_config: DatabaseSettings, canonicalization_delay: u64) -> ClientResult<Self> {
-		use utils::OpenDbError;
+		let (mut db, needs_init) = open_full_database::<Block>(&db_config.source)?;
+		let write_rate_limiter = db_config
+			.write_rate_limit
+			.map(|limit| Arc::new(write_rate_limit::WriteRateLimiter::new(limit)));
+		if let Some(limiter) = &write_rate_limiter {
+			db = Arc::new(write_rate_limit::RateLimitedDatabase::new(db, limiter.clone()));
+		}
 
-		let db_source = &db_config.source;
+		let mut backend = Self::from_database(db, canonicalization_delay, &db_config, needs_init)?;
+		backend.write_rate_limiter = write_rate_limiter;
+		Ok(backend)
+	}
 
-		let (needs_init, db) =
-			match crate::utils::open_database::<Block>(db_source, DatabaseType::Full, false) {
-				Ok(db) => (false, db),
-				Err(OpenDbError::DoesNotExist) => {
-					let db =
-						crate::utils::open_database::<Block>(db_source, DatabaseType::Full, true)?;
-					(true, db)
-				},
-				Err(as_is) => return Err(as_is.into()),
-			};
+	/// Reload the chain metadata from a database written by another node.
+	///
+	/// Used by read-only replicas, whose database is the database of a primary node. The state of
+	/// unfinalized blocks is only kept in the memory of the primary, so the replica reports its
+	/// last finalized block as the best block.
+	///
+	/// Besides the meta and the leaves, the state database is reloaded to pick up the blocks
+	/// canonicalized and pruned by the primary, and the headers cached as unknown are dropped.
+	pub fn follow_primary(&self) -> ClientResult<()> {
+		self.blockchain.reload_meta()?;
+		self.blockchain.header_cache.lock().clear();
+		self.storage
+			.state_db
+			.reset(StateMetaDb(self.storage.db.clone()))
+			.map_err(sp_blockchain::Error::from_state_db)?;
+		let mut meta = self.blockchain.meta.write();
+		meta.best_hash = meta.finalized_hash;
+		meta.best_number = meta.finalized_number;
+		Ok(())
+	}
 
-		Self::from_database(db as Arc<_>, canonicalization_delay, &db_config, needs_init)
+	/// Regenerate the states discarded by the pruning with `regenerator` when requested.
+	pub fn set_state_regenerator(&self, regenerator: Arc<dyn StateRegenerator<Block>>) {
+		*self.state_regenerator.write() = Some(regenerator);
 	}
 
 	/// Reset the shared trie cache.
@@ -1171,6 +1250,7 @@ impl<Block: BlockT> Backend<Block> {
 			state_pruning: Some(state_pruning),
 			source: DatabaseSource::Custom { db, require_create_flag: true },
 			blocks_pruning,
+			write_rate_limit: None,
 		};
 
 		Self::new(db_setting, canonicalization_delay).expect("failed to create test-db")
@@ -1193,6 +1273,23 @@ impl<Block: BlockT> Backend<Block> {
 		self.storage.clone()
 	}
 
+	/// The size of the database on disk in bytes, `None` if it is not stored on disk.
+	fn database_size(&self) -> Option<u64> {
+		if self.database_paths.is_empty() {
+			return None
+		}
+		self.database_size.take_or_else(|| {
+			let size = self
+				.database_paths
+				.iter()
+				.filter(|path| path.exists())
+				.map(|path| utils::directory_size(path))
+				.sum::<io::Result<u64>>();
+			size.map_err(|e| debug!(target: "db", "Failed to compute the database size: {e}"))
+				.ok()
+		})
+	}
+
 	fn from_database(
 		db: Arc<dyn Database<DbHash>>,
 		canonicalization_delay: u64,
@@ -1224,6 +1321,13 @@ impl<Block: BlockT> Backend<Block> {
 
 		let offchain_storage = offchain::LocalStorage::new(db.clone());
 
+		let shared_trie_cache = config.trie_cache_maximum_size.map(|maximum_size| {
+			SharedTrieCache::new(sp_trie::cache::CacheSize::new(maximum_size))
+		});
+		let state_cache_report = shared_trie_cache.clone().map(|cache| {
+			sc_memory_breakdown::report(Subsystem::StateCache, move || cache.used_memory_size())
+		});
+
 		let backend = Backend {
 			storage: Arc::new(storage_db),
 			offchain_storage,
@@ -1233,11 +1337,18 @@ impl<Block: BlockT> Backend<Block> {
 			is_archive: is_archive_pruning,
 			io_stats: FrozenForDuration::new(std::time::Duration::from_secs(1)),
 			state_usage: Arc::new(StateUsageStats::new()),
+			database_paths: match &config.source {
+				DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+					vec![paritydb_path.clone(), rocksdb_path.clone()],
+				source => source.path().map(Into::into).into_iter().collect(),
+			},
+			database_size: FrozenForDuration::new(DATABASE_SIZE_CACHE_DURATION),
 			blocks_pruning: config.blocks_pruning,
 			genesis_state: RwLock::new(None),
-			shared_trie_cache: config.trie_cache_maximum_size.map(|maximum_size| {
-				SharedTrieCache::new(sp_trie::cache::CacheSize::new(maximum_size))
-			}),
+			state_regenerator: RwLock::new(None),
+			shared_trie_cache,
+			_state_cache_report: state_cache_report,
+			write_rate_limiter: None,
 		};
 
 		// Older DB versions have no last state key. Check if the state is available and set it.
@@ -2254,6 +2365,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 				state_reads_cache: state_stats.cache_reads.ops,
 				state_writes_nodes: state_stats.nodes_writes.ops,
 			},
+			database_size: self.database_size(),
 		})
 	}
 
@@ -2500,6 +2612,20 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 							.build();
 					let state = RefTrackingState::new(db_state, self.storage.clone(), Some(hash));
 					Ok(RecordStatsState::new(state, Some(hash), self.state_usage.clone()))
+				} else if let Some(regenerator) = state_regeneration_allowed()
+					.then(|| self.state_regenerator.read().clone())
+					.flatten()
+				{
+					let storage = regenerator.regenerate(hash).map_err(|e| {
+						sp_blockchain::Error::UnknownBlock(format!(
+							"State already discarded for {hash:?} and not regenerated: {e}",
+						))
+					})?;
+					// The regenerated state is not tracked by the state db, nothing to unpin.
+					let db_state =
+						DbStateBuilder::<HashingFor<Block>>::new(storage, hdr.state_root).build();
+					let state = RefTrackingState::new(db_state, self.storage.clone(), None);
+					Ok(RecordStatsState::new(state, Some(hash), self.state_usage.clone()))
 				} else {
 					Err(sp_blockchain::Error::UnknownBlock(format!(
 						"State already discarded for {hash:?}",
@@ -2551,6 +2677,12 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		)
 	}
 
+	fn write_delay(&self) -> std::time::Duration {
+		self.write_rate_limiter
+			.as_ref()
+			.map_or(Default::default(), |limiter| limiter.delay())
+	}
+
 	fn pin_block(&self, hash: <Block as BlockT>::Hash) -> sp_blockchain::Result<()> {
 		let hint = || {
 			let header_metadata = self.blockchain.header_metadata(hash);
@@ -2772,6 +2904,7 @@ pub(crate) mod tests {
 				state_pruning: Some(PruningMode::blocks_pruning(1)),
 				source: DatabaseSource::Custom { db: backing, require_create_flag: false },
 				blocks_pruning: BlocksPruning::KeepFinalized,
+				write_rate_limit: None,
 			},
 			0,
 		)
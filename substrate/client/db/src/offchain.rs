@@ -22,13 +22,15 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::{columns, Database, DbHash, Transaction};
 use log::error;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 
 /// Offchain local storage
 #[derive(Clone)]
 pub struct LocalStorage {
 	db: Arc<dyn Database<DbHash>>,
 	locks: Arc<Mutex<HashMap<Vec<u8>, Arc<Mutex<()>>>>>,
+	/// Held for reading by every write, see [`LocalStorage::pause_writes`].
+	writes: Arc<RwLock<()>>,
 }
 
 impl std::fmt::Debug for LocalStorage {
@@ -48,7 +50,12 @@ impl LocalStorage {
 
 	/// Create offchain local storage with given `KeyValueDB` backend.
 	pub fn new(db: Arc<dyn Database<DbHash>>) -> Self {
-		Self { db, locks: Default::default() }
+		Self { db, locks: Default::default(), writes: Default::default() }
+	}
+
+	/// Block the writes to the storage until the returned guard is dropped.
+	pub(crate) fn pause_writes(&self) -> RwLockWriteGuard<()> {
+		self.writes.write()
 	}
 }
 
@@ -57,6 +64,7 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		let mut tx = Transaction::new();
 		tx.set(columns::OFFCHAIN, &concatenate_prefix_and_key(prefix, key), value);
 
+		let _writes = self.writes.read();
 		if let Err(err) = self.db.commit(tx) {
 			error!("Error setting on local storage: {}", err)
 		}
@@ -66,6 +74,7 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		let mut tx = Transaction::new();
 		tx.remove(columns::OFFCHAIN, &concatenate_prefix_and_key(prefix, key));
 
+		let _writes = self.writes.read();
 		if let Err(err) = self.db.commit(tx) {
 			error!("Error removing on local storage: {}", err)
 		}
@@ -65,6 +65,8 @@ pub mod meta_keys {
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
+	/// Marker of the last backup.
+	pub const BACKUP_MARKER: &[u8; 6] = b"backup";
 }
 
 /// Database metadata.
@@ -424,6 +426,17 @@ fn maybe_migrate_to_type_subdir<Block: BlockT>(
 	Ok(())
 }
 
+/// Returns the total size in bytes of the files in the directory `path` and its subdirectories.
+pub(crate) fn directory_size(path: &Path) -> io::Result<u64> {
+	let mut size = 0;
+	for entry in fs::read_dir(path)? {
+		let entry = entry?;
+		let metadata = entry.metadata()?;
+		size += if metadata.is_dir() { directory_size(&entry.path())? } else { metadata.len() };
+	}
+	Ok(size)
+}
+
 /// Read database column entry for the given block.
 pub fn read_db<Block>(
 	db: &dyn Database<DbHash>,
@@ -857,4 +870,15 @@ mod tests {
 			assert!(db_res.is_ok(), "Existing parity database should be reopened");
 		}
 	}
+
+	#[test]
+	fn directory_size_includes_subdirectories() {
+		let db_dir = tempfile::TempDir::new().unwrap();
+		fs::write(db_dir.path().join("a"), [0u8; 100]).unwrap();
+		fs::create_dir(db_dir.path().join("sub")).unwrap();
+		fs::write(db_dir.path().join("sub/b"), [0u8; 50]).unwrap();
+
+		assert_eq!(directory_size(db_dir.path()).unwrap(), 150);
+		assert!(directory_size(&db_dir.path().join("missing")).is_err());
+	}
 }
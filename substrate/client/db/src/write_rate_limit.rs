@@ -0,0 +1,170 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Limiting of the rate at which the database is written to.
+
+use parking_lot::Mutex;
+use sp_database::{error, Change, ColumnId, Database, Transaction};
+use std::{
+	num::NonZeroU32,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The amount of writes, in time at the limited rate, that is committed without delay.
+const BURST: Duration = Duration::from_secs(1);
+
+/// The longest delay of an import, the writes beyond it are forgiven so that a single large
+/// commit, like the state downloaded by the state sync, doesn't stall the imports for minutes.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Accounting of the writes to the database, telling how long the deferrable writes should wait
+/// so that the average rate of writes stays below a limit.
+///
+/// The commits themselves are never delayed: they may run under the import lock or on async
+/// tasks. The backend rather reports the delay, which the client waits for before taking the
+/// import lock for the blocks of the initial sync.
+pub(crate) struct WriteRateLimiter {
+	bytes_per_second: f64,
+	/// The moment until which the committed writes use up the rate.
+	busy_until: Mutex<Instant>,
+}
+
+impl WriteRateLimiter {
+	/// Limit the writes to `mib_per_second`.
+	pub(crate) fn new(mib_per_second: NonZeroU32) -> Self {
+		Self {
+			bytes_per_second: f64::from(mib_per_second.get()) * 1024.0 * 1024.0,
+			busy_until: Mutex::new(Instant::now()),
+		}
+	}
+
+	/// Account `bytes` written at `now`.
+	fn record(&self, bytes: usize, now: Instant) {
+		let mut busy_until = self.busy_until.lock();
+		let written = Duration::from_secs_f64(bytes as f64 / self.bytes_per_second);
+		*busy_until = ((*busy_until).max(now) + written).min(now + BURST + MAX_DELAY);
+	}
+
+	/// The delay before the next deferrable write at `now`, at most [`MAX_DELAY`].
+	fn delay_at(&self, now: Instant) -> Duration {
+		self.busy_until.lock().saturating_duration_since(now + BURST)
+	}
+
+	/// The delay before the next deferrable write.
+	pub(crate) fn delay(&self) -> Duration {
+		self.delay_at(Instant::now())
+	}
+}
+
+/// A database accounting its commits in a [`WriteRateLimiter`].
+pub(crate) struct RateLimitedDatabase<H> {
+	inner: Arc<dyn Database<H>>,
+	limiter: Arc<WriteRateLimiter>,
+}
+
+impl<H> RateLimitedDatabase<H> {
+	/// Account the writes to `inner` in `limiter`.
+	pub(crate) fn new(inner: Arc<dyn Database<H>>, limiter: Arc<WriteRateLimiter>) -> Self {
+		Self { inner, limiter }
+	}
+}
+
+/// The number of bytes written by `change`.
+fn written_bytes<H: AsRef<[u8]>>(change: &Change<H>) -> usize {
+	match change {
+		Change::Set(_, key, value) => key.len() + value.len(),
+		Change::Store(_, hash, preimage) => hash.as_ref().len() + preimage.len(),
+		Change::Remove(_, key) => key.len(),
+		Change::Reference(_, hash) | Change::Release(_, hash) => hash.as_ref().len(),
+	}
+}
+
+impl<H: Clone + AsRef<[u8]>> Database<H> for RateLimitedDatabase<H> {
+	fn commit(&self, transaction: Transaction<H>) -> error::Result<()> {
+		let bytes = transaction.0.iter().map(written_bytes).sum();
+		self.inner.commit(transaction)?;
+		self.limiter.record(bytes, Instant::now());
+		Ok(())
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+		self.inner.get(col, key)
+	}
+
+	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
+		self.inner.contains(col, key)
+	}
+
+	fn value_size(&self, col: ColumnId, key: &[u8]) -> Option<usize> {
+		self.inner.value_size(col, key)
+	}
+
+	fn with_get(&self, col: ColumnId, key: &[u8], f: &mut dyn FnMut(&[u8])) {
+		self.inner.with_get(col, key, f)
+	}
+
+	fn supports_ref_counting(&self) -> bool {
+		self.inner.supports_ref_counting()
+	}
+
+	fn sanitize_key(&self, key: &mut Vec<u8>) {
+		self.inner.sanitize_key(key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_database::MemDb;
+
+	#[test]
+	fn writes_beyond_the_burst_are_delayed() {
+		let limiter = WriteRateLimiter::new(NonZeroU32::new(1).unwrap());
+		let mib = 1024 * 1024;
+		let now = Instant::now();
+
+		limiter.record(mib / 2, now);
+		assert_eq!(limiter.delay_at(now), Duration::ZERO);
+		limiter.record(mib, now);
+		assert_eq!(limiter.delay_at(now), Duration::from_millis(500));
+		// The rate is used up for 500ms more than the burst, which recovers over time.
+		assert_eq!(limiter.delay_at(now + Duration::from_secs(1)), Duration::ZERO);
+	}
+
+	#[test]
+	fn delay_is_capped() {
+		let limiter = WriteRateLimiter::new(NonZeroU32::new(1).unwrap());
+		let db = RateLimitedDatabase::<crate::DbHash>::new(
+			Arc::new(MemDb::default()),
+			Arc::new(WriteRateLimiter::new(NonZeroU32::new(1).unwrap())),
+		);
+		let now = Instant::now();
+
+		// A GiB written at 1 MiB/s would take 17 minutes.
+		limiter.record(1024 * 1024 * 1024, now);
+		assert_eq!(limiter.delay_at(now), MAX_DELAY);
+		assert_eq!(limiter.delay_at(now + MAX_DELAY), Duration::ZERO);
+
+		// The commits are accounted without waiting.
+		let mut transaction = Transaction::new();
+		transaction.set(0, b"key", &vec![0; 4 * 1024 * 1024]);
+		db.commit(transaction).unwrap();
+		assert!(db.limiter.delay() > Duration::from_secs(2));
+	}
+}
@@ -188,6 +188,25 @@ impl RuntimeBlob {
 			.map(|cs| cs.payload())
 	}
 
+	/// Returns the names of the functions imported by the program, i.e. of the host functions it
+	/// requires.
+	///
+	/// Only valid for WASM programs; will return an error if the blob is a PolkaVM program.
+	pub fn imported_functions(&self) -> Result<Vec<&str>, WasmError> {
+		let raw_module = self.as_webassembly_blob()?;
+		Ok(raw_module
+			.import_section()
+			.map(|section| {
+				section
+					.entries()
+					.iter()
+					.filter(|entry| matches!(entry.external(), External::Function(_)))
+					.map(|entry| entry.field())
+					.collect()
+			})
+			.unwrap_or_default())
+	}
+
 	/// Consumes this runtime blob and serializes it.
 	pub fn serialize(self) -> Vec<u8> {
 		match self.0 {
@@ -193,6 +193,28 @@ impl<H> WasmExecutorBuilder<H> {
 		self
 	}
 
+	/// Extend the host functions of the executor with the host functions `E`.
+	///
+	/// This is the extension point for chains providing additional host functions, e.g. new hash
+	/// functions. Host functions of `E` override the ones with the same name and version in `H`.
+	/// Runtimes importing host functions which are provided by neither are reported when they are
+	/// instantiated, see [`HostApi`](crate::HostApi).
+	pub fn with_host_functions<E: HostFunctions>(
+		self,
+	) -> WasmExecutorBuilder<ExtendedHostFunctions<H, E>> {
+		WasmExecutorBuilder {
+			_phantom: PhantomData,
+			method: self.method,
+			onchain_heap_alloc_strategy: self.onchain_heap_alloc_strategy,
+			offchain_heap_alloc_strategy: self.offchain_heap_alloc_strategy,
+			ignore_onchain_heap_pages: self.ignore_onchain_heap_pages,
+			max_runtime_instances: self.max_runtime_instances,
+			cache_path: self.cache_path,
+			allow_missing_host_functions: self.allow_missing_host_functions,
+			runtime_cache_size: self.runtime_cache_size,
+		}
+	}
+
 	/// Build the configured [`WasmExecutor`].
 	pub fn build(self) -> WasmExecutor<H> {
 		WasmExecutor {
@@ -0,0 +1,155 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Negotiation of the host API between a runtime and the executor.
+//!
+//! Every host function is imported by the runtime under a versioned name of the form
+//! `ext_<name>_version_<version>`, as generated by `#[runtime_interface]`. The [`HostApi`] of a
+//! runtime records which of these versions the runtime requires and which of them are not
+//! provided by the host functions the executor was built with.
+//!
+//! Chains providing additional host functions register them with
+//! [`WasmExecutorBuilder::with_host_functions`](crate::WasmExecutorBuilder::with_host_functions)
+//! instead of patching `sp-io`.
+
+use sc_executor_common::{error::WasmError, runtime_blob::RuntimeBlob};
+use sp_wasm_interface::HostFunctions;
+use std::{collections::BTreeMap, fmt};
+
+/// A versioned host function required by a runtime.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HostApiRequirement {
+	/// Name of the host function, without the `ext_` prefix and the version suffix, e.g.
+	/// `hashing_blake2_256`.
+	pub name: String,
+	/// Version of the host function.
+	pub version: u32,
+}
+
+impl HostApiRequirement {
+	/// Parse the name of an imported function, e.g. `ext_hashing_blake2_256_version_1`.
+	///
+	/// Returns `None` for names not following the versioned naming scheme.
+	pub fn from_import_name(import: &str) -> Option<Self> {
+		let (name, version) = import.strip_prefix("ext_")?.rsplit_once("_version_")?;
+		Some(Self { name: name.into(), version: version.parse().ok()? })
+	}
+
+	/// The name under which the host function is imported.
+	pub fn import_name(&self) -> String {
+		format!("ext_{}_version_{}", self.name, self.version)
+	}
+}
+
+impl fmt::Display for HostApiRequirement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} (version {})", self.name, self.version)
+	}
+}
+
+/// The host API required by a runtime, negotiated against the host functions of the executor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostApi {
+	/// The versioned host functions imported by the runtime.
+	pub required: Vec<HostApiRequirement>,
+	/// The required host functions which are not provided, together with the versions of the
+	/// same function which are.
+	pub unsupported: Vec<(HostApiRequirement, Vec<u32>)>,
+}
+
+impl HostApi {
+	/// Negotiate the host API of the runtime `blob` against the host functions `H`.
+	///
+	/// Imports not following the versioned naming scheme are ignored. Only valid for WASM
+	/// programs; will return an error if the blob is a PolkaVM program.
+	pub fn negotiate<H: HostFunctions>(blob: &RuntimeBlob) -> Result<Self, WasmError> {
+		let mut provided = BTreeMap::<String, Vec<u32>>::new();
+		for function in H::host_functions() {
+			if let Some(requirement) = HostApiRequirement::from_import_name(function.name()) {
+				provided.entry(requirement.name).or_default().push(requirement.version);
+			}
+		}
+
+		let mut required = blob
+			.imported_functions()?
+			.into_iter()
+			.filter_map(HostApiRequirement::from_import_name)
+			.collect::<Vec<_>>();
+		required.sort();
+		required.dedup();
+
+		let unsupported = required
+			.iter()
+			.filter_map(|requirement| {
+				let versions = provided.get(&requirement.name).cloned().unwrap_or_default();
+				(!versions.contains(&requirement.version)).then(|| (requirement.clone(), versions))
+			})
+			.collect();
+
+		Ok(Self { required, unsupported })
+	}
+
+	/// Returns `true` if all the required host functions are provided.
+	pub fn is_supported(&self) -> bool {
+		self.unsupported.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_wasm_interface::ExtendedHostFunctions;
+
+	fn blob() -> RuntimeBlob {
+		RuntimeBlob::uncompress_if_needed(substrate_test_runtime::wasm_binary_unwrap()).unwrap()
+	}
+
+	#[test]
+	fn requirement_from_import_name_works() {
+		assert_eq!(
+			HostApiRequirement::from_import_name("ext_hashing_blake2_256_version_1"),
+			Some(HostApiRequirement { name: "hashing_blake2_256".into(), version: 1 }),
+		);
+		assert_eq!(HostApiRequirement::from_import_name("ext_hashing_blake2_256"), None);
+		assert_eq!(HostApiRequirement::from_import_name("memory_version_1"), None);
+		assert_eq!(
+			HostApiRequirement::from_import_name("ext_misc_print_utf8_version_1")
+				.unwrap()
+				.import_name(),
+			"ext_misc_print_utf8_version_1",
+		);
+	}
+
+	#[test]
+	fn negotiate_works() {
+		let host_api = HostApi::negotiate::<sp_io::SubstrateHostFunctions>(&blob()).unwrap();
+		assert!(host_api.is_supported());
+		assert!(host_api
+			.required
+			.contains(&HostApiRequirement { name: "storage_get".into(), version: 1 }));
+
+		let host_api = HostApi::negotiate::<()>(&blob()).unwrap();
+		assert!(!host_api.is_supported());
+		assert_eq!(host_api.unsupported.len(), host_api.required.len());
+
+		let host_api =
+			HostApi::negotiate::<ExtendedHostFunctions<(), sp_io::SubstrateHostFunctions>>(&blob())
+				.unwrap();
+		assert!(host_api.is_supported());
+	}
+}
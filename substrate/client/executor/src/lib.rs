@@ -32,6 +32,7 @@
 
 #[macro_use]
 mod executor;
+mod host_api;
 #[cfg(test)]
 mod integration_tests;
 mod wasm_runtime;
@@ -39,7 +40,10 @@ mod wasm_runtime;
 pub use codec::Codec;
 #[allow(deprecated)]
 pub use executor::NativeElseWasmExecutor;
-pub use executor::{with_externalities_safe, NativeExecutionDispatch, WasmExecutor};
+pub use executor::{
+	with_externalities_safe, NativeExecutionDispatch, WasmExecutor, WasmExecutorBuilder,
+};
+pub use host_api::{HostApi, HostApiRequirement};
 #[doc(hidden)]
 pub use sp_core::traits::Externalities;
 pub use sp_version::{NativeVersion, RuntimeVersion};
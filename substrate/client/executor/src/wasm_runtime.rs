@@ -402,6 +402,17 @@ where
 	// runtime.
 	let mut version = read_embedded_version(&blob)?;
 
+	if blob.as_polkavm_blob().is_none() {
+		let host_api = crate::HostApi::negotiate::<H>(&blob)?;
+		for (requirement, provided) in &host_api.unsupported {
+			tracing::warn!(
+				target: "wasm-runtime",
+				"Runtime requires host function {requirement}, which is not provided by the \
+				 executor (provided versions: {provided:?})",
+			);
+		}
+	}
+
 	let runtime = create_wasm_runtime_with_code::<H>(
 		wasm_method,
 		heap_alloc_strategy,
@@ -0,0 +1,217 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GraphQL read API over the storage of the node.
+//!
+//! Generates a GraphQL schema from the metadata of the runtime of the best block, exposing the
+//! storage entries of every pallet and the events of a block, and serves read queries against
+//! the best or a given block:
+//!
+//! - `POST /graphql` executes a GraphQL request.
+//! - `GET /graphql/schema` returns the schema in the GraphQL schema definition language.
+//!
+//! The schema is regenerated whenever the runtime of the best block is upgraded. Queries against
+//! blocks of an older runtime are decoded with the types of the current one, and may fail if
+//! the layout of the queried storage changed.
+//!
+//! The server is disabled unless a port is configured with [`GraphqlParams`]. Nodes opt in by
+//! spawning the future returned by [`GraphqlServer::run`].
+
+mod schema;
+
+use async_graphql::dynamic::Schema;
+use clap::Args;
+use http_body_util::{BodyExt, Limited};
+use hyper::{http::StatusCode, Method, Request, Response};
+use sc_client_api::{Backend, StorageProvider};
+use sc_runtime_metadata::RuntimeCache;
+use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	marker::PhantomData,
+	net::{Ipv4Addr, SocketAddr},
+	sync::Arc,
+};
+
+const LOG_TARGET: &str = "graphql";
+
+/// The maximum size of a GraphQL request body.
+const MAX_REQUEST_SIZE: usize = 1024 * 1024;
+
+type Body = http_body_util::Full<hyper::body::Bytes>;
+
+/// Parameters used to configure the GraphQL server.
+#[derive(Default, Debug, Clone, Args)]
+pub struct GraphqlParams {
+	/// Specify the GraphQL server TCP port.
+	///
+	/// The GraphQL server is disabled if no port is given.
+	#[arg(long, value_name = "PORT")]
+	pub graphql_port: Option<u16>,
+
+	/// Listen to all GraphQL interfaces.
+	///
+	/// By default, the node only listens to local GraphQL requests.
+	#[arg(long)]
+	pub graphql_external: bool,
+}
+
+impl GraphqlParams {
+	/// The address the GraphQL server should listen on, if enabled.
+	pub fn listen_addr(&self) -> Option<SocketAddr> {
+		let interface =
+			if self.graphql_external { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+		self.graphql_port.map(|port| SocketAddr::new(interface.into(), port))
+	}
+}
+
+/// GraphQL server errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Http request error.
+	#[error(transparent)]
+	Http(#[from] hyper::http::Error),
+
+	/// i/o error.
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+
+	#[error("GraphQL port {0} already in use.")]
+	PortInUse(SocketAddr),
+}
+
+/// Serves GraphQL queries against the storage of the node.
+pub struct GraphqlServer<Block, BE, Client> {
+	client: Arc<Client>,
+	schema: RuntimeCache<Schema>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+impl<Block, BE, Client> GraphqlServer<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	Client: HeaderBackend<Block>
+		+ StorageProvider<Block, BE>
+		+ ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: Metadata<Block>,
+{
+	/// Create a new [`GraphqlServer`].
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, schema: RuntimeCache::default(), _phantom: PhantomData }
+	}
+
+	/// The schema of the runtime of the best block.
+	fn schema(&self) -> Result<Schema, String> {
+		let best_hash = self.client.info().best_hash;
+		let spec_version = self
+			.client
+			.runtime_version_at(best_hash)
+			.map_err(|e| e.to_string())?
+			.spec_version;
+
+		self.schema.get_or_try_insert_with(spec_version, || {
+			let metadata = sc_runtime_metadata::metadata_at::<Block, _>(&*self.client, best_hash)?
+				.ok_or("The runtime doesn't provide metadata V15")?;
+			let schema = schema::build_schema::<Block, BE, Client>(self.client.clone(), metadata)
+				.map_err(|e| e.to_string())?;
+			log::debug!(target: LOG_TARGET, "Generated schema of runtime version {spec_version}");
+			Ok(schema)
+		})
+	}
+
+	async fn handle(&self, req: Request<hyper::body::Incoming>) -> Result<Response<Body>, Error> {
+		let (status, body) = match (req.method(), req.uri().path()) {
+			(&Method::POST, "/graphql") => self.execute(req).await,
+			(&Method::GET, "/graphql/schema") => match self.schema() {
+				Ok(schema) => (StatusCode::OK, schema.sdl()),
+				Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e),
+			},
+			_ => (StatusCode::NOT_FOUND, "Not found.".into()),
+		};
+		Response::builder().status(status).body(Body::from(body)).map_err(Error::Http)
+	}
+
+	async fn execute(&self, req: Request<hyper::body::Incoming>) -> (StatusCode, String) {
+		let body = match Limited::new(req.into_body(), MAX_REQUEST_SIZE).collect().await {
+			Ok(body) => body.to_bytes(),
+			Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()),
+		};
+		let request: async_graphql::Request = match serde_json::from_slice(&body) {
+			Ok(request) => request,
+			Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()),
+		};
+		let schema = match self.schema() {
+			Ok(schema) => schema,
+			Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e),
+		};
+
+		let response = schema.execute(request).await;
+		match serde_json::to_string(&response) {
+			Ok(response) => (StatusCode::OK, response),
+			Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+		}
+	}
+
+	/// Serve GraphQL requests on `addr` until the returned future is dropped.
+	pub async fn run(self, addr: SocketAddr) -> Result<(), Error> {
+		let listener = tokio::net::TcpListener::bind(&addr).await.map_err(|e| {
+			log::error!(target: LOG_TARGET, "Error binding to '{addr:?}': {e:?}");
+			Error::PortInUse(addr)
+		})?;
+		log::info!(target: LOG_TARGET, "Running GraphQL server: addr={}", listener.local_addr()?);
+
+		let this = Arc::new(self);
+		let server =
+			hyper_util::server::conn::auto::Builder::new(hyper_util::rt::TokioExecutor::new());
+		let graceful = hyper_util::server::graceful::GracefulShutdown::new();
+
+		loop {
+			let io = match listener.accept().await {
+				Ok((sock, _)) => hyper_util::rt::TokioIo::new(sock),
+				Err(e) => {
+					log::debug!(target: LOG_TARGET, "Error accepting connection: {:?}", e);
+					continue
+				},
+			};
+
+			let this = this.clone();
+			let conn = server
+				.serve_connection(
+					io,
+					hyper::service::service_fn(move |req| {
+						let this = this.clone();
+						async move { this.handle(req).await }
+					}),
+				)
+				.into_owned();
+			let conn = graceful.watch(conn);
+
+			tokio::spawn(async move {
+				if let Err(err) = conn.await {
+					log::debug!(target: LOG_TARGET, "connection error: {:?}", err);
+				}
+			});
+		}
+	}
+}
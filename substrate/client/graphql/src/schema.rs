@@ -0,0 +1,323 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Generation of the GraphQL schema from the runtime metadata.
+//!
+//! The query root has one field per pallet with storage, named after the pallet in camel case,
+//! and an `events` field. Every pallet field resolves to an object with one field per storage
+//! entry, returning the decoded value as `JSON`. Storage maps take the SCALE encoded keys as
+//! `0x` prefixed hex strings, one per hasher of the map.
+//!
+//! All root fields take an optional `block` argument with the hash of the block to query,
+//! defaulting to the best block.
+
+use async_graphql::{
+	dynamic::{
+		Field, FieldFuture, FieldValue, InputValue, Object, ResolverContext, Scalar, Schema,
+		SchemaError, TypeRef,
+	},
+	Error, Value,
+};
+use codec::Decode;
+use frame_metadata::v15::{
+	RuntimeMetadataV15, StorageEntryMetadata, StorageEntryModifier, StorageEntryType, StorageHasher,
+};
+use sc_client_api::{Backend, StorageProvider};
+use sc_runtime_metadata::decode::decode_value;
+use scale_info::{form::PortableForm, PortableRegistry};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+
+/// Name of the scalar used for all decoded values.
+const JSON: &str = "JSON";
+
+/// The state shared by all resolvers of a schema.
+struct Runtime<Block, BE, Client> {
+	client: Arc<Client>,
+	registry: PortableRegistry,
+	/// Type of the `System::Events` storage value, if the runtime has one.
+	events_ty: Option<u32>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+/// A storage entry exposed as a GraphQL field.
+struct Entry {
+	/// `twox_128(pallet prefix) ++ twox_128(entry name)`.
+	prefix: Vec<u8>,
+	hashers: Vec<StorageHasher>,
+	value_ty: u32,
+	optional: bool,
+	default: Vec<u8>,
+}
+
+impl Entry {
+	fn new(pallet_prefix: &str, entry: &StorageEntryMetadata<PortableForm>) -> Self {
+		let mut prefix = sp_crypto_hashing::twox_128(pallet_prefix.as_bytes()).to_vec();
+		prefix.extend(sp_crypto_hashing::twox_128(entry.name.as_bytes()));
+		let (hashers, value_ty) = match &entry.ty {
+			StorageEntryType::Plain(value) => (Vec::new(), value.id),
+			StorageEntryType::Map { hashers, value, .. } => (hashers.clone(), value.id),
+		};
+		Self {
+			prefix,
+			hashers,
+			value_ty,
+			optional: matches!(entry.modifier, StorageEntryModifier::Optional),
+			default: entry.default.clone(),
+		}
+	}
+
+	/// The storage key of the value identified by the SCALE encoded `keys`.
+	fn key(&self, keys: &[Vec<u8>]) -> Result<StorageKey, Error> {
+		if keys.len() != self.hashers.len() {
+			return Err(Error::new(format!(
+				"Expected {} keys, got {}",
+				self.hashers.len(),
+				keys.len()
+			)));
+		}
+		let mut key = self.prefix.clone();
+		for (hasher, part) in self.hashers.iter().zip(keys) {
+			key.extend(hash(hasher, part));
+		}
+		Ok(StorageKey(key))
+	}
+}
+
+fn hash(hasher: &StorageHasher, data: &[u8]) -> Vec<u8> {
+	use sp_crypto_hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+
+	match hasher {
+		StorageHasher::Blake2_128 => blake2_128(data).to_vec(),
+		StorageHasher::Blake2_256 => blake2_256(data).to_vec(),
+		StorageHasher::Blake2_128Concat => [&blake2_128(data)[..], data].concat(),
+		StorageHasher::Twox128 => twox_128(data).to_vec(),
+		StorageHasher::Twox256 => twox_256(data).to_vec(),
+		StorageHasher::Twox64Concat => [&twox_64(data)[..], data].concat(),
+		StorageHasher::Identity => data.to_vec(),
+	}
+}
+
+/// The name of the field of a pallet or storage entry.
+fn field_name(name: &str) -> String {
+	let mut chars = name.chars();
+	chars
+		.next()
+		.map(|first| first.to_lowercase().chain(chars).collect())
+		.unwrap_or_default()
+}
+
+fn docs(docs: &[String]) -> String {
+	docs.iter().map(|line| line.trim()).collect::<Vec<_>>().join("\n")
+}
+
+fn decode_hex(what: &str, value: &str) -> Result<Vec<u8>, Error> {
+	array_bytes::hex2bytes(value).map_err(|_| Error::new(format!("Invalid hex in {what}")))
+}
+
+impl<Block, BE, Client> Runtime<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: HeaderBackend<Block> + StorageProvider<Block, BE>,
+{
+	/// The block selected by the `block` argument of a root field.
+	fn block(&self, ctx: &ResolverContext) -> Result<Block::Hash, Error> {
+		match ctx.args.get("block") {
+			Some(block) => {
+				let hash = decode_hex("block", block.string()?)?;
+				Block::Hash::decode(&mut &hash[..]).map_err(|_| Error::new("Invalid block hash"))
+			},
+			None => Ok(self.client.info().best_hash),
+		}
+	}
+
+	fn storage(&self, at: Block::Hash, key: &StorageKey) -> Result<Option<Vec<u8>>, Error> {
+		self.client
+			.storage(at, key)
+			.map(|data| data.map(|data| data.0))
+			.map_err(|e| Error::new(e.to_string()))
+	}
+
+	fn decode(&self, ty: u32, data: &[u8]) -> Result<Value, Error> {
+		let value = decode_value(&self.registry, ty, &mut &data[..])
+			.map_err(|e| Error::new(e.to_string()))?;
+		Value::from_json(value).map_err(|e| Error::new(e.to_string()))
+	}
+
+	fn entry(&self, at: Block::Hash, entry: &Entry, ctx: &ResolverContext) -> Result<Value, Error> {
+		let keys = match ctx.args.get("keys") {
+			Some(keys) => keys
+				.list()?
+				.iter()
+				.map(|key| decode_hex("keys", key.string()?))
+				.collect::<Result<Vec<_>, _>>()?,
+			None => Vec::new(),
+		};
+		match self.storage(at, &entry.key(&keys)?)? {
+			Some(data) => self.decode(entry.value_ty, &data),
+			None if entry.optional => Ok(Value::Null),
+			None => self.decode(entry.value_ty, &entry.default),
+		}
+	}
+
+	/// The events of the block, optionally only the ones of `pallet`.
+	fn events(&self, at: Block::Hash, pallet: Option<&str>) -> Result<Value, Error> {
+		let ty = self.events_ty.ok_or_else(|| Error::new("The runtime has no events"))?;
+		let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+		key.extend(sp_crypto_hashing::twox_128(b"Events"));
+		let Some(data) = self.storage(at, &StorageKey(key))? else {
+			return Ok(Value::List(vec![]));
+		};
+
+		let events = decode_value(&self.registry, ty, &mut &data[..])
+			.map_err(|e| Error::new(e.to_string()))?;
+		let events = match (events, pallet) {
+			(serde_json::Value::Array(records), Some(pallet)) => records
+				.into_iter()
+				.filter(|record| record.get("event").and_then(|event| event.get(pallet)).is_some())
+				.collect(),
+			(events, _) => events,
+		};
+		Value::from_json(events).map_err(|e| Error::new(e.to_string()))
+	}
+}
+
+/// Build the GraphQL schema of the runtime described by `metadata`.
+pub fn build_schema<Block, BE, Client>(
+	client: Arc<Client>,
+	metadata: RuntimeMetadataV15,
+) -> Result<Schema, SchemaError>
+where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	Client: HeaderBackend<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+{
+	let events_ty = metadata
+		.pallets
+		.iter()
+		.find(|pallet| pallet.name == "System")
+		.and_then(|pallet| pallet.storage.as_ref())
+		.and_then(|storage| storage.entries.iter().find(|entry| entry.name == "Events"))
+		.map(|entry| Entry::new("System", entry).value_ty);
+	let runtime: Arc<Runtime<Block, BE, Client>> =
+		Arc::new(Runtime { client, registry: metadata.types, events_ty, _phantom: PhantomData });
+
+	let block_argument = || {
+		InputValue::new("block", TypeRef::named(TypeRef::STRING))
+			.description("Hash of the block to query, defaults to the best block.")
+	};
+
+	let events_runtime = runtime.clone();
+	let mut query = Object::new("Query").field(
+		Field::new("events", TypeRef::named_nn(JSON), move |ctx| {
+			let runtime = events_runtime.clone();
+			FieldFuture::new(async move {
+				let at = runtime.block(&ctx)?;
+				let pallet = ctx.args.get("pallet").map(|pallet| pallet.string()).transpose()?;
+				Ok(Some(FieldValue::value(runtime.events(at, pallet)?)))
+			})
+		})
+		.description("Events emitted in the block.")
+		.argument(block_argument())
+		.argument(
+			InputValue::new("pallet", TypeRef::named(TypeRef::STRING))
+				.description("Only return the events of this pallet."),
+		),
+	);
+	let mut objects = Vec::new();
+	let mut names = HashSet::from([String::from("events")]);
+
+	for pallet in metadata.pallets {
+		let Some(storage) = pallet.storage else { continue };
+		let pallet_field = field_name(&pallet.name);
+		if storage.entries.is_empty() || !names.insert(pallet_field.clone()) {
+			continue;
+		}
+
+		let type_name = format!("{}Storage", pallet.name);
+		let mut object = Object::new(&type_name).description(docs(&pallet.docs));
+		for entry_metadata in &storage.entries {
+			let entry = Arc::new(Entry::new(&storage.prefix, entry_metadata));
+			let runtime = runtime.clone();
+			let is_map = !entry.hashers.is_empty();
+			let name = field_name(&entry_metadata.name);
+			let mut field = Field::new(name, TypeRef::named(JSON), move |ctx| {
+				let runtime = runtime.clone();
+				let entry = entry.clone();
+				FieldFuture::new(async move {
+					let at = *ctx.parent_value.try_downcast_ref::<Block::Hash>()?;
+					Ok(Some(FieldValue::value(runtime.entry(at, &entry, &ctx)?)))
+				})
+			})
+			.description(docs(&entry_metadata.docs));
+			if is_map {
+				field = field.argument(
+					InputValue::new("keys", TypeRef::named_nn_list_nn(TypeRef::STRING))
+						.description("The SCALE encoded keys of the map, as hex strings."),
+				);
+			}
+			object = object.field(field);
+		}
+		objects.push(object);
+
+		let runtime = runtime.clone();
+		query = query.field(
+			Field::new(pallet_field, TypeRef::named_nn(&type_name), move |ctx| {
+				let runtime = runtime.clone();
+				FieldFuture::new(async move {
+					let at = runtime.block(&ctx)?;
+					Ok(Some(FieldValue::owned_any(at)))
+				})
+			})
+			.description(format!("Storage of the `{}` pallet.", pallet.name))
+			.argument(block_argument()),
+		);
+	}
+
+	let mut schema = Schema::build("Query", None, None)
+		.register(query)
+		.register(Scalar::new(JSON).description("A decoded SCALE value."));
+	for object in objects {
+		schema = schema.register(object);
+	}
+	schema.finish()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn field_names_are_camel_case() {
+		assert_eq!(field_name("System"), "system");
+		assert_eq!(field_name("BlockHash"), "blockHash");
+		assert_eq!(field_name(""), "");
+	}
+
+	#[test]
+	fn concat_hashers_append_the_key() {
+		let key = [1u8, 2, 3];
+		let hashed = hash(&StorageHasher::Twox64Concat, &key);
+		assert_eq!(&hashed[..8], &sp_crypto_hashing::twox_64(&key)[..]);
+		assert_eq!(&hashed[8..], &key[..]);
+		assert_eq!(hash(&StorageHasher::Identity, &key), key.to_vec());
+	}
+}
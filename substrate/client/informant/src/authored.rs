@@ -0,0 +1,64 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Counter of the blocks authored by the node.
+
+/// Counts the blocks authored by the node in the current session.
+///
+/// Without a session provider, the sessions are unknown and the blocks are counted since the
+/// start of the node.
+pub(crate) struct AuthoredBlocks {
+	/// The session of the last authored block.
+	session: Option<u64>,
+	/// The blocks authored in `session`.
+	count: u64,
+}
+
+impl AuthoredBlocks {
+	pub(crate) fn new() -> Self {
+		Self { session: None, count: 0 }
+	}
+
+	/// Record a block authored in `session`, and return the number of blocks authored in the
+	/// session so far, the block included.
+	pub(crate) fn record(&mut self, session: Option<u64>) -> u64 {
+		if session != self.session {
+			self.session = session;
+			self.count = 0;
+		}
+		self.count += 1;
+		self.count
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counts_per_session() {
+		let mut authored = AuthoredBlocks::new();
+		assert_eq!(authored.record(Some(4)), 1);
+		assert_eq!(authored.record(Some(4)), 2);
+		assert_eq!(authored.record(Some(5)), 1);
+
+		let mut authored = AuthoredBlocks::new();
+		assert_eq!(authored.record(None), 1);
+		assert_eq!(authored.record(None), 2);
+	}
+}
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Growth rate of the database on disk.
+
+use std::{
+	collections::VecDeque,
+	time::{Duration, Instant},
+};
+
+/// The window over which the growth rate is measured.
+const GROWTH_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// The minimum interval between two recorded samples of the database size.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Samples of the database size, from which its growth rate is estimated.
+pub(crate) struct DatabaseGrowth {
+	/// Samples of the last `GROWTH_WINDOW`, oldest first.
+	samples: VecDeque<(Instant, u64)>,
+}
+
+impl DatabaseGrowth {
+	pub(crate) fn new() -> Self {
+		Self { samples: VecDeque::new() }
+	}
+
+	/// Record the `size` of the database at `now`, and return the growth rate in bytes per hour
+	/// over the last hour, if it has been sampled for long enough.
+	///
+	/// The rate is negative if the database shrank, e.g. after a compaction.
+	pub(crate) fn record(&mut self, now: Instant, size: u64) -> Option<i64> {
+		if self
+			.samples
+			.back()
+			.map_or(true, |(at, _)| now.saturating_duration_since(*at) >= SAMPLE_INTERVAL)
+		{
+			self.samples.push_back((now, size));
+		}
+		while self
+			.samples
+			.front()
+			.is_some_and(|(at, _)| now.saturating_duration_since(*at) > GROWTH_WINDOW)
+		{
+			self.samples.pop_front();
+		}
+
+		let (oldest_at, oldest_size) = *self.samples.front()?;
+		let elapsed = now.saturating_duration_since(oldest_at);
+		if elapsed < SAMPLE_INTERVAL {
+			return None
+		}
+		let growth = size as f64 - oldest_size as f64;
+		Some((growth * GROWTH_WINDOW.as_secs_f64() / elapsed.as_secs_f64()) as i64)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn growth_is_extrapolated_to_an_hour() {
+		let start = Instant::now();
+		let mut growth = DatabaseGrowth::new();
+		assert_eq!(growth.record(start, 1000), None);
+		assert_eq!(growth.record(start + Duration::from_secs(5), 1010), None);
+
+		// 600 bytes in 10 minutes.
+		assert_eq!(growth.record(start + Duration::from_secs(600), 1600), Some(3600));
+
+		// The first samples fall out of the window.
+		assert_eq!(
+			growth.record(start + GROWTH_WINDOW + Duration::from_secs(600), 1000),
+			Some(-600),
+		);
+	}
+}
@@ -20,24 +20,65 @@ use console::style;
 use log::info;
 use sc_client_api::ClientInfo;
 use sc_network::NetworkStatus;
-use sc_network_sync::{SyncState, SyncStatus, WarpSyncPhase, WarpSyncProgress};
-use sp_runtime::traits::{Block as BlockT, CheckedDiv, NumberFor, Saturating, Zero};
-use std::{fmt, time::Instant};
+use sc_network_sync::{
+	strategy::state_sync::StateSyncProgress, SyncState, SyncStatus, WarpSyncPhase, WarpSyncProgress,
+};
+use sc_transaction_pool_api::PoolStatus;
+use sp_runtime::{
+	traits::{Block as BlockT, CheckedDiv, NumberFor, Saturating, Zero},
+	SaturatedConversion,
+};
+use std::{
+	fmt,
+	path::PathBuf,
+	time::{Duration, Instant},
+};
 
-use crate::PrintFullHashOnDebugLogging;
+use crate::{
+	database::DatabaseGrowth,
+	progress_bar::ProgressBar,
+	relay_chain::{self, RelayChainStatus},
+	status_file::{Status, StatusFile},
+	Fields, FinalityLagThresholds, PrintFullHashOnDebugLogging,
+};
+
+/// Weight of the latest measured import speed in the moving average of the import speed.
+const SPEED_SMOOTHING: f64 = 0.1;
+
+/// The number of characters of the progress bar itself.
+const PROGRESS_BAR_WIDTH: usize = 30;
 
 /// State of the informant display system.
 ///
 /// This is the system that handles the line that gets regularly printed and that looks something
 /// like:
 ///
-/// > Syncing  5.4 bps, target=#531028 (4 peers), best: #90683 (0x4ca8…51b8),
-/// > finalized #360 (0x6f24…a38b), ⬇ 5.5kiB/s ⬆ 0.9kiB/s
+/// > Syncing  5.4 bps, target=#531028, ⏳ ~22h 45m remaining (4 peers),
+/// > best: #90683 (0x4ca8…51b8), finalized #360 (0x6f24…a38b), lag 90323,
+/// > txpool: 12 ready, 0 future, 4.1kiB, db: 812.4GiB (+95.2MiB/h), ⬇ 5.5kiB/s ⬆ 0.9kiB/s
+///
+/// During warp sync and state sync, it is followed by a line showing the progress of the current
+/// phase, for example:
+///
+/// > Warp: downloading finality proofs 63%, 12.4MiB downloaded
+///
+/// For a parachain given the status of its relay chain, the status line is followed by a line
+/// showing the heads of the relay chain and the collation backlog, for example:
+///
+/// > 🔗 Relay chain, best: #3150 (0x3f1c…8a2b), finalized #3147 (0x91e0…c4d7) (12 peers),
+/// > collation backlog 2
+///
+/// If the progress bar is enabled and stderr is a terminal, the line is replaced during a major
+/// sync by a bar redrawn in place, like:
+///
+/// > ⚙️  Syncing  5.4 bps [█████████████░░░░░░░░░░░░░░░░░]  45.2% #240102 / #531028, ⏳ ~14h 57m
 ///
 /// # Usage
 ///
 /// Call `InformantDisplay::new` to initialize the state, then regularly call `display` with the
 /// information to display.
+///
+/// If a status file is configured, the displayed information is also written to it as JSON.
 pub struct InformantDisplay<B: BlockT> {
 	/// Head of chain block number from the last time `display` has been called.
 	/// `None` if `display` has never been called.
@@ -48,26 +89,61 @@ pub struct InformantDisplay<B: BlockT> {
 	last_total_bytes_inbound: u64,
 	/// The last seen total of bytes sent.
 	last_total_bytes_outbound: u64,
+	/// Exponential moving average of the import speed, in blocks per second.
+	/// `None` until the speed has been measured once.
+	average_speed: Option<f64>,
+	/// The fields to display.
+	fields: Fields,
+	/// The thresholds from which the finality lag is highlighted.
+	finality_lag_thresholds: FinalityLagThresholds,
+	/// The samples of the database size.
+	database_growth: DatabaseGrowth,
+	/// The log target of the displayed line.
+	log_target: String,
+	/// The file the status is written to, if any.
+	status_file: Option<StatusFile>,
+	/// The progress bar drawn during a major sync, if enabled, stderr is a terminal and no other
+	/// informant draws one.
+	progress_bar: Option<ProgressBar>,
 }
 
 impl<B: BlockT> InformantDisplay<B> {
 	/// Builds a new informant display system.
-	pub fn new() -> InformantDisplay<B> {
+	pub fn new(
+		fields: Fields,
+		finality_lag_thresholds: FinalityLagThresholds,
+		log_target: String,
+		status_file: Option<PathBuf>,
+		progress_bar: bool,
+	) -> InformantDisplay<B> {
 		InformantDisplay {
 			last_number: None,
 			last_update: Instant::now(),
 			last_total_bytes_inbound: 0,
 			last_total_bytes_outbound: 0,
+			average_speed: None,
+			fields,
+			finality_lag_thresholds,
+			database_growth: DatabaseGrowth::new(),
+			log_target,
+			status_file: status_file.map(StatusFile::new),
+			progress_bar: progress_bar.then(ProgressBar::stderr).flatten(),
 		}
 	}
 
 	/// Displays the informant by calling `info!`.
+	///
+	/// The `fragments` of the [`InformantDataProvider`](crate::InformantDataProvider)s are
+	/// appended to the line, and the `relay_chain` status, if any, is displayed on a second line.
 	pub fn display(
 		&mut self,
 		info: &ClientInfo<B>,
 		net_status: NetworkStatus,
 		sync_status: SyncStatus<B>,
 		num_connected_peers: usize,
+		pool_status: Option<PoolStatus>,
+		fragments: Vec<String>,
+		relay_chain: Option<RelayChainStatus>,
 	) {
 		let best_number = info.chain.best_number;
 		let best_hash = info.chain.best_hash;
@@ -77,6 +153,17 @@ impl<B: BlockT> InformantDisplay<B> {
 		let total_bytes_outbound = net_status.total_bytes_outbound;
 
 		let now = Instant::now();
+		if let Some(last_number) = self.last_number {
+			let imported = best_number.saturating_sub(last_number).saturated_into::<u64>();
+			let seconds = (now - self.last_update).as_secs_f64();
+			if seconds > 0.0 {
+				let speed = imported as f64 / seconds;
+				self.average_speed = Some(match self.average_speed {
+					Some(average) => average + SPEED_SMOOTHING * (speed - average),
+					None => speed,
+				});
+			}
+		}
 		let elapsed = (now - self.last_update).as_secs();
 		self.last_update = now;
 		self.last_number = Some(best_number);
@@ -91,6 +178,32 @@ impl<B: BlockT> InformantDisplay<B> {
 			(diff_bytes_inbound, diff_bytes_outbound)
 		};
 
+		let target_block = |target: NumberFor<B>| {
+			if !self.fields.target_block {
+				return String::new()
+			}
+			let remaining = target.saturating_sub(best_number).saturated_into::<u64>();
+			match self.average_speed.filter(|speed| *speed > 0.0) {
+				Some(speed) if remaining > 0 => format!(
+					", target=#{target}, ⏳ ~{} remaining",
+					RemainingTimeFormat(Duration::from_secs_f64(remaining as f64 / speed)),
+				),
+				_ => format!(", target=#{target}"),
+			}
+		};
+		let (sync_state, sync_target) = match sync_status.state {
+			SyncState::Idle => ("idle", None),
+			SyncState::Downloading { target } => ("downloading", Some(target)),
+			SyncState::Importing { target } => ("importing", Some(target)),
+		};
+		let phase = match (&sync_status.warp_sync, &sync_status.state_sync) {
+			(Some(warp), _) if !matches!(warp.phase, WarpSyncPhase::DownloadingBlocks(_)) =>
+				Some(warp_sync_phase(warp)),
+			(_, Some(state)) => Some(state_sync_phase(state)),
+			_ => None,
+		};
+		// The progress bar only shows the sync of the blocks, the other phases have their own line.
+		let progress_target = sync_target.filter(|_| phase.is_none());
 		let (level, status, target) =
 			match (sync_status.state, sync_status.state_sync, sync_status.warp_sync) {
 				// Do not set status to "Block history" when we are doing a major sync.
@@ -101,51 +214,192 @@ impl<B: BlockT> InformantDisplay<B> {
 				(
 					sync_status,
 					_,
-					Some(WarpSyncProgress { phase: WarpSyncPhase::DownloadingBlocks(n), .. }),
-				) if !sync_status.is_major_syncing() => ("⏩", "Block history".into(), format!(", #{}", n)),
-				// Handle all phases besides the two phases we already handle above.
+					Some(WarpSyncProgress {
+						phase: WarpSyncPhase::DownloadingBlocks(n),
+						percentage,
+						..
+					}),
+				) if !sync_status.is_major_syncing() => (
+					"⏩",
+					"Block history".into(),
+					match percentage {
+						Some(percentage) => format!(", #{n} ({percentage}%)"),
+						None => format!(", #{n}"),
+					},
+				),
+				// Handle all phases besides the two phases we already handle above. The details
+				// are shown on the phase line.
 				(_, _, Some(warp))
 					if !matches!(warp.phase, WarpSyncPhase::DownloadingBlocks(_)) =>
-					(
-						"⏩",
-						"Warping".into(),
-						format!(
-							", {}, {:.2} Mib",
-							warp.phase,
-							(warp.total_bytes as f32) / (1024f32 * 1024f32)
-						),
-					),
-				(_, Some(state), _) => (
-					"⚙️ ",
-					"State sync".into(),
-					format!(
-						", {}, {}%, {:.2} Mib",
-						state.phase,
-						state.percentage,
-						(state.size as f32) / (1024f32 * 1024f32)
-					),
-				),
+					("⏩", "Warping".into(), "".into()),
+				(_, Some(_), _) => ("⚙️ ", "State sync".into(), "".into()),
 				(SyncState::Idle, _, _) => ("💤", "Idle".into(), "".into()),
 				(SyncState::Downloading { target }, _, _) =>
-					("⚙️ ", format!("Syncing{}", speed), format!(", target=#{target}")),
+					("⚙️ ", format!("Syncing{}", speed), target_block(target)),
 				(SyncState::Importing { target }, _, _) =>
-					("⚙️ ", format!("Preparing{}", speed), format!(", target=#{target}")),
+					("⚙️ ", format!("Preparing{}", speed), target_block(target)),
 			};
 
-		info!(
-			target: "substrate",
-			"{} {}{} ({} peers), best: #{} ({}), finalized #{} ({}), ⬇ {} ⬆ {}",
-			level,
-			style(&status).white().bold(),
-			target,
-			style(num_connected_peers).white().bold(),
-			style(best_number).white().bold(),
-			PrintFullHashOnDebugLogging(&best_hash),
-			style(finalized_number).white().bold(),
-			PrintFullHashOnDebugLogging(&info.chain.finalized_hash),
-			style(TransferRateFormat(avg_bytes_per_sec_inbound)).green(),
-			style(TransferRateFormat(avg_bytes_per_sec_outbound)).red(),
-		)
+		let peers = if self.fields.peers {
+			format!(" ({} peers)", style(num_connected_peers).white().bold())
+		} else {
+			String::new()
+		};
+		let finality_lag = best_number.saturating_sub(finalized_number).saturated_into::<u64>();
+		let thresholds = self.finality_lag_thresholds;
+		let finality_lag = if finality_lag >= thresholds.critical.into() {
+			style(finality_lag).red().bold()
+		} else if finality_lag >= thresholds.warning.into() {
+			style(finality_lag).yellow().bold()
+		} else {
+			style(finality_lag).white().bold()
+		};
+		let bandwidth = if self.fields.bandwidth {
+			format!(
+				", ⬇ {} ⬆ {}",
+				style(TransferRateFormat(avg_bytes_per_sec_inbound)).green(),
+				style(TransferRateFormat(avg_bytes_per_sec_outbound)).red(),
+			)
+		} else {
+			String::new()
+		};
+		let transaction_pool = match pool_status {
+			Some(status) => format!(
+				", txpool: {} ready, {} future, {}",
+				style(status.ready).white().bold(),
+				style(status.future).white().bold(),
+				SizeFormat((status.ready_bytes + status.future_bytes) as u64),
+			),
+			None => String::new(),
+		};
+		let database_size = match info.usage.as_ref().and_then(|usage| usage.database_size) {
+			Some(size) if self.fields.database_size => {
+				let growth = match self.database_growth.record(now, size) {
+					Some(growth) if growth < 0 =>
+						format!(" (-{}/h)", SizeFormat(growth.unsigned_abs())),
+					Some(growth) => format!(" (+{}/h)", SizeFormat(growth as u64)),
+					None => String::new(),
+				};
+				format!(", db: {}{}", SizeFormat(size), growth)
+			},
+			_ => String::new(),
+		};
+		let fragments: String = fragments.iter().map(|fragment| format!(", {fragment}")).collect();
+
+		match (&mut self.progress_bar, progress_target) {
+			(Some(progress_bar), Some(target)) => progress_bar.draw(&format!(
+				"{} {} {}",
+				level,
+				style(&status).white().bold(),
+				progress_line(
+					best_number.saturated_into(),
+					target.saturated_into(),
+					self.average_speed,
+				),
+			)),
+			(progress_bar, _) => {
+				if let Some(progress_bar) = progress_bar {
+					progress_bar.finish();
+				}
+				info!(
+					target: &self.log_target,
+					"{} {}{}{}, best: #{} ({}), finalized #{} ({}), lag {}{}{}{}{}",
+					level,
+					style(&status).white().bold(),
+					target,
+					peers,
+					style(best_number).white().bold(),
+					PrintFullHashOnDebugLogging(&best_hash),
+					style(finalized_number).white().bold(),
+					PrintFullHashOnDebugLogging(&info.chain.finalized_hash),
+					finality_lag,
+					transaction_pool,
+					database_size,
+					bandwidth,
+					fragments,
+				);
+				if let Some(relay_chain) = relay_chain {
+					info!(target: &self.log_target, "{}", relay_chain::status_line(&relay_chain));
+				}
+			},
+		}
+
+		if let Some(phase) = phase {
+			info!(target: &self.log_target, "{} {}", level, phase);
+		}
+
+		if let Some(status_file) = &mut self.status_file {
+			status_file.write(&Status {
+				timestamp: Status::now(),
+				sync_state,
+				sync_target: sync_target.map(|target| target.saturated_into()),
+				best_number: best_number.saturated_into(),
+				best_hash: format!("{best_hash:?}"),
+				finalized_number: finalized_number.saturated_into(),
+				finalized_hash: format!("{:?}", info.chain.finalized_hash),
+				peers: num_connected_peers,
+				download_bandwidth: avg_bytes_per_sec_inbound,
+				upload_bandwidth: avg_bytes_per_sec_outbound,
+			});
+		}
+	}
+}
+
+/// The phase line of a warp sync, e.g. `Warp: downloading finality proofs 63%, 12.4MiB
+/// downloaded`.
+fn warp_sync_phase<B: BlockT>(warp: &WarpSyncProgress<B>) -> String {
+	let percentage =
+		warp.percentage.map(|percentage| format!(" {percentage}%")).unwrap_or_default();
+	format!(
+		"Warp: {}{}, {} downloaded",
+		lowercase_first(warp.phase.to_string()),
+		percentage,
+		SizeFormat(warp.total_bytes),
+	)
+}
+
+/// The phase line of a state sync, e.g. `State sync: downloading state, 1.2GiB / ~4.1GiB (29%)`.
+///
+/// The total size of the state is estimated from the downloaded size and the percentage.
+fn state_sync_phase(state: &StateSyncProgress) -> String {
+	let phase = lowercase_first(state.phase.to_string());
+	match state.percentage {
+		percentage @ 1..=99 => format!(
+			"State sync: {phase}, {} / ~{} ({percentage}%)",
+			SizeFormat(state.size),
+			SizeFormat(state.size.saturating_mul(100) / u64::from(percentage)),
+		),
+		percentage => {
+			format!("State sync: {phase}, {} ({percentage}%)", SizeFormat(state.size))
+		},
+	}
+}
+
+/// The progress bar of a sync from block `best` to block `target`, e.g.
+/// `[█████████████░░░░░░░░░░░░░░░░░]  45.2% #240102 / #531028, ⏳ ~14h 57m`.
+///
+/// The remaining time is estimated from the average import `speed`, in blocks per second.
+fn progress_line(best: u64, target: u64, speed: Option<f64>) -> String {
+	let progress = if target == 0 { 1.0 } else { (best as f64 / target as f64).min(1.0) };
+	let filled = (progress * PROGRESS_BAR_WIDTH as f64) as usize;
+	let bar = "█".repeat(filled) + &"░".repeat(PROGRESS_BAR_WIDTH - filled);
+	let remaining = target.saturating_sub(best);
+	let eta = match speed.filter(|speed| *speed > 0.0) {
+		Some(speed) if remaining > 0 => format!(
+			", ⏳ ~{}",
+			RemainingTimeFormat(Duration::from_secs_f64(remaining as f64 / speed)),
+		),
+		_ => String::new(),
+	};
+	format!("[{bar}] {:5.1}% #{best} / #{target}{eta}", progress * 100.0)
+}
+
+/// Lowercases the first character of `text`.
+fn lowercase_first(text: String) -> String {
+	let mut chars = text.chars();
+	match chars.next() {
+		Some(first) => first.to_lowercase().chain(chars).collect(),
+		None => text,
 	}
 }
 
@@ -194,6 +448,42 @@ fn speed<B: BlockT>(
 	}
 }
 
+/// Contains an estimated remaining time. Implements `fmt::Display` and shows it with a precision
+/// that is meaningful for an estimate.
+struct RemainingTimeFormat(Duration);
+impl fmt::Display for RemainingTimeFormat {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let minutes = self.0.as_secs() / 60;
+		match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+			(0, 0, 0) => write!(f, "{}s", self.0.as_secs()),
+			(0, 0, minutes) => write!(f, "{minutes}m"),
+			(0, hours, minutes) => write!(f, "{hours}h {minutes}m"),
+			(days, hours, _) => write!(f, "{days}d {hours}h"),
+		}
+	}
+}
+
+/// Contains a number of bytes. Implements `fmt::Display` and shows this number of bytes in a nice
+/// way.
+struct SizeFormat(u64);
+impl fmt::Display for SizeFormat {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.0 < 1024 {
+			return write!(f, "{} B", self.0)
+		}
+
+		if self.0 < 1024 * 1024 {
+			return write!(f, "{:.1}kiB", self.0 as f64 / 1024.0)
+		}
+
+		if self.0 < 1024 * 1024 * 1024 {
+			return write!(f, "{:.1}MiB", self.0 as f64 / (1024.0 * 1024.0))
+		}
+
+		write!(f, "{:.1}GiB", self.0 as f64 / (1024.0 * 1024.0 * 1024.0))
+	}
+}
+
 /// Contains a number of bytes per second. Implements `fmt::Display` and shows this number of bytes
 /// per second in a nice way.
 struct TransferRateFormat(u64);
@@ -217,3 +507,50 @@ impl fmt::Display for TransferRateFormat {
 		write!(f, "{:.1}MiB/s", self.0 as f64 / (1024.0 * 1024.0))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network_sync::strategy::state_sync::StateSyncPhase;
+
+	#[test]
+	fn state_sync_phase_estimates_total_size() {
+		let progress = |percentage, size| StateSyncProgress {
+			percentage,
+			size,
+			phase: StateSyncPhase::DownloadingState,
+		};
+
+		assert_eq!(
+			state_sync_phase(&progress(25, 1024 * 1024 * 1024)),
+			"State sync: downloading state, 1.0GiB / ~4.0GiB (25%)",
+		);
+		assert_eq!(
+			state_sync_phase(&progress(0, 0)),
+			"State sync: downloading state, 0 B (0%)",
+		);
+		assert_eq!(
+			state_sync_phase(&StateSyncProgress {
+				phase: StateSyncPhase::ImportingState,
+				..progress(100, 3 * 1024 * 1024)
+			}),
+			"State sync: importing state, 3.0MiB (100%)",
+		);
+	}
+
+	#[test]
+	fn progress_line_shows_progress_and_remaining_time() {
+		assert_eq!(
+			progress_line(450, 1000, Some(0.5)),
+			"[█████████████░░░░░░░░░░░░░░░░░]  45.0% #450 / #1000, ⏳ ~18m",
+		);
+		assert_eq!(
+			progress_line(1000, 1000, Some(10.0)),
+			"[██████████████████████████████] 100.0% #1000 / #1000",
+		);
+		assert_eq!(
+			progress_line(0, 0, None),
+			"[██████████████████████████████] 100.0% #0 / #0",
+		);
+	}
+}
@@ -18,69 +18,478 @@
 
 //! Console informant. Prints sync progress and block events. Runs on the calling thread.
 
+use authored::AuthoredBlocks;
 use console::style;
 use futures::prelude::*;
 use futures_timer::Delay;
-use log::{debug, info, log_enabled, trace};
+use log::{debug, info, log_enabled, trace, warn};
+use prometheus_endpoint::Registry;
+use reorg::ReorgStats;
 use sc_client_api::{BlockchainEvents, UsageProvider};
 use sc_network::NetworkStatusProvider;
 use sc_network_sync::{SyncStatusProvider, SyncingService};
+use sc_transaction_pool_api::{PoolStatus, TransactionPool};
 use sp_blockchain::HeaderMetadata;
-use sp_runtime::traits::{Block as BlockT, Header};
+use sp_consensus::BlockOrigin;
+use sp_runtime::{
+	traits::{Block as BlockT, Header},
+	SaturatedConversion,
+};
 use std::{
 	collections::VecDeque,
 	fmt::{Debug, Display},
+	path::PathBuf,
 	sync::Arc,
-	time::Duration,
+	time::{Duration, Instant},
 };
 
+mod authored;
+mod database;
 mod display;
+mod peers;
+mod progress_bar;
+mod relay_chain;
+mod reorg;
+mod status_file;
+
+pub use relay_chain::{RelayChain, RelayChainStatus, RelayChainStatusProvider};
 
 /// Creates a stream that returns a new value every `duration`.
 fn interval(duration: Duration) -> impl Stream<Item = ()> + Unpin {
 	futures::stream::unfold((), move |_| Delay::new(duration).map(|_| Some(((), ())))).map(drop)
 }
 
-/// Builds the informant and returns a `Future` that drives the informant.
-pub async fn build<B: BlockT, C, N>(client: Arc<C>, network: N, syncing: Arc<SyncingService<B>>)
-where
-	N: NetworkStatusProvider,
-	C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
-	<C as HeaderMetadata<B>>::Error: Display,
-{
-	let mut display = display::InformantDisplay::new();
+/// The log target used by the informant by default.
+pub const DEFAULT_LOG_TARGET: &str = "substrate";
 
-	let client_1 = client.clone();
+/// The fields of the regularly printed status line that can be enabled or disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fields {
+	/// The number of connected peers.
+	pub peers: bool,
+	/// The average download and upload bandwidth.
+	pub bandwidth: bool,
+	/// The target block of the sync, and the estimated time remaining to reach it.
+	pub target_block: bool,
+	/// The usage statistics of the backend, logged at trace level under the `usage` target.
+	pub usage: bool,
+	/// The size of the ready and future queues of the transaction pool, if one is provided.
+	pub transaction_pool: bool,
+	/// The size of the database on disk and its growth rate per hour, if the backend provides it.
+	pub database_size: bool,
+	/// The slowest sync peers, with their best block and pending requests, logged on a separate
+	/// line at debug level.
+	pub sync_peers: bool,
+}
 
-	let display_notifications = interval(Duration::from_millis(5000))
-		.filter_map(|_| async {
-			let net_status = network.status().await;
-			let sync_status = syncing.status().await;
-			let num_connected_peers = syncing.num_connected_peers();
+impl Default for Fields {
+	fn default() -> Self {
+		Self {
+			peers: true,
+			bandwidth: true,
+			target_block: true,
+			usage: true,
+			transaction_pool: true,
+			database_size: true,
+			sync_peers: true,
+		}
+	}
+}
 
-			match (net_status, sync_status) {
-				(Ok(net), Ok(sync)) => Some((net, sync, num_connected_peers)),
-				_ => None,
-			}
-		})
-		.for_each(move |(net_status, sync_status, num_connected_peers)| {
-			let info = client_1.usage_info();
-			if let Some(ref usage) = info.usage {
-				trace!(target: "usage", "Usage statistics: {}", usage);
-			} else {
-				trace!(
-					target: "usage",
-					"Usage statistics not displayed as backend does not provide it",
-				)
-			}
-			display.display(&info, net_status, sync_status, num_connected_peers);
-			future::ready(())
-		});
+/// Provider of the status of a transaction pool, displayed in the status line.
+pub trait TransactionPoolStatusProvider: Send + Sync {
+	/// Returns the current status of the pool.
+	fn status(&self) -> PoolStatus;
+}
 
-	futures::select! {
-		() = display_notifications.fuse() => (),
-		() = display_block_import(client).fuse() => (),
-	};
+impl<T: TransactionPool> TransactionPoolStatusProvider for T {
+	fn status(&self) -> PoolStatus {
+		TransactionPool::status(self)
+	}
+}
+
+/// Provider of additional data displayed at the end of the status line.
+///
+/// Allows chains to add their own fields to the status line, e.g. the state of one of their
+/// pallets. Closures returning an `Option<String>` implement this trait.
+pub trait InformantDataProvider: Send + Sync {
+	/// Returns the fragment appended to the status line at this tick, or `None` to append nothing.
+	fn fragment(&self) -> Option<String>;
+}
+
+impl<F: Fn() -> Option<String> + Send + Sync> InformantDataProvider for F {
+	fn fragment(&self) -> Option<String> {
+		self()
+	}
+}
+
+/// Provider of the session of the blocks, used to count the blocks authored by the node per
+/// session.
+///
+/// Closures mapping a block number to its session index implement this trait, e.g.
+/// `|number| Some(number / 600)` for sessions of 600 blocks.
+pub trait SessionProvider: Send + Sync {
+	/// Returns the index of the session of the block `number`, or `None` if it is unknown.
+	fn session(&self, number: u64) -> Option<u64>;
+}
+
+impl<F: Fn(u64) -> Option<u64> + Send + Sync> SessionProvider for F {
+	fn session(&self, number: u64) -> Option<u64> {
+		self(number)
+	}
+}
+
+impl Debug for dyn SessionProvider {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("SessionProvider")
+	}
+}
+
+/// Thresholds of the finality lag, the number of blocks between the best and the finalized block,
+/// from which the lag is highlighted in the status line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalityLagThresholds {
+	/// The lag from which it is printed in yellow.
+	pub warning: u32,
+	/// The lag from which it is printed in red.
+	pub critical: u32,
+}
+
+impl Default for FinalityLagThresholds {
+	fn default() -> Self {
+		Self { warning: 10, critical: 50 }
+	}
+}
+
+/// The settings of the informant chosen by the operator of the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InformantConfig {
+	/// The interval at which the status line is printed.
+	pub interval: Duration,
+	/// The thresholds from which the finality lag is highlighted.
+	pub finality_lag_thresholds: FinalityLagThresholds,
+	/// The JSON file the status is written to at every tick, if any.
+	pub status_file: Option<PathBuf>,
+	/// The import duration from which a warning is printed for the imported block, if any.
+	pub slow_import_threshold: Option<Duration>,
+	/// Whether a progress bar replaces the status line during a major sync.
+	pub progress_bar: bool,
+}
+
+impl Default for InformantConfig {
+	fn default() -> Self {
+		Self {
+			interval: Duration::from_millis(5000),
+			finality_lag_thresholds: FinalityLagThresholds::default(),
+			status_file: None,
+			slow_import_threshold: Some(Duration::from_secs(2)),
+			progress_bar: false,
+		}
+	}
+}
+
+/// Builder of the informant.
+///
+/// The defaults print the status line every 5 seconds with all fields enabled, under the
+/// [`DEFAULT_LOG_TARGET`].
+#[derive(Debug, Clone)]
+pub struct InformantBuilder {
+	interval: Duration,
+	fields: Fields,
+	finality_lag_thresholds: FinalityLagThresholds,
+	log_target: String,
+	status_file: Option<PathBuf>,
+	session_provider: Option<Arc<dyn SessionProvider>>,
+	slow_import_threshold: Option<Duration>,
+	progress_bar: bool,
+	relay_chain: Option<Arc<dyn RelayChainStatusProvider>>,
+}
+
+impl Default for InformantBuilder {
+	fn default() -> Self {
+		let config = InformantConfig::default();
+		Self {
+			interval: config.interval,
+			fields: Fields::default(),
+			finality_lag_thresholds: config.finality_lag_thresholds,
+			log_target: DEFAULT_LOG_TARGET.into(),
+			status_file: config.status_file,
+			session_provider: None,
+			slow_import_threshold: config.slow_import_threshold,
+			progress_bar: config.progress_bar,
+			relay_chain: None,
+		}
+	}
+}
+
+impl InformantBuilder {
+	/// Create a new builder with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Apply the settings chosen by the operator of the node.
+	pub fn with_config(self, config: InformantConfig) -> Self {
+		Self {
+			interval: config.interval,
+			finality_lag_thresholds: config.finality_lag_thresholds,
+			status_file: config.status_file,
+			slow_import_threshold: config.slow_import_threshold,
+			progress_bar: config.progress_bar,
+			..self
+		}
+	}
+
+	/// Set the interval at which the status line is printed.
+	pub fn with_interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+		self
+	}
+
+	/// Set all fields of the status line at once.
+	pub fn with_fields(mut self, fields: Fields) -> Self {
+		self.fields = fields;
+		self
+	}
+
+	/// Enable or disable the number of connected peers.
+	pub fn with_peers(mut self, enabled: bool) -> Self {
+		self.fields.peers = enabled;
+		self
+	}
+
+	/// Enable or disable the download and upload bandwidth.
+	pub fn with_bandwidth(mut self, enabled: bool) -> Self {
+		self.fields.bandwidth = enabled;
+		self
+	}
+
+	/// Enable or disable the target block of the sync.
+	pub fn with_target_block(mut self, enabled: bool) -> Self {
+		self.fields.target_block = enabled;
+		self
+	}
+
+	/// Enable or disable the usage statistics of the backend.
+	pub fn with_usage(mut self, enabled: bool) -> Self {
+		self.fields.usage = enabled;
+		self
+	}
+
+	/// Enable or disable the status of the transaction pool.
+	pub fn with_transaction_pool(mut self, enabled: bool) -> Self {
+		self.fields.transaction_pool = enabled;
+		self
+	}
+
+	/// Enable or disable the size of the database and its growth rate.
+	pub fn with_database_size(mut self, enabled: bool) -> Self {
+		self.fields.database_size = enabled;
+		self
+	}
+
+	/// Enable or disable the breakdown of the slowest sync peers.
+	pub fn with_sync_peers(mut self, enabled: bool) -> Self {
+		self.fields.sync_peers = enabled;
+		self
+	}
+
+	/// Set the thresholds from which the finality lag is highlighted.
+	pub fn with_finality_lag_thresholds(mut self, thresholds: FinalityLagThresholds) -> Self {
+		self.finality_lag_thresholds = thresholds;
+		self
+	}
+
+	/// Set the log target of the status line and the imported blocks.
+	pub fn with_log_target(mut self, log_target: impl Into<String>) -> Self {
+		self.log_target = log_target.into();
+		self
+	}
+
+	/// Write the status to the JSON file at `path` at every tick, for external watchdogs.
+	///
+	/// The file contains the best and finalized blocks, the number of peers, the sync state and
+	/// the bandwidth. It is replaced atomically, so it never contains a partially written status.
+	pub fn with_status_file(mut self, path: impl Into<PathBuf>) -> Self {
+		self.status_file = Some(path.into());
+		self
+	}
+
+	/// Count the blocks authored by the node per session of `session_provider`, instead of since
+	/// the start of the node.
+	pub fn with_session_provider(mut self, session_provider: Arc<dyn SessionProvider>) -> Self {
+		self.session_provider = Some(session_provider);
+		self
+	}
+
+	/// Set the import duration from which a warning is printed for the imported block, or `None`
+	/// to disable the warning. Defaults to 2 seconds.
+	pub fn with_slow_import_threshold(mut self, threshold: Option<Duration>) -> Self {
+		self.slow_import_threshold = threshold;
+		self
+	}
+
+	/// Enable or disable the progress bar replacing the status line during a major sync.
+	///
+	/// The bar is drawn on stderr, only if it is a terminal and no other informant of the process
+	/// draws a bar, the status line is printed otherwise. Disabled by default.
+	pub fn with_progress_bar(mut self, enabled: bool) -> Self {
+		self.progress_bar = enabled;
+		self
+	}
+
+	/// Display the status of the relay chain of a parachain on a second line, below the status
+	/// line of the parachain.
+	///
+	/// Use [`RelayChain`] for a relay chain node given its client and syncing service.
+	pub fn with_relay_chain(mut self, relay_chain: Arc<dyn RelayChainStatusProvider>) -> Self {
+		self.relay_chain = Some(relay_chain);
+		self
+	}
+
+	/// Builds the informant and returns a `Future` that drives the informant.
+	///
+	/// The status of the `transaction_pool`, if any, is included in the status line, followed by
+	/// the fragments of the `data_providers`, in order. The statistics of the detected reorgs are
+	/// summarized every hour, and exported to the `prometheus_registry` if any.
+	pub async fn build<B: BlockT, C, N>(
+		self,
+		client: Arc<C>,
+		network: N,
+		syncing: Arc<SyncingService<B>>,
+		transaction_pool: Option<Arc<dyn TransactionPoolStatusProvider>>,
+		data_providers: Vec<Arc<dyn InformantDataProvider>>,
+		prometheus_registry: Option<Registry>,
+	) where
+		N: NetworkStatusProvider,
+		C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
+		<C as HeaderMetadata<B>>::Error: Display,
+	{
+		let Self {
+			interval: tick,
+			fields,
+			finality_lag_thresholds,
+			log_target,
+			status_file,
+			session_provider,
+			slow_import_threshold,
+			progress_bar,
+			relay_chain,
+		} = self;
+		let mut display = display::InformantDisplay::new(
+			fields,
+			finality_lag_thresholds,
+			log_target.clone(),
+			status_file,
+			progress_bar,
+		);
+
+		let reorg_stats = ReorgStats::new(prometheus_registry.as_ref());
+		let client_1 = client.clone();
+		let peers_log_target = log_target.clone();
+
+		let display_notifications = interval(tick)
+			.filter_map(|_| async {
+				let net_status = network.status().await;
+				let sync_status = syncing.status().await;
+				let num_connected_peers = syncing.num_connected_peers();
+				let sync_peers =
+					if fields.sync_peers && log_enabled!(target: &log_target, log::Level::Debug) {
+						syncing.peers_sync_details().await.ok()
+					} else {
+						None
+					};
+
+				let relay_chain_status = match &relay_chain {
+					Some(relay_chain) => relay_chain.status().await,
+					None => None,
+				};
+
+				match (net_status, sync_status) {
+					(Ok(net), Ok(sync)) =>
+						Some((net, sync, num_connected_peers, sync_peers, relay_chain_status)),
+					_ => None,
+				}
+			})
+			.for_each(
+				move |(
+					net_status,
+					sync_status,
+					num_connected_peers,
+					sync_peers,
+					relay_chain_status,
+				)| {
+					let info = client_1.usage_info();
+					if fields.usage {
+						if let Some(ref usage) = info.usage {
+							trace!(target: "usage", "Usage statistics: {}", usage);
+						} else {
+							trace!(
+								target: "usage",
+								"Usage statistics not displayed as backend does not provide it",
+							)
+						}
+					}
+					let pool_status = transaction_pool
+						.as_ref()
+						.filter(|_| fields.transaction_pool)
+						.map(|pool| pool.status());
+					let fragments =
+						data_providers.iter().filter_map(|provider| provider.fragment()).collect();
+					display.display(
+						&info,
+						net_status,
+						sync_status,
+						num_connected_peers,
+						pool_status,
+						fragments,
+						relay_chain_status,
+					);
+					if let Some(sync_peers) = sync_peers.filter(|sync_peers| !sync_peers.is_empty())
+					{
+						debug!(
+							target: &peers_log_target,
+							"🐢 Slowest sync peers: {}",
+							peers::slowest_peers(sync_peers, peers::LISTED_PEERS),
+						);
+					}
+					future::ready(())
+				},
+			);
+
+		let block_imports = display_block_import(
+			client,
+			&log_target,
+			reorg_stats,
+			session_provider,
+			slow_import_threshold,
+		);
+		futures::select! {
+			() = display_notifications.fuse() => (),
+			() = block_imports.fuse() => (),
+		};
+	}
+}
+
+/// Builds the informant with the default settings and returns a `Future` that drives the
+/// informant.
+///
+/// Use [`InformantBuilder`] to customize the informant.
+pub async fn build<B: BlockT, C, N>(
+	client: Arc<C>,
+	network: N,
+	syncing: Arc<SyncingService<B>>,
+	transaction_pool: Option<Arc<dyn TransactionPoolStatusProvider>>,
+	data_providers: Vec<Arc<dyn InformantDataProvider>>,
+	prometheus_registry: Option<Registry>,
+) where
+	N: NetworkStatusProvider,
+	C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
+	<C as HeaderMetadata<B>>::Error: Display,
+{
+	InformantBuilder::new()
+		.build(client, network, syncing, transaction_pool, data_providers, prometheus_registry)
+		.await
 }
 
 /// Print the full hash when debug logging is enabled.
@@ -96,8 +505,13 @@ impl<H: Debug + Display> Display for PrintFullHashOnDebugLogging<'_, H> {
 	}
 }
 
-async fn display_block_import<B: BlockT, C>(client: Arc<C>)
-where
+async fn display_block_import<B: BlockT, C>(
+	client: Arc<C>,
+	log_target: &str,
+	mut reorg_stats: ReorgStats,
+	session_provider: Option<Arc<dyn SessionProvider>>,
+	slow_import_threshold: Option<Duration>,
+) where
 	C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
 	<C as HeaderMetadata<B>>::Error: Display,
 {
@@ -109,9 +523,27 @@ where
 	// Hashes of the last blocks we have seen at import.
 	let mut last_blocks = VecDeque::new();
 	let max_blocks_to_track = 100;
-	let mut notifications = client.import_notification_stream();
+	let mut authored = AuthoredBlocks::new();
+	// Import notifications, interleaved with `None` whenever the reorgs should be summarized.
+	let mut notifications = futures::stream::select(
+		client.import_notification_stream().map(Some),
+		interval(reorg::SUMMARY_WINDOW).map(|_| None),
+	);
 
 	while let Some(n) = notifications.next().await {
+		let Some(n) = n else {
+			if let Some(summary) = reorg_stats.summary(Instant::now()) {
+				info!(
+					target: log_target,
+					"♻️  {} reorgs in the last hour, max depth {}, average depth {:.1}",
+					summary.count,
+					summary.max_depth,
+					summary.average_depth,
+				);
+			}
+			continue
+		};
+
 		// detect and log reorganizations.
 		if let Some((ref last_num, ref last_hash)) = last_best {
 			if n.header.parent_hash() != last_hash && n.is_new_best {
@@ -119,15 +551,19 @@ where
 					sp_blockchain::lowest_common_ancestor(&*client, *last_hash, n.hash);
 
 				match maybe_ancestor {
-					Ok(ref ancestor) if ancestor.hash != *last_hash => info!(
-						"♻️  Reorg on #{},{} to #{},{}, common ancestor #{},{}",
-						style(last_num).red().bold(),
-						PrintFullHashOnDebugLogging(&last_hash),
-						style(n.header.number()).green().bold(),
-						PrintFullHashOnDebugLogging(&n.hash),
-						style(ancestor.number).white().bold(),
-						ancestor.hash,
-					),
+					Ok(ref ancestor) if ancestor.hash != *last_hash => {
+						info!(
+							"♻️  Reorg on #{},{} to #{},{}, common ancestor #{},{}",
+							style(last_num).red().bold(),
+							PrintFullHashOnDebugLogging(&last_hash),
+							style(n.header.number()).green().bold(),
+							PrintFullHashOnDebugLogging(&n.hash),
+							style(ancestor.number).white().bold(),
+							ancestor.hash,
+						);
+						let depth = (*last_num - ancestor.number).saturated_into::<u64>();
+						reorg_stats.record(Instant::now(), depth);
+					},
 					Ok(_) => {},
 					Err(e) => debug!("Error computing tree route: {}", e),
 				}
@@ -138,6 +574,17 @@ where
 			last_best = Some((*n.header.number(), n.hash));
 		}
 
+		if let (Some(duration), Some(threshold)) = (n.import_duration, slow_import_threshold) {
+			if duration > threshold {
+				warn!(
+					target: log_target,
+					"🐌 Slow import of #{} ({}) took {duration:.2?}",
+					n.header.number(),
+					n.hash,
+				);
+			}
+		}
+
 		// If we already printed a message for a given block recently,
 		// we should not print it again.
 		if !last_blocks.contains(&n.hash) {
@@ -147,14 +594,29 @@ where
 				last_blocks.pop_front();
 			}
 
-			let best_indicator = if n.is_new_best { "🏆" } else { "🆕" };
-			info!(
-				target: "substrate",
-				"{best_indicator} Imported #{} ({} → {})",
-				style(n.header.number()).white().bold(),
-				PrintFullHashOnDebugLogging(n.header.parent_hash()),
-				PrintFullHashOnDebugLogging(&n.hash),
-			);
+			if n.origin == BlockOrigin::Own {
+				let number = (*n.header.number()).saturated_into::<u64>();
+				let session =
+					session_provider.as_ref().and_then(|provider| provider.session(number));
+				let count = authored.record(session);
+				info!(
+					target: log_target,
+					"🎁 Authored #{} ({} → {}), {count} blocks authored {}",
+					style(n.header.number()).white().bold(),
+					PrintFullHashOnDebugLogging(n.header.parent_hash()),
+					PrintFullHashOnDebugLogging(&n.hash),
+					if session.is_some() { "this session" } else { "since start" },
+				);
+			} else {
+				let best_indicator = if n.is_new_best { "🏆" } else { "🆕" };
+				info!(
+					target: log_target,
+					"{best_indicator} Imported #{} ({} → {})",
+					style(n.header.number()).white().bold(),
+					PrintFullHashOnDebugLogging(n.header.parent_hash()),
+					PrintFullHashOnDebugLogging(&n.hash),
+				);
+			}
 		}
 	}
 }
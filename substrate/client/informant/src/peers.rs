@@ -0,0 +1,104 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-peer breakdown of the sync, logged at debug level.
+
+use sc_network_sync::types::PeerSyncDetails;
+use sp_runtime::traits::Block as BlockT;
+use std::{fmt::Display, time::Duration};
+
+/// The number of peers listed in the breakdown.
+pub(crate) const LISTED_PEERS: usize = 5;
+
+/// How long the peer keeps us waiting: the longest of its average response time and of the age
+/// of its oldest pending request.
+fn slowness<B: BlockT>(details: &PeerSyncDetails<B>) -> Duration {
+	details
+		.average_response_time
+		.max(details.oldest_pending_request)
+		.unwrap_or_default()
+}
+
+fn seconds(duration: Duration) -> String {
+	format!("{:.1}s", duration.as_secs_f64())
+}
+
+/// Describe the `limit` slowest of the `peers`, slowest first, with their best block and their
+/// pending requests.
+pub(crate) fn slowest_peers<P: Display, B: BlockT>(
+	mut peers: Vec<(P, PeerSyncDetails<B>)>,
+	limit: usize,
+) -> String {
+	peers.sort_by_key(|(_, details)| std::cmp::Reverse(slowness(details)));
+	peers
+		.iter()
+		.take(limit)
+		.map(|(peer, details)| {
+			let response_time = details.average_response_time.map_or_else(|| "n/a".into(), seconds);
+			let oldest = details
+				.oldest_pending_request
+				.map(|oldest| format!(", oldest {}", seconds(oldest)))
+				.unwrap_or_default();
+			format!(
+				"{peer} (best #{}, response {response_time}, {} pending{oldest})",
+				details.best_number, details.pending_requests,
+			)
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::testing::{Block as RawBlock, MockCallU64, TestXt};
+
+	type Block = RawBlock<TestXt<MockCallU64, ()>>;
+
+	fn details(
+		best_number: u64,
+		pending_requests: usize,
+		oldest_pending_request: Option<u64>,
+		average_response_time: Option<u64>,
+	) -> PeerSyncDetails<Block> {
+		PeerSyncDetails {
+			best_hash: Default::default(),
+			best_number,
+			pending_requests,
+			oldest_pending_request: oldest_pending_request.map(Duration::from_millis),
+			average_response_time: average_response_time.map(Duration::from_millis),
+		}
+	}
+
+	#[test]
+	fn lists_slowest_peers_first() {
+		let peers = vec![
+			("fast", details(10, 0, None, Some(200))),
+			("stuck", details(8, 1, Some(12_000), Some(300))),
+			("new", details(10, 0, None, None)),
+			("slow", details(10, 2, Some(500), Some(2_500))),
+		];
+
+		assert_eq!(
+			slowest_peers(peers, 3),
+			"stuck (best #8, response 0.3s, 1 pending, oldest 12.0s), \
+			slow (best #10, response 2.5s, 2 pending, oldest 0.5s), \
+			fast (best #10, response 0.2s, 0 pending)",
+		);
+	}
+}
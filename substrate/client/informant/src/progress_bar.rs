@@ -0,0 +1,72 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Self-updating progress bar replacing the status line during a major sync.
+
+use console::Term;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether a progress bar exists in the process. A node running several informants, e.g. a
+/// parachain node with its embedded relay chain node, would otherwise redraw the same line.
+static IN_USE: AtomicBool = AtomicBool::new(false);
+
+/// A single line of the terminal, redrawn in place at every update.
+///
+/// The bar is drawn on stderr, together with the logs.
+pub(crate) struct ProgressBar {
+	term: Term,
+	/// Whether the bar is drawn, i.e. the cursor is at the end of its line.
+	drawn: bool,
+}
+
+impl ProgressBar {
+	/// Returns the progress bar on stderr, or `None` if stderr is not a terminal or another
+	/// progress bar exists.
+	pub(crate) fn stderr() -> Option<Self> {
+		let term = Term::stderr();
+		if !term.is_term() ||
+			IN_USE.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err()
+		{
+			return None
+		}
+		Some(Self { term, drawn: false })
+	}
+
+	/// Redraws the bar with `line`, truncated to the width of the terminal so it doesn't wrap.
+	pub(crate) fn draw(&mut self, line: &str) {
+		let width = self.term.size().1 as usize;
+		let line = console::truncate_str(line, width.saturating_sub(1), "…");
+		// Failing to draw the bar is not worth interrupting the informant for.
+		let _ = self.term.clear_line().and_then(|()| self.term.write_str(&line));
+		self.drawn = true;
+	}
+
+	/// Ends the line of the bar, if it is drawn, so the following log lines don't overwrite it.
+	pub(crate) fn finish(&mut self) {
+		if std::mem::take(&mut self.drawn) {
+			let _ = self.term.write_line("");
+		}
+	}
+}
+
+impl Drop for ProgressBar {
+	fn drop(&mut self) {
+		self.finish();
+		IN_USE.store(false, Ordering::Release);
+	}
+}
@@ -0,0 +1,148 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Status of the relay chain of a parachain, displayed on a second line below the status line.
+
+use crate::PrintFullHashOnDebugLogging;
+use console::style;
+use futures::{future::BoxFuture, FutureExt};
+use sc_client_api::UsageProvider;
+use sc_network_sync::SyncingService;
+use sp_consensus::SyncOracle;
+use sp_runtime::{traits::Block as BlockT, SaturatedConversion};
+use std::{fmt, sync::Arc};
+
+/// The status of the relay chain, as displayed by the informant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayChainStatus {
+	/// Number of the best block.
+	pub best_number: u64,
+	/// Hash of the best block, as displayed.
+	pub best_hash: String,
+	/// Number of the finalized block.
+	pub finalized_number: u64,
+	/// Hash of the finalized block, as displayed.
+	pub finalized_hash: String,
+	/// The number of connected peers, if known.
+	pub peers: Option<usize>,
+	/// Whether the relay chain is in major sync.
+	pub is_major_syncing: bool,
+	/// The number of parachain blocks on top of the last parachain block included in the relay
+	/// chain, if known.
+	pub collation_backlog: Option<u64>,
+}
+
+/// The line displaying `status`, e.g. `🔗 Relay chain syncing, best: #3150 (0x3f1c…8a2b),
+/// finalized #3147 (0x91e0…c4d7) (12 peers), collation backlog 2`.
+pub(crate) fn status_line(status: &RelayChainStatus) -> String {
+	let peers = status
+		.peers
+		.map(|peers| format!(" ({} peers)", style(peers).white().bold()))
+		.unwrap_or_default();
+	let collation_backlog = status
+		.collation_backlog
+		.map(|backlog| format!(", collation backlog {}", style(backlog).white().bold()))
+		.unwrap_or_default();
+	format!(
+		"🔗 Relay chain{}, best: #{} ({}), finalized #{} ({}){}{}",
+		if status.is_major_syncing { " syncing" } else { "" },
+		style(status.best_number).white().bold(),
+		status.best_hash,
+		style(status.finalized_number).white().bold(),
+		status.finalized_hash,
+		peers,
+		collation_backlog,
+	)
+}
+
+/// Provider of the status of the relay chain of a parachain.
+pub trait RelayChainStatusProvider: Send + Sync {
+	/// Returns the current status of the relay chain, or `None` if it is not available.
+	fn status(&self) -> BoxFuture<'_, Option<RelayChainStatus>>;
+}
+
+impl fmt::Debug for dyn RelayChainStatusProvider {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("RelayChainStatusProvider")
+	}
+}
+
+/// [`RelayChainStatusProvider`] of a relay chain node running in the same process, given its
+/// client and syncing service.
+///
+/// The collation backlog is not known to the relay chain node and is never displayed.
+pub struct RelayChain<B: BlockT, C> {
+	client: Arc<C>,
+	syncing: Arc<SyncingService<B>>,
+}
+
+impl<B: BlockT, C> RelayChain<B, C> {
+	/// Create the provider of the status of the relay chain of `client` and `syncing`.
+	pub fn new(client: Arc<C>, syncing: Arc<SyncingService<B>>) -> Self {
+		Self { client, syncing }
+	}
+}
+
+impl<B: BlockT, C: UsageProvider<B> + Send + Sync> RelayChainStatusProvider for RelayChain<B, C> {
+	fn status(&self) -> BoxFuture<'_, Option<RelayChainStatus>> {
+		let info = self.client.usage_info().chain;
+		let status = RelayChainStatus {
+			best_number: info.best_number.saturated_into(),
+			best_hash: PrintFullHashOnDebugLogging(&info.best_hash).to_string(),
+			finalized_number: info.finalized_number.saturated_into(),
+			finalized_hash: PrintFullHashOnDebugLogging(&info.finalized_hash).to_string(),
+			peers: Some(self.syncing.num_connected_peers()),
+			is_major_syncing: self.syncing.is_major_syncing(),
+			collation_backlog: None,
+		};
+		futures::future::ready(Some(status)).boxed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn status_line_shows_both_heads_and_backlog() {
+		console::set_colors_enabled(false);
+		let status = RelayChainStatus {
+			best_number: 3150,
+			best_hash: "0x3f1c…8a2b".into(),
+			finalized_number: 3147,
+			finalized_hash: "0x91e0…c4d7".into(),
+			peers: Some(12),
+			is_major_syncing: false,
+			collation_backlog: Some(2),
+		};
+		assert_eq!(
+			status_line(&status),
+			"🔗 Relay chain, best: #3150 (0x3f1c…8a2b), finalized #3147 (0x91e0…c4d7) (12 peers), \
+			 collation backlog 2",
+		);
+		assert_eq!(
+			status_line(&RelayChainStatus {
+				peers: None,
+				is_major_syncing: true,
+				collation_backlog: None,
+				..status
+			}),
+			"🔗 Relay chain syncing, best: #3150 (0x3f1c…8a2b), finalized #3147 (0x91e0…c4d7)",
+		);
+	}
+}
@@ -0,0 +1,151 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Statistics of the reorgs detected at block import.
+
+use prometheus_endpoint::{
+	register, Counter, Gauge, Histogram, HistogramOpts, PrometheusError, Registry, U64,
+};
+use std::{
+	collections::VecDeque,
+	time::{Duration, Instant},
+};
+
+/// The window over which the reorgs are summarized, and the interval of the summary line.
+pub(crate) const SUMMARY_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Prometheus metrics of the reorgs.
+struct Metrics {
+	reorgs: Counter<U64>,
+	depth: Histogram,
+	max_depth: Gauge<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			reorgs: register(
+				Counter::new("substrate_reorgs_total", "Number of reorgs of the best chain")?,
+				registry,
+			)?,
+			depth: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"substrate_reorg_depth",
+						"Number of blocks retracted from the best chain by a reorg",
+					)
+					.buckets(vec![1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0, 100.0]),
+				)?,
+				registry,
+			)?,
+			max_depth: register(
+				Gauge::new("substrate_reorg_max_depth", "Largest depth of a reorg since startup")?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// The reorgs of the last [`SUMMARY_WINDOW`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Summary {
+	/// The number of reorgs.
+	pub count: usize,
+	/// The largest depth of the reorgs.
+	pub max_depth: u64,
+	/// The average depth of the reorgs.
+	pub average_depth: f64,
+}
+
+/// Collects the depth of the reorgs, exporting them to Prometheus if a registry is provided.
+pub(crate) struct ReorgStats {
+	/// The time and depth of the reorgs of the last [`SUMMARY_WINDOW`].
+	recent: VecDeque<(Instant, u64)>,
+	max_depth: u64,
+	metrics: Option<Metrics>,
+}
+
+impl ReorgStats {
+	pub(crate) fn new(registry: Option<&Registry>) -> Self {
+		let metrics = registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|err| log::warn!("Failed to register reorg prometheus metrics: {}", err))
+				.ok()
+		});
+		Self { recent: VecDeque::new(), max_depth: 0, metrics }
+	}
+
+	/// Record a reorg retracting `depth` blocks from the best chain.
+	pub(crate) fn record(&mut self, now: Instant, depth: u64) {
+		self.prune(now);
+		self.recent.push_back((now, depth));
+		self.max_depth = self.max_depth.max(depth);
+
+		if let Some(metrics) = &self.metrics {
+			metrics.reorgs.inc();
+			metrics.depth.observe(depth as f64);
+			metrics.max_depth.set(self.max_depth);
+		}
+	}
+
+	/// Summary of the reorgs of the last [`SUMMARY_WINDOW`], or `None` if there were none.
+	pub(crate) fn summary(&mut self, now: Instant) -> Option<Summary> {
+		self.prune(now);
+		let count = self.recent.len();
+		let max_depth = self.recent.iter().map(|(_, depth)| *depth).max()?;
+		let total: u64 = self.recent.iter().map(|(_, depth)| depth).sum();
+		Some(Summary { count, max_depth, average_depth: total as f64 / count as f64 })
+	}
+
+	fn prune(&mut self, now: Instant) {
+		while self
+			.recent
+			.front()
+			.is_some_and(|(at, _)| now.saturating_duration_since(*at) > SUMMARY_WINDOW)
+		{
+			self.recent.pop_front();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn summary_covers_the_last_window() {
+		let start = Instant::now();
+		let mut stats = ReorgStats::new(None);
+		assert_eq!(stats.summary(start), None);
+
+		stats.record(start, 4);
+		stats.record(start + Duration::from_secs(60), 1);
+		stats.record(start + Duration::from_secs(120), 1);
+		assert_eq!(
+			stats.summary(start + Duration::from_secs(180)),
+			Some(Summary { count: 3, max_depth: 4, average_depth: 2.0 }),
+		);
+
+		// The deepest reorg falls out of the window.
+		assert_eq!(
+			stats.summary(start + SUMMARY_WINDOW + Duration::from_secs(90)),
+			Some(Summary { count: 1, max_depth: 1, average_depth: 1.0 }),
+		);
+		assert_eq!(stats.summary(start + SUMMARY_WINDOW * 2), None);
+	}
+}
@@ -0,0 +1,132 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Machine-readable status file, rewritten at every tick of the informant.
+
+use log::warn;
+use serde::Serialize;
+use std::{
+	io,
+	path::{Path, PathBuf},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The status written to the file, as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Status {
+	/// Unix timestamp of the status, in seconds.
+	pub timestamp: u64,
+	/// The sync state: `idle`, `downloading` or `importing`.
+	pub sync_state: &'static str,
+	/// The target block of the sync, if the node is syncing.
+	pub sync_target: Option<u64>,
+	/// The number of the best block.
+	pub best_number: u64,
+	/// The hash of the best block, hex encoded.
+	pub best_hash: String,
+	/// The number of the last finalized block.
+	pub finalized_number: u64,
+	/// The hash of the last finalized block, hex encoded.
+	pub finalized_hash: String,
+	/// The number of connected peers.
+	pub peers: usize,
+	/// The average download bandwidth since the last tick, in bytes per second.
+	pub download_bandwidth: u64,
+	/// The average upload bandwidth since the last tick, in bytes per second.
+	pub upload_bandwidth: u64,
+}
+
+impl Status {
+	/// The current unix timestamp, in seconds.
+	pub(crate) fn now() -> u64 {
+		SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+	}
+}
+
+/// A status file, replaced atomically so readers never see a partially written status.
+pub(crate) struct StatusFile {
+	path: PathBuf,
+	/// Whether the last write failed, to warn only once about consecutive failures.
+	failing: bool,
+}
+
+impl StatusFile {
+	pub(crate) fn new(path: PathBuf) -> Self {
+		Self { path, failing: false }
+	}
+
+	/// Replace the content of the file with `status`.
+	pub(crate) fn write(&mut self, status: &Status) {
+		match write_atomically(&self.path, status) {
+			Ok(()) => self.failing = false,
+			Err(e) if !self.failing => {
+				warn!("Failed to write the status file {}: {e}", self.path.display());
+				self.failing = true;
+			},
+			Err(_) => {},
+		}
+	}
+}
+
+/// Write `status` to a temporary file next to `path`, then rename it to `path`.
+fn write_atomically(path: &Path, status: &Status) -> io::Result<()> {
+	let mut temporary = path.as_os_str().to_owned();
+	temporary.push(".tmp");
+	std::fs::write(&temporary, serde_json::to_vec(status)?)?;
+	std::fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn replaces_the_status() {
+		let directory = tempfile::tempdir().unwrap();
+		let path = directory.path().join("status.json");
+		let mut file = StatusFile::new(path.clone());
+		let mut status = Status {
+			timestamp: 1_700_000_000,
+			sync_state: "downloading",
+			sync_target: Some(1200),
+			best_number: 1000,
+			best_hash: "0x01".into(),
+			finalized_number: 998,
+			finalized_hash: "0x02".into(),
+			peers: 12,
+			download_bandwidth: 2048,
+			upload_bandwidth: 512,
+		};
+
+		file.write(&status);
+		assert_eq!(
+			std::fs::read_to_string(&path).unwrap(),
+			r#"{"timestamp":1700000000,"syncState":"downloading","syncTarget":1200,"bestNumber":1000,"bestHash":"0x01","finalizedNumber":998,"finalizedHash":"0x02","peers":12,"downloadBandwidth":2048,"uploadBandwidth":512}"#,
+		);
+
+		status.sync_state = "idle";
+		status.sync_target = None;
+		file.write(&status);
+		let written: serde_json::Value =
+			serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+		assert_eq!(written["syncState"], "idle");
+		assert_eq!(written["syncTarget"], serde_json::Value::Null);
+		assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+	}
+}
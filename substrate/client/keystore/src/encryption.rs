@@ -0,0 +1,293 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Encryption of the key files at rest.
+//!
+//! The key files of an encrypted keystore are sealed with AES-256-GCM under a master key, which
+//! is either derived from a passphrase or generated randomly and stored wrapped by a key
+//! management service. How to recreate the master key is recorded in [`METADATA_FILE`], together
+//! with a check value to detect a wrong passphrase before any key file is touched.
+
+use crate::{Error, Result};
+use aes_gcm::{
+	aead::{Aead, KeyInit, Payload},
+	Aes256Gcm, Key, Nonce,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sp_core::crypto::{ExposeSecret, SecretString};
+use std::{
+	fmt,
+	io::Write,
+	path::{Path, PathBuf},
+	process::{Command, Stdio},
+	sync::Arc,
+};
+use zeroize::Zeroize;
+
+/// Name of the file describing the encryption of the keystore. It isn't valid hex, so it is
+/// never mistaken for a key file.
+pub const METADATA_FILE: &str = "encryption.json";
+
+const VERSION: u32 = 1;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const CHECK_VALUE: &[u8] = b"sc-keystore master key";
+
+/// PBKDF2-HMAC-SHA512 rounds used to derive the master key from a new passphrase.
+const PBKDF2_ROUNDS: u32 = if cfg!(test) { 1_000 } else { 210_000 };
+
+/// Key management service protecting the master key of an encrypted keystore.
+///
+/// The master key is generated when the keystore is encrypted, wrapped by the service and stored
+/// in the keystore. At startup the service is asked to unwrap it again.
+pub trait Kms: Send + Sync {
+	/// Encrypt the master key for storage on disk.
+	fn wrap(&self, key: &[u8]) -> std::result::Result<Vec<u8>, String>;
+
+	/// Decrypt a master key previously encrypted with [`Kms::wrap`].
+	fn unwrap(&self, wrapped: &[u8]) -> std::result::Result<Vec<u8>, String>;
+}
+
+/// [`Kms`] plugin implemented by an external command.
+///
+/// The command is invoked as `<command> wrap` or `<command> unwrap`, receives the hex encoded
+/// input on stdin and must write the hex encoded output to stdout.
+#[derive(Debug, Clone)]
+pub struct CommandKms {
+	command: PathBuf,
+}
+
+impl CommandKms {
+	/// Create a plugin invoking `command`.
+	pub fn new(command: impl Into<PathBuf>) -> Self {
+		Self { command: command.into() }
+	}
+
+	fn run(&self, operation: &str, input: &[u8]) -> std::result::Result<Vec<u8>, String> {
+		let error = |e: &dyn fmt::Display| format!("`{} {operation}`: {e}", self.command.display());
+
+		let mut child = Command::new(&self.command)
+			.arg(operation)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(|e| error(&e))?;
+		child
+			.stdin
+			.take()
+			.expect("stdin is piped; qed")
+			.write_all(array_bytes::bytes2hex("", input).as_bytes())
+			.map_err(|e| error(&e))?;
+		let output = child.wait_with_output().map_err(|e| error(&e))?;
+		if !output.status.success() {
+			return Err(error(&String::from_utf8_lossy(&output.stderr).trim()))
+		}
+		let stdout = String::from_utf8(output.stdout).map_err(|e| error(&e))?;
+		array_bytes::hex2bytes(stdout.trim()).map_err(|_| error(&"output is not hex"))
+	}
+}
+
+impl Kms for CommandKms {
+	fn wrap(&self, key: &[u8]) -> std::result::Result<Vec<u8>, String> {
+		self.run("wrap", key)
+	}
+
+	fn unwrap(&self, wrapped: &[u8]) -> std::result::Result<Vec<u8>, String> {
+		self.run("unwrap", wrapped)
+	}
+}
+
+/// How the master key of an encrypted keystore is obtained.
+#[derive(Clone)]
+pub enum Unlock {
+	/// Derive the master key from a passphrase.
+	Passphrase(SecretString),
+	/// Unwrap the master key with a key management service.
+	Kms(Arc<dyn Kms>),
+}
+
+impl fmt::Debug for Unlock {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Passphrase(_) => write!(f, "Passphrase(..)"),
+			Self::Kms(_) => write!(f, "Kms(..)"),
+		}
+	}
+}
+
+/// Data sealed with the master key, as stored on disk.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Sealed {
+	nonce: String,
+	ciphertext: String,
+}
+
+/// Content of a key file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum KeyFile {
+	/// A key stored before the keystore was encrypted.
+	Plain(String),
+	/// A key sealed with the master key.
+	Sealed(Sealed),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "unlock")]
+enum Source {
+	Passphrase { salt: String, rounds: u32 },
+	Kms { wrapped_key: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Metadata {
+	version: u32,
+	#[serde(flatten)]
+	source: Source,
+	check: Sealed,
+}
+
+/// The key encrypting the key files.
+pub(crate) struct MasterKey(Aes256Gcm);
+
+impl MasterKey {
+	fn new(mut key: Vec<u8>) -> Result<Self> {
+		if key.len() != 32 {
+			key.zeroize();
+			return Err(Error::InvalidMasterKey)
+		}
+		let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+		key.zeroize();
+		Ok(Self(cipher))
+	}
+
+	fn derive(passphrase: &SecretString, salt: &[u8], rounds: u32) -> Result<Self> {
+		let mut key = vec![0u8; 32];
+		pbkdf2::pbkdf2_hmac::<sha2::Sha512>(
+			passphrase.expose_secret().as_bytes(),
+			salt,
+			rounds,
+			&mut key,
+		);
+		Self::new(key)
+	}
+
+	/// Unlock the master key of the keystore at `path`.
+	///
+	/// If the keystore isn't encrypted yet, a new master key is set up.
+	pub(crate) fn unlock(path: &Path, unlock: &Unlock) -> Result<Self> {
+		let metadata_path = path.join(METADATA_FILE);
+		if !metadata_path.exists() {
+			return Self::setup(&metadata_path, unlock)
+		}
+
+		let metadata: Metadata = serde_json::from_slice(&std::fs::read(&metadata_path)?)?;
+		if metadata.version != VERSION {
+			return Err(Error::UnsupportedEncryption(metadata.version))
+		}
+		let key = match (&metadata.source, unlock) {
+			(Source::Passphrase { salt, rounds }, Unlock::Passphrase(passphrase)) =>
+				Self::derive(passphrase, &decode_hex(salt)?, *rounds)?,
+			(Source::Kms { wrapped_key }, Unlock::Kms(kms)) =>
+				Self::new(kms.unwrap(&decode_hex(wrapped_key)?).map_err(Error::Kms)?)?,
+			_ => return Err(Error::InvalidMasterKey),
+		};
+		match key.open(&metadata.check, METADATA_FILE) {
+			Ok(check) if check == CHECK_VALUE => Ok(key),
+			_ => Err(Error::InvalidMasterKey),
+		}
+	}
+
+	fn setup(metadata_path: &Path, unlock: &Unlock) -> Result<Self> {
+		let (key, source) = match unlock {
+			Unlock::Passphrase(passphrase) => {
+				let mut salt = [0u8; SALT_LEN];
+				OsRng.fill_bytes(&mut salt);
+				let key = Self::derive(passphrase, &salt, PBKDF2_ROUNDS)?;
+				let salt = array_bytes::bytes2hex("", salt);
+				(key, Source::Passphrase { salt, rounds: PBKDF2_ROUNDS })
+			},
+			Unlock::Kms(kms) => {
+				let mut raw = vec![0u8; 32];
+				OsRng.fill_bytes(&mut raw);
+				let wrapped = kms.wrap(&raw);
+				let key = Self::new(raw)?;
+				let wrapped_key = array_bytes::bytes2hex("", wrapped.map_err(Error::Kms)?);
+				(key, Source::Kms { wrapped_key })
+			},
+		};
+
+		let check = key.seal(CHECK_VALUE, METADATA_FILE)?;
+		let metadata = Metadata { version: VERSION, source, check };
+		write_atomically(metadata_path, &serde_json::to_vec(&metadata)?)?;
+		Ok(key)
+	}
+
+	/// Seal `data`, binding it to the name of the file it is stored in.
+	pub(crate) fn seal(&self, data: &[u8], file_name: &str) -> Result<Sealed> {
+		let mut nonce = [0u8; NONCE_LEN];
+		OsRng.fill_bytes(&mut nonce);
+		let ciphertext = self
+			.0
+			.encrypt(Nonce::from_slice(&nonce), Payload { msg: data, aad: file_name.as_bytes() })
+			.map_err(|_| Error::Cipher)?;
+		Ok(Sealed {
+			nonce: array_bytes::bytes2hex("", nonce),
+			ciphertext: array_bytes::bytes2hex("", ciphertext),
+		})
+	}
+
+	/// Open data sealed with [`MasterKey::seal`] for the file `file_name`.
+	pub(crate) fn open(&self, sealed: &Sealed, file_name: &str) -> Result<Vec<u8>> {
+		let nonce = decode_hex(&sealed.nonce)?;
+		if nonce.len() != NONCE_LEN {
+			return Err(Error::Cipher)
+		}
+		let ciphertext = decode_hex(&sealed.ciphertext)?;
+		self.0
+			.decrypt(
+				Nonce::from_slice(&nonce),
+				Payload { msg: &ciphertext, aad: file_name.as_bytes() },
+			)
+			.map_err(|_| Error::Cipher)
+	}
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+	array_bytes::hex2bytes(hex).map_err(|_| Error::Cipher)
+}
+
+/// Replace the content of `path` with `data`, without leaving a partially written file behind.
+pub(crate) fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+	let mut tmp_name = path.file_name().unwrap_or_default().to_owned();
+	tmp_name.push(".tmp");
+	let tmp_path = path.with_file_name(tmp_name);
+
+	let mut file = std::fs::File::create(&tmp_path)?;
+	#[cfg(target_family = "unix")]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+	}
+	file.write_all(data)?;
+	file.sync_all()?;
+	std::fs::rename(tmp_path, path)?;
+	Ok(())
+}
@@ -23,9 +23,15 @@ use sp_core::crypto::KeyTypeId;
 use sp_keystore::Error as TraitError;
 use std::io;
 
+/// Encryption of the key files at rest
+mod encryption;
 /// Local keystore implementation
 mod local;
+/// Delegation of signing to external signers
+pub mod plugin;
+pub use encryption::{CommandKms, Kms, Unlock};
 pub use local::LocalKeystore;
+pub use plugin::{AttestationVerifier, PluginKeystore, SigningPlugin};
 pub use sp_keystore::Keystore;
 
 /// Keystore error.
@@ -55,6 +61,24 @@ pub enum Error {
 	/// Keystore unavailable
 	#[error("Keystore unavailable")]
 	Unavailable,
+	/// The keystore is encrypted, but was opened without a way to unlock it.
+	#[error("Keystore is encrypted and must be unlocked with its passphrase or KMS")]
+	Locked,
+	/// The master key of the keystore couldn't be unlocked.
+	#[error("Failed to unlock the keystore: wrong passphrase or KMS key")]
+	InvalidMasterKey,
+	/// The encryption of the keystore has a version this node doesn't support.
+	#[error("Unsupported keystore encryption version {0}")]
+	UnsupportedEncryption(u32),
+	/// A key file couldn't be encrypted or decrypted.
+	#[error("Failed to encrypt or decrypt a key file")]
+	Cipher,
+	/// The key management service failed.
+	#[error("Key management service error: {0}")]
+	Kms(String),
+	/// The attestation of a signing plugin couldn't be verified.
+	#[error("Signing plugin attestation failed: {0}")]
+	Attestation(String),
 }
 
 /// Keystore Result
@@ -64,9 +88,15 @@ impl From<Error> for TraitError {
 	fn from(error: Error) -> Self {
 		match error {
 			Error::KeyNotSupported(id) => TraitError::KeyNotSupported(id),
-			Error::InvalidSeed | Error::InvalidPhrase | Error::PublicKeyMismatch =>
-				TraitError::ValidationError(error.to_string()),
-			Error::Unavailable => TraitError::Unavailable,
+			Error::InvalidSeed |
+			Error::InvalidPhrase |
+			Error::PublicKeyMismatch |
+			Error::Attestation(_) => TraitError::ValidationError(error.to_string()),
+			Error::Unavailable | Error::Locked => TraitError::Unavailable,
+			Error::InvalidMasterKey |
+			Error::UnsupportedEncryption(_) |
+			Error::Cipher |
+			Error::Kms(_) => TraitError::Other(error.to_string()),
 			Error::Io(e) => TraitError::Other(e.to_string()),
 			Error::Json(e) => TraitError::Other(e.to_string()),
 		}
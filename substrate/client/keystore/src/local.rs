@@ -18,19 +18,20 @@
 //
 //! Local keystore implementation
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use sp_application_crypto::{AppCrypto, AppPair, IsWrappedBy};
 use sp_core::{
 	crypto::{ByteArray, ExposeSecret, KeyTypeId, Pair as CorePair, SecretString, VrfSecret},
 	ecdsa, ed25519, sr25519,
 };
-use sp_keystore::{Error as TraitError, Keystore, KeystorePtr};
+use sp_keystore::{Error as TraitError, KeyUsage, Keystore, KeystorePtr};
 use std::{
 	collections::HashMap,
 	fs::{self, File},
 	io::Write,
-	path::PathBuf,
+	path::{Path, PathBuf},
 	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
 };
 
 sp_keystore::bandersnatch_experimental_enabled! {
@@ -41,7 +42,14 @@ sp_keystore::bls_experimental_enabled! {
 use sp_core::{bls381, ecdsa_bls381, KeccakHasher};
 }
 
-use crate::{Error, Result};
+sp_keystore::dilithium_experimental_enabled! {
+use sp_core::dilithium;
+}
+
+use crate::{
+	encryption::{self, KeyFile, MasterKey, Unlock},
+	Error, Result,
+};
 
 /// A local based keystore that is either memory-based or filesystem-based.
 pub struct LocalKeystore(RwLock<KeystoreInner>);
@@ -60,6 +68,23 @@ impl LocalKeystore {
 		Ok(Self(RwLock::new(inner)))
 	}
 
+	/// Create a local keystore from filesystem, encrypting the key files at rest.
+	///
+	/// The key files are encrypted with a master key obtained as described by `unlock`. When
+	/// opening a keystore that isn't encrypted yet, a new master key is set up and all existing key
+	/// files are encrypted with it. Once encrypted, the keystore can't be opened with
+	/// [`LocalKeystore::open`] anymore.
+	///
+	/// `password` is used as with [`LocalKeystore::open`].
+	pub fn open_encrypted<T: Into<PathBuf>>(
+		path: T,
+		password: Option<SecretString>,
+		unlock: &Unlock,
+	) -> Result<Self> {
+		let inner = KeystoreInner::open_with_encryption(path, password, Some(unlock))?;
+		Ok(Self(RwLock::new(inner)))
+	}
+
 	/// Create a local keystore in memory.
 	pub fn in_memory() -> Self {
 		let inner = KeystoreInner::new_in_memory();
@@ -100,18 +125,28 @@ impl LocalKeystore {
 		Ok(pair.public())
 	}
 
+	/// Sign with the key pair of `public`, recording the signature for [`Keystore::key_usage`].
+	fn sign_with_pair<T: CorePair, S>(
+		&self,
+		key_type: KeyTypeId,
+		public: &T::Public,
+		sign: impl FnOnce(T) -> S,
+	) -> std::result::Result<Option<S>, TraitError> {
+		let inner = self.0.read();
+		let signature = inner.key_pair_by_type::<T>(public, key_type)?.map(sign);
+		if signature.is_some() {
+			inner.record_signature(public.as_slice(), key_type);
+		}
+		Ok(signature)
+	}
+
 	fn sign<T: CorePair>(
 		&self,
 		key_type: KeyTypeId,
 		public: &T::Public,
 		msg: &[u8],
 	) -> std::result::Result<Option<T::Signature>, TraitError> {
-		let signature = self
-			.0
-			.read()
-			.key_pair_by_type::<T>(public, key_type)?
-			.map(|pair| pair.sign(msg));
-		Ok(signature)
+		self.sign_with_pair::<T, _>(key_type, public, |pair| pair.sign(msg))
 	}
 
 	fn vrf_sign<T: CorePair + VrfSecret>(
@@ -120,12 +155,7 @@ impl LocalKeystore {
 		public: &T::Public,
 		data: &T::VrfSignData,
 	) -> std::result::Result<Option<T::VrfSignature>, TraitError> {
-		let sig = self
-			.0
-			.read()
-			.key_pair_by_type::<T>(public, key_type)?
-			.map(|pair| pair.vrf_sign(data));
-		Ok(sig)
+		self.sign_with_pair::<T, _>(key_type, public, |pair| pair.vrf_sign(data))
 	}
 
 	fn vrf_pre_output<T: CorePair + VrfSecret>(
@@ -170,6 +200,15 @@ impl Keystore for LocalKeystore {
 			.all(|(p, t)| self.0.read().key_phrase_by_type(p, *t).ok().flatten().is_some())
 	}
 
+	/// Returns when the key was generated and when it last signed.
+	///
+	/// The generation time of a key in the file system store is the creation time of its key file,
+	/// or its modification time if the file system doesn't record creation times. The signing time
+	/// is only known for signatures made since the keystore was opened.
+	fn key_usage(&self, key_type: KeyTypeId, public: &[u8]) -> KeyUsage {
+		self.0.read().key_usage(public, key_type)
+	}
+
 	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
 		self.public_keys::<sr25519::Pair>(key_type)
 	}
@@ -266,12 +305,7 @@ impl Keystore for LocalKeystore {
 		public: &ecdsa::Public,
 		msg: &[u8; 32],
 	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
-		let sig = self
-			.0
-			.read()
-			.key_pair_by_type::<ecdsa::Pair>(public, key_type)?
-			.map(|pair| pair.sign_prehashed(msg));
-		Ok(sig)
+		self.sign_with_pair::<ecdsa::Pair, _>(key_type, public, |pair| pair.sign_prehashed(msg))
 	}
 
 	sp_keystore::bandersnatch_experimental_enabled! {
@@ -324,12 +358,9 @@ impl Keystore for LocalKeystore {
 			data: &bandersnatch::vrf::VrfSignData,
 			prover: &bandersnatch::ring_vrf::RingProver,
 		) -> std::result::Result<Option<bandersnatch::ring_vrf::RingVrfSignature>, TraitError> {
-			let sig = self
-				.0
-				.read()
-				.key_pair_by_type::<bandersnatch::Pair>(public, key_type)?
-				.map(|pair| pair.ring_vrf_sign(data, prover));
-			Ok(sig)
+			self.sign_with_pair::<bandersnatch::Pair, _>(key_type, public, |pair| {
+				pair.ring_vrf_sign(data, prover)
+			})
 		}
 	}
 
@@ -388,11 +419,35 @@ impl Keystore for LocalKeystore {
 			public: &ecdsa_bls381::Public,
 			msg: &[u8],
 		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
-			 let sig = self.0
-			.read()
-			.key_pair_by_type::<ecdsa_bls381::Pair>(public, key_type)?
-			.map(|pair| pair.sign_with_hasher::<KeccakHasher>(msg));
-			Ok(sig)
+			self.sign_with_pair::<ecdsa_bls381::Pair, _>(key_type, public, |pair| {
+				pair.sign_with_hasher::<KeccakHasher>(msg)
+			})
+		}
+	}
+
+	sp_keystore::dilithium_experimental_enabled! {
+		fn dilithium_public_keys(&self, key_type: KeyTypeId) -> Vec<dilithium::Public> {
+			self.public_keys::<dilithium::Pair>(key_type)
+		}
+
+		/// Generate a new pair compatible with the 'dilithium' signature scheme.
+		///
+		/// If `[seed]` is `Some` then the key will be ephemeral and stored in memory.
+		fn dilithium_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<dilithium::Public, TraitError> {
+			self.generate_new::<dilithium::Pair>(key_type, seed)
+		}
+
+		fn dilithium_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &dilithium::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<dilithium::Signature>, TraitError> {
+			self.sign::<dilithium::Pair>(key_type, public, msg)
 		}
 	}
 }
@@ -413,6 +468,12 @@ struct KeystoreInner {
 	/// Map over `(KeyTypeId, Raw public key)` -> `Key phrase/seed`
 	additional: HashMap<(KeyTypeId, Vec<u8>), String>,
 	password: Option<SecretString>,
+	/// Key the key files are encrypted with, if the store is encrypted.
+	master_key: Option<MasterKey>,
+	/// Map over `(KeyTypeId, Raw public key)` -> generation time of the in-memory keys
+	generated: HashMap<(KeyTypeId, Vec<u8>), u64>,
+	/// Map over `(KeyTypeId, Raw public key)` -> time of the last signature
+	last_signed: Mutex<HashMap<(KeyTypeId, Vec<u8>), u64>>,
 }
 
 impl KeystoreInner {
@@ -420,10 +481,35 @@ impl KeystoreInner {
 	///
 	/// Optionally takes a password that will be used to encrypt/decrypt the keys.
 	fn open<T: Into<PathBuf>>(path: T, password: Option<SecretString>) -> Result<Self> {
+		Self::open_with_encryption(path, password, None)
+	}
+
+	/// Open the store at the given path, encrypting the key files if `unlock` is given.
+	///
+	/// Fails if the store is encrypted, but `unlock` isn't given.
+	fn open_with_encryption<T: Into<PathBuf>>(
+		path: T,
+		password: Option<SecretString>,
+		unlock: Option<&Unlock>,
+	) -> Result<Self> {
 		let path = path.into();
 		fs::create_dir_all(&path)?;
 
-		Ok(Self { path: Some(path), additional: HashMap::new(), password })
+		let master_key = match unlock {
+			Some(unlock) => Some(MasterKey::unlock(&path, unlock)?),
+			None if path.join(encryption::METADATA_FILE).exists() => return Err(Error::Locked),
+			None => None,
+		};
+		let store = Self {
+			path: Some(path),
+			additional: HashMap::new(),
+			password,
+			master_key,
+			generated: HashMap::new(),
+			last_signed: Default::default(),
+		};
+		store.seal_plain_key_files()?;
+		Ok(store)
 	}
 
 	/// Get the password for this store.
@@ -433,7 +519,14 @@ impl KeystoreInner {
 
 	/// Create a new in-memory store.
 	fn new_in_memory() -> Self {
-		Self { path: None, additional: HashMap::new(), password: None }
+		Self {
+			path: None,
+			additional: HashMap::new(),
+			password: None,
+			master_key: None,
+			generated: HashMap::new(),
+			last_signed: Default::default(),
+		}
 	}
 
 	/// Get the key phrase for the given public key and key type from the in-memory store.
@@ -452,6 +545,7 @@ impl KeystoreInner {
 		key_type: KeyTypeId,
 	) {
 		let key = (key_type, pair.public().to_raw_vec());
+		self.generated.insert(key.clone(), unix_millis(SystemTime::now()));
 		self.additional.insert(key, seed.into());
 	}
 
@@ -460,7 +554,7 @@ impl KeystoreInner {
 	/// Places it into the file system store, if a path is configured.
 	fn insert(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<()> {
 		if let Some(path) = self.key_file_path(public, key_type) {
-			Self::write_to_file(path, suri)?;
+			self.write_key_file(path, suri)?;
 		}
 
 		Ok(())
@@ -473,7 +567,7 @@ impl KeystoreInner {
 	fn generate_by_type<Pair: CorePair>(&mut self, key_type: KeyTypeId) -> Result<Pair> {
 		let (pair, phrase, _) = Pair::generate_with_phrase(self.password());
 		if let Some(path) = self.key_file_path(pair.public().as_slice(), key_type) {
-			Self::write_to_file(path, &phrase)?;
+			self.write_key_file(path, &phrase)?;
 		} else {
 			self.insert_ephemeral_pair(&pair, &phrase, key_type);
 		}
@@ -496,6 +590,47 @@ impl KeystoreInner {
 		Ok(())
 	}
 
+	/// Write the key `phrase` to `file`, sealed with the master key if the store is encrypted.
+	fn write_key_file(&self, file: PathBuf, phrase: &str) -> Result<()> {
+		match &self.master_key {
+			Some(master_key) => {
+				let sealed = master_key.seal(phrase.as_bytes(), key_file_name(&file))?;
+				encryption::write_atomically(&file, &serde_json::to_vec(&sealed)?)
+			},
+			None => Self::write_to_file(file, phrase),
+		}
+	}
+
+	/// Read the key phrase stored in `file`.
+	fn read_key_file(&self, file: &Path) -> Result<String> {
+		let key_file: KeyFile = serde_json::from_reader(&File::open(file)?)?;
+		match (key_file, &self.master_key) {
+			(KeyFile::Plain(phrase), _) => Ok(phrase),
+			(KeyFile::Sealed(sealed), Some(master_key)) => {
+				let phrase = master_key.open(&sealed, key_file_name(file))?;
+				String::from_utf8(phrase).map_err(|_| Error::Cipher)
+			},
+			(KeyFile::Sealed(_), None) => Err(Error::Locked),
+		}
+	}
+
+	/// Seal the key files still stored in plain text, if the store is encrypted.
+	fn seal_plain_key_files(&self) -> Result<()> {
+		let (Some(path), Some(_)) = (&self.path, &self.master_key) else { return Ok(()) };
+
+		for entry in fs::read_dir(path)? {
+			let path = entry?.path();
+			if !path.is_file() || array_bytes::hex2bytes(key_file_name(&path)).is_err() {
+				continue
+			}
+			let key_file: KeyFile = serde_json::from_reader(&File::open(&path)?)?;
+			if let KeyFile::Plain(phrase) = key_file {
+				self.write_key_file(path, &phrase)?;
+			}
+		}
+		Ok(())
+	}
+
 	/// Create a new key from seed.
 	///
 	/// Does not place it into the file system store.
@@ -522,9 +657,7 @@ impl KeystoreInner {
 		};
 
 		if path.exists() {
-			let file = File::open(path)?;
-
-			serde_json::from_reader(&file).map_err(Into::into).map(Some)
+			self.read_key_file(&path).map(Some)
 		} else {
 			Ok(None)
 		}
@@ -562,6 +695,22 @@ impl KeystoreInner {
 		Some(buf)
 	}
 
+	/// Get when the given key was generated and when it last signed.
+	fn key_usage(&self, public: &[u8], key_type: KeyTypeId) -> KeyUsage {
+		let key = (key_type, public.to_vec());
+		let generated = self.generated.get(&key).copied().or_else(|| {
+			let metadata = fs::metadata(self.key_file_path(public, key_type)?).ok()?;
+			metadata.created().or_else(|_| metadata.modified()).ok().map(unix_millis)
+		});
+		KeyUsage { generated, last_signed: self.last_signed.lock().get(&key).copied() }
+	}
+
+	/// Record that the given key just produced a signature.
+	fn record_signature(&self, public: &[u8], key_type: KeyTypeId) {
+		let now = unix_millis(SystemTime::now());
+		self.last_signed.lock().insert((key_type, public.to_vec()), now);
+	}
+
 	/// Returns a list of raw public keys filtered by `KeyTypeId`
 	fn raw_public_keys(&self, key_type: KeyTypeId) -> Result<Vec<Vec<u8>>> {
 		let mut public_keys: Vec<Vec<u8>> = self
@@ -608,9 +757,22 @@ impl KeystoreInner {
 	}
 }
 
+/// Name of a key file, which is also the additional data its content is sealed with.
+fn key_file_name(file: &Path) -> &str {
+	file.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+}
+
+/// Milliseconds since the Unix epoch at `time`.
+fn unix_millis(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH)
+		.map(|since| since.as_millis() as u64)
+		.unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::Kms;
 	use sp_application_crypto::{ed25519, sr25519, AppPublic};
 	use sp_core::{crypto::Ss58Codec, testing::SR25519, Pair};
 	use std::{fs, str::FromStr};
@@ -803,6 +965,30 @@ mod tests {
 		assert_eq!(store.sr25519_public_keys(TEST_KEY_TYPE).len(), 2);
 	}
 
+	#[test]
+	fn key_usage_is_tracked() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		let unknown = sp_core::sr25519::Pair::from_string("//Bob", None).unwrap().public();
+		assert_eq!(store.key_usage(TEST_KEY_TYPE, unknown.as_slice()), KeyUsage::default());
+
+		let stored = store.sr25519_generate_new(TEST_KEY_TYPE, None).unwrap();
+		let ephemeral = store.sr25519_generate_new(TEST_KEY_TYPE, Some("//Alice")).unwrap();
+		for public in [stored, ephemeral] {
+			let usage = store.key_usage(TEST_KEY_TYPE, public.as_slice());
+			assert!(usage.generated.is_some());
+			assert_eq!(usage.last_signed, None);
+
+			store.sr25519_sign(TEST_KEY_TYPE, &public, b"message").unwrap().unwrap();
+			let usage = store.key_usage(TEST_KEY_TYPE, public.as_slice());
+			assert!(usage.last_signed.is_some());
+		}
+
+		// Failing to sign doesn't count as signing.
+		assert!(store.sr25519_sign(TEST_KEY_TYPE, &unknown, b"message").unwrap().is_none());
+		assert_eq!(store.key_usage(TEST_KEY_TYPE, unknown.as_slice()).last_signed, None);
+	}
+
 	#[test]
 	#[cfg(target_family = "unix")]
 	fn uses_correct_file_permissions_on_unix() {
@@ -818,4 +1004,93 @@ mod tests {
 
 		assert_eq!(0o100600, permissions.mode());
 	}
+
+	fn passphrase(passphrase: &str) -> Unlock {
+		Unlock::Passphrase(FromStr::from_str(passphrase).unwrap())
+	}
+
+	#[test]
+	fn encrypted_store_works() {
+		let temp_dir = TempDir::new().unwrap();
+		let store =
+			LocalKeystore::open_encrypted(temp_dir.path(), None, &passphrase("secret")).unwrap();
+		let public = store.sr25519_generate_new(TEST_KEY_TYPE, None).unwrap();
+
+		// The seed isn't stored in plain text.
+		let path = store.0.read().key_file_path(public.as_ref(), TEST_KEY_TYPE).unwrap();
+		let content = fs::read_to_string(path).unwrap();
+		let phrase = store.0.read().key_phrase_by_type(public.as_ref(), TEST_KEY_TYPE).unwrap();
+		assert!(!content.contains(&phrase.unwrap()));
+		drop(store);
+
+		let store =
+			LocalKeystore::open_encrypted(temp_dir.path(), None, &passphrase("secret")).unwrap();
+		assert_eq!(store.sr25519_public_keys(TEST_KEY_TYPE), vec![public]);
+		assert!(store.sr25519_sign(TEST_KEY_TYPE, &public, b"msg").unwrap().is_some());
+	}
+
+	#[test]
+	fn encrypted_store_must_be_unlocked() {
+		let temp_dir = TempDir::new().unwrap();
+		LocalKeystore::open_encrypted(temp_dir.path(), None, &passphrase("secret")).unwrap();
+
+		assert!(matches!(LocalKeystore::open(temp_dir.path(), None), Err(Error::Locked)));
+		assert!(matches!(
+			LocalKeystore::open_encrypted(temp_dir.path(), None, &passphrase("wrong")),
+			Err(Error::InvalidMasterKey)
+		));
+	}
+
+	#[test]
+	fn plain_key_files_are_encrypted() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		let public = store.sr25519_generate_new(TEST_KEY_TYPE, None).unwrap();
+		let path = store.0.read().key_file_path(public.as_ref(), TEST_KEY_TYPE).unwrap();
+		let phrase: String = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+		drop(store);
+
+		let store =
+			LocalKeystore::open_encrypted(temp_dir.path(), None, &passphrase("secret")).unwrap();
+		assert!(!fs::read_to_string(&path).unwrap().contains(&phrase));
+		assert_eq!(
+			store.0.read().key_phrase_by_type(public.as_ref(), TEST_KEY_TYPE).unwrap(),
+			Some(phrase),
+		);
+	}
+
+	/// KMS wrapping keys by XOR with a fixed byte.
+	struct XorKms(u8);
+
+	impl Kms for XorKms {
+		fn wrap(&self, key: &[u8]) -> std::result::Result<Vec<u8>, String> {
+			Ok(key.iter().map(|byte| byte ^ self.0).collect())
+		}
+
+		fn unwrap(&self, wrapped: &[u8]) -> std::result::Result<Vec<u8>, String> {
+			self.wrap(wrapped)
+		}
+	}
+
+	#[test]
+	fn kms_unlocks_store() {
+		let temp_dir = TempDir::new().unwrap();
+		let kms = Unlock::Kms(Arc::new(XorKms(0x5a)));
+		let store = LocalKeystore::open_encrypted(temp_dir.path(), None, &kms).unwrap();
+		let public = store.ed25519_generate_new(TEST_KEY_TYPE, None).unwrap();
+		drop(store);
+
+		let store = LocalKeystore::open_encrypted(temp_dir.path(), None, &kms).unwrap();
+		assert_eq!(store.ed25519_public_keys(TEST_KEY_TYPE), vec![public]);
+
+		let other_kms = Unlock::Kms(Arc::new(XorKms(0xa5)));
+		assert!(matches!(
+			LocalKeystore::open_encrypted(temp_dir.path(), None, &other_kms),
+			Err(Error::InvalidMasterKey)
+		));
+		assert!(matches!(
+			LocalKeystore::open_encrypted(temp_dir.path(), None, &passphrase("secret")),
+			Err(Error::InvalidMasterKey)
+		));
+	}
 }
@@ -0,0 +1,291 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Example [`SigningPlugin`] backed by a secure enclave, like an Intel SGX enclave or an ARM
+//! TrustZone trusted application.
+//!
+//! The enclave is hosted by a separate process on the same machine, which the [`EnclaveSigner`]
+//! talks to over TCP. Every message is a SCALE encoded [`Request`] or [`Response`], prefixed by
+//! its length as a little endian `u32`. VRF transcripts are sent as the
+//! [`VrfTranscriptData`] they are built from, for the enclave to rebuild them.
+//!
+//! The evidence of an [`Attestation`] of the enclave is an ed25519 signature of the encoded
+//! [`Report`](super::Report) by an attestation key, e.g. of a service which verified the hardware
+//! quote of the enclave. The [`EnclaveVerifier`] checks it against the trusted attestation keys
+//! and the measurement against the trusted builds of the enclave.
+
+use super::{Attestation, AttestationVerifier, PluginKey, SigningPlugin};
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId, Pair},
+	ed25519, sr25519,
+	sr25519::vrf::{VrfPreOutput, VrfSignData, VrfSignature, VrfTranscriptData},
+};
+use sp_keystore::Error as TraitError;
+use std::{
+	io::{self, Read, Write},
+	net::{SocketAddr, TcpStream},
+	time::Duration,
+};
+
+const LOG_TARGET: &str = "keystore::enclave";
+
+/// The maximum size of a message.
+const MAX_MESSAGE_SIZE: u32 = 1024 * 1024;
+
+/// Timeout of connecting to the enclave and of every read and write.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration of an enclave the keys of some key types are delegated to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnclaveConfig {
+	/// The address the enclave host listens on.
+	pub address: SocketAddr,
+	/// The measurements of the trusted builds of the enclave.
+	pub measurements: Vec<[u8; 32]>,
+	/// The keys trusted to sign the attestations of the enclave.
+	pub attestation_keys: Vec<ed25519::Public>,
+	/// The key types delegated to the enclave.
+	pub key_types: Vec<KeyTypeId>,
+}
+
+/// A request to the enclave.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Request {
+	/// Generate a new key.
+	Generate {
+		/// The key type of the key.
+		key_type: KeyTypeId,
+		/// The crypto of the key.
+		crypto: CryptoTypeId,
+	},
+	/// Sign a message.
+	Sign {
+		/// The key to sign with.
+		key: PluginKey,
+		/// The message.
+		msg: Vec<u8>,
+	},
+	/// Generate an sr25519 VRF signature.
+	Sr25519VrfSign {
+		/// The key to sign with.
+		key: PluginKey,
+		/// The transcript contributing to the VRF output.
+		input: VrfTranscriptData,
+		/// The extra transcript signed by the VRF, if any.
+		extra: Option<VrfTranscriptData>,
+	},
+	/// Compute an sr25519 VRF pre-output.
+	Sr25519VrfPreOutput {
+		/// The key to compute the pre-output with.
+		key: PluginKey,
+		/// The VRF input transcript.
+		input: VrfTranscriptData,
+	},
+	/// Attest the enclave.
+	Attest {
+		/// The challenge the attestation must be bound to.
+		challenge: [u8; 32],
+	},
+}
+
+/// A response of the enclave.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Response {
+	/// The raw public key of the generated key.
+	Generated(Vec<u8>),
+	/// The raw signature, or `None` if the enclave doesn't hold the key.
+	Signature(Option<Vec<u8>>),
+	/// The VRF signature, or `None` if the enclave doesn't hold the key.
+	VrfSignature(Option<VrfSignature>),
+	/// The VRF pre-output, or `None` if the enclave doesn't hold the key.
+	VrfPreOutput(Option<VrfPreOutput>),
+	/// The attestation of the enclave.
+	Attestation(Attestation),
+	/// The request failed.
+	Error(String),
+}
+
+/// Write a length prefixed message to `stream`.
+pub fn write_message<T: Encode>(stream: &mut impl Write, message: &T) -> io::Result<()> {
+	let message = message.encode();
+	let len = u32::try_from(message.len())
+		.ok()
+		.filter(|len| *len <= MAX_MESSAGE_SIZE)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Message too large"))?;
+	stream.write_all(&len.to_le_bytes())?;
+	stream.write_all(&message)?;
+	stream.flush()
+}
+
+/// Read a length prefixed message from `stream`.
+pub fn read_message<T: Decode>(stream: &mut impl Read) -> io::Result<T> {
+	let mut len = [0; 4];
+	stream.read_exact(&mut len)?;
+	let len = u32::from_le_bytes(len);
+	if len > MAX_MESSAGE_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "Message too large"))
+	}
+	let mut message = vec![0; len as usize];
+	stream.read_exact(&mut message)?;
+	T::decode(&mut &message[..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// A [`SigningPlugin`] delegating to an enclave hosted on the same machine.
+///
+/// The connection is opened on the first request and reopened after a failure.
+pub struct EnclaveSigner {
+	address: SocketAddr,
+	connection: Mutex<Option<TcpStream>>,
+}
+
+impl EnclaveSigner {
+	/// Create a signer talking to the enclave host listening on `address`.
+	pub fn new(address: SocketAddr) -> Self {
+		Self { address, connection: Mutex::new(None) }
+	}
+
+	fn request(&self, request: &Request) -> Result<Response, TraitError> {
+		let mut connection = self.connection.lock();
+		let response = exchange(&mut connection, self.address, request);
+		if response.is_err() {
+			*connection = None;
+		}
+
+		match response {
+			Ok(Response::Error(e)) => Err(TraitError::Other(format!("Enclave error: {e}"))),
+			Ok(response) => Ok(response),
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Request to enclave at {} failed: {e}", self.address);
+				Err(TraitError::Unavailable)
+			},
+		}
+	}
+}
+
+fn exchange(
+	connection: &mut Option<TcpStream>,
+	address: SocketAddr,
+	request: &Request,
+) -> io::Result<Response> {
+	let stream = match connection {
+		Some(stream) => stream,
+		None => {
+			let stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+			stream.set_read_timeout(Some(TIMEOUT))?;
+			stream.set_write_timeout(Some(TIMEOUT))?;
+			connection.insert(stream)
+		},
+	};
+	write_message(stream, request)?;
+	read_message(stream)
+}
+
+fn unexpected_response() -> TraitError {
+	TraitError::Other("Unexpected response from the enclave".into())
+}
+
+impl SigningPlugin for EnclaveSigner {
+	fn generate(&self, key_type: KeyTypeId, crypto: CryptoTypeId) -> Result<Vec<u8>, TraitError> {
+		match self.request(&Request::Generate { key_type, crypto })? {
+			Response::Generated(public) => Ok(public),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn sign(&self, key: &PluginKey, msg: &[u8]) -> Result<Option<Vec<u8>>, TraitError> {
+		match self.request(&Request::Sign { key: key.clone(), msg: msg.to_vec() })? {
+			Response::Signature(signature) => Ok(signature),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		data: &VrfSignData,
+	) -> Result<Option<VrfSignature>, TraitError> {
+		let key = PluginKey { key_type, crypto: sr25519::CRYPTO_ID, public: public.to_raw_vec() };
+		let input = data.transcript().data().clone();
+		let extra = data.extra().map(|extra| extra.data().clone());
+		match self.request(&Request::Sr25519VrfSign { key, input, extra })? {
+			Response::VrfSignature(signature) => Ok(signature),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn sr25519_vrf_pre_output(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &sr25519::vrf::VrfInput,
+	) -> Result<Option<VrfPreOutput>, TraitError> {
+		let key = PluginKey { key_type, crypto: sr25519::CRYPTO_ID, public: public.to_raw_vec() };
+		match self.request(&Request::Sr25519VrfPreOutput { key, input: input.data().clone() })? {
+			Response::VrfPreOutput(pre_output) => Ok(pre_output),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn attest(&self, challenge: [u8; 32]) -> Result<Attestation, TraitError> {
+		match self.request(&Request::Attest { challenge })? {
+			Response::Attestation(attestation) => Ok(attestation),
+			_ => Err(unexpected_response()),
+		}
+	}
+}
+
+/// Verifies attestations of an [`EnclaveSigner`].
+pub struct EnclaveVerifier {
+	measurements: Vec<[u8; 32]>,
+	attestation_keys: Vec<ed25519::Public>,
+}
+
+impl EnclaveVerifier {
+	/// Create a verifier trusting the enclave builds with the given `measurements`, attested by
+	/// any of the `attestation_keys`.
+	pub fn new(measurements: Vec<[u8; 32]>, attestation_keys: Vec<ed25519::Public>) -> Self {
+		Self { measurements, attestation_keys }
+	}
+}
+
+impl AttestationVerifier for EnclaveVerifier {
+	fn verify(&self, attestation: &Attestation) -> Result<(), String> {
+		let measurement = attestation.report.measurement;
+		if !self.measurements.contains(&measurement) {
+			return Err(format!(
+				"Untrusted enclave measurement {}",
+				array_bytes::bytes2hex("0x", measurement)
+			));
+		}
+
+		let signature = ed25519::Signature::from_slice(&attestation.evidence)
+			.map_err(|_| "Invalid attestation evidence".to_string())?;
+		let report = attestation.report.encode();
+		if !self
+			.attestation_keys
+			.iter()
+			.any(|key| ed25519::Pair::verify(&signature, &report, key))
+		{
+			return Err("Attestation is not signed by a trusted attestation key".into())
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,566 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Delegation of signing operations to a [`SigningPlugin`], e.g. a hardware secure enclave.
+//!
+//! The [`PluginKeystore`] wraps a keystore and delegates all operations on the keys of selected
+//! key types, e.g. BABE and GRANDPA, to the plugin. The secret keys of these types never leave
+//! the plugin, while keys of the other types are kept in the wrapped keystore.
+//!
+//! The keystore only uses keys the plugin has attested to hold: before the first use and after
+//! generating a key, the plugin is asked for an [`Attestation`] of its code and keys, bound to a
+//! fresh challenge, which is checked by an [`AttestationVerifier`].
+//!
+//! [`enclave`] contains an example plugin, talking to a secure enclave running on the same host.
+
+pub mod enclave;
+
+use codec::{Decode, Encode};
+use parking_lot::RwLock;
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId, VrfPublic},
+	ecdsa, ed25519, sr25519,
+};
+use sp_keystore::{Error as TraitError, KeyUsage, Keystore, KeystorePtr};
+use std::{collections::HashSet, sync::Arc};
+
+sp_keystore::bandersnatch_experimental_enabled! {
+use sp_core::bandersnatch;
+}
+
+sp_keystore::bls_experimental_enabled! {
+use sp_core::{bls381, ecdsa_bls381};
+}
+
+sp_keystore::dilithium_experimental_enabled! {
+use sp_core::dilithium;
+}
+
+use crate::{Error, Result};
+
+/// A key held by a [`SigningPlugin`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+pub struct PluginKey {
+	/// The key type.
+	pub key_type: KeyTypeId,
+	/// The crypto of the key.
+	pub crypto: CryptoTypeId,
+	/// The raw public key.
+	pub public: Vec<u8>,
+}
+
+/// The attested state of a [`SigningPlugin`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Report {
+	/// Measurement of the code running the plugin, e.g. the `MRENCLAVE` of an SGX enclave.
+	pub measurement: [u8; 32],
+	/// The challenge the attestation was requested with.
+	pub challenge: [u8; 32],
+	/// All keys held by the plugin.
+	pub keys: Vec<PluginKey>,
+}
+
+/// Attestation of a [`SigningPlugin`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Attestation {
+	/// The attested state.
+	pub report: Report,
+	/// Evidence that the report was produced by the plugin, e.g. a hardware quote.
+	pub evidence: Vec<u8>,
+}
+
+/// Something that holds secret keys and signs with them on behalf of the keystore.
+///
+/// All keys are identified by their key type, crypto and raw public key. Signatures are returned
+/// as raw bytes.
+pub trait SigningPlugin: Send + Sync {
+	/// Generate a new key of the given key type and crypto, returning its raw public key.
+	fn generate(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+	) -> std::result::Result<Vec<u8>, TraitError>;
+
+	/// Sign `msg` with the given key.
+	///
+	/// Returns `None` if the plugin doesn't hold the key.
+	fn sign(&self, key: &PluginKey, msg: &[u8])
+		-> std::result::Result<Option<Vec<u8>>, TraitError>;
+
+	/// Generate an sr25519 VRF signature for the given data.
+	///
+	/// Not supported by default. A remote signer can rebuild the transcripts of `data` from their
+	/// [`VrfTranscriptData`](sr25519::vrf::VrfTranscriptData).
+	fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_data: &sr25519::vrf::VrfSignData,
+	) -> std::result::Result<Option<sr25519::vrf::VrfSignature>, TraitError> {
+		Err(TraitError::Other("VRF signing is not supported by the signing plugin".into()))
+	}
+
+	/// Generate an sr25519 VRF pre-output for the given input.
+	///
+	/// Not supported by default. A remote signer can rebuild `input` from its
+	/// [`VrfTranscriptData`](sr25519::vrf::VrfTranscriptData).
+	fn sr25519_vrf_pre_output(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_input: &sr25519::vrf::VrfInput,
+	) -> std::result::Result<Option<sr25519::vrf::VrfPreOutput>, TraitError> {
+		Err(TraitError::Other("VRF signing is not supported by the signing plugin".into()))
+	}
+
+	/// Attest the code of the plugin and the keys it holds, bound to `challenge`.
+	fn attest(&self, challenge: [u8; 32]) -> std::result::Result<Attestation, TraitError>;
+}
+
+/// Something that verifies the [`Attestation`] of a [`SigningPlugin`].
+pub trait AttestationVerifier: Send + Sync {
+	/// Verify that the evidence of `attestation` proves its report, and that the measured code is
+	/// trusted.
+	fn verify(&self, attestation: &Attestation) -> std::result::Result<(), String>;
+}
+
+/// A keystore delegating the keys of some key types to a [`SigningPlugin`].
+pub struct PluginKeystore {
+	keystore: KeystorePtr,
+	plugin: Arc<dyn SigningPlugin>,
+	verifier: Arc<dyn AttestationVerifier>,
+	key_types: Vec<KeyTypeId>,
+	/// The keys of the last verified attestation.
+	attested: RwLock<HashSet<PluginKey>>,
+}
+
+impl PluginKeystore {
+	/// Create a keystore delegating the keys of `key_types` to `plugin` and all other keys to
+	/// `keystore`.
+	///
+	/// Fails if the attestation of the plugin can't be verified by `verifier`.
+	pub fn new(
+		keystore: KeystorePtr,
+		plugin: Arc<dyn SigningPlugin>,
+		verifier: Arc<dyn AttestationVerifier>,
+		key_types: Vec<KeyTypeId>,
+	) -> Result<Self> {
+		let this =
+			Self { keystore, plugin, verifier, key_types, attested: RwLock::new(HashSet::new()) };
+		this.attest()?;
+		Ok(this)
+	}
+
+	/// Request a new attestation from the plugin and update the attested keys.
+	pub fn attest(&self) -> Result<()> {
+		let challenge = rand::random();
+		let attestation =
+			self.plugin.attest(challenge).map_err(|e| Error::Attestation(e.to_string()))?;
+		if attestation.report.challenge != challenge {
+			return Err(Error::Attestation("Attestation doesn't answer the challenge".into()))
+		}
+		self.verifier.verify(&attestation).map_err(Error::Attestation)?;
+
+		*self.attested.write() = attestation.report.keys.into_iter().collect();
+		Ok(())
+	}
+
+	fn delegates(&self, key_type: KeyTypeId) -> bool {
+		self.key_types.contains(&key_type)
+	}
+
+	fn plugin_public_keys<T: ByteArray>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+	) -> Vec<T> {
+		self.attested
+			.read()
+			.iter()
+			.filter(|key| key.key_type == key_type && key.crypto == crypto)
+			.filter_map(|key| T::from_slice(&key.public).ok())
+			.collect()
+	}
+
+	fn plugin_generate<T: ByteArray>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<T, TraitError> {
+		if seed.is_some() {
+			return Err(TraitError::ValidationError(
+				"Keys held by the signing plugin can't be generated from a seed".into(),
+			));
+		}
+		let public = self.plugin.generate(key_type, crypto)?;
+		self.attest()?;
+
+		let key = PluginKey { key_type, crypto, public };
+		if !self.attested.read().contains(&key) {
+			return Err(TraitError::Other("Generated key is not attested by the plugin".into()))
+		}
+		T::from_slice(&key.public)
+			.map_err(|_| TraitError::Other("Invalid public key returned by the plugin".into()))
+	}
+
+	fn plugin_sign<T: ByteArray>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+		public: &[u8],
+		msg: &[u8],
+	) -> std::result::Result<Option<T>, TraitError> {
+		let key = PluginKey { key_type, crypto, public: public.to_vec() };
+		if !self.attested.read().contains(&key) {
+			return Ok(None)
+		}
+		self.plugin
+			.sign(&key, msg)?
+			.map(|signature| {
+				T::from_slice(&signature).map_err(|_| {
+					TraitError::Other("Invalid signature returned by the plugin".into())
+				})
+			})
+			.transpose()
+	}
+
+	fn is_attested(&self, key_type: KeyTypeId, crypto: CryptoTypeId, public: &[u8]) -> bool {
+		self.attested
+			.read()
+			.contains(&PluginKey { key_type, crypto, public: public.to_vec() })
+	}
+}
+
+impl Keystore for PluginKeystore {
+	/// Insert a new secret key.
+	///
+	/// Fails for the key types delegated to the plugin, as their secret keys must never leave it.
+	fn insert(
+		&self,
+		key_type: KeyTypeId,
+		suri: &str,
+		public: &[u8],
+	) -> std::result::Result<(), ()> {
+		if self.delegates(key_type) {
+			return Err(())
+		}
+		self.keystore.insert(key_type, suri, public)
+	}
+
+	fn keys(&self, key_type: KeyTypeId) -> std::result::Result<Vec<Vec<u8>>, TraitError> {
+		if self.delegates(key_type) {
+			return Ok(self
+				.attested
+				.read()
+				.iter()
+				.filter(|key| key.key_type == key_type)
+				.map(|key| key.public.clone())
+				.collect());
+		}
+		self.keystore.keys(key_type)
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		public_keys.iter().all(|(public, key_type)| {
+			if self.delegates(*key_type) {
+				self.attested
+					.read()
+					.iter()
+					.any(|key| key.key_type == *key_type && key.public == *public)
+			} else {
+				self.keystore.has_keys(&[(public.clone(), *key_type)])
+			}
+		})
+	}
+
+	fn key_usage(&self, key_type: KeyTypeId, public: &[u8]) -> KeyUsage {
+		if self.delegates(key_type) {
+			return KeyUsage::default()
+		}
+		self.keystore.key_usage(key_type, public)
+	}
+
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		if self.delegates(key_type) {
+			return self.plugin_public_keys(key_type, sr25519::CRYPTO_ID)
+		}
+		self.keystore.sr25519_public_keys(key_type)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		if self.delegates(key_type) {
+			return self.plugin_generate(key_type, sr25519::CRYPTO_ID, seed)
+		}
+		self.keystore.sr25519_generate_new(key_type, seed)
+	}
+
+	fn sr25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<sr25519::Signature>, TraitError> {
+		if self.delegates(key_type) {
+			return self.plugin_sign(key_type, sr25519::CRYPTO_ID, public.as_slice(), msg)
+		}
+		self.keystore.sr25519_sign(key_type, public, msg)
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		data: &sr25519::vrf::VrfSignData,
+	) -> std::result::Result<Option<sr25519::vrf::VrfSignature>, TraitError> {
+		if self.delegates(key_type) {
+			if !self.is_attested(key_type, sr25519::CRYPTO_ID, public.as_slice()) {
+				return Ok(None)
+			}
+			let signature = self.plugin.sr25519_vrf_sign(key_type, public, data)?;
+			if signature.as_ref().is_some_and(|signature| !public.vrf_verify(data, signature)) {
+				return Err(TraitError::Other(
+					"Invalid VRF signature returned by the plugin".into(),
+				))
+			}
+			return Ok(signature)
+		}
+		self.keystore.sr25519_vrf_sign(key_type, public, data)
+	}
+
+	fn sr25519_vrf_pre_output(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &sr25519::vrf::VrfInput,
+	) -> std::result::Result<Option<sr25519::vrf::VrfPreOutput>, TraitError> {
+		if self.delegates(key_type) {
+			if !self.is_attested(key_type, sr25519::CRYPTO_ID, public.as_slice()) {
+				return Ok(None)
+			}
+			return self.plugin.sr25519_vrf_pre_output(key_type, public, input)
+		}
+		self.keystore.sr25519_vrf_pre_output(key_type, public, input)
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		if self.delegates(key_type) {
+			return self.plugin_public_keys(key_type, ed25519::CRYPTO_ID)
+		}
+		self.keystore.ed25519_public_keys(key_type)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		if self.delegates(key_type) {
+			return self.plugin_generate(key_type, ed25519::CRYPTO_ID, seed)
+		}
+		self.keystore.ed25519_generate_new(key_type, seed)
+	}
+
+	fn ed25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ed25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ed25519::Signature>, TraitError> {
+		if self.delegates(key_type) {
+			return self.plugin_sign(key_type, ed25519::CRYPTO_ID, public.as_slice(), msg)
+		}
+		self.keystore.ed25519_sign(key_type, public, msg)
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		if self.delegates(key_type) {
+			return self.plugin_public_keys(key_type, ecdsa::CRYPTO_ID)
+		}
+		self.keystore.ecdsa_public_keys(key_type)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		if self.delegates(key_type) {
+			return self.plugin_generate(key_type, ecdsa::CRYPTO_ID, seed)
+		}
+		self.keystore.ecdsa_generate_new(key_type, seed)
+	}
+
+	fn ecdsa_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		if self.delegates(key_type) {
+			return self.plugin_sign(key_type, ecdsa::CRYPTO_ID, public.as_slice(), msg)
+		}
+		self.keystore.ecdsa_sign(key_type, public, msg)
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		if self.delegates(key_type) {
+			return Err(TraitError::KeyNotSupported(key_type))
+		}
+		self.keystore.ecdsa_sign_prehashed(key_type, public, msg)
+	}
+
+	sp_keystore::bandersnatch_experimental_enabled! {
+		fn bandersnatch_public_keys(&self, key_type: KeyTypeId) -> Vec<bandersnatch::Public> {
+			self.keystore.bandersnatch_public_keys(key_type)
+		}
+
+		fn bandersnatch_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<bandersnatch::Public, TraitError> {
+			self.keystore.bandersnatch_generate_new(key_type, seed)
+		}
+
+		fn bandersnatch_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<bandersnatch::Signature>, TraitError> {
+			self.keystore.bandersnatch_sign(key_type, public, msg)
+		}
+
+		fn bandersnatch_vrf_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			data: &bandersnatch::vrf::VrfSignData,
+		) -> std::result::Result<Option<bandersnatch::vrf::VrfSignature>, TraitError> {
+			self.keystore.bandersnatch_vrf_sign(key_type, public, data)
+		}
+
+		fn bandersnatch_vrf_pre_output(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			input: &bandersnatch::vrf::VrfInput,
+		) -> std::result::Result<Option<bandersnatch::vrf::VrfPreOutput>, TraitError> {
+			self.keystore.bandersnatch_vrf_pre_output(key_type, public, input)
+		}
+
+		fn bandersnatch_ring_vrf_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			data: &bandersnatch::vrf::VrfSignData,
+			prover: &bandersnatch::ring_vrf::RingProver,
+		) -> std::result::Result<Option<bandersnatch::ring_vrf::RingVrfSignature>, TraitError> {
+			self.keystore.bandersnatch_ring_vrf_sign(key_type, public, data, prover)
+		}
+	}
+
+	sp_keystore::bls_experimental_enabled! {
+		fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+			self.keystore.bls381_public_keys(key_type)
+		}
+
+		fn bls381_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<bls381::Public, TraitError> {
+			self.keystore.bls381_generate_new(key_type, seed)
+		}
+
+		fn bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<bls381::Signature>, TraitError> {
+			self.keystore.bls381_sign(key_type, public, msg)
+		}
+
+		fn ecdsa_bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa_bls381::Public> {
+			self.keystore.ecdsa_bls381_public_keys(key_type)
+		}
+
+		fn ecdsa_bls381_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<ecdsa_bls381::Public, TraitError> {
+			self.keystore.ecdsa_bls381_generate_new(key_type, seed)
+		}
+
+		fn ecdsa_bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			self.keystore.ecdsa_bls381_sign(key_type, public, msg)
+		}
+
+		fn ecdsa_bls381_sign_with_keccak256(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			self.keystore.ecdsa_bls381_sign_with_keccak256(key_type, public, msg)
+		}
+	}
+
+	sp_keystore::dilithium_experimental_enabled! {
+		fn dilithium_public_keys(&self, key_type: KeyTypeId) -> Vec<dilithium::Public> {
+			self.keystore.dilithium_public_keys(key_type)
+		}
+
+		fn dilithium_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<dilithium::Public, TraitError> {
+			self.keystore.dilithium_generate_new(key_type, seed)
+		}
+
+		fn dilithium_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &dilithium::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<dilithium::Signature>, TraitError> {
+			self.keystore.dilithium_sign(key_type, public, msg)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests;
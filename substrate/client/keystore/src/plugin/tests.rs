@@ -0,0 +1,198 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{enclave::*, *};
+use crate::LocalKeystore;
+use sp_core::{
+	crypto::{Pair, VrfPublic},
+	sr25519::vrf::{VrfTranscript, VrfTranscriptData},
+	testing::{BABE, GRANDPA},
+};
+use std::{
+	net::{SocketAddr, TcpListener},
+	thread,
+};
+
+const MEASUREMENT: [u8; 32] = [7; 32];
+const OTHER: KeyTypeId = KeyTypeId(*b"othr");
+
+/// Rebuild the transcript of `data`, leaking its labels.
+fn transcript(data: VrfTranscriptData) -> VrfTranscript {
+	let leak = |label: Vec<u8>| -> &'static [u8] { Box::leak(label.into_boxed_slice()) };
+	let items = data.data.into_iter().map(|(label, msg)| (leak(label), msg)).collect::<Vec<_>>();
+	let items = items.iter().map(|(label, msg)| (*label, &msg[..])).collect::<Vec<_>>();
+	VrfTranscript::new(leak(data.label), &items)
+}
+
+/// An enclave simulated in software, keeping its keys in memory.
+struct SoftwareEnclave {
+	keystore: LocalKeystore,
+	attestation_key: ed25519::Pair,
+}
+
+impl SoftwareEnclave {
+	fn handle(&self, request: Request) -> Response {
+		match request {
+			Request::Generate { key_type, crypto } => {
+				let public = match crypto {
+					ed25519::CRYPTO_ID =>
+						self.keystore.ed25519_generate_new(key_type, None).map(|p| p.to_raw_vec()),
+					sr25519::CRYPTO_ID =>
+						self.keystore.sr25519_generate_new(key_type, None).map(|p| p.to_raw_vec()),
+					_ => Err(TraitError::KeyNotSupported(key_type)),
+				};
+				public.map_or_else(|e| Response::Error(e.to_string()), Response::Generated)
+			},
+			Request::Sign { key, msg } => self
+				.keystore
+				.sign_with(key.key_type, key.crypto, &key.public, &msg)
+				.map_or_else(|e| Response::Error(e.to_string()), Response::Signature),
+			Request::Sr25519VrfSign { key, input, extra } => {
+				let public = sr25519::Public::from_slice(&key.public).unwrap();
+				let mut data = transcript(input).into_sign_data();
+				if let Some(extra) = extra {
+					data = data.with_extra(transcript(extra));
+				}
+				self.keystore
+					.sr25519_vrf_sign(key.key_type, &public, &data)
+					.map_or_else(|e| Response::Error(e.to_string()), Response::VrfSignature)
+			},
+			Request::Sr25519VrfPreOutput { key, input } => {
+				let public = sr25519::Public::from_slice(&key.public).unwrap();
+				self.keystore
+					.sr25519_vrf_pre_output(key.key_type, &public, &transcript(input))
+					.map_or_else(|e| Response::Error(e.to_string()), Response::VrfPreOutput)
+			},
+			Request::Attest { challenge } => {
+				let mut keys = Vec::new();
+				for key_type in [GRANDPA, BABE, OTHER] {
+					for public in self.keystore.ed25519_public_keys(key_type) {
+						let crypto = ed25519::CRYPTO_ID;
+						keys.push(PluginKey { key_type, crypto, public: public.to_raw_vec() });
+					}
+					for public in self.keystore.sr25519_public_keys(key_type) {
+						let crypto = sr25519::CRYPTO_ID;
+						keys.push(PluginKey { key_type, crypto, public: public.to_raw_vec() });
+					}
+				}
+				let report = Report { measurement: MEASUREMENT, challenge, keys };
+				let evidence = self.attestation_key.sign(&report.encode()).to_raw_vec();
+				Response::Attestation(Attestation { report, evidence })
+			},
+		}
+	}
+
+	/// Serve the enclave on a local port, returning its address.
+	fn serve(self) -> SocketAddr {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap();
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let mut stream = stream.unwrap();
+				while let Ok(request) = read_message(&mut stream) {
+					write_message(&mut stream, &self.handle(request)).unwrap();
+				}
+			}
+		});
+		address
+	}
+}
+
+fn plugin_keystore(
+	measurement: [u8; 32],
+	attestation_key: &ed25519::Pair,
+) -> (Result<PluginKeystore>, KeystorePtr) {
+	let enclave = SoftwareEnclave {
+		keystore: LocalKeystore::in_memory(),
+		attestation_key: ed25519::Pair::from_seed(&[1; 32]),
+	};
+	let address = enclave.serve();
+	let local: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	let verifier = EnclaveVerifier::new(vec![measurement], vec![attestation_key.public()]);
+	let keystore = PluginKeystore::new(
+		local.clone(),
+		Arc::new(EnclaveSigner::new(address)),
+		Arc::new(verifier),
+		vec![GRANDPA, BABE],
+	);
+	(keystore, local)
+}
+
+#[test]
+fn delegated_keys_are_held_by_the_enclave() {
+	let (keystore, local) = plugin_keystore(MEASUREMENT, &ed25519::Pair::from_seed(&[1; 32]));
+	let keystore = keystore.unwrap();
+
+	let public = keystore.ed25519_generate_new(GRANDPA, None).unwrap();
+	assert_eq!(keystore.ed25519_public_keys(GRANDPA), vec![public]);
+	assert!(keystore.has_keys(&[(public.to_raw_vec(), GRANDPA)]));
+	assert!(local.ed25519_public_keys(GRANDPA).is_empty());
+
+	let signature = keystore.ed25519_sign(GRANDPA, &public, b"message").unwrap().unwrap();
+	assert!(ed25519::Pair::verify(&signature, b"message", &public));
+
+	let unknown = ed25519::Pair::from_seed(&[2; 32]).public();
+	assert_eq!(keystore.ed25519_sign(GRANDPA, &unknown, b"message").unwrap(), None);
+}
+
+#[test]
+fn vrf_signing_is_delegated_to_the_enclave() {
+	let (keystore, local) = plugin_keystore(MEASUREMENT, &ed25519::Pair::from_seed(&[1; 32]));
+	let keystore = keystore.unwrap();
+
+	let public = keystore.sr25519_generate_new(BABE, None).unwrap();
+	assert!(local.sr25519_public_keys(BABE).is_empty());
+
+	let input = VrfTranscript::new(b"label", &[(b"domain", b"message")]);
+	let extra = VrfTranscript::new(b"extra", &[(b"domain", b"data")]);
+	let data = input.clone().into_sign_data().with_extra(extra);
+	let signature = keystore.sr25519_vrf_sign(BABE, &public, &data).unwrap().unwrap();
+	assert!(public.vrf_verify(&data, &signature));
+
+	let pre_output = keystore.sr25519_vrf_pre_output(BABE, &public, &input).unwrap().unwrap();
+	assert_eq!(pre_output, signature.pre_output);
+}
+
+#[test]
+fn delegated_keys_cannot_be_imported() {
+	let (keystore, _) = plugin_keystore(MEASUREMENT, &ed25519::Pair::from_seed(&[1; 32]));
+	let keystore = keystore.unwrap();
+
+	assert!(keystore.ed25519_generate_new(GRANDPA, Some("//Alice")).is_err());
+	let public = ed25519::Pair::from_string("//Alice", None).unwrap().public();
+	assert!(keystore.insert(GRANDPA, "//Alice", public.as_ref()).is_err());
+}
+
+#[test]
+fn other_keys_are_kept_in_the_wrapped_keystore() {
+	let (keystore, local) = plugin_keystore(MEASUREMENT, &ed25519::Pair::from_seed(&[1; 32]));
+	let keystore = keystore.unwrap();
+
+	let public = keystore.sr25519_generate_new(OTHER, Some("//Alice")).unwrap();
+	assert_eq!(local.sr25519_public_keys(OTHER), vec![public]);
+	assert!(keystore.sr25519_sign(OTHER, &public, b"message").unwrap().is_some());
+}
+
+#[test]
+fn untrusted_enclaves_are_rejected() {
+	let (keystore, _) = plugin_keystore([0; 32], &ed25519::Pair::from_seed(&[1; 32]));
+	assert!(matches!(keystore, Err(Error::Attestation(_))));
+
+	let (keystore, _) = plugin_keystore(MEASUREMENT, &ed25519::Pair::from_seed(&[2; 32]));
+	assert!(matches!(keystore, Err(Error::Attestation(_))));
+}
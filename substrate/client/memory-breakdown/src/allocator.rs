@@ -0,0 +1,144 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The instrumented global allocator.
+
+use crate::Subsystem;
+use std::{
+	alloc::{GlobalAlloc, Layout},
+	cell::Cell,
+	sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+thread_local! {
+	/// The subsystem to which the allocations of the current thread are attributed.
+	static CURRENT: Cell<Subsystem> = const { Cell::new(Subsystem::Other) };
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of bytes currently allocated, by subsystem.
+static ALLOCATED: [AtomicUsize; Subsystem::ALL.len()] = [ZERO; Subsystem::ALL.len()];
+
+/// Whether the [`TrackingAllocator`] is used as the global allocator.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Set the subsystem of the current thread, returning the previous one.
+pub(crate) fn set_current(subsystem: Subsystem) -> Subsystem {
+	CURRENT
+		.try_with(|current| current.replace(subsystem))
+		.unwrap_or(Subsystem::Other)
+}
+
+/// Whether the [`TrackingAllocator`] is used as the global allocator.
+pub(crate) fn is_installed() -> bool {
+	INSTALLED.load(Ordering::Relaxed)
+}
+
+/// The number of bytes currently allocated by `subsystem`.
+pub(crate) fn allocated(subsystem: Subsystem) -> usize {
+	ALLOCATED[subsystem as usize].load(Ordering::Relaxed)
+}
+
+/// A global allocator attributing the allocations to the subsystem of the allocating thread.
+///
+/// Every allocation is prefixed with a header storing its subsystem, so that it is accounted to
+/// the same subsystem when it is freed or resized, whichever thread does it. The header takes the
+/// alignment of the allocation, which is 8 or 16 bytes for most allocations.
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: sc_memory_breakdown::TrackingAllocator<std::alloc::System> =
+/// 	sc_memory_breakdown::TrackingAllocator::new(std::alloc::System);
+/// ```
+pub struct TrackingAllocator<A>(A);
+
+impl<A> TrackingAllocator<A> {
+	/// Create a new tracking allocator, wrapping the allocator `inner`.
+	pub const fn new(inner: A) -> Self {
+		Self(inner)
+	}
+}
+
+/// The layout of an allocation of `layout` including the header, and the offset of the returned
+/// pointer from the start of the allocation.
+fn with_header(layout: Layout) -> Option<(Layout, usize)> {
+	let offset = layout.align();
+	let size = layout.size().checked_add(offset)?;
+	Layout::from_size_align(size, layout.align())
+		.ok()
+		.map(|layout| (layout, offset))
+}
+
+impl<A: GlobalAlloc> TrackingAllocator<A> {
+	/// Write the header of a new allocation of `size` bytes and account it.
+	unsafe fn track(&self, base: *mut u8, offset: usize, size: usize) -> *mut u8 {
+		if base.is_null() {
+			return base
+		}
+		let subsystem = CURRENT.try_with(Cell::get).unwrap_or(Subsystem::Other);
+		let ptr = base.add(offset);
+		ptr.sub(1).write(subsystem as u8);
+		ALLOCATED[subsystem as usize].fetch_add(size, Ordering::Relaxed);
+		if !INSTALLED.load(Ordering::Relaxed) {
+			INSTALLED.store(true, Ordering::Relaxed);
+		}
+		ptr
+	}
+}
+
+// SAFETY: The allocations of the inner allocator are only extended by the header, whose layout is
+// derived from the requested layout alone, so that it is identical when the allocation is freed.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let Some((full, offset)) = with_header(layout) else { return std::ptr::null_mut() };
+		self.track(self.0.alloc(full), offset, layout.size())
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		let Some((full, offset)) = with_header(layout) else { return std::ptr::null_mut() };
+		self.track(self.0.alloc_zeroed(full), offset, layout.size())
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		let (full, offset) = with_header(layout).expect("The layout was allocated; qed");
+		let subsystem = ptr.sub(1).read();
+		ALLOCATED[subsystem as usize].fetch_sub(layout.size(), Ordering::Relaxed);
+		self.0.dealloc(ptr.sub(offset), full)
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		let (full, offset) = with_header(layout).expect("The layout was allocated; qed");
+		let Some((new_full, _)) =
+			Layout::from_size_align(new_size, layout.align()).ok().and_then(with_header)
+		else {
+			return std::ptr::null_mut()
+		};
+		let subsystem = ptr.sub(1).read();
+		let base = self.0.realloc(ptr.sub(offset), full, new_full.size());
+		if base.is_null() {
+			return base
+		}
+		// The header is moved along with the data, the allocation stays with its subsystem.
+		let counter = &ALLOCATED[subsystem as usize];
+		counter.fetch_sub(layout.size(), Ordering::Relaxed);
+		counter.fetch_add(new_size, Ordering::Relaxed);
+		base.add(offset)
+	}
+}
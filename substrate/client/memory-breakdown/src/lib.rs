@@ -0,0 +1,202 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Attribution of the heap usage of a node to its subsystems.
+//!
+//! When the [`TrackingAllocator`] is installed as the global allocator of the node binary, every
+//! allocation is attributed to the [`Subsystem`] the allocating thread is working for. Threads
+//! work for [`Subsystem::Other`] unless they are inside [`enter`] or polling a future wrapped with
+//! [`instrument`], which is done for all tasks spawned by the task manager of the node based on
+//! their group.
+//!
+//! Caches can additionally [`report`] their own size, which is added to the size of their
+//! subsystem. The memory of the state cache is reported this way, as its content is loaded by
+//! whichever task reads the state. This memory is therefore also part of the memory allocated by
+//! these tasks, usually [`Subsystem::Other`].
+
+mod allocator;
+
+pub use allocator::TrackingAllocator;
+
+use std::{
+	future::Future,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Mutex,
+	},
+};
+
+/// A subsystem of the node to which memory is attributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Subsystem {
+	/// Everything not attributed to another subsystem.
+	Other = 0,
+	/// The cache of the state.
+	StateCache = 1,
+	/// The networking tasks, including their buffers.
+	Network = 2,
+	/// The transaction pool.
+	TransactionPool = 3,
+	/// The subscriptions of the RPC server.
+	RpcSubscriptions = 4,
+}
+
+impl Subsystem {
+	/// All the subsystems.
+	pub const ALL: [Subsystem; 5] = [
+		Subsystem::Other,
+		Subsystem::StateCache,
+		Subsystem::Network,
+		Subsystem::TransactionPool,
+		Subsystem::RpcSubscriptions,
+	];
+
+	/// The name of the subsystem, as used in metrics and RPC responses.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Subsystem::Other => "other",
+			Subsystem::StateCache => "state-cache",
+			Subsystem::Network => "network",
+			Subsystem::TransactionPool => "transaction-pool",
+			Subsystem::RpcSubscriptions => "rpc-subscriptions",
+		}
+	}
+
+	/// The subsystem of the tasks spawned in the task group `group`.
+	pub fn for_task_group(group: &str) -> Self {
+		match group {
+			"networking" => Subsystem::Network,
+			"transaction-pool" => Subsystem::TransactionPool,
+			"rpc" => Subsystem::RpcSubscriptions,
+			_ => Subsystem::Other,
+		}
+	}
+}
+
+/// Attribute the allocations of the current thread to `subsystem`, until the returned guard is
+/// dropped.
+pub fn enter(subsystem: Subsystem) -> SubsystemGuard {
+	SubsystemGuard { previous: allocator::set_current(subsystem) }
+}
+
+/// Guard returned by [`enter`], restoring the previous subsystem of the thread when dropped.
+pub struct SubsystemGuard {
+	previous: Subsystem,
+}
+
+impl Drop for SubsystemGuard {
+	fn drop(&mut self) {
+		allocator::set_current(self.previous);
+	}
+}
+
+/// Attribute the allocations made while polling `future` to `subsystem`.
+pub fn instrument<F: Future>(subsystem: Subsystem, future: F) -> impl Future<Output = F::Output> {
+	let mut future = Box::pin(future);
+	std::future::poll_fn(move |cx| {
+		let _guard = enter(subsystem);
+		future.as_mut().poll(cx)
+	})
+}
+
+type Reporter = Box<dyn Fn() -> usize + Send + Sync>;
+
+/// The registered reporters, with their id.
+static REPORTERS: Mutex<Vec<(u64, Subsystem, Reporter)>> = Mutex::new(Vec::new());
+
+static NEXT_REPORTER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Report the size returned by `size` as memory of `subsystem`, until the returned handle is
+/// dropped.
+pub fn report(
+	subsystem: Subsystem,
+	size: impl Fn() -> usize + Send + Sync + 'static,
+) -> ReportHandle {
+	let id = NEXT_REPORTER_ID.fetch_add(1, Ordering::Relaxed);
+	REPORTERS
+		.lock()
+		.unwrap_or_else(|e| e.into_inner())
+		.push((id, subsystem, Box::new(size)));
+	ReportHandle { id }
+}
+
+/// Handle returned by [`report`], unregistering the reporter when dropped.
+pub struct ReportHandle {
+	id: u64,
+}
+
+impl Drop for ReportHandle {
+	fn drop(&mut self) {
+		REPORTERS
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.retain(|(id, ..)| *id != self.id);
+	}
+}
+
+/// The memory used by every subsystem, in bytes.
+///
+/// Returns `None` if the [`TrackingAllocator`] is not the global allocator.
+pub fn breakdown() -> Option<Vec<(Subsystem, usize)>> {
+	if !allocator::is_installed() {
+		return None
+	}
+
+	let mut breakdown: Vec<_> = Subsystem::ALL
+		.iter()
+		.map(|subsystem| (*subsystem, allocator::allocated(*subsystem)))
+		.collect();
+	for (_, subsystem, size) in REPORTERS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+		breakdown[*subsystem as usize].1 += size();
+	}
+	Some(breakdown)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[global_allocator]
+	static ALLOC: TrackingAllocator<std::alloc::System> =
+		TrackingAllocator::new(std::alloc::System);
+
+	fn used(subsystem: Subsystem) -> usize {
+		breakdown().unwrap()[subsystem as usize].1
+	}
+
+	#[test]
+	fn allocations_are_attributed_to_subsystems() {
+		let before = used(Subsystem::TransactionPool);
+		let buffer = {
+			let _guard = enter(Subsystem::TransactionPool);
+			vec![0u8; 1 << 20]
+		};
+		assert!(used(Subsystem::TransactionPool) >= before + (1 << 20));
+
+		// Freed from another subsystem, but accounted to the allocating one.
+		let _guard = enter(Subsystem::Network);
+		drop(buffer);
+		assert!(used(Subsystem::TransactionPool) < before + (1 << 20));
+
+		let handle = report(Subsystem::StateCache, || 1 << 30);
+		assert!(used(Subsystem::StateCache) >= 1 << 30);
+		drop(handle);
+		assert!(used(Subsystem::StateCache) < 1 << 30);
+	}
+}
@@ -45,6 +45,11 @@ pub struct SubstrateConfig {
 	/// Maximum number of extrinsics being actively submitted. If a submit extrinsic request's
 	/// delay elapses and we are already at this limit, the request will simply be dropped.
 	pub max_pending_extrinsics: usize,
+
+	/// Route extrinsics submitted via the `author_submitExtrinsic` RPC through the mixnet instead
+	/// of importing them into the local transaction pool. This hides the IP address of the sender
+	/// from the node finally importing the extrinsic.
+	pub route_author_submissions: bool,
 }
 
 impl Default for SubstrateConfig {
@@ -59,6 +64,8 @@ impl Default for SubstrateConfig {
 			extrinsic_queue_capacity: 50,
 			mean_extrinsic_delay: Duration::from_secs(1),
 			max_pending_extrinsics: 20,
+
+			route_author_submissions: false,
 		}
 	}
 }
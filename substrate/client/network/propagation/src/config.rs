@@ -0,0 +1,47 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration of the block propagation latency protocol.
+
+use std::time::Duration;
+
+/// Maximum allowed size for a block-seen notification.
+pub(crate) const MAX_NOTIFICATION_SIZE: u64 = 1024;
+
+/// Maximum number of block-seen messages remembered as already relayed.
+pub(crate) const MAX_KNOWN_MESSAGES: usize = 16384;
+
+/// Number of the most recently imported blocks whose block-seen messages are relayed.
+pub(crate) const MAX_RECENT_BLOCKS: usize = 256;
+
+/// Maximum number of block-seen messages processed per peer and [`REPORT_INTERVAL`], the
+/// following ones are ignored.
+pub(crate) const MAX_MESSAGES_PER_PEER: usize = 512;
+
+/// Maximum difference between the timestamp of a block-seen message and the local clock for the
+/// message to be accepted and relayed.
+pub(crate) const MAX_MESSAGE_AGE: Duration = Duration::from_secs(60);
+
+/// Time during which the latencies of an authored block are collected before being reported.
+pub(crate) const REPORT_WINDOW: Duration = Duration::from_secs(30);
+
+/// Interval at which the latencies of the authored blocks are reported.
+pub(crate) const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of authored blocks whose latencies are collected at the same time.
+pub(crate) const MAX_AUTHORED_BLOCKS: usize = 64;
@@ -0,0 +1,132 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Collection of the latencies of the authored blocks.
+
+use crate::config::{MAX_AUTHORED_BLOCKS, REPORT_WINDOW};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::collections::VecDeque;
+
+/// A block authored by the node, with the latencies reported for it so far.
+struct AuthoredBlock<B: BlockT> {
+	hash: B::Hash,
+	number: NumberFor<B>,
+	/// Milliseconds since the Unix epoch at which the block was authored.
+	authored_at: u64,
+	/// Latencies in milliseconds, one per node that has seen the block.
+	latencies: Vec<u64>,
+}
+
+/// The latencies of an authored block, once its report window is over.
+pub(crate) struct Report<B: BlockT> {
+	pub hash: B::Hash,
+	pub number: NumberFor<B>,
+	/// Latencies in milliseconds, in ascending order.
+	latencies: Vec<u64>,
+}
+
+impl<B: BlockT> Report<B> {
+	/// The number of nodes that have reported seeing the block.
+	pub fn nodes(&self) -> usize {
+		self.latencies.len()
+	}
+
+	/// The latency in milliseconds below which `quantile` of the nodes have seen the block.
+	///
+	/// `None` if no node has reported seeing the block.
+	pub fn percentile(&self, quantile: f64) -> Option<u64> {
+		let rank = (quantile * self.latencies.len() as f64).ceil() as usize;
+		self.latencies.get(rank.max(1) - 1).copied()
+	}
+}
+
+/// The blocks authored by the node whose report window isn't over yet, in authoring order.
+pub(crate) struct AuthoredBlocks<B: BlockT> {
+	blocks: VecDeque<AuthoredBlock<B>>,
+}
+
+impl<B: BlockT> AuthoredBlocks<B> {
+	pub fn new() -> Self {
+		Self { blocks: VecDeque::new() }
+	}
+
+	/// Start collecting the latencies of a block authored at `authored_at`.
+	pub fn insert(&mut self, hash: B::Hash, number: NumberFor<B>, authored_at: u64) {
+		if self.blocks.len() >= MAX_AUTHORED_BLOCKS {
+			self.blocks.pop_front();
+		}
+		self.blocks.push_back(AuthoredBlock { hash, number, authored_at, latencies: Vec::new() });
+	}
+
+	/// Record that a node has seen the block at `seen_at`.
+	pub fn record(&mut self, hash: &B::Hash, seen_at: u64) {
+		if let Some(block) = self.blocks.iter_mut().find(|block| block.hash == *hash) {
+			block.latencies.push(seen_at.saturating_sub(block.authored_at));
+		}
+	}
+
+	/// Take the reports of the blocks whose report window is over at `now`.
+	pub fn take_finished(&mut self, now: u64) -> Vec<Report<B>> {
+		let window = REPORT_WINDOW.as_millis() as u64;
+		let mut reports = Vec::new();
+		while self.blocks.front().is_some_and(|block| block.authored_at + window <= now) {
+			let AuthoredBlock { hash, number, mut latencies, .. } =
+				self.blocks.pop_front().expect("Front block exists; qed");
+			latencies.sort_unstable();
+			reports.push(Report { hash, number, latencies });
+		}
+		reports
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::testing::{Block as RawBlock, MockCallU64, TestXt, H256};
+
+	type Block = RawBlock<TestXt<MockCallU64, ()>>;
+
+	#[test]
+	fn reports_percentiles_after_the_window() {
+		let mut blocks = AuthoredBlocks::<Block>::new();
+		let hash = H256::repeat_byte(1);
+		blocks.insert(hash, 1, 1_000);
+		for latency in (1..=100).rev() {
+			blocks.record(&hash, 1_000 + latency * 10);
+		}
+		blocks.record(&H256::repeat_byte(2), 1_500);
+
+		let window = REPORT_WINDOW.as_millis() as u64;
+		assert!(blocks.take_finished(1_000 + window - 1).is_empty());
+		let reports = blocks.take_finished(1_000 + window);
+		assert_eq!(reports.len(), 1);
+		assert_eq!(reports[0].nodes(), 100);
+		assert_eq!(reports[0].percentile(0.5), Some(500));
+		assert_eq!(reports[0].percentile(0.99), Some(990));
+		assert_eq!(reports[0].percentile(1.0), Some(1_000));
+		assert!(blocks.take_finished(u64::MAX).is_empty());
+	}
+
+	#[test]
+	fn percentile_of_no_latencies_is_none() {
+		let mut blocks = AuthoredBlocks::<Block>::new();
+		blocks.insert(H256::repeat_byte(1), 1, 0);
+		let reports = blocks.take_finished(u64::MAX);
+		assert_eq!(reports[0].percentile(0.5), None);
+	}
+}
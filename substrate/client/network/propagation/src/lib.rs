@@ -0,0 +1,443 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Measurement of the propagation latency of the blocks authored by the node.
+//!
+//! This is an opt-in notification protocol. Every participating node signs, with its network
+//! identity, the moment at which it first imported a block announced by the network and gossips
+//! this block-seen timestamp to its peers, which relay it further as long as they imported the
+//! block recently and the peer stays within its rate limit. Authoring nodes match the
+//! timestamps of their own blocks with the moment they authored them, and export the percentiles
+//! of the latencies through Prometheus once the blocks are 30 seconds old.
+//!
+//! The latencies are computed from the wall clocks of different nodes, so they are only as
+//! accurate as the clocks of the nodes are synchronized.
+//!
+//! Usage:
+//!
+//! - Use [`PropagationHandlerPrototype::new`] to create a prototype.
+//! - Pass the notification protocol config returned from [`PropagationHandlerPrototype::new`] to
+//!   the network configuration.
+//! - Use [`PropagationHandlerPrototype::build`] then [`PropagationHandler::run`] to obtain a
+//!   `Future` that runs the protocol.
+
+mod config;
+mod latency;
+mod message;
+
+use crate::{config::*, latency::AuthoredBlocks, message::BlockSeen};
+
+use codec::{Decode, Encode};
+use futures::{prelude::*, FutureExt};
+use prometheus_endpoint::{register, Gauge, GaugeVec, Opts, PrometheusError, Registry, F64, U64};
+use sc_client_api::{BlockImportNotification, BlockchainEvents, ImportNotifications};
+use sc_network::{
+	config::{NonReservedPeerMode, SetConfig},
+	error, multiaddr,
+	peer_store::PeerStoreProvider,
+	service::{
+		traits::{NotificationEvent, NotificationService, ValidationResult},
+		NotificationMetrics,
+	},
+	types::ProtocolName,
+	utils::{interval, LruHashSet},
+	NetworkBackend, NetworkPeers, NetworkSigner, NetworkStateInfo,
+};
+use sc_network_sync::{SyncEvent, SyncEventStream};
+use sc_network_types::PeerId;
+use sp_consensus::BlockOrigin;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{
+	collections::HashMap,
+	iter,
+	num::NonZeroUsize,
+	pin::Pin,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+mod rep {
+	use sc_network::ReputationChange as Rep;
+	/// Reputation change when a peer sends us a message that can't be decoded or verified.
+	pub const BAD_MESSAGE: Rep = Rep::new(-(1 << 12), "Bad block-seen message");
+}
+
+const LOG_TARGET: &str = "block-propagation";
+
+/// The reported percentiles of the latencies, with their label.
+const QUANTILES: [(f64, &str); 3] = [(0.5, "0.5"), (0.9, "0.9"), (0.99, "0.99")];
+
+/// Parameters of the block propagation latency protocol.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct PropagationLatencyParams {
+	/// Measure the propagation latency of the blocks authored by the node.
+	///
+	/// Enables a protocol exchanging signed block-seen timestamps with the peers that enable it
+	/// too. The percentiles of the latencies of the authored blocks are exported through
+	/// Prometheus.
+	#[arg(long)]
+	pub block_propagation_latency: bool,
+}
+
+struct Metrics {
+	latency: GaugeVec<F64>,
+	reporting_nodes: Gauge<U64>,
+}
+
+impl Metrics {
+	fn register(r: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			latency: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_block_propagation_latency_seconds",
+						"Percentiles of the delay after which the nodes have seen the last block \
+						authored by this node",
+					),
+					&["quantile"],
+				)?,
+				r,
+			)?,
+			reporting_nodes: register(
+				Gauge::new(
+					"substrate_block_propagation_reporting_nodes",
+					"Number of nodes that reported seeing the last block authored by this node",
+				)?,
+				r,
+			)?,
+		})
+	}
+}
+
+/// Prototype for a [`PropagationHandler`].
+pub struct PropagationHandlerPrototype {
+	protocol_name: ProtocolName,
+	notification_service: Box<dyn NotificationService>,
+}
+
+impl PropagationHandlerPrototype {
+	/// Create a new instance.
+	pub fn new<
+		Hash: AsRef<[u8]>,
+		Block: BlockT,
+		Net: NetworkBackend<Block, <Block as BlockT>::Hash>,
+	>(
+		genesis_hash: Hash,
+		fork_id: Option<&str>,
+		metrics: NotificationMetrics,
+		peer_store_handle: Arc<dyn PeerStoreProvider>,
+	) -> (Self, Net::NotificationProtocolConfig) {
+		let genesis_hash = genesis_hash.as_ref();
+		let protocol_name = if let Some(fork_id) = fork_id {
+			format!("/{}/{}/block-seen/1", array_bytes::bytes2hex("", genesis_hash), fork_id)
+		} else {
+			format!("/{}/block-seen/1", array_bytes::bytes2hex("", genesis_hash))
+		};
+		let (config, notification_service) = Net::notification_config(
+			protocol_name.clone().into(),
+			Vec::new(),
+			MAX_NOTIFICATION_SIZE,
+			None,
+			SetConfig {
+				in_peers: 0,
+				out_peers: 0,
+				reserved_nodes: Vec::new(),
+				non_reserved_mode: NonReservedPeerMode::Deny,
+			},
+			metrics,
+			peer_store_handle,
+		);
+
+		(Self { protocol_name: protocol_name.into(), notification_service }, config)
+	}
+
+	/// Turns the prototype into the actual handler.
+	pub fn build<
+		B: BlockT,
+		N: NetworkPeers + NetworkSigner + NetworkStateInfo,
+		S: SyncEventStream,
+		C: BlockchainEvents<B>,
+	>(
+		self,
+		network: N,
+		sync: S,
+		client: &C,
+		metrics_registry: Option<&Registry>,
+	) -> error::Result<PropagationHandler<B, N>> {
+		Ok(PropagationHandler {
+			protocol_name: self.protocol_name,
+			notification_service: self.notification_service,
+			report_timeout: (Box::pin(interval(REPORT_INTERVAL))
+				as Pin<Box<dyn Stream<Item = ()> + Send>>)
+				.fuse(),
+			local_peer_id: network.local_peer_id(),
+			network,
+			sync_event_stream: sync.event_stream("block-propagation-handler-sync").fuse(),
+			import_notifications: client.import_notification_stream().fuse(),
+			peers: HashMap::new(),
+			known_messages: LruHashSet::new(
+				NonZeroUsize::new(MAX_KNOWN_MESSAGES).expect("Constant is nonzero"),
+			),
+			recent_blocks: LruHashSet::new(
+				NonZeroUsize::new(MAX_RECENT_BLOCKS).expect("Constant is nonzero"),
+			),
+			authored_blocks: AuthoredBlocks::new(),
+			metrics: if let Some(r) = metrics_registry {
+				Some(Metrics::register(r)?)
+			} else {
+				None
+			},
+		})
+	}
+}
+
+/// Handler of the block propagation latency protocol. Call [`PropagationHandler::run`] to start
+/// the processing.
+pub struct PropagationHandler<B: BlockT, N> {
+	protocol_name: ProtocolName,
+	/// Notification service.
+	notification_service: Box<dyn NotificationService>,
+	/// Interval at which the latencies of the authored blocks are reported.
+	report_timeout: stream::Fuse<Pin<Box<dyn Stream<Item = ()> + Send>>>,
+	/// Network service to use to sign messages and manage peers.
+	network: N,
+	local_peer_id: PeerId,
+	/// Receiver for syncing-related events.
+	sync_event_stream: stream::Fuse<Pin<Box<dyn Stream<Item = SyncEvent> + Send>>>,
+	/// Notifications of the imported blocks.
+	import_notifications: stream::Fuse<ImportNotifications<B>>,
+	/// Peers with an open substream, with the number of messages received from them during the
+	/// current report interval.
+	peers: HashMap<PeerId, usize>,
+	/// The messages already relayed, by block hash and signer.
+	known_messages: LruHashSet<(B::Hash, PeerId)>,
+	/// The most recently imported blocks, the only ones whose messages are relayed.
+	recent_blocks: LruHashSet<B::Hash>,
+	authored_blocks: AuthoredBlocks<B>,
+	/// Prometheus metrics.
+	metrics: Option<Metrics>,
+}
+
+impl<B, N> PropagationHandler<B, N>
+where
+	B: BlockT,
+	N: NetworkPeers + NetworkSigner,
+{
+	/// Turns the [`PropagationHandler`] into a future that should run forever and not be
+	/// interrupted.
+	pub async fn run(mut self) {
+		loop {
+			futures::select! {
+				_ = self.report_timeout.next() => {
+					self.peers.values_mut().for_each(|received| *received = 0);
+					self.report_latencies();
+				},
+				notification = self.import_notifications.next() => {
+					if let Some(notification) = notification {
+						self.on_block_imported(notification);
+					} else {
+						// The client has seemingly closed. Closing as well.
+						return
+					}
+				},
+				sync_event = self.sync_event_stream.next() => {
+					if let Some(sync_event) = sync_event {
+						self.handle_sync_event(sync_event);
+					} else {
+						// Syncing has seemingly closed. Closing as well.
+						return
+					}
+				},
+				event = self.notification_service.next_event().fuse() => {
+					if let Some(event) = event {
+						self.handle_notification_event(event)
+					} else {
+						// `Notifications` has seemingly closed. Closing as well.
+						return
+					}
+				},
+			}
+		}
+	}
+
+	fn handle_sync_event(&mut self, event: SyncEvent) {
+		match event {
+			SyncEvent::PeerConnected(remote) => {
+				let addr = iter::once(multiaddr::Protocol::P2p(remote.into()))
+					.collect::<multiaddr::Multiaddr>();
+				let result = self.network.add_peers_to_reserved_set(
+					self.protocol_name.clone(),
+					iter::once(addr).collect(),
+				);
+				if let Err(err) = result {
+					log::error!(target: LOG_TARGET, "Add reserved peer failed: {}", err);
+				}
+			},
+			SyncEvent::PeerDisconnected(remote) => {
+				let result = self.network.remove_peers_from_reserved_set(
+					self.protocol_name.clone(),
+					iter::once(remote).collect(),
+				);
+				if let Err(err) = result {
+					log::error!(target: LOG_TARGET, "Failed to remove reserved peer: {err}");
+				}
+			},
+		}
+	}
+
+	fn handle_notification_event(&mut self, event: NotificationEvent) {
+		match event {
+			NotificationEvent::ValidateInboundSubstream { result_tx, .. } => {
+				let _ = result_tx.send(ValidationResult::Accept);
+			},
+			NotificationEvent::NotificationStreamOpened { peer, .. } => {
+				self.peers.insert(peer, 0);
+			},
+			NotificationEvent::NotificationStreamClosed { peer } => {
+				self.peers.remove(&peer);
+			},
+			NotificationEvent::NotificationReceived { peer, notification } => {
+				self.on_block_seen(peer, notification);
+			},
+		}
+	}
+
+	/// Called when a block has been imported.
+	///
+	/// Authored blocks start being tracked, and blocks announced by the network are reported as
+	/// seen to the peers.
+	fn on_block_imported(&mut self, notification: BlockImportNotification<B>) {
+		let now = unix_time_millis();
+		self.recent_blocks.insert(notification.hash);
+		match notification.origin {
+			BlockOrigin::Own =>
+				self.authored_blocks.insert(notification.hash, *notification.header.number(), now),
+			BlockOrigin::NetworkBroadcast => {
+				let message = match BlockSeen::sign(
+					&self.network,
+					self.local_peer_id,
+					notification.hash,
+					now,
+				) {
+					Ok(message) => message,
+					Err(err) => {
+						log::debug!(target: LOG_TARGET, "Failed to sign block-seen message: {err}");
+						return
+					},
+				};
+				self.known_messages.insert((notification.hash, self.local_peer_id));
+				self.send(message.encode(), None);
+			},
+			_ => {},
+		}
+	}
+
+	/// Called when a peer sends us a block-seen message.
+	///
+	/// Only the messages about recently imported blocks are relayed, up to
+	/// [`MAX_MESSAGES_PER_PEER`] per peer and report interval.
+	fn on_block_seen(&mut self, who: PeerId, notification: Vec<u8>) {
+		let Some(received) = self.peers.get_mut(&who) else { return };
+		*received += 1;
+		if *received > MAX_MESSAGES_PER_PEER {
+			log::trace!(target: LOG_TARGET, "Ignoring block-seen message over the limit of {who}");
+			return
+		}
+
+		let Some((message, signer)) = BlockSeen::<B::Hash>::decode(&mut notification.as_ref())
+			.ok()
+			.and_then(|message| message.signer().map(|signer| (message, signer)))
+		else {
+			log::debug!(target: LOG_TARGET, "Failed to decode block-seen message from {who}");
+			self.network.report_peer(who, rep::BAD_MESSAGE);
+			return
+		};
+
+		let id = (message.block_hash, signer);
+		if self.known_messages.contains(&id) {
+			return
+		}
+		// The block may not be imported yet, or be an old one, this is not the fault of the peer.
+		if !self.recent_blocks.contains(&message.block_hash) {
+			log::trace!(target: LOG_TARGET, "Ignoring block-seen message of unknown block from {who}");
+			return
+		}
+		// The timestamps far from the local clock are ignored rather than punished, as they
+		// are caused by the clock skew of the signer.
+		if unix_time_millis().abs_diff(message.seen_at) > MAX_MESSAGE_AGE.as_millis() as u64 {
+			log::trace!(target: LOG_TARGET, "Ignoring outdated block-seen message from {who}");
+			return
+		}
+		if !message.verify(&self.network, signer) {
+			log::debug!(target: LOG_TARGET, "Invalid signature of block-seen message from {who}");
+			self.network.report_peer(who, rep::BAD_MESSAGE);
+			return
+		}
+
+		self.known_messages.insert(id);
+		self.authored_blocks.record(&message.block_hash, message.seen_at);
+		self.send(notification, Some(who));
+	}
+
+	/// Send an encoded message to all peers, except `except`.
+	fn send(&mut self, notification: Vec<u8>, except: Option<PeerId>) {
+		for peer in self.peers.keys().filter(|peer| Some(**peer) != except) {
+			self.notification_service.send_sync_notification(peer, notification.clone());
+		}
+	}
+
+	/// Report the latencies of the authored blocks whose report window is over.
+	fn report_latencies(&mut self) {
+		for report in self.authored_blocks.take_finished(unix_time_millis()) {
+			if let Some(metrics) = &self.metrics {
+				metrics.reporting_nodes.set(report.nodes() as u64);
+			}
+			let [Some(p50), Some(p90), Some(p99)] =
+				QUANTILES.map(|(quantile, _)| report.percentile(quantile))
+			else {
+				log::debug!(
+					target: LOG_TARGET,
+					"Block #{} ({:?}) wasn't reported as seen by any node",
+					report.number,
+					report.hash,
+				);
+				continue
+			};
+			log::debug!(
+				target: LOG_TARGET,
+				"Block #{} ({:?}) seen by {} nodes, p50 {p50}ms, p90 {p90}ms, p99 {p99}ms",
+				report.number,
+				report.hash,
+				report.nodes(),
+			);
+
+			if let Some(metrics) = &self.metrics {
+				for ((_, label), latency) in QUANTILES.iter().zip([p50, p90, p99]) {
+					metrics.latency.with_label_values(&[label]).set(latency as f64 / 1000.0);
+				}
+			}
+		}
+	}
+}
+
+/// Milliseconds since the Unix epoch.
+fn unix_time_millis() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |since| since.as_millis() as u64)
+}
@@ -0,0 +1,82 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The signed block-seen message of the protocol.
+
+use codec::{Decode, Encode};
+use sc_network::{service::signature::SigningError, NetworkSigner};
+use sc_network_types::PeerId;
+
+/// Context of the signed payload, so that the signatures can't be reused for other purposes.
+const SIGNING_CONTEXT: &[u8] = b"substrate-block-seen";
+
+/// The moment at which a node first saw a block, signed with its network identity.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub(crate) struct BlockSeen<H> {
+	/// Hash of the seen block.
+	pub block_hash: H,
+	/// Milliseconds since the Unix epoch at which the block was seen.
+	pub seen_at: u64,
+	/// The peer ID of the node that has seen the block.
+	pub signer: Vec<u8>,
+	/// The Protobuf-encoded public key of the network identity of the node.
+	pub public_key: Vec<u8>,
+	/// Signature of the block hash and the timestamp.
+	pub signature: Vec<u8>,
+}
+
+impl<H: Encode> BlockSeen<H> {
+	/// Create a message signed with the local network identity.
+	pub fn sign(
+		network: &impl NetworkSigner,
+		local_peer_id: PeerId,
+		block_hash: H,
+		seen_at: u64,
+	) -> Result<Self, SigningError> {
+		let signature = network.sign_with_local_identity(payload(&block_hash, seen_at))?;
+		Ok(Self {
+			block_hash,
+			seen_at,
+			signer: local_peer_id.to_bytes(),
+			public_key: signature.public_key.encode_protobuf(),
+			signature: signature.bytes,
+		})
+	}
+
+	/// The peer ID of the node that has seen the block, if valid.
+	pub fn signer(&self) -> Option<PeerId> {
+		PeerId::from_bytes(&self.signer).ok()
+	}
+
+	/// Whether the message is signed by the network identity of `signer`.
+	pub fn verify(&self, network: &impl NetworkSigner, signer: PeerId) -> bool {
+		network
+			.verify(
+				signer,
+				&self.public_key,
+				&self.signature,
+				&payload(&self.block_hash, self.seen_at),
+			)
+			.unwrap_or(false)
+	}
+}
+
+/// The signed payload of a [`BlockSeen`].
+fn payload<H: Encode>(block_hash: &H, seen_at: u64) -> Vec<u8> {
+	(SIGNING_CONTEXT, block_hash, seen_at).encode()
+}
@@ -60,12 +60,13 @@ use std::{
 	future::Future,
 	io::{self, Write},
 	iter,
-	net::Ipv4Addr,
+	net::{Ipv4Addr, SocketAddr},
 	num::NonZeroUsize,
 	path::{Path, PathBuf},
 	pin::Pin,
 	str::{self, FromStr},
 	sync::Arc,
+	time::Duration,
 };
 
 /// Protocol name prefix, transmitted on the wire for legacy protocol names.
@@ -263,6 +264,65 @@ pub enum TransportConfig {
 	MemoryOnly,
 }
 
+/// SOCKS5 proxy that outbound TCP connections are dialed through.
+///
+/// Host names, including the `.onion` addresses of `/onion3/...` multiaddresses, are resolved by
+/// the proxy, so that no DNS queries leak the peers the node connects to.
+#[derive(Clone, Debug)]
+pub struct ProxyConfig {
+	/// Address of the proxy, e.g. `127.0.0.1:9050` for a local Tor daemon.
+	pub address: SocketAddr,
+
+	/// Username and password to authenticate with, if the proxy requires them.
+	///
+	/// Tor isolates connections authenticated with different credentials on separate circuits.
+	pub credentials: Option<(String, String)>,
+}
+
+/// Checkpoint providers that must agree on the target of the warp sync before it is trusted.
+///
+/// If providers are configured, the warp sync of an empty database downloads the state of a
+/// finalized block attested by at least `threshold` of the providers, instead of the block
+/// proven by the warp proofs of the peers.
+#[derive(Clone, Debug, Default)]
+pub struct WarpCheckpointConfig {
+	/// The checkpoint providers: `http(s)://` URLs of RPC endpoints of trusted nodes, or paths of
+	/// signed checkpoint files.
+	pub providers: Vec<String>,
+
+	/// The number of providers that must attest the target. `None` requires all of them.
+	pub threshold: Option<usize>,
+
+	/// The publishers whose signed checkpoints are accepted.
+	pub trusted_publishers: Vec<sp_core::sr25519::Public>,
+}
+
+impl WarpCheckpointConfig {
+	/// Returns `true` if no provider is configured.
+	pub fn is_empty(&self) -> bool {
+		self.providers.is_empty()
+	}
+}
+
+/// How the node propagates the transactions submitted to it locally.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransactionPropagation {
+	/// Gossip locally submitted transactions to all peers, like all other transactions. This is
+	/// the default.
+	#[default]
+	Flood,
+	/// Dandelion-style stem/fluff propagation.
+	///
+	/// Locally submitted transactions are first relayed to a single, periodically rotated peer
+	/// (the stem), which gossips them to its own peers (the fluff). Observers therefore see the
+	/// stem peer as the origin of the transactions. If a transaction isn't received back from
+	/// another peer within the `embargo`, the node gossips it to all peers itself.
+	Stem {
+		/// Time to wait for a stemmed transaction to be received back from the network.
+		embargo: Duration,
+	},
+}
+
 /// The policy for connections to non-reserved peers.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NonReservedPeerMode {
@@ -629,6 +689,9 @@ pub struct NetworkConfiguration {
 	/// Initial syncing mode.
 	pub sync_mode: SyncMode,
 
+	/// Checkpoint providers attesting the target of the warp sync.
+	pub warp_checkpoints: WarpCheckpointConfig,
+
 	/// True if Kademlia random discovery should be enabled.
 	///
 	/// If true, the node will automatically randomly walk the DHT in order to find new peers.
@@ -652,6 +715,14 @@ pub struct NetworkConfiguration {
 
 	/// Networking backend used for P2P communication.
 	pub network_backend: NetworkBackendType,
+
+	/// SOCKS5 proxy to dial outbound TCP connections through, if any.
+	///
+	/// Only supported by the libp2p network backend.
+	pub proxy: Option<ProxyConfig>,
+
+	/// Propagation of locally submitted transactions.
+	pub transaction_propagation: TransactionPropagation,
 }
 
 impl NetworkConfiguration {
@@ -677,6 +748,7 @@ impl NetworkConfiguration {
 			max_parallel_downloads: 5,
 			max_blocks_per_request: 64,
 			sync_mode: SyncMode::Full,
+			warp_checkpoints: WarpCheckpointConfig::default(),
 			enable_dht_random_walk: true,
 			allow_non_globals_in_dht: false,
 			kademlia_disjoint_query_paths: false,
@@ -684,6 +756,8 @@ impl NetworkConfiguration {
 				.expect("value is a constant; constant is non-zero; qed."),
 			ipfs_server: false,
 			network_backend: NetworkBackendType::Libp2p,
+			proxy: None,
+			transaction_propagation: TransactionPropagation::Flood,
 		}
 	}
 
@@ -80,6 +80,9 @@ pub enum Error {
 	/// Litep2p error.
 	#[error("Litep2p error: `{0}`")]
 	Litep2p(litep2p::Error),
+	/// A SOCKS5 proxy has been configured for a network backend not supporting it.
+	#[error("Dialing through a SOCKS5 proxy is only supported by the libp2p network backend")]
+	ProxyNotSupported,
 }
 
 // Make `Debug` use the `Display` implementation.
@@ -389,6 +389,10 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 		params.network_config.sanity_check_addresses()?;
 		params.network_config.sanity_check_bootnodes()?;
 
+		if params.network_config.network_config.proxy.is_some() {
+			return Err(Error::ProxyNotSupported)
+		}
+
 		let mut config_builder =
 			Self::configure_transport(&params.network_config).with_keypair(keypair.clone());
 		let known_addresses = params.network_config.known_addresses();
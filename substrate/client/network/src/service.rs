@@ -344,7 +344,11 @@ where
 				TransportConfig::Normal { .. } => false,
 			};
 
-			transport::build_transport(local_identity.clone().into(), config_mem)
+			transport::build_transport(
+				local_identity.clone().into(),
+				config_mem,
+				network_config.proxy.clone(),
+			)
 		};
 
 		let (to_notifications, from_protocol_controllers) =
@@ -18,6 +18,9 @@
 
 //! Transport that serves as a common ground for all connections.
 
+mod socks5;
+
+use crate::config::ProxyConfig;
 use either::Either;
 use libp2p::{
 	core::{
@@ -38,21 +41,31 @@ pub use libp2p::bandwidth::BandwidthSinks;
 /// If `memory_only` is true, then only communication within the same process are allowed. Only
 /// addresses with the format `/memory/...` are allowed.
 ///
+/// If a `proxy` is given, outbound TCP connections are dialed through it, and host names are
+/// resolved by the proxy instead of the system resolver.
+///
 /// Returns a `BandwidthSinks` object that allows querying the average bandwidth produced by all
 /// the connections spawned with this transport.
 #[allow(deprecated)]
 pub fn build_transport(
 	keypair: identity::Keypair,
 	memory_only: bool,
+	proxy: Option<ProxyConfig>,
 ) -> (Boxed<(PeerId, StreamMuxerBox)>, Arc<BandwidthSinks>) {
 	// Build the base layer of the transport.
-	let transport = if !memory_only {
+	let transport = if let (false, Some(proxy)) = (memory_only, proxy) {
+		// Main transport: SOCKS5(TCP), WS + WSS on top of it
+		let tcp_config = tcp::Config::new().nodelay(true);
+		let socks = socks5::Socks5Transport::new(proxy.clone(), tcp_config.clone());
+		let socks_for_ws = socks5::Socks5Transport::new(proxy, tcp_config);
+		Either::Left(Either::Right(websocket::WsConfig::new(socks_for_ws).or_transport(socks)))
+	} else if !memory_only {
 		// Main transport: DNS(TCP)
 		let tcp_config = tcp::Config::new().nodelay(true);
 		let tcp_trans = tcp::tokio::Transport::new(tcp_config.clone());
 		let dns_init = dns::tokio::Transport::system(tcp_trans);
 
-		Either::Left(if let Ok(dns) = dns_init {
+		Either::Left(Either::Left(if let Ok(dns) = dns_init {
 			// WS + WSS transport
 			//
 			// Main transport can't be used for `/wss` addresses because WSS transport needs
@@ -68,7 +81,7 @@ pub fn build_transport(
 			let desktop_trans = websocket::WsConfig::new(tcp_trans)
 				.or_transport(tcp::tokio::Transport::new(tcp_config));
 			Either::Right(desktop_trans)
-		})
+		}))
 	} else {
 		Either::Right(OptionalTransport::some(libp2p::core::transport::MemoryTransport::default()))
 	};
@@ -0,0 +1,367 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transport dialing outbound TCP connections through a SOCKS5 proxy, as specified by
+//! [RFC 1928](https://www.rfc-editor.org/rfc/rfc1928).
+//!
+//! Host names are sent to the proxy unresolved, which makes it possible to dial `/onion3/...`
+//! addresses through Tor. Listening is done directly over TCP.
+
+use crate::config::ProxyConfig;
+use futures::{future::BoxFuture, FutureExt};
+use libp2p::{
+	core::{
+		transport::{Boxed, DialOpts, ListenerId, TransportError, TransportEvent},
+		Endpoint,
+	},
+	multiaddr::Protocol,
+	tcp, Multiaddr,
+};
+use std::{
+	io,
+	net::SocketAddr,
+	pin::Pin,
+	task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+const USERNAME_PASSWORD: u8 = 0x02;
+const USERNAME_PASSWORD_VERSION: u8 = 0x01;
+const CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// Destination of a connection through the proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+	Ip(SocketAddr),
+	Domain(String, u16),
+}
+
+impl Target {
+	/// Parse the destination of `/ip4`, `/ip6`, `/dns*` and `/onion3` addresses, optionally
+	/// followed by `/p2p/...`.
+	fn from_multiaddr(addr: &Multiaddr) -> Option<Self> {
+		let mut protocols = addr.iter();
+		let target = match protocols.next()? {
+			Protocol::Onion3(onion) =>
+				Target::Domain(format!("{}.onion", base32(onion.hash())), onion.port()),
+			host => {
+				let port = match protocols.next()? {
+					Protocol::Tcp(port) => port,
+					_ => return None,
+				};
+				match host {
+					Protocol::Ip4(ip) => Target::Ip(SocketAddr::new(ip.into(), port)),
+					Protocol::Ip6(ip) => Target::Ip(SocketAddr::new(ip.into(), port)),
+					Protocol::Dns(name) | Protocol::Dns4(name) | Protocol::Dns6(name) =>
+						Target::Domain(name.into_owned(), port),
+					_ => return None,
+				}
+			},
+		};
+		match protocols.next() {
+			None | Some(Protocol::P2p(_)) => Some(target),
+			_ => None,
+		}
+	}
+}
+
+/// Lowercase base32 encoding without padding, as used by onion addresses.
+fn base32(data: &[u8]) -> String {
+	const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+	let mut encoded = String::with_capacity((data.len() * 8).div_ceil(5));
+	let (mut buffer, mut bits) = (0u32, 0);
+	for byte in data {
+		buffer = (buffer << 8) | u32::from(*byte);
+		bits += 8;
+		while bits >= 5 {
+			bits -= 5;
+			encoded.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+		}
+		buffer &= (1 << bits) - 1;
+	}
+	if bits > 0 {
+		encoded.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+	}
+	encoded
+}
+
+fn invalid_data(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("SOCKS5 proxy: {message}"))
+}
+
+/// Negotiate a connection to `target` over `stream`, an established connection to the proxy.
+async fn handshake<S>(
+	stream: &mut S,
+	credentials: Option<&(String, String)>,
+	target: &Target,
+) -> io::Result<()>
+where
+	S: AsyncRead + AsyncWrite + Unpin,
+{
+	let method = if credentials.is_some() { USERNAME_PASSWORD } else { NO_AUTHENTICATION };
+	stream.write_all(&[VERSION, 1, method]).await?;
+	let mut reply = [0u8; 2];
+	stream.read_exact(&mut reply).await?;
+	if reply[0] != VERSION {
+		return Err(invalid_data("unexpected protocol version"))
+	}
+	match (reply[1], credentials) {
+		(NO_AUTHENTICATION, None) => {},
+		(USERNAME_PASSWORD, Some((username, password))) => {
+			if username.len() > 255 || password.len() > 255 {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"SOCKS5 proxy credentials are limited to 255 bytes",
+				));
+			}
+			let mut request = vec![USERNAME_PASSWORD_VERSION, username.len() as u8];
+			request.extend_from_slice(username.as_bytes());
+			request.push(password.len() as u8);
+			request.extend_from_slice(password.as_bytes());
+			stream.write_all(&request).await?;
+
+			stream.read_exact(&mut reply).await?;
+			if reply[1] != 0 {
+				return Err(io::Error::new(
+					io::ErrorKind::PermissionDenied,
+					"SOCKS5 proxy rejected the credentials",
+				));
+			}
+		},
+		_ => return Err(invalid_data("no acceptable authentication method")),
+	}
+
+	let mut request = vec![VERSION, CONNECT, 0];
+	let port = match target {
+		Target::Ip(SocketAddr::V4(addr)) => {
+			request.push(ATYP_IPV4);
+			request.extend_from_slice(&addr.ip().octets());
+			addr.port()
+		},
+		Target::Ip(SocketAddr::V6(addr)) => {
+			request.push(ATYP_IPV6);
+			request.extend_from_slice(&addr.ip().octets());
+			addr.port()
+		},
+		Target::Domain(name, port) => {
+			if name.len() > 255 {
+				return Err(io::Error::new(io::ErrorKind::InvalidInput, "Host name too long"))
+			}
+			request.push(ATYP_DOMAIN);
+			request.push(name.len() as u8);
+			request.extend_from_slice(name.as_bytes());
+			*port
+		},
+	};
+	request.extend_from_slice(&port.to_be_bytes());
+	stream.write_all(&request).await?;
+
+	let mut reply = [0u8; 4];
+	stream.read_exact(&mut reply).await?;
+	if reply[0] != VERSION {
+		return Err(invalid_data("unexpected protocol version"))
+	}
+	let (kind, reason) = match reply[1] {
+		0x00 => (None, ""),
+		0x02 => (Some(io::ErrorKind::PermissionDenied), "connection not allowed by ruleset"),
+		0x03 => (Some(io::ErrorKind::Other), "network unreachable"),
+		0x04 => (Some(io::ErrorKind::Other), "host unreachable"),
+		0x05 => (Some(io::ErrorKind::ConnectionRefused), "connection refused"),
+		0x06 => (Some(io::ErrorKind::TimedOut), "TTL expired"),
+		0x07 => (Some(io::ErrorKind::Unsupported), "command not supported"),
+		0x08 => (Some(io::ErrorKind::Unsupported), "address type not supported"),
+		_ => (Some(io::ErrorKind::Other), "general failure"),
+	};
+	if let Some(kind) = kind {
+		return Err(io::Error::new(kind, format!("SOCKS5 proxy failed to connect: {reason}")))
+	}
+
+	// Skip the address the proxy bound to connect to the target.
+	let address_len = match reply[3] {
+		ATYP_IPV4 => 4,
+		ATYP_IPV6 => 16,
+		ATYP_DOMAIN => stream.read_u8().await? as usize,
+		_ => return Err(invalid_data("unknown address type")),
+	};
+	let mut bound = vec![0u8; address_len + 2];
+	stream.read_exact(&mut bound).await?;
+	Ok(())
+}
+
+async fn connect(proxy: ProxyConfig, target: Target) -> io::Result<tcp::tokio::TcpStream> {
+	let mut stream = tokio::net::TcpStream::connect(proxy.address).await?;
+	stream.set_nodelay(true)?;
+	handshake(&mut stream, proxy.credentials.as_ref(), &target).await?;
+	Ok(tcp::tokio::TcpStream(stream))
+}
+
+/// Transport dialing through a SOCKS5 proxy and listening over TCP.
+pub struct Socks5Transport {
+	proxy: ProxyConfig,
+	listener: Boxed<tcp::tokio::TcpStream>,
+}
+
+impl Socks5Transport {
+	/// Create a transport dialing through `proxy`, listening with the given TCP configuration.
+	pub fn new(proxy: ProxyConfig, tcp_config: tcp::Config) -> Self {
+		let listener = libp2p::Transport::boxed(tcp::tokio::Transport::new(tcp_config));
+		Self { proxy, listener }
+	}
+}
+
+impl libp2p::Transport for Socks5Transport {
+	type Output = tcp::tokio::TcpStream;
+	type Error = io::Error;
+	type ListenerUpgrade = <Boxed<tcp::tokio::TcpStream> as libp2p::Transport>::ListenerUpgrade;
+	type Dial = BoxFuture<'static, io::Result<tcp::tokio::TcpStream>>;
+
+	fn listen_on(
+		&mut self,
+		id: ListenerId,
+		addr: Multiaddr,
+	) -> Result<(), TransportError<Self::Error>> {
+		self.listener.listen_on(id, addr)
+	}
+
+	fn remove_listener(&mut self, id: ListenerId) -> bool {
+		self.listener.remove_listener(id)
+	}
+
+	fn dial(
+		&mut self,
+		addr: Multiaddr,
+		opts: DialOpts,
+	) -> Result<Self::Dial, TransportError<Self::Error>> {
+		// Hole punching requires a direct connection.
+		if matches!(opts.role, Endpoint::Listener) {
+			return Err(TransportError::MultiaddrNotSupported(addr))
+		}
+		let Some(target) = Target::from_multiaddr(&addr) else {
+			return Err(TransportError::MultiaddrNotSupported(addr))
+		};
+		Ok(connect(self.proxy.clone(), target).boxed())
+	}
+
+	fn poll(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+		Pin::new(&mut self.get_mut().listener).poll(cx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::net::IpAddr;
+
+	#[test]
+	fn base32_works() {
+		assert_eq!(base32(b""), "");
+		assert_eq!(base32(b"f"), "my");
+		assert_eq!(base32(b"foobar"), "mzxw6ytboi");
+	}
+
+	#[test]
+	fn target_from_multiaddr() {
+		let target = |addr: &str| Target::from_multiaddr(&addr.parse().unwrap());
+
+		assert_eq!(
+			target("/ip4/1.2.3.4/tcp/30333"),
+			Some(Target::Ip(SocketAddr::new(IpAddr::from([1, 2, 3, 4]), 30333))),
+		);
+		assert_eq!(
+			target(
+				"/dns/example.com/tcp/443/p2p/12D3KooWRq4tRHuwPbMRH8dZyg5aR6L4wxmWW5MW3JVuBnyHifiB"
+			),
+			Some(Target::Domain("example.com".into(), 443)),
+		);
+		assert_eq!(
+			target("/onion3/vww6ybal4bd7szmgncyruucpgfkqahzddi37ktceo3ah7ngmcopnpyyd:1234"),
+			Some(Target::Domain(
+				"vww6ybal4bd7szmgncyruucpgfkqahzddi37ktceo3ah7ngmcopnpyyd.onion".into(),
+				1234,
+			)),
+		);
+		assert_eq!(target("/ip4/1.2.3.4/udp/30333"), None);
+		assert_eq!(target("/memory/5"), None);
+	}
+
+	/// Act as the proxy on `stream`, expecting a connect request to `request`.
+	async fn serve(mut stream: tokio::io::DuplexStream, request: Vec<u8>) {
+		let mut greeting = [0u8; 3];
+		stream.read_exact(&mut greeting).await.unwrap();
+		assert_eq!(greeting, [VERSION, 1, USERNAME_PASSWORD]);
+		stream.write_all(&[VERSION, USERNAME_PASSWORD]).await.unwrap();
+
+		let mut auth = [0u8; 10];
+		stream.read_exact(&mut auth).await.unwrap();
+		assert_eq!(&auth, b"\x01\x03bob\x04pass");
+		stream.write_all(&[USERNAME_PASSWORD_VERSION, 0]).await.unwrap();
+
+		let mut received = vec![0u8; request.len()];
+		stream.read_exact(&mut received).await.unwrap();
+		assert_eq!(received, request);
+		stream
+			.write_all(&[VERSION, 0, 0, ATYP_IPV4, 127, 0, 0, 1, 0x1f, 0x90])
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn handshake_connects_to_domain() {
+		let (mut client, server) = tokio::io::duplex(1024);
+		let target = Target::Domain("peer.onion".into(), 30333);
+		let mut request = vec![VERSION, CONNECT, 0, ATYP_DOMAIN, 10];
+		request.extend_from_slice(b"peer.onion");
+		request.extend_from_slice(&30333u16.to_be_bytes());
+
+		let credentials = ("bob".to_string(), "pass".to_string());
+		let server = tokio::spawn(serve(server, request));
+		handshake(&mut client, Some(&credentials), &target).await.unwrap();
+		server.await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn handshake_reports_failure() {
+		let (mut client, mut server) = tokio::io::duplex(1024);
+		let target = Target::Ip(SocketAddr::new(IpAddr::from([1, 2, 3, 4]), 30333));
+		let server = tokio::spawn(async move {
+			let mut buffer = [0u8; 3];
+			server.read_exact(&mut buffer).await.unwrap();
+			server.write_all(&[VERSION, NO_AUTHENTICATION]).await.unwrap();
+			let mut request = [0u8; 10];
+			server.read_exact(&mut request).await.unwrap();
+			assert_eq!(request, [VERSION, CONNECT, 0, ATYP_IPV4, 1, 2, 3, 4, 0x76, 0x7d]);
+			server
+				.write_all(&[VERSION, 0x05, 0, ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+				.await
+				.unwrap();
+		});
+
+		let err = handshake(&mut client, None, &target).await.unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+		server.await.unwrap();
+	}
+}
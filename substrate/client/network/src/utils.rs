@@ -58,6 +58,11 @@ impl<T: Hash + Eq> LruHashSet<T> {
 		}
 		false
 	}
+
+	/// Returns `true` if the set contains the element.
+	pub fn contains(&self, e: &T) -> bool {
+		self.set.contains(e)
+	}
 }
 
 #[cfg(test)]
@@ -29,7 +29,7 @@ use crate::{
 		syncing_service::{SyncingService, ToServiceCommand},
 	},
 	strategy::{SyncingAction, SyncingStrategy},
-	types::{BadPeer, ExtendedPeerInfo, SyncEvent},
+	types::{BadPeer, ExtendedPeerInfo, PeerSyncDetails, SyncEvent},
 	LOG_TARGET,
 };
 
@@ -77,6 +77,7 @@ use std::{
 		atomic::{AtomicBool, AtomicUsize, Ordering},
 		Arc,
 	},
+	time::Duration,
 };
 
 /// Interval at which we perform time based maintenance
@@ -175,6 +176,8 @@ pub struct Peer<B: BlockT> {
 	pub known_blocks: LruHashSet<B::Hash>,
 	/// Is the peer inbound.
 	inbound: bool,
+	/// Moving average of the response time of the peer.
+	average_response_time: Option<Duration>,
 }
 
 pub struct SyncingEngine<B: BlockT, Client> {
@@ -715,6 +718,25 @@ where
 					self.peers.iter().map(|(peer_id, peer)| (*peer_id, peer.info)).collect();
 				let _ = tx.send(peers_info);
 			},
+			ToServiceCommand::PeersSyncDetails(tx) => {
+				let details = self
+					.peers
+					.iter()
+					.map(|(peer_id, peer)| {
+						let (pending_requests, oldest_pending_request) =
+							self.pending_responses.pending_requests(peer_id);
+						let details = PeerSyncDetails {
+							best_hash: peer.info.best_hash,
+							best_number: peer.info.best_number,
+							pending_requests,
+							oldest_pending_request,
+							average_response_time: peer.average_response_time,
+						};
+						(*peer_id, details)
+					})
+					.collect();
+				let _ = tx.send(details);
+			},
 			ToServiceCommand::OnBlockFinalized(hash, header) =>
 				self.strategy.on_block_finalized(&hash, *header.number()),
 		}
@@ -956,6 +978,7 @@ where
 				NonZeroUsize::new(MAX_KNOWN_BLOCKS).expect("Constant is nonzero"),
 			),
 			inbound: direction.is_inbound(),
+			average_response_time: None,
 		};
 
 		// Only forward full peers to syncing strategy.
@@ -986,10 +1009,16 @@ where
 	}
 
 	fn process_response_event(&mut self, response_event: ResponseEvent) {
-		let ResponseEvent { peer_id, key, response: response_result } = response_event;
+		let ResponseEvent { peer_id, key, response: response_result, duration } = response_event;
 
 		match response_result {
 			Ok(Ok((response, protocol_name))) => {
+				if let Some(peer) = self.peers.get_mut(&peer_id) {
+					peer.average_response_time = Some(match peer.average_response_time {
+						Some(average) => (average * 3 + duration) / 4,
+						None => duration,
+					});
+				}
 				self.strategy.on_generic_response(&peer_id, key, protocol_name, response);
 			},
 			Ok(Err(e)) => {
@@ -27,7 +27,11 @@ use futures::{
 	FutureExt, StreamExt,
 };
 use log::error;
-use std::any::Any;
+use std::{
+	any::Any,
+	collections::HashMap,
+	time::{Duration, Instant},
+};
 
 use sc_network::{request_responses::RequestFailure, types::ProtocolName};
 use sc_network_types::PeerId;
@@ -46,19 +50,23 @@ pub(crate) struct ResponseEvent {
 	pub peer_id: PeerId,
 	pub key: StrategyKey,
 	pub response: ResponseResult,
+	/// Time elapsed since the request was started.
+	pub duration: Duration,
 }
 
 /// Stream taking care of polling pending responses.
 pub(crate) struct PendingResponses {
 	/// Pending responses
 	pending_responses: StreamMap<(PeerId, StrategyKey), BoxStream<'static, ResponseResult>>,
+	/// When the pending requests were started.
+	started: HashMap<(PeerId, StrategyKey), Instant>,
 	/// Waker to implement never terminating stream
 	waker: Option<Waker>,
 }
 
 impl PendingResponses {
 	pub fn new() -> Self {
-		Self { pending_responses: StreamMap::new(), waker: None }
+		Self { pending_responses: StreamMap::new(), started: HashMap::new(), waker: None }
 	}
 
 	pub fn insert(&mut self, peer_id: PeerId, key: StrategyKey, response_future: ResponseFuture) {
@@ -73,6 +81,7 @@ impl PendingResponses {
 			);
 			debug_assert!(false);
 		}
+		self.started.insert((peer_id, key), Instant::now());
 
 		if let Some(waker) = self.waker.take() {
 			waker.wake();
@@ -80,6 +89,7 @@ impl PendingResponses {
 	}
 
 	pub fn remove(&mut self, peer_id: PeerId, key: StrategyKey) -> bool {
+		self.started.remove(&(peer_id, key));
 		self.pending_responses.remove(&(peer_id, key)).is_some()
 	}
 
@@ -92,12 +102,25 @@ impl PendingResponses {
 			.collect::<Vec<_>>();
 		to_remove.iter().for_each(|k| {
 			self.pending_responses.remove(k);
+			self.started.remove(k);
 		});
 	}
 
 	pub fn len(&self) -> usize {
 		self.pending_responses.len()
 	}
+
+	/// The number of pending requests to `peer_id`, and the time elapsed since the oldest one
+	/// was started.
+	pub fn pending_requests(&self, peer_id: &PeerId) -> (usize, Option<Duration>) {
+		let started = self
+			.started
+			.iter()
+			.filter(|((peer, _key), _)| peer == peer_id)
+			.map(|(_, started)| *started)
+			.collect::<Vec<_>>();
+		(started.len(), started.iter().min().map(|oldest| oldest.elapsed()))
+	}
 }
 
 impl Stream for PendingResponses {
@@ -113,8 +136,12 @@ impl Stream for PendingResponses {
 				// it's going to yield `None`, so may not remove it before the next request is made
 				// to the same peer.
 				self.pending_responses.remove(&(peer_id, key));
+				let duration = self
+					.started
+					.remove(&(peer_id, key))
+					.map_or(Duration::ZERO, |started| started.elapsed());
 
-				Poll::Ready(Some(ResponseEvent { peer_id, key, response }))
+				Poll::Ready(Some(ResponseEvent { peer_id, key, response, duration }))
 			},
 			Poll::Ready(None) | Poll::Pending => {
 				self.waker = Some(cx.waker().clone());
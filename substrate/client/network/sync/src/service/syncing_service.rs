@@ -16,7 +16,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::types::{ExtendedPeerInfo, SyncEvent, SyncEventStream, SyncStatus, SyncStatusProvider};
+use crate::types::{
+	ExtendedPeerInfo, PeerSyncDetails, SyncEvent, SyncEventStream, SyncStatus, SyncStatusProvider,
+};
 
 use futures::{channel::oneshot, Stream};
 use sc_network_types::PeerId;
@@ -53,6 +55,7 @@ pub enum ToServiceCommand<B: BlockT> {
 	NumDownloadedBlocks(oneshot::Sender<usize>),
 	NumSyncRequests(oneshot::Sender<usize>),
 	PeersInfo(oneshot::Sender<Vec<(PeerId, ExtendedPeerInfo<B>)>>),
+	PeersSyncDetails(oneshot::Sender<Vec<(PeerId, PeerSyncDetails<B>)>>),
 	OnBlockFinalized(B::Hash, B::Header),
 	// Status {
 	// 	pending_response: oneshot::Sender<SyncStatus<B>>,
@@ -118,6 +121,16 @@ impl<B: BlockT> SyncingService<B> {
 		rx.await
 	}
 
+	/// Get the details about the syncing with each peer.
+	pub async fn peers_sync_details(
+		&self,
+	) -> Result<Vec<(PeerId, PeerSyncDetails<B>)>, oneshot::Canceled> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.tx.unbounded_send(ToServiceCommand::PeersSyncDetails(tx));
+
+		rx.await
+	}
+
 	/// Notify the `SyncingEngine` that a block has been finalized.
 	pub fn on_block_finalized(&self, hash: B::Hash, header: B::Header) {
 		let _ = self.tx.unbounded_send(ToServiceCommand::OnBlockFinalized(hash, header));
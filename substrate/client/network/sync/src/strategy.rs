@@ -23,6 +23,7 @@
 //! possible too.
 
 pub mod chain_sync;
+pub mod checkpoint;
 mod disconnected_peers;
 pub mod polkadot;
 pub mod state;
@@ -72,6 +72,8 @@ use std::{
 	sync::Arc,
 };
 
+#[cfg(test)]
+mod simulation;
 #[cfg(test)]
 mod test;
 
@@ -770,6 +772,11 @@ where
 					// Don't mark it as bad as it still may be synced if explicitly requested.
 					trace!(target: LOG_TARGET, "Obsolete block {hash:?}");
 				},
+				Err(BlockImportError::Vetoed(_)) => {
+					// The block was rejected by a local policy, not because the peer misbehaved.
+					// Don't punish the peer, nor restart the sync which would download it again.
+					debug!(target: LOG_TARGET, "Import of block {hash:?} vetoed");
+				},
 				e @ Err(BlockImportError::UnknownParent) | e @ Err(BlockImportError::Other(_)) => {
 					warn!(target: LOG_TARGET, "💔 Error importing block {hash:?}: {}", e.unwrap_err());
 					self.state_sync = None;
@@ -844,6 +851,11 @@ where
 		let warp_sync_progress = self.gap_sync.as_ref().map(|gap_sync| WarpSyncProgress {
 			phase: WarpSyncPhase::DownloadingBlocks(gap_sync.best_queued_number),
 			total_bytes: 0,
+			percentage: (!gap_sync.target.is_zero()).then(|| {
+				let percentage = gap_sync.best_queued_number.saturated_into::<u64>() * 100 /
+					gap_sync.target.saturated_into::<u64>();
+				percentage.min(100) as u32
+			}),
 		});
 
 		SyncStatus {
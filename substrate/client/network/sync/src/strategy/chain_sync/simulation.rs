@@ -0,0 +1,649 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic network simulation for [`ChainSync`].
+//!
+//! The [`Simulation`] drives a [`ChainSync`] instance against a set of scripted peers on a
+//! virtual clock. Every request sent by the strategy is answered by the [`Behavior`] of the peer,
+//! and responses, request timeouts and block announcements are delivered in the order of their
+//! scheduled virtual time. Blocks queued for import are imported into a local test client right
+//! away, so a scenario runs to completion without any real networking or wall clock time, and
+//! edge cases seen on live networks can be reproduced as regular tests.
+
+use super::*;
+use crate::service::network::NetworkServiceProvider;
+use futures::{channel::oneshot::Canceled, executor::block_on};
+use sc_block_builder::BlockBuilderBuilder;
+use sc_network::RequestFailure;
+use sc_network_common::sync::message::BlockState;
+use std::{collections::BTreeMap, sync::Mutex, time::Duration};
+use substrate_test_runtime_client::{
+	runtime::{Block, Hash, Header},
+	BlockBuilderExt, ClientBlockImportExt, DefaultTestClientBuilderExt, TestClient,
+	TestClientBuilder, TestClientBuilderExt,
+};
+
+/// Timeout of a block request, as configured for the block request protocol.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Latency of the responses of well behaving peers in the scenarios below.
+const LATENCY: Duration = Duration::from_millis(100);
+
+/// Block downloader recording the requests instead of sending them.
+///
+/// The responses are delivered by the [`Simulation`] through
+/// [`SyncingStrategy::on_generic_response`].
+#[derive(Debug, Default)]
+struct RecordingDownloader {
+	requests: Mutex<Vec<(PeerId, BlockRequest<Block>)>>,
+}
+
+impl RecordingDownloader {
+	const PROTOCOL_NAME: ProtocolName = ProtocolName::Static("/simulation/block-request");
+
+	fn take(&self, peer_id: &PeerId) -> Option<BlockRequest<Block>> {
+		let mut requests = self.requests.lock().unwrap();
+		let index = requests.iter().position(|(who, _)| who == peer_id)?;
+		Some(requests.remove(index).1)
+	}
+}
+
+#[async_trait::async_trait]
+impl BlockDownloader<Block> for RecordingDownloader {
+	fn protocol_name(&self) -> &ProtocolName {
+		&Self::PROTOCOL_NAME
+	}
+
+	async fn download_blocks(
+		&self,
+		who: PeerId,
+		request: BlockRequest<Block>,
+	) -> Result<Result<(Vec<u8>, ProtocolName), RequestFailure>, Canceled> {
+		self.requests.lock().unwrap().push((who, request));
+		Ok(Ok((Vec::new(), Self::PROTOCOL_NAME)))
+	}
+
+	fn block_response_into_blocks(
+		&self,
+		_request: &BlockRequest<Block>,
+		_response: Vec<u8>,
+	) -> Result<Vec<BlockData<Block>>, BlockResponseError> {
+		Ok(Vec::new())
+	}
+}
+
+/// The blocks known to a simulated peer.
+#[derive(Clone)]
+pub(super) struct ChainView {
+	blocks: HashMap<Hash, Block>,
+	best: Hash,
+}
+
+impl ChainView {
+	/// A chain made of `blocks`, the last one being the best block.
+	pub(super) fn new(blocks: impl IntoIterator<Item = Block>) -> Self {
+		let mut view = Self { blocks: HashMap::new(), best: Default::default() };
+		for block in blocks {
+			view.import(block);
+		}
+		view
+	}
+
+	/// Import `block` and make it the best block.
+	pub(super) fn import(&mut self, block: Block) {
+		self.best = block.hash();
+		self.blocks.insert(block.hash(), block);
+	}
+
+	/// Header of the best block.
+	pub(super) fn best(&self) -> &Header {
+		&self.blocks[&self.best].header
+	}
+
+	/// Hash of the block with the given number on the best chain.
+	fn canonical_hash(&self, number: u64) -> Option<Hash> {
+		let mut block = self.blocks.get(&self.best)?;
+		while *block.header.number() > number {
+			block = self.blocks.get(block.header.parent_hash())?;
+		}
+		(*block.header.number() == number).then(|| block.hash())
+	}
+
+	/// Answer `request` the way the block request handler of an honest node does.
+	pub(super) fn serve(&self, request: &BlockRequest<Block>) -> Vec<BlockData<Block>> {
+		let start = match request.from {
+			FromBlock::Hash(hash) => Some(hash),
+			FromBlock::Number(number) => self.canonical_hash(number),
+		};
+		let max = request.max.map_or(usize::MAX, |max| max as usize);
+
+		let mut blocks = Vec::new();
+		let mut next = start.and_then(|hash| self.blocks.get(&hash));
+		while let Some(block) = next {
+			if blocks.len() == max {
+				break
+			}
+			blocks.push(BlockData::<Block> {
+				hash: block.hash(),
+				header: request
+					.fields
+					.contains(BlockAttributes::HEADER)
+					.then(|| block.header.clone()),
+				body: request
+					.fields
+					.contains(BlockAttributes::BODY)
+					.then(|| block.extrinsics.clone()),
+				indexed_body: None,
+				receipt: None,
+				message_queue: None,
+				justification: None,
+				justifications: None,
+			});
+			next = match request.direction {
+				Direction::Ascending => self
+					.canonical_hash(block.header.number() + 1)
+					.and_then(|hash| self.blocks.get(&hash)),
+				Direction::Descending => self.blocks.get(block.header.parent_hash()),
+			};
+		}
+		blocks
+	}
+}
+
+/// Reply of a simulated peer to a block request.
+pub(super) enum Reply {
+	/// Respond with the given blocks after the given delay.
+	After(Duration, Vec<BlockData<Block>>),
+	/// Never respond, letting the request time out.
+	Stall,
+}
+
+/// Scripted behavior of a simulated peer.
+pub(super) trait Behavior {
+	/// Reply to a block request, given the chain known to the peer.
+	fn on_block_request(&mut self, chain: &ChainView, request: &BlockRequest<Block>) -> Reply;
+}
+
+/// Serves its chain truthfully.
+pub(super) struct Honest {
+	pub latency: Duration,
+}
+
+impl Behavior for Honest {
+	fn on_block_request(&mut self, chain: &ChainView, request: &BlockRequest<Block>) -> Reply {
+		Reply::After(self.latency, chain.serve(request))
+	}
+}
+
+/// Claims a different block at every height it is asked about during an ancestry search, as a
+/// peer on another chain (or a malicious one) does.
+pub(super) struct LyingAncestry {
+	pub latency: Duration,
+}
+
+impl Behavior for LyingAncestry {
+	fn on_block_request(&mut self, chain: &ChainView, request: &BlockRequest<Block>) -> Reply {
+		let mut blocks = chain.serve(request);
+		if matches!(request.from, FromBlock::Number(_)) && request.max == Some(1) {
+			for block in &mut blocks {
+				block.hash = Hash::repeat_byte(0x42);
+			}
+		}
+		Reply::After(self.latency, blocks)
+	}
+}
+
+/// Serves the first `answered` requests truthfully and never answers afterwards.
+pub(super) struct Stalling {
+	pub latency: Duration,
+	pub answered: usize,
+}
+
+impl Behavior for Stalling {
+	fn on_block_request(&mut self, chain: &ChainView, request: &BlockRequest<Block>) -> Reply {
+		if self.answered == 0 {
+			return Reply::Stall
+		}
+		self.answered -= 1;
+		Reply::After(self.latency, chain.serve(request))
+	}
+}
+
+/// Notable events of a simulation, in the order they happened.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Trace {
+	/// A block request was sent to the peer.
+	Requested(PeerId, FromBlock<Hash, u64>),
+	/// A request to the peer timed out and the peer was disconnected.
+	TimedOut(PeerId),
+	/// The peer was disconnected for misbehavior, with the given reason.
+	Dropped(PeerId, &'static str),
+	/// A block was imported by the local node.
+	Imported(Hash),
+}
+
+/// Scheduled event of a simulation.
+enum Event {
+	/// Response of a peer to the request `request_id`.
+	Response {
+		peer: usize,
+		request_id: u64,
+		request: BlockRequest<Block>,
+		blocks: Vec<BlockData<Block>>,
+	},
+	/// Timeout of the request `request_id` to a peer.
+	Timeout { peer: usize, request_id: u64 },
+	/// Block announcement of a peer.
+	Announce { peer: usize, block: Block },
+}
+
+/// A simulated peer.
+struct Peer {
+	id: PeerId,
+	chain: ChainView,
+	behavior: Box<dyn Behavior>,
+	connected: bool,
+	/// The block request in flight, if any. A new request supersedes the pending one.
+	in_flight: Option<u64>,
+}
+
+/// [`ChainSync`] connected to scripted peers on a virtual clock.
+pub(super) struct Simulation {
+	now: Duration,
+	client: Arc<TestClient>,
+	sync: ChainSync<Block, TestClient>,
+	downloader: Arc<RecordingDownloader>,
+	network: NetworkServiceProvider,
+	peers: Vec<Peer>,
+	/// Events ordered by their virtual time, ties broken by the order of scheduling.
+	events: BTreeMap<(Duration, u64), Event>,
+	next_event_id: u64,
+	next_request_id: u64,
+	trace: Vec<(Duration, Trace)>,
+}
+
+impl Simulation {
+	/// Start syncing the chain of `client`, without any peers.
+	pub(super) fn new(client: Arc<TestClient>) -> Self {
+		let downloader = Arc::new(RecordingDownloader::default());
+		let sync = ChainSync::new(
+			ChainSyncMode::Full,
+			client.clone(),
+			1,
+			64,
+			ProtocolName::Static(""),
+			downloader.clone(),
+			None,
+			std::iter::empty(),
+		)
+		.unwrap();
+
+		Self {
+			now: Duration::ZERO,
+			client,
+			sync,
+			downloader,
+			network: NetworkServiceProvider::new(),
+			peers: Vec::new(),
+			events: BTreeMap::new(),
+			next_event_id: 0,
+			next_request_id: 0,
+			trace: Vec::new(),
+		}
+	}
+
+	/// Connect a peer knowing `chain` and acting according to `behavior`.
+	pub(super) fn add_peer(
+		&mut self,
+		chain: ChainView,
+		behavior: impl Behavior + 'static,
+	) -> PeerId {
+		let id = PeerId::random();
+		let best = chain.best().clone();
+		self.peers.push(Peer {
+			id,
+			chain,
+			behavior: Box::new(behavior),
+			connected: true,
+			in_flight: None,
+		});
+		self.sync.add_peer(id, best.hash(), *best.number());
+		id
+	}
+
+	/// Let `peer` import `block` and announce it as its new best block at `at`.
+	pub(super) fn announce_at(&mut self, at: Duration, peer: &PeerId, block: Block) {
+		let peer = self.peer_index(peer);
+		self.schedule(at, Event::Announce { peer, block });
+	}
+
+	/// Run the simulation until the virtual clock reaches `deadline`.
+	pub(super) fn run_until(&mut self, deadline: Duration) {
+		loop {
+			self.process_actions();
+			match self.events.first_key_value() {
+				Some(((at, _), _)) if *at <= deadline => {},
+				_ => break,
+			}
+			let ((at, _), event) = self.events.pop_first().expect("checked above; qed");
+			self.now = at;
+			self.handle_event(event);
+		}
+		self.now = self.now.max(deadline);
+	}
+
+	/// The current virtual time.
+	pub(super) fn now(&self) -> Duration {
+		self.now
+	}
+
+	/// The events of the simulation so far, with the virtual time they happened at.
+	pub(super) fn trace(&self) -> &[(Duration, Trace)] {
+		&self.trace
+	}
+
+	/// The local client.
+	pub(super) fn client(&self) -> &TestClient {
+		&self.client
+	}
+
+	/// Whether `peer` is still connected.
+	pub(super) fn is_connected(&self, peer: &PeerId) -> bool {
+		self.peers[self.peer_index(peer)].connected
+	}
+
+	fn peer_index(&self, peer: &PeerId) -> usize {
+		self.peers.iter().position(|p| p.id == *peer).expect("unknown simulated peer")
+	}
+
+	fn schedule(&mut self, at: Duration, event: Event) {
+		self.events.insert((at, self.next_event_id), event);
+		self.next_event_id += 1;
+	}
+
+	fn record(&mut self, trace: Trace) {
+		self.trace.push((self.now, trace));
+	}
+
+	fn disconnect(&mut self, peer: usize) {
+		let peer = &mut self.peers[peer];
+		peer.connected = false;
+		peer.in_flight = None;
+		self.sync.remove_peer(&peer.id);
+	}
+
+	fn handle_event(&mut self, event: Event) {
+		match event {
+			Event::Response { peer, request_id, request, blocks } => {
+				let peer = &mut self.peers[peer];
+				if !peer.connected || peer.in_flight != Some(request_id) {
+					return
+				}
+				peer.in_flight = None;
+				self.sync.on_generic_response(
+					&peer.id,
+					ChainSync::<Block, TestClient>::STRATEGY_KEY,
+					RecordingDownloader::PROTOCOL_NAME,
+					Box::new((request, Ok::<_, BlockResponseError>(blocks))),
+				);
+			},
+			Event::Timeout { peer, request_id } => {
+				if !self.peers[peer].connected || self.peers[peer].in_flight != Some(request_id) {
+					return
+				}
+				self.record(Trace::TimedOut(self.peers[peer].id));
+				self.disconnect(peer);
+			},
+			Event::Announce { peer, block } => {
+				let peer = &mut self.peers[peer];
+				peer.chain.import(block.clone());
+				if !peer.connected {
+					return
+				}
+				let announce = BlockAnnounce {
+					header: block.header,
+					state: Some(BlockState::Best),
+					data: Some(Vec::new()),
+				};
+				let _ = self.sync.on_validated_block_announce(true, peer.id, &announce);
+			},
+		}
+	}
+
+	fn process_actions(&mut self) {
+		loop {
+			let actions = self.sync.actions(&self.network.handle()).unwrap();
+			if actions.is_empty() {
+				break
+			}
+			for action in actions {
+				self.handle_action(action);
+			}
+		}
+	}
+
+	fn handle_action(&mut self, action: SyncingAction<Block>) {
+		match action {
+			SyncingAction::StartRequest { peer_id, request, .. } => {
+				// The request future hands the request over to the downloader.
+				let _ = block_on(request);
+				let request = self
+					.downloader
+					.take(&peer_id)
+					.expect("`ChainSync` only sends block requests; qed");
+				self.record(Trace::Requested(peer_id, request.from.clone()));
+
+				let index = self.peer_index(&peer_id);
+				let peer = &mut self.peers[index];
+				let reply = peer.behavior.on_block_request(&peer.chain, &request);
+				let request_id = self.next_request_id;
+				self.next_request_id += 1;
+				self.peers[index].in_flight = Some(request_id);
+				self.schedule(
+					self.now + REQUEST_TIMEOUT,
+					Event::Timeout { peer: index, request_id },
+				);
+				if let Reply::After(delay, blocks) = reply {
+					if delay < REQUEST_TIMEOUT {
+						self.schedule(
+							self.now + delay,
+							Event::Response { peer: index, request_id, request, blocks },
+						);
+					}
+				}
+			},
+			SyncingAction::CancelRequest { peer_id, .. } => {
+				let index = self.peer_index(&peer_id);
+				self.peers[index].in_flight = None;
+			},
+			SyncingAction::DropPeer(BadPeer(peer_id, reputation)) => {
+				let index = self.peer_index(&peer_id);
+				if self.peers[index].connected {
+					self.record(Trace::Dropped(peer_id, reputation.reason));
+					self.disconnect(index);
+				}
+			},
+			SyncingAction::ImportBlocks { origin, blocks } => self.import_blocks(origin, blocks),
+			SyncingAction::ImportJustifications { .. } | SyncingAction::Finished => {},
+		}
+	}
+
+	/// Import `blocks` the way the import queue does, stopping at the first failure.
+	fn import_blocks(&mut self, origin: BlockOrigin, blocks: Vec<IncomingBlock<Block>>) {
+		let count = blocks.len();
+		let mut imported = 0;
+		let mut results = Vec::with_capacity(count);
+		let mut has_error = false;
+		for block in blocks {
+			let hash = block.hash;
+			if has_error {
+				results.push((Err(BlockImportError::Cancelled), hash));
+				continue
+			}
+			let result = match block.header {
+				None => Err(BlockImportError::IncompleteHeader(block.origin)),
+				Some(header) => {
+					let number = *header.number();
+					if self.client.header(hash).unwrap().is_some() {
+						Ok(BlockImportStatus::ImportedKnown(number, block.origin))
+					} else {
+						let body = block.body.unwrap_or_default();
+						match block_on(self.client.import(origin, Block::new(header, body))) {
+							Ok(()) if self.client.header(hash).unwrap().is_some() => {
+								imported += 1;
+								self.record(Trace::Imported(hash));
+								Ok(BlockImportStatus::ImportedUnknown(
+									number,
+									Default::default(),
+									block.origin,
+								))
+							},
+							Ok(()) => Err(BlockImportError::UnknownParent),
+							Err(e) => Err(BlockImportError::VerificationFailed(
+								block.origin,
+								e.to_string(),
+							)),
+						}
+					}
+				},
+			};
+			has_error = result.is_err();
+			results.push((result, hash));
+		}
+
+		self.sync.on_blocks_processed(imported, count, results);
+		let info = self.client.info();
+		self.sync.update_chain_info(&info.best_hash, info.best_number);
+	}
+}
+
+/// Build a chain of `len` blocks on top of `parent` with `world`, which keeps all blocks of a
+/// scenario. Blocks of a `fork` differ from the blocks built on the same parent otherwise.
+fn build_chain(world: &TestClient, parent: Hash, len: usize, fork: bool) -> Vec<Block> {
+	let mut parent = parent;
+	(0..len)
+		.map(|_| {
+			let mut builder = BlockBuilderBuilder::new(world)
+				.on_parent_block(parent)
+				.fetch_parent_block_number(world)
+				.unwrap()
+				.build()
+				.unwrap();
+			if fork {
+				builder.push_storage_change(vec![1, 2, 3], Some(vec![4, 5, 6])).unwrap();
+			}
+			let block = builder.build().unwrap().block;
+			block_on(world.import(BlockOrigin::Own, block.clone())).unwrap();
+			parent = block.hash();
+			block
+		})
+		.collect()
+}
+
+/// The genesis block of `client`.
+fn genesis(client: &TestClient) -> Block {
+	client.block(client.info().genesis_hash).unwrap().unwrap().block
+}
+
+/// A local client that already imported `blocks`.
+fn local_client(blocks: &[Block]) -> Arc<TestClient> {
+	let client = Arc::new(TestClientBuilder::new().build());
+	for block in blocks {
+		block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+	}
+	client
+}
+
+#[test]
+fn syncs_from_honest_peer() {
+	let world = TestClientBuilder::new().build();
+	let blocks = build_chain(&world, world.info().genesis_hash, 100, false);
+
+	let mut sim = Simulation::new(local_client(&[]));
+	let chain = ChainView::new(std::iter::once(genesis(&world)).chain(blocks.iter().cloned()));
+	let peer = sim.add_peer(chain, Honest { latency: LATENCY });
+	sim.run_until(Duration::from_secs(1));
+
+	assert_eq!(sim.client().info().best_hash, blocks[99].hash());
+	assert!(sim.is_connected(&peer));
+	assert!(sim.trace().iter().all(|(_, trace)| !matches!(trace, Trace::Dropped(..))));
+}
+
+#[test]
+fn drops_peer_lying_about_ancestry() {
+	let world = TestClientBuilder::new().build();
+	let blocks = build_chain(&world, world.info().genesis_hash, 40, false);
+	let chain = ChainView::new(std::iter::once(genesis(&world)).chain(blocks.iter().cloned()));
+
+	let mut sim = Simulation::new(local_client(&blocks[..10]));
+	let liar = sim.add_peer(chain.clone(), LyingAncestry { latency: LATENCY });
+	let honest = sim.add_peer(chain, Honest { latency: LATENCY });
+	sim.run_until(Duration::from_secs(5));
+
+	// The liar backs off to genesis, where the mismatch is fatal.
+	assert!(sim
+		.trace()
+		.iter()
+		.any(|(_, trace)| *trace == Trace::Dropped(liar, rep::GENESIS_MISMATCH.reason)));
+	assert!(sim.is_connected(&honest));
+	assert_eq!(sim.client().info().best_hash, blocks[39].hash());
+}
+
+#[test]
+fn stalling_peer_times_out_and_sync_completes() {
+	let world = TestClientBuilder::new().build();
+	let blocks = build_chain(&world, world.info().genesis_hash, 100, false);
+	let chain = ChainView::new(std::iter::once(genesis(&world)).chain(blocks.iter().cloned()));
+
+	let mut sim = Simulation::new(local_client(&[]));
+	let staller = sim.add_peer(chain.clone(), Stalling { latency: LATENCY, answered: 0 });
+	let honest = sim.add_peer(chain, Honest { latency: LATENCY });
+
+	// The range requested from the staller holds back the import until the request times out.
+	sim.run_until(REQUEST_TIMEOUT - Duration::from_secs(1));
+	assert!(sim.client().info().best_number < 100);
+	assert!(sim.is_connected(&staller));
+
+	sim.run_until(REQUEST_TIMEOUT + Duration::from_secs(1));
+	assert!(sim.trace().contains(&(REQUEST_TIMEOUT, Trace::TimedOut(staller))));
+	assert!(!sim.is_connected(&staller));
+	assert!(sim.is_connected(&honest));
+	assert_eq!(sim.client().info().best_hash, blocks[99].hash());
+}
+
+#[test]
+fn imports_both_blocks_of_equivocating_announcer() {
+	let world = TestClientBuilder::new().build();
+	let blocks = build_chain(&world, world.info().genesis_hash, 5, false);
+	let a = build_chain(&world, blocks[4].hash(), 1, false).remove(0);
+	let b = build_chain(&world, blocks[4].hash(), 1, true).remove(0);
+	assert_eq!(a.header.number(), b.header.number());
+
+	let mut sim = Simulation::new(local_client(&blocks));
+	let chain = ChainView::new(std::iter::once(genesis(&world)).chain(blocks.iter().cloned()));
+	let peer = sim.add_peer(chain, Honest { latency: LATENCY });
+	sim.announce_at(Duration::from_secs(1), &peer, a.clone());
+	sim.announce_at(Duration::from_secs(2), &peer, b.clone());
+	sim.run_until(Duration::from_secs(5));
+
+	// Announcing two blocks at the same height is not misbehavior, both must be imported.
+	assert!(sim.client().header(a.hash()).unwrap().is_some());
+	assert!(sim.client().header(b.hash()).unwrap().is_some());
+	assert!(sim.is_connected(&peer));
+	assert_eq!(sim.now(), Duration::from_secs(5));
+}
@@ -0,0 +1,384 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Selection of the warp sync target by agreement of multiple checkpoint providers.
+//!
+//! Instead of trusting the warp proofs served by the peers, a node can require at least `K` of
+//! `N` configured providers to attest a finalized block before downloading its state. The
+//! providers are RPC endpoints of trusted nodes, available with the `rpc-checkpoints` feature, or
+//! checkpoint files signed by trusted publishers for a given chain.
+
+use crate::LOG_TARGET;
+use codec::Encode;
+use futures::future::join_all;
+use log::{debug, info, warn};
+use sc_network::config::WarpCheckpointConfig;
+use serde::{Deserialize, Serialize};
+use sp_core::{sr25519, Pair};
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
+use std::{collections::HashSet, path::Path};
+
+#[cfg(feature = "rpc-checkpoints")]
+pub use rpc::RpcCheckpointProvider;
+
+/// Provider of checkpoints, finalized blocks attested by a source trusted by the node operator.
+#[async_trait::async_trait]
+pub trait CheckpointProvider<B: BlockT>: Send + Sync {
+	/// Name of the provider, for logs.
+	fn name(&self) -> String;
+
+	/// The latest checkpoint of the provider.
+	async fn checkpoint(&self) -> Result<B::Header, String>;
+
+	/// The hash of the finalized block `number`, or `None` if the provider can't attest it.
+	async fn finalized_hash(&self, number: NumberFor<B>) -> Result<Option<B::Hash>, String>;
+}
+
+#[cfg(feature = "rpc-checkpoints")]
+mod rpc {
+	use super::CheckpointProvider;
+	use serde::de::DeserializeOwned;
+	use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
+	use std::time::Duration;
+
+	/// Timeout of the requests to the RPC endpoints.
+	const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+	/// Provider querying the JSON-RPC endpoint of a trusted node over HTTP.
+	pub struct RpcCheckpointProvider {
+		url: String,
+		client: reqwest::Client,
+	}
+
+	impl RpcCheckpointProvider {
+		/// Create a provider querying the RPC endpoint at `url`.
+		pub fn new(url: impl Into<String>) -> Self {
+			Self { url: url.into(), client: reqwest::Client::new() }
+		}
+
+		/// Call `method` with `params`.
+		async fn call<R: DeserializeOwned>(
+			&self,
+			method: &'static str,
+			params: serde_json::Value,
+		) -> Result<R, String> {
+			let request = serde_json::json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"method": method,
+				"params": params,
+			});
+			let response = async {
+				self.client
+					.post(&self.url)
+					.timeout(REQUEST_TIMEOUT)
+					.json(&request)
+					.send()
+					.await?
+					.error_for_status()?
+					.json::<serde_json::Value>()
+					.await
+			}
+			.await
+			.map_err(|e| format!("`{method}` request to {} failed: {e}", self.url))?;
+
+			if let Some(error) = response.get("error") {
+				return Err(format!("`{method}` request to {} failed: {error}", self.url))
+			}
+			serde_json::from_value(response.get("result").cloned().unwrap_or_default())
+				.map_err(|e| format!("Invalid `{method}` response from {}: {e}", self.url))
+		}
+
+		async fn header<B: BlockT>(&self, hash: B::Hash) -> Result<B::Header, String> {
+			self.call::<Option<B::Header>>("chain_getHeader", serde_json::json!([hash]))
+				.await?
+				.ok_or_else(|| format!("Unknown block {hash} at {}", self.url))
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl<B: BlockT> CheckpointProvider<B> for RpcCheckpointProvider {
+		fn name(&self) -> String {
+			self.url.clone()
+		}
+
+		async fn checkpoint(&self) -> Result<B::Header, String> {
+			let hash: B::Hash = self.call("chain_getFinalizedHead", serde_json::json!([])).await?;
+			self.header::<B>(hash).await
+		}
+
+		async fn finalized_hash(&self, number: NumberFor<B>) -> Result<Option<B::Hash>, String> {
+			let finalized = CheckpointProvider::<B>::checkpoint(self).await?;
+			if *finalized.number() < number {
+				return Ok(None)
+			}
+			self.call("chain_getBlockHash", serde_json::json!([number])).await
+		}
+	}
+}
+
+/// A checkpoint signed by its publisher.
+///
+/// The publisher signs the SCALE-encoded genesis hash of the chain and hash of the header with
+/// its sr25519 key, so that the checkpoint can't be replayed on another chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "")]
+pub struct SignedCheckpoint<B: BlockT> {
+	/// The genesis hash of the chain of the checkpoint.
+	pub genesis_hash: B::Hash,
+	/// The header of the finalized block.
+	pub header: B::Header,
+	/// The publisher of the checkpoint.
+	pub publisher: sr25519::Public,
+	/// The signature of the genesis hash and hash of the header by the publisher.
+	pub signature: sr25519::Signature,
+}
+
+impl<B: BlockT> SignedCheckpoint<B> {
+	/// Create the checkpoint of `header` on the chain of `genesis_hash`, signed by `publisher`.
+	pub fn new(genesis_hash: B::Hash, header: B::Header, publisher: &sr25519::Pair) -> Self {
+		let signature = publisher.sign(&Self::payload(&genesis_hash, &header));
+		Self { genesis_hash, header, publisher: publisher.public(), signature }
+	}
+
+	/// Read a checkpoint from the JSON file at `path`, and check that it is a checkpoint of the
+	/// chain of `genesis_hash` signed by one of the `trusted_publishers`.
+	pub fn from_file(
+		path: &Path,
+		genesis_hash: B::Hash,
+		trusted_publishers: &[sr25519::Public],
+	) -> Result<Self, String> {
+		let json = std::fs::read(path)
+			.map_err(|e| format!("Failed to read checkpoint {}: {e}", path.display()))?;
+		let checkpoint: Self = serde_json::from_slice(&json)
+			.map_err(|e| format!("Invalid checkpoint {}: {e}", path.display()))?;
+		checkpoint
+			.verify(genesis_hash, trusted_publishers)
+			.map_err(|e| format!("Checkpoint {}: {e}", path.display()))?;
+		Ok(checkpoint)
+	}
+
+	/// Check that the checkpoint is a checkpoint of the chain of `genesis_hash` signed by one of
+	/// the `trusted_publishers`.
+	pub fn verify(
+		&self,
+		genesis_hash: B::Hash,
+		trusted_publishers: &[sr25519::Public],
+	) -> Result<(), String> {
+		if self.genesis_hash != genesis_hash {
+			return Err(format!("Checkpoint of another chain, with genesis {}", self.genesis_hash))
+		}
+		if !trusted_publishers.contains(&self.publisher) {
+			return Err(format!("Untrusted publisher {}", self.publisher))
+		}
+		let payload = Self::payload(&self.genesis_hash, &self.header);
+		if !sr25519::Pair::verify(&self.signature, &payload, &self.publisher) {
+			return Err(format!("Invalid signature by {}", self.publisher))
+		}
+		Ok(())
+	}
+
+	/// The payload signed by the publisher.
+	fn payload(genesis_hash: &B::Hash, header: &B::Header) -> Vec<u8> {
+		(genesis_hash, header.hash()).encode()
+	}
+}
+
+#[async_trait::async_trait]
+impl<B: BlockT> CheckpointProvider<B> for SignedCheckpoint<B> {
+	fn name(&self) -> String {
+		format!("checkpoint #{} signed by {}", self.header.number(), self.publisher)
+	}
+
+	async fn checkpoint(&self) -> Result<B::Header, String> {
+		Ok(self.header.clone())
+	}
+
+	async fn finalized_hash(&self, number: NumberFor<B>) -> Result<Option<B::Hash>, String> {
+		Ok((*self.header.number() == number).then(|| self.header.hash()))
+	}
+}
+
+/// Create the providers of `config` for the chain of `genesis_hash`: RPC endpoints for
+/// `http(s)://` URLs, which require the `rpc-checkpoints` feature, signed checkpoint files
+/// otherwise.
+pub fn providers_from_config<B: BlockT>(
+	config: &WarpCheckpointConfig,
+	genesis_hash: B::Hash,
+) -> Result<Vec<Box<dyn CheckpointProvider<B>>>, String> {
+	config
+		.providers
+		.iter()
+		.map(|provider| -> Result<Box<dyn CheckpointProvider<B>>, String> {
+			if provider.starts_with("http://") || provider.starts_with("https://") {
+				#[cfg(feature = "rpc-checkpoints")]
+				return Ok(Box::new(RpcCheckpointProvider::new(provider.clone())));
+				#[cfg(not(feature = "rpc-checkpoints"))]
+				return Err(format!(
+					"Checkpoint provider {provider} requires the `rpc-checkpoints` feature"
+				));
+			}
+			let path = Path::new(provider);
+			let checkpoint =
+				SignedCheckpoint::<B>::from_file(path, genesis_hash, &config.trusted_publishers)?;
+			Ok(Box::new(checkpoint))
+		})
+		.collect()
+}
+
+/// Select the warp sync target: the highest checkpoint of the `providers` attested by at least
+/// `threshold` of them.
+///
+/// A provider attests a checkpoint if it reports the same hash for the finalized block at its
+/// height. Unreachable providers attest nothing. The providers are queried concurrently.
+pub async fn select_warp_target<B: BlockT>(
+	providers: &[Box<dyn CheckpointProvider<B>>],
+	threshold: usize,
+) -> Result<B::Header, String> {
+	if threshold == 0 || threshold > providers.len() {
+		return Err(format!(
+			"Invalid checkpoint threshold {threshold} for {} providers",
+			providers.len()
+		))
+	}
+
+	let checkpoints = join_all(providers.iter().map(|provider| provider.checkpoint())).await;
+	let mut candidates = providers
+		.iter()
+		.zip(checkpoints)
+		.filter_map(|(provider, checkpoint)| match checkpoint {
+			Ok(checkpoint) => Some(checkpoint),
+			Err(e) => {
+				warn!(target: LOG_TARGET, "No checkpoint from {}: {e}", provider.name());
+				None
+			},
+		})
+		.collect::<Vec<_>>();
+	candidates.sort_by_key(|header| std::cmp::Reverse(*header.number()));
+
+	let mut checked = HashSet::new();
+	for candidate in candidates {
+		let hash = candidate.hash();
+		if !checked.insert(hash) {
+			continue
+		}
+		let number = *candidate.number();
+		let attested =
+			join_all(providers.iter().map(|provider| provider.finalized_hash(number))).await;
+		let attestations = providers
+			.iter()
+			.zip(attested)
+			.filter(|(provider, attested)| match attested {
+				Ok(attested) => *attested == Some(hash),
+				Err(e) => {
+					debug!(target: LOG_TARGET, "No attestation from {}: {e}", provider.name());
+					false
+				},
+			})
+			.count();
+		if attestations >= threshold {
+			info!(
+				target: LOG_TARGET,
+				"🛡️ Warp sync target #{} ({hash}) attested by {attestations} of {} checkpoint providers",
+				candidate.number(),
+				providers.len(),
+			);
+			return Ok(candidate)
+		}
+		debug!(
+			target: LOG_TARGET,
+			"Checkpoint #{} ({hash}) only attested by {attestations} providers",
+			candidate.number(),
+		);
+	}
+
+	Err(format!(
+		"No warp sync target is attested by {threshold} of the {} checkpoint providers",
+		providers.len()
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::traits::Header as _;
+	use substrate_test_runtime_client::runtime::{Block, Hash, Header};
+
+	fn header(number: u64, seed: u8) -> Header {
+		Header::new(
+			number,
+			Default::default(),
+			Hash::repeat_byte(seed),
+			Default::default(),
+			Default::default(),
+		)
+	}
+
+	/// Provider knowing a chain of headers, the last one being its checkpoint.
+	struct Chain(Vec<Header>);
+
+	#[async_trait::async_trait]
+	impl CheckpointProvider<Block> for Chain {
+		fn name(&self) -> String {
+			"chain".into()
+		}
+
+		async fn checkpoint(&self) -> Result<Header, String> {
+			self.0.last().cloned().ok_or_else(|| "Unreachable".into())
+		}
+
+		async fn finalized_hash(&self, number: u64) -> Result<Option<Hash>, String> {
+			Ok(self.0.iter().find(|header| header.number == number).map(|header| header.hash()))
+		}
+	}
+
+	#[tokio::test]
+	async fn selects_highest_attested_checkpoint() {
+		let (a10, a12, b12) = (header(10, 1), header(12, 2), header(12, 3));
+		let providers: Vec<Box<dyn CheckpointProvider<Block>>> = vec![
+			Box::new(Chain(vec![a10.clone(), a12.clone()])),
+			Box::new(Chain(vec![a10.clone()])),
+			Box::new(Chain(vec![a10.clone(), b12.clone()])),
+			Box::new(Chain(vec![])),
+		];
+
+		// The providers disagree on block 12, but agree on block 10.
+		assert_eq!(select_warp_target(&providers, 3).await, Ok(a10));
+		assert_eq!(select_warp_target(&providers, 1).await, Ok(a12));
+		assert!(select_warp_target(&providers, 4).await.is_err());
+		assert!(select_warp_target(&providers, 0).await.is_err());
+	}
+
+	#[test]
+	fn signed_checkpoints_are_verified() {
+		let publisher = sr25519::Pair::from_seed(&[1; 32]);
+		let genesis_hash = Hash::repeat_byte(42);
+		let checkpoint = SignedCheckpoint::<Block>::new(genesis_hash, header(10, 1), &publisher);
+		assert_eq!(checkpoint.verify(genesis_hash, &[publisher.public()]), Ok(()));
+
+		let other = sr25519::Pair::from_seed(&[2; 32]);
+		assert!(checkpoint.verify(genesis_hash, &[other.public()]).is_err());
+		// The checkpoint can't be used on another chain.
+		assert!(checkpoint.verify(Hash::repeat_byte(43), &[publisher.public()]).is_err());
+		let replayed =
+			SignedCheckpoint { genesis_hash: Hash::repeat_byte(43), ..checkpoint.clone() };
+		assert!(replayed.verify(Hash::repeat_byte(43), &[publisher.public()]).is_err());
+		let forged = SignedCheckpoint { header: header(10, 2), ..checkpoint };
+		assert!(forged.verify(genesis_hash, &[publisher.public()]).is_err());
+	}
+}
@@ -60,8 +60,9 @@ pub struct WarpProofRequest<B: BlockT> {
 
 /// Proof verification result.
 pub enum VerificationResult<Block: BlockT> {
-	/// Proof is valid, but the target was not reached.
-	Partial(SetId, AuthorityList, Block::Hash),
+	/// Proof is valid, but the target was not reached. Contains the header of the last proven
+	/// block.
+	Partial(SetId, AuthorityList, Block::Header),
 	/// Target finality is proved.
 	Complete(SetId, AuthorityList, Block::Header),
 }
@@ -113,6 +114,8 @@ mod rep {
 pub enum WarpSyncPhase<Block: BlockT> {
 	/// Waiting for peers to connect.
 	AwaitingPeers { required_peers: usize },
+	/// Waiting for the target block to be selected.
+	AwaitingTarget,
 	/// Downloading and verifying grandpa warp proofs.
 	DownloadingWarpProofs,
 	/// Downloading target block.
@@ -132,6 +135,7 @@ impl<Block: BlockT> fmt::Display for WarpSyncPhase<Block> {
 		match self {
 			Self::AwaitingPeers { required_peers } =>
 				write!(f, "Waiting for {required_peers} peers to be connected"),
+			Self::AwaitingTarget => write!(f, "Waiting for the target block"),
 			Self::DownloadingWarpProofs => write!(f, "Downloading finality proofs"),
 			Self::DownloadingTargetBlock => write!(f, "Downloading target block"),
 			Self::DownloadingState => write!(f, "Downloading state"),
@@ -145,10 +149,12 @@ impl<Block: BlockT> fmt::Display for WarpSyncPhase<Block> {
 /// Reported warp sync progress.
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct WarpSyncProgress<Block: BlockT> {
-	/// Estimated download percentage.
+	/// Current warp sync phase.
 	pub phase: WarpSyncPhase<Block>,
 	/// Total bytes downloaded so far.
 	pub total_bytes: u64,
+	/// Estimated completion of the current phase, in percent, if known.
+	pub percentage: Option<u32>,
 }
 
 /// Warp sync configuration as accepted by [`WarpSync`].
@@ -159,17 +165,23 @@ pub enum WarpSyncConfig<Block: BlockT> {
 	///
 	/// It is expected that the header provider ensures that the header is trusted.
 	WithTarget(<Block as BlockT>::Header),
+	/// Wait for the header of the state that should be downloaded, then proceed as with
+	/// [`WarpSyncConfig::WithTarget`]. Reverts to full sync if the sender is dropped.
+	WaitForTarget(oneshot::Receiver<<Block as BlockT>::Header>),
 }
 
 /// Warp sync phase used by warp sync state machine.
 enum Phase<B: BlockT> {
 	/// Waiting for enough peers to connect.
 	WaitingForPeers { warp_sync_provider: Arc<dyn WarpSyncProvider<B>> },
+	/// Waiting for the target block to be selected.
+	WaitingForTarget(oneshot::Receiver<B::Header>),
 	/// Downloading warp proofs.
 	WarpProof {
 		set_id: SetId,
 		authorities: AuthorityList,
 		last_hash: B::Hash,
+		last_number: NumberFor<B>,
 		warp_sync_provider: Arc<dyn WarpSyncProvider<B>>,
 	},
 	/// Downloading target block.
@@ -255,6 +267,7 @@ where
 			WarpSyncConfig::WithProvider(warp_sync_provider) =>
 				Phase::WaitingForPeers { warp_sync_provider },
 			WarpSyncConfig::WithTarget(target_header) => Phase::TargetBlock(target_header),
+			WarpSyncConfig::WaitForTarget(target_header) => Phase::WaitingForTarget(target_header),
 		};
 
 		Self {
@@ -324,6 +337,7 @@ where
 			set_id: 0,
 			authorities: warp_sync_provider.current_authorities(),
 			last_hash: self.client.info().genesis_hash,
+			last_number: Zero::zero(),
 			warp_sync_provider: Arc::clone(warp_sync_provider),
 		};
 		trace!(target: LOG_TARGET, "Started warp sync with {} peers.", self.peers.len());
@@ -387,7 +401,7 @@ where
 			peer.state = PeerState::Available;
 		}
 
-		let Phase::WarpProof { set_id, authorities, last_hash, warp_sync_provider } =
+		let Phase::WarpProof { set_id, authorities, last_hash, last_number, warp_sync_provider } =
 			&mut self.phase
 		else {
 			debug!(target: LOG_TARGET, "Unexpected warp proof response");
@@ -402,11 +416,12 @@ where
 				self.actions
 					.push(SyncingAction::DropPeer(BadPeer(*peer_id, rep::BAD_WARP_PROOF)))
 			},
-			Ok(VerificationResult::Partial(new_set_id, new_authorities, new_last_hash)) => {
+			Ok(VerificationResult::Partial(new_set_id, new_authorities, new_last_header)) => {
 				log::debug!(target: LOG_TARGET, "Verified partial proof, set_id={:?}", new_set_id);
 				*set_id = new_set_id;
 				*authorities = new_authorities;
-				*last_hash = new_last_hash;
+				*last_hash = new_last_header.hash();
+				*last_number = *new_last_header.number();
 				self.total_proof_bytes += response.0.len() as u64;
 			},
 			Ok(VerificationResult::Complete(new_set_id, _, header)) => {
@@ -605,7 +620,7 @@ where
 		))
 	}
 
-	/// Returns warp sync estimated progress (stage, bytes received).
+	/// Returns warp sync estimated progress (stage, bytes received, percentage).
 	pub fn progress(&self) -> WarpSyncProgress<B> {
 		match &self.phase {
 			Phase::WaitingForPeers { .. } => WarpSyncProgress {
@@ -613,22 +628,43 @@ where
 					required_peers: MIN_PEERS_TO_START_WARP_SYNC,
 				},
 				total_bytes: self.total_proof_bytes,
+				percentage: None,
 			},
-			Phase::WarpProof { .. } => WarpSyncProgress {
+			Phase::WaitingForTarget(_) => WarpSyncProgress {
+				phase: WarpSyncPhase::AwaitingTarget,
+				total_bytes: self.total_proof_bytes,
+				percentage: None,
+			},
+			Phase::WarpProof { last_number, .. } => WarpSyncProgress {
 				phase: WarpSyncPhase::DownloadingWarpProofs,
 				total_bytes: self.total_proof_bytes,
+				percentage: self.warp_proof_percentage(*last_number),
 			},
 			Phase::TargetBlock(_) => WarpSyncProgress {
 				phase: WarpSyncPhase::DownloadingTargetBlock,
 				total_bytes: self.total_proof_bytes,
+				percentage: None,
 			},
 			Phase::Complete => WarpSyncProgress {
 				phase: WarpSyncPhase::Complete,
 				total_bytes: self.total_proof_bytes + self.total_state_bytes,
+				percentage: None,
 			},
 		}
 	}
 
+	/// Estimated percentage of the warp proofs downloaded, from the number of the last proven
+	/// block and the best block of the peers.
+	fn warp_proof_percentage(&self, last_number: NumberFor<B>) -> Option<u32> {
+		let best_number = self.peers.values().map(|peer| peer.best_number).max()?;
+		if best_number.is_zero() {
+			return None
+		}
+		let percentage = last_number.saturated_into::<u64>().saturating_mul(100) /
+			best_number.saturated_into::<u64>();
+		Some(percentage.min(100) as u32)
+	}
+
 	/// Get the number of peers known to warp sync.
 	pub fn num_peers(&self) -> usize {
 		self.peers.len()
@@ -639,12 +675,14 @@ where
 		SyncStatus {
 			state: match &self.phase {
 				Phase::WaitingForPeers { .. } => SyncState::Downloading { target: Zero::zero() },
+				Phase::WaitingForTarget(_) => SyncState::Downloading { target: Zero::zero() },
 				Phase::WarpProof { .. } => SyncState::Downloading { target: Zero::zero() },
 				Phase::TargetBlock(header) => SyncState::Downloading { target: *header.number() },
 				Phase::Complete => SyncState::Idle,
 			},
 			best_seen_block: match &self.phase {
 				Phase::WaitingForPeers { .. } => None,
+				Phase::WaitingForTarget(_) => None,
 				Phase::WarpProof { .. } => None,
 				Phase::TargetBlock(header) => Some(*header.number()),
 				Phase::Complete => None,
@@ -662,6 +700,8 @@ where
 		&mut self,
 		network_service: &NetworkServiceHandle,
 	) -> impl Iterator<Item = SyncingAction<B>> {
+		self.poll_target();
+
 		let warp_proof_request =
 			self.warp_proof_request().into_iter().map(|(peer_id, protocol_name, request)| {
 				trace!(
@@ -724,6 +764,23 @@ where
 		std::mem::take(&mut self.actions).into_iter()
 	}
 
+	/// Proceed with the download of the target block once it is selected.
+	fn poll_target(&mut self) {
+		let Phase::WaitingForTarget(target_header) = &mut self.phase else { return };
+		match target_header.try_recv() {
+			Ok(None) => {},
+			Ok(Some(target_header)) => self.phase = Phase::TargetBlock(target_header),
+			Err(oneshot::Canceled) => {
+				error!(
+					target: LOG_TARGET,
+					"No warp sync target was selected. Reverting to full sync mode."
+				);
+				self.phase = Phase::Complete;
+				self.actions.push(SyncingAction::Finished);
+			},
+		}
+	}
+
 	/// Take the result of finished warp sync, returning `None` if the sync was unsuccessful.
 	#[must_use]
 	pub fn take_result(&mut self) -> Option<WarpSyncResult<B>> {
@@ -894,6 +951,51 @@ mod test {
 		assert_eq!(warp_sync.actions(&network_handle).count(), 0)
 	}
 
+	#[test]
+	fn warp_sync_waits_for_target() {
+		let client = mock_client_without_state();
+		let (tx, rx) = oneshot::channel();
+		let config = WarpSyncConfig::WaitForTarget(rx);
+		let mut warp_sync =
+			WarpSync::new(Arc::new(client), config, None, Arc::new(MockBlockDownloader::new()));
+
+		let network_provider = NetworkServiceProvider::new();
+		let network_handle = network_provider.handle();
+
+		assert_eq!(warp_sync.actions(&network_handle).count(), 0);
+		assert!(matches!(warp_sync.phase, Phase::WaitingForTarget(_)));
+		assert_eq!(warp_sync.progress().phase, WarpSyncPhase::AwaitingTarget);
+
+		let target_header = <Block as BlockT>::Header::new(
+			1,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		tx.send(target_header.clone()).unwrap();
+		assert_eq!(warp_sync.actions(&network_handle).count(), 0);
+		assert!(matches!(&warp_sync.phase, Phase::TargetBlock(header) if *header == target_header));
+	}
+
+	#[test]
+	fn warp_sync_without_target_reverts_to_full_sync() {
+		let client = mock_client_without_state();
+		let (tx, rx) = oneshot::channel::<<Block as BlockT>::Header>();
+		let config = WarpSyncConfig::WaitForTarget(rx);
+		let mut warp_sync =
+			WarpSync::new(Arc::new(client), config, None, Arc::new(MockBlockDownloader::new()));
+
+		let network_provider = NetworkServiceProvider::new();
+		let network_handle = network_provider.handle();
+
+		drop(tx);
+		let actions = warp_sync.actions(&network_handle).collect::<Vec<_>>();
+		assert_eq!(actions.len(), 1);
+		assert!(matches!(actions[0], SyncingAction::Finished));
+		assert!(warp_sync.take_result().is_none());
+	}
+
 	#[test]
 	fn warp_sync_is_started_only_when_there_is_enough_peers() {
 		let client = mock_client_without_state();
@@ -1182,7 +1284,17 @@ mod test {
 			.return_const(AuthorityList::default());
 		// Warp proof is partial.
 		provider.expect_verify().return_once(|_proof, set_id, authorities| {
-			Ok(VerificationResult::Partial(set_id, authorities, Hash::random()))
+			Ok(VerificationResult::Partial(
+				set_id,
+				authorities,
+				<Block as BlockT>::Header::new(
+					5,
+					Hash::random(),
+					Hash::random(),
+					Hash::random(),
+					Default::default(),
+				),
+			))
 		});
 		let config = WarpSyncConfig::WithProvider(Arc::new(provider));
 		let mut warp_sync = WarpSync::new(
@@ -26,7 +26,7 @@ use crate::strategy::{state_sync::StateSyncProgress, warp::WarpSyncProgress};
 use sc_network_types::PeerId;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
-use std::{fmt, pin::Pin, sync::Arc};
+use std::{fmt, pin::Pin, sync::Arc, time::Duration};
 
 /// The sync status of a peer we are trying to sync with
 #[derive(Debug)]
@@ -59,6 +59,21 @@ where
 
 impl<B> Copy for ExtendedPeerInfo<B> where B: BlockT {}
 
+/// Details about the syncing with a peer, for debugging.
+#[derive(Debug, Clone)]
+pub struct PeerSyncDetails<B: BlockT> {
+	/// Peer best block hash
+	pub best_hash: B::Hash,
+	/// Peer best block number
+	pub best_number: NumberFor<B>,
+	/// Number of requests to the peer awaiting a response.
+	pub pending_requests: usize,
+	/// Time elapsed since the oldest pending request was started.
+	pub oldest_pending_request: Option<Duration>,
+	/// Moving average of the response time of the peer, `None` if it didn't respond yet.
+	pub average_response_time: Option<Duration>,
+}
+
 /// Reported sync state.
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum SyncState<BlockNumber> {
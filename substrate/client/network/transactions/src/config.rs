@@ -38,6 +38,9 @@ pub(crate) const MAX_TRANSACTIONS_SIZE: u64 = MAX_RESPONSE_SIZE;
 /// Maximum number of transaction validation request we keep at any moment.
 pub(crate) const MAX_PENDING_TRANSACTIONS: usize = 8192;
 
+/// Interval at which the stem peer of locally submitted transactions is rotated.
+pub(crate) const STEM_ROTATION_INTERVAL: time::Duration = time::Duration::from_secs(600);
+
 /// Result of the transaction import.
 #[derive(Clone, Copy, Debug)]
 pub enum TransactionImport {
@@ -25,6 +25,11 @@
 //!   network configuration as an extra peers set.
 //! - Use [`TransactionsHandlerPrototype::build`] then [`TransactionsHandler::run`] to obtain a
 //! `Future` that processes transactions.
+//!
+//! With [`TransactionPropagation::Stem`], transactions which weren't received from any peer, i.e.
+//! the ones submitted to the node locally, are first relayed to a single peer only. They are
+//! excluded from the regular gossip until their embargo expires or they are received back from
+//! another peer.
 
 use crate::config::*;
 
@@ -33,8 +38,9 @@ use futures::{prelude::*, stream::FuturesUnordered};
 use log::{debug, trace, warn};
 
 use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use rand::seq::IteratorRandom;
 use sc_network::{
-	config::{NonReservedPeerMode, ProtocolId, SetConfig},
+	config::{NonReservedPeerMode, ProtocolId, SetConfig, TransactionPropagation},
 	error, multiaddr,
 	peer_store::PeerStoreProvider,
 	service::{
@@ -58,6 +64,7 @@ use std::{
 	pin::Pin,
 	sync::Arc,
 	task::Poll,
+	time::{Duration, Instant},
 };
 
 pub mod config;
@@ -85,6 +92,8 @@ mod rep {
 
 struct Metrics {
 	propagated_transactions: Counter<U64>,
+	stemmed_transactions: Counter<U64>,
+	stem_failures: Counter<U64>,
 }
 
 impl Metrics {
@@ -97,6 +106,20 @@ impl Metrics {
 				)?,
 				r,
 			)?,
+			stemmed_transactions: register(
+				Counter::new(
+					"substrate_sync_stemmed_transactions",
+					"Number of locally submitted transactions relayed to the stem peer",
+				)?,
+				r,
+			)?,
+			stem_failures: register(
+				Counter::new(
+					"substrate_sync_stem_failures",
+					"Number of locally submitted transactions gossiped without a working stem peer",
+				)?,
+				r,
+			)?,
 		})
 	}
 }
@@ -182,6 +205,7 @@ impl TransactionsHandlerPrototype {
 		network: N,
 		sync: S,
 		transaction_pool: Arc<dyn TransactionPool<H, B>>,
+		propagation: TransactionPropagation,
 		metrics_registry: Option<&Registry>,
 	) -> error::Result<(TransactionsHandler<B, H, N, S>, TransactionsHandlerController<H>)> {
 		let sync_event_stream = sync.event_stream("transactions-handler-sync");
@@ -201,6 +225,10 @@ impl TransactionsHandlerPrototype {
 			peers: HashMap::new(),
 			transaction_pool,
 			from_controller,
+			stem: match propagation {
+				TransactionPropagation::Flood => None,
+				TransactionPropagation::Stem { embargo } => Some(Stem::new(embargo)),
+			},
 			metrics: if let Some(r) = metrics_registry {
 				Some(Metrics::register(r)?)
 			} else {
@@ -269,6 +297,8 @@ pub struct TransactionsHandler<
 	peers: HashMap<PeerId, Peer<H>>,
 	transaction_pool: Arc<dyn TransactionPool<H, B>>,
 	from_controller: TracingUnboundedReceiver<ToHandler<H>>,
+	/// State of the stem/fluff propagation, if enabled.
+	stem: Option<Stem<H>>,
 	/// Prometheus metrics.
 	metrics: Option<Metrics>,
 	/// Handle that is used to communicate with `sc_network::Notifications`.
@@ -283,6 +313,41 @@ struct Peer<H: ExHashT> {
 	role: ObservedRole,
 }
 
+/// State of the stem/fluff propagation of locally submitted transactions.
+struct Stem<H: ExHashT> {
+	/// Time to wait for a stemmed transaction to be received back from another peer.
+	embargo: Duration,
+	/// The peer locally submitted transactions are relayed to, and when it was chosen.
+	peer: Option<(PeerId, Instant)>,
+	/// Stemmed transactions, with the end of their embargo.
+	embargoed: HashMap<H, Instant>,
+	/// Hashes of the transactions received from peers.
+	received: LruHashSet<H>,
+}
+
+impl<H: ExHashT> Stem<H> {
+	fn new(embargo: Duration) -> Self {
+		Self {
+			embargo,
+			peer: None,
+			embargoed: HashMap::new(),
+			received: LruHashSet::new(
+				NonZeroUsize::new(MAX_KNOWN_TRANSACTIONS).expect("Constant is nonzero"),
+			),
+		}
+	}
+
+	/// Removes the transactions whose embargo ended, or all of them if `all` is set.
+	///
+	/// Returns the number of removed transactions.
+	fn end_embargoes(&mut self, all: bool) -> usize {
+		let now = Instant::now();
+		let len = self.embargoed.len();
+		self.embargoed.retain(|_, end| !all && *end > now);
+		len - self.embargoed.len()
+	}
+}
+
 impl<B, H, N, S> TransactionsHandler<B, H, N, S>
 where
 	B: BlockT + 'static,
@@ -361,6 +426,17 @@ where
 			NotificationEvent::NotificationStreamClosed { peer } => {
 				let _peer = self.peers.remove(&peer);
 				debug_assert!(_peer.is_some());
+
+				if let Some(stem) = self.stem.as_mut().filter(|stem| {
+					stem.peer.is_some_and(|(stem_peer, _)| stem_peer == peer)
+				}) {
+					// The stem peer may not have relayed the transactions, gossip them ourselves.
+					stem.peer = None;
+					let failures = stem.end_embargoes(true);
+					if let Some(ref metrics) = self.metrics {
+						metrics.stem_failures.inc_by(failures as _)
+					}
+				}
 			},
 			NotificationEvent::NotificationReceived { peer, notification } => {
 				if let Ok(m) =
@@ -423,6 +499,14 @@ where
 				let hash = self.transaction_pool.hash_of(&t);
 				peer.known_transactions.insert(hash.clone());
 
+				if let Some(ref mut stem) = self.stem {
+					stem.received.insert(hash.clone());
+					if stem.peer.map_or(true, |(stem_peer, _)| stem_peer != who) {
+						// The transaction was fluffed by the network.
+						stem.embargoed.remove(&hash);
+					}
+				}
+
 				self.network.report_peer(who, rep::ANY_TRANSACTION);
 
 				match self.pending_transactions_peers.entry(hash.clone()) {
@@ -460,16 +544,58 @@ where
 
 		debug!(target: LOG_TARGET, "Propagating transaction [{:?}]", hash);
 		if let Some(transaction) = self.transaction_pool.transaction(hash) {
-			let propagated_to = self.do_propagate_transactions(&[(hash.clone(), transaction)]);
+			// Transactions not received from any peer were submitted locally.
+			let is_local = self.stem.as_ref().is_some_and(|stem| !stem.received.contains(hash));
+			let stem_peer = if is_local { self.stem_peer() } else { None };
+			let propagated_to =
+				self.do_propagate_transactions(&[(hash.clone(), transaction)], stem_peer);
+
+			if let Some(stem) = self.stem.as_mut().filter(|_| is_local) {
+				if stem_peer.is_some() {
+					stem.embargoed.insert(hash.clone(), Instant::now() + stem.embargo);
+				}
+				if let Some(ref metrics) = self.metrics {
+					match stem_peer {
+						Some(_) => metrics.stemmed_transactions.inc(),
+						None => metrics.stem_failures.inc(),
+					}
+				}
+			}
 			self.transaction_pool.on_broadcasted(propagated_to);
 		} else {
 			debug!(target: "sync", "Propagating transaction failure [{:?}]", hash);
 		}
 	}
 
+	/// Returns the peer to relay locally submitted transactions to, rotating it if necessary.
+	///
+	/// Returns `None` if there are no full peers to choose from.
+	fn stem_peer(&mut self) -> Option<PeerId> {
+		let stem = self.stem.as_mut()?;
+		match stem.peer {
+			Some((peer, chosen_at))
+				if self.peers.contains_key(&peer) &&
+					chosen_at.elapsed() < STEM_ROTATION_INTERVAL =>
+				Some(peer),
+			_ => {
+				let peer = self
+					.peers
+					.iter()
+					.filter(|(_, peer)| !matches!(peer.role, ObservedRole::Light))
+					.map(|(who, _)| *who)
+					.choose(&mut rand::thread_rng());
+				stem.peer = peer.map(|peer| (peer, Instant::now()));
+				trace!(target: LOG_TARGET, "Chose {peer:?} as stem peer");
+				peer
+			},
+		}
+	}
+
+	/// Sends the transactions to all peers, or only to `only` if given.
 	fn do_propagate_transactions(
 		&mut self,
 		transactions: &[(H, Arc<B::Extrinsic>)],
+		only: Option<PeerId>,
 	) -> HashMap<H, Vec<String>> {
 		let mut propagated_to = HashMap::<_, Vec<_>>::new();
 		let mut propagated_transactions = 0;
@@ -480,6 +606,10 @@ where
 				continue
 			}
 
+			if only.is_some_and(|only| only != *who) {
+				continue
+			}
+
 			let (hashes, to_send): (Vec<_>, Transactions<_>) = transactions
 				.iter()
 				.filter(|(hash, _)| peer.known_transactions.insert(hash.clone()))
@@ -524,7 +654,15 @@ where
 			return
 		}
 
-		let transactions = self.transaction_pool.transactions();
+		let mut transactions = self.transaction_pool.transactions();
+
+		if let Some(ref mut stem) = self.stem {
+			let failures = stem.end_embargoes(false);
+			if let Some(ref metrics) = self.metrics {
+				metrics.stem_failures.inc_by(failures as _)
+			}
+			transactions.retain(|(hash, _)| !stem.embargoed.contains_key(hash));
+		}
 
 		if transactions.is_empty() {
 			return
@@ -532,7 +670,7 @@ where
 
 		debug!(target: LOG_TARGET, "Propagating transactions");
 
-		let propagated_to = self.do_propagate_transactions(&transactions);
+		let propagated_to = self.do_propagate_transactions(&transactions, None);
 		self.transaction_pool.on_broadcasted(propagated_to);
 	}
 }
@@ -0,0 +1,325 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Client of external randomness beacons.
+//!
+//! The [`RandomnessBeacon`] fetches the pulse of every round of a
+//! [drand](https://drand.love) compatible beacon over HTTP, verifies it against the public key of
+//! the beacon and keeps the latest one. Block authors include it through the
+//! [`InherentDataProvider`] of a [`PulseSource`], so the runtime can verify it again, see
+//! [`sp_randomness_beacon`].
+//!
+//! If the beacon is unreachable, no pulse is provided; whether blocks without a pulse are valid is
+//! up to the runtime.
+
+use clap::Args;
+use http_body_util::{BodyExt, Full};
+use hyper::{body::Bytes, Request};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::{
+	client::legacy::{connect::HttpConnector, Client},
+	rt::TokioExecutor,
+};
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+use sp_core::traits::SpawnNamed;
+use sp_randomness_beacon::{BeaconConfig, InherentDataProvider, Pulse, PUBLIC_KEY_SIZE};
+use std::{
+	sync::Arc,
+	time::{Duration, SystemTime},
+};
+
+const LOG_TARGET: &str = "randomness-beacon";
+
+/// Timeout of a single request to an endpoint.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay after the start of a round before its pulse is fetched, as the beacon needs some time to
+/// aggregate it.
+const FETCH_DELAY: Duration = Duration::from_millis(500);
+
+/// Delay before fetching a pulse again after a failure.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+type HttpClient = Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+
+/// Parameters of the randomness beacon client.
+#[derive(Default, Debug, Clone, Args)]
+pub struct RandomnessBeaconParams {
+	/// HTTP endpoint of a randomness beacon, including the hash of the beacon chain, e.g.
+	/// `https://api.drand.sh/<chain hash>`.
+	///
+	/// Can be given multiple times, the endpoints are tried in order. The client is disabled if no
+	/// endpoint is given.
+	#[arg(long = "randomness-beacon-url", value_name = "URL")]
+	pub urls: Vec<String>,
+
+	/// Hex encoded public key of the randomness beacon.
+	#[arg(long = "randomness-beacon-public-key", value_name = "HEX", requires = "urls")]
+	pub public_key: Option<String>,
+
+	/// Time of the first round of the randomness beacon, in seconds since the UNIX epoch.
+	#[arg(long = "randomness-beacon-genesis-time", value_name = "SECONDS", requires = "urls")]
+	pub genesis_time: Option<u64>,
+
+	/// Time between two rounds of the randomness beacon, in seconds.
+	#[arg(long = "randomness-beacon-period", value_name = "SECONDS", requires = "urls")]
+	pub period: Option<u32>,
+}
+
+impl RandomnessBeaconParams {
+	/// The configuration of the beacon, or `None` if the client is disabled.
+	pub fn config(&self) -> Result<Option<BeaconConfig>, String> {
+		if self.urls.is_empty() {
+			return Ok(None)
+		}
+		let public_key =
+			self.public_key.as_ref().ok_or("--randomness-beacon-public-key is required")?;
+		let public_key = array_bytes::hex2array::<_, PUBLIC_KEY_SIZE>(public_key)
+			.map_err(|e| format!("Invalid randomness beacon public key: {e:?}"))?;
+		let genesis_time =
+			self.genesis_time.ok_or("--randomness-beacon-genesis-time is required")?;
+		let period = self
+			.period
+			.filter(|period| *period > 0)
+			.ok_or("--randomness-beacon-period is required and must be greater than zero")?;
+		Ok(Some(BeaconConfig { public_key, genesis_time, period }))
+	}
+}
+
+struct Metrics {
+	round: Gauge<U64>,
+	failures: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			round: register(
+				Gauge::new(
+					"substrate_randomness_beacon_round",
+					"Round of the latest verified pulse of the randomness beacon",
+				)?,
+				registry,
+			)?,
+			failures: register(
+				Counter::new(
+					"substrate_randomness_beacon_failures_total",
+					"Number of pulses which couldn't be fetched or verified",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Shared access to the latest verified pulse of a [`RandomnessBeacon`].
+#[derive(Clone, Default)]
+pub struct PulseSource {
+	latest: Arc<Mutex<Option<Pulse>>>,
+}
+
+impl PulseSource {
+	/// The latest verified pulse, if any.
+	pub fn latest(&self) -> Option<Pulse> {
+		self.latest.lock().clone()
+	}
+
+	/// An inherent data provider providing the latest verified pulse.
+	pub fn inherent_data_provider(&self) -> InherentDataProvider {
+		InherentDataProvider::new(self.latest())
+	}
+}
+
+/// Fetches and verifies the pulses of a randomness beacon.
+pub struct RandomnessBeacon {
+	urls: Vec<String>,
+	config: BeaconConfig,
+	client: HttpClient,
+	source: PulseSource,
+	metrics: Option<Metrics>,
+}
+
+impl RandomnessBeacon {
+	/// Create a client of the beacon with the given `config`, served by the endpoints `urls`.
+	pub fn new(urls: Vec<String>, config: BeaconConfig) -> std::io::Result<Self> {
+		let connector = HttpsConnectorBuilder::new()
+			.with_provider_and_native_roots(rustls::crypto::ring::default_provider())?
+			.https_or_http()
+			.enable_http1()
+			.build();
+		let client = Client::builder(TokioExecutor::new()).build(connector);
+		Ok(Self { urls, config, client, source: PulseSource::default(), metrics: None })
+	}
+
+	/// The source of the pulses verified by this client.
+	pub fn source(&self) -> PulseSource {
+		self.source.clone()
+	}
+
+	/// Spawn the client, if enabled by `params`.
+	///
+	/// Returns the source of the verified pulses.
+	pub fn spawn(
+		params: RandomnessBeaconParams,
+		registry: Option<&Registry>,
+		spawner: &impl SpawnNamed,
+	) -> Result<Option<PulseSource>, String> {
+		let Some(config) = params.config()? else { return Ok(None) };
+
+		let mut beacon = Self::new(params.urls, config).map_err(|e| e.to_string())?;
+		beacon.metrics = registry.map(Metrics::register).transpose().map_err(|e| e.to_string())?;
+		let source = beacon.source();
+		log::info!(target: LOG_TARGET, "Fetching randomness beacon pulses from {:?}", beacon.urls);
+		spawner.spawn("randomness-beacon", None, Box::pin(beacon.run()));
+		Ok(Some(source))
+	}
+
+	/// Returns a never-ending `Future` fetching the pulse of every round.
+	pub async fn run(self) {
+		loop {
+			let now = unix_millis(SystemTime::now());
+			let round = self.config.round_at(now);
+			let latest = self.source.latest().map_or(0, |pulse| pulse.round);
+
+			let mut delay = self.until_round(round + 1, now) + FETCH_DELAY;
+			if round > latest {
+				match self.fetch(round).await {
+					Ok(pulse) => {
+						log::debug!(target: LOG_TARGET, "Verified pulse of round {round}");
+						if let Some(metrics) = &self.metrics {
+							metrics.round.set(round);
+						}
+						*self.source.latest.lock() = Some(pulse);
+					},
+					Err(e) => {
+						log::warn!(target: LOG_TARGET, "Failed to get pulse of round {round}: {e}");
+						if let Some(metrics) = &self.metrics {
+							metrics.failures.inc();
+						}
+						delay = delay.min(RETRY_DELAY);
+					},
+				}
+			}
+			tokio::time::sleep(delay).await;
+		}
+	}
+
+	/// Time from `now` until the start of `round`.
+	fn until_round(&self, round: u64, now: u64) -> Duration {
+		let start = self
+			.config
+			.genesis_time
+			.saturating_add((round - 1).saturating_mul(u64::from(self.config.period)))
+			.saturating_mul(1000);
+		Duration::from_millis(start.saturating_sub(now))
+	}
+
+	/// Fetch and verify the pulse of `round`, trying the endpoints in order.
+	async fn fetch(&self, round: u64) -> Result<Pulse, String> {
+		let mut error = String::from("No endpoint");
+		for url in &self.urls {
+			let url = format!("{}/public/{round}", url.trim_end_matches('/'));
+			match self.get(&url).await.and_then(|body| parse_pulse(&body)) {
+				Ok(pulse) if pulse.round != round =>
+					error = format!("{url} returned round {} instead", pulse.round),
+				Ok(pulse) if !self.config.verify(&pulse) =>
+					error = format!("{url} returned an invalid signature"),
+				Ok(pulse) => return Ok(pulse),
+				Err(e) => error = format!("{url}: {e}"),
+			}
+		}
+		Err(error)
+	}
+
+	async fn get(&self, url: &str) -> Result<Bytes, String> {
+		let request = Request::get(url).body(Full::default()).map_err(|e| e.to_string())?;
+		let response = tokio::time::timeout(REQUEST_TIMEOUT, self.client.request(request))
+			.await
+			.map_err(|_| "Request timed out".to_string())?
+			.map_err(|e| e.to_string())?;
+		if !response.status().is_success() {
+			return Err(format!("Unexpected status {}", response.status()))
+		}
+		Ok(response.into_body().collect().await.map_err(|e| e.to_string())?.to_bytes())
+	}
+}
+
+/// Milliseconds since the UNIX epoch.
+fn unix_millis(time: SystemTime) -> u64 {
+	time.duration_since(SystemTime::UNIX_EPOCH)
+		.expect("Current time is always after unix epoch; qed")
+		.as_millis() as u64
+}
+
+/// A pulse as returned by the HTTP API of drand.
+#[derive(serde::Deserialize)]
+struct JsonPulse {
+	round: u64,
+	signature: String,
+}
+
+/// Parse a pulse returned by the HTTP API of drand.
+fn parse_pulse(body: &[u8]) -> Result<Pulse, String> {
+	let pulse: JsonPulse = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+	let signature = array_bytes::hex2array(&pulse.signature)
+		.map_err(|e| format!("Invalid signature: {e:?}"))?;
+	Ok(Pulse { round: pulse.round, signature })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_pulse_works() {
+		let signature = "ab".repeat(48);
+		let body = format!(
+			r#"{{"round":1000,"randomness":"{}","signature":"{signature}"}}"#,
+			"cd".repeat(32),
+		);
+		assert_eq!(
+			parse_pulse(body.as_bytes()).unwrap(),
+			Pulse { round: 1000, signature: [0xab; 48] },
+		);
+		assert!(parse_pulse(br#"{"round":1000,"signature":"abab"}"#).is_err());
+		assert!(parse_pulse(b"Not found").is_err());
+	}
+
+	#[test]
+	fn params_config_works() {
+		assert_eq!(RandomnessBeaconParams::default().config(), Ok(None));
+
+		let params = RandomnessBeaconParams {
+			urls: vec!["https://api.drand.sh/hash".into()],
+			public_key: Some(format!("0x{}", "01".repeat(PUBLIC_KEY_SIZE))),
+			genesis_time: Some(1000),
+			period: Some(3),
+		};
+		assert_eq!(
+			params.config(),
+			Ok(Some(BeaconConfig {
+				public_key: [1; PUBLIC_KEY_SIZE],
+				genesis_time: 1000,
+				period: 3,
+			})),
+		);
+		assert!(RandomnessBeaconParams { period: Some(0), ..params.clone() }.config().is_err());
+		assert!(RandomnessBeaconParams { public_key: None, ..params }.config().is_err());
+	}
+}
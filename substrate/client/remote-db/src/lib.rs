@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote database for read-only replicas.
+//!
+//! A primary node serves its database over a dedicated TCP protocol, so read-only replicas can
+//! serve RPC without storing the chain themselves:
+//!
+//! - The primary opens its database as [`PrimaryDatabase`], which publishes the keys changed by
+//!   every commit, and serves it to replicas with a [`RemoteDbServer`].
+//! - A replica uses a [`RemoteDatabase`] as the database of its backend. Reads go to the primary
+//!   and are cached locally. The replica subscribes to the commits of the primary and drops the
+//!   changed values from its cache. If the subscription breaks, cached values are not served for
+//!   longer than [`ReplicaConfig::max_staleness`].
+//!
+//! The primary keeps the state of unfinalized blocks in memory, so replicas only serve the state
+//! of finalized blocks and report the last finalized block as their best block, see
+//! [`follow_primary`]. Replicas don't import blocks and don't emit import notifications.
+
+mod primary;
+mod protocol;
+mod replica;
+
+pub use primary::{PrimaryDatabase, RemoteDbServer};
+pub use replica::{RemoteDatabase, ReplicaConfig};
+
+use clap::Args;
+use sc_client_db::DatabaseSource;
+use sp_runtime::traits::Block as BlockT;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::sync::watch;
+
+const LOG_TARGET: &str = "remote-db";
+
+/// Parameters used to configure the remote database.
+#[derive(Debug, Clone, Args)]
+pub struct RemoteDbParams {
+	/// Serve the database to read-only replicas on this address.
+	#[arg(long, value_name = "ADDR", conflicts_with = "remote_db_primary")]
+	pub remote_db_listen: Option<SocketAddr>,
+
+	/// Run as a read-only replica of the primary node serving its database on this address.
+	#[arg(long, value_name = "HOST:PORT")]
+	pub remote_db_primary: Option<String>,
+
+	/// The number of database values cached by a replica.
+	#[arg(long, value_name = "COUNT", default_value_t = 100_000)]
+	pub remote_db_cache_size: u32,
+
+	/// The maximum number of seconds a replica serves cached values without hearing from the
+	/// primary.
+	#[arg(long, value_name = "SECONDS", default_value_t = 6)]
+	pub remote_db_max_staleness: u64,
+
+	/// The number of connections a replica uses for reads.
+	#[arg(long, value_name = "COUNT", default_value_t = 4)]
+	pub remote_db_connections: usize,
+}
+
+impl RemoteDbParams {
+	/// The configuration of the replica, if the node runs as one.
+	pub fn replica_config(&self) -> Option<ReplicaConfig> {
+		Some(ReplicaConfig {
+			primary: self.remote_db_primary.clone()?,
+			connections: self.remote_db_connections,
+			cache_size: self.remote_db_cache_size,
+			max_staleness: Duration::from_secs(self.remote_db_max_staleness),
+			request_timeout: Duration::from_secs(10),
+		})
+	}
+}
+
+impl RemoteDatabase {
+	/// The database source of the backend of a replica.
+	pub fn source(self: &Arc<Self>) -> DatabaseSource {
+		DatabaseSource::Custom { db: self.clone(), require_create_flag: false }
+	}
+}
+
+/// Keep the chain metadata and the state database of the `backend` of a replica in sync with the
+/// primary.
+///
+/// `commits` are the commits of the [`RemoteDatabase`] of the backend, see
+/// [`RemoteDatabase::commits`]. Returns once the database is dropped.
+pub async fn follow_primary<Block: BlockT>(
+	backend: Arc<sc_client_db::Backend<Block>>,
+	mut commits: watch::Receiver<u64>,
+) {
+	loop {
+		if let Err(e) = backend.follow_primary() {
+			log::warn!(target: LOG_TARGET, "Failed to reload the chain metadata: {e}");
+		}
+		if commits.changed().await.is_err() {
+			return
+		}
+	}
+}
@@ -0,0 +1,219 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The primary side: notifying replicas of commits and serving their reads.
+
+use crate::{
+	protocol::{self, Message, Request, PROTOCOL_VERSION},
+	LOG_TARGET,
+};
+use sc_client_db::{open_full_database, DatabaseSource, DbHash};
+use sp_database::{error, Change, ColumnId, Database, Transaction};
+use sp_runtime::traits::Block as BlockT;
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+	net::{TcpListener, TcpStream},
+	sync::broadcast,
+};
+
+/// Interval of the heartbeats sent to subscribed replicas.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of commits buffered per subscribed replica before it is disconnected.
+const COMMIT_QUEUE_SIZE: usize = 1024;
+
+/// The database of a primary node, notifying subscribed replicas of every commit.
+pub struct PrimaryDatabase<H> {
+	inner: Arc<dyn Database<H>>,
+	commits: broadcast::Sender<Arc<Message>>,
+}
+
+impl<H> PrimaryDatabase<H> {
+	/// Wrap the database `inner`.
+	pub fn new(inner: Arc<dyn Database<H>>) -> Self {
+		let (commits, _) = broadcast::channel(COMMIT_QUEUE_SIZE);
+		Self { inner, commits }
+	}
+}
+
+impl PrimaryDatabase<DbHash> {
+	/// Open the database configured in `source` as primary database.
+	///
+	/// `source` is replaced with the opened database, so the backend of the node built from it
+	/// commits through the primary database.
+	pub fn open<Block: BlockT>(source: &mut DatabaseSource) -> sp_blockchain::Result<Arc<Self>> {
+		let (db, created) = open_full_database::<Block>(source)?;
+		let primary = Arc::new(Self::new(db));
+		*source = DatabaseSource::Custom { db: primary.clone(), require_create_flag: created };
+		Ok(primary)
+	}
+}
+
+/// The keys changed by `transaction`, as `(column, key)`.
+fn changed_keys<H: AsRef<[u8]>>(transaction: &Transaction<H>) -> Vec<(ColumnId, Vec<u8>)> {
+	transaction
+		.0
+		.iter()
+		.map(|change| match change {
+			Change::Set(column, key, _) | Change::Remove(column, key) => (*column, key.clone()),
+			Change::Store(column, hash, _)
+			| Change::Reference(column, hash)
+			| Change::Release(column, hash) => (*column, hash.as_ref().to_vec()),
+		})
+		.collect()
+}
+
+impl<H: Clone + AsRef<[u8]>> Database<H> for PrimaryDatabase<H> {
+	fn commit(&self, transaction: Transaction<H>) -> error::Result<()> {
+		let changes = changed_keys(&transaction);
+		self.inner.commit(transaction)?;
+		// Sending only fails if no replica is subscribed.
+		let _ = self.commits.send(Arc::new(Message::Commit { changes }));
+		Ok(())
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+		self.inner.get(col, key)
+	}
+
+	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
+		self.inner.contains(col, key)
+	}
+
+	fn value_size(&self, col: ColumnId, key: &[u8]) -> Option<usize> {
+		self.inner.value_size(col, key)
+	}
+
+	fn with_get(&self, col: ColumnId, key: &[u8], f: &mut dyn FnMut(&[u8])) {
+		self.inner.with_get(col, key, f)
+	}
+
+	fn supports_ref_counting(&self) -> bool {
+		self.inner.supports_ref_counting()
+	}
+
+	fn sanitize_key(&self, key: &mut Vec<u8>) {
+		self.inner.sanitize_key(key)
+	}
+}
+
+/// Serves the reads of replicas from a [`PrimaryDatabase`].
+pub struct RemoteDbServer<H> {
+	database: Arc<PrimaryDatabase<H>>,
+	listener: TcpListener,
+}
+
+impl<H> RemoteDbServer<H>
+where
+	H: Clone + AsRef<[u8]> + Send + Sync + 'static,
+{
+	/// Listen for replicas on `addr`.
+	pub async fn bind(database: Arc<PrimaryDatabase<H>>, addr: SocketAddr) -> io::Result<Self> {
+		Ok(Self { database, listener: TcpListener::bind(addr).await? })
+	}
+
+	/// The address the server listens on.
+	pub fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.listener.local_addr()
+	}
+
+	/// Returns a never-ending `Future` serving the connecting replicas.
+	pub async fn run(self) {
+		loop {
+			let (stream, peer) = match self.listener.accept().await {
+				Ok(accepted) => accepted,
+				Err(e) => {
+					log::debug!(target: LOG_TARGET, "Failed to accept replica connection: {e}");
+					continue
+				},
+			};
+			let database = self.database.clone();
+			tokio::spawn(async move {
+				if let Err(e) = serve(database, stream).await {
+					log::debug!(target: LOG_TARGET, "Connection of replica {peer} closed: {e}");
+				}
+			});
+		}
+	}
+}
+
+/// Serve the requests of a replica until it disconnects.
+async fn serve<H>(database: Arc<PrimaryDatabase<H>>, mut stream: TcpStream) -> io::Result<()>
+where
+	H: Clone + AsRef<[u8]>,
+{
+	stream.set_nodelay(true)?;
+	let hello = Message::Hello {
+		version: PROTOCOL_VERSION,
+		ref_counting: database.supports_ref_counting(),
+	};
+	protocol::write_async(&mut stream, &hello).await?;
+
+	loop {
+		match protocol::read_async(&mut stream).await? {
+			Request::Get { id, column, key } => {
+				let value = database.inner.get(column, &key);
+				protocol::write_async(&mut stream, &Message::Value { id, value }).await?;
+			},
+			Request::Subscribe => return stream_commits(&database, stream).await,
+		}
+	}
+}
+
+/// Send the commits of `database` to a subscribed replica.
+async fn stream_commits<H>(database: &PrimaryDatabase<H>, mut stream: TcpStream) -> io::Result<()> {
+	let mut commits = database.commits.subscribe();
+	let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+	loop {
+		let message = tokio::select! {
+			commit = commits.recv() => match commit {
+				Ok(commit) => commit,
+				// A replica which missed commits can't trust its cache anymore. Closing the
+				// connection makes it drop the cache and subscribe again.
+				Err(broadcast::error::RecvError::Lagged(_)) =>
+					return Err(io::Error::other("Replica is lagging behind the commits")),
+				Err(broadcast::error::RecvError::Closed) => return Ok(()),
+			},
+			_ = heartbeat.tick() => Arc::new(Message::Heartbeat),
+		};
+		protocol::write_async(&mut stream, &*message).await?;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_database::MemDb;
+
+	#[test]
+	fn commits_are_published() {
+		let database = PrimaryDatabase::<DbHash>::new(Arc::new(MemDb::default()));
+		let mut commits = database.commits.subscribe();
+
+		let mut transaction = Transaction::new();
+		transaction.set(0, b"key", b"value");
+		transaction.remove(1, b"other");
+		database.commit(transaction).unwrap();
+
+		assert_eq!(database.get(0, b"key"), Some(b"value".to_vec()));
+		assert_eq!(
+			*commits.try_recv().unwrap(),
+			Message::Commit { changes: vec![(0, b"key".to_vec()), (1, b"other".to_vec())] },
+		);
+	}
+}
@@ -0,0 +1,144 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The wire protocol between a primary node and its replicas.
+//!
+//! Every message is sent as a frame: the length of the SCALE encoded message as a little endian
+//! `u32`, followed by the encoded message. After accepting a connection the primary sends
+//! [`Message::Hello`], after which the replica either sends [`Request::Get`]s and receives one
+//! [`Message::Value`] per request, or sends a single [`Request::Subscribe`] and receives a
+//! [`Message::Commit`] for every commit of the primary, with [`Message::Heartbeat`]s in between.
+
+use codec::{Decode, Encode};
+use sp_database::ColumnId;
+use std::io::{self, Read, Write};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The version of the protocol, checked by replicas.
+pub(crate) const PROTOCOL_VERSION: u32 = 1;
+
+/// The maximum size of a frame.
+const MAX_FRAME_SIZE: usize = 128 * 1024 * 1024;
+
+/// A request of a replica.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub(crate) enum Request {
+	/// Read the value of `key` in `column`.
+	Get { id: u64, column: ColumnId, key: Vec<u8> },
+	/// Stream the keys changed by the commits of the primary.
+	Subscribe,
+}
+
+/// A message of the primary.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub(crate) enum Message {
+	/// Sent right after the connection is accepted.
+	Hello { version: u32, ref_counting: bool },
+	/// The value of the key requested by [`Request::Get`] with the same `id`.
+	Value { id: u64, value: Option<Vec<u8>> },
+	/// The keys changed by a commit, as `(column, key)`.
+	Commit { changes: Vec<(ColumnId, Vec<u8>)> },
+	/// Sent to subscribed replicas while there are no commits.
+	Heartbeat,
+}
+
+fn frame(message: &impl Encode) -> io::Result<Vec<u8>> {
+	let encoded = message.encode();
+	if encoded.len() > MAX_FRAME_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "Message too large"))
+	}
+	let mut frame = (encoded.len() as u32).to_le_bytes().to_vec();
+	frame.extend(encoded);
+	Ok(frame)
+}
+
+fn check_len(len: [u8; 4]) -> io::Result<usize> {
+	let len = u32::from_le_bytes(len) as usize;
+	if len > MAX_FRAME_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "Frame too large"))
+	}
+	Ok(len)
+}
+
+fn decode<T: Decode>(bytes: &[u8]) -> io::Result<T> {
+	T::decode(&mut &bytes[..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write `message` to a blocking stream.
+pub(crate) fn write(stream: &mut impl Write, message: &impl Encode) -> io::Result<()> {
+	stream.write_all(&frame(message)?)?;
+	stream.flush()
+}
+
+/// Read a message from a blocking stream.
+pub(crate) fn read<T: Decode>(stream: &mut impl Read) -> io::Result<T> {
+	let mut len = [0; 4];
+	stream.read_exact(&mut len)?;
+	let mut bytes = vec![0; check_len(len)?];
+	stream.read_exact(&mut bytes)?;
+	decode(&bytes)
+}
+
+/// Write `message` to an async stream.
+pub(crate) async fn write_async(
+	stream: &mut (impl AsyncWrite + Unpin),
+	message: &impl Encode,
+) -> io::Result<()> {
+	stream.write_all(&frame(message)?).await?;
+	stream.flush().await
+}
+
+/// Read a message from an async stream.
+pub(crate) async fn read_async<T: Decode>(stream: &mut (impl AsyncRead + Unpin)) -> io::Result<T> {
+	let mut len = [0; 4];
+	stream.read_exact(&mut len).await?;
+	let mut bytes = vec![0; check_len(len)?];
+	stream.read_exact(&mut bytes).await?;
+	decode(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frames_roundtrip() {
+		let messages = vec![
+			Message::Hello { version: PROTOCOL_VERSION, ref_counting: true },
+			Message::Value { id: 3, value: Some(vec![1, 2, 3]) },
+			Message::Commit { changes: vec![(1, vec![4, 5])] },
+			Message::Heartbeat,
+		];
+		let mut buffer = Vec::new();
+		for message in &messages {
+			write(&mut buffer, message).unwrap();
+		}
+
+		let mut stream = &buffer[..];
+		for message in messages {
+			assert_eq!(read::<Message>(&mut stream).unwrap(), message);
+		}
+		assert!(read::<Message>(&mut stream).is_err());
+	}
+
+	#[test]
+	fn oversized_frames_are_rejected() {
+		let mut stream = &u32::MAX.to_le_bytes()[..];
+		assert_eq!(read::<Message>(&mut stream).unwrap_err().kind(), io::ErrorKind::InvalidData);
+	}
+}
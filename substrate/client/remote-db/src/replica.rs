@@ -0,0 +1,355 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The replica side: a read-only database served by a primary node.
+
+use crate::{
+	protocol::{self, Message, Request, PROTOCOL_VERSION},
+	LOG_TARGET,
+};
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+use sp_database::{error, ColumnId, Database, Transaction};
+use std::{
+	io,
+	net::{TcpStream, ToSocketAddrs},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Weak,
+	},
+	time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+/// Delay before reconnecting the commit subscription.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The configuration of a [`RemoteDatabase`].
+#[derive(Debug, Clone)]
+pub struct ReplicaConfig {
+	/// The address of the primary, as `host:port`.
+	pub primary: String,
+	/// The number of connections used for reads.
+	pub connections: usize,
+	/// The number of values cached.
+	pub cache_size: u32,
+	/// The maximum time cached values are served without hearing from the primary.
+	///
+	/// Values are removed from the cache as soon as the primary reports a commit changing them.
+	/// If the commit subscription breaks, the cached values are not served for longer than this,
+	/// reads go to the primary instead.
+	pub max_staleness: Duration,
+	/// Timeout of connecting to the primary and of a single read.
+	pub request_timeout: Duration,
+}
+
+/// The cached values, invalidated by the commits of the primary.
+struct Cache {
+	values: LruMap<(ColumnId, Vec<u8>), Option<Vec<u8>>>,
+	/// Incremented on every invalidation, so reads racing with a commit aren't cached.
+	epoch: u64,
+	/// When the primary was last heard of on the commit subscription.
+	last_heard: Option<Instant>,
+}
+
+impl Cache {
+	fn is_fresh(&self, max_staleness: Duration) -> bool {
+		self.last_heard.is_some_and(|last_heard| last_heard.elapsed() <= max_staleness)
+	}
+
+	fn invalidate(&mut self, changes: Vec<(ColumnId, Vec<u8>)>) {
+		for key in changes {
+			self.values.remove(&key);
+		}
+		self.epoch += 1;
+	}
+
+	fn clear(&mut self) {
+		self.values.clear();
+		self.epoch += 1;
+	}
+}
+
+/// State shared with the commit subscription.
+struct Shared {
+	cache: Mutex<Cache>,
+	/// Counts the commits of the primary.
+	commits: watch::Sender<u64>,
+}
+
+impl Shared {
+	fn on_commit(&self) {
+		self.commits.send_modify(|commits| *commits += 1);
+	}
+}
+
+/// A connection used for reads.
+struct Connection {
+	stream: TcpStream,
+	next_id: u64,
+}
+
+impl Connection {
+	fn get(&mut self, column: ColumnId, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+		let id = self.next_id;
+		self.next_id += 1;
+		protocol::write(&mut self.stream, &Request::Get { id, column, key: key.to_vec() })?;
+		match protocol::read(&mut self.stream)? {
+			Message::Value { id: response_id, value } if response_id == id => Ok(value),
+			_ => Err(io::Error::new(io::ErrorKind::InvalidData, "Unexpected response")),
+		}
+	}
+}
+
+/// Connect to the primary at `addr`, returning the stream and whether the primary database
+/// supports reference counting.
+fn connect(addr: &str, timeout: Duration) -> io::Result<(TcpStream, bool)> {
+	let addr = addr
+		.to_socket_addrs()?
+		.next()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Primary address not resolved"))?;
+	let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+	stream.set_nodelay(true)?;
+	stream.set_read_timeout(Some(timeout))?;
+	stream.set_write_timeout(Some(timeout))?;
+	match protocol::read(&mut stream)? {
+		Message::Hello { version: PROTOCOL_VERSION, ref_counting } => Ok((stream, ref_counting)),
+		Message::Hello { version, .. } => Err(io::Error::new(
+			io::ErrorKind::Unsupported,
+			format!("Unsupported protocol version {version} of the primary"),
+		)),
+		_ => Err(io::Error::new(io::ErrorKind::InvalidData, "Expected hello from the primary")),
+	}
+}
+
+/// A read-only database served by a primary node over the network.
+///
+/// Reads are cached locally. The cache is kept coherent by a subscription to the commits of the
+/// primary, running on a background thread.
+pub struct RemoteDatabase {
+	config: ReplicaConfig,
+	shared: Arc<Shared>,
+	connections: Vec<Mutex<Option<Connection>>>,
+	next_connection: AtomicUsize,
+	ref_counting: bool,
+}
+
+impl RemoteDatabase {
+	/// Connect to the primary and subscribe to its commits.
+	pub fn connect(config: ReplicaConfig) -> io::Result<Arc<Self>> {
+		let (stream, ref_counting) = connect(&config.primary, config.request_timeout)?;
+		let shared = Arc::new(Shared {
+			cache: Mutex::new(Cache {
+				values: LruMap::new(ByLength::new(config.cache_size)),
+				epoch: 0,
+				last_heard: None,
+			}),
+			commits: watch::channel(0).0,
+		});
+		let mut connections: Vec<_> =
+			(1..config.connections.max(1)).map(|_| Mutex::new(None)).collect();
+		connections.push(Mutex::new(Some(Connection { stream, next_id: 0 })));
+
+		let weak = Arc::downgrade(&shared);
+		let subscription_config = config.clone();
+		std::thread::Builder::new()
+			.name("remote-db-subscription".into())
+			.spawn(move || subscribe(weak, subscription_config))?;
+
+		Ok(Arc::new(Self {
+			config,
+			shared,
+			connections,
+			next_connection: AtomicUsize::new(0),
+			ref_counting,
+		}))
+	}
+
+	/// A receiver notified after every commit of the primary.
+	///
+	/// The values changed by the commit are already invalidated when the receiver is notified.
+	pub fn commits(&self) -> watch::Receiver<u64> {
+		self.shared.commits.subscribe()
+	}
+
+	/// Read `key` of `column` from the primary, reconnecting once if the connection broke.
+	fn fetch(&self, column: ColumnId, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+		let index = self.next_connection.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+		let mut connection = self.connections[index].lock();
+		if let Some(value) = connection.as_mut().and_then(|c| c.get(column, key).ok()) {
+			return Ok(value)
+		}
+
+		let (stream, _) = connect(&self.config.primary, self.config.request_timeout)?;
+		let connection = connection.insert(Connection { stream, next_id: 0 });
+		connection.get(column, key)
+	}
+}
+
+impl<H: Clone + AsRef<[u8]>> Database<H> for RemoteDatabase {
+	fn commit(&self, transaction: Transaction<H>) -> error::Result<()> {
+		// The backend commits an empty transaction when it is opened.
+		if transaction.0.is_empty() {
+			return Ok(())
+		}
+		Err(error::DatabaseError(Box::new(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			"The database of a replica is read-only",
+		))))
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+		let cache_key = (col, key.to_vec());
+		let epoch = {
+			let mut cache = self.shared.cache.lock();
+			if cache.is_fresh(self.config.max_staleness) {
+				if let Some(value) = cache.values.get(&cache_key) {
+					return value.clone()
+				}
+			}
+			cache.epoch
+		};
+
+		let value = match self.fetch(col, key) {
+			Ok(value) => value,
+			Err(e) => {
+				log::error!(target: LOG_TARGET, "Failed to read from the primary: {e}");
+				return None
+			},
+		};
+
+		let mut cache = self.shared.cache.lock();
+		if cache.epoch == epoch && cache.is_fresh(self.config.max_staleness) {
+			cache.values.insert(cache_key, value.clone());
+		}
+		value
+	}
+
+	fn supports_ref_counting(&self) -> bool {
+		self.ref_counting
+	}
+}
+
+/// Follow the commits of the primary until the database is dropped.
+fn subscribe(shared: Weak<Shared>, config: ReplicaConfig) {
+	loop {
+		let Some(shared) = shared.upgrade() else { return };
+		match follow_commits(&shared, &config) {
+			Ok(()) => return,
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Commit subscription to the primary broke: {e}")
+			},
+		}
+		let mut cache = shared.cache.lock();
+		cache.last_heard = None;
+		cache.clear();
+		drop(cache);
+		// The commits missed while disconnected may have changed the chain.
+		shared.on_commit();
+		drop(shared);
+		std::thread::sleep(RECONNECT_DELAY);
+	}
+}
+
+/// Invalidate the cache on every commit of the primary, until the subscription breaks or the
+/// database is dropped.
+fn follow_commits(shared: &Arc<Shared>, config: &ReplicaConfig) -> io::Result<()> {
+	let (mut stream, _) = connect(&config.primary, config.request_timeout)?;
+	stream.set_read_timeout(Some(config.max_staleness.max(config.request_timeout)))?;
+	protocol::write(&mut stream, &Request::Subscribe)?;
+
+	// Values cached before the subscription may have been changed by missed commits.
+	let mut cache = shared.cache.lock();
+	cache.clear();
+	cache.last_heard = Some(Instant::now());
+	drop(cache);
+
+	while Arc::strong_count(shared) > 1 {
+		let message = protocol::read(&mut stream)?;
+		let mut cache = shared.cache.lock();
+		cache.last_heard = Some(Instant::now());
+		match message {
+			Message::Commit { changes } => {
+				cache.invalidate(changes);
+				drop(cache);
+				shared.on_commit();
+			},
+			Message::Heartbeat => (),
+			_ => return Err(io::Error::new(io::ErrorKind::InvalidData, "Unexpected message")),
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{PrimaryDatabase, RemoteDbServer};
+	use sc_client_db::DbHash;
+	use sp_database::MemDb;
+
+	fn set(database: &dyn Database<DbHash>, key: &[u8], value: &[u8]) {
+		let mut transaction = Transaction::new();
+		transaction.set(0, key, value);
+		database.commit(transaction).unwrap();
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn replica_follows_primary() {
+		let primary = Arc::new(PrimaryDatabase::<DbHash>::new(Arc::new(MemDb::default())));
+		set(&*primary, b"key", b"first");
+		let server = RemoteDbServer::bind(primary.clone(), "127.0.0.1:0".parse().unwrap())
+			.await
+			.unwrap();
+		let config = ReplicaConfig {
+			primary: server.local_addr().unwrap().to_string(),
+			connections: 2,
+			cache_size: 16,
+			max_staleness: Duration::from_secs(5),
+			request_timeout: Duration::from_secs(5),
+		};
+		tokio::spawn(server.run());
+
+		let replica = tokio::task::spawn_blocking(move || {
+			let replica = RemoteDatabase::connect(config).unwrap();
+			while !replica.shared.cache.lock().is_fresh(Duration::from_secs(5)) {
+				std::thread::sleep(Duration::from_millis(10));
+			}
+			replica
+		})
+		.await
+		.unwrap();
+		let mut commits = replica.commits();
+
+		let read = |replica: Arc<RemoteDatabase>| {
+			tokio::task::spawn_blocking(move || Database::<DbHash>::get(&*replica, 0, b"key"))
+		};
+		assert_eq!(read(replica.clone()).await.unwrap(), Some(b"first".to_vec()));
+		// Served from the cache.
+		assert_eq!(read(replica.clone()).await.unwrap(), Some(b"first".to_vec()));
+
+		set(&*primary, b"key", b"second");
+		commits.changed().await.unwrap();
+		assert_eq!(read(replica.clone()).await.unwrap(), Some(b"second".to_vec()));
+
+		let mut transaction = Transaction::<DbHash>::new();
+		transaction.set(0, b"key", b"third");
+		assert!(replica.commit(transaction).is_err());
+		assert!(replica.commit(Transaction::<DbHash>::new()).is_ok());
+	}
+}
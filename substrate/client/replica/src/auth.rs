@@ -0,0 +1,207 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mutual authentication of the upstream node and a replica with their shared secret.
+//!
+//! The upstream node sends a random nonce, the replica answers with its own random nonce and the
+//! HMAC-SHA256 of both nonces, then the upstream node answers with the HMAC-SHA256 of both nonces
+//! in the reverse order. Each side uses its own label, so an answer can't be reflected.
+//!
+//! Both sides then derive a key per direction from the secret and the nonces, and authenticate
+//! every frame with the HMAC-SHA256 of its data and of its position in the stream. Frames can't be
+//! forged, replayed, reordered or moved to another connection.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of the nonces and of the HMACs.
+const LENGTH: usize = 32;
+
+/// The length of the MAC following every frame.
+pub(crate) const MAC_LENGTH: usize = LENGTH;
+
+const UPSTREAM_LABEL: &[u8] = b"replica-upstream";
+const REPLICA_LABEL: &[u8] = b"replica-downstream";
+const UPSTREAM_FRAMES_LABEL: &[u8] = b"replica-upstream-frames";
+const REPLICA_FRAMES_LABEL: &[u8] = b"replica-downstream-frames";
+
+fn mac(secret: &[u8], label: &[u8], first: &[u8; LENGTH], second: &[u8; LENGTH]) -> HmacSha256 {
+	let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length; qed");
+	mac.update(label);
+	mac.update(first);
+	mac.update(second);
+	mac
+}
+
+/// Authenticates the frames sent in one direction of a connection.
+pub(crate) struct FrameMac {
+	key: HmacSha256,
+	/// The number of frames authenticated so far.
+	counter: u64,
+}
+
+impl FrameMac {
+	fn new(
+		secret: &[u8],
+		label: &[u8],
+		upstream_nonce: &[u8; LENGTH],
+		replica_nonce: &[u8; LENGTH],
+	) -> Self {
+		let key = mac(secret, label, upstream_nonce, replica_nonce).finalize().into_bytes();
+		let key = HmacSha256::new_from_slice(&key).expect("HMAC accepts keys of any length; qed");
+		Self { key, counter: 0 }
+	}
+
+	fn frame_mac(&mut self, data: &[u8]) -> HmacSha256 {
+		let mut mac = self.key.clone();
+		mac.update(&self.counter.to_le_bytes());
+		mac.update(data);
+		self.counter += 1;
+		mac
+	}
+
+	/// The MAC of the next frame sent.
+	pub(crate) fn sign(&mut self, data: &[u8]) -> [u8; LENGTH] {
+		self.frame_mac(data).finalize().into_bytes().into()
+	}
+
+	/// Check the MAC of the next frame received.
+	pub(crate) fn verify(&mut self, data: &[u8], mac: &[u8]) -> io::Result<()> {
+		self.frame_mac(data)
+			.verify_slice(mac)
+			.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid frame MAC"))
+	}
+}
+
+/// The MACs of an authenticated connection.
+pub(crate) struct Session {
+	/// Authenticates the frames sent by this side.
+	pub send: FrameMac,
+	/// Authenticates the frames received from the other side.
+	pub receive: FrameMac,
+}
+
+async fn read<S: AsyncRead + Unpin>(stream: &mut S) -> io::Result<[u8; LENGTH]> {
+	let mut bytes = [0; LENGTH];
+	stream.read_exact(&mut bytes).await?;
+	Ok(bytes)
+}
+
+/// Authenticate the replica connected through `stream`, as the upstream node.
+pub(crate) async fn authenticate_replica<S: AsyncRead + AsyncWrite + Unpin>(
+	stream: &mut S,
+	secret: &[u8],
+) -> io::Result<Session> {
+	let upstream_nonce: [u8; LENGTH] = rand::random();
+	stream.write_all(&upstream_nonce).await?;
+	let replica_nonce = read(stream).await?;
+	let replica_mac = read(stream).await?;
+	mac(secret, REPLICA_LABEL, &upstream_nonce, &replica_nonce)
+		.verify_slice(&replica_mac)
+		.map_err(|_| io::Error::new(io::ErrorKind::PermissionDenied, "Invalid replica secret"))?;
+
+	let upstream_mac = mac(secret, UPSTREAM_LABEL, &replica_nonce, &upstream_nonce).finalize();
+	stream.write_all(&upstream_mac.into_bytes()).await?;
+
+	Ok(Session {
+		send: FrameMac::new(secret, UPSTREAM_FRAMES_LABEL, &upstream_nonce, &replica_nonce),
+		receive: FrameMac::new(secret, REPLICA_FRAMES_LABEL, &upstream_nonce, &replica_nonce),
+	})
+}
+
+/// Authenticate the upstream node connected through `stream`, as a replica.
+pub(crate) async fn authenticate_upstream<S: AsyncRead + AsyncWrite + Unpin>(
+	stream: &mut S,
+	secret: &[u8],
+) -> io::Result<Session> {
+	let upstream_nonce = read(stream).await?;
+	let replica_nonce: [u8; LENGTH] = rand::random();
+	let replica_mac = mac(secret, REPLICA_LABEL, &upstream_nonce, &replica_nonce).finalize();
+	stream.write_all(&replica_nonce).await?;
+	stream.write_all(&replica_mac.into_bytes()).await?;
+
+	let upstream_mac = read(stream).await?;
+	mac(secret, UPSTREAM_LABEL, &replica_nonce, &upstream_nonce)
+		.verify_slice(&upstream_mac)
+		.map_err(|_| io::Error::new(io::ErrorKind::PermissionDenied, "Invalid upstream secret"))?;
+
+	Ok(Session {
+		send: FrameMac::new(secret, REPLICA_FRAMES_LABEL, &upstream_nonce, &replica_nonce),
+		receive: FrameMac::new(secret, UPSTREAM_FRAMES_LABEL, &upstream_nonce, &replica_nonce),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn handshake(upstream_secret: &[u8], replica_secret: &[u8]) -> (bool, bool) {
+		let (mut upstream, mut replica) = tokio::io::duplex(1024);
+		let upstream = async move {
+			// Dropping the stream on failure lets the replica notice it.
+			authenticate_replica(&mut upstream, upstream_secret).await.is_ok()
+		};
+		let replica =
+			async move { authenticate_upstream(&mut replica, replica_secret).await.is_ok() };
+		futures::join!(upstream, replica)
+	}
+
+	async fn sessions() -> (Session, Session) {
+		let (mut upstream, mut replica) = tokio::io::duplex(1024);
+		let (upstream, replica) = futures::join!(
+			authenticate_replica(&mut upstream, b"secret"),
+			authenticate_upstream(&mut replica, b"secret"),
+		);
+		(upstream.unwrap(), replica.unwrap())
+	}
+
+	#[tokio::test]
+	async fn handshake_requires_the_same_secret() {
+		assert_eq!(handshake(b"secret", b"secret").await, (true, true));
+		assert_eq!(handshake(b"secret", b"other").await, (false, false));
+	}
+
+	#[tokio::test]
+	async fn frames_are_authenticated() {
+		let (mut upstream, mut replica) = sessions().await;
+		let first = upstream.send.sign(b"first");
+		let second = upstream.send.sign(b"second");
+		assert!(replica.receive.verify(b"first", &first).is_ok());
+		assert!(replica.receive.verify(b"second", &second).is_ok());
+
+		// Modified frames are rejected.
+		let (mut upstream, mut replica) = sessions().await;
+		let first = upstream.send.sign(b"first");
+		assert!(replica.receive.verify(b"forged", &first).is_err());
+
+		// Frames out of order are rejected.
+		let (mut upstream, mut replica) = sessions().await;
+		let _ = upstream.send.sign(b"first");
+		let second = upstream.send.sign(b"second");
+		assert!(replica.receive.verify(b"second", &second).is_err());
+
+		// Frames reflected to their sender are rejected.
+		let (mut upstream, _) = sessions().await;
+		let first = upstream.send.sign(b"first");
+		assert!(upstream.receive.verify(b"first", &first).is_err());
+	}
+}
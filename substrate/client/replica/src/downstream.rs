@@ -0,0 +1,264 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A read replica, importing the blocks streamed by its upstream node.
+
+use crate::{
+	auth,
+	message::{read_message, write_frame, Message, StreamedBlock},
+	LOG_TARGET,
+};
+use codec::Encode;
+use log::{debug, info, warn};
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, F64, U64};
+use sc_client_api::{Backend, Finalizer};
+use sc_consensus::{
+	BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult, StateAction, StorageChanges,
+};
+use sp_api::CallApiAt;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_core::storage::ChildInfo;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, SaturatedConversion},
+	Justification,
+};
+use sp_state_machine::Backend as StateBackend;
+use std::{
+	io,
+	marker::PhantomData,
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpStream;
+
+/// Delay before reconnecting to the upstream node.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+struct Metrics {
+	lag_blocks: Gauge<U64>,
+	lag_seconds: Gauge<F64>,
+	imported_blocks: Counter<U64>,
+	failed_blocks: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			lag_blocks: register(
+				Gauge::new(
+					"substrate_replica_lag_blocks",
+					"Number of blocks the replica is behind its upstream node",
+				)?,
+				registry,
+			)?,
+			lag_seconds: register(
+				Gauge::new(
+					"substrate_replica_lag_seconds",
+					"Time between the upstream node sending the last block and the replica \
+					 importing it",
+				)?,
+				registry,
+			)?,
+			imported_blocks: register(
+				Counter::new(
+					"substrate_replica_imported_blocks_total",
+					"Number of streamed blocks imported by the replica",
+				)?,
+				registry,
+			)?,
+			failed_blocks: register(
+				Counter::new(
+					"substrate_replica_failed_blocks_total",
+					"Number of streamed blocks the replica failed to import",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Follows an upstream node, importing the blocks it streams without executing them.
+pub struct ReplicaDownstream<Block: BlockT, BE, C> {
+	client: Arc<C>,
+	upstream: String,
+	secret: Vec<u8>,
+	metrics: Option<Metrics>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+impl<Block, BE, C> ReplicaDownstream<Block, BE, C>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	C: HeaderBackend<Block> + CallApiAt<Block> + BlockImport<Block> + Finalizer<Block, BE>,
+{
+	/// Create a new replica of the upstream node at `upstream`, given as `HOST:PORT`.
+	pub fn new(
+		client: Arc<C>,
+		upstream: String,
+		secret: Vec<u8>,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			client,
+			upstream,
+			secret,
+			metrics: if let Some(registry) = registry {
+				Some(Metrics::register(registry)?)
+			} else {
+				None
+			},
+			_phantom: PhantomData,
+		})
+	}
+
+	/// Follow the upstream node, reconnecting whenever the connection is lost.
+	pub async fn run(self) {
+		loop {
+			let error = self.follow().await;
+			warn!(
+				target: LOG_TARGET,
+				"Lost the upstream node {}, reconnecting in {RECONNECT_DELAY:?}: {error}",
+				self.upstream,
+			);
+			futures_timer::Delay::new(RECONNECT_DELAY).await;
+		}
+	}
+
+	/// Connect to the upstream node and import the streamed blocks, until the connection fails.
+	async fn follow(&self) -> io::Error {
+		let mut stream = match TcpStream::connect(&self.upstream).await {
+			Ok(stream) => stream,
+			Err(e) => return e,
+		};
+		let mut session = match auth::authenticate_upstream(&mut stream, &self.secret).await {
+			Ok(session) => session,
+			Err(e) => return e,
+		};
+		info!(target: LOG_TARGET, "📡 Following the upstream node {}", self.upstream);
+
+		let best = self.client.info().best_number;
+		if let Err(e) = write_frame(&mut stream, &mut session.send, &best.encode()).await {
+			return e
+		}
+		loop {
+			match read_message(&mut stream, &mut session.receive).await {
+				Ok(Message::Block(block)) => self.on_block(block).await,
+				Ok(Message::Finalized { hash, justification }) =>
+					self.finalize(hash, justification),
+				Err(e) => return e,
+			}
+		}
+	}
+
+	async fn on_block(&self, block: StreamedBlock<Block>) {
+		let hash = block.header.hash();
+		let number = *block.header.number();
+		let upstream_best = block.upstream_best;
+		let sent_at = block.sent_at;
+
+		let result = self.import(block).await;
+		match &result {
+			Ok(()) => debug!(target: LOG_TARGET, "Imported streamed block #{number} ({hash})"),
+			Err(e) => warn!(
+				target: LOG_TARGET,
+				"Failed to import streamed block #{number} ({hash}): {e}",
+			),
+		}
+
+		if let Some(metrics) = &self.metrics {
+			if result.is_ok() {
+				metrics.imported_blocks.inc();
+			} else {
+				metrics.failed_blocks.inc();
+			}
+			let best = self.client.info().best_number;
+			metrics.lag_blocks.set(upstream_best.saturating_sub(best).saturated_into());
+			let now = SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map_or(0, |since| since.as_millis() as u64);
+			metrics.lag_seconds.set(now.saturating_sub(sent_at) as f64 / 1000.0);
+		}
+	}
+
+	/// Import a streamed block, applying its storage changes on top of the state of its parent.
+	async fn import(&self, block: StreamedBlock<Block>) -> Result<(), String> {
+		let hash = block.header.hash();
+		if self.client.status(hash).map_err(|e| e.to_string())? ==
+			sp_blockchain::BlockStatus::InChain
+		{
+			return Ok(())
+		}
+
+		let parent_hash = *block.header.parent_hash();
+		let state_version = self
+			.client
+			.runtime_version_at(parent_hash)
+			.map_err(|e| e.to_string())?
+			.state_version();
+		let child_infos = block
+			.child_changes
+			.iter()
+			.map(|(storage_key, _)| ChildInfo::new_default(storage_key))
+			.collect::<Vec<_>>();
+		let (root, transaction) = {
+			let state = self.client.state_at(parent_hash).map_err(|e| e.to_string())?;
+			let delta = block.main_changes.iter().map(|(key, value)| (&key[..], value.as_deref()));
+			let child_deltas = child_infos.iter().zip(block.child_changes.iter()).map(
+				|(child_info, (_, changes))| {
+					(child_info, changes.iter().map(|(key, value)| (&key[..], value.as_deref())))
+				},
+			);
+			state.full_storage_root(delta, child_deltas, state_version)
+		};
+		if &root != block.header.state_root() {
+			return Err(format!(
+				"State root mismatch: header {}, computed {root}",
+				block.header.state_root()
+			))
+		}
+
+		let changes = sp_state_machine::StorageChanges {
+			main_storage_changes: Arc::unwrap_or_clone(block.main_changes),
+			child_storage_changes: Arc::unwrap_or_clone(block.child_changes),
+			offchain_storage_changes: Default::default(),
+			transaction,
+			transaction_storage_root: root,
+			transaction_index_changes: Vec::new(),
+		};
+		let mut params = BlockImportParams::new(BlockOrigin::NetworkBroadcast, block.header);
+		params.body = Some(block.body);
+		params.justifications = block.justifications;
+		params.state_action = StateAction::ApplyChanges(StorageChanges::Changes(changes));
+		params.fork_choice = Some(ForkChoiceStrategy::Custom(block.is_new_best));
+
+		match self.client.import_block(params).await.map_err(|e| e.to_string())? {
+			ImportResult::Imported(_) | ImportResult::AlreadyInChain => Ok(()),
+			ImportResult::KnownBad => Err("Block is known to be bad".into()),
+			ImportResult::UnknownParent => Err("Unknown parent".into()),
+			ImportResult::MissingState => Err("Missing state of the parent".into()),
+		}
+	}
+
+	fn finalize(&self, hash: Block::Hash, justification: Option<Justification>) {
+		if let Err(e) = self.client.finalize_block(hash, justification, true) {
+			debug!(target: LOG_TARGET, "Failed to finalize streamed block {hash}: {e}");
+		}
+	}
+}
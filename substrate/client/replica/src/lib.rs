@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Streaming of imported blocks from a trusted upstream node to read replicas.
+//!
+//! Large RPC farms run many nodes that only serve reads of the same chain. Instead of having every
+//! one of them verify and execute every block, a designated upstream node streams the blocks it
+//! imports, together with the changes they make to the storage, to its replicas:
+//!
+//! - [`ReplicaUpstream`] accepts the replicas and pushes them every block imported by the node,
+//!   starting with the recent blocks they are missing, and the finalized blocks.
+//! - [`ReplicaDownstream`] follows an upstream node and imports the streamed blocks without
+//!   executing them. The new state root is computed from the streamed changes and the block is
+//!   rejected if it doesn't match the root of its header, so a replica never ends up with a state
+//!   that differs from the header.
+//!
+//! The consensus checks of the streamed blocks are skipped, the upstream node is trusted to only
+//! stream valid blocks. Upstream and replicas authenticate each other with a shared secret when
+//! connecting, and every frame of the stream is authenticated with keys derived from it. The
+//! stream itself is not encrypted, it should only cross trusted networks.
+//!
+//! As streamed blocks bypass the block import of the consensus, the consensus data of a replica,
+//! like the BABE epochs or the GRANDPA authority set, is not kept up to date. Replicas must
+//! neither import blocks from the network nor take part in the consensus or finality, so nodes
+//! following an upstream node stay out of the peer-to-peer network. Blocks imported by the
+//! upstream node during its initial sync are not streamed, replicas further behind than the
+//! recent blocks kept by the upstream node have to be restored from a copy of its database.
+
+mod auth;
+mod downstream;
+mod message;
+mod upstream;
+
+pub use downstream::ReplicaDownstream;
+pub use upstream::ReplicaUpstream;
+
+use clap::Args;
+use std::{net::SocketAddr, path::PathBuf};
+
+const LOG_TARGET: &str = "replica";
+
+/// Parameters of the streaming of blocks to read replicas.
+#[derive(Default, Debug, Clone, Args)]
+pub struct ReplicaParams {
+	/// Stream the imported blocks to the read replicas connecting to this address.
+	///
+	/// Requires `--replica-secret-file`.
+	#[arg(long, value_name = "ADDR", requires = "replica_secret_file")]
+	pub replica_listen_addr: Option<SocketAddr>,
+
+	/// Import the blocks streamed by the upstream node at this address, as `HOST:PORT`, without
+	/// verifying and executing them.
+	///
+	/// The node doesn't connect to the peer-to-peer network and doesn't run GRANDPA. Only follow
+	/// an upstream node you trust. Requires `--replica-secret-file`.
+	#[arg(long, value_name = "HOST:PORT", requires = "replica_secret_file")]
+	pub replica_upstream: Option<String>,
+
+	/// File containing the secret shared by the upstream node and its replicas.
+	///
+	/// Leading and trailing whitespace is ignored.
+	#[arg(long, value_name = "PATH")]
+	pub replica_secret_file: Option<PathBuf>,
+}
+
+impl ReplicaParams {
+	/// Read the secret shared by the upstream node and its replicas.
+	pub fn secret(&self) -> Result<Vec<u8>, String> {
+		let path = self.replica_secret_file.as_ref().ok_or("No replica secret file given")?;
+		let secret = std::fs::read_to_string(path)
+			.map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+		let secret = secret.trim();
+		if secret.is_empty() {
+			return Err(format!("Replica secret file {} is empty", path.display()))
+		}
+		Ok(secret.as_bytes().to_vec())
+	}
+}
@@ -0,0 +1,139 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Messages streamed from the upstream node to the replicas.
+
+use crate::auth::{FrameMac, MAC_LENGTH};
+use codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor},
+	Justification, Justifications,
+};
+use sp_state_machine::{ChildStorageCollection, StorageCollection};
+use std::{io, sync::Arc};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The maximum size of a message.
+const MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// A message of the upstream node.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub(crate) enum Message<Block: BlockT> {
+	/// A block imported by the upstream node.
+	Block(StreamedBlock<Block>),
+	/// A block finalized by the upstream node.
+	Finalized { hash: Block::Hash, justification: Option<Justification> },
+}
+
+/// A block imported by the upstream node, together with the changes it makes to the storage.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub(crate) struct StreamedBlock<Block: BlockT> {
+	pub header: Block::Header,
+	pub body: Vec<Block::Extrinsic>,
+	pub justifications: Option<Justifications>,
+	/// Whether the block became the best block of the upstream node.
+	pub is_new_best: bool,
+	/// The best block number of the upstream node when the block was sent.
+	pub upstream_best: NumberFor<Block>,
+	/// The time the block was sent, in milliseconds since the Unix epoch.
+	pub sent_at: u64,
+	pub main_changes: Arc<StorageCollection>,
+	pub child_changes: Arc<ChildStorageCollection>,
+}
+
+/// Write a length prefixed frame, followed by its MAC.
+pub(crate) async fn write_frame<S: AsyncWrite + Unpin>(
+	stream: &mut S,
+	mac: &mut FrameMac,
+	data: &[u8],
+) -> io::Result<()> {
+	stream.write_all(&(data.len() as u32).to_le_bytes()).await?;
+	stream.write_all(data).await?;
+	stream.write_all(&mac.sign(data)).await?;
+	stream.flush().await
+}
+
+/// Read a length prefixed frame and check its MAC.
+pub(crate) async fn read_frame<S: AsyncRead + Unpin>(
+	stream: &mut S,
+	mac: &mut FrameMac,
+) -> io::Result<Vec<u8>> {
+	let mut len = [0; 4];
+	stream.read_exact(&mut len).await?;
+	let len = u32::from_le_bytes(len) as usize;
+	if len > MAX_MESSAGE_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Message of {len} bytes")))
+	}
+	let mut data = vec![0; len];
+	stream.read_exact(&mut data).await?;
+	let mut frame_mac = [0; MAC_LENGTH];
+	stream.read_exact(&mut frame_mac).await?;
+	mac.verify(&data, &frame_mac)?;
+	Ok(data)
+}
+
+/// Read a length prefixed frame, check its MAC and decode it.
+pub(crate) async fn read_message<S: AsyncRead + Unpin, T: Decode>(
+	stream: &mut S,
+	mac: &mut FrameMac,
+) -> io::Result<T> {
+	let data = read_frame(stream, mac).await?;
+	T::decode(&mut &data[..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::auth::{authenticate_replica, authenticate_upstream};
+	use sp_runtime::traits::Header as _;
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	#[tokio::test]
+	async fn message_roundtrip() {
+		let message = Message::<Block>::Block(StreamedBlock {
+			header: Header::new(
+				1,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			),
+			body: vec![],
+			justifications: None,
+			is_new_best: true,
+			upstream_best: 1,
+			sent_at: 42,
+			main_changes: Arc::new(vec![(b"key".to_vec(), Some(b"value".to_vec()))]),
+			child_changes: Arc::new(vec![(b"child".to_vec(), vec![(b"key".to_vec(), None)])]),
+		});
+
+		let (mut upstream, mut replica) = tokio::io::duplex(1024);
+		let (upstream_session, replica_session) = futures::join!(
+			authenticate_replica(&mut upstream, b"secret"),
+			authenticate_upstream(&mut replica, b"secret"),
+		);
+		let (mut upstream_mac, mut replica_mac) =
+			(upstream_session.unwrap().send, replica_session.unwrap().receive);
+
+		write_frame(&mut upstream, &mut upstream_mac, &message.encode()).await.unwrap();
+		assert_eq!(
+			read_message::<_, Message<Block>>(&mut replica, &mut replica_mac).await.unwrap(),
+			message,
+		);
+	}
+}
@@ -0,0 +1,353 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The upstream node, streaming its imported blocks to the replicas.
+
+use crate::{
+	auth::{self, Session},
+	message::{read_message, write_frame, Message, StreamedBlock},
+	LOG_TARGET,
+};
+use codec::Encode;
+use futures::{FutureExt, StreamExt};
+use log::{debug, error, info, warn};
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+use sc_client_api::{
+	BlockBackend, BlockImportNotification, BlockStorageChanges, BlockStorageChangesEvents,
+	BlockchainEvents, FinalityNotification,
+};
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::SpawnNamed;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use std::{
+	collections::VecDeque,
+	io,
+	net::SocketAddr,
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+	net::{TcpListener, TcpStream},
+	sync::broadcast::{self, error::RecvError},
+};
+
+/// The number of recent blocks sent to the replicas when they connect.
+///
+/// Also the number of messages a replica can lag behind before being disconnected.
+const CATCH_UP_BLOCKS: usize = 1024;
+
+/// The time a connecting replica has to authenticate and send its best block.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of storage changes kept while waiting for the import notification of their block.
+const PENDING_CHANGES: usize = 64;
+
+#[derive(Clone)]
+struct Metrics {
+	connected_replicas: Gauge<U64>,
+	streamed_blocks: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			connected_replicas: register(
+				Gauge::new("substrate_replica_connected", "Number of connected read replicas")?,
+				registry,
+			)?,
+			streamed_blocks: register(
+				Counter::new(
+					"substrate_replica_streamed_blocks_total",
+					"Number of blocks streamed to the read replicas",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// The encoded messages shared with the connections of the replicas.
+struct Shared<Block: BlockT> {
+	/// The recent blocks, sent to the replicas when they connect.
+	recent: VecDeque<(NumberFor<Block>, Arc<Vec<u8>>)>,
+	/// The last finalized block, sent to the replicas when they connect.
+	last_finalized: Option<Arc<Vec<u8>>>,
+	sender: broadcast::Sender<Arc<Vec<u8>>>,
+}
+
+/// Streams the blocks imported by the node to the read replicas.
+pub struct ReplicaUpstream<Block: BlockT, C> {
+	client: Arc<C>,
+	listen_addr: SocketAddr,
+	secret: Arc<[u8]>,
+	spawner: Box<dyn SpawnNamed>,
+	shared: Arc<Mutex<Shared<Block>>>,
+	metrics: Option<Metrics>,
+}
+
+impl<Block, C> ReplicaUpstream<Block, C>
+where
+	Block: BlockT,
+	C: BlockchainEvents<Block>
+		+ BlockStorageChangesEvents<Block>
+		+ BlockBackend<Block>
+		+ HeaderBackend<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	/// Create a new upstream node, accepting the replicas on `listen_addr`.
+	pub fn new(
+		client: Arc<C>,
+		listen_addr: SocketAddr,
+		secret: Vec<u8>,
+		spawner: impl SpawnNamed + 'static,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		let (sender, _) = broadcast::channel(CATCH_UP_BLOCKS);
+		Ok(Self {
+			client,
+			listen_addr,
+			secret: secret.into(),
+			spawner: Box::new(spawner),
+			shared: Arc::new(Mutex::new(Shared {
+				recent: VecDeque::with_capacity(CATCH_UP_BLOCKS),
+				last_finalized: None,
+				sender,
+			})),
+			metrics: if let Some(registry) = registry {
+				Some(Metrics::register(registry)?)
+			} else {
+				None
+			},
+		})
+	}
+
+	/// Accept the replicas and stream the blocks to them.
+	pub async fn run(self) {
+		let listener = match TcpListener::bind(self.listen_addr).await {
+			Ok(listener) => listener,
+			Err(e) => {
+				error!(target: LOG_TARGET, "Failed to listen on {}: {e}", self.listen_addr);
+				return
+			},
+		};
+		info!(target: LOG_TARGET, "📡 Streaming blocks to read replicas on {}", self.listen_addr);
+
+		futures::future::select(Box::pin(self.follow()), Box::pin(self.accept(listener))).await;
+	}
+
+	/// Follow the imported and finalized blocks of the client.
+	async fn follow(&self) {
+		let mut changes_stream = self.client.block_storage_changes_stream().fuse();
+		let mut import_stream = self.client.import_notification_stream().fuse();
+		let mut finality_stream = self.client.finality_notification_stream().fuse();
+		let mut pending = VecDeque::with_capacity(PENDING_CHANGES);
+
+		loop {
+			// The storage changes of a block are sent before its import notification.
+			futures::select_biased! {
+				changes = changes_stream.next() => {
+					let Some(changes) = changes else { return };
+					if pending.len() == PENDING_CHANGES {
+						pending.pop_front();
+					}
+					pending.push_back(changes);
+				},
+				notification = import_stream.next() => {
+					let Some(notification) = notification else { return };
+					self.on_import(notification, &mut pending);
+				},
+				notification = finality_stream.next() => {
+					let Some(notification) = notification else { return };
+					self.on_finality(notification);
+				},
+			}
+		}
+	}
+
+	fn on_import(
+		&self,
+		notification: BlockImportNotification<Block>,
+		pending: &mut VecDeque<BlockStorageChanges<Block::Hash>>,
+	) {
+		let hash = notification.hash;
+		let Some(changes) = pending
+			.iter()
+			.position(|changes| changes.hash == hash)
+			.and_then(|index| pending.remove(index))
+		else {
+			debug!(target: LOG_TARGET, "No storage changes for block {hash}, not streaming it");
+			return
+		};
+
+		let block = match self.streamed_block(notification, changes) {
+			Ok(block) => block,
+			Err(e) => {
+				warn!(target: LOG_TARGET, "Failed to stream block {hash}: {e}");
+				return
+			},
+		};
+		let number = *block.header.number();
+		let message = Arc::new(Message::Block(block).encode());
+
+		let mut shared = self.shared.lock();
+		if shared.recent.len() == CATCH_UP_BLOCKS {
+			shared.recent.pop_front();
+		}
+		shared.recent.push_back((number, message.clone()));
+		// Only fails if no replica is connected.
+		let _ = shared.sender.send(message);
+
+		if let Some(metrics) = &self.metrics {
+			metrics.streamed_blocks.inc();
+		}
+	}
+
+	fn streamed_block(
+		&self,
+		notification: BlockImportNotification<Block>,
+		changes: BlockStorageChanges<Block::Hash>,
+	) -> sp_blockchain::Result<StreamedBlock<Block>> {
+		let hash = notification.hash;
+		let body = self
+			.client
+			.block_body(hash)?
+			.ok_or_else(|| sp_blockchain::Error::UnknownBlock(format!("Body of {hash}")))?;
+		Ok(StreamedBlock {
+			header: notification.header,
+			body,
+			justifications: self.client.justifications(hash)?,
+			is_new_best: notification.is_new_best,
+			upstream_best: self.client.info().best_number,
+			sent_at: SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map_or(0, |since| since.as_millis() as u64),
+			main_changes: changes.main_changes,
+			child_changes: changes.child_changes,
+		})
+	}
+
+	fn on_finality(&self, notification: FinalityNotification<Block>) {
+		let justification = match self.client.justifications(notification.hash) {
+			Ok(justifications) => justifications.and_then(|j| j.into_iter().next()),
+			Err(e) => {
+				warn!(target: LOG_TARGET, "Failed to get the justifications of a block: {e}");
+				None
+			},
+		};
+		let message = Arc::new(
+			Message::<Block>::Finalized { hash: notification.hash, justification }.encode(),
+		);
+
+		let mut shared = self.shared.lock();
+		shared.last_finalized = Some(message.clone());
+		// Only fails if no replica is connected.
+		let _ = shared.sender.send(message);
+	}
+
+	/// Accept the replicas, serving each of them in its own task.
+	async fn accept(&self, listener: TcpListener) {
+		loop {
+			let (mut stream, address) = match listener.accept().await {
+				Ok(connection) => connection,
+				Err(e) => {
+					warn!(target: LOG_TARGET, "Failed to accept a replica: {e}");
+					continue
+				},
+			};
+			let secret = self.secret.clone();
+			let shared = self.shared.clone();
+			let metrics = self.metrics.clone();
+
+			let connection = async move {
+				let handshake = async {
+					let mut session = auth::authenticate_replica(&mut stream, &secret).await?;
+					let best: NumberFor<Block> =
+						read_message(&mut stream, &mut session.receive).await?;
+					Ok::<_, io::Error>((session, best))
+				};
+				let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake);
+				let (session, best) = match handshake.await {
+					Ok(Ok(handshake)) => handshake,
+					Ok(Err(e)) => {
+						warn!(target: LOG_TARGET, "Rejected replica {address}: {e}");
+						return
+					},
+					Err(_) => {
+						warn!(target: LOG_TARGET, "Replica {address} timed out when connecting");
+						return
+					},
+				};
+				info!(target: LOG_TARGET, "Replica {address} connected");
+
+				if let Some(metrics) = &metrics {
+					metrics.connected_replicas.inc();
+				}
+				let result = serve(stream, session, best, &shared).await;
+				if let Some(metrics) = &metrics {
+					metrics.connected_replicas.dec();
+				}
+
+				match result {
+					Ok(()) => info!(target: LOG_TARGET, "Replica {address} disconnected"),
+					Err(e) => info!(target: LOG_TARGET, "Replica {address} disconnected: {e}"),
+				}
+			};
+			self.spawner
+				.spawn("replica-upstream-connection", Some("replica"), connection.boxed());
+		}
+	}
+}
+
+/// Stream the blocks to an authenticated replica, starting with the recent blocks it is missing.
+async fn serve<Block: BlockT>(
+	mut stream: TcpStream,
+	mut session: Session,
+	best: NumberFor<Block>,
+	shared: &Mutex<Shared<Block>>,
+) -> io::Result<()> {
+	// Subscribing while holding the lock, no message is missed or sent twice.
+	let (catch_up, mut receiver) = {
+		let shared = shared.lock();
+		let catch_up = shared
+			.recent
+			.iter()
+			.filter(|(number, _)| *number > best)
+			.map(|(_, message)| message.clone())
+			.chain(shared.last_finalized.clone())
+			.collect::<Vec<_>>();
+		(catch_up, shared.sender.subscribe())
+	};
+	for message in catch_up {
+		write_frame(&mut stream, &mut session.send, &message).await?;
+	}
+
+	loop {
+		match receiver.recv().await {
+			Ok(message) => write_frame(&mut stream, &mut session.send, &message).await?,
+			Err(RecvError::Lagged(skipped)) =>
+				return Err(io::Error::new(
+					io::ErrorKind::Other,
+					format!("Replica lagged behind by {skipped} messages"),
+				)),
+			Err(RecvError::Closed) => return Ok(()),
+		}
+	}
+}
@@ -48,9 +48,15 @@ pub enum Error {
 	/// Invalid session keys encoding.
 	#[error("Session keys are not encoded correctly")]
 	InvalidSessionKeys,
+	/// No session keys are registered for the stash.
+	#[error("No session keys are registered for the stash")]
+	NoSessionKeys,
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
+	/// The idempotency key is too long.
+	#[error("The idempotency key is too long")]
+	IdempotencyKeyTooLong,
 }
 
 /// Base code for all authorship errors.
@@ -59,6 +65,8 @@ const BASE_ERROR: i32 = crate::error::base::AUTHOR;
 const BAD_FORMAT: i32 = BASE_ERROR + 1;
 /// Error during transaction verification in runtime.
 const VERIFICATION_ERROR: i32 = BASE_ERROR + 2;
+/// The idempotency key is too long.
+const IDEMPOTENCY_KEY_TOO_LONG: i32 = BASE_ERROR + 4;
 
 /// Pool rejected the transaction as invalid
 const POOL_INVALID_TX: i32 = BASE_ERROR + 10;
@@ -83,6 +91,8 @@ const POOL_NO_TAGS: i32 = POOL_INVALID_TX + 9;
 const POOL_INVALID_BLOCK_ID: i32 = POOL_INVALID_TX + 10;
 /// The pool is not accepting future transactions.
 const POOL_FUTURE_TX: i32 = POOL_INVALID_TX + 11;
+/// The call of the transaction is currently filtered by the runtime, e.g. paused.
+const POOL_CALL_FILTERED: i32 = POOL_INVALID_TX + 12;
 /// Other error.
 const OTHER_ERR: i32 = BASE_ERROR + 40;
 
@@ -101,6 +111,13 @@ impl From<Error> for ErrorObjectOwned {
 				format!("Verification Error: {}", e),
 				Some(format!("{:?}", e)),
 			),
+			Error::Pool(PoolError::InvalidTransaction(InvalidTransaction::Filtered)) => {
+				ErrorObject::owned(
+					POOL_CALL_FILTERED,
+					"Transaction call is filtered",
+					Some("The call is currently paused or otherwise filtered by the runtime"),
+				)
+			},
 			Error::Pool(PoolError::InvalidTransaction(InvalidTransaction::Custom(e))) => {
 				ErrorObject::owned(
 					POOL_INVALID_TX,
@@ -175,6 +192,11 @@ impl From<Error> for ErrorObjectOwned {
 					None::<()>,
 				)
 			},
+			Error::IdempotencyKeyTooLong => ErrorObject::owned(
+				IDEMPOTENCY_KEY_TOO_LONG,
+				"Idempotency key too long",
+				Some("The idempotency key is longer than 64 bytes"),
+			),
 			Error::UnsafeRpcCalled(e) => e.into(),
 			other => ErrorObject::owned(
 				OTHER_ERR,
@@ -20,18 +20,30 @@
 
 pub mod error;
 pub mod hash;
+pub mod session;
+pub mod status;
+pub mod validity;
 
 use error::Error;
 use jsonrpsee::proc_macros::rpc;
-use sc_transaction_pool_api::TransactionStatus;
 use sp_core::Bytes;
+use status::WatchedStatus;
 
 /// Substrate authoring RPC API
 #[rpc(client, server)]
 pub trait AuthorApi<Hash, BlockHash> {
 	/// Submit hex-encoded extrinsic for inclusion in block.
+	///
+	/// If an `idempotency_key` is given, retrying the submission of the same extrinsic with the
+	/// same key returns the hash of the original submission instead of an error, also from another
+	/// connection. Using the key for another extrinsic submits it as usual. The key is at most 64
+	/// bytes long and is remembered for 10 minutes.
 	#[method(name = "author_submitExtrinsic")]
-	async fn submit_extrinsic(&self, extrinsic: Bytes) -> Result<Hash, Error>;
+	async fn submit_extrinsic(
+		&self,
+		extrinsic: Bytes,
+		idempotency_key: Option<String>,
+	) -> Result<Hash, Error>;
 
 	/// Insert a key into the keystore.
 	#[method(name = "author_insertKey", with_extensions)]
@@ -49,6 +61,18 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[method(name = "author_hasSessionKeys", with_extensions)]
 	fn has_session_keys(&self, session_keys: Bytes) -> Result<bool, Error>;
 
+	/// Reports the status of the session keys of a stash against the local keystore.
+	///
+	/// `stash` is the SCALE encoded account id of the stash, whose session keys are looked up
+	/// in `Session::NextKeys` at the best block.
+	///
+	/// Returns one entry per registered key, stating whether its private key is present in the
+	/// keystore, followed by one entry per key found in the keystore under the same key types
+	/// that is not registered on-chain. Each entry reports when the key was generated and when
+	/// it last signed, as far as known to the keystore.
+	#[method(name = "author_sessionKeysStatus", with_extensions)]
+	fn session_keys_status(&self, stash: Bytes) -> Result<Vec<session::SessionKeyStatus>, Error>;
+
 	/// Checks if the keystore has private keys for the given public key and key type.
 	///
 	/// Returns `true` if a private key could be found.
@@ -59,6 +83,15 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[method(name = "author_pendingExtrinsics")]
 	fn pending_extrinsics(&self) -> Result<Vec<Bytes>, Error>;
 
+	/// Returns the validity window of a ready extrinsic in the pool.
+	///
+	/// Returns `None` if the extrinsic is not ready in the pool.
+	#[method(name = "author_extrinsicValidityWindow")]
+	fn extrinsic_validity_window(
+		&self,
+		hash: Hash,
+	) -> Result<Option<validity::ValidityWindow>, Error>;
+
 	/// Remove given extrinsic from the pool and temporarily ban it to prevent reimporting.
 	#[method(name = "author_removeExtrinsic", with_extensions)]
 	fn remove_extrinsic(
@@ -69,11 +102,12 @@ pub trait AuthorApi<Hash, BlockHash> {
 	/// Submit an extrinsic to watch.
 	///
 	/// See [`TransactionStatus`](sc_transaction_pool_api::TransactionStatus) for details on
-	/// transaction life cycle.
+	/// transaction life cycle. If the node delays the events by a confirmation depth, the delayed
+	/// events carry the number of confirmations, see [`WatchedStatus`].
 	#[subscription(
 		name = "author_submitAndWatchExtrinsic" => "author_extrinsicUpdate",
 		unsubscribe = "author_unwatchExtrinsic",
-		item = TransactionStatus<Hash, BlockHash>,
+		item = WatchedStatus<Hash, BlockHash>,
 	)]
 	fn watch_extrinsic(&self, bytes: Bytes);
 }
@@ -0,0 +1,42 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Session key helpers for author RPC module.
+
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+
+/// Status of a single session key, as seen by the local keystore.
+///
+/// Times are given in milliseconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionKeyStatus {
+	/// The four character key type identifier (e.g. `gran`, `babe`).
+	pub key_type: String,
+	/// The public key.
+	pub public: Bytes,
+	/// Whether this public key is part of the session keys registered on-chain for the stash.
+	pub registered: bool,
+	/// Whether the keystore holds the private key for this public key.
+	pub in_keystore: bool,
+	/// When the key was generated, if known to the keystore.
+	pub generated: Option<u64>,
+	/// When the key last signed, if it signed since the keystore was opened.
+	pub last_signed: Option<u64>,
+}
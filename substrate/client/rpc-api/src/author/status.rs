@@ -0,0 +1,109 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction status events of watched extrinsics.
+
+use sc_transaction_pool_api::TransactionStatus;
+use serde::{
+	de::{DeserializeOwned, Error as _},
+	ser::Error as _,
+	Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value;
+
+/// A transaction status event of a watched extrinsic.
+///
+/// Without a confirmation depth the event has the format of [`TransactionStatus`]. If the node
+/// delays the `InBlock` and `Finalized` events by a confirmation depth, they carry the number of
+/// blocks on top of the including block next to the block hash, as in
+/// `{"inBlock": "0x…", "confirmations": 3}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedStatus<Hash, BlockHash> {
+	/// The status of the transaction.
+	pub status: TransactionStatus<Hash, BlockHash>,
+	/// The number of blocks on top of the including block, if the event was delayed by the
+	/// confirmation depth.
+	pub confirmations: Option<u32>,
+}
+
+impl<Hash, BlockHash> From<TransactionStatus<Hash, BlockHash>> for WatchedStatus<Hash, BlockHash> {
+	fn from(status: TransactionStatus<Hash, BlockHash>) -> Self {
+		Self { status, confirmations: None }
+	}
+}
+
+impl<Hash: Serialize, BlockHash: Serialize> Serialize for WatchedStatus<Hash, BlockHash> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let Some(confirmations) = self.confirmations else {
+			return self.status.serialize(serializer)
+		};
+		let mut value = serde_json::to_value(&self.status).map_err(S::Error::custom)?;
+		let Value::Object(event) = &mut value else {
+			return Err(S::Error::custom("Only events with a block carry confirmations"))
+		};
+		event.insert("confirmations".into(), confirmations.into());
+		value.serialize(serializer)
+	}
+}
+
+impl<'de, Hash: DeserializeOwned, BlockHash: DeserializeOwned> Deserialize<'de>
+	for WatchedStatus<Hash, BlockHash>
+{
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let mut value = Value::deserialize(deserializer)?;
+		let confirmations = value
+			.as_object_mut()
+			.and_then(|event| event.remove("confirmations"))
+			.map(serde_json::from_value)
+			.transpose()
+			.map_err(D::Error::custom)?;
+		let status = serde_json::from_value(value).map_err(D::Error::custom)?;
+		Ok(Self { status, confirmations })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn confirmations_are_part_of_the_payload() {
+		let status = WatchedStatus::<u8, u8> {
+			status: TransactionStatus::InBlock((1, 2)),
+			confirmations: Some(3),
+		};
+		let json = serde_json::to_value(&status).unwrap();
+		assert_eq!(json, json!({ "inBlock": 1, "confirmations": 3 }));
+		let decoded: WatchedStatus<u8, u8> = serde_json::from_value(json).unwrap();
+		assert_eq!(decoded, WatchedStatus { status: TransactionStatus::InBlock((1, 0)), ..status });
+	}
+
+	#[test]
+	fn events_without_confirmations_keep_their_format() {
+		let status = WatchedStatus::<u8, u8>::from(TransactionStatus::Ready);
+		let json = serde_json::to_string(&status).unwrap();
+		assert_eq!(json, r#""ready""#);
+		assert_eq!(serde_json::from_str::<WatchedStatus<u8, u8>>(&json).unwrap(), status);
+
+		let status = WatchedStatus::<u8, u8>::from(TransactionStatus::Finalized((1, 0)));
+		let json = serde_json::to_string(&status).unwrap();
+		assert_eq!(json, r#"{"finalized":1}"#);
+		assert_eq!(serde_json::from_str::<WatchedStatus<u8, u8>>(&json).unwrap(), status);
+	}
+}
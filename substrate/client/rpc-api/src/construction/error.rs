@@ -0,0 +1,66 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction construction RPC module errors.
+
+use jsonrpsee::types::error::{ErrorObject, ErrorObjectOwned};
+
+/// Construction RPC Result type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Construction RPC errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Client error.
+	#[error("Client error: {0}")]
+	Client(Box<dyn std::error::Error + Send + Sync>),
+	/// The runtime doesn't provide usable metadata.
+	#[error("Metadata unavailable: {0}")]
+	MetadataUnavailable(String),
+	/// The pallet or the call doesn't exist.
+	#[error("Unknown call `{0}`")]
+	UnknownCall(String),
+	/// The arguments don't match the call.
+	#[error("Invalid arguments: {0}")]
+	InvalidArguments(String),
+	/// The value of a signed extension of the runtime can't be derived.
+	#[error("Unsupported signed extension `{0}`")]
+	UnsupportedExtension(String),
+	/// The runtime uses an extrinsic version the construction doesn't support.
+	#[error("Unsupported extrinsic version {0}, only version 4 is supported")]
+	UnsupportedExtrinsicVersion(u8),
+}
+
+/// Base code for all construction errors.
+const BASE_ERROR: i32 = crate::error::base::CONSTRUCTION;
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(e: Error) -> Self {
+		let msg = e.to_string();
+
+		match e {
+			Error::Client(_) => ErrorObject::owned(BASE_ERROR + 1, msg, None::<()>),
+			Error::MetadataUnavailable(_) => ErrorObject::owned(BASE_ERROR + 2, msg, None::<()>),
+			Error::UnknownCall(_) => ErrorObject::owned(BASE_ERROR + 3, msg, None::<()>),
+			Error::InvalidArguments(_) => ErrorObject::owned(BASE_ERROR + 4, msg, None::<()>),
+			Error::UnsupportedExtension(_) => ErrorObject::owned(BASE_ERROR + 5, msg, None::<()>),
+			Error::UnsupportedExtrinsicVersion(_) =>
+				ErrorObject::owned(BASE_ERROR + 6, msg, None::<()>),
+		}
+	}
+}
@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate transaction construction API.
+//!
+//! Lets thin clients without metadata handling of their own build transactions: the node encodes
+//! the call from its JSON representation and returns the payload to sign. The client then only
+//! has to sign the payload and assemble the extrinsic from the address, the signature, the
+//! encoded signed extensions and the encoded call.
+
+pub mod error;
+
+use error::Error;
+use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use std::collections::BTreeMap;
+
+/// A transaction to construct with the `construction_payload` RPC.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadRequest {
+	/// Name of the pallet of the call, e.g. `Balances`.
+	pub pallet: String,
+	/// Name of the call, e.g. `transfer_keep_alive`.
+	pub call: String,
+	/// Arguments of the call, as an object with one entry per argument.
+	///
+	/// Values use the JSON representation of the types of the metadata: composites are objects
+	/// or arrays, enum variants are their name or an object with the name as the only key,
+	/// byte arrays are `0x` prefixed hex strings and integers are numbers or decimal strings.
+	#[serde(default)]
+	pub args: serde_json::Value,
+	/// Nonce of the signing account.
+	pub nonce: u64,
+	/// Tip paid to the block author. Defaults to zero.
+	#[serde(default)]
+	pub tip: Option<NumberOrHex>,
+	/// Number of blocks the transaction is valid for, starting at the best block.
+	///
+	/// `0` makes the transaction immortal. Defaults to 64 blocks.
+	#[serde(default)]
+	pub mortality: Option<u64>,
+	/// Values of signed extensions, by identifier, replacing the values derived by the node.
+	///
+	/// Needed for the signed extensions of the runtime the node doesn't know, which otherwise
+	/// must not carry data. The mortality extension is always derived from `mortality`.
+	#[serde(default)]
+	pub extensions: BTreeMap<String, ExtensionValues>,
+}
+
+/// The values of a signed extension, in the JSON representation of their types.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionValues {
+	/// The value included in the extrinsic. Defaults to `null`, for extensions without data.
+	#[serde(default)]
+	pub extra: serde_json::Value,
+	/// The value implied by the extension, signed but not included in the extrinsic. Defaults to
+	/// `null`, for extensions without implied data.
+	#[serde(default)]
+	pub additional_signed: serde_json::Value,
+}
+
+/// The unsigned transaction returned by the `construction_payload` RPC.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedPayload<Hash> {
+	/// The SCALE encoded call.
+	pub call: Bytes,
+	/// The SCALE encoded signed extensions, included in the extrinsic after the signature.
+	pub extra: Bytes,
+	/// The SCALE encoded data implied by the signed extensions, signed but not included in the
+	/// extrinsic.
+	pub additional_signed: Bytes,
+	/// The bytes to sign.
+	///
+	/// The concatenation of `call`, `extra` and `additional_signed`, hashed with blake2-256 if
+	/// longer than 256 bytes.
+	pub signing_payload: Bytes,
+	/// Spec version of the runtime the payload was constructed for.
+	pub spec_version: u32,
+	/// Transaction version of the runtime the payload was constructed for.
+	pub transaction_version: u32,
+	/// Hash of the block the mortality of the transaction starts at, or of the genesis block if
+	/// the transaction is immortal.
+	pub checkpoint: Hash,
+}
+
+/// Substrate transaction construction API.
+#[rpc(client, server)]
+pub trait ConstructionApi<Hash> {
+	/// Encode a call and construct the payload to sign to submit it.
+	///
+	/// The call and the signed extensions are encoded with the metadata of the runtime of the
+	/// best block. Fails if the runtime uses signed extensions whose values can't be derived and
+	/// are not given in the request, or an extrinsic version other than 4.
+	#[method(name = "construction_payload")]
+	async fn payload(&self, request: PayloadRequest) -> Result<UnsignedPayload<Hash>, Error>;
+}
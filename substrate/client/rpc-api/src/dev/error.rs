@@ -38,6 +38,15 @@ pub enum Error {
 	/// The witness compaction failed.
 	#[error("Failed to create to compact the witness")]
 	WitnessCompactionFailed,
+	/// Building a block failed.
+	#[error("Failed to build block: {0}")]
+	BlockBuildFailed(String),
+	/// Updating the block blacklist failed.
+	#[error("Failed to update the blacklist: {0}")]
+	BlacklistFailed(String),
+	/// Taking a backup of the database failed.
+	#[error("Backup failed: {0}")]
+	BackupFailed(String),
 	/// The method is marked as unsafe but unsafe flag wasn't supplied on the CLI.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
@@ -55,6 +64,9 @@ impl From<Error> for ErrorObjectOwned {
 			Error::BlockExecutionFailed => ErrorObject::owned(BASE_ERROR + 3, msg, None::<()>),
 			Error::WitnessCompactionFailed => ErrorObject::owned(BASE_ERROR + 4, msg, None::<()>),
 			Error::ProofExtractionFailed => ErrorObject::owned(BASE_ERROR + 5, msg, None::<()>),
+			Error::BlockBuildFailed(_) => ErrorObject::owned(BASE_ERROR + 6, msg, None::<()>),
+			Error::BlacklistFailed(_) => ErrorObject::owned(BASE_ERROR + 7, msg, None::<()>),
+			Error::BackupFailed(_) => ErrorObject::owned(BASE_ERROR + 8, msg, None::<()>),
 			Error::UnsafeRpcCalled(e) => e.into(),
 		}
 	}
@@ -27,6 +27,8 @@ use error::Error;
 use jsonrpsee::proc_macros::rpc;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_weights::Weight;
 
 /// Statistics of a block returned by the `dev_getBlockStats` RPC.
 #[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, Debug, TypeInfo, Serialize, Deserialize)]
@@ -48,6 +50,70 @@ pub struct BlockStats {
 	pub num_extrinsics: u64,
 }
 
+/// An extrinsic of a block built by the `dev_dryBuildBlock` RPC.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryBuiltExtrinsic<Hash> {
+	/// Hash of the extrinsic.
+	pub hash: Hash,
+	/// The SCALE encoded extrinsic.
+	pub extrinsic: Bytes,
+}
+
+/// The weight consumed by a block built by the `dev_dryBuildBlock` RPC, per dispatch class.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryBuiltWeight {
+	/// Weight of the normal dispatches.
+	pub normal: Weight,
+	/// Weight of the operational dispatches.
+	pub operational: Weight,
+	/// Weight of the mandatory dispatches, like inherents and hooks.
+	pub mandatory: Weight,
+}
+
+/// A block built by the `dev_dryBuildBlock` RPC.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryBuiltBlock<Hash> {
+	/// The parent the block was built on.
+	pub parent_hash: Hash,
+	/// The state root after executing the block.
+	pub state_root: Hash,
+	/// The root of the extrinsics of the block.
+	pub extrinsics_root: Hash,
+	/// The extrinsics included in the block, in order.
+	pub extrinsics: Vec<DryBuiltExtrinsic<Hash>>,
+	/// Length of the block in bytes.
+	pub block_len: u64,
+	/// The weight consumed by the block.
+	///
+	/// `None` if the runtime doesn't store it in `System::BlockWeight`.
+	pub weight: Option<DryBuiltWeight>,
+}
+
+/// A backup of the database taken by the `dev_backup` RPC.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo<Hash, Number> {
+	/// Best block at the time of the backup.
+	pub best_hash: Hash,
+	/// Number of the best block.
+	pub best_number: Number,
+	/// Finalized block at the time of the backup.
+	pub finalized_hash: Hash,
+	/// Number of the finalized block.
+	pub finalized_number: Number,
+	/// Unix timestamp of the backup, in seconds.
+	pub timestamp: u64,
+	/// The snapshot of the database, if the node is configured to create one.
+	pub snapshot: Option<String>,
+	/// Time during which the writes were quiesced, in milliseconds.
+	///
+	/// `None` for the marker of a previous backup.
+	pub duration: Option<u64>,
+}
+
 /// Substrate dev API.
 ///
 /// This API contains unstable and unsafe methods only meant for development nodes. They
@@ -62,3 +128,61 @@ pub trait DevApi<Hash> {
 	#[method(name = "dev_getBlockStats", with_extensions)]
 	fn block_stats(&self, block_hash: Hash) -> Result<Option<BlockStats>, Error>;
 }
+
+/// Substrate dev API for building blocks without importing them.
+///
+/// The methods are unsafe, as building blocks is expensive.
+#[rpc(client, server)]
+pub trait DryBuildApi<Hash> {
+	/// Build a block on top of `parent_hash` from the transaction pool, as the block author would.
+	///
+	/// The block is neither imported nor broadcast. Defaults to building on top of the best block.
+	/// Returns `None` if the parent is not available at the queried node.
+	#[method(name = "dev_dryBuildBlock", with_extensions)]
+	async fn dry_build_block(
+		&self,
+		parent_hash: Option<Hash>,
+	) -> Result<Option<DryBuiltBlock<Hash>>, Error>;
+}
+
+/// Substrate dev API for blacklisting blocks.
+///
+/// The methods are unsafe, as blacklisting a block changes the fork choice of the node.
+#[rpc(client, server)]
+pub trait BlacklistApi<Hash> {
+	/// Blacklist the block `hash`, making the node reject it and its descendants.
+	///
+	/// A blacklisted block of the best chain is retracted from it. Finalized blocks can't be
+	/// blacklisted. The blacklist is persisted across restarts.
+	#[method(name = "dev_blacklistBlock", with_extensions)]
+	fn blacklist_block(&self, hash: Hash) -> Result<(), Error>;
+
+	/// Remove the block `hash` from the blacklist, returns whether it was blacklisted.
+	#[method(name = "dev_unblacklistBlock", with_extensions)]
+	fn unblacklist_block(&self, hash: Hash) -> Result<bool, Error>;
+
+	/// The blacklisted blocks.
+	#[method(name = "dev_blacklistedBlocks", with_extensions)]
+	fn blacklisted_blocks(&self) -> Result<Vec<Hash>, Error>;
+}
+
+/// Substrate dev API for taking consistent backups of the database.
+///
+/// The methods are unsafe, as the backups block the import of blocks while they run.
+#[rpc(client, server)]
+pub trait BackupApi<Hash, Number> {
+	/// Take a backup of the database.
+	///
+	/// The import of blocks is suspended while a consistency marker is committed to the
+	/// database and the snapshot configured on the node is created. The backup hook configured
+	/// on the node then copies the snapshot, or snapshots the database while the import is
+	/// suspended if there is no snapshot.
+	#[method(name = "dev_backup", blocking, with_extensions)]
+	fn backup(&self) -> Result<BackupInfo<Hash, Number>, Error>;
+
+	/// The consistency marker of the last backup, if any.
+	///
+	/// A database restored from a backup contains the marker of the backup.
+	#[method(name = "dev_lastBackup", with_extensions)]
+	fn last_backup(&self) -> Result<Option<BackupInfo<Hash, Number>>, Error>;
+}
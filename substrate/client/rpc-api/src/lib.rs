@@ -24,15 +24,19 @@
 
 mod error;
 mod policy;
+mod unsubscriptions;
 
 pub use policy::{check_if_safe, DenyUnsafe, UnsafeRpcError};
+pub use unsubscriptions::Unsubscriptions;
 
 pub mod author;
 pub mod chain;
 pub mod child_state;
+pub mod construction;
 pub mod dev;
 pub mod mixnet;
 pub mod offchain;
+pub mod scale;
 pub mod state;
 pub mod statement;
 pub mod system;
@@ -21,12 +21,53 @@
 pub mod error;
 
 use error::Error;
-use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 
+/// Delivery status of an extrinsic submitted over the mixnet.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmissionStatus {
+	/// The extrinsic has been sent, but no acknowledgment has been received yet.
+	Pending,
+	/// The destination mixnode acknowledged the import of the extrinsic into its transaction pool.
+	Delivered,
+	/// The submission failed, with the given reason.
+	Failed(String),
+}
+
 #[rpc(client, server)]
 pub trait MixnetApi {
 	/// Submit encoded extrinsic over the mixnet for inclusion in block.
 	#[method(name = "mixnet_submitExtrinsic")]
 	async fn submit_extrinsic(&self, extrinsic: Bytes) -> Result<(), Error>;
 }
+
+/// Submission of extrinsics through the mixnet via the authoring API.
+///
+/// Replaces `author_submitExtrinsic` of the [`AuthorApi`](crate::author::AuthorApi) when enabled.
+#[rpc(client, server)]
+pub trait MixnetAuthorApi<Hash> {
+	/// Submit hex-encoded extrinsic over the mixnet for inclusion in block.
+	///
+	/// Returns the hash of the extrinsic once it has been sent, without waiting for the
+	/// acknowledgment of the destination mixnode.
+	///
+	/// Retrying the submission of the same extrinsic with the same `idempotency_key`, also from
+	/// another connection, returns the original hash without sending the extrinsic again. The key
+	/// is at most 64 bytes long and is remembered for 10 minutes.
+	#[method(name = "author_submitExtrinsic")]
+	async fn submit_extrinsic(
+		&self,
+		extrinsic: Bytes,
+		idempotency_key: Option<String>,
+	) -> RpcResult<Hash>;
+
+	/// Delivery status of an extrinsic submitted with `author_submitExtrinsic`.
+	///
+	/// Returns `None` if the extrinsic wasn't submitted over the mixnet by this node, or if it was
+	/// submitted too long ago.
+	#[method(name = "mixnet_submissionStatus")]
+	fn submission_status(&self, hash: Hash) -> RpcResult<Option<SubmissionStatus>>;
+}
@@ -0,0 +1,53 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! SCALE codec RPC module errors.
+
+use jsonrpsee::types::error::{ErrorObject, ErrorObjectOwned};
+
+/// SCALE codec RPC Result type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// SCALE codec RPC errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Client error.
+	#[error("Client error: {0}")]
+	Client(Box<dyn std::error::Error + Send + Sync>),
+	/// The runtime doesn't provide usable metadata.
+	#[error("Metadata unavailable: {0}")]
+	MetadataUnavailable(String),
+	/// The value doesn't match its type.
+	#[error("Invalid value: {0}")]
+	InvalidValue(String),
+}
+
+/// Base code for all SCALE codec errors.
+const BASE_ERROR: i32 = crate::error::base::SCALE;
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(e: Error) -> Self {
+		let msg = e.to_string();
+
+		match e {
+			Error::Client(_) => ErrorObject::owned(BASE_ERROR + 1, msg, None::<()>),
+			Error::MetadataUnavailable(_) => ErrorObject::owned(BASE_ERROR + 2, msg, None::<()>),
+			Error::InvalidValue(_) => ErrorObject::owned(BASE_ERROR + 3, msg, None::<()>),
+		}
+	}
+}
@@ -0,0 +1,47 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate SCALE codec API.
+//!
+//! Converts values between their SCALE encoding and their JSON representation, using the type
+//! registry of the runtime metadata. The types are identified by their id in the V15 metadata,
+//! e.g. the type of a storage entry or of the `RuntimeCall` and `RuntimeEvent` enums.
+
+pub mod error;
+
+use error::Error;
+use jsonrpsee::proc_macros::rpc;
+use sp_core::Bytes;
+
+/// Substrate SCALE codec API.
+#[rpc(client, server)]
+pub trait ScaleApi<Hash> {
+	/// Decode a SCALE encoded value of the type `ty` of the metadata into JSON.
+	///
+	/// Uses the metadata of the runtime of the block `at`, which defaults to the best block.
+	/// Fails if the value doesn't use all the bytes.
+	#[method(name = "scale_decode")]
+	fn decode(&self, ty: u32, bytes: Bytes, at: Option<Hash>)
+		-> Result<serde_json::Value, Error>;
+
+	/// Encode the JSON representation of a value of the type `ty` of the metadata.
+	///
+	/// Uses the metadata of the runtime of the block `at`, which defaults to the best block.
+	#[method(name = "scale_encode")]
+	fn encode(&self, ty: u32, value: serde_json::Value, at: Option<Hash>) -> Result<Bytes, Error>;
+}
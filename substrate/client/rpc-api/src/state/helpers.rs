@@ -20,6 +20,7 @@
 
 use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
+use sp_version::RuntimeVersion;
 
 /// ReadProof struct returned by the RPC
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -30,3 +31,41 @@ pub struct ReadProof<Hash> {
 	/// A proof used to prove that storage entries are included in the storage trie
 	pub proof: Vec<Bytes>,
 }
+
+/// The runtime version of a block, together with the runtime upgrade pending at it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeUpgradeStatus<Hash> {
+	/// Hash of the block the status was observed at.
+	pub block: Hash,
+	/// Runtime version at the block.
+	pub version: RuntimeVersion,
+	/// The runtime upgrade pending at the block, if any.
+	pub pending_upgrade: Option<PendingUpgrade<Hash>>,
+}
+
+/// A runtime upgrade which is scheduled or authorized, but not enacted yet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingUpgrade<Hash> {
+	/// How the upgrade is pending.
+	pub kind: PendingUpgradeKind,
+	/// Hash of the new runtime code.
+	pub code_hash: Hash,
+	/// The relay chain block number the upgrade is expected to be enacted at.
+	///
+	/// Only known for parachain validation code upgrades.
+	pub estimated_enactment: Option<u32>,
+}
+
+/// The ways a runtime upgrade can be pending.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingUpgradeKind {
+	/// The upgrade was authorized in `System::AuthorizedUpgrade` and is enacted as soon as the
+	/// code is applied with `apply_authorized_upgrade`.
+	Authorized,
+	/// The validation code is stored in `ParachainSystem::PendingValidationCode` and is enacted
+	/// once the relay chain signals to go ahead.
+	ParachainValidationCode,
+}
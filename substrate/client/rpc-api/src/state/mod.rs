@@ -28,7 +28,7 @@ use sp_version::RuntimeVersion;
 pub mod error;
 pub mod helpers;
 
-pub use self::helpers::ReadProof;
+pub use self::helpers::{PendingUpgrade, PendingUpgradeKind, ReadProof, RuntimeUpgradeStatus};
 pub use error::Error;
 
 /// Substrate state API
@@ -131,6 +131,17 @@ pub trait StateApi<Hash> {
 	)]
 	fn subscribe_runtime_version(&self);
 
+	/// New runtime upgrade subscription.
+	///
+	/// Notifies about the runtime version of the best block together with the runtime upgrade
+	/// pending at it, whenever either of them changes.
+	#[subscription(
+		name = "state_subscribeRuntimeUpgrades" => "state_runtimeUpgrade",
+		unsubscribe = "state_unsubscribeRuntimeUpgrades",
+		item = RuntimeUpgradeStatus<Hash>,
+	)]
+	fn subscribe_runtime_upgrades(&self);
+
 	/// New storage subscription
 	#[subscription(
 		name = "state_subscribeStorage" => "state_storage",
@@ -19,10 +19,31 @@
 //! Substrate Statement Store RPC API.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 
 pub mod error;
 
+/// Topic filter of the `statement_query` RPC.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatementFilter {
+	/// Statements must include all of these topics.
+	pub match_all_topics: Vec<[u8; 32]>,
+	/// If not empty, statements must include at least one of these topics.
+	pub match_any_topics: Vec<[u8; 32]>,
+}
+
+/// A page of statements returned by the `statement_query` RPC.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementPage {
+	/// SCALE-encoded statements, ordered by hash.
+	pub statements: Vec<Bytes>,
+	/// The hash to pass as `start_after` to query the next page, if there are more statements.
+	pub next: Option<[u8; 32]>,
+}
+
 /// Substrate statement RPC API
 #[rpc(client, server)]
 pub trait StatementApi {
@@ -50,6 +71,20 @@ pub trait StatementApi {
 		dest: [u8; 32],
 	) -> RpcResult<Vec<Bytes>>;
 
+	/// Return a page of SCALE-encoded statements matching `filter`, ordered by hash.
+	///
+	/// Statements with a hash up to and including `start_after` are skipped. At most `limit`
+	/// statements are returned, 100 by default and at most 1000.
+	///
+	/// Querying without any topic is only allowed on unsafe RPC interfaces.
+	#[method(name = "statement_query", with_extensions)]
+	fn query(
+		&self,
+		filter: StatementFilter,
+		start_after: Option<[u8; 32]>,
+		limit: Option<u32>,
+	) -> RpcResult<StatementPage>;
+
 	/// Submit a pre-encoded statement.
 	#[method(name = "statement_submit")]
 	fn submit(&self, encoded: Bytes) -> RpcResult<()>;
@@ -92,6 +92,28 @@ pub struct SyncState<Number> {
 	pub highest_block: Number,
 }
 
+/// Counters of the log messages of the runtime with one target.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeLogTarget {
+	/// The log target.
+	pub target: String,
+	/// Number of messages written by the runtime log sink.
+	pub written: u64,
+	/// Number of messages dropped because of the rate limit of the runtime log sink.
+	pub dropped: u64,
+}
+
+/// The heap memory used by a subsystem of the node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemMemory {
+	/// The name of the subsystem.
+	pub subsystem: String,
+	/// Number of bytes used by the subsystem.
+	pub bytes: u64,
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -23,7 +23,9 @@ pub mod helpers;
 
 use jsonrpsee::{core::JsonValue, proc_macros::rpc};
 
-pub use self::helpers::{Health, NodeRole, PeerInfo, SyncState, SystemInfo};
+pub use self::helpers::{
+	Health, NodeRole, PeerInfo, RuntimeLogTarget, SubsystemMemory, SyncState, SystemInfo,
+};
 pub use error::Error;
 
 /// Substrate system RPC API
@@ -118,4 +120,26 @@ pub trait SystemApi<Hash, Number> {
 	/// Resets the log filter to Substrate defaults
 	#[method(name = "system_resetLogFilter", with_extensions)]
 	fn system_reset_log_filter(&self) -> Result<(), Error>;
+
+	/// Adds the supplied directives to the log filter of the runtime log sink
+	///
+	/// The syntax is identical to the CLI `<target>=<level>`:
+	///
+	/// `info,runtime::staking=debug`
+	///
+	/// Fails if the node wasn't started with `--runtime-log`.
+	#[method(name = "system_setRuntimeLogLevels", with_extensions)]
+	fn system_set_runtime_log_levels(&self, directives: String) -> Result<(), Error>;
+
+	/// Returns the number of written and dropped log messages of the runtime, by target.
+	///
+	/// Fails if the node wasn't started with `--runtime-log`.
+	#[method(name = "system_runtimeLogCounters", with_extensions)]
+	fn system_runtime_log_counters(&self) -> Result<Vec<RuntimeLogTarget>, Error>;
+
+	/// Returns the heap memory used by each subsystem of the node, in bytes.
+	///
+	/// Fails if the node doesn't use the instrumented allocator of `sc-memory-breakdown`.
+	#[method(name = "system_memoryBreakdown", with_extensions)]
+	fn system_memory_breakdown(&self) -> Result<Vec<SubsystemMemory>, Error>;
 }
@@ -0,0 +1,98 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tracking of the explicit unsubscriptions of a connection.
+//!
+//! Contains an `Unsubscriptions` type telling a subscription ended by an unsubscribe call of the
+//! client apart from a subscription ended by a closed connection.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+/// The subscriptions of a connection the client explicitly unsubscribed from.
+///
+/// The RPC server sets this extension for every connection and notes the unsubscribe calls
+/// before handling them. A subscription only watches its own unsubscribe method, the calls of the
+/// other methods are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Unsubscriptions(Arc<Mutex<HashMap<String, Watched>>>);
+
+#[derive(Debug)]
+struct Watched {
+	/// The unsubscribe method of the subscription.
+	method: &'static str,
+	/// Whether the client called the unsubscribe method.
+	unsubscribed: bool,
+}
+
+impl Unsubscriptions {
+	/// Watch the calls of the unsubscribe `method` for the subscription `sub_id`.
+	pub fn watch(&self, sub_id: String, method: &'static str) {
+		let watched = Watched { method, unsubscribed: false };
+		self.0.lock().expect("never poisoned; qed").insert(sub_id, watched);
+	}
+
+	/// Returns `true` if a subscription watches the calls of `method`.
+	pub fn is_watched(&self, method: &str) -> bool {
+		self.0
+			.lock()
+			.expect("never poisoned; qed")
+			.values()
+			.any(|watched| watched.method == method)
+	}
+
+	/// Note a call of `method` to unsubscribe from `sub_id`.
+	pub fn note(&self, method: &str, sub_id: &str) {
+		let mut subscriptions = self.0.lock().expect("never poisoned; qed");
+		if let Some(watched) =
+			subscriptions.get_mut(sub_id).filter(|watched| watched.method == method)
+		{
+			watched.unsubscribed = true;
+		}
+	}
+
+	/// Stop watching `sub_id`, returns `true` if the client explicitly unsubscribed from it.
+	pub fn take(&self, sub_id: &str) -> bool {
+		let mut subscriptions = self.0.lock().expect("never poisoned; qed");
+		subscriptions.remove(sub_id).map_or(false, |watched| watched.unsubscribed)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_watched_unsubscriptions_are_noted() {
+		let unsubscriptions = Unsubscriptions::default();
+		unsubscriptions.watch("1".into(), "unfollow");
+		unsubscriptions.watch("2".into(), "unfollow");
+		assert!(unsubscriptions.is_watched("unfollow"));
+		assert!(!unsubscriptions.is_watched("unwatch"));
+
+		unsubscriptions.note("unwatch", "1");
+		unsubscriptions.note("unfollow", "2");
+		unsubscriptions.note("unfollow", "3");
+		assert!(!unsubscriptions.take("1"));
+		assert!(unsubscriptions.take("2"));
+		assert!(!unsubscriptions.take("3"));
+		assert!(!unsubscriptions.is_watched("unfollow"));
+	}
+}
@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use prost::Message;
+use std::{env, fs, path::PathBuf};
+
+const PROTOS: &[&str] = &["src/schema/node-v1.proto"];
+const INCLUDES: &[&str] = &["src/schema"];
+
+fn main() {
+	// Parse the schema with `protox` so that building the crate does not require `protoc`.
+	let descriptors = protox::compile(PROTOS, INCLUDES).unwrap();
+	let descriptors_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("node-v1.bin");
+	fs::write(&descriptors_path, descriptors.encode_to_vec()).unwrap();
+
+	tonic_build::configure()
+		.build_client(true)
+		.build_server(true)
+		.file_descriptor_set_path(&descriptors_path)
+		.skip_protoc_run()
+		.compile(PROTOS, INCLUDES)
+		.unwrap();
+}
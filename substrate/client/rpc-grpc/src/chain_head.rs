@@ -0,0 +1,189 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `ChainHead` gRPC service.
+
+use crate::{
+	decode,
+	schema::{
+		chain_head_server::ChainHead, follow_event::Event, BestBlockChanged, BlockRequest,
+		BodyResponse, CallRequest, CallResponse, Finalized, FollowEvent, FollowRequest,
+		HeaderResponse, Initialized, NewBlock, StorageRequest, StorageResponse,
+	},
+};
+use codec::Encode;
+use futures::{
+	stream::{self, BoxStream},
+	StreamExt,
+};
+use sc_client_api::{
+	Backend, BlockBackend, BlockImportNotification, BlockchainEvents, CallExecutor,
+	ExecutorProvider, FinalityNotification, StorageProvider,
+};
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, traits::CallContext};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{marker::PhantomData, sync::Arc};
+use tonic::{Request, Response, Status};
+
+/// Implementation of the `ChainHead` gRPC service.
+pub struct ChainHeadService<Block, BE, Client> {
+	client: Arc<Client>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+impl<Block, BE, Client> ChainHeadService<Block, BE, Client> {
+	/// Create a new [`ChainHeadService`].
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, _phantom: PhantomData }
+	}
+}
+
+impl<Block, BE, Client> ChainHeadService<Block, BE, Client>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block>,
+{
+	/// Decode the block hash of a request and ensure the block is known.
+	fn block_hash(&self, hash: &[u8]) -> Result<Block::Hash, Status> {
+		let hash = decode("block hash", hash)?;
+		match self.client.header(hash) {
+			Ok(Some(_)) => Ok(hash),
+			Ok(None) => Err(Status::not_found(format!("Unknown block {hash:?}"))),
+			Err(e) => Err(Status::internal(e.to_string())),
+		}
+	}
+}
+
+fn event(event: Event) -> FollowEvent {
+	FollowEvent { event: Some(event) }
+}
+
+/// The follow events reported for an imported block.
+fn import_events<Block: BlockT>(notification: BlockImportNotification<Block>) -> Vec<FollowEvent> {
+	let mut events = vec![event(Event::NewBlock(NewBlock {
+		block_hash: notification.hash.encode(),
+		parent_block_hash: notification.header.parent_hash().encode(),
+		header: notification.header.encode(),
+	}))];
+	if notification.is_new_best {
+		events.push(event(Event::BestBlockChanged(BestBlockChanged {
+			best_block_hash: notification.hash.encode(),
+		})));
+	}
+	events
+}
+
+/// The follow event reported for a finality notification.
+fn finalized_event<Block: BlockT>(notification: FinalityNotification<Block>) -> FollowEvent {
+	let finalized_block_hashes = notification
+		.tree_route
+		.iter()
+		.chain(std::iter::once(&notification.hash))
+		.map(Encode::encode)
+		.collect();
+	let pruned_block_hashes = notification.stale_heads.iter().map(Encode::encode).collect();
+	event(Event::Finalized(Finalized { finalized_block_hashes, pruned_block_hashes }))
+}
+
+#[tonic::async_trait]
+impl<Block, BE, Client> ChainHead for ChainHeadService<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block> + Send + Sync + 'static,
+	Client: HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ ExecutorProvider<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	type FollowStream = BoxStream<'static, Result<FollowEvent, Status>>;
+
+	async fn follow(
+		&self,
+		_request: Request<FollowRequest>,
+	) -> Result<Response<Self::FollowStream>, Status> {
+		// Subscribe before reading the chain info, so that no block is missed in between.
+		let imports = self
+			.client
+			.import_notification_stream()
+			.flat_map(|notification| stream::iter(import_events(notification)));
+		let finality = self.client.finality_notification_stream().map(finalized_event);
+
+		let info = self.client.info();
+		let initialized = event(Event::Initialized(Initialized {
+			finalized_block_hash: info.finalized_hash.encode(),
+			best_block_hash: info.best_hash.encode(),
+		}));
+
+		let events = stream::once(async move { initialized })
+			.chain(stream::select(imports, finality))
+			.map(Ok);
+		Ok(Response::new(events.boxed()))
+	}
+
+	async fn header(
+		&self,
+		request: Request<BlockRequest>,
+	) -> Result<Response<HeaderResponse>, Status> {
+		let hash = decode("block hash", &request.get_ref().block_hash)?;
+		match self.client.header(hash) {
+			Ok(Some(header)) => Ok(Response::new(HeaderResponse { header: header.encode() })),
+			Ok(None) => Err(Status::not_found(format!("Unknown block {hash:?}"))),
+			Err(e) => Err(Status::internal(e.to_string())),
+		}
+	}
+
+	async fn body(&self, request: Request<BlockRequest>) -> Result<Response<BodyResponse>, Status> {
+		let hash = decode("block hash", &request.get_ref().block_hash)?;
+		match self.client.block_body(hash) {
+			Ok(Some(body)) => Ok(Response::new(BodyResponse {
+				extrinsics: body.iter().map(Encode::encode).collect(),
+			})),
+			Ok(None) => Err(Status::not_found(format!("Unknown block {hash:?}"))),
+			Err(e) => Err(Status::internal(e.to_string())),
+		}
+	}
+
+	async fn storage(
+		&self,
+		request: Request<StorageRequest>,
+	) -> Result<Response<StorageResponse>, Status> {
+		let StorageRequest { block_hash, key } = request.into_inner();
+		let hash = self.block_hash(&block_hash)?;
+		let value = self
+			.client
+			.storage(hash, &StorageKey(key))
+			.map_err(|e| Status::internal(e.to_string()))?;
+		Ok(Response::new(StorageResponse { value: value.map(|data| data.0) }))
+	}
+
+	async fn call(&self, request: Request<CallRequest>) -> Result<Response<CallResponse>, Status> {
+		let CallRequest { block_hash, function, call_parameters } = request.into_inner();
+		let hash = self.block_hash(&block_hash)?;
+		let output = self
+			.client
+			.executor()
+			.call(hash, &function, &call_parameters, CallContext::Offchain)
+			.map_err(|e| Status::failed_precondition(e.to_string()))?;
+		Ok(Response::new(CallResponse { output }))
+	}
+}
@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! gRPC gateway for the core node APIs.
+//!
+//! Exposes the chain head tracking and transaction submission APIs of the node over gRPC, for
+//! infrastructure that cannot consume JSON-RPC subscriptions over WebSocket reliably. The services
+//! mirror the `chainHead_v1` and `transaction_v1` JSON-RPC functions and are described by the
+//! protobuf schema in `src/schema/node-v1.proto`, which is also exported as [`PROTO_SCHEMA`] so
+//! clients can generate their bindings from the exact schema served by the node.
+//!
+//! The gateway is disabled unless a port is configured with [`GrpcParams`]. Nodes opt in by
+//! spawning the future returned by [`start_server`].
+
+mod chain_head;
+mod transaction;
+
+pub use chain_head::ChainHeadService;
+pub use transaction::TransactionService;
+
+/// Types and services generated from the protobuf schema.
+pub mod schema {
+	tonic::include_proto!("substrate.node.v1");
+}
+
+use clap::Args;
+use sc_client_api::{BlockBackend, BlockchainEvents, ExecutorProvider, StorageProvider};
+use sc_transaction_pool_api::TransactionPool;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	net::{Ipv4Addr, SocketAddr},
+	sync::Arc,
+};
+
+const LOG_TARGET: &str = "rpc-grpc";
+
+/// The protobuf schema of the gRPC services.
+pub const PROTO_SCHEMA: &str = include_str!("schema/node-v1.proto");
+
+/// Parameters used to configure the gRPC gateway.
+#[derive(Default, Debug, Clone, Args)]
+pub struct GrpcParams {
+	/// Specify the gRPC server TCP port.
+	///
+	/// The gRPC gateway is disabled if no port is given.
+	#[arg(long, value_name = "PORT")]
+	pub grpc_port: Option<u16>,
+
+	/// Listen to all gRPC interfaces.
+	///
+	/// By default, the node only listens to local gRPC calls.
+	#[arg(long)]
+	pub grpc_external: bool,
+}
+
+impl GrpcParams {
+	/// The address the gRPC server should listen on, if enabled.
+	pub fn listen_addr(&self) -> Option<SocketAddr> {
+		let interface =
+			if self.grpc_external { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+		self.grpc_port.map(|port| SocketAddr::new(interface.into(), port))
+	}
+}
+
+/// Serve the gRPC services on `addr` until the returned future is dropped.
+pub async fn start_server<Block, BE, Client, Pool>(
+	addr: SocketAddr,
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+) -> Result<(), tonic::transport::Error>
+where
+	Block: BlockT,
+	BE: sc_client_api::Backend<Block> + Send + Sync + 'static,
+	Client: HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ ExecutorProvider<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Pool: TransactionPool<Block = Block> + 'static,
+{
+	log::info!(target: LOG_TARGET, "Running gRPC server: addr={addr}");
+
+	let chain_head = ChainHeadService::<Block, BE, Client>::new(client.clone());
+	let transaction = TransactionService::new(client, pool);
+
+	tonic::transport::Server::builder()
+		.add_service(schema::chain_head_server::ChainHeadServer::new(chain_head))
+		.add_service(schema::transaction_server::TransactionServer::new(transaction))
+		.serve(addr)
+		.await
+}
+
+/// Decode a SCALE encoded value received over gRPC.
+fn decode<T: codec::Decode>(what: &str, mut data: &[u8]) -> Result<T, tonic::Status> {
+	T::decode(&mut data)
+		.map_err(|e| tonic::Status::invalid_argument(format!("Invalid {what}: {e}")))
+}
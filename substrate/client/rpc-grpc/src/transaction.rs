@@ -0,0 +1,163 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `Transaction` gRPC service.
+
+use crate::{
+	decode,
+	schema::{
+		transaction_event::{
+			BestChainBlockIncluded, Broadcasted, Dropped, Event, Finalized, Invalid, Validated,
+		},
+		transaction_server::Transaction,
+		BlockPosition, SubmitRequest, SubmitResponse, TransactionEvent,
+	},
+};
+use codec::Encode;
+use futures::{stream::BoxStream, StreamExt};
+use sc_transaction_pool_api::{
+	error::IntoPoolError, TransactionPool, TransactionSource, TransactionStatus,
+};
+use sp_blockchain::HeaderBackend;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Transactions submitted over gRPC are treated as external, like the ones submitted over
+/// JSON-RPC.
+const TX_SOURCE: TransactionSource = TransactionSource::External;
+
+/// Implementation of the `Transaction` gRPC service.
+pub struct TransactionService<Client, Pool> {
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+}
+
+impl<Client, Pool> TransactionService<Client, Pool> {
+	/// Create a new [`TransactionService`].
+	pub fn new(client: Arc<Client>, pool: Arc<Pool>) -> Self {
+		Self { client, pool }
+	}
+}
+
+fn pool_error<E: IntoPoolError>(error: E) -> Status {
+	match error.into_pool_error() {
+		Ok(error) => Status::invalid_argument(error.to_string()),
+		Err(error) => Status::internal(error.to_string()),
+	}
+}
+
+fn block_position<BlockHash: Encode>((hash, index): (BlockHash, usize)) -> BlockPosition {
+	BlockPosition { block_hash: hash.encode(), index: index as u32 }
+}
+
+/// Translate a status of the transaction pool into a gRPC event.
+fn transaction_event<Hash, BlockHash: Encode>(
+	status: TransactionStatus<Hash, BlockHash>,
+) -> TransactionEvent {
+	let event = match status {
+		TransactionStatus::Ready | TransactionStatus::Future => Event::Validated(Validated {}),
+		TransactionStatus::Broadcast(peers) =>
+			Event::Broadcasted(Broadcasted { num_peers: peers.len() as u32 }),
+		TransactionStatus::InBlock(position) =>
+			Event::BestChainBlockIncluded(BestChainBlockIncluded {
+				block: Some(block_position(position)),
+			}),
+		TransactionStatus::Retracted(_) =>
+			Event::BestChainBlockIncluded(BestChainBlockIncluded { block: None }),
+		TransactionStatus::Finalized(position) =>
+			Event::Finalized(Finalized { block: Some(block_position(position)) }),
+		TransactionStatus::FinalityTimeout(_) =>
+			Event::Dropped(Dropped { error: "Maximum number of finality watchers reached".into() }),
+		TransactionStatus::Usurped(_) =>
+			Event::Invalid(Invalid { error: "Extrinsic was rendered invalid by another".into() }),
+		TransactionStatus::Dropped =>
+			Event::Dropped(Dropped { error: "Extrinsic dropped from the pool".into() }),
+		TransactionStatus::Invalid => Event::Invalid(Invalid { error: "Extrinsic is invalid".into() }),
+	};
+	TransactionEvent { event: Some(event) }
+}
+
+#[tonic::async_trait]
+impl<Client, Pool> Transaction for TransactionService<Client, Pool>
+where
+	Client: HeaderBackend<Pool::Block> + Send + Sync + 'static,
+	Pool: TransactionPool + 'static,
+{
+	type SubmitAndWatchStream = BoxStream<'static, Result<TransactionEvent, Status>>;
+
+	async fn submit(
+		&self,
+		request: Request<SubmitRequest>,
+	) -> Result<Response<SubmitResponse>, Status> {
+		let xt = decode("extrinsic", &request.get_ref().extrinsic)?;
+		let best_hash = self.client.info().best_hash;
+		let hash = self.pool.submit_one(best_hash, TX_SOURCE, xt).await.map_err(pool_error)?;
+		Ok(Response::new(SubmitResponse { transaction_hash: hash.encode() }))
+	}
+
+	async fn submit_and_watch(
+		&self,
+		request: Request<SubmitRequest>,
+	) -> Result<Response<Self::SubmitAndWatchStream>, Status> {
+		let xt = decode("extrinsic", &request.get_ref().extrinsic)?;
+		let best_hash = self.client.info().best_hash;
+		let statuses =
+			self.pool.submit_and_watch(best_hash, TX_SOURCE, xt).await.map_err(pool_error)?;
+		let events = statuses.map(|status| Ok(transaction_event(status)));
+		Ok(Response::new(events.boxed()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	fn event_of(status: TransactionStatus<H256, H256>) -> Event {
+		transaction_event(status).event.unwrap()
+	}
+
+	#[test]
+	fn transaction_statuses_are_translated() {
+		let block = H256::repeat_byte(1);
+
+		assert_eq!(event_of(TransactionStatus::Ready), Event::Validated(Validated {}));
+		assert_eq!(
+			event_of(TransactionStatus::Broadcast(vec!["a".into(), "b".into()])),
+			Event::Broadcasted(Broadcasted { num_peers: 2 }),
+		);
+		assert_eq!(
+			event_of(TransactionStatus::InBlock((block, 3))),
+			Event::BestChainBlockIncluded(BestChainBlockIncluded {
+				block: Some(BlockPosition { block_hash: block.encode(), index: 3 }),
+			}),
+		);
+		assert_eq!(
+			event_of(TransactionStatus::Retracted(block)),
+			Event::BestChainBlockIncluded(BestChainBlockIncluded { block: None }),
+		);
+		assert_eq!(
+			event_of(TransactionStatus::Finalized((block, 0))),
+			Event::Finalized(Finalized {
+				block: Some(BlockPosition { block_hash: block.encode(), index: 0 }),
+			}),
+		);
+		assert!(matches!(event_of(TransactionStatus::Dropped), Event::Dropped(_)));
+		assert!(matches!(event_of(TransactionStatus::Invalid), Event::Invalid(_)));
+	}
+}
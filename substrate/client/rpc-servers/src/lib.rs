@@ -32,6 +32,7 @@ use jsonrpsee::{
 	},
 	Methods, RpcModule,
 };
+use sc_rpc_api::Unsubscriptions;
 use tower::Service;
 use utils::{
 	build_rpc_api, deny_unsafe, format_listen_addrs, get_proxy_ip, ListenAddrError, RpcSettings,
@@ -42,7 +43,9 @@ pub use jsonrpsee::{
 	core::id_providers::{RandomIntegerIdProvider, RandomStringIdProvider},
 	server::{middleware::rpc::RpcServiceBuilder, BatchRequestConfig},
 };
-pub use middleware::{Metrics, MiddlewareLayer, NodeHealthProxyLayer, RpcMetrics};
+pub use middleware::{
+	Metrics, MiddlewareLayer, NodeHealthProxyLayer, RpcMetrics, UnsubscriptionsLayer,
+};
 pub use utils::{RpcEndpoint, RpcMethods};
 
 const MEGABYTE: u32 = 1024 * 1024;
@@ -214,6 +217,7 @@ where
 				let svc =
 					tower::service_fn(move |mut req: http::Request<hyper::body::Incoming>| {
 						req.extensions_mut().insert(deny_unsafe);
+						req.extensions_mut().insert(Unsubscriptions::default());
 
 						let PerConnection { methods, metrics, tokio_handle, stop_handle } =
 							cfg2.clone();
@@ -255,6 +259,7 @@ where
 
 						let rpc_middleware = RpcServiceBuilder::new()
 							.rpc_logger(1024)
+							.layer(UnsubscriptionsLayer)
 							.option_layer(middleware_layer.clone());
 						let mut svc = service_builder
 							.set_rpc_middleware(rpc_middleware)
@@ -0,0 +1,62 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Middleware noting the explicit unsubscriptions of the clients.
+
+use jsonrpsee::{
+	server::middleware::rpc::RpcServiceT,
+	types::{Request, SubscriptionId},
+};
+use sc_rpc_api::Unsubscriptions;
+
+/// Layer that applies [`NoteUnsubscriptions`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnsubscriptionsLayer;
+
+impl<S> tower::Layer<S> for UnsubscriptionsLayer {
+	type Service = NoteUnsubscriptions<S>;
+
+	fn layer(&self, service: S) -> Self::Service {
+		NoteUnsubscriptions { service }
+	}
+}
+
+/// JSON-RPC middleware noting the unsubscribe calls in the [`Unsubscriptions`] of the connection
+/// before they are handled, so that the subscription tells them apart from a closed connection.
+#[derive(Debug, Clone)]
+pub struct NoteUnsubscriptions<S> {
+	service: S,
+}
+
+impl<'a, S: RpcServiceT<'a>> RpcServiceT<'a> for NoteUnsubscriptions<S> {
+	type Future = S::Future;
+
+	fn call(&self, req: Request<'a>) -> Self::Future {
+		if let Some(unsubscriptions) = req.extensions().get::<Unsubscriptions>() {
+			if unsubscriptions.is_watched(req.method_name()) {
+				match req.params().one::<SubscriptionId>() {
+					Ok(SubscriptionId::Num(id)) =>
+						unsubscriptions.note(req.method_name(), &id.to_string()),
+					Ok(SubscriptionId::Str(id)) => unsubscriptions.note(req.method_name(), &id),
+					Err(_) => {},
+				}
+			}
+		}
+		self.service.call(req)
+	}
+}
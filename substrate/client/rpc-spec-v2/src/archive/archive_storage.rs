@@ -35,7 +35,7 @@ use crate::{
 			ArchiveStorageDiffEvent, ArchiveStorageDiffItem, ArchiveStorageDiffOperationType,
 			ArchiveStorageDiffResult, ArchiveStorageDiffType, StorageResult,
 		},
-		storage::Storage,
+		storage::{Snapshot, Storage},
 	},
 };
 use tokio::sync::mpsc;
@@ -101,31 +101,32 @@ where
 	/// Fetch the storage from the given key.
 	fn fetch_storage(
 		&self,
-		hash: Block::Hash,
+		snapshot: &Snapshot<BE, Block>,
 		key: StorageKey,
 		maybe_child_trie: Option<ChildInfo>,
 		ty: FetchStorageType,
 	) -> Result<Option<FetchedStorage>, String> {
 		match ty {
 			FetchStorageType::Value => {
-				let result = self.client.query_value(hash, &key, maybe_child_trie.as_ref())?;
+				let result = self.client.query_value(snapshot, &key, maybe_child_trie.as_ref())?;
 
 				Ok(result.map(FetchedStorage::Value))
 			},
 
 			FetchStorageType::Hash => {
-				let result = self.client.query_hash(hash, &key, maybe_child_trie.as_ref())?;
+				let result = self.client.query_hash(snapshot, &key, maybe_child_trie.as_ref())?;
 
 				Ok(result.map(FetchedStorage::Hash))
 			},
 
 			FetchStorageType::Both => {
-				let Some(value) = self.client.query_value(hash, &key, maybe_child_trie.as_ref())?
+				let Some(value) =
+					self.client.query_value(snapshot, &key, maybe_child_trie.as_ref())?
 				else {
 					return Ok(None);
 				};
 
-				let Some(hash) = self.client.query_hash(hash, &key, maybe_child_trie.as_ref())?
+				let Some(hash) = self.client.query_hash(snapshot, &key, maybe_child_trie.as_ref())?
 				else {
 					return Ok(None);
 				};
@@ -214,9 +215,12 @@ where
 		// Iterator over the current block and previous block
 		// at the same time to compare the keys. This approach effectively
 		// leverages backpressure to avoid memory consumption.
-		let keys_iter = self.client.raw_keys_iter(hash, maybe_child_trie.clone())?;
+		// Read each block from a single state, for its keys and values to be consistent.
+		let snapshot = self.client.snapshot(hash)?;
+		let previous_snapshot = self.client.snapshot(previous_hash)?;
+		let keys_iter = self.client.raw_keys_iter(&snapshot, maybe_child_trie.as_ref())?;
 		let previous_keys_iter =
-			self.client.raw_keys_iter(previous_hash, maybe_child_trie.clone())?;
+			self.client.raw_keys_iter(&previous_snapshot, maybe_child_trie.as_ref())?;
 
 		let mut diff_iter = lexicographic_diff(keys_iter, previous_keys_iter);
 
@@ -233,17 +237,21 @@ where
 			};
 
 			let maybe_result = match operation_type {
-				ArchiveStorageDiffOperationType::Added =>
-					self.fetch_storage(hash, key.clone(), maybe_child_trie.clone(), fetch_type)?,
+				ArchiveStorageDiffOperationType::Added => self.fetch_storage(
+					&snapshot,
+					key.clone(),
+					maybe_child_trie.clone(),
+					fetch_type,
+				)?,
 				ArchiveStorageDiffOperationType::Deleted => self.fetch_storage(
-					previous_hash,
+					&previous_snapshot,
 					key.clone(),
 					maybe_child_trie.clone(),
 					fetch_type,
 				)?,
 				ArchiveStorageDiffOperationType::Modified => {
 					let Some(storage_result) = self.fetch_storage(
-						hash,
+						&snapshot,
 						key.clone(),
 						maybe_child_trie.clone(),
 						fetch_type,
@@ -253,7 +261,7 @@ where
 					};
 
 					let Some(previous_storage_result) = self.fetch_storage(
-						previous_hash,
+						&previous_snapshot,
 						key.clone(),
 						maybe_child_trie.clone(),
 						fetch_type,
@@ -40,9 +40,33 @@ pub trait ChainHeadApi<Hash> {
 		name = "chainHead_v1_follow" => "chainHead_v1_followEvent",
 		unsubscribe = "chainHead_v1_unfollow",
 		item = FollowEvent<Hash>,
+		with_extensions,
 	)]
 	fn chain_head_unstable_follow(&self, with_runtime: bool);
 
+	/// Resume a `chainHead_v1_follow` subscription after its connection closed.
+	///
+	/// If the node keeps the subscriptions after their connection closed, the ID of a previous
+	/// subscription can be provided as `resume_token` to resume it. The subscriptions ended by
+	/// `chainHead_v1_unfollow` are released and cannot be resumed. The blocks pinned by the
+	/// previous subscription remain pinned, and the `NewBlock` events are only generated for
+	/// the blocks it did not report. The `Stop` event is generated if the subscription cannot
+	/// be resumed.
+	///
+	/// The resumed subscription is used like the one of `chainHead_v1_follow`, but is ended by
+	/// `chainHead_unstable_unfollowResumed`.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[subscription(
+		name = "chainHead_unstable_resumeFollow" => "chainHead_v1_followEvent",
+		unsubscribe = "chainHead_unstable_unfollowResumed",
+		item = FollowEvent<Hash>,
+		with_extensions,
+	)]
+	fn chain_head_unstable_resume_follow(&self, with_runtime: bool, resume_token: String);
+
 	/// Retrieves the body (list of transactions) of a pinned block.
 	///
 	/// This method should be seen as a complement to `chainHead_v1_follow`,
@@ -45,7 +45,7 @@ use sc_client_api::{
 	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, StorageKey,
 	StorageProvider,
 };
-use sc_rpc::utils::Subscription;
+use sc_rpc::{utils::Subscription, Unsubscriptions};
 use sp_api::CallApiAt;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::{traits::CallContext, Bytes};
@@ -65,6 +65,9 @@ const STORAGE_QUERY_BUF: usize = 16;
 /// The configuration of [`ChainHead`].
 pub struct ChainHeadConfig {
 	/// The maximum number of pinned blocks across all subscriptions.
+	///
+	/// When exceeded, the subscriptions of the connections pinning more than their share of
+	/// this limit are stopped first.
 	pub global_max_pinned_blocks: usize,
 	/// The maximum duration that a block is allowed to be pinned per subscription.
 	pub subscription_max_pinned_duration: Duration,
@@ -77,6 +80,11 @@ pub struct ChainHeadConfig {
 	pub max_follow_subscriptions_per_connection: usize,
 	/// The maximum number of pending messages per subscription.
 	pub subscription_buffer_cap: usize,
+	/// The duration a subscription is kept after its `chainHead_follow` stream ended, such
+	/// that a `chainHead_unstable_resumeFollow` call can resume it after a transient disconnect.
+	///
+	/// Subscriptions cannot be resumed when `None`.
+	pub subscription_resume_grace_period: Option<Duration>,
 }
 
 /// Maximum pinned blocks across all connections.
@@ -110,6 +118,7 @@ impl Default for ChainHeadConfig {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		}
 	}
 }
@@ -150,6 +159,7 @@ impl<BE: Backend<Block>, Block: BlockT, Client> ChainHead<BE, Block, Client> {
 				config.subscription_max_pinned_duration,
 				config.subscription_max_ongoing_operations,
 				config.max_follow_subscriptions_per_connection,
+				config.subscription_resume_grace_period,
 				backend,
 			),
 			max_lagging_distance: config.max_lagging_distance,
@@ -182,8 +192,7 @@ fn parse_hex_param(param: String) -> Result<Vec<u8>, ChainHeadRpcError> {
 	}
 }
 
-#[async_trait]
-impl<BE, Block, Client> ChainHeadApiServer<Block::Hash> for ChainHead<BE, Block, Client>
+impl<BE, Block, Client> ChainHead<BE, Block, Client>
 where
 	Block: BlockT + 'static,
 	Block::Header: Unpin,
@@ -197,7 +206,19 @@ where
 		+ StorageProvider<Block, BE>
 		+ 'static,
 {
-	fn chain_head_unstable_follow(&self, pending: PendingSubscriptionSink, with_runtime: bool) {
+	/// Start a `chainHead_v1_follow` subscription, or resume the one of `resume_token`.
+	///
+	/// The calls of `unfollow_method` are watched to release the subscription when the client
+	/// unfollows it, instead of keeping it to be resumed.
+	fn follow(
+		&self,
+		pending: PendingSubscriptionSink,
+		ext: &Extensions,
+		with_runtime: bool,
+		resume_token: Option<String>,
+		unfollow_method: &'static str,
+	) {
+		let unsubscriptions = ext.get::<Unsubscriptions>().cloned();
 		let subscriptions = self.subscriptions.clone();
 		let backend = self.backend.clone();
 		let client = self.client.clone();
@@ -209,7 +230,8 @@ where
 			let connection_id = pending.connection_id();
 			// The RAII `reserved_subscription` will clean up resources on drop:
 			// - free the reserved subscription for the connection ID.
-			// - remove the subscription ID from the subscription management.
+			// - remove the subscription ID from the subscription management, unless the
+			//   subscription is suspended to be resumed later.
 			let Some(mut reserved_subscription) = subscriptions.reserve_subscription(connection_id)
 			else {
 				pending.reject(ChainHeadRpcError::ReachedLimits).await;
@@ -220,30 +242,71 @@ where
 
 			let sub_id = read_subscription_id_as_string(&sink);
 			// Keep track of the subscription.
-			let Some(sub_data) =
-				reserved_subscription.insert_subscription(sub_id.clone(), with_runtime)
-			else {
-				// Inserting the subscription can only fail if the JsonRPSee generated a duplicate
-				// subscription ID.
-				debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription already accepted", sub_id);
+			let Some(sub_data) = reserved_subscription.insert_subscription(
+				sub_id.clone(),
+				with_runtime,
+				resume_token.as_deref(),
+			) else {
+				if resume_token.is_some() {
+					debug!(
+						target: LOG_TARGET,
+						"[follow][id={:?}] Subscription {:?} cannot be resumed",
+						sub_id,
+						resume_token
+					);
+				} else {
+					// Inserting the subscription can only fail if the JsonRPSee generated a
+					// duplicate subscription ID.
+					debug!(
+						target: LOG_TARGET,
+						"[follow][id={:?}] Subscription already accepted",
+						sub_id
+					);
+				}
 				let _ = sink.send(&FollowEvent::<String>::Stop).await;
 				return
 			};
 			debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription accepted", sub_id);
+			if let Some(unsubscriptions) = &unsubscriptions {
+				unsubscriptions.watch(sub_id.clone(), unfollow_method);
+			}
 
 			let mut chain_head_follow = ChainHeadFollower::new(
 				client,
 				backend,
-				subscriptions,
+				subscriptions.clone(),
 				with_runtime,
 				sub_id.clone(),
 				max_lagging_distance,
 				subscription_buffer_cap,
 			);
 			let result = chain_head_follow.generate_events(sink, sub_data).await;
-			if let Err(SubscriptionManagementError::BlockDistanceTooLarge) = result {
-				debug!(target: LOG_TARGET, "[follow][id={:?}] All subscriptions are stopped", sub_id);
-				reserved_subscription.stop_all_subscriptions();
+			let unfollowed =
+				unsubscriptions.map_or(false, |unsubscriptions| unsubscriptions.take(&sub_id));
+			match result {
+				// The client unfollowed the subscription, its resources are released.
+				Ok(()) if unfollowed => {},
+				// The subscription was stopped and already removed, or the connection was closed.
+				// In the latter case the subscription is kept to be resumed.
+				Ok(()) => {
+					debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription ended", sub_id);
+					if let Some(grace_period) = reserved_subscription.suspend_subscription() {
+						// Release the blocks once the subscription can no longer be resumed,
+						// even if no other subscription is started until then.
+						tokio::time::sleep(grace_period).await;
+						subscriptions.remove_expired_subscriptions();
+					}
+					return
+				},
+				Err(SubscriptionManagementError::BlockDistanceTooLarge) => {
+					debug!(
+						target: LOG_TARGET,
+						"[follow][id={:?}] All subscriptions are stopped",
+						sub_id
+					);
+					reserved_subscription.stop_all_subscriptions();
+				},
+				Err(_) => {},
 			}
 
 			debug!(target: LOG_TARGET, "[follow][id={:?}] Subscription removed", sub_id);
@@ -251,6 +314,42 @@ where
 
 		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
 	}
+}
+
+#[async_trait]
+impl<BE, Block, Client> ChainHeadApiServer<Block::Hash> for ChainHead<BE, Block, Client>
+where
+	Block: BlockT + 'static,
+	Block::Header: Unpin,
+	BE: Backend<Block> + 'static,
+	Client: BlockBackend<Block>
+		+ ExecutorProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ BlockchainEvents<Block>
+		+ CallApiAt<Block>
+		+ StorageProvider<Block, BE>
+		+ 'static,
+{
+	fn chain_head_unstable_follow(
+		&self,
+		pending: PendingSubscriptionSink,
+		ext: &Extensions,
+		with_runtime: bool,
+	) {
+		self.follow(pending, ext, with_runtime, None, "chainHead_v1_unfollow")
+	}
+
+	fn chain_head_unstable_resume_follow(
+		&self,
+		pending: PendingSubscriptionSink,
+		ext: &Extensions,
+		with_runtime: bool,
+		resume_token: String,
+	) {
+		let unfollow_method = "chainHead_unstable_unfollowResumed";
+		self.follow(pending, ext, with_runtime, Some(resume_token), unfollow_method)
+	}
 
 	async fn chain_head_unstable_body(
 		&self,
@@ -430,6 +529,14 @@ where
 				Err(_) => return ResponsePayload::error(ChainHeadRpcError::InvalidBlock),
 			};
 
+		// The main trie and the child tries are queried from the state of the pinned block.
+		let snapshot = match block_guard.storage_snapshot() {
+			Ok(snapshot) => snapshot,
+			Err(err) => {
+				return ResponsePayload::error(ChainHeadRpcError::InternalError(err.to_string()));
+			},
+		};
+
 		let mut storage_client = ChainHeadStorage::<Client, Block, BE>::new(self.client.clone());
 
 		// Storage items are never discarded.
@@ -450,7 +557,7 @@ where
 			// May fail if the channel is closed or the connection is closed.
 			// which is okay to ignore.
 			let _ = futures::future::join(
-				storage_client.generate_events(hash, items, child_trie, tx),
+				storage_client.generate_events(snapshot, items, child_trie, tx),
 				process_storage_items(rx, response_sender, operation_id, &stop_handle),
 			)
 			.await;
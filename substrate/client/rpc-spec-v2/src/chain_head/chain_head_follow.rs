@@ -303,10 +303,12 @@ where
 
 	/// Get the in-memory blocks of the client, starting from the provided finalized hash.
 	///
-	/// The reported blocks are pinned by this function.
+	/// The reported blocks are pinned by this function, except for the blocks
+	/// still pinned by the resumed subscription.
 	fn get_init_blocks_with_forks(
 		&self,
 		finalized: Block::Hash,
+		resumed: &HashSet<Block::Hash>,
 	) -> Result<InitialBlocks<Block>, SubscriptionManagementError> {
 		let blockchain = self.backend.blockchain();
 		let leaves = blockchain.leaves()?;
@@ -336,7 +338,9 @@ where
 
 					if unique_descendants.insert(pair) {
 						// The finalized block is pinned below.
-						self.sub_handle.pin_block(&self.sub_id, child)?;
+						if !resumed.contains(&child) {
+							self.sub_handle.pin_block(&self.sub_id, child)?;
+						}
 						finalized_block_descendants.push(pair);
 					}
 
@@ -355,14 +359,18 @@ where
 		let mut finalized_block_hashes = VecDeque::with_capacity(MAX_FINALIZED_BLOCKS);
 
 		// Pin the finalized block.
-		self.sub_handle.pin_block(&self.sub_id, current_block)?;
+		if !resumed.contains(&current_block) {
+			self.sub_handle.pin_block(&self.sub_id, current_block)?;
+		}
 		finalized_block_hashes.push_front(current_block);
 		current_block = *header.parent_hash();
 
 		for _ in 0..MAX_FINALIZED_BLOCKS - 1 {
 			let Ok(Some(header)) = blockchain.header(current_block) else { break };
 			// Block cannot be reported if pinning fails.
-			if self.sub_handle.pin_block(&self.sub_id, current_block).is_err() {
+			if !resumed.contains(&current_block) &&
+				self.sub_handle.pin_block(&self.sub_id, current_block).is_err()
+			{
 				break
 			};
 
@@ -375,12 +383,17 @@ where
 
 	/// Generate the initial events reported by the RPC `follow` method.
 	///
+	/// For a resumed subscription, the `NewBlock` events of the blocks still pinned by
+	/// the subscription are skipped, and the pinned blocks pruned in the meantime are
+	/// reported by a `Finalized` event without finalized blocks.
+	///
 	/// Returns the initial events that should be reported directly.
 	fn generate_init_events(
 		&mut self,
 		startup_point: &StartupPoint<Block>,
+		resumed: &HashSet<Block::Hash>,
 	) -> Result<Vec<FollowEvent<Block::Hash>>, SubscriptionManagementError> {
-		let init = self.get_init_blocks_with_forks(startup_point.finalized_hash)?;
+		let init = self.get_init_blocks_with_forks(startup_point.finalized_hash, resumed)?;
 
 		// The initialized event is the first one sent.
 		let initial_blocks = init.finalized_block_descendants;
@@ -414,6 +427,11 @@ where
 			}
 			self.announced_blocks.insert(child, false);
 
+			// The client of the resumed subscription already knows about the block.
+			if resumed.contains(&child) {
+				continue
+			}
+
 			let new_runtime = self.generate_runtime_event(child, Some(parent));
 
 			let event = FollowEvent::NewBlock(NewBlock {
@@ -439,9 +457,48 @@ where
 			finalized_block_descendants.push(best_block);
 		};
 
+		let pruned_block_hashes = self.get_pruned_resumed_blocks(startup_point, resumed);
+		if !pruned_block_hashes.is_empty() {
+			finalized_block_descendants.push(FollowEvent::Finalized(Finalized {
+				finalized_block_hashes: Vec::new(),
+				pruned_block_hashes,
+			}));
+		}
+
 		Ok(finalized_block_descendants)
 	}
 
+	/// Get the blocks pinned by the resumed subscription that were pruned while the
+	/// subscription was suspended.
+	///
+	/// These are the blocks that are neither announced by the initial events,
+	/// nor part of the finalized chain.
+	fn get_pruned_resumed_blocks(
+		&mut self,
+		startup_point: &StartupPoint<Block>,
+		resumed: &HashSet<Block::Hash>,
+	) -> Vec<Block::Hash> {
+		let mut pruned = Vec::new();
+		for hash in resumed {
+			if self.announced_blocks.was_announced(hash) {
+				continue
+			}
+
+			let is_finalized = match self.client.number(*hash) {
+				Ok(Some(number)) =>
+					number <= startup_point.finalized_number &&
+						self.client.hash(number).ok().flatten() == Some(*hash),
+				_ => false,
+			};
+			if !is_finalized {
+				// Do not report the block again from the `Finalized` events.
+				self.pruned_blocks.insert(*hash, ());
+				pruned.push(*hash);
+			}
+		}
+		pruned
+	}
+
 	/// Generate the "NewBlock" event and potentially the "BestBlockChanged" event for the
 	/// given block hash.
 	fn generate_import_events(
@@ -776,7 +833,8 @@ where
 			.map(|response| NotificationType::MethodResponse(response));
 
 		let startup_point = StartupPoint::from(self.client.info());
-		let initial_events = match self.generate_init_events(&startup_point) {
+		let resumed_blocks = &sub_data.resumed_blocks;
+		let initial_events = match self.generate_init_events(&startup_point, resumed_blocks) {
 			Ok(blocks) => blocks,
 			Err(err) => {
 				debug!(
@@ -26,7 +26,7 @@ use tokio::sync::mpsc;
 
 use crate::common::{
 	events::{StorageQuery, StorageQueryType},
-	storage::{IterQueryType, QueryIter, QueryResult, Storage},
+	storage::{IterQueryType, QueryIter, QueryResult, Snapshot, Storage},
 };
 
 /// Generates the events of the `chainHead_storage` method.
@@ -58,7 +58,7 @@ where
 	/// Generate the block events for the `chainHead_storage` method.
 	pub async fn generate_events(
 		&mut self,
-		hash: Block::Hash,
+		snapshot: Snapshot<BE, Block>,
 		items: Vec<StorageQuery<StorageKey>>,
 		child_key: Option<ChildInfo>,
 		tx: mpsc::Sender<QueryResult>,
@@ -66,23 +66,27 @@ where
 		let this = self.clone();
 
 		tokio::task::spawn_blocking(move || {
+			// All the queries are made on the same state, for their results to be consistent.
 			for item in items {
 				match item.query_type {
 					StorageQueryType::Value => {
-						let rp = this.client.query_value(hash, &item.key, child_key.as_ref());
+						let rp = this.client.query_value(&snapshot, &item.key, child_key.as_ref());
 						if tx.blocking_send(rp).is_err() {
 							break;
 						}
 					},
 					StorageQueryType::Hash => {
-						let rp = this.client.query_hash(hash, &item.key, child_key.as_ref());
+						let rp = this.client.query_hash(&snapshot, &item.key, child_key.as_ref());
 						if tx.blocking_send(rp).is_err() {
 							break;
 						}
 					},
 					StorageQueryType::ClosestDescendantMerkleValue => {
-						let rp =
-							this.client.query_merkle_value(hash, &item.key, child_key.as_ref());
+						let rp = this.client.query_merkle_value(
+							&snapshot,
+							&item.key,
+							child_key.as_ref(),
+						);
 						if tx.blocking_send(rp).is_err() {
 							break;
 						}
@@ -95,7 +99,7 @@ where
 						};
 						this.client.query_iter_pagination_with_producer(
 							query,
-							hash,
+							&snapshot,
 							child_key.as_ref(),
 							&tx,
 						)
@@ -108,7 +112,7 @@ where
 						};
 						this.client.query_iter_pagination_with_producer(
 							query,
-							hash,
+							&snapshot,
 							child_key.as_ref(),
 							&tx,
 						)
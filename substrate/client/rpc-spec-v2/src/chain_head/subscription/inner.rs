@@ -17,8 +17,9 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use futures::channel::oneshot;
+use jsonrpsee::ConnectionId;
 use parking_lot::Mutex;
-use sc_client_api::Backend;
+use sc_client_api::{Backend, StorageSnapshot};
 use sp_runtime::traits::Block as BlockT;
 use std::{
 	collections::{hash_map::Entry, HashMap, HashSet},
@@ -281,6 +282,8 @@ struct BlockState {
 
 /// The state of a single subscription ID.
 struct SubscriptionState<Block: BlockT> {
+	/// The connection that started the subscription.
+	connection_id: ConnectionId,
 	/// The `with_runtime` parameter flag of the subscription.
 	with_runtime: bool,
 	/// Signals the "Stop" event.
@@ -465,6 +468,13 @@ impl<Block: BlockT, BE: Backend<Block>> BlockGuard<Block, BE> {
 	pub fn operation(&mut self) -> &mut RegisteredOperation {
 		&mut self.operation
 	}
+
+	/// Get a snapshot of the state of the pinned block.
+	///
+	/// Gives access to the main trie and to the child tries of the block.
+	pub fn storage_snapshot(&self) -> sp_blockchain::Result<StorageSnapshot<BE::State, Block>> {
+		self.backend.state_at(self.hash).map(StorageSnapshot::new)
+	}
 }
 
 impl<Block: BlockT, BE: Backend<Block>> Drop for BlockGuard<Block, BE> {
@@ -473,6 +483,42 @@ impl<Block: BlockT, BE: Backend<Block>> Drop for BlockGuard<Block, BE> {
 	}
 }
 
+/// The blocks pinned by the subscriptions of a single connection.
+struct ConnectionPins<Hash> {
+	/// The number of subscriptions of the connection.
+	subscriptions: usize,
+	/// Reference count the block hashes pinned and not yet unpinned across the subscriptions
+	/// of the connection.
+	///
+	/// A block pinned by several subscriptions is counted once, as in the global limit.
+	blocks: HashMap<Hash, usize>,
+}
+
+impl<Hash> Default for ConnectionPins<Hash> {
+	fn default() -> Self {
+		ConnectionPins { subscriptions: 0, blocks: Default::default() }
+	}
+}
+
+impl<Hash: std::hash::Hash + Eq> ConnectionPins<Hash> {
+	/// Register a block pinned by a subscription of the connection.
+	fn pin(&mut self, hash: Hash) {
+		*self.blocks.entry(hash).or_default() += 1;
+	}
+
+	/// Unregister a block unpinned by a subscription of the connection.
+	fn unpin(&mut self, hash: Hash) {
+		if let Entry::Occupied(mut occupied) = self.blocks.entry(hash) {
+			let counter = occupied.get_mut();
+			if *counter == 1 {
+				occupied.remove();
+			} else {
+				*counter -= 1;
+			}
+		}
+	}
+}
+
 /// The data propagated back to the `chainHead_follow` method after
 /// the subscription is successfully inserted.
 pub struct InsertedSubscriptionData<Block: BlockT> {
@@ -480,6 +526,19 @@ pub struct InsertedSubscriptionData<Block: BlockT> {
 	pub rx_stop: oneshot::Receiver<()>,
 	/// Receive message responses from the `chainHead` methods.
 	pub response_receiver: FollowEventReceiver<Block::Hash>,
+	/// The blocks that are still pinned by the resumed subscription.
+	///
+	/// Empty unless the subscription was resumed.
+	pub resumed_blocks: HashSet<Block::Hash>,
+}
+
+/// A subscription whose `chainHead_follow` stream ended, kept until it is resumed
+/// or the resume grace period elapses.
+struct SuspendedSubscription<Block: BlockT> {
+	/// The state of the subscription, keeping its blocks pinned.
+	state: SubscriptionState<Block>,
+	/// The moment the subscription was suspended.
+	since: Instant,
 }
 
 pub struct SubscriptionsInner<Block: BlockT, BE: Backend<Block>> {
@@ -490,12 +549,27 @@ pub struct SubscriptionsInner<Block: BlockT, BE: Backend<Block>> {
 	global_blocks: HashMap<Block::Hash, usize>,
 	/// The maximum number of pinned blocks across all subscriptions.
 	global_max_pinned_blocks: usize,
+	/// Count the blocks pinned by the subscriptions of each connection.
+	///
+	/// When the global limit is exceeded, only the subscriptions of the connections
+	/// pinning more than their share of the limit are stopped.
+	connections: HashMap<ConnectionId, ConnectionPins<Block::Hash>>,
 	/// The maximum duration that a block is allowed to be pinned per subscription.
 	local_max_pin_duration: Duration,
 	/// The maximum number of ongoing operations per subscription.
 	max_ongoing_operations: usize,
 	/// Map the subscription ID to internal details of the subscription.
 	subs: HashMap<String, SubscriptionState<Block>>,
+	/// The duration the subscriptions are kept after their `chainHead_follow` stream ended.
+	///
+	/// Subscriptions cannot be resumed when `None`.
+	resume_grace_period: Option<Duration>,
+	/// Map the subscription ID to the suspended subscriptions that can be resumed.
+	suspended: HashMap<String, SuspendedSubscription<Block>>,
+	/// The maximum number of suspended subscriptions started by a connection.
+	max_suspended_per_connection: usize,
+	/// The maximum number of suspended subscriptions across all connections.
+	max_suspended: usize,
 
 	/// Backend pinning / unpinning blocks.
 	///
@@ -514,24 +588,52 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		SubscriptionsInner {
 			global_blocks: Default::default(),
 			global_max_pinned_blocks,
+			connections: Default::default(),
 			local_max_pin_duration,
 			max_ongoing_operations,
 			subs: Default::default(),
+			resume_grace_period: None,
+			suspended: Default::default(),
+			max_suspended_per_connection: usize::MAX,
+			max_suspended: usize::MAX,
 			backend,
 		}
 	}
 
+	/// Keep the subscriptions for the given duration after their `chainHead_follow` stream
+	/// ended, such that they can be resumed.
+	pub fn with_resume_grace_period(mut self, resume_grace_period: Option<Duration>) -> Self {
+		self.resume_grace_period = resume_grace_period;
+		self
+	}
+
+	/// Limit the number of suspended subscriptions started by a connection, and across all
+	/// connections. The oldest suspended subscriptions are removed first.
+	pub fn with_max_suspended_subscriptions(
+		mut self,
+		max_suspended_per_connection: usize,
+		max_suspended: usize,
+	) -> Self {
+		self.max_suspended_per_connection = max_suspended_per_connection;
+		self.max_suspended = max_suspended;
+		self
+	}
+
 	/// Insert a new subscription ID.
 	pub fn insert_subscription(
 		&mut self,
 		sub_id: String,
 		with_runtime: bool,
+		connection_id: ConnectionId,
 	) -> Option<InsertedSubscriptionData<Block>> {
+		self.remove_expired_subscriptions();
+
 		if let Entry::Vacant(entry) = self.subs.entry(sub_id) {
 			let (tx_stop, rx_stop) = oneshot::channel();
 			let (response_sender, response_receiver) =
 				futures::channel::mpsc::channel(BUF_CAP_PER_SUBSCRIPTION);
 			let state = SubscriptionState::<Block> {
+				connection_id,
 				with_runtime,
 				tx_stop: Some(tx_stop),
 				response_sender,
@@ -539,13 +641,175 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 				operations: Operations::new(self.max_ongoing_operations),
 			};
 			entry.insert(state);
+			self.connections.entry(connection_id).or_default().subscriptions += 1;
 
-			Some(InsertedSubscriptionData { rx_stop, response_receiver })
+			Some(InsertedSubscriptionData {
+				rx_stop,
+				response_receiver,
+				resumed_blocks: Default::default(),
+			})
 		} else {
 			None
 		}
 	}
 
+	/// Resume the suspended subscription identified by the resume token under a new
+	/// subscription ID.
+	///
+	/// The resume token is the ID of the suspended subscription. The blocks it pinned and
+	/// were not unpinned remain pinned for the new subscription and are reported by
+	/// [`InsertedSubscriptionData::resumed_blocks`].
+	///
+	/// Returns `None` if the token does not identify a suspended subscription, its grace period
+	/// elapsed, or the new subscription ID was already inserted.
+	pub fn resume_subscription(
+		&mut self,
+		resume_token: &str,
+		sub_id: String,
+		with_runtime: bool,
+		connection_id: ConnectionId,
+	) -> Option<InsertedSubscriptionData<Block>> {
+		self.remove_expired_subscriptions();
+
+		if self.subs.contains_key(&sub_id) {
+			return None
+		}
+		let SuspendedSubscription { state: mut sub, .. } = self.suspended.remove(resume_token)?;
+
+		// The blocks unpinned by the client are no longer tracked globally. They are reported
+		// again by the resumed subscription if they are still relevant.
+		sub.blocks.retain(|_, state| !state.state_machine.was_unpinned());
+		let resumed_blocks: HashSet<_> = sub.blocks.keys().copied().collect();
+
+		let (tx_stop, rx_stop) = oneshot::channel();
+		let (response_sender, response_receiver) =
+			futures::channel::mpsc::channel(BUF_CAP_PER_SUBSCRIPTION);
+		sub.connection_id = connection_id;
+		sub.with_runtime = with_runtime;
+		sub.tx_stop = Some(tx_stop);
+		sub.response_sender = response_sender;
+		// The operations of the suspended subscription reported to the previous stream.
+		sub.operations = Operations::new(self.max_ongoing_operations);
+
+		let pins = self.connections.entry(connection_id).or_default();
+		pins.subscriptions += 1;
+		resumed_blocks.iter().for_each(|hash| pins.pin(*hash));
+		self.subs.insert(sub_id, sub);
+
+		Some(InsertedSubscriptionData { rx_stop, response_receiver, resumed_blocks })
+	}
+
+	/// Suspend the subscription ID after its `chainHead_follow` stream ended.
+	///
+	/// The pinned blocks are kept for the resume grace period, after which the subscription
+	/// is removed. The subscription is removed directly if it cannot be resumed.
+	///
+	/// Returns the resume grace period if the subscription was suspended.
+	pub fn suspend_subscription(&mut self, sub_id: &str) -> Option<Duration> {
+		self.remove_expired_subscriptions();
+
+		let Some(grace_period) = self.resume_grace_period else {
+			self.remove_subscription(sub_id);
+			return None
+		};
+		let mut sub = self.subs.remove(sub_id)?;
+
+		// The `Stop` event can be generated only once.
+		sub.stop();
+
+		let pinned = sub
+			.blocks
+			.iter()
+			.filter(|(_, state)| !state.state_machine.was_unpinned())
+			.map(|(hash, _)| *hash);
+		self.release_connection(sub.connection_id, pinned);
+
+		// Make space for the subscription within the limits.
+		let connection_id = sub.connection_id;
+		let from_connection = |suspended: &SuspendedSubscription<Block>| {
+			suspended.state.connection_id == connection_id
+		};
+		while self.suspended.values().filter(|suspended| from_connection(suspended)).count() >=
+			self.max_suspended_per_connection &&
+			self.remove_oldest_suspended_subscription(&from_connection)
+		{}
+		while self.suspended.len() >= self.max_suspended &&
+			self.remove_oldest_suspended_subscription(&|_| true)
+		{}
+
+		self.suspended.insert(
+			sub_id.to_string(),
+			SuspendedSubscription { state: sub, since: Instant::now() },
+		);
+		Some(grace_period)
+	}
+
+	/// Remove the oldest suspended subscription matching `filter`.
+	///
+	/// Returns `false` if no suspended subscription matches.
+	fn remove_oldest_suspended_subscription(
+		&mut self,
+		filter: &dyn Fn(&SuspendedSubscription<Block>) -> bool,
+	) -> bool {
+		let oldest = self
+			.suspended
+			.iter()
+			.filter(|(_, suspended)| filter(suspended))
+			.min_by_key(|(_, suspended)| suspended.since)
+			.map(|(sub_id, _)| sub_id.clone());
+		let Some(sub_id) = oldest else { return false };
+		self.remove_suspended_subscription(&sub_id);
+		true
+	}
+
+	/// Remove the suspended subscription ID with associated pinned blocks.
+	fn remove_suspended_subscription(&mut self, sub_id: &str) {
+		let Some(suspended) = self.suspended.remove(sub_id) else { return };
+
+		for (hash, state) in suspended.state.blocks.iter() {
+			if !state.state_machine.was_unpinned() {
+				self.global_unregister_block(*hash);
+			}
+		}
+	}
+
+	/// Remove the suspended subscriptions whose resume grace period elapsed.
+	pub fn remove_expired_subscriptions(&mut self) {
+		let Some(grace_period) = self.resume_grace_period else { return };
+		if self.suspended.is_empty() {
+			return
+		}
+
+		let now = Instant::now();
+		let expired: Vec<_> = self
+			.suspended
+			.iter()
+			.filter_map(|(sub_id, suspended)| {
+				(now.saturating_duration_since(suspended.since) >= grace_period)
+					.then(|| sub_id.clone())
+			})
+			.collect();
+		for sub_id in expired {
+			self.remove_suspended_subscription(&sub_id);
+		}
+	}
+
+	/// Release the subscription and its pinned blocks from the connection accounting.
+	fn release_connection(
+		&mut self,
+		connection_id: ConnectionId,
+		pinned: impl IntoIterator<Item = Block::Hash>,
+	) {
+		if let Entry::Occupied(mut occupied) = self.connections.entry(connection_id) {
+			let pins = occupied.get_mut();
+			pinned.into_iter().for_each(|hash| pins.unpin(hash));
+			pins.subscriptions -= 1;
+			if pins.subscriptions == 0 {
+				occupied.remove();
+			}
+		}
+	}
+
 	/// Remove the subscription ID with associated pinned blocks.
 	pub fn remove_subscription(&mut self, sub_id: &str) {
 		let Some(mut sub) = self.subs.remove(sub_id) else { return };
@@ -553,31 +817,42 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		// The `Stop` event can be generated only once.
 		sub.stop();
 
+		let mut pinned = Vec::new();
 		for (hash, state) in sub.blocks.iter() {
 			if !state.state_machine.was_unpinned() {
 				self.global_unregister_block(*hash);
+				pinned.push(*hash);
 			}
 		}
+
+		self.release_connection(sub.connection_id, pinned);
 	}
 
-	/// All active subscriptions are removed.
+	/// All active and suspended subscriptions are removed.
 	pub fn stop_all_subscriptions(&mut self) {
 		let to_remove: Vec<_> = self.subs.keys().map(|sub_id| sub_id.clone()).collect();
 
 		for sub_id in to_remove {
 			self.remove_subscription(&sub_id);
 		}
+
+		let to_remove: Vec<_> = self.suspended.keys().map(|sub_id| sub_id.clone()).collect();
+		for sub_id in to_remove {
+			self.remove_suspended_subscription(&sub_id);
+		}
 	}
 
 	/// Ensure that a new block could be pinned.
 	///
 	/// If the global number of blocks has been reached this method
-	/// will remove all subscriptions that have blocks older than the
-	/// specified pin duration.
+	/// will first remove the suspended subscriptions, and then all
+	/// subscriptions that have blocks older than the specified pin duration.
 	///
 	/// If after removing all subscriptions that exceed the pin duration
-	/// there is no space for pinning a new block, then all subscriptions
-	/// are terminated.
+	/// there is no space for pinning a new block, then the subscriptions
+	/// of the connections pinning more than their share of the global limit
+	/// are terminated. If there is still no space, all subscriptions are
+	/// terminated.
 	///
 	/// Returns true if the given subscription is also terminated.
 	fn ensure_block_space(&mut self, request_sub_id: &str) -> bool {
@@ -585,6 +860,17 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 			return false
 		}
 
+		// The blocks of the active subscriptions take precedence over the blocks kept
+		// for resuming subscriptions.
+		let to_remove: Vec<_> = self.suspended.keys().map(|sub_id| sub_id.clone()).collect();
+		for sub_id in to_remove {
+			self.remove_suspended_subscription(&sub_id);
+		}
+
+		if self.global_blocks.len() < self.global_max_pinned_blocks {
+			return false
+		}
+
 		// Terminate all subscriptions that have blocks older than
 		// the specified pin duration.
 		let now = Instant::now();
@@ -616,6 +902,29 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 			return is_terminated
 		}
 
+		// Terminate the subscriptions of the connections pinning more than their
+		// share of blocks, keeping the well-behaved connections alive.
+		let share = self.global_max_pinned_blocks / self.connections.len().max(1);
+		let to_remove: Vec<_> = self
+			.subs
+			.iter()
+			.filter_map(|(sub_id, sub)| {
+				let pinned =
+					self.connections.get(&sub.connection_id).map_or(0, |pins| pins.blocks.len());
+				(pinned > share).then(|| sub_id.clone())
+			})
+			.collect();
+		for sub_id in to_remove {
+			if sub_id == request_sub_id {
+				is_terminated = true;
+			}
+			self.remove_subscription(&sub_id);
+		}
+
+		if self.global_blocks.len() < self.global_max_pinned_blocks {
+			return is_terminated
+		}
+
 		// Sanity check: cannot uphold `chainHead` guarantees anymore. We have not
 		// found any subscriptions that have older pinned blocks to terminate.
 		let to_remove: Vec<_> = self.subs.keys().map(|sub_id| sub_id.clone()).collect();
@@ -633,6 +942,8 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		sub_id: &str,
 		hash: Block::Hash,
 	) -> Result<bool, SubscriptionManagementError> {
+		self.remove_expired_subscriptions();
+
 		let Some(sub) = self.subs.get_mut(sub_id) else {
 			return Err(SubscriptionManagementError::SubscriptionAbsent)
 		};
@@ -642,6 +953,7 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		if !sub.register_block(hash) {
 			return Ok(false)
 		}
+		self.connections.entry(sub.connection_id).or_default().pin(hash);
 
 		// Ensure we have enough space only if the hash is not globally registered.
 		if !self.global_blocks.contains_key(&hash) {
@@ -733,8 +1045,12 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		// thinking we borrow `&mut self` twice: once from `self.subs.get_mut` and once from
 		// `self.global_unregister_block`. Although the borrowing is correct, since different
 		// fields of the structure are borrowed, one at a time.
+		let mut pins = self.connections.get_mut(&sub.connection_id);
 		for hash in hashes.clone() {
 			sub.unregister_block(hash);
+			if let Some(pins) = pins.as_mut() {
+				pins.unpin(hash);
+			}
 		}
 
 		// Block have been removed from the subscription. Remove them from the global tracking.
@@ -983,13 +1299,13 @@ mod tests {
 		let id_2 = "abcd".to_string();
 
 		// Pin all blocks for the first subscription.
-		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_3).unwrap(), true);
 
 		// Pin only block 2 for the second subscription.
-		let _stop = subs.insert_subscription(id_2.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
 
 		// Check reference count.
@@ -1027,9 +1343,9 @@ mod tests {
 		let err = subs.lock_block(&id, hash, 1).unwrap_err();
 		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
 
-		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
 		// Cannot insert the same subscription ID twice.
-		assert!(subs.insert_subscription(id.clone(), true).is_none());
+		assert!(subs.insert_subscription(id.clone(), true, ConnectionId(1)).is_none());
 
 		// No block hash.
 		let err = subs.lock_block(&id, hash, 1).unwrap_err();
@@ -1053,7 +1369,7 @@ mod tests {
 			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
 		let id = "abc".to_string();
 
-		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
 
 		// First time we are pinning the block.
 		assert_eq!(subs.pin_block(&id, hash).unwrap(), true);
@@ -1083,7 +1399,7 @@ mod tests {
 			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
 		let id = "abc".to_string();
 
-		let _stop = subs.insert_subscription(id.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id, hash).unwrap(), true);
 		// Check the global ref count.
 		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 1);
@@ -1097,7 +1413,7 @@ mod tests {
 
 		// Ensure the hash propagates for the second subscription.
 		let id_second = "abcd".to_string();
-		let _stop = subs.insert_subscription(id_second.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_second.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_second, hash).unwrap(), true);
 		// Check the global ref count.
 		assert_eq!(*subs.global_blocks.get(&hash).unwrap(), 2);
@@ -1128,13 +1444,13 @@ mod tests {
 		let id_2 = "abcd".to_string();
 
 		// Pin all blocks for the first subscription.
-		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_3).unwrap(), true);
 
 		// Pin only block 2 for the second subscription.
-		let _stop = subs.insert_subscription(id_2.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
 
 		// Check reference count.
@@ -1168,11 +1484,11 @@ mod tests {
 		let id_2 = "abcd".to_string();
 
 		// Both subscriptions can pin the maximum limit.
-		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
 
-		let _stop = subs.insert_subscription(id_2.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_2, hash_1).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
 
@@ -1212,7 +1528,7 @@ mod tests {
 		let id_1 = "abc".to_string();
 		let id_2 = "abcd".to_string();
 
-		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
 
@@ -1220,7 +1536,7 @@ mod tests {
 		// the first subscription.
 		std::thread::sleep(std::time::Duration::from_secs(5));
 
-		let _stop = subs.insert_subscription(id_2.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_2, hash_1).unwrap(), true);
 
 		// Check reference count.
@@ -1253,6 +1569,54 @@ mod tests {
 		assert_eq!(subs.global_blocks.len(), 0);
 	}
 
+	#[test]
+	fn subscription_check_limits_per_connection() {
+		let (backend, client) = init_backend();
+
+		let hashes = produce_blocks(client, 4);
+		let (hash_1, hash_2, hash_3, hash_4) = (hashes[0], hashes[1], hashes[2], hashes[3]);
+
+		// Maximum number of pinned blocks is 3, shared by 2 connections.
+		let mut subs =
+			SubscriptionsInner::new(3, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+		let id_3 = "abcde".to_string();
+
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
+
+		// The second connection pins blocks from two subscriptions.
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(2)).unwrap();
+		let _stop = subs.insert_subscription(id_3.clone(), true, ConnectionId(2)).unwrap();
+		assert_eq!(subs.pin_block(&id_2, hash_1).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_3, hash_3).unwrap(), true);
+		// A block pinned by both subscriptions is counted once.
+		assert_eq!(subs.pin_block(&id_3, hash_1).unwrap(), true);
+		assert_eq!(subs.connections.get(&ConnectionId(2)).unwrap().blocks.len(), 3);
+
+		// Block 4 pinning will exceed the limit. Only the subscriptions of the second
+		// connection are terminated, since it pins more than its share of blocks.
+		let err = subs.pin_block(&id_3, hash_4).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::ExceededLimits);
+
+		let err = subs.lock_block(&id_2, hash_1, 1).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
+		let err = subs.lock_block(&id_3, hash_3, 1).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
+		assert!(subs.connections.get(&ConnectionId(2)).is_none());
+
+		// The first connection is kept alive.
+		let _block_guard = subs.lock_block(&id_1, hash_1, 1).unwrap();
+		assert_eq!(subs.global_blocks.len(), 1);
+		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
+		assert_eq!(subs.connections.get(&ConnectionId(1)).unwrap().blocks.len(), 2);
+
+		subs.unpin_blocks(&id_1, vec![hash_1, hash_2]).unwrap();
+		assert_eq!(subs.connections.get(&ConnectionId(1)).unwrap().blocks.len(), 0);
+	}
+
 	#[test]
 	fn subscription_check_stop_event() {
 		let builder = TestClientBuilder::new();
@@ -1262,7 +1626,7 @@ mod tests {
 
 		let id = "abc".to_string();
 
-		let mut sub_data = subs.insert_subscription(id.clone(), true).unwrap();
+		let mut sub_data = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
 
 		// Check the stop signal was not received.
 		let res = sub_data.rx_stop.try_recv().unwrap();
@@ -1315,13 +1679,13 @@ mod tests {
 		let id_2 = "abcd".to_string();
 
 		// Pin all blocks for the first subscription.
-		let _stop = subs.insert_subscription(id_1.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
 		assert_eq!(subs.pin_block(&id_1, hash_3).unwrap(), true);
 
 		// Pin only block 2 for the second subscription.
-		let _stop = subs.insert_subscription(id_2.clone(), true).unwrap();
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(1)).unwrap();
 		assert_eq!(subs.pin_block(&id_2, hash_2).unwrap(), true);
 
 		// Check reference count.
@@ -1335,6 +1699,161 @@ mod tests {
 		assert!(subs.global_blocks.is_empty());
 	}
 
+	#[test]
+	fn suspend_and_resume_subscription() {
+		let (backend, client) = init_backend();
+
+		let hashes = produce_blocks(client, 3);
+		let (hash_1, hash_2, hash_3) = (hashes[0], hashes[1], hashes[2]);
+
+		let mut subs =
+			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend)
+				.with_resume_grace_period(Some(Duration::from_secs(10)));
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_1, hash_3).unwrap(), true);
+		subs.unpin_blocks(&id_1, vec![hash_3]).unwrap();
+
+		// The suspended subscription keeps its blocks pinned.
+		subs.suspend_subscription(&id_1);
+		assert!(subs.subs.is_empty());
+		assert!(subs.connections.is_empty());
+		assert_eq!(*subs.global_blocks.get(&hash_1).unwrap(), 1);
+		assert_eq!(*subs.global_blocks.get(&hash_2).unwrap(), 1);
+		assert!(subs.global_blocks.get(&hash_3).is_none());
+
+		// Unknown resume token.
+		assert!(subs.resume_subscription("xyz", id_2.clone(), true, ConnectionId(2)).is_none());
+
+		let sub_data =
+			subs.resume_subscription(&id_1, id_2.clone(), true, ConnectionId(2)).unwrap();
+		assert_eq!(sub_data.resumed_blocks, HashSet::from([hash_1, hash_2]));
+		assert_eq!(subs.connections.get(&ConnectionId(2)).unwrap().blocks.len(), 2);
+		assert_eq!(*subs.global_blocks.get(&hash_1).unwrap(), 1);
+
+		// The blocks are available under the new subscription ID.
+		let _block_guard = subs.lock_block(&id_2, hash_1, 1).unwrap();
+		let err = subs.lock_block(&id_1, hash_1, 1).unwrap_err();
+		assert_eq!(err, SubscriptionManagementError::SubscriptionAbsent);
+
+		// The subscription can be resumed only once.
+		assert!(subs.resume_subscription(&id_1, "abcde".into(), true, ConnectionId(2)).is_none());
+
+		subs.remove_subscription(&id_2);
+		assert_eq!(subs.global_blocks.len(), 0);
+	}
+
+	#[test]
+	fn suspend_subscription_without_grace_period() {
+		let (backend, client) = init_backend();
+
+		let hashes = produce_blocks(client, 1);
+		let hash_1 = hashes[0];
+
+		let mut subs =
+			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend);
+		let id = "abc".to_string();
+
+		let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
+		assert_eq!(subs.pin_block(&id, hash_1).unwrap(), true);
+
+		// The subscription cannot be resumed and is removed.
+		subs.suspend_subscription(&id);
+		assert!(subs.suspended.is_empty());
+		assert_eq!(subs.global_blocks.len(), 0);
+		assert!(subs.resume_subscription(&id, "abcd".into(), true, ConnectionId(1)).is_none());
+	}
+
+	#[test]
+	fn suspended_subscription_expires() {
+		let (backend, client) = init_backend();
+
+		let hashes = produce_blocks(client, 1);
+		let hash_1 = hashes[0];
+
+		let mut subs =
+			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend)
+				.with_resume_grace_period(Some(Duration::from_millis(10)));
+		let id = "abc".to_string();
+
+		let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
+		assert_eq!(subs.pin_block(&id, hash_1).unwrap(), true);
+		subs.suspend_subscription(&id);
+		assert_eq!(subs.global_blocks.len(), 1);
+
+		// The blocks are unpinned once the grace period elapsed.
+		std::thread::sleep(std::time::Duration::from_millis(20));
+		assert!(subs.resume_subscription(&id, "abcd".into(), true, ConnectionId(1)).is_none());
+		assert!(subs.suspended.is_empty());
+		assert_eq!(subs.global_blocks.len(), 0);
+	}
+
+	#[test]
+	fn suspended_subscriptions_released_first() {
+		let (backend, client) = init_backend();
+
+		let hashes = produce_blocks(client, 3);
+		let (hash_1, hash_2, hash_3) = (hashes[0], hashes[1], hashes[2]);
+
+		// Maximum number of pinned blocks is 2.
+		let mut subs =
+			SubscriptionsInner::new(2, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend)
+				.with_resume_grace_period(Some(Duration::from_secs(10)));
+		let id_1 = "abc".to_string();
+		let id_2 = "abcd".to_string();
+
+		let _stop = subs.insert_subscription(id_1.clone(), true, ConnectionId(1)).unwrap();
+		assert_eq!(subs.pin_block(&id_1, hash_1).unwrap(), true);
+		assert_eq!(subs.pin_block(&id_1, hash_2).unwrap(), true);
+		subs.suspend_subscription(&id_1);
+
+		// The suspended subscription is released to make space for the active one.
+		let _stop = subs.insert_subscription(id_2.clone(), true, ConnectionId(2)).unwrap();
+		assert_eq!(subs.pin_block(&id_2, hash_3).unwrap(), true);
+
+		assert!(subs.suspended.is_empty());
+		assert_eq!(subs.global_blocks.len(), 1);
+		assert_eq!(*subs.global_blocks.get(&hash_3).unwrap(), 1);
+	}
+
+	#[test]
+	fn suspended_subscriptions_are_limited() {
+		let (backend, client) = init_backend();
+
+		let hashes = produce_blocks(client, 3);
+
+		let mut subs =
+			SubscriptionsInner::new(10, Duration::from_secs(10), MAX_OPERATIONS_PER_SUB, backend)
+				.with_resume_grace_period(Some(Duration::from_secs(10)))
+				.with_max_suspended_subscriptions(2, 3);
+
+		// A connection follows and suspends repeatedly.
+		for (index, hash) in hashes.iter().enumerate() {
+			let id = format!("conn1-{index}");
+			let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(1)).unwrap();
+			assert_eq!(subs.pin_block(&id, *hash).unwrap(), true);
+			subs.suspend_subscription(&id);
+		}
+		// The oldest suspended subscription of the connection is removed with its blocks.
+		assert_eq!(subs.suspended.len(), 2);
+		assert!(!subs.suspended.contains_key("conn1-0"));
+		assert!(subs.global_blocks.get(&hashes[0]).is_none());
+
+		// The oldest suspended subscription of any connection is removed past the global limit.
+		for index in 0..2 {
+			let id = format!("conn2-{index}");
+			let _stop = subs.insert_subscription(id.clone(), true, ConnectionId(2)).unwrap();
+			subs.suspend_subscription(&id);
+		}
+		assert_eq!(subs.suspended.len(), 3);
+		assert!(!subs.suspended.contains_key("conn1-1"));
+		assert!(subs.global_blocks.get(&hashes[1]).is_none());
+	}
+
 	#[test]
 	fn reserved_subscription_cleans_resources() {
 		let builder = TestClientBuilder::new();
@@ -1372,9 +1891,9 @@ mod tests {
 
 		// Insert subscriptions.
 		let _sub_data_first =
-			reserved_sub_first.insert_subscription("sub1".to_string(), true).unwrap();
+			reserved_sub_first.insert_subscription("sub1".to_string(), true, None).unwrap();
 		let _sub_data_second =
-			reserved_sub_second.insert_subscription("sub2".to_string(), true).unwrap();
+			reserved_sub_second.insert_subscription("sub2".to_string(), true, None).unwrap();
 		// Check we have 2 subscriptions under management.
 		assert_eq!(subs.read().subs.len(), 2);
 
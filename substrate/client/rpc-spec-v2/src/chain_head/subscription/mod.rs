@@ -36,6 +36,9 @@ pub use self::inner::OperationState;
 pub use error::SubscriptionManagementError;
 pub use inner::{BlockGuard, InsertedSubscriptionData, StopHandle};
 
+/// The maximum number of suspended subscriptions across all connections.
+const MAX_SUSPENDED_SUBSCRIPTIONS: usize = 1024;
+
 /// Manage block pinning / unpinning for subscription IDs.
 pub struct SubscriptionManagement<Block: BlockT, BE: Backend<Block>> {
 	/// Manage subscription by mapping the subscription ID
@@ -65,15 +68,23 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		local_max_pin_duration: Duration,
 		max_ongoing_operations: usize,
 		max_follow_subscriptions_per_connection: usize,
+		resume_grace_period: Option<Duration>,
 		backend: Arc<BE>,
 	) -> Self {
 		SubscriptionManagement {
-			inner: Arc::new(RwLock::new(SubscriptionsInner::new(
-				global_max_pinned_blocks,
-				local_max_pin_duration,
-				max_ongoing_operations,
-				backend,
-			))),
+			inner: Arc::new(RwLock::new(
+				SubscriptionsInner::new(
+					global_max_pinned_blocks,
+					local_max_pin_duration,
+					max_ongoing_operations,
+					backend,
+				)
+				.with_resume_grace_period(resume_grace_period)
+				.with_max_suspended_subscriptions(
+					max_follow_subscriptions_per_connection,
+					MAX_SUSPENDED_SUBSCRIPTIONS,
+				),
+			)),
 			rpc_connections: RpcConnections::new(max_follow_subscriptions_per_connection),
 		}
 	}
@@ -102,6 +113,7 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 
 		Some(ReservedSubscription {
 			state: ConnectionState::Reserved(reserved_token),
+			connection_id,
 			inner: self.inner.clone(),
 		})
 	}
@@ -121,6 +133,12 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		inner.remove_subscription(sub_id)
 	}
 
+	/// Remove the suspended subscriptions whose resume grace period elapsed.
+	pub fn remove_expired_subscriptions(&self) {
+		let mut inner = self.inner.write();
+		inner.remove_expired_subscriptions()
+	}
+
 	/// The block is pinned in the backend only once when the block's hash is first encountered.
 	///
 	/// Each subscription is expected to call this method twice:
@@ -197,6 +215,7 @@ enum ConnectionState {
 /// gives back the reserved space for the connection.
 pub struct ReservedSubscription<Block: BlockT, BE: Backend<Block>> {
 	state: ConnectionState,
+	connection_id: ConnectionId,
 	inner: Arc<RwLock<SubscriptionsInner<Block, BE>>>,
 }
 
@@ -207,6 +226,9 @@ impl<Block: BlockT, BE: Backend<Block>> ReservedSubscription<Block, BE> {
 	/// triggered upon the "Stop" event. Otherwise, if the subscription ID was already
 	/// inserted returns none.
 	///
+	/// When a resume token is provided, the suspended subscription it identifies is adopted
+	/// instead. Returns none if the subscription cannot be resumed.
+	///
 	/// # Note
 	///
 	/// This method should be called only once.
@@ -214,6 +236,7 @@ impl<Block: BlockT, BE: Backend<Block>> ReservedSubscription<Block, BE> {
 		&mut self,
 		sub_id: String,
 		runtime_updates: bool,
+		resume_token: Option<&str>,
 	) -> Option<InsertedSubscriptionData<Block>> {
 		match std::mem::replace(&mut self.state, ConnectionState::Empty) {
 			ConnectionState::Reserved(reserved) => {
@@ -224,7 +247,15 @@ impl<Block: BlockT, BE: Backend<Block>> ReservedSubscription<Block, BE> {
 				};
 
 				let mut inner = self.inner.write();
-				inner.insert_subscription(sub_id, runtime_updates)
+				match resume_token {
+					Some(resume_token) => inner.resume_subscription(
+						resume_token,
+						sub_id,
+						runtime_updates,
+						self.connection_id,
+					),
+					None => inner.insert_subscription(sub_id, runtime_updates, self.connection_id),
+				}
 			},
 			// Cannot insert multiple subscriptions into one single reserved space.
 			ConnectionState::Registered { .. } | ConnectionState::Empty => {
@@ -237,11 +268,27 @@ impl<Block: BlockT, BE: Backend<Block>> ReservedSubscription<Block, BE> {
 	/// Stop all active subscriptions.
 	///
 	/// For all active subscriptions, the internal data is discarded, blocks are unpinned and the
-	/// `Stop` event will be generated.
+	/// `Stop` event will be generated. The suspended subscriptions are discarded as well.
 	pub fn stop_all_subscriptions(&self) {
 		let mut inner = self.inner.write();
 		inner.stop_all_subscriptions()
 	}
+
+	/// Suspend the subscription after its `chainHead_follow` stream ended.
+	///
+	/// Gives back the reserved space for the connection, while the pinned blocks are kept
+	/// such that a new `chainHead_follow` call can resume the subscription.
+	///
+	/// Returns the resume grace period, after which the suspended subscription expires, if the
+	/// subscription was suspended.
+	pub fn suspend_subscription(mut self) -> Option<Duration> {
+		let state = std::mem::replace(&mut self.state, ConnectionState::Empty);
+		match state {
+			ConnectionState::Registered { sub_id, .. } =>
+				self.inner.write().suspend_subscription(&sub_id),
+			ConnectionState::Reserved(_) | ConnectionState::Empty => None,
+		}
+	}
 }
 
 impl<Block: BlockT, BE: Backend<Block>> Drop for ReservedSubscription<Block, BE> {
@@ -32,7 +32,7 @@ use jsonrpsee::{
 	rpc_params, MethodsError as Error, RpcModule,
 };
 use sc_block_builder::BlockBuilderBuilder;
-use sc_client_api::ChildInfo;
+use sc_client_api::{ChildInfo, MerkleValue, StorageKey, StorageProvider};
 use sc_rpc::testing::TokioTestExecutor;
 use sc_service::client::new_with_backend;
 use sp_blockchain::HeaderBackend;
@@ -87,6 +87,7 @@ pub async fn run_server() -> std::net::SocketAddr {
 			max_follow_subscriptions_per_connection: 1,
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -149,6 +150,7 @@ async fn setup_api() -> (
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -257,6 +259,7 @@ async fn follow_subscription_produces_blocks() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -310,6 +313,116 @@ async fn follow_subscription_produces_blocks() {
 	assert_eq!(event, expected);
 }
 
+#[tokio::test]
+async fn follow_resume_subscription() {
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		Arc::new(TokioTestExecutor::default()),
+		ChainHeadConfig {
+			global_max_pinned_blocks: MAX_PINNED_BLOCKS,
+			subscription_max_pinned_duration: Duration::from_secs(MAX_PINNED_SECS),
+			subscription_max_ongoing_operations: MAX_OPERATIONS,
+			max_lagging_distance: MAX_LAGGING_DISTANCE,
+			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
+			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: Some(Duration::from_secs(MAX_PINNED_SECS)),
+		},
+	)
+	.into_rpc();
+
+	let finalized_hash = client.info().finalized_hash;
+	let mut sub = api.subscribe_unbounded("chainHead_v1_follow", [false]).await.unwrap();
+	let sub_id = serde_json::to_string(&sub.subscription_id()).unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::Initialized(_)
+	);
+
+	let block_1 = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+
+	// Drop the subscription and import a block it does not report. Without the server middleware
+	// the unsubscription is not noted, as for a closed connection.
+	drop(sub);
+	let block_2 = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_1_hash)
+		.with_parent_block_number(1)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+	// Ensure the `chainHead_unfollow` is propagated to the server.
+	tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+	let mut sub = api
+		.subscribe_unbounded("chainHead_unstable_resumeFollow", rpc_params![false, &sub_id])
+		.await
+		.unwrap();
+	let resumed_sub_id = serde_json::to_string(&sub.subscription_id()).unwrap();
+
+	// The catch-up events only report the block that was missed.
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::Initialized(Initialized {
+		finalized_block_hashes: vec![format!("{:?}", finalized_hash)],
+		finalized_block_runtime: None,
+		with_runtime: false,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::NewBlock(NewBlock {
+		block_hash: format!("{:?}", block_2_hash),
+		parent_block_hash: format!("{:?}", block_1_hash),
+		new_runtime: None,
+		with_runtime: false,
+	});
+	assert_eq!(event, expected);
+
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::BestBlockChanged(BestBlockChanged {
+		best_block_hash: format!("{:?}", block_2_hash),
+	});
+	assert_eq!(event, expected);
+
+	// The block reported by the previous subscription is still pinned.
+	let block_1_hash = format!("{:?}", block_1_hash);
+	let res: Option<String> =
+		api.call("chainHead_v1_header", [&resumed_sub_id, &block_1_hash]).await.unwrap();
+	assert!(res.is_some());
+
+	// The subscription was already resumed.
+	let mut sub = api
+		.subscribe_unbounded("chainHead_unstable_resumeFollow", rpc_params![false, &sub_id])
+		.await
+		.unwrap();
+	assert_matches!(get_next_event::<FollowEvent<String>>(&mut sub).await, FollowEvent::Stop);
+}
+
 #[tokio::test]
 async fn follow_with_runtime() {
 	let builder = TestClientBuilder::new();
@@ -327,6 +440,7 @@ async fn follow_with_runtime() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -636,6 +750,7 @@ async fn call_runtime_without_flag() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1097,6 +1212,71 @@ async fn get_storage_value() {
 	);
 }
 
+#[tokio::test]
+async fn get_child_storage_value_and_merkle_value() {
+	let (client, api, mut block_sub, sub_id, _) = setup_api().await;
+	let genesis_hash = client.genesis_hash();
+	let key = hex_string(&KEY);
+
+	// Child value set in `setup_api`.
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let child_trie_key = hex_string(&CHILD_STORAGE_KEY);
+	let expected_merkle_value = match client
+		.child_closest_merkle_value(genesis_hash, &child_info, &StorageKey(KEY.to_vec()))
+		.unwrap()
+		.unwrap()
+	{
+		MerkleValue::Node(data) => hex_string(&data.as_slice()),
+		MerkleValue::Hash(hash) => hex_string(&hash.as_ref()),
+	};
+
+	let response: MethodResponse = api
+		.call(
+			"chainHead_v1_storage",
+			rpc_params![
+				&sub_id,
+				&format!("{:?}", genesis_hash),
+				vec![
+					StorageQuery { key: key.clone(), query_type: StorageQueryType::Value },
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::ClosestDescendantMerkleValue
+					}
+				],
+				&child_trie_key
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	let expected_value = hex_string(&CHILD_VALUE);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == key &&
+			res.items[0].result == StorageResultType::Value(expected_value) &&
+			res.items[0].child_trie_key.as_ref() == Some(&child_trie_key)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageItems(res) if res.operation_id == operation_id &&
+			res.items.len() == 1 &&
+			res.items[0].key == key &&
+			res.items[0].result ==
+				StorageResultType::ClosestDescendantMerkleValue(expected_merkle_value) &&
+			res.items[0].child_trie_key.as_ref() == Some(&child_trie_key)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+		FollowEvent::OperationStorageDone(done) if done.operation_id == operation_id
+	);
+}
+
 #[tokio::test]
 async fn get_storage_non_queryable_key() {
 	let (mut _client, api, mut block_sub, sub_id, block) = setup_api().await;
@@ -1296,6 +1476,7 @@ async fn separate_operation_ids_for_subscriptions() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1383,6 +1564,7 @@ async fn follow_generates_initial_blocks() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1540,6 +1722,7 @@ async fn follow_exceeding_pinned_blocks() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1618,6 +1801,7 @@ async fn follow_with_unpin() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1725,6 +1909,7 @@ async fn unpin_duplicate_hashes() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1829,6 +2014,7 @@ async fn follow_with_multiple_unpin_hashes() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -1975,6 +2161,7 @@ async fn follow_prune_best_block() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -2162,6 +2349,7 @@ async fn follow_forks_pruned_block() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -2323,6 +2511,7 @@ async fn follow_report_multiple_pruned_block() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -2570,6 +2759,7 @@ async fn pin_block_references() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -2706,6 +2896,7 @@ async fn follow_finalized_before_new_block() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -2822,6 +3013,7 @@ async fn ensure_operation_limits_works() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -2928,6 +3120,7 @@ async fn storage_is_backpressured() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -3066,6 +3259,7 @@ async fn stop_storage_operation() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -3367,6 +3561,7 @@ async fn chain_head_stop_all_subscriptions() {
 			max_lagging_distance: 5,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -3445,7 +3640,7 @@ async fn chain_head_single_connection_context() {
 		.unwrap();
 
 	let mut sub: RpcClientSubscription<FollowEvent<String>> =
-		ChainHeadApiClient::<String>::chain_head_unstable_follow(&client, true)
+		ChainHeadApiClient::<String>::chain_head_unstable_follow(&client, true, None)
 			.await
 			.unwrap();
 
@@ -3581,6 +3776,7 @@ async fn chain_head_limit_reached() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: 1,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -3622,6 +3818,7 @@ async fn follow_unique_pruned_blocks() {
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -3792,6 +3989,7 @@ async fn follow_report_best_block_of_a_known_block() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -4011,6 +4209,7 @@ async fn follow_event_with_unknown_parent() {
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			subscription_buffer_cap: MAX_PINNED_BLOCKS,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
@@ -4078,6 +4277,7 @@ async fn events_are_backpressured() {
 			max_lagging_distance: MAX_LAGGING_DISTANCE,
 			max_follow_subscriptions_per_connection: MAX_FOLLOW_SUBSCRIPTIONS_PER_CONNECTION,
 			subscription_buffer_cap: 10,
+			subscription_resume_grace_period: None,
 		},
 	)
 	.into_rpc();
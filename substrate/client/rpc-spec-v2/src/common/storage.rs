@@ -20,7 +20,7 @@
 
 use std::{marker::PhantomData, sync::Arc};
 
-use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider, StorageSnapshot};
 use sp_runtime::traits::Block as BlockT;
 use tokio::sync::mpsc;
 
@@ -70,23 +70,31 @@ pub enum IterQueryType {
 /// The result of making a query call.
 pub type QueryResult = Result<Option<StorageResult>, String>;
 
+/// The snapshot of the state of a block the queries are made on.
+pub type Snapshot<BE, Block> = StorageSnapshot<<BE as Backend<Block>>::State, Block>;
+
 impl<Client, Block, BE> Storage<Client, Block, BE>
 where
 	Block: BlockT + 'static,
 	BE: Backend<Block> + 'static,
 	Client: StorageProvider<Block, BE> + 'static,
 {
+	/// Take a snapshot of the state of the block, for the queries made on it to be consistent.
+	pub fn snapshot(&self, hash: Block::Hash) -> Result<Snapshot<BE, Block>, String> {
+		self.client.storage_snapshot(hash).map_err(|error| error.to_string())
+	}
+
 	/// Fetch the value from storage.
 	pub fn query_value(
 		&self,
-		hash: Block::Hash,
+		snapshot: &Snapshot<BE, Block>,
 		key: &StorageKey,
 		child_key: Option<&ChildInfo>,
 	) -> QueryResult {
 		let result = if let Some(child_key) = child_key {
-			self.client.child_storage(hash, child_key, key)
+			snapshot.child_storage(child_key, key)
 		} else {
-			self.client.storage(hash, key)
+			snapshot.storage(key)
 		};
 
 		result
@@ -103,14 +111,14 @@ where
 	/// Fetch the hash of a value from storage.
 	pub fn query_hash(
 		&self,
-		hash: Block::Hash,
+		snapshot: &Snapshot<BE, Block>,
 		key: &StorageKey,
 		child_key: Option<&ChildInfo>,
 	) -> QueryResult {
 		let result = if let Some(child_key) = child_key {
-			self.client.child_storage_hash(hash, child_key, key)
+			snapshot.child_storage_hash(child_key, key)
 		} else {
-			self.client.storage_hash(hash, key)
+			snapshot.storage_hash(key)
 		};
 
 		result
@@ -127,14 +135,14 @@ where
 	/// Fetch the closest merkle value.
 	pub fn query_merkle_value(
 		&self,
-		hash: Block::Hash,
+		snapshot: &Snapshot<BE, Block>,
 		key: &StorageKey,
 		child_key: Option<&ChildInfo>,
 	) -> QueryResult {
 		let result = if let Some(ref child_key) = child_key {
-			self.client.child_closest_merkle_value(hash, child_key, key)
+			snapshot.child_closest_merkle_value(child_key, key)
 		} else {
-			self.client.closest_merkle_value(hash, key)
+			snapshot.closest_merkle_value(key)
 		};
 
 		result
@@ -162,22 +170,14 @@ where
 	pub fn query_iter_pagination_with_producer(
 		&self,
 		query: QueryIter,
-		hash: Block::Hash,
+		snapshot: &Snapshot<BE, Block>,
 		child_key: Option<&ChildInfo>,
 		tx: &mpsc::Sender<QueryResult>,
 	) {
 		let QueryIter { ty, query_key, pagination_start_key } = query;
 
-		let maybe_storage = if let Some(child_key) = child_key {
-			self.client.child_storage_keys(
-				hash,
-				child_key.to_owned(),
-				Some(&query_key),
-				pagination_start_key.as_ref(),
-			)
-		} else {
-			self.client.storage_keys(hash, Some(&query_key), pagination_start_key.as_ref())
-		};
+		let maybe_storage =
+			snapshot.keys(child_key, Some(&query_key), pagination_start_key.as_ref());
 
 		let keys_iter = match maybe_storage {
 			Ok(keys_iter) => keys_iter,
@@ -189,8 +189,8 @@ where
 
 		for key in keys_iter {
 			let result = match ty {
-				IterQueryType::Value => self.query_value(hash, &key, child_key),
-				IterQueryType::Hash => self.query_hash(hash, &key, child_key),
+				IterQueryType::Value => self.query_value(snapshot, &key, child_key),
+				IterQueryType::Hash => self.query_hash(snapshot, &key, child_key),
 			};
 
 			if tx.blocking_send(result).is_err() {
@@ -200,18 +200,12 @@ where
 	}
 
 	/// Raw iterator over the keys.
-	pub fn raw_keys_iter(
+	pub fn raw_keys_iter<'a>(
 		&self,
-		hash: Block::Hash,
-		child_key: Option<ChildInfo>,
-	) -> Result<impl Iterator<Item = StorageKey>, String> {
-		let keys_iter = if let Some(child_key) = child_key {
-			self.client.child_storage_keys(hash, child_key, None, None)
-		} else {
-			self.client.storage_keys(hash, None, None)
-		};
-
-		keys_iter.map_err(|err| err.to_string())
+		snapshot: &'a Snapshot<BE, Block>,
+		child_key: Option<&ChildInfo>,
+	) -> Result<impl Iterator<Item = StorageKey> + 'a, String> {
+		snapshot.keys(child_key, None, None).map_err(|err| err.to_string())
 	}
 }
 
@@ -252,23 +246,35 @@ where
 		let this = self.clone();
 
 		tokio::task::spawn_blocking(move || {
+			// All the queries are made on the same state, for their results to be consistent.
+			let snapshot = match this.client.snapshot(hash) {
+				Ok(snapshot) => snapshot,
+				Err(error) => {
+					let _ = tx.blocking_send(Err(error));
+					return;
+				},
+			};
+
 			for item in items {
 				match item.query_type {
 					StorageQueryType::Value => {
-						let rp = this.client.query_value(hash, &item.key, child_key.as_ref());
+						let rp = this.client.query_value(&snapshot, &item.key, child_key.as_ref());
 						if tx.blocking_send(rp).is_err() {
 							break;
 						}
 					},
 					StorageQueryType::Hash => {
-						let rp = this.client.query_hash(hash, &item.key, child_key.as_ref());
+						let rp = this.client.query_hash(&snapshot, &item.key, child_key.as_ref());
 						if tx.blocking_send(rp).is_err() {
 							break;
 						}
 					},
 					StorageQueryType::ClosestDescendantMerkleValue => {
-						let rp =
-							this.client.query_merkle_value(hash, &item.key, child_key.as_ref());
+						let rp = this.client.query_merkle_value(
+							&snapshot,
+							&item.key,
+							child_key.as_ref(),
+						);
 						if tx.blocking_send(rp).is_err() {
 							break;
 						}
@@ -281,7 +287,7 @@ where
 						};
 						this.client.query_iter_pagination_with_producer(
 							query,
-							hash,
+							&snapshot,
 							child_key.as_ref(),
 							&tx,
 						)
@@ -294,7 +300,7 @@ where
 						};
 						this.client.query_iter_pagination_with_producer(
 							query,
-							hash,
+							&snapshot,
 							child_key.as_ref(),
 							&tx,
 						)
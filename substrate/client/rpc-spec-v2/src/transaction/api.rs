@@ -44,12 +44,21 @@ pub trait TransactionApi<Hash: Clone> {
 pub trait TransactionBroadcastApi {
 	/// Broadcast an extrinsic to the chain.
 	///
+	/// If an `idempotency_key` is given, retrying the broadcast of the same extrinsic with the
+	/// same key returns the operation ID of the original broadcast instead of starting a new one,
+	/// also from another connection, as long as the broadcast is running. Using the key for another
+	/// extrinsic starts a new broadcast. The key is at most 64 bytes long.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and subject to change in the future.
 
 	#[method(name = "transaction_v1_broadcast", with_extensions)]
-	async fn broadcast(&self, bytes: Bytes) -> RpcResult<Option<String>>;
+	async fn broadcast(
+		&self,
+		bytes: Bytes,
+		idempotency_key: Option<String>,
+	) -> RpcResult<Option<String>>;
 
 	/// Broadcast an extrinsic to the chain.
 	///
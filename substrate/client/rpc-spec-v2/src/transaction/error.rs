@@ -106,6 +106,9 @@ pub enum ErrorBroadcast {
 	/// The provided operation ID is invalid.
 	#[error("Invalid operation id")]
 	InvalidOperationID,
+	/// The idempotency key is too long.
+	#[error("Idempotency key too long")]
+	IdempotencyKeyTooLong,
 }
 
 /// General purpose errors, as defined in
@@ -120,7 +123,7 @@ impl From<ErrorBroadcast> for ErrorObject<'static> {
 		let msg = e.to_string();
 
 		match e {
-			ErrorBroadcast::InvalidOperationID =>
+			ErrorBroadcast::InvalidOperationID | ErrorBroadcast::IdempotencyKeyTooLong =>
 				ErrorObject::owned(json_rpc_spec::INVALID_PARAM_ERROR, msg, None::<()>),
 		}
 	}
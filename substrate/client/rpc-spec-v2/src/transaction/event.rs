@@ -28,6 +28,11 @@ pub struct TransactionBlock<Hash> {
 	pub hash: Hash,
 	/// The index (zero-based) of the transaction within the body of the block.
 	pub index: usize,
+	/// The number of blocks on top of the block when the event was reported.
+	///
+	/// Only present if the node delays the events by a confirmation depth.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub confirmations: Option<u32>,
 }
 
 /// The transaction could not be processed due to an error.
@@ -224,6 +229,7 @@ mod tests {
 			TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
 				hash: H256::from_low_u64_be(1),
 				index: 2,
+				confirmations: None,
 			}));
 		let ser = serde_json::to_string(&event).unwrap();
 
@@ -239,6 +245,7 @@ mod tests {
 		let event: TransactionEvent<H256> = TransactionEvent::Finalized(TransactionBlock {
 			hash: H256::from_low_u64_be(1),
 			index: 10,
+			confirmations: None,
 		});
 		let ser = serde_json::to_string(&event).unwrap();
 
@@ -247,6 +254,19 @@ mod tests {
 
 		let event_dec: TransactionEvent<H256> = serde_json::from_str(exp).unwrap();
 		assert_eq!(event_dec, event);
+
+		let event: TransactionEvent<H256> = TransactionEvent::Finalized(TransactionBlock {
+			hash: H256::from_low_u64_be(1),
+			index: 10,
+			confirmations: Some(6),
+		});
+		let ser = serde_json::to_string(&event).unwrap();
+
+		let exp = r#"{"event":"finalized","block":{"hash":"0x0000000000000000000000000000000000000000000000000000000000000001","index":10,"confirmations":6}}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: TransactionEvent<H256> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
 	}
 
 	#[test]
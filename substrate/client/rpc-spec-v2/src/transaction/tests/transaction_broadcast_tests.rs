@@ -309,6 +309,7 @@ async fn tx_broadcast_resubmits_invalid_tx() {
 	let limits = PoolLimit { count: 8192, total_bytes: 20 * 1024 * 1024 };
 	let options = Options {
 		ready: limits.clone(),
+		reserved: Default::default(),
 		future: limits,
 		reject_future_transactions: false,
 		// This ensures that a transaction is not banned.
@@ -429,6 +430,7 @@ async fn tx_broadcast_resubmits_dropped_tx() {
 	let limits = PoolLimit { count: 1, total_bytes: 1000 };
 	let options = Options {
 		ready: limits.clone(),
+		reserved: Default::default(),
 		future: limits,
 		reject_future_transactions: false,
 		// This ensures that a transaction is not banned.
@@ -558,3 +560,55 @@ async fn tx_broadcast_limit_reached() {
 	let _operation_id: String =
 		tx_api.call("transaction_v1_broadcast", rpc_params![&xt]).await.unwrap();
 }
+
+#[tokio::test]
+async fn tx_broadcast_with_idempotency_key_returns_original_operation() {
+	let (api, _pool, client_mock, tx_api, mut exec_middleware, mut pool_middleware) =
+		setup_api(Default::default(), MAX_TX_PER_CONNECTION);
+
+	// Start at block 1.
+	let block_1_header = api.push_block(1, vec![], true);
+	let uxt = uxt(Alice, ALICE_NONCE);
+	let xt = hex_string(&uxt.encode());
+
+	let operation_id: String = tx_api
+		.call("transaction_v1_broadcast", rpc_params![&xt, "retry"])
+		.await
+		.unwrap();
+
+	// Announce block 1 to `transaction_v1_broadcast`.
+	client_mock.trigger_import_stream(block_1_header).await;
+
+	// Ensure the tx propagated from `transaction_v1_broadcast` to the transaction pool.
+	let event = get_next_event!(&mut pool_middleware);
+	assert_eq!(
+		event,
+		MiddlewarePoolEvent::TransactionStatus {
+			transaction: xt.clone(),
+			status: TxStatusTypeTest::Ready
+		}
+	);
+	assert_eq!(1, exec_middleware.num_tasks());
+
+	// Retrying with the same key returns the same operation without broadcasting again.
+	let retried_operation_id: String = tx_api
+		.call("transaction_v1_broadcast", rpc_params![&xt, "retry"])
+		.await
+		.unwrap();
+	assert_eq!(operation_id, retried_operation_id);
+	assert_eq!(1, exec_middleware.num_tasks());
+
+	// Force the future to exit by calling stop.
+	let _: () = tx_api.call("transaction_v1_stop", rpc_params![&operation_id]).await.unwrap();
+
+	// Ensure the broadcast future finishes.
+	let _ = get_next_event!(&mut exec_middleware.recv);
+	assert_eq!(0, exec_middleware.num_tasks());
+
+	// The key is forgotten once the broadcast ended.
+	let new_operation_id: String = tx_api
+		.call("transaction_v1_broadcast", rpc_params![&xt, "retry"])
+		.await
+		.unwrap();
+	assert_ne!(operation_id, new_operation_id);
+}
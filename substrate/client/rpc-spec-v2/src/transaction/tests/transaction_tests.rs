@@ -78,11 +78,19 @@ async fn tx_in_finalized() {
 		event,
 		TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
 			hash: block_2,
-			index: 0
+			index: 0,
+			confirmations: None,
 		}))
 	);
 	let event: TransactionEvent<H256> = get_next_event_sub!(&mut sub);
-	assert_eq!(event, TransactionEvent::Finalized(TransactionBlock { hash: block_2, index: 0 }));
+	assert_eq!(
+		event,
+		TransactionEvent::Finalized(TransactionBlock {
+			hash: block_2,
+			index: 0,
+			confirmations: None
+		})
+	);
 }
 
 #[tokio::test]
@@ -113,7 +121,8 @@ async fn tx_with_pruned_best_block() {
 		event,
 		TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
 			hash: block_2,
-			index: 0
+			index: 0,
+			confirmations: None,
 		}))
 	);
 
@@ -140,14 +149,22 @@ async fn tx_with_pruned_best_block() {
 		event,
 		TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
 			hash: block_2,
-			index: 0
+			index: 0,
+			confirmations: None,
 		}))
 	);
 
 	let event = ChainEvent::Finalized { hash: block_2, tree_route: Arc::from(vec![]) };
 	pool.inner_pool.maintain(event).await;
 	let event: TransactionEvent<H256> = get_next_event_sub!(&mut sub);
-	assert_eq!(event, TransactionEvent::Finalized(TransactionBlock { hash: block_2, index: 0 }));
+	assert_eq!(
+		event,
+		TransactionEvent::Finalized(TransactionBlock {
+			hash: block_2,
+			index: 0,
+			confirmations: None
+		})
+	);
 }
 
 #[tokio::test]
@@ -223,14 +240,20 @@ async fn tx_slow_client_replace_old_messages() {
 		TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
 			hash: block_hash,
 			index: 0,
+			confirmations: None,
 		})),
 		// Most recent 3 messages.
 		TransactionEvent::Validated,
 		TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
 			hash: block2_hash,
 			index: 0,
+			confirmations: None,
 		})),
-		TransactionEvent::Finalized(TransactionBlock { hash: block2_hash, index: 0 }),
+		TransactionEvent::Finalized(TransactionBlock {
+			hash: block2_hash,
+			index: 0,
+			confirmations: None,
+		}),
 	];
 
 	assert_eq!(res, exp);
@@ -30,7 +30,11 @@ use crate::{
 use codec::Decode;
 use futures::{StreamExt, TryFutureExt};
 use jsonrpsee::{core::async_trait, PendingSubscriptionSink};
-use sc_rpc::utils::{RingBuffer, Subscription};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::{
+	author::confirmations::with_confirmation_depth,
+	utils::{RingBuffer, Subscription},
+};
 use sc_transaction_pool_api::{
 	error::IntoPoolError, BlockHash, TransactionFor, TransactionPool, TransactionSource,
 	TransactionStatus,
@@ -50,12 +54,25 @@ pub struct Transaction<Pool, Client> {
 	pool: Arc<Pool>,
 	/// Executor to spawn subscriptions.
 	executor: SubscriptionTaskExecutor,
+	/// Number of blocks on top of the including block before a transaction is reported in a
+	/// block or finalized.
+	confirmation_depth: u32,
 }
 
 impl<Pool, Client> Transaction<Pool, Client> {
 	/// Creates a new [`Transaction`].
 	pub fn new(client: Arc<Client>, pool: Arc<Pool>, executor: SubscriptionTaskExecutor) -> Self {
-		Transaction { client, pool, executor }
+		Transaction { client, pool, executor, confirmation_depth: 0 }
+	}
+
+	/// Delay the `bestChainBlockIncluded` and `finalized` events until `depth` blocks are built on
+	/// top of the including block.
+	///
+	/// The number of blocks on top of the block is then reported in the `confirmations` field of
+	/// the events. See [`sc_rpc::author::confirmations`] for details.
+	pub fn with_confirmation_depth(mut self, depth: u32) -> Self {
+		self.confirmation_depth = depth;
+		self
 	}
 }
 
@@ -72,11 +89,12 @@ where
 	Pool: TransactionPool + Sync + Send + 'static,
 	Pool::Hash: Unpin,
 	<Pool::Block as BlockT>::Hash: Unpin,
-	Client: HeaderBackend<Pool::Block> + Send + Sync + 'static,
+	Client: HeaderBackend<Pool::Block> + BlockchainEvents<Pool::Block> + Send + Sync + 'static,
 {
 	fn submit_and_watch(&self, pending: PendingSubscriptionSink, xt: Bytes) {
 		let client = self.client.clone();
 		let pool = self.pool.clone();
+		let confirmation_depth = self.confirmation_depth;
 
 		let fut = async move {
 			let decoded_extrinsic = match TransactionFor::<Pool>::decode(&mut &xt[..]) {
@@ -112,8 +130,15 @@ where
 
 			match submit.await {
 				Ok(stream) => {
-					let stream =
-						stream.filter_map(move |event| async move { handle_event(event) }).boxed();
+					let stream = with_confirmation_depth::<Pool::Block, _, _>(
+						client,
+						stream,
+						confirmation_depth,
+					)
+					.filter_map(move |(event, confirmations)| async move {
+						handle_event(event, confirmations)
+					})
+					.boxed();
 
 					// If the subscription is too slow older events will be overwritten.
 					sink.pipe_from_stream(stream, RingBuffer::new(3)).await;
@@ -133,22 +158,30 @@ where
 
 /// Handle events generated by the transaction-pool and convert them
 /// to the new API expected state.
+///
+/// The `confirmations` are the number of blocks on top of the block of an `InBlock` or
+/// `Finalized` event delayed by the confirmation depth.
 #[inline]
 pub fn handle_event<Hash: Clone, BlockHash: Clone>(
 	event: TransactionStatus<Hash, BlockHash>,
+	confirmations: Option<u32>,
 ) -> Option<TransactionEvent<BlockHash>> {
 	match event {
 		TransactionStatus::Ready | TransactionStatus::Future =>
 			Some(TransactionEvent::<BlockHash>::Validated),
 		TransactionStatus::InBlock((hash, index)) =>
-			Some(TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock { hash, index }))),
+			Some(TransactionEvent::BestChainBlockIncluded(Some(TransactionBlock {
+				hash,
+				index,
+				confirmations,
+			}))),
 		TransactionStatus::Retracted(_) => Some(TransactionEvent::BestChainBlockIncluded(None)),
 		TransactionStatus::FinalityTimeout(_) =>
 			Some(TransactionEvent::Dropped(TransactionDropped {
 				error: "Maximum number of finality watchers has been reached".into(),
 			})),
 		TransactionStatus::Finalized((hash, index)) =>
-			Some(TransactionEvent::Finalized(TransactionBlock { hash, index })),
+			Some(TransactionEvent::Finalized(TransactionBlock { hash, index, confirmations })),
 		TransactionStatus::Usurped(_) => Some(TransactionEvent::Invalid(TransactionError {
 			error: "Extrinsic was rendered invalid by another extrinsic".into(),
 		})),
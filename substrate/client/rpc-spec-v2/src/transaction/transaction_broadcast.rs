@@ -32,6 +32,7 @@ use jsonrpsee::{
 use parking_lot::RwLock;
 use rand::{distributions::Alphanumeric, Rng};
 use sc_client_api::BlockchainEvents;
+use sc_rpc::utils::{IdempotencyCache, IdempotencyLookup};
 use sc_transaction_pool_api::{
 	error::IntoPoolError, TransactionFor, TransactionPool, TransactionSource,
 };
@@ -54,6 +55,8 @@ pub struct TransactionBroadcast<Pool: TransactionPool, Client> {
 	broadcast_ids: Arc<RwLock<HashMap<String, BroadcastState<Pool>>>>,
 	/// Keep track of how many concurrent operations are active for each connection.
 	rpc_connections: RpcConnections,
+	/// The operation IDs of the running broadcasts started with an idempotency key.
+	idempotency_keys: Arc<IdempotencyCache<String>>,
 }
 
 /// The state of a broadcast operation.
@@ -78,6 +81,7 @@ impl<Pool: TransactionPool, Client> TransactionBroadcast<Pool, Client> {
 			executor,
 			broadcast_ids: Default::default(),
 			rpc_connections: RpcConnections::new(max_transactions_per_connection),
+			idempotency_keys: Default::default(),
 		}
 	}
 
@@ -121,13 +125,28 @@ where
 	<Pool::Block as BlockT>::Hash: Unpin,
 	Client: HeaderBackend<Pool::Block> + BlockchainEvents<Pool::Block> + Send + Sync + 'static,
 {
-	async fn broadcast(&self, ext: &Extensions, bytes: Bytes) -> RpcResult<Option<String>> {
+	async fn broadcast(
+		&self,
+		ext: &Extensions,
+		bytes: Bytes,
+		idempotency_key: Option<String>,
+	) -> RpcResult<Option<String>> {
 		let pool = self.pool.clone();
 		let conn_id = ext
 			.get::<ConnectionId>()
 			.copied()
 			.expect("ConnectionId is always set by jsonrpsee; qed");
 
+		// A retried broadcast returns the operation ID of the original one.
+		if let Some(key) = &idempotency_key {
+			match self.idempotency_keys.lookup(key, &bytes) {
+				IdempotencyLookup::Unknown => {},
+				IdempotencyLookup::Known(id) => return Ok(Some(id)),
+				IdempotencyLookup::TooLong =>
+					return Err(ErrorBroadcast::IdempotencyKeyTooLong.into()),
+			}
+		}
+
 		// The unique ID of this operation.
 		let id = self.generate_unique_id();
 
@@ -149,6 +168,9 @@ where
 		};
 		// Save the tx hash to remove it later.
 		let tx_hash = pool.hash_of(&decoded_extrinsic);
+		if let Some(key) = &idempotency_key {
+			self.idempotency_keys.insert(key.clone(), &bytes, id.clone());
+		}
 
 		// The compiler can no longer deduce the type of the stream and complains
 		// about `one type is more general than the other`.
@@ -213,12 +235,18 @@ where
 		let broadcast_ids = self.broadcast_ids.clone();
 		let drop_id = id.clone();
 		let pool = self.pool.clone();
+		let idempotency_keys = self.idempotency_keys.clone();
 		// The future expected by the executor must be `Future<Output = ()>` instead of
 		// `Future<Output = Result<(), Aborted>>`.
 		let fut = fut.map(move |result| {
 			// Connection space is cleaned when this object is dropped.
 			drop(reserved_identifier);
 
+			// A retry with the idempotency key starts a new broadcast once this one ended.
+			if let Some(key) = idempotency_key {
+				idempotency_keys.remove(&key, &bytes);
+			}
+
 			// Remove the entry from the broadcast IDs map.
 			let Some(broadcast_state) = broadcast_ids.write().remove(&drop_id) else { return };
 
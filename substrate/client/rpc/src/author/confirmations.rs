@@ -0,0 +1,192 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Confirmation depth of the transaction status events.
+//!
+//! Chains bridging to systems with probabilistic finality may want to wait for some blocks on top
+//! of a transaction before reporting it, to protect against reorgs. With a confirmation depth of
+//! `N`, the `InBlock` event is held back until `N` blocks are built on top of the including block
+//! in the best chain, and the `Finalized` event until `N` blocks are finalized on top of it. An
+//! `InBlock` event retracted before being reported is dropped together with its `Retracted` event.
+
+use futures::{
+	stream::{self, BoxStream, Fuse},
+	StreamExt,
+};
+use sc_client_api::{BlockchainEvents, FinalityNotifications, ImportNotifications};
+use sc_transaction_pool_api::{TransactionStatus, TransactionStatusStream, TxIndex};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, NumberFor, SaturatedConversion};
+use std::{pin::Pin, sync::Arc};
+
+/// A transaction status event, with the number of blocks on top of the including block when the
+/// event was delayed by the confirmation depth.
+pub type ConfirmedStatus<Hash, BlockHash> = (TransactionStatus<Hash, BlockHash>, Option<u32>);
+
+/// Delay the `InBlock` and `Finalized` events of `statuses` until `depth` blocks are built on top
+/// of the including block.
+///
+/// The delayed events are returned with the number of blocks on top of the including block. All
+/// events are returned as they are if `depth` is zero.
+pub fn with_confirmation_depth<Block, Client, Hash>(
+	client: Arc<Client>,
+	statuses: Pin<Box<TransactionStatusStream<Hash, Block::Hash>>>,
+	depth: u32,
+) -> BoxStream<'static, ConfirmedStatus<Hash, Block::Hash>>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + BlockchainEvents<Block> + 'static,
+	Hash: Send + 'static,
+{
+	if depth == 0 {
+		return statuses.map(|status| (status, None)).boxed()
+	}
+
+	let confirmations = Confirmations {
+		best: client.import_notification_stream().fuse(),
+		finalized: client.finality_notification_stream().fuse(),
+		client,
+		depth: depth.into(),
+		statuses: statuses.fuse(),
+		in_block: None,
+		finalized_in: None,
+	};
+	stream::unfold(confirmations, |mut confirmations| async move {
+		let status = confirmations.next().await?;
+		Some((status, confirmations))
+	})
+	.boxed()
+}
+
+/// A block including the transaction, whose event is delayed.
+type Inclusion<Block> = (<Block as BlockT>::Hash, TxIndex, NumberFor<Block>);
+
+struct Confirmations<Block: BlockT, Client, Hash> {
+	client: Arc<Client>,
+	depth: NumberFor<Block>,
+	statuses: Fuse<Pin<Box<TransactionStatusStream<Hash, Block::Hash>>>>,
+	best: Fuse<ImportNotifications<Block>>,
+	finalized: Fuse<FinalityNotifications<Block>>,
+	/// The block of the delayed `InBlock` event.
+	in_block: Option<Inclusion<Block>>,
+	/// The block of the delayed `Finalized` event.
+	finalized_in: Option<Inclusion<Block>>,
+}
+
+impl<Block, Client, Hash> Confirmations<Block, Client, Hash>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block>,
+{
+	async fn next(&mut self) -> Option<ConfirmedStatus<Hash, Block::Hash>> {
+		loop {
+			let info = self.client.info();
+			if let Some((hash, index, number)) = self.in_block {
+				if info.best_number >= number + self.depth {
+					self.in_block = None;
+					let confirmations = (info.best_number - number).saturated_into();
+					return Some((TransactionStatus::InBlock((hash, index)), Some(confirmations)))
+				}
+			}
+			if let Some((hash, index, number)) = self.finalized_in {
+				if info.finalized_number >= number + self.depth {
+					self.finalized_in = None;
+					let confirmations = (info.finalized_number - number).saturated_into();
+					return Some((TransactionStatus::Finalized((hash, index)), Some(confirmations)))
+				}
+			}
+			if self.statuses.is_done() && self.finalized_in.is_none() {
+				return None
+			}
+
+			futures::select! {
+				status = self.statuses.next() => match status {
+					Some(TransactionStatus::InBlock((hash, index))) =>
+						match self.client.number(hash).ok().flatten() {
+							Some(number) => self.in_block = Some((hash, index, number)),
+							None => return Some((TransactionStatus::InBlock((hash, index)), None)),
+						},
+					Some(TransactionStatus::Retracted(hash))
+						if self.in_block.is_some_and(|(in_block, ..)| in_block == hash) =>
+						self.in_block = None,
+					Some(TransactionStatus::Finalized((hash, index))) =>
+						match self.client.number(hash).ok().flatten() {
+							Some(number) => self.finalized_in = Some((hash, index, number)),
+							None =>
+								return Some((TransactionStatus::Finalized((hash, index)), None)),
+						},
+					Some(status) => return Some((status, None)),
+					None => {},
+				},
+				_ = self.best.next() => {},
+				_ = self.finalized.next() => {},
+				complete => return None,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::FutureExt;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_consensus::BlockOrigin;
+	use substrate_test_runtime_client::{prelude::*, runtime::Block};
+
+	async fn import_block(client: &Client<Backend>) -> <Block as BlockT>::Hash {
+		let block = BlockBuilderBuilder::new(client)
+			.on_parent_block(client.chain_info().best_hash)
+			.with_parent_block_number(client.chain_info().best_number)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap()
+			.block;
+		let hash = block.hash();
+		client.import(BlockOrigin::Own, block).await.unwrap();
+		hash
+	}
+
+	#[tokio::test]
+	async fn events_are_delayed_by_the_confirmation_depth() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let block = import_block(&client).await;
+		let statuses = stream::iter([
+			TransactionStatus::<u64, _>::Ready,
+			TransactionStatus::InBlock((block, 0)),
+			TransactionStatus::Finalized((block, 0)),
+		]);
+		let mut confirmed =
+			with_confirmation_depth::<Block, _, _>(client.clone(), Box::pin(statuses), 2);
+
+		assert_eq!(confirmed.next().await, Some((TransactionStatus::Ready, None)));
+		assert_eq!(confirmed.next().now_or_never(), None);
+
+		import_block(&client).await;
+		let last = import_block(&client).await;
+		assert_eq!(confirmed.next().await, Some((TransactionStatus::InBlock((block, 0)), Some(2))));
+
+		client.finalize_block(last, None).unwrap();
+		assert_eq!(
+			confirmed.next().await,
+			Some((TransactionStatus::Finalized((block, 0)), Some(2))),
+		);
+		assert_eq!(confirmed.next().await, None);
+	}
+}
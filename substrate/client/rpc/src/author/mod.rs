@@ -21,24 +21,33 @@
 #[cfg(test)]
 mod tests;
 
+pub mod confirmations;
+
 use self::error::{Error, Result};
 use crate::{
-	utils::{spawn_subscription_task, BoundedVecDeque, PendingSubscription},
+	utils::{
+		spawn_subscription_task, BoundedVecDeque, IdempotencyCache, IdempotencyLookup,
+		PendingSubscription,
+	},
 	SubscriptionTaskExecutor,
 };
 use codec::{Decode, Encode};
+use futures::StreamExt;
 use jsonrpsee::{core::async_trait, types::ErrorObject, Extensions, PendingSubscriptionSink};
-use sc_rpc_api::check_if_safe;
+use sc_client_api::BlockchainEvents;
+use sc_rpc_api::{author::status::WatchedStatus, check_if_safe};
 use sc_transaction_pool_api::{
 	error::IntoPoolError, BlockHash, InPoolTransaction, TransactionFor, TransactionPool,
 	TransactionSource, TxHash, TxInvalidityReportMap,
 };
-use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_api::{ApiExt, CallApiAt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
-use sp_core::Bytes;
+use sp_core::{crypto::KeyTypeId, Bytes};
+use sp_crypto_hashing::{twox_128, twox_64};
 use sp_keystore::{KeystoreExt, KeystorePtr};
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, SaturatedConversion};
 use sp_session::SessionKeys;
+use sp_state_machine::Backend as StateBackend;
 use std::sync::Arc;
 
 /// Re-export the API for backward compatibility.
@@ -54,6 +63,11 @@ pub struct Author<P, Client> {
 	keystore: KeystorePtr,
 	/// Executor to spawn subscriptions.
 	executor: SubscriptionTaskExecutor,
+	/// Idempotency keys of the successful submissions.
+	submitted: IdempotencyCache<()>,
+	/// Number of blocks on top of the including block before a watched transaction is reported
+	/// in a block or finalized.
+	confirmation_depth: u32,
 }
 
 impl<P, Client> Author<P, Client> {
@@ -64,7 +78,24 @@ impl<P, Client> Author<P, Client> {
 		keystore: KeystorePtr,
 		executor: SubscriptionTaskExecutor,
 	) -> Self {
-		Author { client, pool, keystore, executor }
+		Author {
+			client,
+			pool,
+			keystore,
+			executor,
+			submitted: Default::default(),
+			confirmation_depth: 0,
+		}
+	}
+
+	/// Delay the `InBlock` and `Finalized` events of the watched transactions until `depth` blocks
+	/// are built on top of the including block.
+	///
+	/// The number of blocks on top of the block is then reported in the `confirmations` field of
+	/// the delayed events, see [`WatchedStatus`]. See [`confirmations`] for details.
+	pub fn with_confirmation_depth(mut self, depth: u32) -> Self {
+		self.confirmation_depth = depth;
+		self
 	}
 }
 
@@ -75,27 +106,52 @@ impl<P, Client> Author<P, Client> {
 /// some unique transactions via RPC and have them included in the pool.
 const TX_SOURCE: TransactionSource = TransactionSource::External;
 
+/// Storage key of the session keys registered for `stash` in `Session::NextKeys`.
+fn next_keys_key(stash: &[u8]) -> Vec<u8> {
+	[&twox_128(b"Session")[..], &twox_128(b"NextKeys"), &twox_64(stash), stash].concat()
+}
+
 #[async_trait]
 impl<P, Client> AuthorApiServer<TxHash<P>, BlockHash<P>> for Author<P, Client>
 where
 	P: TransactionPool + Sync + Send + 'static,
-	Client: HeaderBackend<P::Block> + ProvideRuntimeApi<P::Block> + Send + Sync + 'static,
+	Client: HeaderBackend<P::Block>
+		+ BlockchainEvents<P::Block>
+		+ ProvideRuntimeApi<P::Block>
+		+ CallApiAt<P::Block>
+		+ Send
+		+ Sync
+		+ 'static,
 	Client::Api: SessionKeys<P::Block>,
 	P::Hash: Unpin,
 	<P::Block as BlockT>::Hash: Unpin,
 {
-	async fn submit_extrinsic(&self, ext: Bytes) -> Result<TxHash<P>> {
+	async fn submit_extrinsic(
+		&self,
+		ext: Bytes,
+		idempotency_key: Option<String>,
+	) -> Result<TxHash<P>> {
 		let xt = match Decode::decode(&mut &ext[..]) {
 			Ok(xt) => xt,
 			Err(err) => return Err(Error::Client(Box::new(err)).into()),
 		};
+		if let Some(key) = &idempotency_key {
+			match self.submitted.lookup(key, &ext) {
+				IdempotencyLookup::Unknown => {},
+				IdempotencyLookup::Known(()) => return Ok(self.pool.hash_of(&xt)),
+				IdempotencyLookup::TooLong => return Err(Error::IdempotencyKeyTooLong),
+			}
+		}
 		let best_block_hash = self.client.info().best_hash;
-		self.pool.submit_one(best_block_hash, TX_SOURCE, xt).await.map_err(|e| {
+		let hash = self.pool.submit_one(best_block_hash, TX_SOURCE, xt).await.map_err(|e| {
 			e.into_pool_error()
 				.map(|e| Error::Pool(e))
 				.unwrap_or_else(|e| Error::Verification(Box::new(e)))
-				.into()
-		})
+		})?;
+		if let Some(key) = idempotency_key {
+			self.submitted.insert(key, &ext, ());
+		}
+		Ok(hash)
 	}
 
 	fn insert_key(
@@ -142,6 +198,63 @@ where
 		Ok(self.keystore.has_keys(&keys))
 	}
 
+	fn session_keys_status(
+		&self,
+		ext: &Extensions,
+		stash: Bytes,
+	) -> Result<Vec<session::SessionKeyStatus>> {
+		check_if_safe(ext)?;
+
+		let best_block_hash = self.client.info().best_hash;
+		let session_keys = self
+			.client
+			.state_at(best_block_hash)
+			.map_err(|e| Error::Client(Box::new(e)))?
+			.storage(&next_keys_key(&stash))
+			.map_err(|e| Error::Client(e.to_string().into()))?
+			.ok_or(Error::NoSessionKeys)?;
+		let keys = self
+			.client
+			.runtime_api()
+			.decode_session_keys(best_block_hash, session_keys)
+			.map_err(|e| Error::Client(Box::new(e)))?
+			.ok_or(Error::InvalidSessionKeys)?;
+
+		let key_status = |public: Vec<u8>, key_type: KeyTypeId, registered: bool| {
+			let usage = self.keystore.key_usage(key_type, &public);
+			session::SessionKeyStatus {
+				key_type: String::from_utf8_lossy(&key_type.0).into_owned(),
+				in_keystore: self.keystore.has_keys(&[(public.clone(), key_type)]),
+				public: public.into(),
+				registered,
+				generated: usage.generated,
+				last_signed: usage.last_signed,
+			}
+		};
+
+		let mut status = keys
+			.iter()
+			.map(|(public, key_type)| key_status(public.clone(), *key_type, true))
+			.collect::<Vec<_>>();
+
+		let mut key_types = keys.iter().map(|(_, key_type)| *key_type).collect::<Vec<_>>();
+		key_types.sort();
+		key_types.dedup();
+
+		for key_type in key_types {
+			let local_keys =
+				self.keystore.keys(key_type).map_err(|_| Error::KeystoreUnavailable)?;
+			status.extend(
+				local_keys
+					.into_iter()
+					.filter(|public| !keys.contains(&(public.clone(), key_type)))
+					.map(|public| key_status(public, key_type, false)),
+			);
+		}
+
+		Ok(status)
+	}
+
 	fn has_key(&self, ext: &Extensions, public_key: Bytes, key_type: String) -> Result<bool> {
 		check_if_safe(ext)?;
 
@@ -153,6 +266,20 @@ where
 		Ok(self.pool.ready().map(|tx| tx.data().encode().into()).collect())
 	}
 
+	fn extrinsic_validity_window(
+		&self,
+		hash: TxHash<P>,
+	) -> Result<Option<validity::ValidityWindow>> {
+		let best_number = self.client.info().best_number.saturated_into::<u64>();
+		Ok(self.pool.ready_transaction(&hash).map(|tx| {
+			let valid_till = *tx.longevity();
+			validity::ValidityWindow {
+				valid_till,
+				remaining: valid_till.saturating_sub(best_number),
+			}
+		}))
+	}
+
 	fn remove_extrinsic(
 		&self,
 		ext: &Extensions,
@@ -189,6 +316,8 @@ where
 		};
 
 		let pool = self.pool.clone();
+		let client = self.client.clone();
+		let confirmation_depth = self.confirmation_depth;
 		let fut = async move {
 			let submit =
 				pool.submit_and_watch(best_block_hash, TX_SOURCE, dxt).await.map_err(|e| {
@@ -205,6 +334,12 @@ where
 				},
 			};
 
+			let stream = confirmations::with_confirmation_depth::<P::Block, _, _>(
+				client,
+				stream,
+				confirmation_depth,
+			)
+			.map(|(status, confirmations)| WatchedStatus { status, confirmations });
 			PendingSubscription::from(pending)
 				.pipe_from_stream(stream, BoundedVecDeque::default())
 				.await;
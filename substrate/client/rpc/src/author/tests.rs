@@ -28,7 +28,7 @@ use sc_transaction_pool_api::TransactionStatus;
 use sp_core::{
 	bytes::to_hex,
 	crypto::{ByteArray, Pair},
-	ed25519,
+	ecdsa, ed25519,
 	testing::{ED25519, SR25519},
 	H256,
 };
@@ -39,7 +39,8 @@ use std::sync::Arc;
 use substrate_test_runtime_client::{
 	self,
 	runtime::{Block, Extrinsic, ExtrinsicBuilder, SessionKeys, Transfer},
-	Backend, Client, DefaultTestClientBuilderExt, Sr25519Keyring, TestClientBuilderExt,
+	Backend, Client, DefaultTestClientBuilderExt, ExecutorDispatch, Sr25519Keyring,
+	TestClientBuilder, TestClientBuilderExt,
 };
 
 fn uxt(sender: Sr25519Keyring, nonce: u64) -> Extrinsic {
@@ -62,8 +63,16 @@ struct TestSetup {
 
 impl Default for TestSetup {
 	fn default() -> Self {
-		let keystore = Arc::new(MemoryKeystore::new());
-		let client = Arc::new(substrate_test_runtime_client::TestClientBuilder::new().build());
+		Self::new(Arc::new(MemoryKeystore::new()), TestClientBuilder::new())
+	}
+}
+
+impl TestSetup {
+	fn new(
+		keystore: Arc<MemoryKeystore>,
+		client_builder: TestClientBuilder<ExecutorDispatch, Backend>,
+	) -> Self {
+		let client = Arc::new(client_builder.build());
 
 		let spawner = sp_core::testing::TaskExecutor::new();
 		let pool = Arc::from(BasicPool::new_full(
@@ -75,16 +84,14 @@ impl Default for TestSetup {
 		));
 		TestSetup { client, keystore, pool }
 	}
-}
 
-impl TestSetup {
 	fn to_rpc(&self) -> RpcModule<Author<FullTransactionPool, Client<Backend>>> {
-		let mut module = Author {
-			client: self.client.clone(),
-			pool: self.pool.clone(),
-			keystore: self.keystore.clone(),
-			executor: test_executor(),
-		}
+		let mut module = Author::new(
+			self.client.clone(),
+			self.pool.clone(),
+			self.keystore.clone(),
+			test_executor(),
+		)
 		.into_rpc();
 		module.extensions_mut().insert(DenyUnsafe::No);
 		module
@@ -111,6 +118,35 @@ async fn author_submit_transaction_should_not_cause_error() {
 	);
 }
 
+#[tokio::test]
+async fn author_submit_transaction_with_idempotency_key_should_return_original_hash() {
+	let api = TestSetup::into_rpc();
+
+	let xt_bytes = uxt(Sr25519Keyring::Alice, 1).encode();
+	let extrinsic_hash: H256 = blake2_256(&xt_bytes).into();
+	let xt = to_hex(&xt_bytes, true);
+	let params = [xt.clone(), "retry".to_string()];
+	let response: H256 = api.call("author_submitExtrinsic", params.clone()).await.unwrap();
+	assert_eq!(response, extrinsic_hash);
+
+	// Retrying with the same key returns the original hash.
+	let response: H256 = api.call("author_submitExtrinsic", params).await.unwrap();
+	assert_eq!(response, extrinsic_hash);
+
+	// Without the key, the submission is rejected as usual.
+	assert_matches!(
+		api.call::<_, H256>("author_submitExtrinsic", [xt]).await,
+		Err(RpcError::JsonRpc(err)) if err.code() == 1013
+	);
+
+	// The key is bound to the extrinsic, another one is submitted as usual.
+	let other_bytes = uxt(Sr25519Keyring::Alice, 2).encode();
+	let other = to_hex(&other_bytes, true);
+	let response: H256 =
+		api.call("author_submitExtrinsic", [other, "retry".to_string()]).await.unwrap();
+	assert_eq!(response, blake2_256(&other_bytes).into());
+}
+
 #[tokio::test]
 async fn author_should_watch_extrinsic() {
 	let api = TestSetup::into_rpc();
@@ -182,6 +218,24 @@ async fn author_should_return_pending_extrinsics() {
 	assert_eq!(pending, vec![xt_bytes]);
 }
 
+#[tokio::test]
+async fn author_should_return_extrinsic_validity_window() {
+	const METHOD: &'static str = "author_extrinsicValidityWindow";
+	let api = TestSetup::into_rpc();
+
+	let xt_bytes: Bytes = uxt(Sr25519Keyring::Alice, 0).encode().into();
+	let xt_hash: H256 = api.call("author_submitExtrinsic", [xt_bytes]).await.unwrap();
+
+	let window: Option<validity::ValidityWindow> = api.call(METHOD, [xt_hash]).await.unwrap();
+	let window = window.expect("Extrinsic is ready");
+	// The best block is the genesis block.
+	assert_eq!(window.remaining, window.valid_till);
+
+	let unknown: Option<validity::ValidityWindow> =
+		api.call(METHOD, [H256::repeat_byte(1)]).await.unwrap();
+	assert_eq!(unknown, None);
+}
+
 #[tokio::test]
 async fn author_should_remove_extrinsics() {
 	const METHOD: &'static str = "author_removeExtrinsic";
@@ -283,6 +337,53 @@ async fn author_has_session_keys() {
 	);
 }
 
+#[tokio::test]
+async fn author_session_keys_status() {
+	let keystore = Arc::new(MemoryKeystore::new());
+	let ed25519 = keystore.ed25519_generate_new(ED25519, None).unwrap();
+	let sr25519 = keystore.sr25519_generate_new(SR25519, None).unwrap();
+	// The keystore doesn't hold the private key of the registered ecdsa key.
+	let ecdsa = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+	// A key which is in the keystore, but not registered on-chain.
+	let unregistered = keystore.sr25519_generate_new(SR25519, None).unwrap();
+
+	let stash = Sr25519Keyring::Alice.to_account_id().encode();
+	let session_keys =
+		SessionKeys { ed25519: ed25519.into(), sr25519: sr25519.into(), ecdsa: ecdsa.into() };
+	let client_builder =
+		TestClientBuilder::new().add_extra_storage(next_keys_key(&stash), session_keys.encode());
+	let api = TestSetup::new(keystore, client_builder).to_rpc();
+
+	let status = api
+		.call::<_, Vec<session::SessionKeyStatus>>("author_sessionKeysStatus", vec![Bytes(stash)])
+		.await
+		.unwrap();
+
+	let key_status = |public: &[u8]| {
+		let status = status.iter().find(|status| &status.public[..] == public).unwrap();
+		(status.key_type.as_str(), status.registered, status.in_keystore)
+	};
+	assert_eq!(status.len(), 4);
+	assert_eq!(key_status(ed25519.as_slice()), ("ed25", true, true));
+	assert_eq!(key_status(sr25519.as_slice()), ("sr25", true, true));
+	assert_eq!(key_status(ecdsa.as_slice()), ("ecds", true, false));
+	assert_eq!(key_status(unregistered.as_slice()), ("sr25", false, true));
+	// The memory keystore doesn't keep track of the usage of its keys.
+	assert!(status
+		.iter()
+		.all(|status| status.generated.is_none() && status.last_signed.is_none()));
+
+	let unknown_stash = Sr25519Keyring::Bob.to_account_id().encode();
+	assert_matches!(
+		api.call::<_, Vec<session::SessionKeyStatus>>(
+			"author_sessionKeysStatus",
+			vec![Bytes(unknown_stash)]
+		)
+		.await,
+		Err(RpcError::JsonRpc(err)) if err.message().contains("No session keys are registered")
+	);
+}
+
 #[tokio::test]
 async fn author_has_key() {
 	let api = TestSetup::into_rpc();
@@ -42,12 +42,18 @@ pub struct FullChain<Block: BlockT, Client> {
 	_phantom: PhantomData<Block>,
 	/// Subscription executor.
 	executor: SubscriptionTaskExecutor,
+	/// Whether only finalized blocks are served.
+	finalized_only: bool,
 }
 
 impl<Block: BlockT, Client> FullChain<Block, Client> {
 	/// Create new Chain API RPC handler.
-	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
-		Self { client, executor, _phantom: PhantomData }
+	pub fn new(
+		client: Arc<Client>,
+		executor: SubscriptionTaskExecutor,
+		finalized_only: bool,
+	) -> Self {
+		Self { client, executor, finalized_only, _phantom: PhantomData }
 	}
 }
 
@@ -62,6 +68,10 @@ where
 		&self.client
 	}
 
+	fn finalized_only(&self) -> bool {
+		self.finalized_only
+	}
+
 	fn header(&self, hash: Option<Block::Hash>) -> Result<Option<Block::Header>, Error> {
 		self.client.header(self.unwrap_or_best(hash)).map_err(client_err)
 	}
@@ -71,6 +81,10 @@ where
 	}
 
 	fn subscribe_all_heads(&self, pending: PendingSubscriptionSink) {
+		if self.finalized_only {
+			return self.subscribe_finalized_heads(pending)
+		}
+
 		subscribe_headers(
 			&self.client,
 			&self.executor,
@@ -85,6 +99,10 @@ where
 	}
 
 	fn subscribe_new_heads(&self, pending: PendingSubscriptionSink) {
+		if self.finalized_only {
+			return self.subscribe_finalized_heads(pending)
+		}
+
 		subscribe_headers(
 			&self.client,
 			&self.executor,
@@ -52,10 +52,24 @@ where
 	/// Get client reference.
 	fn client(&self) -> &Arc<Client>;
 
-	/// Tries to unwrap passed block hash, or uses best block hash otherwise.
+	/// Whether the finalized block is served instead of the best block.
+	fn finalized_only(&self) -> bool;
+
+	/// The hash of the block served by default: the best block, or the last finalized block if
+	/// only finalized blocks are served.
+	fn head(&self) -> Block::Hash {
+		let info = self.client().info();
+		if self.finalized_only() {
+			info.finalized_hash
+		} else {
+			info.best_hash
+		}
+	}
+
+	/// Tries to unwrap passed block hash, or uses the default head otherwise.
 	fn unwrap_or_best(&self, hash: Option<Block::Hash>) -> Block::Hash {
 		match hash {
-			None => self.client().info().best_hash,
+			None => self.head(),
 			Some(hash) => hash,
 		}
 	}
@@ -68,10 +82,10 @@ where
 
 	/// Get hash of the n-th block in the canon chain.
 	///
-	/// By default returns latest block hash.
+	/// By default returns the hash of the default head.
 	fn block_hash(&self, number: Option<NumberOrHex>) -> Result<Option<Block::Hash>, Error> {
 		match number {
-			None => Ok(Some(self.client().info().best_hash)),
+			None => Ok(Some(self.head())),
 			Some(num_or_hex) => {
 				// FIXME <2329>: Database seems to limit the block number to u32 for no reason
 				let block_num: u32 = num_or_hex.try_into().map_err(|_| {
@@ -102,16 +116,20 @@ where
 }
 
 /// Create new state API that works on full node.
+///
+/// If `finalized_only` is set, the requests without block hash are served from the last finalized
+/// block instead of the best block, and the subscriptions to new heads only yield finalized heads.
 pub fn new_full<Block: BlockT, Client>(
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	finalized_only: bool,
 ) -> Chain<Block, Client>
 where
 	Block: BlockT + 'static,
 	Block::Header: Unpin,
 	Client: BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + 'static,
 {
-	Chain { backend: Box::new(self::chain_full::FullChain::new(client, executor)) }
+	Chain { backend: Box::new(self::chain_full::FullChain::new(client, executor, finalized_only)) }
 }
 
 /// Chain API with subscriptions support.
@@ -31,7 +31,7 @@ use substrate_test_runtime_client::{
 #[tokio::test]
 async fn should_return_header() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let api = new_full(client.clone(), test_executor()).into_rpc();
+	let api = new_full(client.clone(), test_executor(), false).into_rpc();
 
 	let res: Header =
 		api.call("chain_getHeader", [H256::from(client.genesis_hash())]).await.unwrap();
@@ -73,7 +73,7 @@ async fn should_return_header() {
 #[tokio::test]
 async fn should_return_a_block() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let api = new_full(client.clone(), test_executor()).into_rpc();
+	let api = new_full(client.clone(), test_executor(), false).into_rpc();
 
 	let block = BlockBuilderBuilder::new(&*client)
 		.on_parent_block(client.chain_info().best_hash)
@@ -138,7 +138,7 @@ async fn should_return_a_block() {
 #[tokio::test]
 async fn should_return_block_hash() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let api = new_full(client.clone(), test_executor()).into_rpc();
+	let api = new_full(client.clone(), test_executor(), false).into_rpc();
 
 	let res: ListOrValue<Option<H256>> =
 		api.call("chain_getBlockHash", EmptyParams::new()).await.unwrap();
@@ -205,7 +205,7 @@ async fn should_return_block_hash() {
 #[tokio::test]
 async fn should_return_finalized_hash() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let api = new_full(client.clone(), test_executor()).into_rpc();
+	let api = new_full(client.clone(), test_executor(), false).into_rpc();
 
 	let res: H256 = api.call("chain_getFinalizedHead", EmptyParams::new()).await.unwrap();
 	assert_eq!(res, client.genesis_hash());
@@ -251,7 +251,7 @@ async fn test_head_subscription(method: &str) {
 	let client = Arc::new(substrate_test_runtime_client::new());
 
 	let mut sub = {
-		let api = new_full(client.clone(), test_executor()).into_rpc();
+		let api = new_full(client.clone(), test_executor(), false).into_rpc();
 		let sub = api.subscribe_unbounded(method, EmptyParams::new()).await.unwrap();
 		let block = BlockBuilderBuilder::new(&*client)
 			.on_parent_block(client.chain_info().best_hash)
@@ -273,3 +273,39 @@ async fn test_head_subscription(method: &str) {
 	sub.close();
 	assert_matches!(timeout_secs(10, sub.next::<Header>()).await, Ok(None));
 }
+
+#[tokio::test]
+async fn should_serve_finalized_head_if_finalized_only() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client.clone(), test_executor(), true).into_rpc();
+	let mut sub = api
+		.subscribe_unbounded("chain_subscribeNewHeads", EmptyParams::new())
+		.await
+		.unwrap();
+
+	let block = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().best_hash)
+		.with_parent_block_number(client.chain_info().best_number)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	let block_hash = block.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	// The new best block is not served until it is finalized.
+	let res: Header = api.call("chain_getHeader", EmptyParams::new()).await.unwrap();
+	assert_eq!(res.number, 0);
+	let res: ListOrValue<Option<H256>> =
+		api.call("chain_getBlockHash", EmptyParams::new()).await.unwrap();
+	assert_matches!(res, ListOrValue::Value(Some(ref x)) if x == &client.genesis_hash());
+	let (header, _) = timeout_secs(10, sub.next::<Header>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(header.number, 0);
+
+	client.finalize_block(block_hash, None).unwrap();
+	let res: Header = api.call("chain_getHeader", EmptyParams::new()).await.unwrap();
+	assert_eq!(res.number, 1);
+	let (header, _) = timeout_secs(10, sub.next::<Header>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(header.number, 1);
+}
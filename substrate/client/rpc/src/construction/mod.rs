@@ -0,0 +1,258 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the [`ConstructionApiServer`] trait, constructing transactions from the
+//! JSON representation of their call.
+
+#[cfg(test)]
+mod tests;
+
+use codec::Encode;
+use frame_metadata::v15::RuntimeMetadataV15;
+use jsonrpsee::core::async_trait;
+use sc_rpc_api::construction::error::{Error, Result};
+use sc_runtime_metadata::{encode::encode_value, MetadataCache};
+use scale_info::TypeDef;
+use serde_json::{json, Map, Value};
+use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::bytes::to_hex;
+use sp_runtime::{generic::Era, traits::Block as BlockT, SaturatedConversion};
+use std::{marker::PhantomData, sync::Arc};
+
+pub use sc_rpc_api::construction::{ConstructionApiServer, PayloadRequest, UnsignedPayload};
+
+/// The number of blocks a transaction is valid for if the request doesn't specify it.
+const DEFAULT_MORTALITY: u64 = 64;
+
+/// The extrinsic format version supported by the construction.
+const EXTRINSIC_VERSION: u8 = 4;
+
+/// Signing payloads longer than this are hashed before being signed.
+const MAX_UNHASHED_PAYLOAD_LEN: usize = 256;
+
+/// Construction API.
+pub struct Construction<Block, Client> {
+	client: Arc<Client>,
+	metadata: MetadataCache,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> Construction<Block, Client> {
+	/// Create a new construction API.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, metadata: MetadataCache::default(), _phantom: PhantomData }
+	}
+}
+
+impl<Block, Client> Construction<Block, Client>
+where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: Metadata<Block>,
+{
+	/// The metadata of the runtime at `hash`, whose spec version is `spec_version`.
+	fn metadata(&self, hash: Block::Hash, spec_version: u32) -> Result<Arc<RuntimeMetadataV15>> {
+		let metadata = self
+			.metadata
+			.get::<Block, _>(&*self.client, hash, spec_version)
+			.map_err(Error::MetadataUnavailable)?;
+		if metadata.extrinsic.version != EXTRINSIC_VERSION {
+			return Err(Error::UnsupportedExtrinsicVersion(metadata.extrinsic.version))
+		}
+		Ok(metadata)
+	}
+}
+
+/// Encode the call named `call` of the pallet named `pallet`, with the arguments `args`.
+fn encode_call(
+	metadata: &RuntimeMetadataV15,
+	pallet: &str,
+	call: &str,
+	args: &Value,
+) -> Result<Vec<u8>> {
+	let unknown_call = || Error::UnknownCall(format!("{pallet}.{call}"));
+	let pallet_metadata =
+		metadata.pallets.iter().find(|p| p.name == pallet).ok_or_else(unknown_call)?;
+	let calls_ty = pallet_metadata.calls.as_ref().ok_or_else(unknown_call)?.ty.id;
+	let is_known = match metadata.types.resolve(calls_ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Variant(calls)) => calls.variants.iter().any(|variant| variant.name == call),
+		_ => false,
+	};
+	if !is_known {
+		return Err(unknown_call())
+	}
+
+	let value = match args {
+		Value::Null => Value::String(call.into()),
+		args => Value::Object(Map::from_iter([(call.into(), args.clone())])),
+	};
+	let mut encoded = vec![pallet_metadata.index];
+	encode_value(&metadata.types, calls_ty, &value, &mut encoded)
+		.map_err(Error::InvalidArguments)?;
+	Ok(encoded)
+}
+
+/// The value of the payment extension of type `ty`, paying `tip`.
+fn payment(metadata: &RuntimeMetadataV15, ty: u32, tip: u128) -> Value {
+	match metadata.types.resolve(ty).map(|ty| &ty.type_def) {
+		// `ChargeAssetTxPayment`, paying with the native asset.
+		Some(TypeDef::Composite(composite))
+			if composite.fields.iter().any(|field| field.name.as_deref() == Some("asset_id")) =>
+			json!({ "tip": number(tip), "asset_id": "None" }),
+		_ => number(tip),
+	}
+}
+
+/// The type of the payment extension wrapped by the `Sponsored` extension of type `ty`.
+fn sponsored_payment(metadata: &RuntimeMetadataV15, ty: u32) -> Option<u32> {
+	match &metadata.types.resolve(ty)?.type_def {
+		TypeDef::Composite(composite) => composite
+			.fields
+			.iter()
+			.find(|field| field.name.as_deref() == Some("inner"))
+			.map(|field| field.ty.id),
+		_ => None,
+	}
+}
+
+/// A JSON number, or a decimal string if it doesn't fit into 64 bits.
+fn number(value: u128) -> Value {
+	match u64::try_from(value) {
+		Ok(value) => value.into(),
+		Err(_) => value.to_string().into(),
+	}
+}
+
+#[async_trait]
+impl<Block, Client> ConstructionApiServer<Block::Hash> for Construction<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client::Api: Core<Block> + Metadata<Block>,
+{
+	async fn payload(&self, request: PayloadRequest) -> Result<UnsignedPayload<Block::Hash>> {
+		let info = self.client.info();
+		let version = self
+			.client
+			.runtime_api()
+			.version(info.best_hash)
+			.map_err(|e| Error::Client(Box::new(e)))?;
+		let metadata = self.metadata(info.best_hash, version.spec_version)?;
+
+		let call = encode_call(&metadata, &request.pallet, &request.call, &request.args)?;
+		let tip = match request.tip {
+			Some(tip) => u128::try_from(tip)
+				.map_err(|_| Error::InvalidArguments("The tip doesn't fit into 128 bits".into()))?,
+			None => 0,
+		};
+		let (era, checkpoint) = match request.mortality.unwrap_or(DEFAULT_MORTALITY) {
+			0 => (Era::Immortal, info.genesis_hash),
+			period => {
+				let era = Era::mortal(period, info.best_number.saturated_into());
+				let birth = era.birth(info.best_number.saturated_into());
+				let checkpoint = self
+					.client
+					.hash(birth.saturated_into())
+					.map_err(|e| Error::Client(Box::new(e)))?
+					.ok_or_else(|| Error::Client(format!("Block #{birth} not found").into()))?;
+				(era, checkpoint)
+			},
+		};
+
+		let extensions = &metadata.extrinsic.signed_extensions;
+		if let Some(identifier) = request.extensions.keys().find(|identifier| {
+			!extensions.iter().any(|extension| &extension.identifier == *identifier)
+		}) {
+			return Err(Error::InvalidArguments(format!("Unknown signed extension `{identifier}`")))
+		}
+
+		let mut extra = Vec::new();
+		let mut additional_signed = Vec::new();
+		for extension in extensions {
+			let identifier = extension.identifier.as_str();
+			let is_mortality = matches!(identifier, "CheckMortality" | "CheckEra");
+			let supplied = request.extensions.get(identifier);
+			if is_mortality && supplied.is_some() {
+				return Err(Error::InvalidArguments(format!(
+					"`{identifier}` is derived from the mortality"
+				)))
+			}
+			let (extra_value, additional_value): (Value, Value) = if let Some(values) = supplied {
+				(values.extra.clone(), values.additional_signed.clone())
+			} else {
+				match identifier {
+					"CheckSpecVersion" => (Value::Null, version.spec_version.into()),
+					"CheckTxVersion" => (Value::Null, version.transaction_version.into()),
+					"CheckGenesis" =>
+						(Value::Null, to_hex(info.genesis_hash.as_ref(), false).into()),
+					"CheckMortality" | "CheckEra" => {
+						// The variants of `Era` are named after their encoding, encode it directly.
+						era.encode_to(&mut extra);
+						(Value::Null, to_hex(checkpoint.as_ref(), false).into())
+					},
+					"CheckNonce" => (request.nonce.into(), Value::Null),
+					"CheckNonceOrTag" => (json!({ "Nonce": request.nonce }), Value::Null),
+					"ChargeTransactionPayment" | "ChargeAssetTxPayment" =>
+						(payment(&metadata, extension.ty.id, tip), Value::Null),
+					// Not sponsored, the signer pays the fee.
+					"Sponsored" => {
+						let inner = sponsored_payment(&metadata, extension.ty.id)
+							.ok_or_else(|| Error::UnsupportedExtension(identifier.into()))?;
+						let inner = payment(&metadata, inner, tip);
+						(json!({ "envelope": "None", "inner": inner }), Value::Null)
+					},
+					"AuthorizeAccount" => (json!("Disabled"), Value::Null),
+					"CheckMetadataHash" => (json!("Disabled"), json!("None")),
+					// Any other extension must not carry data.
+					_ => (Value::Null, Value::Null),
+				}
+			};
+			let unsupported = |e: String| match supplied {
+				Some(_) => Error::InvalidArguments(format!("Invalid value of `{identifier}`: {e}")),
+				None => Error::UnsupportedExtension(identifier.into()),
+			};
+			if !is_mortality {
+				encode_value(&metadata.types, extension.ty.id, &extra_value, &mut extra)
+					.map_err(unsupported)?;
+			}
+			encode_value(
+				&metadata.types,
+				extension.additional_signed.id,
+				&additional_value,
+				&mut additional_signed,
+			)
+			.map_err(unsupported)?;
+		}
+
+		let mut signing_payload = [&call[..], &extra, &additional_signed].concat();
+		if signing_payload.len() > MAX_UNHASHED_PAYLOAD_LEN {
+			signing_payload = sp_crypto_hashing::blake2_256(&signing_payload).to_vec();
+		}
+
+		Ok(UnsignedPayload {
+			call: call.into(),
+			extra: extra.into(),
+			additional_signed: additional_signed.into(),
+			signing_payload: signing_payload.into(),
+			spec_version: version.spec_version,
+			transaction_version: version.transaction_version,
+			checkpoint,
+		})
+	}
+}
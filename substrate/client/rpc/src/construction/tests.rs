@@ -0,0 +1,108 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use jsonrpsee::{MethodsError as RpcError, RpcModule};
+use sp_core::{sr25519, Pair, H256};
+use sp_runtime::generic::Preamble;
+use substrate_test_runtime_client::{
+	runtime::{Block, ExtrinsicBuilder, Transfer},
+	Backend, Client, Sr25519Keyring,
+};
+
+fn api() -> RpcModule<Construction<Block, Client<Backend>>> {
+	Construction::new(Arc::new(substrate_test_runtime_client::new())).into_rpc()
+}
+
+#[tokio::test]
+async fn construction_payload_matches_signed_extrinsic() {
+	let api = api();
+
+	let request = json!({
+		"pallet": "Balances",
+		"call": "transfer_allow_death",
+		"args": {
+			"dest": to_hex(&Sr25519Keyring::Bob.to_raw_public(), false),
+			"value": 5,
+		},
+		"nonce": 3,
+		"mortality": 0,
+	});
+	let payload: UnsignedPayload<H256> =
+		api.call("construction_payload", [request]).await.unwrap();
+
+	let xt = ExtrinsicBuilder::new_transfer(Transfer {
+		from: Sr25519Keyring::Alice.into(),
+		to: Sr25519Keyring::Bob.into(),
+		amount: 5,
+		nonce: 3,
+	})
+	.build();
+	let Preamble::Signed(signer, signature, extension) = xt.preamble else {
+		panic!("The transfer is signed")
+	};
+
+	assert_eq!(payload.call.0, xt.function.encode());
+	assert_eq!(payload.extra.0, extension.encode());
+	assert!(sr25519::Pair::verify(&signature, &payload.signing_payload[..], &signer));
+}
+
+#[tokio::test]
+async fn construction_payload_uses_supplied_extension_values() {
+	let api = api();
+
+	let request = json!({
+		"pallet": "Balances",
+		"call": "transfer_allow_death",
+		"args": { "dest": to_hex(&Sr25519Keyring::Bob.to_raw_public(), false), "value": 5 },
+		"nonce": 3,
+		"mortality": 0,
+		"extensions": { "CheckNonce": { "extra": 4 } },
+	});
+	let payload: UnsignedPayload<H256> =
+		api.call("construction_payload", [request.clone()]).await.unwrap();
+	let xt = ExtrinsicBuilder::new_transfer(Transfer {
+		from: Sr25519Keyring::Alice.into(),
+		to: Sr25519Keyring::Bob.into(),
+		amount: 5,
+		nonce: 4,
+	})
+	.build();
+	let Preamble::Signed(_, _, extension) = xt.preamble else { panic!("The transfer is signed") };
+	assert_eq!(payload.extra.0, extension.encode());
+
+	let mut request = request;
+	request["extensions"] = json!({ "Unknown": {} });
+	assert_matches!(
+		api.call::<_, UnsignedPayload<H256>>("construction_payload", [request]).await,
+		Err(RpcError::JsonRpc(err))
+			if err.message() == "Invalid arguments: Unknown signed extension `Unknown`"
+	);
+}
+
+#[tokio::test]
+async fn construction_payload_rejects_unknown_call() {
+	let api = api();
+
+	let request = json!({ "pallet": "Balances", "call": "unknown", "nonce": 0 });
+	assert_matches!(
+		api.call::<_, UnsignedPayload<H256>>("construction_payload", [request]).await,
+		Err(RpcError::JsonRpc(err)) if err.message() == "Unknown call `Balances.unknown`"
+	);
+}
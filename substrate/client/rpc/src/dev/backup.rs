@@ -0,0 +1,83 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the [`BackupApiServer`] trait, taking consistent backups of the database.
+
+use jsonrpsee::Extensions;
+use sc_client_api::{BackupBackend, BackupMarker, BackupSettings};
+use sc_rpc_api::{check_if_safe, dev::error::Error};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::{marker::PhantomData, sync::Arc};
+
+pub use sc_rpc_api::dev::{BackupApiServer, BackupInfo};
+
+/// The backup API. All methods are unsafe.
+pub struct Backup<Block: BlockT, Backend> {
+	backend: Arc<Backend>,
+	settings: BackupSettings,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT, Backend> Backup<Block, Backend> {
+	/// Create a new backup API, taking the backups with the given `settings`.
+	pub fn new(backend: Arc<Backend>, settings: BackupSettings) -> Self {
+		Self { backend, settings, _phantom: PhantomData }
+	}
+}
+
+fn backup_info<Block: BlockT>(
+	marker: BackupMarker<Block>,
+) -> BackupInfo<Block::Hash, NumberFor<Block>> {
+	BackupInfo {
+		best_hash: marker.best_hash,
+		best_number: marker.best_number,
+		finalized_hash: marker.finalized_hash,
+		finalized_number: marker.finalized_number,
+		timestamp: marker.timestamp,
+		snapshot: None,
+		duration: None,
+	}
+}
+
+impl<Block, Backend> BackupApiServer<Block::Hash, NumberFor<Block>> for Backup<Block, Backend>
+where
+	Block: BlockT + 'static,
+	Backend: BackupBackend<Block> + 'static,
+{
+	fn backup(&self, ext: &Extensions) -> Result<BackupInfo<Block::Hash, NumberFor<Block>>, Error> {
+		check_if_safe(ext)?;
+		let report = self
+			.backend
+			.backup(&self.settings)
+			.map_err(|e| Error::BackupFailed(e.to_string()))?;
+		Ok(BackupInfo {
+			snapshot: report.snapshot.map(|path| path.display().to_string()),
+			duration: Some(report.duration.as_millis() as u64),
+			..backup_info(report.marker)
+		})
+	}
+
+	fn last_backup(
+		&self,
+		ext: &Extensions,
+	) -> Result<Option<BackupInfo<Block::Hash, NumberFor<Block>>>, Error> {
+		check_if_safe(ext)?;
+		let marker = self.backend.last_backup().map_err(|e| Error::BackupFailed(e.to_string()))?;
+		Ok(marker.map(backup_info))
+	}
+}
@@ -0,0 +1,65 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the [`BlacklistApiServer`] trait, managing the block blacklist.
+
+use jsonrpsee::Extensions;
+use sc_client_api::BlockBlacklist;
+use sc_rpc_api::{check_if_safe, dev::error::Error};
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, sync::Arc};
+
+pub use sc_rpc_api::dev::BlacklistApiServer;
+
+/// The blacklist API. All methods are unsafe.
+pub struct Blacklist<Block: BlockT, Client> {
+	client: Arc<Client>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT, Client> Blacklist<Block, Client> {
+	/// Create a new blacklist API.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, _phantom: PhantomData }
+	}
+}
+
+impl<Block, Client> BlacklistApiServer<Block::Hash> for Blacklist<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client: BlockBlacklist<Block> + Send + Sync + 'static,
+{
+	fn blacklist_block(&self, ext: &Extensions, hash: Block::Hash) -> Result<(), Error> {
+		check_if_safe(ext)?;
+		self.client
+			.blacklist_block(hash)
+			.map_err(|e| Error::BlacklistFailed(e.to_string()))
+	}
+
+	fn unblacklist_block(&self, ext: &Extensions, hash: Block::Hash) -> Result<bool, Error> {
+		check_if_safe(ext)?;
+		self.client
+			.unblacklist_block(hash)
+			.map_err(|e| Error::BlacklistFailed(e.to_string()))
+	}
+
+	fn blacklisted_blocks(&self, ext: &Extensions) -> Result<Vec<Block::Hash>, Error> {
+		check_if_safe(ext)?;
+		Ok(self.client.blacklisted_blocks())
+	}
+}
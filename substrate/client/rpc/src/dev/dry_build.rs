@@ -0,0 +1,165 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the [`DryBuildApiServer`] trait, building blocks without importing them.
+
+use codec::{Decode, Encode};
+use jsonrpsee::{core::async_trait, Extensions};
+use parking_lot::Mutex;
+use sc_client_api::HeaderBackend;
+use sc_rpc_api::{check_if_safe, dev::error::Error};
+use sp_consensus::{Environment, Proposer};
+use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
+use sp_runtime::{
+	traits::{Block as BlockT, Hash, HashingFor, Header},
+	Digest,
+};
+use sp_weights::Weight;
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+pub use sc_rpc_api::dev::{DryBuildApiServer, DryBuiltBlock, DryBuiltExtrinsic, DryBuiltWeight};
+
+/// The maximum time spent building a block.
+const MAX_BUILD_DURATION: Duration = Duration::from_secs(2);
+
+/// Creates the pre-runtime digests of a block from its inherent data.
+///
+/// Needed if the runtime expects a consensus pre-runtime digest, like the BABE slot claim.
+pub type PreDigestProvider = Box<dyn Fn(&InherentData) -> Result<Digest, String> + Send + Sync>;
+
+/// The dry-build API, building blocks with the same proposer as the block author.
+pub struct DryBuild<Block: BlockT, Client, PF, CIDP> {
+	client: Arc<Client>,
+	proposer_factory: Mutex<PF>,
+	create_inherent_data_providers: CIDP,
+	pre_digest: Option<PreDigestProvider>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT, Client, PF, CIDP> DryBuild<Block, Client, PF, CIDP> {
+	/// Create a new dry-build API.
+	pub fn new(
+		client: Arc<Client>,
+		proposer_factory: PF,
+		create_inherent_data_providers: CIDP,
+	) -> Self {
+		Self {
+			client,
+			proposer_factory: Mutex::new(proposer_factory),
+			create_inherent_data_providers,
+			pre_digest: None,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Add the pre-runtime digests created by `pre_digest` to the built blocks.
+	pub fn with_pre_digest(mut self, pre_digest: PreDigestProvider) -> Self {
+		self.pre_digest = Some(pre_digest);
+		self
+	}
+}
+
+fn build_failed(e: impl std::fmt::Display) -> Error {
+	Error::BlockBuildFailed(e.to_string())
+}
+
+#[async_trait]
+impl<Block, Client, PF, CIDP> DryBuildApiServer<Block::Hash> for DryBuild<Block, Client, PF, CIDP>
+where
+	Block: BlockT + 'static,
+	Client: HeaderBackend<Block> + Send + Sync + 'static,
+	PF: Environment<Block> + Send + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
+{
+	async fn dry_build_block(
+		&self,
+		ext: &Extensions,
+		parent_hash: Option<Block::Hash>,
+	) -> Result<Option<DryBuiltBlock<Block::Hash>>, Error> {
+		check_if_safe(ext)?;
+
+		let parent_hash = parent_hash.unwrap_or_else(|| self.client.info().best_hash);
+		let Some(parent) = self
+			.client
+			.header(parent_hash)
+			.map_err(|e| Error::BlockQueryError(Box::new(e)))?
+		else {
+			return Ok(None)
+		};
+
+		let inherent_data = self
+			.create_inherent_data_providers
+			.create_inherent_data_providers(parent_hash, ())
+			.await
+			.map_err(build_failed)?
+			.create_inherent_data()
+			.await
+			.map_err(build_failed)?;
+		let digest = match &self.pre_digest {
+			Some(pre_digest) => pre_digest(&inherent_data).map_err(Error::BlockBuildFailed)?,
+			None => Digest::default(),
+		};
+
+		let create_proposer = self.proposer_factory.lock().init(&parent);
+		let proposal = create_proposer
+			.await
+			.map_err(build_failed)?
+			.propose(inherent_data, digest, MAX_BUILD_DURATION, None)
+			.await
+			.map_err(build_failed)?;
+
+		let block_len = proposal.block.encoded_size() as u64;
+		let (header, extrinsics) = proposal.block.deconstruct();
+		let extrinsics = extrinsics
+			.into_iter()
+			.map(|xt| {
+				let extrinsic = xt.encode();
+				DryBuiltExtrinsic {
+					hash: HashingFor::<Block>::hash(&extrinsic),
+					extrinsic: extrinsic.into(),
+				}
+			})
+			.collect();
+
+		let weight_key =
+			[sp_crypto_hashing::twox_128(b"System"), sp_crypto_hashing::twox_128(b"BlockWeight")]
+				.concat();
+		let weight = proposal
+			.storage_changes
+			.main_storage_changes
+			.iter()
+			.find(|(key, _)| *key == weight_key)
+			.and_then(|(_, value)| value.as_ref())
+			// `PerDispatchClass<Weight>` encodes the normal, operational and mandatory weights.
+			.and_then(|value| <[Weight; 3]>::decode(&mut &value[..]).ok())
+			.map(|[normal, operational, mandatory]| DryBuiltWeight {
+				normal,
+				operational,
+				mandatory,
+			});
+
+		Ok(Some(DryBuiltBlock {
+			parent_hash,
+			state_root: *header.state_root(),
+			extrinsics_root: *header.extrinsics_root(),
+			extrinsics,
+			block_len,
+			weight,
+		}))
+	}
+}
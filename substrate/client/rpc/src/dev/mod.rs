@@ -22,6 +22,10 @@
 #[cfg(test)]
 mod tests;
 
+mod backup;
+mod blacklist;
+mod dry_build;
+
 use jsonrpsee::Extensions;
 use sc_client_api::{BlockBackend, HeaderBackend};
 use sc_rpc_api::{check_if_safe, dev::error::Error};
@@ -36,6 +40,12 @@ use std::{
 	sync::Arc,
 };
 
+pub use backup::{Backup, BackupApiServer, BackupInfo};
+pub use blacklist::{Blacklist, BlacklistApiServer};
+pub use dry_build::{
+	DryBuild, DryBuildApiServer, DryBuiltBlock, DryBuiltExtrinsic, DryBuiltWeight,
+	PreDigestProvider,
+};
 pub use sc_rpc_api::dev::{BlockStats, DevApiServer};
 
 type HasherOf<Block> = <<Block as BlockT>::Header as Header>::Hashing;
@@ -18,10 +18,24 @@
 
 use super::*;
 use crate::DenyUnsafe;
+use futures::StreamExt;
+use jsonrpsee::core::EmptyServerParams as EmptyParams;
+use sc_basic_authorship::ProposerFactory;
 use sc_block_builder::BlockBuilderBuilder;
+use sc_client_api::BlockchainEvents;
+use sc_transaction_pool::BasicPool;
+use sc_transaction_pool_api::{
+	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionSource,
+};
 use sp_blockchain::HeaderBackend;
-use sp_consensus::BlockOrigin;
-use substrate_test_runtime_client::{prelude::*, runtime::Block};
+use sp_consensus::{BlockOrigin, SelectChain};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use substrate_test_runtime_client::{
+	prelude::*,
+	runtime::{Block, ExtrinsicBuilder, Transfer},
+	Sr25519Keyring,
+};
 
 #[tokio::test]
 async fn block_stats_work() {
@@ -107,3 +121,126 @@ async fn deny_unsafe_works() {
 		r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"RPC call is unsafe to be called externally"}}"#
 	);
 }
+
+#[tokio::test]
+async fn dry_build_block_works() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let pool = Arc::from(BasicPool::new_full(
+		Default::default(),
+		true.into(),
+		None,
+		spawner.clone(),
+		client.clone(),
+	));
+	let genesis_hash = client.info().genesis_hash;
+	let genesis_header = client.header(genesis_hash).unwrap().unwrap();
+
+	let xt = ExtrinsicBuilder::new_transfer(Transfer {
+		from: Sr25519Keyring::Alice.into(),
+		to: Sr25519Keyring::Bob.into(),
+		amount: 1,
+		nonce: 0,
+	})
+	.build();
+	pool.submit_one(genesis_hash, TransactionSource::External, xt.clone()).await.unwrap();
+	pool.maintain(ChainEvent::NewBestBlock { hash: genesis_hash, tree_route: None }).await;
+
+	let proposer_factory = ProposerFactory::new(spawner, client.clone(), pool, None, None);
+	let create_inherent_data_providers =
+		|_: H256, ()| async { Ok::<_, Box<dyn std::error::Error + Send + Sync>>(()) };
+	let mut api = <DryBuild<Block, _, _, _>>::new(
+		client.clone(),
+		proposer_factory,
+		create_inherent_data_providers,
+	)
+	.into_rpc();
+	api.extensions_mut().insert(DenyUnsafe::No);
+
+	let block = api
+		.call::<_, Option<DryBuiltBlock<H256>>>("dev_dryBuildBlock", [genesis_hash])
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(block.parent_hash, genesis_hash);
+	assert_eq!(
+		block.extrinsics,
+		vec![DryBuiltExtrinsic { hash: BlakeTwo256::hash_of(&xt), extrinsic: xt.encode().into() }],
+	);
+	assert_ne!(block.state_root, *genesis_header.state_root());
+	assert!(block.weight.is_some());
+
+	// The block is not imported.
+	assert_eq!(client.info().best_hash, genesis_hash);
+
+	assert_eq!(
+		api.call::<_, Option<DryBuiltBlock<H256>>>("dev_dryBuildBlock", [H256::repeat_byte(1)])
+			.await
+			.unwrap(),
+		None,
+	);
+}
+
+#[tokio::test]
+async fn blacklist_block_works() {
+	let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+	let client = Arc::new(client);
+	let mut api = <Blacklist<Block, _>>::new(client.clone()).into_rpc();
+	api.extensions_mut().insert(DenyUnsafe::No);
+
+	let genesis_hash = client.chain_info().genesis_hash;
+	let mut parent = (genesis_hash, 0);
+	let mut blocks = Vec::new();
+	for _ in 0..2 {
+		let block = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(parent.0)
+			.with_parent_block_number(parent.1)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap()
+			.block;
+		parent = (block.hash(), parent.1 + 1);
+		blocks.push(block.clone());
+		client.import(BlockOrigin::Own, block).await.unwrap();
+	}
+	let hash = blocks[0].hash();
+	assert_eq!(client.info().best_hash, blocks[1].hash());
+
+	// The finalized genesis can't be blacklisted.
+	assert!(api.call::<_, ()>("dev_blacklistBlock", [genesis_hash]).await.is_err());
+
+	let mut imports = client.import_notification_stream();
+	api.call::<_, ()>("dev_blacklistBlock", [hash]).await.unwrap();
+	assert_eq!(client.info().best_hash, genesis_hash);
+	assert_eq!(select_chain.best_chain().await.unwrap().hash(), genesis_hash);
+	assert_eq!(client.number(blocks[1].hash()).unwrap(), None);
+
+	// The new best block is notified, retracting the blacklisted block and its descendant.
+	let notification = imports.next().await.unwrap();
+	assert_eq!(notification.hash, genesis_hash);
+	assert!(notification.is_new_best);
+	let tree_route = notification.tree_route.unwrap();
+	let retracted = tree_route.retracted().iter().map(|block| block.hash).collect::<Vec<_>>();
+	assert_eq!(retracted, vec![blocks[1].hash(), hash]);
+
+	// The blacklisted block is rejected at import.
+	client.import(BlockOrigin::Own, blocks[0].clone()).await.unwrap();
+	assert_eq!(client.number(hash).unwrap(), None);
+	assert_eq!(
+		api.call::<_, Vec<H256>>("dev_blacklistedBlocks", EmptyParams::new())
+			.await
+			.unwrap(),
+		vec![hash],
+	);
+
+	assert!(api.call::<_, bool>("dev_unblacklistBlock", [hash]).await.unwrap());
+	assert!(!api.call::<_, bool>("dev_unblacklistBlock", [hash]).await.unwrap());
+	assert!(api
+		.call::<_, Vec<H256>>("dev_blacklistedBlocks", EmptyParams::new())
+		.await
+		.unwrap()
+		.is_empty());
+	client.import(BlockOrigin::Own, blocks[0].clone()).await.unwrap();
+	assert_eq!(client.number(hash).unwrap(), Some(1));
+}
@@ -26,13 +26,15 @@ pub use jsonrpsee::core::id_providers::{
 	RandomIntegerIdProvider as RandomIntegerSubscriptionId,
 	RandomStringIdProvider as RandomStringSubscriptionId,
 };
-pub use sc_rpc_api::DenyUnsafe;
+pub use sc_rpc_api::{DenyUnsafe, Unsubscriptions};
 
 pub mod author;
 pub mod chain;
+pub mod construction;
 pub mod dev;
 pub mod mixnet;
 pub mod offchain;
+pub mod scale;
 pub mod state;
 pub mod statement;
 pub mod system;
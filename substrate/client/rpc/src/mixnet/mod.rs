@@ -18,11 +18,30 @@
 
 //! Substrate mixnet API.
 
-use jsonrpsee::core::async_trait;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	utils::{IdempotencyCache, IdempotencyLookup},
+	SubscriptionTaskExecutor,
+};
+use codec::Decode;
+use futures::FutureExt;
+use jsonrpsee::core::{async_trait, RpcResult};
+use parking_lot::Mutex;
 use sc_mixnet::Api;
-use sc_rpc_api::mixnet::error::Error;
-pub use sc_rpc_api::mixnet::MixnetApiServer;
+pub use sc_rpc_api::mixnet::{MixnetApiServer, MixnetAuthorApiServer, SubmissionStatus};
+use sc_rpc_api::{author::error::Error as AuthorError, mixnet::error::Error};
+use sc_transaction_pool_api::{TransactionFor, TransactionPool, TxHash};
 use sp_core::Bytes;
+use std::{
+	collections::{HashMap, VecDeque},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Maximum number of submissions whose delivery status is tracked.
+const MAX_TRACKED_SUBMISSIONS: usize = 1024;
 
 /// Mixnet API.
 pub struct Mixnet(futures::lock::Mutex<Api>);
@@ -45,3 +64,119 @@ impl MixnetApiServer for Mixnet {
 		Ok(fut.await.map_err(Error)?)
 	}
 }
+
+/// Delivery status of the most recent submissions.
+struct Submissions<H> {
+	statuses: HashMap<H, SubmissionStatus>,
+	/// Hashes of the tracked submissions, oldest first.
+	order: VecDeque<H>,
+}
+
+impl<H: Hash + Eq + Clone> Submissions<H> {
+	fn new() -> Self {
+		Self { statuses: HashMap::new(), order: VecDeque::new() }
+	}
+
+	/// Start tracking a submission, forgetting the oldest one if at the limit.
+	fn insert(&mut self, hash: H) {
+		if self.statuses.insert(hash.clone(), SubmissionStatus::Pending).is_some() {
+			return
+		}
+		self.order.push_back(hash);
+		if self.order.len() > MAX_TRACKED_SUBMISSIONS {
+			if let Some(oldest) = self.order.pop_front() {
+				self.statuses.remove(&oldest);
+			}
+		}
+	}
+
+	/// Update the status of a tracked submission.
+	fn set(&mut self, hash: &H, status: SubmissionStatus) {
+		if let Some(current) = self.statuses.get_mut(hash) {
+			*current = status;
+		}
+	}
+
+	fn get(&self, hash: &H) -> Option<SubmissionStatus> {
+		self.statuses.get(hash).cloned()
+	}
+}
+
+/// Authoring API submitting extrinsics over the mixnet instead of to the local transaction pool.
+pub struct MixnetAuthor<P: TransactionPool> {
+	pool: Arc<P>,
+	api: futures::lock::Mutex<Api>,
+	submissions: Arc<Mutex<Submissions<TxHash<P>>>>,
+	/// Idempotency keys of the sent submissions.
+	submitted: IdempotencyCache<()>,
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<P: TransactionPool> MixnetAuthor<P> {
+	/// Create a new mixnet authoring API instance.
+	///
+	/// The transaction pool is only used to compute the hashes of the submitted extrinsics.
+	pub fn new(pool: Arc<P>, api: Api, executor: SubscriptionTaskExecutor) -> Self {
+		Self {
+			pool,
+			api: futures::lock::Mutex::new(api),
+			submissions: Arc::new(Mutex::new(Submissions::new())),
+			submitted: Default::default(),
+			executor,
+		}
+	}
+}
+
+#[async_trait]
+impl<P> MixnetAuthorApiServer<TxHash<P>> for MixnetAuthor<P>
+where
+	P: TransactionPool + Sync + Send + 'static,
+{
+	async fn submit_extrinsic(
+		&self,
+		extrinsic: Bytes,
+		idempotency_key: Option<String>,
+	) -> RpcResult<TxHash<P>> {
+		let xt: TransactionFor<P> = Decode::decode(&mut &extrinsic[..])
+			.map_err(|err| AuthorError::Client(Box::new(err)))?;
+		let hash = self.pool.hash_of(&xt);
+		if let Some(key) = &idempotency_key {
+			match self.submitted.lookup(key, &extrinsic) {
+				IdempotencyLookup::Unknown => {},
+				IdempotencyLookup::Known(()) => return Ok(hash),
+				IdempotencyLookup::TooLong => return Err(AuthorError::IdempotencyKeyTooLong.into()),
+			}
+		}
+
+		if let Some(key) = idempotency_key {
+			self.submitted.insert(key, &extrinsic, ());
+		}
+
+		// Track the submission before awaiting, so its status is known as soon as it is sent.
+		self.submissions.lock().insert(hash.clone());
+
+		// We only hold the lock while pushing the request into the requests channel
+		let fut = {
+			let mut api = self.api.lock().await;
+			api.submit_extrinsic(extrinsic).await
+		};
+
+		let submissions = self.submissions.clone();
+		let tracked_hash = hash.clone();
+		let track = async move {
+			let status = match fut.await {
+				Ok(()) => SubmissionStatus::Delivered,
+				Err(err) => SubmissionStatus::Failed(err.to_string()),
+			};
+			submissions.lock().set(&tracked_hash, status);
+		};
+		self.executor
+			.spawn("substrate-rpc-mixnet-submission", Some("rpc"), track.boxed());
+
+		Ok(hash)
+	}
+
+	fn submission_status(&self, hash: TxHash<P>) -> RpcResult<Option<SubmissionStatus>> {
+		Ok(self.submissions.lock().get(&hash))
+	}
+}
@@ -0,0 +1,46 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+#[test]
+fn submissions_track_status() {
+	let mut submissions = Submissions::new();
+	submissions.insert(1u64);
+	assert_eq!(submissions.get(&1), Some(SubmissionStatus::Pending));
+	assert_eq!(submissions.get(&2), None);
+
+	submissions.set(&1, SubmissionStatus::Delivered);
+	assert_eq!(submissions.get(&1), Some(SubmissionStatus::Delivered));
+
+	// Untracked submissions are not inserted by status updates.
+	submissions.set(&2, SubmissionStatus::Failed("no reply".into()));
+	assert_eq!(submissions.get(&2), None);
+}
+
+#[test]
+fn submissions_forget_oldest_at_limit() {
+	let mut submissions = Submissions::new();
+	for hash in 0..=MAX_TRACKED_SUBMISSIONS as u64 {
+		submissions.insert(hash);
+	}
+	assert_eq!(submissions.get(&0), None);
+	assert_eq!(submissions.get(&1), Some(SubmissionStatus::Pending));
+	assert_eq!(submissions.get(&(MAX_TRACKED_SUBMISSIONS as u64)), Some(SubmissionStatus::Pending));
+	assert_eq!(submissions.statuses.len(), MAX_TRACKED_SUBMISSIONS);
+}
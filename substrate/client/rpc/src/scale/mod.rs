@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the [`ScaleApiServer`] trait, converting values between SCALE and JSON.
+
+#[cfg(test)]
+mod tests;
+
+use frame_metadata::v15::RuntimeMetadataV15;
+use sc_rpc_api::scale::error::{Error, Result};
+use sc_runtime_metadata::{decode::decode_value, encode::encode_value, MetadataCache};
+use serde_json::Value;
+use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, sync::Arc};
+
+pub use sc_rpc_api::scale::ScaleApiServer;
+
+/// SCALE codec API.
+pub struct Scale<Block, Client> {
+	client: Arc<Client>,
+	metadata: MetadataCache,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> Scale<Block, Client> {
+	/// Create a new SCALE codec API.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, metadata: MetadataCache::default(), _phantom: PhantomData }
+	}
+}
+
+impl<Block, Client> Scale<Block, Client>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+	Client::Api: Core<Block> + Metadata<Block>,
+{
+	/// The metadata of the runtime at `at`, or at the best block.
+	fn metadata(&self, at: Option<Block::Hash>) -> Result<Arc<RuntimeMetadataV15>> {
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let version = self
+			.client
+			.runtime_api()
+			.version(hash)
+			.map_err(|e| Error::Client(Box::new(e)))?;
+		self.metadata
+			.get::<Block, _>(&*self.client, hash, version.spec_version)
+			.map_err(Error::MetadataUnavailable)
+	}
+}
+
+impl<Block, Client> ScaleApiServer<Block::Hash> for Scale<Block, Client>
+where
+	Block: BlockT + 'static,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client::Api: Core<Block> + Metadata<Block>,
+{
+	fn decode(&self, ty: u32, bytes: Bytes, at: Option<Block::Hash>) -> Result<Value> {
+		let metadata = self.metadata(at)?;
+		let mut input = &bytes[..];
+		let value = decode_value(&metadata.types, ty, &mut input)
+			.map_err(|e| Error::InvalidValue(e.to_string()))?;
+		if !input.is_empty() {
+			return Err(Error::InvalidValue(format!("{} bytes left after decoding", input.len())))
+		}
+		Ok(value)
+	}
+
+	fn encode(&self, ty: u32, value: Value, at: Option<Block::Hash>) -> Result<Bytes> {
+		let metadata = self.metadata(at)?;
+		let mut encoded = Vec::new();
+		encode_value(&metadata.types, ty, &value, &mut encoded).map_err(Error::InvalidValue)?;
+		Ok(encoded.into())
+	}
+}
@@ -0,0 +1,71 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use codec::Encode;
+use jsonrpsee::{core::EmptyServerParams as EmptyParams, MethodsError as RpcError};
+use serde_json::json;
+use sp_core::bytes::to_hex;
+use substrate_test_runtime_client::{
+	runtime::{BalancesCall, Block, RuntimeCall},
+	Sr25519Keyring,
+};
+
+#[tokio::test]
+async fn scale_values_round_trip() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let call_ty = MetadataCache::default()
+		.get::<Block, _>(&*client, client.info().best_hash, 0)
+		.unwrap()
+		.outer_enums
+		.call_enum_ty
+		.id;
+	let api = <Scale<Block, _>>::new(client).into_rpc();
+
+	let call = RuntimeCall::Balances(BalancesCall::transfer_allow_death {
+		dest: Sr25519Keyring::Bob.into(),
+		value: 5,
+	});
+	let value = json!({
+		"Balances": {
+			"transfer_allow_death": {
+				"dest": to_hex(&Sr25519Keyring::Bob.to_raw_public(), false),
+				"value": 5,
+			},
+		},
+	});
+
+	let params = [json!(call_ty), json!(Bytes(call.encode()))];
+	let decoded: Value = api.call("scale_decode", params).await.unwrap();
+	assert_eq!(decoded, value);
+
+	let encoded: Bytes = api.call("scale_encode", [json!(call_ty), value]).await.unwrap();
+	assert_eq!(encoded.0, call.encode());
+
+	let mut bytes = call.encode();
+	bytes.push(0);
+	assert_matches!(
+		api.call::<_, Value>("scale_decode", [json!(call_ty), json!(Bytes(bytes))]).await,
+		Err(RpcError::JsonRpc(err)) if err.message() == "Invalid value: 1 bytes left after decoding"
+	);
+	assert_matches!(
+		api.call::<_, Value>("scale_decode", EmptyParams::new()).await,
+		Err(RpcError::JsonRpc(_))
+	);
+}
@@ -22,12 +22,13 @@ mod state_full;
 mod utils;
 
 #[cfg(test)]
-mod tests;
 
+mod tests;
 use crate::SubscriptionTaskExecutor;
 use jsonrpsee::{core::async_trait, Extensions, PendingSubscriptionSink};
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, ExecutorProvider, ProofProvider, StorageProvider,
+	with_state_regeneration, Backend, BlockBackend, BlockchainEvents, ExecutorProvider,
+	ProofProvider, StorageProvider,
 };
 use sc_rpc_api::{check_if_safe, DenyUnsafe};
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
@@ -151,6 +152,9 @@ where
 	/// New runtime version subscription
 	fn subscribe_runtime_version(&self, pending: PendingSubscriptionSink);
 
+	/// New runtime upgrade subscription
+	fn subscribe_runtime_upgrades(&self, pending: PendingSubscriptionSink);
+
 	/// New storage subscription
 	fn subscribe_storage(
 		&self,
@@ -161,9 +165,13 @@ where
 }
 
 /// Create new state API that works on full node.
+///
+/// If `finalized_only` is set, the requests without block hash are served from the last finalized
+/// block instead of the best block, and the subscriptions only follow the finalized blocks.
 pub fn new_full<BE, Block: BlockT, Client>(
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	finalized_only: bool,
 ) -> (State<Block, Client>, ChildState<Block, Client>)
 where
 	Block: BlockT + 'static,
@@ -183,9 +191,12 @@ where
 		+ 'static,
 	Client::Api: Metadata<Block>,
 {
-	let child_backend =
-		Box::new(self::state_full::FullState::new(client.clone(), executor.clone()));
-	let backend = Box::new(self::state_full::FullState::new(client, executor));
+	let child_backend = Box::new(self::state_full::FullState::new(
+		client.clone(),
+		executor.clone(),
+		finalized_only,
+	));
+	let backend = Box::new(self::state_full::FullState::new(client, executor, finalized_only));
 	(State { backend }, ChildState { backend: child_backend })
 }
 
@@ -206,7 +217,7 @@ where
 		data: Bytes,
 		block: Option<Block::Hash>,
 	) -> Result<Bytes, Error> {
-		self.backend.call(block, method, data).map_err(Into::into)
+		with_state_regeneration(|| self.backend.call(block, method, data)).map_err(Into::into)
 	}
 
 	fn storage_keys(
@@ -214,7 +225,7 @@ where
 		key_prefix: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Vec<StorageKey>, Error> {
-		self.backend.storage_keys(block, key_prefix).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_keys(block, key_prefix)).map_err(Into::into)
 	}
 
 	fn storage_pairs(
@@ -224,7 +235,8 @@ where
 		block: Option<Block::Hash>,
 	) -> Result<Vec<(StorageKey, StorageData)>, Error> {
 		check_if_safe(ext)?;
-		self.backend.storage_pairs(block, key_prefix).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_pairs(block, key_prefix))
+			.map_err(Into::into)
 	}
 
 	fn storage_keys_paged(
@@ -237,8 +249,7 @@ where
 		if count > STORAGE_KEYS_PAGED_MAX_COUNT {
 			return Err(Error::InvalidCount { value: count, max: STORAGE_KEYS_PAGED_MAX_COUNT })
 		}
-		self.backend
-			.storage_keys_paged(block, prefix, count, start_key)
+		with_state_regeneration(|| self.backend.storage_keys_paged(block, prefix, count, start_key))
 			.map_err(Into::into)
 	}
 
@@ -247,7 +258,7 @@ where
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<StorageData>, Error> {
-		self.backend.storage(block, key).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage(block, key)).map_err(Into::into)
 	}
 
 	fn storage_hash(
@@ -255,7 +266,7 @@ where
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<Block::Hash>, Error> {
-		self.backend.storage_hash(block, key).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_hash(block, key)).map_err(Into::into)
 	}
 
 	async fn storage_size(
@@ -272,11 +283,11 @@ where
 	}
 
 	fn metadata(&self, block: Option<Block::Hash>) -> Result<Bytes, Error> {
-		self.backend.metadata(block).map_err(Into::into)
+		with_state_regeneration(|| self.backend.metadata(block)).map_err(Into::into)
 	}
 
 	fn runtime_version(&self, at: Option<Block::Hash>) -> Result<RuntimeVersion, Error> {
-		self.backend.runtime_version(at).map_err(Into::into)
+		with_state_regeneration(|| self.backend.runtime_version(at)).map_err(Into::into)
 	}
 
 	fn query_storage(
@@ -287,7 +298,7 @@ where
 		to: Option<Block::Hash>,
 	) -> Result<Vec<StorageChangeSet<Block::Hash>>, Error> {
 		check_if_safe(ext)?;
-		self.backend.query_storage(from, to, keys).map_err(Into::into)
+		with_state_regeneration(|| self.backend.query_storage(from, to, keys)).map_err(Into::into)
 	}
 
 	fn query_storage_at(
@@ -295,7 +306,7 @@ where
 		keys: Vec<StorageKey>,
 		at: Option<Block::Hash>,
 	) -> Result<Vec<StorageChangeSet<Block::Hash>>, Error> {
-		self.backend.query_storage_at(keys, at).map_err(Into::into)
+		with_state_regeneration(|| self.backend.query_storage_at(keys, at)).map_err(Into::into)
 	}
 
 	fn read_proof(
@@ -303,7 +314,7 @@ where
 		keys: Vec<StorageKey>,
 		block: Option<Block::Hash>,
 	) -> Result<ReadProof<Block::Hash>, Error> {
-		self.backend.read_proof(block, keys).map_err(Into::into)
+		with_state_regeneration(|| self.backend.read_proof(block, keys)).map_err(Into::into)
 	}
 
 	/// Re-execute the given block with the tracing targets given in `targets`
@@ -320,8 +331,7 @@ where
 		methods: Option<String>,
 	) -> Result<sp_rpc::tracing::TraceBlockResponse, Error> {
 		check_if_safe(ext)?;
-		self.backend
-			.trace_block(block, targets, storage_keys, methods)
+		with_state_regeneration(|| self.backend.trace_block(block, targets, storage_keys, methods))
 			.map_err(Into::into)
 	}
 
@@ -329,6 +339,10 @@ where
 		self.backend.subscribe_runtime_version(pending)
 	}
 
+	fn subscribe_runtime_upgrades(&self, pending: PendingSubscriptionSink) {
+		self.backend.subscribe_runtime_upgrades(pending)
+	}
+
 	fn subscribe_storage(
 		&self,
 		pending: PendingSubscriptionSink,
@@ -427,7 +441,8 @@ where
 		key_prefix: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Vec<StorageKey>, Error> {
-		self.backend.storage_keys(block, storage_key, key_prefix).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_keys(block, storage_key, key_prefix))
+			.map_err(Into::into)
 	}
 
 	fn storage_keys_paged(
@@ -438,9 +453,10 @@ where
 		start_key: Option<StorageKey>,
 		block: Option<Block::Hash>,
 	) -> Result<Vec<StorageKey>, Error> {
-		self.backend
-			.storage_keys_paged(block, storage_key, prefix, count, start_key)
-			.map_err(Into::into)
+		with_state_regeneration(|| {
+			self.backend.storage_keys_paged(block, storage_key, prefix, count, start_key)
+		})
+		.map_err(Into::into)
 	}
 
 	fn storage(
@@ -449,7 +465,8 @@ where
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<StorageData>, Error> {
-		self.backend.storage(block, storage_key, key).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage(block, storage_key, key))
+			.map_err(Into::into)
 	}
 
 	fn storage_entries(
@@ -458,7 +475,8 @@ where
 		keys: Vec<StorageKey>,
 		block: Option<Block::Hash>,
 	) -> Result<Vec<Option<StorageData>>, Error> {
-		self.backend.storage_entries(block, storage_key, keys).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_entries(block, storage_key, keys))
+			.map_err(Into::into)
 	}
 
 	fn storage_hash(
@@ -467,7 +485,8 @@ where
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<Block::Hash>, Error> {
-		self.backend.storage_hash(block, storage_key, key).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_hash(block, storage_key, key))
+			.map_err(Into::into)
 	}
 
 	fn storage_size(
@@ -476,7 +495,8 @@ where
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<u64>, Error> {
-		self.backend.storage_size(block, storage_key, key).map_err(Into::into)
+		with_state_regeneration(|| self.backend.storage_size(block, storage_key, key))
+			.map_err(Into::into)
 	}
 
 	fn read_child_proof(
@@ -485,8 +505,7 @@ where
 		keys: Vec<StorageKey>,
 		block: Option<Block::Hash>,
 	) -> Result<ReadProof<Block::Hash>, Error> {
-		self.backend
-			.read_child_proof(block, child_storage_key, keys)
+		with_state_regeneration(|| self.backend.read_child_proof(block, child_storage_key, keys))
 			.map_err(Into::into)
 	}
 }
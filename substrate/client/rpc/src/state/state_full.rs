@@ -30,13 +30,16 @@ use crate::{
 	DenyUnsafe, SubscriptionTaskExecutor,
 };
 
-use futures::{future, stream, StreamExt};
+use codec::Decode;
+use futures::{future, stream, stream::BoxStream, FutureExt, StreamExt};
 use jsonrpsee::{core::async_trait, types::ErrorObject, PendingSubscriptionSink};
+use parking_lot::Mutex;
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, ProofProvider,
-	StorageProvider,
+	with_state_regeneration, Backend, BlockBackend, BlockchainEvents, CallExecutor,
+	ExecutorProvider, ProofProvider, StorageProvider,
 };
-use sc_rpc_api::state::ReadProof;
+use sc_rpc_api::state::{PendingUpgrade, PendingUpgradeKind, ReadProof, RuntimeUpgradeStatus};
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
 use sp_blockchain::{
 	CachedHeaderMetadata, Error as ClientError, HeaderBackend, HeaderMetadata,
@@ -49,12 +52,21 @@ use sp_core::{
 	traits::CallContext,
 	Bytes,
 };
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, HashingFor, Header as HeaderT};
 use sp_version::RuntimeVersion;
 
 /// The maximum time allowed for an RPC call when running without unsafe RPC enabled.
 const MAXIMUM_SAFE_RPC_CALL_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// The maximum number of blocks searched for the block a pending parachain upgrade was scheduled
+/// at.
+const MAX_UPGRADE_LOOKBACK: u32 = 4096;
+
+/// Senders of the `state_subscribeRuntimeUpgrades` subscribers, `None` while no task is following
+/// the runtime upgrade status of new blocks.
+type RuntimeUpgradeSubscribers<Hash> =
+	Arc<Mutex<Option<Vec<TracingUnboundedSender<RuntimeUpgradeStatus<Hash>>>>>>;
+
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
 	/// Hashes of all the blocks in the range.
@@ -65,6 +77,9 @@ struct QueryStorageRange<Block: BlockT> {
 pub struct FullState<BE, Block: BlockT, Client> {
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	/// Whether only finalized blocks are served.
+	finalized_only: bool,
+	runtime_upgrade_subscribers: RuntimeUpgradeSubscribers<Block::Hash>,
 	_phantom: PhantomData<(BE, Block)>,
 }
 
@@ -78,13 +93,31 @@ where
 	Block: BlockT + 'static,
 {
 	/// Create new state API backend for full nodes.
-	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
-		Self { client, executor, _phantom: PhantomData }
+	pub fn new(
+		client: Arc<Client>,
+		executor: SubscriptionTaskExecutor,
+		finalized_only: bool,
+	) -> Self {
+		Self {
+			client,
+			executor,
+			finalized_only,
+			runtime_upgrade_subscribers: Default::default(),
+			_phantom: PhantomData,
+		}
 	}
 
-	/// Returns given block hash or best block hash if None is passed.
+	/// Returns given block hash or, if None is passed, the best block hash or the last finalized
+	/// block hash if only finalized blocks are served.
 	fn block_or_best(&self, hash: Option<Block::Hash>) -> ClientResult<Block::Hash> {
-		Ok(hash.unwrap_or_else(|| self.client.info().best_hash))
+		Ok(hash.unwrap_or_else(|| {
+			let info = self.client.info();
+			if self.finalized_only {
+				info.finalized_hash
+			} else {
+				info.best_hash
+			}
+		}))
 	}
 
 	/// Validates block range.
@@ -147,13 +180,16 @@ where
 	) -> Result<()> {
 		for block_hash in &range.hashes {
 			let mut block_changes = StorageChangeSet { block: *block_hash, changes: Vec::new() };
-			for key in keys {
-				let (has_changed, data) = {
-					let curr_data = self.client.storage(*block_hash, key).map_err(client_err)?;
-					match last_values.get(key) {
-						Some(prev_data) => (curr_data != *prev_data, curr_data),
-						None => (true, curr_data),
-					}
+			// Read all keys from the same state, for the values to be consistent.
+			let values = self
+				.client
+				.storage_snapshot(*block_hash)
+				.and_then(|snapshot| snapshot.storage_batch(keys))
+				.map_err(client_err)?;
+			for (key, curr_data) in keys.iter().zip(values) {
+				let (has_changed, data) = match last_values.get(key) {
+					Some(prev_data) => (curr_data != *prev_data, curr_data),
+					None => (true, curr_data),
 				};
 				if has_changed {
 					block_changes.changes.push((key.clone(), data.clone()));
@@ -166,6 +202,48 @@ where
 		}
 		Ok(())
 	}
+
+	/// Subscribe to the changes of the values of `keys` between finalized blocks.
+	///
+	/// The values are compared at every newly finalized block, so the changes of blocks finalized
+	/// together are merged into one notification.
+	fn subscribe_finalized_storage(
+		&self,
+		pending: PendingSubscriptionSink,
+		keys: Option<Vec<StorageKey>>,
+	) where
+		Client: BlockchainEvents<Block> + Send + Sync + 'static,
+	{
+		let Some(keys) = keys else {
+			let err = "Subscribing to all storage changes is not supported when serving finalized \
+				blocks only";
+			spawn_subscription_task(&self.executor, pending.reject(Error::Client(err.into())));
+			return
+		};
+
+		let client = self.client.clone();
+		let mut last_values = HashMap::new();
+		let stream = stream::once(future::ready(self.client.info().finalized_hash))
+			.chain(new_heads(&*self.client, true))
+			.map(move |block| {
+				let changes = keys
+					.iter()
+					.filter_map(|key| {
+						let value = client.storage(block, key).ok().flatten();
+						let has_changed = last_values.get(key) != Some(&value);
+						last_values.insert(key.clone(), value.clone());
+						has_changed.then(|| (key.clone(), value))
+					})
+					.collect();
+				StorageChangeSet { block, changes }
+			})
+			.filter(|storage| future::ready(!storage.changes.is_empty()));
+
+		spawn_subscription_task(
+			&self.executor,
+			PendingSubscription::from(pending).pipe_from_stream(stream, BoundedVecDeque::default()),
+		);
+	}
 }
 
 #[async_trait]
@@ -271,32 +349,35 @@ where
 		};
 
 		super::utils::spawn_blocking_with_timeout(timeout, move |is_timed_out| {
-			// Does the key point to a concrete entry in the database?
-			match client.storage(block, &key) {
-				Ok(Some(d)) => return Ok(Ok(Some(d.0.len() as u64))),
-				Err(e) => return Ok(Err(client_err(e))),
-				Ok(None) => {},
-			}
+			with_state_regeneration(|| {
+				// Does the key point to a concrete entry in the database?
+				match client.storage(block, &key) {
+					Ok(Some(d)) => return Ok(Ok(Some(d.0.len() as u64))),
+					Err(e) => return Ok(Err(client_err(e))),
+					Ok(None) => {},
+				}
 
-			// The key doesn't point to anything, so it's probably a prefix.
-			let iter = match client.storage_keys(block, Some(&key), None).map_err(client_err) {
-				Ok(iter) => iter,
-				Err(e) => return Ok(Err(e)),
-			};
+				// The key doesn't point to anything, so it's probably a prefix.
+				let iter = match client.storage_keys(block, Some(&key), None).map_err(client_err) {
+					Ok(iter) => iter,
+					Err(e) => return Ok(Err(e)),
+				};
 
-			let mut sum = 0;
-			for storage_key in iter {
-				let value = client.storage(block, &storage_key).ok().flatten().unwrap_or_default();
-				sum += value.0.len() as u64;
+				let mut sum = 0;
+				for storage_key in iter {
+					let value =
+						client.storage(block, &storage_key).ok().flatten().unwrap_or_default();
+					sum += value.0.len() as u64;
 
-				is_timed_out.check_if_timed_out()?;
-			}
+					is_timed_out.check_if_timed_out()?;
+				}
 
-			if sum > 0 {
-				Ok(Ok(Some(sum)))
-			} else {
-				Ok(Ok(None))
-			}
+				if sum > 0 {
+					Ok(Ok(Some(sum)))
+				} else {
+					Ok(Ok(None))
+				}
+			})
 		})
 		.await
 		.map_err(|error| Error::Client(Box::new(error)))?
@@ -352,7 +433,7 @@ where
 		keys: Vec<StorageKey>,
 		at: Option<Block::Hash>,
 	) -> std::result::Result<Vec<StorageChangeSet<Block::Hash>>, Error> {
-		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let at = self.block_or_best(at).map_err(client_err)?;
 		self.query_storage(at, Some(at), keys)
 	}
 
@@ -388,12 +469,10 @@ where
 		let client = self.client.clone();
 
 		// A stream of new versions
-		let version_stream = client
-			.import_notification_stream()
-			.filter(|n| future::ready(n.is_new_best))
-			.filter_map(move |n| {
+		let version_stream =
+			new_heads(&*self.client, self.finalized_only).filter_map(move |hash| {
 				let version =
-					client.runtime_version_at(n.hash).map_err(|e| Error::Client(Box::new(e)));
+					client.runtime_version_at(hash).map_err(|e| Error::Client(Box::new(e)));
 
 				match version {
 					Ok(version) if version != previous_version => {
@@ -411,6 +490,61 @@ where
 		);
 	}
 
+	fn subscribe_runtime_upgrades(&self, pending: PendingSubscriptionSink) {
+		let initial = match self
+			.block_or_best(None)
+			.and_then(|block| runtime_upgrade_status::<BE, _, _>(&*self.client, block, None))
+			.map_err(|e| Error::Client(Box::new(e)))
+		{
+			Ok(initial) => initial,
+			Err(e) => {
+				spawn_subscription_task(&self.executor, pending.reject(e));
+				return
+			},
+		};
+
+		// The status of new blocks is computed once for all subscribers, by a blocking task that
+		// runs for as long as there are subscribers.
+		let (sender, receiver) = tracing_unbounded("mpsc_runtime_upgrades_subscriber", 100);
+		{
+			let mut subscribers = self.runtime_upgrade_subscribers.lock();
+			match &mut *subscribers {
+				Some(subscribers) => subscribers.push(sender),
+				None => {
+					*subscribers = Some(vec![sender]);
+					self.executor.spawn_blocking(
+						"substrate-rpc-runtime-upgrades",
+						Some("rpc"),
+						follow_runtime_upgrades::<BE, _, _>(
+							self.client.clone(),
+							self.finalized_only,
+							initial.clone(),
+							self.runtime_upgrade_subscribers.clone(),
+						)
+						.boxed(),
+					);
+				},
+			}
+		}
+
+		// A stream of changed runtime versions or pending upgrades
+		let mut previous = initial.clone();
+		let status_stream = receiver.filter(move |status| {
+			let changed = status.version != previous.version ||
+				status.pending_upgrade != previous.pending_upgrade;
+			if changed {
+				previous = status.clone();
+			}
+			future::ready(changed)
+		});
+
+		let stream = futures::stream::once(future::ready(initial)).chain(status_stream);
+		spawn_subscription_task(
+			&self.executor,
+			PendingSubscription::from(pending).pipe_from_stream(stream, BoundedVecDeque::default()),
+		);
+	}
+
 	fn subscribe_storage(
 		&self,
 		pending: PendingSubscriptionSink,
@@ -424,6 +558,10 @@ where
 			}
 		}
 
+		if self.finalized_only {
+			return self.subscribe_finalized_storage(pending, keys)
+		}
+
 		let stream = match self.client.storage_changes_notification_stream(keys.as_deref(), None) {
 			Ok(stream) => stream,
 			Err(blockchain_err) => {
@@ -647,3 +785,169 @@ fn invalid_block_range<B: BlockT>(
 fn invalid_block<B: BlockT>(from: B::Hash, to: Option<B::Hash>, details: String) -> Error {
 	Error::InvalidBlockRange { from: format!("{:?}", from), to: format!("{:?}", to), details }
 }
+
+/// Stream of the hashes of the new best blocks, or of the newly finalized blocks if
+/// `finalized_only` is set.
+fn new_heads<Block, Client>(
+	client: &Client,
+	finalized_only: bool,
+) -> BoxStream<'static, Block::Hash>
+where
+	Block: BlockT,
+	Client: BlockchainEvents<Block>,
+{
+	if finalized_only {
+		client.finality_notification_stream().map(|n| n.hash).boxed()
+	} else {
+		client
+			.import_notification_stream()
+			.filter(|n| future::ready(n.is_new_best))
+			.map(|n| n.hash)
+			.boxed()
+	}
+}
+
+/// Storage key of the storage value `item` of the pallet named `pallet`.
+fn storage_value_key(pallet: &str, item: &str) -> StorageKey {
+	let mut key = sp_crypto_hashing::twox_128(pallet.as_bytes()).to_vec();
+	key.extend(sp_crypto_hashing::twox_128(item.as_bytes()));
+	StorageKey(key)
+}
+
+/// Computes the runtime upgrade status of every new block and sends the changed ones to the
+/// `subscribers`, until there are none left.
+async fn follow_runtime_upgrades<BE, Block, Client>(
+	client: Arc<Client>,
+	finalized_only: bool,
+	mut previous: RuntimeUpgradeStatus<Block::Hash>,
+	subscribers: RuntimeUpgradeSubscribers<Block::Hash>,
+) where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: StorageProvider<Block, BE>
+		+ HeaderBackend<Block>
+		+ CallApiAt<Block>
+		+ BlockchainEvents<Block>,
+{
+	let mut heads = new_heads(&*client, finalized_only);
+	while let Some(hash) = heads.next().await {
+		let status =
+			runtime_upgrade_status::<BE, _, _>(&*client, hash, previous.pending_upgrade.as_ref())
+				.ok()
+				.filter(|status| {
+					status.version != previous.version ||
+						status.pending_upgrade != previous.pending_upgrade
+				});
+
+		{
+			let mut subscribers = subscribers.lock();
+			let senders = subscribers.get_or_insert_with(Vec::new);
+			match &status {
+				Some(status) =>
+					senders.retain(|sender| sender.unbounded_send(status.clone()).is_ok()),
+				None => senders.retain(|sender| !sender.is_closed()),
+			}
+			if senders.is_empty() {
+				*subscribers = None;
+				return
+			}
+		}
+
+		if let Some(status) = status {
+			previous = status;
+		}
+	}
+
+	*subscribers.lock() = None;
+}
+
+/// The runtime version at `block` and the runtime upgrade pending at it.
+///
+/// Pending upgrades are read from the storage of `System` and `ParachainSystem`, assuming the
+/// pallets use these names in the runtime. The estimated enactment of a parachain upgrade equal to
+/// the `previous` one is reused, instead of estimating it again.
+fn runtime_upgrade_status<BE, Block, Client>(
+	client: &Client,
+	block: Block::Hash,
+	previous: Option<&PendingUpgrade<Block::Hash>>,
+) -> ClientResult<RuntimeUpgradeStatus<Block::Hash>>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: StorageProvider<Block, BE> + HeaderBackend<Block> + CallApiAt<Block>,
+{
+	let version = client.runtime_version_at(block)?;
+
+	let pending_code = client
+		.storage(block, &storage_value_key("ParachainSystem", "PendingValidationCode"))?
+		.and_then(|code| Vec::<u8>::decode(&mut &code.0[..]).ok())
+		.filter(|code| !code.is_empty());
+	let pending_upgrade = if let Some(code) = pending_code {
+		let code_hash = HashingFor::<Block>::hash(&code);
+		match previous {
+			Some(previous)
+				if previous.kind == PendingUpgradeKind::ParachainValidationCode &&
+					previous.code_hash == code_hash =>
+				Some(previous.clone()),
+			_ => Some(PendingUpgrade {
+				kind: PendingUpgradeKind::ParachainValidationCode,
+				code_hash,
+				estimated_enactment: estimate_enactment::<BE, _, _>(client, block)?,
+			}),
+		}
+	} else {
+		client
+			.storage(block, &storage_value_key("System", "AuthorizedUpgrade"))?
+			.and_then(|authorization| <(Block::Hash, bool)>::decode(&mut &authorization.0[..]).ok())
+			.map(|(code_hash, _check_version)| PendingUpgrade {
+				kind: PendingUpgradeKind::Authorized,
+				code_hash,
+				estimated_enactment: None,
+			})
+	};
+
+	Ok(RuntimeUpgradeStatus { block, version, pending_upgrade })
+}
+
+/// Estimate the relay chain block the parachain validation code pending at `block` is enacted at.
+///
+/// The relay chain enacts the code `validation_upgrade_delay` blocks after the relay parent of
+/// the parachain block which scheduled it, which is searched by following the parents of `block`
+/// for as long as the same code is pending.
+fn estimate_enactment<BE, Block, Client>(
+	client: &Client,
+	block: Block::Hash,
+) -> ClientResult<Option<u32>>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: StorageProvider<Block, BE> + HeaderBackend<Block>,
+{
+	let code_key = storage_value_key("ParachainSystem", "PendingValidationCode");
+	let code = client.storage_hash(block, &code_key)?;
+
+	let mut scheduled_at = block;
+	for _ in 0..MAX_UPGRADE_LOOKBACK {
+		let Some(header) = client.header(scheduled_at)? else { break };
+		let parent = *header.parent_hash();
+		// The state of the parent may be pruned already.
+		match client.storage_hash(parent, &code_key) {
+			Ok(parent_code) if parent_code == code => scheduled_at = parent,
+			_ => break,
+		}
+	}
+
+	let relay_parent = client
+		.storage(scheduled_at, &storage_value_key("ParachainSystem", "LastRelayChainBlockNumber"))?
+		.and_then(|number| u32::decode(&mut &number.0[..]).ok());
+	// `validation_upgrade_delay` is preceded by eight `u32` fields in the abridged host
+	// configuration.
+	let delay = client
+		.storage(scheduled_at, &storage_value_key("ParachainSystem", "HostConfiguration"))?
+		.and_then(|config| <([u32; 8], u32)>::decode(&mut &config.0[..]).ok())
+		.map(|(_, delay)| delay);
+
+	Ok(relay_parent
+		.zip(delay)
+		.map(|(relay_parent, delay)| relay_parent.saturating_add(delay)))
+}
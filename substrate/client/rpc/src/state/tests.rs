@@ -53,7 +53,7 @@ async fn should_return_storage() {
 		.add_extra_storage(b":map:acc2".to_vec(), vec![1, 2, 3])
 		.build();
 	let genesis_hash = client.genesis_hash();
-	let (client, child) = new_full(Arc::new(client), test_executor());
+	let (client, child) = new_full(Arc::new(client), test_executor(), false);
 	let key = StorageKey(KEY.to_vec());
 	let ext = allow_unsafe();
 
@@ -106,7 +106,7 @@ async fn should_return_storage_entries() {
 		.add_extra_child_storage(&child_info, KEY2.to_vec(), CHILD_VALUE2.to_vec())
 		.build();
 	let genesis_hash = client.genesis_hash();
-	let (_client, child) = new_full(Arc::new(client), test_executor());
+	let (_client, child) = new_full(Arc::new(client), test_executor(), false);
 
 	let keys = &[StorageKey(KEY1.to_vec()), StorageKey(KEY2.to_vec())];
 	assert_eq!(
@@ -137,7 +137,7 @@ async fn should_return_child_storage() {
 			.build(),
 	);
 	let genesis_hash = client.genesis_hash();
-	let (_client, child) = new_full(client, test_executor());
+	let (_client, child) = new_full(client, test_executor(), false);
 	let child_key = prefixed_storage_key();
 	let key = StorageKey(b"key".to_vec());
 
@@ -168,7 +168,7 @@ async fn should_return_child_storage_entries() {
 			.build(),
 	);
 	let genesis_hash = client.genesis_hash();
-	let (_client, child) = new_full(client, test_executor());
+	let (_client, child) = new_full(client, test_executor(), false);
 	let child_key = prefixed_storage_key();
 	let keys = vec![StorageKey(b"key1".to_vec()), StorageKey(b"key2".to_vec())];
 
@@ -199,7 +199,7 @@ async fn should_return_child_storage_entries() {
 async fn should_call_contract() {
 	let client = Arc::new(substrate_test_runtime_client::new());
 	let genesis_hash = client.genesis_hash();
-	let (client, _child) = new_full(client, test_executor());
+	let (client, _child) = new_full(client, test_executor(), false);
 
 	assert_matches!(
 		client.call("balanceOf".into(), Bytes(vec![1, 2, 3]), Some(genesis_hash).into()),
@@ -211,7 +211,7 @@ async fn should_call_contract() {
 async fn should_notify_about_storage_changes() {
 	let mut sub = {
 		let client = Arc::new(substrate_test_runtime_client::new());
-		let (api, _child) = new_full(client.clone(), test_executor());
+		let (api, _child) = new_full(client.clone(), test_executor(), false);
 		let mut api_rpc = api.into_rpc();
 		api_rpc.extensions_mut().insert(DenyUnsafe::No);
 
@@ -250,7 +250,7 @@ async fn should_notify_about_storage_changes() {
 async fn should_send_initial_storage_changes_and_notifications() {
 	let mut sub = {
 		let client = Arc::new(substrate_test_runtime_client::new());
-		let (api, _child) = new_full(client.clone(), test_executor());
+		let (api, _child) = new_full(client.clone(), test_executor(), false);
 
 		let alice_balance_key = [
 			sp_crypto_hashing::twox_128(b"System"),
@@ -297,10 +297,76 @@ async fn should_send_initial_storage_changes_and_notifications() {
 	assert_matches!(timeout_secs(1, sub.next::<StorageChangeSet<H256>>()).await, Ok(Some(_)));
 }
 
+#[tokio::test]
+async fn should_notify_about_finalized_storage_changes_only_if_finalized_only() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let (api, _child) = new_full(client.clone(), test_executor(), true);
+
+	let alice_balance_key = [
+		sp_crypto_hashing::twox_128(b"System"),
+		sp_crypto_hashing::twox_128(b"Account"),
+		sp_crypto_hashing::blake2_128(&Sr25519Keyring::Alice.public()),
+	]
+	.concat()
+	.iter()
+	.chain(Sr25519Keyring::Alice.public().0.iter())
+	.cloned()
+	.collect::<Vec<u8>>();
+
+	let mut api_rpc = api.into_rpc();
+	api_rpc.extensions_mut().insert(DenyUnsafe::No);
+
+	// Subscribing to all storage changes is not supported.
+	assert!(api_rpc
+		.subscribe_unbounded("state_subscribeStorage", EmptyParams::new())
+		.await
+		.is_err());
+
+	let mut sub = api_rpc
+		.subscribe_unbounded("state_subscribeStorage", [[StorageKey(alice_balance_key.to_vec())]])
+		.await
+		.unwrap();
+	let (initial, _) = timeout_secs(1, sub.next::<StorageChangeSet<H256>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	assert_eq!(initial.block, client.genesis_hash());
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().best_hash)
+		.with_parent_block_number(client.chain_info().best_number)
+		.build()
+		.unwrap();
+	builder
+		.push_transfer(Transfer {
+			from: Sr25519Keyring::Alice.into(),
+			to: Sr25519Keyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	// The change is not notified before the block is finalized.
+	assert_matches!(timeout_secs(1, sub.next::<StorageChangeSet<H256>>()).await, Err(_));
+
+	client.finalize_block(block_hash, None).unwrap();
+	let (changes, _) = timeout_secs(1, sub.next::<StorageChangeSet<H256>>())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	assert_eq!(changes.block, block_hash);
+	assert_eq!(changes.changes.len(), 1);
+}
+
 #[tokio::test]
 async fn should_query_storage() {
 	async fn run_tests(client: Arc<TestClient>) {
-		let (api, _child) = new_full(client.clone(), test_executor());
+		let (api, _child) = new_full(client.clone(), test_executor(), false);
 
 		let add_block = |index| {
 			let mut builder = BlockBuilderBuilder::new(&*client)
@@ -468,7 +534,7 @@ async fn should_query_storage() {
 #[tokio::test]
 async fn should_return_runtime_version() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let (api, _child) = new_full(client.clone(), test_executor());
+	let (api, _child) = new_full(client.clone(), test_executor(), false);
 
 	// it is basically json-encoded substrate_test_runtime_client::runtime::VERSION
 	let result = "{\"specName\":\"test\",\"implName\":\"parity-test\",\"authoringVersion\":1,\
@@ -491,7 +557,7 @@ async fn should_return_runtime_version() {
 async fn should_notify_on_runtime_version_initially() {
 	let mut sub = {
 		let client = Arc::new(substrate_test_runtime_client::new());
-		let (api, _child) = new_full(client, test_executor());
+		let (api, _child) = new_full(client, test_executor(), false);
 		let mut api_rpc = api.into_rpc();
 		api_rpc.extensions_mut().insert(DenyUnsafe::No);
 
@@ -507,6 +573,43 @@ async fn should_notify_on_runtime_version_initially() {
 	assert_matches!(timeout_secs(10, sub.next::<RuntimeVersion>()).await, Ok(Some(_)));
 }
 
+#[tokio::test]
+async fn should_notify_on_runtime_upgrades_initially() {
+	use codec::Encode;
+	use sc_rpc_api::state::{PendingUpgrade, PendingUpgradeKind, RuntimeUpgradeStatus};
+
+	let code_hash = H256::repeat_byte(7);
+	let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+	key.extend(sp_crypto_hashing::twox_128(b"AuthorizedUpgrade"));
+	let client = Arc::new(
+		TestClientBuilder::new()
+			.add_extra_storage(key, (code_hash, true).encode())
+			.build(),
+	);
+	let genesis_hash = client.genesis_hash();
+	let (api, _child) = new_full(client, test_executor(), false);
+	let mut api_rpc = api.into_rpc();
+	api_rpc.extensions_mut().insert(DenyUnsafe::No);
+
+	let mut sub = api_rpc
+		.subscribe_unbounded("state_subscribeRuntimeUpgrades", EmptyParams::new())
+		.await
+		.unwrap();
+
+	let status: RuntimeUpgradeStatus<H256> =
+		timeout_secs(10, sub.next()).await.unwrap().unwrap().unwrap().0;
+	assert_eq!(status.block, genesis_hash);
+	assert_eq!(status.version.spec_version, 2);
+	assert_eq!(
+		status.pending_upgrade,
+		Some(PendingUpgrade {
+			kind: PendingUpgradeKind::Authorized,
+			code_hash,
+			estimated_enactment: None,
+		}),
+	);
+}
+
 #[test]
 fn should_deserialize_storage_key() {
 	let k = "\"0x7f864e18e3dd8b58386310d2fe0919eef27c6e558564b7f67f22d99d20f587b\"";
@@ -518,7 +621,7 @@ fn should_deserialize_storage_key() {
 #[tokio::test]
 async fn wildcard_storage_subscriptions_are_rpc_unsafe() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let (api, _child) = new_full(client, test_executor());
+	let (api, _child) = new_full(client, test_executor(), false);
 	let mut api_rpc = api.into_rpc();
 	api_rpc.extensions_mut().insert(DenyUnsafe::Yes);
 
@@ -529,7 +632,7 @@ async fn wildcard_storage_subscriptions_are_rpc_unsafe() {
 #[tokio::test]
 async fn concrete_storage_subscriptions_are_rpc_safe() {
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let (api, _child) = new_full(client, test_executor());
+	let (api, _child) = new_full(client, test_executor(), false);
 	let mut api_rpc = api.into_rpc();
 	api_rpc.extensions_mut().insert(DenyUnsafe::Yes);
 
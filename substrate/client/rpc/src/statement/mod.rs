@@ -25,10 +25,17 @@ use jsonrpsee::{
 };
 /// Re-export the API for backward compatibility.
 pub use sc_rpc_api::statement::{error::Error, StatementApiServer};
+use sc_rpc_api::statement::{StatementFilter, StatementPage};
 use sp_core::Bytes;
-use sp_statement_store::{StatementSource, SubmitResult};
+use sp_statement_store::{StatementSource, SubmitResult, TopicFilter};
 use std::sync::Arc;
 
+/// The number of statements returned by `statement_query` if no limit is given.
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// The maximum number of statements returned by `statement_query`.
+const MAX_PAGE_SIZE: u32 = 1000;
+
 /// Statement store API
 pub struct StatementStore {
 	store: Arc<dyn sp_statement_store::StatementStore>,
@@ -85,6 +92,31 @@ impl StatementApiServer for StatementStore {
 			.collect())
 	}
 
+	fn query(
+		&self,
+		ext: &Extensions,
+		filter: StatementFilter,
+		start_after: Option<[u8; 32]>,
+		limit: Option<u32>,
+	) -> RpcResult<StatementPage> {
+		// Without a topic the query pages through the whole store, just like `dump`.
+		if filter.match_all_topics.is_empty() && filter.match_any_topics.is_empty() {
+			sc_rpc_api::check_if_safe(ext)?;
+		}
+
+		let filter =
+			TopicFilter { match_all: filter.match_all_topics, match_any: filter.match_any_topics };
+		let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+		let page = self
+			.store
+			.query(&filter, start_after, limit as usize)
+			.map_err(|e| Error::StatementStore(e.to_string()))?;
+		Ok(StatementPage {
+			statements: page.statements.into_iter().map(|(_, s)| s.encode().into()).collect(),
+			next: page.next,
+		})
+	}
+
 	fn submit(&self, encoded: Bytes) -> RpcResult<()> {
 		let statement = Decode::decode(&mut &*encoded)
 			.map_err(|e| Error::StatementStore(format!("Error decoding statement: {:?}", e)))?;
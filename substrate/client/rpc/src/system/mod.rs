@@ -31,7 +31,9 @@ use sc_tracing::logging;
 use sc_utils::mpsc::TracingUnboundedSender;
 use sp_runtime::traits::{self, Header as HeaderT};
 
-pub use self::helpers::{Health, NodeRole, PeerInfo, SyncState, SystemInfo};
+pub use self::helpers::{
+	Health, NodeRole, PeerInfo, RuntimeLogTarget, SubsystemMemory, SyncState, SystemInfo,
+};
 pub use sc_rpc_api::system::*;
 
 /// System API implementation
@@ -187,4 +189,42 @@ impl<B: traits::Block> SystemApiServer<B::Hash, <B::Header as HeaderT>::Number>
 		check_if_safe(ext)?;
 		logging::reset_log_filter().map_err(|e| Error::Internal(e))
 	}
+
+	fn system_set_runtime_log_levels(
+		&self,
+		ext: &Extensions,
+		directives: String,
+	) -> Result<(), Error> {
+		check_if_safe(ext)?;
+		logging::set_runtime_log_levels(&directives).map_err(Error::Internal)
+	}
+
+	fn system_runtime_log_counters(
+		&self,
+		ext: &Extensions,
+	) -> Result<Vec<RuntimeLogTarget>, Error> {
+		check_if_safe(ext)?;
+		let counters = logging::runtime_log_counters().map_err(Error::Internal)?;
+		Ok(counters
+			.into_iter()
+			.map(|(target, counters)| RuntimeLogTarget {
+				target,
+				written: counters.written,
+				dropped: counters.dropped,
+			})
+			.collect())
+	}
+
+	fn system_memory_breakdown(&self, ext: &Extensions) -> Result<Vec<SubsystemMemory>, Error> {
+		check_if_safe(ext)?;
+		let breakdown = sc_memory_breakdown::breakdown()
+			.ok_or_else(|| Error::Internal("Memory breakdown is not enabled".into()))?;
+		Ok(breakdown
+			.into_iter()
+			.map(|(subsystem, bytes)| SubsystemMemory {
+				subsystem: subsystem.name().into(),
+				bytes: bytes as u64,
+			})
+			.collect())
+	}
 }
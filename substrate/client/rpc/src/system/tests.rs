@@ -337,6 +337,30 @@ async fn system_network_reserved_peers() {
 	assert_eq!(reserved_peers, vec!["QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string()],);
 }
 
+#[tokio::test]
+async fn system_runtime_log_rpcs_require_sink() {
+	assert_matches!(
+		api(None).call::<_, ()>("system_setRuntimeLogLevels", ["runtime=debug"]).await,
+		Err(RpcError::JsonRpc(err)) if err.message().contains("Runtime log sink is not enabled")
+	);
+	assert_matches!(
+		api(None)
+			.call::<_, Vec<RuntimeLogTarget>>("system_runtimeLogCounters", EmptyParams::new())
+			.await,
+		Err(RpcError::JsonRpc(err)) if err.message().contains("Runtime log sink is not enabled")
+	);
+}
+
+#[tokio::test]
+async fn system_memory_breakdown_requires_tracking_allocator() {
+	assert_matches!(
+		api(None)
+			.call::<_, Vec<SubsystemMemory>>("system_memoryBreakdown", EmptyParams::new())
+			.await,
+		Err(RpcError::JsonRpc(err)) if err.message().contains("Memory breakdown is not enabled")
+	);
+}
+
 #[test]
 fn test_add_reset_log_filter() {
 	const EXPECTED_BEFORE_ADD: &'static str = "EXPECTED_BEFORE_ADD";
@@ -27,8 +27,14 @@ use jsonrpsee::{
 	types::SubscriptionId, DisconnectError, PendingSubscriptionSink, SubscriptionMessage,
 	SubscriptionSink,
 };
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+use sp_crypto_hashing::blake2_256;
 use sp_runtime::Serialize;
-use std::collections::VecDeque;
+use std::{
+	collections::VecDeque,
+	time::{Duration, Instant},
+};
 
 const DEFAULT_BUF_SIZE: usize = 16;
 
@@ -285,6 +291,88 @@ pub fn spawn_subscription_task(
 	executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
 }
 
+/// The maximum length of an idempotency key, in bytes.
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 64;
+
+/// The default number of idempotency keys remembered by an [`IdempotencyCache`].
+const DEFAULT_IDEMPOTENCY_KEYS: u32 = 16 * 1024;
+
+/// How long an [`IdempotencyCache`] remembers a key by default.
+const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The outcome of looking up an idempotency key in an [`IdempotencyCache`].
+#[derive(Debug, PartialEq)]
+pub enum IdempotencyLookup<V> {
+	/// The key was not used before for the payload, the submission should proceed.
+	Unknown,
+	/// The key was used before for the same payload, with the given result.
+	Known(V),
+	/// The key is longer than [`MAX_IDEMPOTENCY_KEY_LEN`].
+	TooLong,
+}
+
+/// Remembers the results of submissions made with an idempotency key.
+///
+/// The results are keyed by the idempotency key and the hash of the submitted payload across all
+/// connections, so that a client retrying a submission after its connection dropped gets the
+/// original result instead of an error. Since the payload is part of the key, a client can only
+/// match its own submissions, and a key used for another payload starts a new submission. The
+/// keys expire after a TTL, and only the most recently used ones are remembered.
+pub struct IdempotencyCache<V> {
+	entries: Mutex<LruMap<(String, [u8; 32]), (Instant, V)>>,
+	ttl: Duration,
+}
+
+impl<V: Clone> Default for IdempotencyCache<V> {
+	fn default() -> Self {
+		Self::new(DEFAULT_IDEMPOTENCY_KEYS, DEFAULT_IDEMPOTENCY_TTL)
+	}
+}
+
+impl<V: Clone> IdempotencyCache<V> {
+	/// Create a new cache remembering up to `max_keys` keys, each for `ttl`.
+	pub fn new(max_keys: u32, ttl: Duration) -> Self {
+		Self { entries: Mutex::new(LruMap::new(ByLength::new(max_keys))), ttl }
+	}
+
+	/// Look up the result of an earlier submission of `payload` made with `key`.
+	pub fn lookup(&self, key: &str, payload: &[u8]) -> IdempotencyLookup<V> {
+		if key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+			return IdempotencyLookup::TooLong
+		}
+		let entry = (key.to_owned(), blake2_256(payload));
+		let mut entries = self.entries.lock();
+		let known = entries
+			.get(&entry)
+			.filter(|(at, _)| at.elapsed() < self.ttl)
+			.map(|(_, value)| value.clone());
+		match known {
+			Some(value) => IdempotencyLookup::Known(value),
+			None => {
+				// Forget the expired key, if any.
+				entries.remove(&entry);
+				IdempotencyLookup::Unknown
+			},
+		}
+	}
+
+	/// Remember the result of the submission of `payload` made with `key`.
+	///
+	/// Keys longer than [`MAX_IDEMPOTENCY_KEY_LEN`] are ignored.
+	pub fn insert(&self, key: String, payload: &[u8], value: V) {
+		if key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+			return
+		}
+		self.entries.lock().insert((key, blake2_256(payload)), (Instant::now(), value));
+	}
+
+	/// Forget the submission of `payload` made with `key`, e.g. once the operation it started has
+	/// ended.
+	pub fn remove(&self, key: &str, payload: &[u8]) {
+		self.entries.lock().remove(&(key.to_owned(), blake2_256(payload)));
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -402,4 +490,35 @@ mod tests {
 		// that's why 0 is included here.
 		assert_eq!(res, vec![0, 17, 18, 19]);
 	}
+
+	#[test]
+	fn idempotency_cache_works() {
+		let cache = IdempotencyCache::new(2, Duration::from_secs(60));
+		assert_eq!(cache.lookup("a", b"tx1"), IdempotencyLookup::Unknown);
+
+		cache.insert("a".into(), b"tx1", 1);
+		assert_eq!(cache.lookup("a", b"tx1"), IdempotencyLookup::Known(1));
+		// The key is bound to the payload.
+		assert_eq!(cache.lookup("a", b"tx2"), IdempotencyLookup::Unknown);
+
+		// The least recently used key is forgotten.
+		cache.insert("b".into(), b"tx2", 2);
+		cache.insert("c".into(), b"tx3", 3);
+		assert_eq!(cache.lookup("a", b"tx1"), IdempotencyLookup::Unknown);
+		assert_eq!(cache.lookup("c", b"tx3"), IdempotencyLookup::Known(3));
+
+		cache.remove("c", b"tx3");
+		assert_eq!(cache.lookup("c", b"tx3"), IdempotencyLookup::Unknown);
+
+		let long_key = "k".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1);
+		cache.insert(long_key.clone(), b"tx1", 1);
+		assert_eq!(cache.lookup(&long_key, b"tx1"), IdempotencyLookup::TooLong);
+	}
+
+	#[test]
+	fn idempotency_keys_expire() {
+		let cache = IdempotencyCache::new(2, Duration::ZERO);
+		cache.insert("a".into(), b"tx1", 1);
+		assert_eq!(cache.lookup("a", b"tx1"), IdempotencyLookup::Unknown);
+	}
 }
@@ -0,0 +1,151 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Caching of the runtime metadata, and of values derived from it, per runtime version.
+//!
+//! Decoding the metadata, and deriving schemas or decoders from it, is expensive, while the
+//! metadata only changes with the runtime. A [`RuntimeCache`] keeps the value of the most recently
+//! used runtime and only computes it again when the spec version changes.
+
+use codec::Decode;
+use frame_metadata::{v15::RuntimeMetadataV15, RuntimeMetadata, RuntimeMetadataPrefixed};
+use parking_lot::Mutex;
+use sp_api::{Metadata, ProvideRuntimeApi};
+use sp_runtime::traits::Block as BlockT;
+use std::{convert::Infallible, sync::Arc};
+
+/// The version of the metadata used by the clients of the runtime metadata.
+pub const METADATA_VERSION: u32 = 15;
+
+/// Fetch and decode the metadata of the runtime at `hash`.
+///
+/// Returns `None` if the runtime doesn't provide metadata V15.
+pub fn metadata_at<Block, Client>(
+	client: &Client,
+	hash: Block::Hash,
+) -> Result<Option<RuntimeMetadataV15>, String>
+where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: Metadata<Block>,
+{
+	let Some(metadata) = client
+		.runtime_api()
+		.metadata_at_version(hash, METADATA_VERSION)
+		.map_err(|e| e.to_string())?
+	else {
+		return Ok(None)
+	};
+	match RuntimeMetadataPrefixed::decode(&mut &metadata[..]).map_err(|e| e.to_string())? {
+		RuntimeMetadataPrefixed(_, RuntimeMetadata::V15(metadata)) => Ok(Some(metadata)),
+		_ => Err("Unsupported metadata version".into()),
+	}
+}
+
+/// A value derived from the metadata of the most recently used runtime.
+pub struct RuntimeCache<T>(Mutex<Option<(u32, T)>>);
+
+impl<T> Default for RuntimeCache<T> {
+	fn default() -> Self {
+		Self(Mutex::new(None))
+	}
+}
+
+impl<T: Clone> RuntimeCache<T> {
+	/// The value of the runtime whose spec version is `spec_version`.
+	///
+	/// The value is computed with `f` unless it was already computed for this runtime.
+	pub fn get_or_try_insert_with<E>(
+		&self,
+		spec_version: u32,
+		f: impl FnOnce() -> Result<T, E>,
+	) -> Result<T, E> {
+		let mut cached = self.0.lock();
+		if let Some((version, value)) = &*cached {
+			if *version == spec_version {
+				return Ok(value.clone())
+			}
+		}
+
+		let value = f()?;
+		*cached = Some((spec_version, value.clone()));
+		Ok(value)
+	}
+
+	/// The value of the runtime whose spec version is `spec_version`.
+	///
+	/// The value is computed with `f` unless it was already computed for this runtime.
+	pub fn get_or_insert_with(&self, spec_version: u32, f: impl FnOnce() -> T) -> T {
+		match self.get_or_try_insert_with(spec_version, || Ok::<_, Infallible>(f())) {
+			Ok(value) => value,
+			Err(never) => match never {},
+		}
+	}
+}
+
+/// The metadata of the most recently used runtime, decoded once per runtime version.
+pub type MetadataCache = RuntimeCache<Arc<RuntimeMetadataV15>>;
+
+impl MetadataCache {
+	/// The metadata of the runtime at `hash`, whose spec version is `spec_version`.
+	pub fn get<Block, Client>(
+		&self,
+		client: &Client,
+		hash: Block::Hash,
+		spec_version: u32,
+	) -> Result<Arc<RuntimeMetadataV15>, String>
+	where
+		Block: BlockT,
+		Client: ProvideRuntimeApi<Block>,
+		Client::Api: Metadata<Block>,
+	{
+		self.get_or_try_insert_with(spec_version, || {
+			metadata_at::<Block, _>(client, hash)?
+				.map(Arc::new)
+				.ok_or_else(|| "Runtime doesn't provide metadata V15".into())
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn values_are_computed_once_per_runtime_version() {
+		let cache = RuntimeCache::default();
+		let computed = std::cell::Cell::new(0);
+		let get = |spec_version| {
+			cache.get_or_try_insert_with(spec_version, || {
+				computed.set(computed.get() + 1);
+				Ok::<_, &str>(spec_version * 10)
+			})
+		};
+
+		assert_eq!(get(1), Ok(10));
+		assert_eq!(get(1), Ok(10));
+		assert_eq!(get(2), Ok(20));
+		assert_eq!(get(1), Ok(10));
+		assert_eq!(computed.get(), 3);
+
+		// Failures are not cached.
+		assert_eq!(cache.get_or_try_insert_with(3, || Err("failed")), Err("failed"));
+		assert_eq!(get(1), Ok(10));
+		assert_eq!(computed.get(), 3);
+	}
+}
@@ -0,0 +1,303 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of SCALE encoded values into JSON, driven by the type registry of the metadata.
+//!
+//! - Structs with named fields become objects, newtypes become their inner value and all other
+//!   composites and tuples become arrays.
+//! - Enum variants without fields become their name, other variants become an object with the
+//!   name of the variant as the only key.
+//! - Byte sequences and arrays become `0x` prefixed hex strings.
+//! - Integers which don't fit into 64 bits become decimal strings.
+//!
+//! Values nested deeper than [`MAX_DEPTH`] are rejected. Sequences must not declare more items
+//! than there are bytes left in the input, which also rules out sequences of zero-sized types
+//! longer than the rest of the input.
+
+use crate::MAX_DEPTH;
+use codec::{Compact, Decode, Error};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{Map, Value};
+
+/// Decode a value of type `ty` from `input`.
+pub fn decode_value(
+	registry: &PortableRegistry,
+	ty: u32,
+	input: &mut &[u8],
+) -> Result<Value, Error> {
+	decode_nested(registry, ty, input, 0)
+}
+
+fn decode_nested(
+	registry: &PortableRegistry,
+	ty: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, Error> {
+	if depth >= MAX_DEPTH {
+		return Err("Value is nested too deeply".into())
+	}
+	let depth = depth + 1;
+	let type_info = registry.resolve(ty).ok_or("Type not found in registry")?;
+
+	match &type_info.type_def {
+		TypeDef::Composite(composite) => decode_fields(registry, &composite.fields, input, depth),
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input)?;
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| variant.index == index)
+				.ok_or("Unknown variant index")?;
+			if variant.fields.is_empty() {
+				return Ok(Value::String(variant.name.clone()))
+			}
+			let fields = decode_fields(registry, &variant.fields, input, depth)?;
+			Ok(Value::Object(Map::from_iter([(variant.name.clone(), fields)])))
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input)?.0 as usize;
+			if len > input.len() {
+				return Err("Sequence is longer than the remaining input".into())
+			}
+			decode_items(registry, sequence.type_param.id, len, input, depth)
+		},
+		TypeDef::Array(array) =>
+			decode_items(registry, array.type_param.id, array.len as usize, input, depth),
+		TypeDef::Tuple(tuple) => tuple
+			.fields
+			.iter()
+			.map(|field| decode_nested(registry, field.id, input, depth))
+			.collect::<Result<_, _>>()
+			.map(Value::Array),
+		TypeDef::Primitive(primitive) => decode_primitive(primitive, input),
+		TypeDef::Compact(compact) => decode_compact(registry, compact.type_param.id, input, depth),
+		TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+	}
+}
+
+fn decode_fields(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, Error> {
+	match fields {
+		[field] if field.name.is_none() => decode_nested(registry, field.ty.id, input, depth),
+		_ if fields.iter().all(|field| field.name.is_some()) && !fields.is_empty() => {
+			let mut object = Map::new();
+			for field in fields {
+				let name = field.name.clone().expect("All fields are named; qed");
+				object.insert(name, decode_nested(registry, field.ty.id, input, depth)?);
+			}
+			Ok(Value::Object(object))
+		},
+		_ => fields
+			.iter()
+			.map(|field| decode_nested(registry, field.ty.id, input, depth))
+			.collect::<Result<_, _>>()
+			.map(Value::Array),
+	}
+}
+
+fn decode_items(
+	registry: &PortableRegistry,
+	ty: u32,
+	len: usize,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, Error> {
+	let is_byte = matches!(
+		registry.resolve(ty).map(|item| &item.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	);
+	if is_byte {
+		if input.len() < len {
+			return Err("Not enough data to fill byte array".into())
+		}
+		let (bytes, rest) = input.split_at(len);
+		*input = rest;
+		return Ok(Value::String(array_bytes::bytes2hex("0x", bytes)))
+	}
+
+	(0..len)
+		.map(|_| decode_nested(registry, ty, input, depth))
+		.collect::<Result<_, _>>()
+		.map(Value::Array)
+}
+
+fn number(value: u128) -> Value {
+	match u64::try_from(value) {
+		Ok(value) => value.into(),
+		Err(_) => value.to_string().into(),
+	}
+}
+
+fn signed_number(value: i128) -> Value {
+	match i64::try_from(value) {
+		Ok(value) => value.into(),
+		Err(_) => value.to_string().into(),
+	}
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, Error> {
+	Ok(match primitive {
+		TypeDefPrimitive::Bool => bool::decode(input)?.into(),
+		TypeDefPrimitive::Char => char::from_u32(u32::decode(input)?)
+			.ok_or("Invalid character")?
+			.to_string()
+			.into(),
+		TypeDefPrimitive::Str => String::decode(input)?.into(),
+		TypeDefPrimitive::U8 => u8::decode(input)?.into(),
+		TypeDefPrimitive::U16 => u16::decode(input)?.into(),
+		TypeDefPrimitive::U32 => u32::decode(input)?.into(),
+		TypeDefPrimitive::U64 => u64::decode(input)?.into(),
+		TypeDefPrimitive::U128 => number(u128::decode(input)?),
+		TypeDefPrimitive::I8 => i8::decode(input)?.into(),
+		TypeDefPrimitive::I16 => i16::decode(input)?.into(),
+		TypeDefPrimitive::I32 => i32::decode(input)?.into(),
+		TypeDefPrimitive::I64 => i64::decode(input)?.into(),
+		TypeDefPrimitive::I128 => signed_number(i128::decode(input)?),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			array_bytes::bytes2hex("0x", <[u8; 32]>::decode(input)?).into()
+		},
+	})
+}
+
+fn decode_compact(
+	registry: &PortableRegistry,
+	ty: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<Value, Error> {
+	if depth >= MAX_DEPTH {
+		return Err("Value is nested too deeply".into())
+	}
+	let type_info = registry.resolve(ty).ok_or("Type not found in registry")?;
+	match &type_info.type_def {
+		TypeDef::Primitive(_) => Ok(number(Compact::<u128>::decode(input)?.0)),
+		// Compact encoded wrappers, like `Perbill`, encode their single field as compact.
+		TypeDef::Composite(composite) => match &composite.fields[..] {
+			[] => Ok(Value::Null),
+			[field] => {
+				let value = decode_compact(registry, field.ty.id, input, depth + 1)?;
+				Ok(match &field.name {
+					Some(name) => Value::Object(Map::from_iter([(name.clone(), value)])),
+					None => value,
+				})
+			},
+			_ => Err("Compact encoding of composites with several fields".into()),
+		},
+		_ => Err("Unsupported compact encoded type".into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use scale_info::{meta_type, Registry, TypeInfo};
+	use serde_json::json;
+
+	#[derive(Encode, TypeInfo)]
+	enum Status {
+		Idle,
+		Busy { since: u32 },
+	}
+
+	#[derive(Encode, TypeInfo)]
+	struct Account {
+		id: [u8; 4],
+		balance: u128,
+		#[codec(compact)]
+		nonce: u64,
+		history: Vec<(u8, bool)>,
+		status: Status,
+		other: Status,
+	}
+
+	#[test]
+	fn decode_value_works() {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Account>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let account = Account {
+			id: [1, 2, 3, 4],
+			balance: u128::MAX,
+			nonce: 7,
+			history: vec![(1, true)],
+			status: Status::Idle,
+			other: Status::Busy { since: 10 },
+		};
+		let encoded = account.encode();
+
+		assert_eq!(
+			decode_value(&registry, ty, &mut &encoded[..]).unwrap(),
+			json!({
+				"id": "0x01020304",
+				"balance": u128::MAX.to_string(),
+				"nonce": 7,
+				"history": [[1, true]],
+				"status": "Idle",
+				"other": { "Busy": { "since": 10 } },
+			}),
+		);
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum Tree {
+		Leaf,
+		Node(Box<Tree>),
+	}
+
+	#[test]
+	fn decode_value_rejects_deep_nesting() {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Tree>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let nested = |depth| (0..depth).fold(Tree::Leaf, |tree, _| Tree::Node(Box::new(tree)));
+		let encoded = nested(MAX_DEPTH as usize - 1).encode();
+		assert!(decode_value(&registry, ty, &mut &encoded[..]).is_ok());
+
+		let encoded = nested(MAX_DEPTH as usize).encode();
+		assert_eq!(
+			decode_value(&registry, ty, &mut &encoded[..]),
+			Err("Value is nested too deeply".into()),
+		);
+	}
+
+	#[test]
+	fn decode_value_rejects_sequences_longer_than_input() {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Vec<()>>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let encoded = Compact(u32::MAX).encode();
+		assert_eq!(
+			decode_value(&registry, ty, &mut &encoded[..]),
+			Err("Sequence is longer than the remaining input".into()),
+		);
+		let encoded = vec![(); 2].encode();
+		assert_eq!(
+			decode_value(&registry, ty, &mut &encoded[..]),
+			Err("Sequence is longer than the remaining input".into()),
+		);
+	}
+}
@@ -0,0 +1,367 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Encoding of JSON values into SCALE, driven by the type registry of the metadata.
+//!
+//! Accepts the representation produced by [`decode_value`](crate::decode::decode_value):
+//!
+//! - Structs with named fields are objects, newtypes are their inner value and all other
+//!   composites and tuples are arrays.
+//! - Enum variants without fields are their name, other variants are an object with the name of
+//!   the variant as the only key.
+//! - Byte sequences and arrays are `0x` prefixed hex strings or arrays of numbers.
+//! - Integers are numbers or decimal strings.
+//!
+//! Values nested deeper than [`MAX_DEPTH`] are rejected.
+
+use crate::MAX_DEPTH;
+use codec::{Compact, Encode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::Value;
+
+/// Encode `value` as a value of type `ty`, appending it to `out`.
+pub fn encode_value(
+	registry: &PortableRegistry,
+	ty: u32,
+	value: &Value,
+	out: &mut Vec<u8>,
+) -> Result<(), String> {
+	encode_nested(registry, ty, value, out, 0)
+}
+
+fn encode_nested(
+	registry: &PortableRegistry,
+	ty: u32,
+	value: &Value,
+	out: &mut Vec<u8>,
+	depth: u32,
+) -> Result<(), String> {
+	if depth >= MAX_DEPTH {
+		return Err("Value is nested too deeply".into())
+	}
+	let depth = depth + 1;
+	let type_info = registry.resolve(ty).ok_or("Type not found in registry")?;
+
+	match &type_info.type_def {
+		TypeDef::Composite(composite) =>
+			encode_fields(registry, &composite.fields, value, out, depth),
+		TypeDef::Variant(variant) => {
+			let (name, fields) = match value {
+				Value::String(name) => (name, &Value::Null),
+				Value::Object(object) if object.len() == 1 =>
+					object.iter().next().expect("The object has one entry; qed"),
+				_ => return Err(format!("Expected an enum variant, found `{value}`")),
+			};
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| &variant.name == name)
+				.ok_or_else(|| format!("Unknown variant `{name}`"))?;
+			out.push(variant.index);
+			encode_fields(registry, &variant.fields, fields, out, depth)
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = match bytes(registry, sequence.type_param.id, value)? {
+				Some(bytes) => bytes.len(),
+				None => value.as_array().map_or(0, Vec::len),
+			};
+			Compact(len as u32).encode_to(out);
+			encode_items(registry, sequence.type_param.id, len, value, out, depth)
+		},
+		TypeDef::Array(array) =>
+			encode_items(registry, array.type_param.id, array.len as usize, value, out, depth),
+		TypeDef::Tuple(tuple) => {
+			let fields = tuple.fields.iter().map(|field| field.id).collect::<Vec<_>>();
+			encode_unnamed(registry, &fields, value, out, depth)
+		},
+		TypeDef::Primitive(primitive) => encode_primitive(primitive, value, out),
+		TypeDef::Compact(compact) =>
+			encode_compact(registry, compact.type_param.id, value, out, depth),
+		TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+	}
+}
+
+fn encode_fields(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	value: &Value,
+	out: &mut Vec<u8>,
+	depth: u32,
+) -> Result<(), String> {
+	match fields {
+		[field] if field.name.is_none() => encode_nested(registry, field.ty.id, value, out, depth),
+		_ if fields.iter().all(|field| field.name.is_some()) && !fields.is_empty() => {
+			let object = value
+				.as_object()
+				.ok_or_else(|| format!("Expected an object, found `{value}`"))?;
+			if let Some(key) = object
+				.keys()
+				.find(|key| !fields.iter().any(|field| field.name.as_ref() == Some(*key)))
+			{
+				return Err(format!("Unknown field `{key}`"))
+			}
+			for field in fields {
+				let name = field.name.as_ref().expect("All fields are named; qed");
+				let value = object.get(name).ok_or_else(|| format!("Missing field `{name}`"))?;
+				encode_nested(registry, field.ty.id, value, out, depth)?;
+			}
+			Ok(())
+		},
+		_ => {
+			let fields = fields.iter().map(|field| field.ty.id).collect::<Vec<_>>();
+			encode_unnamed(registry, &fields, value, out, depth)
+		},
+	}
+}
+
+/// Encode the values of a tuple or of a composite with unnamed fields.
+fn encode_unnamed(
+	registry: &PortableRegistry,
+	fields: &[u32],
+	value: &Value,
+	out: &mut Vec<u8>,
+	depth: u32,
+) -> Result<(), String> {
+	let items = match value {
+		Value::Array(items) => &items[..],
+		// Values without fields may be omitted.
+		Value::Null if fields.is_empty() => &[],
+		Value::Object(object) if fields.is_empty() && object.is_empty() => &[],
+		_ => return Err(format!("Expected an array, found `{value}`")),
+	};
+	if items.len() != fields.len() {
+		return Err(format!("Expected {} values, found `{value}`", fields.len()))
+	}
+	fields
+		.iter()
+		.zip(items)
+		.try_for_each(|(ty, item)| encode_nested(registry, *ty, item, out, depth))
+}
+
+/// The bytes of `value`, if it is a hex string and `ty` is `u8`.
+fn bytes(registry: &PortableRegistry, ty: u32, value: &Value) -> Result<Option<Vec<u8>>, String> {
+	let is_byte = matches!(
+		registry.resolve(ty).map(|item| &item.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	);
+	match value {
+		Value::String(hex) if is_byte => sp_core::bytes::from_hex(hex)
+			.map(Some)
+			.map_err(|e| format!("Invalid hex string `{hex}`: {e}")),
+		_ => Ok(None),
+	}
+}
+
+fn encode_items(
+	registry: &PortableRegistry,
+	ty: u32,
+	len: usize,
+	value: &Value,
+	out: &mut Vec<u8>,
+	depth: u32,
+) -> Result<(), String> {
+	if let Some(bytes) = bytes(registry, ty, value)? {
+		if bytes.len() != len {
+			return Err(format!("Expected {len} bytes, found `{value}`"))
+		}
+		out.extend(bytes);
+		return Ok(())
+	}
+
+	let items = value.as_array().ok_or_else(|| format!("Expected an array, found `{value}`"))?;
+	if items.len() != len {
+		return Err(format!("Expected {len} values, found `{value}`"))
+	}
+	items.iter().try_for_each(|item| encode_nested(registry, ty, item, out, depth))
+}
+
+fn unsigned<T: TryFrom<u128>>(value: &Value) -> Result<T, String> {
+	let number = match value {
+		Value::Number(number) => number.as_u64().map(Into::into),
+		Value::String(number) => number.parse::<u128>().ok(),
+		_ => None,
+	};
+	number
+		.and_then(|number| T::try_from(number).ok())
+		.ok_or_else(|| format!("Expected an unsigned integer, found `{value}`"))
+}
+
+fn signed<T: TryFrom<i128>>(value: &Value) -> Result<T, String> {
+	let number = match value {
+		Value::Number(number) => number.as_i64().map(Into::into),
+		Value::String(number) => number.parse::<i128>().ok(),
+		_ => None,
+	};
+	number
+		.and_then(|number| T::try_from(number).ok())
+		.ok_or_else(|| format!("Expected an integer, found `{value}`"))
+}
+
+fn encode_primitive(
+	primitive: &TypeDefPrimitive,
+	value: &Value,
+	out: &mut Vec<u8>,
+) -> Result<(), String> {
+	let string = || value.as_str().ok_or_else(|| format!("Expected a string, found `{value}`"));
+	match primitive {
+		TypeDefPrimitive::Bool => value
+			.as_bool()
+			.ok_or_else(|| format!("Expected a boolean, found `{value}`"))?
+			.encode_to(out),
+		TypeDefPrimitive::Char => {
+			let mut chars = string()?.chars();
+			match (chars.next(), chars.next()) {
+				(Some(char), None) => (char as u32).encode_to(out),
+				_ => return Err(format!("Expected a character, found `{value}`")),
+			}
+		},
+		TypeDefPrimitive::Str => string()?.encode_to(out),
+		TypeDefPrimitive::U8 => unsigned::<u8>(value)?.encode_to(out),
+		TypeDefPrimitive::U16 => unsigned::<u16>(value)?.encode_to(out),
+		TypeDefPrimitive::U32 => unsigned::<u32>(value)?.encode_to(out),
+		TypeDefPrimitive::U64 => unsigned::<u64>(value)?.encode_to(out),
+		TypeDefPrimitive::U128 => unsigned::<u128>(value)?.encode_to(out),
+		TypeDefPrimitive::I8 => signed::<i8>(value)?.encode_to(out),
+		TypeDefPrimitive::I16 => signed::<i16>(value)?.encode_to(out),
+		TypeDefPrimitive::I32 => signed::<i32>(value)?.encode_to(out),
+		TypeDefPrimitive::I64 => signed::<i64>(value)?.encode_to(out),
+		TypeDefPrimitive::I128 => signed::<i128>(value)?.encode_to(out),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			let bytes = sp_core::bytes::from_hex(string()?)
+				.map_err(|e| format!("Invalid hex string `{value}`: {e}"))?;
+			if bytes.len() != 32 {
+				return Err(format!("Expected 32 bytes, found `{value}`"))
+			}
+			out.extend(bytes);
+		},
+	}
+	Ok(())
+}
+
+fn encode_compact(
+	registry: &PortableRegistry,
+	ty: u32,
+	value: &Value,
+	out: &mut Vec<u8>,
+	depth: u32,
+) -> Result<(), String> {
+	if depth >= MAX_DEPTH {
+		return Err("Value is nested too deeply".into())
+	}
+	let type_info = registry.resolve(ty).ok_or("Type not found in registry")?;
+	match &type_info.type_def {
+		// The compact encoding only depends on the value, not on the width of the integer.
+		TypeDef::Primitive(_) => Compact(unsigned::<u128>(value)?).encode_to(out),
+		// Compact encoded wrappers, like `Perbill`, encode their single field as compact.
+		TypeDef::Composite(composite) => match &composite.fields[..] {
+			[] => {},
+			[field] => {
+				let value = match &field.name {
+					Some(name) =>
+						value.get(name).ok_or_else(|| format!("Missing field `{name}`"))?,
+					None => value,
+				};
+				encode_compact(registry, field.ty.id, value, out, depth + 1)?;
+			},
+			_ => return Err("Compact encoding of composites with several fields".into()),
+		},
+		_ => return Err("Unsupported compact encoded type".into()),
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::decode::decode_value;
+	use scale_info::{meta_type, Registry, TypeInfo};
+	use serde_json::json;
+
+	#[derive(Encode, TypeInfo)]
+	enum Status {
+		Idle,
+		Busy { since: u32 },
+	}
+
+	#[derive(Encode, TypeInfo)]
+	struct Account {
+		id: [u8; 4],
+		balance: u128,
+		#[codec(compact)]
+		nonce: u64,
+		history: Vec<(u8, bool)>,
+		status: Status,
+		other: Status,
+	}
+
+	#[test]
+	fn encode_value_works() {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Account>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let account = Account {
+			id: [1, 2, 3, 4],
+			balance: u128::MAX,
+			nonce: 7,
+			history: vec![(1, true)],
+			status: Status::Idle,
+			other: Status::Busy { since: 10 },
+		};
+		let value = json!({
+			"id": "0x01020304",
+			"balance": u128::MAX.to_string(),
+			"nonce": 7,
+			"history": [[1, true]],
+			"status": "Idle",
+			"other": { "Busy": { "since": 10 } },
+		});
+
+		let mut encoded = Vec::new();
+		encode_value(&registry, ty, &value, &mut encoded).unwrap();
+		assert_eq!(encoded, account.encode());
+		assert_eq!(decode_value(&registry, ty, &mut &encoded[..]).unwrap(), value);
+
+		let mut value = value;
+		value["extra"] = json!(1);
+		assert_eq!(
+			encode_value(&registry, ty, &value, &mut Vec::new()),
+			Err("Unknown field `extra`".into()),
+		);
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum Tree {
+		Leaf,
+		Node(Box<Tree>),
+	}
+
+	#[test]
+	fn encode_value_rejects_deep_nesting() {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Tree>()).id;
+		let registry: PortableRegistry = registry.into();
+
+		let nested = |depth| (0..depth).fold(json!("Leaf"), |tree, _| json!({ "Node": tree }));
+		assert!(encode_value(&registry, ty, &nested(MAX_DEPTH - 1), &mut Vec::new()).is_ok());
+		assert_eq!(
+			encode_value(&registry, ty, &nested(MAX_DEPTH), &mut Vec::new()),
+			Err("Value is nested too deeply".into()),
+		);
+	}
+}
@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversion of SCALE encoded values of the runtime to and from JSON.
+//!
+//! The conversion is driven by the type registry of the runtime metadata, so that values of any
+//! type known to the runtime can be read and written by clients without compiled-in types.
+//!
+//! Both directions reject values nested deeper than [`MAX_DEPTH`], and the decoder checks
+//! declared sequence lengths against the remaining input before decoding any item. Untrusted
+//! input, like the parameters of an RPC call, can therefore be converted safely.
+//!
+//! The metadata itself can be cached per runtime version with a [`MetadataCache`], see
+//! [`cache`].
+
+pub mod cache;
+pub mod decode;
+pub mod encode;
+
+pub use cache::{metadata_at, MetadataCache, RuntimeCache, METADATA_VERSION};
+
+/// The maximum nesting depth of the values converted by [`decode`] and [`encode`].
+pub const MAX_DEPTH: u32 = 128;
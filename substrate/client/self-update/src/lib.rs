@@ -0,0 +1,290 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Self-update of node binaries.
+//!
+//! An opt-in service for fleets of non-validator nodes, e.g. RPC nodes, which keeps the node
+//! binary up to date. It regularly polls a release feed, a URL serving the JSON of the latest
+//! [`Release`]. When the feed announces a version newer than the running one, the binary is
+//! downloaded and only installed if it is signed by the configured publisher for the target the
+//! node is built for. The verified binary is staged next to the running executable, and the node
+//! restarts into it in the next maintenance window, at a random instant of the window so the nodes
+//! of a fleet don't all restart at the same time.
+//!
+//! For the restart, the service stops the node like a failed essential task. Once the node has
+//! stopped, [`restart_if_updated`] must be called to replace the executable by the staged binary
+//! and start it with the same arguments.
+
+mod release;
+mod window;
+
+pub use release::{Release, TARGET};
+pub use window::MaintenanceWindow;
+
+use clap::Args;
+use sp_core::{crypto::Ss58Codec, sr25519, traits::SpawnEssentialNamed};
+use std::{
+	io,
+	path::PathBuf,
+	sync::OnceLock,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const LOG_TARGET: &str = "self-update";
+
+/// Timeout of requests to the release feed and of binary downloads.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// The maximum size of the release feed, in bytes.
+const MAX_FEED_SIZE: u64 = 64 * 1024;
+
+/// The maximum size of a downloaded binary, in bytes.
+const MAX_BINARY_SIZE: u64 = 512 * 1024 * 1024;
+
+/// The update to restart into, set when the updater requested the restart.
+static RESTART: OnceLock<StagedUpdate> = OnceLock::new();
+
+/// A verified binary, staged next to the executable it replaces.
+struct StagedUpdate {
+	staged: PathBuf,
+	executable: PathBuf,
+}
+
+/// Result type used in this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error type used in this crate.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("IO Error")]
+	IOError(#[from] io::Error),
+	#[error("HTTP Error: {0}")]
+	HttpError(#[from] reqwest::Error),
+	#[error("Invalid release feed: {0}")]
+	InvalidFeed(#[from] serde_json::Error),
+	#[error("Response of {0} is larger than {1} bytes")]
+	TooLarge(String, u64),
+	#[error("Invalid version `{0}`")]
+	InvalidVersion(String),
+	#[error("Release verification failed: {0}")]
+	VerificationFailed(String),
+	#[error("Self-update requires a release publisher")]
+	MissingPublisher,
+	#[error("Self-update is not available for authorities")]
+	Authority,
+}
+
+/// Parameters used to configure the self-update.
+#[derive(Default, Debug, Clone, Args)]
+pub struct SelfUpdateParams {
+	/// URL of the release feed the node binary is updated from.
+	///
+	/// Self-update is disabled if no feed is given. It is not available for validators.
+	#[arg(long, value_name = "URL")]
+	pub self_update_feed: Option<String>,
+
+	/// Public key of the release publisher, as SS58 address or hex.
+	///
+	/// Releases of the feed that aren't signed by the publisher are rejected.
+	#[arg(long, value_name = "PUBLIC_KEY", value_parser = parse_publisher)]
+	pub self_update_publisher: Option<sr25519::Public>,
+
+	/// Daily maintenance window in UTC during which the node restarts into an installed update,
+	/// given as `HH:MM-HH:MM`.
+	///
+	/// By default, the node restarts as soon as the update is installed.
+	#[arg(long, value_name = "HH:MM-HH:MM")]
+	pub self_update_window: Option<MaintenanceWindow>,
+
+	/// How often the release feed is polled.
+	#[arg(long, value_name = "SECONDS", default_value_t = 3600, value_parser = clap::value_parser!(u32).range(60..))]
+	pub self_update_interval: u32,
+}
+
+fn parse_publisher(s: &str) -> std::result::Result<sr25519::Public, String> {
+	if let Some(hex) = s.strip_prefix("0x") {
+		let bytes =
+			array_bytes::hex2array(hex).map_err(|e| format!("Invalid public key: {e:?}"))?;
+		Ok(sr25519::Public::from_raw(bytes))
+	} else {
+		sr25519::Public::from_ss58check(s).map_err(|e| format!("Invalid public key: {e}"))
+	}
+}
+
+/// Replace the executable by the staged update and start it in place of the current process, if
+/// the [`SelfUpdater`] requested a restart.
+///
+/// Must be called once the node has stopped. Returns immediately if no restart was requested.
+pub fn restart_if_updated() -> io::Result<()> {
+	let Some(StagedUpdate { staged, executable }) = RESTART.get() else { return Ok(()) };
+	log::info!(target: LOG_TARGET, "Restarting into the update of {}", executable.display());
+
+	// The rename replaces the executable atomically.
+	std::fs::rename(staged, executable)?;
+
+	let mut command = std::process::Command::new(executable);
+	command.args(std::env::args_os().skip(1));
+	#[cfg(unix)]
+	{
+		use std::os::unix::process::CommandExt;
+		Err(command.exec())
+	}
+	#[cfg(not(unix))]
+	{
+		command.spawn().map(|_| ())
+	}
+}
+
+/// Self-update service: installs the releases of the release feed and restarts the node.
+pub struct SelfUpdater {
+	feed: String,
+	publisher: sr25519::Public,
+	window: Option<MaintenanceWindow>,
+	polling_period: Duration,
+	/// The version of the running binary.
+	version: Vec<u64>,
+	/// The executable of the running binary, replaced by updates when restarting.
+	executable: PathBuf,
+	client: reqwest::Client,
+}
+
+impl SelfUpdater {
+	/// Spawns the self-update of a node running version `impl_version`, if a feed is configured.
+	pub fn try_spawn(
+		parameters: SelfUpdateParams,
+		impl_version: &str,
+		is_authority: bool,
+		spawner: &impl SpawnEssentialNamed,
+	) -> Result<()> {
+		let Some(feed) = parameters.self_update_feed else { return Ok(()) };
+		if is_authority {
+			return Err(Error::Authority)
+		}
+		let publisher = parameters.self_update_publisher.ok_or(Error::MissingPublisher)?;
+		let version = release::parse_version(impl_version)
+			.ok_or_else(|| Error::InvalidVersion(impl_version.into()))?;
+		let executable = std::env::current_exe()?.canonicalize()?;
+		let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+
+		log::info!(
+			target: LOG_TARGET,
+			"Self-update of {} enabled, release feed: {feed}",
+			executable.display(),
+		);
+		let self_updater = SelfUpdater {
+			feed,
+			publisher,
+			window: parameters.self_update_window,
+			polling_period: Duration::from_secs(parameters.self_update_interval.into()),
+			version,
+			executable,
+			client,
+		};
+		spawner.spawn_essential("self-update", None, Box::pin(self_updater.run()));
+
+		Ok(())
+	}
+
+	/// Main loop, intended to be spawned as essential task. Quits to stop the node once an update
+	/// was installed and the restart is due.
+	async fn run(self) {
+		loop {
+			match self.update().await {
+				Ok(Some(release)) => {
+					log::info!(
+						target: LOG_TARGET,
+						"Staged version {}, restarting into it in the maintenance window",
+						release.version,
+					);
+					break
+				},
+				Ok(None) => {},
+				Err(e) => log::warn!(target: LOG_TARGET, "Self-update failed: {e}"),
+			}
+			tokio::time::sleep(self.polling_period).await;
+		}
+
+		if let Some(window) = self.window {
+			let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+			let delay = window.delay((now % (24 * 60 * 60)) as u32, rand::random());
+			tokio::time::sleep(delay).await;
+		}
+		let staged = self.staged();
+		let _ = RESTART.set(StagedUpdate { staged, executable: self.executable });
+		log::info!(target: LOG_TARGET, "Stopping the node to restart into the update");
+	}
+
+	/// Installs the release of the feed if it's newer than the running version.
+	async fn update(&self) -> Result<Option<Release>> {
+		let feed = self.download(&self.feed, MAX_FEED_SIZE).await?;
+		let release: Release = serde_json::from_slice(&feed)?;
+		let version = release::parse_version(&release.version)
+			.ok_or_else(|| Error::InvalidVersion(release.version.clone()))?;
+		if version <= self.version {
+			log::debug!(target: LOG_TARGET, "No update, latest release is {}", release.version);
+			return Ok(None)
+		}
+
+		log::info!(
+			target: LOG_TARGET,
+			"Downloading version {} from {}",
+			release.version,
+			release.url,
+		);
+		let binary = self.download(&release.url, MAX_BINARY_SIZE).await?;
+		release.verify(&binary, &self.publisher).map_err(Error::VerificationFailed)?;
+		self.install(&binary).await?;
+		Ok(Some(release))
+	}
+
+	/// Downloads `url`, failing as soon as the response is larger than `max_size` bytes.
+	async fn download(&self, url: &str, max_size: u64) -> Result<Vec<u8>> {
+		let too_large = || Error::TooLarge(url.into(), max_size);
+		let mut response = self.client.get(url).send().await?.error_for_status()?;
+		if response.content_length().is_some_and(|len| len > max_size) {
+			return Err(too_large())
+		}
+
+		let mut body = Vec::new();
+		while let Some(chunk) = response.chunk().await? {
+			if (body.len() + chunk.len()) as u64 > max_size {
+				return Err(too_large())
+			}
+			body.extend_from_slice(&chunk);
+		}
+		Ok(body)
+	}
+
+	/// The path the update is staged at until the restart, next to the executable.
+	fn staged(&self) -> PathBuf {
+		let mut staged = self.executable.clone().into_os_string();
+		staged.push(".update");
+		PathBuf::from(staged)
+	}
+
+	/// Stages `binary` next to the executable, with the permissions of the executable.
+	///
+	/// The executable is only replaced by [`restart_if_updated`], in the maintenance window.
+	async fn install(&self, binary: &[u8]) -> Result<()> {
+		let staged = self.staged();
+		tokio::fs::write(&staged, binary).await?;
+		let permissions = tokio::fs::metadata(&self.executable).await?.permissions();
+		tokio::fs::set_permissions(&staged, permissions).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,130 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Releases announced by the release feed.
+
+use codec::Encode;
+use serde::{Deserialize, Serialize};
+use sp_core::{sr25519, Pair, H256};
+
+/// The target triple the node is built for, e.g. `x86_64-unknown-linux-gnu`.
+pub const TARGET: &str = env!("SELF_UPDATE_TARGET");
+
+/// Context of the release signatures, so they can't be mistaken for other signatures of the
+/// publisher.
+const SIGNING_CONTEXT: &[u8; 21] = b"substrate-self-update";
+
+/// A release of the node binary, as published by the release feed.
+///
+/// The binary is only accepted if its BLAKE2-256 hash is `hash` and `signature` is the signature
+/// by the publisher of the SCALE encoded `(b"substrate-self-update", target, version, hash)`,
+/// where `target` is the [`TARGET`] of the node. So an old binary can't be announced as a new
+/// version, and the binary of a target can't be installed on another one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Release {
+	/// The version of the released binary, e.g. `1.2.0`.
+	pub version: String,
+	/// The URL the binary is downloaded from.
+	pub url: String,
+	/// The BLAKE2-256 hash of the binary.
+	pub hash: H256,
+	/// The signature of the release by the publisher.
+	pub signature: sr25519::Signature,
+}
+
+impl Release {
+	/// Release `binary` for `target` as `version`, hosted at `url` and signed by `publisher`.
+	pub fn new(
+		target: &str,
+		version: String,
+		url: String,
+		binary: &[u8],
+		publisher: &sr25519::Pair,
+	) -> Self {
+		let hash = H256(sp_crypto_hashing::blake2_256(binary));
+		let signature = publisher.sign(&signed_payload(target, &version, hash));
+		Self { version, url, hash, signature }
+	}
+
+	/// Check that `binary` is the released binary for [`TARGET`], signed by `publisher`.
+	pub fn verify(&self, binary: &[u8], publisher: &sr25519::Public) -> Result<(), String> {
+		let hash = H256(sp_crypto_hashing::blake2_256(binary));
+		if hash != self.hash {
+			return Err(format!("Binary has hash {hash:?}, expected {:?}", self.hash))
+		}
+		let payload = signed_payload(TARGET, &self.version, self.hash);
+		if !sr25519::Pair::verify(&self.signature, payload, publisher) {
+			return Err(format!(
+				"Invalid signature of release {} for {TARGET} by {publisher}",
+				self.version,
+			))
+		}
+		Ok(())
+	}
+}
+
+/// The payload signed by the publisher for the release of `version` for `target`.
+fn signed_payload(target: &str, version: &str, hash: H256) -> Vec<u8> {
+	(SIGNING_CONTEXT, target, version, hash).encode()
+}
+
+/// The numeric components of `version`, ignoring any pre-release or build suffix.
+///
+/// For example, `1.2.0-dev-a1b2c3` is parsed as `[1, 2, 0]`.
+pub(crate) fn parse_version(version: &str) -> Option<Vec<u64>> {
+	version
+		.split(['-', '+'])
+		.next()?
+		.split('.')
+		.map(|component| component.parse().ok())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn release_is_verified() {
+		let publisher = sr25519::Pair::from_seed(&[1; 32]);
+		let binary = b"node binary".to_vec();
+		let url = "https://example.com/node".to_string();
+		let release = Release::new(TARGET, "1.2.0".into(), url.clone(), &binary, &publisher);
+		assert_eq!(release.verify(&binary, &publisher.public()), Ok(()));
+		assert!(release.verify(b"other binary", &publisher.public()).is_err());
+
+		let other = sr25519::Pair::from_seed(&[2; 32]);
+		assert!(release.verify(&binary, &other.public()).is_err());
+
+		let relabeled = Release { version: "1.3.0".into(), ..release };
+		assert!(relabeled.verify(&binary, &publisher.public()).is_err());
+
+		let other_target = Release::new("other-target", "1.2.0".into(), url, &binary, &publisher);
+		assert!(other_target.verify(&binary, &publisher.public()).is_err());
+	}
+
+	#[test]
+	fn versions_are_parsed() {
+		assert_eq!(parse_version("1.2.0"), Some(vec![1, 2, 0]));
+		assert_eq!(parse_version("1.2.0-dev-a1b2c3"), Some(vec![1, 2, 0]));
+		assert_eq!(parse_version("3.0.0+linux"), Some(vec![3, 0, 0]));
+		assert_eq!(parse_version("latest"), None);
+		assert!(parse_version("1.10.0") > parse_version("1.9.3"));
+	}
+}
@@ -0,0 +1,99 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Daily maintenance windows.
+
+use std::{str::FromStr, time::Duration};
+
+const DAY: u32 = 24 * 60 * 60;
+
+/// A daily time window in UTC, given as `HH:MM-HH:MM`.
+///
+/// The window may wrap around midnight, e.g. `23:00-01:00`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaintenanceWindow {
+	/// Start of the window, in seconds since midnight.
+	start: u32,
+	/// Length of the window, in seconds.
+	length: u32,
+}
+
+impl MaintenanceWindow {
+	/// The delay from `now` until an instant of the current window, or of the next one if the
+	/// window isn't open.
+	///
+	/// `now` is the number of seconds since midnight. The instant is at the fraction `position`
+	/// of the remaining window, which is chosen randomly so the nodes of a fleet don't all restart
+	/// at the same time.
+	pub(crate) fn delay(&self, now: u32, position: f64) -> Duration {
+		let elapsed = (now % DAY + DAY - self.start) % DAY;
+		let (wait, remaining) = if elapsed < self.length {
+			(0, self.length - elapsed)
+		} else {
+			(DAY - elapsed, self.length)
+		};
+		Duration::from_secs(u64::from(wait) + (f64::from(remaining) * position) as u64)
+	}
+}
+
+impl FromStr for MaintenanceWindow {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let parse_time = |time: &str| -> Option<u32> {
+			let (hours, minutes) = time.split_once(':')?;
+			let (hours, minutes) = (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?);
+			(hours < 24 && minutes < 60).then_some(hours * 3600 + minutes * 60)
+		};
+		let invalid = || format!("Invalid maintenance window `{s}`, expected `HH:MM-HH:MM`");
+		let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+		let start = parse_time(start).ok_or_else(invalid)?;
+		let end = parse_time(end).ok_or_else(invalid)?;
+		if start == end {
+			return Err(format!("Maintenance window `{s}` is empty"))
+		}
+		Ok(Self { start, length: (end + DAY - start) % DAY })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn window_delay_works() {
+		let window = MaintenanceWindow::from_str("02:00-04:00").unwrap();
+		assert_eq!(window, MaintenanceWindow { start: 2 * 3600, length: 2 * 3600 });
+
+		// Before and after the window, the restart waits for the next one.
+		assert_eq!(window.delay(3600, 0.0), Duration::from_secs(3600));
+		assert_eq!(window.delay(3600, 0.5), Duration::from_secs(2 * 3600));
+		assert_eq!(window.delay(5 * 3600, 0.0), Duration::from_secs(21 * 3600));
+		// In the window, the restart happens in its remainder.
+		assert_eq!(window.delay(3 * 3600, 0.0), Duration::ZERO);
+		assert_eq!(window.delay(3 * 3600, 0.5), Duration::from_secs(1800));
+
+		let wrapping = MaintenanceWindow::from_str("23:00-01:00").unwrap();
+		assert_eq!(wrapping.delay(1800, 0.0), Duration::ZERO);
+		assert_eq!(wrapping.delay(22 * 3600, 0.0), Duration::from_secs(3600));
+
+		assert!(MaintenanceWindow::from_str("02:00-02:00").is_err());
+		assert!(MaintenanceWindow::from_str("24:00-02:00").is_err());
+		assert!(MaintenanceWindow::from_str("02:00").is_err());
+	}
+}
@@ -0,0 +1,282 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics about how full the blocks of the best chain are, per dispatch class.
+//!
+//! For every new best block the `System::BlockWeight` storage item is read and compared with the
+//! limits of the `System::BlockWeights` constant of the runtime metadata. Runtimes which are not
+//! based on FRAME don't have these items, in which case no metrics are reported.
+
+use codec::Decode;
+use futures::StreamExt;
+use log::{debug, warn};
+use prometheus_endpoint::{register, GaugeVec, Opts, PrometheusError, Registry, F64, U64};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_runtime_metadata::RuntimeCache;
+use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
+use sp_runtime::traits::Block as BlockT;
+use sp_storage::StorageKey;
+use sp_weights::Weight;
+use std::{collections::VecDeque, marker::PhantomData, sync::Arc};
+
+const LOG_TARGET: &str = "block-fullness";
+
+/// The number of blocks the rolling averages are computed over.
+const AVERAGE_WINDOW: usize = 100;
+
+/// The dispatch classes, in the order in which they are encoded.
+const CLASSES: [&str; 3] = ["normal", "operational", "mandatory"];
+
+/// The weight limits of a dispatch class, as encoded by `frame_system::limits::WeightsPerClass`.
+#[derive(Decode)]
+struct WeightsPerClass {
+	_base_extrinsic: Weight,
+	_max_extrinsic: Option<Weight>,
+	max_total: Option<Weight>,
+	_reserved: Option<Weight>,
+}
+
+/// The block weight limits, as encoded by `frame_system::limits::BlockWeights`.
+#[derive(Decode)]
+struct BlockWeights {
+	_base_block: Weight,
+	max_block: Weight,
+	per_class: [WeightsPerClass; 3],
+}
+
+impl BlockWeights {
+	/// The maximum weight which may be consumed by each class.
+	fn max_per_class(&self) -> [Weight; 3] {
+		self.per_class.each_ref().map(|class| class.max_total.unwrap_or(self.max_block))
+	}
+}
+
+/// The share of `max` which is used by `used`, for both weight dimensions.
+fn utilization(used: Weight, max: Weight) -> [f64; 2] {
+	let ratio = |used: u64, max: u64| if max == 0 { 0.0 } else { used as f64 / max as f64 };
+	[ratio(used.ref_time(), max.ref_time()), ratio(used.proof_size(), max.proof_size())]
+}
+
+/// Storage key of `System::BlockWeight`.
+fn block_weight_key() -> StorageKey {
+	let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+	key.extend(sp_crypto_hashing::twox_128(b"BlockWeight"));
+	StorageKey(key)
+}
+
+/// Block fullness metrics.
+#[derive(Clone)]
+struct Metrics {
+	weight: GaugeVec<U64>,
+	utilization: GaugeVec<F64>,
+	average_utilization: GaugeVec<F64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			weight: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_block_weight",
+						"Weight consumed by the best block, per dispatch class",
+					),
+					&["class", "resource"],
+				)?,
+				registry,
+			)?,
+			utilization: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_block_weight_utilization_ratio",
+						"Share of the maximum weight of a dispatch class consumed by the best block",
+					),
+					&["class", "resource"],
+				)?,
+				registry,
+			)?,
+			average_utilization: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_block_weight_utilization_average_ratio",
+						"Share of the maximum weight of a dispatch class consumed on average by the \
+						 last 100 best blocks",
+					),
+					&["class", "resource"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Reports the fullness of every new best block to Prometheus.
+pub(crate) struct BlockFullnessMetrics<Block, BE, Client> {
+	client: Arc<Client>,
+	metrics: Metrics,
+	/// The weight limits of the last seen runtime, `None` if they are unknown.
+	limits: RuntimeCache<Option<[Weight; 3]>>,
+	/// The utilizations of the most recent blocks, newest last.
+	history: VecDeque<[[f64; 2]; 3]>,
+	_phantom: PhantomData<(Block, BE)>,
+}
+
+impl<Block, BE, Client> BlockFullnessMetrics<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>,
+	Client::Api: Metadata<Block>,
+{
+	/// Creates the block fullness metrics and registers them in `registry`.
+	pub(crate) fn new(client: Arc<Client>, registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			client,
+			metrics: Metrics::register(registry)?,
+			limits: RuntimeCache::default(),
+			history: VecDeque::with_capacity(AVERAGE_WINDOW),
+			_phantom: PhantomData,
+		})
+	}
+
+	/// Returns a never-ending `Future` that updates the metrics on every new best block.
+	pub(crate) async fn run(mut self) {
+		let mut imports = self.client.import_notification_stream();
+		while let Some(notification) = imports.next().await {
+			if notification.is_new_best {
+				self.update(notification.hash);
+			}
+		}
+	}
+
+	/// Update the metrics with the weight consumed by `hash`.
+	fn update(&mut self, hash: Block::Hash) {
+		let weights = match self.client.storage(hash, &block_weight_key()) {
+			Ok(Some(data)) => match <[Weight; 3]>::decode(&mut &data.0[..]) {
+				Ok(weights) => weights,
+				Err(err) => {
+					debug!(target: LOG_TARGET, "Failed to decode block weight at {hash:?}: {err}");
+					return
+				},
+			},
+			Ok(None) => return,
+			Err(err) => {
+				debug!(target: LOG_TARGET, "Failed to read block weight at {hash:?}: {err}");
+				return
+			},
+		};
+		let Some(limits) = self.limits(hash) else { return };
+
+		let mut utilizations = [[0.0; 2]; 3];
+		for (index, class) in CLASSES.into_iter().enumerate() {
+			let used = weights[index];
+			utilizations[index] = utilization(used, limits[index]);
+
+			let weight = &self.metrics.weight;
+			weight.with_label_values(&[class, "ref_time"]).set(used.ref_time());
+			weight.with_label_values(&[class, "proof_size"]).set(used.proof_size());
+
+			let utilization = &self.metrics.utilization;
+			utilization.with_label_values(&[class, "ref_time"]).set(utilizations[index][0]);
+			utilization
+				.with_label_values(&[class, "proof_size"])
+				.set(utilizations[index][1]);
+		}
+
+		if self.history.len() == AVERAGE_WINDOW {
+			self.history.pop_front();
+		}
+		self.history.push_back(utilizations);
+
+		let len = self.history.len() as f64;
+		for (index, class) in CLASSES.into_iter().enumerate() {
+			for (resource_index, resource) in ["ref_time", "proof_size"].into_iter().enumerate() {
+				let sum: f64 = self.history.iter().map(|block| block[index][resource_index]).sum();
+				self.metrics
+					.average_utilization
+					.with_label_values(&[class, resource])
+					.set(sum / len);
+			}
+		}
+	}
+
+	/// The maximum weight of each dispatch class of the runtime at `hash`.
+	fn limits(&self, hash: Block::Hash) -> Option<[Weight; 3]> {
+		let spec_version = self.client.runtime_version_at(hash).ok()?.spec_version;
+		self.limits.get_or_insert_with(spec_version, || {
+			let limits = self.fetch_limits(hash);
+			if limits.is_none() {
+				warn!(
+					target: LOG_TARGET,
+					"`System::BlockWeights` not found in the metadata of runtime version \
+					 {spec_version}, block fullness is not reported",
+				);
+			}
+			limits
+		})
+	}
+
+	fn fetch_limits(&self, hash: Block::Hash) -> Option<[Weight; 3]> {
+		let metadata = sc_runtime_metadata::metadata_at::<Block, _>(&*self.client, hash).ok()??;
+		let constant = metadata
+			.pallets
+			.iter()
+			.find(|pallet| pallet.name == "System")?
+			.constants
+			.iter()
+			.find(|constant| constant.name == "BlockWeights")?;
+		let weights = BlockWeights::decode(&mut &constant.value[..]).ok()?;
+		Some(weights.max_per_class())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+
+	#[test]
+	fn block_weights_decode_from_frame_system_layout() {
+		let max_block = Weight::from_parts(2_000, 5_000);
+		let normal = Weight::from_parts(1_500, 3_750);
+		let encoded = (
+			Weight::from_parts(10, 0),
+			max_block,
+			// Normal class.
+			(Weight::from_parts(1, 0), Some(normal), Some(normal), None::<Weight>),
+			// Operational class.
+			(Weight::from_parts(1, 0), Some(max_block), Some(max_block), Some(normal)),
+			// Mandatory class.
+			(Weight::from_parts(1, 0), None::<Weight>, None::<Weight>, None::<Weight>),
+		)
+			.encode();
+
+		let weights = BlockWeights::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(weights.max_per_class(), [normal, max_block, max_block]);
+	}
+
+	#[test]
+	fn utilization_works() {
+		let max = Weight::from_parts(2_000, 0);
+		assert_eq!(utilization(Weight::from_parts(500, 10), max), [0.25, 0.0]);
+		assert_eq!(utilization(Weight::from_parts(2_000, 0), max), [1.0, 0.0]);
+	}
+}
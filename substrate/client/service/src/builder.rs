@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	build_network_future, build_system_rpc_future,
+	block_fullness::BlockFullnessMetrics, build_network_future, build_system_rpc_future,
 	client::{Client, ClientConfig},
 	config::{Configuration, ExecutorConfiguration, KeystoreConfig, Multiaddr, PrometheusConfig},
 	error::Error,
@@ -25,9 +25,9 @@ use crate::{
 	start_rpc_servers, BuildGenesisBlock, GenesisBlockBuilder, RpcHandlers, SpawnTaskHandle,
 	TaskManager, TransactionPoolAdapter,
 };
-use futures::{select, FutureExt, StreamExt};
+use futures::{channel::oneshot, select, FutureExt, StreamExt};
 use jsonrpsee::RpcModule;
-use log::info;
+use log::{error, info};
 use prometheus_endpoint::Registry;
 use sc_chain_spec::{get_extension, ChainSpec};
 use sc_client_api::{
@@ -35,12 +35,19 @@ use sc_client_api::{
 	BlockBackend, BlockchainEvents, ExecutorProvider, ForkBlocks, StorageProvider, UsageProvider,
 };
 use sc_client_db::{Backend, BlocksPruning, DatabaseSettings, PruningMode};
-use sc_consensus::import_queue::{ImportQueue, ImportQueueService};
+use sc_consensus::{
+	block_import::{ImportVeto, VetoBlockImport},
+	import_queue::{BoxBlockImport, ImportQueue, ImportQueueService},
+	BlockImport,
+};
 use sc_executor::{
 	sp_wasm_interface::HostFunctions, HeapAllocStrategy, NativeExecutionDispatch, RuntimeVersionOf,
-	WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY,
+	WasmExecutor, WasmExecutorBuilder, DEFAULT_HEAP_ALLOC_STRATEGY,
+};
+use sc_keystore::{
+	plugin::enclave::{EnclaveSigner, EnclaveVerifier},
+	AttestationVerifier, LocalKeystore, PluginKeystore, SigningPlugin,
 };
-use sc_keystore::LocalKeystore;
 use sc_network::{
 	config::{FullNetworkConfiguration, ProtocolId, SyncMode},
 	multiaddr::Protocol,
@@ -59,6 +66,7 @@ use sc_network_sync::{
 	service::network::{NetworkServiceHandle, NetworkServiceProvider},
 	state_request_handler::StateRequestHandler,
 	strategy::{
+		checkpoint::{providers_from_config, select_warp_target},
 		polkadot::{PolkadotSyncingStrategy, PolkadotSyncingStrategyConfig},
 		SyncingStrategy,
 	},
@@ -87,15 +95,23 @@ use sp_blockchain::{HeaderBackend, HeaderMetadata};
 use sp_consensus::block_validation::{
 	BlockAnnounceValidator, Chain, DefaultBlockAnnounceValidator,
 };
-use sp_core::traits::{CodeExecutor, SpawnNamed};
+use sp_core::{
+	crypto::KeyTypeId,
+	traits::{CodeExecutor, SpawnNamed},
+};
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, BlockIdTo, NumberFor, Zero};
 use std::{
+	borrow::Cow,
 	str::FromStr,
 	sync::Arc,
 	time::{Duration, SystemTime},
 };
 
+/// Built-in RPC methods that the additional RPC modules of the node may replace, e.g. to submit
+/// extrinsics through the mixnet.
+pub const REPLACEABLE_RPC_METHODS: &[&str] = &["author_submitExtrinsic"];
+
 /// Full client type.
 pub type TFullClient<TBl, TRtApi, TExec> =
 	Client<TFullBackend<TBl>, TFullCallExecutor<TBl, TExec>, TBl, TRtApi>;
@@ -110,28 +126,61 @@ type TFullParts<TBl, TRtApi, TExec> =
 	(TFullClient<TBl, TRtApi, TExec>, Arc<TFullBackend<TBl>>, KeystoreContainer, TaskManager);
 
 /// Construct a local keystore shareable container
-pub struct KeystoreContainer(Arc<LocalKeystore>);
+pub struct KeystoreContainer {
+	local: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
+}
 
 impl KeystoreContainer {
 	/// Construct KeystoreContainer
+	///
+	/// The keys of the key types of the configured enclave, if any, are delegated to it.
 	pub fn new(config: &KeystoreConfig) -> Result<Self, Error> {
 		let keystore = Arc::new(match config {
-			KeystoreConfig::Path { path, password } =>
+			KeystoreConfig::Path { path, password, encryption: None, .. } =>
 				LocalKeystore::open(path.clone(), password.clone())?,
+			KeystoreConfig::Path { path, password, encryption: Some(unlock), .. } =>
+				LocalKeystore::open_encrypted(path.clone(), password.clone(), unlock)?,
 			KeystoreConfig::InMemory => LocalKeystore::in_memory(),
 		});
 
-		Ok(Self(keystore))
+		let container = Self { local: keystore.clone(), keystore };
+		match config {
+			KeystoreConfig::Path { enclave: Some(enclave), .. } => container.with_signing_plugin(
+				Arc::new(EnclaveSigner::new(enclave.address)),
+				Arc::new(EnclaveVerifier::new(
+					enclave.measurements.clone(),
+					enclave.attestation_keys.clone(),
+				)),
+				enclave.key_types.clone(),
+			),
+			_ => Ok(container),
+		}
+	}
+
+	/// Delegate the keys of `key_types` to a signing plugin, e.g. a secure enclave.
+	///
+	/// Fails if the attestation of the plugin isn't accepted by `verifier`.
+	pub fn with_signing_plugin(
+		mut self,
+		plugin: Arc<dyn SigningPlugin>,
+		verifier: Arc<dyn AttestationVerifier>,
+		key_types: Vec<KeyTypeId>,
+	) -> Result<Self, Error> {
+		self.keystore = Arc::new(PluginKeystore::new(self.keystore, plugin, verifier, key_types)?);
+		Ok(self)
 	}
 
 	/// Returns a shared reference to a dynamic `Keystore` trait implementation.
 	pub fn keystore(&self) -> KeystorePtr {
-		self.0.clone()
+		self.keystore.clone()
 	}
 
 	/// Returns a shared reference to the local keystore .
+	///
+	/// Doesn't contain the keys delegated to a signing plugin.
 	pub fn local_keystore(&self) -> Arc<LocalKeystore> {
-		self.0.clone()
+		self.local.clone()
 	}
 }
 
@@ -212,6 +261,7 @@ where
 	let task_manager = {
 		let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
 		TaskManager::new(config.tokio_handle.clone(), registry)?
+			.with_dedicated_runtimes(config.dedicated_runtimes.clone())
 	};
 
 	let chain_spec = &config.chain_spec;
@@ -282,6 +332,21 @@ pub fn new_native_or_wasm_executor<D: NativeExecutionDispatch>(
 
 /// Creates a [`WasmExecutor`] according to [`ExecutorConfiguration`].
 pub fn new_wasm_executor<H: HostFunctions>(config: &ExecutorConfiguration) -> WasmExecutor<H> {
+	wasm_executor_builder::<H>(config).build()
+}
+
+/// Creates a [`WasmExecutorBuilder`] configured according to [`ExecutorConfiguration`].
+///
+/// Chains providing additional host functions register them on the returned builder, e.g.:
+///
+/// ```ignore
+/// let executor = sc_service::wasm_executor_builder::<sp_io::SubstrateHostFunctions>(&config)
+/// 	.with_host_functions::<my_chain_io::HostFunctions>()
+/// 	.build();
+/// ```
+pub fn wasm_executor_builder<H: HostFunctions>(
+	config: &ExecutorConfiguration,
+) -> WasmExecutorBuilder<H> {
 	let strategy = config
 		.default_heap_pages
 		.map_or(DEFAULT_HEAP_ALLOC_STRATEGY, |p| HeapAllocStrategy::Static { extra_pages: p as _ });
@@ -291,7 +356,6 @@ pub fn new_wasm_executor<H: HostFunctions>(config: &ExecutorConfiguration) -> Wa
 		.with_offchain_heap_alloc_strategy(strategy)
 		.with_max_runtime_instances(config.max_runtime_instances)
 		.with_runtime_cache_size(config.runtime_cache_size)
-		.build()
 }
 
 /// Create an instance of default DB-backend backend.
@@ -355,6 +419,73 @@ where
 	)
 }
 
+/// A layer of the block import pipeline, wrapping the block import below it.
+pub type BlockImportLayer<Block> =
+	Box<dyn FnOnce(BoxBlockImport<Block>) -> BoxBlockImport<Block> + Send>;
+
+/// Custom layers of the block import pipeline, e.g. policy checks of the chain, inserted above
+/// the block import of the consensus.
+///
+/// The layers are ordered by registration: the first registered layer is the outermost one and
+/// sees the blocks first, the block import passed to [`BlockImportLayers::apply`] sees them last.
+/// A layer rejecting a block, like a veto, thus shields the layers registered after it. All
+/// layers see the blocks after their verification by the import queue, so the consensus seals
+/// are checked before any layer runs.
+pub struct BlockImportLayers<Block: BlockT> {
+	layers: Vec<BlockImportLayer<Block>>,
+}
+
+impl<Block: BlockT> Default for BlockImportLayers<Block> {
+	fn default() -> Self {
+		Self { layers: Vec::new() }
+	}
+}
+
+impl<Block: BlockT> BlockImportLayers<Block> {
+	/// Create an empty set of layers.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register a layer below the already registered ones.
+	pub fn with_layer(
+		mut self,
+		layer: impl FnOnce(BoxBlockImport<Block>) -> BoxBlockImport<Block> + Send + 'static,
+	) -> Self {
+		self.layers.push(Box::new(layer));
+		self
+	}
+
+	/// Register a layer vetoing blocks with `veto`, below the already registered ones.
+	///
+	/// The `name` of the veto is logged with the blocks it rejects.
+	pub fn with_veto(
+		self,
+		name: impl Into<Cow<'static, str>>,
+		veto: impl ImportVeto<Block> + 'static,
+	) -> Self {
+		let name = name.into();
+		let veto: Arc<dyn ImportVeto<Block>> = Arc::new(veto);
+		self.with_layer(move |inner| Box::new(VetoBlockImport::new(inner, name, veto)))
+	}
+
+	/// Returns `true` if no layer is registered.
+	pub fn is_empty(&self) -> bool {
+		self.layers.is_empty()
+	}
+
+	/// Stack the layers on top of `block_import`, the block import of the consensus.
+	///
+	/// The returned block import is the one to pass to the import queue.
+	pub fn apply(
+		self,
+		block_import: impl BlockImport<Block, Error = sp_consensus::Error> + Send + Sync + 'static,
+	) -> BoxBlockImport<Block> {
+		let block_import: BoxBlockImport<Block> = Box::new(block_import);
+		self.layers.into_iter().rev().fold(block_import, |inner, layer| layer(inner))
+	}
+}
+
 /// Parameters to pass into `build`.
 pub struct SpawnTasksParams<'a, TBl: BlockT, TCl, TExPool, TRpc, Backend> {
 	/// The service configuration.
@@ -369,7 +500,8 @@ pub struct SpawnTasksParams<'a, TBl: BlockT, TCl, TExPool, TRpc, Backend> {
 	pub keystore: KeystorePtr,
 	/// A shared transaction pool.
 	pub transaction_pool: Arc<TExPool>,
-	/// Builds additional [`RpcModule`]s that should be added to the server
+	/// Builds additional [`RpcModule`]s that should be added to the server. Their methods may only
+	/// replace the built-in methods listed in [`REPLACEABLE_RPC_METHODS`].
 	pub rpc_builder: Box<dyn Fn(SubscriptionTaskExecutor) -> Result<RpcModule<TRpc>, Error>>,
 	/// A shared network instance.
 	pub network: Arc<dyn sc_network::service::traits::NetworkService>,
@@ -382,6 +514,12 @@ pub struct SpawnTasksParams<'a, TBl: BlockT, TCl, TExPool, TRpc, Backend> {
 	pub sync_service: Arc<SyncingService<TBl>>,
 	/// Telemetry instance for this node.
 	pub telemetry: Option<&'a mut Telemetry>,
+	/// Providers of additional data displayed in the status line of the informant.
+	pub informant_data_providers: Vec<Arc<dyn sc_informant::InformantDataProvider>>,
+	/// Provider of the status of the relay chain displayed by the informant of a parachain.
+	pub informant_relay_chain: Option<Arc<dyn sc_informant::RelayChainStatusProvider>>,
+	/// Provider of the sessions the informant counts the blocks authored by the node per.
+	pub informant_session_provider: Option<Arc<dyn sc_informant::SessionProvider>>,
 }
 
 /// Spawn the tasks that are required to run a node.
@@ -426,6 +564,9 @@ where
 		tx_handler_controller,
 		sync_service,
 		telemetry,
+		informant_data_providers,
+		informant_relay_chain,
+		informant_session_provider,
 	} = params;
 
 	let chain_info = client.usage_info().chain;
@@ -492,7 +633,12 @@ where
 			spawn_handle.spawn(
 				"prometheus-endpoint",
 				None,
-				prometheus_endpoint::init_prometheus(port, registry).map(drop),
+				prometheus_endpoint::init_prometheus(port, registry.clone()).map(drop),
+			);
+			spawn_handle.spawn(
+				"block-fullness-metrics",
+				None,
+				BlockFullnessMetrics::new(client.clone(), &registry)?.run(),
 			);
 
 			metrics
@@ -527,6 +673,9 @@ where
 			config.chain_spec.as_ref(),
 			&config.state_pruning,
 			config.blocks_pruning,
+			config.rpc.serve_finalized_only,
+			config.rpc.transaction_confirmations,
+			config.rpc.chain_head_resume_grace_period,
 			backend.clone(),
 			&*rpc_builder,
 		)
@@ -535,7 +684,7 @@ where
 	let rpc_server_handle = start_rpc_servers(
 		&config.rpc,
 		config.prometheus_registry(),
-		&config.tokio_handle,
+		config.dedicated_runtimes.rpc.as_ref().unwrap_or(&config.tokio_handle),
 		gen_rpc_module,
 		rpc_id_provider,
 	)?;
@@ -559,10 +708,24 @@ where
 	let in_memory_rpc_handle = RpcHandlers::new(Arc::new(in_memory_rpc), listen_addrs);
 
 	// Spawn informant task
+	let mut informant = sc_informant::InformantBuilder::new().with_config(config.informant.clone());
+	if let Some(relay_chain) = informant_relay_chain {
+		informant = informant.with_relay_chain(relay_chain);
+	}
+	if let Some(session_provider) = informant_session_provider {
+		informant = informant.with_session_provider(session_provider);
+	}
 	spawn_handle.spawn(
 		"informant",
 		None,
-		sc_informant::build(client.clone(), network, sync_service.clone()),
+		informant.build(
+			client.clone(),
+			network,
+			sync_service.clone(),
+			Some(transaction_pool.clone() as Arc<dyn sc_informant::TransactionPoolStatusProvider>),
+			informant_data_providers,
+			config.prometheus_registry().cloned(),
+		),
 	);
 
 	task_manager.keep_alive((config.base_path, rpc_server_handle));
@@ -674,6 +837,9 @@ pub fn gen_rpc_module<TBl, TBackend, TCl, TRpc, TExPool>(
 	chain_spec: &dyn ChainSpec,
 	state_pruning: &Option<PruningMode>,
 	blocks_pruning: BlocksPruning,
+	serve_finalized_only: bool,
+	transaction_confirmations: u32,
+	chain_head_resume_grace_period: Option<Duration>,
 	backend: Arc<TBackend>,
 	rpc_builder: &(dyn Fn(SubscriptionTaskExecutor) -> Result<RpcModule<TRpc>, Error>),
 ) -> Result<RpcModule<()>, Error>
@@ -709,8 +875,11 @@ where
 	let task_executor = Arc::new(spawn_handle);
 
 	let (chain, state, child_state) = {
-		let chain = sc_rpc::chain::new_full(client.clone(), task_executor.clone()).into_rpc();
-		let (state, child_state) = sc_rpc::state::new_full(client.clone(), task_executor.clone());
+		let chain =
+			sc_rpc::chain::new_full(client.clone(), task_executor.clone(), serve_finalized_only)
+				.into_rpc();
+		let (state, child_state) =
+			sc_rpc::state::new_full(client.clone(), task_executor.clone(), serve_finalized_only);
 		let state = state.into_rpc();
 		let child_state = child_state.into_rpc();
 
@@ -732,6 +901,7 @@ where
 		transaction_pool.clone(),
 		task_executor.clone(),
 	)
+	.with_confirmation_depth(transaction_confirmations)
 	.into_rpc();
 
 	let chain_head_v2 = sc_rpc_spec_v2::chain_head::ChainHead::new(
@@ -739,7 +909,10 @@ where
 		backend.clone(),
 		task_executor.clone(),
 		// Defaults to sensible limits for the `ChainHead`.
-		sc_rpc_spec_v2::chain_head::ChainHeadConfig::default(),
+		sc_rpc_spec_v2::chain_head::ChainHeadConfig {
+			subscription_resume_grace_period: chain_head_resume_grace_period,
+			..Default::default()
+		},
 	)
 	.into_rpc();
 
@@ -775,6 +948,7 @@ where
 		keystore,
 		task_executor.clone(),
 	)
+	.with_confirmation_depth(transaction_confirmations)
 	.into_rpc();
 
 	let system = sc_rpc::system::System::new(system_info, system_rpc_tx).into_rpc();
@@ -801,6 +975,12 @@ where
 	rpc_api.merge(child_state).map_err(|e| Error::Application(e.into()))?;
 	// Additional [`RpcModule`]s defined in the node to fit the specific blockchain
 	let extra_rpcs = rpc_builder(task_executor.clone())?;
+	// They may replace some of the methods above, other collisions are rejected by `merge`.
+	for method in extra_rpcs.method_names() {
+		if REPLACEABLE_RPC_METHODS.contains(&method) {
+			rpc_api.remove_method(method);
+		}
+	}
 	rpc_api.merge(extra_rpcs).map_err(|e| Error::Application(e.into()))?;
 
 	Ok(rpc_api)
@@ -1064,6 +1244,7 @@ where
 	});
 
 	// Create transactions protocol and add it to the list of supported protocols of
+	let transaction_propagation = net_config.network_config.transaction_propagation;
 	let (transactions_handler_proto, transactions_config) =
 		sc_network_transactions::TransactionsHandlerPrototype::new::<_, Block, Net>(
 			protocol_id.clone(),
@@ -1106,6 +1287,7 @@ where
 		network.clone(),
 		sync_service.clone(),
 		Arc::new(TransactionPoolAdapter { pool: transaction_pool, client: client.clone() }),
+		transaction_propagation,
 		metrics_registry,
 	)?;
 	spawn_handle.spawn_blocking(
@@ -1369,6 +1551,30 @@ where
 		net_config.add_request_response_protocol(config);
 	}
 
+	// Only trust a warp sync target attested by enough checkpoint providers, unless the node
+	// already has finalized blocks to sync from. The providers are queried in the background, warp
+	// sync waits for the selected target.
+	let checkpoints = &net_config.network_config.warp_checkpoints;
+	let warp_sync_config = if !checkpoints.is_empty() &&
+		net_config.network_config.sync_mode.is_warp() &&
+		client.info().finalized_number.is_zero()
+	{
+		let providers = providers_from_config::<Block>(checkpoints, client.info().genesis_hash)?;
+		let threshold = checkpoints.threshold.unwrap_or(providers.len());
+		let (tx, rx) = oneshot::channel();
+		spawn_handle.spawn("warp-checkpoints", Some("networking"), async move {
+			match select_warp_target(&providers, threshold).await {
+				Ok(target) => {
+					let _ = tx.send(target);
+				},
+				Err(e) => error!("Failed to select the warp sync target: {e}"),
+			}
+		});
+		Some(WarpSyncConfig::WaitForTarget(rx))
+	} else {
+		warp_sync_config
+	};
+
 	let syncing_config = PolkadotSyncingStrategyConfig {
 		mode: net_config.network_config.sync_mode,
 		max_parallel_downloads: net_config.network_config.max_parallel_downloads,
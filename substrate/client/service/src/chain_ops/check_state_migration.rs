@@ -0,0 +1,356 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use sc_client_api::backend::Backend;
+use sp_core::{
+	storage::{ChildInfo, TRIE_VALUE_NODE_THRESHOLD},
+	Hasher,
+};
+use sp_runtime::traits::{Block as BlockT, HashingFor};
+use sp_state_machine::backend::AsTrieBackend;
+use sp_trie::{
+	read_child_trie_roots, trie_types::TrieDBBuilder, KeySpacedDB, LayoutV1, NodePlan, Trie,
+	ValuePlan,
+};
+use trie_db::{HashDBRef, NibbleVec, TrieDBNodeIterator, TrieIterator};
+
+/// Number of values scanned between two progress reports.
+const REPORT_INTERVAL: u64 = 100_000;
+
+/// Progress of the migration of one or more tries to the `V1` state version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrieMigrationProgress {
+	/// Number of values stored in the tries.
+	pub total: u64,
+	/// Number of values which are still stored inline, as in the `V0` state version.
+	pub remaining: u64,
+	/// Size in bytes of the values which are still stored inline.
+	pub remaining_size: u64,
+}
+
+/// Progress of the migration of a state to the `V1` state version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StateMigrationStatus {
+	/// Progress of the migration of the top trie.
+	pub top: TrieMigrationProgress,
+	/// Progress of the migration of all the child tries.
+	pub child: TrieMigrationProgress,
+	/// Number of child tries.
+	pub child_tries: u64,
+}
+
+impl StateMigrationStatus {
+	/// Whether all the values of the state are stored as in the `V1` state version.
+	pub fn is_complete(&self) -> bool {
+		self.top.remaining == 0 && self.child.remaining == 0
+	}
+}
+
+/// Values of a state which remain to be migrated to the `V1` state version, all stored in the same
+/// trie.
+#[derive(Debug, Clone)]
+pub struct StateMigrationBatch {
+	/// The child trie storing the values, `None` for the top trie.
+	pub child_info: Option<ChildInfo>,
+	/// The keys of the values.
+	pub keys: Vec<Vec<u8>>,
+	/// Size in bytes of the values.
+	pub size: u64,
+}
+
+impl StateMigrationBatch {
+	/// Add a value to the batch, returning whether it holds fewer than `max_items` values.
+	fn push(&mut self, key: &[u8], size: u32, max_items: usize) -> bool {
+		self.keys.push(key.to_vec());
+		self.size += u64::from(size);
+		self.keys.len() < max_items
+	}
+}
+
+/// Scan the state at `hash` and return how much of it remains to be migrated to the `V1` state
+/// version.
+///
+/// The migration itself is done on chain, for instance by the state trie migration pallet, as
+/// migrating a value changes the state root. See [`next_state_migration_batch`] for the values to
+/// migrate. `report` is called with the intermediate status while scanning, which can take a while
+/// for large states.
+///
+/// With `verify`, the state is also checked to be intact: the data of every trie node must match
+/// its hash and every value must be readable.
+pub fn check_state_migration<B, BA>(
+	backend: &BA,
+	hash: B::Hash,
+	verify: bool,
+	mut report: impl FnMut(&StateMigrationStatus),
+) -> Result<StateMigrationStatus, Error>
+where
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let state = backend.state_at(hash)?;
+	let trie_backend = state.as_trie_backend();
+	let essence = trie_backend.essence();
+
+	let mut status = StateMigrationStatus::default();
+	scan_trie::<HashingFor<B>>(
+		essence,
+		essence.root(),
+		&[],
+		verify,
+		&mut status.top,
+		&mut |top| report(&StateMigrationStatus { top: *top, ..Default::default() }),
+		&mut |_, _| true,
+	)?;
+
+	let child_roots = read_child_trie_roots::<LayoutV1<HashingFor<B>>>(essence, essence.root())
+		.map_err(|e| Error::Other(format!("Failed to read the child tries: {e}")))?;
+	for (child_info, root) in child_roots {
+		let storage = KeySpacedDB::new(essence, child_info.keyspace());
+		let top = status.top;
+		let child_tries = status.child_tries;
+		scan_trie::<HashingFor<B>>(
+			&storage,
+			&root,
+			&[],
+			verify,
+			&mut status.child,
+			&mut |child| report(&StateMigrationStatus { top, child: *child, child_tries }),
+			&mut |_, _| true,
+		)?;
+		status.child_tries += 1;
+	}
+
+	Ok(status)
+}
+
+/// The first values of the state at `hash` which remain to be migrated to the `V1` state version,
+/// at most `max_items` of them.
+///
+/// The values of the top trie are returned first, then the values of each child trie. A value is
+/// migrated by writing it again with the `V1` state version, for instance with the
+/// `migrate_custom_top` and `migrate_custom_child` calls of the state trie migration pallet.
+/// Returns `None` once the whole state is migrated.
+///
+/// The values before the first value of `from`, a batch returned for an earlier block, are expected
+/// to be migrated and are skipped, so the state isn't scanned from the start for every batch.
+pub fn next_state_migration_batch<B, BA>(
+	backend: &BA,
+	hash: B::Hash,
+	from: Option<&StateMigrationBatch>,
+	max_items: usize,
+) -> Result<Option<StateMigrationBatch>, Error>
+where
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let state = backend.state_at(hash)?;
+	let trie_backend = state.as_trie_backend();
+	let essence = trie_backend.essence();
+
+	let from_key = from.and_then(|from| from.keys.first()).map_or(&[][..], |key| &key[..]);
+	let from_child = from.and_then(|from| from.child_info.as_ref());
+	let mut batch = StateMigrationBatch { child_info: None, keys: Vec::new(), size: 0 };
+	if from_child.is_none() {
+		scan_trie::<HashingFor<B>>(
+			essence,
+			essence.root(),
+			from_key,
+			false,
+			&mut TrieMigrationProgress::default(),
+			&mut |_| (),
+			&mut |key, size| batch.push(key, size, max_items),
+		)?;
+		if !batch.keys.is_empty() {
+			return Ok(Some(batch))
+		}
+	}
+
+	let child_roots = read_child_trie_roots::<LayoutV1<HashingFor<B>>>(essence, essence.root())
+		.map_err(|e| Error::Other(format!("Failed to read the child tries: {e}")))?;
+	for (child_info, root) in child_roots {
+		let start = match from_child.map(|from| from.storage_key()) {
+			Some(from) if child_info.storage_key() < from => continue,
+			Some(from) if child_info.storage_key() == from => from_key,
+			_ => &[],
+		};
+		let storage = KeySpacedDB::new(essence, child_info.keyspace());
+		scan_trie::<HashingFor<B>>(
+			&storage,
+			&root,
+			start,
+			false,
+			&mut TrieMigrationProgress::default(),
+			&mut |_| (),
+			&mut |key, size| batch.push(key, size, max_items),
+		)?;
+		if !batch.keys.is_empty() {
+			batch.child_info = Some(child_info);
+			return Ok(Some(batch))
+		}
+	}
+
+	Ok(None)
+}
+
+/// Scan the nodes of the trie with the given `root` from the `start` key, adding its values to
+/// `progress`.
+///
+/// `remaining` is called with the key and the size of every value still stored inline, the scan
+/// stops once it returns `false`.
+fn scan_trie<H: Hasher>(
+	storage: &dyn HashDBRef<H, Vec<u8>>,
+	root: &H::Out,
+	start: &[u8],
+	verify: bool,
+	progress: &mut TrieMigrationProgress,
+	report: &mut dyn FnMut(&TrieMigrationProgress),
+	remaining: &mut dyn FnMut(&[u8], u32) -> bool,
+) -> Result<(), Error> {
+	let trie = TrieDBBuilder::new(storage, root).build();
+	let mut nodes = TrieDBNodeIterator::new(&trie)
+		.map_err(|e| Error::Other(format!("Failed to iterate trie {root:?}: {e}")))?;
+	nodes
+		.seek(start)
+		.map_err(|e| Error::Other(format!("Failed to iterate trie {root:?}: {e}")))?;
+	for node in nodes {
+		let (prefix, node_hash, node) =
+			node.map_err(|e| Error::Other(format!("Failed to read node of trie {root:?}: {e}")))?;
+		// Nodes inlined in their parent have no hash.
+		if let (true, Some(node_hash)) = (verify, node_hash) {
+			if H::hash(node.data()) != node_hash {
+				return Err(Error::Other(format!(
+					"Node {node_hash:?} of trie {root:?} doesn't match its hash"
+				)));
+			}
+		}
+
+		let (partial, value) = match node.node_plan() {
+			NodePlan::Leaf { partial, value } |
+			NodePlan::NibbledBranch { partial, value: Some(value), .. } => (partial, value),
+			_ => continue,
+		};
+		progress.total += 1;
+		if let ValuePlan::Inline(range) = value {
+			let size = (range.end - range.start) as u32;
+			if size >= TRIE_VALUE_NODE_THRESHOLD {
+				progress.remaining += 1;
+				progress.remaining_size += u64::from(size);
+
+				let mut key: NibbleVec = prefix;
+				key.append_partial(partial.build(node.data()).right());
+				let (key, None) = key.as_prefix() else {
+					return Err(Error::Other(format!("Value of trie {root:?} has an odd key")))
+				};
+				if !remaining(key, size) {
+					return Ok(())
+				}
+			}
+		}
+		if progress.total % REPORT_INTERVAL == 0 {
+			report(progress);
+		}
+	}
+
+	if verify {
+		// Values stored outside of their node are only read when iterating the values.
+		let values = trie
+			.iter()
+			.map_err(|e| Error::Other(format!("Failed to iterate trie {root:?}: {e}")))?;
+		for value in values {
+			value
+				.map_err(|e| Error::Other(format!("Failed to read value of trie {root:?}: {e}")))?;
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime_client::{prelude::*, runtime::Block};
+
+	#[test]
+	fn check_state_migration_works() {
+		let child_info = ChildInfo::new_default(b"child");
+		let builder = TestClientBuilder::new()
+			.add_extra_storage(b":large".to_vec(), vec![1; 64])
+			.add_extra_child_storage(&child_info, b"key".to_vec(), vec![2; 64]);
+		let backend = builder.backend();
+		let client = builder.build();
+		let hash = client.chain_info().genesis_hash;
+
+		let mut reported = false;
+		let status =
+			check_state_migration::<Block, _>(&*backend, hash, true, |_| reported = true).unwrap();
+		assert_eq!(status.child_tries, 1);
+		assert_eq!(status.child.total, 1);
+		assert!(status.top.total > 1);
+		assert!(!reported);
+
+		// The test runtime uses the `V1` state version since genesis.
+		assert!(status.is_complete());
+		assert_eq!(status.top.remaining_size, 0);
+		assert!(next_state_migration_batch::<Block, _>(&*backend, hash, None, 10)
+			.unwrap()
+			.is_none());
+	}
+
+	#[test]
+	fn values_stored_inline_are_found() {
+		use sp_trie::{trie_types::TrieDBMutBuilderV0, MemoryDB, TrieMut};
+		type Hasher = sp_core::Blake2Hasher;
+
+		let mut db = MemoryDB::<Hasher>::default();
+		let mut root = Default::default();
+		{
+			let mut trie = TrieDBMutBuilderV0::new(&mut db, &mut root).build();
+			trie.insert(b"large_a", &[1; 64]).unwrap();
+			trie.insert(b"large_b", &[2; 33]).unwrap();
+			trie.insert(b"small", &[3; 8]).unwrap();
+		}
+
+		let mut remaining = Vec::new();
+		let mut progress = TrieMigrationProgress::default();
+		scan_trie::<Hasher>(&db, &root, &[], true, &mut progress, &mut |_| (), &mut |key, size| {
+			remaining.push((key.to_vec(), size));
+			true
+		})
+		.unwrap();
+		assert_eq!(remaining, vec![(b"large_a".to_vec(), 64), (b"large_b".to_vec(), 33)]);
+		assert_eq!(progress, TrieMigrationProgress { total: 3, remaining: 2, remaining_size: 97 });
+
+		// The scan starts from the given key and stops once enough values are found.
+		let mut remaining = Vec::new();
+		scan_trie::<Hasher>(
+			&db,
+			&root,
+			b"large_b",
+			false,
+			&mut TrieMigrationProgress::default(),
+			&mut |_| (),
+			&mut |key, _| {
+				remaining.push(key.to_vec());
+				false
+			},
+		)
+		.unwrap();
+		assert_eq!(remaining, vec![b"large_b".to_vec()]);
+	}
+}
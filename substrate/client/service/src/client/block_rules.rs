@@ -41,6 +41,8 @@ pub enum LookupResult<B: BlockT> {
 pub struct BlockRules<B: BlockT> {
 	bad: HashSet<B::Hash>,
 	forks: HashMap<NumberFor<B>, B::Hash>,
+	/// The blocks blacklisted by the operator, which can be removed from the blacklist.
+	blacklisted: HashSet<B::Hash>,
 }
 
 impl<B: BlockT> BlockRules<B> {
@@ -49,6 +51,7 @@ impl<B: BlockT> BlockRules<B> {
 		Self {
 			bad: bad_blocks.unwrap_or_default(),
 			forks: fork_blocks.unwrap_or_default().into_iter().collect(),
+			blacklisted: HashSet::new(),
 		}
 	}
 
@@ -57,6 +60,26 @@ impl<B: BlockT> BlockRules<B> {
 		self.bad.insert(hash);
 	}
 
+	/// Add a block to the blacklist, returns whether it was not blacklisted yet.
+	pub fn blacklist(&mut self, hash: B::Hash) -> bool {
+		self.blacklisted.insert(hash)
+	}
+
+	/// Remove a block from the blacklist, returns whether it was blacklisted.
+	pub fn unblacklist(&mut self, hash: &B::Hash) -> bool {
+		self.blacklisted.remove(hash)
+	}
+
+	/// The blacklisted blocks.
+	pub fn blacklisted(&self) -> Vec<B::Hash> {
+		self.blacklisted.iter().copied().collect()
+	}
+
+	/// Check if the block is known to be bad or blacklisted.
+	pub fn is_bad(&self, hash: &B::Hash) -> bool {
+		self.bad.contains(hash) || self.blacklisted.contains(hash)
+	}
+
 	/// Check if there's any rule affecting the given block.
 	pub fn lookup(&self, number: NumberFor<B>, hash: &B::Hash) -> LookupResult<B> {
 		if let Some(hash_for_height) = self.forks.get(&number) {
@@ -65,7 +88,7 @@ impl<B: BlockT> BlockRules<B> {
 			}
 		}
 
-		if self.bad.contains(hash) {
+		if self.is_bad(hash) {
 			return LookupResult::KnownBad
 		}
 
@@ -23,6 +23,7 @@ use super::{
 	CodeProvider,
 };
 use crate::client::notification_pinning::NotificationPinningWorker;
+use codec::{Decode, Encode};
 use log::{debug, info, trace, warn};
 use parking_lot::{Mutex, RwLock};
 use prometheus_endpoint::Registry;
@@ -34,14 +35,15 @@ use sc_client_api::{
 		ImportNotificationAction, ImportSummary, LockImportRun, NewBlockState, StorageProvider,
 	},
 	client::{
-		BadBlocks, BlockBackend, BlockImportNotification, BlockOf, BlockchainEvents, ClientInfo,
-		FinalityNotification, FinalityNotifications, ForkBlocks, ImportNotifications,
-		PreCommitActions, ProvideUncles,
+		BadBlocks, BlockBackend, BlockBlacklist, BlockImportNotification, BlockOf,
+		BlockStorageChanges, BlockStorageChangesEvents, BlockStorageChangesNotifications,
+		BlockchainEvents, BlockchainEventsReplay, ClientInfo, FinalityNotification,
+		FinalityNotifications, ForkBlocks, ImportNotifications, PreCommitActions, ProvideUncles,
 	},
 	execution_extensions::ExecutionExtensions,
 	notifications::{StorageEventStream, StorageNotifications},
 	CallExecutor, ExecutorProvider, KeysIter, OnFinalityAction, OnImportAction, PairsIter,
-	ProofProvider, UnpinWorkerMessage, UsageProvider,
+	ProofProvider, StorageSnapshot, UnpinWorkerMessage, UsageProvider,
 };
 use sc_consensus::{
 	BlockCheckParams, BlockImportParams, ForkChoiceStrategy, ImportResult, StateAction,
@@ -58,7 +60,7 @@ use sp_blockchain::{
 };
 use sp_consensus::{BlockOrigin, BlockStatus, Error as ConsensusError};
 
-use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_core::{
 	storage::{ChildInfo, ChildType, PrefixedStorageKey, StorageChild, StorageData, StorageKey},
 	traits::{CallContext, SpawnNamed},
@@ -79,10 +81,11 @@ use sp_state_machine::{
 };
 use sp_trie::{proof_size_extension::ProofSizeExt, CompactProof, MerkleValue, StorageProof};
 use std::{
-	collections::{HashMap, HashSet},
+	collections::{HashMap, HashSet, VecDeque},
 	marker::PhantomData,
 	path::PathBuf,
 	sync::Arc,
+	time::Instant,
 };
 
 use super::call_executor::LocalCallExecutor;
@@ -90,6 +93,43 @@ use sp_core::traits::CodeExecutor;
 
 type NotificationSinks<T> = Mutex<Vec<TracingUnboundedSender<T>>>;
 
+/// The number of most recent import and finality notifications retained for replay.
+///
+/// The retained notifications keep their blocks pinned.
+const RETAINED_NOTIFICATIONS: usize = 64;
+
+/// Auxiliary storage key of the blocks blacklisted through [`BlockBlacklist`].
+const BLACKLISTED_BLOCKS_KEY: &[u8] = b"client_blacklisted_blocks";
+
+/// Retain `notification` for replay, discarding the oldest one beyond the retained number.
+fn retain_for_replay<T>(retained: &Mutex<VecDeque<T>>, notification: T) {
+	let mut retained = retained.lock();
+	if retained.len() == RETAINED_NOTIFICATIONS {
+		retained.pop_front();
+	}
+	retained.push_back(notification);
+}
+
+/// Register a new sink to `sinks`, after sending it up to `max_replay` of the `retained`
+/// notifications.
+fn stream_with_replay<T: Clone>(
+	name: &'static str,
+	sinks: &NotificationSinks<T>,
+	retained: &Mutex<VecDeque<T>>,
+	max_replay: usize,
+) -> TracingUnboundedReceiver<T> {
+	let (sink, stream) = tracing_unbounded(name, 100_000);
+	// Notifications are sent and retained while holding the lock of the sinks, so holding it
+	// until the sink is registered neither misses nor repeats a notification.
+	let mut sinks = sinks.lock();
+	let retained = retained.lock();
+	for notification in retained.iter().skip(retained.len().saturating_sub(max_replay)) {
+		let _ = sink.unbounded_send(notification.clone());
+	}
+	sinks.push(sink);
+	stream
+}
+
 /// Substrate Client
 pub struct Client<B, E, Block, RA>
 where
@@ -101,6 +141,11 @@ where
 	import_notification_sinks: NotificationSinks<BlockImportNotification<Block>>,
 	every_import_notification_sinks: NotificationSinks<BlockImportNotification<Block>>,
 	finality_notification_sinks: NotificationSinks<FinalityNotification<Block>>,
+	block_storage_changes_sinks: NotificationSinks<BlockStorageChanges<Block::Hash>>,
+	// The most recent notifications sent to `import_notification_sinks`, for replay.
+	retained_import_notifications: Mutex<VecDeque<BlockImportNotification<Block>>>,
+	// The most recent notifications sent to `finality_notification_sinks`, for replay.
+	retained_finality_notifications: Mutex<VecDeque<FinalityNotification<Block>>>,
 	// Collects auxiliary operations to be performed atomically together with
 	// block import operations.
 	import_actions: Mutex<Vec<OnImportAction<Block>>>,
@@ -109,7 +154,7 @@ where
 	finality_actions: Mutex<Vec<OnFinalityAction<Block>>>,
 	// Holds the block hash currently being imported. TODO: replace this with block queue.
 	importing_block: RwLock<Option<Block::Hash>>,
-	block_rules: BlockRules<Block>,
+	block_rules: RwLock<BlockRules<Block>>,
 	config: ClientConfig<Block>,
 	telemetry: Option<TelemetryHandle>,
 	unpin_worker_sender: TracingUnboundedSender<UnpinWorkerMessage<Block>>,
@@ -256,7 +301,9 @@ where
 				FinalityNotification::from_summary(summary, self.unpin_worker_sender.clone())
 			});
 
-			let (import_notification, storage_changes, import_notification_action) =
+			let import_started =
+				notify_imported.as_ref().and_then(|summary| summary.import_started);
+			let (mut import_notification, storage_changes, import_notification_action) =
 				match notify_imported {
 					Some(mut summary) => {
 						let import_notification_action = summary.import_notification_action;
@@ -286,6 +333,11 @@ where
 
 			self.backend.commit_operation(op)?;
 
+			// The import duration includes the commit of the block to the database.
+			if let Some(notification) = import_notification.as_mut() {
+				notification.import_duration = import_started.map(|started| started.elapsed());
+			}
+
 			// We need to pin the block in the backend once
 			// for each notification. Once all notifications are
 			// dropped, the block will be unpinned automatically.
@@ -406,6 +458,16 @@ where
 		spawn_handle.spawn("notification-pinning-worker", None, Box::pin(unpin_worker.run()));
 		let code_provider = CodeProvider::new(&config, executor.clone(), backend.clone())?;
 
+		let mut block_rules = BlockRules::new(fork_blocks, bad_blocks);
+		if let Some(encoded) = backend::AuxStore::get_aux(&*backend, BLACKLISTED_BLOCKS_KEY)? {
+			let blacklisted = Vec::<Block::Hash>::decode(&mut &encoded[..]).map_err(|e| {
+				Error::Backend(format!("Failed to decode the blacklisted blocks: {e}"))
+			})?;
+			for hash in blacklisted {
+				block_rules.blacklist(hash);
+			}
+		}
+
 		Ok(Client {
 			backend,
 			executor,
@@ -413,10 +475,13 @@ where
 			import_notification_sinks: Default::default(),
 			every_import_notification_sinks: Default::default(),
 			finality_notification_sinks: Default::default(),
+			block_storage_changes_sinks: Default::default(),
+			retained_import_notifications: Default::default(),
+			retained_finality_notifications: Default::default(),
 			import_actions: Default::default(),
 			finality_actions: Default::default(),
 			importing_block: Default::default(),
-			block_rules: BlockRules::new(fork_blocks, bad_blocks),
+			block_rules: RwLock::new(block_rules),
 			config,
 			telemetry,
 			unpin_worker_sender,
@@ -782,6 +847,7 @@ where
 				storage_changes,
 				tree_route,
 				import_notification_action,
+				import_started: None,
 			})
 		}
 
@@ -990,10 +1056,38 @@ where
 		);
 
 		sinks.retain(|sink| sink.unbounded_send(notification.clone()).is_ok());
+		retain_for_replay(&self.retained_finality_notifications, notification);
 
 		Ok(())
 	}
 
+	/// Send `notification` to the import notification sinks, retaining it for replay.
+	fn send_import_notification(&self, notification: &BlockImportNotification<Block>) {
+		let mut sinks = self.import_notification_sinks.lock();
+		sinks.retain(|sink| sink.unbounded_send(notification.clone()).is_ok());
+		retain_for_replay(&self.retained_import_notifications, notification.clone());
+	}
+
+	/// Send the storage changes of the block `hash` to the block storage changes sinks, if any.
+	fn send_block_storage_changes(
+		&self,
+		hash: Block::Hash,
+		(main_changes, child_changes): &(StorageCollection, ChildStorageCollection),
+	) {
+		let mut sinks = self.block_storage_changes_sinks.lock();
+		sinks.retain(|sink| !sink.is_closed());
+		if sinks.is_empty() {
+			return
+		}
+
+		let changes = BlockStorageChanges {
+			hash,
+			main_changes: Arc::new(main_changes.clone()),
+			child_changes: Arc::new(child_changes.clone()),
+		};
+		sinks.retain(|sink| sink.unbounded_send(changes.clone()).is_ok());
+	}
+
 	fn notify_imported(
 		&self,
 		notification: Option<BlockImportNotification<Block>>,
@@ -1019,6 +1113,7 @@ where
 
 		let trigger_storage_changes_notification = || {
 			if let Some(storage_changes) = storage_changes {
+				self.send_block_storage_changes(notification.hash, &storage_changes);
 				// TODO [ToDr] How to handle re-orgs? Should we re-emit all storage changes?
 				self.storage_notifications.trigger(
 					&notification.hash,
@@ -1031,9 +1126,7 @@ where
 		match import_notification_action {
 			ImportNotificationAction::Both => {
 				trigger_storage_changes_notification();
-				self.import_notification_sinks
-					.lock()
-					.retain(|sink| sink.unbounded_send(notification.clone()).is_ok());
+				self.send_import_notification(&notification);
 
 				self.every_import_notification_sinks
 					.lock()
@@ -1041,9 +1134,7 @@ where
 			},
 			ImportNotificationAction::RecentBlock => {
 				trigger_storage_changes_notification();
-				self.import_notification_sinks
-					.lock()
-					.retain(|sink| sink.unbounded_send(notification.clone()).is_ok());
+				self.send_import_notification(&notification);
 
 				self.every_import_notification_sinks.lock().retain(|sink| !sink.is_closed());
 			},
@@ -1092,7 +1183,7 @@ where
 		let (number, reverted) = self.backend.revert(n, true)?;
 		if blacklist {
 			for b in reverted {
-				self.block_rules.mark_bad(b);
+				self.block_rules.get_mut().mark_bad(b);
 			}
 		}
 		Ok(number)
@@ -1103,8 +1194,26 @@ where
 		self.backend.blockchain().info()
 	}
 
+	/// Remove the block `hash` and all its descendants from the database, leaf by leaf.
+	fn remove_subtree(&self, hash: Block::Hash) -> sp_blockchain::Result<()> {
+		let _import_lock = self.backend.get_import_lock().write();
+		let blockchain = self.backend.blockchain();
+		while blockchain.number(hash)?.is_some() {
+			for leaf in blockchain.leaves()? {
+				if sp_blockchain::tree_route(blockchain, hash, leaf)?.common_block().hash == hash {
+					self.backend.remove_leaf_block(leaf)?;
+				}
+			}
+		}
+		Ok(())
+	}
+
 	/// Get block status.
 	pub fn block_status(&self, hash: Block::Hash) -> sp_blockchain::Result<BlockStatus> {
+		if self.block_rules.read().is_bad(&hash) {
+			return Ok(BlockStatus::KnownBad)
+		}
+
 		// this can probably be implemented more efficiently
 		if self
 			.importing_block
@@ -1511,6 +1620,13 @@ where
 			.child_closest_merkle_value(child_info, &key.0)
 			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
 	}
+
+	fn storage_snapshot(
+		&self,
+		hash: <Block as BlockT>::Hash,
+	) -> sp_blockchain::Result<StorageSnapshot<B::State, Block>> {
+		Ok(StorageSnapshot::new(self.state_at(hash)?))
+	}
 }
 
 impl<B, E, Block, RA> HeaderMetadata<Block> for Client<B, E, Block, RA>
@@ -1537,6 +1653,78 @@ where
 	}
 }
 
+impl<B, E, Block, RA> BlockBlacklist<Block> for Client<B, E, Block, RA>
+where
+	B: backend::Backend<Block>,
+	E: CallExecutor<Block>,
+	Block: BlockT,
+{
+	fn blacklist_block(&self, hash: Block::Hash) -> sp_blockchain::Result<()> {
+		self.lock_import_and_run(|operation| {
+			let blockchain = self.backend.blockchain();
+			let info = blockchain.info();
+			if let Some(number) = blockchain.number(hash)? {
+				if number <= info.finalized_number && blockchain.hash(number)? == Some(hash) {
+					return Err(Error::Application(
+						format!("Can't blacklist the finalized block {hash:?}").into(),
+					))
+				}
+
+				// Retract the block from the best chain by making its parent the best block.
+				let route_to_best = sp_blockchain::tree_route(blockchain, hash, info.best_hash)?;
+				if route_to_best.common_block().hash == hash {
+					let parent_hash = *blockchain.expect_header(hash)?.parent_hash();
+					operation.op.mark_head(parent_hash)?;
+					operation.notify_imported = Some(ImportSummary {
+						hash: parent_hash,
+						origin: BlockOrigin::Own,
+						header: blockchain.expect_header(parent_hash)?,
+						is_new_best: true,
+						storage_changes: None,
+						tree_route: Some(sp_blockchain::tree_route(
+							blockchain,
+							info.best_hash,
+							parent_hash,
+						)?),
+						import_notification_action: ImportNotificationAction::Both,
+						import_started: None,
+					});
+				}
+			}
+
+			let mut blacklisted = self.block_rules.read().blacklisted();
+			if !blacklisted.contains(&hash) {
+				blacklisted.push(hash);
+			}
+			apply_aux(operation, &[(BLACKLISTED_BLOCKS_KEY, &blacklisted.encode()[..])], &[])
+		})?;
+		self.block_rules.write().blacklist(hash);
+		self.remove_subtree(hash)?;
+
+		warn!("⛔ Blacklisted block {:?}", hash);
+		Ok(())
+	}
+
+	fn unblacklist_block(&self, hash: Block::Hash) -> sp_blockchain::Result<bool> {
+		let mut blacklisted = self.block_rules.read().blacklisted();
+		if !blacklisted.contains(&hash) {
+			return Ok(false)
+		}
+		blacklisted.retain(|blacklisted| *blacklisted != hash);
+		self.lock_import_and_run(|operation| {
+			apply_aux(operation, &[(BLACKLISTED_BLOCKS_KEY, &blacklisted.encode()[..])], &[])
+		})?;
+		self.block_rules.write().unblacklist(&hash);
+
+		info!("Removed block {:?} from the blacklist", hash);
+		Ok(true)
+	}
+
+	fn blacklisted_blocks(&self) -> Vec<Block::Hash> {
+		self.block_rules.read().blacklisted()
+	}
+}
+
 impl<B, E, Block, RA> ProvideUncles<Block> for Client<B, E, Block, RA>
 where
 	B: backend::Backend<Block>,
@@ -1723,8 +1911,19 @@ where
 		&self,
 		mut import_block: BlockImportParams<Block>,
 	) -> Result<ImportResult, Self::Error> {
+		// The blocks of the initial sync can wait for the backend to catch up with its writes,
+		// before taking the import lock.
+		if import_block.origin == BlockOrigin::NetworkInitialSync {
+			let delay = self.backend.write_delay();
+			if !delay.is_zero() {
+				trace!("Delaying the import by {delay:?} to limit the write rate");
+				futures_timer::Delay::new(delay).await;
+			}
+		}
+
 		let span = tracing::span!(tracing::Level::DEBUG, "import_block");
 		let _enter = span.enter();
+		let started = Instant::now();
 
 		let storage_changes =
 			match self.prepare_block_storage_changes(&mut import_block).map_err(|e| {
@@ -1736,7 +1935,11 @@ where
 			};
 
 		self.lock_import_and_run(|operation| {
-			self.apply_block(operation, import_block, storage_changes)
+			let result = self.apply_block(operation, import_block, storage_changes);
+			if let Some(summary) = operation.notify_imported.as_mut() {
+				summary.import_started = Some(started);
+			}
+			result
 		})
 		.map_err(|e| {
 			warn!("Block import error: {}", e);
@@ -1760,7 +1963,8 @@ where
 
 		// Check the block against white and black lists if any are defined
 		// (i.e. fork blocks and bad blocks respectively)
-		match self.block_rules.lookup(number, &hash) {
+		let lookup = self.block_rules.read().lookup(number, &hash);
+		match lookup {
 			BlockLookupResult::KnownBad => {
 				trace!("Rejecting known bad block: #{} {:?}", number, hash);
 				return Ok(ImportResult::KnownBad)
@@ -1937,6 +2141,48 @@ where
 	}
 }
 
+impl<B, E, Block, RA> BlockStorageChangesEvents<Block> for Client<B, E, Block, RA>
+where
+	E: CallExecutor<Block>,
+	Block: BlockT,
+{
+	fn block_storage_changes_stream(&self) -> BlockStorageChangesNotifications<Block> {
+		let (sink, stream) = tracing_unbounded("mpsc_block_storage_changes_stream", 100_000);
+		self.block_storage_changes_sinks.lock().push(sink);
+		stream
+	}
+}
+
+impl<B, E, Block, RA> BlockchainEventsReplay<Block> for Client<B, E, Block, RA>
+where
+	E: CallExecutor<Block>,
+	Block: BlockT,
+{
+	fn import_notification_stream_with_replay(
+		&self,
+		max_replay: usize,
+	) -> ImportNotifications<Block> {
+		stream_with_replay(
+			"mpsc_import_notification_stream",
+			&self.import_notification_sinks,
+			&self.retained_import_notifications,
+			max_replay,
+		)
+	}
+
+	fn finality_notification_stream_with_replay(
+		&self,
+		max_replay: usize,
+	) -> FinalityNotifications<Block> {
+		stream_with_replay(
+			"mpsc_finality_notification_stream",
+			&self.finality_notification_sinks,
+			&self.retained_finality_notifications,
+			max_replay,
+		)
+	}
+}
+
 impl<B, E, Block, RA> BlockBackend<Block> for Client<B, E, Block, RA>
 where
 	B: backend::Backend<Block>,
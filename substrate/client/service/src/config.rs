@@ -22,7 +22,9 @@ pub use jsonrpsee::server::BatchRequestConfig as RpcBatchRequestConfig;
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
 pub use sc_client_db::{BlocksPruning, Database, DatabaseSource, PruningMode};
+pub use sc_consensus::ImportQueueConfig;
 pub use sc_executor::{WasmExecutionMethod, WasmtimeInstantiationStrategy};
+pub use sc_informant::{FinalityLagThresholds, InformantConfig};
 pub use sc_network::{
 	config::{
 		MultiaddrWithPeerId, NetworkConfiguration, NodeKeyConfig, NonDefaultSetConfig, ProtocolId,
@@ -44,6 +46,7 @@ use std::{
 	net::SocketAddr,
 	num::NonZeroU32,
 	path::{Path, PathBuf},
+	time::Duration,
 };
 use tempfile::TempDir;
 
@@ -58,6 +61,8 @@ pub struct Configuration {
 	pub role: Role,
 	/// Handle to the tokio runtime. Will be used to spawn futures by the task manager.
 	pub tokio_handle: tokio::runtime::Handle,
+	/// Handles to the tokio runtimes dedicated to some subsystems, instead of `tokio_handle`.
+	pub dedicated_runtimes: DedicatedRuntimes,
 	/// Extrinsic pool configuration.
 	pub transaction_pool: TransactionPoolOptions,
 	/// Network configuration.
@@ -76,6 +81,8 @@ pub struct Configuration {
 	///
 	/// NOTE: only finalized blocks are subject for removal!
 	pub blocks_pruning: BlocksPruning,
+	/// Maximum average rate at which the database is written to, in MiB per second.
+	pub database_write_rate_limit: Option<NonZeroU32>,
 	/// Chain configuration.
 	pub chain_spec: Box<dyn ChainSpec>,
 	/// Runtime executor configuration.
@@ -84,6 +91,8 @@ pub struct Configuration {
 	/// over on-chain runtimes when the spec version matches. Set to `None` to
 	/// disable overrides (default).
 	pub wasm_runtime_overrides: Option<PathBuf>,
+	/// Configuration of the block verification by the import queue.
+	pub import_queue: ImportQueueConfig,
 	/// RPC configuration.
 	pub rpc: RpcConfiguration,
 	/// Prometheus endpoint configuration. `None` if disabled.
@@ -109,6 +118,8 @@ pub struct Configuration {
 	pub tracing_receiver: sc_tracing::TracingReceiver,
 	/// Announce block automatically after they have been imported
 	pub announce_block: bool,
+	/// Informant settings.
+	pub informant: InformantConfig,
 	/// Data path root for the configured chain.
 	pub data_path: PathBuf,
 	/// Base path of the configuration. This is shared between chains.
@@ -133,6 +144,11 @@ pub enum KeystoreConfig {
 		path: PathBuf,
 		/// Node keystore's password.
 		password: Option<SecretString>,
+		/// How to unlock the master key the key files are encrypted with. `None` if the key files
+		/// are not encrypted.
+		encryption: Option<sc_keystore::Unlock>,
+		/// The enclave the keys of some key types are delegated to, if any.
+		enclave: Option<sc_keystore::plugin::enclave::EnclaveConfig>,
 	},
 	/// In-memory keystore. Recommended for in-browser nodes.
 	InMemory,
@@ -147,6 +163,28 @@ impl KeystoreConfig {
 		}
 	}
 }
+
+/// Handles to tokio runtimes dedicated to heavy subsystems, so that their load doesn't delay the
+/// tasks of the main runtime, like block authoring.
+#[derive(Debug, Clone, Default)]
+pub struct DedicatedRuntimes {
+	/// Runtime of the tasks of the `block-import` group, which includes the import queue.
+	pub block_import: Option<tokio::runtime::Handle>,
+	/// Runtime of the RPC servers and of the tasks of the `rpc` group.
+	pub rpc: Option<tokio::runtime::Handle>,
+}
+
+impl DedicatedRuntimes {
+	/// The runtime dedicated to the tasks of `group`, if any.
+	pub fn for_group(&self, group: &str) -> Option<&tokio::runtime::Handle> {
+		match group {
+			"block-import" => self.block_import.as_ref(),
+			"rpc" => self.rpc.as_ref(),
+			_ => None,
+		}
+	}
+}
+
 /// Configuration of the database of the client.
 #[derive(Debug, Clone, Default)]
 pub struct OffchainWorkerConfig {
@@ -217,6 +255,7 @@ impl Configuration {
 			state_pruning: self.state_pruning.clone(),
 			source: self.database.clone(),
 			blocks_pruning: self.blocks_pruning,
+			write_rate_limit: self.database_write_rate_limit,
 		}
 	}
 }
@@ -321,6 +360,15 @@ pub struct RpcConfiguration {
 	pub rate_limit_whitelisted_ips: Vec<IpNetwork>,
 	/// RPC rate limit trust proxy headers.
 	pub rate_limit_trust_proxy_headers: bool,
+	/// Serve the last finalized block instead of the best block by default, and only notify
+	/// finalized blocks to the subscriptions of the legacy RPC API.
+	pub serve_finalized_only: bool,
+	/// Number of blocks on top of a watched transaction before it is reported in a block or
+	/// finalized.
+	pub transaction_confirmations: u32,
+	/// The duration a `chainHead_follow` subscription is kept after its stream ended, such
+	/// that it can be resumed. `None` if the subscriptions cannot be resumed.
+	pub chain_head_resume_grace_period: Option<Duration>,
 }
 
 /// Runtime executor configuration.
@@ -27,6 +27,7 @@ pub mod client;
 pub mod config;
 pub mod error;
 
+mod block_fullness;
 mod builder;
 mod metrics;
 mod task_manager;
@@ -60,9 +61,10 @@ pub use self::{
 		build_network_advanced, build_polkadot_syncing_strategy, gen_rpc_module, init_telemetry,
 		new_client, new_db_backend, new_full_client, new_full_parts, new_full_parts_record_import,
 		new_full_parts_with_genesis_builder, new_wasm_executor,
-		propagate_transaction_notifications, spawn_tasks, BuildNetworkAdvancedParams,
-		BuildNetworkParams, DefaultSyncingEngineConfig, KeystoreContainer, SpawnTasksParams,
-		TFullBackend, TFullCallExecutor, TFullClient,
+		propagate_transaction_notifications, spawn_tasks, wasm_executor_builder, BlockImportLayer,
+		BlockImportLayers, BuildNetworkAdvancedParams, BuildNetworkParams,
+		DefaultSyncingEngineConfig, KeystoreContainer, SpawnTasksParams, TFullBackend,
+		TFullCallExecutor, TFullClient, REPLACEABLE_RPC_METHODS,
 	},
 	client::{ClientConfig, LocalCallExecutor},
 	error::Error,
@@ -40,6 +40,9 @@ struct PrometheusMetrics {
 	// I/O
 	database_cache: Gauge<U64>,
 	state_cache: Gauge<U64>,
+
+	// memory
+	memory_breakdown: GaugeVec<U64>,
 }
 
 impl PrometheusMetrics {
@@ -113,6 +116,18 @@ impl PrometheusMetrics {
 				Gauge::new("substrate_state_cache_bytes", "State cache size in bytes")?,
 				registry,
 			)?,
+
+			// memory
+			memory_breakdown: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_memory_breakdown_bytes",
+						"Heap memory used by each subsystem of the node",
+					),
+					&["subsystem"],
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -248,6 +263,13 @@ impl MetricsService {
 				metrics.database_cache.set(info.memory.database_cache.as_bytes() as u64);
 				metrics.state_cache.set(info.memory.state_cache.as_bytes() as u64);
 			}
+
+			for (subsystem, bytes) in sc_memory_breakdown::breakdown().unwrap_or_default() {
+				metrics
+					.memory_breakdown
+					.with_label_values(&[subsystem.name()])
+					.set(bytes as u64);
+			}
 		}
 
 		// Update/send network status information, if any.
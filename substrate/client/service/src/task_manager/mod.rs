@@ -18,7 +18,10 @@
 
 //! Substrate service tasks management module.
 
-use crate::{config::TaskType, Error};
+use crate::{
+	config::{DedicatedRuntimes, TaskType},
+	Error,
+};
 use exit_future::Signal;
 use futures::{
 	future::{pending, select, try_join_all, BoxFuture, Either},
@@ -29,6 +32,7 @@ use prometheus_endpoint::{
 	exponential_buckets, register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError,
 	Registry, U64,
 };
+use sc_memory_breakdown::Subsystem;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use std::{
 	collections::{hash_map::Entry, HashMap},
@@ -78,6 +82,7 @@ impl From<&'static str> for GroupName {
 pub struct SpawnTaskHandle {
 	on_exit: exit_future::Exit,
 	tokio_handle: Handle,
+	dedicated_runtimes: DedicatedRuntimes,
 	metrics: Option<Metrics>,
 	task_registry: TaskRegistry,
 }
@@ -145,6 +150,9 @@ impl SpawnTaskHandle {
 				.inc_by(0);
 		}
 
+		// Attribute the memory allocated by the task to the subsystem of its group.
+		let task = sc_memory_breakdown::instrument(Subsystem::for_task_group(group), task);
+
 		let future = async move {
 			// Register the task and keep the "token" alive until the task is ended. Then this
 			// "token" will unregister this task.
@@ -194,13 +202,14 @@ impl SpawnTaskHandle {
 		}
 		.in_current_span();
 
+		let tokio_handle = self.dedicated_runtimes.for_group(group).unwrap_or(&self.tokio_handle);
 		match task_type {
 			TaskType::Async => {
-				self.tokio_handle.spawn(future);
+				tokio_handle.spawn(future);
 			},
 			TaskType::Blocking => {
-				let handle = self.tokio_handle.clone();
-				self.tokio_handle.spawn_blocking(move || {
+				let handle = tokio_handle.clone();
+				tokio_handle.spawn_blocking(move || {
 					handle.block_on(future);
 				});
 			},
@@ -279,7 +288,10 @@ impl SpawnEssentialTaskHandle {
 		task_type: TaskType,
 	) {
 		let essential_failed = self.essential_failed_tx.clone();
-		let essential_task = std::panic::AssertUnwindSafe(task).catch_unwind().map(move |_| {
+		let essential_task = std::panic::AssertUnwindSafe(task).catch_unwind().map(move |result| {
+			if result.is_err() {
+				sp_panic_handler::report_last_panic();
+			}
 			log::error!("Essential task `{}` failed. Shutting down service.", name);
 			let _ = essential_failed.close();
 		});
@@ -317,6 +329,8 @@ pub struct TaskManager {
 	_signal: Signal,
 	/// Tokio runtime handle that is used to spawn futures.
 	tokio_handle: Handle,
+	/// Tokio runtime handles used to spawn the futures of some groups instead of `tokio_handle`.
+	dedicated_runtimes: DedicatedRuntimes,
 	/// Prometheus metric where to report the polling times.
 	metrics: Option<Metrics>,
 	/// Send a signal when a spawned essential task has concluded. The next time
@@ -353,6 +367,7 @@ impl TaskManager {
 			on_exit,
 			_signal: signal,
 			tokio_handle,
+			dedicated_runtimes: Default::default(),
 			metrics,
 			essential_failed_tx,
 			essential_failed_rx,
@@ -362,11 +377,18 @@ impl TaskManager {
 		})
 	}
 
+	/// Spawn the tasks of some groups on the given dedicated runtimes instead of the main one.
+	pub fn with_dedicated_runtimes(mut self, dedicated_runtimes: DedicatedRuntimes) -> Self {
+		self.dedicated_runtimes = dedicated_runtimes;
+		self
+	}
+
 	/// Get a handle for spawning tasks.
 	pub fn spawn_handle(&self) -> SpawnTaskHandle {
 		SpawnTaskHandle {
 			on_exit: self.on_exit.clone(),
 			tokio_handle: self.tokio_handle.clone(),
+			dedicated_runtimes: self.dedicated_runtimes.clone(),
 			metrics: self.metrics.clone(),
 			task_registry: self.task_registry.clone(),
 		}
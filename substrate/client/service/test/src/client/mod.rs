@@ -21,8 +21,8 @@ use codec::{Decode, Encode, Joiner};
 use futures::executor::block_on;
 use sc_block_builder::BlockBuilderBuilder;
 use sc_client_api::{
-	in_mem, BlockBackend, BlockchainEvents, ExecutorProvider, FinalityNotifications, HeaderBackend,
-	StorageProvider,
+	in_mem, BlockBackend, BlockchainEvents, BlockchainEventsReplay, ExecutorProvider,
+	FinalityNotifications, HeaderBackend, StorageProvider,
 };
 use sc_client_db::{Backend, BlocksPruning, DatabaseSettings, DatabaseSource, PruningMode};
 use sc_consensus::{
@@ -40,7 +40,13 @@ use sp_runtime::{
 };
 use sp_state_machine::{backend::Backend as _, InMemoryBackend, OverlayedChanges, StateMachine};
 use sp_storage::{ChildInfo, StorageKey};
-use std::{collections::HashSet, sync::Arc};
+use std::{
+	collections::HashSet,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
 use substrate_test_runtime::TestAPI;
 use substrate_test_runtime_client::{
 	runtime::{
@@ -1041,6 +1047,52 @@ fn finality_target_with_best_not_on_longest_chain() {
 	assert_eq!(b4.hash(), block_on(chain_select.finality_target(b4.hash(), None)).unwrap());
 }
 
+#[test]
+fn notification_streams_replay_recent_notifications() {
+	// block tree:
+	// G -> A1 -> A2 -> A3
+	let client = substrate_test_runtime_client::new();
+
+	let mut parent = client.chain_info().genesis_hash;
+	let mut blocks = Vec::new();
+	for number in 0..3 {
+		let block = BlockBuilderBuilder::new(&client)
+			.on_parent_block(parent)
+			.with_parent_block_number(number)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap()
+			.block;
+		block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+		client.finalize_block(block.hash(), None).unwrap();
+		parent = block.hash();
+		blocks.push(block.hash());
+	}
+
+	let mut import_notifications = client.import_notification_stream_with_replay(2);
+	let mut finality_notifications = client.finality_notification_stream_with_replay(10);
+	assert_eq!(import_notifications.try_recv().unwrap().hash, blocks[1]);
+	assert_eq!(import_notifications.try_recv().unwrap().hash, blocks[2]);
+	assert!(matches!(import_notifications.try_recv().unwrap_err(), TryRecvError::Empty));
+	for hash in &blocks {
+		finality_notification_check(&mut finality_notifications, &[*hash], &[]);
+	}
+	assert!(matches!(finality_notifications.try_recv().unwrap_err(), TryRecvError::Empty));
+
+	// A3 -> A4
+	let a4 = BlockBuilderBuilder::new(&client)
+		.on_parent_block(parent)
+		.with_parent_block_number(3)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	block_on(client.import(BlockOrigin::Own, a4.clone())).unwrap();
+	assert_eq!(import_notifications.try_recv().unwrap().hash, a4.hash());
+}
+
 #[test]
 fn import_with_justification() {
 	// block tree:
@@ -1485,6 +1537,7 @@ fn doesnt_import_blocks_that_revert_finality() {
 				trie_cache_maximum_size: Some(1 << 20),
 				state_pruning: Some(PruningMode::ArchiveAll),
 				blocks_pruning: BlocksPruning::KeepAll,
+				write_rate_limit: None,
 				source: DatabaseSource::RocksDb { path: tmp.path().into(), cache_size: 1024 },
 			},
 			u64::MAX,
@@ -1762,6 +1815,7 @@ fn returns_status_for_pruned_blocks() {
 				trie_cache_maximum_size: Some(1 << 20),
 				state_pruning: Some(PruningMode::blocks_pruning(1)),
 				blocks_pruning: BlocksPruning::KeepFinalized,
+				write_rate_limit: None,
 				source: DatabaseSource::RocksDb { path: tmp.path().into(), cache_size: 1024 },
 			},
 			u64::MAX,
@@ -1975,6 +2029,31 @@ fn storage_keys_prefix_and_start_key_works() {
 	assert_eq!(res, [b"third".to_vec()]);
 }
 
+#[test]
+fn storage_snapshot_works() {
+	let child_info = ChildInfo::new_default(b"child");
+	let client = TestClientBuilder::new()
+		.add_extra_child_storage(&child_info, b"first".to_vec(), vec![1u8; 32])
+		.add_extra_child_storage(&child_info, b"second".to_vec(), vec![2u8; 32])
+		.build();
+
+	let block_hash = client.info().best_hash;
+	let snapshot = client.storage_snapshot(block_hash).unwrap();
+
+	let keys = [StorageKey(b":code".to_vec()), StorageKey(b"missing".to_vec())];
+	let values = snapshot.storage_batch(&keys).unwrap();
+	assert_eq!(values, vec![client.storage(block_hash, &keys[0]).unwrap(), None]);
+	assert_eq!(
+		snapshot.storage_hash(&keys[0]).unwrap(),
+		client.storage_hash(block_hash, &keys[0]).unwrap(),
+	);
+
+	let second = StorageKey(b"second".to_vec());
+	assert_eq!(snapshot.child_storage(&child_info, &second).unwrap().unwrap().0, vec![2u8; 32]);
+	let res: Vec<_> = snapshot.keys(Some(&child_info), None, None).unwrap().map(|x| x.0).collect();
+	assert_eq!(res, [b"first".to_vec(), b"second".to_vec()]);
+}
+
 #[test]
 fn storage_keys_works() {
 	sp_tracing::try_init_simple();
@@ -2307,3 +2386,71 @@ fn finalize_after_best_block_updates_best() {
 	assert_eq!(client.chain_info().finalized_hash, a3.hash());
 	assert_eq!(client.chain_info().best_hash, a3.hash());
 }
+
+#[test]
+fn block_import_layers_veto_blocks() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let checks = Arc::new(AtomicUsize::new(0));
+	let veto_checks = checks.clone();
+	let block_import = sc_service::BlockImportLayers::new()
+		.with_veto(
+			"test",
+			move |header: &Header, _body: Option<&[<Block as BlockT>::Extrinsic]>| {
+				veto_checks.fetch_add(1, Ordering::Relaxed);
+				if *header.number() == 2 {
+					Err("no block 2".into())
+				} else {
+					Ok(())
+				}
+			},
+		)
+		.apply(client.clone());
+
+	let mut parent = (client.chain_info().genesis_hash, 0);
+	let mut blocks = Vec::new();
+	let mut results = Vec::new();
+	for _ in 0..2 {
+		let block = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(parent.0)
+			.with_parent_block_number(parent.1)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap()
+			.block;
+		parent = (block.hash(), parent.1 + 1);
+		blocks.push(block.clone());
+		let (header, extrinsics) = block.deconstruct();
+		let mut import_params = BlockImportParams::new(BlockOrigin::Own, header);
+		import_params.body = Some(extrinsics);
+		import_params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+		results.push(block_on(block_import.import_block(import_params)));
+	}
+
+	assert!(matches!(results[0], Ok(ImportResult::Imported(_))));
+	assert!(matches!(results[1], Err(ConsensusError::ImportVetoed(_))));
+	assert_eq!(client.chain_info().best_number, 1);
+	assert_eq!(checks.load(Ordering::Relaxed), 2);
+
+	// The vetoed block and its descendants are rejected without consulting the veto again.
+	let (header, extrinsics) = blocks[1].clone().deconstruct();
+	let mut import_params = BlockImportParams::new(BlockOrigin::NetworkBroadcast, header);
+	import_params.body = Some(extrinsics);
+	assert!(matches!(
+		block_on(block_import.import_block(import_params)),
+		Err(ConsensusError::ImportVetoed(_))
+	));
+	let check_params = BlockCheckParams {
+		hash: H256::repeat_byte(3),
+		number: 3,
+		parent_hash: blocks[1].hash(),
+		allow_missing_state: false,
+		allow_missing_parent: false,
+		import_existing: false,
+	};
+	assert!(matches!(
+		block_on(block_import.check_block(check_params)),
+		Err(ConsensusError::ImportVetoed(_))
+	));
+	assert_eq!(checks.load(Ordering::Relaxed), 2);
+}
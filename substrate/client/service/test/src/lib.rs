@@ -230,16 +230,24 @@ fn node_config<E: ChainSpecExtension + Clone + 'static + Send + Sync>(
 		impl_version: String::from("0.1"),
 		role,
 		tokio_handle,
+		dedicated_runtimes: Default::default(),
 		transaction_pool: Default::default(),
 		network: network_config,
-		keystore: KeystoreConfig::Path { path: root.join("key"), password: None },
+		keystore: KeystoreConfig::Path {
+			path: root.join("key"),
+			password: None,
+			encryption: None,
+			enclave: None,
+		},
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
 		trie_cache_maximum_size: Some(16 * 1024 * 1024),
 		state_pruning: Default::default(),
 		blocks_pruning: BlocksPruning::KeepFinalized,
+		database_write_rate_limit: None,
 		chain_spec: Box::new((*spec).clone()),
 		executor: ExecutorConfiguration::default(),
 		wasm_runtime_overrides: Default::default(),
+		import_queue: Default::default(),
 		rpc: RpcConfiguration {
 			addr: Default::default(),
 			max_connections: Default::default(),
@@ -255,6 +263,9 @@ fn node_config<E: ChainSpecExtension + Clone + 'static + Send + Sync>(
 			rate_limit: None,
 			rate_limit_whitelisted_ips: Default::default(),
 			rate_limit_trust_proxy_headers: Default::default(),
+			serve_finalized_only: false,
+			transaction_confirmations: 0,
+			chain_head_resume_grace_period: None,
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -265,6 +276,7 @@ fn node_config<E: ChainSpecExtension + Clone + 'static + Send + Sync>(
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
 		announce_block: true,
+		informant: Default::default(),
 		base_path: BasePath::new(root.clone()),
 		data_path: root,
 	}
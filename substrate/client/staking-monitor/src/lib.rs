@@ -0,0 +1,52 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Staking monitor service.
+//!
+//! Watches finalized blocks for offence and slash events that affect a configured set of stashes
+//! and reports them through logs, telemetry and Prometheus, so operators learn about slashes from
+//! their own node as soon as they are final.
+//!
+//! Optionally, the [`PayoutService`] detects unclaimed era rewards of configured validators and
+//! nominators and submits the payouts itself, replacing external cron scripts.
+//!
+//! The events are read from the raw `System::Events` storage of every finalized block. Decoding
+//! them is runtime specific, so the node has to provide an [`EventDecoder`] that turns the raw
+//! events into [`StakingEvent`]s.
+
+mod metrics;
+mod offences;
+mod payout;
+
+pub use offences::{EventDecoder, OffenceMonitor, StakingEvent};
+pub use payout::{PayoutExtrinsicBuilder, PayoutParams, PayoutService, PendingPayout};
+
+use clap::Args;
+use sp_core::crypto::AccountId32;
+
+const LOG_TARGET: &str = "staking-monitor";
+
+/// Parameters used to create the staking monitor.
+#[derive(Default, Debug, Clone, Args)]
+pub struct StakingMonitorParams {
+	/// Stash account to watch for offences and slashes.
+	///
+	/// Can be passed multiple times. If no stash is given monitoring is disabled.
+	#[arg(long = "monitor-stash", value_name = "SS58")]
+	pub stashes: Vec<AccountId32>,
+}
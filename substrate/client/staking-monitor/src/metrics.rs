@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the staking monitor.
+
+use prometheus_endpoint::{
+	prometheus::CounterVec, register, Counter, Gauge, Opts, PrometheusError, Registry, U64,
+};
+
+/// Staking monitor metrics.
+#[derive(Clone)]
+pub(crate) struct Metrics {
+	pub offences: CounterVec,
+	pub slashes: CounterVec,
+	pub slashed_amount: CounterVec,
+	pub last_processed_block: Gauge<U64>,
+}
+
+impl Metrics {
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			offences: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_staking_monitor_offences_total",
+						"Number of offences reported against a monitored stash",
+					),
+					&["stash"],
+				)?,
+				registry,
+			)?,
+			slashes: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_staking_monitor_slashes_total",
+						"Number of slashes applied to a monitored stash",
+					),
+					&["stash"],
+				)?,
+				registry,
+			)?,
+			slashed_amount: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_staking_monitor_slashed_amount_total",
+						"Total amount slashed from a monitored stash, in plancks",
+					),
+					&["stash"],
+				)?,
+				registry,
+			)?,
+			last_processed_block: register(
+				Gauge::new(
+					"substrate_staking_monitor_last_processed_block",
+					"Number of the last finalized block inspected by the staking monitor",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Payout service metrics.
+#[derive(Clone)]
+pub(crate) struct PayoutMetrics {
+	pub submitted: Counter<U64>,
+	pub skipped: Counter<U64>,
+	pub failed: Counter<U64>,
+}
+
+impl PayoutMetrics {
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			submitted: register(
+				Counter::new(
+					"substrate_staking_payouts_submitted_total",
+					"Number of era payouts submitted by the payout service",
+				)?,
+				registry,
+			)?,
+			skipped: register(
+				Counter::new(
+					"substrate_staking_payouts_skipped_total",
+					"Number of payout transactions skipped because of the fee limit",
+				)?,
+				registry,
+			)?,
+			failed: register(
+				Counter::new(
+					"substrate_staking_payouts_failed_total",
+					"Number of payout transactions rejected by the transaction pool",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
@@ -0,0 +1,273 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Detection and reporting of offences and slashes.
+
+use crate::{metrics::Metrics, StakingMonitorParams, LOG_TARGET};
+use futures::StreamExt;
+use prometheus_endpoint::Registry;
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
+use sp_core::{crypto::AccountId32, storage::StorageKey, traits::SpawnNamed};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	SaturatedConversion,
+};
+use std::{collections::BTreeSet, marker::PhantomData, sync::Arc};
+
+/// A staking related event extracted from the events of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StakingEvent {
+	/// An offence of the given kind was reported against `offender`.
+	Offence {
+		/// The offending stash.
+		offender: AccountId32,
+		/// Human readable kind of the offence, e.g. `im-online:offlin` or `grandpa:equivocation`.
+		kind: String,
+	},
+	/// `stash` was slashed by `amount`.
+	Slashed {
+		/// The slashed stash.
+		stash: AccountId32,
+		/// The slashed amount.
+		amount: u128,
+	},
+}
+
+impl StakingEvent {
+	/// The stash this event is about.
+	pub fn stash(&self) -> &AccountId32 {
+		match self {
+			Self::Offence { offender, .. } => offender,
+			Self::Slashed { stash, .. } => stash,
+		}
+	}
+}
+
+/// Decodes the raw, SCALE encoded `System::Events` storage value into [`StakingEvent`]s.
+///
+/// Implemented for closures, so a node can simply pass
+/// `|events: &[u8]| { /* decode `Vec<EventRecord<RuntimeEvent, Hash>>` */ }`.
+pub trait EventDecoder: Send + Sync + 'static {
+	/// Extract all offence and slash events found in `events`.
+	fn decode(&self, events: &[u8]) -> Result<Vec<StakingEvent>, codec::Error>;
+}
+
+impl<F> EventDecoder for F
+where
+	F: Fn(&[u8]) -> Result<Vec<StakingEvent>, codec::Error> + Send + Sync + 'static,
+{
+	fn decode(&self, events: &[u8]) -> Result<Vec<StakingEvent>, codec::Error> {
+		(self)(events)
+	}
+}
+
+/// Storage key of `System::Events`.
+fn system_events_key() -> StorageKey {
+	let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+	key.extend(sp_crypto_hashing::twox_128(b"Events"));
+	StorageKey(key)
+}
+
+/// Offence monitor: inspects the events of every finalized block for the configured stashes.
+pub struct OffenceMonitor<Block, BE, Client, D> {
+	client: Arc<Client>,
+	decoder: D,
+	stashes: BTreeSet<AccountId32>,
+	metrics: Option<Metrics>,
+	telemetry: Option<TelemetryHandle>,
+	_phantom: PhantomData<(Block, BE)>,
+}
+
+impl<Block, BE, Client, D> OffenceMonitor<Block, BE, Client, D>
+where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	Client: BlockchainEvents<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+	D: EventDecoder,
+{
+	/// Spawns the offence monitor, unless no stash is configured.
+	pub fn spawn(
+		params: StakingMonitorParams,
+		client: Arc<Client>,
+		decoder: D,
+		registry: Option<&Registry>,
+		telemetry: Option<TelemetryHandle>,
+		spawner: &impl SpawnNamed,
+	) {
+		if params.stashes.is_empty() {
+			log::debug!(target: LOG_TARGET, "No stash configured, offence monitoring disabled");
+			return
+		}
+
+		let metrics = registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|err| {
+					log::warn!(
+						target: LOG_TARGET,
+						"Failed to register staking monitor prometheus metrics: {}",
+						err,
+					)
+				})
+				.ok()
+		});
+
+		log::info!(
+			target: LOG_TARGET,
+			"👮 Monitoring {} stash(es) for offences and slashes",
+			params.stashes.len(),
+		);
+
+		let monitor = Self {
+			client,
+			decoder,
+			stashes: params.stashes.into_iter().collect(),
+			metrics,
+			telemetry,
+			_phantom: PhantomData,
+		};
+
+		spawner.spawn("staking-offence-monitor", None, Box::pin(monitor.run()));
+	}
+
+	/// Main loop, processing every finalized block exactly once.
+	async fn run(self) {
+		let mut finality_stream = self.client.finality_notification_stream();
+
+		while let Some(notification) = finality_stream.next().await {
+			for hash in notification.tree_route.iter().chain(std::iter::once(&notification.hash)) {
+				self.process_block(*hash);
+			}
+
+			if let Some(metrics) = &self.metrics {
+				let number: u64 = (*notification.header.number()).saturated_into();
+				metrics.last_processed_block.set(number);
+			}
+		}
+	}
+
+	/// Reports all relevant events of the block with the given `hash`.
+	fn process_block(&self, hash: Block::Hash) {
+		let events = match self.client.storage(hash, &system_events_key()) {
+			Ok(Some(events)) => events,
+			Ok(None) => return,
+			Err(err) => {
+				log::debug!(target: LOG_TARGET, "Failed to read events of {hash:?}: {err}");
+				return
+			},
+		};
+
+		let events = match self.decoder.decode(&events.0) {
+			Ok(events) => events,
+			Err(err) => {
+				log::warn!(target: LOG_TARGET, "Failed to decode events of {hash:?}: {err}");
+				return
+			},
+		};
+
+		for event in relevant_events(&self.stashes, events) {
+			self.report(hash, &event);
+		}
+	}
+
+	fn report(&self, hash: Block::Hash, event: &StakingEvent) {
+		let stash = event.stash().to_string();
+		match event {
+			StakingEvent::Offence { kind, .. } => {
+				log::warn!(
+					target: LOG_TARGET,
+					"🚨 Offence `{kind}` reported against {stash} in block {hash:?}",
+				);
+				telemetry!(
+					self.telemetry;
+					SUBSTRATE_INFO;
+					"staking.offence";
+					"stash" => &stash,
+					"kind" => kind,
+					"block" => ?hash,
+				);
+				if let Some(metrics) = &self.metrics {
+					metrics.offences.with_label_values(&[&stash]).inc();
+				}
+			},
+			StakingEvent::Slashed { amount, .. } => {
+				log::warn!(
+					target: LOG_TARGET,
+					"🚨 Stash {stash} was slashed by {amount} in block {hash:?}",
+				);
+				telemetry!(
+					self.telemetry;
+					SUBSTRATE_INFO;
+					"staking.slashed";
+					"stash" => &stash,
+					"amount" => amount,
+					"block" => ?hash,
+				);
+				if let Some(metrics) = &self.metrics {
+					metrics.slashes.with_label_values(&[&stash]).inc();
+					metrics.slashed_amount.with_label_values(&[&stash]).inc_by(*amount as f64);
+				}
+			},
+		}
+	}
+}
+
+/// Keeps only the events concerning one of the given `stashes`.
+fn relevant_events(
+	stashes: &BTreeSet<AccountId32>,
+	events: Vec<StakingEvent>,
+) -> impl Iterator<Item = StakingEvent> + '_ {
+	events.into_iter().filter(move |event| stashes.contains(event.stash()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn account(byte: u8) -> AccountId32 {
+		AccountId32::new([byte; 32])
+	}
+
+	#[test]
+	fn only_monitored_stashes_are_reported() {
+		let stashes = [account(1), account(2)].into_iter().collect();
+		let events = vec![
+			StakingEvent::Offence { offender: account(1), kind: "babe:equivocation".into() },
+			StakingEvent::Slashed { stash: account(3), amount: 10 },
+			StakingEvent::Slashed { stash: account(2), amount: 20 },
+		];
+
+		let relevant = relevant_events(&stashes, events).collect::<Vec<_>>();
+
+		assert_eq!(
+			relevant,
+			vec![
+				StakingEvent::Offence { offender: account(1), kind: "babe:equivocation".into() },
+				StakingEvent::Slashed { stash: account(2), amount: 20 },
+			]
+		);
+	}
+
+	#[test]
+	fn system_events_key_is_correct() {
+		assert_eq!(
+			sp_core::hexdisplay::HexDisplay::from(&system_events_key().0).to_string(),
+			"26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7",
+		);
+	}
+}
@@ -0,0 +1,384 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Automatic payout of unclaimed staking rewards.
+
+use crate::{metrics::PayoutMetrics, LOG_TARGET};
+use clap::Args;
+use codec::{Decode, Encode};
+use frame_system_rpc_runtime_api::AccountNonceApi;
+use futures::StreamExt;
+use pallet_staking_runtime_api::StakingApi;
+use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi;
+use prometheus_endpoint::Registry;
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TransactionSource};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{crypto::AccountId32, storage::StorageKey, traits::SpawnNamed};
+use sp_runtime::traits::Block as BlockT;
+use sp_staking::EraIndex;
+use std::{collections::BTreeSet, marker::PhantomData, sync::Arc};
+
+/// Parameters of the payout service.
+#[derive(Debug, Clone, Args)]
+pub struct PayoutParams {
+	/// Validator stash whose unclaimed rewards should be paid out automatically.
+	///
+	/// Can be passed multiple times. If neither a validator nor a nominator stash is given the
+	/// payout service is disabled.
+	#[arg(long = "payout-stash", value_name = "SS58")]
+	pub stashes: Vec<AccountId32>,
+
+	/// Nominator stash whose unclaimed rewards should be paid out automatically.
+	///
+	/// Rewards are paid out for the validators currently nominated by the stash. Can be passed
+	/// multiple times.
+	#[arg(long = "payout-nominator", value_name = "SS58")]
+	pub nominators: Vec<AccountId32>,
+
+	/// Maximum fee, in plancks, the node is allowed to pay for a single payout transaction.
+	///
+	/// Payout transactions estimated above this limit are not submitted.
+	#[arg(long = "payout-max-fee", value_name = "PLANCKS", default_value_t = u128::MAX)]
+	pub max_fee: u128,
+
+	/// Maximum number of `payout_stakers` calls batched into a single transaction.
+	#[arg(long = "payout-batch-size", value_name = "COUNT", default_value_t = 8, value_parser = clap::value_parser!(u32).range(1..))]
+	pub batch_size: u32,
+
+	/// Number of past eras inspected for unclaimed rewards.
+	///
+	/// Should match the `HistoryDepth` of the staking pallet.
+	#[arg(long = "payout-history-depth", value_name = "ERAS", default_value_t = 84)]
+	pub history_depth: u32,
+}
+
+impl Default for PayoutParams {
+	fn default() -> Self {
+		Self {
+			stashes: Vec::new(),
+			nominators: Vec::new(),
+			max_fee: u128::MAX,
+			batch_size: 8,
+			history_depth: 84,
+		}
+	}
+}
+
+/// A single unclaimed reward: the next unclaimed page of `stash` in `era`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingPayout {
+	/// The validator stash.
+	pub stash: AccountId32,
+	/// The era in which the rewards were earned.
+	pub era: EraIndex,
+}
+
+/// Builds the signed transaction paying out a batch of [`PendingPayout`]s.
+///
+/// Signing and the transaction extensions are runtime specific, so this is provided by the node.
+/// The usual implementation wraps one `Staking::payout_stakers` call per payout into a
+/// `Utility::batch_all` signed by a local account.
+pub trait PayoutExtrinsicBuilder<Block: BlockT>: Send + Sync + 'static {
+	/// The account signing the payout transactions.
+	fn signer(&self) -> AccountId32;
+
+	/// Build a transaction on top of `at`, signed with `nonce`, paying out all `payouts`.
+	fn build(
+		&self,
+		at: Block::Hash,
+		nonce: u32,
+		payouts: &[PendingPayout],
+	) -> Result<Block::Extrinsic, String>;
+}
+
+/// Storage key of `Staking::ActiveEra`.
+fn active_era_key() -> StorageKey {
+	let mut key = sp_crypto_hashing::twox_128(b"Staking").to_vec();
+	key.extend(sp_crypto_hashing::twox_128(b"ActiveEra"));
+	StorageKey(key)
+}
+
+/// Storage key of `Staking::Nominators` for `stash`.
+fn nominators_key(stash: &AccountId32) -> StorageKey {
+	let mut key = sp_crypto_hashing::twox_128(b"Staking").to_vec();
+	key.extend(sp_crypto_hashing::twox_128(b"Nominators"));
+	key.extend(sp_crypto_hashing::twox_64(stash.as_ref()));
+	key.extend(stash.encode());
+	StorageKey(key)
+}
+
+/// Payout service: once per era, submits payouts for all unclaimed rewards of the configured
+/// validators and of the validators nominated by the configured nominators.
+pub struct PayoutService<Block: BlockT, BE, Client, Pool, B> {
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+	builder: B,
+	params: PayoutParams,
+	metrics: Option<PayoutMetrics>,
+	_phantom: PhantomData<(Block, BE)>,
+}
+
+impl<Block, BE, Client, Pool, B> PayoutService<Block, BE, Client, Pool, B>
+where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	Client: BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: StakingApi<Block, u128, AccountId32>
+		+ TransactionPaymentApi<Block, u128>
+		+ AccountNonceApi<Block, AccountId32, u32>,
+	Pool: TransactionPool<Block = Block> + 'static,
+	B: PayoutExtrinsicBuilder<Block>,
+{
+	/// Spawns the payout service, unless no stash is configured.
+	pub fn spawn(
+		params: PayoutParams,
+		client: Arc<Client>,
+		pool: Arc<Pool>,
+		builder: B,
+		registry: Option<&Registry>,
+		spawner: &impl SpawnNamed,
+	) {
+		if params.stashes.is_empty() && params.nominators.is_empty() {
+			log::debug!(target: LOG_TARGET, "No stash configured, automatic payouts disabled");
+			return
+		}
+
+		let metrics = registry.and_then(|registry| {
+			PayoutMetrics::register(registry)
+				.map_err(|err| {
+					log::warn!(
+						target: LOG_TARGET,
+						"Failed to register payout prometheus metrics: {}",
+						err,
+					)
+				})
+				.ok()
+		});
+
+		log::info!(
+			target: LOG_TARGET,
+			"💰 Automatic payouts enabled for {} validator and {} nominator stash(es)",
+			params.stashes.len(),
+			params.nominators.len(),
+		);
+
+		let service =
+			Self { client, pool, builder, params, metrics, _phantom: PhantomData };
+
+		spawner.spawn("staking-payout", None, Box::pin(service.run()));
+	}
+
+	/// Main loop: checks for unclaimed rewards at start and whenever a new era becomes active.
+	async fn run(self) {
+		let mut finality_stream = self.client.finality_notification_stream();
+		let mut last_era = None;
+
+		while let Some(notification) = finality_stream.next().await {
+			let Some(active_era) = self.active_era(notification.hash) else { continue };
+			if last_era == Some(active_era) {
+				continue
+			}
+			last_era = Some(active_era);
+
+			let pending = self.pending_payouts(notification.hash, active_era);
+			if pending.is_empty() {
+				continue
+			}
+
+			log::info!(
+				target: LOG_TARGET,
+				"💰 Found {} unclaimed payout(s) at era {active_era}",
+				pending.len(),
+			);
+
+			let mut nonce = match self.next_nonce(notification.hash) {
+				Ok(nonce) => nonce,
+				Err(err) => {
+					log::warn!(target: LOG_TARGET, "Failed to query the payout signer nonce: {err}");
+					continue
+				},
+			};
+			for batch in pending.chunks(self.params.batch_size as usize) {
+				if self.submit(notification.hash, nonce, batch).await {
+					nonce += 1;
+				}
+			}
+		}
+	}
+
+	/// Returns the next nonce of the signer at `at`, taking into account the transactions of the
+	/// signer that are already ready in the pool.
+	fn next_nonce(&self, at: Block::Hash) -> Result<u32, sp_api::ApiError> {
+		let signer = self.builder.signer();
+		let mut nonce = self.client.runtime_api().account_nonce(at, signer.clone())?;
+
+		// Ready transactions are ordered by nonce, so a single pass is enough.
+		let mut tag = (signer.clone(), nonce).encode();
+		for tx in self.pool.ready() {
+			if tx.provides().first() == Some(&tag) {
+				nonce += 1;
+				tag = (signer.clone(), nonce).encode();
+			}
+		}
+
+		Ok(nonce)
+	}
+
+	/// Returns the validators to pay out: the configured validator stashes and the validators
+	/// nominated by the configured nominator stashes at `at`.
+	fn validators(&self, at: Block::Hash) -> BTreeSet<AccountId32> {
+		let mut validators = self.params.stashes.iter().cloned().collect::<BTreeSet<_>>();
+		for nominator in &self.params.nominators {
+			let targets = match self.client.storage(at, &nominators_key(nominator)) {
+				// `Nominations` starts with the targets.
+				Ok(Some(raw)) => Vec::<AccountId32>::decode(&mut &raw.0[..]).unwrap_or_default(),
+				Ok(None) => {
+					log::debug!(target: LOG_TARGET, "{nominator} is not nominating");
+					continue
+				},
+				Err(err) => {
+					log::debug!(
+						target: LOG_TARGET,
+						"Failed to read the nominations of {nominator}: {err}",
+					);
+					continue
+				},
+			};
+			validators.extend(targets);
+		}
+		validators
+	}
+
+	/// Reads the index of the active era at `at`.
+	fn active_era(&self, at: Block::Hash) -> Option<EraIndex> {
+		let raw = self.client.storage(at, &active_era_key()).ok()??;
+		// `ActiveEraInfo` starts with the era index.
+		EraIndex::decode(&mut &raw.0[..]).ok()
+	}
+
+	/// Collects all unclaimed rewards of the validators to pay out within the history depth.
+	fn pending_payouts(&self, at: Block::Hash, active_era: EraIndex) -> Vec<PendingPayout> {
+		let api = self.client.runtime_api();
+		let first_era = active_era.saturating_sub(self.params.history_depth);
+
+		let mut pending = Vec::new();
+		for stash in &self.validators(at) {
+			for era in first_era..active_era {
+				match api.pending_rewards(at, era, stash.clone()) {
+					Ok(true) => pending.push(PendingPayout { stash: stash.clone(), era }),
+					Ok(false) => {},
+					Err(err) => {
+						log::debug!(
+							target: LOG_TARGET,
+							"Failed to query pending rewards of {stash} in era {era}: {err}",
+						);
+					},
+				}
+			}
+		}
+
+		pending
+	}
+
+	/// Builds, prices and submits a single payout transaction signed with `nonce`.
+	///
+	/// Returns whether the transaction was accepted by the pool, i.e. whether `nonce` was used.
+	async fn submit(&self, at: Block::Hash, nonce: u32, batch: &[PendingPayout]) -> bool {
+		let xt = match self.builder.build(at, nonce, batch) {
+			Ok(xt) => xt,
+			Err(err) => {
+				log::warn!(target: LOG_TARGET, "Failed to build payout transaction: {err}");
+				return false
+			},
+		};
+
+		let fee =
+			match self.client.runtime_api().query_info(at, xt.clone(), xt.encoded_size() as u32) {
+				Ok(info) => info.partial_fee,
+				Err(err) => {
+					log::warn!(target: LOG_TARGET, "Failed to estimate payout fee: {err}");
+					return false
+				},
+			};
+
+		if fee > self.params.max_fee {
+			log::warn!(
+				target: LOG_TARGET,
+				"Skipping payout of {} reward(s): fee {fee} exceeds limit {}",
+				batch.len(),
+				self.params.max_fee,
+			);
+			if let Some(metrics) = &self.metrics {
+				metrics.skipped.inc();
+			}
+			return false
+		}
+
+		match self.pool.submit_one(at, TransactionSource::Local, xt).await {
+			Ok(hash) => {
+				log::info!(
+					target: LOG_TARGET,
+					"💰 Submitted payout of {} reward(s) with fee {fee}: {hash:?}",
+					batch.len(),
+				);
+				if let Some(metrics) = &self.metrics {
+					metrics.submitted.inc_by(batch.len() as u64);
+				}
+				true
+			},
+			Err(err) => {
+				log::warn!(target: LOG_TARGET, "Failed to submit payout transaction: {err}");
+				if let Some(metrics) = &self.metrics {
+					metrics.failed.inc();
+				}
+				false
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn active_era_key_is_correct() {
+		assert_eq!(
+			sp_core::hexdisplay::HexDisplay::from(&active_era_key().0).to_string(),
+			"5f3e4907f716ac89b6347d15ececedca487df464e44a534ba6b0cbb32407b587",
+		);
+	}
+
+	#[test]
+	fn nominators_key_is_correct() {
+		let stash = AccountId32::new([1; 32]);
+		let key = nominators_key(&stash).0;
+
+		assert_eq!(
+			sp_core::hexdisplay::HexDisplay::from(&key[..32].to_vec()).to_string(),
+			"5f3e4907f716ac89b6347d15ececedca9c6a637f62ae2af1c7e31eed7e96be04",
+		);
+		assert_eq!(&key[40..], stash.as_ref());
+	}
+}
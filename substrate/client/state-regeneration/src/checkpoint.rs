@@ -0,0 +1,510 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Checkpoints of the state, stored as files containing trie nodes of the state.
+//!
+//! A full checkpoint contains all the trie nodes of the state, an incremental checkpoint only the
+//! trie nodes of the state which are not in the state of the previous checkpoint. The state of an
+//! incremental checkpoint is restored from the last full checkpoint before it and all the
+//! incremental checkpoints following that one.
+//!
+//! The trie nodes are written in batches of [`BATCH_KEYS`] keys, which bounds the memory used to
+//! write a checkpoint.
+
+use codec::{Decode, Encode};
+use hash_db::{HashDB, EMPTY_PREFIX};
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{well_known_keys, ChildInfo, ChildType, PrefixedStorageKey},
+};
+use sp_runtime::traits::{Block as BlockT, HashingFor};
+use sp_state_machine::{
+	backend::AsTrieBackend, Backend, IterArgs, TrieBackend, TrieBackendBuilder, TrieBackendStorage,
+};
+use sp_trie::{cache::LocalTrieCache, recorder::Recorder, MemoryDB, StorageProof};
+use std::{
+	cmp::Ordering,
+	fs::{self, File},
+	io::{BufWriter, Write},
+	marker::PhantomData,
+	path::{Path, PathBuf},
+};
+
+/// Extension of the full checkpoint files.
+const FULL_EXTENSION: &str = "nodes";
+
+/// Extension of the incremental checkpoint files.
+const INCREMENTAL_EXTENSION: &str = "delta";
+
+/// Number of keys read per batch while writing a checkpoint.
+const BATCH_KEYS: usize = 10_000;
+
+/// A key of the state: the key of an entry of the top trie or of a child trie.
+type StateKey = (Option<ChildInfo>, Vec<u8>);
+
+/// The trie of a state.
+type Trie<S, Block> = TrieBackend<S, HashingFor<Block>, LocalTrieCache<HashingFor<Block>>>;
+
+/// A [`Trie`] recording the trie nodes it reads.
+type RecordingTrie<'a, S, Block> =
+	TrieBackend<&'a S, HashingFor<Block>, &'a LocalTrieCache<HashingFor<Block>>>;
+
+/// A checkpoint of the state of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Checkpoint {
+	/// Number of the block.
+	pub number: u64,
+	/// Hex encoded hash of the block.
+	hash: String,
+	/// Whether the checkpoint contains all the trie nodes of the state.
+	pub full: bool,
+}
+
+impl Checkpoint {
+	fn new<Hash: AsRef<[u8]>>(number: u64, hash: &Hash, full: bool) -> Self {
+		Self { number, hash: format!("{}", HexDisplay::from(hash)), full }
+	}
+
+	/// Whether the checkpoint is the one of the block `hash`.
+	pub fn is_of<Hash: AsRef<[u8]>>(&self, hash: &Hash) -> bool {
+		self.hash == format!("{}", HexDisplay::from(hash))
+	}
+
+	fn file_name(&self) -> String {
+		let extension = if self.full { FULL_EXTENSION } else { INCREMENTAL_EXTENSION };
+		format!("{}-{}.{extension}", self.number, self.hash)
+	}
+
+	fn from_file_name(file_name: &str) -> Option<Self> {
+		let (stem, extension) = file_name.rsplit_once('.')?;
+		let full = match extension {
+			FULL_EXTENSION => true,
+			INCREMENTAL_EXTENSION => false,
+			_ => return None,
+		};
+		let (number, hash) = stem.split_once('-')?;
+		Some(Self { number: number.parse().ok()?, hash: hash.into(), full })
+	}
+}
+
+/// The checkpoints stored in a directory.
+pub(crate) struct Checkpoints<Block> {
+	directory: PathBuf,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block: BlockT> Checkpoints<Block> {
+	/// Open the checkpoints stored in `directory`, creating it if needed.
+	pub fn open(directory: PathBuf) -> Result<Self, String> {
+		fs::create_dir_all(&directory)
+			.map_err(|e| format!("Failed to create {}: {e}", directory.display()))?;
+		Ok(Self { directory, _phantom: PhantomData })
+	}
+
+	pub fn directory(&self) -> &Path {
+		&self.directory
+	}
+
+	/// The stored checkpoints, ordered by block number.
+	pub fn list(&self) -> Result<Vec<Checkpoint>, String> {
+		let entries = fs::read_dir(&self.directory)
+			.map_err(|e| format!("Failed to read {}: {e}", self.directory.display()))?;
+		let mut checkpoints = Vec::new();
+		for entry in entries {
+			let entry = entry.map_err(|e| e.to_string())?;
+			if let Some(checkpoint) = entry.file_name().to_str().and_then(Checkpoint::from_file_name)
+			{
+				checkpoints.push(checkpoint);
+			}
+		}
+		checkpoints.sort_by_key(|checkpoint| checkpoint.number);
+		Ok(checkpoints)
+	}
+
+	/// Write a checkpoint of `state`, the state of the block `hash`.
+	///
+	/// The checkpoint is incremental if `previous`, the state of the last checkpoint, is given,
+	/// and full otherwise.
+	pub fn write<S: AsTrieBackend<HashingFor<Block>>>(
+		&self,
+		number: u64,
+		hash: Block::Hash,
+		state: &S,
+		previous: Option<&S>,
+	) -> Result<Checkpoint, String> {
+		let checkpoint = Checkpoint::new(number, &hash, previous.is_none());
+		let mut file = NodesFile::create(self.directory.join(checkpoint.file_name()))?;
+		let state = state.as_trie_backend();
+		match previous {
+			Some(previous) => {
+				let keys = Self::changed_keys(previous.as_trie_backend(), state)?;
+				for batch in keys.chunks(BATCH_KEYS) {
+					file.append(Self::record(state, |backend| {
+						for (child_info, key) in batch {
+							match child_info {
+								Some(child_info) => backend.child_storage(child_info, key)?,
+								None => backend.storage(key)?,
+							};
+						}
+						Ok(())
+					})?)?;
+				}
+			},
+			None => {
+				let mut child_infos = Vec::new();
+				Self::record_all(state, None, &mut file, |key| {
+					if let Some(child_info) = child_info(key) {
+						child_infos.push(child_info);
+					}
+				})?;
+				for child_info in child_infos {
+					Self::record_all(state, Some(&child_info), &mut file, |_| ())?;
+				}
+			},
+		}
+		file.finish()?;
+		Ok(checkpoint)
+	}
+
+	/// Read the trie nodes of the state of `checkpoint`.
+	pub fn read(&self, checkpoint: &Checkpoint) -> Result<MemoryDB<HashingFor<Block>>, String> {
+		let checkpoints = self.list()?;
+		let position = checkpoints
+			.iter()
+			.position(|listed| listed == checkpoint)
+			.ok_or_else(|| format!("Missing the checkpoint of block #{}", checkpoint.number))?;
+		let full = checkpoints[..=position]
+			.iter()
+			.rposition(|listed| listed.full)
+			.ok_or_else(|| format!("No full checkpoint before block #{}", checkpoint.number))?;
+
+		let mut nodes = MemoryDB::default();
+		for checkpoint in &checkpoints[full..=position] {
+			let path = self.directory.join(checkpoint.file_name());
+			let encoded =
+				fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+			let mut input = &encoded[..];
+			while !input.is_empty() {
+				let node = Vec::<u8>::decode(&mut input)
+					.map_err(|e| format!("Invalid checkpoint {}: {e}", path.display()))?;
+				nodes.insert(EMPTY_PREFIX, &node);
+			}
+		}
+		Ok(nodes)
+	}
+
+	/// Delete the checkpoints which are not needed to restore the last `keep` ones.
+	pub fn prune(&self, keep: usize) -> Result<(), String> {
+		let checkpoints = self.list()?;
+		let Some(oldest_kept) = checkpoints.len().checked_sub(keep.max(1)) else { return Ok(()) };
+		let Some(full) = checkpoints[..=oldest_kept].iter().rposition(|listed| listed.full) else {
+			return Ok(())
+		};
+		for checkpoint in &checkpoints[..full] {
+			let path = self.directory.join(checkpoint.file_name());
+			fs::remove_file(&path)
+				.map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+		}
+		Ok(())
+	}
+
+	/// The trie nodes of `state` read by `read`.
+	fn record<S: TrieBackendStorage<HashingFor<Block>>>(
+		state: &Trie<S, Block>,
+		read: impl FnOnce(&RecordingTrie<S, Block>) -> Result<(), String>,
+	) -> Result<StorageProof, String> {
+		let backend = TrieBackendBuilder::wrap(state).with_recorder(Recorder::default()).build();
+		read(&backend)?;
+		Ok(backend.extract_proof().expect("A recorder is set; qed"))
+	}
+
+	/// Write all the trie nodes of the top trie of `state`, or of the child trie `child_info`, to
+	/// `file`, calling `on_key` with every key.
+	fn record_all<S: TrieBackendStorage<HashingFor<Block>>>(
+		state: &Trie<S, Block>,
+		child_info: Option<&ChildInfo>,
+		file: &mut NodesFile,
+		mut on_key: impl FnMut(&[u8]),
+	) -> Result<(), String> {
+		let mut start_at = None;
+		loop {
+			let mut last = None;
+			let mut count = 0;
+			// Iterating over the keys with a recorder collects their trie nodes.
+			file.append(Self::record(state, |backend| {
+				let args = IterArgs {
+					child_info: child_info.cloned(),
+					start_at: start_at.as_deref(),
+					start_at_exclusive: true,
+					..Default::default()
+				};
+				for pair in backend.pairs(args)?.take(BATCH_KEYS) {
+					let (key, _) = pair?;
+					on_key(&key);
+					last = Some(key);
+					count += 1;
+				}
+				Ok(())
+			})?)?;
+			if count < BATCH_KEYS {
+				return Ok(())
+			}
+			start_at = last;
+		}
+	}
+
+	/// The keys whose values differ between the states `old` and `new`.
+	fn changed_keys<S: TrieBackendStorage<HashingFor<Block>>>(
+		old: &Trie<S, Block>,
+		new: &Trie<S, Block>,
+	) -> Result<Vec<StateKey>, String> {
+		let mut keys = Vec::new();
+		let mut child_infos = Vec::new();
+		diff(old.pairs(Default::default())?, new.pairs(Default::default())?, |key| {
+			// The nodes of a removed child trie are not needed.
+			if let Some(child_info) =
+				child_info(&key).filter(|_| new.storage(&key).ok().flatten().is_some())
+			{
+				child_infos.push(child_info);
+			}
+			keys.push((None, key));
+		})?;
+		for child_info in child_infos {
+			let args = || IterArgs { child_info: Some(child_info.clone()), ..Default::default() };
+			diff(old.pairs(args())?, new.pairs(args())?, |key| {
+				keys.push((Some(child_info.clone()), key))
+			})?;
+		}
+		Ok(keys)
+	}
+}
+
+/// A checkpoint file being written.
+///
+/// The trie nodes are written to a temporary file first, to never leave a partial checkpoint.
+struct NodesFile {
+	path: PathBuf,
+	tmp_path: PathBuf,
+	file: BufWriter<File>,
+}
+
+impl NodesFile {
+	fn create(path: PathBuf) -> Result<Self, String> {
+		let tmp_path = path.with_extension("tmp");
+		let file = File::create(&tmp_path)
+			.map_err(|e| format!("Failed to create {}: {e}", tmp_path.display()))?;
+		Ok(Self { path, tmp_path, file: BufWriter::new(file) })
+	}
+
+	fn append(&mut self, nodes: StorageProof) -> Result<(), String> {
+		for node in nodes.into_iter_nodes() {
+			self.file
+				.write_all(&node.encode())
+				.map_err(|e| format!("Failed to write {}: {e}", self.tmp_path.display()))?;
+		}
+		Ok(())
+	}
+
+	fn finish(self) -> Result<(), String> {
+		self.file
+			.into_inner()
+			.map_err(|e| e.into_error())
+			.and_then(|file| file.sync_all())
+			.and_then(|()| fs::rename(&self.tmp_path, &self.path))
+			.map_err(|e| format!("Failed to write {}: {e}", self.path.display()))
+	}
+}
+
+/// The child trie whose root is stored at `key` of the top trie, if any.
+fn child_info(key: &[u8]) -> Option<ChildInfo> {
+	if !key.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+		return None
+	}
+	match ChildType::from_prefixed_key(&PrefixedStorageKey::new(key.to_vec())) {
+		Some((ChildType::ParentKeyId, unprefixed)) => Some(ChildInfo::new_default(unprefixed)),
+		None => None,
+	}
+}
+
+/// Call `changed` with the keys whose values differ between the pairs `old` and `new`, both
+/// ordered by key.
+fn diff(
+	mut old: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), String>>,
+	mut new: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), String>>,
+	mut changed: impl FnMut(Vec<u8>),
+) -> Result<(), String> {
+	let mut old_pair = old.next().transpose()?;
+	let mut new_pair = new.next().transpose()?;
+	loop {
+		let ordering = match (&old_pair, &new_pair) {
+			(None, None) => return Ok(()),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(Some((old_key, _)), Some((new_key, _))) => old_key.cmp(new_key),
+		};
+		match ordering {
+			Ordering::Less => {
+				let (key, _) = old_pair.take().expect("Checked above; qed");
+				changed(key);
+				old_pair = old.next().transpose()?;
+			},
+			Ordering::Greater => {
+				let (key, _) = new_pair.take().expect("Checked above; qed");
+				changed(key);
+				new_pair = new.next().transpose()?;
+			},
+			Ordering::Equal => {
+				let (key, old_value) = old_pair.take().expect("Checked above; qed");
+				let (_, new_value) = new_pair.take().expect("Checked above; qed");
+				if old_value != new_value {
+					changed(key);
+				}
+				old_pair = old.next().transpose()?;
+				new_pair = new.next().transpose()?;
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sc_client_api::backend::Backend as _;
+	use sp_blockchain::HeaderBackend;
+	use sp_runtime::traits::Header as _;
+	use substrate_test_runtime_client::{
+		prelude::*,
+		runtime::{Block, ExtrinsicBuilder},
+		BlockOrigin, DefaultTestClientBuilderExt,
+	};
+
+	#[test]
+	fn checkpoint_file_name_roundtrip() {
+		let full = Checkpoint::new(42, &[0xab; 32], true);
+		assert_eq!(Checkpoint::from_file_name(&full.file_name()), Some(full));
+		let incremental = Checkpoint::new(43, &[0xcd; 32], false);
+		assert_eq!(Checkpoint::from_file_name(&incremental.file_name()), Some(incremental));
+		assert_eq!(Checkpoint::from_file_name("42-abcd.tmp"), None);
+	}
+
+	#[test]
+	fn checkpoint_contains_whole_state() {
+		let child_info = ChildInfo::new_default(b"child");
+		let builder = TestClientBuilder::new().add_extra_child_storage(
+			&child_info,
+			b"key".to_vec(),
+			vec![1; 64],
+		);
+		let backend = builder.backend();
+		let client = builder.build();
+		let hash = client.info().genesis_hash;
+		let root = *client.header(hash).unwrap().unwrap().state_root();
+
+		let directory = tempfile::tempdir().unwrap();
+		let checkpoints = Checkpoints::<Block>::open(directory.path().to_owned()).unwrap();
+		checkpoints.write(0, hash, &backend.state_at(hash).unwrap(), None).unwrap();
+		let listed = checkpoints.list().unwrap();
+		assert_eq!(listed, vec![Checkpoint::new(0, &hash, true)]);
+		assert!(listed[0].is_of(&hash));
+
+		let nodes = checkpoints.read(&listed[0]).unwrap();
+		let state = TrieBackendBuilder::new(nodes, root).build();
+		assert_eq!(
+			state.storage(well_known_keys::CODE).unwrap(),
+			backend.state_at(hash).unwrap().storage(well_known_keys::CODE).unwrap(),
+		);
+		assert_eq!(state.child_storage(&child_info, b"key").unwrap(), Some(vec![1; 64]));
+	}
+
+	#[test]
+	fn incremental_checkpoints_restore_state() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let client = builder.build();
+		let directory = tempfile::tempdir().unwrap();
+		let checkpoints = Checkpoints::<Block>::open(directory.path().to_owned()).unwrap();
+
+		let genesis_hash = client.info().genesis_hash;
+		let mut previous = backend.state_at(genesis_hash).unwrap();
+		checkpoints.write(0, genesis_hash, &previous, None).unwrap();
+		for number in 1..=3u8 {
+			let mut builder = BlockBuilderBuilder::new(&*client)
+				.on_parent_block(client.chain_info().best_hash)
+				.with_parent_block_number(client.chain_info().best_number)
+				.build()
+				.unwrap();
+			builder
+				.push(
+					ExtrinsicBuilder::new_storage_change(vec![number], Some(vec![number; 64]))
+						.build(),
+				)
+				.unwrap();
+			let block = builder.build().unwrap().block;
+			let hash = block.header.hash();
+			futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+
+			let state = backend.state_at(hash).unwrap();
+			checkpoints.write(number.into(), hash, &state, Some(&previous)).unwrap();
+			previous = state;
+		}
+
+		let listed = checkpoints.list().unwrap();
+		assert_eq!(listed.iter().filter(|checkpoint| checkpoint.full).count(), 1);
+		let full_size = fs::metadata(directory.path().join(listed[0].file_name())).unwrap().len();
+		let incremental_size =
+			fs::metadata(directory.path().join(listed[3].file_name())).unwrap().len();
+		assert!(incremental_size < full_size / 10);
+
+		let hash = client.chain_info().best_hash;
+		let root = *client.header(hash).unwrap().unwrap().state_root();
+		let state = TrieBackendBuilder::new(checkpoints.read(&listed[3]).unwrap(), root).build();
+		for number in 1..=3u8 {
+			assert_eq!(state.storage(&[number]).unwrap(), Some(vec![number; 64]));
+		}
+		assert_eq!(
+			state.storage(well_known_keys::CODE).unwrap(),
+			backend.state_at(hash).unwrap().storage(well_known_keys::CODE).unwrap(),
+		);
+
+		// The full checkpoint is needed by all the others.
+		checkpoints.prune(2).unwrap();
+		assert_eq!(checkpoints.list().unwrap(), listed);
+	}
+
+	#[test]
+	fn prune_keeps_checkpoints_needed_by_the_last_ones() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let client = builder.build();
+		let hash = client.info().genesis_hash;
+		let state = backend.state_at(hash).unwrap();
+
+		let directory = tempfile::tempdir().unwrap();
+		let checkpoints = Checkpoints::<Block>::open(directory.path().to_owned()).unwrap();
+		for (number, full) in [(0, true), (1, false), (2, true), (3, false), (4, false)] {
+			checkpoints.write(number, hash, &state, (!full).then_some(&state)).unwrap();
+		}
+		let listed = checkpoints.list().unwrap();
+
+		checkpoints.prune(2).unwrap();
+		assert_eq!(checkpoints.list().unwrap(), listed[2..]);
+		checkpoints.prune(1).unwrap();
+		assert_eq!(checkpoints.list().unwrap(), listed[2..]);
+	}
+}
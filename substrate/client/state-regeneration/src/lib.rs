@@ -0,0 +1,329 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Regeneration of pruned states from periodic state checkpoints.
+//!
+//! A node pruning the states older than `--state-retention-days` still answers the queries of
+//! older states, with latency: a checkpoint of the state of the finalized block is written to
+//! disk every `--state-checkpoint-interval` blocks, and a state requested after being pruned is
+//! regenerated by re-executing the blocks following the closest checkpoint. This trades disk for
+//! CPU, for archive nodes with little traffic on historical states.
+//!
+//! Most checkpoints are incremental: they only contain the trie nodes changed since the previous
+//! checkpoint, whose state must not be pruned yet when the checkpoint is written. A full
+//! checkpoint is written every [`FULL_CHECKPOINT_PERIOD`] checkpoints, or when the state of the
+//! previous checkpoint is gone. Only the last `--state-checkpoints-kept` checkpoints, and the ones
+//! they are restored from, are kept.
+//!
+//! Regenerations are CPU intensive and limited to `--state-regeneration-rate` per minute; the
+//! queries above the rate fail as if the state was pruned. Only the state RPC regenerates pruned
+//! states, the requests of the network peers fail as if the state was pruned. Only a few
+//! regenerated states are kept in memory, in full.
+//!
+//! The blocks must be kept to be re-executed, i.e. the node must run with
+//! `--blocks-pruning archive`. The states of the blocks before the first checkpoint and of the
+//! non canonical blocks can't be regenerated. The blocks are re-executed with the on-chain
+//! runtime, code substitutes are not applied.
+
+mod checkpoint;
+mod regenerator;
+
+use checkpoint::Checkpoints;
+use clap::Args;
+use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use regenerator::Regenerator;
+use sc_client_api::backend::Backend as _;
+use sc_client_db::{Backend, PruningMode};
+use sc_executor::RuntimeVersionOf;
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::{CodeExecutor, ReadRuntimeVersion, SpawnNamed};
+use sp_runtime::{traits::Block as BlockT, SaturatedConversion};
+use std::{
+	num::NonZeroU32,
+	path::PathBuf,
+	sync::{Arc, Weak},
+	time::{Duration, Instant},
+};
+
+const LOG_TARGET: &str = "state-regeneration";
+
+/// Delay between two checks whether a checkpoint is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Granularity of the waits of the checkpoint worker, which stops at most this late after the node.
+const WAIT_STEP: Duration = Duration::from_secs(1);
+
+/// Number of checkpoints from a full checkpoint to the next one, the others being incremental.
+pub const FULL_CHECKPOINT_PERIOD: usize = 16;
+
+/// Parameters of the regeneration of pruned states.
+#[derive(Debug, Clone, Args)]
+pub struct StateRegenerationParams {
+	/// Write the state of the finalized block to disk every given number of blocks, and
+	/// regenerate the pruned states on demand from these checkpoints.
+	///
+	/// The blocks following a checkpoint are re-executed to regenerate their states, which
+	/// requires `--blocks-pruning archive`. Disabled by default.
+	#[arg(long = "state-checkpoint-interval", value_name = "BLOCKS")]
+	pub checkpoint_interval: Option<NonZeroU32>,
+
+	/// Prune the states older than the given number of days.
+	///
+	/// Overrides `--state-pruning`, computing the number of blocks from the expected block time.
+	#[arg(long = "state-retention-days", value_name = "DAYS", requires = "checkpoint_interval")]
+	pub retention_days: Option<NonZeroU32>,
+
+	/// Maximum number of pruned states regenerated per minute.
+	#[arg(long = "state-regeneration-rate", value_name = "STATES", default_value_t = 6)]
+	pub regeneration_rate: u32,
+
+	/// Number of state checkpoints kept, the older ones are deleted.
+	///
+	/// The checkpoints needed to restore the kept ones are kept as well.
+	#[arg(
+		long = "state-checkpoints-kept",
+		value_name = "CHECKPOINTS",
+		default_value_t = 64,
+		value_parser = clap::value_parser!(u32).range(1..),
+	)]
+	pub checkpoints_kept: u32,
+}
+
+impl Default for StateRegenerationParams {
+	fn default() -> Self {
+		Self {
+			checkpoint_interval: None,
+			retention_days: None,
+			regeneration_rate: 6,
+			checkpoints_kept: 64,
+		}
+	}
+}
+
+impl StateRegenerationParams {
+	/// The state pruning keeping `--state-retention-days` of states, given the expected time
+	/// between two blocks.
+	pub fn state_pruning(&self, block_time: Duration) -> Option<PruningMode> {
+		let days = self.retention_days?;
+		let block_time = block_time.as_millis().max(1);
+		let blocks = u128::from(days.get()) * 24 * 3600 * 1000 / block_time;
+		Some(PruningMode::blocks_pruning(blocks.saturated_into()))
+	}
+}
+
+#[derive(Clone)]
+struct Metrics {
+	checkpoints: Counter<U64>,
+	regenerated: Counter<U64>,
+	executed_blocks: Counter<U64>,
+	rate_limited: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			checkpoints: register(
+				Counter::new(
+					"substrate_state_checkpoints_total",
+					"Number of state checkpoints written",
+				)?,
+				registry,
+			)?,
+			regenerated: register(
+				Counter::new(
+					"substrate_state_regenerations_total",
+					"Number of pruned states regenerated",
+				)?,
+				registry,
+			)?,
+			executed_blocks: register(
+				Counter::new(
+					"substrate_state_regeneration_executed_blocks_total",
+					"Number of blocks re-executed to regenerate pruned states",
+				)?,
+				registry,
+			)?,
+			rate_limited: register(
+				Counter::new(
+					"substrate_state_regeneration_rate_limited_total",
+					"Number of state regenerations refused because of the rate limit",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Writes checkpoints of the state of the finalized block.
+pub struct StateRegeneration<Block: BlockT> {
+	/// The worker stops once the backend is dropped with the rest of the node.
+	backend: Weak<Backend<Block>>,
+	checkpoints: Arc<Checkpoints<Block>>,
+	interval: u64,
+	kept: usize,
+	metrics: Option<Metrics>,
+}
+
+impl<Block: BlockT> StateRegeneration<Block> {
+	/// Spawn the checkpoint worker and register the regeneration of the pruned states with
+	/// `backend`, if enabled by `params`.
+	///
+	/// The checkpoints are stored in `directory`, the blocks are re-executed with `executor`.
+	pub fn spawn<E>(
+		params: StateRegenerationParams,
+		backend: &Arc<Backend<Block>>,
+		executor: E,
+		directory: PathBuf,
+		registry: Option<&Registry>,
+		spawner: &impl SpawnNamed,
+	) -> Result<(), String>
+	where
+		E: CodeExecutor + RuntimeVersionOf + ReadRuntimeVersion + Clone + 'static,
+	{
+		let Some(interval) = params.checkpoint_interval else { return Ok(()) };
+
+		let metrics = registry.map(Metrics::register).transpose().map_err(|e| e.to_string())?;
+		let checkpoints = Arc::new(Checkpoints::open(directory)?);
+		backend.set_state_regenerator(Arc::new(Regenerator::new(
+			backend,
+			checkpoints.clone(),
+			executor,
+			params.regeneration_rate,
+			metrics.clone(),
+		)));
+
+		let worker = Self {
+			backend: Arc::downgrade(backend),
+			checkpoints,
+			interval: interval.get().into(),
+			kept: params.checkpoints_kept as usize,
+			metrics,
+		};
+		log::info!(
+			target: LOG_TARGET,
+			"Writing a checkpoint of the state every {interval} blocks to {}",
+			worker.checkpoints.directory().display(),
+		);
+		spawner.spawn_blocking("state-checkpoints", None, Box::pin(async move { worker.run() }));
+		Ok(())
+	}
+
+	/// Write a checkpoint whenever one is due, until the node stops.
+	fn run(self) {
+		loop {
+			match self.checkpoint_if_due() {
+				Some(Ok(())) => (),
+				Some(Err(e)) => {
+					log::warn!(target: LOG_TARGET, "Failed to write a state checkpoint: {e}")
+				},
+				None => return,
+			}
+			if !self.wait(CHECK_INTERVAL) {
+				return
+			}
+		}
+	}
+
+	/// Write a checkpoint of the finalized block if the last one is `interval` blocks old.
+	///
+	/// Returns `None` if the node stopped in the meantime.
+	fn checkpoint_if_due(&self) -> Option<Result<(), String>> {
+		let backend = self.backend.upgrade()?;
+		let info = backend.blockchain().info();
+		let number: u64 = info.finalized_number.saturated_into();
+		let checkpoints = match self.checkpoints.list() {
+			Ok(checkpoints) => checkpoints,
+			Err(e) => return Some(Err(e)),
+		};
+		let last = checkpoints.last();
+		if last.is_some_and(|last| number < last.number.saturating_add(self.interval)) {
+			return Some(Ok(()))
+		}
+
+		let hash = info.finalized_hash;
+		// Keep the states from being pruned while the checkpoint is written.
+		if let Err(e) = backend.pin_block(hash) {
+			return Some(Err(e.to_string()))
+		}
+		let since_full = checkpoints.iter().rev().take_while(|checkpoint| !checkpoint.full).count();
+		let previous = last.filter(|_| since_full + 1 < FULL_CHECKPOINT_PERIOD).and_then(|last| {
+			let hash = backend.blockchain().hash(last.number.saturated_into()).ok().flatten()?;
+			(last.is_of(&hash) && backend.pin_block(hash).is_ok()).then_some(hash)
+		});
+		let started = Instant::now();
+		let result = backend.state_at(hash).map_err(|e| e.to_string()).and_then(|state| {
+			let previous = previous
+				.map(|previous| backend.state_at(previous).map_err(|e| e.to_string()))
+				.transpose()?;
+			self.checkpoints.write(number, hash, &state, previous.as_ref())
+		});
+		backend.unpin_block(hash);
+		if let Some(previous) = previous {
+			backend.unpin_block(previous);
+		}
+
+		let checkpoint = match result {
+			Ok(checkpoint) => checkpoint,
+			Err(e) => return Some(Err(e)),
+		};
+		log::info!(
+			target: LOG_TARGET,
+			"Wrote a {} checkpoint of the state of block #{number} in {:?}",
+			if checkpoint.full { "full" } else { "incremental" },
+			started.elapsed(),
+		);
+		if let Some(metrics) = &self.metrics {
+			metrics.checkpoints.inc();
+		}
+		Some(self.checkpoints.prune(self.kept))
+	}
+
+	/// Wait for `duration`, returning `false` if the node stopped in the meantime.
+	fn wait(&self, duration: Duration) -> bool {
+		let deadline = Instant::now() + duration;
+		loop {
+			if self.backend.strong_count() == 0 {
+				return false
+			}
+			let now = Instant::now();
+			if now >= deadline {
+				return true
+			}
+			std::thread::sleep(WAIT_STEP.min(deadline - now));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn state_pruning_keeps_retention_days() {
+		let params = StateRegenerationParams {
+			checkpoint_interval: NonZeroU32::new(1000),
+			retention_days: NonZeroU32::new(2),
+			..Default::default()
+		};
+		assert_eq!(
+			params.state_pruning(Duration::from_secs(6)),
+			Some(PruningMode::blocks_pruning(28_800)),
+		);
+		assert_eq!(StateRegenerationParams::default().state_pruning(Duration::from_secs(6)), None);
+	}
+}
@@ -0,0 +1,316 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Regeneration of pruned states by re-executing the blocks following a checkpoint.
+
+use crate::{checkpoint::Checkpoints, Metrics, LOG_TARGET};
+use codec::Encode;
+use hash_db::{HashDB, Prefix, EMPTY_PREFIX};
+use parking_lot::Mutex;
+use sc_client_api::{backend::Backend as _, blockchain::Backend as _};
+use sc_client_db::{Backend, StateRegenerator};
+use sc_executor::RuntimeVersionOf;
+use sp_blockchain::HeaderBackend;
+use sp_core::traits::{CallContext, CodeExecutor, ReadRuntimeVersion, ReadRuntimeVersionExt};
+use sp_externalities::Extensions;
+use sp_runtime::{
+	traits::{Block as BlockT, HashingFor, Header as HeaderT, NumberFor},
+	SaturatedConversion,
+};
+use sp_state_machine::{
+	backend::BackendRuntimeCode, DefaultError, Ext, OverlayedChanges, StateMachine, Storage,
+	StorageChanges, TrieBackendBuilder,
+};
+use sp_trie::{DBValue, MemoryDB};
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Weak},
+	time::{Duration, Instant},
+};
+
+/// Window over which the number of regenerations is limited.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Number of regenerated states kept in memory.
+const CACHE_SIZE: usize = 4;
+
+type RegeneratedState<Block> = Arc<dyn Storage<HashingFor<Block>>>;
+
+/// Regenerates the pruned states from the checkpoints.
+pub(crate) struct Regenerator<Block: BlockT, E> {
+	backend: Weak<Backend<Block>>,
+	checkpoints: Arc<Checkpoints<Block>>,
+	executor: E,
+	rate: usize,
+	/// Start times of the regenerations within the last [`RATE_WINDOW`].
+	recent: Mutex<VecDeque<Instant>>,
+	/// Serializes the regenerations, as every one of them keeps a whole state in memory.
+	running: Mutex<()>,
+	/// The last regenerated states, most recent last.
+	cache: Mutex<VecDeque<(Block::Hash, RegeneratedState<Block>)>>,
+	metrics: Option<Metrics>,
+}
+
+impl<Block, E> Regenerator<Block, E>
+where
+	Block: BlockT,
+	E: CodeExecutor + RuntimeVersionOf + ReadRuntimeVersion + Clone + 'static,
+{
+	/// Create a regenerator executing blocks with `executor`, at most `rate` times per minute.
+	pub fn new(
+		backend: &Arc<Backend<Block>>,
+		checkpoints: Arc<Checkpoints<Block>>,
+		executor: E,
+		rate: u32,
+		metrics: Option<Metrics>,
+	) -> Self {
+		Self {
+			backend: Arc::downgrade(backend),
+			checkpoints,
+			executor,
+			rate: rate as usize,
+			recent: Default::default(),
+			running: Default::default(),
+			cache: Default::default(),
+			metrics,
+		}
+	}
+
+	fn cached(&self, hash: Block::Hash) -> Option<RegeneratedState<Block>> {
+		let cache = self.cache.lock();
+		cache.iter().find(|(cached, _)| *cached == hash).map(|(_, state)| state.clone())
+	}
+
+	/// Account for a regeneration, failing if the rate limit is reached.
+	fn acquire_rate(&self) -> Result<(), String> {
+		let now = Instant::now();
+		let mut recent = self.recent.lock();
+		while recent.front().is_some_and(|start| now.duration_since(*start) >= RATE_WINDOW) {
+			recent.pop_front();
+		}
+		if recent.len() >= self.rate {
+			if let Some(metrics) = &self.metrics {
+				metrics.rate_limited.inc();
+			}
+			return Err("Too many states regenerated in the last minute, retry later".into())
+		}
+		recent.push_back(now);
+		Ok(())
+	}
+
+	/// Regenerate the state of the canonical block `hash` from the closest checkpoint before it.
+	fn regenerate_from_checkpoint(
+		&self,
+		hash: Block::Hash,
+	) -> Result<MemoryDB<HashingFor<Block>>, String> {
+		let backend = self.backend.upgrade().ok_or("The node is stopping")?;
+		let blockchain = backend.blockchain();
+		let header = blockchain.header(hash).map_err(|e| e.to_string())?.ok_or("Unknown block")?;
+		let number: u64 = (*header.number()).saturated_into();
+		let canonical_hash = |number: u64| {
+			blockchain.hash(number.saturated_into::<NumberFor<Block>>()).ok().flatten()
+		};
+		if canonical_hash(number) != Some(hash) {
+			return Err("The states of non canonical blocks can't be regenerated".into())
+		}
+
+		let (checkpoint, checkpoint_hash) = self
+			.checkpoints
+			.list()?
+			.into_iter()
+			.rev()
+			.filter(|checkpoint| checkpoint.number <= number)
+			.find_map(|checkpoint| {
+				let hash = canonical_hash(checkpoint.number)?;
+				checkpoint.is_of(&hash).then_some((checkpoint, hash))
+			})
+			.ok_or_else(|| format!("No state checkpoint before block #{number}"))?;
+		let mut nodes = self.checkpoints.read(&checkpoint)?;
+		let mut root = *blockchain
+			.header(checkpoint_hash)
+			.map_err(|e| e.to_string())?
+			.ok_or("Unknown checkpoint block")?
+			.state_root();
+		log::debug!(
+			target: LOG_TARGET,
+			"Regenerating the state of block #{number} from the checkpoint of block #{}",
+			checkpoint.number,
+		);
+
+		for block_number in checkpoint.number + 1..=number {
+			let block_hash = canonical_hash(block_number)
+				.ok_or_else(|| format!("Unknown block #{block_number}"))?;
+			let header = blockchain
+				.header(block_hash)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| format!("Unknown block #{block_number}"))?;
+			let body = blockchain
+				.body(block_hash)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| format!("Body of block #{block_number} was pruned"))?;
+			let state_root = *header.state_root();
+
+			let mut changes = self.execute(&nodes, root, Block::new(header, body))?;
+			if changes.transaction_storage_root != state_root {
+				return Err(format!("State root mismatch after re-executing block #{block_number}"))
+			}
+			for (_, (node, rc)) in changes.transaction.drain() {
+				if rc > 0 {
+					nodes.insert(EMPTY_PREFIX, &node);
+				}
+			}
+			root = state_root;
+
+			if let Some(metrics) = &self.metrics {
+				metrics.executed_blocks.inc();
+			}
+		}
+		Ok(nodes)
+	}
+
+	/// Execute `block` on top of the state with the given `root`.
+	fn execute(
+		&self,
+		nodes: &MemoryDB<HashingFor<Block>>,
+		root: Block::Hash,
+		block: Block,
+	) -> Result<StorageChanges<HashingFor<Block>>, String> {
+		let state = TrieBackendBuilder::new(nodes, root).build();
+		let runtime_code = BackendRuntimeCode::new(&state).runtime_code()?;
+		let mut overlay = OverlayedChanges::default();
+		let state_version = self
+			.executor
+			.runtime_version(&mut Ext::new(&mut overlay, &state, None), &runtime_code)
+			.map_err(|e| e.to_string())?
+			.state_version();
+
+		let mut extensions = Extensions::default();
+		extensions.register(ReadRuntimeVersionExt::new(self.executor.clone()));
+		StateMachine::new(
+			&state,
+			&mut overlay,
+			&self.executor,
+			"Core_execute_block",
+			&block.encode(),
+			&mut extensions,
+			&runtime_code,
+			CallContext::Onchain,
+		)
+		.execute()
+		.map_err(|e| format!("Failed to execute the block: {e}"))?;
+		overlay.drain_storage_changes(&state, state_version)
+	}
+}
+
+impl<Block, E> StateRegenerator<Block> for Regenerator<Block, E>
+where
+	Block: BlockT,
+	E: CodeExecutor + RuntimeVersionOf + ReadRuntimeVersion + Clone + 'static,
+{
+	fn regenerate(&self, hash: Block::Hash) -> Result<RegeneratedState<Block>, String> {
+		if let Some(state) = self.cached(hash) {
+			return Ok(state)
+		}
+		self.acquire_rate()?;
+		let _running = self.running.lock();
+		// The state may have been regenerated while waiting for the previous regeneration.
+		if let Some(state) = self.cached(hash) {
+			return Ok(state)
+		}
+
+		let started = Instant::now();
+		let state: RegeneratedState<Block> =
+			Arc::new(Nodes(self.regenerate_from_checkpoint(hash)?));
+		log::debug!(
+			target: LOG_TARGET,
+			"Regenerated the state of block {hash:?} in {:?}",
+			started.elapsed(),
+		);
+		if let Some(metrics) = &self.metrics {
+			metrics.regenerated.inc();
+		}
+
+		let mut cache = self.cache.lock();
+		if cache.len() >= CACHE_SIZE {
+			cache.pop_front();
+		}
+		cache.push_back((hash, state.clone()));
+		Ok(state)
+	}
+}
+
+/// The trie nodes of a regenerated state.
+struct Nodes<Block: BlockT>(MemoryDB<HashingFor<Block>>);
+
+impl<Block: BlockT> Storage<HashingFor<Block>> for Nodes<Block> {
+	fn get(&self, key: &Block::Hash, prefix: Prefix) -> Result<Option<DBValue>, DefaultError> {
+		Ok(HashDB::get(&self.0, key, prefix))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_core::storage::well_known_keys;
+	use sp_state_machine::Backend as _;
+	use substrate_test_runtime_client::{
+		prelude::*, runtime::Block, BlockOrigin, DefaultTestClientBuilderExt, WasmExecutor,
+	};
+
+	#[test]
+	fn regenerates_state_from_checkpoint() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let client = builder.build();
+		let genesis_hash = client.info().genesis_hash;
+
+		let directory = tempfile::tempdir().unwrap();
+		let checkpoints = Arc::new(Checkpoints::open(directory.path().to_owned()).unwrap());
+		checkpoints
+			.write(0, genesis_hash, &backend.state_at(genesis_hash).unwrap(), None)
+			.unwrap();
+
+		for _ in 0..3 {
+			let block = BlockBuilderBuilder::new(&*client)
+				.on_parent_block(client.chain_info().best_hash)
+				.with_parent_block_number(client.chain_info().best_number)
+				.build()
+				.unwrap()
+				.build()
+				.unwrap()
+				.block;
+			futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+		let hash = client.chain_info().best_hash;
+		let root = *client.header(hash).unwrap().unwrap().state_root();
+
+		let regenerator =
+			Regenerator::<Block, _>::new(&backend, checkpoints, WasmExecutor::default(), 1, None);
+		let state = regenerator.regenerate(hash).unwrap();
+		let state = TrieBackendBuilder::new(state, root).build();
+		assert_eq!(
+			state.storage(well_known_keys::CODE).unwrap(),
+			backend.state_at(hash).unwrap().storage(well_known_keys::CODE).unwrap(),
+		);
+
+		// Served from the cache, despite the rate limit.
+		assert!(regenerator.regenerate(hash).is_ok());
+		assert!(regenerator.regenerate(genesis_hash).is_err());
+	}
+}
@@ -0,0 +1,363 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Background scrubbing of the state trie.
+//!
+//! The [`StateScrubber`] periodically walks the state of the finalized block at a bounded rate and
+//! checks every trie node against its hash. This detects silent corruption of the database before
+//! block import stumbles on it.
+//!
+//! Corrupt and missing nodes are logged and counted in the
+//! `substrate_state_scrubber_corrupt_nodes_total` metric. They are not repaired: peers only serve
+//! key-value ranges of the state, not individual trie nodes, so a corrupt database has to be
+//! restored from a backup or synced again.
+
+use clap::Args;
+use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use sc_client_api::backend::Backend;
+use sp_blockchain::HeaderBackend;
+use sp_core::{traits::SpawnNamed, Hasher};
+use sp_runtime::traits::{Block as BlockT, HashingFor};
+use sp_state_machine::backend::AsTrieBackend;
+use sp_trie::{read_child_trie_roots, trie_types::TrieDBBuilder, KeySpacedDB, LayoutV1};
+use std::{
+	marker::PhantomData,
+	num::NonZeroU32,
+	sync::{Arc, Weak},
+	time::{Duration, Instant},
+};
+use trie_db::{HashDBRef, TrieDBNodeIterator};
+
+const LOG_TARGET: &str = "state-scrubber";
+
+/// Granularity of the waits of the scrubber, which stops at most this late after the node.
+const WAIT_STEP: Duration = Duration::from_secs(1);
+
+/// Parameters of the state scrubber.
+#[derive(Default, Debug, Clone, Args)]
+pub struct StateScrubberParams {
+	/// Scrub the state of the finalized block, checking at most the given number of trie nodes
+	/// per second.
+	///
+	/// Every node of the state trie is checked against its hash, to detect corruption of the
+	/// database. Disabled by default.
+	#[arg(long = "state-scrubber-rate", value_name = "NODES")]
+	pub rate: Option<NonZeroU32>,
+
+	/// Delay between the end of a scrub of the state and the start of the next one.
+	#[arg(long = "state-scrubber-interval", value_name = "SECONDS", default_value_t = 3600)]
+	pub interval: u64,
+}
+
+/// Outcome of a scrub.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScrubReport {
+	/// Number of trie nodes checked.
+	pub checked: u64,
+	/// Number of trie nodes which don't match their hash or can't be read.
+	pub corrupt: u64,
+}
+
+struct Metrics {
+	checked: Counter<U64>,
+	corrupt: Counter<U64>,
+	scrubs: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			checked: register(
+				Counter::new(
+					"substrate_state_scrubber_checked_nodes_total",
+					"Number of state trie nodes checked by the scrubber",
+				)?,
+				registry,
+			)?,
+			corrupt: register(
+				Counter::new(
+					"substrate_state_scrubber_corrupt_nodes_total",
+					"Number of corrupt or missing state trie nodes found by the scrubber",
+				)?,
+				registry,
+			)?,
+			scrubs: register(
+				Counter::new(
+					"substrate_state_scrubber_scrubs_total",
+					"Number of complete scrubs of the state",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Walks the state trie of the finalized block and checks its nodes against their hashes.
+pub struct StateScrubber<Block, BE> {
+	/// The scrubber stops once the backend is dropped with the rest of the node.
+	backend: Weak<BE>,
+	rate: u32,
+	interval: Duration,
+	metrics: Option<Metrics>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, BE> StateScrubber<Block, BE>
+where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+{
+	/// Create a scrubber checking at most `rate` nodes per second.
+	pub fn new(backend: &Arc<BE>, rate: NonZeroU32, interval: Duration) -> Self {
+		Self {
+			backend: Arc::downgrade(backend),
+			rate: rate.get(),
+			interval,
+			metrics: None,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Spawn the scrubber, if enabled by `params`.
+	pub fn spawn(
+		params: StateScrubberParams,
+		backend: &Arc<BE>,
+		registry: Option<&Registry>,
+		spawner: &impl SpawnNamed,
+	) -> Result<(), PrometheusError> {
+		let Some(rate) = params.rate else { return Ok(()) };
+
+		let mut scrubber = Self::new(backend, rate, Duration::from_secs(params.interval));
+		scrubber.metrics = registry.map(Metrics::register).transpose()?;
+		log::info!(target: LOG_TARGET, "Scrubbing the state at {rate} nodes per second");
+		spawner.spawn_blocking("state-scrubber", None, Box::pin(async move { scrubber.run() }));
+		Ok(())
+	}
+
+	/// Scrub the state of the finalized block every `interval`, until the node stops.
+	fn run(self) {
+		loop {
+			match self.scrub_finalized() {
+				Some(Ok(report)) if report.corrupt > 0 => log::error!(
+					target: LOG_TARGET,
+					"Found {} corrupt state trie nodes out of {}, the database must be restored",
+					report.corrupt,
+					report.checked,
+				),
+				Some(Ok(report)) => log::debug!(
+					target: LOG_TARGET,
+					"Checked {} state trie nodes, none is corrupt",
+					report.checked,
+				),
+				Some(Err(e)) => log::warn!(target: LOG_TARGET, "Failed to scrub the state: {e}"),
+				None => return,
+			}
+			if !self.wait(self.interval) {
+				return
+			}
+		}
+	}
+
+	/// Scrub the state of the current finalized block.
+	///
+	/// Returns `None` if the node stopped in the meantime.
+	pub fn scrub_finalized(&self) -> Option<sp_blockchain::Result<ScrubReport>> {
+		let backend = self.backend.upgrade()?;
+		let hash = backend.blockchain().info().finalized_hash;
+		// Keep the state from being pruned while it is scrubbed.
+		if let Err(e) = backend.pin_block(hash) {
+			return Some(Err(e))
+		}
+		let state = backend.state_at(hash);
+		drop(backend);
+
+		let report = match state {
+			Ok(state) => self.scrub(hash, &state).map(Ok),
+			Err(e) => Some(Err(e)),
+		};
+		if let Some(backend) = self.backend.upgrade() {
+			backend.unpin_block(hash);
+		}
+		report
+	}
+
+	/// Scrub the top trie and all the child tries of `state`.
+	fn scrub(&self, hash: Block::Hash, state: &BE::State) -> Option<ScrubReport> {
+		let trie_backend = state.as_trie_backend();
+		let essence = trie_backend.essence();
+		let mut throttle = Throttle::new(self.rate);
+		let mut report = ScrubReport::default();
+
+		log::debug!(target: LOG_TARGET, "Scrubbing the state of block {hash:?}");
+		self.scrub_trie::<HashingFor<Block>>(essence, essence.root(), &mut throttle, &mut report)?;
+		match read_child_trie_roots::<LayoutV1<HashingFor<Block>>>(essence, essence.root()) {
+			Ok(child_roots) => {
+				for (child_info, root) in child_roots {
+					let storage = KeySpacedDB::new(essence, child_info.keyspace());
+					self.scrub_trie::<HashingFor<Block>>(
+						&storage,
+						&root,
+						&mut throttle,
+						&mut report,
+					)?;
+				}
+			},
+			Err(e) => self.report_corrupt(
+				&mut report,
+				format!("Failed to read the child tries of block {hash:?}: {e}"),
+			),
+		}
+
+		if let Some(metrics) = &self.metrics {
+			metrics.scrubs.inc();
+		}
+		Some(report)
+	}
+
+	/// Check all the nodes of the trie with the given `root`.
+	///
+	/// Returns `None` if the node stopped in the meantime.
+	fn scrub_trie<H: Hasher>(
+		&self,
+		storage: &dyn HashDBRef<H, Vec<u8>>,
+		root: &H::Out,
+		throttle: &mut Throttle,
+		report: &mut ScrubReport,
+	) -> Option<()> {
+		let trie = TrieDBBuilder::new(storage, root).build();
+		let nodes = match TrieDBNodeIterator::new(&trie) {
+			Ok(nodes) => nodes,
+			Err(e) => {
+				self.report_corrupt(report, format!("Failed to read root of trie {root:?}: {e}"));
+				return Some(())
+			},
+		};
+
+		for node in nodes {
+			if let Some(pause) = throttle.tick() {
+				if !self.wait(pause) {
+					return None
+				}
+			}
+			report.checked += 1;
+			if let Some(metrics) = &self.metrics {
+				metrics.checked.inc();
+			}
+
+			match node {
+				// Nodes inlined in their parent have no hash.
+				Ok((_, Some(hash), node)) if H::hash(node.data()) != hash => self.report_corrupt(
+					report,
+					format!("Node {hash:?} of trie {root:?} doesn't match its hash"),
+				),
+				Ok(_) => (),
+				// The iteration can't continue below a node which can't be read.
+				Err(e) => {
+					self.report_corrupt(
+						report,
+						format!("Failed to read node of trie {root:?}: {e}"),
+					);
+					break
+				},
+			}
+		}
+		Some(())
+	}
+
+	fn report_corrupt(&self, report: &mut ScrubReport, message: String) {
+		log::error!(target: LOG_TARGET, "{message}");
+		report.corrupt += 1;
+		if let Some(metrics) = &self.metrics {
+			metrics.corrupt.inc();
+		}
+	}
+
+	/// Wait for `duration`, returning `false` if the node stopped in the meantime.
+	fn wait(&self, duration: Duration) -> bool {
+		let deadline = Instant::now() + duration;
+		loop {
+			if self.backend.strong_count() == 0 {
+				return false
+			}
+			let now = Instant::now();
+			if now >= deadline {
+				return true
+			}
+			std::thread::sleep(WAIT_STEP.min(deadline - now));
+		}
+	}
+}
+
+/// Limits the number of nodes checked per second.
+struct Throttle {
+	rate: u32,
+	count: u32,
+	window_start: Instant,
+}
+
+impl Throttle {
+	fn new(rate: u32) -> Self {
+		Self { rate, count: 0, window_start: Instant::now() }
+	}
+
+	/// Account for a node to check, returning how long to wait before checking it.
+	fn tick(&mut self) -> Option<Duration> {
+		if self.count < self.rate {
+			self.count += 1;
+			return None
+		}
+		let pause = Duration::from_secs(1).checked_sub(self.window_start.elapsed());
+		self.count = 1;
+		self.window_start = Instant::now() + pause.unwrap_or_default();
+		pause
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::ChildInfo;
+	use substrate_test_runtime_client::{prelude::*, DefaultTestClientBuilderExt};
+
+	#[test]
+	fn scrubbing_intact_state_works() {
+		let child_info = ChildInfo::new_default(b"child");
+		let builder = TestClientBuilder::new().add_extra_child_storage(
+			&child_info,
+			b"key".to_vec(),
+			vec![1; 64],
+		);
+		let backend = builder.backend();
+		let _client = builder.build();
+
+		let scrubber =
+			StateScrubber::new(&backend, NonZeroU32::new(u32::MAX).unwrap(), Duration::ZERO);
+		let report = scrubber.scrub_finalized().unwrap().unwrap();
+		assert!(report.checked > 0);
+		assert_eq!(report.corrupt, 0);
+	}
+
+	#[test]
+	fn throttle_limits_rate() {
+		let mut throttle = Throttle::new(2);
+		assert_eq!(throttle.tick(), None);
+		assert_eq!(throttle.tick(), None);
+		assert!(throttle.tick().is_some());
+		assert_eq!(throttle.tick(), None);
+	}
+}
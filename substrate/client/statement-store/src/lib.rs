@@ -43,6 +43,11 @@
 //! explicitly with the `remove` function) the statement is marked as expired. Expired statements
 //! can't be added to the store for `Options::purge_after_sec` seconds. This is to prevent old
 //! statements from being propagated on the network.
+//!
+//! Statements with a topic listed in `Options::topic_expiry` are removed once they have been in
+//! the store for the configured number of seconds. If several topics of a statement are listed,
+//! the shortest expiry applies. The time statements were added is persisted, so expiry carries
+//! over node restarts.
 
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
@@ -64,7 +69,7 @@ use sp_statement_store::{
 		InvalidStatement, StatementSource, StatementStoreExt, ValidStatement, ValidateStatement,
 	},
 	AccountId, BlockHash, Channel, DecryptionKey, Hash, NetworkPriority, Proof, Result, Statement,
-	SubmitResult, Topic,
+	StatementPage, SubmitResult, Topic, TopicFilter,
 };
 use std::{
 	collections::{BTreeMap, HashMap, HashSet},
@@ -72,7 +77,7 @@ use std::{
 };
 
 const KEY_VERSION: &[u8] = b"version".as_slice();
-const CURRENT_VERSION: u32 = 1;
+const CURRENT_VERSION: u32 = 2;
 
 const LOG_TARGET: &str = "statement-store";
 
@@ -86,8 +91,10 @@ mod col {
 	pub const META: u8 = 0;
 	pub const STATEMENTS: u8 = 1;
 	pub const EXPIRED: u8 = 2;
+	/// Time a statement was added to the store. Added in version 2.
+	pub const INSERTED: u8 = 3;
 
-	pub const COUNT: u8 = 3;
+	pub const COUNT: u8 = 4;
 }
 
 #[derive(Eq, PartialEq, Debug, Ord, PartialOrd, Clone, Copy)]
@@ -131,12 +138,14 @@ struct StatementsForAccount {
 pub struct Options {
 	/// Maximum statement allowed in the store. Once this limit is reached lower-priority
 	/// statements may be evicted.
-	max_total_statements: usize,
+	pub max_total_statements: usize,
 	/// Maximum total data size allowed in the store. Once this limit is reached lower-priority
 	/// statements may be evicted.
-	max_total_size: usize,
+	pub max_total_size: usize,
 	/// Number of seconds for which removed statements won't be allowed to be added back in.
-	purge_after_sec: u64,
+	pub purge_after_sec: u64,
+	/// Number of seconds after which statements with the topic are removed from the store.
+	pub topic_expiry: HashMap<Topic, u64>,
 }
 
 impl Default for Options {
@@ -145,10 +154,44 @@ impl Default for Options {
 			max_total_statements: DEFAULT_MAX_TOTAL_STATEMENTS,
 			max_total_size: DEFAULT_MAX_TOTAL_SIZE,
 			purge_after_sec: DEFAULT_PURGE_AFTER_SEC,
+			topic_expiry: HashMap::new(),
 		}
 	}
 }
 
+/// Statement store command line parameters.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct StatementStoreParams {
+	/// Remove statements with the given topic once they have been in the store for the given
+	/// number of seconds.
+	///
+	/// The topic is given as 32 bytes in hex. Can be passed multiple times.
+	#[arg(
+		long = "statement-topic-expiry",
+		value_name = "TOPIC=SECONDS",
+		value_parser = parse_topic_expiry,
+	)]
+	pub topic_expiry: Vec<(Topic, u64)>,
+}
+
+impl StatementStoreParams {
+	/// Store options with the configured topic expiry.
+	pub fn options(&self) -> Options {
+		Options { topic_expiry: self.topic_expiry.iter().copied().collect(), ..Default::default() }
+	}
+}
+
+fn parse_topic_expiry(value: &str) -> Result<(Topic, u64), String> {
+	let (topic, seconds) =
+		value.split_once('=').ok_or_else(|| "expected `TOPIC=SECONDS`".to_string())?;
+	let topic = sp_core::bytes::from_hex(topic)
+		.map_err(|e| format!("invalid topic: {e}"))?
+		.try_into()
+		.map_err(|_| "topic must be 32 bytes".to_string())?;
+	let seconds = seconds.parse().map_err(|e| format!("invalid number of seconds: {e}"))?;
+	Ok((topic, seconds))
+}
+
 #[derive(Default)]
 struct Index {
 	by_topic: HashMap<Topic, HashSet<Hash>>,
@@ -156,6 +199,7 @@ struct Index {
 	topics_and_keys: HashMap<Hash, ([Option<Topic>; MAX_TOPICS], Option<DecryptionKey>)>,
 	entries: HashMap<Hash, (AccountId, Priority, usize)>,
 	expired: HashMap<Hash, u64>, // Value is expiration timestamp.
+	expires: HashMap<Hash, u64>, // Value is the time the statement expires due to its topics.
 	accounts: HashMap<AccountId, StatementsForAccount>,
 	options: Options,
 	total_size: usize,
@@ -224,7 +268,7 @@ impl Index {
 		Index { options, ..Default::default() }
 	}
 
-	fn insert_new(&mut self, hash: Hash, account: AccountId, statement: &Statement) {
+	fn insert_new(&mut self, hash: Hash, account: AccountId, statement: &Statement, inserted: u64) {
 		let mut all_topics = [None; MAX_TOPICS];
 		let mut nt = 0;
 		while let Some(t) = statement.topic(nt) {
@@ -237,6 +281,14 @@ impl Index {
 		if nt > 0 || key.is_some() {
 			self.topics_and_keys.insert(hash, (all_topics, key));
 		}
+		let expire_after = all_topics
+			.iter()
+			.flatten()
+			.filter_map(|t| self.options.topic_expiry.get(t))
+			.min();
+		if let Some(expire_after) = expire_after {
+			self.expires.insert(hash, inserted.saturating_add(*expire_after));
+		}
 		let priority = Priority(statement.priority().unwrap_or(0));
 		self.entries.insert(hash, (account, priority, statement.data_len()));
 		self.total_size += statement.data_len();
@@ -305,7 +357,57 @@ impl Index {
 		Ok(())
 	}
 
-	fn maintain(&mut self, current_time: u64) -> Vec<Hash> {
+	/// The hashes of the statements matching `filter`, in ascending order.
+	fn matching(&self, filter: &TopicFilter) -> Vec<Hash> {
+		let has_topic = |hash: &Hash, topic: &Topic| {
+			self.topics_and_keys
+				.get(hash)
+				.is_some_and(|(topics, _)| topics.contains(&Some(*topic)))
+		};
+		let matches = |hash: &&Hash| {
+			filter.match_all.iter().all(|topic| has_topic(hash, topic)) &&
+				(filter.match_any.is_empty() ||
+					filter.match_any.iter().any(|topic| has_topic(hash, topic)))
+		};
+
+		// Start with the smallest candidate set.
+		let empty = HashSet::new();
+		let candidates = filter
+			.match_all
+			.iter()
+			.map(|topic| self.by_topic.get(topic).unwrap_or(&empty))
+			.min_by_key(|set| set.len());
+		let mut result: Vec<Hash> = match candidates {
+			Some(candidates) => candidates.iter().filter(matches).copied().collect(),
+			None if !filter.match_any.is_empty() => filter
+				.match_any
+				.iter()
+				.filter_map(|topic| self.by_topic.get(topic))
+				.flatten()
+				.copied()
+				.collect::<HashSet<_>>()
+				.into_iter()
+				.collect(),
+			None => self.entries.keys().copied().collect(),
+		};
+		result.sort();
+		result
+	}
+
+	/// Expire the statements whose topic expiry has passed and purge statements expired long
+	/// enough ago. Returns the expired and the purged statements.
+	fn maintain(&mut self, current_time: u64) -> (Vec<Hash>, Vec<Hash>) {
+		let expired: Vec<_> = self
+			.expires
+			.iter()
+			.filter(|(_, expires)| **expires <= current_time)
+			.map(|(hash, _)| *hash)
+			.collect();
+		for hash in &expired {
+			log::trace!(target: LOG_TARGET, "Topic expiry of {:?}", HexDisplay::from(hash));
+			self.make_expired(hash, current_time);
+		}
+
 		// Purge previously expired messages.
 		let mut purged = Vec::new();
 		self.expired.retain(|hash, timestamp| {
@@ -317,12 +419,13 @@ impl Index {
 				true
 			}
 		});
-		purged
+		(expired, purged)
 	}
 
 	fn make_expired(&mut self, hash: &Hash, current_time: u64) -> bool {
 		if let Some((account, priority, len)) = self.entries.remove(hash) {
 			self.total_size -= len;
+			self.expires.remove(hash);
 			if let Some((topics, key)) = self.topics_and_keys.remove(hash) {
 				for t in topics.into_iter().flatten() {
 					if let std::collections::hash_map::Entry::Occupied(mut set) =
@@ -469,7 +572,7 @@ impl Index {
 		for h in &evicted {
 			self.make_expired(h, current_time);
 		}
-		self.insert_new(hash, *account, statement);
+		self.insert_new(hash, *account, statement, current_time);
 		MaybeInserted::Inserted(evicted)
 	}
 }
@@ -533,13 +636,25 @@ impl Store {
 		let mut path: std::path::PathBuf = path.into();
 		path.push("statements");
 
-		let mut config = parity_db::Options::with_columns(&path, col::COUNT);
+		let db_config = |columns| {
+			let mut config = parity_db::Options::with_columns(&path, columns);
+			let statement_col = &mut config.columns[col::STATEMENTS as usize];
+			statement_col.ref_counted = false;
+			statement_col.preimage = true;
+			statement_col.uniform = true;
+			config
+		};
+
+		// Version 1 databases lack the column of insertion times.
+		let metadata =
+			parity_db::Options::load_metadata(&path).map_err(|e| Error::Db(e.to_string()))?;
+		if metadata.is_some_and(|metadata| metadata.columns.len() == col::INSERTED as usize) {
+			parity_db::Db::add_column(&mut db_config(col::INSERTED), Default::default())
+				.map_err(|e| Error::Db(e.to_string()))?;
+		}
 
-		let statement_col = &mut config.columns[col::STATEMENTS as usize];
-		statement_col.ref_counted = false;
-		statement_col.preimage = true;
-		statement_col.uniform = true;
-		let db = parity_db::Db::open_or_create(&config).map_err(|e| Error::Db(e.to_string()))?;
+		let db = parity_db::Db::open_or_create(&db_config(col::COUNT))
+			.map_err(|e| Error::Db(e.to_string()))?;
 		match db.get(col::META, &KEY_VERSION).map_err(|e| Error::Db(e.to_string()))? {
 			Some(version) => {
 				let version = u32::from_le_bytes(
@@ -547,8 +662,17 @@ impl Store {
 						.try_into()
 						.map_err(|_| Error::Db("Error reading database version".into()))?,
 				);
-				if version != CURRENT_VERSION {
-					return Err(Error::Db(format!("Unsupported database version: {version}")))
+				match version {
+					CURRENT_VERSION => (),
+					// Statements of version 1 get their insertion time when loaded.
+					1 => db
+						.commit([(
+							col::META,
+							KEY_VERSION.to_vec(),
+							Some(CURRENT_VERSION.to_le_bytes().to_vec()),
+						)])
+						.map_err(|e| Error::Db(e.to_string()))?,
+					_ => return Err(Error::Db(format!("Unsupported database version: {version}"))),
 				}
 			},
 			None => {
@@ -583,7 +707,22 @@ impl Store {
 	// This function should only be used on startup. There should be no other DB operations when
 	// iterating the index.
 	fn populate(&self) -> Result<()> {
+		let current_time = self.timestamp();
+		let mut missing_inserted = Vec::new();
 		{
+			let mut inserted = HashMap::new();
+			self.db
+				.iter_column_while(col::INSERTED, |item| {
+					if let (Ok(hash), Ok(time)) = (
+						Hash::try_from(item.key.as_slice()),
+						u64::decode(&mut item.value.as_slice()),
+					) {
+						inserted.insert(hash, time);
+					}
+					true
+				})
+				.map_err(|e| Error::Db(e.to_string()))?;
+
 			let mut index = self.index.write();
 			self.db
 				.iter_column_while(col::STATEMENTS, |item| {
@@ -596,7 +735,15 @@ impl Store {
 							HexDisplay::from(&hash)
 						);
 						if let Some(account_id) = statement.account_id() {
-							index.insert_new(hash, account_id, &statement);
+							let time = inserted.get(&hash).copied().unwrap_or_else(|| {
+								missing_inserted.push((
+									col::INSERTED,
+									hash.to_vec(),
+									Some(current_time.encode()),
+								));
+								current_time
+							});
+							index.insert_new(hash, account_id, &statement, time);
 						} else {
 							log::debug!(
 								target: LOG_TARGET,
@@ -625,6 +772,7 @@ impl Store {
 				})
 				.map_err(|e| Error::Db(e.to_string()))?;
 		}
+		self.db.commit(missing_inserted).map_err(|e| Error::Db(e.to_string()))?;
 
 		self.maintain();
 		Ok(())
@@ -671,11 +819,17 @@ impl Store {
 	/// Perform periodic store maintenance
 	pub fn maintain(&self) {
 		log::trace!(target: LOG_TARGET, "Started store maintenance");
-		let deleted = self.index.write().maintain(self.timestamp());
-		let deleted: Vec<_> =
-			deleted.into_iter().map(|hash| (col::EXPIRED, hash.to_vec(), None)).collect();
-		let count = deleted.len() as u64;
-		if let Err(e) = self.db.commit(deleted) {
+		let current_time = self.timestamp();
+		let (expired, purged) = self.index.write().maintain(current_time);
+		let count = purged.len() as u64;
+		let mut commit: Vec<_> =
+			purged.into_iter().map(|hash| (col::EXPIRED, hash.to_vec(), None)).collect();
+		for hash in expired {
+			commit.push((col::STATEMENTS, hash.to_vec(), None));
+			commit.push((col::INSERTED, hash.to_vec(), None));
+			commit.push((col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())));
+		}
+		if let Err(e) = self.db.commit(commit) {
 			log::warn!(target: LOG_TARGET, "Error writing to the statement database: {:?}", e);
 		} else {
 			self.metrics.report(|metrics| metrics.statements_pruned.inc_by(count));
@@ -814,6 +968,40 @@ impl StatementStore for Store {
 		})
 	}
 
+	/// Return a page of the statements matching `filter`, ordered by hash.
+	fn query(
+		&self,
+		filter: &TopicFilter,
+		start_after: Option<Hash>,
+		limit: usize,
+	) -> Result<StatementPage> {
+		let index = self.index.read();
+		let hashes = index.matching(filter);
+		let start =
+			start_after.map_or(0, |start_after| hashes.partition_point(|h| *h <= start_after));
+		let mut page = StatementPage::default();
+		for hash in &hashes[start..] {
+			if page.statements.len() == limit {
+				page.next = page.statements.last().map(|(hash, _)| *hash);
+				break
+			}
+			match self.db.get(col::STATEMENTS, hash).map_err(|e| Error::Db(e.to_string()))? {
+				Some(entry) => page.statements.push((
+					*hash,
+					Statement::decode(&mut entry.as_slice())
+						.map_err(|e| Error::Decode(e.to_string()))?,
+				)),
+				// DB inconsistency
+				None => log::warn!(
+					target: LOG_TARGET,
+					"Missing statement {:?}",
+					HexDisplay::from(hash)
+				),
+			}
+		}
+		Ok(page)
+	}
+
 	/// Submit a statement to the store. Validates the statement and returns validation result.
 	fn submit(&self, statement: Statement, source: StatementSource) -> SubmitResult {
 		let hash = statement.hash();
@@ -882,8 +1070,10 @@ impl StatementStore for Store {
 				};
 
 			commit.push((col::STATEMENTS, hash.to_vec(), Some(statement.encode())));
+			commit.push((col::INSERTED, hash.to_vec(), Some(current_time.encode())));
 			for hash in evicted {
 				commit.push((col::STATEMENTS, hash.to_vec(), None));
+				commit.push((col::INSERTED, hash.to_vec(), None));
 				commit.push((col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())));
 			}
 			if let Err(e) = self.db.commit(commit) {
@@ -910,6 +1100,7 @@ impl StatementStore for Store {
 			if index.make_expired(hash, current_time) {
 				let commit = [
 					(col::STATEMENTS, hash.to_vec(), None),
+					(col::INSERTED, hash.to_vec(), None),
 					(col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())),
 				];
 				if let Err(e) = self.db.commit(commit) {
@@ -929,13 +1120,13 @@ impl StatementStore for Store {
 
 #[cfg(test)]
 mod tests {
-	use crate::Store;
+	use crate::{Options, Store};
 	use sc_keystore::Keystore;
 	use sp_core::Pair;
 	use sp_statement_store::{
 		runtime_api::{InvalidStatement, ValidStatement, ValidateStatement},
 		AccountId, Channel, DecryptionKey, NetworkPriority, Proof, SignatureVerificationResult,
-		Statement, StatementSource, StatementStore, SubmitResult, Topic,
+		Statement, StatementSource, StatementStore, SubmitResult, Topic, TopicFilter,
 	};
 
 	type Extrinsic = sp_runtime::OpaqueExtrinsic;
@@ -1033,6 +1224,22 @@ mod tests {
 		(store, temp_dir) // return order is important. Store must be dropped before TempDir
 	}
 
+	fn reopen(store: Store, temp: &tempfile::TempDir, options: Options) -> Store {
+		let keystore = store.keystore.clone();
+		drop(store);
+		let client = std::sync::Arc::new(TestClient);
+		let mut path: std::path::PathBuf = temp.path().into();
+		path.push("db");
+		Store::new(&path, options, client, keystore, None).unwrap()
+	}
+
+	fn now() -> u64 {
+		std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.unwrap()
+			.as_secs()
+	}
+
 	fn signed_statement(data: u8) -> Statement {
 		signed_statement_with_topics(data, &[], None)
 	}
@@ -1293,4 +1500,115 @@ mod tests {
 		let posted_clear = store.posted_clear(&[], public.into()).unwrap();
 		assert_eq!(posted_clear, vec![plain]);
 	}
+
+	#[test]
+	fn statements_expire_by_topic() {
+		let (mut store, temp) = test_store();
+		store.index.write().options.topic_expiry = [(topic(1), 100), (topic(2), 50)].into();
+		store.set_time(1000);
+		let plain = signed_statement_with_topics(0, &[topic(0)], None);
+		let expiring = signed_statement_with_topics(1, &[topic(0), topic(1)], None);
+		let shortest = signed_statement_with_topics(2, &[topic(1), topic(2)], None);
+		for statement in [&plain, &expiring, &shortest] {
+			store.submit(statement.clone(), StatementSource::Network);
+		}
+
+		store.set_time(1050);
+		store.maintain();
+		assert_eq!(store.statements().unwrap().len(), 2);
+		assert_eq!(store.statement(&shortest.hash()).unwrap(), None);
+
+		store.set_time(1100);
+		store.maintain();
+		assert_eq!(store.statements().unwrap().len(), 1);
+		assert_eq!(store.index.read().expired.len(), 2);
+		assert_eq!(
+			store.submit(expiring.clone(), StatementSource::Network),
+			SubmitResult::KnownExpired
+		);
+
+		let store = reopen(store, &temp, Default::default());
+		assert_eq!(store.statements().unwrap(), vec![(plain.hash(), plain)]);
+	}
+
+	#[test]
+	fn insertion_time_survives_restart() {
+		let (mut store, temp) = test_store();
+		let now = now();
+		store.set_time(now - 500);
+		let statement = signed_statement_with_topics(0, &[topic(1)], None);
+		store.submit(statement.clone(), StatementSource::Network);
+
+		let options = Options { topic_expiry: [(topic(1), 1000)].into(), ..Default::default() };
+		let mut store = reopen(store, &temp, options);
+		assert_eq!(store.statements().unwrap().len(), 1);
+
+		// Expires 1000 seconds after it was submitted, not after it was loaded.
+		store.set_time(now + 600);
+		store.maintain();
+		assert_eq!(store.statements().unwrap().len(), 0);
+	}
+
+	#[test]
+	fn query_pages_by_topic() {
+		let (store, _temp) = test_store();
+		let statements: Vec<_> = (0..5)
+			.map(|i| signed_statement_with_topics(i, &[topic(0), topic(1 + i as u64 % 2)], None))
+			.chain([signed_statement_with_topics(9, &[topic(3)], None)])
+			.collect();
+		for statement in &statements {
+			store.submit(statement.clone(), StatementSource::Network);
+		}
+
+		let query_all = |filter: TopicFilter| {
+			let mut hashes = Vec::new();
+			let mut start_after = None;
+			loop {
+				let page = store.query(&filter, start_after, 2).unwrap();
+				assert!(page.statements.len() <= 2);
+				hashes.extend(page.statements.into_iter().map(|(hash, _)| hash));
+				match page.next {
+					Some(next) => start_after = Some(next),
+					None => return hashes,
+				}
+			}
+		};
+		let expected = |indices: &[usize]| {
+			let mut hashes: Vec<_> = indices.iter().map(|i| statements[*i].hash()).collect();
+			hashes.sort();
+			hashes
+		};
+
+		assert_eq!(query_all(Default::default()), expected(&[0, 1, 2, 3, 4, 5]));
+		assert_eq!(
+			query_all(TopicFilter { match_all: vec![topic(0)], match_any: vec![] }),
+			expected(&[0, 1, 2, 3, 4])
+		);
+		assert_eq!(
+			query_all(TopicFilter { match_all: vec![topic(0), topic(2)], match_any: vec![] }),
+			expected(&[1, 3])
+		);
+		assert_eq!(
+			query_all(TopicFilter { match_all: vec![], match_any: vec![topic(1), topic(3)] }),
+			expected(&[0, 2, 4, 5])
+		);
+		assert_eq!(
+			query_all(TopicFilter { match_all: vec![topic(0)], match_any: vec![topic(2)] }),
+			expected(&[1, 3])
+		);
+		assert_eq!(
+			query_all(TopicFilter { match_all: vec![topic(7)], match_any: vec![] }),
+			expected(&[])
+		);
+	}
+
+	#[test]
+	fn topic_expiry_is_parsed() {
+		assert_eq!(
+			crate::parse_topic_expiry(&format!("0x{}=60", "01".repeat(32))),
+			Ok(([1; 32], 60)),
+		);
+		assert!(crate::parse_topic_expiry("0x01=60").is_err());
+		assert!(crate::parse_topic_expiry(&"01".repeat(32)).is_err());
+	}
 }
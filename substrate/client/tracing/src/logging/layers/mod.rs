@@ -17,5 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 mod prefix_layer;
+mod recent_logs_layer;
 
 pub use prefix_layer::*;
+pub use recent_logs_layer::*;
@@ -0,0 +1,78 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use chrono::Local;
+use parking_lot::Mutex;
+use std::{collections::VecDeque, fmt::Write, time::Duration};
+use tracing::{field::Field, Event, Subscriber};
+use tracing_subscriber::{layer::Context, Layer};
+
+/// Number of log messages kept by the [`RecentLogsLayer`].
+const RECENT_LOGS_CAPACITY: usize = 1000;
+
+static RECENT_LOGS: Mutex<VecDeque<String>> = parking_lot::const_mutex(VecDeque::new());
+
+/// Returns the most recent log messages, oldest first.
+///
+/// Returns no messages if the messages can't be accessed within a second, as this is called when
+/// the node crashes, possibly while logging.
+pub fn recent_logs() -> Vec<String> {
+	RECENT_LOGS
+		.try_lock_for(Duration::from_secs(1))
+		.map(|logs| logs.iter().cloned().collect())
+		.unwrap_or_default()
+}
+
+/// A `Layer` that keeps the most recent log messages in memory, see [`recent_logs`].
+pub struct RecentLogsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+	fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+		let metadata = event.metadata();
+		let mut line = format!(
+			"{} {} {}:",
+			Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+			metadata.level(),
+			metadata.target(),
+		);
+		event.record(&mut FieldsVisitor(&mut line));
+
+		let mut logs = RECENT_LOGS.lock();
+		if logs.len() == RECENT_LOGS_CAPACITY {
+			logs.pop_front();
+		}
+		logs.push_back(line);
+	}
+}
+
+/// Appends the fields of an event, except the metadata of `log` records.
+struct FieldsVisitor<'a>(&'a mut String);
+
+impl<'a> tracing::field::Visit for FieldsVisitor<'a> {
+	fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+		match field.name() {
+			"message" => {
+				let _ = write!(self.0, " {:?}", value);
+			},
+			name if name.starts_with("log.") => {},
+			name => {
+				let _ = write!(self.0, " {}={:?}", name, value);
+			},
+		}
+	}
+}
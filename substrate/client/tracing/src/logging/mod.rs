@@ -26,15 +26,17 @@ mod directives;
 mod event_format;
 mod fast_local_time;
 mod layers;
+mod runtime_logs;
 mod stderr_writer;
 
 pub(crate) type DefaultLogger = stderr_writer::MakeStderrWriter;
 
 pub use directives::*;
+pub use runtime_logs::{runtime_log_counters, set_runtime_log_levels, RuntimeLogCounters};
 pub use sc_tracing_proc_macro::*;
 
 use is_terminal::IsTerminal;
-use std::io;
+use std::{io, path::PathBuf};
 use tracing::Subscriber;
 use tracing_subscriber::{
 	filter::LevelFilter,
@@ -60,12 +62,17 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 #[non_exhaustive]
-#[error(transparent)]
 pub enum Error {
+	#[error(transparent)]
 	IoError(#[from] io::Error),
+	#[error(transparent)]
 	SetGlobalDefaultError(#[from] tracing::subscriber::SetGlobalDefaultError),
+	#[error(transparent)]
 	DirectiveParseError(#[from] tracing_subscriber::filter::ParseError),
+	#[error(transparent)]
 	SetLoggerError(#[from] tracing_log::log_tracer::SetLoggerError),
+	#[error("Runtime log sink: {0}")]
+	RuntimeLogSink(String),
 }
 
 macro_rules! enable_log_reloading {
@@ -97,6 +104,7 @@ fn prepare_subscriber<N, E, F, W>(
 	profiling_targets: Option<&str>,
 	force_colors: Option<bool>,
 	detailed_output: bool,
+	recent_logs: bool,
 	builder_hook: impl Fn(
 		SubscriberBuilder<format::DefaultFields, EventFormat, EnvFilter, DefaultLogger>,
 	) -> SubscriberBuilder<N, E, F, W>,
@@ -206,7 +214,8 @@ where
 
 	let builder = builder_hook(builder);
 
-	let subscriber = builder.finish().with(PrefixLayer);
+	let subscriber =
+		builder.finish().with(PrefixLayer).with(recent_logs.then_some(RecentLogsLayer));
 
 	Ok(subscriber)
 }
@@ -219,6 +228,8 @@ pub struct LoggerBuilder {
 	log_reloading: bool,
 	force_colors: Option<bool>,
 	detailed_output: bool,
+	recent_logs: bool,
+	runtime_logs: Option<(String, Option<PathBuf>, u32)>,
 }
 
 impl LoggerBuilder {
@@ -231,6 +242,8 @@ impl LoggerBuilder {
 			log_reloading: false,
 			force_colors: None,
 			detailed_output: false,
+			recent_logs: false,
+			runtime_logs: None,
 		}
 	}
 
@@ -270,16 +283,43 @@ impl LoggerBuilder {
 		self
 	}
 
+	/// Keep the most recent log messages in memory, see [`recent_logs`].
+	///
+	/// Off by default, as every log message then has to be formatted and stored.
+	pub fn with_recent_logs(&mut self, enable: bool) -> &mut Self {
+		self.recent_logs = enable;
+		self
+	}
+
 	/// Force enable/disable colors.
 	pub fn with_colors(&mut self, enable: bool) -> &mut Self {
 		self.force_colors = Some(enable);
 		self
 	}
 
+	/// Route the log messages of the runtime to a dedicated sink with the given `directives`,
+	/// instead of the node log.
+	///
+	/// The messages are written to `file`, or to stderr if no file is given, with at most
+	/// `rate_limit` messages per second. A `rate_limit` of `0` disables the limit.
+	pub fn with_runtime_logs<S: Into<String>>(
+		&mut self,
+		directives: S,
+		file: Option<PathBuf>,
+		rate_limit: u32,
+	) -> &mut Self {
+		self.runtime_logs = Some((directives.into(), file, rate_limit));
+		self
+	}
+
 	/// Initialize the global logger
 	///
 	/// This sets various global logging and tracing instances and thus may only be called once.
 	pub fn init(self) -> Result<()> {
+		if let Some((directives, file, rate_limit)) = &self.runtime_logs {
+			runtime_logs::init(directives, file.as_deref(), *rate_limit)?;
+		}
+
 		if let Some((tracing_receiver, profiling_targets)) = self.profiling {
 			if self.log_reloading {
 				let subscriber = prepare_subscriber(
@@ -287,6 +327,7 @@ impl LoggerBuilder {
 					Some(&profiling_targets),
 					self.force_colors,
 					self.detailed_output,
+					self.recent_logs,
 					|builder| enable_log_reloading!(builder),
 				)?;
 				let mut profiling =
@@ -305,6 +346,7 @@ impl LoggerBuilder {
 					Some(&profiling_targets),
 					self.force_colors,
 					self.detailed_output,
+					self.recent_logs,
 					|builder| builder,
 				)?;
 				let mut profiling =
@@ -324,6 +366,7 @@ impl LoggerBuilder {
 				None,
 				self.force_colors,
 				self.detailed_output,
+				self.recent_logs,
 				|builder| enable_log_reloading!(builder),
 			)?;
 
@@ -336,6 +379,7 @@ impl LoggerBuilder {
 				None,
 				self.force_colors,
 				self.detailed_output,
+				self.recent_logs,
 				|builder| builder,
 			)?;
 
@@ -0,0 +1,265 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Dedicated sink of the log messages of the runtime.
+//!
+//! When enabled with [`LoggerBuilder::with_runtime_logs`](super::LoggerBuilder::with_runtime_logs),
+//! the log messages of the runtime bypass the node log filter and are written to a dedicated file,
+//! or to stderr. The sink has its own levels per target, which can be changed at runtime with
+//! [`set_runtime_log_levels`], and writes at most a given number of messages per second. The
+//! messages beyond the limit are dropped, and counted per target together with the written ones,
+//! see [`runtime_log_counters`].
+
+use chrono::Local;
+use log::{Level, LevelFilter};
+use parking_lot::Mutex;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs::OpenOptions,
+	io::{self, LineWriter, Write},
+	path::Path,
+	str::FromStr,
+	sync::OnceLock,
+	time::{Duration, Instant},
+};
+
+static RUNTIME_LOGS: OnceLock<Mutex<RuntimeLogs>> = OnceLock::new();
+
+/// Counters of the log messages of the runtime with one target.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RuntimeLogCounters {
+	/// Number of written messages.
+	pub written: u64,
+	/// Number of messages dropped because of the rate limit.
+	pub dropped: u64,
+}
+
+/// The levels of the runtime log messages, by target.
+struct Levels {
+	default: LevelFilter,
+	targets: HashMap<String, LevelFilter>,
+}
+
+impl Levels {
+	/// Add directives with the syntax of the CLI `<target>=<level>`, e.g. `info,runtime=debug`.
+	///
+	/// A level without target sets the default level.
+	fn add(&mut self, directives: &str) -> Result<(), String> {
+		let parse_level = |level: &str| {
+			LevelFilter::from_str(level).map_err(|_| format!("Invalid log level `{level}`"))
+		};
+		for directive in directives.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+			match directive.split_once('=') {
+				Some((target, level)) => {
+					self.targets.insert(target.to_owned(), parse_level(level)?);
+				},
+				None => self.default = parse_level(directive)?,
+			}
+		}
+		Ok(())
+	}
+
+	/// The level of `target`, inherited from its closest parent target, e.g. `runtime` for
+	/// `runtime::staking`.
+	fn level(&self, target: &str) -> LevelFilter {
+		let mut target = target;
+		loop {
+			if let Some(level) = self.targets.get(target) {
+				return *level
+			}
+			match target.rsplit_once("::") {
+				Some((parent, _)) => target = parent,
+				None => return self.default,
+			}
+		}
+	}
+
+	fn max(&self) -> LevelFilter {
+		self.targets.values().copied().fold(self.default, Ord::max)
+	}
+}
+
+/// The state of the runtime log sink.
+struct RuntimeLogs {
+	levels: Levels,
+	/// Maximum number of messages written per second, `0` for no limit.
+	rate_limit: u32,
+	counters: HashMap<String, RuntimeLogCounters>,
+	/// Start of the current rate limit period.
+	period_start: Instant,
+	written_in_period: u32,
+	dropped_in_period: u64,
+	output: Box<dyn Write + Send>,
+}
+
+impl RuntimeLogs {
+	fn log(&mut self, now: Instant, level: Level, target: &str, message: &str) {
+		if level > self.levels.level(target) {
+			return
+		}
+
+		if now.duration_since(self.period_start) >= Duration::from_secs(1) {
+			if self.dropped_in_period > 0 {
+				let _ = writeln!(
+					self.output,
+					"{} Dropped {} runtime log messages because of the rate limit",
+					Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+					self.dropped_in_period,
+				);
+			}
+			self.period_start = now;
+			self.written_in_period = 0;
+			self.dropped_in_period = 0;
+		}
+
+		let counters = self.counters.entry(target.to_owned()).or_default();
+		if self.rate_limit != 0 && self.written_in_period >= self.rate_limit {
+			counters.dropped += 1;
+			self.dropped_in_period += 1;
+			return
+		}
+		counters.written += 1;
+		self.written_in_period += 1;
+		let _ = writeln!(
+			self.output,
+			"{} {level:<5} {target}: {message}",
+			Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+		);
+	}
+}
+
+/// Forwards the log messages of the runtime to the runtime log sink.
+struct Sink;
+
+impl sp_io::RuntimeLogSink for Sink {
+	fn log(&self, level: Level, target: &str, message: &str) {
+		if let Some(logs) = RUNTIME_LOGS.get() {
+			logs.lock().log(Instant::now(), level, target, message);
+		}
+	}
+
+	fn max_level(&self) -> LevelFilter {
+		RUNTIME_LOGS.get().map_or(LevelFilter::Off, |logs| logs.lock().levels.max())
+	}
+}
+
+/// Enable the runtime log sink, writing the messages to `file` or to stderr.
+pub(crate) fn init(directives: &str, file: Option<&Path>, rate_limit: u32) -> super::Result<()> {
+	let mut levels = Levels { default: LevelFilter::Info, targets: HashMap::new() };
+	levels.add(directives).map_err(super::Error::RuntimeLogSink)?;
+	let output: Box<dyn Write + Send> = match file {
+		Some(file) =>
+			Box::new(LineWriter::new(OpenOptions::new().create(true).append(true).open(file)?)),
+		None => Box::new(io::stderr()),
+	};
+
+	let logs = RuntimeLogs {
+		levels,
+		rate_limit,
+		counters: HashMap::new(),
+		period_start: Instant::now(),
+		written_in_period: 0,
+		dropped_in_period: 0,
+		output,
+	};
+	if RUNTIME_LOGS.set(Mutex::new(logs)).is_err() || !sp_io::set_runtime_log_sink(Box::new(Sink)) {
+		return Err(super::Error::RuntimeLogSink("already enabled".into()))
+	}
+	Ok(())
+}
+
+/// Add directives to the levels of the runtime log sink.
+///
+/// The syntax is identical to the CLI `<target>=<level>`:
+///
+/// `info,runtime::staking=debug`
+pub fn set_runtime_log_levels(directives: &str) -> Result<(), String> {
+	RUNTIME_LOGS
+		.get()
+		.ok_or("Runtime log sink is not enabled")?
+		.lock()
+		.levels
+		.add(directives)
+}
+
+/// Returns the counters of the runtime log sink, by target.
+pub fn runtime_log_counters() -> Result<BTreeMap<String, RuntimeLogCounters>, String> {
+	let logs = RUNTIME_LOGS.get().ok_or("Runtime log sink is not enabled")?.lock();
+	Ok(logs.counters.iter().map(|(target, counters)| (target.clone(), *counters)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+
+	#[derive(Clone, Default)]
+	struct Output(Arc<Mutex<Vec<u8>>>);
+
+	impl Write for Output {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().write(buf)
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn levels_are_inherited() {
+		let mut levels = Levels { default: LevelFilter::Info, targets: HashMap::new() };
+		levels.add("warn,runtime=debug,runtime::staking=trace").unwrap();
+		assert_eq!(levels.level("runtime::staking::election"), LevelFilter::Trace);
+		assert_eq!(levels.level("runtime::balances"), LevelFilter::Debug);
+		assert_eq!(levels.level("runtime"), LevelFilter::Debug);
+		assert_eq!(levels.level("other"), LevelFilter::Warn);
+		assert_eq!(levels.max(), LevelFilter::Trace);
+		assert!(levels.add("runtime=verbose").is_err());
+	}
+
+	#[test]
+	fn messages_are_rate_limited() {
+		let output = Output::default();
+		let start = Instant::now();
+		let mut logs = RuntimeLogs {
+			levels: Levels { default: LevelFilter::Info, targets: HashMap::new() },
+			rate_limit: 2,
+			counters: HashMap::new(),
+			period_start: start,
+			written_in_period: 0,
+			dropped_in_period: 0,
+			output: Box::new(output.clone()),
+		};
+
+		logs.log(start, Level::Debug, "runtime", "filtered");
+		for _ in 0..3 {
+			logs.log(start, Level::Info, "runtime", "first period");
+		}
+		logs.log(start + Duration::from_secs(1), Level::Info, "runtime::staking", "second period");
+
+		assert_eq!(logs.counters["runtime"], RuntimeLogCounters { written: 2, dropped: 1 });
+		assert_eq!(logs.counters["runtime::staking"], RuntimeLogCounters { written: 1, dropped: 0 });
+		let output = String::from_utf8(output.0.lock().clone()).unwrap();
+		let lines = output.lines().collect::<Vec<_>>();
+		assert_eq!(lines.len(), 4);
+		assert!(lines[0].ends_with("INFO  runtime: first period"));
+		assert!(lines[2].ends_with("Dropped 1 runtime log messages because of the rate limit"));
+		assert!(lines[3].ends_with("INFO  runtime::staking: second period"));
+	}
+}
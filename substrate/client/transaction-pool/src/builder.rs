@@ -28,7 +28,7 @@ use crate::{
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_transaction_pool_api::{LocalTransactionPool, MaintainedTransactionPool};
 use sp_core::traits::SpawnEssentialNamed;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, transaction_validity::TransactionPriority};
 use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 /// The type of transaction pool.
@@ -84,6 +84,20 @@ impl TransactionPoolOptions {
 		TransactionPoolOptions { options, txpool_type }
 	}
 
+	/// Sets the capacity of the ready queue reserved for privileged transactions.
+	///
+	/// See [`ReservedLimit`](crate::ReservedLimit) for which transactions are privileged.
+	pub fn with_reserved_limit(
+		mut self,
+		count: usize,
+		total_bytes: usize,
+		min_priority: TransactionPriority,
+	) -> Self {
+		self.options.reserved =
+			crate::graph::base_pool::ReservedLimit { count, total_bytes, min_priority };
+		self
+	}
+
 	/// Creates predefined options for benchmarking
 	pub fn new_for_benchmarks() -> TransactionPoolOptions {
 		TransactionPoolOptions {
@@ -92,6 +106,7 @@ impl TransactionPoolOptions {
 					count: 100_000,
 					total_bytes: 100 * 1024 * 1024,
 				},
+				reserved: Default::default(),
 				future: crate::graph::base_pool::Limit {
 					count: 100_000,
 					total_bytes: 100 * 1024 * 1024,
@@ -208,6 +208,7 @@ where
 			Default::default(),
 			mempool_max_transactions_count,
 			ready_limits.total_bytes + future_limits.total_bytes,
+			Default::default(),
 		));
 
 		let (dropped_stream_controller, dropped_stream) =
@@ -339,6 +340,7 @@ where
 			metrics.clone(),
 			TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER * options.total_count(),
 			options.ready.total_bytes + options.future.total_bytes,
+			options.reserved.clone(),
 		));
 
 		let (dropped_stream_controller, dropped_stream) =
@@ -1297,7 +1299,7 @@ where
 		)
 		.await
 		.into_iter()
-		.for_each(|enacted_log| {
+		.for_each(|(enacted_log, _)| {
 			pruned_log.extend(enacted_log);
 		});
 
@@ -1376,7 +1378,8 @@ where
 	///
 	/// Performs a house-keeping required for finalized event. This includes:
 	/// - executing the on finalized procedure for the view store,
-	/// - purging finalized transactions from the mempool and triggering mempool revalidation,
+	/// - purging finalized transactions from the mempool,
+	/// - removing stale transactions from the mempool and triggering mempool revalidation,
 	async fn handle_finalized(&self, finalized_hash: Block::Hash, tree_route: &[Block::Hash]) {
 		let finalized_number = self.api.block_id_to_number(&BlockId::Hash(finalized_hash));
 		debug!(
@@ -1395,12 +1398,10 @@ where
 			.report(|metrics| metrics.finalized_txs.inc_by(finalized_xts.len() as _));
 
 		if let Ok(Some(finalized_number)) = finalized_number {
+			let finalized_block = HashAndNumber { hash: finalized_hash, number: finalized_number };
+			self.mempool.remove_expired(&self.view_store, &finalized_block);
 			self.revalidation_queue
-				.revalidate_mempool(
-					self.mempool.clone(),
-					self.view_store.clone(),
-					HashAndNumber { hash: finalized_hash, number: finalized_number },
-				)
+				.revalidate_mempool(self.mempool.clone(), self.view_store.clone(), finalized_block)
 				.await;
 		} else {
 			trace!(
@@ -75,6 +75,8 @@ pub struct Metrics {
 	pub submitted_from_mempool_txs: Counter<U64>,
 	/// Total number of transactions found as invalid during mempool revalidation.
 	pub mempool_revalidation_invalid_txs: Counter<U64>,
+	/// Total number of transactions removed from mempool as stale.
+	pub mempool_expired_txs: Counter<U64>,
 	/// Total number of transactions found as invalid during view revalidation.
 	pub view_revalidation_invalid_txs: Counter<U64>,
 	/// Total number of valid transactions processed during view revalidation.
@@ -319,6 +321,13 @@ impl MetricsRegistrant for Metrics {
 				)?,
 				registry,
 			)?,
+			mempool_expired_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_mempool_expired_txs_total",
+					"Total number of transactions removed from mempool as stale.",
+				)?,
+				registry,
+			)?,
 			view_revalidation_invalid_txs: register(
 				Counter::new(
 					"substrate_sub_txpool_view_revalidation_invalid_txs_total",
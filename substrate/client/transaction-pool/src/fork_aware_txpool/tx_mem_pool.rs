@@ -51,7 +51,11 @@ use sp_runtime::{
 use crate::{
 	common::tracing_log_xt::log_xt_trace,
 	graph,
-	graph::{base_pool::TimedTransactionSource, tracked_map::Size, ExtrinsicFor, ExtrinsicHash},
+	graph::{
+		base_pool::{ReservedLimit, TimedTransactionSource},
+		tracked_map::Size,
+		ExtrinsicFor, ExtrinsicHash,
+	},
 	LOG_TARGET,
 };
 
@@ -94,6 +98,8 @@ where
 	/// Priority of transaction at some block. It is assumed it will not be changed often. None if
 	/// not known.
 	priority: RwLock<Option<TransactionPriority>>,
+	/// The block number at which the transaction becomes stale. None if not known.
+	valid_till: RwLock<Option<u64>>,
 }
 
 impl<ChainApi, Block> TxInMemPool<ChainApi, Block>
@@ -154,6 +160,7 @@ where
 			validated_at: AtomicU64::new(0),
 			bytes,
 			priority: priority.into(),
+			valid_till: None.into(),
 		}
 	}
 
@@ -173,6 +180,11 @@ where
 	pub(crate) fn priority(&self) -> Option<TransactionPriority> {
 		*self.priority.read()
 	}
+
+	/// Returns the block number at which the transaction becomes stale.
+	pub(crate) fn valid_till(&self) -> Option<u64> {
+		*self.valid_till.read()
+	}
 }
 
 impl<ChainApi, Block> Size for Arc<TxInMemPool<ChainApi, Block>>
@@ -224,6 +236,9 @@ where
 
 	/// Maximal size of encodings of all transactions in the memory pool.
 	max_transactions_total_bytes: usize,
+
+	/// Capacity in addition to the limits above, reserved for privileged transactions.
+	reserved: ReservedLimit,
 }
 
 /// Helper structure to encapsulate a result of [`TxMemPool::try_insert`].
@@ -257,6 +272,7 @@ where
 		metrics: PrometheusMetrics,
 		max_transactions_count: usize,
 		max_transactions_total_bytes: usize,
+		reserved: ReservedLimit,
 	) -> Self {
 		Self {
 			api,
@@ -265,6 +281,7 @@ where
 			metrics,
 			max_transactions_count,
 			max_transactions_total_bytes,
+			reserved,
 		}
 	}
 
@@ -282,6 +299,7 @@ where
 			metrics: Default::default(),
 			max_transactions_count,
 			max_transactions_total_bytes,
+			reserved: Default::default(),
 		}
 	}
 
@@ -312,9 +330,26 @@ where
 	}
 
 	/// Returns true if provided values would exceed defined limits.
-	fn is_limit_exceeded(&self, length: usize, current_total_bytes: usize) -> bool {
-		length > self.max_transactions_count ||
-			current_total_bytes > self.max_transactions_total_bytes
+	///
+	/// Privileged transactions may additionally use the reserved capacity.
+	fn is_limit_exceeded(
+		&self,
+		length: usize,
+		current_total_bytes: usize,
+		privileged: bool,
+	) -> bool {
+		let (reserved_count, reserved_bytes) =
+			if privileged { (self.reserved.count, self.reserved.total_bytes) } else { (0, 0) };
+		length > self.max_transactions_count.saturating_add(reserved_count) ||
+			current_total_bytes > self.max_transactions_total_bytes.saturating_add(reserved_bytes)
+	}
+
+	/// Returns true if the transaction is privileged, see [`ReservedLimit`].
+	///
+	/// Transactions with unknown priority are only privileged if they are submitted locally.
+	fn is_privileged(&self, tx: &TxInMemPool<ChainApi, Block>) -> bool {
+		self.reserved
+			.is_privileged_source(tx.source.source, tx.priority().unwrap_or_default())
 	}
 
 	/// Attempts to insert a transaction into the memory pool, ensuring it does not
@@ -327,9 +362,10 @@ where
 		let mut transactions = self.transactions.write();
 
 		let bytes = self.transactions.bytes();
+		let privileged = self.is_privileged(&tx);
 
 		let result = match (
-			self.is_limit_exceeded(transactions.len() + 1, bytes + tx.bytes),
+			self.is_limit_exceeded(transactions.len() + 1, bytes + tx.bytes, privileged),
 			transactions.contains_key(&tx_hash),
 		) {
 			(false, false) => {
@@ -371,7 +407,8 @@ where
 	) -> Result<InsertionInfo<ExtrinsicHash<ChainApi>>, sc_transaction_pool_api::error::Error> {
 		let (hash, length) = self.api.hash_and_length(&new_tx);
 		let new_tx = TxInMemPool::new_with_priority(watched, source, new_tx, length, priority);
-		if new_tx.bytes > self.max_transactions_total_bytes {
+		let privileged = self.is_privileged(&new_tx);
+		if self.is_limit_exceeded(1, new_tx.bytes, privileged) {
 			return Err(sc_transaction_pool_api::error::Error::ImmediatelyDropped);
 		}
 
@@ -381,6 +418,18 @@ where
 			return Err(sc_transaction_pool_api::error::Error::AlreadyImported(Box::new(hash)));
 		}
 
+		// Privileged transactions fitting into the reserved capacity don't replace anything.
+		if privileged &&
+			!self.is_limit_exceeded(
+				transactions.len() + 1,
+				self.transactions.bytes() + new_tx.bytes,
+				true,
+			) {
+			let source = new_tx.source();
+			transactions.insert(hash, Arc::from(new_tx));
+			return Ok(InsertionInfo::new(hash, source))
+		}
+
 		let mut sorted = transactions
 			.iter()
 			.filter_map(|(h, v)| v.priority().map(|_| (*h, v.clone())))
@@ -401,7 +450,8 @@ where
 
 		let mut total_size_removed = 0usize;
 		let mut to_be_removed = vec![];
-		let free_bytes = self.max_transactions_total_bytes - self.transactions.bytes();
+		let free_bytes =
+			self.max_transactions_total_bytes.saturating_sub(self.transactions.bytes());
 
 		loop {
 			let Some((worst_hash, worst_tx)) = sorted.pop() else {
@@ -425,7 +475,11 @@ where
 		for worst_hash in &to_be_removed {
 			transactions.remove(worst_hash);
 		}
-		debug_assert!(!self.is_limit_exceeded(transactions.len(), self.transactions.bytes()));
+		debug_assert!(!self.is_limit_exceeded(
+			transactions.len(),
+			self.transactions.bytes(),
+			privileged
+		));
 
 		Ok(InsertionInfo::new_with_removed(hash, source, to_be_removed))
 	}
@@ -510,8 +564,12 @@ where
 			self.api
 				.validate_transaction(finalized_block.hash, xt.source.clone().into(), xt.tx())
 				.map(move |validation_result| {
-					xt.validated_at
-						.store(finalized_block.number.into().as_u64(), atomic::Ordering::Relaxed);
+					let finalized_block_number = finalized_block.number.into().as_u64();
+					xt.validated_at.store(finalized_block_number, atomic::Ordering::Relaxed);
+					if let Ok(Ok(validity)) = &validation_result {
+						*xt.valid_till.write() =
+							Some(finalized_block_number.saturating_add(validity.longevity));
+					}
 					(xt_hash, validation_result)
 				})
 		});
@@ -578,9 +636,72 @@ where
 	) {
 		let revalidated_invalid_hashes = self.revalidate_inner(finalized_block.clone()).await;
 
-		let mut invalid_hashes_subtrees =
-			revalidated_invalid_hashes.clone().into_iter().collect::<HashSet<_>>();
-		for tx in &revalidated_invalid_hashes {
+		let invalid_hashes_subtrees_len =
+			self.remove_invalid(&view_store, &revalidated_invalid_hashes);
+
+		self.metrics.report(|metrics| {
+			metrics.mempool_revalidation_invalid_txs.inc_by(invalid_hashes_subtrees_len as _)
+		});
+
+		let revalidated_invalid_hashes_len = revalidated_invalid_hashes.len();
+
+		trace!(
+			target: LOG_TARGET,
+			?finalized_block,
+			revalidated_invalid_hashes_len,
+			invalid_hashes_subtrees_len,
+			"mempool::revalidate"
+		);
+	}
+
+	/// Removes the transactions which became stale at the given finalized block.
+	///
+	/// The block number at which a transaction becomes stale is known from its last validation, so
+	/// the transactions whose mortality expired are removed without waiting for the revalidation.
+	pub(super) fn remove_expired(
+		&self,
+		view_store: &ViewStore<ChainApi, Block>,
+		finalized_block: &HashAndNumber<Block>,
+	) {
+		let finalized_block_number = finalized_block.number.into().as_u64();
+		let expired_hashes = self
+			.transactions
+			.clone_map()
+			.into_iter()
+			.filter(|(_, xt)| {
+				xt.valid_till().is_some_and(|valid_till| valid_till <= finalized_block_number)
+			})
+			.map(|(tx_hash, _)| tx_hash)
+			.collect::<Vec<_>>();
+		if expired_hashes.is_empty() {
+			return
+		}
+
+		let removed_len = self.remove_invalid(view_store, &expired_hashes);
+
+		self.metrics
+			.report(|metrics| metrics.mempool_expired_txs.inc_by(removed_len as _));
+
+		debug!(
+			target: LOG_TARGET,
+			?finalized_block,
+			expired_hashes_len = expired_hashes.len(),
+			removed_len,
+			"mempool::remove_expired"
+		);
+	}
+
+	/// Removes the given invalid transactions, together with the transactions depending on them,
+	/// from the memory pool and the views, and notifies the listener.
+	///
+	/// Returns the number of removed transactions.
+	fn remove_invalid(
+		&self,
+		view_store: &ViewStore<ChainApi, Block>,
+		invalid_hashes: &[ExtrinsicHash<ChainApi>],
+	) -> usize {
+		let mut invalid_hashes_subtrees = invalid_hashes.iter().copied().collect::<HashSet<_>>();
+		for tx in invalid_hashes {
 			invalid_hashes_subtrees.extend(
 				view_store
 					.remove_transaction_subtree(*tx, |_, _| {})
@@ -596,36 +717,23 @@ where
 			});
 		};
 
-		self.metrics.report(|metrics| {
-			metrics
-				.mempool_revalidation_invalid_txs
-				.inc_by(invalid_hashes_subtrees.len() as _)
-		});
-
-		let revalidated_invalid_hashes_len = revalidated_invalid_hashes.len();
 		let invalid_hashes_subtrees_len = invalid_hashes_subtrees.len();
-
 		self.listener
 			.transactions_invalidated(&invalid_hashes_subtrees.into_iter().collect::<Vec<_>>());
-
-		trace!(
-			target: LOG_TARGET,
-			?finalized_block,
-			revalidated_invalid_hashes_len,
-			invalid_hashes_subtrees_len,
-			"mempool::revalidate"
-		);
+		invalid_hashes_subtrees_len
 	}
 
-	/// Updates the priority of transaction stored in mempool using provided view_store submission
-	/// outcome.
+	/// Updates the priority and the end of the validity period of transaction stored in mempool
+	/// using provided view_store submission outcome.
 	pub(super) fn update_transaction_priority(&self, outcome: &ViewStoreSubmitOutcome<ChainApi>) {
-		outcome.priority().map(|priority| {
-			self.transactions
-				.write()
-				.get_mut(&outcome.hash())
-				.map(|p| *p.priority.write() = Some(priority))
-		});
+		if let Some(tx) = self.transactions.write().get_mut(&outcome.hash()) {
+			if let Some(priority) = outcome.priority() {
+				*tx.priority.write() = Some(priority);
+			}
+			if let Some(valid_till) = outcome.valid_till() {
+				*tx.valid_till.write() = Some(valid_till);
+			}
+		}
 	}
 
 	/// Counts the number of transactions in the provided iterator of hashes
@@ -673,6 +781,34 @@ mod tx_mem_pool_tests {
 		));
 	}
 
+	#[test]
+	fn extend_unwatched_uses_reserved_capacity_for_local_transactions() {
+		let max = 10;
+		let api = Arc::from(TestApi::default());
+		let mut mempool = TxMemPool::new_test(api, max, usize::MAX);
+		mempool.reserved =
+			ReservedLimit { count: 2, total_bytes: 0, min_priority: TransactionPriority::MAX };
+
+		let xts = (0..max).map(|x| Arc::from(uxt(x as _))).collect::<Vec<_>>();
+		let results = mempool.extend_unwatched(TransactionSource::External, &xts);
+		assert!(results.iter().all(Result::is_ok));
+
+		let xts = (max..max + 3).map(|x| Arc::from(uxt(x as _))).collect::<Vec<_>>();
+		let results = mempool.extend_unwatched(TransactionSource::External, &xts[..1]);
+		assert!(matches!(
+			results.into_iter().last().unwrap().unwrap_err(),
+			sc_transaction_pool_api::error::Error::ImmediatelyDropped
+		));
+
+		let results = mempool.extend_unwatched(TransactionSource::Local, &xts);
+		assert!(results.iter().take(2).all(Result::is_ok));
+		assert!(matches!(
+			results.into_iter().last().unwrap().unwrap_err(),
+			sc_transaction_pool_api::error::Error::ImmediatelyDropped
+		));
+		assert_eq!(mempool.len(), max + 2);
+	}
+
 	#[test]
 	fn extend_unwatched_detects_already_imported() {
 		sp_tracing::try_init_simple();
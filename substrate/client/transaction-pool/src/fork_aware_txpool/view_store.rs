@@ -184,7 +184,7 @@ impl<ChainApi: graph::ChainApi> From<ValidatedPoolSubmitOutcome<ChainApi>>
 	for ViewStoreSubmitOutcome<ChainApi>
 {
 	fn from(value: ValidatedPoolSubmitOutcome<ChainApi>) -> Self {
-		Self::new(value.hash(), value.priority())
+		Self::new(value.hash(), value.priority()).with_valid_till(value.valid_till())
 	}
 }
 
@@ -444,38 +444,40 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	/// them. Technically the worst transaction should be evaluated by computing the entire pending
 	/// set. We use a simplified approach to remove transactions with the lowest priority first or
 	/// those that occupy the pool for the longest time in case priority is the same.
+	///
+	/// Privileged transactions, see [`ReservedLimit`], may use the `reserved` capacity of the
+	/// ready queue on top of the `ready` limit, which only applies to the other transactions. So
+	/// other transactions can't push privileged ones out of the pool, as long as the privileged
+	/// ones fit into the reserved capacity.
 	pub fn enforce_limits(
 		&mut self,
 		ready: &Limit,
+		reserved: &ReservedLimit,
 		future: &Limit,
 	) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		let mut removed = vec![];
+		let total = Limit {
+			count: ready.count.saturating_add(reserved.count),
+			total_bytes: ready.total_bytes.saturating_add(reserved.total_bytes),
+		};
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
-			// find the worst transaction
-			let worst =
-				self.ready.fold::<Option<TransactionRef<Hash, Ex>>, _>(None, |worst, current| {
-					let transaction = &current.transaction;
-					worst
-						.map(|worst| {
-							// Here we don't use `TransactionRef`'s ordering implementation because
-							// while it prefers priority like need here, it also prefers older
-							// transactions for inclusion purposes and limit enforcement needs to
-							// prefer newer transactions instead and drop the older ones.
-							match worst.transaction.priority.cmp(&transaction.transaction.priority)
-							{
-								Ordering::Less => worst,
-								Ordering::Equal =>
-									if worst.insertion_id > transaction.insertion_id {
-										transaction.clone()
-									} else {
-										worst
-									},
-								Ordering::Greater => transaction.clone(),
-							}
-						})
-						.or_else(|| Some(transaction.clone()))
-				});
+			let (count, bytes) = self.ready.fold((0, 0), |(count, bytes), current| {
+				let transaction = &current.transaction.transaction;
+				if reserved.is_privileged(transaction) {
+					(count, bytes)
+				} else {
+					(count + 1, bytes + transaction.bytes)
+				}
+			});
+
+			let worst = if ready.is_exceeded(count, bytes) {
+				self.worst_ready(|transaction| !reserved.is_privileged(transaction))
+			} else if total.is_exceeded(self.ready.len(), self.ready.bytes()) {
+				self.worst_ready(|_| true)
+			} else {
+				break
+			};
 
 			if let Some(worst) = worst {
 				removed.append(&mut self.remove_subtree(&[worst.transaction.hash.clone()]))
@@ -518,6 +520,37 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		removed
 	}
 
+	/// Returns the worst of the ready transactions matching `filter`.
+	fn worst_ready(
+		&self,
+		filter: impl Fn(&Transaction<Hash, Ex>) -> bool,
+	) -> Option<TransactionRef<Hash, Ex>> {
+		self.ready.fold::<Option<TransactionRef<Hash, Ex>>, _>(None, |worst, current| {
+			let transaction = &current.transaction;
+			if !filter(&transaction.transaction) {
+				return worst
+			}
+			worst
+				.map(|worst| {
+					// Here we don't use `TransactionRef`'s ordering implementation because
+					// while it prefers priority like need here, it also prefers older
+					// transactions for inclusion purposes and limit enforcement needs to
+					// prefer newer transactions instead and drop the older ones.
+					match worst.transaction.priority.cmp(&transaction.transaction.priority) {
+						Ordering::Less => worst,
+						Ordering::Equal =>
+							if worst.insertion_id > transaction.insertion_id {
+								transaction.clone()
+							} else {
+								worst
+							},
+						Ordering::Greater => transaction.clone(),
+					}
+				})
+				.or_else(|| Some(transaction.clone()))
+		})
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -612,6 +645,41 @@ impl Limit {
 	}
 }
 
+/// Capacity of the ready queue reserved for privileged transactions.
+///
+/// Privileged transactions are the locally submitted ones, e.g. offence reports submitted by
+/// offchain workers, and the ones with a priority of at least `min_priority`. FRAME based runtimes
+/// give operational-class transactions and many unsigned reports high priorities, so a threshold
+/// lets them use the reserved capacity as well.
+#[derive(Debug, Clone)]
+pub struct ReservedLimit {
+	/// Maximal number of privileged transactions in addition to the ready limit.
+	pub count: usize,
+	/// Maximal size of encodings of privileged transactions in addition to the ready limit.
+	pub total_bytes: usize,
+	/// Minimal priority of transactions from other sources to be privileged.
+	pub min_priority: Priority,
+}
+
+impl ReservedLimit {
+	/// Returns true if the transaction is privileged.
+	pub fn is_privileged<Hash, Ex>(&self, transaction: &Transaction<Hash, Ex>) -> bool {
+		self.is_privileged_source(transaction.source.source, transaction.priority)
+	}
+
+	/// Returns true if a transaction from `source` with `priority` is privileged.
+	pub fn is_privileged_source(&self, source: TransactionSource, priority: Priority) -> bool {
+		source == TransactionSource::Local || priority >= self.min_priority
+	}
+}
+
+/// No capacity is reserved.
+impl Default for ReservedLimit {
+	fn default() -> Self {
+		Self { count: 0, total_bytes: 0, min_priority: Priority::MAX }
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -636,6 +704,42 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn enforce_limits_keeps_privileged_transactions_in_reserved_capacity() {
+		// given
+		let mut pool = pool();
+		let ready = Limit { count: 2, total_bytes: 100 };
+		let reserved = ReservedLimit { count: 1, total_bytes: 100, min_priority: 100 };
+		let future = Limit { count: 10, total_bytes: 100 };
+		pool.import(Transaction { hash: 1, priority: 50, ..default_tx() }).unwrap();
+		pool.import(Transaction { hash: 2, priority: 50, ..default_tx() }).unwrap();
+		pool.import(Transaction {
+			hash: 3,
+			priority: 1,
+			source: TimedTransactionSource::new_local(false),
+			..default_tx()
+		})
+		.unwrap();
+		assert!(pool.enforce_limits(&ready, &reserved, &future).is_empty());
+
+		// when
+		pool.import(Transaction { hash: 4, priority: 60, ..default_tx() }).unwrap();
+		let removed = pool.enforce_limits(&ready, &reserved, &future);
+
+		// then
+		// the oldest of the worst other transactions is removed, the local one stays
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+
+		// when
+		pool.import(Transaction { hash: 5, priority: 100, ..default_tx() }).unwrap();
+		let removed = pool.enforce_limits(&ready, &reserved, &future);
+
+		// then
+		// the privileged transactions exceed the reserved capacity, so the worst one is removed
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3]);
+		assert_eq!(pool.ready().count(), 3);
+	}
+
 	#[test]
 	fn prune_for_ready_works() {
 		// given
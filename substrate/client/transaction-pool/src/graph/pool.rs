@@ -138,6 +138,8 @@ pub trait ChainApi: Send + Sync {
 pub struct Options {
 	/// Ready queue limits.
 	pub ready: base::Limit,
+	/// Capacity of the ready queue reserved for privileged transactions.
+	pub reserved: base::ReservedLimit,
 	/// Future queue limits.
 	pub future: base::Limit,
 	/// Reject future transactions.
@@ -150,6 +152,7 @@ impl Default for Options {
 	fn default() -> Self {
 		Self {
 			ready: base::Limit { count: 8192, total_bytes: 20 * 1024 * 1024 },
+			reserved: Default::default(),
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
@@ -158,9 +161,9 @@ impl Default for Options {
 }
 
 impl Options {
-	/// Total (ready+future) maximal number of transactions in the pool.
+	/// Total (ready+reserved+future) maximal number of transactions in the pool.
 	pub fn total_count(&self) -> usize {
-		self.ready.count + self.future.count
+		self.ready.count + self.reserved.count + self.future.count
 	}
 }
 
@@ -299,12 +302,14 @@ impl<B: ChainApi, L: EventHandler<B>> Pool<B, L> {
 	/// To perform pruning we need the tags that each extrinsic provides and to avoid calling
 	/// into runtime too often we first look up all extrinsics that are in the pool and get
 	/// their provided tags from there. Otherwise we query the runtime at the `parent` block.
+	///
+	/// Returns the number of transactions removed as stale.
 	pub async fn prune(
 		&self,
 		at: &HashAndNumber<B::Block>,
 		parent: <B::Block as BlockT>::Hash,
 		extrinsics: &[RawExtrinsicFor<B>],
-	) {
+	) -> usize {
 		log::debug!(
 			target: LOG_TARGET,
 			"Starting pruning of block {:?} (extrinsics: {})",
@@ -373,7 +378,7 @@ impl<B: ChainApi, L: EventHandler<B>> Pool<B, L> {
 	/// 1. Provide that tag directly
 	/// 2. Are a dependency of pruned transaction.
 	///
-	/// Returns transactions that have been removed from the pool and must be reverified
+	/// Transactions that have been removed from the pool must be reverified
 	/// before reinserting to the pool.
 	///
 	/// By removing predecessor transactions as well we might actually end up
@@ -384,12 +389,15 @@ impl<B: ChainApi, L: EventHandler<B>> Pool<B, L> {
 	/// the second parameter of `known_imported_hashes`. These transactions
 	/// (if pruned) are not revalidated and become temporarily banned to
 	/// prevent importing them in the (near) future.
+	///
+	/// Finally the transactions beyond their longevity period are removed, the number of which is
+	/// returned.
 	pub async fn prune_tags(
 		&self,
 		at: &HashAndNumber<B::Block>,
 		tags: impl IntoIterator<Item = Tag>,
 		known_imported_hashes: impl IntoIterator<Item = ExtrinsicHash<B>> + Clone,
-	) {
+	) -> usize {
 		let now = Instant::now();
 		log::trace!(target: LOG_TARGET, "Pruning at {:?}", at);
 		// Prune all transactions that provide given tags
@@ -539,6 +547,14 @@ mod tests {
 
 	type Pool<Api> = super::Pool<Api, ()>;
 
+	#[test]
+	fn default_options_reserve_no_capacity() {
+		let options = Options::default();
+
+		assert_eq!((options.reserved.count, options.reserved.total_bytes), (0, 0));
+		assert_eq!(options.total_count(), 8192 + 512);
+	}
+
 	#[test]
 	fn should_validate_and_import_transaction() {
 		// given
@@ -766,9 +782,10 @@ mod tests {
 		.hash();
 
 		// when
-		pool.validated_pool.clear_stale(&api.expect_hash_and_number(5));
+		let removed = pool.validated_pool.clear_stale(&api.expect_hash_and_number(5));
 
 		// then
+		assert_eq!(removed, 3);
 		assert_eq!(pool.validated_pool().ready().count(), 0);
 		assert_eq!(pool.validated_pool().status().future, 0);
 		assert_eq!(pool.validated_pool().status().ready, 0);
@@ -120,6 +120,9 @@ pub struct BaseSubmitOutcome<B: ChainApi, W> {
 
 	/// The priority of the transaction. Defaults to None if unknown.
 	priority: Option<TransactionPriority>,
+
+	/// The block number at which the transaction becomes stale. Defaults to None if unknown.
+	valid_till: Option<u64>,
 }
 
 /// Type alias to outcome of submission to `ValidatedPool`.
@@ -129,7 +132,13 @@ pub type ValidatedPoolSubmitOutcome<B> =
 impl<B: ChainApi, W> BaseSubmitOutcome<B, W> {
 	/// Creates a new instance with given hash and priority.
 	pub fn new(hash: ExtrinsicHash<B>, priority: Option<TransactionPriority>) -> Self {
-		Self { hash, priority, watcher: None }
+		Self { hash, priority, watcher: None, valid_till: None }
+	}
+
+	/// Sets the block number at which the transaction becomes stale.
+	pub fn with_valid_till(mut self, valid_till: Option<u64>) -> Self {
+		self.valid_till = valid_till;
+		self
 	}
 
 	/// Sets the transaction watcher.
@@ -143,6 +152,11 @@ impl<B: ChainApi, W> BaseSubmitOutcome<B, W> {
 		self.priority
 	}
 
+	/// Provides the block number at which the submitted transaction becomes stale.
+	pub fn valid_till(&self) -> Option<u64> {
+		self.valid_till
+	}
+
 	/// Provides hash of submitted transaction.
 	pub fn hash(&self) -> ExtrinsicHash<B> {
 		self.hash
@@ -314,6 +328,7 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		match tx {
 			ValidatedTransaction::Valid(tx) => {
 				let priority = tx.priority;
+				let valid_till = tx.valid_till;
 				log::trace!(target: LOG_TARGET, "[{:?}] ValidatedPool::submit_one", tx.hash);
 				if !tx.propagate && !(self.is_validator.0)() {
 					return Err(error::Error::Unactionable.into())
@@ -341,7 +356,8 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 
 				let mut event_dispatcher = self.event_dispatcher.write();
 				fire_events(&mut *event_dispatcher, &imported);
-				Ok(ValidatedPoolSubmitOutcome::new(*imported.hash(), Some(priority)))
+				Ok(ValidatedPoolSubmitOutcome::new(*imported.hash(), Some(priority))
+					.with_valid_till(Some(valid_till)))
 			},
 			ValidatedTransaction::Invalid(hash, err) => {
 				log::trace!(target: LOG_TARGET, "[{:?}] ValidatedPool::submit_one invalid: {:?}", hash, err);
@@ -359,6 +375,7 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 	fn enforce_limits(&self) -> HashSet<ExtrinsicHash<B>> {
 		let status = self.pool.read().status();
 		let ready_limit = &self.options.ready;
+		let reserved_limit = &self.options.reserved;
 		let future_limit = &self.options.future;
 
 		if ready_limit.is_exceeded(status.ready, status.ready_bytes) ||
@@ -366,9 +383,11 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		{
 			log::debug!(
 				target: LOG_TARGET,
-				"Enforcing limits ({}/{}kB ready, {}/{}kB future",
+				"Enforcing limits ({}/{}kB ready, {}/{}kB reserved, {}/{}kB future",
 				ready_limit.count,
 				ready_limit.total_bytes / 1024,
+				reserved_limit.count,
+				reserved_limit.total_bytes / 1024,
 				future_limit.count,
 				future_limit.total_bytes / 1024,
 			);
@@ -377,7 +396,7 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 			let removed = {
 				let mut pool = self.pool.write();
 				let removed = pool
-					.enforce_limits(ready_limit, future_limit)
+					.enforce_limits(ready_limit, reserved_limit, future_limit)
 					.into_iter()
 					.map(|x| x.hash)
 					.collect::<HashSet<_>>();
@@ -612,13 +631,15 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 	}
 
 	/// Resubmit transactions that have been revalidated after prune_tags call.
+	///
+	/// Returns the number of transactions removed as stale, see [`Self::clear_stale`].
 	pub fn resubmit_pruned(
 		&self,
 		at: &HashAndNumber<B::Block>,
 		known_imported_hashes: impl IntoIterator<Item = ExtrinsicHash<B>> + Clone,
 		pruned_hashes: Vec<ExtrinsicHash<B>>,
 		pruned_xts: Vec<ValidatedTransactionFor<B>>,
-	) {
+	) -> usize {
 		debug_assert_eq!(pruned_hashes.len(), pruned_xts.len());
 
 		// Resubmit pruned transactions
@@ -639,7 +660,7 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 
 		// perform regular cleanup of old transactions in the pool
 		// and update temporary bans.
-		self.clear_stale(at);
+		self.clear_stale(at)
 	}
 
 	/// Fire notifications for pruned transactions.
@@ -665,7 +686,9 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 	/// Stale transactions are transaction beyond their longevity period.
 	/// Note this function does not remove transactions that are already included in the chain.
 	/// See `prune_tags` if you want this.
-	pub fn clear_stale(&self, at: &HashAndNumber<B::Block>) {
+	///
+	/// Returns the number of removed transactions, including the ones depending on stale ones.
+	pub fn clear_stale(&self, at: &HashAndNumber<B::Block>) -> usize {
 		let HashAndNumber { number, .. } = *at;
 		let number = number.saturated_into::<u64>();
 		let now = Instant::now();
@@ -686,10 +709,11 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 			hashes
 		};
 		// removing old transactions
-		self.remove_invalid(&to_remove);
-		self.remove_invalid(&futures_to_remove);
+		let removed =
+			self.remove_invalid(&to_remove).len() + self.remove_invalid(&futures_to_remove).len();
 		// clear banned transactions timeouts
 		self.rotator.clear_timeouts(&now);
+		removed
 	}
 
 	/// Get api reference.
@@ -29,6 +29,7 @@ pub struct Metrics {
 	pub validations_invalid: Counter<U64>,
 	pub block_transactions_pruned: Counter<U64>,
 	pub block_transactions_resubmitted: Counter<U64>,
+	pub expired_transactions: Counter<U64>,
 }
 
 impl MetricsRegistrant for Metrics {
@@ -62,6 +63,13 @@ impl MetricsRegistrant for Metrics {
 				)?,
 				registry,
 			)?,
+			expired_transactions: register(
+				Counter::new(
+					"substrate_sub_txpool_expired_transactions",
+					"Total number of transactions that were removed from the pool as stale",
+				)?,
+				registry,
+			)?,
 		}))
 	}
 }
@@ -583,6 +583,9 @@ impl<N: Clone + Copy + AtLeast32Bit> RevalidationStatus<N> {
 }
 
 /// Prune the known txs for the given block.
+///
+/// Returns the hashes of the block's extrinsics and the number of transactions removed from the
+/// pool as stale.
 pub async fn prune_known_txs_for_block<
 	Block: BlockT,
 	Api: graph::ChainApi<Block = Block>,
@@ -591,7 +594,7 @@ pub async fn prune_known_txs_for_block<
 	at: &HashAndNumber<Block>,
 	api: &Api,
 	pool: &graph::Pool<Api, L>,
-) -> Vec<ExtrinsicHash<Api>> {
+) -> (Vec<ExtrinsicHash<Api>>, usize) {
 	let extrinsics = api
 		.block_body(at.hash)
 		.await
@@ -607,18 +610,18 @@ pub async fn prune_known_txs_for_block<
 		Ok(Some(h)) => h,
 		Ok(None) => {
 			log::trace!(target: LOG_TARGET, "Could not find header for {:?}.", at.hash);
-			return hashes
+			return (hashes, 0)
 		},
 		Err(e) => {
 			log::trace!(target: LOG_TARGET, "Error retrieving header for {:?}: {}", at.hash, e);
-			return hashes
+			return (hashes, 0)
 		},
 	};
 
 	log_xt_trace!(target: LOG_TARGET, &hashes, "[{:?}] Pruning transaction.");
 
-	pool.prune(at, *header.parent_hash(), &extrinsics).await;
-	hashes
+	let expired = pool.prune(at, *header.parent_hash(), &extrinsics).await;
+	(hashes, expired)
 }
 
 impl<PoolApi, Block> BasicPool<PoolApi, Block>
@@ -655,6 +658,7 @@ where
 		// We keep track of everything we prune so that later we won't add
 		// transactions with those hashes from the retracted blocks.
 		let mut pruned_log = HashSet::<ExtrinsicHash<PoolApi>>::new();
+		let mut expired = 0;
 
 		// If there is a tree route, we use this to prune known tx based on the enacted
 		// blocks. Before pruning enacted transactions, we inform the listeners about
@@ -671,12 +675,15 @@ where
 		)
 		.await
 		.into_iter()
-		.for_each(|enacted_log| {
+		.for_each(|(enacted_log, enacted_expired)| {
 			pruned_log.extend(enacted_log);
+			expired += enacted_expired;
 		});
 
-		self.metrics
-			.report(|metrics| metrics.block_transactions_pruned.inc_by(pruned_log.len() as u64));
+		self.metrics.report(|metrics| {
+			metrics.block_transactions_pruned.inc_by(pruned_log.len() as u64);
+			metrics.expired_transactions.inc_by(expired as u64);
+		});
 
 		if next_action.resubmit {
 			let mut resubmit_transactions = Vec::new();
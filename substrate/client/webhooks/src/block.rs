@@ -0,0 +1,209 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of the events and extrinsics of a block into notifications.
+
+use codec::{Compact, Decode, Error};
+use frame_metadata::v15::RuntimeMetadataV15;
+use sc_runtime_metadata::decode::decode_value;
+use scale_info::PortableRegistry;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The extrinsic format version supported by the decoder.
+const EXTRINSIC_VERSION: u8 = 4;
+
+/// An event emitted in a finalized block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventNotification {
+	/// Index of the extrinsic which emitted the event, if any.
+	pub extrinsic_index: Option<u32>,
+	/// Name of the pallet which emitted the event.
+	pub pallet: String,
+	/// Name of the event.
+	pub name: String,
+	/// The decoded fields of the event.
+	pub fields: Value,
+}
+
+/// The outcome of an extrinsic included in a finalized block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicNotification {
+	/// Index of the extrinsic in the block.
+	pub index: u32,
+	/// Name of the pallet of the call.
+	pub pallet: String,
+	/// Name of the call.
+	pub call: String,
+	/// The decoded arguments of the call.
+	pub args: Value,
+	/// The decoded address of the signer, for signed extrinsics.
+	pub signer: Option<Value>,
+	/// Whether the call was dispatched successfully.
+	pub success: bool,
+	/// The decoded dispatch error, for failed calls.
+	pub error: Option<Value>,
+}
+
+/// The notification sent to a webhook for a finalized block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockNotification {
+	/// Number of the block.
+	pub block_number: u64,
+	/// Hash of the block.
+	pub block_hash: String,
+	/// The events matching the filter of the webhook.
+	pub events: Vec<EventNotification>,
+	/// The extrinsics matching the filter of the webhook.
+	pub extrinsics: Vec<ExtrinsicNotification>,
+}
+
+/// Splits a decoded enum value into the name of its variant and its fields.
+fn variant(value: Value) -> Option<(String, Value)> {
+	match value {
+		Value::String(name) => Some((name, Value::Null)),
+		Value::Object(object) if object.len() == 1 => object.into_iter().next(),
+		_ => None,
+	}
+}
+
+/// Decodes events and extrinsics with the types of a runtime.
+pub(crate) struct Decoder {
+	registry: PortableRegistry,
+	events_ty: u32,
+	address_ty: u32,
+	call_ty: u32,
+	signature_ty: u32,
+	extra_ty: u32,
+}
+
+impl Decoder {
+	/// Create a decoder for the runtime described by `metadata`.
+	///
+	/// Returns `None` if the runtime has no `System::Events` storage.
+	pub(crate) fn new(metadata: RuntimeMetadataV15) -> Option<Self> {
+		use frame_metadata::v15::StorageEntryType;
+
+		let events_ty = metadata
+			.pallets
+			.iter()
+			.find(|pallet| pallet.name == "System")?
+			.storage
+			.as_ref()?
+			.entries
+			.iter()
+			.find(|entry| entry.name == "Events")
+			.and_then(|entry| match &entry.ty {
+				StorageEntryType::Plain(ty) => Some(ty.id),
+				StorageEntryType::Map { .. } => None,
+			})?;
+		Some(Self {
+			registry: metadata.types,
+			events_ty,
+			address_ty: metadata.extrinsic.address_ty.id,
+			call_ty: metadata.extrinsic.call_ty.id,
+			signature_ty: metadata.extrinsic.signature_ty.id,
+			extra_ty: metadata.extrinsic.extra_ty.id,
+		})
+	}
+
+	/// Decode the value of the `System::Events` storage.
+	pub(crate) fn events(&self, mut data: &[u8]) -> Result<Vec<EventNotification>, Error> {
+		let Value::Array(records) = decode_value(&self.registry, self.events_ty, &mut data)? else {
+			return Err("Events are not a sequence".into())
+		};
+
+		records
+			.into_iter()
+			.map(|mut record| -> Result<_, Error> {
+				let extrinsic_index = record
+					.get("phase")
+					.and_then(|phase| phase.get("ApplyExtrinsic"))
+					.and_then(Value::as_u64)
+					.map(|index| index as u32);
+				let (pallet, event) = record
+					.get_mut("event")
+					.map(Value::take)
+					.and_then(variant)
+					.ok_or("Invalid event record")?;
+				let (name, fields) = variant(event).ok_or("Invalid event")?;
+				Ok(EventNotification { extrinsic_index, pallet, name, fields })
+			})
+			.collect()
+	}
+
+	/// Decode an extrinsic, as encoded in the block body.
+	///
+	/// The outcome is not part of the extrinsic, it's taken from the `System` events emitted by it.
+	pub(crate) fn extrinsic(
+		&self,
+		index: u32,
+		mut data: &[u8],
+		events: &[EventNotification],
+	) -> Result<ExtrinsicNotification, Error> {
+		let input = &mut data;
+		let _length = Compact::<u32>::decode(input)?;
+		let version = u8::decode(input)?;
+		if version & 0b0111_1111 != EXTRINSIC_VERSION {
+			return Err("Unsupported extrinsic version".into())
+		}
+
+		let signer = if version & 0b1000_0000 != 0 {
+			let address = decode_value(&self.registry, self.address_ty, input)?;
+			decode_value(&self.registry, self.signature_ty, input)?;
+			decode_value(&self.registry, self.extra_ty, input)?;
+			Some(address)
+		} else {
+			None
+		};
+		let call = decode_value(&self.registry, self.call_ty, input)?;
+		let (pallet, call) = variant(call).ok_or("Invalid call")?;
+		let (call, args) = variant(call).ok_or("Invalid call")?;
+
+		let outcome = events.iter().find(|event| {
+			event.extrinsic_index == Some(index)
+				&& event.pallet == "System"
+				&& matches!(event.name.as_str(), "ExtrinsicSuccess" | "ExtrinsicFailed")
+		});
+		let success = outcome.is_some_and(|event| event.name == "ExtrinsicSuccess");
+		let error = outcome
+			.filter(|event| event.name == "ExtrinsicFailed")
+			.and_then(|event| event.fields.get("dispatch_error").cloned());
+
+		Ok(ExtrinsicNotification { index, pallet, call, args, signer, success, error })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn variants_are_split() {
+		assert_eq!(variant(json!("CodeUpdated")), Some(("CodeUpdated".into(), Value::Null)));
+		assert_eq!(
+			variant(json!({ "Transfer": { "amount": 1 } })),
+			Some(("Transfer".into(), json!({ "amount": 1 }))),
+		);
+		assert_eq!(variant(json!([1, 2])), None);
+	}
+}
@@ -0,0 +1,223 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration of the webhooks and filtering of the notifications sent to them.
+
+use crate::block::{EventNotification, ExtrinsicNotification};
+use serde::Deserialize;
+use serde_json::Value;
+use sp_core::crypto::AccountId32;
+use std::{path::Path, str::FromStr};
+
+/// The webhooks configuration file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhooksConfig {
+	/// The webhooks to notify.
+	pub webhooks: Vec<WebhookConfig>,
+}
+
+impl WebhooksConfig {
+	/// Load the configuration from the JSON file at `path`.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let file = std::fs::File::open(path)
+			.map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+		let config: Self = serde_json::from_reader(file)
+			.map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+		for webhook in &config.webhooks {
+			webhook.filter()?;
+		}
+		Ok(config)
+	}
+}
+
+/// A webhook and the notifications it is interested in.
+///
+/// Empty filter lists match everything.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WebhookConfig {
+	/// The URL notifications are posted to.
+	pub url: String,
+	/// Secret used to sign the notifications with HMAC-SHA256.
+	#[serde(default)]
+	pub secret: Option<String>,
+	/// Only notify about events emitted by and calls to these pallets.
+	#[serde(default)]
+	pub pallets: Vec<String>,
+	/// Only notify about the outcome of these calls, formatted as `Pallet.call_name`.
+	#[serde(default)]
+	pub calls: Vec<String>,
+	/// Only notify about events and extrinsics mentioning one of these accounts, given in SS58 or
+	/// hex.
+	#[serde(default)]
+	pub accounts: Vec<String>,
+	/// Whether to notify about events.
+	#[serde(default = "default_true")]
+	pub events: bool,
+	/// Whether to notify about the outcome of extrinsics.
+	#[serde(default = "default_true")]
+	pub extrinsics: bool,
+	/// How many times a failed delivery is retried.
+	#[serde(default = "default_max_retries")]
+	pub max_retries: u32,
+}
+
+fn default_true() -> bool {
+	true
+}
+
+fn default_max_retries() -> u32 {
+	5
+}
+
+impl WebhookConfig {
+	/// The filter described by this configuration.
+	pub(crate) fn filter(&self) -> Result<Filter, String> {
+		let accounts = self
+			.accounts
+			.iter()
+			.map(|account| {
+				AccountId32::from_str(account)
+					.map(|account| array_bytes::bytes2hex("0x", account))
+					.map_err(|_| format!("Invalid account `{account}` for webhook {}", self.url))
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Filter {
+			pallets: self.pallets.clone(),
+			calls: self.calls.clone(),
+			accounts,
+			events: self.events,
+			extrinsics: self.extrinsics,
+		})
+	}
+}
+
+/// Selects the notifications sent to a webhook.
+#[derive(Debug, Clone)]
+pub(crate) struct Filter {
+	pallets: Vec<String>,
+	calls: Vec<String>,
+	/// Hex encoded account ids, as they appear in decoded values.
+	accounts: Vec<String>,
+	events: bool,
+	extrinsics: bool,
+}
+
+impl Filter {
+	pub(crate) fn event(&self, event: &EventNotification) -> bool {
+		self.events
+			&& (self.pallets.is_empty() || self.pallets.contains(&event.pallet))
+			&& self.mentions_account(&[&event.fields])
+	}
+
+	pub(crate) fn extrinsic(&self, extrinsic: &ExtrinsicNotification) -> bool {
+		let call = format!("{}.{}", extrinsic.pallet, extrinsic.call);
+		let signer = extrinsic.signer.clone().unwrap_or_default();
+		self.extrinsics
+			&& (self.pallets.is_empty() || self.pallets.contains(&extrinsic.pallet))
+			&& (self.calls.is_empty() || self.calls.contains(&call))
+			&& self.mentions_account(&[&signer, &extrinsic.args])
+	}
+
+	fn mentions_account(&self, values: &[&Value]) -> bool {
+		self.accounts.is_empty() || values.iter().any(|value| self.contains_account(value))
+	}
+
+	fn contains_account(&self, value: &Value) -> bool {
+		match value {
+			Value::String(string) =>
+				self.accounts.iter().any(|account| account.eq_ignore_ascii_case(string)),
+			Value::Array(values) => values.iter().any(|value| self.contains_account(value)),
+			Value::Object(values) => values.values().any(|value| self.contains_account(value)),
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	const ALICE: &str = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+	fn webhook(config: Value) -> WebhookConfig {
+		serde_json::from_value(config).unwrap()
+	}
+
+	fn transfer(from: &str) -> EventNotification {
+		EventNotification {
+			extrinsic_index: Some(1),
+			pallet: "Balances".into(),
+			name: "Transfer".into(),
+			fields: json!({ "from": from, "to": "0x00", "amount": 10 }),
+		}
+	}
+
+	#[test]
+	fn empty_filter_matches_everything() {
+		let filter = webhook(json!({ "url": "http://localhost" })).filter().unwrap();
+		assert!(filter.event(&transfer("0x00")));
+	}
+
+	#[test]
+	fn events_are_filtered_by_pallet_and_account() {
+		let filter = webhook(json!({
+			"url": "http://localhost",
+			"pallets": ["Balances"],
+			"accounts": ["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"],
+		}))
+		.filter()
+		.unwrap();
+
+		assert!(filter.event(&transfer(ALICE)));
+		assert!(!filter.event(&transfer("0x00")));
+		assert!(!filter.event(&EventNotification { pallet: "Staking".into(), ..transfer(ALICE) }));
+	}
+
+	#[test]
+	fn extrinsics_are_filtered_by_call() {
+		let filter = webhook(json!({
+			"url": "http://localhost",
+			"calls": ["Balances.transfer_keep_alive"],
+			"events": false,
+		}))
+		.filter()
+		.unwrap();
+		let extrinsic = ExtrinsicNotification {
+			index: 1,
+			pallet: "Balances".into(),
+			call: "transfer_keep_alive".into(),
+			args: json!({ "dest": ALICE, "value": 10 }),
+			signer: Some(json!({ "Id": ALICE })),
+			success: true,
+			error: None,
+		};
+
+		assert!(filter.extrinsic(&extrinsic));
+		assert!(!filter.extrinsic(&ExtrinsicNotification { call: "burn".into(), ..extrinsic }));
+		assert!(!filter.event(&transfer(ALICE)));
+	}
+
+	#[test]
+	fn invalid_accounts_are_rejected() {
+		let config = webhook(json!({ "url": "http://localhost", "accounts": ["alice"] }));
+		assert!(config.filter().is_err());
+	}
+}
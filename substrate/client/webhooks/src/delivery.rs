@@ -0,0 +1,146 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Delivery of notifications to a webhook, with signing and retries.
+
+use crate::LOG_TARGET;
+use futures::{channel::mpsc, StreamExt};
+use hmac::{Hmac, Mac};
+use http_body_util::Full;
+use hyper::{body::Bytes, header::CONTENT_TYPE, Request};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::{
+	client::legacy::{connect::HttpConnector, Client},
+	rt::TokioExecutor,
+};
+use sha2::Sha256;
+use std::time::Duration;
+
+/// Header containing the signature of the body.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// The number of notifications queued for a webhook before new ones are dropped.
+const QUEUE_SIZE: usize = 1024;
+
+/// Timeout of a single delivery attempt.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first retry, doubled for every following retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+pub(crate) type HttpClient = Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+
+pub(crate) fn http_client() -> std::io::Result<HttpClient> {
+	let connector = HttpsConnectorBuilder::new()
+		.with_provider_and_native_roots(rustls::crypto::ring::default_provider())?
+		.https_or_http()
+		.enable_http1()
+		.build();
+	Ok(Client::builder(TokioExecutor::new()).build(connector))
+}
+
+/// The signature of `body`: the hex encoded HMAC-SHA256 of the body, keyed with `secret`.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+		.expect("HMAC accepts keys of any length; qed");
+	mac.update(body);
+	format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Sends the notifications queued for one webhook.
+pub(crate) struct Delivery {
+	url: String,
+	secret: Option<String>,
+	max_retries: u32,
+	client: HttpClient,
+	queue: mpsc::Receiver<Vec<u8>>,
+}
+
+impl Delivery {
+	/// Create the delivery of a webhook, together with the sender of its queue.
+	pub(crate) fn new(
+		url: String,
+		secret: Option<String>,
+		max_retries: u32,
+		client: HttpClient,
+	) -> (Self, mpsc::Sender<Vec<u8>>) {
+		let (sender, queue) = mpsc::channel(QUEUE_SIZE);
+		(Self { url, secret, max_retries, client, queue }, sender)
+	}
+
+	/// Deliver the queued notifications in order, until the queue is closed.
+	pub(crate) async fn run(mut self) {
+		while let Some(body) = self.queue.next().await {
+			let mut delay = INITIAL_RETRY_DELAY;
+			for attempt in 0..=self.max_retries {
+				match self.post(&body).await {
+					Ok(()) => break,
+					Err(e) if attempt < self.max_retries => {
+						log::debug!(
+							target: LOG_TARGET,
+							"Delivery to {} failed, retrying in {delay:?}: {e}",
+							self.url,
+						);
+						futures_timer::Delay::new(delay).await;
+						delay *= 2;
+					},
+					Err(e) => log::warn!(
+						target: LOG_TARGET,
+						"Dropping notification to {} after {} attempts: {e}",
+						self.url,
+						attempt + 1,
+					),
+				}
+			}
+		}
+	}
+
+	async fn post(&self, body: &[u8]) -> Result<(), String> {
+		let mut request = Request::post(&self.url).header(CONTENT_TYPE, "application/json");
+		if let Some(secret) = &self.secret {
+			request = request.header(SIGNATURE_HEADER, signature(secret, body));
+		}
+		let request = request
+			.body(Full::new(Bytes::copy_from_slice(body)))
+			.map_err(|e| e.to_string())?;
+
+		let response = tokio::time::timeout(REQUEST_TIMEOUT, self.client.request(request))
+			.await
+			.map_err(|_| "Request timed out".to_string())?
+			.map_err(|e| e.to_string())?;
+		if response.status().is_success() {
+			Ok(())
+		} else {
+			Err(format!("Unexpected status {}", response.status()))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn signature_is_hmac_sha256() {
+		// Test vector of RFC 4231, test case 2.
+		assert_eq!(
+			signature("Jefe", b"what do ya want for nothing?"),
+			"sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+		);
+	}
+}
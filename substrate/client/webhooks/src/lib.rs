@@ -0,0 +1,216 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Webhook sink for chain events.
+//!
+//! Posts the events and extrinsic outcomes of every finalized block to user supplied webhook
+//! URLs, so notification systems can be built without running an indexer. Every webhook has its
+//! own filter by pallet, call and account, see [`WebhookConfig`].
+//!
+//! For every finalized block with matching events or extrinsics, a [`BlockNotification`] is
+//! posted as JSON. Events, calls and their arguments are decoded with the types of the runtime
+//! metadata. If the webhook has a secret, the body is signed with HMAC-SHA256 and the signature is
+//! sent in the [`SIGNATURE_HEADER`] header, formatted as `sha256=<hex>`. Failed deliveries are
+//! retried with an exponential backoff.
+
+mod block;
+mod config;
+mod delivery;
+
+pub use block::{BlockNotification, EventNotification, ExtrinsicNotification};
+pub use config::{WebhookConfig, WebhooksConfig};
+pub use delivery::{signature, SIGNATURE_HEADER};
+
+use block::Decoder;
+use clap::Args;
+use codec::Encode;
+use config::Filter;
+use futures::{channel::mpsc, StreamExt};
+use sc_client_api::{Backend, BlockBackend, BlockchainEvents, StorageProvider};
+use sc_runtime_metadata::RuntimeCache;
+use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, traits::SpawnNamed};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	SaturatedConversion,
+};
+use std::{marker::PhantomData, path::PathBuf, sync::Arc};
+
+const LOG_TARGET: &str = "webhooks";
+
+/// Parameters used to configure the webhooks.
+#[derive(Default, Debug, Clone, Args)]
+pub struct WebhookParams {
+	/// Path to a JSON file configuring webhooks notified about finalized events and extrinsics.
+	///
+	/// Webhooks are disabled if no file is given.
+	#[arg(long, value_name = "PATH")]
+	pub webhooks_config: Option<PathBuf>,
+}
+
+/// A webhook together with the queue of its delivery task.
+struct Webhook {
+	filter: Filter,
+	sender: mpsc::Sender<Vec<u8>>,
+	url: String,
+}
+
+/// Notifies webhooks about the events and extrinsics of finalized blocks.
+pub struct WebhookService<Block, BE, Client> {
+	client: Arc<Client>,
+	webhooks: Vec<Webhook>,
+	/// The decoder of the last seen runtime, `None` if its events can't be decoded.
+	decoder: RuntimeCache<Option<Arc<Decoder>>>,
+	_phantom: PhantomData<fn() -> (Block, BE)>,
+}
+
+impl<Block, BE, Client> WebhookService<Block, BE, Client>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ ProvideRuntimeApi<Block>
+		+ CallApiAt<Block>,
+	Client::Api: Metadata<Block>,
+{
+	/// Create the service and spawn one delivery task per configured webhook.
+	pub fn new(
+		client: Arc<Client>,
+		config: WebhooksConfig,
+		spawner: &impl SpawnNamed,
+	) -> Result<Self, String> {
+		let http_client = delivery::http_client().map_err(|e| e.to_string())?;
+		let webhooks = config
+			.webhooks
+			.into_iter()
+			.map(|webhook| {
+				let filter = webhook.filter()?;
+				let (delivery, sender) = delivery::Delivery::new(
+					webhook.url.clone(),
+					webhook.secret,
+					webhook.max_retries,
+					http_client.clone(),
+				);
+				spawner.spawn("webhook-delivery", Some("webhooks"), Box::pin(delivery.run()));
+				Ok(Webhook { filter, sender, url: webhook.url })
+			})
+			.collect::<Result<_, String>>()?;
+
+		Ok(Self { client, webhooks, decoder: RuntimeCache::default(), _phantom: PhantomData })
+	}
+
+	/// Returns a never-ending `Future` that notifies the webhooks about every finalized block.
+	pub async fn run(mut self) {
+		let mut finality = self.client.finality_notification_stream();
+		while let Some(notification) = finality.next().await {
+			for hash in notification.tree_route.iter().chain(std::iter::once(&notification.hash)) {
+				if let Err(e) = self.notify(*hash) {
+					log::warn!(target: LOG_TARGET, "Failed to process block {hash:?}: {e}");
+				}
+			}
+		}
+	}
+
+	/// Notify the webhooks about the finalized block `hash`.
+	fn notify(&mut self, hash: Block::Hash) -> Result<(), String> {
+		let Some(decoder) = self.decoder(hash)? else { return Ok(()) };
+		let number = self
+			.client
+			.header(hash)
+			.map_err(|e| e.to_string())?
+			.ok_or("Unknown block")?
+			.number()
+			.saturated_into::<u64>();
+
+		let mut key = sp_crypto_hashing::twox_128(b"System").to_vec();
+		key.extend(sp_crypto_hashing::twox_128(b"Events"));
+		let events = match self.client.storage(hash, &StorageKey(key)).map_err(|e| e.to_string())? {
+			Some(data) => decoder.events(&data.0).map_err(|e| e.to_string())?,
+			None => Vec::new(),
+		};
+		let extrinsics = self
+			.client
+			.block_body(hash)
+			.map_err(|e| e.to_string())?
+			.unwrap_or_default()
+			.iter()
+			.enumerate()
+			.filter_map(|(index, xt)| {
+				decoder
+					.extrinsic(index as u32, &xt.encode(), &events)
+					.map_err(
+						|e| log::debug!(target: LOG_TARGET, "Failed to decode extrinsic {index}: {e}"),
+					)
+					.ok()
+			})
+			.collect::<Vec<_>>();
+
+		for webhook in &mut self.webhooks {
+			let notification = BlockNotification {
+				block_number: number,
+				block_hash: array_bytes::bytes2hex("0x", hash.as_ref()),
+				events: events
+					.iter()
+					.filter(|event| webhook.filter.event(event))
+					.cloned()
+					.collect(),
+				extrinsics: extrinsics
+					.iter()
+					.filter(|extrinsic| webhook.filter.extrinsic(extrinsic))
+					.cloned()
+					.collect(),
+			};
+			if notification.events.is_empty() && notification.extrinsics.is_empty() {
+				continue
+			}
+
+			let body = serde_json::to_vec(&notification).map_err(|e| e.to_string())?;
+			if webhook.sender.try_send(body).is_err() {
+				log::warn!(
+					target: LOG_TARGET,
+					"Delivery queue of {} is full, dropping notification of block {number}",
+					webhook.url,
+				);
+			}
+		}
+		Ok(())
+	}
+
+	/// The decoder of the runtime at `hash`, or `None` if its events can't be decoded.
+	fn decoder(&self, hash: Block::Hash) -> Result<Option<Arc<Decoder>>, String> {
+		let spec_version =
+			self.client.runtime_version_at(hash).map_err(|e| e.to_string())?.spec_version;
+		self.decoder.get_or_try_insert_with(spec_version, || {
+			let decoder = sc_runtime_metadata::metadata_at::<Block, _>(&*self.client, hash)?
+				.and_then(Decoder::new)
+				.map(Arc::new);
+			if decoder.is_none() {
+				log::warn!(
+					target: LOG_TARGET,
+					"Runtime version {spec_version} has no decodable events, webhooks are not \
+					notified",
+				);
+			}
+			Ok(decoder)
+		})
+	}
+}
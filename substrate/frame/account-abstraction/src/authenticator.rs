@@ -0,0 +1,231 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Authenticators checking the proofs for the credentials of accounts.
+
+use alloc::vec::Vec;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use frame_support::{pallet_prelude::ConstU32, BoundedVec, Parameter};
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{IdentifyAccount, Verify},
+	RuntimeDebug,
+};
+
+/// Checks that a proof authorizes a message for a credential.
+///
+/// The message is the `blake2_256` hash of the payload of a transaction, see
+/// [`AuthorizeAccount`](crate::AuthorizeAccount).
+pub trait Authenticator {
+	/// A credential of an account, e.g. a public key.
+	type Credential: Parameter + MaxEncodedLen;
+	/// A proof that a credential authorizes a message, e.g. a signature.
+	type Proof: Parameter;
+
+	/// Whether `proof` authorizes `msg` for `credential`.
+	fn authenticate(credential: &Self::Credential, msg: &[u8; 32], proof: &Self::Proof) -> bool;
+}
+
+/// A credential or proof of either of two authenticators.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+)]
+pub enum Either<L, R> {
+	/// Of the first authenticator.
+	Left(L),
+	/// Of the second authenticator.
+	Right(R),
+}
+
+impl<A: Authenticator, B: Authenticator> Authenticator for (A, B) {
+	type Credential = Either<A::Credential, B::Credential>;
+	type Proof = Either<A::Proof, B::Proof>;
+
+	fn authenticate(credential: &Self::Credential, msg: &[u8; 32], proof: &Self::Proof) -> bool {
+		match (credential, proof) {
+			(Either::Left(credential), Either::Left(proof)) =>
+				A::authenticate(credential, msg, proof),
+			(Either::Right(credential), Either::Right(proof)) =>
+				B::authenticate(credential, msg, proof),
+			_ => false,
+		}
+	}
+}
+
+/// Authenticates keys signing the message with the signature `S`, e.g. session keys.
+pub struct SignedBy<S>(PhantomData<S>);
+
+impl<S> Authenticator for SignedBy<S>
+where
+	S: Verify + Parameter,
+	<S::Signer as IdentifyAccount>::AccountId: Parameter + MaxEncodedLen,
+{
+	type Credential = <S::Signer as IdentifyAccount>::AccountId;
+	type Proof = S;
+
+	fn authenticate(credential: &Self::Credential, msg: &[u8; 32], proof: &Self::Proof) -> bool {
+		proof.verify(&msg[..], credential)
+	}
+}
+
+/// Maximum length of the authenticator data of a [`WebAuthnAssertion`].
+pub const MAX_AUTHENTICATOR_DATA_LEN: u32 = 256;
+
+/// Maximum length of the client data of a [`WebAuthnAssertion`].
+pub const MAX_CLIENT_DATA_LEN: u32 = 1024;
+
+/// Maximum length of a DER encoded P-256 ECDSA signature.
+pub const MAX_DER_SIGNATURE_LEN: u32 = 72;
+
+/// The compressed SEC1 encoded P-256 public key of a passkey.
+pub type PasskeyPublic = [u8; 33];
+
+/// A WebAuthn assertion, as returned by `navigator.credentials.get()`.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct WebAuthnAssertion {
+	/// The authenticator data.
+	pub authenticator_data: BoundedVec<u8, ConstU32<MAX_AUTHENTICATOR_DATA_LEN>>,
+	/// The client data JSON, as serialized by the client.
+	pub client_data_json: BoundedVec<u8, ConstU32<MAX_CLIENT_DATA_LEN>>,
+	/// The DER encoded signature of the authenticator data and the SHA-256 hash of the client
+	/// data.
+	pub signature: BoundedVec<u8, ConstU32<MAX_DER_SIGNATURE_LEN>>,
+}
+
+/// Authenticates passkeys with WebAuthn assertions signed with ES256, ECDSA over P-256 with
+/// SHA-256.
+///
+/// The challenge of the assertion must be the message, base64url encoded without padding, and the
+/// user must have been present. The relying party and origin are not checked: the passkey is bound
+/// to the account by being registered as its credential.
+pub struct Passkey;
+
+impl Authenticator for Passkey {
+	type Credential = PasskeyPublic;
+	type Proof = WebAuthnAssertion;
+
+	fn authenticate(credential: &Self::Credential, msg: &[u8; 32], proof: &Self::Proof) -> bool {
+		// The flags follow the 32 bytes of the relying party id hash, `0x01` is user present.
+		if proof.authenticator_data.get(32).map_or(true, |flags| flags & 0x01 == 0) {
+			return false
+		}
+		if !is_assertion_of(&proof.client_data_json, msg) {
+			return false
+		}
+		let Ok(public) = VerifyingKey::from_sec1_bytes(credential) else { return false };
+		let Ok(signature) = Signature::from_der(&proof.signature) else { return false };
+
+		let mut signed = proof.authenticator_data.to_vec();
+		signed.extend(sp_io::hashing::sha2_256(&proof.client_data_json));
+		public.verify(&signed, &signature).is_ok()
+	}
+}
+
+/// Whether the client data is of an assertion with the challenge `msg`.
+///
+/// The client data is serialized by the client in a canonical form, which allows checking its
+/// members without parsing the JSON.
+fn is_assertion_of(client_data_json: &[u8], msg: &[u8; 32]) -> bool {
+	let mut challenge = b"\"challenge\":\"".to_vec();
+	challenge.extend(base64url(msg));
+	challenge.push(b'"');
+	contains(client_data_json, b"{\"type\":\"webauthn.get\",")
+		&& contains(client_data_json, &challenge)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+	haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+/// Base64url encoding of `bytes`, without padding.
+fn base64url(bytes: &[u8]) -> Vec<u8> {
+	const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+	let mut encoded = Vec::with_capacity((bytes.len() * 4).div_ceil(3));
+	for chunk in bytes.chunks(3) {
+		let n = chunk
+			.iter()
+			.enumerate()
+			.fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+		for i in 0..=chunk.len() {
+			encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]);
+		}
+	}
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use p256::ecdsa::{signature::Signer, SigningKey};
+
+	#[test]
+	fn base64url_works() {
+		assert_eq!(base64url(b"Man"), b"TWFu");
+		assert_eq!(base64url(b"Ma"), b"TWE");
+		assert_eq!(base64url(b"M"), b"TQ");
+		assert_eq!(base64url(&[0xfb, 0xff]), b"-_8");
+		assert_eq!(base64url(&[0u8; 32]).len(), 43);
+	}
+
+	fn assertion(key: &SigningKey, challenge: &[u8], flags: u8) -> WebAuthnAssertion {
+		let mut authenticator_data = [7u8; 37].to_vec();
+		authenticator_data[32] = flags;
+		let mut client_data_json = b"{\"type\":\"webauthn.get\",\"challenge\":\"".to_vec();
+		client_data_json.extend(base64url(challenge));
+		client_data_json.extend(b"\",\"origin\":\"https://wallet.example\",\"crossOrigin\":false}");
+
+		let mut signed = authenticator_data.clone();
+		signed.extend(sp_io::hashing::sha2_256(&client_data_json));
+		let signature: Signature = key.sign(&signed);
+		WebAuthnAssertion {
+			authenticator_data: authenticator_data.try_into().unwrap(),
+			client_data_json: client_data_json.try_into().unwrap(),
+			signature: signature.to_der().as_bytes().to_vec().try_into().unwrap(),
+		}
+	}
+
+	#[test]
+	fn passkey_works() {
+		let key = SigningKey::from_slice(&[42u8; 32]).unwrap();
+		let public: PasskeyPublic =
+			key.verifying_key().to_encoded_point(true).as_bytes().try_into().unwrap();
+		let msg = [1u8; 32];
+
+		assert!(Passkey::authenticate(&public, &msg, &assertion(&key, &msg, 0x05)));
+		// Wrong challenge.
+		assert!(!Passkey::authenticate(&public, &msg, &assertion(&key, &[2u8; 32], 0x05)));
+		// User not present.
+		assert!(!Passkey::authenticate(&public, &msg, &assertion(&key, &msg, 0x04)));
+		// Other key.
+		let other = SigningKey::from_slice(&[43u8; 32]).unwrap();
+		assert!(!Passkey::authenticate(&public, &msg, &assertion(&other, &msg, 0x05)));
+		// Not an assertion.
+		let mut proof = assertion(&key, &msg, 0x05);
+		proof.client_data_json[10] = b'x';
+		assert!(!Passkey::authenticate(&public, &msg, &proof));
+	}
+}
@@ -0,0 +1,231 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for the Account Abstraction Pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use crate::Pallet as AccountAbstraction;
+use alloc::{vec, vec::Vec};
+use frame_benchmarking::{v2::*, BenchmarkError};
+use frame_support::{
+	dispatch::{DispatchInfo, GetDispatchInfo},
+	pallet_prelude::TransactionSource,
+};
+use frame_system::{Call as SystemCall, RawOrigin};
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	generic::ExtensionVersion,
+	traits::{AsTransactionAuthorizedOrigin, DispatchTransaction, Dispatchable},
+};
+
+/// Helper to create the credentials and proofs of the authenticator of the pallet.
+pub trait BenchmarkHelper<Credential, Proof> {
+	/// A credential, distinct for every `index`.
+	fn credential(index: u32) -> Credential;
+	/// A proof of `msg` for the credential of `index`.
+	fn proof(index: u32, msg: &[u8; 32]) -> Proof;
+}
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+/// A funded caller, with `count` credentials.
+fn caller_with_credentials<T: Config>(count: u32) -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Consideration::ensure_successful(
+		&caller,
+		AccountAbstraction::<T>::footprint(T::MaxCredentials::get() as usize),
+	);
+	for i in 0..count {
+		let credential = T::BenchmarkHelper::credential(i);
+		AccountAbstraction::<T>::add_credential(
+			RawOrigin::Signed(caller.clone()).into(),
+			credential,
+			None,
+		)
+		.unwrap();
+	}
+	caller
+}
+
+/// A funded caller, with `n` guardians of which `threshold` can recover it.
+fn caller_with_guardians<T: Config>(n: u32, threshold: u32) -> (T::AccountId, Vec<T::AccountId>) {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Consideration::ensure_successful(&caller, Footprint::from_mel::<GuardianSetOf<T>>());
+	let guardians: Vec<T::AccountId> = (0..n).map(|i| account("guardian", i, 0)).collect();
+	AccountAbstraction::<T>::set_guardians(
+		RawOrigin::Signed(caller.clone()).into(),
+		guardians.clone().try_into().unwrap(),
+		threshold,
+	)
+	.unwrap();
+	(caller, guardians)
+}
+
+#[benchmarks(where
+	T: Config + Send + Sync,
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo> + GetDispatchInfo,
+	T::RuntimeOrigin: AsTransactionAuthorizedOrigin,
+)]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn add_credential() {
+		let max = T::MaxCredentials::get();
+		let caller = caller_with_credentials::<T>(max - 1);
+		let credential = T::BenchmarkHelper::credential(max - 1);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), credential.clone(), None);
+
+		assert_last_event::<T>(Event::CredentialAdded { who: caller, credential }.into());
+	}
+
+	#[benchmark]
+	fn remove_credential() {
+		let max = T::MaxCredentials::get();
+		let caller = caller_with_credentials::<T>(max);
+		let credential = T::BenchmarkHelper::credential(max - 1);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), credential.clone());
+
+		assert_last_event::<T>(Event::CredentialRemoved { who: caller, credential }.into());
+	}
+
+	#[benchmark]
+	fn set_guardians(n: Linear<1, { T::MaxGuardians::get() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Consideration::ensure_successful(&caller, Footprint::from_mel::<GuardianSetOf<T>>());
+		let guardians: Vec<T::AccountId> = (0..n).map(|i| account("guardian", i, 0)).collect();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), guardians.try_into().unwrap(), n);
+
+		assert_last_event::<T>(Event::GuardiansSet { who: caller, threshold: n }.into());
+	}
+
+	#[benchmark]
+	fn remove_guardians() {
+		let (caller, _) = caller_with_guardians::<T>(T::MaxGuardians::get(), 1);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert_last_event::<T>(Event::GuardiansRemoved { who: caller }.into());
+	}
+
+	// The last approval, which initiates the recovery of the account.
+	#[benchmark]
+	fn approve_recovery(n: Linear<1, { T::MaxGuardians::get() }>) {
+		let (caller, mut guardians) = caller_with_guardians::<T>(n, n);
+		let credential = T::BenchmarkHelper::credential(0);
+		let last = guardians.pop().unwrap();
+		for guardian in guardians {
+			AccountAbstraction::<T>::approve_recovery(
+				RawOrigin::Signed(guardian).into(),
+				caller.clone(),
+				credential.clone(),
+			)
+			.unwrap();
+		}
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(last), caller.clone(), credential.clone());
+
+		assert!(Recoveries::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn cancel_recovery() {
+		let (caller, guardians) = caller_with_guardians::<T>(2, 2);
+		AccountAbstraction::<T>::approve_recovery(
+			RawOrigin::Signed(guardians[0].clone()).into(),
+			caller.clone(),
+			T::BenchmarkHelper::credential(0),
+		)
+		.unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert_last_event::<T>(Event::RecoveryCancelled { who: caller }.into());
+	}
+
+	#[benchmark]
+	fn finish_recovery() {
+		let max = T::MaxCredentials::get();
+		let who = caller_with_credentials::<T>(max);
+		let (_, guardians) = caller_with_guardians::<T>(1, 1);
+		let credential = T::BenchmarkHelper::credential(max);
+		AccountAbstraction::<T>::approve_recovery(
+			RawOrigin::Signed(guardians[0].clone()).into(),
+			who.clone(),
+			credential.clone(),
+		)
+		.unwrap();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now.saturating_add(T::RecoveryDelay::get()));
+		let caller: T::AccountId = account("caller", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), who.clone());
+
+		assert_last_event::<T>(Event::AccountRecovered { who, credential }.into());
+	}
+
+	#[benchmark]
+	fn authorize() -> Result<(), BenchmarkError> {
+		let max = T::MaxCredentials::get();
+		let account = caller_with_credentials::<T>(max);
+		let call: T::RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+		let ext_version: ExtensionVersion = 0;
+		let info = call.get_dispatch_info();
+		let msg = (ext_version, &call).using_encoded(blake2_256);
+		// The last credential is the worst case for the lookup.
+		let credential = T::BenchmarkHelper::credential(max - 1);
+		let proof = T::BenchmarkHelper::proof(max - 1, &msg);
+		let ext = AuthorizeAccount::<T>::new_authorized(account, credential, proof);
+
+		#[block]
+		{
+			assert!(ext
+				.validate_only(
+					RawOrigin::None.into(),
+					&call,
+					&info,
+					0,
+					TransactionSource::External,
+					ext_version
+				)
+				.is_ok());
+		}
+
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(
+		AccountAbstraction,
+		crate::tests::new_test_ext(),
+		crate::tests::Test
+	);
+}
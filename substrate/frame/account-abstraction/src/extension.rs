@@ -0,0 +1,152 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Transaction extension which authorizes an account with a proof for one of its credentials.
+
+use crate::{Authenticator, Config, CredentialOf, Pallet, ProofOf, WeightInfo};
+use codec::{Decode, DecodeWithMemTracking, Encode};
+use frame_support::{
+	pallet_prelude::TransactionSource, traits::OriginTrait, weights::Weight, CloneNoBound,
+	EqNoBound, PartialEqNoBound,
+};
+use scale_info::TypeInfo;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	impl_tx_ext_default,
+	traits::{
+		transaction_extension::TransactionExtension, AsTransactionAuthorizedOrigin, DispatchInfoOf,
+		Dispatchable,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError, ValidTransaction},
+};
+
+/// Extension that, if enabled, authorizes a transaction as signed by an account, given a proof for
+/// one of the credentials registered for the account in the pallet.
+///
+/// The proof must authorize the `blake2_256` hash of the payload constructed from the call and the
+/// rest of the transaction extension pipeline, like the signature checked by
+/// `pallet_verify_signature::VerifySignature`. It is meant to be placed ahead of any other
+/// extensions that do authorization work in the [`TransactionExtension`] pipeline.
+#[derive(
+	Encode, Decode, DecodeWithMemTracking, CloneNoBound, EqNoBound, PartialEqNoBound, TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+pub enum AuthorizeAccount<T: Config + Send + Sync> {
+	/// The extension will check the proof and, if successful, authorize the transaction as
+	/// signed by the account.
+	Authorize {
+		/// The account the transaction is authorized for.
+		account: T::AccountId,
+		/// The credential of the account the proof is for.
+		credential: CredentialOf<T>,
+		/// The proof provided by the transaction submitter.
+		proof: ProofOf<T>,
+	},
+	/// The extension is disabled and will be passthrough.
+	Disabled,
+}
+
+impl<T: Config + Send + Sync> core::fmt::Debug for AuthorizeAccount<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "AuthorizeAccount")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> AuthorizeAccount<T> {
+	/// Create a new extension instance that will authorize `account` with the provided proof.
+	pub fn new_authorized(
+		account: T::AccountId,
+		credential: CredentialOf<T>,
+		proof: ProofOf<T>,
+	) -> Self {
+		Self::Authorize { account, credential, proof }
+	}
+
+	/// Create a new passthrough extension instance.
+	pub fn new_disabled() -> Self {
+		Self::Disabled
+	}
+}
+
+impl<T> TransactionExtension<T::RuntimeCall> for AuthorizeAccount<T>
+where
+	T: Config + Send + Sync,
+	<T::RuntimeCall as Dispatchable>::RuntimeOrigin: AsTransactionAuthorizedOrigin,
+{
+	const IDENTIFIER: &'static str = "AuthorizeAccount";
+	type Implicit = ();
+	type Val = ();
+	type Pre = ();
+
+	fn weight(&self, _call: &T::RuntimeCall) -> Weight {
+		match &self {
+			// The benchmarked weight of the credential lookup and proof checking.
+			Self::Authorize { .. } => T::WeightInfo::authorize(),
+			// When the extension is passthrough, it consumes no weight.
+			Self::Disabled => Weight::zero(),
+		}
+	}
+
+	fn validate(
+		&self,
+		mut origin: <T::RuntimeCall as Dispatchable>::RuntimeOrigin,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		_: (),
+		inherited_implication: &impl Encode,
+		_source: TransactionSource,
+	) -> Result<
+		(ValidTransaction, Self::Val, <T::RuntimeCall as Dispatchable>::RuntimeOrigin),
+		TransactionValidityError,
+	> {
+		// If the extension is disabled, return early.
+		let (account, credential, proof) = match &self {
+			Self::Authorize { account, credential, proof } => (account, credential, proof),
+			Self::Disabled => return Ok((Default::default(), (), origin)),
+		};
+
+		// Like `VerifySignature`, this extension headlines the authorization pipeline.
+		if origin.is_transaction_authorized() {
+			return Err(InvalidTransaction::BadSigner.into())
+		}
+
+		// Only credentials registered for the account, and not expired, can authorize it.
+		if !Pallet::<T>::is_valid_credential(account, credential) {
+			return Err(InvalidTransaction::BadSigner.into())
+		}
+
+		// The inherited implication contains the encoded bytes of the call and all of the
+		// extension data of the extensions that follow in the pipeline.
+		let msg = inherited_implication.using_encoded(blake2_256);
+		if !T::Authenticator::authenticate(credential, &msg, proof) {
+			Err(InvalidTransaction::BadProof)?
+		}
+
+		// Return the account as the transaction origin.
+		origin.set_caller_from_signed(account.clone());
+		Ok((ValidTransaction::default(), (), origin))
+	}
+
+	impl_tx_ext_default!(T::RuntimeCall; prepare);
+}
@@ -0,0 +1,501 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Account Abstraction Pallet
+//!
+//! Alternative authorization of the transactions of accounts.
+//!
+//! ## Overview
+//!
+//! Accounts register credentials, e.g. session keys or passkeys, in this pallet. The
+//! [`AuthorizeAccount`] transaction extension then authorizes a transaction as signed by an
+//! account, given a proof of one of its valid credentials for the transaction, e.g. a signature or
+//! a WebAuthn assertion. How credentials are proven is configured by the [`Authenticator`] of the
+//! pallet, see [`SignedBy`] and [`Passkey`]; several can be combined with a tuple.
+//!
+//! Credentials can expire at a given block, which makes them suitable for the session keys of
+//! meta-transactions. For every account with credentials, a deposit is held through the
+//! configured [`Consideration`].
+//!
+//! ### Social Recovery
+//!
+//! An account can appoint guardians, and a threshold of them can replace the credentials of the
+//! account with a new one, e.g. after the passkey of the account was lost. The new credential only
+//! takes effect after the configured [`RecoveryDelay`](Config::RecoveryDelay), during which the
+//! account can still cancel the recovery, e.g. if its guardians colluded.
+//!
+//! ## Dispatchable Functions
+//!
+//! - [`add_credential`](Pallet::add_credential) - Register a credential for the caller.
+//! - [`remove_credential`](Pallet::remove_credential) - Remove a credential of the caller.
+//! - [`set_guardians`](Pallet::set_guardians) - Set the guardians of the caller.
+//! - [`remove_guardians`](Pallet::remove_guardians) - Remove the guardians of the caller.
+//! - [`approve_recovery`](Pallet::approve_recovery) - Approve a new credential for an account as
+//!   one of its guardians.
+//! - [`cancel_recovery`](Pallet::cancel_recovery) - Cancel the pending recovery of the caller.
+//! - [`finish_recovery`](Pallet::finish_recovery) - Replace the credentials of an account by the
+//!   credential approved by its guardians, once the recovery delay passed.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod authenticator;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod extension;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+extern crate alloc;
+
+pub use authenticator::*;
+#[cfg(feature = "runtime-benchmarks")]
+pub use benchmarking::BenchmarkHelper;
+pub use extension::AuthorizeAccount;
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Consideration, Footprint},
+};
+use frame_system::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Saturating, RuntimeDebug};
+
+pub use pallet::*;
+
+/// The credential type of the authenticator of the pallet.
+pub type CredentialOf<T> = <<T as Config>::Authenticator as Authenticator>::Credential;
+
+/// The proof type of the authenticator of the pallet.
+pub type ProofOf<T> = <<T as Config>::Authenticator as Authenticator>::Proof;
+
+/// A credential registered for an account.
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct CredentialInfo<Credential, BlockNumber> {
+	/// The credential.
+	pub credential: Credential,
+	/// The block from which on the credential is no longer valid, if any.
+	pub expires_at: Option<BlockNumber>,
+}
+
+/// The guardians of an account.
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[scale_info(skip_type_params(MaxGuardians))]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+pub struct GuardianSet<AccountId, MaxGuardians: Get<u32>> {
+	/// The guardians.
+	pub guardians: BoundedVec<AccountId, MaxGuardians>,
+	/// The number of guardians that need to approve a recovery.
+	pub threshold: u32,
+}
+
+type CredentialInfoOf<T> = CredentialInfo<CredentialOf<T>, BlockNumberFor<T>>;
+type GuardianSetOf<T> =
+	GuardianSet<<T as frame_system::Config>::AccountId, <T as Config>::MaxGuardians>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Checks the proofs for the credentials of accounts.
+		type Authenticator: Authenticator;
+
+		/// The maximum number of credentials of an account.
+		#[pallet::constant]
+		type MaxCredentials: Get<u32>;
+
+		/// The maximum number of guardians of an account.
+		#[pallet::constant]
+		type MaxGuardians: Get<u32>;
+
+		/// The number of blocks between the guardians of an account approving a new credential
+		/// and the credential replacing the credentials of the account.
+		///
+		/// During this period the account can cancel the recovery.
+		#[pallet::constant]
+		type RecoveryDelay: Get<BlockNumberFor<Self>>;
+
+		/// A means of providing some cost for storing the credentials or guardians of an account.
+		type Consideration: Consideration<Self::AccountId, Footprint>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Helper to create credentials and proofs to be benchmarked.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<CredentialOf<Self>, ProofOf<Self>>;
+	}
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The credentials or guardians of an account.
+		#[codec(index = 0)]
+		Deposit,
+	}
+
+	/// The credentials of an account, together with the ticket for their deposit.
+	#[pallet::storage]
+	pub type Credentials<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		(BoundedVec<CredentialInfoOf<T>, T::MaxCredentials>, T::Consideration),
+	>;
+
+	/// The guardians of an account, together with the ticket for their deposit.
+	#[pallet::storage]
+	pub type Guardians<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (GuardianSetOf<T>, T::Consideration)>;
+
+	/// The approvals of the pending recovery of an account: the guardians and the credential each
+	/// of them approved.
+	#[pallet::storage]
+	pub type Approvals<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(T::AccountId, CredentialOf<T>), T::MaxGuardians>,
+		ValueQuery,
+	>;
+
+	/// The recoveries approved by the guardians of an account: the approved credential and the
+	/// block from which on it can replace the credentials of the account.
+	#[pallet::storage]
+	pub type Recoveries<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (CredentialOf<T>, BlockNumberFor<T>)>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A credential was added to an account.
+		CredentialAdded { who: T::AccountId, credential: CredentialOf<T> },
+		/// A credential was removed from an account.
+		CredentialRemoved { who: T::AccountId, credential: CredentialOf<T> },
+		/// The guardians of an account were set.
+		GuardiansSet { who: T::AccountId, threshold: u32 },
+		/// The guardians of an account were removed.
+		GuardiansRemoved { who: T::AccountId },
+		/// A guardian approved a new credential for an account.
+		RecoveryApproved { who: T::AccountId, guardian: T::AccountId, credential: CredentialOf<T> },
+		/// A threshold of the guardians of an account approved a new credential, which can replace
+		/// the credentials of the account from block `executable_at` on.
+		RecoveryInitiated {
+			who: T::AccountId,
+			credential: CredentialOf<T>,
+			executable_at: BlockNumberFor<T>,
+		},
+		/// The credentials of an account were replaced by the credential approved by its
+		/// guardians.
+		AccountRecovered { who: T::AccountId, credential: CredentialOf<T> },
+		/// The pending recovery of an account was cancelled.
+		RecoveryCancelled { who: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The expiry of the credential is not in the future.
+		AlreadyExpired,
+		/// The credential is already registered for the account.
+		AlreadyRegistered,
+		/// The account has the maximum number of credentials.
+		TooManyCredentials,
+		/// The credential is not registered for the account.
+		UnknownCredential,
+		/// The threshold is zero or exceeds the number of guardians.
+		InvalidThreshold,
+		/// A guardian is given more than once.
+		DuplicateGuardian,
+		/// The account has no guardians.
+		NoGuardians,
+		/// The caller is not a guardian of the account.
+		NotGuardian,
+		/// The account has no pending recovery.
+		NoRecovery,
+		/// The guardians of the account already approved a new credential.
+		RecoveryInitiated,
+		/// The recovery delay has not passed yet.
+		RecoveryDelayed,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(T::MaxCredentials::get() > 0, "MaxCredentials must be greater than 0");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a credential which can authorize transactions of the caller until
+		/// `expires_at`, if given.
+		///
+		/// Holds a deposit for the credentials of the caller.
+		///
+		/// Emits [`Event::CredentialAdded`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::add_credential())]
+		pub fn add_credential(
+			origin: OriginFor<T>,
+			credential: CredentialOf<T>,
+			expires_at: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if let Some(at) = expires_at {
+				ensure!(frame_system::Pallet::<T>::block_number() < at, Error::<T>::AlreadyExpired);
+			}
+
+			let (mut credentials, ticket) = match Credentials::<T>::take(&who) {
+				Some((credentials, ticket)) => (credentials, Some(ticket)),
+				None => (BoundedVec::new(), None),
+			};
+			ensure!(
+				!credentials.iter().any(|info| info.credential == credential),
+				Error::<T>::AlreadyRegistered,
+			);
+			credentials
+				.try_push(CredentialInfo { credential: credential.clone(), expires_at })
+				.map_err(|_| Error::<T>::TooManyCredentials)?;
+
+			let ticket = Self::update_ticket(&who, ticket, Self::footprint(credentials.len()))?;
+			Credentials::<T>::insert(&who, (credentials, ticket));
+
+			Self::deposit_event(Event::CredentialAdded { who, credential });
+			Ok(())
+		}
+
+		/// Remove a credential of the caller.
+		///
+		/// The deposit is released once the caller has no credentials left.
+		///
+		/// Emits [`Event::CredentialRemoved`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_credential())]
+		pub fn remove_credential(
+			origin: OriginFor<T>,
+			credential: CredentialOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (mut credentials, ticket) =
+				Credentials::<T>::get(&who).ok_or(Error::<T>::UnknownCredential)?;
+			let index = credentials
+				.iter()
+				.position(|info| info.credential == credential)
+				.ok_or(Error::<T>::UnknownCredential)?;
+			credentials.remove(index);
+
+			if credentials.is_empty() {
+				ticket.drop(&who)?;
+				Credentials::<T>::remove(&who);
+			} else {
+				let ticket = ticket.update(&who, Self::footprint(credentials.len()))?;
+				Credentials::<T>::insert(&who, (credentials, ticket));
+			}
+
+			Self::deposit_event(Event::CredentialRemoved { who, credential });
+			Ok(())
+		}
+
+		/// Set the guardians of the caller, `threshold` of which can recover the account.
+		///
+		/// Replaces the previous guardians and cancels a pending recovery. Holds a deposit for the
+		/// guardians.
+		///
+		/// Emits [`Event::GuardiansSet`].
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::set_guardians(guardians.len() as u32))]
+		pub fn set_guardians(
+			origin: OriginFor<T>,
+			guardians: BoundedVec<T::AccountId, T::MaxGuardians>,
+			threshold: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				threshold > 0 && threshold as usize <= guardians.len(),
+				Error::<T>::InvalidThreshold,
+			);
+			for (i, guardian) in guardians.iter().enumerate() {
+				ensure!(!guardians[..i].contains(guardian), Error::<T>::DuplicateGuardian);
+			}
+
+			let ticket = Guardians::<T>::take(&who).map(|(_, ticket)| ticket);
+			let footprint = Footprint::from_mel::<GuardianSetOf<T>>();
+			let ticket = Self::update_ticket(&who, ticket, footprint)?;
+			Guardians::<T>::insert(&who, (GuardianSet { guardians, threshold }, ticket));
+			Approvals::<T>::remove(&who);
+			Recoveries::<T>::remove(&who);
+
+			Self::deposit_event(Event::GuardiansSet { who, threshold });
+			Ok(())
+		}
+
+		/// Remove the guardians of the caller and cancel a pending recovery.
+		///
+		/// Emits [`Event::GuardiansRemoved`].
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::remove_guardians())]
+		pub fn remove_guardians(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (_, ticket) = Guardians::<T>::take(&who).ok_or(Error::<T>::NoGuardians)?;
+			ticket.drop(&who)?;
+			Approvals::<T>::remove(&who);
+			Recoveries::<T>::remove(&who);
+
+			Self::deposit_event(Event::GuardiansRemoved { who });
+			Ok(())
+		}
+
+		/// Approve `credential` as the new credential of `account`, as one of its guardians.
+		///
+		/// Replaces a previous approval of the caller. Once `threshold` guardians approved the
+		/// same credential, it can replace all credentials of the account after
+		/// [`Config::RecoveryDelay`], see [`Pallet::finish_recovery`].
+		///
+		/// Emits [`Event::RecoveryApproved`] and, once the threshold is reached,
+		/// [`Event::RecoveryInitiated`].
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::approve_recovery(T::MaxGuardians::get()))]
+		pub fn approve_recovery(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			credential: CredentialOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let guardian = ensure_signed(origin)?;
+			let (set, _) = Guardians::<T>::get(&account).ok_or(Error::<T>::NoGuardians)?;
+			ensure!(set.guardians.contains(&guardian), Error::<T>::NotGuardian);
+			ensure!(!Recoveries::<T>::contains_key(&account), Error::<T>::RecoveryInitiated);
+			let weight = T::WeightInfo::approve_recovery(set.guardians.len() as u32);
+
+			let mut approvals = Approvals::<T>::get(&account);
+			approvals.retain(|(approver, _)| approver != &guardian);
+			approvals.try_push((guardian.clone(), credential.clone())).expect(
+				"One approval per guardian, of which there are at most `MaxGuardians`; qed",
+			);
+			Self::deposit_event(Event::RecoveryApproved {
+				who: account.clone(),
+				guardian,
+				credential: credential.clone(),
+			});
+
+			let approved = approvals.iter().filter(|(_, approved)| approved == &credential).count();
+			if approved < set.threshold as usize {
+				Approvals::<T>::insert(&account, approvals);
+				return Ok(Some(weight).into())
+			}
+
+			let executable_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::RecoveryDelay::get());
+			Recoveries::<T>::insert(&account, (credential.clone(), executable_at));
+			Approvals::<T>::remove(&account);
+
+			Self::deposit_event(Event::RecoveryInitiated {
+				who: account,
+				credential,
+				executable_at,
+			});
+			Ok(Some(weight).into())
+		}
+
+		/// Cancel the pending recovery of the caller, e.g. if the caller did not lose access.
+		///
+		/// Discards the approvals of the guardians, including a recovery they already approved.
+		///
+		/// Emits [`Event::RecoveryCancelled`].
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::cancel_recovery())]
+		pub fn cancel_recovery(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				Approvals::<T>::contains_key(&who) || Recoveries::<T>::contains_key(&who),
+				Error::<T>::NoRecovery,
+			);
+			Approvals::<T>::remove(&who);
+			Recoveries::<T>::remove(&who);
+
+			Self::deposit_event(Event::RecoveryCancelled { who });
+			Ok(())
+		}
+
+		/// Replace all credentials of `account` by the credential approved by its guardians,
+		/// without expiry, once [`Config::RecoveryDelay`] passed.
+		///
+		/// May be called by any signed origin.
+		///
+		/// Emits [`Event::AccountRecovered`].
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::finish_recovery())]
+		pub fn finish_recovery(origin: OriginFor<T>, account: T::AccountId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let (credential, executable_at) =
+				Recoveries::<T>::get(&account).ok_or(Error::<T>::NoRecovery)?;
+			ensure!(
+				executable_at <= frame_system::Pallet::<T>::block_number(),
+				Error::<T>::RecoveryDelayed,
+			);
+
+			let info = CredentialInfo { credential: credential.clone(), expires_at: None };
+			let credentials = BoundedVec::truncate_from(alloc::vec![info]);
+			let ticket = Credentials::<T>::take(&account).map(|(_, ticket)| ticket);
+			let ticket = Self::update_ticket(&account, ticket, Self::footprint(1))?;
+			Credentials::<T>::insert(&account, (credentials, ticket));
+			Recoveries::<T>::remove(&account);
+
+			Self::deposit_event(Event::AccountRecovered { who: account, credential });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether `credential` is registered for `account` and not expired.
+	pub fn is_valid_credential(account: &T::AccountId, credential: &CredentialOf<T>) -> bool {
+		let now = frame_system::Pallet::<T>::block_number();
+		Credentials::<T>::get(account).is_some_and(|(credentials, _)| {
+			credentials.iter().any(|info| {
+				&info.credential == credential && info.expires_at.map_or(true, |at| now < at)
+			})
+		})
+	}
+
+	/// The footprint of `count` credentials.
+	fn footprint(count: usize) -> Footprint {
+		Footprint::from_parts(count, CredentialInfoOf::<T>::max_encoded_len())
+	}
+
+	/// Update the ticket for a deposit, or create it if there is none.
+	fn update_ticket(
+		who: &T::AccountId,
+		ticket: Option<T::Consideration>,
+		footprint: Footprint,
+	) -> Result<T::Consideration, DispatchError> {
+		match ticket {
+			Some(ticket) => ticket.update(who, footprint),
+			None => T::Consideration::new(who, footprint),
+		}
+	}
+}
@@ -0,0 +1,338 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Tests for the Account Abstraction Pallet.
+
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec, derive_impl,
+	dispatch::GetDispatchInfo,
+	pallet_prelude::{InvalidTransaction, TransactionSource, TransactionValidityError},
+	traits::{ConstU32, ConstU64, OriginTrait},
+};
+use frame_system::Call as SystemCall;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	generic::ExtensionVersion, testing::TestSignature, traits::DispatchTransaction, BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		AccountAbstraction: crate,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct BenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<u64, TestSignature> for BenchmarkHelper {
+	fn credential(index: u32) -> u64 {
+		100 + index as u64
+	}
+
+	fn proof(index: u32, msg: &[u8; 32]) -> TestSignature {
+		TestSignature(100 + index as u64, msg.to_vec())
+	}
+}
+
+impl Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Authenticator = SignedBy<TestSignature>;
+	type MaxCredentials = ConstU32<3>;
+	type MaxGuardians = ConstU32<5>;
+	type RecoveryDelay = ConstU64<10>;
+	type Consideration = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = BenchmarkHelper;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+const ALICE: u64 = 1;
+const SESSION_KEY: u64 = 11;
+
+fn authorize(
+	account: u64,
+	credential: u64,
+	signer: u64,
+) -> Result<Option<u64>, TransactionValidityError> {
+	let call: RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+	let ext_version: ExtensionVersion = 0;
+	let proof = TestSignature(signer, (ext_version, &call).using_encoded(blake2_256).to_vec());
+	let info = call.get_dispatch_info();
+
+	AuthorizeAccount::<Test>::new_authorized(account, credential, proof)
+		.validate_only(None.into(), &call, &info, 0, TransactionSource::External, ext_version)
+		.map(|(_, _, origin)| origin.as_signer().cloned())
+}
+
+fn credentials(who: u64) -> Vec<(u64, Option<u64>)> {
+	Credentials::<Test>::get(who)
+		.map(|(credentials, _)| {
+			credentials.into_iter().map(|info| (info.credential, info.expires_at)).collect()
+		})
+		.unwrap_or_default()
+}
+
+#[test]
+fn authorize_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			authorize(ALICE, SESSION_KEY, SESSION_KEY),
+			Err(InvalidTransaction::BadSigner.into())
+		);
+
+		assert_ok!(AccountAbstraction::add_credential(
+			RuntimeOrigin::signed(ALICE),
+			SESSION_KEY,
+			None
+		));
+		assert_eq!(authorize(ALICE, SESSION_KEY, SESSION_KEY), Ok(Some(ALICE)));
+		// The credential is registered for another account only.
+		assert_eq!(
+			authorize(2, SESSION_KEY, SESSION_KEY),
+			Err(InvalidTransaction::BadSigner.into())
+		);
+		// Signed by another key.
+		assert_eq!(authorize(ALICE, SESSION_KEY, 12), Err(InvalidTransaction::BadProof.into()));
+	});
+}
+
+#[test]
+fn authorize_needs_unauthorized_origin() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccountAbstraction::add_credential(
+			RuntimeOrigin::signed(ALICE),
+			SESSION_KEY,
+			None
+		));
+		let call: RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+		let proof = TestSignature(SESSION_KEY, (0u8, &call).using_encoded(blake2_256).to_vec());
+		let info = call.get_dispatch_info();
+
+		assert_eq!(
+			AuthorizeAccount::<Test>::new_authorized(ALICE, SESSION_KEY, proof)
+				.validate_only(Some(42).into(), &call, &info, 0, TransactionSource::External, 0)
+				.unwrap_err(),
+			TransactionValidityError::Invalid(InvalidTransaction::BadSigner)
+		);
+	});
+}
+
+#[test]
+fn disabled_extension_works() {
+	new_test_ext().execute_with(|| {
+		let call: RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+		let info = call.get_dispatch_info();
+
+		let (_, _, origin) = AuthorizeAccount::<Test>::new_disabled()
+			.validate_only(Some(42).into(), &call, &info, 0, TransactionSource::External, 0)
+			.unwrap();
+		assert_eq!(origin.as_signer().unwrap(), &42)
+	});
+}
+
+#[test]
+fn credentials_expire() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AccountAbstraction::add_credential(RuntimeOrigin::signed(ALICE), SESSION_KEY, Some(1)),
+			Error::<Test>::AlreadyExpired
+		);
+		assert_ok!(AccountAbstraction::add_credential(
+			RuntimeOrigin::signed(ALICE),
+			SESSION_KEY,
+			Some(3)
+		));
+
+		System::set_block_number(2);
+		assert_eq!(authorize(ALICE, SESSION_KEY, SESSION_KEY), Ok(Some(ALICE)));
+		System::set_block_number(3);
+		assert_eq!(
+			authorize(ALICE, SESSION_KEY, SESSION_KEY),
+			Err(InvalidTransaction::BadSigner.into())
+		);
+	});
+}
+
+#[test]
+fn add_and_remove_credentials_works() {
+	new_test_ext().execute_with(|| {
+		for credential in [11, 12, 13] {
+			assert_ok!(AccountAbstraction::add_credential(
+				RuntimeOrigin::signed(ALICE),
+				credential,
+				None
+			));
+		}
+		System::assert_last_event(Event::CredentialAdded { who: ALICE, credential: 13 }.into());
+		assert_noop!(
+			AccountAbstraction::add_credential(RuntimeOrigin::signed(ALICE), 12, None),
+			Error::<Test>::AlreadyRegistered
+		);
+		assert_noop!(
+			AccountAbstraction::add_credential(RuntimeOrigin::signed(ALICE), 14, None),
+			Error::<Test>::TooManyCredentials
+		);
+
+		assert_ok!(AccountAbstraction::remove_credential(RuntimeOrigin::signed(ALICE), 12));
+		System::assert_last_event(Event::CredentialRemoved { who: ALICE, credential: 12 }.into());
+		assert_eq!(credentials(ALICE), vec![(11, None), (13, None)]);
+		assert_noop!(
+			AccountAbstraction::remove_credential(RuntimeOrigin::signed(ALICE), 12),
+			Error::<Test>::UnknownCredential
+		);
+
+		assert_ok!(AccountAbstraction::remove_credential(RuntimeOrigin::signed(ALICE), 11));
+		assert_ok!(AccountAbstraction::remove_credential(RuntimeOrigin::signed(ALICE), 13));
+		assert!(!Credentials::<Test>::contains_key(ALICE));
+	});
+}
+
+#[test]
+fn set_guardians_works() {
+	new_test_ext().execute_with(|| {
+		let origin = || RuntimeOrigin::signed(ALICE);
+		assert_noop!(
+			AccountAbstraction::set_guardians(origin(), bounded_vec![2, 3], 0),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_noop!(
+			AccountAbstraction::set_guardians(origin(), bounded_vec![2, 3], 3),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_noop!(
+			AccountAbstraction::set_guardians(origin(), bounded_vec![2, 3, 2], 2),
+			Error::<Test>::DuplicateGuardian
+		);
+
+		assert_ok!(AccountAbstraction::set_guardians(origin(), bounded_vec![2, 3, 4], 2));
+		System::assert_last_event(Event::GuardiansSet { who: ALICE, threshold: 2 }.into());
+
+		assert_ok!(AccountAbstraction::remove_guardians(origin()));
+		System::assert_last_event(Event::GuardiansRemoved { who: ALICE }.into());
+		assert_noop!(AccountAbstraction::remove_guardians(origin()), Error::<Test>::NoGuardians);
+	});
+}
+
+#[test]
+fn recovery_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccountAbstraction::add_credential(
+			RuntimeOrigin::signed(ALICE),
+			SESSION_KEY,
+			Some(10)
+		));
+		assert_ok!(AccountAbstraction::set_guardians(
+			RuntimeOrigin::signed(ALICE),
+			bounded_vec![2, 3, 4],
+			2
+		));
+
+		assert_noop!(
+			AccountAbstraction::approve_recovery(RuntimeOrigin::signed(5), ALICE, 20),
+			Error::<Test>::NotGuardian
+		);
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(2), ALICE, 20));
+		System::assert_last_event(
+			Event::RecoveryApproved { who: ALICE, guardian: 2, credential: 20 }.into(),
+		);
+		// A vote for another credential doesn't count, nor do repeated votes.
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(3), ALICE, 21));
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(2), ALICE, 20));
+		assert_eq!(credentials(ALICE), vec![(SESSION_KEY, Some(10))]);
+
+		// Guardian 3 changes the vote, which starts the delay.
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(3), ALICE, 20));
+		System::assert_last_event(
+			Event::RecoveryInitiated { who: ALICE, credential: 20, executable_at: 11 }.into(),
+		);
+		assert!(!Approvals::<Test>::contains_key(ALICE));
+		assert_noop!(
+			AccountAbstraction::approve_recovery(RuntimeOrigin::signed(4), ALICE, 21),
+			Error::<Test>::RecoveryInitiated
+		);
+		assert_noop!(
+			AccountAbstraction::finish_recovery(RuntimeOrigin::signed(5), ALICE),
+			Error::<Test>::RecoveryDelayed
+		);
+		assert_eq!(credentials(ALICE), vec![(SESSION_KEY, Some(10))]);
+
+		System::set_block_number(11);
+		assert_ok!(AccountAbstraction::finish_recovery(RuntimeOrigin::signed(5), ALICE));
+		System::assert_last_event(Event::AccountRecovered { who: ALICE, credential: 20 }.into());
+		assert_eq!(credentials(ALICE), vec![(20, None)]);
+		assert!(!Recoveries::<Test>::contains_key(ALICE));
+		assert_eq!(authorize(ALICE, 20, 20), Ok(Some(ALICE)));
+		assert_eq!(
+			authorize(ALICE, SESSION_KEY, SESSION_KEY),
+			Err(InvalidTransaction::BadSigner.into())
+		);
+	});
+}
+
+#[test]
+fn cancel_recovery_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccountAbstraction::set_guardians(
+			RuntimeOrigin::signed(ALICE),
+			bounded_vec![2, 3],
+			2
+		));
+		assert_noop!(
+			AccountAbstraction::cancel_recovery(RuntimeOrigin::signed(ALICE)),
+			Error::<Test>::NoRecovery
+		);
+
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(2), ALICE, 20));
+		assert_ok!(AccountAbstraction::cancel_recovery(RuntimeOrigin::signed(ALICE)));
+		System::assert_last_event(Event::RecoveryCancelled { who: ALICE }.into());
+
+		// The approval of guardian 2 was discarded.
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(3), ALICE, 20));
+		assert!(!Recoveries::<Test>::contains_key(ALICE));
+
+		// A recovery approved by a threshold of guardians can be cancelled during the delay.
+		assert_ok!(AccountAbstraction::approve_recovery(RuntimeOrigin::signed(2), ALICE, 20));
+		assert!(Recoveries::<Test>::contains_key(ALICE));
+		assert_ok!(AccountAbstraction::cancel_recovery(RuntimeOrigin::signed(ALICE)));
+		System::set_block_number(11);
+		assert_noop!(
+			AccountAbstraction::finish_recovery(RuntimeOrigin::signed(2), ALICE),
+			Error::<Test>::NoRecovery
+		);
+		assert!(!Credentials::<Test>::contains_key(ALICE));
+	});
+}
@@ -0,0 +1,247 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholder weights for `pallet_account_abstraction`.
+//!
+//! THESE WEIGHTS WERE NOT GENERATED BY THE BENCHMARK CLI. They are estimates derived from the
+//! storage accesses of each call and comparable benchmarked calls of other pallets, and must be
+//! replaced by running the benchmarks of the pallet before it is deployed to a production chain:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime=target/production/wbuild/kitchensink-runtime/kitchensink_runtime.wasm \
+//!     --pallet=pallet_account_abstraction --extrinsic=* --steps=50 --repeat=20 \
+//!     --header=substrate/HEADER-APACHE2 \
+//!     --output=substrate/frame/account-abstraction/src/weights.rs \
+//!     --template=substrate/.maintain/frame-weight-template.hbs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(dead_code)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_account_abstraction`.
+pub trait WeightInfo {
+	fn add_credential() -> Weight;
+	fn remove_credential() -> Weight;
+	fn set_guardians(n: u32, ) -> Weight;
+	fn remove_guardians() -> Weight;
+	fn approve_recovery(n: u32, ) -> Weight;
+	fn cancel_recovery() -> Weight;
+	fn finish_recovery() -> Weight;
+	fn authorize() -> Weight;
+}
+
+/// Weights for `pallet_account_abstraction` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn add_credential() -> Weight {
+		Weight::from_parts(55_630_000, 28584)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn remove_credential() -> Weight {
+		Weight::from_parts(51_318_000, 28584)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Guardians` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Guardians` (`max_values`: None, `max_size`: Some(373), added: 2848, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Approvals` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 10]`.
+	fn set_guardians(n: u32, ) -> Weight {
+		Weight::from_parts(51_044_000, 28584)
+			.saturating_add(Weight::from_parts(93_416, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `AccountAbstraction::Guardians` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Guardians` (`max_values`: None, `max_size`: Some(373), added: 2848, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Approvals` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	fn remove_guardians() -> Weight {
+		Weight::from_parts(47_502_000, 3892)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `AccountAbstraction::Guardians` (r:1 w:0)
+	/// Proof: `AccountAbstraction::Guardians` (`max_values`: None, `max_size`: Some(373), added: 2848, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Approvals` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 10]`.
+	fn approve_recovery(n: u32, ) -> Weight {
+		Weight::from_parts(31_420_000, 4156)
+			.saturating_add(Weight::from_parts(412_830, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Approvals` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	fn cancel_recovery() -> Weight {
+		Weight::from_parts(17_930_000, 4156)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Recoveries` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn finish_recovery() -> Weight {
+		Weight::from_parts(58_910_000, 28584)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:0)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	fn authorize() -> Weight {
+		Weight::from_parts(75_129_000, 3842)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn add_credential() -> Weight {
+		Weight::from_parts(55_630_000, 28584)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn remove_credential() -> Weight {
+		Weight::from_parts(51_318_000, 28584)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Guardians` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Guardians` (`max_values`: None, `max_size`: Some(373), added: 2848, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Approvals` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 10]`.
+	fn set_guardians(n: u32, ) -> Weight {
+		Weight::from_parts(51_044_000, 28584)
+			.saturating_add(Weight::from_parts(93_416, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `AccountAbstraction::Guardians` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Guardians` (`max_values`: None, `max_size`: Some(373), added: 2848, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Approvals` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:0 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	fn remove_guardians() -> Weight {
+		Weight::from_parts(47_502_000, 3892)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `AccountAbstraction::Guardians` (r:1 w:0)
+	/// Proof: `AccountAbstraction::Guardians` (`max_values`: None, `max_size`: Some(373), added: 2848, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Approvals` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 10]`.
+	fn approve_recovery(n: u32, ) -> Weight {
+		Weight::from_parts(31_420_000, 4156)
+			.saturating_add(Weight::from_parts(412_830, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Approvals` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Approvals` (`max_values`: None, `max_size`: Some(691), added: 3166, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Recoveries` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	fn cancel_recovery() -> Weight {
+		Weight::from_parts(17_930_000, 4156)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AccountAbstraction::Recoveries` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Recoveries` (`max_values`: None, `max_size`: Some(89), added: 2564, mode: `MaxEncodedLen`)
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:1)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn finish_recovery() -> Weight {
+		Weight::from_parts(58_910_000, 28584)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `AccountAbstraction::Credentials` (r:1 w:0)
+	/// Proof: `AccountAbstraction::Credentials` (`max_values`: None, `max_size`: Some(377), added: 2852, mode: `MaxEncodedLen`)
+	fn authorize() -> Weight {
+		Weight::from_parts(75_129_000, 3842)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+}
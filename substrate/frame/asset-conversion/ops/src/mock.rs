@@ -114,6 +114,7 @@ impl pallet_asset_conversion::Config for Test {
 	type LPFee = ConstU32<3>;
 	type LiquidityWithdrawalFee = LiquidityWithdrawalFee;
 	type MaxSwapPathLength = ConstU32<4>;
+	type MaxPriceObservations = ConstU32<8>;
 	type MintMinLiquidity = ConstU64<100>;
 	#[cfg(feature = "runtime-benchmarks")]
 	type BenchmarkHelper = ();
@@ -28,6 +28,7 @@ use frame_support::{
 		fungible::NativeOrWithId,
 		fungibles::{Create, Inspect, Mutate, Refund},
 	},
+	BoundedVec,
 };
 use frame_system::RawOrigin as SystemOrigin;
 use sp_core::Get;
@@ -166,6 +167,28 @@ where
 	(lp_token, liquidity1, liquidity2)
 }
 
+/// Fills the price observations of the pool of `asset1` and `asset2` with observations from past
+/// blocks, so that the next observation has to be recorded and evicts the oldest one.
+fn fill_price_observations<T: Config>(asset1: &T::AssetKind, asset2: &T::AssetKind) {
+	let pool_id = T::PoolLocator::pool_id(asset1, asset2).unwrap();
+	let max_observations = T::MaxPriceObservations::get();
+	if frame_system::Pallet::<T>::block_number() <= max_observations.into() {
+		frame_system::Pallet::<T>::set_block_number((max_observations + 1).into());
+	}
+	let now = frame_system::Pallet::<T>::block_number();
+	let observations = (0..max_observations)
+		.map(|i| PriceObservation {
+			block: now - (max_observations - i).into(),
+			reserve1: T::Balance::one(),
+			reserve2: T::Balance::one(),
+		})
+		.collect::<Vec<_>>();
+	PriceObservations::<T>::insert(
+		pool_id,
+		BoundedVec::<_, T::MaxPriceObservations>::truncate_from(observations),
+	);
+}
+
 fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	let events = frame_system::Pallet::<T>::events();
 	let system_event: <T as frame_system::Config>::RuntimeEvent = generic_event.into();
@@ -207,6 +230,7 @@ mod benchmarks {
 		create_fee_asset::<T>(&caller);
 		let (lp_token, liquidity1, liquidity2) =
 			create_asset_and_pool::<T>(&caller, &asset1, &asset2);
+		fill_price_observations::<T>(&asset1, &asset2);
 
 		#[extrinsic_call]
 		_(
@@ -251,6 +275,7 @@ mod benchmarks {
 		));
 		let total_supply =
 			<T::PoolAssets as Inspect<T::AccountId>>::total_issuance(lp_token.clone());
+		fill_price_observations::<T>(&asset1, &asset2);
 
 		#[extrinsic_call]
 		_(
@@ -295,6 +320,7 @@ mod benchmarks {
 				T::Balance::zero(),
 				caller.clone(),
 			));
+			fill_price_observations::<T>(&asset1, &asset2);
 		}
 
 		let asset_in = *path.first().unwrap().clone();
@@ -347,6 +373,7 @@ mod benchmarks {
 				T::Balance::zero(),
 				caller.clone(),
 			));
+			fill_price_observations::<T>(&asset1, &asset2);
 		}
 
 		let asset_in = *path.first().unwrap().clone();
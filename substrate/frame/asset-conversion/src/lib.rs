@@ -34,6 +34,8 @@
 //!    a runtime call endpoint
 //!  - [query the size of a liquidity pool](`AssetConversionApi::get_reserves`) via a runtime api
 //!    endpoint.
+//!  - [query a time-weighted average price](`Pallet::time_weighted_price`) that can't be moved by
+//!    trading within a block, e.g. to value assets via [`TimeWeightedPriceConverter`].
 //!
 //! The `quote_price_exact_tokens_for_tokens` and `quote_price_tokens_for_exact_tokens` functions
 //! both take a path parameter of the route to take. If you want to swap from native asset to
@@ -73,7 +75,7 @@ pub use weights::WeightInfo;
 extern crate alloc;
 
 use alloc::{boxed::Box, collections::btree_set::BTreeSet, vec::Vec};
-use codec::Codec;
+use codec::{Codec, Encode};
 use frame_support::{
 	storage::{with_storage_layer, with_transaction},
 	traits::{
@@ -94,7 +96,8 @@ use sp_runtime::{
 		CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Ensure, IntegerSquareRoot, MaybeDisplay,
 		One, TrailingZeroInput, Zero,
 	},
-	DispatchError, Saturating, TokenError, TransactionOutcome,
+	DispatchError, FixedPointNumber, FixedU128, Permill, SaturatedConversion, Saturating,
+	TokenError, TransactionOutcome,
 };
 
 #[frame_support::pallet]
@@ -105,7 +108,7 @@ pub mod pallet {
 		traits::fungibles::Refund,
 	};
 	use frame_system::pallet_prelude::*;
-	use sp_arithmetic::{traits::Unsigned, Permill};
+	use sp_arithmetic::traits::Unsigned;
 
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
@@ -185,6 +188,11 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxSwapPathLength: Get<u32>;
 
+		/// The max number of price observations kept per pool for the time-weighted average
+		/// price. Zero disables the observations.
+		#[pallet::constant]
+		type MaxPriceObservations: Get<u32>;
+
 		/// The pallet's id, used for deriving its sovereign account ID.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
@@ -208,6 +216,19 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type NextPoolAssetId<T: Config> = StorageValue<_, T::PoolAssetId, OptionQuery>;
 
+	/// Reserves of each pool observed before its first change in a block, oldest first.
+	///
+	/// Used to compute time-weighted average prices which, unlike the spot price, can't be moved
+	/// by changing the reserves within a single block.
+	#[pallet::storage]
+	pub type PriceObservations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		BoundedVec<PriceObservation<BlockNumberFor<T>, T::Balance>, T::MaxPriceObservations>,
+		ValueQuery,
+	>;
+
 	// Pallet's events.
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -504,6 +525,7 @@ pub mod pallet {
 				Error::<T>::AmountTwoLessThanMinimal
 			);
 
+			Self::observe_price(&asset1, &asset2);
 			T::Assets::transfer(*asset1, &sender, &pool_account, amount1, Preserve)?;
 			T::Assets::transfer(*asset2, &sender, &pool_account, amount2, Preserve)?;
 
@@ -597,6 +619,8 @@ pub mod pallet {
 				Error::<T>::ReserveLeftLessThanMinimal
 			);
 
+			Self::observe_price(&asset1, &asset2);
+
 			// burn the provided lp token amount that includes the fee
 			T::PoolAssets::burn_from(
 				pool.lp_token.clone(),
@@ -955,6 +979,12 @@ pub mod pallet {
 			credit_in: CreditOf<T>,
 			path: &BalancePath<T>,
 		) -> Result<CreditOf<T>, (CreditOf<T>, DispatchError)> {
+			for pair in path.windows(2) {
+				if let [(asset1, _), (asset2, _)] = pair {
+					Self::observe_price(asset1, asset2);
+				}
+			}
+
 			let resolve_path = || -> Result<CreditOf<T>, DispatchError> {
 				for pos in 0..=path.len() {
 					if let Some([(asset1, _), (asset2, amount_out)]) = path.get(pos..=pos + 1) {
@@ -1141,6 +1171,158 @@ pub mod pallet {
 			}
 		}
 
+		/// Provides a quote for swapping exactly `amount_in` along `path`.
+		///
+		/// Returns the expected amount out together with the minimum amount out allowing for
+		/// `slippage`, to be used as `amount_out_min`.
+		pub fn quote_exact_tokens_for_tokens_with_slippage(
+			path: Vec<T::AssetKind>,
+			amount_in: T::Balance,
+			slippage: Permill,
+		) -> Option<(T::Balance, T::Balance)> {
+			Self::validate_swap_path(&path).ok()?;
+			let path = Self::balance_path_from_amount_in(amount_in, path).ok()?;
+			let amount_out = path.last().map(|(_, a)| *a)?;
+			Some((amount_out, amount_out.saturating_sub(slippage * amount_out)))
+		}
+
+		/// Provides a quote for swapping along `path` to get exactly `amount_out`.
+		///
+		/// Returns the expected amount in together with the maximum amount in allowing for
+		/// `slippage`, to be used as `amount_in_max`.
+		pub fn quote_tokens_for_exact_tokens_with_slippage(
+			path: Vec<T::AssetKind>,
+			amount_out: T::Balance,
+			slippage: Permill,
+		) -> Option<(T::Balance, T::Balance)> {
+			Self::validate_swap_path(&path).ok()?;
+			let path = Self::balance_path_from_amount_out(amount_out, path).ok()?;
+			let amount_in = path.first().map(|(_, a)| *a)?;
+			Some((amount_in, amount_in.saturating_add(slippage * amount_in)))
+		}
+
+		/// Quotes `amount` of `path[0]` in `path[last]` at the time-weighted average prices of the
+		/// pools along `path` over the last `window` blocks. Fees are not included.
+		///
+		/// Returns `None` if a pool lacks the observations to cover the window or had no liquidity
+		/// during it.
+		pub fn quote_time_weighted_price(
+			path: Vec<T::AssetKind>,
+			amount: T::Balance,
+			window: BlockNumberFor<T>,
+		) -> Option<T::Balance> {
+			Self::validate_swap_path(&path).ok()?;
+			let mut amount: u128 = amount.saturated_into();
+			for pair in path.windows(2) {
+				if let [asset1, asset2] = pair {
+					let price = Self::time_weighted_price(asset1, asset2, window)?;
+					amount = price.checked_mul_int(amount)?;
+				}
+			}
+			amount.try_into().ok()
+		}
+
+		/// Returns the average price of `asset1` in `asset2` over the last `window` blocks, i.e.
+		/// the amount of `asset2` per unit of `asset1`, weighted by the number of blocks each
+		/// price was in effect.
+		///
+		/// The reserves of the current block are only taken into account from the next block on.
+		pub fn time_weighted_price(
+			asset1: &T::AssetKind,
+			asset2: &T::AssetKind,
+			window: BlockNumberFor<T>,
+		) -> Option<FixedU128> {
+			if window.is_zero() {
+				return None
+			}
+			let pool_id = T::PoolLocator::pool_id(asset1, asset2).ok()?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let start = now.checked_sub(&window)?;
+			let observations = PriceObservations::<T>::get(&pool_id);
+			if observations.first()?.block > start {
+				return None
+			}
+
+			let ordered = Self::is_ordered(asset1, asset2);
+			let price_of = |reserve1: T::Balance, reserve2: T::Balance| {
+				let (reserve_in, reserve_out) =
+					if ordered { (reserve1, reserve2) } else { (reserve2, reserve1) };
+				if reserve_out.is_zero() {
+					return None
+				}
+				FixedU128::checked_from_rational(
+					reserve_out.saturated_into::<u128>(),
+					reserve_in.saturated_into::<u128>(),
+				)
+			};
+			let weighted = |price: FixedU128, from: BlockNumberFor<T>, to: BlockNumberFor<T>| {
+				let blocks = to.saturating_sub(from.max(start)).saturated_into::<u128>();
+				price.checked_mul(&FixedU128::checked_from_integer(blocks)?)
+			};
+
+			let mut sum = FixedU128::zero();
+			for pair in observations.windows(2) {
+				if let [previous, next] = pair {
+					if next.block <= start {
+						continue
+					}
+					let price = price_of(next.reserve1, next.reserve2)?;
+					sum = sum.checked_add(&weighted(price, previous.block, next.block)?)?;
+				}
+			}
+			// Reserves changed in the current block are not in effect yet.
+			let last = observations.last()?;
+			if last.block < now {
+				let pool_account = T::PoolLocator::address(&pool_id).ok()?;
+				let (first, second) = if ordered { (asset1, asset2) } else { (asset2, asset1) };
+				let price = price_of(
+					Self::get_balance(&pool_account, first.clone()),
+					Self::get_balance(&pool_account, second.clone()),
+				)?;
+				sum = sum.checked_add(&weighted(price, last.block, now)?)?;
+			}
+
+			sum.checked_div(&FixedU128::checked_from_integer(window.saturated_into::<u128>())?)
+		}
+
+		/// Records the reserves of the pool of `asset1` and `asset2`, if they haven't been recorded
+		/// in the current block yet.
+		///
+		/// Must be called before changing the reserves of a pool.
+		fn observe_price(asset1: &T::AssetKind, asset2: &T::AssetKind) {
+			if T::MaxPriceObservations::get().is_zero() {
+				return
+			}
+			let Ok(pool_id) = T::PoolLocator::pool_id(asset1, asset2) else { return };
+			let Ok(pool_account) = T::PoolLocator::address(&pool_id) else { return };
+			let now = frame_system::Pallet::<T>::block_number();
+
+			PriceObservations::<T>::mutate(&pool_id, |observations| {
+				if observations.last().is_some_and(|o| o.block == now) {
+					return
+				}
+				let (first, second) = if Self::is_ordered(asset1, asset2) {
+					(asset1, asset2)
+				} else {
+					(asset2, asset1)
+				};
+				let observation = PriceObservation {
+					block: now,
+					reserve1: Self::get_balance(&pool_account, first.clone()),
+					reserve2: Self::get_balance(&pool_account, second.clone()),
+				};
+				if observations.len() >= T::MaxPriceObservations::get() as usize {
+					observations.remove(0);
+				}
+				let _ = observations.try_push(observation);
+			});
+		}
+
+		/// Whether `asset1` comes first in the [`PriceObservation`]s of its pool with `asset2`.
+		fn is_ordered(asset1: &T::AssetKind, asset2: &T::AssetKind) -> bool {
+			asset1.encode() <= asset2.encode()
+		}
+
 		/// Calculates the optimal amount from the reserves.
 		pub fn quote(
 			amount: &T::Balance,
@@ -1322,6 +1504,37 @@ sp_api::decl_runtime_apis! {
 
 		/// Returns the size of the liquidity pool for the given asset pair.
 		fn get_reserves(asset1: AssetId, asset2: AssetId) -> Option<(Balance, Balance)>;
+
+		/// Provides a multi-hop quote for [`Pallet::swap_exact_tokens_for_tokens`] along `path`.
+		///
+		/// Returns the expected amount out and the `amount_out_min` allowing for `slippage`.
+		#[api_version(2)]
+		fn quote_exact_tokens_for_tokens_with_slippage(
+			path: Vec<AssetId>,
+			amount_in: Balance,
+			slippage: Permill,
+		) -> Option<(Balance, Balance)>;
+
+		/// Provides a multi-hop quote for [`Pallet::swap_tokens_for_exact_tokens`] along `path`.
+		///
+		/// Returns the expected amount in and the `amount_in_max` allowing for `slippage`.
+		#[api_version(2)]
+		fn quote_tokens_for_exact_tokens_with_slippage(
+			path: Vec<AssetId>,
+			amount_out: Balance,
+			slippage: Permill,
+		) -> Option<(Balance, Balance)>;
+
+		/// Quotes `amount` of `path[0]` in `path[last]` at the time-weighted average prices over
+		/// the last `window` blocks.
+		///
+		/// Unlike the spot price quotes, the result can't be moved by trades within a block.
+		#[api_version(2)]
+		fn quote_time_weighted_price(
+			path: Vec<AssetId>,
+			amount: Balance,
+			window: u32,
+		) -> Option<Balance>;
 	}
 }
 
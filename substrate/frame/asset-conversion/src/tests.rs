@@ -24,10 +24,11 @@ use frame_support::{
 		fungible::{Inspect as FungibleInspect, NativeOrWithId},
 		fungibles,
 		fungibles::{Inspect, InspectEnumerable},
-		Get,
+		tokens::ConversionFromAssetBalance,
+		ConstU64, Get,
 	},
 };
-use sp_arithmetic::Permill;
+use sp_arithmetic::{FixedPointNumber, FixedU128, Permill};
 use sp_runtime::{DispatchError, TokenError};
 
 fn events() -> Vec<Event<Test>> {
@@ -2493,3 +2494,227 @@ fn swap_credit_invalid_path() {
 		assert_eq!(error, (expected_credit_in, Error::<Test>::InvalidPath.into()));
 	});
 }
+
+fn create_native_pool_with_liquidity(user: u128, token_2: NativeOrWithId<u32>) {
+	create_tokens(user, vec![token_2.clone()]);
+	assert_ok!(AssetConversion::create_pool(
+		RuntimeOrigin::signed(user),
+		Box::new(NativeOrWithId::Native),
+		Box::new(token_2.clone())
+	));
+	assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 100000));
+	assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user, 1000));
+	assert_ok!(AssetConversion::add_liquidity(
+		RuntimeOrigin::signed(user),
+		Box::new(NativeOrWithId::Native),
+		Box::new(token_2),
+		10000,
+		200,
+		1,
+		1,
+		user,
+	));
+}
+
+#[test]
+fn time_weighted_price_ignores_changes_within_the_block() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		create_native_pool_with_liquidity(user, token_2.clone());
+
+		// not enough history yet
+		System::set_block_number(5);
+		assert_eq!(AssetConversion::time_weighted_price(&token_2, &token_1, 10), None);
+
+		System::set_block_number(11);
+		assert_eq!(
+			AssetConversion::time_weighted_price(&token_2, &token_1, 10),
+			Some(FixedU128::from_u32(50))
+		);
+		assert_eq!(
+			AssetConversion::time_weighted_price(&token_1, &token_2, 10),
+			Some(FixedU128::from_rational(1, 50))
+		);
+
+		// moving the spot price doesn't affect the average within the same block
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(user),
+			bvec![token_2.clone(), token_1.clone()],
+			200,
+			1,
+			user,
+			false,
+		));
+		assert_eq!(
+			AssetConversion::get_reserves(token_1.clone(), token_2.clone()),
+			Ok((5008, 400))
+		);
+		assert_eq!(
+			AssetConversion::time_weighted_price(&token_2, &token_1, 10),
+			Some(FixedU128::from_u32(50))
+		);
+		assert_eq!(
+			AssetConversion::quote_time_weighted_price(
+				vec![token_2.clone(), token_1.clone()],
+				2,
+				10
+			),
+			Some(100)
+		);
+
+		// from the next block on the new price is weighted in
+		System::set_block_number(16);
+		assert_eq!(
+			AssetConversion::time_weighted_price(&token_2, &token_1, 10),
+			Some(FixedU128::from_rational(3126, 100))
+		);
+		assert_eq!(
+			TimeWeightedPriceConverter::<Test, Native, ConstU64<10>>::from_asset_balance(
+				100,
+				token_2.clone()
+			),
+			Ok(3126)
+		);
+		assert_eq!(
+			TimeWeightedPriceConverter::<Test, Native, ConstU64<10>>::from_asset_balance(
+				100, token_1
+			),
+			Ok(100)
+		);
+		// the window isn't covered by the observations
+		assert_eq!(
+			TimeWeightedPriceConverter::<Test, Native, ConstU64<20>>::from_asset_balance(
+				100, token_2
+			),
+			Err(())
+		);
+	});
+}
+
+#[test]
+fn price_observations_are_bounded() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		create_native_pool_with_liquidity(user, token_2.clone());
+		let pool_id = <Test as Config>::PoolLocator::pool_id(&token_1, &token_2).unwrap();
+
+		let max_observations: u32 = <Test as Config>::MaxPriceObservations::get();
+		for block in 2..=u64::from(max_observations) + 2 {
+			System::set_block_number(block);
+			// only the first change in a block is observed
+			for _ in 0..2 {
+				assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+					RuntimeOrigin::signed(user),
+					bvec![token_1.clone(), token_2.clone()],
+					1000,
+					1,
+					user,
+					false,
+				));
+			}
+		}
+
+		let observations = PriceObservations::<Test>::get(&pool_id);
+		assert_eq!(observations.len() as u32, max_observations);
+		assert_eq!(observations.first().map(|o| o.block), Some(3));
+		assert_eq!(observations.last().map(|o| o.block), Some(u64::from(max_observations) + 2));
+	});
+}
+
+#[test]
+fn can_quote_with_slippage() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		let token_3 = NativeOrWithId::WithId(3);
+		create_native_pool_with_liquidity(user, token_2.clone());
+
+		create_tokens(user, vec![token_3.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_3.clone())
+		));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 3, user, 1000));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_3.clone()),
+			10000,
+			500,
+			1,
+			1,
+			user,
+		));
+
+		let slippage = Permill::from_percent(10);
+		let path = vec![token_2.clone(), token_1.clone(), token_3.clone()];
+
+		let (amount_out, amount_out_min) =
+			AssetConversion::quote_exact_tokens_for_tokens_with_slippage(
+				path.clone(),
+				10,
+				slippage,
+			)
+			.unwrap();
+		let hop = AssetConversion::quote_price_exact_tokens_for_tokens(
+			token_2.clone(),
+			token_1.clone(),
+			10,
+			true,
+		)
+		.unwrap();
+		assert_eq!(
+			Some(amount_out),
+			AssetConversion::quote_price_exact_tokens_for_tokens(
+				token_1.clone(),
+				token_3.clone(),
+				hop,
+				true
+			)
+		);
+		assert_eq!(amount_out_min, amount_out - slippage * amount_out);
+
+		let (amount_in, amount_in_max) =
+			AssetConversion::quote_tokens_for_exact_tokens_with_slippage(
+				path.clone(),
+				10,
+				slippage,
+			)
+			.unwrap();
+		assert_eq!(amount_in_max, amount_in + slippage * amount_in);
+
+		// the quotes hold when executed
+		assert_ok!(AssetConversion::swap_tokens_for_exact_tokens(
+			RuntimeOrigin::signed(user),
+			path.iter().cloned().map(Box::new).collect(),
+			10,
+			amount_in_max,
+			user,
+			false,
+		));
+
+		// invalid paths can't be quoted
+		assert_eq!(
+			AssetConversion::quote_exact_tokens_for_tokens_with_slippage(
+				vec![token_2.clone()],
+				10,
+				slippage
+			),
+			None
+		);
+		assert_eq!(
+			AssetConversion::quote_tokens_for_exact_tokens_with_slippage(
+				vec![token_2.clone(), token_1.clone(), token_2],
+				10,
+				slippage
+			),
+			None
+		);
+	});
+}
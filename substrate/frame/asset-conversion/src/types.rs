@@ -18,6 +18,8 @@
 use super::*;
 use codec::{Decode, Encode, MaxEncodedLen};
 use core::marker::PhantomData;
+use frame_support::traits::tokens::ConversionFromAssetBalance;
+use frame_system::pallet_prelude::BlockNumberFor;
 use scale_info::TypeInfo;
 use sp_runtime::traits::TryConvert;
 
@@ -41,6 +43,21 @@ pub struct PoolInfo<PoolAssetId> {
 	pub lp_token: PoolAssetId,
 }
 
+/// Reserves of a pool observed before its first change in a block.
+///
+/// The reserves are ordered by the encoding of the pool's assets, the asset with the smaller
+/// encoding being first. They remained unchanged from the block of the previous observation until
+/// `block`.
+#[derive(Decode, Encode, Clone, Debug, PartialEq, Eq, MaxEncodedLen, TypeInfo)]
+pub struct PriceObservation<BlockNumber, Balance> {
+	/// The block in which the observation was made.
+	pub block: BlockNumber,
+	/// The reserve of the asset with the smaller encoding.
+	pub reserve1: Balance,
+	/// The reserve of the asset with the larger encoding.
+	pub reserve2: Balance,
+}
+
 /// Provides means to resolve the `PoolId` and `AccountId` from a pair of assets.
 ///
 /// Resulting `PoolId` remains consistent whether the asset pair is presented as (asset1, asset2)
@@ -169,3 +186,51 @@ where
 			.using_encoded(|e| Decode::decode(&mut TrailingZeroInput::new(e)).map_err(|_| id))
 	}
 }
+
+/// Converts balances of an asset to balances of `Target` at the time-weighted average price over
+/// the last `Window` blocks.
+///
+/// Unlike the spot price, the conversion can't be influenced by trading against the pool within a
+/// block. The conversion fails if the pool lacks the price observations to cover the window.
+pub struct TimeWeightedPriceConverter<T, Target, Window>(PhantomData<(T, Target, Window)>);
+impl<T, Target, Window> ConversionFromAssetBalance<T::Balance, T::AssetKind, T::Balance>
+	for TimeWeightedPriceConverter<T, Target, Window>
+where
+	T: Config,
+	Target: Get<T::AssetKind>,
+	Window: Get<BlockNumberFor<T>>,
+{
+	type Error = ();
+
+	fn from_asset_balance(
+		balance: T::Balance,
+		asset_id: T::AssetKind,
+	) -> Result<T::Balance, Self::Error> {
+		let target = Target::get();
+		if asset_id == target {
+			return Ok(balance)
+		}
+		Pallet::<T>::quote_time_weighted_price(
+			alloc::vec![asset_id, target],
+			balance,
+			Window::get(),
+		)
+		.ok_or(())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_successful(asset_id: T::AssetKind) {
+		let Ok(pool_id) = T::PoolLocator::pool_id(&asset_id, &Target::get()) else { return };
+		let window = Window::get();
+		if frame_system::Pallet::<T>::block_number() < window {
+			frame_system::Pallet::<T>::set_block_number(window);
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		let observation =
+			|block| PriceObservation { block, reserve1: One::one(), reserve2: One::one() };
+		let observations = alloc::vec![observation(now.saturating_sub(window)), observation(now)];
+		if let Ok(observations) = observations.try_into() {
+			PriceObservations::<T>::insert(pool_id, observations);
+		}
+	}
+}
@@ -118,14 +118,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: `PoolAssets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `PoolAssets::Account` (r:2 w:2)
 	/// Proof: `PoolAssets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:1 w:1)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	fn add_liquidity() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1090`
 		//  Estimated: `11426`
 		// Minimum execution time: 138_751_000 picoseconds.
 		Weight::from_parts(141_390_000, 11426)
-			.saturating_add(T::DbWeight::get().reads(11_u64))
-			.saturating_add(T::DbWeight::get().writes(10_u64))
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
 	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(30), added: 2505, mode: `MaxEncodedLen`)
@@ -137,48 +139,54 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: `PoolAssets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `PoolAssets::Account` (r:1 w:1)
 	/// Proof: `PoolAssets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:1 w:1)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	fn remove_liquidity() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1233`
 		//  Estimated: `11426`
 		// Minimum execution time: 124_722_000 picoseconds.
 		Weight::from_parts(128_644_000, 11426)
-			.saturating_add(T::DbWeight::get().reads(9_u64))
-			.saturating_add(T::DbWeight::get().writes(8_u64))
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
 	}
 	/// Storage: `Assets::Asset` (r:4 w:4)
 	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::Account` (r:8 w:8)
 	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:3 w:3)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	/// The range of component `n` is `[2, 4]`.
 	fn swap_exact_tokens_for_tokens(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + n * (419 ±0)`
-		//  Estimated: `990 + n * (5218 ±0)`
-		// Minimum execution time: 88_884_000 picoseconds.
+		//  Measured:  `0 + n * (2751 ±0)`
+		//  Estimated: `990 + n * (10025 ±0)`
+		// Minimum execution time: 97_112_000 picoseconds.
 		Weight::from_parts(91_036_000, 990)
 			// Standard Error: 337_841
-			.saturating_add(Weight::from_parts(11_478_919, 0).saturating_mul(n.into()))
-			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
-			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
-			.saturating_add(Weight::from_parts(0, 5218).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(19_205_347, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10025).saturating_mul(n.into()))
 	}
 	/// Storage: `Assets::Asset` (r:4 w:4)
 	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::Account` (r:8 w:8)
 	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:3 w:3)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	/// The range of component `n` is `[2, 4]`.
 	fn swap_tokens_for_exact_tokens(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + n * (419 ±0)`
-		//  Estimated: `990 + n * (5218 ±0)`
-		// Minimum execution time: 89_080_000 picoseconds.
+		//  Measured:  `0 + n * (2751 ±0)`
+		//  Estimated: `990 + n * (10025 ±0)`
+		// Minimum execution time: 97_403_000 picoseconds.
 		Weight::from_parts(90_913_000, 990)
 			// Standard Error: 340_609
-			.saturating_add(Weight::from_parts(11_562_623, 0).saturating_mul(n.into()))
-			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
-			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
-			.saturating_add(Weight::from_parts(0, 5218).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(19_317_082, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10025).saturating_mul(n.into()))
 	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(30), added: 2505, mode: `MaxEncodedLen`)
@@ -243,14 +251,16 @@ impl WeightInfo for () {
 	/// Proof: `PoolAssets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `PoolAssets::Account` (r:2 w:2)
 	/// Proof: `PoolAssets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:1 w:1)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	fn add_liquidity() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1090`
 		//  Estimated: `11426`
 		// Minimum execution time: 138_751_000 picoseconds.
 		Weight::from_parts(141_390_000, 11426)
-			.saturating_add(RocksDbWeight::get().reads(11_u64))
-			.saturating_add(RocksDbWeight::get().writes(10_u64))
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
 	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(30), added: 2505, mode: `MaxEncodedLen`)
@@ -262,48 +272,54 @@ impl WeightInfo for () {
 	/// Proof: `PoolAssets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `PoolAssets::Account` (r:1 w:1)
 	/// Proof: `PoolAssets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:1 w:1)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	fn remove_liquidity() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1233`
 		//  Estimated: `11426`
 		// Minimum execution time: 124_722_000 picoseconds.
 		Weight::from_parts(128_644_000, 11426)
-			.saturating_add(RocksDbWeight::get().reads(9_u64))
-			.saturating_add(RocksDbWeight::get().writes(8_u64))
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
 	}
 	/// Storage: `Assets::Asset` (r:4 w:4)
 	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::Account` (r:8 w:8)
 	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:3 w:3)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	/// The range of component `n` is `[2, 4]`.
 	fn swap_exact_tokens_for_tokens(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + n * (419 ±0)`
-		//  Estimated: `990 + n * (5218 ±0)`
-		// Minimum execution time: 88_884_000 picoseconds.
+		//  Measured:  `0 + n * (2751 ±0)`
+		//  Estimated: `990 + n * (10025 ±0)`
+		// Minimum execution time: 97_112_000 picoseconds.
 		Weight::from_parts(91_036_000, 990)
 			// Standard Error: 337_841
-			.saturating_add(Weight::from_parts(11_478_919, 0).saturating_mul(n.into()))
-			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(n.into())))
-			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
-			.saturating_add(Weight::from_parts(0, 5218).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(19_205_347, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10025).saturating_mul(n.into()))
 	}
 	/// Storage: `Assets::Asset` (r:4 w:4)
 	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::Account` (r:8 w:8)
 	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `AssetConversion::PriceObservations` (r:3 w:3)
+	/// Proof: `AssetConversion::PriceObservations` (`max_values`: None, `max_size`: Some(2332), added: 4807, mode: `MaxEncodedLen`)
 	/// The range of component `n` is `[2, 4]`.
 	fn swap_tokens_for_exact_tokens(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + n * (419 ±0)`
-		//  Estimated: `990 + n * (5218 ±0)`
-		// Minimum execution time: 89_080_000 picoseconds.
+		//  Measured:  `0 + n * (2751 ±0)`
+		//  Estimated: `990 + n * (10025 ±0)`
+		// Minimum execution time: 97_403_000 picoseconds.
 		Weight::from_parts(90_913_000, 990)
 			// Standard Error: 340_609
-			.saturating_add(Weight::from_parts(11_562_623, 0).saturating_mul(n.into()))
-			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(n.into())))
-			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
-			.saturating_add(Weight::from_parts(0, 5218).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(19_317_082, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 10025).saturating_mul(n.into()))
 	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(30), added: 2505, mode: `MaxEncodedLen`)
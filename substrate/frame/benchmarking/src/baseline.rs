@@ -108,6 +108,56 @@ benchmarks! {
 	);
 }
 
+/// Baseline of the verification of the hybrid sr25519 and ML-DSA-65 signatures, which are only
+/// available with the `dilithium-experimental` feature.
+#[cfg(feature = "dilithium-experimental")]
+pub mod dilithium {
+	use super::*;
+	use sp_core::paired_crypto::sr25519_dilithium;
+	use sp_runtime::{
+		traits::{IdentifyAccount, Verify},
+		MultiSignature, MultiSigner,
+	};
+
+	pub struct Pallet<T: Config>(System<T>);
+
+	benchmarks! {
+		sr25519_dilithium_verification {
+			let i in 0 .. 100;
+
+			let sr25519_public = sp_io::crypto::sr25519_generate(crypto::TEST_KEY_TYPE_ID, None);
+			let dilithium_public =
+				sp_io::crypto::dilithium_generate(crypto::TEST_KEY_TYPE_ID, None);
+			let public = sr25519_dilithium::Public::from_parts(&sr25519_public, &dilithium_public);
+			let account = MultiSigner::from(public).into_account();
+
+			let sigs_count: u8 = i.try_into().unwrap();
+			let msg_and_sigs: Vec<_> = (0..sigs_count).map(|j| {
+				let msg = vec![j, j];
+				let sr25519_sig =
+					sp_io::crypto::sr25519_sign(crypto::TEST_KEY_TYPE_ID, &sr25519_public, &msg)
+						.unwrap();
+				let dilithium_sig =
+					sp_io::crypto::dilithium_sign(crypto::TEST_KEY_TYPE_ID, &dilithium_public, &msg)
+						.unwrap();
+				let sig = sr25519_dilithium::Signature::from_parts(&sr25519_sig, &dilithium_sig);
+				(msg, MultiSignature::Sr25519Dilithium(public, sig))
+			})
+			.collect();
+		}: {
+			msg_and_sigs.iter().for_each(|(msg, sig)| {
+				assert!(sig.verify(&msg[..], &account));
+			});
+		}
+
+		impl_benchmark_test_suite!(
+			Pallet,
+			super::mock::new_test_ext(),
+			super::mock::Test,
+		);
+	}
+}
+
 #[cfg(test)]
 pub mod mock {
 	use frame_support::derive_impl;
@@ -16,11 +16,12 @@
 // limitations under the License.
 
 use super::*;
+use alloc::vec::Vec;
 use frame_support::{
 	pallet_prelude::*,
 	traits::{fungible::Mutate, tokens::Preservation::Expendable, DefensiveResult},
 };
-use sp_arithmetic::traits::{CheckedDiv, Saturating, Zero};
+use sp_arithmetic::traits::{CheckedDiv, SaturatedConversion, Saturating, Zero};
 use sp_runtime::traits::{BlockNumberProvider, Convert};
 use CompletionStatus::{Complete, Partial};
 
@@ -611,4 +612,65 @@ impl<T: Config> Pallet<T> {
 		let now = RCBlockNumberProviderOf::<T::Coretime>::current_block_number();
 		Ok(Self::sale_price(&sale, now))
 	}
+
+	/// How the regions which are currently scheduled make use of each core.
+	pub fn region_utilization() -> Result<Vec<RegionUtilization>, DispatchError> {
+		let status = Status::<T>::get().ok_or(Error::<T>::Uninitialized)?;
+
+		Ok((0..status.core_count)
+			.map(|core| {
+				let (mut tasks, mut pool) = (0, 0);
+				for item in Workload::<T>::get(core) {
+					match item.assignment {
+						CoreAssignment::Task(_) => tasks.saturating_accrue(item.mask.count_ones()),
+						CoreAssignment::Pool => pool.saturating_accrue(item.mask.count_ones()),
+						CoreAssignment::Idle => {},
+					}
+				}
+				let idle = (CORE_MASK_BITS as CoreMaskBitCount).saturating_sub(tasks + pool);
+				RegionUtilization { core, tasks, pool, idle }
+			})
+			.collect())
+	}
+
+	/// The renewals which can be made in the ongoing sale or in the one following it, ordered by
+	/// their deadline.
+	///
+	/// This iterates over all `PotentialRenewals` and is therefore only meant to be used
+	/// off-chain.
+	pub fn upcoming_renewals() -> Result<Vec<UpcomingRenewalOf<T>>, DispatchError> {
+		let config = Configuration::<T>::get().ok_or(Error::<T>::Uninitialized)?;
+		let sale = SaleInfo::<T>::get().ok_or(Error::<T>::NoSales)?;
+		let auto_renewals = AutoRenewals::<T>::get();
+		let now = RCBlockNumberProviderOf::<T::Coretime>::current_block_number();
+		let timeslice_period = T::TimeslicePeriod::get();
+
+		let mut renewals = PotentialRenewals::<T>::iter()
+			.filter(|(id, _)| id.when == sale.region_begin || id.when == sale.region_end)
+			.map(|(id, record)| {
+				// The sale rotates, which makes the renewal expire, as soon as the timeslice `when`
+				// is ready to be committed.
+				let deadline = RelayBlockNumberOf::<T>::from(id.when)
+					.saturating_mul(timeslice_period)
+					.saturating_sub(config.advance_notice);
+				UpcomingRenewal {
+					core: id.core,
+					when: id.when,
+					price: record.price,
+					completion: record.completion,
+					open: id.when == sale.region_begin,
+					auto_renew: auto_renewals.iter().any(|r| r.core == id.core),
+					blocks_left: deadline.saturating_sub(now).saturated_into(),
+				}
+			})
+			.collect::<Vec<_>>();
+		renewals.sort_by_key(|r| (r.when, r.core));
+
+		Ok(renewals)
+	}
+
+	/// The outcomes of the most recent sales, oldest first.
+	pub fn price_history() -> Vec<PriceHistoryRecordOf<T>> {
+		PriceHistory::<T>::get().into_inner()
+	}
 }
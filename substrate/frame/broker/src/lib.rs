@@ -130,6 +130,10 @@ pub mod pallet {
 		/// Needed to prevent spam attacks.
 		#[pallet::constant]
 		type MinimumCreditPurchase: Get<BalanceOf<Self>>;
+
+		/// Maximum number of past sales which are kept in the price history.
+		#[pallet::constant]
+		type MaxPriceHistory: Get<u32>;
 	}
 
 	/// The current configuration of this pallet.
@@ -201,6 +205,11 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type RevenueInbox<T> = StorageValue<_, OnDemandRevenueRecordOf<T>, OptionQuery>;
 
+	/// The outcomes of the most recent sales, oldest first.
+	#[pallet::storage]
+	pub type PriceHistory<T: Config> =
+		StorageValue<_, BoundedVec<PriceHistoryRecordOf<T>, T::MaxPriceHistory>, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
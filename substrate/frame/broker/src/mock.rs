@@ -205,6 +205,7 @@ impl crate::Config for Test {
 	type MaxAutoRenewals = ConstU32<3>;
 	type PriceAdapter = CenterTargetPrice<BalanceOf<Self>>;
 	type MinimumCreditPurchase = MinimumCreditPurchase;
+	type MaxPriceHistory = ConstU32<8>;
 }
 
 pub fn advance_to(b: u64) {
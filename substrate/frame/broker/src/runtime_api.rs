@@ -17,6 +17,8 @@
 
 //! Runtime API definition for the FRAME Broker pallet.
 
+use crate::{PriceHistoryRecord, RegionUtilization, UpcomingRenewal};
+use alloc::vec::Vec;
 use codec::Codec;
 use sp_runtime::DispatchError;
 
@@ -27,5 +29,18 @@ sp_api::decl_runtime_apis! {
 	{
 		/// If there is an ongoing sale returns the current price of a core.
 		fn sale_price() -> Result<Balance, DispatchError>;
+
+		/// How the regions which are currently scheduled make use of each core.
+		#[api_version(2)]
+		fn region_utilization() -> Result<Vec<RegionUtilization>, DispatchError>;
+
+		/// The renewals which can be made in the ongoing sale or in the one following it, ordered
+		/// by their deadline.
+		#[api_version(2)]
+		fn upcoming_renewals() -> Result<Vec<UpcomingRenewal<Balance>>, DispatchError>;
+
+		/// The outcomes of the most recent sales, oldest first.
+		#[api_version(2)]
+		fn price_history() -> Vec<PriceHistoryRecord<Balance>>;
 	}
 }
@@ -2192,3 +2192,122 @@ fn force_reserve_works() {
 		assert_eq!(Workplan::<Test>::get((10, 0)), Some(system_workload.clone()));
 	});
 }
+
+#[test]
+fn region_utilization_works() {
+	TestExt::new().endow(1, 1000).execute_with(|| {
+		assert_noop!(Broker::region_utilization(), Error::<Test>::Uninitialized);
+		assert_ok!(Broker::do_start_sales(100, 1));
+		assert_eq!(
+			Broker::region_utilization(),
+			Ok(vec![RegionUtilization { core: 0, tasks: 0, pool: 0, idle: 80 }])
+		);
+
+		advance_to(2);
+		let region = Broker::do_purchase(1, u64::max_value()).unwrap();
+		let (region1, region2) =
+			Broker::do_interlace(region, None, CoreMask::from_chunk(0, 40)).unwrap();
+		let (region2, _region3) =
+			Broker::do_interlace(region2, None, CoreMask::from_chunk(40, 60)).unwrap();
+		assert_ok!(Broker::do_assign(region1, None, 1001, Provisional));
+		assert_ok!(Broker::do_pool(region2, None, 1, Provisional));
+
+		// Nothing changes until the regions begin.
+		advance_to(5);
+		assert_eq!(
+			Broker::region_utilization(),
+			Ok(vec![RegionUtilization { core: 0, tasks: 0, pool: 0, idle: 80 }])
+		);
+		advance_to(6);
+		assert_eq!(
+			Broker::region_utilization(),
+			Ok(vec![RegionUtilization { core: 0, tasks: 40, pool: 20, idle: 20 }])
+		);
+	});
+}
+
+#[test]
+fn upcoming_renewals_works() {
+	TestExt::new().endow(1, 100_000).execute_with(|| {
+		assert_noop!(Broker::upcoming_renewals(), Error::<Test>::NoSales);
+		assert_ok!(Broker::do_start_sales(100, 1));
+		advance_to(2);
+		assert_eq!(Broker::upcoming_renewals(), Ok(vec![]));
+
+		let region = Broker::do_purchase(1, u64::max_value()).unwrap();
+		assert_ok!(Broker::do_assign(region, None, 1001, Final));
+		let workload = Schedule::truncate_from(vec![ScheduleItem {
+			mask: CoreMask::complete(),
+			assignment: Task(1001),
+		}]);
+
+		// Renewable in the next sale, which rotates at block 7 * 2 - 2.
+		assert_eq!(
+			Broker::upcoming_renewals(),
+			Ok(vec![UpcomingRenewal {
+				core: 0,
+				when: 7,
+				price: 100,
+				completion: CompletionStatus::Complete(workload.clone()),
+				open: false,
+				auto_renew: false,
+				blocks_left: 10,
+			}])
+		);
+
+		advance_to(6);
+		let renewals = Broker::upcoming_renewals().unwrap();
+		assert_eq!(renewals.len(), 1);
+		assert!(renewals[0].open);
+		assert_eq!(renewals[0].blocks_left, 6);
+
+		// Once renewed, the workload becomes renewable in the following sale.
+		let core = Broker::do_renew(1, region.core).unwrap();
+		let renewals = Broker::upcoming_renewals().unwrap();
+		assert_eq!(renewals.len(), 1);
+		assert_eq!((renewals[0].core, renewals[0].when), (core, 10));
+		assert!(!renewals[0].open);
+		assert_eq!(renewals[0].blocks_left, 12);
+
+		// A lapsed renewal is not upcoming anymore.
+		advance_sale_period();
+		advance_sale_period();
+		assert_eq!(Broker::upcoming_renewals(), Ok(vec![]));
+	});
+}
+
+#[test]
+fn price_history_is_recorded_and_bounded() {
+	TestExt::new().endow(1, 100_000).execute_with(|| {
+		assert_ok!(Broker::do_start_sales(100, 1));
+		// The bootstrapping sale is not recorded.
+		assert!(Broker::price_history().is_empty());
+
+		advance_to(2);
+		assert_ok!(Broker::do_purchase(1, u64::max_value()));
+		let sale = SaleInfo::<Test>::get().unwrap();
+		advance_sale_period();
+		assert_eq!(
+			Broker::price_history(),
+			vec![PriceHistoryRecord {
+				region_begin: 4,
+				region_end: 7,
+				end_price: sale.end_price,
+				sellout_price: sale.sellout_price,
+				cores_offered: 1,
+				cores_sold: 1,
+			}]
+		);
+
+		for _ in 0..10 {
+			advance_sale_period();
+		}
+		let history = Broker::price_history();
+		let max = <Test as Config>::MaxPriceHistory::get();
+		assert_eq!(history.len(), max as usize);
+		// The oldest records have been dropped.
+		let last_begin = SaleInfo::<Test>::get().unwrap().region_begin - 3;
+		let begins = history.iter().map(|r| r.region_begin).collect::<Vec<_>>();
+		assert_eq!(begins, (0..max).map(|i| last_begin - 3 * (max - 1 - i)).collect::<Vec<_>>());
+	});
+}
@@ -54,6 +54,8 @@ impl<T: Config> Pallet<T> {
 			if let Some(sale) = SaleInfo::<T>::get() {
 				if commit_timeslice >= sale.region_begin {
 					// Sale can be rotated.
+					Self::record_price_history(&sale);
+					meter.consume(T::DbWeight::get().reads_writes(1, 1));
 					Self::rotate_sale(sale, &config, &status);
 					meter.consume(T::WeightInfo::rotate_sale(status.core_count.into()));
 				}
@@ -280,6 +282,21 @@ impl<T: Config> Pallet<T> {
 		Some(())
 	}
 
+	/// Keep the outcome of a finished sale in the `PriceHistory`, dropping the oldest record if
+	/// necessary.
+	pub(crate) fn record_price_history(sale: &SaleInfoRecordOf<T>) {
+		let max = T::MaxPriceHistory::get() as usize;
+		if max == 0 {
+			return
+		}
+		PriceHistory::<T>::mutate(|history| {
+			if history.len() >= max {
+				history.remove(0);
+			}
+			let _ = history.try_push(sale.into());
+		});
+	}
+
 	pub(crate) fn process_pool(when: Timeslice, status: &mut StatusRecord) {
 		let pool_io = InstaPoolIo::<T>::take(when);
 		status.private_pool_size = (status.private_pool_size as SignedCoreMaskBitCount)
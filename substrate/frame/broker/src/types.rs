@@ -376,3 +376,73 @@ pub struct AutoRenewalRecord {
 	/// tasks to ensure that the renewal process does not begin until the lease expires.
 	pub next_renewal: Timeslice,
 }
+
+/// The outcome of a past Bulk Coretime sale, kept to provide a price history.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct PriceHistoryRecord<Balance> {
+	/// The first timeslice of the Regions which were sold in the sale.
+	pub region_begin: Timeslice,
+	/// The timeslice on which the Regions which were sold in the sale terminated.
+	pub region_end: Timeslice,
+	/// The price of Bulk Coretime after the Leadin Period.
+	pub end_price: Balance,
+	/// The price at which cores have been sold out, or `None` if no core was offered.
+	pub sellout_price: Option<Balance>,
+	/// Number of cores which were offered for sale.
+	pub cores_offered: CoreIndex,
+	/// Number of cores which were sold.
+	pub cores_sold: CoreIndex,
+}
+pub type PriceHistoryRecordOf<T> = PriceHistoryRecord<BalanceOf<T>>;
+
+impl<Balance: Clone, RelayBlockNumber> From<&SaleInfoRecord<Balance, RelayBlockNumber>>
+	for PriceHistoryRecord<Balance>
+{
+	fn from(sale: &SaleInfoRecord<Balance, RelayBlockNumber>) -> Self {
+		Self {
+			region_begin: sale.region_begin,
+			region_end: sale.region_end,
+			end_price: sale.end_price.clone(),
+			sellout_price: sale.sellout_price.clone(),
+			cores_offered: sale.cores_offered,
+			cores_sold: sale.cores_sold,
+		}
+	}
+}
+
+/// How the regions which are currently scheduled on a core make use of it.
+///
+/// All values are measured in Core Mask Bits and add up to `CORE_MASK_BITS`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct RegionUtilization {
+	/// The core in question.
+	pub core: CoreIndex,
+	/// The parts of the core which are assigned to tasks.
+	pub tasks: CoreMaskBitCount,
+	/// The parts of the core which are assigned to the Instantaneous Coretime Pool.
+	pub pool: CoreMaskBitCount,
+	/// The parts of the core which are idle.
+	pub idle: CoreMaskBitCount,
+}
+
+/// A renewal which can be made in the ongoing sale or in the one following it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct UpcomingRenewal<Balance> {
+	/// The core whose workload may be renewed.
+	pub core: CoreIndex,
+	/// The timeslice at which the renewed workload would begin.
+	pub when: Timeslice,
+	/// The price for which the renewal can be made.
+	pub price: Balance,
+	/// The workload which would be renewed, or if incomplete, the parts of the core which have
+	/// been scheduled.
+	pub completion: CompletionStatus,
+	/// Whether the renewal can be made in the ongoing sale. Otherwise it only becomes possible
+	/// once the next sale has started.
+	pub open: bool,
+	/// Whether auto-renewal is enabled for the core.
+	pub auto_renew: bool,
+	/// The number of Relay-chain blocks left until the renewal can no longer be made.
+	pub blocks_left: u32,
+}
+pub type UpcomingRenewalOf<T> = UpcomingRenewal<BalanceOf<T>>;
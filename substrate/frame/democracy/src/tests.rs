@@ -93,6 +93,7 @@ impl pallet_preimage::Config for Test {
 	type Currency = Balances;
 	type ManagerOrigin = EnsureRoot<u64>;
 	type Consideration = ();
+	type ExpiryPeriod = ConstU64<10>;
 }
 
 impl pallet_scheduler::Config for Test {
@@ -0,0 +1,80 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multi phase election miner
+//!
+//! Helpers for the off-chain miners (e.g. the staking-miner) of
+//! [`pallet_election_provider_multi_phase`]:
+//!
+//! - [`mine_and_check`] mines a solution from a snapshot fetched by the miner and checks its
+//!   feasibility, so that the miner knows the solution is accepted before paying for it.
+//! - [`submission`] decides whether a mined solution is worth submitting, and how much the miner
+//!   is willing to pay for it in deposit and transaction fees.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod submission;
+
+pub use submission::{SubmissionBudget, SubmissionStrategy, TipStrategy};
+
+use alloc::vec::Vec;
+use frame_election_provider_support::NposSolver;
+use pallet_election_provider_multi_phase::{
+	unsigned::{MinerError, MinerVoterOf},
+	ElectionCompute, Miner, MinerConfig, RawSolution, ReadySolutionOf, RoundSnapshot, SolutionOf,
+	SolutionOrSnapshotSize,
+};
+use sp_npos_elections::ElectionScore;
+
+/// Mine a solution from the given snapshot and check its feasibility, returning a [`RawSolution`]
+/// ready to be submitted in `round`, along with the checked solution and the size of the
+/// snapshot.
+///
+/// The solution is checked as a signed one would be on chain, against `minimum_untrusted_score`
+/// if any.
+pub fn mine_and_check<T, S>(
+	voters: Vec<MinerVoterOf<T>>,
+	targets: Vec<T::AccountId>,
+	desired_targets: u32,
+	round: u32,
+	minimum_untrusted_score: Option<ElectionScore>,
+) -> Result<(RawSolution<SolutionOf<T>>, ReadySolutionOf<T>, SolutionOrSnapshotSize), MinerError>
+where
+	T: MinerConfig,
+	S: NposSolver<AccountId = T::AccountId>,
+{
+	let (solution, score, size, _trimming) = Miner::<T>::mine_solution_with_snapshot::<S>(
+		voters.clone(),
+		targets.clone(),
+		desired_targets,
+	)?;
+	let raw_solution = RawSolution { solution, score, round };
+
+	let ready = Miner::<T>::feasibility_check(
+		raw_solution.clone(),
+		ElectionCompute::Signed,
+		desired_targets,
+		RoundSnapshot { voters, targets },
+		round,
+		minimum_untrusted_score,
+	)
+	.map_err(MinerError::Feasibility)?;
+
+	Ok((raw_solution, ready, size))
+}
@@ -0,0 +1,148 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Submission strategies for off-chain miners.
+//!
+//! These helpers let an off-chain miner (e.g. the staking-miner) decide whether a mined solution
+//! is worth submitting given the current best score, and how much it is willing to pay for it in
+//! deposit and transaction fees.
+
+use sp_npos_elections::ElectionScore;
+use sp_runtime::{traits::Saturating, Perbill};
+
+/// When a mined solution should be submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionStrategy {
+	/// Always submit, regardless of the current best score.
+	Always,
+	/// Only submit if the solution is strictly better than the current best score.
+	IfLeading,
+	/// Only submit if the solution is at least the given threshold better than the current best
+	/// score.
+	ClaimBetterThan(Perbill),
+	/// Submit if the solution is no more than the given threshold worse than the current best
+	/// score.
+	ClaimNoWorseThan(Perbill),
+}
+
+impl SubmissionStrategy {
+	/// Whether a solution with score `ours` should be submitted, given the `best` score already
+	/// queued on chain, if any.
+	pub fn should_submit(&self, ours: ElectionScore, best: Option<ElectionScore>) -> bool {
+		let Some(best) = best else { return true };
+
+		match self {
+			Self::Always => true,
+			Self::IfLeading => ours.strict_threshold_better(best, Perbill::zero()),
+			Self::ClaimBetterThan(threshold) => ours.strict_threshold_better(best, *threshold),
+			Self::ClaimNoWorseThan(threshold) => !best.strict_threshold_better(ours, *threshold),
+		}
+	}
+}
+
+/// How much tip to attach to a signed submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipStrategy<Balance> {
+	/// Always attach the same tip.
+	Fixed(Balance),
+	/// Start from `base` and add `per_submission` for every submission already queued, up to
+	/// `max`. This outbids competing miners only when there is competition.
+	EscalateWithQueue {
+		/// The tip when the queue is empty.
+		base: Balance,
+		/// Increase for every queued submission.
+		per_submission: Balance,
+		/// Upper bound of the tip.
+		max: Balance,
+	},
+}
+
+impl<Balance: Saturating + Ord + Copy + From<u32>> TipStrategy<Balance> {
+	/// The tip to attach, given the number of submissions already in the signed queue.
+	pub fn tip(&self, queue_len: u32) -> Balance {
+		match *self {
+			Self::Fixed(tip) => tip,
+			Self::EscalateWithQueue { base, per_submission, max } =>
+				base.saturating_add(per_submission.saturating_mul(queue_len.into())).min(max),
+		}
+	}
+}
+
+/// Upper bounds on what a miner is willing to spend on a single signed submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmissionBudget<Balance> {
+	/// Maximum deposit, see [`pallet_election_provider_multi_phase::Pallet::deposit_for`].
+	pub max_deposit: Balance,
+	/// Maximum transaction fee, including the tip.
+	pub max_fee: Balance,
+}
+
+impl<Balance: PartialOrd> SubmissionBudget<Balance> {
+	/// Whether a submission costing `deposit` and `fee` fits in the budget.
+	pub fn allows(&self, deposit: Balance, fee: Balance) -> bool {
+		deposit <= self.max_deposit && fee <= self.max_fee
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn score(minimal_stake: u128) -> ElectionScore {
+		ElectionScore { minimal_stake, sum_stake: 100, sum_stake_squared: 100 }
+	}
+
+	#[test]
+	fn submission_strategy_works() {
+		// nothing queued yet.
+		assert!(SubmissionStrategy::IfLeading.should_submit(score(10), None));
+
+		assert!(SubmissionStrategy::Always.should_submit(score(10), Some(score(20))));
+
+		assert!(SubmissionStrategy::IfLeading.should_submit(score(21), Some(score(20))));
+		assert!(!SubmissionStrategy::IfLeading.should_submit(score(20), Some(score(20))));
+
+		let ten_percent = Perbill::from_percent(10);
+		assert!(SubmissionStrategy::ClaimBetterThan(ten_percent)
+			.should_submit(score(23), Some(score(20))));
+		assert!(!SubmissionStrategy::ClaimBetterThan(ten_percent)
+			.should_submit(score(21), Some(score(20))));
+
+		assert!(SubmissionStrategy::ClaimNoWorseThan(ten_percent)
+			.should_submit(score(19), Some(score(20))));
+		assert!(!SubmissionStrategy::ClaimNoWorseThan(ten_percent)
+			.should_submit(score(10), Some(score(20))));
+	}
+
+	#[test]
+	fn tip_strategy_works() {
+		assert_eq!(TipStrategy::Fixed(5u64).tip(10), 5);
+
+		let escalate = TipStrategy::EscalateWithQueue { base: 10u64, per_submission: 5, max: 30 };
+		assert_eq!(escalate.tip(0), 10);
+		assert_eq!(escalate.tip(2), 20);
+		assert_eq!(escalate.tip(10), 30);
+	}
+
+	#[test]
+	fn submission_budget_works() {
+		let budget = SubmissionBudget { max_deposit: 100u64, max_fee: 10 };
+		assert!(budget.allows(100, 10));
+		assert!(!budget.allows(101, 10));
+		assert!(!budget.allows(100, 11));
+	}
+}
@@ -161,9 +161,9 @@ use frame_support::{
 	migrations::MultiStepMigrator,
 	pallet_prelude::InvalidTransaction,
 	traits::{
-		BeforeAllRuntimeMigrations, EnsureInherentsAreFirst, ExecuteBlock, OffchainWorker,
-		OnFinalize, OnIdle, OnInitialize, OnPoll, OnRuntimeUpgrade, PostInherents,
-		PostTransactions, PreInherents,
+		BeforeAllRuntimeMigrations, Contains, EnsureInherentsAreFirst, ExecuteBlock,
+		ExtrinsicCall, OffchainWorker, OnFinalize, OnIdle, OnInitialize, OnPoll, OnRuntimeUpgrade,
+		PostInherents, PostTransactions, PreInherents,
 	},
 	weights::{Weight, WeightMeter},
 };
@@ -903,3 +903,47 @@ where
 		)
 	}
 }
+
+impl<
+		System: frame_system::Config + EnsureInherentsAreFirst<Block>,
+		Block: traits::Block<
+			Header = frame_system::pallet_prelude::HeaderFor<System>,
+			Hash = System::Hash,
+		>,
+		Context: Default,
+		UnsignedValidator,
+		AllPalletsWithSystem: OnRuntimeUpgrade
+			+ BeforeAllRuntimeMigrations
+			+ OnInitialize<BlockNumberFor<System>>
+			+ OnIdle<BlockNumberFor<System>>
+			+ OnFinalize<BlockNumberFor<System>>
+			+ OffchainWorker<BlockNumberFor<System>>
+			+ OnPoll<BlockNumberFor<System>>,
+		COnRuntimeUpgrade: OnRuntimeUpgrade,
+	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+where
+	Block::Extrinsic: Checkable<Context> + Codec + ExtrinsicCall<Call = System::RuntimeCall>,
+	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
+	CallOf<Block::Extrinsic, Context>:
+		Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
+	UnsignedValidator: ValidateUnsigned<Call = CallOf<Block::Extrinsic, Context>>,
+{
+	/// Check a given transaction for validity like [`Self::validate_transaction`], but also reject
+	/// it if its call does not pass the `BaseCallFilter` of the runtime.
+	///
+	/// Filtered calls, e.g. calls paused by `pallet-tx-pause` or disallowed while
+	/// `pallet-safe-mode` is entered, would fail once included in a block. Rejecting them here
+	/// keeps them out of the transaction pool, which also drops them on revalidation as soon as
+	/// the filter starts to apply.
+	pub fn validate_transaction_with_call_filter(
+		source: TransactionSource,
+		uxt: Block::Extrinsic,
+		block_hash: Block::Hash,
+	) -> TransactionValidity {
+		if !System::BaseCallFilter::contains(uxt.call()) {
+			return Err(InvalidTransaction::Filtered.into())
+		}
+		Self::validate_transaction(source, uxt, block_hash)
+	}
+}
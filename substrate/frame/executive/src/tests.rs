@@ -36,7 +36,7 @@ use frame_support::{
 	migrations::MultiStepMigrator,
 	pallet_prelude::*,
 	parameter_types,
-	traits::{fungible, ConstU8, Currency, IsInherent, VariantCount, VariantCountOf},
+	traits::{fungible, ConstU8, Contains, Currency, IsInherent, VariantCount, VariantCountOf},
 	weights::{ConstantMultiplier, IdentityFee, RuntimeDbWeight, Weight, WeightMeter, WeightToFee},
 };
 use frame_system::{pallet_prelude::*, ChainContext, LastRuntimeUpgrade, LastRuntimeUpgradeInfo};
@@ -342,6 +342,7 @@ impl frame_system::ExtensionsWeightInfo for MockExtensionsWeights {
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
 impl frame_system::Config for Runtime {
+	type BaseCallFilter = MockedCallFilter;
 	type BlockWeights = BlockWeights;
 	type RuntimeOrigin = RuntimeOrigin;
 	type Nonce = u64;
@@ -569,6 +570,17 @@ impl MultiStepMigrator for MockedModeGetter {
 	}
 }
 
+parameter_types! {
+	pub static BalancesPaused: bool = false;
+}
+
+pub struct MockedCallFilter;
+impl Contains<RuntimeCall> for MockedCallFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		!(BalancesPaused::get() && matches!(call, RuntimeCall::Balances(_)))
+	}
+}
+
 fn tx_ext(nonce: u64, fee: Balance) -> TxExtension {
 	(
 		frame_system::CheckEra::from(Era::Immortal),
@@ -1549,3 +1561,34 @@ fn is_inherent_works() {
 	let ext = UncheckedXt::new_bare(RuntimeCall::Custom2(custom2::Call::allowed_unsigned {}));
 	assert!(!Runtime::is_inherent(&ext), "Unsigned ext are not automatically inherents");
 }
+
+#[test]
+fn validate_transaction_with_call_filter_rejects_filtered_calls() {
+	let xt = UncheckedXt::new_signed(call_transfer(2, 69), 1, 1.into(), tx_ext(0, 0));
+
+	new_test_ext(1).execute_with(|| {
+		assert!(Executive::validate_transaction_with_call_filter(
+			TransactionSource::External,
+			xt.clone(),
+			Default::default(),
+		)
+		.is_ok());
+
+		BalancesPaused::set(true);
+		assert_eq!(
+			Executive::validate_transaction_with_call_filter(
+				TransactionSource::External,
+				xt.clone(),
+				Default::default(),
+			),
+			Err(InvalidTransaction::Filtered.into()),
+		);
+		// The plain validation does not consider the call filter.
+		assert!(Executive::validate_transaction(
+			TransactionSource::External,
+			xt,
+			Default::default(),
+		)
+		.is_ok());
+	});
+}
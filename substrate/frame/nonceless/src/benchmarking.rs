@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for the Nonceless Pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use crate::Pallet as Nonceless;
+use frame_benchmarking::v2::*;
+use frame_support::dispatch::PostDispatchInfo;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{AsTransactionAuthorizedOrigin, DispatchTransaction, One};
+
+#[benchmarks(where
+	T: Send + Sync,
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	<T::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		AsSystemOriginSigner<T::AccountId> + AsTransactionAuthorizedOrigin + Clone,
+)]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn check_tag() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = account("caller", 0, 0);
+		frame_system::Pallet::<T>::inc_providers(&caller);
+		let expires_at = frame_system::Pallet::<T>::block_number() + One::one();
+		let ext = CheckNonceOrTag::<T>::new_tagged(expires_at, [1; 16]);
+		let info = DispatchInfo { call_weight: Weight::zero(), ..Default::default() };
+		let call: T::RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+
+		#[block]
+		{
+			ext.test_run(RawOrigin::Signed(caller.clone()).into(), &call, &info, 0, 0, |_| {
+				Ok(().into())
+			})
+			.unwrap()
+			.unwrap();
+		}
+
+		assert!(UsedTags::<T>::contains_key((expires_at, caller, [1; 16])));
+		Ok(())
+	}
+
+	// Moving past an expiry without used tags.
+	#[benchmark]
+	fn prune_expiry() {
+		let now = frame_system::Pallet::<T>::block_number();
+		NextToPrune::<T>::put(now);
+
+		#[block]
+		{
+			Nonceless::<T>::prune(now, &mut WeightMeter::new());
+		}
+
+		assert_eq!(NextToPrune::<T>::get(), Some(now + One::one()));
+	}
+
+	// Moving past an expiry with a single used tag.
+	#[benchmark]
+	fn prune_tag() {
+		let now = frame_system::Pallet::<T>::block_number();
+		let who: T::AccountId = account("who", 0, 0);
+		UsedTags::<T>::insert((now, &who, [1; 16]), ());
+		NextToPrune::<T>::put(now);
+
+		#[block]
+		{
+			Nonceless::<T>::prune(now, &mut WeightMeter::new());
+		}
+
+		assert!(!UsedTags::<T>::contains_key((now, who, [1; 16])));
+		assert_eq!(NextToPrune::<T>::get(), Some(now + One::one()));
+	}
+
+	impl_benchmark_test_suite!(Nonceless, crate::mock::new_test_ext(), crate::mock::Runtime);
+}
@@ -0,0 +1,360 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Nonceless Pallet
+//!
+//! Lets accounts protect their transactions against replay with an expiring unique tag instead of
+//! the account nonce.
+//!
+//! ## Overview
+//!
+//! The nonce of an account orders its transactions: a transaction is only valid once all
+//! transactions with lower nonces were included. Submitters sending many independent transactions,
+//! e.g. IoT devices or batch jobs, have to coordinate their nonces and a single dropped transaction
+//! holds up all following ones.
+//!
+//! The [`CheckNonceOrTag`] transaction extension replaces [`CheckNonce`] in the pipeline of the
+//! runtime. A transaction either uses the nonce, exactly like with [`CheckNonce`], or a
+//! [`UniqueTag`]: a tag chosen by the submitter, e.g. a random UUID, and the block from which on
+//! the transaction is no longer valid, at most [`MaxLifetime`] blocks ahead. Tagged transactions
+//! don't read or increment the nonce and neither require nor provide any nonce, so they are
+//! independent of each other and of the nonced transactions of the account.
+//!
+//! A tag can be used once per account and expiry: the transaction pool rejects transactions
+//! providing the same tag and the pallet records the used tags in [`UsedTags`] until they expire.
+//! Expired tags are removed in [`on_idle`](Hooks::on_idle), as far as the remaining weight of the
+//! block allows. Lagging behind is harmless, as transactions with expired tags are invalid anyway.
+//!
+//! [`CheckNonce`]: frame_system::CheckNonce
+//! [`MaxLifetime`]: Config::MaxLifetime
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{
+	dispatch::{DispatchInfo, DispatchResult},
+	pallet_prelude::TransactionSource,
+	traits::Get,
+	weights::{Weight, WeightMeter},
+	RuntimeDebugNoBound,
+};
+use frame_system::{extensions::check_nonce, pallet_prelude::BlockNumberFor, CheckNonce};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{
+		AsSystemOriginSigner, DispatchInfoOf, DispatchOriginOf, Dispatchable, Implication,
+		PostDispatchInfoOf, Saturating, TransactionExtension, ValidateResult, Zero,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError, ValidTransaction},
+	RuntimeDebug, SaturatedConversion,
+};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// A tag distinguishing the transactions of an account with the same expiry.
+pub type Tag = [u8; 16];
+
+/// The replay protection of a transaction without nonce.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+)]
+pub struct UniqueTag<BlockNumber> {
+	/// The block from which on the transaction is no longer valid.
+	pub expires_at: BlockNumber,
+	/// The tag, unique among the transactions of the account with the same expiry.
+	pub tag: Tag,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The maximum number of blocks for which a tagged transaction can be valid.
+		///
+		/// Bounds the time for which used tags are stored.
+		#[pallet::constant]
+		type MaxLifetime: Get<BlockNumberFor<Self>>;
+
+		/// The weight information of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The tags used by accounts, by their expiry.
+	#[pallet::storage]
+	pub type UsedTags<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, BlockNumberFor<T>>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Identity, Tag>,
+		),
+		(),
+	>;
+
+	/// The earliest expiry of which the used tags may not be removed yet.
+	#[pallet::storage]
+	pub type NextToPrune<T: Config> = StorageValue<_, BlockNumberFor<T>>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+			let mut meter = WeightMeter::with_limit(limit);
+			Self::prune(now, &mut meter);
+			meter.consumed()
+		}
+
+		fn integrity_test() {
+			assert!(!T::MaxLifetime::get().is_zero(), "MaxLifetime must be greater than 0");
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Remove the tags which expired at or before `now`, as far as `meter` allows.
+	pub(crate) fn prune(now: BlockNumberFor<T>, meter: &mut WeightMeter) {
+		if meter.try_consume(T::DbWeight::get().reads_writes(1, 1)).is_err() {
+			return
+		}
+		// Tags are only used with an expiry after the current block, so there are no tags
+		// expiring before the first block the pallet prunes.
+		let mut expiry = NextToPrune::<T>::get().unwrap_or(now);
+		while expiry <= now {
+			if meter.try_consume(T::WeightInfo::prune_expiry()).is_err() {
+				break
+			}
+			let mut tags = UsedTags::<T>::drain_prefix((expiry,));
+			let drained = loop {
+				if !meter.can_consume(T::WeightInfo::prune_tag()) {
+					break false;
+				}
+				match tags.next() {
+					Some(_) => meter.consume(T::WeightInfo::prune_tag()),
+					None => break true,
+				}
+			};
+			if !drained {
+				break
+			}
+			expiry.saturating_inc();
+		}
+		NextToPrune::<T>::put(expiry);
+	}
+}
+
+/// A [`TransactionExtension`] that protects a transaction against replay, either with the nonce
+/// of the signer or with a [`UniqueTag`].
+///
+/// # Transaction Validity
+///
+/// Like [`CheckNonce`], this extension affects the `requires` and `provides` tags of validity, but
+/// DOES NOT set the `priority` field. Tagged transactions provide the signer, the expiry and the
+/// tag, require nothing and their longevity ends at the expiry.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub enum CheckNonceOrTag<T: Config> {
+	/// Check and increment the nonce of the signer.
+	Nonce(CheckNonce<T>),
+	/// Check that the signer didn't use the tag before, leaving the nonce untouched.
+	Tag(UniqueTag<BlockNumberFor<T>>),
+}
+
+impl<T: Config> CheckNonceOrTag<T> {
+	/// Create a new extension instance, using `tag` instead of the nonce.
+	pub fn new_tagged(expires_at: BlockNumberFor<T>, tag: Tag) -> Self {
+		Self::Tag(UniqueTag { expires_at, tag })
+	}
+}
+
+impl<T: Config> From<CheckNonce<T>> for CheckNonceOrTag<T> {
+	fn from(check_nonce: CheckNonce<T>) -> Self {
+		Self::Nonce(check_nonce)
+	}
+}
+
+impl<T: Config> core::fmt::Debug for CheckNonceOrTag<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		match self {
+			Self::Nonce(check_nonce) => check_nonce.fmt(f),
+			Self::Tag(unique) => write!(f, "CheckNonceOrTag({:?})", unique),
+		}
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+		Ok(())
+	}
+}
+
+/// Operation to perform from `validate` to `prepare` in [`CheckNonceOrTag`] transaction extension.
+#[derive(RuntimeDebugNoBound)]
+pub enum Val<T: Config> {
+	/// The [`CheckNonce`] extension and its operation.
+	Nonce(CheckNonce<T>, check_nonce::Val<T>),
+	/// Account and the tag to record as used.
+	Tag(T::AccountId, UniqueTag<BlockNumberFor<T>>),
+	/// Weight to refund.
+	Refund(Weight),
+}
+
+/// Operation to perform from `prepare` to `post_dispatch_details` in [`CheckNonceOrTag`]
+/// transaction extension.
+#[derive(RuntimeDebugNoBound)]
+pub enum Pre {
+	/// Operation of [`CheckNonce`].
+	Nonce(check_nonce::Pre),
+	/// The transaction extension weight should not be refunded.
+	TagChecked,
+	/// The transaction extension weight should be refunded.
+	Refund(Weight),
+}
+
+impl<T: Config> TransactionExtension<T::RuntimeCall> for CheckNonceOrTag<T>
+where
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+	<T::RuntimeCall as Dispatchable>::RuntimeOrigin: AsSystemOriginSigner<T::AccountId> + Clone,
+{
+	const IDENTIFIER: &'static str = "CheckNonceOrTag";
+	type Implicit = ();
+	type Val = Val<T>;
+	type Pre = Pre;
+
+	fn weight(&self, call: &T::RuntimeCall) -> Weight {
+		match self {
+			Self::Nonce(check_nonce) => check_nonce.weight(call),
+			Self::Tag(_) => T::WeightInfo::check_tag(),
+		}
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		self_implicit: Self::Implicit,
+		inherited_implication: &impl Implication,
+		source: TransactionSource,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		let unique = match self {
+			Self::Nonce(check_nonce) => {
+				let (valid, val, origin) = check_nonce.validate(
+					origin,
+					call,
+					info,
+					len,
+					self_implicit,
+					inherited_implication,
+					source,
+				)?;
+				return Ok((valid, Val::Nonce(check_nonce.clone(), val), origin))
+			},
+			Self::Tag(unique) => unique,
+		};
+		let Some(who) = origin.as_system_origin_signer() else {
+			return Ok((Default::default(), Val::Refund(self.weight(call)), origin))
+		};
+		let account = frame_system::Account::<T>::get(who);
+		if account.providers.is_zero() && account.sufficients.is_zero() {
+			// Like the nonce, the used tags are only stored for existing accounts.
+			return Err(InvalidTransaction::Payment.into())
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		if unique.expires_at <= now {
+			return Err(InvalidTransaction::Stale.into())
+		}
+		if unique.expires_at > now.saturating_add(T::MaxLifetime::get()) {
+			return Err(InvalidTransaction::Future.into())
+		}
+		if UsedTags::<T>::contains_key((unique.expires_at, who, unique.tag)) {
+			return Err(InvalidTransaction::Stale.into())
+		}
+
+		let validity = ValidTransaction {
+			priority: 0,
+			requires: vec![],
+			provides: vec![Encode::encode(&(who, unique.expires_at, unique.tag))],
+			longevity: (unique.expires_at - now).saturated_into(),
+			propagate: true,
+		};
+		Ok((validity, Val::Tag(who.clone(), *unique), origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		origin: &DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		match val {
+			Val::Nonce(check_nonce, val) =>
+				check_nonce.prepare(val, origin, call, info, len).map(Pre::Nonce),
+			// The tag was checked in `validate` right before.
+			Val::Tag(who, unique) => {
+				UsedTags::<T>::insert((unique.expires_at, who, unique.tag), ());
+				Ok(Pre::TagChecked)
+			},
+			Val::Refund(weight) => Ok(Pre::Refund(weight)),
+		}
+	}
+
+	fn post_dispatch_details(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<Weight, TransactionValidityError> {
+		match pre {
+			Pre::Nonce(pre) =>
+				CheckNonce::<T>::post_dispatch_details(pre, info, post_info, len, result),
+			Pre::TagChecked => Ok(Weight::zero()),
+			Pre::Refund(weight) => Ok(weight),
+		}
+	}
+}
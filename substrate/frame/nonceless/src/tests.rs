@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::mock::*;
+
+use frame_support::{assert_ok, traits::Hooks};
+use sp_runtime::{traits::DispatchTransaction, transaction_validity::TransactionSource::External};
+
+const TAG: Tag = [7; 16];
+
+fn call() -> RuntimeCall {
+	frame_system::Call::remark { remark: vec![1, 2, 3] }.into()
+}
+
+fn validate(
+	ext: CheckNonceOrTag<Runtime>,
+	who: u64,
+) -> Result<ValidTransaction, TransactionValidityError> {
+	ext.validate_only(Some(who).into(), &call(), &Default::default(), 0, External, 0)
+		.map(|(valid, _, _)| valid)
+}
+
+fn run(ext: CheckNonceOrTag<Runtime>, who: u64) -> Result<(), TransactionValidityError> {
+	ext.test_run(Some(who).into(), &call(), &Default::default(), 0, 0, |_| Ok(().into()))
+		.map(|result| assert_ok!(result))
+}
+
+#[test]
+fn nonce_is_checked_and_incremented() {
+	new_test_ext().execute_with(|| {
+		let ext = CheckNonceOrTag::from(CheckNonce::from(0));
+		assert_eq!(validate(ext.clone(), ALICE).unwrap().provides, vec![(ALICE, 0u64).encode()]);
+		assert_ok!(run(ext.clone(), ALICE));
+		assert_eq!(System::account_nonce(ALICE), 1);
+
+		assert_eq!(run(ext, ALICE), Err(InvalidTransaction::Stale.into()));
+	});
+}
+
+#[test]
+fn tagged_transaction_is_independent_of_nonce() {
+	new_test_ext().execute_with(|| {
+		let valid = validate(CheckNonceOrTag::new_tagged(11, TAG), ALICE).unwrap();
+		assert_eq!(valid.provides, vec![(ALICE, 11u64, TAG).encode()]);
+		assert!(valid.requires.is_empty());
+		assert_eq!(valid.longevity, 10);
+
+		assert_ok!(run(CheckNonceOrTag::new_tagged(11, TAG), ALICE));
+		assert_ok!(run(CheckNonceOrTag::new_tagged(11, [8; 16]), ALICE));
+		assert_ok!(run(CheckNonceOrTag::new_tagged(12, TAG), ALICE));
+		assert_eq!(System::account_nonce(ALICE), 0);
+		assert!(UsedTags::<Runtime>::contains_key((11, ALICE, TAG)));
+
+		// Nonced transactions are unaffected.
+		assert_ok!(run(CheckNonceOrTag::from(CheckNonce::from(0)), ALICE));
+	});
+}
+
+#[test]
+fn tag_cannot_be_reused() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(run(CheckNonceOrTag::new_tagged(11, TAG), ALICE));
+		assert_eq!(
+			validate(CheckNonceOrTag::new_tagged(11, TAG), ALICE),
+			Err(InvalidTransaction::Stale.into())
+		);
+		assert_eq!(
+			run(CheckNonceOrTag::new_tagged(11, TAG), ALICE),
+			Err(InvalidTransaction::Stale.into())
+		);
+	});
+}
+
+#[test]
+fn expiry_is_checked() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(10);
+		assert_eq!(
+			run(CheckNonceOrTag::new_tagged(10, TAG), ALICE),
+			Err(InvalidTransaction::Stale.into())
+		);
+		assert_eq!(
+			run(CheckNonceOrTag::new_tagged(111, TAG), ALICE),
+			Err(InvalidTransaction::Future.into())
+		);
+		assert_ok!(run(CheckNonceOrTag::new_tagged(110, TAG), ALICE));
+	});
+}
+
+#[test]
+fn tagged_transaction_requires_existing_account() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			run(CheckNonceOrTag::new_tagged(11, TAG), 2),
+			Err(InvalidTransaction::Payment.into())
+		);
+	});
+}
+
+#[test]
+fn expired_tags_are_pruned() {
+	new_test_ext().execute_with(|| {
+		for expires_at in 2..=4 {
+			assert_ok!(run(CheckNonceOrTag::new_tagged(expires_at, TAG), ALICE));
+		}
+		Nonceless::on_idle(1, Weight::MAX);
+		assert_eq!(UsedTags::<Runtime>::iter().count(), 3);
+		assert_eq!(NextToPrune::<Runtime>::get(), Some(2));
+
+		// Without weight left, nothing is pruned.
+		Nonceless::on_idle(3, Weight::zero());
+		assert_eq!(UsedTags::<Runtime>::iter().count(), 3);
+
+		assert!(Nonceless::on_idle(3, Weight::MAX).any_gt(Weight::zero()));
+		assert_eq!(UsedTags::<Runtime>::iter_keys().collect::<Vec<_>>(), vec![(4, ALICE, TAG)]);
+		assert_eq!(NextToPrune::<Runtime>::get(), Some(4));
+	});
+}
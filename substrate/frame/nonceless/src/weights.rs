@@ -0,0 +1,110 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholder weights for `pallet_nonceless`.
+//!
+//! THESE WEIGHTS WERE NOT GENERATED BY THE BENCHMARK CLI. They are estimates derived from the
+//! storage accesses of each call and comparable benchmarked calls of other pallets, and must be
+//! replaced by running the benchmarks of the pallet before it is deployed to a production chain:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime=target/production/wbuild/kitchensink-runtime/kitchensink_runtime.wasm \
+//!     --pallet=pallet_nonceless --extrinsic=* --steps=50 --repeat=20 \
+//!     --header=substrate/HEADER-APACHE2 \
+//!     --output=substrate/frame/nonceless/src/weights.rs \
+//!     --template=substrate/.maintain/frame-weight-template.hbs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(dead_code)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_nonceless`.
+pub trait WeightInfo {
+	fn check_tag() -> Weight;
+	fn prune_expiry() -> Weight;
+	fn prune_tag() -> Weight;
+}
+
+/// Weights for `pallet_nonceless` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `System::Account` (r:1 w:0)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Nonceless::UsedTags` (r:1 w:1)
+	/// Proof: `Nonceless::UsedTags` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn check_tag() -> Weight {
+		Weight::from_parts(15_693_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Nonceless::NextToPrune` (r:1 w:1)
+	/// Proof: `Nonceless::NextToPrune` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Nonceless::UsedTags` (r:1 w:0)
+	/// Proof: `Nonceless::UsedTags` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn prune_expiry() -> Weight {
+		Weight::from_parts(4_871_000, 3541)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Nonceless::NextToPrune` (r:1 w:1)
+	/// Proof: `Nonceless::NextToPrune` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Nonceless::UsedTags` (r:2 w:1)
+	/// Proof: `Nonceless::UsedTags` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn prune_tag() -> Weight {
+		Weight::from_parts(10_795_000, 6092)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `System::Account` (r:1 w:0)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Nonceless::UsedTags` (r:1 w:1)
+	/// Proof: `Nonceless::UsedTags` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn check_tag() -> Weight {
+		Weight::from_parts(15_693_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Nonceless::NextToPrune` (r:1 w:1)
+	/// Proof: `Nonceless::NextToPrune` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Nonceless::UsedTags` (r:1 w:0)
+	/// Proof: `Nonceless::UsedTags` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn prune_expiry() -> Weight {
+		Weight::from_parts(4_871_000, 3541)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Nonceless::NextToPrune` (r:1 w:1)
+	/// Proof: `Nonceless::NextToPrune` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Nonceless::UsedTags` (r:2 w:1)
+	/// Proof: `Nonceless::UsedTags` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn prune_tag() -> Weight {
+		Weight::from_parts(10_795_000, 6092)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
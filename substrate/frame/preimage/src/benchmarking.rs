@@ -258,6 +258,24 @@ mod benchmarks {
 		assert_eq!(c, 0);
 	}
 
+	// Expensive clear - will unreserve the noter's deposit.
+	#[benchmark]
+	fn clear_expired_preimage() {
+		let caller = funded_account::<T>();
+		let (preimage, hash) = preimage_and_hash::<T>();
+		let noter: T::AccountId = account("noter", 0, 0);
+		T::Currency::make_free_balance_be(&noter, BalanceOf::<T>::max_value() / 2u32.into());
+		assert_ok!(Pallet::<T>::note_preimage(RawOrigin::Signed(noter).into(), preimage));
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number().saturating_add(T::ExpiryPeriod::get()),
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), hash);
+
+		assert!(!Pallet::<T>::have_preimage(&hash));
+	}
+
 	impl_benchmark_test_suite! {
 		Pallet,
 		mock::new_test_ext(),
@@ -33,6 +33,7 @@ mod benchmarking;
 pub mod migration;
 #[cfg(test)]
 mod mock;
+pub mod runtime_api;
 #[cfg(test)]
 mod tests;
 pub mod weights;
@@ -88,9 +89,25 @@ pub enum RequestStatus<AccountId, Ticket> {
 	Requested { maybe_ticket: Option<(AccountId, Ticket)>, count: u32, maybe_len: Option<u32> },
 }
 
+/// The usage of a preimage, as returned by [`Pallet::preimage_usage`].
+#[derive(Clone, Eq, PartialEq, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct PreimageUsage<AccountId, BlockNumber> {
+	/// The number of outstanding requests for the preimage.
+	pub requests: u32,
+	/// The length of the preimage, or `None` if it has not been noted yet.
+	pub len: Option<u32>,
+	/// The account which holds a deposit for the preimage, if any.
+	pub depositor: Option<AccountId>,
+	/// The block from which on anyone may clear the preimage, or `None` if it is requested or
+	/// was noted before expiry was tracked.
+	pub expires_at: Option<BlockNumber>,
+}
+
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 pub type TicketOf<T> = <T as Config>::Consideration;
+pub type PreimageUsageOf<T> =
+	PreimageUsage<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
 
 /// Maximum size of preimage we can store is 4mb.
 pub const MAX_SIZE: u32 = 4 * 1024 * 1024;
@@ -125,6 +142,11 @@ pub mod pallet {
 
 		/// A means of providing some cost while data is stored on-chain.
 		type Consideration: Consideration<Self::AccountId, Footprint>;
+
+		/// The period after which a preimage which is not requested may be cleared by anyone,
+		/// returning the deposit to the account which noted it.
+		#[pallet::constant]
+		type ExpiryPeriod: Get<BlockNumberFor<Self>>;
 	}
 
 	#[pallet::pallet]
@@ -160,6 +182,8 @@ pub mod pallet {
 		TooMany,
 		/// Too few hashes were requested to be upgraded (i.e. zero).
 		TooFew,
+		/// The preimage has not been unrequested for long enough to be cleared by anyone.
+		NotExpired,
 	}
 
 	/// A reason for this pallet placing a hold on funds.
@@ -184,6 +208,13 @@ pub mod pallet {
 	pub type PreimageFor<T: Config> =
 		StorageMap<_, Identity, (T::Hash, u32), BoundedVec<u8, ConstU32<MAX_SIZE>>>;
 
+	/// The block at which an unrequested preimage was noted or lost its last request.
+	///
+	/// Unrequested preimages without an entry predate this storage item and never expire, they
+	/// can only be cleared by the account which noted them.
+	#[pallet::storage]
+	pub type UnrequestedSince<T: Config> = StorageMap<_, Identity, T::Hash, BlockNumberFor<T>>;
+
 	#[pallet::call(weight = T::WeightInfo)]
 	impl<T: Config> Pallet<T> {
 		/// Register a preimage on-chain.
@@ -255,6 +286,20 @@ pub mod pallet {
 			let pays: Pays = (ratio < Perbill::from_percent(90)).into();
 			Ok(pays.into())
 		}
+
+		/// Clear a preimage which has not been requested for at least `ExpiryPeriod` blocks.
+		///
+		/// May be called by any signed origin. The deposit is returned to the account which noted
+		/// the preimage and the caller pays no fee if the preimage was cleared.
+		#[pallet::call_index(5)]
+		pub fn clear_expired_preimage(
+			origin: OriginFor<T>,
+			hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::do_clear_expired_preimage(&hash)?;
+			Ok(Pays::No.into())
+		}
 	}
 }
 
@@ -340,16 +385,20 @@ impl<T: Config> Pallet<T> {
 				RequestStatus::Requested { maybe_ticket, count, maybe_len: Some(len) },
 			(Some(RequestStatus::Unrequested { .. }), Some(_)) =>
 				return Err(Error::<T>::AlreadyNoted.into()),
-			(Some(RequestStatus::Unrequested { ticket, len }), None) => RequestStatus::Requested {
-				maybe_ticket: Some(ticket),
-				count: 1,
-				maybe_len: Some(len),
+			(Some(RequestStatus::Unrequested { ticket, len }), None) => {
+				UnrequestedSince::<T>::remove(hash);
+				RequestStatus::Requested {
+					maybe_ticket: Some(ticket),
+					count: 1,
+					maybe_len: Some(len),
+				}
 			},
 			(None, None) =>
 				RequestStatus::Requested { maybe_ticket: None, count: 1, maybe_len: Some(len) },
 			(None, Some(depositor)) => {
 				let ticket =
 					T::Consideration::new(depositor, Footprint::from_parts(1, len as usize))?;
+				UnrequestedSince::<T>::insert(hash, frame_system::Pallet::<T>::block_number());
 				RequestStatus::Unrequested { ticket: (depositor.clone(), ticket), len }
 			},
 		};
@@ -376,7 +425,10 @@ impl<T: Config> Pallet<T> {
 					count.saturating_inc();
 					(count, maybe_len, maybe_ticket)
 				},
-				RequestStatus::Unrequested { ticket, len } => (1, Some(len), Some(ticket)),
+				RequestStatus::Unrequested { ticket, len } => {
+					UnrequestedSince::<T>::remove(hash);
+					(1, Some(len), Some(ticket))
+				},
 			});
 		RequestStatusFor::<T>::insert(
 			hash,
@@ -416,6 +468,7 @@ impl<T: Config> Pallet<T> {
 				ensure!(maybe_check_owner.map_or(true, |c| c == owner), Error::<T>::NotAuthorized);
 				let _ = ticket.drop(&owner);
 				RequestStatusFor::<T>::remove(hash);
+				UnrequestedSince::<T>::remove(hash);
 
 				Self::remove(hash, len);
 				Self::deposit_event(Event::Cleared { hash: *hash });
@@ -452,6 +505,10 @@ impl<T: Config> Pallet<T> {
 							hash,
 							RequestStatus::Unrequested { ticket, len },
 						);
+						UnrequestedSince::<T>::insert(
+							hash,
+							frame_system::Pallet::<T>::block_number(),
+						);
 					},
 				}
 			},
@@ -460,6 +517,60 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Clear an unrequested preimage once it expired, returning the deposit to its owner.
+	fn do_clear_expired_preimage(hash: &T::Hash) -> DispatchResult {
+		Self::do_ensure_updated(&hash);
+		match RequestStatusFor::<T>::get(hash).ok_or(Error::<T>::NotNoted)? {
+			RequestStatus::Requested { .. } => return Err(Error::<T>::Requested.into()),
+			RequestStatus::Unrequested { .. } => {
+				let now = frame_system::Pallet::<T>::block_number();
+				ensure!(
+					Self::expires_at(hash).map_or(false, |expires_at| expires_at <= now),
+					Error::<T>::NotExpired
+				);
+			},
+		}
+		Self::do_unnote_preimage(hash, None)
+	}
+
+	/// The block from which on the unrequested preimage `hash` may be cleared by anyone, or
+	/// `None` if it was noted before expiry was tracked.
+	fn expires_at(hash: &T::Hash) -> Option<BlockNumberFor<T>> {
+		UnrequestedSince::<T>::get(hash).map(|since| since.saturating_add(T::ExpiryPeriod::get()))
+	}
+
+	/// The usage of the preimage `hash`, or `None` if it is neither noted nor requested.
+	pub fn preimage_usage(hash: &T::Hash) -> Option<PreimageUsageOf<T>> {
+		Some(match RequestStatusFor::<T>::get(hash)? {
+			RequestStatus::Requested { maybe_ticket, count, maybe_len } => PreimageUsage {
+				requests: count,
+				len: maybe_len,
+				depositor: maybe_ticket.map(|(who, _)| who),
+				expires_at: None,
+			},
+			RequestStatus::Unrequested { ticket: (who, _), len } => PreimageUsage {
+				requests: 0,
+				len: Some(len),
+				depositor: Some(who),
+				expires_at: Self::expires_at(hash),
+			},
+		})
+	}
+
+	/// The hashes of all unrequested preimages which may be cleared by anyone.
+	///
+	/// This iterates over all preimages and is therefore only meant to be used off-chain.
+	pub fn expired_preimages() -> Vec<T::Hash> {
+		let now = frame_system::Pallet::<T>::block_number();
+		RequestStatusFor::<T>::iter()
+			.filter(|(hash, status)| {
+				matches!(status, RequestStatus::Unrequested { .. }) &&
+					Self::expires_at(hash).map_or(false, |expires_at| expires_at <= now)
+			})
+			.map(|(hash, _)| hash)
+			.collect()
+	}
+
 	fn insert(hash: &T::Hash, preimage: Cow<[u8]>) -> Result<(), ()> {
 		BoundedSlice::<u8, ConstU32<MAX_SIZE>>::try_from(preimage.as_ref())
 			.map_err(|_| ())
@@ -75,6 +75,7 @@ impl Config for Test {
 	type Currency = Balances;
 	type ManagerOrigin = EnsureSignedBy<One, u64>;
 	type Consideration = HoldConsideration<u64, Balances, PreimageHoldReason, ConvertDeposit>;
+	type ExpiryPeriod = ConstU64<10>;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
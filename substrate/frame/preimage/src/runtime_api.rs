@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the FRAME Preimage pallet.
+
+use crate::PreimageUsage;
+use alloc::vec::Vec;
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// Introspect the usage of the noted and requested preimages.
+	pub trait PreimageApi<AccountId, Hash, BlockNumber>
+	where
+		AccountId: Codec,
+		Hash: Codec,
+		BlockNumber: Codec,
+	{
+		/// The usage of the preimage `hash`, or `None` if it is neither noted nor requested.
+		fn preimage_usage(hash: Hash) -> Option<PreimageUsage<AccountId, BlockNumber>>;
+
+		/// The hashes of all unrequested preimages which may be cleared by anyone.
+		fn expired_preimages() -> Vec<Hash>;
+	}
+}
@@ -520,3 +520,120 @@ fn ensure_updated_works() {
 		}
 	});
 }
+
+#[test]
+fn clear_expired_preimage_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(2), vec![1]));
+		assert_eq!(Balances::balance_on_hold(&PreimageHoldReason::get(), &2), 3);
+
+		let h = hashed([1]);
+		assert_noop!(
+			Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), hashed([2])),
+			Error::<Test>::NotNoted
+		);
+		System::set_block_number(10);
+		assert_noop!(
+			Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), h),
+			Error::<Test>::NotExpired
+		);
+		assert!(Preimage::expired_preimages().is_empty());
+
+		System::set_block_number(11);
+		assert_eq!(Preimage::expired_preimages(), vec![h]);
+		assert_ok!(Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), h));
+		System::assert_last_event(Event::Cleared { hash: h }.into());
+		assert_eq!(Balances::balance_on_hold(&PreimageHoldReason::get(), &2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+		assert!(!Preimage::have_preimage(&h));
+		assert!(!UnrequestedSince::<Test>::contains_key(h));
+		assert!(Preimage::expired_preimages().is_empty());
+	});
+}
+
+#[test]
+fn clear_expired_preimage_counts_from_last_request() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(2), vec![1]));
+		let h = hashed([1]);
+		assert_ok!(Preimage::request_preimage(RuntimeOrigin::signed(1), h));
+		assert!(!UnrequestedSince::<Test>::contains_key(h));
+
+		System::set_block_number(20);
+		assert_noop!(
+			Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), h),
+			Error::<Test>::Requested
+		);
+		assert_ok!(Preimage::unrequest_preimage(RuntimeOrigin::signed(1), h));
+		assert_eq!(UnrequestedSince::<Test>::get(h), Some(20));
+
+		System::set_block_number(29);
+		assert_noop!(
+			Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), h),
+			Error::<Test>::NotExpired
+		);
+		System::set_block_number(30);
+		assert_ok!(Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), h));
+		assert_eq!(Balances::free_balance(2), 100);
+		assert!(!Preimage::have_preimage(&h));
+	});
+}
+
+#[test]
+fn clear_expired_preimage_keeps_untracked_preimages() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(2), vec![1]));
+		let h = hashed([1]);
+		// Preimages noted before expiry was tracked never expire.
+		UnrequestedSince::<Test>::remove(h);
+		System::set_block_number(100);
+
+		assert!(Preimage::expired_preimages().is_empty());
+		assert_eq!(Preimage::preimage_usage(&h).unwrap().expires_at, None);
+		assert_noop!(
+			Preimage::clear_expired_preimage(RuntimeOrigin::signed(3), h),
+			Error::<Test>::NotExpired
+		);
+		assert!(Preimage::have_preimage(&h));
+
+		assert_ok!(Preimage::unnote_preimage(RuntimeOrigin::signed(2), h));
+		assert_eq!(Balances::free_balance(2), 100);
+	});
+}
+
+#[test]
+fn preimage_usage_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let h = hashed([1]);
+		assert_eq!(Preimage::preimage_usage(&h), None);
+
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(2), vec![1]));
+		assert_eq!(
+			Preimage::preimage_usage(&h),
+			Some(PreimageUsage {
+				requests: 0,
+				len: Some(1),
+				depositor: Some(2),
+				expires_at: Some(11)
+			})
+		);
+
+		assert_ok!(Preimage::request_preimage(RuntimeOrigin::signed(1), h));
+		assert_ok!(Preimage::request_preimage(RuntimeOrigin::signed(1), h));
+		assert_eq!(
+			Preimage::preimage_usage(&h),
+			Some(PreimageUsage { requests: 2, len: Some(1), depositor: Some(2), expires_at: None })
+		);
+
+		let h = hashed([2]);
+		assert_ok!(Preimage::request_preimage(RuntimeOrigin::signed(1), h));
+		assert_eq!(
+			Preimage::preimage_usage(&h),
+			Some(PreimageUsage { requests: 1, len: None, depositor: None, expires_at: None })
+		);
+	});
+}
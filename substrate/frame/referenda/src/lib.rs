@@ -90,6 +90,7 @@ use sp_runtime::{
 
 mod branch;
 pub mod migration;
+pub mod runtime_api;
 mod types;
 pub mod weights;
 
@@ -99,8 +100,9 @@ pub use self::{
 	types::{
 		BalanceOf, BlockNumberFor, BoundedCallOf, CallOf, Curve, DecidingStatus, DecidingStatusOf,
 		Deposit, InsertSorted, NegativeImbalanceOf, PalletsOriginOf, ReferendumIndex,
-		ReferendumInfo, ReferendumInfoOf, ReferendumStatus, ReferendumStatusOf, ScheduleAddressOf,
-		TallyOf, Track, TrackIdOf, TrackInfo, TrackInfoOf, TracksInfo, VotesOf,
+		ReferendumInfo, ReferendumInfoOf, ReferendumStatus, ReferendumStatusOf, ReferendumSummary,
+		ReferendumSummaryOf, ScheduleAddressOf, TallyOf, Track, TrackIdOf, TrackInfo, TrackInfoOf,
+		TracksInfo, VotesOf,
 	},
 	weights::WeightInfo,
 };
@@ -873,6 +875,90 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		}
 	}
 
+	/// Summarize all ongoing referenda.
+	///
+	/// This iterates over all referenda and is therefore only meant to be used off-chain.
+	pub fn ongoing_referenda() -> Vec<ReferendumSummaryOf<T, I>> {
+		let now = T::BlockNumberProvider::current_block_number();
+		ReferendumInfoFor::<T, I>::iter()
+			.filter_map(|(index, info)| match info {
+				ReferendumInfo::Ongoing(status) => Self::summarize(index, status, now),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Summarize the ongoing referendum `index` with the given `status`.
+	///
+	/// Returns `None` if its track does not exist.
+	fn summarize(
+		index: ReferendumIndex,
+		status: ReferendumStatusOf<T, I>,
+		now: BlockNumberFor<T, I>,
+	) -> Option<ReferendumSummaryOf<T, I>> {
+		use frame_support::traits::Bounded::*;
+
+		let track = T::Tracks::info(status.track)?;
+		let approval = status.tally.approval(status.track);
+		let support = status.tally.support(status.track);
+		let elapsed = status
+			.deciding
+			.as_ref()
+			.map_or(Zero::zero(), |deciding| now.saturating_sub(deciding.since));
+		let x = Perbill::from_rational(elapsed.min(track.decision_period), track.decision_period);
+
+		let projected_approval = status.deciding.as_ref().and_then(|deciding| {
+			if let Some(confirming) = deciding.confirming {
+				return Some(confirming)
+			}
+			// The same point at which the alarm for the decision is set.
+			let offset =
+				track.min_approval.delay(approval).max(track.min_support.delay(support)).max(x);
+			(track.min_approval.passing(offset, approval) &&
+				track.min_support.passing(offset, support))
+			.then(|| {
+				deciding
+					.since
+					.saturating_add(offset.mul_ceil(track.decision_period))
+					.saturating_add(track.confirm_period)
+			})
+		});
+
+		let proposal = match &status.proposal {
+			Inline(data) => Some(data.to_vec()),
+			Lookup { hash, len } =>
+				T::Preimages::fetch(hash, Some(*len)).ok().map(|p| p.into_owned()),
+			Legacy { hash, .. } => T::Preimages::fetch(hash, None).ok().map(|p| p.into_owned()),
+		};
+
+		Some(ReferendumSummary {
+			index,
+			track: status.track,
+			track_name: track.name.iter().copied().take_while(|c| *c != 0).collect(),
+			proposal_hash: status.proposal.hash(),
+			proposal,
+			submitted: status.submitted,
+			decision_deposit_placed: status.decision_deposit.is_some(),
+			decision_ends: status
+				.deciding
+				.as_ref()
+				.map(|deciding| deciding.since.saturating_add(track.decision_period)),
+			undeciding_timeout: status
+				.deciding
+				.is_none()
+				.then(|| status.submitted.saturating_add(T::UndecidingTimeout::get())),
+			deciding: status.deciding,
+			in_queue: status.in_queue,
+			track_deciding: DecidingCount::<T, I>::get(status.track),
+			track_max_deciding: track.max_deciding,
+			approval,
+			support,
+			min_approval: track.min_approval.threshold(x),
+			min_support: track.min_support.threshold(x),
+			projected_approval,
+		})
+	}
+
 	// Enqueue a proposal from a referendum which has presumably passed.
 	fn schedule_enactment(
 		index: ReferendumIndex,
@@ -707,3 +707,64 @@ fn zero_enactment_delay_executes_proposal_at_next_block() {
 		assert_eq!(Balances::free_balance(42), 20);
 	});
 }
+
+#[test]
+fn ongoing_referenda_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(Referenda::submit(
+			RuntimeOrigin::signed(1),
+			Box::new(RawOrigin::Root.into()),
+			set_balance_proposal_bounded(1),
+			DispatchTime::At(10),
+		));
+		assert_eq!(
+			Referenda::ongoing_referenda(),
+			vec![ReferendumSummary {
+				index: 0,
+				track: 0,
+				track_name: b"root".to_vec(),
+				proposal_hash: set_balance_proposal_bounded(1).hash(),
+				proposal: Some(set_balance_proposal(1)),
+				submitted: 1,
+				decision_deposit_placed: false,
+				deciding: None,
+				in_queue: false,
+				track_deciding: 0,
+				track_max_deciding: 1,
+				approval: Perbill::zero(),
+				support: Perbill::zero(),
+				min_approval: Perbill::one(),
+				min_support: Perbill::one(),
+				decision_ends: None,
+				undeciding_timeout: Some(21),
+				projected_approval: None,
+			}]
+		);
+
+		assert_ok!(Referenda::place_decision_deposit(RuntimeOrigin::signed(2), 0));
+		run_to(5);
+		set_tally(0, 30, 10);
+		let summary = Referenda::ongoing_referenda().pop().unwrap();
+		assert_eq!(summary.deciding, Some(DecidingStatus { since: 5, confirming: None }));
+		assert_eq!(summary.track_deciding, 1);
+		assert_eq!(summary.decision_ends, Some(9));
+		assert_eq!(summary.undeciding_timeout, None);
+		// Support is the bottleneck: it passes 70% into the decision period, which is rounded up
+		// to block 8, and is approved after confirming for two blocks.
+		assert_eq!(summary.projected_approval, Some(10));
+
+		// Not enough approval to ever pass.
+		set_tally(0, 30, 90);
+		let summary = Referenda::ongoing_referenda().pop().unwrap();
+		assert_eq!(summary.projected_approval, None);
+
+		// Once confirming the end of the confirmation is projected.
+		set_tally(0, 100, 0);
+		run_to(6);
+		let summary = Referenda::ongoing_referenda().pop().unwrap();
+		assert_eq!(summary.projected_approval, Some(confirming_until(0)));
+
+		run_to(9);
+		assert!(Referenda::ongoing_referenda().is_empty());
+	});
+}
@@ -66,6 +66,8 @@ pub type ReferendumStatusOf<T, I> = ReferendumStatus<
 	ScheduleAddressOf<T, I>,
 >;
 pub type DecidingStatusOf<T, I> = DecidingStatus<BlockNumberFor<T, I>>;
+pub type ReferendumSummaryOf<T, I> =
+	ReferendumSummary<TrackIdOf<T, I>, BlockNumberFor<T, I>, <T as frame_system::Config>::Hash>;
 pub type TrackInfoOf<T, I = ()> = TrackInfo<BalanceOf<T, I>, BlockNumberFor<T, I>>;
 pub type TrackIdOf<T, I> =
 	<<T as Config<I>>::Tracks as TracksInfo<BalanceOf<T, I>, BlockNumberFor<T, I>>>::Id;
@@ -326,6 +328,50 @@ impl<
 	}
 }
 
+/// A summary of an ongoing referendum together with the state of its track and a projection of
+/// its timeline, meant for off-chain consumers.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ReferendumSummary<TrackId, Moment, Hash> {
+	/// The index of the referendum.
+	pub index: ReferendumIndex,
+	/// The track of the referendum.
+	pub track: TrackId,
+	/// The name of the track.
+	pub track_name: Vec<u8>,
+	/// The hash of the proposal.
+	pub proposal_hash: Hash,
+	/// The encoded proposal, or `None` if its preimage is not available.
+	pub proposal: Option<Vec<u8>>,
+	/// The time of submission.
+	pub submitted: Moment,
+	/// Whether the Decision Deposit has been placed.
+	pub decision_deposit_placed: bool,
+	/// The status of the decision, or `None` if it has not entered the deciding period.
+	pub deciding: Option<DecidingStatus<Moment>>,
+	/// Whether the referendum is queued for being decided.
+	pub in_queue: bool,
+	/// The number of referenda on the track which are being decided.
+	pub track_deciding: u32,
+	/// The number of referenda on the track which may be decided at once.
+	pub track_max_deciding: u32,
+	/// The current approval.
+	pub approval: Perbill,
+	/// The current support.
+	pub support: Perbill,
+	/// The approval which is currently needed to pass.
+	pub min_approval: Perbill,
+	/// The support which is currently needed to pass.
+	pub min_support: Perbill,
+	/// The point at which the deciding period ends. A referendum which is confirming at that
+	/// point may still be approved afterwards.
+	pub decision_ends: Option<Moment>,
+	/// The point at which the referendum times out if it does not enter the deciding period.
+	pub undeciding_timeout: Option<Moment>,
+	/// The point at which the referendum will be approved if the tally does not change, or `None`
+	/// if it would not pass in time or is not being decided.
+	pub projected_approval: Option<Moment>,
+}
+
 /// Type for describing a curve over the 2-dimensional space of axes between 0-1, as represented
 /// by `(Perbill, Perbill)`.
 #[derive(Clone, Eq, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen)]
@@ -184,6 +184,11 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		rate_limit: rpc_params.rpc_rate_limit,
 		rate_limit_whitelisted_ips: rpc_params.rpc_rate_limit_whitelisted_ips,
 		rate_limit_trust_proxy_headers: rpc_params.rpc_rate_limit_trust_proxy_headers,
+		serve_finalized_only: rpc_params.serve_finalized_only,
+		transaction_confirmations: rpc_params.rpc_transaction_confirmations,
+		chain_head_resume_grace_period: rpc_params
+			.rpc_chain_head_resume_grace_period
+			.map(std::time::Duration::from_secs),
 	};
 
 	let prometheus_config =
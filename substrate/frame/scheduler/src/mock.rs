@@ -22,7 +22,7 @@ use super::*;
 use crate as scheduler;
 use frame_support::{
 	derive_impl, ord_parameter_types, parameter_types,
-	traits::{ConstU32, Contains, EitherOfDiverse, EqualPrivilegeOnly},
+	traits::{ConstU32, ConstU64, Contains, EitherOfDiverse, EqualPrivilegeOnly},
 };
 use frame_system::{EnsureRoot, EnsureSignedBy};
 use sp_runtime::{BuildStorage, Perbill};
@@ -155,6 +155,7 @@ impl pallet_preimage::Config for Test {
 	type Currency = ();
 	type ManagerOrigin = EnsureRoot<u64>;
 	type Consideration = ();
+	type ExpiryPeriod = ConstU64<10>;
 }
 
 pub struct TestWeightInfo;
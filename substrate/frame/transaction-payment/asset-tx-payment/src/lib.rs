@@ -262,6 +262,12 @@ impl<T: Config> core::fmt::Debug for ChargeAssetTxPayment<T> {
 	}
 }
 
+impl<T: Config> pallet_transaction_payment::Tipped<BalanceOf<T>> for ChargeAssetTxPayment<T> {
+	fn tip(&self) -> BalanceOf<T> {
+		self.tip
+	}
+}
+
 /// The info passed between the validate and prepare steps for the `ChargeAssetTxPayment` extension.
 pub enum Val<T: Config> {
 	Charge {
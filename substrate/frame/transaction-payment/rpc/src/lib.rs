@@ -29,7 +29,8 @@ use jsonrpsee::{
 	},
 };
 use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
-use sp_api::ProvideRuntimeApi;
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiRef, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
@@ -37,6 +38,23 @@ use sp_runtime::traits::{Block as BlockT, MaybeDisplay};
 
 pub use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi as TransactionPaymentRuntimeApi;
 
+/// The maximum number of extrinsics which can be queried by `payment_queryInfoBatch` at once.
+pub const MAX_BATCH_SIZE: usize = 128;
+
+/// The dispatch info of an extrinsic together with the breakdown of its fee, as returned by
+/// `payment_queryInfoBatch`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeInfo<DispatchInfo> {
+	/// The weight, dispatch class and partial fee of the extrinsic.
+	#[serde(flatten)]
+	pub info: DispatchInfo,
+	/// The base, length and adjusted weight fee, or `None` for extrinsics without inclusion fee.
+	pub inclusion_fee: Option<InclusionFee<NumberOrHex>>,
+	/// The tip. Tips are not decoded from the extrinsic, so this is always zero.
+	pub tip: NumberOrHex,
+}
+
 #[rpc(client, server)]
 pub trait TransactionPaymentApi<BlockHash, ResponseType> {
 	#[method(name = "payment_queryInfo")]
@@ -48,6 +66,14 @@ pub trait TransactionPaymentApi<BlockHash, ResponseType> {
 		encoded_xt: Bytes,
 		at: Option<BlockHash>,
 	) -> RpcResult<FeeDetails<NumberOrHex>>;
+
+	/// Query the dispatch info and fee breakdown of up to [`MAX_BATCH_SIZE`] extrinsics at once.
+	#[method(name = "payment_queryInfoBatch")]
+	fn query_info_batch(
+		&self,
+		encoded_xts: Vec<Bytes>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<FeeInfo<ResponseType>>>;
 }
 
 /// Provides RPC methods to query a dispatchable's class, weight and fee.
@@ -81,6 +107,44 @@ impl From<Error> for i32 {
 	}
 }
 
+fn decode_error(error: impl std::fmt::Debug, desc: &'static str) -> ErrorObjectOwned {
+	ErrorObject::owned(Error::DecodeError.into(), desc, Some(format!("{:?}", error)))
+}
+
+fn runtime_error(error: impl ToString, desc: &'static str) -> ErrorObjectOwned {
+	ErrorObject::owned(Error::RuntimeError.into(), desc, Some(error.to_string()))
+}
+
+fn try_into_rpc_balance<Balance>(value: Balance) -> RpcResult<NumberOrHex>
+where
+	Balance: MaybeDisplay + Copy + TryInto<NumberOrHex>,
+{
+	value.try_into().map_err(|_| {
+		ErrorObject::owned(
+			ErrorCode::InvalidParams.code(),
+			format!("{} doesn't fit in NumberOrHex representation", value),
+			None::<()>,
+		)
+	})
+}
+
+fn into_rpc_inclusion_fee<Balance>(
+	inclusion_fee: Option<InclusionFee<Balance>>,
+) -> RpcResult<Option<InclusionFee<NumberOrHex>>>
+where
+	Balance: MaybeDisplay + Copy + TryInto<NumberOrHex>,
+{
+	inclusion_fee
+		.map(|inclusion_fee| {
+			Ok(InclusionFee {
+				base_fee: try_into_rpc_balance(inclusion_fee.base_fee)?,
+				len_fee: try_into_rpc_balance(inclusion_fee.len_fee)?,
+				adjusted_weight_fee: try_into_rpc_balance(inclusion_fee.adjusted_weight_fee)?,
+			})
+		})
+		.transpose()
+}
+
 impl<C, Block, Balance>
 	TransactionPaymentApiServer<
 		<Block as BlockT>::Hash,
@@ -102,21 +166,12 @@ where
 
 		let encoded_len = encoded_xt.len() as u32;
 
-		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(|e| {
-			ErrorObject::owned(
-				Error::DecodeError.into(),
-				"Unable to query dispatch info.",
-				Some(format!("{:?}", e)),
-			)
-		})?;
-
-		fn map_err(error: impl ToString, desc: &'static str) -> ErrorObjectOwned {
-			ErrorObject::owned(Error::RuntimeError.into(), desc, Some(error.to_string()))
-		}
+		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt)
+			.map_err(|e| decode_error(e, "Unable to query dispatch info."))?;
 
 		let res = api
 			.query_info(at_hash, uxt, encoded_len)
-			.map_err(|e| map_err(e, "Unable to query dispatch info."))?;
+			.map_err(|e| runtime_error(e, "Unable to query dispatch info."))?;
 
 		Ok(RuntimeDispatchInfo {
 			weight: res.weight,
@@ -135,42 +190,73 @@ where
 
 		let encoded_len = encoded_xt.len() as u32;
 
-		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(|e| {
-			ErrorObject::owned(
-				Error::DecodeError.into(),
-				"Unable to query fee details.",
-				Some(format!("{:?}", e)),
-			)
-		})?;
-		let fee_details = api.query_fee_details(at_hash, uxt, encoded_len).map_err(|e| {
-			ErrorObject::owned(
-				Error::RuntimeError.into(),
-				"Unable to query fee details.",
-				Some(e.to_string()),
-			)
-		})?;
-
-		let try_into_rpc_balance = |value: Balance| {
-			value.try_into().map_err(|_| {
-				ErrorObject::owned(
-					ErrorCode::InvalidParams.code(),
-					format!("{} doesn't fit in NumberOrHex representation", value),
-					None::<()>,
-				)
-			})
-		};
+		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt)
+			.map_err(|e| decode_error(e, "Unable to query fee details."))?;
+		let fee_details = api
+			.query_fee_details(at_hash, uxt, encoded_len)
+			.map_err(|e| runtime_error(e, "Unable to query fee details."))?;
 
 		Ok(FeeDetails {
-			inclusion_fee: if let Some(inclusion_fee) = fee_details.inclusion_fee {
-				Some(InclusionFee {
-					base_fee: try_into_rpc_balance(inclusion_fee.base_fee)?,
-					len_fee: try_into_rpc_balance(inclusion_fee.len_fee)?,
-					adjusted_weight_fee: try_into_rpc_balance(inclusion_fee.adjusted_weight_fee)?,
-				})
-			} else {
-				None
-			},
+			inclusion_fee: into_rpc_inclusion_fee(fee_details.inclusion_fee)?,
 			tip: Default::default(),
 		})
 	}
+
+	fn query_info_batch(
+		&self,
+		encoded_xts: Vec<Bytes>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<FeeInfo<RuntimeDispatchInfo<Balance, sp_weights::Weight>>>> {
+		if encoded_xts.len() > MAX_BATCH_SIZE {
+			return Err(ErrorObject::owned(
+				ErrorCode::InvalidParams.code(),
+				format!("At most {} extrinsics can be queried at once", MAX_BATCH_SIZE),
+				None::<()>,
+			))
+		}
+
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		encoded_xts
+			.into_iter()
+			.enumerate()
+			.map(|(index, encoded_xt)| Self::query_fee_info(&api, at_hash, index, encoded_xt))
+			.collect()
+	}
+}
+
+impl<C, Block> TransactionPayment<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+{
+	/// Query the dispatch info and fee breakdown of the `index`th extrinsic of a batch.
+	fn query_fee_info<Balance>(
+		api: &ApiRef<'_, C::Api>,
+		at_hash: Block::Hash,
+		index: usize,
+		encoded_xt: Bytes,
+	) -> RpcResult<FeeInfo<RuntimeDispatchInfo<Balance, sp_weights::Weight>>>
+	where
+		C::Api: TransactionPaymentRuntimeApi<Block, Balance>,
+		Balance: Codec + MaybeDisplay + Copy + TryInto<NumberOrHex>,
+	{
+		let encoded_len = encoded_xt.len() as u32;
+
+		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt)
+			.map_err(|e| decode_error((index, e), "Unable to query fee info."))?;
+		let info = api
+			.query_info(at_hash, uxt.clone(), encoded_len)
+			.map_err(|e| runtime_error(e, "Unable to query fee info."))?;
+		let fee_details = api
+			.query_fee_details(at_hash, uxt, encoded_len)
+			.map_err(|e| runtime_error(e, "Unable to query fee info."))?;
+
+		Ok(FeeInfo {
+			info,
+			inclusion_fee: into_rpc_inclusion_fee(fee_details.inclusion_fee)?,
+			tip: try_into_rpc_balance(fee_details.tip)?,
+		})
+	}
 }
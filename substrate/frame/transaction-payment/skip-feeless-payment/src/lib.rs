@@ -45,6 +45,7 @@ use frame_support::{
 	traits::{IsType, OriginTrait},
 	weights::Weight,
 };
+use pallet_transaction_payment::Tipped;
 use scale_info::{StaticTypeInfo, TypeInfo};
 use sp_runtime::{
 	traits::{
@@ -111,6 +112,12 @@ impl<T, S> From<S> for SkipCheckIfFeeless<T, S> {
 	}
 }
 
+impl<T, S: Tipped<Balance>, Balance> Tipped<Balance> for SkipCheckIfFeeless<T, S> {
+	fn tip(&self) -> Balance {
+		self.0.tip()
+	}
+}
+
 pub enum Intermediate<T, O> {
 	/// The wrapped extension should be applied.
 	Apply(T),
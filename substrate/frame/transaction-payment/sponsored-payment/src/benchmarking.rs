@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for the Sponsored Payment Pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use crate::Pallet as SponsoredPayment;
+use frame_benchmarking::v2::*;
+use frame_support::dispatch::{DispatchClass, Pays};
+use frame_system::RawOrigin;
+use sp_runtime::{
+	generic::ExtensionVersion,
+	traits::{AsTransactionAuthorizedOrigin, Bounded, DispatchTransaction, One},
+};
+
+/// Helper to create the envelopes of a sponsor.
+pub trait BenchmarkHelper<Signature, AccountId> {
+	/// The sponsor signing with [`Self::sign`].
+	fn sponsor() -> AccountId;
+	/// A signature of `msg` by the sponsor.
+	fn sign(msg: &[u8]) -> Signature;
+}
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn set_policy<T: Config>(sponsor: &T::AccountId) {
+	T::Consideration::ensure_successful(
+		sponsor,
+		Footprint::from_mel::<SponsorPolicy<BalanceOf<T>>>(),
+	);
+	let max = BalanceOf::<T>::max_value();
+	SponsoredPayment::<T>::set_policy(RawOrigin::Signed(sponsor.clone()).into(), max, max, 100)
+		.unwrap();
+}
+
+#[benchmarks(where
+	T: Config + Send + Sync,
+	T::RuntimeOrigin: AsTransactionAuthorizedOrigin,
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+)]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn set_policy() {
+		let sponsor: T::AccountId = whitelisted_caller();
+		T::Consideration::ensure_successful(
+			&sponsor,
+			Footprint::from_mel::<SponsorPolicy<BalanceOf<T>>>(),
+		);
+		let budget = BalanceOf::<T>::max_value();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(sponsor.clone()), budget, budget, 100);
+
+		assert_last_event::<T>(
+			Event::PolicySet { sponsor, budget, max_fee: budget, max_txs_per_period: 100 }.into(),
+		);
+	}
+
+	#[benchmark]
+	fn remove_policy() {
+		let sponsor: T::AccountId = whitelisted_caller();
+		set_policy::<T>(&sponsor);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(sponsor.clone()));
+
+		assert_last_event::<T>(Event::PolicyRemoved { sponsor }.into());
+	}
+
+	// The overhead of the sponsorship, around an extension which doesn't charge anything.
+	#[benchmark]
+	fn sponsored() -> Result<(), BenchmarkError> {
+		let sponsor = T::BenchmarkHelper::sponsor();
+		set_policy::<T>(&sponsor);
+		let who: T::AccountId = account("who", 0, 0);
+		let call: T::RuntimeCall = frame_system::Call::remark { remark: alloc::vec![] }.into();
+		let ext_version: ExtensionVersion = 0;
+		let expires_at = frame_system::Pallet::<T>::block_number() + One::one();
+		let msg = (SPONSOR_CONTEXT, &who, &sponsor, expires_at, (), (ext_version, &call))
+			.using_encoded(blake2_256);
+		let signature = T::BenchmarkHelper::sign(&msg);
+		let envelope = SponsorEnvelope { sponsor: sponsor.clone(), expires_at, signature };
+		let ext = Sponsored::<T, ()>::new_sponsored(envelope, ());
+		let info = DispatchInfo {
+			call_weight: Weight::from_parts(100, 0),
+			extension_weight: ext.weight(&call),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes,
+		};
+		let post_info = PostDispatchInfo { actual_weight: None, pays_fee: Pays::Yes };
+
+		#[block]
+		{
+			assert!(ext
+				.test_run(
+					RawOrigin::Signed(who.clone()).into(),
+					&call,
+					&info,
+					10,
+					ext_version,
+					|_| { Ok(post_info) }
+				)
+				.unwrap()
+				.is_ok());
+		}
+
+		assert_eq!(Usage::<T>::get(&sponsor, &who).count, 1);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(SponsoredPayment, crate::mock::new_test_ext(), crate::mock::Runtime);
+}
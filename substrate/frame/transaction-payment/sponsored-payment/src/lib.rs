@@ -0,0 +1,513 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Sponsored Payment Pallet
+//!
+//! Lets sponsors pay the transaction fees of other accounts, e.g. to onboard users which don't hold
+//! any funds yet.
+//!
+//! ## Overview
+//!
+//! A sponsor registers a [`SponsorPolicy`] with [`set_policy`](Pallet::set_policy): the budget
+//! of fees it pays, the maximum fee of a single transaction and the maximum number of transactions
+//! it pays per account and [`UsagePeriod`](Config::UsagePeriod).
+//!
+//! To sponsor a transaction, the sponsor signs a [`SponsorEnvelope`] for it, which the user
+//! attaches to the [`Sponsored`] transaction extension. The extension wraps the payment extension
+//! of the runtime, e.g. `pallet_transaction_payment::ChargeTransactionPayment`, and lets it charge
+//! the sponsor instead of the signer of the transaction. The sponsor signs the `blake2_256` hash of
+//! the signer, the envelope, the wrapped extension, including the tip, and the payload constructed
+//! from the call and the rest of the transaction extension pipeline, so the envelope is only valid
+//! for that transaction.
+//!
+//! The policy of the sponsor is enforced when the transaction is validated, so transactions
+//! exceeding it don't even enter the transaction pool, and are dropped from the pool once the
+//! budget of the sponsor is exhausted on revalidation. The longevity of a sponsored transaction
+//! ends when its envelope expires, which is at most [`MaxEnvelopeLifetime`] blocks ahead.
+//!
+//! The budget is charged with the fee including the tip, which the sponsor approves by signing the
+//! envelope, and the unused part of the fee is returned to the budget after dispatch. The wrapped
+//! extension reports its tip through [`Tipped`].
+//!
+//! ## Integration
+//!
+//! Wrap the payment extension of the runtime in [`Sponsored`]. Unsponsored transactions set no
+//! envelope and are passed to the wrapped extension unchanged. As the sponsored transaction is
+//! still signed by the user, the extension must follow the authorization in the pipeline.
+//!
+//! [`MaxEnvelopeLifetime`]: Config::MaxEnvelopeLifetime
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{
+	dispatch::{DispatchInfo, DispatchResult, PostDispatchInfo},
+	pallet_prelude::TransactionSource,
+	traits::{Consideration, Footprint, IsType, OriginTrait},
+	weights::Weight,
+	Parameter,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_transaction_payment::{OnChargeTransaction, Tipped};
+use scale_info::TypeInfo;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{
+		DispatchInfoOf, DispatchOriginOf, Dispatchable, IdentifyAccount, Implication,
+		PostDispatchInfoOf, Saturating, TransactionExtension, ValidateResult, Verify,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	RuntimeDebug, SaturatedConversion,
+};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub use benchmarking::BenchmarkHelper;
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+type OnChargeOf<T> = <T as pallet_transaction_payment::Config>::OnChargeTransaction;
+
+/// The balance type of the transaction fees.
+pub type BalanceOf<T> = <OnChargeOf<T> as OnChargeTransaction<T>>::Balance;
+
+/// The envelope type of the pallet.
+pub type SponsorEnvelopeOf<T> = SponsorEnvelope<
+	<T as frame_system::Config>::AccountId,
+	BlockNumberFor<T>,
+	<T as Config>::Signature,
+>;
+
+/// Prefix of the messages signed by sponsors, which distinguishes them from transaction payloads.
+pub const SPONSOR_CONTEXT: &[u8] = b"sponsor";
+
+/// The terms under which a sponsor pays the transaction fees of other accounts.
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SponsorPolicy<Balance> {
+	/// The remaining amount of fees the sponsor pays.
+	pub budget: Balance,
+	/// The maximum fee of a single sponsored transaction, including the tip.
+	pub max_fee: Balance,
+	/// The maximum number of transactions sponsored per account and usage period.
+	pub max_txs_per_period: u32,
+}
+
+/// The number of sponsored transactions of an account in a usage period.
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, Default)]
+pub struct UsageInfo<BlockNumber> {
+	/// The index of the usage period, i.e. the block number divided by the usage period.
+	pub period: BlockNumber,
+	/// The number of sponsored transactions in the period.
+	pub count: u32,
+}
+
+/// The approval of a sponsor to pay the fee of a transaction.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SponsorEnvelope<AccountId, BlockNumber, Signature> {
+	/// The sponsor paying the fee.
+	pub sponsor: AccountId,
+	/// The block from which on the envelope is no longer valid.
+	pub expires_at: BlockNumber,
+	/// The signature of the sponsor, see [`Sponsored`].
+	pub signature: Signature,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Signature type with which sponsors sign envelopes.
+		type Signature: Verify<Signer = Self::AccountIdentifier> + Parameter + Send + Sync;
+
+		/// The account identifier used by the signature type.
+		type AccountIdentifier: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// The number of blocks over which the transactions sponsored per account are limited.
+		#[pallet::constant]
+		type UsagePeriod: Get<BlockNumberFor<Self>>;
+
+		/// The maximum number of blocks for which an envelope can be valid.
+		#[pallet::constant]
+		type MaxEnvelopeLifetime: Get<BlockNumberFor<Self>>;
+
+		/// A means of providing some cost for storing the policy of a sponsor.
+		type Consideration: Consideration<Self::AccountId, Footprint>;
+
+		/// The weight information of this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Helper to create envelopes to be benchmarked.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::Signature, Self::AccountId>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The policy of a sponsor.
+		#[codec(index = 0)]
+		Policy,
+	}
+
+	/// The policies of sponsors, together with the ticket for their deposit.
+	#[pallet::storage]
+	pub type Policies<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		(SponsorPolicy<BalanceOf<T>>, T::Consideration),
+	>;
+
+	/// The transactions a sponsor paid for an account in the current usage period.
+	#[pallet::storage]
+	pub type Usage<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		UsageInfo<BlockNumberFor<T>>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The policy of a sponsor was set.
+		PolicySet {
+			sponsor: T::AccountId,
+			budget: BalanceOf<T>,
+			max_fee: BalanceOf<T>,
+			max_txs_per_period: u32,
+		},
+		/// The policy of a sponsor was removed.
+		PolicyRemoved { sponsor: T::AccountId },
+		/// A sponsor paid the fee `actual_fee`, including the tip, of a transaction of `who`.
+		TransactionSponsored { sponsor: T::AccountId, who: T::AccountId, actual_fee: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account has no policy.
+		NoPolicy,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(!T::UsagePeriod::get().is_zero(), "UsagePeriod must be greater than 0");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the policy under which the caller sponsors transactions.
+		///
+		/// Replaces the previous policy, including its remaining budget. Holds a deposit for the
+		/// policy.
+		///
+		/// Emits [`Event::PolicySet`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_policy())]
+		pub fn set_policy(
+			origin: OriginFor<T>,
+			budget: BalanceOf<T>,
+			max_fee: BalanceOf<T>,
+			max_txs_per_period: u32,
+		) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			let footprint = Footprint::from_mel::<SponsorPolicy<BalanceOf<T>>>();
+			let ticket = match Policies::<T>::take(&sponsor) {
+				Some((_, ticket)) => ticket.update(&sponsor, footprint)?,
+				None => T::Consideration::new(&sponsor, footprint)?,
+			};
+			let policy = SponsorPolicy { budget, max_fee, max_txs_per_period };
+			Policies::<T>::insert(&sponsor, (policy, ticket));
+
+			Self::deposit_event(Event::PolicySet { sponsor, budget, max_fee, max_txs_per_period });
+			Ok(())
+		}
+
+		/// Remove the policy of the caller, which stops sponsoring transactions.
+		///
+		/// Emits [`Event::PolicyRemoved`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_policy())]
+		pub fn remove_policy(origin: OriginFor<T>) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			let (_, ticket) = Policies::<T>::take(&sponsor).ok_or(Error::<T>::NoPolicy)?;
+			ticket.drop(&sponsor)?;
+
+			Self::deposit_event(Event::PolicyRemoved { sponsor });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The index of the current usage period.
+	fn current_period() -> BlockNumberFor<T> {
+		frame_system::Pallet::<T>::block_number() / T::UsagePeriod::get()
+	}
+
+	/// Check that `sponsor` pays `fee` for a transaction of `who` under its policy.
+	fn check_policy(
+		sponsor: &T::AccountId,
+		who: &T::AccountId,
+		fee: BalanceOf<T>,
+	) -> Result<(), TransactionValidityError> {
+		let (policy, _) = Policies::<T>::get(sponsor).ok_or(InvalidTransaction::Payment)?;
+		if fee > policy.max_fee || fee > policy.budget {
+			return Err(InvalidTransaction::Payment.into())
+		}
+		let usage = Usage::<T>::get(sponsor, who);
+		if usage.period == Self::current_period() && usage.count >= policy.max_txs_per_period {
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+		Ok(())
+	}
+}
+
+/// A [`TransactionExtension`] that lets a sponsor pay the fee charged by the wrapped extension.
+///
+/// If an envelope is given, the wrapped extension is applied with the sponsor as signer, so it
+/// charges the sponsor the fee and the tip, both of which count against the budget of the sponsor.
+/// The signature of the envelope must be by the sponsor, for the `blake2_256` hash of
+/// [`SPONSOR_CONTEXT`], the signer of the transaction, the sponsor, the expiry of the envelope, the
+/// wrapped extension and the inherited implication.
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct Sponsored<T: Config, S> {
+	/// The envelope of the sponsor, if the transaction is sponsored.
+	pub envelope: Option<SponsorEnvelopeOf<T>>,
+	/// The wrapped payment extension.
+	pub inner: S,
+}
+
+impl<T: Config, S: Encode> core::fmt::Debug for Sponsored<T, S> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "Sponsored<{:?}, {:?}>", self.envelope.is_some(), self.inner.encode())
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config, S> Sponsored<T, S> {
+	/// Create a new extension instance, sponsored with `envelope`.
+	pub fn new_sponsored(envelope: SponsorEnvelopeOf<T>, inner: S) -> Self {
+		Self { envelope: Some(envelope), inner }
+	}
+}
+
+impl<T: Config, S> From<S> for Sponsored<T, S> {
+	fn from(inner: S) -> Self {
+		Self { envelope: None, inner }
+	}
+}
+
+/// The sponsor of a transaction, paying for the transaction of `who`.
+pub struct Sponsorship<AccountId, Balance> {
+	sponsor: AccountId,
+	who: AccountId,
+	/// The fee charged to the sponsor before dispatch, including the tip.
+	fee: Balance,
+	tip: Balance,
+}
+
+/// The intermediate state of the wrapped extension.
+pub enum Intermediate<T, AccountId, Balance> {
+	/// The wrapped extension charges the signer.
+	Charge(T),
+	/// The wrapped extension charges the sponsor.
+	ChargeSponsor(T, Sponsorship<AccountId, Balance>),
+}
+use Intermediate::*;
+
+impl<T, S> TransactionExtension<T::RuntimeCall> for Sponsored<T, S>
+where
+	T: Config + Send + Sync,
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	S: TransactionExtension<T::RuntimeCall> + Tipped<BalanceOf<T>>,
+{
+	const IDENTIFIER: &'static str = "Sponsored";
+	type Implicit = S::Implicit;
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		self.inner.implicit()
+	}
+	type Val = Intermediate<S::Val, T::AccountId, BalanceOf<T>>;
+	type Pre = Intermediate<S::Pre, T::AccountId, BalanceOf<T>>;
+
+	fn weight(&self, call: &T::RuntimeCall) -> Weight {
+		let sponsorship = match self.envelope {
+			Some(_) => T::WeightInfo::sponsored(),
+			None => Weight::zero(),
+		};
+		self.inner.weight(call).saturating_add(sponsorship)
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		self_implicit: S::Implicit,
+		inherited_implication: &impl Implication,
+		source: TransactionSource,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		let Some(envelope) = &self.envelope else {
+			let (valid, val, origin) = self.inner.validate(
+				origin,
+				call,
+				info,
+				len,
+				self_implicit,
+				inherited_implication,
+				source,
+			)?;
+			return Ok((valid, Charge(val), origin))
+		};
+		let who = frame_system::ensure_signed(origin.clone())
+			.map_err(|_| InvalidTransaction::BadSigner)?;
+
+		let now = frame_system::Pallet::<T>::block_number();
+		if envelope.expires_at <= now {
+			return Err(InvalidTransaction::Stale.into())
+		}
+		if envelope.expires_at > now.saturating_add(T::MaxEnvelopeLifetime::get()) {
+			return Err(InvalidTransaction::Future.into())
+		}
+
+		let tip = self.inner.tip();
+		let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, tip);
+		Pallet::<T>::check_policy(&envelope.sponsor, &who, fee)?;
+
+		let msg = (
+			SPONSOR_CONTEXT,
+			&who,
+			&envelope.sponsor,
+			envelope.expires_at,
+			&self.inner,
+			inherited_implication,
+		)
+			.using_encoded(blake2_256);
+		if !envelope.signature.verify(&msg[..], &envelope.sponsor) {
+			return Err(InvalidTransaction::BadProof.into())
+		}
+
+		let sponsor_origin =
+			<DispatchOriginOf<T::RuntimeCall> as OriginTrait>::signed(envelope.sponsor.clone());
+		let (mut valid, val, _) = self.inner.validate(
+			sponsor_origin,
+			call,
+			info,
+			len,
+			self_implicit,
+			inherited_implication,
+			source,
+		)?;
+		// The transaction can't be included once the envelope expired.
+		let remaining = (envelope.expires_at - now).saturated_into::<u64>();
+		valid.longevity = valid.longevity.min(remaining);
+
+		let sponsorship = Sponsorship { sponsor: envelope.sponsor.clone(), who, fee, tip };
+		Ok((valid, ChargeSponsor(val, sponsorship), origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		origin: &DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let (val, sponsorship) = match val {
+			Charge(val) => return self.inner.prepare(val, origin, call, info, len).map(Charge),
+			ChargeSponsor(val, sponsorship) => (val, sponsorship),
+		};
+		// Other transactions of the block may have used up the policy since validation.
+		let Sponsorship { sponsor, who, fee, .. } = &sponsorship;
+		Pallet::<T>::check_policy(sponsor, who, *fee)?;
+
+		let sponsor_origin =
+			<DispatchOriginOf<T::RuntimeCall> as OriginTrait>::signed(sponsor.clone());
+		let pre = self.inner.prepare(val, &sponsor_origin, call, info, len)?;
+
+		Policies::<T>::mutate_extant(sponsor, |(policy, _)| {
+			policy.budget = policy.budget.saturating_sub(*fee)
+		});
+		let period = Pallet::<T>::current_period();
+		Usage::<T>::mutate(sponsor, who, |usage| {
+			if usage.period != period {
+				*usage = UsageInfo { period, count: 0 };
+			}
+			usage.count.saturating_inc();
+		});
+		Ok(ChargeSponsor(pre, sponsorship))
+	}
+
+	fn post_dispatch_details(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<Weight, TransactionValidityError> {
+		let (pre, Sponsorship { sponsor, who, fee, tip }) = match pre {
+			Charge(pre) => return S::post_dispatch_details(pre, info, post_info, len, result),
+			ChargeSponsor(pre, sponsorship) => (pre, sponsorship),
+		};
+		let unspent = S::post_dispatch_details(pre, info, post_info, len, result)?;
+
+		let actual_fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+			len as u32,
+			info,
+			post_info,
+			tip,
+		)
+		.min(fee);
+		Policies::<T>::mutate_extant(&sponsor, |(policy, _)| {
+			policy.budget = policy.budget.saturating_add(fee - actual_fee)
+		});
+		Pallet::<T>::deposit_event(Event::<T>::TransactionSponsored { sponsor, who, actual_fee });
+		Ok(unspent)
+	}
+}
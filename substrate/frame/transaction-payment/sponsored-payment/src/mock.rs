@@ -0,0 +1,101 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate as pallet_sponsored_payment;
+
+use frame_support::{
+	derive_impl,
+	traits::ConstU64,
+	weights::{ConstantMultiplier, IdentityFee},
+};
+use pallet_transaction_payment::FungibleAdapter;
+use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+pub const SPONSOR: u64 = 1;
+pub const USER: u64 = 2;
+pub const FUNDED_USER: u64 = 3;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		Balances: pallet_balances,
+		TransactionPayment: pallet_transaction_payment,
+		SponsoredPayment: pallet_sponsored_payment,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+	type AccountStore = System;
+}
+
+#[derive_impl(pallet_transaction_payment::config_preludes::TestDefaultConfig)]
+impl pallet_transaction_payment::Config for Runtime {
+	type OnChargeTransaction = FungibleAdapter<Balances, ()>;
+	// Only the length is charged, which keeps the fees small.
+	type WeightToFee = ConstantMultiplier<u64, ConstU64<0>>;
+	type LengthToFee = IdentityFee<u64>;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct BenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<TestSignature, u64> for BenchmarkHelper {
+	fn sponsor() -> u64 {
+		SPONSOR
+	}
+
+	fn sign(msg: &[u8]) -> TestSignature {
+		TestSignature(SPONSOR, msg.to_vec())
+	}
+}
+
+impl crate::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Signature = TestSignature;
+	type AccountIdentifier = UintAuthorityId;
+	type UsagePeriod = ConstU64<10>;
+	type MaxEnvelopeLifetime = ConstU64<100>;
+	type Consideration = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = BenchmarkHelper;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(SPONSOR, 1_000_000), (FUNDED_USER, 1_000_000)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,248 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::mock::*;
+
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{GetDispatchInfo, Pays},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{testing::TestSignature, traits::DispatchTransaction};
+
+type Extension = Sponsored<Runtime, ChargeTransactionPayment<Runtime>>;
+
+const LEN: usize = 10;
+
+fn call() -> RuntimeCall {
+	frame_system::Call::remark { remark: vec![1, 2, 3] }.into()
+}
+
+fn fee() -> u64 {
+	TransactionPayment::compute_fee(LEN as u32, &call().get_dispatch_info(), 0)
+}
+
+fn sponsored(who: u64, expires_at: u64, signer: u64) -> Extension {
+	sponsored_with_tip(who, expires_at, signer, 0)
+}
+
+fn sponsored_with_tip(who: u64, expires_at: u64, signer: u64, tip: u64) -> Extension {
+	let inner = ChargeTransactionPayment::from(tip);
+	let msg = (SPONSOR_CONTEXT, who, SPONSOR, expires_at, &inner, (0u8, call()))
+		.using_encoded(blake2_256);
+	let signature = TestSignature(signer, msg.to_vec());
+	Sponsored::new_sponsored(SponsorEnvelope { sponsor: SPONSOR, expires_at, signature }, inner)
+}
+
+fn run(ext: Extension, who: u64) -> Result<(), TransactionValidityError> {
+	let post_info = PostDispatchInfo { actual_weight: None, pays_fee: Pays::Yes };
+	ext.test_run(Some(who).into(), &call(), &call().get_dispatch_info(), LEN, 0, |_| Ok(post_info))
+		.map(|result| assert_ok!(result))
+}
+
+fn set_policy(budget: u64, max_fee: u64, max_txs_per_period: u32) {
+	assert_ok!(SponsoredPayment::set_policy(
+		RuntimeOrigin::signed(SPONSOR),
+		budget,
+		max_fee,
+		max_txs_per_period
+	));
+}
+
+fn budget() -> u64 {
+	Policies::<Runtime>::get(SPONSOR).unwrap().0.budget
+}
+
+#[test]
+fn sponsored_transaction_charges_sponsor() {
+	new_test_ext().execute_with(|| {
+		set_policy(1_000, 100, 5);
+		let fee = fee();
+		assert!(fee > 0);
+
+		assert_ok!(run(sponsored(USER, 5, SPONSOR), USER));
+		assert_eq!(Balances::free_balance(SPONSOR), 1_000_000 - fee);
+		assert_eq!(Balances::free_balance(USER), 0);
+		assert_eq!(budget(), 1_000 - fee);
+		assert_eq!(Usage::<Runtime>::get(SPONSOR, USER), UsageInfo { period: 0, count: 1 });
+		System::assert_last_event(
+			Event::TransactionSponsored { sponsor: SPONSOR, who: USER, actual_fee: fee }.into(),
+		);
+	});
+}
+
+#[test]
+fn tip_is_charged_to_budget() {
+	new_test_ext().execute_with(|| {
+		set_policy(1_000, 100, 5);
+		let tip = 7;
+		let fee = fee() + tip;
+
+		assert_ok!(run(sponsored_with_tip(USER, 5, SPONSOR, tip), USER));
+		assert_eq!(Balances::free_balance(SPONSOR), 1_000_000 - fee);
+		assert_eq!(budget(), 1_000 - fee);
+		System::assert_last_event(
+			Event::TransactionSponsored { sponsor: SPONSOR, who: USER, actual_fee: fee }.into(),
+		);
+
+		// The tip counts against the maximum fee.
+		set_policy(1_000, fee - 1, 5);
+		assert_eq!(
+			run(sponsored_with_tip(USER, 5, SPONSOR, tip), USER),
+			Err(InvalidTransaction::Payment.into())
+		);
+	});
+}
+
+#[test]
+fn unsponsored_transaction_charges_signer() {
+	new_test_ext().execute_with(|| {
+		set_policy(1_000, 100, 5);
+
+		assert_ok!(run(ChargeTransactionPayment::from(0).into(), FUNDED_USER));
+		assert_eq!(Balances::free_balance(FUNDED_USER), 1_000_000 - fee());
+		assert_eq!(Balances::free_balance(SPONSOR), 1_000_000);
+		assert_eq!(budget(), 1_000);
+
+		// Without a sponsor, an account without funds can't pay.
+		assert_eq!(
+			run(ChargeTransactionPayment::from(0).into(), USER),
+			Err(InvalidTransaction::Payment.into())
+		);
+	});
+}
+
+#[test]
+fn envelope_is_checked() {
+	new_test_ext().execute_with(|| {
+		// No policy.
+		assert_eq!(run(sponsored(USER, 5, SPONSOR), USER), Err(InvalidTransaction::Payment.into()));
+		set_policy(1_000, 100, 5);
+
+		// Expired, or valid for too long.
+		assert_eq!(run(sponsored(USER, 1, SPONSOR), USER), Err(InvalidTransaction::Stale.into()));
+		assert_eq!(
+			run(sponsored(USER, 102, SPONSOR), USER),
+			Err(InvalidTransaction::Future.into())
+		);
+		// Not signed by the sponsor.
+		assert_eq!(run(sponsored(USER, 5, USER), USER), Err(InvalidTransaction::BadProof.into()));
+		// Signed for another account.
+		assert_eq!(
+			run(sponsored(FUNDED_USER, 5, SPONSOR), USER),
+			Err(InvalidTransaction::BadProof.into())
+		);
+		// Not signed at all.
+		assert_eq!(
+			sponsored(USER, 5, SPONSOR)
+				.validate_only(
+					None.into(),
+					&call(),
+					&call().get_dispatch_info(),
+					LEN,
+					TransactionSource::External,
+					0
+				)
+				.map(|_| ()),
+			Err(InvalidTransaction::BadSigner.into())
+		);
+	});
+}
+
+#[test]
+fn longevity_ends_with_envelope() {
+	new_test_ext().execute_with(|| {
+		set_policy(1_000, 100, 5);
+		System::set_block_number(3);
+
+		let (valid, _, _) = sponsored(USER, 10, SPONSOR)
+			.validate_only(
+				Some(USER).into(),
+				&call(),
+				&call().get_dispatch_info(),
+				LEN,
+				TransactionSource::External,
+				0,
+			)
+			.unwrap();
+		assert_eq!(valid.longevity, 7);
+	});
+}
+
+#[test]
+fn policy_limits_fees() {
+	new_test_ext().execute_with(|| {
+		let fee = fee();
+		set_policy(1_000, fee - 1, 5);
+		assert_eq!(run(sponsored(USER, 5, SPONSOR), USER), Err(InvalidTransaction::Payment.into()));
+
+		set_policy(2 * fee, fee, 5);
+		assert_ok!(run(sponsored(USER, 5, SPONSOR), USER));
+		assert_ok!(run(sponsored(USER, 5, SPONSOR), USER));
+		assert_eq!(budget(), 0);
+		assert_eq!(run(sponsored(USER, 5, SPONSOR), USER), Err(InvalidTransaction::Payment.into()));
+	});
+}
+
+#[test]
+fn policy_limits_transactions_per_period() {
+	new_test_ext().execute_with(|| {
+		set_policy(1_000, 100, 2);
+		assert_ok!(run(sponsored(USER, 15, SPONSOR), USER));
+		assert_ok!(run(sponsored(USER, 15, SPONSOR), USER));
+		assert_eq!(
+			run(sponsored(USER, 15, SPONSOR), USER),
+			Err(InvalidTransaction::ExhaustsResources.into())
+		);
+		// Other accounts are limited separately.
+		assert_ok!(run(sponsored(FUNDED_USER, 15, SPONSOR), FUNDED_USER));
+
+		// The limit resets with the next period.
+		System::set_block_number(10);
+		assert_ok!(run(sponsored(USER, 15, SPONSOR), USER));
+		assert_eq!(Usage::<Runtime>::get(SPONSOR, USER), UsageInfo { period: 1, count: 1 });
+	});
+}
+
+#[test]
+fn set_and_remove_policy_works() {
+	new_test_ext().execute_with(|| {
+		set_policy(1_000, 100, 5);
+		System::assert_last_event(
+			Event::PolicySet {
+				sponsor: SPONSOR,
+				budget: 1_000,
+				max_fee: 100,
+				max_txs_per_period: 5,
+			}
+			.into(),
+		);
+		assert_eq!(
+			Policies::<Runtime>::get(SPONSOR).unwrap().0,
+			SponsorPolicy { budget: 1_000, max_fee: 100, max_txs_per_period: 5 }
+		);
+
+		assert_ok!(SponsoredPayment::remove_policy(RuntimeOrigin::signed(SPONSOR)));
+		System::assert_last_event(Event::PolicyRemoved { sponsor: SPONSOR }.into());
+		assert!(!Policies::<Runtime>::contains_key(SPONSOR));
+		assert_noop!(
+			SponsoredPayment::remove_policy(RuntimeOrigin::signed(SPONSOR)),
+			Error::<Runtime>::NoPolicy
+		);
+	});
+}
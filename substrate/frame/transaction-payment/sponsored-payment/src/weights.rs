@@ -0,0 +1,118 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholder weights for `pallet_sponsored_payment`.
+//!
+//! THESE WEIGHTS WERE NOT GENERATED BY THE BENCHMARK CLI. They are estimates derived from the
+//! storage accesses of each call and comparable benchmarked calls of other pallets, and must be
+//! replaced by running the benchmarks of the pallet before it is deployed to a production chain:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime=target/production/wbuild/kitchensink-runtime/kitchensink_runtime.wasm \
+//!     --pallet=pallet_sponsored_payment --extrinsic=* --steps=50 --repeat=20 \
+//!     --header=substrate/HEADER-APACHE2 \
+//!     --output=substrate/frame/transaction-payment/sponsored-payment/src/weights.rs \
+//!     --template=substrate/.maintain/frame-weight-template.hbs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(dead_code)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_sponsored_payment`.
+pub trait WeightInfo {
+	fn set_policy() -> Weight;
+	fn remove_policy() -> Weight;
+	fn sponsored() -> Weight;
+}
+
+/// Weights for `pallet_sponsored_payment` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `SponsoredPayment::Policies` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Policies` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn set_policy() -> Weight {
+		Weight::from_parts(49_802_000, 28584)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `SponsoredPayment::Policies` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Policies` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn remove_policy() -> Weight {
+		Weight::from_parts(45_213_000, 3892)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `TransactionPayment::NextFeeMultiplier` (r:1 w:0)
+	/// Proof: `TransactionPayment::NextFeeMultiplier` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `SponsoredPayment::Policies` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Policies` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// Storage: `SponsoredPayment::Usage` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Usage` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn sponsored() -> Weight {
+		Weight::from_parts(72_944_000, 3581)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `SponsoredPayment::Policies` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Policies` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// Storage: `Parameters::Parameters` (r:2 w:0)
+	/// Proof: `Parameters::Parameters` (`max_values`: None, `max_size`: Some(11322), added: 13797, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn set_policy() -> Weight {
+		Weight::from_parts(49_802_000, 28584)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `SponsoredPayment::Policies` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Policies` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `MaxEncodedLen`)
+	fn remove_policy() -> Weight {
+		Weight::from_parts(45_213_000, 3892)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `TransactionPayment::NextFeeMultiplier` (r:1 w:0)
+	/// Proof: `TransactionPayment::NextFeeMultiplier` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `SponsoredPayment::Policies` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Policies` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// Storage: `SponsoredPayment::Usage` (r:1 w:1)
+	/// Proof: `SponsoredPayment::Usage` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn sponsored() -> Weight {
+		Weight::from_parts(72_944_000, 3581)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
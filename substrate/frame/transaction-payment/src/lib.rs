@@ -698,6 +698,14 @@ where
 	}
 }
 
+/// A payment transaction extension which charges a tip on top of the fee of the transaction.
+///
+/// Lets extensions wrapping the payment extension account for the full amount it charges.
+pub trait Tipped<Balance> {
+	/// The tip chosen by the transaction sender.
+	fn tip(&self) -> Balance;
+}
+
 /// Require the transactor pay for themselves and maybe include a tip to gain additional priority
 /// in the queue.
 ///
@@ -851,6 +859,18 @@ impl<T: Config> core::fmt::Debug for ChargeTransactionPayment<T> {
 	}
 }
 
+impl<T: Config> Tipped<BalanceOf<T>> for ChargeTransactionPayment<T> {
+	fn tip(&self) -> BalanceOf<T> {
+		self.0
+	}
+}
+
+impl<Balance: Zero> Tipped<Balance> for () {
+	fn tip(&self) -> Balance {
+		Zero::zero()
+	}
+}
+
 /// The info passed between the validate and prepare steps for the `ChargeAssetTxPayment` extension.
 #[derive(RuntimeDebugNoBound)]
 pub enum Val<T: Config> {
@@ -21,7 +21,7 @@
 
 use crate as pallet_whitelist;
 
-use frame_support::{construct_runtime, derive_impl};
+use frame_support::{construct_runtime, derive_impl, traits::ConstU64};
 use frame_system::EnsureRoot;
 use sp_runtime::BuildStorage;
 
@@ -54,6 +54,7 @@ impl pallet_preimage::Config for Test {
 	type ManagerOrigin = EnsureRoot<Self::AccountId>;
 	type Consideration = ();
 	type WeightInfo = ();
+	type ExpiryPeriod = ConstU64<10>;
 }
 
 impl pallet_whitelist::Config for Test {
@@ -10,3 +10,4 @@ cargo check --release --target=$T --no-default-features  --features="serde,full_
 cargo check --release --target=$T --no-default-features  --features="bandersnatch-experimental"
 cargo check --release --target=$T --no-default-features  --features="bls-experimental"
 cargo check --release --target=$T --no-default-features  --features="bls-experimental,full_crypto"
+cargo check --release --target=$T --no-default-features  --features="dilithium-experimental"
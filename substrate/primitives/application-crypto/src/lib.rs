@@ -48,6 +48,8 @@ pub use serde;
 pub mod bandersnatch;
 #[cfg(feature = "bls-experimental")]
 pub mod bls381;
+#[cfg(feature = "dilithium-experimental")]
+pub mod dilithium;
 pub mod ecdsa;
 #[cfg(feature = "bls-experimental")]
 pub mod ecdsa_bls381;
@@ -47,6 +47,9 @@ pub enum Error {
 	/// Error from the client while fetching some data from the chain.
 	#[error("Chain lookup failed: {0}")]
 	ChainLookup(String),
+	/// A local policy vetoed the import of the block.
+	#[error("Import vetoed: {0}")]
+	ImportVetoed(String),
 	/// Signing failed.
 	#[error("Failed to sign: {0}")]
 	CannotSign(String),
@@ -0,0 +1,212 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Content identifiers.
+
+use alloc::string::String;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::{fmt, str::FromStr};
+use scale_info::TypeInfo;
+
+/// The version, codec and multihash header of the supported CIDs: CIDv1, `raw`, SHA2-256.
+const PREFIX: [u8; 4] = [0x01, 0x55, 0x12, 0x20];
+
+/// The multibase prefix of base32 encoded CIDs.
+const BASE32_PREFIX: char = 'b';
+
+/// The lower case RFC 4648 base32 alphabet.
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// A CIDv1 of a blob, with the `raw` codec and a SHA2-256 multihash.
+///
+/// Only the digest is stored, so on chain a CID takes 32 bytes. The string representation is the
+/// usual base32 multibase encoding, e.g. `bafkrei...`.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	TypeInfo,
+)]
+pub struct Cid([u8; 32]);
+
+impl Cid {
+	/// The CID of `data`.
+	pub fn for_data(data: &[u8]) -> Self {
+		Self(sp_crypto_hashing::sha2_256(data))
+	}
+
+	/// The CID with the given SHA2-256 digest.
+	pub const fn from_digest(digest: [u8; 32]) -> Self {
+		Self(digest)
+	}
+
+	/// The SHA2-256 digest of the blob.
+	pub fn digest(&self) -> &[u8; 32] {
+		&self.0
+	}
+
+	/// Whether `data` is the blob identified by this CID.
+	pub fn matches(&self, data: &[u8]) -> bool {
+		*self == Self::for_data(data)
+	}
+
+	/// The binary representation of the CID.
+	pub fn to_bytes(&self) -> [u8; 36] {
+		let mut bytes = [0; 36];
+		bytes[..4].copy_from_slice(&PREFIX);
+		bytes[4..].copy_from_slice(&self.0);
+		bytes
+	}
+
+	/// Parse the binary representation of a CID.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseCidError> {
+		match bytes.strip_prefix(&PREFIX[..]) {
+			Some(digest) => digest.try_into().map(Self).map_err(|_| ParseCidError::InvalidLength),
+			None => Err(ParseCidError::Unsupported),
+		}
+	}
+}
+
+/// An error parsing a CID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseCidError {
+	/// The string is not base32 multibase encoded.
+	InvalidEncoding,
+	/// The CID is not a CIDv1 with `raw` codec and SHA2-256 multihash.
+	Unsupported,
+	/// The digest has an invalid length.
+	InvalidLength,
+}
+
+impl fmt::Display for ParseCidError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::InvalidEncoding => write!(f, "CID is not base32 encoded"),
+			Self::Unsupported => write!(f, "Only CIDv1 with raw codec and SHA2-256 are supported"),
+			Self::InvalidLength => write!(f, "Invalid digest length"),
+		}
+	}
+}
+
+impl fmt::Display for Cid {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut encoded = String::with_capacity(59);
+		encoded.push(BASE32_PREFIX);
+		let (mut buffer, mut bits) = (0u16, 0);
+		for byte in self.to_bytes() {
+			buffer = (buffer << 8) | u16::from(byte);
+			bits += 8;
+			while bits >= 5 {
+				bits -= 5;
+				encoded.push(BASE32_ALPHABET[usize::from((buffer >> bits) & 31)] as char);
+			}
+		}
+		if bits > 0 {
+			encoded.push(BASE32_ALPHABET[usize::from((buffer << (5 - bits)) & 31)] as char);
+		}
+		f.write_str(&encoded)
+	}
+}
+
+impl fmt::Debug for Cid {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+impl FromStr for Cid {
+	type Err = ParseCidError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let encoded = s.strip_prefix(BASE32_PREFIX).ok_or(ParseCidError::InvalidEncoding)?;
+		let mut bytes = alloc::vec::Vec::with_capacity(encoded.len() * 5 / 8);
+		let (mut buffer, mut bits) = (0u16, 0);
+		for c in encoded.bytes() {
+			let value = BASE32_ALPHABET
+				.iter()
+				.position(|a| *a == c.to_ascii_lowercase())
+				.ok_or(ParseCidError::InvalidEncoding)?;
+			buffer = (buffer << 5) | value as u16;
+			bits += 5;
+			if bits >= 8 {
+				bits -= 8;
+				bytes.push((buffer >> bits) as u8);
+			}
+		}
+		Self::from_bytes(&bytes)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Cid {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Cid {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		s.parse()
+			.map_err(|e: ParseCidError| serde::de::Error::custom(alloc::format!("{e}")))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::string::ToString;
+
+	#[test]
+	fn cid_of_data_matches_ipfs() {
+		// `echo -n "hello world" | ipfs add --cid-version 1 --raw-leaves`
+		let cid = Cid::for_data(b"hello world");
+		assert_eq!(cid.to_string(), "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e",);
+		assert!(cid.matches(b"hello world"));
+		assert!(!cid.matches(b"hello"));
+	}
+
+	#[test]
+	fn cid_string_roundtrip() {
+		let cid = Cid::for_data(b"content");
+		assert_eq!(cid.to_string().parse::<Cid>(), Ok(cid));
+		assert_eq!(Cid::from_bytes(&cid.to_bytes()), Ok(cid));
+	}
+
+	#[test]
+	fn unsupported_cids_are_rejected() {
+		// A CIDv0, which is base58 encoded.
+		assert_eq!(
+			"QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".parse::<Cid>(),
+			Err(ParseCidError::InvalidEncoding),
+		);
+		// A CIDv1 with the `dag-pb` codec.
+		assert_eq!(
+			"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".parse::<Cid>(),
+			Err(ParseCidError::Unsupported),
+		);
+	}
+}
@@ -0,0 +1,102 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Host functions giving offchain workers access to the content store of the node.
+
+use crate::Cid;
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use sp_core::offchain::Timestamp;
+use sp_runtime_interface::{pass_by::PassByEnum, runtime_interface};
+
+#[cfg(feature = "std")]
+use sp_externalities::ExternalitiesExt;
+
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+	/// The content store extension that will be registered at the Substrate externalities.
+	pub struct ContentStoreExt(std::sync::Arc<dyn crate::ContentStore>);
+}
+
+#[cfg(feature = "std")]
+impl ContentStoreExt {
+	/// Create new instance of externalities extensions.
+	pub fn new(store: std::sync::Arc<dyn crate::ContentStore>) -> Self {
+		Self(store)
+	}
+}
+
+/// The result of publishing or pinning a blob.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Encode, Decode, PassByEnum)]
+pub enum PublishResult {
+	/// The blob was queued for pinning.
+	Queued,
+	/// The blob exceeds [`crate::MAX_BLOB_SIZE`].
+	TooLarge,
+	/// The queue of blobs to pin is full.
+	Full,
+	/// The content store is not available.
+	NotAvailable,
+}
+
+/// Export functions for the WASM host.
+#[cfg(feature = "std")]
+pub type HostFunctions = (content_store::HostFunctions,);
+
+/// Host interface
+#[runtime_interface]
+pub trait ContentStore {
+	/// Publish a blob to the pinning backends of the node. Its CID is [`Cid::for_data`].
+	/// This is meant to be used by the offchain worker.
+	fn publish(&mut self, data: &[u8]) -> PublishResult {
+		if data.len() > crate::MAX_BLOB_SIZE {
+			return PublishResult::TooLarge;
+		}
+		if let Some(ContentStoreExt(store)) = self.extension::<ContentStoreExt>() {
+			store.publish(data.to_vec())
+		} else {
+			PublishResult::NotAvailable
+		}
+	}
+
+	/// Pin the blob with the given CID digest on the pinning backends of the node.
+	fn pin(&mut self, digest: [u8; 32]) -> PublishResult {
+		if let Some(ContentStoreExt(store)) = self.extension::<ContentStoreExt>() {
+			store.pin(Cid::from_digest(digest))
+		} else {
+			PublishResult::NotAvailable
+		}
+	}
+
+	/// Fetch the blob with the given CID digest, waiting until `deadline` at most.
+	///
+	/// Returns `None` if the blob couldn't be fetched in time or the store is not available.
+	fn fetch(&mut self, digest: [u8; 32], deadline: Option<Timestamp>) -> Option<Vec<u8>> {
+		let timeout = deadline.map(|deadline| {
+			let now = std::time::SystemTime::now()
+				.duration_since(std::time::UNIX_EPOCH)
+				.map(|now| now.as_millis() as u64)
+				.unwrap_or_default();
+			std::time::Duration::from_millis(deadline.unix_millis().saturating_sub(now))
+		});
+		if let Some(ContentStoreExt(store)) = self.extension::<ContentStoreExt>() {
+			store.fetch(Cid::from_digest(digest), timeout)
+		} else {
+			None
+		}
+	}
+}
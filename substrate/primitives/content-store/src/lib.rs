@@ -0,0 +1,57 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+//! Primitives for content-addressed off-chain data.
+//!
+//! Chains often store only the [`Cid`] of large blobs, like NFT media or file manifests, and keep
+//! the blobs themselves in IPFS. The [`content_store`] host functions let offchain workers publish
+//! and fetch such blobs through the node, which forwards them to its configured pinning backends.
+//!
+//! Only CIDv1 with the `raw` codec and a SHA2-256 multihash are supported. These identify a blob
+//! which is stored as a single IPFS block, so blobs are limited to [`MAX_BLOB_SIZE`] bytes.
+
+extern crate alloc;
+
+mod cid;
+mod host;
+
+pub use cid::{Cid, ParseCidError};
+pub use host::{content_store, PublishResult};
+#[cfg(feature = "std")]
+pub use host::{ContentStoreExt, HostFunctions};
+
+/// The maximum size of a blob, which is the maximum size of an IPFS block.
+pub const MAX_BLOB_SIZE: usize = 1024 * 1024;
+
+/// A store of content-addressed blobs, accessible by offchain workers.
+#[cfg(feature = "std")]
+pub trait ContentStore: Send + Sync {
+	/// Store `data` and queue it for pinning on all backends.
+	fn publish(&self, data: Vec<u8>) -> PublishResult;
+
+	/// Queue an already published blob for pinning on all backends.
+	fn pin(&self, cid: Cid) -> PublishResult;
+
+	/// Fetch the blob `cid`, waiting at most `timeout`.
+	///
+	/// Returns `None` if no backend provided the blob in time. Returned blobs are verified to
+	/// match `cid`.
+	fn fetch(&self, cid: Cid, timeout: Option<std::time::Duration>) -> Option<Vec<u8>>;
+}
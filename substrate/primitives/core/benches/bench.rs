@@ -87,5 +87,67 @@ fn bench_ecdsa(c: &mut Criterion) {
 	group.finish();
 }
 
+#[cfg(feature = "dilithium-experimental")]
+fn bench_dilithium(c: &mut Criterion) {
+	let mut group = c.benchmark_group("dilithium");
+
+	for &msg_size in &[32, 1024, 1024 * 1024] {
+		let msg = (0..msg_size).map(|_| rand::random::<u8>()).collect::<Vec<_>>();
+		let key = sp_core::dilithium::Pair::generate().0;
+		group.bench_function(BenchmarkId::new("signing", format!("{}", msg_size)), |b| {
+			b.iter(|| key.sign(&msg))
+		});
+	}
+
+	for &msg_size in &[32, 1024, 1024 * 1024] {
+		let msg = (0..msg_size).map(|_| rand::random::<u8>()).collect::<Vec<_>>();
+		let key = sp_core::dilithium::Pair::generate().0;
+		let sig = key.sign(&msg);
+		let public = key.public();
+		group.bench_function(BenchmarkId::new("verifying", format!("{}", msg_size)), |b| {
+			b.iter(|| sp_core::dilithium::Pair::verify(&sig, &msg, &public))
+		});
+	}
+
+	group.finish();
+}
+
+#[cfg(feature = "dilithium-experimental")]
+fn bench_sr25519_dilithium(c: &mut Criterion) {
+	use sp_core::paired_crypto::sr25519_dilithium;
+
+	let mut group = c.benchmark_group("sr25519_dilithium");
+
+	for &msg_size in &[32, 1024, 1024 * 1024] {
+		let msg = (0..msg_size).map(|_| rand::random::<u8>()).collect::<Vec<_>>();
+		let key = sr25519_dilithium::Pair::generate().0;
+		group.bench_function(BenchmarkId::new("signing", format!("{}", msg_size)), |b| {
+			b.iter(|| key.sign(&msg))
+		});
+	}
+
+	for &msg_size in &[32, 1024, 1024 * 1024] {
+		let msg = (0..msg_size).map(|_| rand::random::<u8>()).collect::<Vec<_>>();
+		let key = sr25519_dilithium::Pair::generate().0;
+		let sig = key.sign(&msg);
+		let public = key.public();
+		group.bench_function(BenchmarkId::new("verifying", format!("{}", msg_size)), |b| {
+			b.iter(|| sr25519_dilithium::Pair::verify(&sig, &msg, &public))
+		});
+	}
+
+	group.finish();
+}
+
+#[cfg(not(feature = "dilithium-experimental"))]
 criterion_group!(benches, bench_ed25519, bench_sr25519, bench_ecdsa,);
+#[cfg(feature = "dilithium-experimental")]
+criterion_group!(
+	benches,
+	bench_ed25519,
+	bench_sr25519,
+	bench_ecdsa,
+	bench_dilithium,
+	bench_sr25519_dilithium
+);
 criterion_main!(benches);
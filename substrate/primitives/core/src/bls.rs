@@ -61,9 +61,11 @@ pub mod bls377 {
 
 /// BLS-381 specialized types
 pub mod bls381 {
+	use super::{DoublePublicKey, DoubleSignature, Message, SerializableToBytes};
 	pub use super::{PUBLIC_KEY_SERIALIZED_SIZE, SIGNATURE_SERIALIZED_SIZE};
-	use crate::crypto::CryptoTypeId;
+	use crate::crypto::{CryptoTypeId, PublicBytes, SignatureBytes};
 	pub(crate) use w3f_bls::TinyBLS381 as BlsEngine;
+	use w3f_bls::{single, EngineBLS};
 
 	/// An identifier used to match public keys against BLS12-381 keys
 	pub const CRYPTO_ID: CryptoTypeId = CryptoTypeId(*b"bls8");
@@ -81,6 +83,112 @@ pub mod bls381 {
 	impl super::HardJunctionId for BlsEngine {
 		const ID: &'static str = "BLS12381HDKD";
 	}
+
+	/// Size of a point of the signature group, i.e. of an aggregate or share signature.
+	pub const SINGLE_SIGNATURE_SERIALIZED_SIZE: usize =
+		<single::Signature<BlsEngine> as SerializableToBytes>::SERIALIZED_BYTES_SIZE;
+
+	/// Size of a point of the public key group, i.e. of a threshold commitment.
+	pub const SINGLE_PUBLIC_KEY_SERIALIZED_SIZE: usize =
+		<single::PublicKey<BlsEngine> as SerializableToBytes>::SERIALIZED_BYTES_SIZE;
+
+	#[doc(hidden)]
+	pub struct AggregateTag;
+
+	#[doc(hidden)]
+	pub struct ShareTag;
+
+	#[doc(hidden)]
+	pub struct CommitmentTag;
+
+	/// Aggregate of BLS12-381 signatures on the same message.
+	pub type AggregateSignature = SignatureBytes<SINGLE_SIGNATURE_SERIALIZED_SIZE, AggregateTag>;
+
+	/// BLS12-381 signature of a threshold key share.
+	pub type ShareSignature = SignatureBytes<SINGLE_SIGNATURE_SERIALIZED_SIZE, ShareTag>;
+
+	/// Commitment to a coefficient of the secret polynomial of a threshold key, as published by
+	/// a distributed key generation.
+	///
+	/// The commitment to the constant coefficient is the public key of the threshold key.
+	pub type ThresholdCommitment = PublicBytes<SINGLE_PUBLIC_KEY_SERIALIZED_SIZE, CommitmentTag>;
+
+	/// Aggregate the signatures of a message into a single signature.
+	///
+	/// Returns `None` if there are no signatures or if any of them is invalid.
+	pub fn aggregate_signatures(signatures: &[Signature]) -> Option<AggregateSignature> {
+		let mut aggregate = None;
+		for signature in signatures {
+			let signature = DoubleSignature::<BlsEngine>::from_bytes(&signature.0).ok()?.0;
+			aggregate = Some(match aggregate {
+				Some(aggregate) => aggregate + signature,
+				None => signature,
+			});
+		}
+		let bytes = single::Signature::<BlsEngine>(aggregate?).to_bytes();
+		Some(AggregateSignature::from_raw(bytes.try_into().ok()?))
+	}
+
+	/// Verify an aggregate `signature` of `message` by all of `public_keys`.
+	///
+	/// The public keys must have been checked to be owned by their signers, e.g. by a proof of
+	/// possession when they were registered. Otherwise a rogue key can be chosen to forge the
+	/// aggregate.
+	pub fn aggregate_verify(
+		signature: &AggregateSignature,
+		message: &[u8],
+		public_keys: &[Public],
+	) -> bool {
+		let Ok(signature) = single::Signature::<BlsEngine>::from_bytes(&signature.0) else {
+			return false
+		};
+		let mut aggregate = None;
+		for public_key in public_keys {
+			let Ok(public_key) = DoublePublicKey::<BlsEngine>::from_bytes(&public_key.0) else {
+				return false
+			};
+			aggregate = Some(match aggregate {
+				Some(aggregate) => aggregate + public_key.1,
+				None => public_key.1,
+			});
+		}
+		let Some(aggregate) = aggregate else { return false };
+
+		signature.verify(&Message::new(b"", message), &single::PublicKey(aggregate))
+	}
+
+	/// Verify the `signature` of `message` by the share `index` of a threshold key.
+	///
+	/// The public key of the share is the evaluation at `index` of the polynomial committed to by
+	/// `commitments`, ordered from the constant coefficient up. Share indices start at `1`, the
+	/// evaluation at `0` being the threshold key itself.
+	pub fn threshold_share_verify(
+		signature: &ShareSignature,
+		message: &[u8],
+		commitments: &[ThresholdCommitment],
+		index: u32,
+	) -> bool {
+		if index == 0 {
+			return false
+		}
+		let Ok(signature) = single::Signature::<BlsEngine>::from_bytes(&signature.0) else {
+			return false
+		};
+		let x = <BlsEngine as EngineBLS>::Scalar::from(u64::from(index));
+		let mut public_key = None;
+		for commitment in commitments.iter().rev() {
+			let Ok(commitment) = single::PublicKey::<BlsEngine>::from_bytes(&commitment.0) else {
+				return false
+			};
+			public_key = Some(match public_key {
+				Some(public_key) => public_key * x + commitment.0,
+				None => commitment.0,
+			});
+		}
+		let Some(public_key) = public_key else { return false };
+
+		signature.verify(&Message::new(b"", message), &single::PublicKey(public_key))
+	}
 }
 
 trait BlsBound: EngineBLS + HardJunctionId + Send + Sync + 'static {}
@@ -544,4 +652,52 @@ mod tests {
 	fn signature_serialization_doesnt_panic_for_bls381() {
 		signature_serialization_doesnt_panic::<bls381::BlsEngine>();
 	}
+
+	#[test]
+	fn aggregate_verify_works_for_bls381() {
+		let pairs = ["//Alice", "//Bob", "//Charlie"]
+			.map(|suri| Bls381Pair::from_string(suri, None).unwrap());
+		let public_keys = pairs.iter().map(|pair| pair.public()).collect::<Vec<_>>();
+		let message = b"Something important";
+		let signatures = pairs.iter().map(|pair| pair.sign(message)).collect::<Vec<_>>();
+
+		let aggregate = bls381::aggregate_signatures(&signatures).unwrap();
+		assert!(bls381::aggregate_verify(&aggregate, message, &public_keys));
+		assert!(!bls381::aggregate_verify(&aggregate, b"Something else", &public_keys));
+		assert!(!bls381::aggregate_verify(&aggregate, message, &public_keys[..2]));
+		assert!(!bls381::aggregate_verify(&aggregate, message, &[]));
+		assert!(bls381::aggregate_signatures(&[]).is_none());
+	}
+
+	#[test]
+	fn threshold_share_verify_works_for_bls381() {
+		use w3f_bls::{single::SecretKeyVT, EngineBLS};
+		type Scalar = <bls381::BlsEngine as EngineBLS>::Scalar;
+
+		// Secret polynomial `3 + 5x` of a 2-out-of-n threshold key.
+		let coefficients = [Scalar::from(3u64), Scalar::from(5u64)];
+		let commitments = coefficients.map(|coefficient| {
+			let public = SecretKeyVT::<bls381::BlsEngine>(coefficient).into_public();
+			bls381::ThresholdCommitment::from_raw(public.to_bytes().try_into().unwrap())
+		});
+		let message = b"Something important";
+		let share_signature = |index: u64| {
+			let secret = coefficients[0] + coefficients[1] * Scalar::from(index);
+			let signature =
+				SecretKeyVT::<bls381::BlsEngine>(secret).sign(&Message::new(b"", message));
+			bls381::ShareSignature::from_raw(signature.to_bytes().try_into().unwrap())
+		};
+
+		assert!(bls381::threshold_share_verify(&share_signature(2), message, &commitments, 2));
+		assert!(bls381::threshold_share_verify(&share_signature(7), message, &commitments, 7));
+		assert!(!bls381::threshold_share_verify(&share_signature(2), message, &commitments, 1));
+		assert!(!bls381::threshold_share_verify(
+			&share_signature(2),
+			b"Something else",
+			&commitments,
+			2,
+		));
+		assert!(!bls381::threshold_share_verify(&share_signature(0), message, &commitments, 0));
+		assert!(!bls381::threshold_share_verify(&share_signature(2), message, &[], 2));
+	}
 }
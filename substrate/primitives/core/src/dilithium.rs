@@ -0,0 +1,227 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Post-quantum ML-DSA-65 signature API.
+//!
+//! ML-DSA is the module lattice based signature scheme standardized in
+//! [FIPS 204](https://csrc.nist.gov/pubs/fips/204/final) from CRYSTALS-Dilithium; ML-DSA-65 is
+//! its security category 3 parameter set. Key pairs are expanded from a 32 byte seed and
+//! signatures use the deterministic variant, with an empty context string.
+//!
+//! ML-DSA is meant to be used together with a classical scheme, see
+//! [`sr25519_dilithium`](crate::paired_crypto::sr25519_dilithium).
+
+use crate::crypto::{
+	CryptoType, CryptoTypeId, DeriveError, DeriveJunction, Pair as TraitPair, PublicBytes,
+	SecretStringError, SignatureBytes,
+};
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "full_crypto")]
+use fips204::traits::Signer;
+use fips204::{
+	ml_dsa_65,
+	traits::{KeyGen, SerDes, Verifier},
+};
+
+/// An identifier used to match public keys against ML-DSA-65 keys
+pub const CRYPTO_ID: CryptoTypeId = CryptoTypeId(*b"mlds");
+
+/// The byte length of public key
+pub const PUBLIC_KEY_SERIALIZED_SIZE: usize = 1952;
+
+/// The byte length of signature
+pub const SIGNATURE_SERIALIZED_SIZE: usize = 3309;
+
+/// The seed the key pair is expanded from, `ξ` in FIPS 204.
+type Seed = [u8; 32];
+
+/// The randomness of the deterministic variant of the signing algorithm, `rnd` in FIPS 204.
+#[cfg(feature = "full_crypto")]
+const DETERMINISTIC_RND: [u8; 32] = [0; 32];
+
+#[doc(hidden)]
+pub struct DilithiumTag;
+
+/// A public key.
+pub type Public = PublicBytes<PUBLIC_KEY_SERIALIZED_SIZE, DilithiumTag>;
+
+/// A signature.
+pub type Signature = SignatureBytes<SIGNATURE_SERIALIZED_SIZE, DilithiumTag>;
+
+/// A key pair.
+///
+/// Only the seed is kept, the expanded signing key is several kilobytes and is recomputed when
+/// signing.
+#[derive(Clone)]
+pub struct Pair {
+	seed: Seed,
+	public: Public,
+}
+
+/// Derive a single hard junction.
+fn derive_hard_junction(secret_seed: &Seed, cc: &[u8; 32]) -> Seed {
+	use codec::Encode;
+	("DilithiumHDKD", secret_seed, cc).using_encoded(sp_crypto_hashing::blake2_256)
+}
+
+impl TraitPair for Pair {
+	type Public = Public;
+	type Seed = Seed;
+	type Signature = Signature;
+
+	/// Make a new key pair from secret seed material. The slice must be 32 bytes long or it
+	/// will return `None`.
+	///
+	/// You should never need to use this; generate(), generate_with_phrase
+	fn from_seed_slice(seed_slice: &[u8]) -> Result<Pair, SecretStringError> {
+		let seed = Seed::try_from(seed_slice).map_err(|_| SecretStringError::InvalidSeedLength)?;
+		let (public, _) = ml_dsa_65::KG::keygen_from_seed(&seed);
+		Ok(Pair { seed, public: Public::from_raw(public.into_bytes()) })
+	}
+
+	/// Derive a child key from a series of given junctions.
+	fn derive<Iter: Iterator<Item = DeriveJunction>>(
+		&self,
+		path: Iter,
+		_seed: Option<Seed>,
+	) -> Result<(Pair, Option<Seed>), DeriveError> {
+		let mut acc = self.seed;
+		for j in path {
+			match j {
+				DeriveJunction::Soft(_cc) => return Err(DeriveError::SoftKeyInPath),
+				DeriveJunction::Hard(cc) => acc = derive_hard_junction(&acc, &cc),
+			}
+		}
+		Ok((Self::from_seed(&acc), Some(acc)))
+	}
+
+	/// Get the public key.
+	fn public(&self) -> Public {
+		self.public
+	}
+
+	/// Sign a message.
+	#[cfg(feature = "full_crypto")]
+	fn sign(&self, message: &[u8]) -> Signature {
+		let (_, secret) = ml_dsa_65::KG::keygen_from_seed(&self.seed);
+		let signature = secret
+			.try_sign_with_seed(&DETERMINISTIC_RND, message, &[])
+			.expect("Signing only fails for contexts longer than 255 bytes; qed");
+		Signature::from_raw(signature)
+	}
+
+	/// Verify a signature on a message.
+	///
+	/// Returns true if the signature is good.
+	fn verify<M: AsRef<[u8]>>(sig: &Signature, message: M, public: &Public) -> bool {
+		let Ok(public) = ml_dsa_65::PublicKey::try_from_bytes(public.0) else { return false };
+		public.verify(message.as_ref(), &sig.0, &[])
+	}
+
+	/// Return a vec filled with raw data.
+	fn to_raw_vec(&self) -> Vec<u8> {
+		self.seed().to_vec()
+	}
+}
+
+impl Pair {
+	/// Get the seed for this key.
+	pub fn seed(&self) -> Seed {
+		self.seed
+	}
+}
+
+impl CryptoType for Public {
+	type Pair = Pair;
+}
+
+impl CryptoType for Signature {
+	type Pair = Pair;
+}
+
+impl CryptoType for Pair {
+	type Pair = Pair;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::crypto::DEV_PHRASE;
+
+	#[test]
+	fn default_phrase_should_be_used() {
+		assert_eq!(
+			Pair::from_string("//Alice///password", None).unwrap().public(),
+			Pair::from_string(&format!("{}//Alice", DEV_PHRASE), Some("password"))
+				.unwrap()
+				.public(),
+		);
+	}
+
+	#[test]
+	fn seed_and_derive_should_work() {
+		let seed = [7u8; 32];
+		let pair = Pair::from_seed(&seed);
+		assert_eq!(pair.seed(), seed);
+		let path = vec![DeriveJunction::Hard([0u8; 32])];
+		let (derived, derived_seed) = pair.derive(path.into_iter(), None).unwrap();
+		assert_eq!(derived_seed, Some(derive_hard_junction(&seed, &[0u8; 32])));
+		assert_ne!(derived.public(), pair.public());
+		assert!(pair.derive([DeriveJunction::soft(1)].into_iter(), None).is_err());
+	}
+
+	#[test]
+	fn generate_with_phrase_should_be_recoverable_with_from_string() {
+		let (pair, phrase, seed) = Pair::generate_with_phrase(None);
+		let repair_seed = Pair::from_seed_slice(seed.as_ref()).expect("seed slice is valid");
+		assert_eq!(pair.public(), repair_seed.public());
+		let repair_string = Pair::from_string(phrase.as_str(), None).expect("seed slice is valid");
+		assert_eq!(pair.public(), repair_string.public());
+		assert_eq!(pair.to_raw_vec(), repair_string.to_raw_vec());
+	}
+
+	#[test]
+	fn generated_pair_should_work() {
+		let (pair, _) = Pair::generate();
+		let public = pair.public();
+		let message = b"Something important";
+		let signature = pair.sign(&message[..]);
+		assert!(Pair::verify(&signature, &message[..], &public));
+		assert!(!Pair::verify(&signature, b"Something else", &public));
+	}
+
+	#[test]
+	fn signatures_are_deterministic() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let message = b"Something important";
+		assert_eq!(pair.sign(&message[..]), pair.sign(&message[..]));
+
+		let other = Pair::from_seed(b"22345678901234567890123456789012");
+		assert!(!Pair::verify(&pair.sign(&message[..]), &message[..], &other.public()));
+	}
+
+	#[test]
+	fn tampered_signature_should_not_verify() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let message = b"Something important";
+		let mut signature = pair.sign(&message[..]);
+		signature.0[0] ^= 1;
+		assert!(!Pair::verify(&signature, &message[..], &pair.public()));
+	}
+}
@@ -75,6 +75,8 @@ pub mod bandersnatch;
 #[cfg(feature = "bls-experimental")]
 pub mod bls;
 pub mod crypto_bytes;
+#[cfg(feature = "dilithium-experimental")]
+pub mod dilithium;
 pub mod ecdsa;
 pub mod ed25519;
 pub mod paired_crypto;
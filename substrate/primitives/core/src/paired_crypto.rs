@@ -226,6 +226,101 @@ pub mod ecdsa_bls381 {
 	}
 }
 
+/// Sr25519 and ML-DSA-65 (Dilithium) paired crypto scheme.
+///
+/// A hybrid classical and post-quantum scheme: a signature is only valid if both its sr25519 and
+/// its ML-DSA-65 components are, so it stays secure as long as one of the two schemes is.
+#[cfg(feature = "dilithium-experimental")]
+pub mod sr25519_dilithium {
+	use crate::{
+		crypto::{CryptoTypeId, UncheckedFrom},
+		dilithium, sr25519,
+	};
+
+	/// An identifier used to match public keys against sr25519 and ML-DSA-65 keys
+	pub const CRYPTO_ID: CryptoTypeId = CryptoTypeId(*b"srdl");
+
+	/// The byte length of public key
+	pub const PUBLIC_KEY_SERIALIZED_SIZE: usize =
+		sr25519::PUBLIC_KEY_SERIALIZED_SIZE + dilithium::PUBLIC_KEY_SERIALIZED_SIZE;
+
+	/// The byte length of signature
+	pub const SIGNATURE_SERIALIZED_SIZE: usize =
+		sr25519::SIGNATURE_SERIALIZED_SIZE + dilithium::SIGNATURE_SERIALIZED_SIZE;
+
+	#[doc(hidden)]
+	pub struct Sr25519DilithiumTag(sr25519::Sr25519Tag, dilithium::DilithiumTag);
+
+	impl super::PairedCryptoSubTagBound for Sr25519DilithiumTag {}
+
+	/// (Sr25519,ML-DSA-65) key-pair pair.
+	pub type Pair = super::Pair<
+		sr25519::Pair,
+		dilithium::Pair,
+		PUBLIC_KEY_SERIALIZED_SIZE,
+		SIGNATURE_SERIALIZED_SIZE,
+		Sr25519DilithiumTag,
+	>;
+
+	/// (Sr25519,ML-DSA-65) public key pair.
+	pub type Public = super::Public<PUBLIC_KEY_SERIALIZED_SIZE, Sr25519DilithiumTag>;
+
+	/// (Sr25519,ML-DSA-65) signature pair.
+	pub type Signature = super::Signature<SIGNATURE_SERIALIZED_SIZE, Sr25519DilithiumTag>;
+
+	impl super::CryptoType for Public {
+		type Pair = Pair;
+	}
+
+	impl super::CryptoType for Signature {
+		type Pair = Pair;
+	}
+
+	impl super::CryptoType for Pair {
+		type Pair = Pair;
+	}
+
+	impl Public {
+		/// Pair the sr25519 and the ML-DSA-65 public keys.
+		pub fn from_parts(left: &sr25519::Public, right: &dilithium::Public) -> Self {
+			let mut raw = [0u8; PUBLIC_KEY_SERIALIZED_SIZE];
+			raw[..sr25519::PUBLIC_KEY_SERIALIZED_SIZE].copy_from_slice(left.as_ref());
+			raw[sr25519::PUBLIC_KEY_SERIALIZED_SIZE..].copy_from_slice(right.as_ref());
+			Self::unchecked_from(raw)
+		}
+
+		/// The sr25519 and the ML-DSA-65 public keys.
+		pub fn split(&self) -> (sr25519::Public, dilithium::Public) {
+			let (left, right) = self.0.split_at(sr25519::PUBLIC_KEY_SERIALIZED_SIZE);
+			(
+				sr25519::Public::try_from(left).expect("`left` is an sr25519 public key; qed"),
+				dilithium::Public::try_from(right)
+					.expect("`right` is an ML-DSA-65 public key; qed"),
+			)
+		}
+	}
+
+	impl Signature {
+		/// Pair the sr25519 and the ML-DSA-65 signatures.
+		pub fn from_parts(left: &sr25519::Signature, right: &dilithium::Signature) -> Self {
+			let mut raw = [0u8; SIGNATURE_SERIALIZED_SIZE];
+			raw[..sr25519::SIGNATURE_SERIALIZED_SIZE].copy_from_slice(left.as_ref());
+			raw[sr25519::SIGNATURE_SERIALIZED_SIZE..].copy_from_slice(right.as_ref());
+			Self::unchecked_from(raw)
+		}
+
+		/// The sr25519 and the ML-DSA-65 signatures.
+		pub fn split(&self) -> (sr25519::Signature, dilithium::Signature) {
+			let (left, right) = self.0.split_at(sr25519::SIGNATURE_SERIALIZED_SIZE);
+			(
+				sr25519::Signature::try_from(left).expect("`left` is an sr25519 signature; qed"),
+				dilithium::Signature::try_from(right)
+					.expect("`right` is an ML-DSA-65 signature; qed"),
+			)
+		}
+	}
+}
+
 /// Secure seed length.
 ///
 /// Currently only supporting sub-schemes whose seed is a 32-bytes array.
@@ -629,3 +724,63 @@ mod tests {
 		assert_eq!(signature, decoded_signature)
 	}
 }
+
+// Test set exercising the (Sr25519,ML-DSA-65) implementation
+#[cfg(all(test, feature = "dilithium-experimental"))]
+mod sr25519_dilithium_tests {
+	use super::*;
+	use crate::{dilithium, sr25519};
+	use sr25519_dilithium::{Pair, Public, Signature};
+
+	#[test]
+	fn test_length_of_paired_sr25519_and_dilithium_public_key_and_signature_is_correct() {
+		assert_eq!(
+			<Pair as PairT>::Public::LEN,
+			sr25519::PUBLIC_KEY_SERIALIZED_SIZE + dilithium::PUBLIC_KEY_SERIALIZED_SIZE
+		);
+		assert_eq!(
+			<Pair as PairT>::Signature::LEN,
+			sr25519::SIGNATURE_SERIALIZED_SIZE + dilithium::SIGNATURE_SERIALIZED_SIZE
+		);
+	}
+
+	#[test]
+	fn generated_pair_should_work() {
+		let (pair, _) = Pair::generate();
+		let public = pair.public();
+		let message = b"Something important";
+		let signature = pair.sign(&message[..]);
+		assert!(Pair::verify(&signature, &message[..], &public));
+		assert!(!Pair::verify(&signature, b"Something else", &public));
+	}
+
+	#[test]
+	fn both_signatures_are_checked() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let public = pair.public();
+		let message = b"Something important";
+		let signature = pair.sign(&message[..]);
+
+		let (sr25519_public, dilithium_public) = public.split();
+		let (sr25519_signature, dilithium_signature) = signature.split();
+		assert!(sr25519::Pair::verify(&sr25519_signature, &message[..], &sr25519_public));
+		assert!(dilithium::Pair::verify(&dilithium_signature, &message[..], &dilithium_public));
+		assert_eq!(Public::from_parts(&sr25519_public, &dilithium_public), public);
+		assert_eq!(Signature::from_parts(&sr25519_signature, &dilithium_signature), signature);
+
+		for index in [0, sr25519::SIGNATURE_SERIALIZED_SIZE] {
+			let mut tampered = signature;
+			tampered.0[index] ^= 1;
+			assert!(!Pair::verify(&tampered, &message[..], &public));
+		}
+		assert!(!Pair::verify(&Signature::default(), &message[..], &public));
+	}
+
+	#[test]
+	fn hard_derivation_should_work() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let (derived, _) = pair.derive([DeriveJunction::hard(1)].into_iter(), None).unwrap();
+		assert_ne!(derived.public(), pair.public());
+		assert!(pair.derive([DeriveJunction::soft(1)].into_iter(), None).is_err());
+	}
+}
@@ -314,7 +314,19 @@ pub mod vrf {
 
 	/// Transcript ready to be used for VRF related operations.
 	#[derive(Clone)]
-	pub struct VrfTranscript(pub merlin::Transcript);
+	pub struct VrfTranscript(pub merlin::Transcript, VrfTranscriptData);
+
+	/// The label and messages a [`VrfTranscript`] is built from.
+	///
+	/// Unlike the transcript, it can be encoded, e.g. to let a remote signer rebuild the
+	/// transcript.
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+	pub struct VrfTranscriptData {
+		/// The label of the transcript.
+		pub label: Vec<u8>,
+		/// The `(domain, message)` tuples appended to the transcript.
+		pub data: Vec<(Vec<u8>, Vec<u8>)>,
+	}
 
 	impl VrfTranscript {
 		/// Build a new transcript instance.
@@ -323,7 +335,19 @@ pub mod vrf {
 		pub fn new(label: &'static [u8], data: &[(&'static [u8], &[u8])]) -> Self {
 			let mut transcript = merlin::Transcript::new(label);
 			data.iter().for_each(|(l, b)| transcript.append_message(l, b));
-			VrfTranscript(transcript)
+			let recorded = VrfTranscriptData {
+				label: label.to_vec(),
+				data: data.iter().map(|(l, b)| (l.to_vec(), b.to_vec())).collect(),
+			};
+			VrfTranscript(transcript, recorded)
+		}
+
+		/// The label and messages the transcript was built from.
+		///
+		/// Messages appended to the inner transcript after [`VrfTranscript::new`] are not
+		/// included.
+		pub fn data(&self) -> &VrfTranscriptData {
+			&self.1
 		}
 
 		/// Map transcript to `VrfSignData`.
@@ -374,6 +398,16 @@ pub mod vrf {
 			self.extra = Some(extra);
 			self
 		}
+
+		/// The transcript contributing to the VRF output.
+		pub fn transcript(&self) -> &VrfTranscript {
+			&self.transcript
+		}
+
+		/// The extra transcript signed by the VRF, if any.
+		pub fn extra(&self) -> Option<&VrfTranscript> {
+			self.extra.as_ref()
+		}
 	}
 
 	/// VRF signature data
@@ -35,6 +35,8 @@ pub const BLS381: KeyTypeId = KeyTypeId(*b"bls8");
 pub const ECDSA_BLS377: KeyTypeId = KeyTypeId(*b"ecb7");
 /// Key type for (ECDSA,BLS12-381) key pair
 pub const ECDSA_BLS381: KeyTypeId = KeyTypeId(*b"ecb8");
+/// Key type for generic ML-DSA-65 (Dilithium) key.
+pub const DILITHIUM: KeyTypeId = KeyTypeId(*b"mlds");
 
 /// Macro for exporting functions from wasm in with the expected signature for using it with the
 /// wasm executor. This is useful for tests where you need to call a function in wasm.
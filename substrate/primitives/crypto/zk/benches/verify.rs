@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification time of the proofs, as the number of public inputs grows.
+//!
+//! The time without public inputs gives the `base` of the `VerifyCost` of a proof system and
+//! curve, the slope gives its `per_input`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use sp_crypto_zk::{bls12_381, bn254, testing, VerifyError, MAX_PUBLIC_INPUTS};
+
+type Proof = (Vec<u8>, Vec<u8>, Vec<u8>);
+type Verify = fn(Vec<u8>, Vec<u8>, Vec<u8>) -> Result<bool, VerifyError>;
+
+fn bench_verify(c: &mut Criterion, name: &str, prove: fn(&[u64]) -> Proof, verify: Verify) {
+	let mut group = c.benchmark_group(name);
+
+	for inputs in [0, 1, 16, 128, MAX_PUBLIC_INPUTS] {
+		let (vk, proof, public_inputs) = prove(&vec![1; inputs as usize]);
+		group.bench_function(BenchmarkId::new("inputs", inputs), |b| {
+			b.iter(|| {
+				assert_eq!(verify(vk.clone(), proof.clone(), public_inputs.clone()), Ok(true))
+			})
+		});
+	}
+
+	group.finish();
+}
+
+fn bench_bn254(c: &mut Criterion) {
+	type E = ark_bn254::Bn254;
+	bench_verify(
+		c,
+		"bn254_groth16",
+		testing::groth16_proof::<E>,
+		bn254::host_calls::bn254_groth16_verify,
+	);
+	bench_verify(
+		c,
+		"bn254_plonk",
+		testing::plonk_proof::<E>,
+		bn254::host_calls::bn254_plonk_verify,
+	);
+}
+
+fn bench_bls12_381(c: &mut Criterion) {
+	type E = ark_bls12_381::Bls12_381;
+	bench_verify(
+		c,
+		"bls12_381_groth16",
+		testing::groth16_proof::<E>,
+		bls12_381::host_calls::bls12_381_groth16_verify,
+	);
+	bench_verify(
+		c,
+		"bls12_381_plonk",
+		testing::plonk_proof::<E>,
+		bls12_381::host_calls::bls12_381_plonk_verify,
+	);
+}
+
+criterion_group!(benches, bench_bn254, bench_bls12_381);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of zero-knowledge proofs over *BLS12-381*.
+
+use crate::{VerifyCost, VerifyError};
+use alloc::vec::Vec;
+use sp_runtime_interface::runtime_interface;
+
+// The costs are the lowest lines above the mean times measured by the `verify` benchmark,
+// `cargo bench -p sp-crypto-zk --features all-curves --bench verify`, on one vCPU of an Intel
+// Xeon server for 0, 1, 16, 128 and 1024 public inputs:
+// - Groth16: 4.3, 4.7, 7.8, 25.0 and 177.5 ms.
+// - PLONK: 10.7, 10.7, 8.9, 8.8 and 16.7 ms.
+
+/// Cost of [`host_calls::bls12_381_groth16_verify`].
+pub const GROTH16_COST: VerifyCost = VerifyCost { base: 4_800_000_000, per_input: 190_000_000 };
+
+/// Cost of [`host_calls::bls12_381_plonk_verify`].
+pub const PLONK_COST: VerifyCost = VerifyCost { base: 10_800_000_000, per_input: 6_000_000 };
+
+/// Interface to the host functions verifying proofs over *BLS12-381*.
+#[runtime_interface]
+pub trait HostCalls {
+	/// Verify a Groth16 proof over *BLS12-381*.
+	///
+	/// - `vk`: the verifying key, `ark_groth16::VerifyingKey<Bls12_381>`.
+	/// - `proof`: the proof, `ark_groth16::Proof<Bls12_381>`.
+	/// - `public_inputs`: the public inputs, `Vec<Fr>`.
+	///
+	/// All of them compressed with the canonical serialization of arkworks. Returns `Ok(false)`
+	/// if the proof is well formed but invalid. Costs [`GROTH16_COST`].
+	fn bls12_381_groth16_verify(
+		vk: Vec<u8>,
+		proof: Vec<u8>,
+		public_inputs: Vec<u8>,
+	) -> Result<bool, VerifyError> {
+		crate::groth16::verify::<ark_bls12_381::Bls12_381>(&vk, &proof, &public_inputs)
+	}
+
+	/// Verify a PLONK proof over *BLS12-381*, made with the transcript of snarkjs.
+	///
+	/// - `vk`: the verifying key, the number of public inputs and the log2 of the size of the
+	///   domain as `u32`, `k1`, `k2` and the generator of the domain as `Fr`, the commitments
+	///   `Qm`, `Ql`, `Qr`, `Qo`, `Qc`, `S1`, `S2`, `S3` as `G1` and `X_2` as `G2`.
+	/// - `proof`: the proof, the commitments `A`, `B`, `C`, `Z`, `T1`, `T2`, `T3`, `Wxi`, `Wxiw` as
+	///   `G1` and the evaluations `a`, `b`, `c`, `s1`, `s2`, `zw` as `Fr`.
+	/// - `public_inputs`: the public inputs, `Vec<Fr>`.
+	///
+	/// All of them compressed with the canonical serialization of arkworks. Returns `Ok(false)`
+	/// if the proof is well formed but invalid. Costs [`PLONK_COST`].
+	fn bls12_381_plonk_verify(
+		vk: Vec<u8>,
+		proof: Vec<u8>,
+		public_inputs: Vec<u8>,
+	) -> Result<bool, VerifyError> {
+		crate::plonk::verify::<ark_bls12_381::Bls12_381>(&vk, &proof, &public_inputs)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::host_calls;
+	use crate::testing;
+	use ark_bls12_381::Bls12_381;
+
+	#[test]
+	fn groth16_verify_works() {
+		crate::groth16::tests::verify_works::<Bls12_381>();
+	}
+
+	#[test]
+	fn groth16_host_call_works() {
+		let (vk, proof, inputs) = testing::groth16_proof::<Bls12_381>(&[1]);
+		assert_eq!(host_calls::bls12_381_groth16_verify(vk, proof, inputs), Ok(true));
+	}
+
+	#[test]
+	fn plonk_verify_works() {
+		crate::plonk::tests::verify_works::<Bls12_381>();
+		crate::plonk::tests::verify_without_public_inputs_works::<Bls12_381>();
+		crate::plonk::tests::domain_must_match_omega::<Bls12_381>();
+	}
+
+	#[test]
+	fn plonk_host_call_works() {
+		let (vk, proof, inputs) = testing::plonk_proof::<Bls12_381>(&[1]);
+		assert_eq!(host_calls::bls12_381_plonk_verify(vk, proof, inputs), Ok(true));
+	}
+}
@@ -0,0 +1,198 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of Groth16 proofs, generic over the pairing.
+
+use crate::{VerifyError, MAX_PUBLIC_INPUTS};
+use alloc::vec::Vec;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{Field, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// A Groth16 verifying key, laid out as the one of `ark-groth16`.
+struct VerifyingKey<E: Pairing> {
+	alpha_g1: E::G1Affine,
+	beta_g2: E::G2Affine,
+	gamma_g2: E::G2Affine,
+	delta_g2: E::G2Affine,
+	gamma_abc_g1: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+	/// Decode a key for `inputs` public inputs.
+	///
+	/// The number of points of `gamma_abc_g1` is checked before decoding them, as the
+	/// verification is only charged for `inputs`.
+	fn decode(mut bytes: &[u8], inputs: usize) -> Result<Self, VerifyError> {
+		let invalid = |_| VerifyError::InvalidVerifyingKey;
+		let alpha_g1 = CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?;
+		let beta_g2 = CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?;
+		let gamma_g2 = CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?;
+		let delta_g2 = CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?;
+		let len = inputs as u64 + 1;
+		if u64::deserialize_compressed(bytes).map_err(invalid)? != len {
+			return Err(VerifyError::InvalidPublicInputs)
+		}
+		let gamma_abc_g1 = decode_vec(&mut bytes, len).ok_or(VerifyError::InvalidVerifyingKey)?;
+		if !bytes.is_empty() {
+			return Err(VerifyError::InvalidVerifyingKey)
+		}
+		Ok(Self { alpha_g1, beta_g2, gamma_g2, delta_g2, gamma_abc_g1 })
+	}
+}
+
+/// A Groth16 proof, laid out as the one of `ark-groth16`.
+struct Proof<E: Pairing> {
+	a: E::G1Affine,
+	b: E::G2Affine,
+	c: E::G1Affine,
+}
+
+impl<E: Pairing> Proof<E> {
+	fn decode(mut bytes: &[u8]) -> Option<Self> {
+		let proof = Self {
+			a: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			b: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			c: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+		};
+		bytes.is_empty().then_some(proof)
+	}
+}
+
+/// Decode a vector with the canonical serialization, failing early if it has more than `max`
+/// items.
+pub(crate) fn decode_vec<T: CanonicalDeserialize>(bytes: &mut &[u8], max: u64) -> Option<Vec<T>> {
+	let len = u64::deserialize_compressed(&mut *bytes).ok()?;
+	if len > max {
+		return None
+	}
+	(0..len).map(|_| T::deserialize_compressed(&mut *bytes).ok()).collect()
+}
+
+/// Decode the public inputs of a proof, checking their number before decoding anything, as the
+/// cost of a verification grows with it.
+pub(crate) fn decode_public_inputs<F: CanonicalDeserialize>(
+	public_inputs: &[u8],
+) -> Result<Vec<F>, VerifyError> {
+	let len =
+		u64::deserialize_compressed(public_inputs).map_err(|_| VerifyError::InvalidPublicInputs)?;
+	if len > u64::from(MAX_PUBLIC_INPUTS) {
+		return Err(VerifyError::TooManyPublicInputs)
+	}
+	let mut inputs_bytes = public_inputs;
+	decode_vec::<F>(&mut inputs_bytes, len)
+		.filter(|_| inputs_bytes.is_empty())
+		.ok_or(VerifyError::InvalidPublicInputs)
+}
+
+/// Verify a Groth16 `proof` of the `public_inputs` against the verifying key `vk`.
+///
+/// Returns `Ok(false)` if the proof is well formed but invalid.
+pub fn verify<E: Pairing>(
+	vk: &[u8],
+	proof: &[u8],
+	public_inputs: &[u8],
+) -> Result<bool, VerifyError> {
+	let inputs = decode_public_inputs::<E::ScalarField>(public_inputs)?;
+	let vk = VerifyingKey::<E>::decode(vk, inputs.len())?;
+	let proof = Proof::<E>::decode(proof).ok_or(VerifyError::InvalidProof)?;
+
+	let mut prepared_inputs = vk.gamma_abc_g1[0].into_group();
+	for (input, base) in inputs.iter().zip(&vk.gamma_abc_g1[1..]) {
+		prepared_inputs += *base * *input;
+	}
+
+	// e(A, B) = e(alpha, beta) * e(inputs, gamma) * e(C, delta)
+	Ok(E::multi_pairing(
+		[proof.a.into_group(), -vk.alpha_g1.into_group(), -prepared_inputs, -proof.c.into_group()],
+		[proof.b, vk.beta_g2, vk.gamma_g2, vk.delta_g2],
+	)
+	.is_zero())
+}
+
+/// Encoded verifying key, proof and public inputs of a valid proof.
+///
+/// The proof is simulated with the trapdoor of the verifying key instead of proving a circuit,
+/// which only matters for soundness, not for the verification equation.
+pub fn simulated_proof<E: Pairing>(inputs: &[u64]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+	let scalar = |n: u64| E::ScalarField::from(n);
+	let (alpha, beta, gamma, delta) = (scalar(2), scalar(3), scalar(5), scalar(7));
+	let gamma_abc = (0..=inputs.len() as u64).map(|i| scalar(11 + i)).collect::<Vec<_>>();
+	let (a, b) = (scalar(13), scalar(17));
+
+	let prepared_inputs = inputs
+		.iter()
+		.zip(&gamma_abc[1..])
+		.fold(gamma_abc[0], |acc, (input, base)| acc + scalar(*input) * base);
+	let c =
+		(a * b - alpha * beta - prepared_inputs * gamma) * delta.inverse().expect("Non zero; qed");
+
+	let g1 = E::G1Affine::generator();
+	let g2 = E::G2Affine::generator();
+	let mut vk = Vec::new();
+	encode(&mut vk, (g1 * alpha).into_affine());
+	encode(&mut vk, (g2 * beta).into_affine());
+	encode(&mut vk, (g2 * gamma).into_affine());
+	encode(&mut vk, (g2 * delta).into_affine());
+	encode(&mut vk, gamma_abc.iter().map(|s| (g1 * *s).into_affine()).collect::<Vec<_>>());
+
+	let mut proof = Vec::new();
+	encode(&mut proof, (g1 * a).into_affine());
+	encode(&mut proof, (g2 * b).into_affine());
+	encode(&mut proof, (g1 * c).into_affine());
+
+	(vk, proof, encode_public_inputs::<E>(inputs))
+}
+
+/// Encoded public inputs, as expected by the verifiers.
+pub(crate) fn encode_public_inputs<E: Pairing>(inputs: &[u64]) -> Vec<u8> {
+	let mut public_inputs = Vec::new();
+	encode(&mut public_inputs, inputs.iter().map(|i| E::ScalarField::from(*i)).collect::<Vec<_>>());
+	public_inputs
+}
+
+/// Append the compressed canonical serialization of `value` to `bytes`.
+pub(crate) fn encode<T: CanonicalSerialize>(bytes: &mut Vec<u8>, value: T) {
+	value.serialize_compressed(bytes).expect("Writing to a vector can't fail; qed");
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+
+	pub(crate) fn verify_works<E: Pairing>() {
+		let (vk, proof, inputs) = simulated_proof::<E>(&[1, 2, 3]);
+		assert_eq!(verify::<E>(&vk, &proof, &inputs), Ok(true));
+
+		let (_, _, other_inputs) = simulated_proof::<E>(&[1, 2, 4]);
+		assert_eq!(verify::<E>(&vk, &proof, &other_inputs), Ok(false));
+
+		let (_, _, fewer_inputs) = simulated_proof::<E>(&[1, 2]);
+		assert_eq!(verify::<E>(&vk, &proof, &fewer_inputs), Err(VerifyError::InvalidPublicInputs));
+		assert_eq!(
+			verify::<E>(&vk[..vk.len() - 1], &proof, &inputs),
+			Err(VerifyError::InvalidVerifyingKey),
+		);
+		assert_eq!(verify::<E>(&vk, &proof[..10], &inputs), Err(VerifyError::InvalidProof));
+
+		let too_many_inputs = encode_public_inputs::<E>(&[0; MAX_PUBLIC_INPUTS as usize + 1]);
+		assert_eq!(
+			verify::<E>(&vk, &proof, &too_many_inputs),
+			Err(VerifyError::TooManyPublicInputs),
+		);
+	}
+}
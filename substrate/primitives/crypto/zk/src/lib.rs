@@ -0,0 +1,109 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metered host functions for the verification of zero-knowledge proofs.
+//!
+//! Verifying a proof in Wasm costs several hundred milliseconds, which makes rollup-style pallets
+//! impractical. The host functions of this crate verify [Groth16](https://eprint.iacr.org/2016/260)
+//! and [PLONK](https://eprint.iacr.org/2019/953) proofs natively, over the curve enabled by the
+//! feature of the same name:
+//!
+//! - `bn254`: [`bn254::host_calls::bn254_groth16_verify`] and
+//!   [`bn254::host_calls::bn254_plonk_verify`],
+//! - `bls12-381`: [`bls12_381::host_calls::bls12_381_groth16_verify`] and
+//!   [`bls12_381::host_calls::bls12_381_plonk_verify`].
+//!
+//! Verifying keys, proofs and public inputs are passed with the compressed canonical
+//! serialization of arkworks. Groth16 uses the encoding of `ark-groth16`, PLONK the verifier of
+//! snarkjs, whose transcript the proofs must be made with. The number of public inputs is bounded
+//! by [`MAX_PUBLIC_INPUTS`], so that the cost of a call is bounded too: runtimes charge the weight
+//! given by [`VerifyCost::weight`] of the proof system and curve before verifying a proof.
+//!
+//! The host functions need to be registered in the executor of the node, e.g. by adding
+//! `sp_crypto_zk::bn254::host_calls::HostFunctions` to its host functions.
+
+#![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "bls12-381")]
+pub mod bls12_381;
+#[cfg(feature = "bn254")]
+pub mod bn254;
+
+#[cfg(all(feature = "std", feature = "common"))]
+mod groth16;
+#[cfg(all(feature = "std", feature = "common"))]
+mod plonk;
+
+/// Simulated proofs, for tests and benchmarks.
+#[cfg(all(feature = "std", feature = "common"))]
+pub mod testing {
+	pub use crate::{
+		groth16::simulated_proof as groth16_proof, plonk::simulated_proof as plonk_proof,
+	};
+}
+
+use codec::{Decode, Encode};
+use sp_weights::Weight;
+
+/// Maximum number of public inputs of a proof.
+pub const MAX_PUBLIC_INPUTS: u32 = 1024;
+
+/// Errors of the verification of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum VerifyError {
+	/// The verifying key can't be decoded.
+	InvalidVerifyingKey,
+	/// The proof can't be decoded.
+	InvalidProof,
+	/// The public inputs can't be decoded or don't match the verifying key.
+	InvalidPublicInputs,
+	/// There are more than [`MAX_PUBLIC_INPUTS`] public inputs.
+	TooManyPublicInputs,
+}
+
+/// Cost model of the verification of a proof.
+///
+/// The cost is dominated by the final pairing check and grows linearly with the number of public
+/// inputs: each one requires a scalar multiplication and a point of the verifying key to be
+/// decoded for Groth16, and the evaluation of a Lagrange polynomial for PLONK.
+///
+/// The constants of the proof systems and curves are derived from the `verify` benchmark of this
+/// crate, which measures the verification time of each of them as the number of public inputs
+/// grows. The machine and the measurements are noted next to the constants: run the benchmark
+/// with `cargo bench -p sp-crypto-zk --features all-curves` to derive them for another machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyCost {
+	/// Reference time of a verification without public inputs, in picoseconds.
+	pub base: u64,
+	/// Reference time per public input, in picoseconds.
+	pub per_input: u64,
+}
+
+impl VerifyCost {
+	/// Weight of the verification of a proof with `public_inputs` public inputs.
+	///
+	/// Calls with more than [`MAX_PUBLIC_INPUTS`] inputs fail early, they are charged as if they
+	/// had the maximum number of inputs.
+	pub const fn weight(&self, public_inputs: u32) -> Weight {
+		let inputs =
+			if public_inputs > MAX_PUBLIC_INPUTS { MAX_PUBLIC_INPUTS } else { public_inputs };
+		Weight::from_parts(self.base + self.per_input * inputs as u64, 0)
+	}
+}
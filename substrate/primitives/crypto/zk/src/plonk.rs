@@ -0,0 +1,453 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of PLONK proofs, generic over the pairing.
+//!
+//! The verifier is the one of snarkjs: KZG commitments, the linearisation of the
+//! [PLONK paper](https://eprint.iacr.org/2019/953) where the evaluation of the linearisation
+//! polynomial is folded into the batched opening, and a Keccak-256 transcript hashing points by
+//! their uncompressed big endian coordinates and scalars in big endian.
+
+use crate::{
+	groth16::{decode_public_inputs, encode},
+	VerifyError, MAX_PUBLIC_INPUTS,
+};
+use alloc::{vec, vec::Vec};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, FftField, Field, One, PrimeField, Zero};
+use ark_serialize::CanonicalDeserialize;
+
+/// A PLONK verifying key, laid out as the one of snarkjs.
+struct VerifyingKey<E: Pairing> {
+	public_inputs: u32,
+	/// Log2 of the size of the evaluation domain.
+	power: u32,
+	k1: E::ScalarField,
+	k2: E::ScalarField,
+	/// Generator of the evaluation domain.
+	omega: E::ScalarField,
+	qm: E::G1Affine,
+	ql: E::G1Affine,
+	qr: E::G1Affine,
+	qo: E::G1Affine,
+	qc: E::G1Affine,
+	s1: E::G1Affine,
+	s2: E::G1Affine,
+	s3: E::G1Affine,
+	x_2: E::G2Affine,
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+	/// Decode a key for `inputs` public inputs.
+	///
+	/// The number of public inputs of the key is checked before decoding its points, as the
+	/// verification is only charged for `inputs`.
+	fn decode(mut bytes: &[u8], inputs: usize) -> Result<Self, VerifyError> {
+		let invalid = |_| VerifyError::InvalidVerifyingKey;
+		let public_inputs = u32::deserialize_compressed(&mut bytes).map_err(invalid)?;
+		if public_inputs as usize != inputs {
+			return Err(VerifyError::InvalidPublicInputs)
+		}
+		let key = Self {
+			public_inputs,
+			power: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			k1: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			k2: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			omega: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			qm: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			ql: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			qr: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			qo: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			qc: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			s1: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			s2: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			s3: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+			x_2: CanonicalDeserialize::deserialize_compressed(&mut bytes).map_err(invalid)?,
+		};
+		if !bytes.is_empty() ||
+			key.public_inputs > MAX_PUBLIC_INPUTS ||
+			key.power > E::ScalarField::TWO_ADICITY ||
+			u64::from(key.public_inputs) > 1 << key.power
+		{
+			return Err(VerifyError::InvalidVerifyingKey)
+		}
+		// `omega` must generate a domain of `2^power` elements.
+		let order = |power: u32| key.omega.pow([1u64 << power]).is_one();
+		(order(key.power) && (key.power == 0 || !order(key.power - 1)))
+			.then_some(key)
+			.ok_or(VerifyError::InvalidVerifyingKey)
+	}
+}
+
+/// A PLONK proof, laid out as the one of snarkjs.
+struct Proof<E: Pairing> {
+	a: E::G1Affine,
+	b: E::G1Affine,
+	c: E::G1Affine,
+	z: E::G1Affine,
+	t1: E::G1Affine,
+	t2: E::G1Affine,
+	t3: E::G1Affine,
+	w_xi: E::G1Affine,
+	w_xiw: E::G1Affine,
+	eval_a: E::ScalarField,
+	eval_b: E::ScalarField,
+	eval_c: E::ScalarField,
+	eval_s1: E::ScalarField,
+	eval_s2: E::ScalarField,
+	eval_zw: E::ScalarField,
+}
+
+impl<E: Pairing> Proof<E> {
+	fn decode(mut bytes: &[u8]) -> Option<Self> {
+		let proof = Self {
+			a: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			b: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			c: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			z: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			t1: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			t2: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			t3: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			w_xi: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			w_xiw: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			eval_a: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			eval_b: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			eval_c: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			eval_s1: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			eval_s2: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+			eval_zw: CanonicalDeserialize::deserialize_compressed(&mut bytes).ok()?,
+		};
+		bytes.is_empty().then_some(proof)
+	}
+}
+
+/// The Keccak-256 transcript of snarkjs, reset after each challenge.
+#[derive(Default)]
+struct Transcript(Vec<u8>);
+
+impl Transcript {
+	fn scalar<F: PrimeField>(&mut self, scalar: &F) {
+		self.0.extend(scalar.into_bigint().to_bytes_be());
+	}
+
+	fn point<A: AffineRepr>(&mut self, point: &A) {
+		let zero = A::BaseField::zero();
+		let start = self.0.len();
+		let (x, y) = point.xy().unwrap_or((&zero, &zero));
+		x.to_base_prime_field_elements().for_each(|x| self.scalar(&x));
+		y.to_base_prime_field_elements().for_each(|y| self.scalar(&y));
+		if point.is_zero() {
+			self.0[start] |= 0x40;
+		}
+	}
+
+	fn challenge<F: PrimeField>(&mut self) -> F {
+		let challenge = F::from_be_bytes_mod_order(&sp_crypto_hashing::keccak_256(&self.0));
+		self.0.clear();
+		challenge
+	}
+}
+
+/// Challenges of the verifier.
+#[derive(Clone, Copy)]
+struct Challenges<F> {
+	beta: F,
+	gamma: F,
+	alpha: F,
+	xi: F,
+	v: F,
+	u: F,
+}
+
+fn challenges<E: Pairing>(
+	vk: &VerifyingKey<E>,
+	proof: &Proof<E>,
+	inputs: &[E::ScalarField],
+) -> Challenges<E::ScalarField> {
+	let mut transcript = Transcript::default();
+	for point in [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3] {
+		transcript.point(&point);
+	}
+	inputs.iter().for_each(|input| transcript.scalar(input));
+	for point in [proof.a, proof.b, proof.c] {
+		transcript.point(&point);
+	}
+	let beta: E::ScalarField = transcript.challenge();
+
+	transcript.scalar(&beta);
+	let gamma: E::ScalarField = transcript.challenge();
+
+	transcript.scalar(&beta);
+	transcript.scalar(&gamma);
+	transcript.point(&proof.z);
+	let alpha: E::ScalarField = transcript.challenge();
+
+	transcript.scalar(&alpha);
+	for point in [proof.t1, proof.t2, proof.t3] {
+		transcript.point(&point);
+	}
+	let xi: E::ScalarField = transcript.challenge();
+
+	transcript.scalar(&xi);
+	for eval in
+		[proof.eval_a, proof.eval_b, proof.eval_c, proof.eval_s1, proof.eval_s2, proof.eval_zw]
+	{
+		transcript.scalar(&eval);
+	}
+	let v = transcript.challenge();
+
+	transcript.point(&proof.w_xi);
+	transcript.point(&proof.w_xiw);
+	let u = transcript.challenge();
+
+	Challenges { beta, gamma, alpha, xi, v, u }
+}
+
+/// Points and scalars of `[F]_1 - [E]_1`, the commitment minus the evaluation of the batched
+/// opening of the proof at `xi` and `xi * omega`.
+///
+/// Returns `None` if `xi` is in the evaluation domain.
+fn batched_opening<E: Pairing>(
+	vk: &VerifyingKey<E>,
+	proof: &Proof<E>,
+	inputs: &[E::ScalarField],
+	challenges: &Challenges<E::ScalarField>,
+) -> Option<Vec<(E::G1Affine, E::ScalarField)>> {
+	let Challenges { beta, gamma, alpha, xi, v, u } = *challenges;
+	let (a, b, c) = (proof.eval_a, proof.eval_b, proof.eval_c);
+	let (s1, s2, zw) = (proof.eval_s1, proof.eval_s2, proof.eval_zw);
+
+	// Evaluations of the vanishing polynomial and of the Lagrange polynomials of the public
+	// inputs, at least of the first one.
+	let domain_size = 1u64 << vk.power;
+	let xi_n = xi.pow([domain_size]);
+	let zh = xi_n - E::ScalarField::one();
+	let zh_over_n = zh * E::ScalarField::from(domain_size).inverse()?;
+	let mut lagrange = Vec::with_capacity(inputs.len().max(1));
+	let mut w = E::ScalarField::one();
+	for _ in 0..inputs.len().max(1) {
+		lagrange.push(w * zh_over_n * (xi - w).inverse()?);
+		w *= vk.omega;
+	}
+	let l1 = lagrange[0];
+	let pi = -inputs
+		.iter()
+		.zip(&lagrange)
+		.map(|(input, l)| *input * l)
+		.sum::<E::ScalarField>();
+
+	let alpha2 = alpha.square();
+	let permutation = (a + beta * s1 + gamma) * (b + beta * s2 + gamma);
+	let r0 = pi - l1 * alpha2 - permutation * (c + gamma) * zw * alpha;
+
+	let beta_xi = beta * xi;
+	let (k1, k2) = (beta_xi * vk.k1, beta_xi * vk.k2);
+	let wiring = (a + beta_xi + gamma) * (b + k1 + gamma) * (c + k2 + gamma);
+	let z = wiring * alpha + l1 * alpha2 + u;
+	let s3 = -permutation * alpha * beta * zw;
+
+	let v2 = v.square();
+	let v3 = v2 * v;
+	let v4 = v3 * v;
+	let v5 = v4 * v;
+	let e = -r0 + v * a + v2 * b + v3 * c + v4 * s1 + v5 * s2 + u * zw;
+
+	Some(vec![
+		(vk.qm, a * b),
+		(vk.ql, a),
+		(vk.qr, b),
+		(vk.qo, c),
+		(vk.qc, E::ScalarField::one()),
+		(proof.z, z),
+		(vk.s3, s3),
+		(proof.t1, -zh),
+		(proof.t2, -zh * xi_n),
+		(proof.t3, -zh * xi_n.square()),
+		(proof.a, v),
+		(proof.b, v2),
+		(proof.c, v3),
+		(vk.s1, v4),
+		(vk.s2, v5),
+		(E::G1Affine::generator(), -e),
+	])
+}
+
+/// Verify a PLONK `proof` of the `public_inputs` against the verifying key `vk`.
+///
+/// Returns `Ok(false)` if the proof is well formed but invalid.
+pub fn verify<E: Pairing>(
+	vk: &[u8],
+	proof: &[u8],
+	public_inputs: &[u8],
+) -> Result<bool, VerifyError> {
+	let inputs = decode_public_inputs::<E::ScalarField>(public_inputs)?;
+	let vk = VerifyingKey::<E>::decode(vk, inputs.len())?;
+	let proof = Proof::<E>::decode(proof).ok_or(VerifyError::InvalidProof)?;
+
+	let challenges = challenges(&vk, &proof, &inputs);
+	let Some(opening) = batched_opening(&vk, &proof, &inputs, &challenges) else {
+		return Ok(false)
+	};
+	let opening = opening.into_iter().map(|(point, scalar)| point * scalar).sum::<E::G1>();
+
+	// e(W_xi + u * W_xiw, [x]_2) = e(xi * W_xi + u * xi * omega * W_xiw + F - E, [1]_2)
+	let Challenges { xi, u, .. } = challenges;
+	let w_xi = proof.w_xi.into_group();
+	Ok(E::multi_pairing(
+		[-(w_xi + proof.w_xiw * u), w_xi * xi + proof.w_xiw * (u * xi * vk.omega) + opening],
+		[vk.x_2, E::G2Affine::generator()],
+	)
+	.is_zero())
+}
+
+/// Encoded verifying key, proof and public inputs of a valid proof.
+///
+/// The proof is simulated with the trapdoor of the verifying key instead of proving a circuit,
+/// which only matters for soundness, not for the verification equation.
+pub fn simulated_proof<E: Pairing>(inputs: &[u64]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+	let scalar = |n: u64| E::ScalarField::from(n);
+	let point = |n: u64| (E::G1Affine::generator() * scalar(n)).into_affine();
+	let tau = scalar(1_000_003);
+
+	let power = inputs.len().max(1).next_power_of_two().trailing_zeros();
+	let omega = E::ScalarField::get_root_of_unity(1 << power).expect("Small domain; qed");
+	let vk = VerifyingKey::<E> {
+		public_inputs: inputs.len() as u32,
+		power,
+		k1: scalar(2),
+		k2: scalar(3),
+		omega,
+		qm: point(2),
+		ql: point(3),
+		qr: point(4),
+		qo: point(5),
+		qc: point(6),
+		s1: point(7),
+		s2: point(8),
+		s3: point(9),
+		x_2: (E::G2Affine::generator() * tau).into_affine(),
+	};
+	let mut proof = Proof::<E> {
+		a: point(10),
+		b: point(11),
+		c: point(12),
+		z: point(13),
+		t1: point(14),
+		t2: point(15),
+		t3: point(16),
+		w_xi: E::G1Affine::zero(),
+		w_xiw: E::G1Affine::zero(),
+		eval_a: scalar(20),
+		eval_b: scalar(21),
+		eval_c: scalar(22),
+		eval_s1: scalar(23),
+		eval_s2: scalar(24),
+		eval_zw: scalar(25),
+	};
+	let inputs_scalars = inputs.iter().map(|input| scalar(*input)).collect::<Vec<_>>();
+
+	// `[F]_1 - [E]_1` is linear in `u`, its discrete logarithm is split in the part opened at `xi`
+	// and the one opened at `xi * omega`, before `u` is known.
+	let challenges = challenges(&vk, &proof, &inputs_scalars);
+	let log = |point: &E::G1Affine| {
+		(1..=16)
+			.map(scalar)
+			.find(|s| (E::G1Affine::generator() * *s).into_affine() == *point)
+	};
+	let opening = |u| {
+		batched_opening(&vk, &proof, &inputs_scalars, &Challenges { u, ..challenges })
+			.expect("`xi` is a random challenge; qed")
+			.iter()
+			.map(|(point, s)| log(point).expect("Points of the simulation; qed") * s)
+			.sum::<E::ScalarField>()
+	};
+	let at_xi = opening(scalar(0));
+	let at_xi_omega = opening(scalar(1)) - at_xi;
+	let w_xi = at_xi * (tau - challenges.xi).inverse().expect("Random challenge; qed");
+	let w_xiw =
+		at_xi_omega * (tau - challenges.xi * omega).inverse().expect("Random challenge; qed");
+	proof.w_xi = (E::G1Affine::generator() * w_xi).into_affine();
+	proof.w_xiw = (E::G1Affine::generator() * w_xiw).into_affine();
+
+	let mut vk_bytes = Vec::new();
+	encode(&mut vk_bytes, vk.public_inputs);
+	encode(&mut vk_bytes, vk.power);
+	for s in [vk.k1, vk.k2, vk.omega] {
+		encode(&mut vk_bytes, s);
+	}
+	for p in [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3] {
+		encode(&mut vk_bytes, p);
+	}
+	encode(&mut vk_bytes, vk.x_2);
+
+	let mut proof_bytes = Vec::new();
+	for p in [proof.a, proof.b, proof.c, proof.z, proof.t1, proof.t2, proof.t3] {
+		encode(&mut proof_bytes, p);
+	}
+	encode(&mut proof_bytes, proof.w_xi);
+	encode(&mut proof_bytes, proof.w_xiw);
+	for s in [proof.eval_a, proof.eval_b, proof.eval_c, proof.eval_s1, proof.eval_s2, proof.eval_zw]
+	{
+		encode(&mut proof_bytes, s);
+	}
+
+	(vk_bytes, proof_bytes, crate::groth16::encode_public_inputs::<E>(inputs))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::groth16::encode_public_inputs;
+
+	pub(crate) fn verify_works<E: Pairing>() {
+		let (vk, proof, inputs) = simulated_proof::<E>(&[1, 2, 3]);
+		assert_eq!(verify::<E>(&vk, &proof, &inputs), Ok(true));
+
+		assert_eq!(verify::<E>(&vk, &proof, &encode_public_inputs::<E>(&[1, 2, 4])), Ok(false));
+		let mut other_proof = proof.clone();
+		*other_proof.last_mut().unwrap() ^= 1;
+		assert_eq!(verify::<E>(&vk, &other_proof, &inputs), Ok(false));
+
+		assert_eq!(
+			verify::<E>(&vk, &proof, &encode_public_inputs::<E>(&[1, 2])),
+			Err(VerifyError::InvalidPublicInputs),
+		);
+		assert_eq!(
+			verify::<E>(&vk[..vk.len() - 1], &proof, &inputs),
+			Err(VerifyError::InvalidVerifyingKey),
+		);
+		assert_eq!(verify::<E>(&vk, &proof[..10], &inputs), Err(VerifyError::InvalidProof));
+
+		let too_many_inputs = encode_public_inputs::<E>(&[0; MAX_PUBLIC_INPUTS as usize + 1]);
+		assert_eq!(
+			verify::<E>(&vk, &proof, &too_many_inputs),
+			Err(VerifyError::TooManyPublicInputs),
+		);
+	}
+
+	pub(crate) fn verify_without_public_inputs_works<E: Pairing>() {
+		let (vk, proof, inputs) = simulated_proof::<E>(&[]);
+		assert_eq!(verify::<E>(&vk, &proof, &inputs), Ok(true));
+	}
+
+	pub(crate) fn domain_must_match_omega<E: Pairing>() {
+		let (mut vk, proof, inputs) = simulated_proof::<E>(&[1, 2, 3]);
+		// Doubling the domain keeps `omega`, which only generates half of it.
+		vk[4] += 1;
+		assert_eq!(verify::<E>(&vk, &proof, &inputs), Err(VerifyError::InvalidVerifyingKey));
+	}
+}
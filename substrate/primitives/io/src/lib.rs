@@ -110,6 +110,9 @@ use sp_core::{
 #[cfg(feature = "bls-experimental")]
 use sp_core::{bls381, ecdsa_bls381};
 
+#[cfg(feature = "dilithium-experimental")]
+use sp_core::dilithium;
+
 #[cfg(feature = "std")]
 use sp_trie::{LayoutV0, LayoutV1, TrieConfiguration};
 
@@ -1238,6 +1241,37 @@ pub trait Crypto {
 			.expect("`ecdsa_bls381_generate` failed")
 	}
 
+	/// Verify a `bls12-381` aggregate signature of `msg` by all of `pub_keys`.
+	///
+	/// The public keys must have been checked to be owned by their signers, e.g. by a proof of
+	/// possession when they were registered.
+	///
+	/// Returns `true` when the verification was successful.
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_aggregate_verify(
+		sig: &bls381::AggregateSignature,
+		msg: &[u8],
+		pub_keys: Vec<bls381::Public>,
+	) -> bool {
+		bls381::aggregate_verify(sig, msg, &pub_keys)
+	}
+
+	/// Verify a `bls12-381` signature of `msg` by the share `index` of a threshold key.
+	///
+	/// The threshold key is given by the `commitments` to the coefficients of its secret
+	/// polynomial, ordered from the constant coefficient up. Share indices start at `1`.
+	///
+	/// Returns `true` when the verification was successful.
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_threshold_share_verify(
+		sig: &bls381::ShareSignature,
+		msg: &[u8],
+		commitments: Vec<bls381::ThresholdCommitment>,
+		index: u32,
+	) -> bool {
+		bls381::threshold_share_verify(sig, msg, &commitments, index)
+	}
+
 	/// Generate a `bandersnatch` key pair for the given key type using an optional
 	/// `seed` and store it in the keystore.
 	///
@@ -1256,6 +1290,59 @@ pub trait Crypto {
 			.bandersnatch_generate_new(id, seed)
 			.expect("`bandernatch_generate` failed")
 	}
+
+	/// Returns all `dilithium` public keys for the given key id from the keystore.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_public_keys(&mut self, id: KeyTypeId) -> Vec<dilithium::Public> {
+		self.extension::<KeystoreExt>()
+			.expect("No `keystore` associated for the current context!")
+			.dilithium_public_keys(id)
+	}
+
+	/// Generate a `dilithium` key for the given key type using an optional `seed` and
+	/// store it in the keystore.
+	///
+	/// The `seed` needs to be a valid utf8.
+	///
+	/// Returns the public key.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_generate(&mut self, id: KeyTypeId, seed: Option<Vec<u8>>) -> dilithium::Public {
+		let seed = seed.as_ref().map(|s| std::str::from_utf8(s).expect("Seed is valid utf8!"));
+		self.extension::<KeystoreExt>()
+			.expect("No `keystore` associated for the current context!")
+			.dilithium_generate_new(id, seed)
+			.expect("`dilithium_generate` failed")
+	}
+
+	/// Sign the given `msg` with the `dilithium` key that corresponds to the given public key and
+	/// key type in the keystore.
+	///
+	/// Returns the signature.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_sign(
+		&mut self,
+		id: KeyTypeId,
+		pub_key: &dilithium::Public,
+		msg: &[u8],
+	) -> Option<dilithium::Signature> {
+		self.extension::<KeystoreExt>()
+			.expect("No `keystore` associated for the current context!")
+			.dilithium_sign(id, pub_key, msg)
+			.ok()
+			.flatten()
+	}
+
+	/// Verify a `dilithium` signature.
+	///
+	/// Returns `true` when the verification was successful.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_verify(
+		sig: &dilithium::Signature,
+		msg: &[u8],
+		pub_key: &dilithium::Public,
+	) -> bool {
+		dilithium::Pair::verify(sig, msg, pub_key)
+	}
 }
 
 /// Interface that provides functions for hashing with different algorithms.
@@ -1584,6 +1671,27 @@ pub trait PanicHandler {
 	}
 }
 
+/// Sink handling the log messages of the runtime on the host, instead of the `log` crate.
+#[cfg(feature = "std")]
+pub trait RuntimeLogSink: Send + Sync {
+	/// Handle a log message of the runtime.
+	fn log(&self, level: log::Level, target: &str, message: &str);
+
+	/// The most verbose level of the messages handled by the sink.
+	fn max_level(&self) -> log::LevelFilter;
+}
+
+#[cfg(feature = "std")]
+static RUNTIME_LOG_SINK: std::sync::OnceLock<Box<dyn RuntimeLogSink>> = std::sync::OnceLock::new();
+
+/// Route the log messages of the runtime to `sink`.
+///
+/// The sink can only be set once per process. Returns `false` if a sink was already set.
+#[cfg(feature = "std")]
+pub fn set_runtime_log_sink(sink: Box<dyn RuntimeLogSink>) -> bool {
+	RUNTIME_LOG_SINK.set(sink).is_ok()
+}
+
 /// Interface that provides functions for logging from within the runtime.
 #[runtime_interface]
 pub trait Logging {
@@ -1595,13 +1703,16 @@ pub trait Logging {
 	/// Instead of using directly, prefer setting up `RuntimeLogger` and using `log` macros.
 	fn log(level: LogLevel, target: &str, message: &[u8]) {
 		if let Ok(message) = std::str::from_utf8(message) {
-			log::log!(target: target, log::Level::from(level), "{}", message)
+			match RUNTIME_LOG_SINK.get() {
+				Some(sink) => sink.log(level.into(), target, message),
+				None => log::log!(target: target, log::Level::from(level), "{}", message),
+			}
 		}
 	}
 
 	/// Returns the max log level used by the host.
 	fn max_level() -> LogLevelFilter {
-		log::max_level().into()
+		RUNTIME_LOG_SINK.get().map_or_else(log::max_level, |sink| sink.max_level()).into()
 	}
 }
 
@@ -26,6 +26,8 @@ pub mod testing;
 
 #[cfg(feature = "bandersnatch-experimental")]
 use sp_core::bandersnatch;
+#[cfg(feature = "dilithium-experimental")]
+use sp_core::dilithium;
 #[cfg(feature = "bls-experimental")]
 use sp_core::{bls381, ecdsa_bls381};
 use sp_core::{
@@ -62,6 +64,17 @@ impl core::fmt::Display for Error {
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+/// When a key was generated and when it last signed, in milliseconds since the Unix epoch.
+///
+/// Each time is `None` if it isn't known to the keystore.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyUsage {
+	/// The time at which the key was generated or inserted.
+	pub generated: Option<u64>,
+	/// The time at which the key last produced a signature.
+	pub last_signed: Option<u64>,
+}
+
 /// Something that generates, stores and provides access to secret keys.
 pub trait Keystore: Send + Sync {
 	/// Returns all the sr25519 public keys for the given key type.
@@ -356,6 +369,37 @@ pub trait Keystore: Send + Sync {
 		msg: &[u8],
 	) -> Result<Option<ecdsa_bls381::Signature>, Error>;
 
+	/// Returns all ML-DSA-65 (Dilithium) public keys for the given key type.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_public_keys(&self, key_type: KeyTypeId) -> Vec<dilithium::Public>;
+
+	/// Generate a new ML-DSA-65 (Dilithium) key pair for the given key type and an optional seed.
+	///
+	/// Returns a `dilithium::Public` key of the generated key pair or an `Err` if
+	/// something failed during key generation.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<dilithium::Public, Error>;
+
+	/// Generate an ML-DSA-65 (Dilithium) signature for a given message.
+	///
+	/// Receives [`KeyTypeId`] and a [`dilithium::Public`] key to be able to map
+	/// them to a private key that exists in the keystore.
+	///
+	/// Returns a [`dilithium::Signature`] or `None` in case the given `key_type`
+	/// and `public` combination doesn't exist in the keystore.
+	/// An `Err` will be returned if generating the signature itself failed.
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &dilithium::Public,
+		msg: &[u8],
+	) -> Result<Option<dilithium::Signature>, Error>;
+
 	/// Insert a new secret key.
 	fn insert(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()>;
 
@@ -369,6 +413,14 @@ pub trait Keystore: Send + Sync {
 	/// Returns `true` iff all private keys could be found.
 	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool;
 
+	/// Returns when the key with the given key type and raw public key was generated and when it
+	/// last signed.
+	///
+	/// The default implementation doesn't keep track of either.
+	fn key_usage(&self, _key_type: KeyTypeId, _public: &[u8]) -> KeyUsage {
+		KeyUsage::default()
+	}
+
 	/// Convenience method to sign a message using the given key type and a raw public key
 	/// for secret lookup.
 	///
@@ -381,6 +433,7 @@ pub trait Keystore: Send + Sync {
 	/// - bandersnatch
 	/// - bls381
 	/// - (ecdsa,bls381) paired keys
+	/// - dilithium
 	///
 	/// To support more schemes you can overwrite this method.
 	///
@@ -430,6 +483,12 @@ pub trait Keystore: Send + Sync {
 					.map_err(|_| Error::ValidationError("Invalid public key format".into()))?;
 				self.ecdsa_bls381_sign(id, &public, msg)?.map(|s| s.encode())
 			},
+			#[cfg(feature = "dilithium-experimental")]
+			dilithium::CRYPTO_ID => {
+				let public = dilithium::Public::from_slice(public)
+					.map_err(|_| Error::ValidationError("Invalid public key format".into()))?;
+				self.dilithium_sign(id, &public, msg)?.map(|s| s.encode())
+			},
 			_ => return Err(Error::KeyNotSupported(id)),
 		};
 		Ok(signature)
@@ -640,6 +699,30 @@ impl<T: Keystore + ?Sized> Keystore for Arc<T> {
 		(**self).ecdsa_bls381_sign_with_keccak256(key_type, public, msg)
 	}
 
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_public_keys(&self, key_type: KeyTypeId) -> Vec<dilithium::Public> {
+		(**self).dilithium_public_keys(key_type)
+	}
+
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<dilithium::Public, Error> {
+		(**self).dilithium_generate_new(key_type, seed)
+	}
+
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &dilithium::Public,
+		msg: &[u8],
+	) -> Result<Option<dilithium::Signature>, Error> {
+		(**self).dilithium_sign(key_type, public, msg)
+	}
+
 	fn insert(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
 		(**self).insert(key_type, suri, public)
 	}
@@ -651,6 +734,10 @@ impl<T: Keystore + ?Sized> Keystore for Arc<T> {
 	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
 		(**self).has_keys(public_keys)
 	}
+
+	fn key_usage(&self, key_type: KeyTypeId, public: &[u8]) -> KeyUsage {
+		(**self).key_usage(key_type, public)
+	}
 }
 
 /// A shared pointer to a keystore implementation.
@@ -686,3 +773,9 @@ sp_core::generate_feature_enabled_macro!(
 	feature = "bls-experimental",
 	$
 );
+
+sp_core::generate_feature_enabled_macro!(
+	dilithium_experimental_enabled,
+	feature = "dilithium-experimental",
+	$
+);
@@ -21,6 +21,8 @@ use crate::{Error, Keystore, KeystorePtr};
 
 #[cfg(feature = "bandersnatch-experimental")]
 use sp_core::bandersnatch;
+#[cfg(feature = "dilithium-experimental")]
+use sp_core::dilithium;
 #[cfg(feature = "bls-experimental")]
 use sp_core::{bls381, ecdsa_bls381, KeccakHasher};
 use sp_core::{
@@ -335,6 +337,30 @@ impl Keystore for MemoryKeystore {
 		Ok(sig)
 	}
 
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_public_keys(&self, key_type: KeyTypeId) -> Vec<dilithium::Public> {
+		self.public_keys::<dilithium::Pair>(key_type)
+	}
+
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<dilithium::Public, Error> {
+		self.generate_new::<dilithium::Pair>(key_type, seed)
+	}
+
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &dilithium::Public,
+		msg: &[u8],
+	) -> Result<Option<dilithium::Signature>, Error> {
+		self.sign::<dilithium::Pair>(key_type, public, msg)
+	}
+
 	fn insert(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
 		self.keys
 			.write()
@@ -578,4 +604,24 @@ mod tests {
 
 		assert!(result.unwrap().is_some());
 	}
+
+	#[test]
+	#[cfg(feature = "dilithium-experimental")]
+	fn dilithium_sign_with_works() {
+		use codec::Decode;
+		use sp_core::testing::DILITHIUM;
+
+		let store = MemoryKeystore::new();
+
+		let public = store.dilithium_generate_new(DILITHIUM, None).unwrap();
+		assert_eq!(store.dilithium_public_keys(DILITHIUM), vec![public]);
+
+		let msg = b"Something important";
+		let sig = store
+			.sign_with(DILITHIUM, dilithium::CRYPTO_ID, public.as_slice(), &msg[..])
+			.unwrap()
+			.unwrap();
+		let sig = dilithium::Signature::decode(&mut &sig[..]).unwrap();
+		assert!(dilithium::Pair::verify(&sig, &msg[..], &public));
+	}
 }
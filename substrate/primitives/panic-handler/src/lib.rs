@@ -23,20 +23,39 @@
 //!
 //! By default, the panic handler aborts the process by calling [`std::process::exit`]. This can
 //! temporarily be disabled by using an [`AbortGuard`].
+//!
+//! A crash handler set with [`set_crash_handler`] is called with a [`PanicReport`] of every panic
+//! that aborts the process, e.g. to write a crash report.
 
 use backtrace::Backtrace;
 use regex::Regex;
 use std::{
-	cell::Cell,
+	cell::{Cell, RefCell},
 	io::{self, Write},
 	marker::PhantomData,
 	panic::{self, PanicHookInfo},
-	sync::LazyLock,
+	sync::{LazyLock, OnceLock},
 	thread,
 };
 
 thread_local! {
 	static ON_PANIC: Cell<OnPanic> = Cell::new(OnPanic::Abort);
+	static LAST_PANIC: RefCell<Option<PanicReport>> = RefCell::new(None);
+}
+
+static CRASH_HANDLER: OnceLock<Box<dyn Fn(&PanicReport) + Send + Sync>> = OnceLock::new();
+
+/// Details of a panic, passed to the crash handler.
+#[derive(Debug, Clone)]
+pub struct PanicReport {
+	/// The panic message.
+	pub message: String,
+	/// The source location of the panic, as `file:line`.
+	pub location: String,
+	/// The name of the panicking thread.
+	pub thread: String,
+	/// The backtrace of the panic.
+	pub backtrace: String,
 }
 
 /// Panic action.
@@ -64,6 +83,27 @@ pub fn set(bug_url: &str, version: &str) {
 	}));
 }
 
+/// Set the crash handler.
+///
+/// The handler is called with the report of every panic that aborts the process, and of the
+/// panics passed to [`report_last_panic`]. It can only be set once, returns `false` if a handler
+/// was already set.
+pub fn set_crash_handler(handler: impl Fn(&PanicReport) + Send + Sync + 'static) -> bool {
+	CRASH_HANDLER.set(Box::new(handler)).is_ok()
+}
+
+/// Pass the last panic of the current thread to the crash handler.
+///
+/// To be called after catching a panic that is fatal although it unwound, e.g. the panic of an
+/// essential task.
+pub fn report_last_panic() {
+	if let Some(report) = LAST_PANIC.with(|last| last.borrow_mut().take()) {
+		if let Some(handler) = CRASH_HANDLER.get() {
+			handler(&report);
+		}
+	}
+}
+
 macro_rules! ABOUT_PANIC {
 	() => {
 		"
@@ -167,7 +207,7 @@ fn panic_hook(info: &PanicHookInfo, report_url: &str, version: &str) {
 	let thread = thread::current();
 	let name = thread.name().unwrap_or("<unnamed>");
 
-	let backtrace = Backtrace::new();
+	let backtrace = format!("{:?}", Backtrace::new());
 
 	let mut stderr = io::stderr();
 
@@ -176,16 +216,27 @@ fn panic_hook(info: &PanicHookInfo, report_url: &str, version: &str) {
 	let _ = writeln!(stderr);
 	let _ = writeln!(stderr, "Version: {}", version);
 	let _ = writeln!(stderr);
-	let _ = writeln!(stderr, "{:?}", backtrace);
+	let _ = writeln!(stderr, "{}", backtrace);
 	let _ = writeln!(stderr);
 	let _ = writeln!(stderr, "Thread '{}' panicked at '{}', {}:{}", name, msg, file, line);
 
 	let _ = writeln!(stderr, ABOUT_PANIC!(), report_url);
+
+	let report = PanicReport {
+		message: msg.into_owned(),
+		location: format!("{}:{}", file, line),
+		thread: name.to_owned(),
+		backtrace,
+	};
 	ON_PANIC.with(|val| {
 		if val.get() == OnPanic::Abort {
+			if let Some(handler) = CRASH_HANDLER.get() {
+				handler(&report);
+			}
 			::std::process::exit(1);
 		}
-	})
+	});
+	LAST_PANIC.with(|last| *last.borrow_mut() = Some(report));
 }
 
 #[cfg(test)]
@@ -199,6 +250,16 @@ mod tests {
 		::std::panic::catch_unwind(|| panic!()).ok();
 	}
 
+	#[test]
+	fn unwound_panic_is_reported() {
+		set("test", "1.2.3");
+		let _guard = AbortGuard::force_unwind();
+		::std::panic::catch_unwind(|| panic!("essential task failed")).ok();
+		let report = LAST_PANIC.with(|last| last.borrow().clone()).unwrap();
+		assert_eq!(report.message, "essential task failed");
+		assert!(report.location.starts_with(file!()));
+	}
+
 	#[test]
 	fn does_not_abort_after_never_abort() {
 		set("test", "1.2.3");
@@ -0,0 +1,346 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Substrate core types, verification and inherents for external randomness beacons.
+//!
+//! A randomness beacon publishes a [`Pulse`] every period, whose signature can be verified
+//! against the public key of the beacon and whose randomness is derived from the signature. As
+//! the signature is deterministic, the randomness can't be biased by the block author. The only
+//! choice left to the author is which round to include, which is why the runtime should require
+//! the round to be the one of the block's timestamp, see [`BeaconConfig::check`].
+//!
+//! Pulses of [drand](https://drand.love) beacons using the `bls-unchained-g1-rfc9380` scheme,
+//! e.g. the `quicknet` beacon of the League of Entropy, are supported.
+//!
+//! The verification uses the host functions of `sp-crypto-ec-utils` for the pairing, which need
+//! to be registered in the executor of the nodes running a runtime verifying pulses.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use ark_ec::{
+	hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve},
+	pairing::Pairing,
+	AffineRepr,
+};
+use ark_ff::{field_hashers::DefaultFieldHasher, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sha2::{Digest, Sha256};
+use sp_crypto_ec_utils::bls12_381::{Bls12_381, G1Affine, G2Affine};
+use sp_inherents::{InherentData, InherentIdentifier, IsFatalError};
+
+/// The identifier for the randomness beacon inherent.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"rndbecn0";
+
+/// The type of the inherent.
+pub type InherentType = Pulse;
+
+/// Size of the signature of a pulse, a compressed BLS12-381 G1 point.
+pub const SIGNATURE_SIZE: usize = 48;
+
+/// Size of the public key of a beacon, a compressed BLS12-381 G2 point.
+pub const PUBLIC_KEY_SIZE: usize = 96;
+
+/// Domain separation tag of the hash to curve of the `bls-unchained-g1-rfc9380` scheme.
+const DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// A pulse of a randomness beacon.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	TypeInfo,
+	sp_runtime::RuntimeDebug,
+)]
+pub struct Pulse {
+	/// The round of the pulse.
+	pub round: u64,
+	/// The signature of the round by the beacon.
+	pub signature: [u8; SIGNATURE_SIZE],
+}
+
+impl Pulse {
+	/// The message signed by the beacon: the SHA-256 hash of the big endian round.
+	pub fn message(&self) -> [u8; 32] {
+		Sha256::digest(self.round.to_be_bytes()).into()
+	}
+
+	/// The randomness of the pulse: the SHA-256 hash of the signature.
+	///
+	/// Only meaningful once the pulse has been verified.
+	pub fn randomness(&self) -> [u8; 32] {
+		Sha256::digest(self.signature).into()
+	}
+}
+
+/// The parameters of a randomness beacon.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	TypeInfo,
+	sp_runtime::RuntimeDebug,
+)]
+pub struct BeaconConfig {
+	/// The public key of the beacon.
+	pub public_key: [u8; PUBLIC_KEY_SIZE],
+	/// The time of the first round, in seconds since the UNIX_EPOCH.
+	pub genesis_time: u64,
+	/// The time between two rounds, in seconds.
+	pub period: u32,
+}
+
+impl BeaconConfig {
+	/// The latest round at `timestamp`, in milliseconds since the UNIX_EPOCH.
+	///
+	/// Returns `0` before the first round.
+	pub fn round_at(&self, timestamp: u64) -> u64 {
+		let now = timestamp / 1000;
+		if now < self.genesis_time || self.period == 0 {
+			return 0
+		}
+		(now - self.genesis_time) / u64::from(self.period) + 1
+	}
+
+	/// Verify the signature of `pulse`.
+	pub fn verify(&self, pulse: &Pulse) -> bool {
+		let Ok(public_key) = G2Affine::deserialize_compressed(&self.public_key[..]) else {
+			return false
+		};
+		let Ok(signature) = G1Affine::deserialize_compressed(&pulse.signature[..]) else {
+			return false
+		};
+		let Some(message) = hash_to_g1(&pulse.message()) else { return false };
+
+		Bls12_381::multi_pairing([signature, message], [-G2Affine::generator(), public_key])
+			.is_zero()
+	}
+
+	/// Check the pulse included in a block with the given `timestamp`.
+	///
+	/// The round of the pulse must be at most `max_lag` rounds behind the latest round at
+	/// `timestamp`, to account for the latency of the beacon and the drift of the clocks.
+	pub fn check(&self, pulse: &Pulse, timestamp: u64, max_lag: u64) -> Result<(), InherentError> {
+		let latest = self.round_at(timestamp);
+		if pulse.round > latest || pulse.round.saturating_add(max_lag) < latest {
+			return Err(InherentError::UnexpectedRound)
+		}
+		if !self.verify(pulse) {
+			return Err(InherentError::InvalidSignature)
+		}
+		Ok(())
+	}
+}
+
+/// Hash `message` to a point of G1, as specified by RFC 9380.
+fn hash_to_g1(message: &[u8]) -> Option<G1Affine> {
+	let hasher = MapToCurveBasedHasher::<
+		ark_bls12_381::G1Projective,
+		DefaultFieldHasher<Sha256, 128>,
+		WBMap<ark_bls12_381::g1::Config>,
+	>::new(DST)
+	.ok()?;
+	let point = hasher.hash(message).ok()?;
+
+	// The curve types of `sp-crypto-ec-utils` only differ by using the host functions.
+	let mut bytes = Vec::with_capacity(point.uncompressed_size());
+	point.serialize_uncompressed(&mut bytes).ok()?;
+	G1Affine::deserialize_uncompressed_unchecked(&bytes[..]).ok()
+}
+
+/// Errors that can occur while checking the randomness beacon inherent.
+#[derive(Encode, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, thiserror::Error))]
+pub enum InherentError {
+	/// The signature of the pulse is not valid for the beacon.
+	#[cfg_attr(feature = "std", error("The signature of the pulse is invalid."))]
+	InvalidSignature,
+	/// The round of the pulse doesn't match the timestamp of the block.
+	#[cfg_attr(
+		feature = "std",
+		error("The round of the pulse doesn't match the block timestamp.")
+	)]
+	UnexpectedRound,
+}
+
+impl IsFatalError for InherentError {
+	fn is_fatal_error(&self) -> bool {
+		match self {
+			InherentError::InvalidSignature => true,
+			InherentError::UnexpectedRound => true,
+		}
+	}
+}
+
+impl InherentError {
+	/// Try to create an instance ouf of the given identifier and data.
+	#[cfg(feature = "std")]
+	pub fn try_from(id: &InherentIdentifier, mut data: &[u8]) -> Option<Self> {
+		if id == &INHERENT_IDENTIFIER {
+			<InherentError as codec::Decode>::decode(&mut data).ok()
+		} else {
+			None
+		}
+	}
+}
+
+/// Auxiliary trait to extract randomness beacon inherent data.
+pub trait RandomnessBeaconInherentData {
+	/// Get randomness beacon inherent data.
+	fn randomness_beacon_inherent_data(&self) -> Result<Option<InherentType>, sp_inherents::Error>;
+}
+
+impl RandomnessBeaconInherentData for InherentData {
+	fn randomness_beacon_inherent_data(&self) -> Result<Option<InherentType>, sp_inherents::Error> {
+		self.get_data(&INHERENT_IDENTIFIER)
+	}
+}
+
+/// Provide the latest pulse of a randomness beacon.
+///
+/// No inherent data is provided if there is no pulse, e.g. when the beacon is unreachable.
+#[cfg(feature = "std")]
+pub struct InherentDataProvider {
+	pulse: Option<InherentType>,
+}
+
+#[cfg(feature = "std")]
+impl InherentDataProvider {
+	/// Create `Self` providing the given `pulse`.
+	pub fn new(pulse: Option<InherentType>) -> Self {
+		Self { pulse }
+	}
+
+	/// Returns the pulse of this inherent data provider.
+	pub fn pulse(&self) -> Option<&InherentType> {
+		self.pulse.as_ref()
+	}
+}
+
+#[cfg(feature = "std")]
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+	async fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		match &self.pulse {
+			Some(pulse) => inherent_data.put_data(INHERENT_IDENTIFIER, pulse),
+			None => Ok(()),
+		}
+	}
+
+	async fn try_handle_error(
+		&self,
+		identifier: &InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		Some(Err(sp_inherents::Error::Application(Box::from(InherentError::try_from(
+			identifier, error,
+		)?))))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ark_ec::CurveGroup;
+
+	/// A beacon with a test key, signing as a drand `bls-unchained-g1-rfc9380` beacon.
+	struct TestBeacon(ark_bls12_381::Fr);
+
+	impl TestBeacon {
+		fn config(&self) -> BeaconConfig {
+			let public_key = (ark_bls12_381::G2Affine::generator() * self.0).into_affine();
+			let mut bytes = Vec::new();
+			public_key.serialize_compressed(&mut bytes).unwrap();
+			BeaconConfig { public_key: bytes.try_into().unwrap(), genesis_time: 1000, period: 3 }
+		}
+
+		fn pulse(&self, round: u64) -> Pulse {
+			let mut pulse = Pulse { round, signature: [0; SIGNATURE_SIZE] };
+			let hasher = MapToCurveBasedHasher::<
+				ark_bls12_381::G1Projective,
+				DefaultFieldHasher<Sha256, 128>,
+				WBMap<ark_bls12_381::g1::Config>,
+			>::new(DST)
+			.unwrap();
+			let signature = (hasher.hash(&pulse.message()).unwrap() * self.0).into_affine();
+			signature.serialize_compressed(&mut pulse.signature[..]).unwrap();
+			pulse
+		}
+	}
+
+	#[test]
+	fn verify_works() {
+		let beacon = TestBeacon(42u64.into());
+		let config = beacon.config();
+		let pulse = beacon.pulse(7);
+		assert!(config.verify(&pulse));
+
+		// Signature of another round.
+		assert!(!config.verify(&Pulse { round: 8, ..pulse.clone() }));
+		// Signature of another beacon.
+		assert!(!TestBeacon(43u64.into()).config().verify(&pulse));
+		// Invalid point.
+		assert!(!config.verify(&Pulse { signature: [0xff; SIGNATURE_SIZE], ..pulse }));
+	}
+
+	#[test]
+	fn round_at_works() {
+		let config = TestBeacon(42u64.into()).config();
+		assert_eq!(config.round_at(999_999), 0);
+		assert_eq!(config.round_at(1_000_000), 1);
+		assert_eq!(config.round_at(1_002_999), 1);
+		assert_eq!(config.round_at(1_003_000), 2);
+	}
+
+	#[test]
+	fn check_works() {
+		let beacon = TestBeacon(42u64.into());
+		let config = beacon.config();
+		// Round 5 starts at 1012 seconds.
+		let timestamp = 1_012_500;
+
+		assert!(config.check(&beacon.pulse(5), timestamp, 1).is_ok());
+		assert!(config.check(&beacon.pulse(4), timestamp, 1).is_ok());
+		assert!(matches!(
+			config.check(&beacon.pulse(3), timestamp, 1),
+			Err(InherentError::UnexpectedRound)
+		));
+		assert!(matches!(
+			config.check(&beacon.pulse(6), timestamp, 1),
+			Err(InherentError::UnexpectedRound)
+		));
+		assert!(matches!(
+			config.check(&Pulse { round: 5, ..beacon.pulse(4) }, timestamp, 1),
+			Err(InherentError::InvalidSignature)
+		));
+	}
+}
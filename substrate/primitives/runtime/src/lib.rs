@@ -72,6 +72,8 @@ pub use sp_core::storage::StateVersion;
 #[cfg(feature = "std")]
 pub use sp_core::storage::{Storage, StorageChild};
 
+#[cfg(feature = "dilithium-experimental")]
+use sp_core::paired_crypto::sr25519_dilithium;
 use sp_core::{
 	crypto::{self, ByteArray, FromEntropy},
 	ecdsa, ed25519,
@@ -290,6 +292,12 @@ pub enum MultiSignature {
 	Sr25519(sr25519::Signature),
 	/// An ECDSA/SECP256k1 signature.
 	Ecdsa(ecdsa::Signature),
+	/// A hybrid sr25519 and ML-DSA-65 (Dilithium) signature, with the public key of the signer.
+	///
+	/// The account of the signer is the Blake2 hash of its public key, which can't be recovered
+	/// from the signature.
+	#[cfg(feature = "dilithium-experimental")]
+	Sr25519Dilithium(sr25519_dilithium::Public, sr25519_dilithium::Signature),
 }
 
 impl From<ed25519::Signature> for MultiSignature {
@@ -364,6 +372,10 @@ pub enum MultiSigner {
 	Sr25519(sr25519::Public),
 	/// An SECP256k1/ECDSA identity (actually, the Blake2 hash of the compressed pub key).
 	Ecdsa(ecdsa::Public),
+	/// A hybrid sr25519 and ML-DSA-65 (Dilithium) identity (actually, the Blake2 hash of the pub
+	/// key).
+	#[cfg(feature = "dilithium-experimental")]
+	Sr25519Dilithium(sr25519_dilithium::Public),
 }
 
 impl FromEntropy for MultiSigner {
@@ -390,6 +402,8 @@ impl AsRef<[u8]> for MultiSigner {
 			Self::Ed25519(ref who) => who.as_ref(),
 			Self::Sr25519(ref who) => who.as_ref(),
 			Self::Ecdsa(ref who) => who.as_ref(),
+			#[cfg(feature = "dilithium-experimental")]
+			Self::Sr25519Dilithium(ref who) => who.as_ref(),
 		}
 	}
 }
@@ -401,6 +415,8 @@ impl traits::IdentifyAccount for MultiSigner {
 			Self::Ed25519(who) => <[u8; 32]>::from(who).into(),
 			Self::Sr25519(who) => <[u8; 32]>::from(who).into(),
 			Self::Ecdsa(who) => sp_io::hashing::blake2_256(who.as_ref()).into(),
+			#[cfg(feature = "dilithium-experimental")]
+			Self::Sr25519Dilithium(who) => sp_io::hashing::blake2_256(who.as_ref()).into(),
 		}
 	}
 }
@@ -456,6 +472,25 @@ impl TryFrom<MultiSigner> for ecdsa::Public {
 	}
 }
 
+#[cfg(feature = "dilithium-experimental")]
+impl From<sr25519_dilithium::Public> for MultiSigner {
+	fn from(x: sr25519_dilithium::Public) -> Self {
+		Self::Sr25519Dilithium(x)
+	}
+}
+
+#[cfg(feature = "dilithium-experimental")]
+impl TryFrom<MultiSigner> for sr25519_dilithium::Public {
+	type Error = ();
+	fn try_from(m: MultiSigner) -> Result<Self, Self::Error> {
+		if let MultiSigner::Sr25519Dilithium(x) = m {
+			Ok(x)
+		} else {
+			Err(())
+		}
+	}
+}
+
 #[cfg(feature = "std")]
 impl std::fmt::Display for MultiSigner {
 	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -463,6 +498,8 @@ impl std::fmt::Display for MultiSigner {
 			Self::Ed25519(who) => write!(fmt, "ed25519: {}", who),
 			Self::Sr25519(who) => write!(fmt, "sr25519: {}", who),
 			Self::Ecdsa(who) => write!(fmt, "ecdsa: {}", who),
+			#[cfg(feature = "dilithium-experimental")]
+			Self::Sr25519Dilithium(who) => write!(fmt, "sr25519-dilithium: {}", who),
 		}
 	}
 }
@@ -479,6 +516,17 @@ impl Verify for MultiSignature {
 				sp_io::crypto::secp256k1_ecdsa_recover_compressed(sig.as_ref(), &m)
 					.map_or(false, |pubkey| sp_io::hashing::blake2_256(&pubkey) == who)
 			},
+			#[cfg(feature = "dilithium-experimental")]
+			Self::Sr25519Dilithium(public, sig) => {
+				if sp_io::hashing::blake2_256(public.as_ref()) != who {
+					return false
+				}
+				let msg = msg.get();
+				let (sr25519_public, dilithium_public) = public.split();
+				let (sr25519_sig, dilithium_sig) = sig.split();
+				sp_io::crypto::sr25519_verify(&sr25519_sig, msg, &sr25519_public) &&
+					sp_io::crypto::dilithium_verify(&dilithium_sig, msg, &dilithium_public)
+			},
 		}
 	}
 }
@@ -1182,6 +1230,25 @@ mod tests {
 		);
 	}
 
+	#[test]
+	#[cfg(feature = "dilithium-experimental")]
+	fn multi_signature_sr25519_dilithium_verify_works() {
+		let msg = &b"test-message"[..];
+		let (pair, _) = sr25519_dilithium::Pair::generate();
+		let (other, _) = sr25519_dilithium::Pair::generate();
+
+		let multi_sig = MultiSignature::Sr25519Dilithium(pair.public(), pair.sign(&msg));
+		let account = MultiSigner::from(pair.public()).into_account();
+		assert_eq!(account, sp_io::hashing::blake2_256(pair.public().as_ref()).into());
+		assert!(multi_sig.verify(msg, &account));
+		assert!(!multi_sig.verify(&b"test-msg"[..], &account));
+		assert!(!multi_sig.verify(msg, &MultiSigner::from(other.public()).into_account()));
+
+		// The signature must be made by the given public key.
+		let multi_sig = MultiSignature::Sr25519Dilithium(other.public(), pair.sign(&msg));
+		assert!(!multi_sig.verify(msg, &MultiSigner::from(other.public()).into_account()));
+	}
+
 	#[test]
 	fn multi_signature_ecdsa_verify_works() {
 		let msg = &b"test-message"[..];
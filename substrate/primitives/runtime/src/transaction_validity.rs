@@ -86,6 +86,10 @@ pub enum InvalidTransaction {
 	IndeterminateImplicit,
 	/// The transaction extension did not authorize any origin.
 	UnknownOrigin,
+	/// The call of the transaction is filtered by the runtime, e.g. because it is paused.
+	///
+	/// The transaction may become valid again once the call is no longer filtered.
+	Filtered,
 }
 
 impl InvalidTransaction {
@@ -121,6 +125,7 @@ impl From<InvalidTransaction> for &'static str {
 				"The implicit data was unable to be calculated",
 			InvalidTransaction::UnknownOrigin =>
 				"The transaction extension did not authorize any origin",
+			InvalidTransaction::Filtered => "Transaction call is filtered",
 		}
 	}
 }
@@ -61,6 +61,24 @@ pub enum SubmitResult {
 /// Result type for `Error`
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Topic filter of a statement query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopicFilter {
+	/// Statements must include all of these topics.
+	pub match_all: Vec<Topic>,
+	/// If not empty, statements must include at least one of these topics.
+	pub match_any: Vec<Topic>,
+}
+
+/// A page of statements, ordered by hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementPage {
+	/// The statements of the page, with their hashes.
+	pub statements: Vec<(Hash, Statement)>,
+	/// The hash to continue the query after, if there are more statements.
+	pub next: Option<Hash>,
+}
+
 /// Statement store API.
 pub trait StatementStore: Send + Sync {
 	/// Return all statements.
@@ -82,6 +100,15 @@ pub trait StatementStore: Send + Sync {
 	/// `dest`. The key must be available to the client.
 	fn posted_clear(&self, match_all_topics: &[Topic], dest: [u8; 32]) -> Result<Vec<Vec<u8>>>;
 
+	/// Return up to `limit` statements matching `filter` with a hash greater than `start_after`,
+	/// ordered by hash.
+	fn query(
+		&self,
+		filter: &TopicFilter,
+		start_after: Option<Hash>,
+		limit: usize,
+	) -> Result<StatementPage>;
+
 	/// Submit a statement.
 	fn submit(&self, statement: Statement, source: StatementSource) -> SubmitResult;
 
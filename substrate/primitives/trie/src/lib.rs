@@ -48,6 +48,7 @@ use hash_db::{Hasher, Prefix};
 pub use memory_db::{prefixed_key, HashKey, KeyFunction, PrefixedKey};
 /// The Substrate format implementation of `NodeCodec`.
 pub use node_codec::NodeCodec;
+use sp_core::storage::{well_known_keys, ChildInfo, ChildType, PrefixedStorageKey};
 pub use storage_proof::{CompactProof, StorageProof, StorageProofError};
 /// Trie codec reexport, mainly child trie support
 /// for trie compact proof.
@@ -497,6 +498,40 @@ where
 		.map(|x| x.map(|val| val.to_vec()))
 }
 
+/// Read the default child tries referenced by the trie with the given `root`, and their roots.
+pub fn read_child_trie_roots<L: TrieLayout>(
+	db: &dyn hash_db::HashDBRef<L::Hash, trie_db::DBValue>,
+	root: &TrieHash<L>,
+) -> Result<Vec<(ChildInfo, TrieHash<L>)>, Box<TrieError<L>>> {
+	use trie_db::TrieIterator;
+
+	let prefix = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+	let trie = TrieDBBuilder::<L>::new(db, root).build();
+	let mut pairs = TrieDBIterator::new(&trie)?;
+	pairs.seek(prefix)?;
+
+	let mut child_roots = Vec::new();
+	for pair in pairs {
+		let (key, value) = pair?;
+		if !key.starts_with(prefix) {
+			break
+		}
+		let prefixed_key = PrefixedStorageKey::new(key);
+		let Some((ChildType::ParentKeyId, unprefixed)) =
+			ChildType::from_prefixed_key(&prefixed_key)
+		else {
+			continue
+		};
+		let mut child_root = TrieHash::<L>::default();
+		if child_root.as_ref().len() != value.len() {
+			return Err(Box::new(trie_db::TrieError::InvalidHash(*root, value)))
+		}
+		child_root.as_mut().copy_from_slice(&value);
+		child_roots.push((ChildInfo::new_default(unprefixed), child_root));
+	}
+	Ok(child_roots)
+}
+
 /// `HashDB` implementation that append a encoded prefix (unique id bytes) in addition to the
 /// prefix of every key value.
 pub struct KeySpacedDB<'a, DB: ?Sized, H>(&'a DB, &'a [u8], PhantomData<H>);
@@ -843,6 +878,25 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn read_child_trie_roots_works() {
+		let child_info = ChildInfo::new_default(b"child");
+		let child_root = Blake2Hasher::hash(b"child root");
+		let prefixed_key = child_info.prefixed_storage_key().into_inner();
+		let (db, root) = create_trie::<LayoutV1>(&[
+			(&b"key"[..], &b"value"[..]),
+			(&prefixed_key[..], child_root.as_ref()),
+			(&b"zzz"[..], &b"value"[..]),
+		]);
+		assert_eq!(
+			read_child_trie_roots::<LayoutV1>(&db, &root).unwrap(),
+			vec![(child_info, child_root)],
+		);
+
+		let (db, root) = create_trie::<LayoutV1>(&[(&prefixed_key[..], &b"not a root"[..])]);
+		assert!(read_child_trie_roots::<LayoutV1>(&db, &root).is_err());
+	}
+
 	#[test]
 	fn random_should_work() {
 		random_should_work_inner::<LayoutV1>();
@@ -128,6 +128,9 @@ for PALLET in "${PALLETS[@]}"; do
   elif [ "$PALLET" == "pallet_asset_conversion_ops" ] || [ "$PALLET" == "pallet-asset-conversion-ops" ]
   then
     WEIGHT_FILE="./frame/asset-conversion/ops/src/weights.rs"
+  elif [ "$PALLET" == "pallet_sponsored_payment" ] || [ "$PALLET" == "pallet-sponsored-payment" ]
+  then
+    WEIGHT_FILE="./frame/transaction-payment/sponsored-payment/src/weights.rs"
   fi
 
   echo "[+] Benchmarking $PALLET with weight file $WEIGHT_FILE";
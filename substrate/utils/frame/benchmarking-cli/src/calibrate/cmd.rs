@@ -0,0 +1,263 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the [`CalibrateCmd`] as entry point for the CLI to execute
+//! the *calibrate* benchmark.
+
+use codec::{Codec, Decode, Encode};
+use frame_support::weights::{constants::WEIGHT_REF_TIME_PER_NANOS, Weight};
+use log::info;
+use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi;
+use sc_block_builder::BlockBuilderApi;
+use sc_cli::{CliConfiguration, Error, ImportParams, Result, SharedParams};
+use sc_client_api::BlockBackend;
+use sc_runtime_metadata::RuntimeCache;
+use sp_api::{ApiExt, Core, Metadata, ProvideRuntimeApi};
+use sp_blockchain::{Error::RuntimeApiError, HeaderBackend};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, MaybeDisplay, UniqueSaturatedInto},
+	DigestItem, OpaqueExtrinsic, SaturatedConversion,
+};
+use subxt::{ext::subxt_core::blocks::decode_from, SubstrateConfig};
+
+use clap::{Args, Parser};
+use serde::Serialize;
+use std::{fs, path::PathBuf, sync::Arc, time::Instant};
+
+use super::report::Calibration;
+use crate::shared::{StatSelect, Stats};
+
+/// Calibrate the weights and fees of the extrinsics against their execution time on this machine.
+///
+/// Replays the extrinsics of recent blocks one by one and compares their benchmarked weight to
+/// their measured execution time. The results are grouped by call, the calls whose weight is
+/// systematically too high or too low compared to the other calls are reported as mispriced.
+/// Example:
+///
+/// $ substrate benchmark calibrate --blocks 1000 -d /tmp/my-node --pruning archive
+///
+/// If this machine is reference hardware, the ratio of the measured execution time to the weight
+/// of all the extrinsics is the factor by which the `WeightToFee` of the runtime should be scaled
+/// to charge the actual execution time.
+#[derive(Debug, Parser)]
+pub struct CalibrateCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub import_params: ImportParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub params: CalibrateParams,
+}
+
+/// Parameters for modifying the calibration behaviour.
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Args)]
+pub struct CalibrateParams {
+	/// Number of recent blocks to replay, up to the best block.
+	#[arg(long, default_value_t = 100)]
+	pub blocks: u32,
+
+	/// Number of times that each block is replayed.
+	#[arg(long, default_value_t = 5)]
+	pub repeat: u32,
+
+	/// Maximal deviation in percent of the ratio of the execution time to the weight of a call
+	/// from the one of all calls, before the call is reported as mispriced.
+	#[arg(long, default_value_t = 25.0)]
+	pub tolerance: f64,
+
+	/// Write the calibration report in JSON format into the given file.
+	#[arg(long)]
+	pub json_file: Option<PathBuf>,
+}
+
+impl CalibrateCmd {
+	/// Replay recent blocks and report how their benchmarked weights compare to their measured
+	/// execution time.
+	///
+	/// Output will be printed to console.
+	pub fn run<Block, C, Balance>(&self, client: Arc<C>) -> Result<()>
+	where
+		Block: BlockT<Extrinsic = OpaqueExtrinsic>,
+		C: BlockBackend<Block> + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+		C::Api: ApiExt<Block>
+			+ BlockBuilderApi<Block>
+			+ Metadata<Block>
+			+ TransactionPaymentApi<Block, Balance>,
+		Balance: Codec + MaybeDisplay + UniqueSaturatedInto<u128>,
+	{
+		if self.params.repeat == 0 {
+			return Err("The --repeat argument must be at least 1".into())
+		}
+		if self.params.tolerance < 0.0 {
+			return Err("The --tolerance argument must be positive".into())
+		}
+
+		let to: u32 = client.info().best_number.saturated_into();
+		let from = to.saturating_sub(self.params.blocks.saturating_sub(1)).max(1);
+		if from > to {
+			return Err("Cannot calibrate on the genesis block".into())
+		}
+
+		let mut calibration = Calibration::default();
+		let metadata = RuntimeCache::default();
+		for number in from..=to {
+			let hash = client.expect_block_hash_from_id(&BlockId::Number(number.into()))?;
+			let block = client.block(hash)?.ok_or(format!("Block #{number} not found"))?.block;
+			let (mut header, extrinsics) = block.deconstruct();
+			// Remove the consensus seal, which is not part of the executed header.
+			header.digest_mut().logs.retain(|item| !matches!(item, DigestItem::Seal(_, _)));
+			let parent_hash = *header.parent_hash();
+
+			let calls = self.call_names(&*client, parent_hash, &extrinsics, &metadata)?;
+			let runtime_api = client.runtime_api();
+			let mut weights = Vec::with_capacity(extrinsics.len());
+			for xt in &extrinsics {
+				let info = runtime_api
+					.query_info(parent_hash, xt.clone(), xt.encoded_size() as u32)
+					.map_err(|e| Error::Client(RuntimeApiError(e)))?;
+				weights.push(info.weight.ref_time() / WEIGHT_REF_TIME_PER_NANOS);
+			}
+			let times = self.measure_extrinsics(&*client, parent_hash, &header, &extrinsics)?;
+
+			for ((call, weight), took) in calls.into_iter().zip(weights).zip(times) {
+				calibration.add(call, weight, took);
+			}
+			if (number - from) % 100 == 99 {
+				info!("Replayed block #{number}");
+			}
+		}
+
+		// The fees are computed with the `WeightToFee` of the latest runtime.
+		let best_hash = client.info().best_hash;
+		let runtime_api = client.runtime_api();
+		let report = calibration.report(from, to, self.params.tolerance, |ns| {
+			let weight = Weight::from_parts(ns.saturating_mul(WEIGHT_REF_TIME_PER_NANOS), 0);
+			runtime_api
+				.query_weight_to_fee(best_hash, weight)
+				.map(|fee| fee.saturated_into())
+				.map_err(|e| Error::Client(RuntimeApiError(e)))
+		})?;
+		report.print();
+
+		if let Some(path) = &self.params.json_file {
+			let json = serde_json::to_string_pretty(&report)
+				.map_err(|e| format!("Serializing into JSON: {:?}", e))?;
+			fs::write(path, json)?;
+		}
+		Ok(())
+	}
+
+	/// Return the average execution time of each extrinsic of the block.
+	///
+	/// The extrinsics are applied one by one on top of the initialized block.
+	fn measure_extrinsics<Block, C>(
+		&self,
+		client: &C,
+		parent_hash: Block::Hash,
+		header: &Block::Header,
+		extrinsics: &[Block::Extrinsic],
+	) -> Result<Vec<u64>>
+	where
+		Block: BlockT,
+		C: ProvideRuntimeApi<Block>,
+		C::Api: BlockBuilderApi<Block>,
+	{
+		let mut records = vec![Vec::new(); extrinsics.len()];
+		for _ in 0..self.params.repeat {
+			// The changes of every call are kept by the runtime api for the following calls.
+			let runtime_api = client.runtime_api();
+			runtime_api
+				.initialize_block(parent_hash, header)
+				.map_err(|e| Error::Client(RuntimeApiError(e)))?;
+
+			for (xt, record) in extrinsics.iter().zip(records.iter_mut()) {
+				let xt = xt.clone();
+				let start = Instant::now();
+				runtime_api
+					.apply_extrinsic(parent_hash, xt)
+					.map_err(|e| Error::Client(RuntimeApiError(e)))?;
+				record.push(start.elapsed().as_nanos() as u64);
+			}
+		}
+
+		records
+			.iter()
+			.map(|record| Ok(Stats::new(record)?.select(StatSelect::Average)))
+			.collect()
+	}
+
+	/// Return the `Pallet.call` called by each extrinsic.
+	///
+	/// The extrinsics are decoded with the metadata of the runtime at `parent_hash`, which is
+	/// cached in `metadata`.
+	fn call_names<Block, C>(
+		&self,
+		client: &C,
+		parent_hash: Block::Hash,
+		extrinsics: &[Block::Extrinsic],
+		metadata: &RuntimeCache<subxt::Metadata>,
+	) -> Result<Vec<String>>
+	where
+		Block: BlockT,
+		C: ProvideRuntimeApi<Block>,
+		C::Api: Metadata<Block>,
+	{
+		let runtime_api = client.runtime_api();
+		let spec_version = runtime_api
+			.version(parent_hash)
+			.map_err(|e| Error::Client(RuntimeApiError(e)))?
+			.spec_version;
+		let metadata = metadata.get_or_try_insert_with(spec_version, || -> Result<_> {
+			let opaque_metadata = runtime_api
+				.metadata(parent_hash)
+				.map_err(|e| format!("Unable to fetch metadata: {:?}", e))?;
+			Ok(subxt::Metadata::decode(&mut (*opaque_metadata).as_slice())?)
+		})?;
+
+		let extrinsics = decode_from::<SubstrateConfig>(
+			extrinsics.iter().map(Encode::encode).collect(),
+			metadata,
+		)
+		.map_err(|e| format!("Unable to decode extrinsics: {e}"))?;
+		extrinsics
+			.iter()
+			.map(|xt| {
+				let xt = xt.map_err(|e| format!("Unable to decode extrinsic: {e}"))?;
+				let pallet = xt.pallet_name().map_err(|e| e.to_string())?;
+				let call = xt.variant_name().map_err(|e| e.to_string())?;
+				Ok(format!("{pallet}.{call}"))
+			})
+			.collect()
+	}
+}
+
+// Boilerplate
+impl CliConfiguration for CalibrateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
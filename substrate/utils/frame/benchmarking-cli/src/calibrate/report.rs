@@ -0,0 +1,230 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aggregation of the replayed extrinsics into a calibration report.
+
+use comfy_table::Table;
+use log::{info, warn};
+use sc_cli::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use thousands::Separable;
+
+/// Weight and execution time of the extrinsics calling one call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallCalibration {
+	/// The call, as `Pallet.call`.
+	pub call: String,
+	/// Number of replayed extrinsics calling the call.
+	pub count: u64,
+	/// Average benchmarked weight, in nanoseconds.
+	pub weight_ns: u64,
+	/// Average measured execution time, in nanoseconds.
+	pub measured_ns: u64,
+	/// Ratio of the measured execution time to the weight, `None` for calls without weight.
+	pub ratio: Option<f64>,
+	/// The fee of the average weight, according to the `WeightToFee` of the runtime.
+	pub weight_fee: u128,
+	/// The fee of the average weight, if the weight matched the measured execution time.
+	///
+	/// The execution time is scaled by the ratio of all calls, to compensate for the speed of
+	/// this machine.
+	pub suggested_weight_fee: u128,
+	/// Whether the ratio of the call deviates from the one of all calls by more than the
+	/// tolerance.
+	pub mispriced: bool,
+}
+
+/// Comparison of the benchmarked weights to the measured execution time of the extrinsics of a
+/// range of blocks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalibrationReport {
+	/// Number of the first replayed block.
+	pub from: u32,
+	/// Number of the last replayed block.
+	pub to: u32,
+	/// Number of replayed extrinsics.
+	pub extrinsics: u64,
+	/// Ratio of the measured execution time to the weight of all extrinsics.
+	///
+	/// On reference hardware, this is the factor by which the `WeightToFee` of the runtime should
+	/// be scaled to charge the measured execution time.
+	pub ratio: Option<f64>,
+	/// The calibration of every call, ordered by name.
+	pub calls: Vec<CallCalibration>,
+}
+
+/// Accumulated weight and execution time of the extrinsics calling one call.
+#[derive(Default)]
+struct Totals {
+	count: u64,
+	weight_ns: u128,
+	measured_ns: u128,
+}
+
+/// Accumulates the replayed extrinsics by call.
+#[derive(Default)]
+pub(crate) struct Calibration {
+	calls: BTreeMap<String, Totals>,
+}
+
+impl Calibration {
+	/// Account for an extrinsic calling `call`.
+	pub fn add(&mut self, call: String, weight_ns: u64, measured_ns: u64) {
+		let totals = self.calls.entry(call).or_default();
+		totals.count += 1;
+		totals.weight_ns += u128::from(weight_ns);
+		totals.measured_ns += u128::from(measured_ns);
+	}
+
+	/// Create the report of the blocks `from..=to`.
+	///
+	/// `weight_to_fee` returns the fee of a weight given in nanoseconds.
+	pub fn report(
+		self,
+		from: u32,
+		to: u32,
+		tolerance: f64,
+		weight_to_fee: impl Fn(u64) -> Result<u128>,
+	) -> Result<CalibrationReport> {
+		let (weight_ns, measured_ns) = self
+			.calls
+			.values()
+			.fold((0, 0), |(weight, measured), t| (weight + t.weight_ns, measured + t.measured_ns));
+		let overall = ratio(weight_ns, measured_ns);
+
+		let mut calls = Vec::with_capacity(self.calls.len());
+		for (call, totals) in self.calls {
+			let weight_ns = (totals.weight_ns / u128::from(totals.count)) as u64;
+			let measured_ns = (totals.measured_ns / u128::from(totals.count)) as u64;
+			let call_ratio = ratio(totals.weight_ns, totals.measured_ns);
+			let suggested_ns = match overall {
+				Some(overall) => (measured_ns as f64 / overall) as u64,
+				None => measured_ns,
+			};
+			let mispriced = match (call_ratio, overall) {
+				(Some(call_ratio), Some(overall)) =>
+					(call_ratio / overall - 1.0).abs() * 100.0 > tolerance,
+				// A call without weight is mispriced if it takes time at all.
+				(None, _) => measured_ns > 0,
+				(Some(_), None) => false,
+			};
+			calls.push(CallCalibration {
+				call,
+				count: totals.count,
+				weight_ns,
+				measured_ns,
+				ratio: call_ratio,
+				weight_fee: weight_to_fee(weight_ns)?,
+				suggested_weight_fee: weight_to_fee(suggested_ns)?,
+				mispriced,
+			});
+		}
+
+		let extrinsics = calls.iter().map(|call| call.count).sum();
+		Ok(CalibrationReport { from, to, extrinsics, ratio: overall, calls })
+	}
+}
+
+/// The ratio of `measured` to `weight`, if there is any weight.
+fn ratio(weight: u128, measured: u128) -> Option<f64> {
+	(weight > 0).then(|| measured as f64 / weight as f64)
+}
+
+impl CalibrationReport {
+	/// Prints the report to the console.
+	pub fn print(&self) {
+		let mut table = Table::new();
+		table.set_header([
+			"Call",
+			"Count",
+			"Weight (ns)",
+			"Measured (ns)",
+			"Ratio",
+			"Weight fee",
+			"Suggested fee",
+		]);
+		for call in &self.calls {
+			table.add_row([
+				if call.mispriced { format!("{} ⚠️", call.call) } else { call.call.clone() },
+				call.count.to_string(),
+				call.weight_ns.separate_with_commas(),
+				call.measured_ns.separate_with_commas(),
+				call.ratio.map_or("-".into(), |ratio| format!("{:.1}%", ratio * 100.0)),
+				call.weight_fee.separate_with_commas(),
+				call.suggested_weight_fee.separate_with_commas(),
+			]);
+		}
+		info!(
+			"\n{}\nReplayed {} extrinsics of blocks #{}..=#{}.",
+			table, self.extrinsics, self.from, self.to
+		);
+
+		match self.ratio {
+			Some(ratio) => info!(
+				"The extrinsics took {:.1}% of their weight on this machine. If it is reference \
+				hardware, scale the `WeightToFee` of the runtime by {:.3} to charge their \
+				execution time.",
+				ratio * 100.0,
+				ratio,
+			),
+			None => warn!("The replayed extrinsics have no weight"),
+		}
+		let mispriced = self.calls.iter().filter(|call| call.mispriced).count();
+		if mispriced > 0 {
+			warn!(
+				"{} calls are mispriced compared to the other calls, their weights should be \
+				benchmarked again",
+				mispriced
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn report_flags_mispriced_calls() {
+		let mut calibration = Calibration::default();
+		calibration.add("Balances.transfer".into(), 100, 80);
+		calibration.add("Balances.transfer".into(), 100, 80);
+		calibration.add("System.remark".into(), 100, 90);
+		calibration.add("Utility.batch".into(), 100, 150);
+		calibration.add("Timestamp.set".into(), 0, 10);
+
+		let report = calibration.report(1, 10, 25.0, |ns| Ok(u128::from(ns) * 2)).unwrap();
+		assert_eq!(report.extrinsics, 5);
+		assert_eq!(report.ratio, Some(1.025));
+
+		let calls: Vec<_> = report.calls.iter().map(|c| (c.call.as_str(), c.mispriced)).collect();
+		assert_eq!(
+			calls,
+			vec![
+				("Balances.transfer", false),
+				("System.remark", false),
+				("Timestamp.set", true),
+				("Utility.batch", true),
+			]
+		);
+		assert_eq!(report.calls[0].weight_ns, 100);
+		assert_eq!(report.calls[0].weight_fee, 200);
+		// 80 ns at a ratio of 102.5% is a weight of 78 ns.
+		assert_eq!(report.calls[0].suggested_weight_fee, 156);
+	}
+}
@@ -18,6 +18,7 @@
 //! Contains the root [`BenchmarkCmd`] command and exports its sub-commands.
 
 mod block;
+mod calibrate;
 mod extrinsic;
 mod machine;
 mod overhead;
@@ -26,6 +27,7 @@ mod shared;
 mod storage;
 
 pub use block::BlockCmd;
+pub use calibrate::{CalibrateCmd, CalibrateParams, CalibrationReport, CallCalibration};
 pub use extrinsic::{ExtrinsicBuilder, ExtrinsicCmd, ExtrinsicFactory};
 pub use machine::{MachineCmd, SUBSTRATE_REFERENCE_HARDWARE};
 pub use overhead::{
@@ -47,6 +49,7 @@ pub enum BenchmarkCmd {
 	Storage(StorageCmd),
 	Overhead(OverheadCmd),
 	Block(BlockCmd),
+	Calibrate(CalibrateCmd),
 	Machine(MachineCmd),
 	Extrinsic(ExtrinsicCmd),
 }
@@ -63,6 +66,7 @@ macro_rules! unwrap_cmd {
 			BenchmarkCmd::Storage($cmd) => $code,
 			BenchmarkCmd::Overhead($cmd) => $code,
 			BenchmarkCmd::Block($cmd) => $code,
+			BenchmarkCmd::Calibrate($cmd) => $code,
 			BenchmarkCmd::Machine($cmd) => $code,
 			BenchmarkCmd::Extrinsic($cmd) => $code,
 		}
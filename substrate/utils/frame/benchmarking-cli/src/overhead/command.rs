@@ -489,6 +489,7 @@ impl OverheadCmd {
 			trie_cache_maximum_size: self.trie_cache_maximum_size()?,
 			state_pruning: None,
 			blocks_pruning: BlocksPruning::KeepAll,
+			write_rate_limit: None,
 			source: database_source,
 		})?;
 
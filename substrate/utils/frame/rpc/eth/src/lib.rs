@@ -0,0 +1,487 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal Ethereum compatible JSON-RPC adapter for FRAME based chains.
+//!
+//! Exposes a small subset of the `eth_*` namespace on top of any chain built with the SDK, without
+//! requiring an EVM in the runtime. This allows tooling which only speaks Ethereum JSON-RPC to do
+//! basic reads:
+//!
+//! - Blocks are reported with their Substrate header fields. Extrinsics are listed by their hash,
+//!   as they have no Ethereum transaction representation.
+//! - Ethereum addresses are mapped onto runtime accounts by an [`AddressMapping`], and balances are
+//!   read from the storage item described by a [`BalanceMapping`].
+//! - `eth_sendRawTransaction` accepts a SCALE encoded extrinsic wrapping the call to dispatch,
+//!   instead of an RLP encoded Ethereum transaction.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Codec, Decode, Encode};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::ErrorObject,
+};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H160, H256, U256};
+use sp_runtime::{
+	traits::{Block as BlockT, Hash, HashingFor, Header as HeaderT},
+	SaturatedConversion,
+};
+use sp_storage::StorageKey;
+
+pub use frame_system_rpc_runtime_api::AccountNonceApi;
+
+/// Ethereum compatible RPC methods.
+#[rpc(client, server)]
+pub trait EthApi {
+	/// Returns the chain id used to identify the chain in Ethereum tooling.
+	#[method(name = "eth_chainId")]
+	fn chain_id(&self) -> RpcResult<U256>;
+
+	/// Returns the number of the best block.
+	#[method(name = "eth_blockNumber")]
+	fn block_number(&self) -> RpcResult<U256>;
+
+	/// Returns the block with the given number, or `None` if it is unknown.
+	///
+	/// Transactions are always returned as hashes, `full` is accepted for compatibility only.
+	#[method(name = "eth_getBlockByNumber")]
+	fn block_by_number(&self, number: BlockNumberOrTag, full: bool) -> RpcResult<Option<EthBlock>>;
+
+	/// Returns the block with the given hash, or `None` if it is unknown.
+	///
+	/// Transactions are always returned as hashes, `full` is accepted for compatibility only.
+	#[method(name = "eth_getBlockByHash")]
+	fn block_by_hash(&self, hash: H256, full: bool) -> RpcResult<Option<EthBlock>>;
+
+	/// Returns the free balance of the account mapped to `address`.
+	#[method(name = "eth_getBalance")]
+	fn balance(&self, address: H160, block: Option<BlockNumberOrTag>) -> RpcResult<U256>;
+
+	/// Returns the nonce of the account mapped to `address`.
+	#[method(name = "eth_getTransactionCount")]
+	fn transaction_count(&self, address: H160, block: Option<BlockNumberOrTag>) -> RpcResult<U256>;
+
+	/// Submits a SCALE encoded extrinsic to the transaction pool and returns its hash.
+	#[method(name = "eth_sendRawTransaction")]
+	async fn send_raw_transaction(&self, extrinsic: Bytes) -> RpcResult<H256>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The requested block is not known.
+	UnknownBlock,
+	/// The call to runtime or the access to the database failed.
+	RuntimeError,
+	/// Data could not be decoded.
+	DecodeError,
+	/// The transaction pool rejected the transaction.
+	PoolError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::UnknownBlock => 1,
+			Error::RuntimeError => 2,
+			Error::DecodeError => 3,
+			Error::PoolError => 4,
+		}
+	}
+}
+
+/// A block number or one of the block tags defined by the Ethereum JSON-RPC specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockNumberOrTag {
+	/// The block with the given number.
+	Number(u64),
+	/// The genesis block.
+	Earliest,
+	/// The best block.
+	Latest,
+	/// The best block, pending blocks are not tracked.
+	Pending,
+	/// The last finalized block.
+	Safe,
+	/// The last finalized block.
+	Finalized,
+}
+
+impl Serialize for BlockNumberOrTag {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Number(number) => serializer.serialize_str(&format!("{number:#x}")),
+			Self::Earliest => serializer.serialize_str("earliest"),
+			Self::Latest => serializer.serialize_str("latest"),
+			Self::Pending => serializer.serialize_str("pending"),
+			Self::Safe => serializer.serialize_str("safe"),
+			Self::Finalized => serializer.serialize_str("finalized"),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for BlockNumberOrTag {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let value = String::deserialize(deserializer)?;
+		match value.as_str() {
+			"earliest" => Ok(Self::Earliest),
+			"latest" => Ok(Self::Latest),
+			"pending" => Ok(Self::Pending),
+			"safe" => Ok(Self::Safe),
+			"finalized" => Ok(Self::Finalized),
+			number => {
+				let digits = number
+					.strip_prefix("0x")
+					.ok_or_else(|| D::Error::custom("block number must be `0x` prefixed"))?;
+				u64::from_str_radix(digits, 16).map(Self::Number).map_err(D::Error::custom)
+			},
+		}
+	}
+}
+
+/// A block, as returned by `eth_getBlockByNumber` and `eth_getBlockByHash`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthBlock {
+	/// Number of the block.
+	pub number: U256,
+	/// Hash of the block.
+	pub hash: H256,
+	/// Hash of the parent block.
+	pub parent_hash: H256,
+	/// State root after executing the block.
+	pub state_root: H256,
+	/// Root of the extrinsics of the block.
+	pub transactions_root: H256,
+	/// Timestamp of the block in seconds, or zero if the chain has no `Timestamp` pallet.
+	pub timestamp: U256,
+	/// Hashes of the extrinsics of the block.
+	pub transactions: Vec<H256>,
+}
+
+/// Maps Ethereum addresses onto runtime accounts.
+pub trait AddressMapping<AccountId>: Send + Sync + 'static {
+	/// Returns the account controlled by `address`.
+	fn into_account_id(&self, address: H160) -> AccountId;
+}
+
+/// Maps an address onto the account whose id is the `blake2_256` hash of `b"evm:" ++ address`.
+///
+/// This is the same mapping as the one used by Frontier's `HashedAddressMapping`.
+pub struct HashedAddressMapping;
+
+impl<AccountId: From<[u8; 32]>> AddressMapping<AccountId> for HashedAddressMapping {
+	fn into_account_id(&self, address: H160) -> AccountId {
+		let mut data = [0u8; 24];
+		data[..4].copy_from_slice(b"evm:");
+		data[4..].copy_from_slice(address.as_bytes());
+		sp_crypto_hashing::blake2_256(&data).into()
+	}
+}
+
+/// Describes where the free balance of an account is stored.
+///
+/// The balance is read from a `Blake2_128Concat` storage map keyed by the account id, and decoded
+/// as the `Balance` type of the runtime.
+pub struct BalanceMapping<Balance> {
+	/// Name of the pallet owning the storage map.
+	pub pallet: &'static str,
+	/// Name of the storage map.
+	pub storage: &'static str,
+	/// Extracts the free balance from the encoded value of the storage map.
+	pub free_balance: fn(&[u8]) -> Option<Balance>,
+}
+
+impl<Balance> Clone for BalanceMapping<Balance> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<Balance> Copy for BalanceMapping<Balance> {}
+
+impl<Balance: Decode> BalanceMapping<Balance> {
+	/// Balances kept in `System::Account`, as configured by most runtimes using `pallet-balances`.
+	///
+	/// `Nonce` is the nonce type of the runtime.
+	pub fn system_account<Nonce: Decode>() -> Self {
+		Self {
+			pallet: "System",
+			storage: "Account",
+			free_balance: decode_system_account::<Nonce, Balance>,
+		}
+	}
+
+	/// Balances kept in the `Account` map of a `pallet-balances` instance named `pallet`.
+	pub fn balances_account(pallet: &'static str) -> Self {
+		Self { pallet, storage: "Account", free_balance: decode_balances_account::<Balance> }
+	}
+
+	fn storage_key<AccountId: Encode>(&self, account: &AccountId) -> StorageKey {
+		let account = account.encode();
+		let mut key = sp_crypto_hashing::twox_128(self.pallet.as_bytes()).to_vec();
+		key.extend(sp_crypto_hashing::twox_128(self.storage.as_bytes()));
+		key.extend(sp_crypto_hashing::blake2_128(&account));
+		key.extend(account);
+		StorageKey(key)
+	}
+}
+
+/// Decodes the free balance out of a `frame_system::AccountInfo<Nonce, AccountData<Balance>>`.
+fn decode_system_account<Nonce: Decode, Balance: Decode>(mut data: &[u8]) -> Option<Balance> {
+	let (_nonce, _consumers, _providers, _sufficients, free) =
+		<(Nonce, u32, u32, u32, Balance)>::decode(&mut data).ok()?;
+	Some(free)
+}
+
+/// Decodes the free balance out of a `pallet_balances::AccountData<Balance>`.
+fn decode_balances_account<Balance: Decode>(mut data: &[u8]) -> Option<Balance> {
+	Balance::decode(&mut data).ok()
+}
+
+/// Storage key of `Timestamp::Now`.
+fn timestamp_key() -> StorageKey {
+	let mut key = sp_crypto_hashing::twox_128(b"Timestamp").to_vec();
+	key.extend(sp_crypto_hashing::twox_128(b"Now"));
+	StorageKey(key)
+}
+
+fn runtime_error(e: impl ToString) -> ErrorObject<'static> {
+	ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the chain.",
+		Some(e.to_string()),
+	)
+}
+
+/// An implementation of the Ethereum compatible RPC methods on full client.
+///
+/// `AccountId`, `Nonce` and `Balance` are the corresponding types of the runtime.
+pub struct Eth<C, P, M, Block, BE, AccountId, Nonce, Balance> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	chain_id: u64,
+	address_mapping: M,
+	balance_mapping: BalanceMapping<Balance>,
+	_marker: PhantomData<(Block, BE, AccountId, Nonce)>,
+}
+
+impl<C, P, M, Block, BE, AccountId, Nonce, Balance>
+	Eth<C, P, M, Block, BE, AccountId, Nonce, Balance>
+{
+	/// Create new `Eth` given client, transaction pool, the chain id reported to Ethereum tooling
+	/// and the mappings of addresses and balances.
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		chain_id: u64,
+		address_mapping: M,
+		balance_mapping: BalanceMapping<Balance>,
+	) -> Self {
+		Self {
+			client,
+			pool,
+			chain_id,
+			address_mapping,
+			balance_mapping,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, P, M, Block, BE, AccountId, Nonce, Balance>
+	Eth<C, P, M, Block, BE, AccountId, Nonce, Balance>
+where
+	Block: BlockT<Hash = H256>,
+	BE: Backend<Block>,
+	C: HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, BE>,
+{
+	/// Resolves `block` to a block hash, returning `None` for unknown block numbers.
+	fn resolve(&self, block: BlockNumberOrTag) -> RpcResult<Option<H256>> {
+		let info = self.client.info();
+		Ok(match block {
+			BlockNumberOrTag::Latest | BlockNumberOrTag::Pending => Some(info.best_hash),
+			BlockNumberOrTag::Safe | BlockNumberOrTag::Finalized => Some(info.finalized_hash),
+			BlockNumberOrTag::Earliest => Some(info.genesis_hash),
+			BlockNumberOrTag::Number(number) =>
+				self.client.hash(number.saturated_into()).map_err(runtime_error)?,
+		})
+	}
+
+	/// Resolves `block` to a block hash, defaulting to the best block.
+	fn resolve_existing(&self, block: Option<BlockNumberOrTag>) -> RpcResult<H256> {
+		self.resolve(block.unwrap_or(BlockNumberOrTag::Latest))?.ok_or_else(|| {
+			ErrorObject::owned(Error::UnknownBlock.into(), "Unknown block.", None::<()>)
+		})
+	}
+
+	fn block(&self, hash: H256) -> RpcResult<Option<EthBlock>> {
+		let Some(block) = self.client.block(hash).map_err(runtime_error)? else { return Ok(None) };
+		let (header, extrinsics) = block.block.deconstruct();
+
+		let timestamp = self
+			.client
+			.storage(hash, &timestamp_key())
+			.map_err(runtime_error)?
+			.and_then(|data| u64::decode(&mut &data.0[..]).ok())
+			.map_or(0, |millis| millis / 1000);
+
+		Ok(Some(EthBlock {
+			number: (*header.number()).saturated_into::<u64>().into(),
+			hash,
+			parent_hash: *header.parent_hash(),
+			state_root: *header.state_root(),
+			transactions_root: *header.extrinsics_root(),
+			timestamp: timestamp.into(),
+			transactions: extrinsics.iter().map(HashingFor::<Block>::hash_of).collect(),
+		}))
+	}
+}
+
+#[async_trait]
+impl<C, P, M, Block, BE, AccountId, Nonce, Balance> EthApiServer
+	for Eth<C, P, M, Block, BE, AccountId, Nonce, Balance>
+where
+	Block: BlockT<Hash = H256>,
+	BE: Backend<Block> + Send + Sync + 'static,
+	C: HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, BE>,
+	C: ProvideRuntimeApi<Block>,
+	C: Send + Sync + 'static,
+	C::Api: AccountNonceApi<Block, AccountId, Nonce>,
+	P: TransactionPool<Block = Block, Hash = H256> + 'static,
+	M: AddressMapping<AccountId>,
+	AccountId: Codec + Send + Sync + 'static,
+	Nonce: Codec + Into<U256> + Send + Sync + 'static,
+	Balance: Into<U256> + Send + Sync + 'static,
+{
+	fn chain_id(&self) -> RpcResult<U256> {
+		Ok(self.chain_id.into())
+	}
+
+	fn block_number(&self) -> RpcResult<U256> {
+		Ok(self.client.info().best_number.saturated_into::<u64>().into())
+	}
+
+	fn block_by_number(
+		&self,
+		number: BlockNumberOrTag,
+		_full: bool,
+	) -> RpcResult<Option<EthBlock>> {
+		match self.resolve(number)? {
+			Some(hash) => self.block(hash),
+			None => Ok(None),
+		}
+	}
+
+	fn block_by_hash(&self, hash: H256, _full: bool) -> RpcResult<Option<EthBlock>> {
+		self.block(hash)
+	}
+
+	fn balance(&self, address: H160, block: Option<BlockNumberOrTag>) -> RpcResult<U256> {
+		let at = self.resolve_existing(block)?;
+		let account = self.address_mapping.into_account_id(address);
+		let Some(data) = self
+			.client
+			.storage(at, &self.balance_mapping.storage_key(&account))
+			.map_err(runtime_error)?
+		else {
+			return Ok(U256::zero())
+		};
+
+		(self.balance_mapping.free_balance)(&data.0).map(Into::into).ok_or_else(|| {
+			ErrorObject::owned(Error::DecodeError.into(), "Unable to decode balance.", None::<()>)
+		})
+	}
+
+	fn transaction_count(&self, address: H160, block: Option<BlockNumberOrTag>) -> RpcResult<U256> {
+		let at = self.resolve_existing(block)?;
+		let account = self.address_mapping.into_account_id(address);
+		let nonce = self.client.runtime_api().account_nonce(at, account).map_err(runtime_error)?;
+		Ok(nonce.into())
+	}
+
+	async fn send_raw_transaction(&self, extrinsic: Bytes) -> RpcResult<H256> {
+		let xt = Decode::decode(&mut &extrinsic[..]).map_err(|e| {
+			ErrorObject::owned(
+				Error::DecodeError.into(),
+				"Unable to decode extrinsic.",
+				Some(e.to_string()),
+			)
+		})?;
+		let best_hash = self.client.info().best_hash;
+		self.pool
+			.submit_one(best_hash, TransactionSource::External, xt)
+			.await
+			.map_err(|e| {
+				ErrorObject::owned(
+					Error::PoolError.into(),
+					"Unable to submit extrinsic.",
+					Some(e.to_string()),
+				)
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::AccountId32;
+
+	#[test]
+	fn block_number_or_tag_serde_works() {
+		let decode = |value: &str| serde_json::from_str::<BlockNumberOrTag>(value);
+
+		assert_eq!(decode("\"latest\"").unwrap(), BlockNumberOrTag::Latest);
+		assert_eq!(decode("\"finalized\"").unwrap(), BlockNumberOrTag::Finalized);
+		assert_eq!(decode("\"0x1b4\"").unwrap(), BlockNumberOrTag::Number(436));
+		assert!(decode("\"436\"").is_err());
+		assert!(decode("\"newest\"").is_err());
+
+		let encoded = serde_json::to_string(&BlockNumberOrTag::Number(436)).unwrap();
+		assert_eq!(encoded, "\"0x1b4\"");
+	}
+
+	#[test]
+	fn balance_mappings_decode_free_balance() {
+		let account = (7u32, 1u32, 1u32, 0u32, 1_000u128, 5u128, 0u128, 0u128).encode();
+		let system = BalanceMapping::<u128>::system_account::<u32>();
+		assert_eq!((system.free_balance)(&account), Some(1_000));
+
+		let balances = BalanceMapping::<u128>::balances_account("Balances");
+		assert_eq!((balances.free_balance)(&account[16..]), Some(1_000));
+
+		// Runtimes with a smaller balance type.
+		let account = (7u64, 1u32, 1u32, 0u32, 1_000u64, 5u64, 0u64, 0u64).encode();
+		let system = BalanceMapping::<u64>::system_account::<u64>();
+		assert_eq!((system.free_balance)(&account), Some(1_000));
+	}
+
+	#[test]
+	fn hashed_address_mapping_is_prefixed() {
+		let address = H160::repeat_byte(0x42);
+		let account: AccountId32 = HashedAddressMapping.into_account_id(address);
+
+		let mut data = b"evm:".to_vec();
+		data.extend(address.as_bytes());
+		assert_eq!(account, AccountId32::new(sp_crypto_hashing::blake2_256(&data)));
+	}
+}
@@ -18,8 +18,12 @@
 mod sourced;
 
 use hyper::{http::StatusCode, Request, Response};
-use prometheus::{core::Collector, Encoder, TextEncoder};
-use std::net::SocketAddr;
+use prometheus::{core::Collector, proto::MetricFamily, Encoder, TextEncoder};
+use std::{
+	collections::{btree_map::Entry, BTreeMap},
+	net::SocketAddr,
+	sync::{Arc, Mutex, PoisonError},
+};
 
 pub use prometheus::{
 	self,
@@ -34,6 +38,24 @@ pub use sourced::{MetricSource, SourcedCounter, SourcedGauge, SourcedMetric};
 
 type Body = http_body_util::Full<hyper::body::Bytes>;
 
+/// The registries served by an endpoint.
+type Registries = Arc<Mutex<Vec<Registry>>>;
+
+/// The endpoints running in this process, by address.
+///
+/// Starting another endpoint at the address of a running one adds its registry to the running
+/// endpoint instead, which allows several chains of one process to share an endpoint.
+static ENDPOINTS: Mutex<BTreeMap<SocketAddr, Registries>> = Mutex::new(BTreeMap::new());
+
+/// Removes an endpoint from [`ENDPOINTS`] when it stops.
+struct EndpointGuard(SocketAddr);
+
+impl Drop for EndpointGuard {
+	fn drop(&mut self) {
+		ENDPOINTS.lock().unwrap_or_else(PoisonError::into_inner).remove(&self.0);
+	}
+}
+
 pub fn register<T: Clone + Collector + 'static>(
 	metric: T,
 	registry: &Registry,
@@ -62,10 +84,10 @@ pub enum Error {
 
 async fn request_metrics(
 	req: Request<hyper::body::Incoming>,
-	registry: Registry,
+	registries: Registries,
 ) -> Result<Response<Body>, Error> {
 	if req.uri().path() == "/metrics" {
-		let metric_families = registry.gather();
+		let metric_families = gather(&registries.lock().unwrap_or_else(PoisonError::into_inner));
 		let mut buffer = vec![];
 		let encoder = TextEncoder::new();
 		encoder.encode(&metric_families, &mut buffer).unwrap();
@@ -83,22 +105,74 @@ async fn request_metrics(
 	}
 }
 
+/// Gathers the metrics of all registries, merging the metric families of the same name.
+fn gather(registries: &[Registry]) -> Vec<MetricFamily> {
+	if let [registry] = registries {
+		return registry.gather();
+	}
+
+	let mut families = BTreeMap::<String, MetricFamily>::new();
+	for mut family in registries.iter().flat_map(Registry::gather) {
+		match families.entry(family.get_name().to_owned()) {
+			Entry::Vacant(entry) => {
+				entry.insert(family);
+			},
+			Entry::Occupied(mut entry) => {
+				for metric in family.take_metric().into_vec() {
+					entry.get_mut().mut_metric().push(metric);
+				}
+			},
+		}
+	}
+	families.into_values().collect()
+}
+
 /// Initializes the metrics context, and starts an HTTP server
 /// to serve metrics.
+///
+/// If this process already serves metrics at `prometheus_addr`, the metrics of `registry` are
+/// served by the running server as well and the function returns immediately. The registries
+/// should then label their metrics differently, e.g. by the `chain` label.
 pub async fn init_prometheus(prometheus_addr: SocketAddr, registry: Registry) -> Result<(), Error> {
+	// Endpoints at random ports can't be shared.
+	let shared = prometheus_addr.port() != 0;
+	let registries = {
+		let mut endpoints = ENDPOINTS.lock().unwrap_or_else(PoisonError::into_inner);
+		if let Some(registries) = endpoints.get(&prometheus_addr) {
+			registries.lock().unwrap_or_else(PoisonError::into_inner).push(registry);
+			log::info!(target: "prometheus", "〽️ Sharing Prometheus exporter at {prometheus_addr}");
+			return Ok(());
+		}
+
+		// Registered before binding, so that the chains started concurrently share the endpoint
+		// instead of racing for the port.
+		let registries = Arc::new(Mutex::new(vec![registry]));
+		if shared {
+			endpoints.insert(prometheus_addr, registries.clone());
+		}
+		registries
+	};
+	let _guard = shared.then(|| EndpointGuard(prometheus_addr));
+
 	let listener = tokio::net::TcpListener::bind(&prometheus_addr).await.map_err(|e| {
 		log::error!(target: "prometheus", "Error binding to '{prometheus_addr:?}': {e:?}");
 		Error::PortInUse(prometheus_addr)
 	})?;
 
-	init_prometheus_with_listener(listener, registry).await
+	serve(listener, registries).await
 }
 
 /// Init prometheus using the given listener.
+#[cfg(test)]
 async fn init_prometheus_with_listener(
 	listener: tokio::net::TcpListener,
 	registry: Registry,
 ) -> Result<(), Error> {
+	serve(listener, Arc::new(Mutex::new(vec![registry]))).await
+}
+
+/// Serve the metrics of `registries` using the given listener.
+async fn serve(listener: tokio::net::TcpListener, registries: Registries) -> Result<(), Error> {
 	log::info!(target: "prometheus", "〽️ Prometheus exporter started at {}", listener.local_addr()?);
 
 	let server = hyper_util::server::conn::auto::Builder::new(hyper_util::rt::TokioExecutor::new());
@@ -113,12 +187,12 @@ async fn init_prometheus_with_listener(
 			},
 		};
 
-		let registry = registry.clone();
+		let registries = registries.clone();
 
 		let conn = server
 			.serve_connection_with_upgrades(
 				io,
-				hyper::service::service_fn(move |req| request_metrics(req, registry.clone())),
+				hyper::service::service_fn(move |req| request_metrics(req, registries.clone())),
 			)
 			.into_owned();
 		let conn = graceful.watch(conn);
@@ -170,4 +244,42 @@ mod tests {
 
 		assert!(body.contains(&format!("{} 0", METRIC_NAME)));
 	}
+
+	#[tokio::test]
+	async fn prometheus_endpoint_is_shared() {
+		let addr = std::net::TcpListener::bind("127.0.0.1:0")
+			.and_then(|listener| listener.local_addr())
+			.expect("Finds a free port");
+
+		let registries = ["first", "second"].map(|chain| {
+			let labels = [("chain".to_string(), chain.to_string())].into_iter().collect();
+			let registry =
+				Registry::new_custom(None, Some(labels)).expect("Creates the test registry");
+			register(
+				prometheus::Counter::new(METRIC_NAME, "yeah").expect("Creates test counter"),
+				&registry,
+			)
+			.expect("Registers the test metric");
+			registry
+		});
+		let [first, second] = registries;
+
+		tokio::spawn(init_prometheus(addr, first));
+		while !ENDPOINTS.lock().unwrap().contains_key(&addr) {
+			tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+		}
+		init_prometheus(addr, second).await.expect("Shares the running endpoint");
+
+		let client = Client::builder(TokioExecutor::new()).build_http::<Body>();
+		let res = client
+			.get(Uri::try_from(&format!("http://{}/metrics", addr)).expect("Parses URI"))
+			.await
+			.expect("Requests metrics");
+		let buf = res.into_body().collect().await.expect("Failed to read HTTP body").to_bytes();
+		let body = String::from_utf8(buf.to_vec()).expect("Converts body to String");
+
+		assert_eq!(body.matches(&format!("# TYPE {} counter", METRIC_NAME)).count(), 1);
+		assert!(body.contains(&format!("{}{{chain=\"first\"}} 0", METRIC_NAME)));
+		assert!(body.contains(&format!("{}{{chain=\"second\"}} 0", METRIC_NAME)));
+	}
 }
@@ -194,6 +194,9 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		sync_service,
 		config,
 		telemetry: telemetry.as_mut(),
+		informant_data_providers: Vec::new(),
+		informant_relay_chain: None,
+		informant_session_provider: None,
 	})?;
 
 	let proposer = sc_basic_authorship::ProposerFactory::new(
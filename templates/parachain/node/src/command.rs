@@ -4,7 +4,7 @@ use cumulus_client_service::storage_proof_size::HostFunctions as ReclaimHostFunc
 use cumulus_primitives_core::ParaId;
 use frame_benchmarking_cli::{BenchmarkCmd, SUBSTRATE_REFERENCE_HARDWARE};
 use log::info;
-use parachain_template_runtime::Block;
+use parachain_template_runtime::{Balance, Block};
 use sc_cli::{
 	ChainSpec, CliConfiguration, DefaultConfigurationValues, ImportParams, KeystoreParams,
 	NetworkParams, Result, RpcEndpoint, SharedParams, SubstrateCli,
@@ -193,6 +193,10 @@ pub fn run() -> Result<()> {
 					let partials = new_partial(&config)?;
 					cmd.run(partials.client)
 				}),
+				BenchmarkCmd::Calibrate(cmd) => runner.sync_run(|config| {
+					let partials = new_partial(&config)?;
+					cmd.run::<Block, _, Balance>(partials.client)
+				}),
 				#[cfg(not(feature = "runtime-benchmarks"))]
 				BenchmarkCmd::Storage(_) => Err(sc_cli::Error::Input(
 					"Compile with --features=runtime-benchmarks \
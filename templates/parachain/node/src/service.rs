@@ -332,6 +332,9 @@ pub async fn start_parachain_node(
 		system_rpc_tx,
 		tx_handler_controller,
 		telemetry: telemetry.as_mut(),
+		informant_data_providers: Vec::new(),
+		informant_relay_chain: None,
+		informant_session_provider: None,
 	})?;
 
 	if let Some(hwbench) = hwbench {
@@ -47,7 +47,7 @@ use sp_version::RuntimeVersion;
 use super::{
 	AccountId, Balance, Block, ConsensusHook, Executive, InherentDataExt, Nonce, ParachainSystem,
 	Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment,
-	SLOT_DURATION, VERSION,
+	XcmpQueue, SLOT_DURATION, VERSION,
 };
 
 // we move some impls outside so we can easily use them with `docify`.
@@ -225,6 +225,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl cumulus_primitives_core::XcmpQueueStatusApi<Block> for Runtime {
+		fn xcmp_queue_status() -> cumulus_primitives_core::XcmpQueueStatus {
+			XcmpQueue::queue_status()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -234,6 +234,9 @@ pub fn new_full<
 		sync_service: sync_service.clone(),
 		config,
 		telemetry: telemetry.as_mut(),
+		informant_data_providers: Vec::new(),
+		informant_relay_chain: None,
+		informant_session_provider: None,
 	})?;
 
 	if role.is_authority() {
@@ -292,13 +295,16 @@ pub fn new_full<
 		let grandpa_config = sc_consensus_grandpa::Config {
 			// FIXME #1578 make this available through chainspec
 			gossip_duration: Duration::from_millis(333),
-			justification_generation_period: GRANDPA_JUSTIFICATION_PERIOD,
+			justification_policy: sc_consensus_grandpa::JustificationPolicy::Interval(
+				GRANDPA_JUSTIFICATION_PERIOD,
+			),
 			name: Some(name),
 			observer_enabled: false,
 			keystore,
 			local_role: role,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			protocol_name: grandpa_protocol_name,
+			slashing_protection: None,
 		};
 
 		// start the full GRANDPA voter
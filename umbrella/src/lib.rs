@@ -76,6 +76,10 @@ pub use bridge_hub_test_utils;
 #[cfg(feature = "bridge-runtime-common")]
 pub use bridge_runtime_common;
 
+/// Availability sampling of parachain candidates on the relay chain.
+#[cfg(feature = "cumulus-client-availability-sampling")]
+pub use cumulus_client_availability_sampling;
+
 /// Parachain node CLI utilities.
 #[cfg(feature = "cumulus-client-cli")]
 pub use cumulus_client_cli;
@@ -292,6 +296,10 @@ pub use mmr_gadget;
 #[cfg(feature = "mmr-rpc")]
 pub use mmr_rpc;
 
+/// FRAME pallet for alternative authorization of account transactions.
+#[cfg(feature = "pallet-account-abstraction")]
+pub use pallet_account_abstraction;
+
 /// The Alliance pallet provides a collective for standard-setting industry collaboration.
 #[cfg(feature = "pallet-alliance")]
 pub use pallet_alliance;
@@ -455,6 +463,11 @@ pub use pallet_election_provider_multi_block;
 #[cfg(feature = "pallet-election-provider-multi-phase")]
 pub use pallet_election_provider_multi_phase;
 
+/// Solution mining, feasibility checking and submission strategies for the off-chain miners of
+/// the multi phase election provider.
+#[cfg(feature = "pallet-election-provider-multi-phase-miner")]
+pub use pallet_election_provider_multi_phase_miner;
+
 /// Benchmarking for election provider support onchain config trait.
 #[cfg(feature = "pallet-election-provider-support-benchmarking")]
 pub use pallet_election_provider_support_benchmarking;
@@ -551,6 +564,11 @@ pub use pallet_nomination_pools_benchmarking;
 #[cfg(feature = "pallet-nomination-pools-runtime-api")]
 pub use pallet_nomination_pools_runtime_api;
 
+/// FRAME pallet for transactions protected against replay by expiring unique tags instead of
+/// nonces.
+#[cfg(feature = "pallet-nonceless")]
+pub use pallet_nonceless;
+
 /// FRAME offences pallet.
 #[cfg(feature = "pallet-offences")]
 pub use pallet_offences;
@@ -653,6 +671,10 @@ pub use pallet_skip_feeless_payment;
 #[cfg(feature = "pallet-society")]
 pub use pallet_society;
 
+/// Pallet to let sponsors pay the transaction fees of other accounts.
+#[cfg(feature = "pallet-sponsored-payment")]
+pub use pallet_sponsored_payment;
+
 /// FRAME pallet staking.
 #[cfg(feature = "pallet-staking")]
 pub use pallet_staking;
@@ -993,6 +1015,10 @@ pub use sc_basic_authorship;
 #[cfg(feature = "sc-block-builder")]
 pub use sc_block_builder;
 
+/// Streaming exporter of finalized blocks of Substrate nodes to Kafka or NATS.
+#[cfg(feature = "sc-chain-exporter")]
+pub use sc_chain_exporter;
+
 /// Substrate chain configurations.
 #[cfg(feature = "sc-chain-spec")]
 pub use sc_chain_spec;
@@ -1057,10 +1083,26 @@ pub use sc_consensus_manual_seal;
 #[cfg(feature = "sc-consensus-pow")]
 pub use sc_consensus_pow;
 
+/// Slashing protection store refusing to sign conflicting blocks and votes.
+#[cfg(feature = "sc-consensus-slashing-protection")]
+pub use sc_consensus_slashing_protection;
+
 /// Generic slots-based utilities for consensus.
 #[cfg(feature = "sc-consensus-slots")]
 pub use sc_consensus_slots;
 
+/// Content-addressed off-chain data availability for Substrate nodes, backed by IPFS.
+#[cfg(feature = "sc-content-store")]
+pub use sc_content_store;
+
+/// Crash reports of Substrate nodes.
+#[cfg(feature = "sc-crash-report")]
+pub use sc_crash_report;
+
+/// Export of decoded chain data of Substrate nodes to CSV or Parquet files.
+#[cfg(feature = "sc-data-export")]
+pub use sc_data_export;
+
 /// A crate that provides means of executing/dispatching calls into the runtime.
 #[cfg(feature = "sc-executor")]
 pub use sc_executor;
@@ -1077,6 +1119,10 @@ pub use sc_executor_polkavm;
 #[cfg(feature = "sc-executor-wasmtime")]
 pub use sc_executor_wasmtime;
 
+/// GraphQL read API over the storage of Substrate nodes.
+#[cfg(feature = "sc-graphql")]
+pub use sc_graphql;
+
 /// Substrate informant.
 #[cfg(feature = "sc-informant")]
 pub use sc_informant;
@@ -1085,6 +1131,10 @@ pub use sc_informant;
 #[cfg(feature = "sc-keystore")]
 pub use sc_keystore;
 
+/// Attribution of the heap usage of Substrate nodes to their subsystems.
+#[cfg(feature = "sc-memory-breakdown")]
+pub use sc_memory_breakdown;
+
 /// Substrate mixnet service.
 #[cfg(feature = "sc-mixnet")]
 pub use sc_mixnet;
@@ -1105,6 +1155,10 @@ pub use sc_network_gossip;
 #[cfg(feature = "sc-network-light")]
 pub use sc_network_light;
 
+/// Substrate block propagation latency protocol.
+#[cfg(feature = "sc-network-propagation")]
+pub use sc_network_propagation;
+
 /// Substrate statement protocol.
 #[cfg(feature = "sc-network-statement")]
 pub use sc_network_statement;
@@ -1129,6 +1183,18 @@ pub use sc_offchain;
 #[cfg(feature = "sc-proposer-metrics")]
 pub use sc_proposer_metrics;
 
+/// Client of external randomness beacons providing verified pulses as inherent data.
+#[cfg(feature = "sc-randomness-beacon")]
+pub use sc_randomness_beacon;
+
+/// Remote database serving read-only replicas of Substrate nodes.
+#[cfg(feature = "sc-remote-db")]
+pub use sc_remote_db;
+
+/// Streaming of imported blocks and their state changes from a trusted node to read replicas.
+#[cfg(feature = "sc-replica")]
+pub use sc_replica;
+
 /// Substrate Client RPC.
 #[cfg(feature = "sc-rpc")]
 pub use sc_rpc;
@@ -1137,6 +1203,10 @@ pub use sc_rpc;
 #[cfg(feature = "sc-rpc-api")]
 pub use sc_rpc_api;
 
+/// gRPC gateway for the chain head and transaction APIs of Substrate nodes.
+#[cfg(feature = "sc-rpc-grpc")]
+pub use sc_rpc_grpc;
+
 /// Substrate RPC servers.
 #[cfg(feature = "sc-rpc-server")]
 pub use sc_rpc_server;
@@ -1145,19 +1215,42 @@ pub use sc_rpc_server;
 #[cfg(feature = "sc-rpc-spec-v2")]
 pub use sc_rpc_spec_v2;
 
+/// Conversion of SCALE encoded values of the runtime to and from JSON, driven by the metadata.
+#[cfg(feature = "sc-runtime-metadata")]
+pub use sc_runtime_metadata;
+
 /// Substrate client utilities for frame runtime functions calls.
 #[cfg(feature = "sc-runtime-utilities")]
 pub use sc_runtime_utilities;
 
+/// Self-update of node binaries from a release feed, verified with the signature of the
+/// publisher.
+#[cfg(feature = "sc-self-update")]
+pub use sc_self_update;
+
 /// Substrate service. Starts a thread that spins up the network, client, and extrinsic pool.
 /// Manages communication between them.
 #[cfg(feature = "sc-service")]
 pub use sc_service;
 
+/// Staking monitor service for substrate.
+#[cfg(feature = "sc-staking-monitor")]
+pub use sc_staking_monitor;
+
 /// State database maintenance. Handles canonicalization and pruning in the database.
 #[cfg(feature = "sc-state-db")]
 pub use sc_state_db;
 
+/// Regeneration of the states discarded by the pruning, by re-executing blocks from periodic state
+/// checkpoints.
+#[cfg(feature = "sc-state-regeneration")]
+pub use sc_state_regeneration;
+
+/// Background verification of the state trie nodes against their hashes, to detect database
+/// corruption.
+#[cfg(feature = "sc-state-scrubber")]
+pub use sc_state_scrubber;
+
 /// Substrate statement store.
 #[cfg(feature = "sc-statement-store")]
 pub use sc_statement_store;
@@ -1198,6 +1291,10 @@ pub use sc_transaction_pool_api;
 #[cfg(feature = "sc-utils")]
 pub use sc_utils;
 
+/// Webhook sink for the events of finalized blocks of Substrate nodes.
+#[cfg(feature = "sc-webhooks")]
+pub use sc_webhooks;
+
 /// Helper crate for generating slot ranges for the Polkadot runtime.
 #[cfg(feature = "slot-range-helper")]
 pub use slot_range_helper;
@@ -1318,6 +1415,10 @@ pub use sp_consensus_pow;
 #[cfg(feature = "sp-consensus-slots")]
 pub use sp_consensus_slots;
 
+/// Primitives for content-addressed off-chain data, like IPFS blobs referenced on chain.
+#[cfg(feature = "sp-content-store")]
+pub use sp_content_store;
+
 /// Shareable Substrate types.
 #[cfg(feature = "sp-core")]
 pub use sp_core;
@@ -1343,6 +1444,10 @@ pub use sp_crypto_hashing;
 #[cfg(feature = "sp-crypto-hashing-proc-macro")]
 pub use sp_crypto_hashing_proc_macro;
 
+/// Metered host functions for the verification of zero-knowledge proofs.
+#[cfg(feature = "sp-crypto-zk")]
+pub use sp_crypto_zk;
+
 /// Substrate database trait.
 #[cfg(feature = "sp-database")]
 pub use sp_database;
@@ -1403,6 +1508,10 @@ pub use sp_offchain;
 #[cfg(feature = "sp-panic-handler")]
 pub use sp_panic_handler;
 
+/// Substrate core types, verification and inherents for external randomness beacons.
+#[cfg(feature = "sp-randomness-beacon")]
+pub use sp_randomness_beacon;
+
 /// Substrate RPC primitives and utilities.
 #[cfg(feature = "sp-rpc")]
 pub use sp_rpc;
@@ -1525,6 +1634,10 @@ pub use substrate_bip39;
 #[cfg(feature = "substrate-build-script-utils")]
 pub use substrate_build_script_utils;
 
+/// Minimal Ethereum compatible JSON-RPC adapter for FRAME based chains.
+#[cfg(feature = "substrate-frame-rpc-eth")]
+pub use substrate_frame_rpc_eth;
+
 /// Substrate RPC for FRAME's support.
 #[cfg(feature = "substrate-frame-rpc-support")]
 pub use substrate_frame_rpc_support;